@@ -14,6 +14,15 @@ pub enum Def2 {
         identifier_id: IdentId,
         expr_id: NodeId<Expr2>,
     },
+    // TypeAnnotation example: `main : Str`. The type is kept as formatted text for now.
+    TypeAnnotation {
+        identifier_id: IdentId,
+        type_str: String,
+    },
+    // Expect example: `expect 1 + 1 == 2`. The condition is kept as formatted text for now.
+    Expect {
+        condition: String,
+    },
     Blank,
     CommentsBefore {
         comments: String,
@@ -43,6 +52,19 @@ pub fn def2_to_string(node_id: DefId, pool: &Pool) -> String {
                 expr2_to_string(*expr_id, pool)
             );
         }
+        Def2::TypeAnnotation {
+            identifier_id,
+            type_str,
+        } => {
+            let _ = write!(
+                full_string,
+                "Def2::TypeAnnotation(identifier_id: >>{:?}), type_str: >>{})",
+                identifier_id, type_str
+            );
+        }
+        Def2::Expect { condition } => {
+            let _ = write!(full_string, "Def2::Expect(condition: >>{})", condition);
+        }
         Def2::Blank => {
             full_string.push_str("Def2::Blank");
         }
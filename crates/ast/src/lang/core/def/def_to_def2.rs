@@ -1,8 +1,12 @@
 use bumpalo::Bump;
-use roc_parse::{ast::CommentOrNewline, parser::SyntaxError};
-use roc_region::all::Region;
+use roc_fmt::{annotation::Formattable, Buf};
+use roc_parse::ast::{CommentOrNewline, Expr, Pattern, TypeAnnotation};
+use roc_region::all::{Loc, Region};
 
-use crate::lang::{core::expr::expr_to_expr2::loc_expr_to_expr2, env::Env, scope::Scope};
+use crate::{
+    ast_error::{ASTResult, SyntaxErrorNoBacktraceSnafu},
+    lang::{core::expr::expr_to_expr2::loc_expr_to_expr2, env::Env, scope::Scope},
+};
 
 use super::def2::Def2;
 
@@ -26,41 +30,55 @@ pub fn toplevel_defs_to_defs2<'a>(
     scope: &mut Scope,
     parsed_defs: roc_parse::ast::Defs<'a>,
     region: Region,
-) -> Vec<Def2> {
+) -> ASTResult<Vec<Def2>> {
     let mut result = Vec::with_capacity(parsed_defs.tags.len());
 
     for (index, def) in parsed_defs.defs().enumerate() {
-        let mut def = match def {
-            Err(roc_parse::ast::ValueDef::Body(&loc_pattern, &loc_expr)) => {
-                let expr2 = loc_expr_to_expr2(arena, loc_expr, env, scope, region).0;
-                let expr_id = env.pool.add(expr2);
-
-                use roc_parse::ast::Pattern::*;
-
-                match loc_pattern.value {
-                    Identifier(id_str) => {
-                        let identifier_id = env.ident_ids.get_or_insert(id_str);
-
-                        // TODO support with annotation
-                        Def2::ValueDef {
-                            identifier_id,
-                            expr_id,
-                        }
-                    }
-                    other => {
-                        unimplemented!(
-                            "I don't yet know how to convert the pattern {:?} into an expr2",
-                            other
-                        )
-                    }
-                }
+        // an annotated body becomes a TypeAnnotation followed by a ValueDef
+        let mut defs = match def {
+            Err(roc_parse::ast::ValueDef::Body(loc_pattern, loc_expr)) => {
+                vec![value_def2(
+                    arena,
+                    env,
+                    scope,
+                    loc_pattern,
+                    loc_expr,
+                    region,
+                )?]
+            }
+            Err(roc_parse::ast::ValueDef::Annotation(loc_pattern, loc_type)) => {
+                vec![type_annotation_def2(arena, env, loc_pattern, loc_type)?]
+            }
+            Err(roc_parse::ast::ValueDef::AnnotatedBody {
+                ann_pattern,
+                ann_type,
+                comment: _,
+                body_pattern,
+                body_expr,
+            }) => {
+                vec![
+                    type_annotation_def2(arena, env, ann_pattern, ann_type)?,
+                    value_def2(arena, env, scope, body_pattern, body_expr, region)?,
+                ]
+            }
+            Err(roc_parse::ast::ValueDef::Expect {
+                condition,
+                preceding_comment: _,
+            }) => {
+                vec![expect_def2(arena, condition)]
             }
 
-            other => {
-                unimplemented!(
-                    "I don't know how to make an expr2 from this def yet: {:?}",
-                    other
-                )
+            Ok(type_def) => {
+                return SyntaxErrorNoBacktraceSnafu {
+                    msg: format!("I can't open type defs in the editor yet: {:?}", type_def),
+                }
+                .fail()
+            }
+            Err(other) => {
+                return SyntaxErrorNoBacktraceSnafu {
+                    msg: format!("I can't open this def in the editor yet: {:?}", other),
+                }
+                .fail()
             }
         };
 
@@ -68,25 +86,96 @@ pub fn toplevel_defs_to_defs2<'a>(
         let spaces_after = &parsed_defs.spaces[parsed_defs.space_after[index].indices()];
 
         if let Some(comments) = spaces_to_comments(spaces_before) {
-            let inner_def_id = env.pool.add(def);
-            def = Def2::CommentsBefore {
-                comments,
-                def_id: inner_def_id,
-            };
+            let inner_def_id = env.pool.add(defs.remove(0));
+            defs.insert(
+                0,
+                Def2::CommentsBefore {
+                    comments,
+                    def_id: inner_def_id,
+                },
+            );
         }
 
         if let Some(comments) = spaces_to_comments(spaces_after) {
-            let inner_def_id = env.pool.add(def);
-            def = Def2::CommentsAfter {
+            // safe because there is always at least one def
+            let inner_def_id = env.pool.add(defs.pop().unwrap());
+            defs.push(Def2::CommentsAfter {
                 comments,
                 def_id: inner_def_id,
-            };
+            });
         }
 
-        result.push(def)
+        result.append(&mut defs)
     }
 
-    result
+    Ok(result)
+}
+
+fn value_def2<'a>(
+    arena: &'a Bump,
+    env: &mut Env<'a>,
+    scope: &mut Scope,
+    loc_pattern: &Loc<Pattern<'a>>,
+    loc_expr: &Loc<Expr<'a>>,
+    region: Region,
+) -> ASTResult<Def2> {
+    let expr2 = loc_expr_to_expr2(arena, *loc_expr, env, scope, region).0;
+    let expr_id = env.pool.add(expr2);
+
+    match loc_pattern.value {
+        Pattern::Identifier(id_str) => {
+            let identifier_id = env.ident_ids.get_or_insert(id_str);
+
+            Ok(Def2::ValueDef {
+                identifier_id,
+                expr_id,
+            })
+        }
+        other => SyntaxErrorNoBacktraceSnafu {
+            msg: format!(
+                "I can only open defs that assign to a plain identifier in the editor, not {:?}",
+                other
+            ),
+        }
+        .fail(),
+    }
+}
+
+fn type_annotation_def2<'a>(
+    arena: &'a Bump,
+    env: &mut Env<'a>,
+    loc_pattern: &Loc<Pattern<'a>>,
+    loc_type: &Loc<TypeAnnotation<'a>>,
+) -> ASTResult<Def2> {
+    match loc_pattern.value {
+        Pattern::Identifier(id_str) => {
+            let identifier_id = env.ident_ids.get_or_insert(id_str);
+
+            let mut buf = Buf::new_in(arena);
+            loc_type.value.format(&mut buf, 0);
+
+            Ok(Def2::TypeAnnotation {
+                identifier_id,
+                type_str: buf.into_bump_str().to_owned(),
+            })
+        }
+        other => SyntaxErrorNoBacktraceSnafu {
+            msg: format!(
+                "I can only open annotations of a plain identifier in the editor, not {:?}",
+                other
+            ),
+        }
+        .fail(),
+    }
+}
+
+fn expect_def2<'a>(arena: &'a Bump, condition: &Loc<Expr<'a>>) -> Def2 {
+    let mut buf = Buf::new_in(arena);
+    condition.value.format(&mut buf, 0);
+
+    Def2::Expect {
+        condition: buf.into_bump_str().to_owned(),
+    }
 }
 
 fn all_newlines(comments: &[CommentOrNewline]) -> bool {
@@ -101,9 +190,8 @@ pub fn str_to_def2<'a>(
     env: &mut Env<'a>,
     scope: &mut Scope,
     region: Region,
-) -> Result<Vec<Def2>, SyntaxError<'a>> {
-    match roc_parse::test_helpers::parse_defs_with(arena, input.trim()) {
-        Ok(defs) => Ok(toplevel_defs_to_defs2(arena, env, scope, defs, region)),
-        Err(fail) => Err(fail),
-    }
+) -> ASTResult<Vec<Def2>> {
+    let defs = roc_parse::test_helpers::parse_defs_with(arena, input.trim())?;
+
+    toplevel_defs_to_defs2(arena, env, scope, defs, region)
 }
@@ -3,6 +3,7 @@ use super::expr::expr2::ExprId;
 #[derive(Debug)]
 pub struct AppHeader {
     pub app_name: String,
+    pub packages_shorthand: String, // e.g. `pf`
     pub packages_base: String,
     pub imports: Vec<String>,
     pub provides: Vec<String>,
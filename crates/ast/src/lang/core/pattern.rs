@@ -597,7 +597,9 @@ pub(crate) fn flatten_str_literal(pool: &mut Pool, literal: &StrLiteral<'_>) ->
     use roc_parse::ast::StrLiteral::*;
 
     match literal {
-        PlainLine(str_slice) => Pattern2::StrLiteral(PoolStr::new(str_slice, pool)),
+        PlainLine(str_slice) | Raw(str_slice) => {
+            Pattern2::StrLiteral(PoolStr::new(str_slice, pool))
+        }
         Line(segments) => flatten_str_lines(pool, &[segments]),
         Block(lines) => flatten_str_lines(pool, lines),
     }
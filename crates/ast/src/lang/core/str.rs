@@ -28,7 +28,7 @@ pub(crate) fn flatten_str_literal<'a>(
     use roc_parse::ast::StrLiteral::*;
 
     match literal {
-        PlainLine(str_slice) => {
+        PlainLine(str_slice) | Raw(str_slice) => {
             // TODO use smallstr
             let expr = Expr2::Str(PoolStr::new(str_slice, env.pool));
 
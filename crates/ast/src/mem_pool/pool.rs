@@ -87,6 +87,29 @@ pub struct Pool {
     num_nodes: u32,
     capacity: u32,
     // free_1node_slots: Vec<NodeId<T>>,
+    /// The previous bytes of every node that was overwritten since start_journal was called.
+    /// Newly added nodes are not in here, nothing referred to them before they were added.
+    journal_opt: Option<Vec<(u32, [MaybeUninit<u8>; NODE_BYTES])>>,
+}
+
+/// A node that was overwritten, see Pool::start_journal.
+#[derive(Debug, Clone)]
+pub struct PoolChange {
+    index: u32,
+    before: [MaybeUninit<u8>; NODE_BYTES],
+    after: [MaybeUninit<u8>; NODE_BYTES],
+}
+
+impl PoolChange {
+    /// Combine the changes of two consecutive journals into one
+    pub fn merge(earlier: &mut Vec<PoolChange>, later: Vec<PoolChange>) {
+        for change in later {
+            match earlier.iter_mut().find(|prev| prev.index == change.index) {
+                Some(prev) => prev.after = change.after,
+                None => earlier.push(change),
+            }
+        }
+    }
 }
 
 impl Pool {
@@ -143,9 +166,67 @@ impl Pool {
             nodes,
             num_nodes: 0,
             capacity,
+            journal_opt: None,
+        }
+    }
+
+    /// Start keeping track of the nodes that are overwritten, so the changes can be undone.
+    pub fn start_journal(&mut self) {
+        self.journal_opt = Some(Vec::new());
+    }
+
+    /// Stop keeping track of overwritten nodes, and return the changes made since start_journal.
+    pub fn take_journal(&mut self) -> Vec<PoolChange> {
+        let journal = self.journal_opt.take().unwrap_or_default();
+
+        journal
+            .into_iter()
+            .map(|(index, before)| PoolChange {
+                index,
+                before,
+                after: self.read_node_bytes(index),
+            })
+            .collect()
+    }
+
+    pub fn undo_changes(&mut self, changes: &[PoolChange]) {
+        for change in changes.iter().rev() {
+            self.write_node_bytes(change.index, change.before);
         }
     }
 
+    pub fn redo_changes(&mut self, changes: &[PoolChange]) {
+        for change in changes {
+            self.write_node_bytes(change.index, change.after);
+        }
+    }
+
+    /// Remember the current bytes of these nodes if a journal is active and they were not
+    /// overwritten before.
+    pub(super) fn record_overwrite(&mut self, first_index: u32, nr_of_nodes: u32) {
+        if self.journal_opt.is_none() {
+            return;
+        }
+
+        for index in first_index..first_index + nr_of_nodes {
+            let bytes = self.read_node_bytes(index);
+
+            if let Some(journal) = &mut self.journal_opt {
+                if !journal.iter().any(|(recorded, _)| *recorded == index) {
+                    journal.push((index, bytes));
+                }
+            }
+        }
+    }
+
+    fn read_node_bytes(&self, index: u32) -> [MaybeUninit<u8>; NODE_BYTES] {
+        unsafe { *self.nodes.offset(index as isize) }
+    }
+
+    fn write_node_bytes(&mut self, index: u32, bytes: [MaybeUninit<u8>; NODE_BYTES]) {
+        unsafe { *self.nodes.offset(index as isize) = bytes };
+    }
+
     pub fn add<T>(&mut self, node: T) -> NodeId<T> {
         // It's only safe to store this if T fits in S.
         debug_assert!(
@@ -192,6 +273,8 @@ impl Pool {
     }
 
     pub fn get_mut<T>(&mut self, node_id: NodeId<T>) -> &mut T {
+        self.record_overwrite(node_id.index, 1);
+
         unsafe {
             let node_ptr = self.get_ptr(node_id) as *mut T;
 
@@ -200,6 +283,8 @@ impl Pool {
     }
 
     pub fn set<T>(&mut self, node_id: NodeId<T>, element: T) {
+        self.record_overwrite(node_id.index, 1);
+
         unsafe {
             let node_ptr = self.get_ptr(node_id);
 
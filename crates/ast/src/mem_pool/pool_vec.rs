@@ -84,6 +84,8 @@ impl<'a, T: 'a + Sized> PoolVec<T> {
     }
 
     pub fn iter_mut(&self, pool: &'a mut Pool) -> impl ExactSizeIterator<Item = &'a mut T> {
+        pool.record_overwrite(self.first_node_id.index, self.len);
+
         self.pool_list_iter_mut(pool)
     }
 
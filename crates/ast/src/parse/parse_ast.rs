@@ -48,7 +48,7 @@ pub fn parse_from_string<'a>(
     let ast_node_id = env.pool.add(Expr2::Blank);
 
     Ok(AST {
-        header: parse_header::parse_from_string(header_str, ast_node_id),
+        header: parse_header::parse_from_string(header_str, ast_node_id, ast_arena)?,
         def_ids,
     })
 }
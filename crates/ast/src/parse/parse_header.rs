@@ -1,12 +1,111 @@
-use crate::lang::core::{expr::expr2::ExprId, header::AppHeader};
-
-// TODO don't use mock struct and actually parse string
-pub fn parse_from_string(_header_str: &str, ast_node_id: ExprId) -> AppHeader {
-    AppHeader {
-        app_name: "\"untitled-app\"".to_owned(),
-        packages_base: "\"rust-platform/main.roc\"".to_owned(),
-        imports: vec![],
-        provides: vec!["main".to_owned()],
+use bumpalo::Bump;
+use roc_fmt::{annotation::Formattable, Buf};
+use roc_parse::{
+    ast::{Header, Spaced, StrLiteral},
+    module::parse_header,
+    state::State,
+};
+
+use crate::{
+    ast_error::{ASTResult, SyntaxErrorNoBacktraceSnafu},
+    lang::core::{expr::expr2::ExprId, header::AppHeader},
+};
+
+// Only app headers are supported for now.
+pub fn parse_from_string<'a>(
+    header_str: &'a str,
+    ast_node_id: ExprId,
+    ast_arena: &'a Bump,
+) -> ASTResult<AppHeader> {
+    let app_header = match parse_header(ast_arena, State::new(header_str.as_bytes())) {
+        Ok((module, _)) => match module.header {
+            Header::App(app_header) => app_header,
+            _ => {
+                return SyntaxErrorNoBacktraceSnafu {
+                    msg: "I can only open app modules in the editor for now.".to_owned(),
+                }
+                .fail()
+            }
+        },
+        Err(src_err) => {
+            return SyntaxErrorNoBacktraceSnafu {
+                msg: format!("Failed to parse the app header: {:?}", src_err.problem),
+            }
+            .fail()
+        }
+    };
+
+    let app_name = match app_header.name.value {
+        StrLiteral::PlainLine(name) => format!("\"{}\"", name),
+        other => {
+            return SyntaxErrorNoBacktraceSnafu {
+                msg: format!("I can only handle a plain app name, not {:?}", other),
+            }
+            .fail()
+        }
+    };
+
+    let package_entry_opt = app_header.packages.and_then(|packages| {
+        packages
+            .item
+            .items
+            .first()
+            .map(|loc_entry| spaced_item(&loc_entry.value))
+    });
+
+    let (packages_shorthand, packages_base) = match package_entry_opt {
+        Some(package_entry) => (
+            package_entry.shorthand.to_owned(),
+            format!("\"{}\"", package_entry.package_name.value.as_str()),
+        ),
+        None => {
+            return SyntaxErrorNoBacktraceSnafu {
+                msg: "I expected the app header to have a platform in `packages`.".to_owned(),
+            }
+            .fail()
+        }
+    };
+
+    let imports = match app_header.imports {
+        Some(imports) => imports
+            .item
+            .items
+            .iter()
+            .map(|loc_entry| format_to_string(spaced_item(&loc_entry.value), ast_arena))
+            .collect(),
+        None => vec![],
+    };
+
+    let provides = app_header
+        .provides
+        .entries
+        .items
+        .iter()
+        .map(|loc_entry| format_to_string(spaced_item(&loc_entry.value), ast_arena))
+        .collect();
+
+    Ok(AppHeader {
+        app_name,
+        packages_shorthand,
+        packages_base,
+        imports,
+        provides,
         ast_node_id,
+    })
+}
+
+fn spaced_item<'a, T>(spaced: &'a Spaced<'a, T>) -> &'a T {
+    match spaced {
+        Spaced::Item(item) => item,
+        Spaced::SpaceBefore(inner, _) | Spaced::SpaceAfter(inner, _) => spaced_item(inner),
     }
 }
+
+// the formatter output, so e.g. `pf.Task.{ Task, await }` is written back the same way
+fn format_to_string<T: Formattable>(formattable: &T, arena: &Bump) -> String {
+    let mut buf = Buf::new_in(arena);
+
+    formattable.format(&mut buf, 0);
+
+    buf.into_bump_str().to_owned()
+}
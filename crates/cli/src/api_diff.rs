@@ -0,0 +1,312 @@
+//! Compare the public API of two versions of a package, so package authors can tell
+//! which version number to bump to when publishing.
+//!
+//! The API is everything the package's modules expose, along with the inferred type
+//! of each exposed value. Removing something, or changing the type of a value, is a
+//! breaking change. Exposing something new is an addition.
+use bumpalo::Bump;
+use roc_can::expr::DeclarationTag;
+use roc_collections::MutMap;
+use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, OverflowMode, Threading};
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_reporting::report::{ansi_color, RenderTarget, ANSI_STYLE_CODES, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Exposed {
+    /// An exposed value, along with its inferred type
+    Value(String),
+    /// An exposed type alias, opaque type, or ability
+    Type,
+}
+
+/// Everything a package exposes, keyed by fully-qualified name (e.g. `Json.decode`)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Api {
+    pub exposed: BTreeMap<String, Exposed>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Removed(String),
+    /// The name, its old type, and its new type
+    TypeChanged(String, String, String),
+    /// A value became a type or vice versa
+    KindChanged(String),
+    Added(String),
+}
+
+impl Change {
+    pub fn is_breaking(&self) -> bool {
+        !matches!(self, Change::Added(_))
+    }
+}
+
+impl Api {
+    pub fn from_loaded(loaded: LoadedModule) -> Self {
+        let interns = &loaded.interns;
+        let mut subs = loaded.solved.into_inner();
+        let mut declarations_by_id = loaded.declarations_by_id;
+
+        // Interface modules exposed by a package get docs generated for them, which tells us
+        // what they expose. If there are none, this is a lone interface module.
+        let mut exposed_by_module: Vec<(ModuleId, Vec<Symbol>)> = loaded
+            .docs_by_module
+            .iter()
+            .map(|(module_id, docs)| (*module_id, docs.exposed_symbols.iter().copied().collect()))
+            .collect();
+
+        if exposed_by_module.is_empty() {
+            exposed_by_module.push((loaded.module_id, loaded.exposed_values.clone()));
+        }
+
+        let mut exposed = BTreeMap::new();
+
+        for (module_id, symbols) in exposed_by_module {
+            let mut vars_by_symbol = MutMap::default();
+
+            if let Some(declarations) = declarations_by_id.remove(&module_id) {
+                for index in 0..declarations.len() {
+                    use DeclarationTag::*;
+
+                    if let Value | Function(_) | Recursive(_) | TailRecursive(_) =
+                        declarations.declarations[index]
+                    {
+                        vars_by_symbol.insert(
+                            declarations.symbols[index].value,
+                            declarations.variables[index],
+                        );
+                    }
+                }
+            }
+
+            for symbol in symbols {
+                let name = format!(
+                    "{}.{}",
+                    interns.module_name(symbol.module_id()),
+                    symbol.as_str(interns)
+                );
+                let entry = match vars_by_symbol.get(&symbol) {
+                    Some(var) => Exposed::Value(name_and_print_var(
+                        *var,
+                        &mut subs,
+                        module_id,
+                        interns,
+                        DebugPrint::NOTHING,
+                    )),
+                    None => Exposed::Type,
+                };
+
+                exposed.insert(name, entry);
+            }
+        }
+
+        Api { exposed }
+    }
+
+    /// Every difference between this (older) API and the given newer one, in name order
+    pub fn diff(&self, new: &Api) -> Vec<Change> {
+        let mut changes = Vec::new();
+
+        for (name, old_entry) in self.exposed.iter() {
+            match (old_entry, new.exposed.get(name)) {
+                (_, None) => changes.push(Change::Removed(name.clone())),
+                (Exposed::Value(old_type), Some(Exposed::Value(new_type))) => {
+                    // We can't yet tell whether the new type is strictly more general than the
+                    // old one, so any change to it has to be treated as breaking.
+                    if old_type != new_type {
+                        changes.push(Change::TypeChanged(
+                            name.clone(),
+                            old_type.clone(),
+                            new_type.clone(),
+                        ));
+                    }
+                }
+                (Exposed::Type, Some(Exposed::Type)) => {}
+                (_, Some(_)) => changes.push(Change::KindChanged(name.clone())),
+            }
+        }
+
+        for name in new.exposed.keys() {
+            if !self.exposed.contains_key(name) {
+                changes.push(Change::Added(name.clone()));
+            }
+        }
+
+        changes.sort_by(|a, b| change_name(a).cmp(change_name(b)));
+
+        changes
+    }
+}
+
+fn change_name(change: &Change) -> &str {
+    match change {
+        Change::Removed(name)
+        | Change::TypeChanged(name, _, _)
+        | Change::KindChanged(name)
+        | Change::Added(name) => name,
+    }
+}
+
+fn load_api<'a>(
+    arena: &'a Bump,
+    path: &Path,
+    threading: Threading,
+) -> Result<Api, LoadingProblem<'a>> {
+    let load_config = LoadConfig {
+        // only used for generating errors, since we don't do code generation here
+        target_info: TargetInfo::default_x86_64(),
+        render: RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE,
+        threading,
+        exec_mode: ExecutionMode::Check,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants: true,
+    };
+    let loaded = roc_load::load_and_typecheck(
+        arena,
+        path.to_path_buf(),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        load_config,
+    )?;
+
+    Ok(Api::from_loaded(loaded))
+}
+
+/// Print the differences between the APIs of the two given versions of a package, along with
+/// which part of the version number ought to be bumped. Returns exit code 1 if any of the
+/// changes are breaking, so this can be used in CI to catch accidental breakage.
+pub fn api_diff(old_path: &Path, new_path: &Path, threading: Threading) -> io::Result<i32> {
+    let mut apis = Vec::with_capacity(2);
+
+    for path in [old_path, new_path] {
+        let arena = Bump::new();
+
+        match load_api(&arena, path, threading) {
+            Ok(api) => apis.push(api),
+            Err(LoadingProblem::FormattedReport(report)) => {
+                print!("{}", report);
+
+                return Ok(1);
+            }
+            Err(other) => {
+                panic!("api_diff failed with error:\n{:?}", other);
+            }
+        }
+    }
+
+    let changes = apis[0].diff(&apis[1]);
+    let breaking = changes.iter().filter(|change| change.is_breaking()).count();
+
+    for change in changes.iter() {
+        match change {
+            Change::Removed(name) => println!(
+                "{} (removed)",
+                ansi_color(ANSI_STYLE_CODES.red, format!("- {name}"))
+            ),
+            Change::TypeChanged(name, old_type, new_type) => println!(
+                "{} (type changed)\n      was: {old_type}\n      now: {new_type}",
+                ansi_color(ANSI_STYLE_CODES.red, format!("~ {name}"))
+            ),
+            Change::KindChanged(name) => println!(
+                "{} (changed between a type and a value)",
+                ansi_color(ANSI_STYLE_CODES.red, format!("~ {name}"))
+            ),
+            Change::Added(name) => println!(
+                "{} (added)",
+                ansi_color(ANSI_STYLE_CODES.green, format!("+ {name}"))
+            ),
+        }
+    }
+
+    if !changes.is_empty() {
+        println!();
+    }
+
+    if breaking > 0 {
+        println!(
+            "Found {breaking} breaking {}. This needs a major version bump (or a minor one, for 0.x versions).",
+            if breaking == 1 { "change" } else { "changes" }
+        );
+
+        Ok(1)
+    } else if !changes.is_empty() {
+        println!("Found only additions. This needs a minor version bump (or a patch one, for 0.x versions).");
+
+        Ok(0)
+    } else {
+        println!("The exposed API is unchanged, so a patch version bump is enough.");
+
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api(entries: &[(&str, Option<&str>)]) -> Api {
+        Api {
+            exposed: entries
+                .iter()
+                .map(|(name, opt_type)| {
+                    let entry = match opt_type {
+                        Some(type_str) => Exposed::Value(type_str.to_string()),
+                        None => Exposed::Type,
+                    };
+
+                    (name.to_string(), entry)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn classify_changes() {
+        let old = api(&[
+            ("Json.Json", None),
+            ("Json.decode", Some("List U8 -> Result a Str")),
+            ("Json.encode", Some("a -> List U8")),
+            ("Json.utf8", Some("Json")),
+        ]);
+        let new = api(&[
+            ("Json.Json", None),
+            ("Json.decode", Some("List U8 -> Result a DecodeError")),
+            ("Json.encode", Some("a -> List U8")),
+            ("Json.pretty", Some("Json")),
+        ]);
+
+        let changes = old.diff(&new);
+
+        assert_eq!(
+            changes,
+            vec![
+                Change::TypeChanged(
+                    "Json.decode".into(),
+                    "List U8 -> Result a Str".into(),
+                    "List U8 -> Result a DecodeError".into()
+                ),
+                Change::Added("Json.pretty".into()),
+                Change::Removed("Json.utf8".into()),
+            ]
+        );
+        assert_eq!(
+            changes.iter().filter(|change| change.is_breaking()).count(),
+            2
+        );
+    }
+
+    #[test]
+    fn unchanged() {
+        let old = api(&[("Json.Json", None), ("Json.utf8", Some("Json"))]);
+
+        assert!(old.diff(&old.clone()).is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+//! Report which exposed values can reach a `crash` (or another runtime error) through the calls
+//! they make, with the shortest chain of calls that gets there. Library authors can use this to
+//! keep their APIs total, and app authors to audit where their program can crash.
+use bumpalo::Bump;
+use roc_can::crash_reachability::{crash_path, CrashKind, CrashPath};
+use roc_collections::MutMap;
+use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, OverflowMode, Threading};
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_region::all::{LineInfo, Region};
+use roc_reporting::report::{ansi_color, RenderTarget, ANSI_STYLE_CODES, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Print the path to a crash of every exposed value that can crash. Returns exit code 1 if there
+/// are any, so CI can check that an API stays total.
+pub fn crash_paths(path: &Path, threading: Threading) -> io::Result<i32> {
+    let arena = Bump::new();
+
+    let load_config = LoadConfig {
+        // only used for generating errors, since we don't do code generation here
+        target_info: TargetInfo::default_x86_64(),
+        render: RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE,
+        threading,
+        exec_mode: ExecutionMode::Check,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants: true,
+    };
+
+    let loaded = match roc_load::load_and_typecheck(
+        &arena,
+        path.to_path_buf(),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        load_config,
+    ) {
+        Ok(loaded) => loaded,
+        Err(LoadingProblem::FormattedReport(report)) => {
+            print!("{}", report);
+
+            return Ok(1);
+        }
+        Err(other) => {
+            panic!("crash_paths failed with error:\n{:?}", other);
+        }
+    };
+
+    let exposed = exposed_values(&loaded);
+    let mut locations = Locations::new(&loaded.sources);
+    let mut crashing = 0;
+
+    for symbol in exposed.iter() {
+        if let Some(path) = crash_path(&loaded.crash_summaries, *symbol) {
+            crashing += 1;
+
+            print_path(&loaded.interns, &mut locations, *symbol, &path);
+        }
+    }
+
+    match crashing {
+        0 => {
+            println!("None of the {} exposed values can crash.", exposed.len());
+
+            Ok(0)
+        }
+        _ => {
+            println!(
+                "{} of the {} exposed values can crash.",
+                crashing,
+                exposed.len()
+            );
+
+            Ok(1)
+        }
+    }
+}
+
+/// The values of the modules a package exposes, or what an app provides
+fn exposed_values(loaded: &LoadedModule) -> Vec<Symbol> {
+    let mut exposed: Vec<Symbol> = loaded
+        .docs_by_module
+        .values()
+        .flat_map(|docs| docs.exposed_symbols.iter().copied())
+        .collect();
+
+    if exposed.is_empty() {
+        exposed = loaded.exposed_values.clone();
+    }
+
+    // types are exposed too, but only values have a summary
+    exposed.retain(|symbol| {
+        loaded
+            .crash_summaries
+            .get(&symbol.module_id())
+            .map_or(false, |summary| summary.references.contains_key(symbol))
+    });
+
+    exposed
+}
+
+fn print_path(interns: &Interns, locations: &mut Locations, symbol: Symbol, path: &CrashPath) {
+    println!(
+        "{} can crash:",
+        ansi_color(ANSI_STYLE_CODES.yellow, qualified(interns, symbol))
+    );
+
+    for (index, step) in path.steps.iter().enumerate() {
+        let location = locations.describe(step.symbol.module_id(), step.region);
+
+        match path.steps.get(index + 1) {
+            Some(next) => println!(
+                "    {} calls {} at {}",
+                qualified(interns, step.symbol),
+                qualified(interns, next.symbol),
+                location
+            ),
+            None => {
+                let what = match path.kind {
+                    CrashKind::Crash => "a `crash`",
+                    CrashKind::NonExhaustiveWhen => "a `when` that doesn't cover every value",
+                    CrashKind::RuntimeError => "code with a compile error",
+                };
+
+                println!(
+                    "    {} has {} at {}",
+                    qualified(interns, step.symbol),
+                    what,
+                    location
+                );
+            }
+        }
+    }
+
+    println!();
+}
+
+pub(crate) fn qualified(interns: &Interns, symbol: Symbol) -> String {
+    format!(
+        "{}.{}",
+        interns.module_name(symbol.module_id()),
+        symbol.as_str(interns)
+    )
+}
+
+/// Turns regions into `path:line:column`, reading the lines of each module only once
+pub(crate) struct Locations<'a> {
+    sources: &'a MutMap<ModuleId, (PathBuf, Box<str>)>,
+    line_infos: MutMap<ModuleId, LineInfo>,
+}
+
+impl<'a> Locations<'a> {
+    pub(crate) fn new(sources: &'a MutMap<ModuleId, (PathBuf, Box<str>)>) -> Self {
+        Self {
+            sources,
+            line_infos: MutMap::default(),
+        }
+    }
+
+    pub(crate) fn describe(&mut self, module_id: ModuleId, region: Region) -> String {
+        let (path, src) = match self.sources.get(&module_id) {
+            Some(source) => source,
+            None => return "an unknown location".to_string(),
+        };
+
+        let line_info = self
+            .line_infos
+            .entry(module_id)
+            .or_insert_with(|| LineInfo::new(src));
+        let start = line_info.convert_region(region).start;
+
+        format!("{}:{}:{}", path.display(), start.line + 1, start.column + 1)
+    }
+}
@@ -0,0 +1,766 @@
+//! `roc test --cross-backend`: run the top-level `expect`s of a module through every code generation
+//! backend, and report the ones where the backends disagree.
+//!
+//! This is for finding miscompilations in the compiler, not for testing Roc code. The LLVM backend
+//! runs the expects like `roc test` does. The dev and wasm backends don't support `expect` yet, so for
+//! them every top-level expect is compiled into a function that returns its condition, which is then
+//! called directly. In all backends, `expect`s and `dbg`s inside of functions are left out, so that only
+//! the top-level conditions are compared.
+
+use bumpalo::Bump;
+use clap::ArgMatches;
+use roc_build::program::{
+    handle_error_module, handle_loading_problem, report_problems_monomorphized,
+};
+use roc_collections::all::{MutMap, MutSet};
+use roc_error_macros::user_error;
+use roc_gen_llvm::llvm::build::LlvmBackendMode;
+use roc_load::{
+    ExecutionMode, LoadConfig, LoadMonomorphizedError, MonomorphizedModule, OverflowMode, Threading,
+};
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_mono::ir::{Proc, ProcLayout, Stmt};
+use roc_mono::layout::{Layout, LayoutIds, Niche, STLayoutInterner};
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_region::all::{LineInfo, Region};
+use roc_reporting::report::{
+    ansi_color, in_charset, RenderTarget, ANSI_STYLE_CODES, DEFAULT_PALETTE,
+};
+use roc_target::TargetInfo;
+use std::ffi::OsStr;
+use std::io;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+use std::time::Instant;
+use target_lexicon::Triple;
+
+use crate::{opt_level_from_flags, FLAG_MAX_THREADS};
+
+type Procedures<'a> = MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>;
+
+/// What running one top-level expect on one backend did
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    Passed,
+    Failed,
+    /// The backend could not compile or run the expect, e.g. because it crashed
+    Error(String),
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Passed => write!(f, "passed"),
+            Outcome::Failed => write!(f, "failed"),
+            Outcome::Error(message) => write!(f, "error: {}", message),
+        }
+    }
+}
+
+/// The outcome of every top-level expect on one backend, by the region of the expect
+struct BackendOutcomes {
+    backend: &'static str,
+    outcomes: MutMap<Region, Outcome>,
+}
+
+impl BackendOutcomes {
+    fn new(
+        backend: &'static str,
+        regions: &[Region],
+        result: Result<Vec<Outcome>, String>,
+    ) -> Self {
+        let outcomes = match result {
+            Ok(outcomes) => regions.iter().copied().zip(outcomes).collect(),
+            // a backend that could not build the module has the same error for every expect
+            Err(message) => regions
+                .iter()
+                .map(|region| (*region, Outcome::Error(message.clone())))
+                .collect(),
+        };
+
+        BackendOutcomes { backend, outcomes }
+    }
+
+    fn get(&self, region: Region) -> Outcome {
+        self.outcomes
+            .get(&region)
+            .cloned()
+            .unwrap_or_else(|| Outcome::Error("this expect was not run".to_string()))
+    }
+}
+
+pub(crate) fn test_file_cross_backend(
+    matches: &ArgMatches,
+    triple: Triple,
+    filename: &OsStr,
+) -> io::Result<i32> {
+    let start_time = Instant::now();
+    let arena = Bump::new();
+    let path = Path::new(filename);
+
+    let mut loaded = match load(&arena, matches, path, &triple) {
+        Ok(loaded) => loaded,
+        Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+            return handle_loading_problem(problem);
+        }
+        Err(LoadMonomorphizedError::ErrorModule(module)) => {
+            return handle_error_module(module, start_time.elapsed(), filename, false);
+        }
+    };
+
+    let problems = report_problems_monomorphized(&mut loaded);
+
+    if problems.warnings > 0 {
+        problems.print_to_stdout(start_time.elapsed());
+        println!();
+    }
+
+    let expects: Vec<(Symbol, Region)> = loaded
+        .toplevel_expects
+        .pure
+        .iter()
+        .map(|(symbol, region)| (*symbol, *region))
+        .collect();
+
+    if expects.is_empty() {
+        println!("No expectations were found.");
+
+        return Ok(2);
+    }
+
+    let regions: Vec<Region> = expects.iter().map(|(_, region)| *region).collect();
+    let (src_path, line_info) = match loaded.sources.get(&loaded.module_id) {
+        Some((src_path, src)) => (src_path.clone(), LineInfo::new(src)),
+        None => (path.to_path_buf(), LineInfo::new("")),
+    };
+
+    let (llvm_procedures, condition_procedures) =
+        procedures_for_backends(&arena, std::mem::take(&mut loaded.procedures), &expects);
+
+    // the IR is rendered now, while the layout interner is still around
+    let condition_irs: MutMap<Region, String> = expects
+        .iter()
+        .filter_map(|(symbol, region)| {
+            let proc = condition_procedures.get(&(*symbol, condition_proc_layout()))?;
+
+            Some((*region, proc.to_pretty(&loaded.layout_interner, 200, true)))
+        })
+        .collect();
+
+    loaded.procedures = llvm_procedures;
+
+    let module_id = loaded.module_id;
+    let mut interns = loaded.interns.clone();
+    let mut expectations = std::mem::take(&mut loaded.expectations);
+    let opt_level = opt_level_from_flags(matches);
+
+    let (lib, llvm_expects, mut layout_interner) =
+        roc_repl_expect::run::expect_mono_module_to_dylib(
+            &arena,
+            triple.clone(),
+            loaded,
+            opt_level,
+            LlvmBackendMode::CliTest,
+        )
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+
+    let dev_result = catch_crash(|| {
+        run_dev(
+            &arena,
+            module_id,
+            &mut interns,
+            &mut layout_interner,
+            &triple,
+            condition_procedures,
+            &expects,
+        )
+    });
+
+    let wasm_result = catch_crash(|| run_wasm(matches, path, &regions));
+
+    let run_arena = Bump::new();
+    let run_interns = run_arena.alloc(interns);
+
+    let llvm_outcomes = roc_repl_expect::run::run_toplevel_expects_separately(
+        RenderTarget::Generic,
+        &run_arena,
+        run_interns,
+        &layout_interner.into_global(),
+        &lib,
+        &mut expectations,
+        llvm_expects,
+    )?
+    .into_iter()
+    .filter(|result| result.module_id == module_id)
+    .map(|result| {
+        let outcome = if result.passed {
+            Outcome::Passed
+        } else {
+            Outcome::Failed
+        };
+
+        (result.region, outcome)
+    })
+    .collect();
+
+    let backends = [
+        BackendOutcomes {
+            backend: "llvm",
+            outcomes: llvm_outcomes,
+        },
+        BackendOutcomes::new("dev", &regions, dev_result),
+        BackendOutcomes::new("wasm", &regions, wasm_result),
+    ];
+
+    let mut divergences = 0;
+
+    for region in regions.iter().copied() {
+        let outcomes = match disagreement(&backends, region) {
+            Some(outcomes) => outcomes,
+            None => continue,
+        };
+
+        divergences += 1;
+
+        let line = line_info.convert_pos(region.start()).line + 1;
+        let location = format!("{}:{}", src_path.display(), line);
+
+        print!(
+            "{}",
+            report_disagreement(&location, &backends, &outcomes, condition_irs.get(&region))
+        );
+    }
+
+    println!(
+        "\n{} of {} expects gave the same result on every backend, in {} ms.",
+        regions.len() - divergences,
+        regions.len(),
+        start_time.elapsed().as_millis()
+    );
+
+    Ok((divergences > 0) as i32)
+}
+
+/// The outcome of every backend for the expect at `region`, if they are not all the same
+fn disagreement(backends: &[BackendOutcomes], region: Region) -> Option<Vec<Outcome>> {
+    let outcomes: Vec<Outcome> = backends.iter().map(|backend| backend.get(region)).collect();
+
+    if outcomes.iter().all(|outcome| outcome == &outcomes[0]) {
+        None
+    } else {
+        Some(outcomes)
+    }
+}
+
+/// The report for an expect at `location` (a `path:line`) that the backends disagree about
+fn report_disagreement(
+    location: &str,
+    backends: &[BackendOutcomes],
+    outcomes: &[Outcome],
+    condition_ir: Option<&String>,
+) -> String {
+    let header = format!("── BACKENDS DISAGREE ─ {} {}", location, "─".repeat(40));
+    let mut report = format!(
+        "{}\n\n",
+        ansi_color(ANSI_STYLE_CODES.red, in_charset(&header))
+    );
+
+    for (backend, outcome) in backends.iter().zip(outcomes.iter()) {
+        report.push_str(&format!("    {:<6}{}\n", backend.backend, outcome));
+    }
+
+    if let Some(ir) = condition_ir {
+        report.push_str(&format!(
+            "\nThe mono IR of this expect's condition:\n\n{}\n\n",
+            ir
+        ));
+    }
+
+    report
+}
+
+fn load<'a>(
+    arena: &'a Bump,
+    matches: &ArgMatches,
+    path: &Path,
+    triple: &Triple,
+) -> Result<MonomorphizedModule<'a>, LoadMonomorphizedError<'a>> {
+    let threading = match matches
+        .value_of(FLAG_MAX_THREADS)
+        .and_then(|s| s.parse::<usize>().ok())
+    {
+        None => Threading::AllAvailable,
+        Some(0) => user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(n),
+    };
+
+    let load_config = LoadConfig {
+        target_info: TargetInfo::from(triple),
+        render: RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE,
+        threading,
+        exec_mode: ExecutionMode::Test,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants: true,
+    };
+
+    roc_load::load_and_monomorphize(
+        arena,
+        path.to_path_buf(),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        load_config,
+    )
+}
+
+/// A crash of a backend becomes the outcome of the expects, instead of stopping the whole comparison.
+fn catch_crash<T>(run: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    match catch_unwind(AssertUnwindSafe(run)) {
+        Ok(result) => result,
+        Err(payload) => {
+            let message = if let Some(message) = payload.downcast_ref::<&str>() {
+                message.to_string()
+            } else if let Some(message) = payload.downcast_ref::<String>() {
+                message.clone()
+            } else {
+                "(no message)".to_string()
+            };
+
+            Err(format!("the backend crashed: {}", message))
+        }
+    }
+}
+
+/// A top-level expect is a proc without arguments that returns `{}`
+fn expect_proc_layout<'a>() -> ProcLayout<'a> {
+    ProcLayout {
+        arguments: &[],
+        result: Layout::UNIT,
+        niche: Niche::NONE,
+    }
+}
+
+/// ..and the proc that returns its condition instead returns a `Bool`
+fn condition_proc_layout<'a>() -> ProcLayout<'a> {
+    ProcLayout {
+        arguments: &[],
+        result: Layout::BOOL,
+        niche: Niche::NONE,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ExpectRewrite {
+    /// Continue as if the `expect` (or `dbg`) was not there
+    Drop,
+    /// Return the condition of the `expect`
+    ReturnCondition,
+}
+
+/// The procedures for the LLVM backend, which still runs the top-level expects as `expect`s,
+/// and for the other backends, where every top-level expect returns its condition.
+fn procedures_for_backends<'a>(
+    arena: &'a Bump,
+    procedures: Procedures<'a>,
+    expects: &[(Symbol, Region)],
+) -> (Procedures<'a>, Procedures<'a>) {
+    let expect_symbols: MutSet<Symbol> = expects.iter().map(|(symbol, _)| *symbol).collect();
+
+    let mut llvm_procedures = MutMap::default();
+    let mut condition_procedures = MutMap::default();
+
+    for ((symbol, proc_layout), proc) in procedures {
+        if expect_symbols.contains(&symbol) && proc_layout == expect_proc_layout() {
+            let condition_proc = Proc {
+                body: rewrite_expects(arena, &proc.body, ExpectRewrite::ReturnCondition),
+                ret_layout: Layout::BOOL,
+                ..proc.clone()
+            };
+
+            condition_procedures.insert((symbol, condition_proc_layout()), condition_proc);
+            llvm_procedures.insert((symbol, proc_layout), proc);
+        } else {
+            let proc = Proc {
+                body: rewrite_expects(arena, &proc.body, ExpectRewrite::Drop),
+                ..proc
+            };
+
+            condition_procedures.insert((symbol, proc_layout), proc.clone());
+            llvm_procedures.insert((symbol, proc_layout), proc);
+        }
+    }
+
+    (llvm_procedures, condition_procedures)
+}
+
+fn rewrite_expects<'a>(arena: &'a Bump, stmt: &Stmt<'a>, rewrite: ExpectRewrite) -> Stmt<'a> {
+    let rewrite_in =
+        |stmt: &Stmt<'a>| -> &'a Stmt<'a> { arena.alloc(rewrite_expects(arena, stmt, rewrite)) };
+
+    match stmt {
+        Stmt::Let(symbol, expr, layout, continuation) => {
+            Stmt::Let(*symbol, expr.clone(), *layout, rewrite_in(continuation))
+        }
+        Stmt::Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout,
+        } => {
+            let branches = bumpalo::collections::Vec::from_iter_in(
+                branches.iter().map(|(tag, info, branch)| {
+                    (*tag, info.clone(), rewrite_expects(arena, branch, rewrite))
+                }),
+                arena,
+            );
+
+            Stmt::Switch {
+                cond_symbol: *cond_symbol,
+                cond_layout: *cond_layout,
+                branches: branches.into_bump_slice(),
+                default_branch: (default_branch.0.clone(), rewrite_in(default_branch.1)),
+                ret_layout: *ret_layout,
+            }
+        }
+        Stmt::Refcounting(modify, continuation) => {
+            Stmt::Refcounting(*modify, rewrite_in(continuation))
+        }
+        Stmt::Expect {
+            condition,
+            remainder,
+            ..
+        }
+        | Stmt::ExpectFx {
+            condition,
+            remainder,
+            ..
+        } => match rewrite {
+            ExpectRewrite::Drop => rewrite_expects(arena, remainder, rewrite),
+            ExpectRewrite::ReturnCondition => Stmt::Ret(*condition),
+        },
+        Stmt::Dbg { remainder, .. } => rewrite_expects(arena, remainder, rewrite),
+        Stmt::Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => Stmt::Join {
+            id: *id,
+            parameters: *parameters,
+            body: rewrite_in(body),
+            remainder: rewrite_in(remainder),
+        },
+        Stmt::Ret(_) | Stmt::Jump(..) | Stmt::Crash(..) => stmt.clone(),
+    }
+}
+
+fn run_dev<'a>(
+    arena: &'a Bump,
+    module_id: ModuleId,
+    interns: &mut Interns,
+    layout_interner: &mut STLayoutInterner<'a>,
+    triple: &Triple,
+    procedures: Procedures<'a>,
+    expects: &[(Symbol, Region)],
+) -> Result<Vec<Outcome>, String> {
+    use roc_build::link::{link, LinkType};
+
+    let env = roc_gen_dev::Env {
+        arena,
+        module_id,
+        exposed_to_host: expects.iter().map(|(symbol, _)| *symbol).collect(),
+        lazy_literals: false,
+        mode: roc_gen_dev::AssemblyBackendMode::Test,
+    };
+
+    let module_object =
+        roc_gen_dev::build_module(&env, interns, layout_interner, triple, procedures);
+    let module_bytes = module_object.write().map_err(|err| err.to_string())?;
+
+    let dir = tempfile::tempdir().map_err(|err| err.to_string())?;
+    let app_o_file = dir.path().join("app.o");
+    std::fs::write(&app_o_file, module_bytes).map_err(|err| err.to_string())?;
+
+    let builtins_host_tempfile = roc_bitcode::host_tempfile().map_err(|err| err.to_string())?;
+
+    let (mut child, dylib_path) = link(
+        triple,
+        app_o_file.clone(),
+        &[
+            app_o_file.to_str().unwrap(),
+            builtins_host_tempfile.path().to_str().unwrap(),
+        ],
+        LinkType::Dylib,
+    )
+    .map_err(|err| err.to_string())?;
+
+    child.wait().map_err(|err| err.to_string())?;
+
+    let lib = unsafe { libloading::Library::new(&dylib_path) }.map_err(|err| err.to_string())?;
+
+    let mut layout_ids = LayoutIds::default();
+
+    let outcomes = expects
+        .iter()
+        .map(|(symbol, _)| {
+            let name = layout_ids
+                .get_toplevel(*symbol, &condition_proc_layout())
+                .to_exposed_symbol_string(*symbol, interns);
+
+            let condition: libloading::Symbol<unsafe extern "C" fn() -> bool> =
+                match unsafe { lib.get(name.as_bytes()) } {
+                    Ok(condition) => condition,
+                    Err(err) => return Outcome::Error(err.to_string()),
+                };
+
+            if unsafe { condition() } {
+                Outcome::Passed
+            } else {
+                Outcome::Failed
+            }
+        })
+        .collect();
+
+    Ok(outcomes)
+}
+
+/// The wasm backend compiles for wasm32, so the module is loaded again, with 32-bit layouts.
+/// The expects are run in Roc's WebAssembly interpreter.
+#[cfg(feature = "run-wasm32")]
+fn run_wasm(matches: &ArgMatches, path: &Path, regions: &[Region]) -> Result<Vec<Outcome>, String> {
+    use roc_wasm_interp::Instance;
+
+    let arena = Bump::new();
+    let triple = crate::Target::Wasm32.to_triple();
+
+    let mut loaded = load(&arena, matches, path, &triple)
+        .map_err(|_| "the module does not compile for wasm32".to_string())?;
+
+    // the symbols of the expects are not the same as in the first load, but their regions are
+    let expects: Vec<(Symbol, Region)> = loaded
+        .toplevel_expects
+        .pure
+        .iter()
+        .map(|(symbol, region)| (*symbol, *region))
+        .collect();
+
+    let (_, procedures) =
+        procedures_for_backends(&arena, std::mem::take(&mut loaded.procedures), &expects);
+
+    let env = roc_gen_wasm::Env {
+        arena: &arena,
+        module_id: loaded.module_id,
+        exposed_to_host: expects.iter().map(|(symbol, _)| *symbol).collect(),
+        stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+    };
+
+    let host_tempfile = roc_bitcode::host_wasm_tempfile().map_err(|err| err.to_string())?;
+    let host_bytes = std::fs::read(host_tempfile.path()).map_err(|err| err.to_string())?;
+    let host_module =
+        roc_gen_wasm::parse_host(&arena, &host_bytes).map_err(|err| format!("{:?}", err))?;
+
+    let (wasm_module, _, _) = roc_gen_wasm::build_app_module(
+        &env,
+        &mut loaded.layout_interner,
+        &mut loaded.interns,
+        host_module,
+        procedures,
+    );
+
+    let mut wasm_bytes = Vec::with_capacity(wasm_module.size());
+    wasm_module.serialize(&mut wasm_bytes);
+
+    let mut layout_ids = LayoutIds::default();
+    let mut outcomes = MutMap::default();
+
+    for (symbol, region) in expects {
+        // every expect gets a fresh instance, so a trap in one does not affect the others
+        let mut instance =
+            Instance::from_bytes(&arena, &wasm_bytes, TestImports::default(), false)?;
+
+        // gen_wasm exports the exposed procs that the host does not call under these names
+        let name = layout_ids
+            .get_toplevel(symbol, &condition_proc_layout())
+            .to_exposed_symbol_string(symbol, &loaded.interns);
+
+        let outcome = match instance.call_export(&name, []) {
+            Ok(Some(value)) => match value.expect_i32() {
+                Ok(0) => Outcome::Failed,
+                Ok(_) => Outcome::Passed,
+                Err(_) => Outcome::Error(format!("{} did not return a Bool", name)),
+            },
+            Ok(None) => Outcome::Error(format!("{} did not return a value", name)),
+            Err(message) => Outcome::Error(message),
+        };
+
+        outcomes.insert(region, outcome);
+    }
+
+    Ok(regions
+        .iter()
+        .map(|region| {
+            outcomes
+                .remove(region)
+                .unwrap_or_else(|| Outcome::Error("this expect was not run".to_string()))
+        })
+        .collect())
+}
+
+#[cfg(not(feature = "run-wasm32"))]
+fn run_wasm(
+    _matches: &ArgMatches,
+    _path: &Path,
+    _regions: &[Region],
+) -> Result<Vec<Outcome>, String> {
+    Err("this roc was built without the run-wasm32 feature".to_string())
+}
+
+/// The host functions that the builtins need, for running expects without a platform.
+/// Memory is never freed: it is allocated downwards from the end of the memory, and an instance
+/// only runs one expect.
+#[cfg(feature = "run-wasm32")]
+struct TestImports {
+    heap_top: Option<u32>,
+    wasi: roc_wasm_interp::WasiDispatcher<'static>,
+}
+
+#[cfg(feature = "run-wasm32")]
+impl Default for TestImports {
+    fn default() -> Self {
+        TestImports {
+            heap_top: None,
+            wasi: roc_wasm_interp::WasiDispatcher::new(&[]),
+        }
+    }
+}
+
+#[cfg(feature = "run-wasm32")]
+impl TestImports {
+    fn alloc(&mut self, memory: &[u8], size: u32, alignment: u32) -> u32 {
+        let top = self.heap_top.unwrap_or(memory.len() as u32);
+        let alignment = alignment.max(1);
+        let address = top.saturating_sub(size) / alignment * alignment;
+
+        self.heap_top = Some(address);
+
+        address
+    }
+}
+
+#[cfg(feature = "run-wasm32")]
+impl roc_wasm_interp::ImportDispatcher for TestImports {
+    fn dispatch(
+        &mut self,
+        module_name: &str,
+        function_name: &str,
+        arguments: &[roc_wasm_interp::Value],
+        memory: &mut [u8],
+    ) -> Option<roc_wasm_interp::Value> {
+        use roc_wasm_interp::Value;
+
+        let arg = |index: usize| match arguments.get(index) {
+            Some(Value::I32(value)) => *value as u32,
+            _ => 0,
+        };
+
+        match (module_name, function_name) {
+            (_, "roc_alloc") => Some(Value::I32(self.alloc(memory, arg(0), arg(1)) as i32)),
+            (_, "roc_realloc") => {
+                let (old_ptr, new_size, old_size) = (arg(0), arg(1), arg(2));
+                let new_ptr = self.alloc(memory, new_size, arg(3));
+                let copied = old_size.min(new_size) as usize;
+
+                memory.copy_within(
+                    old_ptr as usize..old_ptr as usize + copied,
+                    new_ptr as usize,
+                );
+
+                Some(Value::I32(new_ptr as i32))
+            }
+            // the wasm traps right after a panic, which is how the expect ends up as an error
+            (_, "roc_dealloc") | (_, "roc_panic") | (_, "roc_dbg") => None,
+            (roc_wasm_interp::wasi::MODULE_NAME, _) => {
+                self.wasi.dispatch(function_name, arguments, memory)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roc_region::all::Position;
+
+    fn region(start: u32, end: u32) -> Region {
+        Region::new(Position::new(start), Position::new(end))
+    }
+
+    #[test]
+    fn report_diverging_backends() {
+        let agreed = region(0, 10);
+        let diverged = region(20, 30);
+        let regions = [agreed, diverged];
+
+        // the dev backend deliberately miscompiles the second expect, and wasm can't build at all
+        let backends = [
+            BackendOutcomes::new("llvm", &regions, Ok(vec![Outcome::Passed, Outcome::Passed])),
+            BackendOutcomes::new("dev", &regions, Ok(vec![Outcome::Passed, Outcome::Failed])),
+            BackendOutcomes::new("wasm", &regions, Err("no wasm32".to_string())),
+        ];
+
+        let outcomes = disagreement(&backends, diverged).unwrap();
+
+        assert_eq!(
+            outcomes,
+            vec![
+                Outcome::Passed,
+                Outcome::Failed,
+                Outcome::Error("no wasm32".to_string())
+            ]
+        );
+
+        let ir = "procedure Test.1 ():\n    ret Test.2;".to_string();
+        let report = report_disagreement("Test.roc:3", &backends, &outcomes, Some(&ir));
+
+        assert!(report.contains("BACKENDS DISAGREE"));
+        assert!(report.contains("Test.roc:3"));
+        assert!(report.contains("    llvm  passed\n"));
+        assert!(report.contains("    dev   failed\n"));
+        assert!(report.contains("    wasm  error: no wasm32\n"));
+        assert!(report.contains(&ir));
+    }
+
+    #[test]
+    fn agreeing_backends_are_not_reported() {
+        let regions = [region(0, 10)];
+
+        let backends = [
+            BackendOutcomes::new("llvm", &regions, Ok(vec![Outcome::Failed])),
+            BackendOutcomes::new("dev", &regions, Ok(vec![Outcome::Failed])),
+        ];
+
+        assert_eq!(disagreement(&backends, regions[0]), None);
+    }
+
+    #[test]
+    fn expect_missing_from_a_backend_is_reported() {
+        let regions = [region(0, 10)];
+
+        let backends = [
+            BackendOutcomes::new("llvm", &regions, Ok(vec![Outcome::Passed])),
+            BackendOutcomes::new("dev", &[], Ok(vec![])),
+        ];
+
+        assert_eq!(
+            disagreement(&backends, regions[0]),
+            Some(vec![
+                Outcome::Passed,
+                Outcome::Error("this expect was not run".to_string())
+            ])
+        );
+    }
+}
@@ -0,0 +1,56 @@
+//! `roc demangle`: turn the names the compiler gives specializations in object files, like
+//! `List_walk_00c0ffee00c0ffee`, back into the names of the functions they come from. Without
+//! arguments it works like `c++filt`, and replaces every name in its input, so the output of a
+//! profiler can be piped through it.
+use crate::SYMBOL_NAMES;
+use clap::ArgMatches;
+use roc_mono::mangle;
+use std::io::{self, BufRead, Write};
+
+pub fn demangle(matches: &ArgMatches) -> io::Result<i32> {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    match matches.values_of(SYMBOL_NAMES) {
+        Some(names) => {
+            for name in names {
+                writeln!(stdout, "{}", demangle_line(name))?;
+            }
+        }
+        None => {
+            for line in io::stdin().lock().lines() {
+                writeln!(stdout, "{}", demangle_line(&line?))?;
+            }
+        }
+    }
+
+    Ok(0)
+}
+
+/// Replace every name in the line which [mangle::demangle] understands.
+fn demangle_line(line: &str) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(start) = rest.find(is_name_char) {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+        let (name, after) = rest.split_at(end);
+
+        match mangle::demangle(name) {
+            Some(demangled) => output.push_str(&demangled.to_string()),
+            None => output.push_str(name),
+        }
+
+        rest = after;
+    }
+
+    output.push_str(rest);
+    output
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '#')
+}
@@ -0,0 +1,216 @@
+//! `roc dev --watch`: on every change to a .roc file, check the app, then run its expects,
+//! and if both pass, (re)start the program.
+//!
+//! Every step runs this same `roc` executable as a child process, so its output is exactly what
+//! `roc check`, `roc test` and `roc dev` print. The watching process itself stays alive, and so does
+//! the program it started (e.g. a server), until the next change restarts it.
+
+use crate::{
+    ARGS_FOR_APP, CMD_CHECK, CMD_DEV, CMD_TEST, FLAG_BACKEND, FLAG_DEV, FLAG_LINKER,
+    FLAG_MAX_THREADS, FLAG_OPTIMIZE, FLAG_OPT_SIZE, FLAG_PREBUILT, ROC_FILE,
+};
+use clap::ArgMatches;
+use roc_reporting::report::{ansi_color, in_charset, ANSI_STYLE_CODES};
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often the .roc files are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// `roc test` exits with this code when there are no expects, which is fine in the dev loop.
+const NO_EXPECTS_EXIT_CODE: i32 = 2;
+
+pub fn dev_loop(matches: &ArgMatches) -> io::Result<i32> {
+    let roc_file = PathBuf::from(matches.value_of_os(ROC_FILE).unwrap());
+    let watched_dir = match roc_file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let roc_exe = std::env::current_exe()?;
+    let mut running_program: Option<Child> = None;
+    let mut last_change = latest_modification(&watched_dir);
+
+    loop {
+        stop(&mut running_program);
+
+        if run_step("check", &roc_exe, check_args(matches, &roc_file))? == 0
+            && matches!(
+                run_step("test", &roc_exe, test_args(matches, &roc_file))?,
+                0 | NO_EXPECTS_EXIT_CODE
+            )
+        {
+            print_separator("run");
+
+            running_program = Some(
+                Command::new(&roc_exe)
+                    .args(run_args(matches, &roc_file))
+                    .spawn()?,
+            );
+        }
+
+        let watching = format!("Watching {} for changes…", watched_dir.display());
+        println!(
+            "\n{}",
+            ansi_color(ANSI_STYLE_CODES.cyan, in_charset(&watching))
+        );
+
+        // wait for a change, and report when the program stops by itself in the meantime
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            if let Some(child) = running_program.as_mut() {
+                if let Some(status) = child.try_wait()? {
+                    print_separator(&format!("program exited with {}", status));
+                    running_program = None;
+                }
+            }
+
+            let latest_change = latest_modification(&watched_dir);
+
+            if latest_change > last_change {
+                last_change = latest_change;
+                break;
+            }
+        }
+    }
+}
+
+fn run_step(name: &str, roc_exe: &Path, args: Vec<OsString>) -> io::Result<i32> {
+    print_separator(name);
+
+    let status = Command::new(roc_exe).args(args).status()?;
+
+    // a step that was killed by a signal has no exit code
+    Ok(status.code().unwrap_or(1))
+}
+
+fn stop(running_program: &mut Option<Child>) {
+    if let Some(mut child) = running_program.take() {
+        // the program may have exited already, then there is nothing to kill
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+fn print_separator(name: &str) {
+    let dashes = 80usize.saturating_sub(name.len() + 4);
+
+    let header = format!("── {} {}", name, "─".repeat(dashes));
+    println!(
+        "\n{}\n",
+        ansi_color(ANSI_STYLE_CODES.cyan, in_charset(&header))
+    );
+}
+
+fn max_threads_args(matches: &ArgMatches) -> Vec<OsString> {
+    match matches.value_of(FLAG_MAX_THREADS) {
+        Some(max_threads) => vec![format!("--{}", FLAG_MAX_THREADS).into(), max_threads.into()],
+        None => Vec::new(),
+    }
+}
+
+fn check_args(matches: &ArgMatches, roc_file: &Path) -> Vec<OsString> {
+    let mut args: Vec<OsString> = vec![CMD_CHECK.into()];
+
+    args.extend(max_threads_args(matches));
+    args.push(roc_file.into());
+
+    args
+}
+
+fn test_args(matches: &ArgMatches, roc_file: &Path) -> Vec<OsString> {
+    let mut args: Vec<OsString> = vec![CMD_TEST.into()];
+
+    args.extend(max_threads_args(matches));
+    args.push(roc_file.into());
+
+    args
+}
+
+fn run_args(matches: &ArgMatches, roc_file: &Path) -> Vec<OsString> {
+    let mut args: Vec<OsString> = vec![CMD_DEV.into()];
+
+    args.extend(max_threads_args(matches));
+
+    for flag in [FLAG_OPTIMIZE, FLAG_OPT_SIZE, FLAG_DEV] {
+        if matches.is_present(flag) {
+            args.push(format!("--{}", flag).into());
+        }
+    }
+
+    for flag in [FLAG_BACKEND, FLAG_LINKER, FLAG_PREBUILT] {
+        if let Some(value) = matches.value_of(flag) {
+            args.push(format!("--{}={}", flag, value).into());
+        }
+    }
+
+    args.push(roc_file.into());
+
+    if let Some(app_args) = matches.values_of_os(ARGS_FOR_APP) {
+        args.push("--".into());
+        args.extend(app_args.map(OsString::from));
+    }
+
+    args
+}
+
+/// The most recent modification time of the .roc files in `dir` and its subdirectories
+fn latest_modification(dir: &Path) -> SystemTime {
+    let mut latest = SystemTime::UNIX_EPOCH;
+
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return latest,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        let modified = if path.is_dir() {
+            // build output and dependencies are not part of the app
+            let is_hidden = path
+                .file_name()
+                .map_or(false, |name| name.to_string_lossy().starts_with('.'));
+
+            if is_hidden {
+                continue;
+            }
+
+            latest_modification(&path)
+        } else if path.extension().map_or(false, |ext| ext == "roc") {
+            match entry.metadata().and_then(|metadata| metadata.modified()) {
+                Ok(modified) => modified,
+                Err(_) => continue,
+            }
+        } else {
+            continue;
+        };
+
+        latest = latest.max(modified);
+    }
+
+    latest
+}
+
+#[cfg(test)]
+mod test_dev_loop {
+    use super::latest_modification;
+    use std::time::SystemTime;
+
+    #[test]
+    fn only_roc_files_count() {
+        let dir = tempfile::tempdir().unwrap();
+
+        std::fs::write(dir.path().join("notes.txt"), "not roc").unwrap();
+        assert_eq!(latest_modification(dir.path()), SystemTime::UNIX_EPOCH);
+
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src").join("main.roc"), "app \"a\"").unwrap();
+        assert!(latest_modification(dir.path()) > SystemTime::UNIX_EPOCH);
+    }
+}
@@ -0,0 +1,475 @@
+//! Organize the imports of every module of a package (or an app): import what names which aren't
+//! in scope refer to, when only one module exposes them, and remove the imports that nothing
+//! uses. All the changes to a module's imports are made in a single edit of its header, which is
+//! written the way `roc format` writes it.
+use bumpalo::Bump;
+use roc_fmt::module::{fmt_module, replace_spaced_item};
+use roc_fmt::Buf;
+use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, OverflowMode, Threading};
+use roc_module::symbol::ModuleId;
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_parse::ast::{Collection, CommentOrNewline, ExtractSpaces, Header, Spaced, Spaces};
+use roc_parse::header::{ExposedName, ImportsEntry, ImportsKeyword, KeywordItem, ModuleName};
+use roc_parse::module::parse_header;
+use roc_parse::state::State;
+use roc_problem::can::{Problem, RuntimeError};
+use roc_region::all::Loc;
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+/// What to change about the imports of a module. Modules are named the way they are imported,
+/// like `Dict` or `pf.Task`.
+#[derive(Debug, Default)]
+pub struct ImportChanges {
+    /// The modules to import, each with the name to expose from it, if any
+    pub add: Vec<(String, Option<String>)>,
+    /// The modules that nothing is used from
+    pub remove_modules: Vec<String>,
+    /// The exposed names that are never used, each with the module it is imported from
+    pub remove_names: Vec<(String, String)>,
+}
+
+impl ImportChanges {
+    pub fn is_empty(&self) -> bool {
+        self.add.is_empty() && self.remove_modules.is_empty() && self.remove_names.is_empty()
+    }
+}
+
+/// Organize the imports of every module in the package of `path`, and write back the modules
+/// that changed.
+pub fn fix_imports(path: &Path, threading: Threading) -> io::Result<i32> {
+    let arena = Bump::new();
+
+    let load_config = LoadConfig {
+        // only used for generating errors, since we don't do code generation here
+        target_info: TargetInfo::default_x86_64(),
+        render: RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE,
+        threading,
+        exec_mode: ExecutionMode::Check,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants: true,
+    };
+
+    let loaded = match roc_load::load_and_typecheck(
+        &arena,
+        path.to_path_buf(),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        load_config,
+    ) {
+        Ok(loaded) => loaded,
+        Err(LoadingProblem::FormattedReport(report)) => {
+            print!("{}", report);
+
+            return Ok(1);
+        }
+        Err(other) => {
+            panic!("fix_imports failed with error:\n{:?}", other);
+        }
+    };
+
+    // only the modules of the root's package are ours to change
+    let mut modules: Vec<_> = loaded
+        .reference_summaries
+        .keys()
+        .filter_map(|module_id| {
+            let (module_path, src) = loaded.sources.get(module_id)?;
+
+            Some((module_path, src, *module_id))
+        })
+        .collect();
+    modules.sort_by_key(|(module_path, _, _)| *module_path);
+
+    let mut organized = 0;
+
+    for (module_path, src, module_id) in modules {
+        let changes = import_changes(&loaded, module_id);
+
+        if changes.is_empty() {
+            continue;
+        }
+
+        if let Some((range, header)) = organize_imports(src, &changes) {
+            let mut new_src = String::with_capacity(src.len());
+            new_src.push_str(&src[..range.start]);
+            new_src.push_str(&header);
+            new_src.push_str(&src[range.end..]);
+
+            std::fs::write(module_path, new_src)?;
+
+            println!("Organized the imports of {}", module_path.display());
+            organized += 1;
+        }
+    }
+
+    if organized == 0 {
+        println!("The imports are already organized.");
+    }
+
+    Ok(0)
+}
+
+/// What to change about the imports of a module, going by the problems canonicalization found
+/// in it: the imports it reported unused, the modules that are used without being imported, and
+/// the names that aren't in scope but are exposed by exactly one module.
+pub fn import_changes(loaded: &LoadedModule, module_id: ModuleId) -> ImportChanges {
+    let interns = &loaded.interns;
+    let mut changes = ImportChanges::default();
+
+    let problems = match loaded.can_problems.get(&module_id) {
+        Some(problems) => problems.as_slice(),
+        None => &[],
+    };
+
+    for problem in problems {
+        match problem {
+            Problem::UnusedModuleImport(unused, _) => {
+                changes.remove_modules.push(import_name(loaded, *unused));
+            }
+            Problem::UnusedImport(symbol, _) => {
+                let module_name = import_name(loaded, symbol.module_id());
+
+                changes
+                    .remove_names
+                    .push((module_name, symbol.as_str(interns).to_string()));
+            }
+            Problem::RuntimeError(RuntimeError::ModuleNotImported {
+                module_name,
+                module_exists: true,
+                ..
+            }) => {
+                let named = loaded
+                    .exposed_symbols_by_module
+                    .keys()
+                    .copied()
+                    .filter(|other| interns.module_name(*other) == module_name);
+
+                if let Some(found) = only(named) {
+                    changes.add.push((import_name(loaded, found), None));
+                }
+            }
+            Problem::RuntimeError(RuntimeError::LookupNotInScope(loc_ident, _)) => {
+                let name = loc_ident.value.as_str();
+
+                let exposing = loaded
+                    .exposed_symbols_by_module
+                    .iter()
+                    .filter(|(other, symbols)| {
+                        **other != module_id
+                            && symbols.iter().any(|symbol| symbol.as_str(interns) == name)
+                    })
+                    .map(|(other, _)| *other);
+
+                if let Some(found) = only(exposing) {
+                    changes
+                        .add
+                        .push((import_name(loaded, found), Some(name.to_string())));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    changes
+}
+
+/// The edit that makes `changes` to the imports of the module in `src`: the range of its header,
+/// and the header to put there instead. Returns `None` if nothing changes, or if the header
+/// can't have imports, like the header of a package.
+pub fn organize_imports(src: &str, changes: &ImportChanges) -> Option<(Range<usize>, String)> {
+    let arena = Bump::new();
+
+    let (mut module, state) = parse_header(&arena, State::new(src.as_bytes())).ok()?;
+    let header_end = src[..state.pos().offset as usize].trim_end().len();
+
+    match &mut module.header {
+        Header::Interface(header) => {
+            header.imports.item = change_imports(&arena, header.imports.item, changes);
+        }
+        Header::Platform(header) => {
+            header.imports.item = change_imports(&arena, header.imports.item, changes);
+        }
+        Header::Hosted(header) => {
+            header.imports.item = change_imports(&arena, header.imports.item, changes);
+        }
+        Header::App(header) => {
+            let imports = header
+                .imports
+                .as_ref()
+                .map_or_else(Collection::empty, |imports| imports.item);
+            let item = change_imports(&arena, imports, changes);
+
+            let keyword = match &header.imports {
+                Some(imports) => imports.keyword.clone(),
+                None if item.is_empty() => return None,
+                None => {
+                    // put `imports` on a line of its own if `provides` is on one
+                    let before: &[_] = if header.provides.provides_keyword.before.is_empty() {
+                        &[]
+                    } else {
+                        &[CommentOrNewline::Newline]
+                    };
+
+                    Spaces {
+                        before,
+                        item: ImportsKeyword,
+                        after: &[],
+                    }
+                }
+            };
+
+            header.imports = Some(KeywordItem { keyword, item });
+        }
+        Header::Package(_) => return None,
+    }
+
+    let mut buf = Buf::new_in(&arena);
+    fmt_module(&mut buf, &module);
+
+    let header = buf.as_str();
+
+    if header == &src[..header_end] {
+        None
+    } else {
+        Some((0..header_end, header.to_string()))
+    }
+}
+
+fn change_imports<'a>(
+    arena: &'a Bump,
+    imports: Collection<'a, Loc<Spaced<'a, ImportsEntry<'a>>>>,
+    changes: &ImportChanges,
+) -> Collection<'a, Loc<Spaced<'a, ImportsEntry<'a>>>> {
+    let mut entries = Vec::with_capacity(imports.len() + changes.add.len());
+    let mut imported = Vec::new();
+
+    for loc_entry in imports.iter() {
+        let (module_name, exposes) = match loc_entry.value.extract_spaces().item {
+            ImportsEntry::Module(module_name, exposes) => {
+                let module_name: &str = module_name.into();
+
+                (module_name.to_string(), exposes)
+            }
+            ImportsEntry::Package(shorthand, module_name, exposes) => {
+                let module_name: &str = module_name.into();
+
+                (format!("{}.{}", shorthand, module_name), exposes)
+            }
+            ImportsEntry::IngestedFile(_, _) => {
+                entries.push(*loc_entry);
+                continue;
+            }
+        };
+
+        if changes.remove_modules.contains(&module_name) {
+            continue;
+        }
+
+        let exposes = change_exposes(arena, &module_name, exposes, changes);
+        let entry = with_exposes(loc_entry.value.extract_spaces().item, exposes);
+
+        entries.push(loc_entry.with_value(replace_spaced_item(arena, &loc_entry.value, entry)));
+        imported.push(module_name);
+    }
+
+    for (module_name, _) in changes.add.iter() {
+        if imported.contains(module_name) {
+            continue;
+        }
+
+        let exposes = change_exposes(arena, module_name, Collection::empty(), changes);
+
+        // package shorthands are lowercase, module names uppercase
+        let entry = match module_name.split_once('.') {
+            Some((shorthand, name)) if shorthand.starts_with(char::is_lowercase) => {
+                ImportsEntry::Package(
+                    arena.alloc_str(shorthand),
+                    ModuleName::new(arena.alloc_str(name)),
+                    exposes,
+                )
+            }
+            _ => ImportsEntry::Module(ModuleName::new(arena.alloc_str(module_name)), exposes),
+        };
+
+        entries.push(Loc::at_zero(Spaced::Item(entry)));
+        imported.push(module_name.clone());
+    }
+
+    imports.replace_items(arena.alloc_slice_copy(&entries))
+}
+
+/// The names `module_name` should expose: those in `exposes` that are used, and those that
+/// should be added.
+fn change_exposes<'a>(
+    arena: &'a Bump,
+    module_name: &str,
+    exposes: Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>>,
+    changes: &ImportChanges,
+) -> Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>> {
+    let mut names: Vec<_> = exposes
+        .iter()
+        .filter(|loc_name| {
+            let name: &str = loc_name.value.extract_spaces().item.into();
+
+            !changes
+                .remove_names
+                .iter()
+                .any(|(module, removed)| module == module_name && removed == name)
+        })
+        .copied()
+        .collect();
+
+    for (module, added) in changes.add.iter() {
+        let added = match added {
+            Some(added) if module == module_name => added,
+            _ => continue,
+        };
+
+        let is_exposed = names.iter().any(|loc_name| {
+            let name: &str = loc_name.value.extract_spaces().item.into();
+            name == added
+        });
+
+        if !is_exposed {
+            let name = ExposedName::new(arena.alloc_str(added));
+            names.push(Loc::at_zero(Spaced::Item(name)));
+        }
+    }
+
+    exposes.replace_items(arena.alloc_slice_copy(&names))
+}
+
+fn with_exposes<'a>(
+    entry: ImportsEntry<'a>,
+    exposes: Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>>,
+) -> ImportsEntry<'a> {
+    match entry {
+        ImportsEntry::Module(module_name, _) => ImportsEntry::Module(module_name, exposes),
+        ImportsEntry::Package(shorthand, module_name, _) => {
+            ImportsEntry::Package(shorthand, module_name, exposes)
+        }
+        ImportsEntry::IngestedFile(_, _) => entry,
+    }
+}
+
+/// The name `module_id` is imported with, like `pf.Task` for a module of the platform.
+fn import_name(loaded: &LoadedModule, module_id: ModuleId) -> String {
+    let module_name = loaded.interns.module_name(module_id).as_str();
+
+    match loaded.package_shorthands.get(&module_id) {
+        Some(shorthand) => format!("{}.{}", shorthand, module_name),
+        None => module_name.to_string(),
+    }
+}
+
+/// The only item of `iter`, if it has exactly one.
+fn only<T>(mut iter: impl Iterator<Item = T>) -> Option<T> {
+    match (iter.next(), iter.next()) {
+        (Some(item), None) => Some(item),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn organized(src: &str, changes: &ImportChanges) -> Option<String> {
+        organize_imports(src, changes).map(|(range, header)| {
+            format!("{}{}{}", &src[..range.start], header, &src[range.end..])
+        })
+    }
+
+    #[test]
+    fn add_and_remove_imports_in_one_edit() {
+        let src = indoc!(
+            r#"
+            interface Foo
+                exposes [foo]
+                imports [Dict, List.{ map, walk }]
+
+            foo = map [] Str.trim
+            "#
+        );
+
+        let changes = ImportChanges {
+            add: vec![
+                ("Str".to_string(), None),
+                ("pf.Task".to_string(), Some("await".to_string())),
+            ],
+            remove_modules: vec!["Dict".to_string()],
+            remove_names: vec![("List".to_string(), "walk".to_string())],
+        };
+
+        assert_eq!(
+            organized(src, &changes).as_deref(),
+            Some(indoc!(
+                r#"
+                interface Foo
+                    exposes [foo]
+                    imports [List.{ map }, Str, pf.Task.{ await }]
+
+                foo = map [] Str.trim
+                "#
+            ))
+        );
+    }
+
+    #[test]
+    fn add_imports_to_app_without_any() {
+        let src = indoc!(
+            r#"
+            app "hello"
+                packages { pf: "platform/main.roc" }
+                provides [main] to pf
+
+            main = Stdout.line "Hello"
+            "#
+        );
+
+        let changes = ImportChanges {
+            add: vec![("pf.Stdout".to_string(), None)],
+            ..ImportChanges::default()
+        };
+
+        assert_eq!(
+            organized(src, &changes).as_deref(),
+            Some(indoc!(
+                r#"
+                app "hello"
+                    packages { pf: "platform/main.roc" }
+                    imports [pf.Stdout]
+                    provides [main] to pf
+
+                main = Stdout.line "Hello"
+                "#
+            ))
+        );
+    }
+
+    #[test]
+    fn organized_imports_are_left_alone() {
+        let src = indoc!(
+            r#"
+            interface Foo
+                exposes [foo]
+                imports [List.{ map }]
+
+            foo = map
+            "#
+        );
+
+        let changes = ImportChanges {
+            add: vec![("List".to_string(), Some("map".to_string()))],
+            ..ImportChanges::default()
+        };
+
+        assert_eq!(organized(src, &changes), None);
+    }
+}
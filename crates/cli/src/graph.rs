@@ -0,0 +1,418 @@
+//! `roc graph`: print which modules import which, and optionally which specialized functions call
+//! which, as Graphviz dot or as JSON. The graphs are meant for visualizing the architecture of a
+//! program, and for tools that want to find out what a change can affect.
+use crate::{FLAG_CALLS, FLAG_FORMAT, FLAG_MAX_THREADS, ROC_FILE};
+use bumpalo::Bump;
+use clap::ArgMatches;
+use roc_build::program::{
+    handle_error_module, handle_loading_problem, standard_load_config, BuildOrdering,
+};
+use roc_collections::{MutMap, MutSet};
+use roc_error_macros::user_error;
+use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError, OverflowMode, Threading};
+use roc_module::ident::ModuleName;
+use roc_module::symbol::{Interns, ModuleId};
+use roc_mono::debug::{call_graph, CallGraph};
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use std::fmt::Write;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use target_lexicon::Triple;
+
+struct Graph {
+    /// The name and path of every module, sorted by name
+    modules: Vec<(String, PathBuf)>,
+    /// Which module imports which, as indices into `modules`
+    imports: Vec<(usize, usize)>,
+    /// The specialized functions and the calls between them, if they were asked for
+    calls: Option<(Vec<Function>, Vec<(usize, usize)>)>,
+}
+
+struct Function {
+    name: String,
+    module: String,
+    layout: String,
+}
+
+pub fn graph(matches: &ArgMatches) -> io::Result<i32> {
+    let start_time = Instant::now();
+    let arena = Bump::new();
+    let filename = matches.value_of_os(ROC_FILE).unwrap();
+    let path = Path::new(filename);
+
+    let threading = match matches
+        .value_of(FLAG_MAX_THREADS)
+        .and_then(|s| s.parse::<usize>().ok())
+    {
+        None => Threading::AllAvailable,
+        Some(0) => user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(n),
+    };
+
+    let rendered_graph = if matches.is_present(FLAG_CALLS) {
+        // the call graph only exists after specialization, so this needs an app and its platform
+        let load_config =
+            standard_load_config(&Triple::host(), BuildOrdering::BuildIfChecks, threading);
+
+        let loaded = match roc_load::load_and_monomorphize(
+            &arena,
+            path.to_path_buf(),
+            RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+            load_config,
+        ) {
+            Ok(loaded) => loaded,
+            Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+                return handle_loading_problem(problem);
+            }
+            Err(LoadMonomorphizedError::ErrorModule(module)) => {
+                return handle_error_module(module, start_time.elapsed(), filename, false);
+            }
+        };
+
+        let CallGraph { functions, calls } =
+            call_graph(&loaded.interns, &loaded.layout_interner, &loaded.procedures);
+
+        let functions = functions
+            .into_iter()
+            .map(|function| {
+                let module = module_name(
+                    &loaded.interns,
+                    &loaded.sources,
+                    function.symbol.module_id(),
+                );
+
+                Function {
+                    name: format!("{}.{}", module, function.symbol.as_str(&loaded.interns)),
+                    module,
+                    layout: function.layout,
+                }
+            })
+            .collect();
+
+        let mut graph = module_graph(&loaded.interns, &loaded.sources, &loaded.imports);
+        graph.calls = Some((functions, calls));
+
+        render(&graph, matches.value_of(FLAG_FORMAT))
+    } else {
+        let load_config = LoadConfig {
+            // only used for generating errors, since we don't do code generation here
+            target_info: TargetInfo::default_x86_64(),
+            render: RenderTarget::ColorTerminal,
+            palette: DEFAULT_PALETTE,
+            threading,
+            exec_mode: ExecutionMode::Check,
+            recover_from_syntax_errors: false,
+            static_data: false,
+            overflow: OverflowMode::Trap,
+            fold_constants: true,
+        };
+
+        let loaded = match roc_load::load_and_typecheck(
+            &arena,
+            path.to_path_buf(),
+            RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+            load_config,
+        ) {
+            Ok(loaded) => loaded,
+            Err(problem) => return handle_loading_problem(problem),
+        };
+
+        let graph = module_graph(&loaded.interns, &loaded.sources, &loaded.imports);
+
+        render(&graph, matches.value_of(FLAG_FORMAT))
+    };
+
+    print!("{}", rendered_graph);
+
+    Ok(0)
+}
+
+/// The import graph of every module except the builtins, which every module imports implicitly
+fn module_graph(
+    interns: &Interns,
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    imports: &MutMap<ModuleId, MutSet<ModuleId>>,
+) -> Graph {
+    let mut module_ids: Vec<ModuleId> = sources
+        .keys()
+        .chain(imports.keys())
+        .chain(imports.values().flatten())
+        .filter(|module_id| !module_id.is_builtin())
+        .copied()
+        .collect::<MutSet<_>>()
+        .into_iter()
+        .collect();
+    module_ids.sort_by_cached_key(|module_id| module_name(interns, sources, *module_id));
+
+    let index_of: MutMap<ModuleId, usize> = module_ids
+        .iter()
+        .enumerate()
+        .map(|(index, module_id)| (*module_id, index))
+        .collect();
+
+    let mut module_imports: Vec<(usize, usize)> = imports
+        .iter()
+        .flat_map(|(importer, imported)| {
+            imported
+                .iter()
+                .filter_map(|imported| Some((*index_of.get(importer)?, *index_of.get(imported)?)))
+        })
+        .collect();
+    module_imports.sort_unstable();
+
+    let modules = module_ids
+        .iter()
+        .map(|module_id| {
+            let path = match sources.get(module_id) {
+                Some((path, _)) => path.clone(),
+                None => PathBuf::new(),
+            };
+
+            (module_name(interns, sources, *module_id), path)
+        })
+        .collect();
+
+    Graph {
+        modules,
+        imports: module_imports,
+        calls: None,
+    }
+}
+
+/// App modules all have the same name internally, so they are named after their file instead
+fn module_name(
+    interns: &Interns,
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    module_id: ModuleId,
+) -> String {
+    let name = interns.module_name(module_id).as_str();
+
+    match sources.get(&module_id) {
+        Some((path, _)) if name == ModuleName::APP => path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| name.to_string()),
+        _ => name.to_string(),
+    }
+}
+
+fn render(graph: &Graph, format: Option<&str>) -> String {
+    match format {
+        Some("json") => render_json(graph),
+        _ => render_dot(graph),
+    }
+}
+
+fn render_dot(graph: &Graph) -> String {
+    let mut buf = String::new();
+
+    buf.push_str("digraph modules {\n");
+
+    for (name, _) in graph.modules.iter() {
+        writeln!(buf, "    {};", dot_string(name)).unwrap();
+    }
+
+    for (importer, imported) in graph.imports.iter() {
+        writeln!(
+            buf,
+            "    {} -> {};",
+            dot_string(&graph.modules[*importer].0),
+            dot_string(&graph.modules[*imported].0)
+        )
+        .unwrap();
+    }
+
+    buf.push_str("}\n");
+
+    if let Some((functions, calls)) = &graph.calls {
+        buf.push_str("digraph calls {\n    node [shape=box];\n");
+
+        for (index, function) in functions.iter().enumerate() {
+            let label = format!("{}\n{}", function.name, function.layout);
+
+            writeln!(buf, "    f{} [label={}];", index, dot_string(&label)).unwrap();
+        }
+
+        for (caller, callee) in calls.iter() {
+            writeln!(buf, "    f{} -> f{};", caller, callee).unwrap();
+        }
+
+        buf.push_str("}\n");
+    }
+
+    buf
+}
+
+/// `{ "modules": [{ "name", "path" }], "imports": [{ "from", "to" }] }` with module names, and
+/// when asked for, `"functions": [{ "name", "module", "layout" }]` and `"calls": [{ "from", "to" }]`
+/// with indices into `functions`.
+fn render_json(graph: &Graph) -> String {
+    let modules = graph.modules.iter().map(|(name, path)| {
+        format!(
+            "{{\"name\": {}, \"path\": {}}}",
+            json_string(name),
+            json_string(&path.to_string_lossy())
+        )
+    });
+
+    let imports = graph.imports.iter().map(|(importer, imported)| {
+        format!(
+            "{{\"from\": {}, \"to\": {}}}",
+            json_string(&graph.modules[*importer].0),
+            json_string(&graph.modules[*imported].0)
+        )
+    });
+
+    let mut fields = vec![
+        format!("\"modules\": {}", json_array(modules)),
+        format!("\"imports\": {}", json_array(imports)),
+    ];
+
+    if let Some((functions, calls)) = &graph.calls {
+        let functions = functions.iter().map(|function| {
+            format!(
+                "{{\"name\": {}, \"module\": {}, \"layout\": {}}}",
+                json_string(&function.name),
+                json_string(&function.module),
+                json_string(&function.layout)
+            )
+        });
+
+        let calls = calls
+            .iter()
+            .map(|(caller, callee)| format!("{{\"from\": {}, \"to\": {}}}", caller, callee));
+
+        fields.push(format!("\"functions\": {}", json_array(functions)));
+        fields.push(format!("\"calls\": {}", json_array(calls)));
+    }
+
+    format!("{{\n  {}\n}}\n", fields.join(",\n  "))
+}
+
+fn json_array(items: impl Iterator<Item = String>) -> String {
+    let items: Vec<String> = items.collect();
+
+    if items.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[\n    {}\n  ]", items.join(",\n    "))
+    }
+}
+
+fn json_string(string: &str) -> String {
+    let mut buf = String::with_capacity(string.len() + 2);
+
+    buf.push('"');
+
+    for c in string.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            c if c.is_control() => write!(buf, "\\u{:04x}", c as u32).unwrap(),
+            c => buf.push(c),
+        }
+    }
+
+    buf.push('"');
+
+    buf
+}
+
+fn dot_string(string: &str) -> String {
+    format!(
+        "\"{}\"",
+        string
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> Graph {
+        Graph {
+            modules: vec![
+                ("Lib".to_string(), PathBuf::from("Lib.roc")),
+                ("main".to_string(), PathBuf::from("main.roc")),
+            ],
+            imports: vec![(1, 0)],
+            calls: Some((
+                vec![
+                    Function {
+                        name: "Lib.greet".to_string(),
+                        module: "Lib".to_string(),
+                        layout: "(Str) -> Str".to_string(),
+                    },
+                    Function {
+                        name: "main.main".to_string(),
+                        module: "main".to_string(),
+                        layout: "() -> Str".to_string(),
+                    },
+                ],
+                vec![(1, 0)],
+            )),
+        }
+    }
+
+    #[test]
+    fn render_graph_as_dot() {
+        assert_eq!(
+            render_dot(&graph()),
+            indoc::indoc!(
+                r#"
+                digraph modules {
+                    "Lib";
+                    "main";
+                    "main" -> "Lib";
+                }
+                digraph calls {
+                    node [shape=box];
+                    f0 [label="Lib.greet\n(Str) -> Str"];
+                    f1 [label="main.main\n() -> Str"];
+                    f1 -> f0;
+                }
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn render_graph_as_json() {
+        assert_eq!(
+            render_json(&graph()),
+            indoc::indoc!(
+                r#"
+                {
+                  "modules": [
+                    {"name": "Lib", "path": "Lib.roc"},
+                    {"name": "main", "path": "main.roc"}
+                  ],
+                  "imports": [
+                    {"from": "main", "to": "Lib"}
+                  ],
+                  "functions": [
+                    {"name": "Lib.greet", "module": "Lib", "layout": "(Str) -> Str"},
+                    {"name": "main.main", "module": "main", "layout": "() -> Str"}
+                  ],
+                  "calls": [
+                    {"from": 1, "to": 0}
+                  ]
+                }
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn escape_json_strings() {
+        assert_eq!(json_string("a \"b\"\n\\"), r#""a \"b\"\n\\""#);
+    }
+}
@@ -0,0 +1,284 @@
+//! `roc info type`: print the size, alignment and memory layout of a type for a target, as the
+//! compiler lays it out. Platform authors can use it to write host types that match, and anyone
+//! can use it to find out why a value takes up more memory than expected.
+use crate::{Target, FLAG_TARGET, ROC_FILE, TYPE_ANNOTATION};
+use bumpalo::Bump;
+use clap::ArgMatches;
+use roc_build::program::{handle_loading_problem, report_problems_typechecked};
+use roc_error_macros::user_error;
+use roc_mono::debug::{layout_info, FieldInfo, LayoutInfo, Shape, TagIdStorage, TagInfo};
+use roc_mono::layout::{GlobalLayoutInterner, LayoutCache, LayoutProblem};
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use std::fmt::Write;
+use std::io;
+use std::path::Path;
+
+/// The def that is added to the end of the module, so the type is written in the module's scope
+const PROBE: &str = "rocInfoTypeProbe";
+
+pub fn info_type(matches: &ArgMatches) -> io::Result<i32> {
+    let arena = Bump::new();
+    let path = Path::new(matches.value_of_os(ROC_FILE).unwrap());
+    let type_annotation = matches.value_of(TYPE_ANNOTATION).unwrap();
+    let target: Target = matches.value_of_t(FLAG_TARGET).unwrap_or_default();
+    let target_info = TargetInfo::from(&target.to_triple());
+
+    let module_source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(error) => user_error!("could not read {}: {}", path.display(), error),
+    };
+
+    let source = arena.alloc_str(&format!(
+        "{}\n\n{} : {}\n{} = crash \"only the type of this def is used\"\n",
+        module_source.trim_end(),
+        PROBE,
+        type_annotation,
+        PROBE
+    ));
+
+    let src_dir = match path.parent() {
+        Some(parent) => parent.to_path_buf(),
+        None => Path::new(".").to_path_buf(),
+    };
+
+    let mut loaded = match roc_load::load_and_typecheck_str(
+        &arena,
+        path.to_path_buf(),
+        source,
+        src_dir,
+        target_info,
+        RenderTarget::ColorTerminal,
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        DEFAULT_PALETTE,
+    ) {
+        Ok(loaded) => loaded,
+        Err(problem) => return handle_loading_problem(problem),
+    };
+
+    let problems = report_problems_typechecked(&mut loaded);
+
+    if problems.errors > 0 {
+        return Ok(problems.exit_code());
+    }
+
+    let decls = &loaded.declarations_by_id[&loaded.module_id];
+    let index = decls
+        .symbols
+        .iter()
+        .position(|loc_symbol| loc_symbol.value.as_str(&loaded.interns) == PROBE)
+        .unwrap();
+    let var = decls.variables[index];
+    let subs = loaded.solved.inner();
+
+    let layout_interner = GlobalLayoutInterner::with_capacity(128, target_info);
+    let mut layout_cache = LayoutCache::new(layout_interner.fork(), target_info);
+
+    let layout = match layout_cache.from_var(&arena, var, subs) {
+        Ok(layout) => layout,
+        Err(LayoutProblem::UnresolvedTypeVar(_)) => user_error!(
+            "`{}` has type variables, which need to be filled in to get a layout, like in `List U8`",
+            type_annotation
+        ),
+        Err(LayoutProblem::Erroneous) => {
+            user_error!("`{}` has no layout, because it has errors", type_annotation)
+        }
+    };
+
+    let info = layout_info(&layout_cache.interner, subs, var, layout);
+
+    print!(
+        "{}",
+        render(type_annotation, Into::<&'static str>::into(target), &info)
+    );
+
+    Ok(0)
+}
+
+fn render(type_annotation: &str, target: &str, info: &LayoutInfo) -> String {
+    let mut buf = String::new();
+
+    writeln!(buf, "{} on {}", type_annotation, target).unwrap();
+    writeln!(buf, "    layout: {}", info.layout).unwrap();
+    writeln!(buf, "    size: {}", bytes(info.size)).unwrap();
+    writeln!(buf, "    alignment: {}", bytes(info.alignment)).unwrap();
+
+    match &info.shape {
+        Shape::Builtin => {}
+        Shape::Struct(fields) => {
+            buf.push_str("    fields, in memory order:\n");
+            render_fields(&mut buf, fields);
+        }
+        Shape::Enum(tags) => {
+            buf.push_str("    tags, stored as just their id:\n");
+            render_tags(&mut buf, tags);
+        }
+        Shape::TagUnion(union) => {
+            if union.recursive {
+                buf.push_str("    recursive: a pointer to the payload on the heap\n");
+            }
+
+            match union.tag_id {
+                TagIdStorage::None => {
+                    buf.push_str("    tag id: not stored, there is only one tag that isn't null\n")
+                }
+                TagIdStorage::Data { offset, size } => writeln!(
+                    buf,
+                    "    tag id: {} at offset {} of the payload",
+                    bytes(size),
+                    offset
+                )
+                .unwrap(),
+                TagIdStorage::PointerBits { bits } => writeln!(
+                    buf,
+                    "    tag id: in the lowest {} bits of the pointer",
+                    bits
+                )
+                .unwrap(),
+            }
+
+            buf.push_str("    tags:\n");
+            render_tags(&mut buf, &union.tags);
+        }
+        Shape::Boxed(inner) => {
+            writeln!(buf, "    a pointer to a {} on the heap", inner).unwrap();
+        }
+    }
+
+    buf
+}
+
+fn render_tags(buf: &mut String, tags: &[TagInfo]) {
+    for tag in tags {
+        let null = if tag.is_null {
+            ", the null pointer"
+        } else {
+            ""
+        };
+
+        writeln!(buf, "        {} (id {}{})", tag.name, tag.id, null).unwrap();
+
+        for field in tag.payload.iter() {
+            buf.push_str("    ");
+            render_field(buf, field);
+        }
+    }
+}
+
+fn render_fields(buf: &mut String, fields: &[FieldInfo]) {
+    for field in fields {
+        render_field(buf, field);
+    }
+}
+
+fn render_field(buf: &mut String, field: &FieldInfo) {
+    write!(buf, "        offset {}: ", field.offset).unwrap();
+
+    if let Some(name) = &field.name {
+        write!(buf, "{} : ", name).unwrap();
+    }
+
+    writeln!(buf, "{} ({})", field.layout, bytes(field.size)).unwrap();
+}
+
+fn bytes(n: u32) -> String {
+    match n {
+        1 => "1 byte".to_string(),
+        n => format!("{} bytes", n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roc_mono::debug::TagUnionInfo;
+
+    fn field(name: Option<&str>, offset: u32, size: u32, layout: &str) -> FieldInfo {
+        FieldInfo {
+            name: name.map(|name| name.to_string()),
+            offset,
+            size,
+            layout: layout.to_string(),
+        }
+    }
+
+    #[test]
+    fn render_record() {
+        let info = LayoutInfo {
+            layout: "{Str, U32, U8}".to_string(),
+            size: 32,
+            alignment: 8,
+            shape: Shape::Struct(vec![
+                field(Some("name"), 0, 24, "Str"),
+                field(Some("age"), 24, 4, "U32"),
+                field(Some("level"), 28, 1, "U8"),
+            ]),
+        };
+
+        assert_eq!(
+            render("Person", "linux64", &info),
+            indoc::indoc!(
+                r#"
+                Person on linux64
+                    layout: {Str, U32, U8}
+                    size: 32 bytes
+                    alignment: 8 bytes
+                    fields, in memory order:
+                        offset 0: name : Str (24 bytes)
+                        offset 24: age : U32 (4 bytes)
+                        offset 28: level : U8 (1 byte)
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn render_tag_union() {
+        let info = LayoutInfo {
+            layout: "[[F64], [F64, F64]]".to_string(),
+            size: 24,
+            alignment: 8,
+            shape: Shape::TagUnion(TagUnionInfo {
+                recursive: false,
+                tag_id: TagIdStorage::Data {
+                    offset: 16,
+                    size: 1,
+                },
+                tags: vec![
+                    TagInfo {
+                        name: "Circle".to_string(),
+                        id: 0,
+                        is_null: false,
+                        payload: vec![field(None, 0, 8, "F64")],
+                    },
+                    TagInfo {
+                        name: "Rect".to_string(),
+                        id: 1,
+                        is_null: false,
+                        payload: vec![field(None, 0, 8, "F64"), field(None, 8, 8, "F64")],
+                    },
+                ],
+            }),
+        };
+
+        assert_eq!(
+            render("Shape", "wasm32", &info),
+            indoc::indoc!(
+                r#"
+                Shape on wasm32
+                    layout: [[F64], [F64, F64]]
+                    size: 24 bytes
+                    alignment: 8 bytes
+                    tag id: 1 byte at offset 16 of the payload
+                    tags:
+                        Circle (id 0)
+                            offset 0: F64 (8 bytes)
+                        Rect (id 1)
+                            offset 0: F64 (8 bytes)
+                            offset 8: F64 (8 bytes)
+                "#
+            )
+        );
+    }
+}
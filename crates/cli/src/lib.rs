@@ -8,15 +8,20 @@ use clap::{Arg, ArgMatches, Command, ValueSource};
 use roc_build::link::{LinkType, LinkingStrategy};
 use roc_build::program::{
     handle_error_module, handle_loading_problem, standard_load_config, BuildFileError,
-    BuildOrdering, BuiltFile, CodeGenBackend, CodeGenOptions, DEFAULT_ROC_FILENAME,
+    BuildOrdering, BuiltFile, CodeGenBackend, CodeGenOptions, ProfileOptions, DEFAULT_ROC_FILENAME,
 };
 use roc_error_macros::{internal_error, user_error};
 use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
-use roc_load::{ExpectMetadata, Threading};
+use roc_gen_llvm::llvm::profile::Profile;
+use roc_gen_llvm::llvm::refcounting::AtomicRefcounts;
+use roc_load::{ExpectMetadata, OverflowMode, Threading};
 use roc_mono::ir::OptLevel;
 use roc_packaging::cache::RocCacheDir;
 use roc_packaging::tarball::Compression;
+use roc_reporting::report::{
+    ansi_color, in_charset, ColorChoice, RenderStyle, Report, ANSI_STYLE_CODES, DEFAULT_PALETTE,
+};
 use std::env;
 use std::ffi::{CString, OsStr};
 use std::io;
@@ -33,9 +38,50 @@ use target_lexicon::{
 #[cfg(not(target_os = "linux"))]
 use tempfile::TempDir;
 
+mod api_diff;
+pub use api_diff::api_diff;
+
+mod crash_paths;
+pub use crash_paths::crash_paths;
+
+#[cfg(not(windows))]
+mod cross_backend;
+
+mod demangle;
+pub use demangle::demangle;
+
+mod dev_loop;
+pub use dev_loop::dev_loop;
+
+mod fix_imports;
+pub use fix_imports::fix_imports;
+
 mod format;
 pub use format::format;
 
+mod graph;
+pub use graph::graph;
+
+mod info;
+pub use info::info_type;
+
+mod minimize;
+pub use minimize::minimize;
+
+mod platform_check;
+pub use platform_check::check_platform;
+
+mod publish;
+pub use publish::publish;
+
+#[cfg(not(windows))]
+mod test_harness;
+
+mod unused_report;
+pub use unused_report::unused_report;
+
+pub mod workspace;
+
 pub const CMD_BUILD: &str = "build";
 pub const CMD_RUN: &str = "run";
 pub const CMD_DEV: &str = "dev";
@@ -48,10 +94,18 @@ pub const CMD_FORMAT: &str = "format";
 pub const CMD_TEST: &str = "test";
 pub const CMD_GLUE: &str = "glue";
 pub const CMD_GEN_STUB_LIB: &str = "gen-stub-lib";
+pub const CMD_PUBLISH: &str = "publish";
+pub const CMD_MINIMIZE: &str = "minimize";
+pub const CMD_GRAPH: &str = "graph";
+pub const CMD_DEMANGLE: &str = "demangle";
+pub const CMD_INFO: &str = "info";
+pub const CMD_INFO_TYPE: &str = "type";
 
 pub const FLAG_DEBUG: &str = "debug";
 pub const FLAG_BUNDLE: &str = "bundle";
+pub const FLAG_PRECOMPILE_PACKAGE: &str = "precompile-package";
 pub const FLAG_DEV: &str = "dev";
+pub const FLAG_BACKEND: &str = "backend";
 pub const FLAG_OPTIMIZE: &str = "optimize";
 pub const FLAG_MAX_THREADS: &str = "max-threads";
 pub const FLAG_OPT_SIZE: &str = "opt-size";
@@ -60,18 +114,61 @@ pub const FLAG_NO_LINK: &str = "no-link";
 pub const FLAG_TARGET: &str = "target";
 pub const FLAG_TIME: &str = "time";
 pub const FLAG_LINKER: &str = "linker";
+pub const FLAG_VERBOSE_LINK: &str = "verbose-link";
+pub const FLAG_EXPLAIN_SPECIALIZATIONS: &str = "explain-specializations";
+pub const FLAG_EXPLAIN_CLOSURES: &str = "explain-closures";
 pub const FLAG_PREBUILT: &str = "prebuilt-platform";
+pub const FLAG_REBUILD_HOST: &str = "rebuild-host";
 pub const FLAG_CHECK: &str = "check";
 pub const FLAG_WASM_STACK_SIZE_KB: &str = "wasm-stack-size-kb";
+pub const FLAG_UPLOAD: &str = "upload";
+pub const FLAG_WORKSPACE: &str = "workspace";
+pub const FLAG_API_DIFF: &str = "api-diff";
+pub const FLAG_HOST: &str = "host";
+pub const FLAG_LINT_SINGLE_IMPLEMENTATIONS: &str = "lint-single-implementations";
+pub const FLAG_CRASH_PATHS: &str = "crash-paths";
+pub const FLAG_UNUSED_REPORT: &str = "unused-report";
+pub const FLAG_FIX_IMPORTS: &str = "fix-imports";
+pub const FLAG_FORMAT: &str = "format";
+pub const FLAG_CALLS: &str = "calls";
+pub const FLAG_STUB: &str = "stub";
+pub const FLAG_VERIFY: &str = "verify";
+pub const FLAG_PROFILE_GEN: &str = "profile-gen";
+pub const FLAG_PROFILE_USE: &str = "profile-use";
+pub const FLAG_ATOMIC_REFCOUNTS: &str = "atomic-refcounts";
+pub const FLAG_ARENA_ALLOCATION: &str = "arena-allocation";
+pub const FLAG_SCHEDULE_REFCOUNTS: &str = "schedule-refcounts";
+pub const FLAG_OVERFLOW: &str = "overflow";
+pub const FLAG_EXPR: &str = "expr";
+pub const FLAG_WATCH: &str = "watch";
+pub const FLAG_OUTPUT_CONTAINS: &str = "output-contains";
+pub const FLAG_CROSS_BACKEND: &str = "cross-backend";
+pub const FLAG_COLOR: &str = "color";
+pub const FLAG_CHARSET: &str = "charset";
+pub const FLAG_VERBOSE_ERRORS: &str = "verbose-errors";
 pub const ROC_FILE: &str = "ROC_FILE";
 pub const ROC_DIR: &str = "ROC_DIR";
 pub const GLUE_DIR: &str = "GLUE_DIR";
 pub const GLUE_SPEC: &str = "GLUE_SPEC";
 pub const DIRECTORY_OR_FILES: &str = "DIRECTORY_OR_FILES";
 pub const ARGS_FOR_APP: &str = "ARGS_FOR_APP";
+pub const TYPE_ANNOTATION: &str = "TYPE_ANNOTATION";
+pub const SYMBOL_NAMES: &str = "SYMBOL_NAMES";
 
 const VERSION: &str = include_str!("../../../version.txt");
 
+/// The style every report is rendered in, from `--color`, `--charset` and the environment
+pub fn render_style(matches: &ArgMatches) -> RenderStyle {
+    let is_terminal = unsafe { libc::isatty(libc::STDOUT_FILENO) } != 0;
+
+    RenderStyle::resolve(
+        matches.value_of_t(FLAG_COLOR).unwrap_or(ColorChoice::Auto),
+        matches.value_of_t(FLAG_CHARSET).ok(),
+        |name| env::var(name).ok(),
+        is_terminal,
+    )
+}
+
 pub fn build_app<'a>() -> Command<'a> {
     let flag_optimize = Arg::new(FLAG_OPTIMIZE)
         .long(FLAG_OPTIMIZE)
@@ -95,11 +192,39 @@ pub fn build_app<'a>() -> Command<'a> {
         .help("Make compilation finish as soon as possible, at the expense of runtime performance")
         .required(false);
 
+    let flag_backend = Arg::new(FLAG_BACKEND)
+        .long(FLAG_BACKEND)
+        .help("Choose how to generate code\n(`dev` is the same as --dev. `wasm-interp` compiles to WebAssembly and runs it in Roc's built-in WebAssembly interpreter, which works on any machine, even ones no other backend supports; the platform needs to provide a wasm32 host. Running needs a roc built with the `run-wasm32` feature.)")
+        .possible_values(["llvm", "dev", "wasm-interp"])
+        .conflicts_with(FLAG_DEV)
+        .required(false);
+
     let flag_debug = Arg::new(FLAG_DEBUG)
         .long(FLAG_DEBUG)
         .help("Store LLVM debug information in the generated program")
         .required(false);
 
+    let flag_color = Arg::new(FLAG_COLOR)
+        .long(FLAG_COLOR)
+        .help("Whether to color the output\n(`auto` colors it when it goes to a terminal, unless the NO_COLOR environment variable is set or TERM is `dumb`.)")
+        .possible_values(["auto", "always", "never"])
+        .default_value("auto")
+        .global(true)
+        .required(false);
+
+    let flag_charset = Arg::new(FLAG_CHARSET)
+        .long(FLAG_CHARSET)
+        .help("Which characters reports are drawn with\n(`ascii` replaces box drawing, arrows and other Unicode with plain ASCII. Defaults to the ROC_CHARSET environment variable, or to `ascii` when TERM is `dumb`.)")
+        .possible_values(["unicode", "ascii"])
+        .global(true)
+        .required(false);
+
+    let flag_verbose_errors = Arg::new(FLAG_VERBOSE_ERRORS)
+        .long(FLAG_VERBOSE_ERRORS)
+        .help("Expand the tips in error messages with worked examples")
+        .global(true)
+        .required(false);
+
     let flag_time = Arg::new(FLAG_TIME)
         .long(FLAG_TIME)
         .help("Print detailed compilation time information")
@@ -111,12 +236,33 @@ pub fn build_app<'a>() -> Command<'a> {
         .possible_values(["surgical", "legacy"])
         .required(false);
 
+    let flag_verbose_link = Arg::new(FLAG_VERBOSE_LINK)
+        .long(FLAG_VERBOSE_LINK)
+        .help("Print each decision the surgical linker makes to stderr, one `link: <event> key=value ...` line each\n(Useful for diagnosing link failures. Has no effect when the legacy linker is used.)")
+        .required(false);
+
+    let flag_explain_specializations = Arg::new(FLAG_EXPLAIN_SPECIALIZATIONS)
+        .long(FLAG_EXPLAIN_SPECIALIZATIONS)
+        .help("Print every function that was specialized more than once, with the layout of each specialization and the functions that call it\n(Closure arguments show up as the lambda sets they were specialized for. Useful for finding out why higher-order code compiles to so much code.)")
+        .required(false);
+
+    let flag_explain_closures = Arg::new(FLAG_EXPLAIN_CLOSURES)
+        .long(FLAG_EXPLAIN_CLOSURES)
+        .help("Print every lambda set with how its closures are represented, the boxed ones first\n(Names the functions whose captures make a lambda set recursive, and so boxed, and the functions that are called with their capture instead of the lambda set.)")
+        .required(false);
+
     let flag_prebuilt = Arg::new(FLAG_PREBUILT)
         .long(FLAG_PREBUILT)
         .help("Assume the platform has been prebuilt and skip rebuilding the platform\n(This is enabled by default when using `roc build` with a --target other than `--target <current machine>`.)")
         .possible_values(["true", "false"])
         .required(false);
 
+    let flag_rebuild_host = Arg::new(FLAG_REBUILD_HOST)
+        .long(FLAG_REBUILD_HOST)
+        .help("Rebuild the platform's host even if a cached build of it exists\n(The host is built the way the platform's host.recipe says, or based on which host source files it has if there is no recipe.)")
+        .conflicts_with(FLAG_PREBUILT)
+        .required(false);
+
     let flag_wasm_stack_size_kb = Arg::new(FLAG_WASM_STACK_SIZE_KB)
         .long(FLAG_WASM_STACK_SIZE_KB)
         .help("Stack size in kilobytes for wasm32 target\n(This only applies when --dev also provided.)")
@@ -124,6 +270,48 @@ pub fn build_app<'a>() -> Command<'a> {
         .validator(|s| s.parse::<u32>())
         .required(false);
 
+    let flag_profile_gen = Arg::new(FLAG_PROFILE_GEN)
+        .long(FLAG_PROFILE_GEN)
+        .help("Make the compiled program count which code runs most, and write the counts to a .profile file beside the .roc file when it exits\n(Pass that file to --profile-use to optimize for it.)")
+        .conflicts_with(FLAG_DEV)
+        .required(false);
+
+    let flag_profile_use = Arg::new(FLAG_PROFILE_USE)
+        .long(FLAG_PROFILE_USE)
+        .help("Optimize the compiled program for a profile written by a --profile-gen build")
+        .takes_value(true)
+        .value_name("PROFILE")
+        .allow_invalid_utf8(true)
+        .conflicts_with_all(&[FLAG_DEV, FLAG_PROFILE_GEN])
+        .required(false);
+
+    let flag_atomic_refcounts = Arg::new(FLAG_ATOMIC_REFCOUNTS)
+        .long(FLAG_ATOMIC_REFCOUNTS)
+        .help("Use atomic refcounts, so the platform can share Roc values between threads\n(`inferred` only makes values which can reach the platform atomic; values which never leave Roc code keep the faster non-atomic refcounts.)")
+        .possible_values(["never", "always", "inferred"])
+        .required(false);
+
+    let flag_arena_allocation = Arg::new(FLAG_ARENA_ALLOCATION)
+        .long(FLAG_ARENA_ALLOCATION)
+        .help("Never free individual values, because the platform allocates into an arena which it resets as a whole (e.g. once per request)\n(This skips all refcount decrements. Memory is only reclaimed when the platform resets its arena.)")
+        .required(false);
+
+    let flag_schedule_refcounts = Arg::new(FLAG_SCHEDULE_REFCOUNTS)
+        .long(FLAG_SCHEDULE_REFCOUNTS)
+        .help("Move refcount increments and decrements next to the uses of their values, so memory is freed sooner\n(This is experimental, and off by default.)")
+        .required(false);
+
+    let flag_overflow = Arg::new(FLAG_OVERFLOW)
+        .long(FLAG_OVERFLOW)
+        .help("What `+`, `-` and `*` on integers do when the result doesn't fit: crash (`trap`, the default), wrap around like `Num.addWrap` (`wrap`), or clamp like `Num.addSaturated` (`saturate`)\n(Fractions are not affected. The dev backend doesn't check for overflow yet, so it wraps with `trap` too.)")
+        .possible_values(["trap", "wrap", "saturate"])
+        .required(false);
+
+    let flag_workspace = Arg::new(FLAG_WORKSPACE)
+        .long(FLAG_WORKSPACE)
+        .help("Operate on every member of the workspace (listed in the nearest roc.workspace file) instead of a single file")
+        .required(false);
+
     let roc_file_to_run = Arg::new(ROC_FILE)
         .help("The .roc file of an app to run")
         .allow_invalid_utf8(true)
@@ -147,11 +335,22 @@ pub fn build_app<'a>() -> Command<'a> {
             .arg(flag_max_threads.clone())
             .arg(flag_opt_size.clone())
             .arg(flag_dev.clone())
+            .arg(flag_backend.clone())
             .arg(flag_debug.clone())
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
+            .arg(flag_verbose_link.clone())
+            .arg(flag_explain_specializations.clone())
+            .arg(flag_explain_closures.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_rebuild_host.clone())
             .arg(flag_wasm_stack_size_kb.clone())
+            .arg(flag_profile_gen.clone())
+            .arg(flag_profile_use.clone())
+            .arg(flag_atomic_refcounts.clone())
+            .arg(flag_arena_allocation.clone())
+            .arg(flag_schedule_refcounts.clone())
+            .arg(flag_overflow.clone())
             .arg(
                 Arg::new(FLAG_TARGET)
                     .long(FLAG_TARGET)
@@ -176,6 +375,14 @@ pub fn build_app<'a>() -> Command<'a> {
                     .possible_values([".tar", ".tar.gz", ".tar.br"])
                     .required(false),
             )
+            .arg(
+                Arg::new(FLAG_PRECOMPILE_PACKAGE)
+                    .long(FLAG_PRECOMPILE_PACKAGE)
+                    .help("Solve the types of a package's modules ahead of time, and write them next to its main.roc so apps that use the package skip that work. --bundle includes them in the archive.")
+                    .conflicts_with(FLAG_LIB)
+                    .conflicts_with(FLAG_NO_LINK)
+                    .required(false),
+            )
             .arg(
                 Arg::new(FLAG_NO_LINK)
                     .long(FLAG_NO_LINK)
@@ -192,6 +399,7 @@ pub fn build_app<'a>() -> Command<'a> {
         )
         .subcommand(Command::new(CMD_TEST)
             .about("Run all top-level `expect`s in a main module and any modules it imports")
+            .arg(flag_workspace.clone())
             .arg(flag_optimize.clone())
             .arg(flag_max_threads.clone())
             .arg(flag_opt_size.clone())
@@ -200,15 +408,52 @@ pub fn build_app<'a>() -> Command<'a> {
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_overflow.clone())
+            .arg(
+                Arg::new(FLAG_CROSS_BACKEND)
+                    .long(FLAG_CROSS_BACKEND)
+                    .help("Run the top-level `expect`s on the LLVM, dev and wasm backends, and report the ones where they disagree, with the mono IR of the expect\n(This is for finding bugs in the compiler's backends.)")
+                    .conflicts_with(FLAG_WORKSPACE)
+                    .hide(true)
+                    .required(false),
+            )
             .arg(
                 Arg::new(ROC_FILE)
-                    .help("The .roc file for the main module")
+                    .help("The .roc file for the main module of an app, package or platform")
                     .allow_invalid_utf8(true)
                     .required(false)
                     .default_value(DEFAULT_ROC_FILENAME)
             )
             .arg(args_for_app.clone())
         )
+        .subcommand(Command::new(CMD_PUBLISH)
+            .about("Check a package, generate its docs, and run its `expect`s, then bundle it into an archive others can depend on")
+            .arg(flag_optimize.clone())
+            .arg(flag_max_threads.clone())
+            .arg(flag_opt_size.clone())
+            .arg(flag_dev.clone())
+            .arg(
+                Arg::new(FLAG_BUNDLE)
+                    .long(FLAG_BUNDLE)
+                    .help("The kind of archive to create")
+                    .possible_values([".tar", ".tar.gz", ".tar.br"])
+                    .default_value(".tar.br")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_UPLOAD)
+                    .long(FLAG_UPLOAD)
+                    .help("Upload the archive to the URL configured via ROC_PUBLISH_URL or the `publish` setting in packages.conf")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The package's main .roc file")
+                    .allow_invalid_utf8(true)
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME),
+            )
+        )
         .subcommand(Command::new(CMD_REPL)
             .about("Launch the interactive Read Eval Print Loop (REPL)")
         )
@@ -218,10 +463,29 @@ pub fn build_app<'a>() -> Command<'a> {
             .arg(flag_max_threads.clone())
             .arg(flag_opt_size.clone())
             .arg(flag_dev.clone())
+            .arg(flag_backend.clone())
             .arg(flag_debug.clone())
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
+            .arg(flag_verbose_link.clone())
+            .arg(flag_explain_specializations.clone())
+            .arg(flag_explain_closures.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_rebuild_host.clone())
+            .arg(flag_profile_gen.clone())
+            .arg(flag_profile_use.clone())
+            .arg(flag_atomic_refcounts.clone())
+            .arg(flag_arena_allocation.clone())
+            .arg(flag_schedule_refcounts.clone())
+            .arg(flag_overflow.clone())
+            .arg(
+                Arg::new(FLAG_EXPR)
+                    .long(FLAG_EXPR)
+                    .help("Evaluate this expression and print its value and type, instead of running a .roc file")
+                    .takes_value(true)
+                    .value_name("EXPR")
+                    .required(false),
+            )
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone())
         )
@@ -231,15 +495,33 @@ pub fn build_app<'a>() -> Command<'a> {
             .arg(flag_max_threads.clone())
             .arg(flag_opt_size.clone())
             .arg(flag_dev.clone())
+            .arg(flag_backend.clone())
             .arg(flag_debug.clone())
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
+            .arg(flag_verbose_link.clone())
+            .arg(flag_explain_specializations.clone())
+            .arg(flag_explain_closures.clone())
             .arg(flag_prebuilt.clone())
+            .arg(flag_rebuild_host.clone())
+            .arg(flag_profile_gen.clone())
+            .arg(flag_profile_use.clone())
+            .arg(flag_atomic_refcounts.clone())
+            .arg(flag_arena_allocation.clone())
+            .arg(flag_schedule_refcounts.clone())
+            .arg(flag_overflow.clone())
+            .arg(
+                Arg::new(FLAG_WATCH)
+                    .long(FLAG_WATCH)
+                    .help("Keep running: on every change to a .roc file, `check` and `test` it, and restart the program if both pass")
+                    .required(false),
+            )
             .arg(roc_file_to_run.clone())
             .arg(args_for_app.clone())
         )
         .subcommand(Command::new(CMD_FORMAT)
             .about("Format a .roc file using standard Roc formatting")
+            .arg(flag_workspace.clone().conflicts_with(DIRECTORY_OR_FILES))
             .arg(
                 Arg::new(DIRECTORY_OR_FILES)
                     .index(1)
@@ -257,6 +539,53 @@ pub fn build_app<'a>() -> Command<'a> {
             .about(concatcp!("Print the Roc compiler’s version, which is currently ", VERSION)))
         .subcommand(Command::new(CMD_CHECK)
             .about("Check the code for problems, but don’t build or run it")
+            .arg(flag_workspace.clone())
+            .arg(
+                Arg::new(FLAG_API_DIFF)
+                    .long(FLAG_API_DIFF)
+                    .help("Compare what two versions of a package expose, and report breaking changes")
+                    .value_names(&["OLD", "NEW"])
+                    .number_of_values(2)
+                    .allow_invalid_utf8(true)
+                    .conflicts_with(FLAG_WORKSPACE)
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_HOST)
+                    .long(FLAG_HOST)
+                    .help("Check a platform's header against its prebuilt host (an object file or static library), and report mismatches which would stop apps from linking")
+                    .value_name("HOST")
+                    .allow_invalid_utf8(true)
+                    .conflicts_with_all(&[FLAG_WORKSPACE, FLAG_API_DIFF])
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_LINT_SINGLE_IMPLEMENTATIONS)
+                    .long(FLAG_LINT_SINGLE_IMPLEMENTATIONS)
+                    .help("Also warn about functions which are generic over an ability, but are only ever used with one type\n(Only functions which their module doesn't expose are checked, since other modules could use the exposed ones with any type.)")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_CRASH_PATHS)
+                    .long(FLAG_CRASH_PATHS)
+                    .help("Report the exposed functions which can reach a `crash`, a `when` that doesn't cover every value, or code with a compile error, with the calls that lead there\n(Exits with code 1 if there are any, so CI can check that an API can't crash.)")
+                    .conflicts_with_all(&[FLAG_WORKSPACE, FLAG_API_DIFF, FLAG_HOST])
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_UNUSED_REPORT)
+                    .long(FLAG_UNUSED_REPORT)
+                    .help("Report the top-level defs, types and imports that nothing uses, starting from what the package exposes or the app provides\n(Prints one `path:line:column: unused <kind> <name>` line per finding, and exits with code 1 if there are any.)")
+                    .conflicts_with_all(&[FLAG_WORKSPACE, FLAG_API_DIFF, FLAG_HOST, FLAG_CRASH_PATHS])
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_FIX_IMPORTS)
+                    .long(FLAG_FIX_IMPORTS)
+                    .help("Import what names which aren't in scope refer to, when only one module exposes them, and remove the imports nothing uses\n(Rewrites the header of each module of the package that needs it.)")
+                    .conflicts_with_all(&[FLAG_WORKSPACE, FLAG_API_DIFF, FLAG_HOST, FLAG_CRASH_PATHS, FLAG_UNUSED_REPORT])
+                    .required(false),
+            )
             .arg(flag_time.clone())
             .arg(flag_max_threads.clone())
             .arg(
@@ -281,6 +610,19 @@ pub fn build_app<'a>() -> Command<'a> {
         .subcommand(Command::new(CMD_GLUE)
             .about("Generate glue code between a platform's Roc API and its host language")
             .arg(&flag_dev)
+            .arg(
+                Arg::new(FLAG_STUB)
+                    .long(FLAG_STUB)
+                    .help("Also generate a host which builds, with a stub for every effect the platform needs and a `main` which calls its entrypoints\n(Existing files are never overwritten.)")
+                    .possible_values(["rust", "c"])
+                    .required(false)
+            )
+            .arg(
+                Arg::new(FLAG_VERIFY)
+                    .long(FLAG_VERIFY)
+                    .help("Also build and run a conformance suite, which passes a value of every type the platform exposes through Roc and checks that it comes back laid out the way glue expects\n(The suite is generated in a `conformance` directory inside the glue directory.)")
+                    .required(false)
+            )
             .arg(
                 Arg::new(GLUE_SPEC)
                     .help("The specification for how to translate Roc types into output files.")
@@ -301,6 +643,94 @@ pub fn build_app<'a>() -> Command<'a> {
                     .default_value(DEFAULT_ROC_FILENAME)
             )
         )
+        .subcommand(Command::new(CMD_MINIMIZE)
+            .about("Shrink a .roc file which crashes the compiler to a small file which still crashes it the same way, and write it to a .min.roc file next to it\n(Give the arguments that make `roc` crash after `--`, e.g. `roc minimize crash.roc -- build --optimize`. The default is `check`.)")
+            .arg(
+                Arg::new(FLAG_OUTPUT_CONTAINS)
+                    .long(FLAG_OUTPUT_CONTAINS)
+                    .help("Instead of a crash, minimize while the output of `roc` contains this text\n(Useful for miscompilations, e.g. a wrong value printed by the program.)")
+                    .takes_value(true)
+                    .value_name("TEXT")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file which crashes the compiler")
+                    .allow_invalid_utf8(true)
+                    .required(true),
+            )
+            .arg(
+                Arg::new(ARGS_FOR_APP)
+                    .help("The arguments for `roc` which make it crash, without the .roc file")
+                    .allow_invalid_utf8(true)
+                    .multiple_values(true)
+                    .takes_value(true)
+                    .allow_hyphen_values(true)
+                    .last(true),
+            )
+        )
+        .subcommand(Command::new(CMD_GRAPH)
+            .about("Print which modules import which, and optionally which functions call which, e.g. for `roc graph | dot -Tsvg > graph.svg`")
+            .arg(
+                Arg::new(FLAG_FORMAT)
+                    .long(FLAG_FORMAT)
+                    .help("Print the graphs in Graphviz dot, or as JSON for other tools")
+                    .possible_values(["dot", "json"])
+                    .default_value("dot")
+                    .required(false),
+            )
+            .arg(
+                Arg::new(FLAG_CALLS)
+                    .long(FLAG_CALLS)
+                    .help("Also print the call graph of every specialization of every function\n(This needs an app, since functions are only specialized for an app and its platform.)")
+                    .required(false),
+            )
+            .arg(flag_max_threads.clone())
+            .arg(
+                Arg::new(ROC_FILE)
+                    .help("The .roc file of an app or package")
+                    .allow_invalid_utf8(true)
+                    .required(false)
+                    .default_value(DEFAULT_ROC_FILENAME),
+            )
+        )
+        .subcommand(Command::new(CMD_DEMANGLE)
+            .about("Print which functions the symbols of specializations in a compiled program belong to\n(Without names, replace every such symbol in the text on stdin, e.g. for `perf report | roc demangle`.)")
+            .arg(
+                Arg::new(SYMBOL_NAMES)
+                    .help("The symbol names, e.g. `List_walk_00c0ffee00c0ffee`")
+                    .multiple_values(true)
+                    .required(false),
+            )
+        )
+        .subcommand(Command::new(CMD_INFO)
+            .about("Print what the compiler knows about part of a program")
+            .subcommand_required(true)
+            .subcommand(Command::new(CMD_INFO_TYPE)
+                .about("Print the size, alignment, field offsets and tag id representation of a type for a target")
+                .arg(
+                    Arg::new(FLAG_TARGET)
+                        .long(FLAG_TARGET)
+                        .help("Choose a different target")
+                        .default_value(Target::default().into())
+                        .possible_values(Target::iter().map(|target| {
+                            Into::<&'static str>::into(target)
+                        }))
+                        .required(false),
+                )
+                .arg(
+                    Arg::new(ROC_FILE)
+                        .help("The .roc file of the module the type is in scope in")
+                        .allow_invalid_utf8(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::new(TYPE_ANNOTATION)
+                        .help("The type, written as in that module, e.g. `Shape` or `Tree U8`")
+                        .required(true),
+                )
+            )
+        )
         .subcommand(Command::new(CMD_GEN_STUB_LIB)
             .about("Generate a stubbed shared library that can be used for linking a platform binary.\nThe stubbed library has prototypes, but no function bodies.\n\nNote: This command will be removed in favor of just using `roc build` once all platforms support the surgical linker")
             .arg(
@@ -325,10 +755,24 @@ pub fn build_app<'a>() -> Command<'a> {
         .arg(flag_max_threads.clone())
         .arg(flag_opt_size)
         .arg(flag_dev)
+        .arg(flag_backend)
         .arg(flag_debug)
         .arg(flag_time)
         .arg(flag_linker)
+        .arg(flag_verbose_link)
+        .arg(flag_explain_specializations)
+        .arg(flag_explain_closures)
         .arg(flag_prebuilt)
+        .arg(flag_rebuild_host)
+        .arg(flag_profile_gen)
+        .arg(flag_profile_use)
+        .arg(flag_atomic_refcounts)
+        .arg(flag_arena_allocation)
+        .arg(flag_schedule_refcounts)
+        .arg(flag_overflow)
+        .arg(flag_color)
+        .arg(flag_charset)
+        .arg(flag_verbose_errors)
         .arg(roc_file_to_run.required(false))
         .arg(args_for_app);
 
@@ -375,21 +819,41 @@ fn opt_level_from_flags(matches: &ArgMatches) -> OptLevel {
     }
 }
 
+pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
+    if matches.is_present(FLAG_WORKSPACE) {
+        let workspace = workspace::Workspace::discover(&env::current_dir()?)
+            .unwrap_or_else(|problem| user_error!("{}", problem));
+
+        workspace::for_each_member(&workspace, |member| {
+            test_file(matches, triple.clone(), member.as_os_str())
+        })
+    } else {
+        test_file(matches, triple, matches.value_of_os(ROC_FILE).unwrap())
+    }
+}
+
 #[cfg(windows)]
-pub fn test(_matches: &ArgMatches, _triple: Triple) -> io::Result<i32> {
+pub(crate) fn test_file(
+    _matches: &ArgMatches,
+    _triple: Triple,
+    _filename: &OsStr,
+) -> io::Result<i32> {
     todo!("running tests does not work on windows right now")
 }
 
 #[cfg(not(windows))]
-pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
+pub(crate) fn test_file(matches: &ArgMatches, triple: Triple, filename: &OsStr) -> io::Result<i32> {
     use roc_build::program::report_problems_monomorphized;
     use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError};
     use roc_packaging::cache;
     use roc_target::TargetInfo;
 
+    if matches.is_present(FLAG_CROSS_BACKEND) {
+        return cross_backend::test_file_cross_backend(matches, triple, filename);
+    }
+
     let start_time = Instant::now();
     let arena = Bump::new();
-    let filename = matches.value_of_os(ROC_FILE).unwrap();
     let opt_level = opt_level_from_flags(matches);
 
     let threading = match matches
@@ -427,6 +891,13 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
     let opt_level = opt_level;
     let target_info = TargetInfo::from(target);
 
+    // Packages and platforms can't be loaded on their own, so they are tested through an app
+    let harness = test_harness::harness_for(path)?;
+    let (root_path, exec_mode) = match &harness {
+        Some(harness) => (harness.app.clone(), ExecutionMode::TestPackage),
+        None => (path.to_path_buf(), ExecutionMode::Test),
+    };
+
     // Step 1: compile the app and generate the .o file
     let load_config = LoadConfig {
         target_info,
@@ -434,11 +905,15 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
         render: roc_reporting::report::RenderTarget::ColorTerminal,
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading,
-        exec_mode: ExecutionMode::Test,
+        exec_mode,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow: overflow_mode(matches),
+        fold_constants: true,
     };
     let load_result = roc_load::load_and_monomorphize(
         arena,
-        path.to_path_buf(),
+        root_path,
         RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
         load_config,
     );
@@ -452,6 +927,12 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
             return handle_error_module(module, start_time.elapsed(), filename, false);
         }
     };
+
+    if harness.is_some() {
+        // the generated app imports every module it tests without using them
+        loaded.can_problems.remove(&loaded.module_id);
+    }
+
     let problems = report_problems_monomorphized(&mut loaded);
 
     let mut expectations = std::mem::take(&mut loaded.expectations);
@@ -475,7 +956,11 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
         );
         if problems.warnings > 0 {
             problems.print_to_stdout(start_time.elapsed());
-            println!(".\n\nRunning tests…\n\n\x1B[36m{}\x1B[39m", "─".repeat(80));
+            println!(
+                ".\n\n{}\n\n{}",
+                in_charset("Running tests…"),
+                Report::horizontal_rule(&DEFAULT_PALETTE)
+            );
         }
     }
 
@@ -484,6 +969,7 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
     let interns = arena.alloc(interns);
 
     let mut writer = std::io::stdout();
+    let mut failures = Vec::new();
 
     let (failed, passed) = roc_repl_expect::run::run_toplevel_expects(
         &mut writer,
@@ -494,6 +980,7 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
         &lib,
         &mut expectations,
         expects,
+        &mut failures,
     )
     .unwrap();
 
@@ -511,20 +998,88 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
         Ok(2)
     } else {
         let failed_color = if failed == 0 {
-            32 // green
+            ANSI_STYLE_CODES.green
         } else {
-            31 // red
+            ANSI_STYLE_CODES.red
         };
 
         println!(
-            "\n\x1B[{failed_color}m{failed}\x1B[39m failed and \x1B[32m{passed}\x1B[39m passed in {} ms.\n",
+            "\n{} failed and {} passed in {} ms.\n",
+            ansi_color(failed_color, failed),
+            ansi_color(ANSI_STYLE_CODES.green, passed),
             total_time.as_millis(),
         );
 
+        if matches.is_present(FLAG_DEV) {
+            offer_shrinking(arena, interns, &expectations, &failures)?;
+        }
+
         Ok((failed > 0) as i32)
     }
 }
 
+/// Offer to look for smaller inputs for each `expect` inside a function which failed
+#[cfg(not(windows))]
+fn offer_shrinking<'a>(
+    arena: &'a Bump,
+    interns: &roc_module::symbol::Interns,
+    expectations: &roc_collections::VecMap<roc_module::symbol::ModuleId, roc_load::Expectations>,
+    failures: &[roc_repl_expect::run::ExpectFailure<'a>],
+) -> io::Result<()> {
+    use roc_repl_expect::shrink::{can_shrink, failure_location, shrink_failure};
+    use std::io::Write;
+
+    // Only ask if someone is there to answer
+    if unsafe { libc::isatty(libc::STDIN_FILENO) } == 0 {
+        return Ok(());
+    }
+
+    for failure in failures
+        .iter()
+        .filter(|failure| can_shrink(failure, interns))
+    {
+        print!(
+            "Look for smaller inputs which still make the expectation at {} fail? [y/N] ",
+            failure_location(expectations, failure)
+        );
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+
+        if !answer.trim().eq_ignore_ascii_case("y") {
+            continue;
+        }
+
+        match shrink_failure(arena, interns, expectations, failure) {
+            Some(inputs) => {
+                println!("\nThe expectation still fails with these inputs:\n");
+
+                for (name, value) in inputs {
+                    println!("    {} = {}", name, value);
+                }
+
+                println!();
+            }
+            None => {
+                println!(
+                    "\nThis failure could not be reproduced outside of its function, so its inputs can't be made smaller.\n"
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn overflow_mode(matches: &ArgMatches) -> OverflowMode {
+    match matches.value_of(FLAG_OVERFLOW) {
+        Some("wrap") => OverflowMode::Wrap,
+        Some("saturate") => OverflowMode::Saturate,
+        _ => OverflowMode::Trap,
+    }
+}
+
 pub fn build(
     matches: &ArgMatches,
     config: BuildConfig,
@@ -581,6 +1136,8 @@ pub fn build(
 
             // Rather than building an executable or library, we're building
             // a tarball so this code can be distributed via a HTTPS
+            platform_check::record_host_abi(path)?;
+
             let filename = roc_packaging::tarball::build(path, compression)?;
             let total_time_ms = start_time.elapsed().as_millis();
             let total_time = if total_time_ms > 1000 {
@@ -591,9 +1148,13 @@ pub fn build(
             let created_path = path.with_file_name(&filename);
 
             println!(
-                "\nBundled \x1B[33m{}\x1B[39m and its dependent files into the following archive in {total_time}:\n\n\t\x1B[33m{}\x1B[39m\n\nTo distribute this archive as a package, upload this to some URL and then add it as a dependency with:\n\n\t\x1B[32m\"https://your-url-goes-here/{filename}\"\x1B[39m\n",
-                path.to_string_lossy(),
-                created_path.to_string_lossy()
+                "\nBundled {} and its dependent files into the following archive in {total_time}:\n\n\t{}\n\nTo distribute this archive as a package, upload this to some URL and then add it as a dependency with:\n\n\t{}\n",
+                ansi_color(ANSI_STYLE_CODES.yellow, path.to_string_lossy()),
+                ansi_color(ANSI_STYLE_CODES.yellow, created_path.to_string_lossy()),
+                ansi_color(
+                    ANSI_STYLE_CODES.green,
+                    format!("\"https://your-url-goes-here/{filename}\"")
+                )
             );
 
             return Ok(0);
@@ -602,6 +1163,24 @@ pub fn build(
         path.to_path_buf()
     };
 
+    if config == BuildConfig::BuildOnly && matches.is_present(FLAG_PRECOMPILE_PACKAGE) {
+        let target_info = roc_target::TargetInfo::from(&triple);
+        let modules = roc_load::precompile_package(&path_buf, target_info, roc_cache_dir)?;
+
+        println!(
+            "\nPrecompiled {} module(s) of {} into {}: {}\n",
+            modules.len(),
+            ansi_color(ANSI_STYLE_CODES.yellow, path_buf.to_string_lossy()),
+            ansi_color(
+                ANSI_STYLE_CODES.yellow,
+                roc_load::precompiled::PRECOMPILED_PACKAGE_FILENAME
+            ),
+            modules.join(", ")
+        );
+
+        return Ok(0);
+    }
+
     // the process will end after this function,
     // so we don't want to spend time freeing these values
     let arena = ManuallyDrop::new(Bump::new());
@@ -612,9 +1191,29 @@ pub fn build(
         opt_level_from_flags(matches)
     };
 
+    let backend = matches.value_of(FLAG_BACKEND);
+
+    // The WebAssembly interpreter runs the dev backend's output, whatever machine we're on.
+    let triple = if backend == Some("wasm-interp") {
+        if triple != Triple::host() && !matches!(triple.architecture, Architecture::Wasm32) {
+            user_error!("--{FLAG_BACKEND}=wasm-interp always builds for wasm32, so it cannot be combined with --{FLAG_TARGET}");
+        }
+
+        if !cfg!(feature = "run-wasm32") && !matches!(config, BuildOnly) {
+            user_error!("This roc was built without the `run-wasm32` feature, so it can build with --{FLAG_BACKEND}=wasm-interp but cannot run the result. Use `roc build` instead.");
+        }
+
+        Target::Wasm32.to_triple()
+    } else {
+        triple
+    };
+
+    let dev_backend =
+        matches.is_present(FLAG_DEV) || matches!(backend, Some("dev" | "wasm-interp"));
+
     // Note: This allows using `--dev` with `--optimize`.
     // This means frontend optimizations and dev backend.
-    let code_gen_backend = if matches.is_present(FLAG_DEV) {
+    let code_gen_backend = if dev_backend {
         if matches!(triple.architecture, Architecture::Wasm32) {
             CodeGenBackend::Wasm
         } else {
@@ -642,19 +1241,50 @@ pub fn build(
         Some(n) => Threading::AtMost(n),
     };
 
+    let profile = if matches.is_present(FLAG_PROFILE_GEN) {
+        let mut profile_path = std::fs::canonicalize(&path_buf)?;
+        profile_path.set_extension("profile");
+
+        ProfileOptions::Generate(profile_path)
+    } else if let Some(profile_path) = matches.value_of_os(FLAG_PROFILE_USE) {
+        let text = match std::fs::read_to_string(profile_path) {
+            Ok(text) => text,
+            Err(err) => user_error!(
+                "I could not read the profile at {}: {}",
+                Path::new(profile_path).display(),
+                err
+            ),
+        };
+
+        match Profile::parse(&text) {
+            Ok(profile) => ProfileOptions::Use(profile),
+            Err(problem) => user_error!(
+                "The profile at {} is invalid: {}",
+                Path::new(profile_path).display(),
+                problem
+            ),
+        }
+    } else {
+        ProfileOptions::Off
+    };
+
     let wasm_dev_backend = matches!(code_gen_backend, CodeGenBackend::Wasm);
 
     let linking_strategy = if wasm_dev_backend {
         LinkingStrategy::Additive
     } else if !roc_linker::supported(link_type, &triple)
         || matches.value_of(FLAG_LINKER) == Some("legacy")
+        // the surgical linker does not run the destructor which writes the profile
+        || matches!(profile, ProfileOptions::Generate(_))
     {
         LinkingStrategy::Legacy
     } else {
         LinkingStrategy::Surgical
     };
 
-    let prebuilt = if matches.is_present(FLAG_PREBUILT) {
+    let prebuilt = if matches.is_present(FLAG_REBUILD_HOST) {
+        false
+    } else if matches.is_present(FLAG_PREBUILT) {
         matches.value_of(FLAG_PREBUILT) == Some("true")
     } else {
         // When compiling for a different target, default to assuming a prebuilt platform.
@@ -675,13 +1305,24 @@ pub fn build(
         _ => BuildOrdering::AlwaysBuild,
     };
 
+    let atomic_refcounts = match matches.value_of(FLAG_ATOMIC_REFCOUNTS) {
+        Some("always") => AtomicRefcounts::Always,
+        Some("inferred") => AtomicRefcounts::Inferred,
+        _ => AtomicRefcounts::Never,
+    };
+
     let code_gen_options = CodeGenOptions {
         backend: code_gen_backend,
         opt_level,
         emit_debug_info,
+        profile,
+        atomic_refcounts,
+        arena_allocation: matches.is_present(FLAG_ARENA_ALLOCATION),
+        schedule_refcounts: matches.is_present(FLAG_SCHEDULE_REFCOUNTS),
     };
 
-    let load_config = standard_load_config(&triple, build_ordering, threading);
+    let mut load_config = standard_load_config(&triple, build_ordering, threading);
+    load_config.overflow = overflow_mode(matches);
 
     let res_binary_path = build_file(
         &arena,
@@ -689,9 +1330,13 @@ pub fn build(
         path_buf,
         code_gen_options,
         emit_timings,
+        matches.is_present(FLAG_EXPLAIN_SPECIALIZATIONS),
+        matches.is_present(FLAG_EXPLAIN_CLOSURES),
         link_type,
         linking_strategy,
+        roc_linker::LinkLog::new(matches.is_present(FLAG_VERBOSE_LINK)),
         prebuilt,
+        matches.is_present(FLAG_REBUILD_HOST),
         wasm_dev_stack_bytes,
         roc_cache_dir,
         load_config,
@@ -727,8 +1372,9 @@ pub fn build(
                     if problems.fatally_errored {
                         problems.print_to_stdout(total_time);
                         println!(
-                            ".\n\nCannot run program due to fatal error…\n\n\x1B[36m{}\x1B[39m",
-                            "─".repeat(80)
+                            ".\n\n{}\n\n{}",
+                            in_charset("Cannot run program due to fatal error…"),
+                            Report::horizontal_rule(&DEFAULT_PALETTE)
                         );
 
                         // Return a nonzero exit code due to fatal problem
@@ -737,8 +1383,9 @@ pub fn build(
                     if problems.errors > 0 || problems.warnings > 0 {
                         problems.print_to_stdout(total_time);
                         println!(
-                            ".\n\nRunning program anyway…\n\n\x1B[36m{}\x1B[39m",
-                            "─".repeat(80)
+                            ".\n\n{}\n\n{}",
+                            in_charset("Running program anyway…"),
+                            Report::horizontal_rule(&DEFAULT_PALETTE)
                         );
                     }
 
@@ -754,8 +1401,9 @@ pub fn build(
                     if problems.fatally_errored {
                         problems.print_to_stdout(total_time);
                         println!(
-                            ".\n\nCannot run program due to fatal error…\n\n\x1B[36m{}\x1B[39m",
-                            "─".repeat(80)
+                            ".\n\n{}\n\n{}",
+                            in_charset("Cannot run program due to fatal error…"),
+                            Report::horizontal_rule(&DEFAULT_PALETTE)
                         );
 
                         // Return a nonzero exit code due to fatal problem
@@ -769,8 +1417,9 @@ pub fn build(
                     if problems.warnings > 0 {
                         problems.print_to_stdout(total_time);
                         println!(
-                            ".\n\nRunning program…\n\n\x1B[36m{}\x1B[39m",
-                            "─".repeat(80)
+                            ".\n\n{}\n\n{}",
+                            in_charset("Running program…"),
+                            Report::horizontal_rule(&DEFAULT_PALETTE)
                         );
                     }
 
@@ -1241,6 +1890,8 @@ pub enum Target {
     Windows64,
     #[strum(serialize = "wasm32")]
     Wasm32,
+    #[strum(serialize = "linux-s390x")]
+    LinuxS390x,
 }
 
 impl Target {
@@ -1277,6 +1928,13 @@ impl Target {
                 environment: Environment::Unknown,
                 binary_format: BinaryFormat::Wasm,
             },
+            LinuxS390x => Triple {
+                architecture: Architecture::S390x,
+                vendor: Vendor::Unknown,
+                operating_system: OperatingSystem::Linux,
+                environment: Environment::Musl,
+                binary_format: BinaryFormat::Elf,
+            },
         }
     }
 }
@@ -1303,6 +1961,7 @@ impl std::str::FromStr for Target {
             "linux64" => Ok(Target::Linux64),
             "windows64" => Ok(Target::Windows64),
             "wasm32" => Ok(Target::Wasm32),
+            "linux-s390x" => Ok(Target::LinuxS390x),
             _ => Err(format!("Roc does not know how to compile to {}", string)),
         }
     }
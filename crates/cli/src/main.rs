@@ -1,18 +1,27 @@
 //! The `roc` binary that brings together all functionality in the Roc toolset.
+use clap::ArgMatches;
 use roc_build::link::LinkType;
 use roc_build::program::{check_file, CodeGenBackend};
+use roc_cli::workspace::{for_each_member, Workspace};
 use roc_cli::{
-    build_app, format, test, BuildConfig, FormatMode, Target, CMD_BUILD, CMD_CHECK, CMD_DEV,
-    CMD_DOCS, CMD_EDIT, CMD_FORMAT, CMD_GEN_STUB_LIB, CMD_GLUE, CMD_REPL, CMD_RUN, CMD_TEST,
-    CMD_VERSION, DIRECTORY_OR_FILES, FLAG_CHECK, FLAG_DEV, FLAG_LIB, FLAG_NO_LINK, FLAG_TARGET,
-    FLAG_TIME, GLUE_DIR, GLUE_SPEC, ROC_FILE,
+    api_diff, build_app, check_platform, crash_paths, demangle, dev_loop, fix_imports, format,
+    graph, info_type, minimize, publish, render_style, test, unused_report, BuildConfig,
+    FormatMode, Target, CMD_BUILD, CMD_CHECK, CMD_DEMANGLE, CMD_DEV, CMD_DOCS, CMD_EDIT,
+    CMD_FORMAT, CMD_GEN_STUB_LIB, CMD_GLUE, CMD_GRAPH, CMD_INFO, CMD_INFO_TYPE, CMD_MINIMIZE,
+    CMD_PUBLISH, CMD_REPL, CMD_RUN, CMD_TEST, CMD_VERSION, DIRECTORY_OR_FILES, FLAG_API_DIFF,
+    FLAG_CHECK, FLAG_CRASH_PATHS, FLAG_DEV, FLAG_EXPR, FLAG_FIX_IMPORTS, FLAG_HOST, FLAG_LIB,
+    FLAG_LINT_SINGLE_IMPLEMENTATIONS, FLAG_NO_LINK, FLAG_STUB, FLAG_TARGET, FLAG_TIME,
+    FLAG_UNUSED_REPORT, FLAG_VERBOSE_ERRORS, FLAG_VERIFY, FLAG_WATCH, FLAG_WORKSPACE, GLUE_DIR,
+    GLUE_SPEC, ROC_FILE,
 };
 use roc_docs::generate_docs_html;
-use roc_error_macros::user_error;
+use roc_error_macros::{crash_report, user_error};
 use roc_gen_dev::AssemblyBackendMode;
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
 use roc_load::{LoadingProblem, Threading};
 use roc_packaging::cache::{self, RocCacheDir};
+use roc_reporting::catalog::{set_language, Language};
+use roc_reporting::report::{set_render_style, set_verbose_errors};
 use std::fs::{self, FileType};
 use std::io;
 use std::path::{Path, PathBuf};
@@ -31,8 +40,20 @@ use roc_cli::build;
 fn main() -> io::Result<()> {
     let _tracing_guards = roc_tracing::setup_tracing!();
 
+    crash_report::install(include_str!("../../../version.txt"));
+
     let matches = build_app().get_matches();
 
+    set_render_style(render_style(&matches));
+    set_verbose_errors(matches.is_present(FLAG_VERBOSE_ERRORS));
+
+    if let Some(language) = std::env::var("ROC_LANG")
+        .ok()
+        .and_then(|locale| Language::from_locale(&locale))
+    {
+        set_language(language);
+    }
+
     let exit_code = match matches.subcommand() {
         None => {
             if matches.is_present(ROC_FILE) {
@@ -50,7 +71,9 @@ fn main() -> io::Result<()> {
             }
         }
         Some((CMD_RUN, matches)) => {
-            if matches.is_present(ROC_FILE) {
+            if let Some(expr) = matches.value_of(FLAG_EXPR) {
+                Ok(roc_repl_cli::eval_expr(expr))
+            } else if matches.is_present(ROC_FILE) {
                 build(
                     matches,
                     BuildConfig::BuildAndRun,
@@ -74,7 +97,9 @@ fn main() -> io::Result<()> {
             }
         }
         Some((CMD_DEV, matches)) => {
-            if matches.is_present(ROC_FILE) {
+            if matches.is_present(ROC_FILE) && matches.is_present(FLAG_WATCH) {
+                dev_loop(matches)
+            } else if matches.is_present(ROC_FILE) {
                 build(
                     matches,
                     BuildConfig::BuildAndRunIfNoErrors,
@@ -100,7 +125,30 @@ fn main() -> io::Result<()> {
             };
 
             if !output_path.exists() || output_path.is_dir() {
-                roc_glue::generate(input_path, output_path, spec_path, backend)
+                let exit_code = roc_glue::generate(input_path, output_path, spec_path, backend)?;
+
+                if exit_code != 0 {
+                    Ok(exit_code)
+                } else {
+                    if let Ok(language) = matches.value_of_t::<roc_glue::StubLanguage>(FLAG_STUB) {
+                        roc_glue::generate_stub(input_path, output_path, language)?;
+                    }
+
+                    if matches.is_present(FLAG_VERIFY) {
+                        let app_path =
+                            roc_glue::generate_conformance_suite(input_path, output_path)?;
+
+                        // build and run the suite with this same `roc`, just like `roc run` would
+                        let status = std::process::Command::new(std::env::current_exe()?)
+                            .arg(CMD_RUN)
+                            .arg(&app_path)
+                            .status()?;
+
+                        Ok(status.code().unwrap_or(1))
+                    } else {
+                        Ok(0)
+                    }
+                }
             } else {
                 eprintln!("`roc glue` must be given a directory to output into, because the glue might generate multiple files.");
 
@@ -137,69 +185,46 @@ fn main() -> io::Result<()> {
             )?)
         }
         Some((CMD_CHECK, matches)) => {
-            let arena = bumpalo::Bump::new();
-
-            let emit_timings = matches.is_present(FLAG_TIME);
-            let filename = matches.value_of_os(ROC_FILE).unwrap();
-            let roc_file_path = PathBuf::from(filename);
-            let threading = match matches
-                .value_of(roc_cli::FLAG_MAX_THREADS)
-                .and_then(|s| s.parse::<usize>().ok())
-            {
-                None => Threading::AllAvailable,
-                Some(0) => user_error!("cannot build with at most 0 threads"),
-                Some(1) => Threading::Single,
-                Some(n) => Threading::AtMost(n),
-            };
+            if let Some(mut paths) = matches.values_of_os(FLAG_API_DIFF) {
+                let old_path = Path::new(paths.next().unwrap());
+                let new_path = Path::new(paths.next().unwrap());
 
-            match check_file(
-                &arena,
-                roc_file_path,
-                emit_timings,
-                RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
-                threading,
-            ) {
-                Ok((problems, total_time)) => {
-                    println!(
-                        "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms.",
-                        if problems.errors == 0 {
-                            32 // green
-                        } else {
-                            33 // yellow
-                        },
-                        problems.errors,
-                        if problems.errors == 1 {
-                            "error"
-                        } else {
-                            "errors"
-                        },
-                        if problems.warnings == 0 {
-                            32 // green
-                        } else {
-                            33 // yellow
-                        },
-                        problems.warnings,
-                        if problems.warnings == 1 {
-                            "warning"
-                        } else {
-                            "warnings"
-                        },
-                        total_time.as_millis(),
-                    );
-
-                    Ok(problems.exit_code())
-                }
+                api_diff(old_path, new_path, threading_from(matches))
+            } else if let Some(host_path) = matches.value_of_os(FLAG_HOST) {
+                let platform_path = matches.value_of_os(ROC_FILE).unwrap();
 
-                Err(LoadingProblem::FormattedReport(report)) => {
-                    print!("{}", report);
+                check_platform(Path::new(platform_path), Path::new(host_path))
+            } else if matches.is_present(FLAG_CRASH_PATHS) {
+                let filename = matches.value_of_os(ROC_FILE).unwrap();
 
-                    Ok(1)
-                }
-                Err(other) => {
-                    panic!("build_file failed with error:\n{:?}", other);
-                }
+                crash_paths(Path::new(filename), threading_from(matches))
+            } else if matches.is_present(FLAG_UNUSED_REPORT) {
+                let filename = matches.value_of_os(ROC_FILE).unwrap();
+
+                unused_report(Path::new(filename), threading_from(matches))
+            } else if matches.is_present(FLAG_FIX_IMPORTS) {
+                let filename = matches.value_of_os(ROC_FILE).unwrap();
+
+                fix_imports(Path::new(filename), threading_from(matches))
+            } else if matches.is_present(FLAG_WORKSPACE) {
+                let workspace = Workspace::discover(&std::env::current_dir()?)
+                    .unwrap_or_else(|problem| user_error!("{}", problem));
+
+                for_each_member(&workspace, |member| check(matches, member.to_path_buf()))
+            } else {
+                let filename = matches.value_of_os(ROC_FILE).unwrap();
+
+                check(matches, PathBuf::from(filename))
             }
         }
+        Some((CMD_PUBLISH, matches)) => publish(matches),
+        Some((CMD_MINIMIZE, matches)) => minimize(matches),
+        Some((CMD_GRAPH, matches)) => graph(matches),
+        Some((CMD_DEMANGLE, matches)) => demangle(matches),
+        Some((CMD_INFO, matches)) => match matches.subcommand() {
+            Some((CMD_INFO_TYPE, matches)) => info_type(matches),
+            _ => unreachable!(),
+        },
         Some((CMD_REPL, _)) => Ok(roc_repl_cli::main()),
         Some((CMD_EDIT, matches)) => {
             match matches
@@ -230,6 +255,14 @@ fn main() -> io::Result<()> {
             let mut values: Vec<OsString> = Vec::new();
 
             match maybe_values {
+                None if matches.is_present(FLAG_WORKSPACE) => {
+                    let workspace = Workspace::discover(&std::env::current_dir()?)
+                        .unwrap_or_else(|problem| user_error!("{}", problem));
+
+                    for dir in workspace.member_dirs() {
+                        values.push(dir.into_os_string());
+                    }
+                }
                 None => {
                     let mut os_string_values: Vec<OsString> = Vec::new();
                     read_all_roc_files(
@@ -284,6 +317,50 @@ fn main() -> io::Result<()> {
     std::process::exit(exit_code);
 }
 
+fn check(matches: &ArgMatches, roc_file_path: PathBuf) -> io::Result<i32> {
+    let arena = bumpalo::Bump::new();
+
+    let emit_timings = matches.is_present(FLAG_TIME);
+    let threading = threading_from(matches);
+
+    match check_file(
+        &arena,
+        roc_file_path,
+        emit_timings,
+        matches.is_present(FLAG_LINT_SINGLE_IMPLEMENTATIONS),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        threading,
+    ) {
+        Ok((problems, total_time)) => {
+            problems.print_to_stdout(total_time);
+            println!(".");
+
+            Ok(problems.exit_code())
+        }
+
+        Err(LoadingProblem::FormattedReport(report)) => {
+            print!("{}", report);
+
+            Ok(1)
+        }
+        Err(other) => {
+            panic!("build_file failed with error:\n{:?}", other);
+        }
+    }
+}
+
+fn threading_from(matches: &ArgMatches) -> Threading {
+    match matches
+        .value_of(roc_cli::FLAG_MAX_THREADS)
+        .and_then(|s| s.parse::<usize>().ok())
+    {
+        None => Threading::AllAvailable,
+        Some(0) => user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(n),
+    }
+}
+
 fn read_all_roc_files(
     dir: &OsString,
     roc_file_paths: &mut Vec<OsString>,
@@ -0,0 +1,476 @@
+//! `roc minimize`: shrink a .roc file that crashes the compiler to a small reproducer.
+//!
+//! This is delta debugging on the syntax tree. Every candidate is a smaller version of the file:
+//! one with some top-level defs removed, or with an expression replaced by one of its
+//! sub-expressions. A candidate is kept when running `roc` on it still fails the same way,
+//! that is, it panics at the same place in the compiler (or its output still contains the given text),
+//! and then the search starts over on the smaller file, until no candidate reproduces anymore.
+
+use crate::{ARGS_FOR_APP, CMD_CHECK, FLAG_OUTPUT_CONTAINS, ROC_FILE};
+use bumpalo::Bump;
+use clap::ArgMatches;
+use roc_error_macros::crash_report::NO_CRASH_REPORT_ENV_VAR;
+use roc_parse::ast::{Defs, Expr, ValueDef};
+use roc_parse::module::{self, module_defs};
+use roc_parse::parser::Parser;
+use roc_parse::state::State;
+use roc_region::all::{Loc, Region};
+use std::ffi::OsString;
+use std::io::{self, Read, Seek};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A run of a candidate that takes this many times longer than the original run is treated as hanging.
+const TIMEOUT_FACTOR: u32 = 10;
+const MIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How a run of the compiler failed. Two runs reproduce the same bug if their signatures are equal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CrashSignature {
+    /// The compiler panicked at this location in its source code
+    Panic(String),
+    /// The process was killed by a signal, e.g. a segfault
+    Signal,
+    /// The output contained the text passed to --output-contains
+    OutputContains,
+}
+
+pub fn minimize(matches: &ArgMatches) -> io::Result<i32> {
+    let roc_file = PathBuf::from(matches.value_of_os(ROC_FILE).unwrap());
+    let output_contains = matches.value_of(FLAG_OUTPUT_CONTAINS);
+    let roc_args: Vec<OsString> = match matches.values_of_os(ARGS_FOR_APP) {
+        Some(args) => args.map(OsString::from).collect(),
+        None => vec![CMD_CHECK.into()],
+    };
+
+    let original_src = std::fs::read_to_string(&roc_file)?;
+
+    // candidates are written next to the original, so relative paths to platforms and modules still work
+    let candidate_path = roc_file.with_file_name(format!(
+        ".{}.minimize.roc",
+        roc_file.file_stem().unwrap_or_default().to_string_lossy()
+    ));
+
+    let runner = Runner {
+        roc_exe: std::env::current_exe()?,
+        roc_args,
+        candidate_path: candidate_path.clone(),
+        output_contains: output_contains.map(str::to_string),
+        timeout: Duration::MAX,
+    };
+
+    println!("Checking that the original file reproduces the problem…");
+
+    let start = Instant::now();
+    let signature = runner.run(&original_src)?;
+    let runner = Runner {
+        timeout: MIN_TIMEOUT.max(start.elapsed() * TIMEOUT_FACTOR),
+        ..runner
+    };
+
+    let signature = match signature {
+        Some(signature) => signature,
+        None => {
+            let _ = std::fs::remove_file(&candidate_path);
+
+            eprintln!(
+                "`roc {}` on {} does not crash the compiler{}, so there is nothing to minimize.",
+                runner.roc_args_display(),
+                roc_file.display(),
+                match output_contains {
+                    Some(text) => format!(" or print {:?}", text),
+                    None => String::new(),
+                }
+            );
+
+            return Ok(1);
+        }
+    };
+
+    println!("The problem reproduces: {}", signature.describe());
+
+    let mut src = original_src.clone();
+    let mut attempts = 0;
+
+    loop {
+        let candidates = candidates(&src);
+        let mut reduced = false;
+
+        for candidate in candidates {
+            attempts += 1;
+
+            if runner.run(&candidate)?.as_ref() == Some(&signature) {
+                println!(
+                    "{} → {} bytes ({} attempts)",
+                    src.len(),
+                    candidate.len(),
+                    attempts
+                );
+
+                src = candidate;
+                reduced = true;
+
+                break;
+            }
+        }
+
+        if !reduced {
+            break;
+        }
+    }
+
+    let _ = std::fs::remove_file(&candidate_path);
+
+    let minimized_path = roc_file.with_extension("min.roc");
+    std::fs::write(&minimized_path, &src)?;
+
+    println!(
+        "\nMinimized {} from {} to {} bytes, and wrote the result to {}",
+        roc_file.display(),
+        original_src.len(),
+        src.len(),
+        minimized_path.display()
+    );
+
+    Ok(0)
+}
+
+impl CrashSignature {
+    fn describe(&self) -> String {
+        match self {
+            CrashSignature::Panic(location) => format!("the compiler panics at {}", location),
+            CrashSignature::Signal => "the compiler is killed by a signal".to_string(),
+            CrashSignature::OutputContains => "the output contains the text".to_string(),
+        }
+    }
+}
+
+struct Runner {
+    roc_exe: PathBuf,
+    roc_args: Vec<OsString>,
+    candidate_path: PathBuf,
+    output_contains: Option<String>,
+    timeout: Duration,
+}
+
+impl Runner {
+    fn roc_args_display(&self) -> String {
+        let args: Vec<_> = self
+            .roc_args
+            .iter()
+            .map(|arg| arg.to_string_lossy())
+            .collect();
+
+        args.join(" ")
+    }
+
+    /// Run `roc` on this version of the file, and return how it failed, if it did.
+    /// A run that hangs, or only reports problems with the code, does not count as a failure.
+    fn run(&self, src: &str) -> io::Result<Option<CrashSignature>> {
+        std::fs::write(&self.candidate_path, src)?;
+
+        // the output goes to a file instead of a pipe, so a chatty compiler can not block on a full pipe
+        let mut output = tempfile::tempfile()?;
+
+        let mut child = Command::new(&self.roc_exe)
+            .args(&self.roc_args)
+            .arg(&self.candidate_path)
+            .env(NO_CRASH_REPORT_ENV_VAR, "1")
+            .stdin(Stdio::null())
+            .stdout(output.try_clone()?)
+            .stderr(output.try_clone()?)
+            .spawn()?;
+
+        let start = Instant::now();
+
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+
+            if start.elapsed() > self.timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+
+                return Ok(None);
+            }
+
+            thread::sleep(Duration::from_millis(20));
+        };
+
+        let mut bytes = Vec::new();
+        output.rewind()?;
+        output.read_to_end(&mut bytes)?;
+
+        let output = String::from_utf8_lossy(&bytes);
+
+        let signature = match &self.output_contains {
+            Some(text) => output
+                .contains(text.as_str())
+                .then_some(CrashSignature::OutputContains),
+            None => match panic_location(&output) {
+                Some(location) => Some(CrashSignature::Panic(location)),
+                None if status.code().is_none() => Some(CrashSignature::Signal),
+                None => None,
+            },
+        };
+
+        Ok(signature)
+    }
+}
+
+/// Where in the compiler the panic happened, e.g. `crates/compiler/mono/src/ir.rs:1234:17`.
+/// The message is left out, because it often contains variables or symbol ids that change
+/// when the code gets smaller.
+fn panic_location(output: &str) -> Option<String> {
+    let line = output.lines().find(|line| line.contains("panicked at "))?;
+    let after = &line[line.find("panicked at ")? + "panicked at ".len()..];
+
+    let location = if after.starts_with('\'') {
+        // thread 'main' panicked at 'message', crates/…/file.rs:1:2
+        after.rsplit_once("', ")?.1
+    } else {
+        // thread 'main' panicked at crates/…/file.rs:1:2:
+        after.trim_end_matches(':')
+    };
+
+    Some(location.to_string())
+}
+
+/// The smaller versions of `src` to try, roughly the biggest reductions first
+fn candidates(src: &str) -> Vec<String> {
+    let arena = Bump::new();
+
+    let state = match module::parse_header(&arena, State::new(src.as_bytes())) {
+        Ok((_, state)) => state,
+        Err(_) => return Vec::new(),
+    };
+
+    let defs = match module_defs().parse(&arena, state, 0) {
+        Ok((_, defs, _)) => defs,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut candidates = Vec::new();
+
+    // remove the top-level defs in chunks, halving the chunk size down to single defs
+    let mut chunk_size = (defs.regions.len() / 2).max(1);
+
+    while chunk_size >= 1 {
+        for chunk in defs.regions.chunks(chunk_size) {
+            candidates.push(remove_regions(src, chunk));
+        }
+
+        chunk_size /= 2;
+    }
+
+    let mut reductions = Vec::new();
+    defs_reductions(&defs, &mut reductions);
+
+    for reduction in reductions {
+        candidates.push(reduction.apply(src));
+    }
+
+    candidates
+}
+
+fn remove_regions(src: &str, regions: &[Region]) -> String {
+    let mut result = String::with_capacity(src.len());
+    let mut copied_until = 0;
+
+    for region in regions {
+        result.push_str(&src[copied_until..region.start().offset as usize]);
+        copied_until = region.end().offset as usize;
+    }
+
+    result.push_str(&src[copied_until..]);
+
+    result
+}
+
+/// One way to make the code smaller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Reduction {
+    Remove(Region),
+    /// Replace the code in the first region with the code in the second one, which is inside it
+    Replace(Region, Region),
+}
+
+impl Reduction {
+    fn apply(&self, src: &str) -> String {
+        match self {
+            Reduction::Remove(region) => remove_regions(src, &[*region]),
+            Reduction::Replace(outer, inner) => {
+                let start = outer.start().offset as usize;
+                let end = outer.end().offset as usize;
+
+                format!(
+                    "{}{}{}",
+                    &src[..start],
+                    &src[inner.start().offset as usize..inner.end().offset as usize],
+                    &src[end..]
+                )
+            }
+        }
+    }
+}
+
+fn defs_reductions(defs: &Defs, reductions: &mut Vec<Reduction>) {
+    for def in defs.defs() {
+        match def {
+            Err(ValueDef::Body(_, body)) => expr_reductions(&body.value, body.region, reductions),
+            Err(ValueDef::AnnotatedBody {
+                ann_pattern,
+                ann_type,
+                body_expr,
+                ..
+            }) => {
+                reductions.push(Reduction::Remove(Region::span_across(
+                    &ann_pattern.region,
+                    &ann_type.region,
+                )));
+
+                expr_reductions(&body_expr.value, body_expr.region, reductions);
+            }
+            Err(ValueDef::Expect { condition, .. })
+            | Err(ValueDef::ExpectFx { condition, .. })
+            | Err(ValueDef::Dbg { condition, .. }) => {
+                expr_reductions(&condition.value, condition.region, reductions)
+            }
+            Err(ValueDef::Annotation(..)) | Ok(_) => {}
+        }
+    }
+}
+
+fn replace_with(outer: Region, inner: &Loc<Expr>, reductions: &mut Vec<Reduction>) {
+    if outer != inner.region {
+        reductions.push(Reduction::Replace(outer, inner.region));
+    }
+}
+
+/// Replacing an expression with one of its sub-expressions, and removing the defs inside it.
+/// Many of these don't type check, but the ones that don't change the crash are kept.
+fn expr_reductions(expr: &Expr, region: Region, reductions: &mut Vec<Reduction>) {
+    match expr {
+        Expr::Defs(defs, final_expr) => {
+            replace_with(region, final_expr, reductions);
+
+            for def_region in defs.regions.iter() {
+                reductions.push(Reduction::Remove(*def_region));
+            }
+
+            defs_reductions(defs, reductions);
+            expr_reductions(&final_expr.value, final_expr.region, reductions);
+        }
+        Expr::Closure(_, body) => expr_reductions(&body.value, body.region, reductions),
+        Expr::Backpassing(_, source, continuation) => {
+            replace_with(region, continuation, reductions);
+
+            expr_reductions(&source.value, source.region, reductions);
+            expr_reductions(&continuation.value, continuation.region, reductions);
+        }
+        Expr::Expect(condition, continuation) | Expr::Dbg(condition, continuation) => {
+            replace_with(region, continuation, reductions);
+
+            expr_reductions(&condition.value, condition.region, reductions);
+            expr_reductions(&continuation.value, continuation.region, reductions);
+        }
+        Expr::Apply(function, args, _) => {
+            for arg in args.iter() {
+                replace_with(region, arg, reductions);
+            }
+
+            expr_reductions(&function.value, function.region, reductions);
+
+            for arg in args.iter() {
+                expr_reductions(&arg.value, arg.region, reductions);
+            }
+        }
+        Expr::BinOps(operands, last) => {
+            for (operand, _) in operands.iter() {
+                replace_with(region, operand, reductions);
+            }
+            replace_with(region, last, reductions);
+
+            for (operand, _) in operands.iter() {
+                expr_reductions(&operand.value, operand.region, reductions);
+            }
+            expr_reductions(&last.value, last.region, reductions);
+        }
+        Expr::UnaryOp(operand, _) => {
+            replace_with(region, operand, reductions);
+
+            expr_reductions(&operand.value, operand.region, reductions);
+        }
+        Expr::If(branches, final_else) => {
+            for (_, then) in branches.iter() {
+                replace_with(region, then, reductions);
+            }
+            replace_with(region, final_else, reductions);
+
+            for (condition, then) in branches.iter() {
+                expr_reductions(&condition.value, condition.region, reductions);
+                expr_reductions(&then.value, then.region, reductions);
+            }
+            expr_reductions(&final_else.value, final_else.region, reductions);
+        }
+        Expr::When(condition, branches) => {
+            for branch in branches.iter() {
+                replace_with(region, &branch.value, reductions);
+            }
+
+            expr_reductions(&condition.value, condition.region, reductions);
+
+            for branch in branches.iter() {
+                expr_reductions(&branch.value.value, branch.value.region, reductions);
+            }
+        }
+        Expr::List(items) | Expr::Tuple(items) => {
+            for item in items.iter() {
+                expr_reductions(&item.value, item.region, reductions);
+            }
+        }
+        Expr::ParensAround(inner) | Expr::SpaceBefore(inner, _) | Expr::SpaceAfter(inner, _) => {
+            expr_reductions(inner, region, reductions)
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{candidates, panic_location};
+
+    #[test]
+    fn panic_location_old_and_new_format() {
+        assert_eq!(
+            panic_location("thread 'main' panicked at 'oops, no layout', crates/compiler/mono/src/ir.rs:10:5\nnote: …"),
+            Some("crates/compiler/mono/src/ir.rs:10:5".to_string())
+        );
+        assert_eq!(
+            panic_location("thread 'main' panicked at crates/compiler/mono/src/ir.rs:10:5:\noops"),
+            Some("crates/compiler/mono/src/ir.rs:10:5".to_string())
+        );
+        assert_eq!(panic_location("── TYPE MISMATCH ──"), None);
+    }
+
+    #[test]
+    fn candidates_remove_defs_and_simplify() {
+        let src =
+            "app \"test\" provides [main] to \"./platform\"\n\nmain =\n    x = 1\n\n    x + 2\n";
+
+        let candidates = candidates(src);
+
+        // removing `main`
+        assert!(candidates
+            .contains(&"app \"test\" provides [main] to \"./platform\"\n\n\n".to_string()));
+        // simplifying the body of `main`
+        assert!(candidates
+            .iter()
+            .any(|candidate| candidate.contains("main =")
+                && !candidate.contains("x = 1")
+                && candidate.contains("x + 2")));
+    }
+}
@@ -0,0 +1,498 @@
+//! Check a platform's header against its prebuilt host, so platform authors find out about
+//! mismatches between the two right away, instead of from a cryptic error when linking an app.
+//!
+//! The host has to define the functions Roc code calls (like `roc_alloc`), and every entrypoint
+//! it calls (like `roc__mainForHost_1_exposed_generic`) has to be one the platform `provides`.
+//! Every effect of the platform has to be implemented by the host too, with the signature it had
+//! when `roc glue --stub` generated the host, since packages can call them through `host.`.
+//! The same goes for host intrinsics, the hosted functions which return a plain value instead of
+//! an effect.
+use bumpalo::Bump;
+use roc_collections::MutMap;
+use roc_glue::stub::{parse_effect_signatures, EFFECT_SIGNATURES_FILE};
+use roc_linker::HostSymbols;
+use roc_packaging::host_abi::{write_host_abi, HostHooks, HOST_ABI_FILENAME};
+use roc_parse::ast::{Header, Pattern, Spaced, TypeAnnotation, ValueDef};
+use roc_parse::header::ExposedName;
+use roc_parse::state::State;
+use roc_region::all::Loc;
+use roc_reporting::report::{ansi_color, ANSI_STYLE_CODES};
+use std::ffi::OsStr;
+use std::io;
+use std::path::Path;
+
+/// The functions every host has to define, along with their C signatures
+const HOST_FUNCTIONS: &[(&str, &str)] = &[
+    (
+        "roc_alloc",
+        "void *roc_alloc(size_t size, unsigned int alignment)",
+    ),
+    (
+        "roc_realloc",
+        "void *roc_realloc(void *ptr, size_t new_size, size_t old_size, unsigned int alignment)",
+    ),
+    (
+        "roc_dealloc",
+        "void roc_dealloc(void *ptr, unsigned int alignment)",
+    ),
+    (
+        "roc_panic",
+        "void roc_panic(struct RocStr *msg, unsigned int tag_id)",
+    ),
+    (
+        "roc_memcpy",
+        "void *roc_memcpy(void *dest, const void *src, size_t n)",
+    ),
+    (
+        "roc_memset",
+        "void *roc_memset(void *dest, int c, size_t n)",
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlatformProblem {
+    /// The platform provides a value it doesn't define
+    ProvidedButUndefined(String),
+    /// The host calls an entrypoint (given by its symbol) that the platform doesn't provide
+    UnknownEntrypoint(String),
+    /// The platform provides a value the host never calls, with its type if it's annotated
+    UnusedEntrypoint(String, Option<String>),
+    /// The host doesn't define a function Roc code calls, with its C signature
+    MissingHostFunction(&'static str, &'static str),
+    /// The host doesn't define `roc_fx_{name}` for an effect, with the effect's annotation
+    MissingEffect(String, String),
+    /// The host doesn't define `roc_fx_{name}` for a host intrinsic, with its annotation
+    MissingIntrinsic(String, String),
+    /// The annotation of an effect isn't the one the host was generated for: the name, the
+    /// annotation the host was generated for, and the current one
+    ChangedEffect(String, String, String),
+}
+
+impl PlatformProblem {
+    pub fn is_error(&self) -> bool {
+        !matches!(self, PlatformProblem::UnusedEntrypoint(..))
+    }
+}
+
+/// Compare what the platform provides, with the types they're annotated with, to the host's symbols
+pub fn platform_problems(
+    provides: &[&str],
+    annotations: &MutMap<&str, String>,
+    defined: &[&str],
+    host: &HostSymbols,
+) -> Vec<PlatformProblem> {
+    let mut problems = Vec::new();
+
+    for name in provides {
+        if !defined.contains(name) {
+            problems.push(PlatformProblem::ProvidedButUndefined(name.to_string()));
+        }
+    }
+
+    // e.g. `roc__mainForHost_1_exposed_generic` or `roc__mainForHost_0_caller`
+    let entrypoint_of = |symbol: &str| {
+        provides
+            .iter()
+            .find(|name| symbol.starts_with(&format!("roc__{}_", name)))
+            .copied()
+    };
+
+    for symbol in host.undefined.iter() {
+        let is_entrypoint = symbol.starts_with("roc__");
+
+        if is_entrypoint && entrypoint_of(symbol).is_none() {
+            problems.push(PlatformProblem::UnknownEntrypoint(symbol.clone()));
+        }
+    }
+
+    for name in provides {
+        let called = host
+            .undefined
+            .iter()
+            .any(|symbol| entrypoint_of(symbol) == Some(*name));
+
+        if !called {
+            let annotation = annotations.get(name).cloned();
+
+            problems.push(PlatformProblem::UnusedEntrypoint(
+                name.to_string(),
+                annotation,
+            ));
+        }
+    }
+
+    for (name, signature) in HOST_FUNCTIONS {
+        if host.defined.iter().all(|defined| defined != name) {
+            problems.push(PlatformProblem::MissingHostFunction(name, signature));
+        }
+    }
+
+    problems
+}
+
+/// Compare the platform's effects to the host, and to the signatures the host was generated for.
+/// The effects named in `intrinsics` are host intrinsics.
+pub fn effect_problems(
+    effects: &[(String, String)],
+    intrinsics: &[String],
+    generated_for: &[(String, String)],
+    host: &HostSymbols,
+) -> Vec<PlatformProblem> {
+    let mut problems = Vec::new();
+
+    for (name, annotation) in effects {
+        let symbol = format!("roc_fx_{}", name);
+
+        if host.defined.iter().all(|defined| *defined != symbol) {
+            if intrinsics.contains(name) {
+                problems.push(PlatformProblem::MissingIntrinsic(
+                    name.clone(),
+                    annotation.clone(),
+                ));
+            } else {
+                problems.push(PlatformProblem::MissingEffect(
+                    name.clone(),
+                    annotation.clone(),
+                ));
+            }
+        }
+
+        let generated_annotation = generated_for
+            .iter()
+            .find(|(generated_name, _)| generated_name == name)
+            .map(|(_, generated_annotation)| generated_annotation);
+
+        if let Some(generated_annotation) = generated_annotation {
+            if generated_annotation != annotation {
+                problems.push(PlatformProblem::ChangedEffect(
+                    name.clone(),
+                    generated_annotation.clone(),
+                    annotation.clone(),
+                ));
+            }
+        }
+    }
+
+    problems
+}
+
+fn exposed_name<'a>(spaced: &Spaced<'a, ExposedName<'a>>) -> &'a str {
+    match spaced {
+        Spaced::Item(name) => (*name).into(),
+        Spaced::SpaceBefore(inner, _) | Spaced::SpaceAfter(inner, _) => exposed_name(inner),
+    }
+}
+
+/// The names of the platform module's toplevel values, and the types of those that are annotated
+fn toplevel_values<'a>(
+    arena: &'a Bump,
+    state: State<'a>,
+) -> (Vec<&'a str>, MutMap<&'a str, String>) {
+    use roc_fmt::annotation::Formattable;
+
+    let (defs, _) = roc_parse::module::module_defs_recovering(arena, state);
+
+    let mut defined = Vec::new();
+    let mut annotations = MutMap::default();
+
+    let mut add_annotation = |pattern: &Loc<Pattern<'a>>, annotation: &Loc<TypeAnnotation<'a>>| {
+        if let Pattern::Identifier(name) = pattern.value {
+            let mut buf = roc_fmt::Buf::new_in(arena);
+            annotation.value.format(&mut buf, 0);
+
+            annotations.insert(name, buf.into_bump_str().to_string());
+        }
+    };
+
+    for value_def in defs.value_defs.iter() {
+        match value_def {
+            ValueDef::Annotation(pattern, annotation) => add_annotation(pattern, annotation),
+            ValueDef::AnnotatedBody {
+                ann_pattern,
+                ann_type,
+                body_pattern,
+                ..
+            } => {
+                add_annotation(ann_pattern, ann_type);
+
+                if let Pattern::Identifier(name) = body_pattern.value {
+                    defined.push(name);
+                }
+            }
+            ValueDef::Body(pattern, _) => {
+                if let Pattern::Identifier(name) = pattern.value {
+                    defined.push(name);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (defined, annotations)
+}
+
+/// Print the problems between the platform whose main module is at `platform_path`, and the
+/// prebuilt host at `host_path` (an object file, or a static library). Returns exit code 1 if any
+/// of them would stop apps from linking, and 2 if there are only warnings.
+pub fn check_platform(platform_path: &Path, host_path: &Path) -> io::Result<i32> {
+    let arena = Bump::new();
+    let src = std::fs::read(platform_path)?;
+
+    let (module, state) =
+        match roc_parse::module::parse_header(&arena, State::new(arena.alloc_slice_copy(&src))) {
+            Ok(parsed) => parsed,
+            Err(_) => {
+                println!(
+                    "The header of {} could not be parsed. Run `roc check` on it to see why.",
+                    platform_path.display()
+                );
+
+                return Ok(1);
+            }
+        };
+
+    let header = match module.header {
+        Header::Platform(header) => header,
+        _ => {
+            println!(
+                "{} is not a platform module, so there is no host to check it against.",
+                platform_path.display()
+            );
+
+            return Ok(1);
+        }
+    };
+
+    let provides: Vec<&str> = header
+        .provides
+        .item
+        .items
+        .iter()
+        .map(|name| exposed_name(&name.value))
+        .collect();
+
+    let (defined, annotations) = toplevel_values(&arena, state);
+
+    let host = roc_linker::host_symbols(host_path)?;
+
+    let effects = roc_glue::stub::effect_signatures(platform_path)?;
+    let intrinsics = roc_glue::stub::host_intrinsics(platform_path)?;
+
+    // Hosts which weren't generated by `roc glue --stub` don't have signatures to compare with
+    let signatures_path = host_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(EFFECT_SIGNATURES_FILE);
+    let generated_for = match std::fs::read_to_string(signatures_path) {
+        Ok(contents) => parse_effect_signatures(&contents),
+        Err(_) => Vec::new(),
+    };
+
+    let mut problems = platform_problems(&provides, &annotations, &defined, &host);
+    problems.extend(effect_problems(
+        &effects,
+        &intrinsics,
+        &generated_for,
+        &host,
+    ));
+    let errors = problems.iter().filter(|problem| problem.is_error()).count();
+
+    for problem in problems.iter() {
+        match problem {
+            PlatformProblem::ProvidedButUndefined(name) => println!(
+                "{} is in the platform's `provides`, but the platform doesn't define it.",
+                ansi_color(ANSI_STYLE_CODES.red, name)
+            ),
+            PlatformProblem::UnknownEntrypoint(symbol) => println!(
+                "{} is called by the host, but the platform doesn't provide it.",
+                ansi_color(ANSI_STYLE_CODES.red, symbol)
+            ),
+            PlatformProblem::UnusedEntrypoint(name, annotation) => {
+                println!(
+                    "{} is provided by the platform, but the host never calls it.",
+                    ansi_color(ANSI_STYLE_CODES.yellow, name)
+                );
+
+                if let Some(annotation) = annotation {
+                    println!("      {name} : {annotation}");
+                }
+            }
+            PlatformProblem::MissingHostFunction(name, signature) => println!(
+                "{} is called by Roc code, but the host doesn't define it. It should be:\n      {signature}",
+                ansi_color(ANSI_STYLE_CODES.red, name)
+            ),
+            PlatformProblem::MissingEffect(name, annotation) => println!(
+                "{} is an effect of the platform, but the host doesn't define roc_fx_{name} for it.\n      {name} : {annotation}",
+                ansi_color(ANSI_STYLE_CODES.red, name)
+            ),
+            PlatformProblem::MissingIntrinsic(name, annotation) => println!(
+                "{} is a host intrinsic of the platform, but the host doesn't define roc_fx_{name} for it.\n      {name} : {annotation}",
+                ansi_color(ANSI_STYLE_CODES.red, name)
+            ),
+            PlatformProblem::ChangedEffect(name, generated_for, annotation) => println!(
+                "{} has changed since the host was generated for it, so the host may be passed arguments it doesn't expect.\n      was: {name} : {generated_for}\n      now: {name} : {annotation}\n      Update roc_fx_{name} in the host, and its line in {EFFECT_SIGNATURES_FILE}.",
+                ansi_color(ANSI_STYLE_CODES.red, name)
+            ),
+        }
+    }
+
+    if !problems.is_empty() {
+        println!();
+    }
+
+    if errors > 0 {
+        println!(
+            "Found {errors} {} which would stop apps using this platform from linking.",
+            if errors == 1 { "problem" } else { "problems" }
+        );
+
+        Ok(1)
+    } else if !problems.is_empty() {
+        println!("Found only warnings. Apps using this platform will link.");
+
+        Ok(2)
+    } else {
+        println!("The host matches the platform's header.");
+
+        Ok(0)
+    }
+}
+
+/// Before bundling a platform, record which of the standard I/O hooks each of its prebuilt hosts
+/// defines, so tools using the bundle know which ones fall back to the builtins' defaults. Does
+/// nothing for modules which aren't platforms.
+pub fn record_host_abi(path_to_main: &Path) -> io::Result<()> {
+    let arena = Bump::new();
+    let src = std::fs::read(path_to_main)?;
+
+    let is_platform = matches!(
+        roc_parse::module::parse_header(&arena, State::new(arena.alloc_slice_copy(&src))),
+        Ok((module, _)) if matches!(module.header, Header::Platform(_))
+    );
+
+    if !is_platform {
+        return Ok(());
+    }
+
+    let platform_dir = path_to_main.parent().unwrap_or_else(|| Path::new("."));
+    let mut hosts = Vec::new();
+
+    for entry in std::fs::read_dir(platform_dir)? {
+        let path = entry?.path();
+
+        // the same prebuilt hosts the bundle includes, except for the surgical linker's metadata
+        let is_host = matches!(
+            path.extension().and_then(OsStr::to_str),
+            Some("o" | "obj" | "rh")
+        );
+
+        if is_host {
+            let name = path.file_name().unwrap().to_string_lossy();
+            let symbols = roc_linker::host_symbols(&path)?;
+
+            hosts.push(HostHooks::from_defined(&name, &symbols.defined));
+        }
+    }
+
+    hosts.sort_by(|a, b| a.host.cmp(&b.host));
+
+    std::fs::write(platform_dir.join(HOST_ABI_FILENAME), write_host_abi(&hosts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(defined: &[&str], undefined: &[&str]) -> HostSymbols {
+        HostSymbols {
+            defined: defined.iter().map(|name| name.to_string()).collect(),
+            undefined: undefined.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    const ALL_HOST_FUNCTIONS: &[&str] = &[
+        "roc_alloc",
+        "roc_dealloc",
+        "roc_memcpy",
+        "roc_memset",
+        "roc_panic",
+        "roc_realloc",
+    ];
+
+    #[test]
+    fn matching_host() {
+        let host = host(ALL_HOST_FUNCTIONS, &["roc__mainForHost_1_exposed_generic"]);
+
+        let problems = platform_problems(
+            &["mainForHost"],
+            &MutMap::default(),
+            &["mainForHost"],
+            &host,
+        );
+
+        assert_eq!(problems, vec![]);
+    }
+
+    #[test]
+    fn mismatched_host() {
+        let host = host(
+            &[
+                "roc_alloc",
+                "roc_dealloc",
+                "roc_memcpy",
+                "roc_memset",
+                "roc_panic",
+            ],
+            &["roc__main_1_exposed_generic"],
+        );
+
+        let mut annotations = MutMap::default();
+        annotations.insert("mainForHost", "Str".to_string());
+
+        let problems = platform_problems(&["mainForHost"], &annotations, &["mainForHost"], &host);
+
+        assert_eq!(
+            problems,
+            vec![
+                PlatformProblem::UnknownEntrypoint("roc__main_1_exposed_generic".into()),
+                PlatformProblem::UnusedEntrypoint("mainForHost".into(), Some("Str".into())),
+                PlatformProblem::MissingHostFunction("roc_realloc", HOST_FUNCTIONS[1].1),
+            ]
+        );
+        assert_eq!(
+            problems.iter().filter(|problem| problem.is_error()).count(),
+            2
+        );
+    }
+
+    #[test]
+    fn effects_against_generated_host() {
+        let host = host(&["roc_fx_putLine", "roc_fx_sha256"], &[]);
+        let intrinsics = ["sha256".to_string(), "popCount".to_string()];
+
+        let signature = |name: &str, annotation: &str| (name.to_string(), annotation.to_string());
+
+        let effects = [
+            signature("putLine", "Str -> Effect {}"),
+            signature("sha256", "List U8 -> List U8"),
+            signature("getLine", "Effect Str"),
+            signature("popCount", "U64 -> U8"),
+        ];
+        let generated_for = parse_effect_signatures(
+            "putLine : Str -> Effect {}\nsha256 : List U8 -> Str\ngetLine : Effect Str\n",
+        );
+
+        assert_eq!(
+            effect_problems(&effects, &intrinsics, &generated_for, &host),
+            vec![
+                PlatformProblem::ChangedEffect(
+                    "sha256".into(),
+                    "List U8 -> Str".into(),
+                    "List U8 -> List U8".into()
+                ),
+                PlatformProblem::MissingEffect("getLine".into(), "Effect Str".into()),
+                PlatformProblem::MissingIntrinsic("popCount".into(), "U64 -> U8".into()),
+            ]
+        );
+    }
+}
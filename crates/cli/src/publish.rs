@@ -0,0 +1,159 @@
+use crate::{test_file, FLAG_BUNDLE, FLAG_MAX_THREADS, FLAG_UPLOAD, ROC_FILE};
+use bumpalo::Bump;
+use clap::ArgMatches;
+use roc_build::program::{check_file, handle_loading_problem};
+use roc_docs::generate_docs_html;
+use roc_error_macros::user_error;
+use roc_load::Threading;
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_packaging::config::PackageConfig;
+use roc_packaging::tarball::Compression;
+use roc_reporting::report::{ansi_color, in_charset, ANSI_STYLE_CODES};
+use std::io;
+use std::path::Path;
+use target_lexicon::Triple;
+
+/// Validate a package and bundle it up for distribution, optionally uploading the bundle.
+///
+/// Validation means the package type-checks without errors, its docs generate,
+/// and all of its top-level `expect`s pass. The bundle is named after the hash of its
+/// contents, which is what `install_package` verifies when someone downloads it.
+pub fn publish(matches: &ArgMatches) -> io::Result<i32> {
+    let path = Path::new(matches.value_of_os(ROC_FILE).unwrap());
+
+    if !path.exists() {
+        eprintln!(
+            "\nThis file was not found: {}\n\nYou can run `roc help` for more information on how to provide a .roc file.\n",
+            path.to_string_lossy()
+        );
+
+        return Ok(1);
+    }
+
+    // Check the upload configuration before doing anything expensive,
+    // so a typo in the config doesn't cost a full validation run.
+    let opt_upload = if matches.is_present(FLAG_UPLOAD) {
+        let config = PackageConfig::load().unwrap_or_else(|problem| user_error!("{}", problem));
+
+        match config.publish_url.clone() {
+            Some(publish_url) => Some((config, publish_url)),
+            None => {
+                eprintln!("\nI don't know where to upload this package to!\n\nSet the ROC_PUBLISH_URL environment variable, or add a `publish = <url>` line to your packages.conf file.\n");
+
+                return Ok(1);
+            }
+        }
+    } else {
+        None
+    };
+
+    let threading = match matches
+        .value_of(FLAG_MAX_THREADS)
+        .and_then(|s| s.parse::<usize>().ok())
+    {
+        None => Threading::AllAvailable,
+        Some(0) => user_error!("cannot build with at most 0 threads"),
+        Some(1) => Threading::Single,
+        Some(n) => Threading::AtMost(n),
+    };
+
+    // Step 1: the package must check without errors
+    println!("Checking {}…\n", path.to_string_lossy());
+
+    {
+        let arena = Bump::new();
+
+        match check_file(
+            &arena,
+            path.to_path_buf(),
+            false,
+            false,
+            RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+            threading,
+        ) {
+            Ok((problems, total_time)) => {
+                if problems.errors > 0 {
+                    problems.print_to_stdout(total_time);
+                    println!(".\n\nA package can't be published while it has errors.");
+
+                    return Ok(1);
+                }
+            }
+            Err(problem) => return handle_loading_problem(problem),
+        }
+    }
+
+    // Step 2: its docs must generate
+    println!("Generating docs…\n");
+
+    generate_docs_html(path.to_path_buf());
+
+    // Step 3: its expects must pass
+    println!("Running expects…\n");
+
+    match test_file(matches, Triple::host(), path.as_os_str())? {
+        // 2 means there were no expects at all, which is fine for a package.
+        0 | 2 => {}
+        exit_code => {
+            println!("A package can't be published while some of its expects are failing.");
+
+            return Ok(exit_code);
+        }
+    }
+
+    // Step 4: bundle it up
+    let compression = matches
+        .value_of(FLAG_BUNDLE)
+        .and_then(|ext| Compression::try_from(ext).ok())
+        .unwrap_or(Compression::Brotli);
+    crate::platform_check::record_host_abi(path)?;
+
+    let filename = roc_packaging::tarball::build(path, compression)?;
+    let created_path = path.with_file_name(&filename);
+
+    println!(
+        "Bundled the package into:\n\n\t{}\n",
+        ansi_color(ANSI_STYLE_CODES.yellow, created_path.to_string_lossy())
+    );
+
+    // Step 5: optionally upload it
+    match opt_upload {
+        Some((config, publish_url)) => {
+            let url = format!("{}/{filename}", publish_url.trim_end_matches('/'));
+            let bytes = std::fs::read(&created_path)?;
+
+            println!(
+                "Uploading to {}{}\n",
+                ansi_color(ANSI_STYLE_CODES.cyan, &url),
+                in_charset("…")
+            );
+
+            match roc_packaging::https::upload(&url, bytes, config.token_for(&url)) {
+                Ok(()) => {
+                    println!(
+                        "Published! Others can now add this package as a dependency with:\n\n\t{}\n",
+                        ansi_color(ANSI_STYLE_CODES.green, format!("\"{url}\""))
+                    );
+
+                    Ok(0)
+                }
+                Err(problem) => {
+                    eprintln!("The upload failed: {problem:?}");
+
+                    Ok(1)
+                }
+            }
+        }
+        None => {
+            println!(
+                "To distribute this package, upload it to some URL (or re-run with --{FLAG_UPLOAD}) and then add it as a dependency with:\n\n\t{}\n",
+                ansi_color(
+                    ANSI_STYLE_CODES.green,
+                    format!("\"https://your-url-goes-here/{filename}\"")
+                )
+            );
+
+            Ok(0)
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! `roc test` on the main module of a package or a platform. Only apps can be built, so the
+//! expects of a package or platform run inside an app that `roc test` generates, which depends on
+//! it as [`TESTED_PACKAGE_SHORTHAND`]. Library authors don't have to keep a dummy app around just
+//! to run their tests.
+//!
+//! A package gets a stub platform, and the app imports every module the package exposes, so they
+//! are all loaded. A platform is the app's platform itself; the app's `main` crashes, which has any
+//! type the platform requires, and is never run.
+use bumpalo::Bump;
+use roc_load::TESTED_PACKAGE_SHORTHAND;
+use roc_parse::ast::{ExtractSpaces, Header};
+use roc_parse::state::State;
+use std::io;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// The name of the stub platform's shorthand, for testing packages
+const STUB_PLATFORM_SHORTHAND: &str = "pf";
+
+const STUB_PLATFORM: &str = r#"platform "roc-test-harness"
+    requires {} { main : Str }
+    exposes []
+    packages {}
+    imports []
+    provides [mainForHost]
+
+mainForHost : Str
+mainForHost = main
+"#;
+
+/// A generated app that runs the tests of a package or platform. The files are removed when it
+/// is dropped.
+pub(crate) struct Harness {
+    _dir: TempDir,
+    pub app: PathBuf,
+}
+
+/// What kind of module is tested
+#[derive(Debug, PartialEq, Eq)]
+enum Tested<'a> {
+    /// A package, and the modules it exposes
+    Package(Vec<&'a str>),
+    /// A platform, the modules it exposes, and the types it requires the app to provide
+    Platform(Vec<&'a str>, Vec<&'a str>),
+}
+
+/// The harness to test the package or platform whose main module is at `path`, or None when it is
+/// something `roc test` can load directly, like an app. Headers which don't parse are left for
+/// loading to report.
+pub(crate) fn harness_for(path: &Path) -> io::Result<Option<Harness>> {
+    let arena = Bump::new();
+    let src = match std::fs::read(path) {
+        Ok(src) => src,
+        // loading reports files which can't be read
+        Err(_) => return Ok(None),
+    };
+
+    let tested = match roc_parse::module::parse_header(&arena, State::new(&src)) {
+        Ok((module, _)) => match tested(&module.header) {
+            Some(tested) => tested,
+            None => return Ok(None),
+        },
+        Err(_) => return Ok(None),
+    };
+
+    let dir = tempfile::tempdir()?;
+    let tested_path = path.canonicalize()?;
+
+    let app_source = match &tested {
+        Tested::Package(_) => {
+            let platform_path = dir.path().join("platform.roc");
+            std::fs::write(&platform_path, STUB_PLATFORM)?;
+
+            harness_app(&tested, &tested_path, Some(&platform_path))
+        }
+        Tested::Platform(_, _) => harness_app(&tested, &tested_path, None),
+    };
+
+    let app = dir.path().join("main.roc");
+    std::fs::write(&app, app_source)?;
+
+    Ok(Some(Harness { _dir: dir, app }))
+}
+
+fn tested<'a>(header: &Header<'a>) -> Option<Tested<'a>> {
+    match header {
+        Header::Package(header) => Some(Tested::Package(
+            header
+                .exposes
+                .item
+                .items
+                .iter()
+                .map(|name| name.extract_spaces().item.into())
+                .collect(),
+        )),
+        Header::Platform(header) => Some(Tested::Platform(
+            header
+                .exposes
+                .item
+                .items
+                .iter()
+                .map(|name| name.extract_spaces().item.into())
+                .collect(),
+            header
+                .requires
+                .item
+                .rigids
+                .items
+                .iter()
+                .map(|rigid| rigid.extract_spaces().item.into())
+                .collect(),
+        )),
+        Header::Interface(_) | Header::App(_) | Header::Hosted(_) => None,
+    }
+}
+
+fn harness_app(tested: &Tested, tested_path: &Path, platform_path: Option<&Path>) -> String {
+    let (exposed, provided_types) = match tested {
+        Tested::Package(exposed) => (exposed, &[][..]),
+        Tested::Platform(exposed, required_types) => (exposed, &required_types[..]),
+    };
+
+    let mut packages = vec![format!(
+        "{}: {}",
+        TESTED_PACKAGE_SHORTHAND,
+        roc_string(tested_path)
+    )];
+
+    if let Some(platform_path) = platform_path {
+        packages.push(format!(
+            "{}: {}",
+            STUB_PLATFORM_SHORTHAND,
+            roc_string(platform_path)
+        ));
+    }
+
+    let imports: Vec<String> = exposed
+        .iter()
+        .map(|module| format!("{}.{}", TESTED_PACKAGE_SHORTHAND, module))
+        .collect();
+
+    let provides = if provided_types.is_empty() {
+        "[main]".to_string()
+    } else {
+        format!("[main] {{ {} }}", provided_types.join(", "))
+    };
+
+    let (platform, main) = match platform_path {
+        Some(_) => (STUB_PLATFORM_SHORTHAND, "\"\"".to_string()),
+        None => (
+            TESTED_PACKAGE_SHORTHAND,
+            "crash \"roc test never runs main\"".to_string(),
+        ),
+    };
+
+    let mut source = format!(
+        "app \"roc-test-harness\"\n    packages {{ {} }}\n    imports [{}]\n    provides {} to {}\n\nmain = {}\n",
+        packages.join(", "),
+        imports.join(", "),
+        provides,
+        platform,
+        main
+    );
+
+    for provided_type in provided_types {
+        source.push_str(&format!("\n{} : {{}}\n", provided_type));
+    }
+
+    source
+}
+
+fn roc_string(path: &Path) -> String {
+    format!(
+        "\"{}\"",
+        path.to_string_lossy()
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn package_harness() {
+        let tested = Tested::Package(vec!["Json", "Json.Decode"]);
+
+        assert_eq!(
+            harness_app(
+                &tested,
+                Path::new("/pkg/main.roc"),
+                Some(Path::new("/tmp/platform.roc"))
+            ),
+            indoc::indoc!(
+                r#"
+                app "roc-test-harness"
+                    packages { tested: "/pkg/main.roc", pf: "/tmp/platform.roc" }
+                    imports [tested.Json, tested.Json.Decode]
+                    provides [main] to pf
+
+                main = ""
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn platform_harness() {
+        let tested = Tested::Platform(vec!["Task"], vec!["Model"]);
+
+        assert_eq!(
+            harness_app(&tested, Path::new("/pf/main.roc"), None),
+            indoc::indoc!(
+                r#"
+                app "roc-test-harness"
+                    packages { tested: "/pf/main.roc" }
+                    imports [tested.Task]
+                    provides [main] { Model } to tested
+
+                main = crash "roc test never runs main"
+
+                Model : {}
+                "#
+            )
+        );
+    }
+}
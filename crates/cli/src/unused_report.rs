@@ -0,0 +1,96 @@
+//! Report the top-level defs, types and imports of a package (or an app) that nothing uses,
+//! starting from what the package exposes (or the app provides). Each finding is printed on a
+//! line of its own, like `Lib.roc:12:1: unused value Lib.helper`, so CI can parse them.
+use crate::crash_paths::{qualified, Locations};
+use bumpalo::Bump;
+use roc_can::dead_code::{find_unused, UnusedKind};
+use roc_load::{ExecutionMode, LoadConfig, LoadingProblem, OverflowMode, Threading};
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use std::io;
+use std::path::Path;
+
+/// Print everything that nothing uses to stdout. Returns exit code 1 if there is anything.
+pub fn unused_report(path: &Path, threading: Threading) -> io::Result<i32> {
+    let arena = Bump::new();
+
+    let load_config = LoadConfig {
+        // only used for generating errors, since we don't do code generation here
+        target_info: TargetInfo::default_x86_64(),
+        render: RenderTarget::ColorTerminal,
+        palette: DEFAULT_PALETTE,
+        threading,
+        exec_mode: ExecutionMode::Check,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants: true,
+    };
+
+    let loaded = match roc_load::load_and_typecheck(
+        &arena,
+        path.to_path_buf(),
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        load_config,
+    ) {
+        Ok(loaded) => loaded,
+        Err(LoadingProblem::FormattedReport(report)) => {
+            print!("{}", report);
+
+            return Ok(1);
+        }
+        Err(other) => {
+            panic!("unused_report failed with error:\n{:?}", other);
+        }
+    };
+
+    // an app starts from what it provides, a package from the modules it exposes
+    let mut root_modules = vec![loaded.module_id];
+    root_modules.extend(loaded.docs_by_module.keys().copied());
+
+    let mut unused = find_unused(&loaded.reference_summaries, &root_modules);
+
+    unused.sort_by_cached_key(|unused| {
+        (
+            loaded.interns.module_name(unused.module_id).to_string(),
+            unused.region.start(),
+        )
+    });
+
+    let interns = &loaded.interns;
+    let mut locations = Locations::new(&loaded.sources);
+
+    for unused in unused.iter() {
+        let (kind, name) = match unused.kind {
+            UnusedKind::Value(symbol) => ("value", qualified(interns, symbol)),
+            UnusedKind::Type(symbol) => ("type", qualified(interns, symbol)),
+            UnusedKind::Import(module_id) => ("import", interns.module_name(module_id).to_string()),
+        };
+
+        println!(
+            "{}: unused {} {}",
+            locations.describe(unused.module_id, unused.region),
+            kind,
+            name
+        );
+    }
+
+    // keep stdout machine-readable
+    match unused.len() {
+        0 => {
+            eprintln!("Everything is used.");
+
+            Ok(0)
+        }
+        count => {
+            eprintln!(
+                "Found {} unused {}.",
+                count,
+                if count == 1 { "item" } else { "items" }
+            );
+
+            Ok(1)
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! A workspace is a directory tree containing several Roc apps and/or packages which get checked,
+//! tested, and formatted together. Its root directory contains a `roc.workspace` file listing the
+//! main module of each member, one per line, relative to the root:
+//!
+//! ```text
+//! # Apps
+//! apps/server/main.roc
+//! apps/cli
+//!
+//! # Packages shared between them
+//! packages/json/main.roc
+//! ```
+//!
+//! A member which is a directory refers to the `main.roc` inside it.
+//! Blank lines and lines beginning with `#` are ignored.
+use roc_build::program::DEFAULT_ROC_FILENAME;
+use roc_reporting::report::{ansi_color, in_charset, ANSI_STYLE_CODES};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const WORKSPACE_FILENAME: &str = "roc.workspace";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    /// The directory containing the `roc.workspace` file
+    pub root: PathBuf,
+    /// The main module of each member, in the order they were listed
+    pub members: Vec<PathBuf>,
+}
+
+#[derive(Debug)]
+pub enum WorkspaceProblem {
+    NotFound { searched_from: PathBuf },
+    IoErr(PathBuf, io::Error),
+    MissingMember { line_number: usize, path: PathBuf },
+    NoMembers(PathBuf),
+}
+
+impl fmt::Display for WorkspaceProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorkspaceProblem::NotFound { searched_from } => write!(
+                f,
+                "I could not find a {WORKSPACE_FILENAME} file in {} or any of its parent directories.",
+                searched_from.display()
+            ),
+            WorkspaceProblem::IoErr(path, err) => {
+                write!(f, "I could not read {}: {err}", path.display())
+            }
+            WorkspaceProblem::MissingMember { line_number, path } => write!(
+                f,
+                "Line {line_number} of {WORKSPACE_FILENAME} refers to {}, but that file does not exist.",
+                path.display()
+            ),
+            WorkspaceProblem::NoMembers(path) => {
+                write!(f, "{} does not list any members.", path.display())
+            }
+        }
+    }
+}
+
+impl Workspace {
+    /// Look for a `roc.workspace` file in the given directory and then each of its ancestors,
+    /// and load the first one found.
+    pub fn discover(start_dir: &Path) -> Result<Self, WorkspaceProblem> {
+        for dir in start_dir.ancestors() {
+            let path = dir.join(WORKSPACE_FILENAME);
+
+            if path.is_file() {
+                return Self::load(&path);
+            }
+        }
+
+        Err(WorkspaceProblem::NotFound {
+            searched_from: start_dir.to_path_buf(),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, WorkspaceProblem> {
+        let src =
+            fs::read_to_string(path).map_err(|err| WorkspaceProblem::IoErr(path.into(), err))?;
+        let root = path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .to_path_buf();
+        let workspace = Self::parse(&src, root)?;
+
+        if workspace.members.is_empty() {
+            return Err(WorkspaceProblem::NoMembers(path.to_path_buf()));
+        }
+
+        Ok(workspace)
+    }
+
+    fn parse(src: &str, root: PathBuf) -> Result<Self, WorkspaceProblem> {
+        let mut members: Vec<PathBuf> = Vec::new();
+
+        for (index, line) in src.lines().enumerate() {
+            let trimmed = line.trim();
+
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let mut path = root.join(trimmed);
+
+            if path.is_dir() {
+                path.push(DEFAULT_ROC_FILENAME);
+            }
+
+            if !path.is_file() {
+                return Err(WorkspaceProblem::MissingMember {
+                    line_number: index + 1,
+                    path,
+                });
+            }
+
+            if !members.contains(&path) {
+                members.push(path);
+            }
+        }
+
+        Ok(Workspace { root, members })
+    }
+
+    /// The directory of each member, without duplicates. Formatting a workspace
+    /// formats every .roc file in these directories.
+    pub fn member_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = Vec::with_capacity(self.members.len());
+
+        for member in self.members.iter() {
+            let dir = member.parent().unwrap_or(&self.root).to_path_buf();
+
+            // Skip directories nested inside one we already have; they'd get formatted twice.
+            if dirs.iter().any(|existing| dir.starts_with(existing)) {
+                continue;
+            }
+
+            dirs.retain(|existing| !existing.starts_with(&dir));
+            dirs.push(dir);
+        }
+
+        dirs
+    }
+
+    /// A member's path relative to the workspace root, for display purposes
+    pub fn display_member<'a>(&self, member: &'a Path) -> std::path::Display<'a> {
+        member.strip_prefix(&self.root).unwrap_or(member).display()
+    }
+}
+
+/// Run the given command on each member in turn, printing a heading before each one.
+/// Returns the highest exit code any member returned, so a single failure fails the whole run.
+///
+/// All members are processed in this one process, so packages they have in common only
+/// get downloaded (or read from the package cache) once.
+pub fn for_each_member(
+    workspace: &Workspace,
+    mut run: impl FnMut(&Path) -> io::Result<i32>,
+) -> io::Result<i32> {
+    let mut exit_code = 0;
+
+    for member in workspace.members.iter() {
+        let header = format!("── {} {}", workspace.display_member(member), "─".repeat(60));
+        println!(
+            "\n{}\n",
+            ansi_color(ANSI_STYLE_CODES.cyan, in_charset(&header))
+        );
+
+        exit_code = exit_code.max(run(member)?);
+    }
+
+    Ok(exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discover_from_subdirectory() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        fs::create_dir_all(root.join("apps/cli")).unwrap();
+        fs::create_dir_all(root.join("apps/server")).unwrap();
+        fs::write(root.join("apps/cli/main.roc"), "").unwrap();
+        fs::write(root.join("apps/server/Server.roc"), "").unwrap();
+        fs::write(
+            root.join(WORKSPACE_FILENAME),
+            "# apps\napps/cli\n\napps/server/Server.roc\napps/cli/main.roc\n",
+        )
+        .unwrap();
+
+        let workspace = Workspace::discover(&root.join("apps/server")).unwrap();
+
+        assert_eq!(workspace.root, root);
+        assert_eq!(
+            workspace.members,
+            vec![
+                root.join("apps/cli/main.roc"),
+                root.join("apps/server/Server.roc")
+            ]
+        );
+        assert_eq!(
+            workspace.member_dirs(),
+            vec![root.join("apps/cli"), root.join("apps/server")]
+        );
+    }
+
+    #[test]
+    fn missing_member() {
+        let dir = tempfile::tempdir().unwrap();
+
+        fs::write(dir.path().join(WORKSPACE_FILENAME), "nope/main.roc\n").unwrap();
+
+        assert!(matches!(
+            Workspace::discover(dir.path()),
+            Err(WorkspaceProblem::MissingMember { line_number: 1, .. })
+        ));
+    }
+}
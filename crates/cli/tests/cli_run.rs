@@ -50,10 +50,12 @@ mod cli_run {
 
     const OPTIMIZE_FLAG: &str = concatcp!("--", roc_cli::FLAG_OPTIMIZE);
     const LINKER_FLAG: &str = concatcp!("--", roc_cli::FLAG_LINKER);
+    const VERBOSE_LINK_FLAG: &str = concatcp!("--", roc_cli::FLAG_VERBOSE_LINK);
     const CHECK_FLAG: &str = concatcp!("--", roc_cli::FLAG_CHECK);
     const PREBUILT_PLATFORM: &str = concatcp!("--", roc_cli::FLAG_PREBUILT, "=true");
     #[allow(dead_code)]
     const TARGET_FLAG: &str = concatcp!("--", roc_cli::FLAG_TARGET);
+    const EXPR_FLAG: &str = concatcp!("--", roc_cli::FLAG_EXPR);
 
     #[derive(Debug)]
     enum CliMode {
@@ -627,6 +629,11 @@ mod cli_run {
                  9│>
                 10│>      a == b
 
+                The two sides were not equal:
+
+                    left:  1
+                    right: 2
+
                 When it failed, these variables had these values:
 
                 a : Num *
@@ -644,6 +651,22 @@ mod cli_run {
         );
     }
 
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn expects_with_effect_interpreters() {
+        test_roc_app(
+            "crates/cli_testing_examples/effect-interpreters",
+            "main.roc",
+            "effect-interpreters",
+            &[],
+            &[],
+            &[],
+            "0 failed and 3 passed in <ignored for test> ms.",
+            UseValgrind::No,
+            TestCliCommands::Test,
+        );
+    }
+
     #[test]
     #[cfg_attr(
         windows,
@@ -1324,6 +1347,106 @@ mod cli_run {
         );
     }
 
+    #[test]
+    #[serial(multi_dep_str)]
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    fn build_with_verbose_link() {
+        // --verbose-link only affects the surgical linker, which only supports linux x86_64
+        let file = fixture_file("multi-dep-str", "Main.roc");
+        let out = run_roc(
+            [
+                CMD_BUILD,
+                LINKER_FLAG,
+                "surgical",
+                VERBOSE_LINK_FLAG,
+                file.to_str().unwrap(),
+            ],
+            &[],
+            &[],
+        );
+
+        assert!(out.status.success(), "{}", out.stderr);
+        assert!(out.stderr.contains("link: add-segment kind=text "));
+        assert!(out
+            .stderr
+            .contains("link: replace-placeholder symbol=roc__mainForHost_1_exposed_generic "));
+    }
+
+    #[test]
+    fn run_expr() {
+        let out = run_roc([CMD_RUN, EXPR_FLAG, "List.sum [1, 2, 3]"], &[], &[]);
+
+        assert!(out.status.success(), "{}", out.stderr);
+        assert!(strip_colors(&out.stdout).contains("6 : Num *"));
+    }
+
+    #[test]
+    fn run_expr_with_type_error() {
+        let out = run_roc([CMD_RUN, EXPR_FLAG, "1 + \"two\""], &[], &[]);
+
+        assert!(!out.status.success());
+        assert!(strip_colors(&out.stdout).contains("TYPE MISMATCH"));
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn run_host_built_by_recipe() {
+        // The platform has both a host.c and a host.zig that fails to compile. Only the recipe
+        // makes the build pick host.c.
+        check_output_with_stdin(
+            &fixture_file("host-recipe", "Main.roc"),
+            &[],
+            "host-recipe",
+            &[],
+            &[],
+            &[],
+            "Built with the recipe's builder\n",
+            UseValgrind::Yes,
+            TestCliCommands::Run,
+        );
+    }
+
+    #[test]
+    #[cfg_attr(windows, ignore)]
+    fn run_host_intrinsic() {
+        // The platform's hosted popCount returns a U8 rather than an effect, so the app's
+        // code calls the host's roc_fx_popCount directly.
+        check_output_with_stdin(
+            &fixture_file("host-intrinsic", "Main.roc"),
+            &[],
+            "host-intrinsic",
+            &[],
+            &[],
+            &[],
+            "popCount 255 = 8\n",
+            UseValgrind::Yes,
+            TestCliCommands::Run,
+        );
+    }
+
+    #[test]
+    #[serial(wasm32_host)]
+    #[cfg(feature = "wasm32-cli-run")]
+    #[cfg_attr(windows, ignore)]
+    fn run_wasm32_host_with_wasm_interp_backend() {
+        // `--backend=wasm-interp` builds the app and the platform's host.zig for wasm32-wasi,
+        // then runs the result in roc_wasm_interp from inside the roc process.
+        let out = run_roc_on_failure_is_panic(
+            &fixture_file("wasm32-host", "Main.roc"),
+            [CMD_RUN, concatcp!("--", roc_cli::FLAG_BACKEND, "=wasm-interp")],
+            &[],
+            &[],
+            &[],
+        );
+
+        assert!(
+            out.stdout.ends_with("Hello from the interpreter!\n"),
+            "expected output to end with {:?} but instead got {:#?}",
+            "Hello from the interpreter!\n",
+            out.stdout
+        );
+    }
+
     #[test]
     #[serial(multi_dep_thunk)]
     #[cfg_attr(windows, ignore)]
@@ -0,0 +1,6 @@
+app "host-intrinsic"
+    packages { pf: "platform/main.roc" }
+    imports []
+    provides [main] to pf
+
+main = 255
@@ -0,0 +1,7 @@
+hosted Intrinsics
+    exposes [popCount]
+    imports []
+    generates Effect with []
+
+# Doesn't return an Effect, so Roc calls roc_fx_popCount directly.
+popCount : U64 -> U8
@@ -0,0 +1,10 @@
+platform "host-intrinsic"
+    requires {} { main : U64 }
+    exposes []
+    packages {}
+    imports [Intrinsics.{ popCount }]
+    provides [mainForHost]
+
+mainForHost : Str
+mainForHost =
+    "popCount \(Num.toStr main) = \(Num.toStr (popCount main))\n"
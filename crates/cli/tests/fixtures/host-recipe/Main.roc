@@ -0,0 +1,6 @@
+app "host-recipe"
+    packages { pf: "platform/main.roc" }
+    imports []
+    provides [main] to pf
+
+main = "Built with the recipe's builder\n"
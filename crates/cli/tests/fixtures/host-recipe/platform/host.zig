@@ -0,0 +1,3 @@
+comptime {
+    @compileError("this platform's host.recipe should have picked host.c");
+}
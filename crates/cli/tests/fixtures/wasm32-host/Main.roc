@@ -0,0 +1,7 @@
+app "wasm32-host"
+    packages { pf: "platform/main.roc" }
+    imports []
+    provides [main] to pf
+
+main : Str
+main = Str.joinWith ["Hello", "from", "the", "interpreter!"] " "
@@ -1,5 +1,5 @@
 interface Task
-    exposes [Task, succeed, fail, after, map, putLine, putInt, getInt, forever, loop]
+    exposes [Task, succeed, fail, after, map, run, putLine, putInt, getInt, forever, loop]
     imports [pf.Effect]
 
 Task ok err : Effect.Effect (Result ok err)
@@ -55,6 +55,11 @@ map = \effect, transform ->
                 Ok a -> Ok (transform a)
                 Err err -> Err err
 
+## Performs the task right away, only meant for expects.
+## `roc test` answers its effects with the effect interpreters of the tested module.
+run : Task ok err -> Result ok err
+run = \task -> Effect.run task
+
 putLine : Str -> Task {} *
 putLine = \line -> Effect.map (Effect.putLine line) (\_ -> Ok {})
 
@@ -0,0 +1,30 @@
+app "effect-interpreters"
+    packages { pf: "../benchmarks/platform/main.roc" }
+    imports [pf.Task.{ Task }]
+    provides [main] to pf
+
+main : Task {} []
+main =
+    n <- Task.after Task.getInt
+
+    Task.putInt (double n)
+
+double : I64 -> I64
+double = \n -> 2 * n
+
+# `roc test` answers the effects of the platform with these interpreters instead of the host
+
+interpretGetInt : { value : I64, isError : Bool }
+interpretGetInt = { value: 21, isError: Bool.false }
+
+interpretPutInt : I64 -> {}
+interpretPutInt = \_ -> {}
+
+expect Task.run Task.getInt == Ok 21
+
+expect
+    doubled = Task.run (Task.map Task.getInt double)
+
+    doubled == Ok 42
+
+expect Task.run main == Ok {}
@@ -2,6 +2,7 @@
 use snafu::ensure;
 
 use crate::markup_error::{CaretNotFoundSnafu, MarkResult};
+use crate::underline_style::UnderlineStyle;
 
 #[derive(Debug, Copy, Clone)]
 pub struct Caret {
@@ -55,7 +56,10 @@ pub enum Attribute {
     HighlightStart { highlight_start: HighlightStart },
     HighlightEnd { highlight_end: HighlightEnd },
 
-    Underline { underline_spec: UnderlineSpec },
+    Underline {
+        underline_spec: UnderlineSpec,
+        underline_style: UnderlineStyle,
+    },
 }
 
 #[derive(Debug)]
@@ -104,6 +108,17 @@ impl Attributes {
         carets
     }
 
+    pub fn add_underline(&mut self, underline_style: UnderlineStyle) {
+        self.all.push(Attribute::Underline {
+            underline_spec: UnderlineSpec::Full,
+            underline_style,
+        });
+    }
+
+    pub fn delete_underlines(&mut self) {
+        self.all.retain(|attr| !matches!(attr, Attribute::Underline { .. }));
+    }
+
     pub fn delete_caret(&mut self, offset_col: usize, node_id: usize) -> MarkResult<()> {
         let old_len = self.all.len();
 
@@ -41,6 +41,10 @@ pub fn new_colon_mn() -> MarkupNode {
     new_operator_mn(nodes::COLON.to_owned())
 }
 
+pub fn new_type_colon_mn() -> MarkupNode {
+    new_operator_mn(nodes::TYPE_COLON.to_owned())
+}
+
 pub fn new_operator_mn(content: String) -> MarkupNode {
     common_text_node(content, HighlightStyle::Operator, 0)
 }
@@ -74,11 +78,14 @@ pub fn new_arg_name_mn(content: String) -> MarkupNode {
 }
 
 pub fn new_arrow_mn(newlines_at_end: usize) -> MarkupNode {
-    common_text_node(
-        nodes::ARROW.to_owned(),
-        HighlightStyle::Operator,
-        newlines_at_end,
-    )
+    // no trailing space if the arrow ends the line
+    let arrow = if newlines_at_end > 0 {
+        nodes::ARROW.trim_end()
+    } else {
+        nodes::ARROW
+    };
+
+    common_text_node(arrow.to_owned(), HighlightStyle::Operator, newlines_at_end)
 }
 
 pub fn new_comments_mn(comment: String, newlines_at_end: usize) -> MarkupNode {
@@ -130,7 +137,7 @@ pub fn new_module_var_mn(
 }
 
 pub fn if_mn() -> MarkupNode {
-    keyword_mn("if ")
+    keyword_mn(nodes::IF)
 }
 
 pub fn then_mn() -> MarkupNode {
@@ -141,6 +148,19 @@ pub fn else_mn() -> MarkupNode {
     keyword_mn(" else ")
 }
 
+pub fn else_if_mn() -> MarkupNode {
+    keyword_mn(" else if ")
+}
+
+pub fn is_mn() -> MarkupNode {
+    common_text_node(" is".to_owned(), HighlightStyle::Keyword, 1)
+}
+
+// the `if` of a guard in a `when` branch
+pub fn guard_if_mn() -> MarkupNode {
+    keyword_mn(" if ")
+}
+
 fn keyword_mn(keyword: &str) -> MarkupNode {
     common_text_node(keyword.to_owned(), HighlightStyle::Keyword, 0)
 }
@@ -3,7 +3,10 @@ use crate::{
         common_nodes::new_blank_mn_w_nls,
         mark_id_ast_id_map::MarkIdAstIdMap,
         nodes::MarkupNode,
-        top_level_def::{assignment_mark_node, tld_w_comments_mark_node},
+        top_level_def::{
+            assignment_mark_node, expect_mark_node, tld_w_comments_mark_node,
+            type_annotation_mark_node,
+        },
     },
     slow_pool::{MarkNodeId, SlowPool},
 };
@@ -71,6 +74,27 @@ pub fn def2_to_markup(
 
             add_node(tld_mn, ast_node_id, mark_node_pool, mark_id_ast_id_map)
         }
+        Def2::TypeAnnotation {
+            identifier_id,
+            type_str,
+        } => {
+            let annotation_mn = type_annotation_mark_node(
+                *identifier_id,
+                type_str,
+                ast_node_id,
+                mark_node_pool,
+                mark_id_ast_id_map,
+                env,
+            )?;
+
+            add_node(annotation_mn, ast_node_id, mark_node_pool, mark_id_ast_id_map)
+        }
+        Def2::Expect { condition } => {
+            let expect_mn =
+                expect_mark_node(condition, ast_node_id, mark_node_pool, mark_id_ast_id_map);
+
+            add_node(expect_mn, ast_node_id, mark_node_pool, mark_id_ast_id_map)
+        }
         Def2::Blank => add_node(
             new_blank_mn_w_nls(2),
             ast_node_id,
@@ -2,13 +2,15 @@ use crate::{
     markup::{
         attribute::Attributes,
         common_nodes::{
-            new_arg_name_mn, new_arrow_mn, new_blank_mn, new_colon_mn, new_comma_mn, new_equals_mn,
-            new_left_accolade_mn, new_left_square_mn, new_operator_mn, new_right_accolade_mn,
-            new_right_square_mn,
+            else_if_mn, else_mn, guard_if_mn, is_mn, new_arg_name_mn, new_arrow_mn, new_blank_mn,
+            new_colon_mn, new_comma_mn, new_dot_mn, new_equals_mn, new_left_accolade_mn,
+            new_left_square_mn, new_operator_mn, new_right_accolade_mn, new_right_square_mn,
+            then_mn,
         },
         mark_id_ast_id_map::MarkIdAstIdMap,
         nodes::{
-            get_string, join_mark_nodes_commas, join_mark_nodes_spaces, new_markup_node, MarkupNode,
+            get_string, join_mark_nodes_commas, join_mark_nodes_spaces, make_nested_mn,
+            new_markup_node, MarkupNode, IF, WHEN,
         },
     },
     slow_pool::{MarkNodeId, SlowPool},
@@ -24,15 +26,18 @@ use roc_ast::{
                 expr2::{Expr2, ExprId},
                 record_field::RecordField,
             },
-            pattern::{get_identifier_string, Pattern2},
+            pattern::get_identifier_string,
             val_def::ValueDef,
         },
         env::Env,
     },
 };
-use roc_module::{module_err::ModuleResult, symbol::Interns};
+use roc_module::symbol::Interns;
 
-use super::from_def2::add_node;
+use super::{
+    from_def2::add_node,
+    from_pattern2::{pattern2_to_markup, pattern2_to_string},
+};
 
 // make Markup Nodes: generate String representation, assign Highlighting Style
 pub fn expr2_to_markup(
@@ -153,6 +158,44 @@ pub fn expr2_to_markup(
                 indent_level,
             )
         }
+        Expr2::InvalidLookup(pool_str) => new_markup_node(
+            pool_str.as_str(env.pool).to_owned(),
+            ast_node_id,
+            HighlightStyle::Value,
+            mark_node_pool,
+            mark_id_ast_id_map,
+            indent_level,
+        ),
+        Expr2::Access { field, expr, .. } => {
+            let record_mn_id = expr2_to_markup(
+                env,
+                env.pool.get(*expr),
+                *expr,
+                mark_node_pool,
+                mark_id_ast_id_map,
+                interns,
+                indent_level,
+            )?;
+
+            let dot_mn_id = add_node(new_dot_mn(), ast_node_id, mark_node_pool, mark_id_ast_id_map);
+
+            let field_mn_id = new_markup_node(
+                field.as_str(env.pool).to_owned(),
+                ast_node_id,
+                HighlightStyle::RecordField,
+                mark_node_pool,
+                mark_id_ast_id_map,
+                0,
+            );
+
+            let access_mn = MarkupNode::Nested {
+                children_ids: vec![record_mn_id, dot_mn_id, field_mn_id],
+                parent_id_opt: None,
+                newlines_at_end: 0,
+            };
+
+            add_node(access_mn, ast_node_id, mark_node_pool, mark_id_ast_id_map)
+        }
         Expr2::List { elems, .. } => {
             let mut children_ids = vec![add_node(
                 new_left_square_mn(),
@@ -384,34 +427,12 @@ pub fn expr2_to_markup(
                 mark_id_ast_id_map,
             );
 
-            let arg_names: Vec<&str> = args
+            let arg_names: Vec<String> = args
                 .iter(env.pool)
-                .map(|(_, arg_node_id)| {
-                    let arg_pattern2 = env.pool.get(*arg_node_id);
-
-                    match arg_pattern2 {
-                        Pattern2::Identifier(id_symbol) => {
-                            let ident_id = id_symbol.ident_id();
-
-                            env.ident_ids.get_name_str_res(ident_id)
-                        }
-                        Pattern2::Shadowed { shadowed_ident } => {
-                            Ok(shadowed_ident.as_str(env.pool))
-                        }
-                        other => {
-                            todo!(
-                                "TODO: support the following pattern2 as function arg: {:?}",
-                                other
-                            );
-                        }
-                    }
-                })
-                .collect::<ModuleResult<Vec<&str>>>()?;
+                .map(|(_, arg_node_id)| pattern2_to_string(env, env.pool.get(*arg_node_id)))
+                .collect::<ASTResult<Vec<String>>>()?;
 
-            let arg_mark_nodes: Vec<_> = arg_names
-                .iter()
-                .map(|arg_name| new_arg_name_mn(arg_name.to_string()))
-                .collect();
+            let arg_mark_nodes: Vec<_> = arg_names.into_iter().map(new_arg_name_mn).collect();
 
             let args_with_commas: Vec<MarkupNode> = join_mark_nodes_commas(arg_mark_nodes);
 
@@ -455,6 +476,184 @@ pub fn expr2_to_markup(
 
             add_node(function_mn, ast_node_id, mark_node_pool, mark_id_ast_id_map)
         }
+        Expr2::If {
+            branches,
+            final_else,
+            ..
+        } => {
+            let mut children_ids = Vec::new();
+
+            // `else if` chains are flattened into the branches
+            for (idx, (cond_id, then_id)) in branches.iter(env.pool).enumerate() {
+                let if_keyword_mn_id = if idx == 0 {
+                    new_markup_node(
+                        IF.to_owned(),
+                        ast_node_id,
+                        HighlightStyle::Keyword,
+                        mark_node_pool,
+                        mark_id_ast_id_map,
+                        indent_level,
+                    )
+                } else {
+                    add_node(else_if_mn(), ast_node_id, mark_node_pool, mark_id_ast_id_map)
+                };
+                children_ids.push(if_keyword_mn_id);
+
+                children_ids.push(expr2_to_markup(
+                    env,
+                    env.pool.get(*cond_id),
+                    *cond_id,
+                    mark_node_pool,
+                    mark_id_ast_id_map,
+                    interns,
+                    0,
+                )?);
+
+                children_ids.push(add_node(
+                    then_mn(),
+                    ast_node_id,
+                    mark_node_pool,
+                    mark_id_ast_id_map,
+                ));
+
+                children_ids.push(expr2_to_markup(
+                    env,
+                    env.pool.get(*then_id),
+                    *then_id,
+                    mark_node_pool,
+                    mark_id_ast_id_map,
+                    interns,
+                    0,
+                )?);
+            }
+
+            children_ids.push(add_node(
+                else_mn(),
+                ast_node_id,
+                mark_node_pool,
+                mark_id_ast_id_map,
+            ));
+
+            children_ids.push(expr2_to_markup(
+                env,
+                env.pool.get(*final_else),
+                *final_else,
+                mark_node_pool,
+                mark_id_ast_id_map,
+                interns,
+                0,
+            )?);
+
+            let if_expr_mn = MarkupNode::Nested {
+                children_ids,
+                parent_id_opt: None,
+                newlines_at_end: 0,
+            };
+
+            add_node(if_expr_mn, ast_node_id, mark_node_pool, mark_id_ast_id_map)
+        }
+        Expr2::When { branches, cond, .. } => {
+            let when_mn_id = new_markup_node(
+                WHEN.to_owned(),
+                ast_node_id,
+                HighlightStyle::Keyword,
+                mark_node_pool,
+                mark_id_ast_id_map,
+                indent_level,
+            );
+
+            let cond_mn_id = expr2_to_markup(
+                env,
+                env.pool.get(*cond),
+                *cond,
+                mark_node_pool,
+                mark_id_ast_id_map,
+                interns,
+                0,
+            )?;
+
+            let is_mn_id = add_node(is_mn(), ast_node_id, mark_node_pool, mark_id_ast_id_map);
+
+            let mut children_ids = vec![when_mn_id, cond_mn_id, is_mn_id];
+
+            for (idx, branch) in branches.iter(env.pool).enumerate() {
+                let mut branch_children_ids = Vec::new();
+
+                for (pattern_idx, pattern2) in branch.patterns.iter(env.pool).enumerate() {
+                    if pattern_idx > 0 {
+                        branch_children_ids.push(add_node(
+                            new_operator_mn(" | ".to_owned()),
+                            ast_node_id,
+                            mark_node_pool,
+                            mark_id_ast_id_map,
+                        ));
+                    }
+
+                    branch_children_ids.push(pattern2_to_markup(
+                        env,
+                        pattern2,
+                        ast_node_id,
+                        mark_node_pool,
+                        mark_id_ast_id_map,
+                        if pattern_idx == 0 { indent_level + 1 } else { 0 },
+                    )?);
+                }
+
+                if let Some(guard_id) = branch.guard {
+                    branch_children_ids.push(add_node(
+                        guard_if_mn(),
+                        ast_node_id,
+                        mark_node_pool,
+                        mark_id_ast_id_map,
+                    ));
+
+                    branch_children_ids.push(expr2_to_markup(
+                        env,
+                        env.pool.get(guard_id),
+                        guard_id,
+                        mark_node_pool,
+                        mark_id_ast_id_map,
+                        interns,
+                        0,
+                    )?);
+                }
+
+                branch_children_ids.push(add_node(
+                    new_arrow_mn(0),
+                    ast_node_id,
+                    mark_node_pool,
+                    mark_id_ast_id_map,
+                ));
+
+                branch_children_ids.push(expr2_to_markup(
+                    env,
+                    env.pool.get(branch.body),
+                    branch.body,
+                    mark_node_pool,
+                    mark_id_ast_id_map,
+                    interns,
+                    0,
+                )?);
+
+                // the last branch gets its newlines from the enclosing definition
+                let newlines_at_end = if idx + 1 < branches.len() { 1 } else { 0 };
+
+                children_ids.push(add_node(
+                    make_nested_mn(branch_children_ids, newlines_at_end),
+                    ast_node_id,
+                    mark_node_pool,
+                    mark_id_ast_id_map,
+                ));
+            }
+
+            let when_expr_mn = MarkupNode::Nested {
+                children_ids,
+                parent_id_opt: None,
+                newlines_at_end: 0,
+            };
+
+            add_node(when_expr_mn, ast_node_id, mark_node_pool, mark_id_ast_id_map)
+        }
         Expr2::RuntimeError() => new_markup_node(
             "RunTimeError".to_string(),
             ast_node_id,
@@ -0,0 +1,143 @@
+use roc_ast::{
+    ast_error::{ASTResult, SyntaxErrorNoBacktraceSnafu, UnexpectedPattern2VariantSnafu},
+    lang::{
+        core::{
+            ast::ASTNodeId,
+            expr::expr2::{FloatVal, IntVal},
+            pattern::{DestructType, Pattern2},
+        },
+        env::Env,
+    },
+};
+
+use crate::{
+    markup::{mark_id_ast_id_map::MarkIdAstIdMap, nodes::new_markup_node},
+    slow_pool::{MarkNodeId, SlowPool},
+    syntax_highlight::HighlightStyle,
+};
+
+// Patterns are not edited yet, so the whole pattern is one MarkupNode.
+pub fn pattern2_to_markup(
+    env: &Env<'_>,
+    pattern2: &Pattern2,
+    ast_node_id: ASTNodeId,
+    mark_node_pool: &mut SlowPool,
+    mark_id_ast_id_map: &mut MarkIdAstIdMap,
+    indent_level: usize,
+) -> ASTResult<MarkNodeId> {
+    let highlight_style = match pattern2 {
+        Pattern2::NumLiteral(..) | Pattern2::IntLiteral(_) | Pattern2::FloatLiteral(_) => {
+            HighlightStyle::Number
+        }
+        Pattern2::StrLiteral(_) | Pattern2::CharacterLiteral(_) => HighlightStyle::String,
+        Pattern2::Tag { .. } => HighlightStyle::Type,
+        _ => HighlightStyle::Value,
+    };
+
+    let content = pattern2_to_string(env, pattern2)?;
+
+    Ok(new_markup_node(
+        content,
+        ast_node_id,
+        highlight_style,
+        mark_node_pool,
+        mark_id_ast_id_map,
+        indent_level,
+    ))
+}
+
+pub fn pattern2_to_string(env: &Env<'_>, pattern2: &Pattern2) -> ASTResult<String> {
+    let pattern_str = match pattern2 {
+        Pattern2::Identifier(symbol) => env
+            .ident_ids
+            .get_name_str_res(symbol.ident_id())?
+            .to_owned(),
+        Pattern2::NumLiteral(_, num) => num.to_string(),
+        Pattern2::IntLiteral(int_val) => int_val_to_string(int_val),
+        Pattern2::FloatLiteral(FloatVal::F64(float)) => float.to_string(),
+        Pattern2::FloatLiteral(FloatVal::F32(float)) => float.to_string(),
+        Pattern2::StrLiteral(pool_str) => format!("\"{}\"", pool_str.as_str(env.pool)),
+        Pattern2::CharacterLiteral(ch) => format!("'{}'", ch),
+        Pattern2::Underscore => "_".to_owned(),
+        Pattern2::Tag {
+            tag_name,
+            arguments,
+            ..
+        } => {
+            let mut tag_str = tag_name.as_str(env.pool).to_owned();
+
+            for (_, arg_id) in arguments.iter(env.pool) {
+                let arg_pattern = env.pool.get(*arg_id);
+                let arg_str = pattern2_to_string(env, arg_pattern)?;
+
+                // arguments that are tags with arguments of their own need parens
+                let needs_parens = matches!(
+                    arg_pattern,
+                    Pattern2::Tag { arguments, .. } if !arguments.is_empty()
+                );
+
+                if needs_parens {
+                    tag_str.push_str(&format!(" ({})", arg_str));
+                } else {
+                    tag_str.push(' ');
+                    tag_str.push_str(&arg_str);
+                }
+            }
+
+            tag_str
+        }
+        Pattern2::RecordDestructure { destructs, .. } => {
+            let field_strs = destructs
+                .iter(env.pool)
+                .map(|destruct| {
+                    let label = destruct.label.as_str(env.pool);
+
+                    match env.pool.get(destruct.typ) {
+                        DestructType::Required => Ok(label.to_owned()),
+                        DestructType::Guard(_, pattern_id) => {
+                            let guard_str = pattern2_to_string(env, env.pool.get(*pattern_id))?;
+
+                            Ok(format!("{}: {}", label, guard_str))
+                        }
+                        DestructType::Optional(..) => SyntaxErrorNoBacktraceSnafu {
+                            msg: format!(
+                                "I can't show the optional record field `{}` in the editor yet.",
+                                label
+                            ),
+                        }
+                        .fail(),
+                    }
+                })
+                .collect::<ASTResult<Vec<String>>>()?;
+
+            if field_strs.is_empty() {
+                "{}".to_owned()
+            } else {
+                format!("{{ {} }}", field_strs.join(", "))
+            }
+        }
+        Pattern2::Shadowed { shadowed_ident } => shadowed_ident.as_str(env.pool).to_owned(),
+        other => {
+            return UnexpectedPattern2VariantSnafu {
+                required_pattern2: "pattern the editor can show".to_owned(),
+                encountered_pattern2: format!("{:?}", other),
+            }
+            .fail()
+        }
+    };
+
+    Ok(pattern_str)
+}
+
+fn int_val_to_string(int_val: &IntVal) -> String {
+    match int_val {
+        IntVal::I64(int) => int.to_string(),
+        IntVal::U64(int) => int.to_string(),
+        IntVal::I32(int) => int.to_string(),
+        IntVal::U32(int) => int.to_string(),
+        IntVal::I16(int) => int.to_string(),
+        IntVal::U16(int) => int.to_string(),
+        IntVal::I8(int) => int.to_string(),
+        IntVal::U8(int) => int.to_string(),
+    }
+}
@@ -181,6 +181,15 @@ impl MarkupNode {
         }
     }
 
+    // Nested and Indent nodes have no attributes
+    pub fn get_attributes_mut(&mut self) -> Option<&mut Attributes> {
+        match self {
+            MarkupNode::Text { attributes, .. } => Some(attributes),
+            MarkupNode::Blank { attributes, .. } => Some(attributes),
+            MarkupNode::Nested { .. } | MarkupNode::Indent { .. } => None,
+        }
+    }
+
     pub fn is_all_alphanumeric(&self) -> bool {
         self.get_content()
             .chars()
@@ -268,12 +277,16 @@ pub const RIGHT_ACCOLADE: &str = " }";
 pub const LEFT_SQUARE_BR: &str = "[ ";
 pub const RIGHT_SQUARE_BR: &str = " ]";
 pub const COLON: &str = ": ";
+pub const TYPE_COLON: &str = " : ";
 pub const COMMA: &str = ", ";
 pub const DOT: &str = ".";
 pub const STRING_QUOTES: &str = "\"\"";
 pub const EQUALS: &str = " = ";
 pub const ARROW: &str = " -> ";
 pub const SINGLE_INDENT: &str = "    "; // 4 spaces
+pub const IF: &str = "if ";
+pub const WHEN: &str = "when ";
+pub const EXPECT: &str = "expect";
 
 pub fn new_markup_node(
     text: String,
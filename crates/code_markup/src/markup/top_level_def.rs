@@ -7,15 +7,17 @@ use roc_module::symbol::IdentId;
 use crate::{
     markup::{
         attribute::Attributes,
-        common_nodes::{new_comments_mn, new_equals_mn},
-        nodes::MarkupNode,
+        common_nodes::{new_comments_mn, new_equals_mn, new_type_colon_mn},
+        nodes::{make_nested_mn, MarkupNode, EXPECT, SINGLE_INDENT},
     },
     slow_pool::{MarkNodeId, SlowPool},
     syntax_highlight::HighlightStyle,
 };
 
 use super::{
-    common_nodes::new_assign_mn, convert::from_def2::add_node, mark_id_ast_id_map::MarkIdAstIdMap,
+    common_nodes::{new_assign_mn, NEW_LINES_AFTER_DEF},
+    convert::from_def2::add_node,
+    mark_id_ast_id_map::MarkIdAstIdMap,
 };
 
 // represents for example: `main = "Hello, World!"`
@@ -53,6 +55,107 @@ pub fn assignment_mark_node(
     ))
 }
 
+// represents for example: `main : Str`, the value definition follows on the next line
+pub fn type_annotation_mark_node(
+    identifier_id: IdentId,
+    type_str: &str,
+    ast_node_id: ASTNodeId,
+    mark_node_pool: &mut SlowPool,
+    mark_id_ast_id_map: &mut MarkIdAstIdMap,
+    env: &Env<'_>,
+) -> ASTResult<MarkupNode> {
+    let val_name = env.ident_ids.get_name_str_res(identifier_id)?;
+
+    let val_name_mn = MarkupNode::Text {
+        content: val_name.to_owned(),
+        syn_high_style: HighlightStyle::Value,
+        attributes: Attributes::default(),
+        parent_id_opt: None,
+        newlines_at_end: 0,
+    };
+
+    let val_name_mn_id = add_node(val_name_mn, ast_node_id, mark_node_pool, mark_id_ast_id_map);
+
+    let colon_mn_id = add_node(
+        new_type_colon_mn(),
+        ast_node_id,
+        mark_node_pool,
+        mark_id_ast_id_map,
+    );
+
+    let type_mn = MarkupNode::Text {
+        content: type_str.to_owned(),
+        syn_high_style: HighlightStyle::Type,
+        attributes: Attributes::default(),
+        parent_id_opt: None,
+        newlines_at_end: 0,
+    };
+
+    let type_mn_id = add_node(type_mn, ast_node_id, mark_node_pool, mark_id_ast_id_map);
+
+    Ok(make_nested_mn(
+        vec![val_name_mn_id, colon_mn_id, type_mn_id],
+        1,
+    ))
+}
+
+// represents for example: `expect 1 + 1 == 2`, a multiline condition starts on the next line
+pub fn expect_mark_node(
+    condition: &str,
+    ast_node_id: ASTNodeId,
+    mark_node_pool: &mut SlowPool,
+    mark_id_ast_id_map: &mut MarkIdAstIdMap,
+) -> MarkupNode {
+    let condition_lines: Vec<&str> = condition.lines().collect();
+    let is_multiline = condition_lines.len() > 1;
+
+    let expect_mn = MarkupNode::Text {
+        content: if is_multiline {
+            EXPECT.to_owned()
+        } else {
+            format!("{} ", EXPECT)
+        },
+        syn_high_style: HighlightStyle::Keyword,
+        attributes: Attributes::default(),
+        parent_id_opt: None,
+        newlines_at_end: if is_multiline { 1 } else { 0 },
+    };
+
+    let mut children_ids = vec![add_node(
+        expect_mn,
+        ast_node_id,
+        mark_node_pool,
+        mark_id_ast_id_map,
+    )];
+
+    for (idx, line) in condition_lines.iter().enumerate() {
+        let line_mn = MarkupNode::Text {
+            content: if is_multiline {
+                format!("{}{}", SINGLE_INDENT, line)
+            } else {
+                line.to_string()
+            },
+            syn_high_style: HighlightStyle::Value,
+            attributes: Attributes::default(),
+            parent_id_opt: None,
+            newlines_at_end: if idx + 1 < condition_lines.len() {
+                1
+            } else {
+                0
+            },
+        };
+
+        children_ids.push(add_node(
+            line_mn,
+            ast_node_id,
+            mark_node_pool,
+            mark_id_ast_id_map,
+        ));
+    }
+
+    make_nested_mn(children_ids, NEW_LINES_AFTER_DEF)
+}
+
 pub fn tld_w_comments_mark_node(
     comments: String,
     def_mark_node_id: MarkNodeId,
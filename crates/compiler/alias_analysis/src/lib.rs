@@ -21,6 +21,7 @@ use roc_mono::layout::{
     Builtin, InLayout, Layout, LayoutInterner, LayoutRepr, Niche, RawFunctionLayout,
     STLayoutInterner, UnionLayout,
 };
+use roc_mono::static_data::StaticConstants;
 
 // just using one module for now
 pub const MOD_APP: ModName = ModName(b"UserApp");
@@ -143,6 +144,7 @@ pub fn spec_program<'a, 'r, I>(
     opt_level: OptLevel,
     entry_point: roc_mono::ir::EntryPoint<'a>,
     procs: I,
+    static_constants: &StaticConstants<'a>,
 ) -> Result<morphic_lib::Solutions>
 where
     I: Iterator<Item = &'r Proc<'a>>,
@@ -224,7 +226,27 @@ where
                 );
             }
 
-            let (spec, type_names) = proc_spec(arena, interner, proc)?;
+            let proc_layout = ProcLayout {
+                arguments: &[],
+                result: proc.ret_layout,
+                niche: proc.name.niche(),
+            };
+
+            let is_static = proc.args.is_empty()
+                && static_constants.contains_key(&(proc.name.name(), proc_layout));
+
+            let (spec, type_names) = if is_static {
+                // The function name bytes double as the name of the constant
+                let const_name = ConstName(&bytes);
+                let (const_def, const_type_names) = static_constant_def(interner, proc)?;
+                m.add_const(const_name, const_def)?;
+
+                type_definitions.extend(const_type_names);
+
+                static_constant_proc_spec(interner, const_name, proc)?
+            } else {
+                proc_spec(arena, interner, proc)?
+            };
 
             type_definitions.extend(type_names);
 
@@ -470,6 +492,45 @@ fn proc_spec<'a>(
     Ok((spec, env.type_names))
 }
 
+/// Static data is modelled as a constant, so that it is never updated in place.
+/// Its contents don't matter for that, so they are left unknown.
+fn static_constant_def<'a>(
+    interner: &mut STLayoutInterner<'a>,
+    proc: &Proc<'a>,
+) -> Result<(morphic_lib::ConstDef, MutSet<UnionLayout<'a>>)> {
+    let mut builder = ConstDefBuilder::new();
+    let mut env = Env::new();
+
+    let block = builder.add_block();
+    let type_id = layout_spec(&mut env, &mut builder, interner, proc.ret_layout)?;
+    let value_id = builder.add_unknown_with(block, &[], type_id)?;
+
+    let spec = builder.build(type_id, BlockExpr(block, value_id))?;
+
+    Ok((spec, env.type_names))
+}
+
+/// A top-level constant whose value was computed at compile time just returns that value.
+fn static_constant_proc_spec<'a>(
+    interner: &mut STLayoutInterner<'a>,
+    const_name: ConstName,
+    proc: &Proc<'a>,
+) -> Result<(FuncDef, MutSet<UnionLayout<'a>>)> {
+    let mut builder = FuncDefBuilder::new();
+    let mut env = Env::new();
+
+    let block = builder.add_block();
+    let value_id = builder.add_const_ref(block, MOD_APP, const_name)?;
+
+    let unit_layout = interner.insert_no_semantic(LayoutRepr::struct_(&[]));
+    let arg_type_id = layout_spec(&mut env, &mut builder, interner, unit_layout)?;
+    let ret_type_id = layout_spec(&mut env, &mut builder, interner, proc.ret_layout)?;
+
+    let spec = builder.build(arg_type_id, ret_type_id, BlockExpr(block, value_id))?;
+
+    Ok((spec, env.type_names))
+}
+
 struct Env<'a> {
     symbols: MutMap<Symbol, ValueId>,
     join_points: MutMap<roc_mono::ir::JoinPointId, morphic_lib::ContinuationId>,
@@ -493,7 +554,7 @@ fn apply_refcount_operation(
     modify_rc: &ModifyRc,
 ) -> Result<()> {
     match modify_rc {
-        ModifyRc::Inc(symbol, _) => {
+        ModifyRc::Inc(symbol, _) | ModifyRc::IncN(symbol, _) => {
             let argument = env.symbols[symbol];
 
             // a recursive touch is never worse for optimizations than a normal touch
@@ -771,6 +832,9 @@ fn call_spec<'a>(
         Foreign {
             foreign_symbol: _,
             ret_layout,
+            arg_ownership: _,
+            ret_ownership: _,
+            pure: _,
         } => {
             let arguments: Vec<_> = call
                 .arguments
@@ -1168,6 +1232,20 @@ fn lowlevel_spec<'a>(
 
             list_append(builder, block, update_mode_var, list, to_insert)
         }
+        ListRepeat => {
+            // a new list which holds the element; how often is not relevant for morphic
+            let to_insert = env.symbols[&arguments[0]];
+
+            match interner.get(layout).repr {
+                LayoutRepr::Builtin(Builtin::List(element_layout)) => {
+                    let type_id = layout_spec(env, builder, interner, element_layout)?;
+                    let list = new_list(builder, block, type_id)?;
+
+                    list_append(builder, block, update_mode_var, list, to_insert)
+                }
+                _ => unreachable!("List.repeat does not have a list layout"),
+            }
+        }
         StrToUtf8 => {
             let string = env.symbols[&arguments[0]];
 
@@ -1343,7 +1421,7 @@ fn expr_spec<'a>(
 
             builder.add_make_named(block, MOD_APP, type_name, tag_value_id)
         }
-        ExprBox { symbol } => {
+        ExprBox { symbol } | StackBox { symbol } => {
             let value_id = env.symbols[symbol];
 
             with_new_heap_cell(builder, block, value_id)
@@ -1414,7 +1492,7 @@ fn expr_spec<'a>(
             let value_id = env.symbols[structure];
             builder.add_get_tuple_field(block, value_id, *index as u32)
         }
-        Array { elem_layout, elems } => {
+        Array { elem_layout, elems } | StackArray { elem_layout, elems } => {
             let type_id = layout_spec(env, builder, interner, *elem_layout)?;
 
             let list = new_list(builder, block, type_id)?;
@@ -0,0 +1,397 @@
+//! A library API for compiling Roc programs without going through the CLI,
+//! e.g. from build servers or plugin hosts.
+//!
+//! ```ignore
+//! let compiled = Compiler::from_source("main.roc", src, "/path/to/app")
+//!     .opt_level(OptLevel::Optimize)
+//!     .compile()?;
+//!
+//! std::fs::write("app.o", &compiled.object)?;
+//! ```
+//!
+//! Compiling produces the app's object file, which then still needs to be linked with its
+//! platform's host. Problems with the program are returned as [Diagnostic]s, instead of being
+//! printed to the terminal.
+
+use std::path::{Path, PathBuf};
+
+use bumpalo::Bump;
+use roc_collections::MutMap;
+use roc_gen_llvm::llvm::build::LlvmBackendMode;
+use roc_gen_llvm::llvm::refcounting::AtomicRefcounts;
+use roc_load::{LoadMonomorphizedError, LoadingProblem, Threading};
+use roc_module::symbol::{Interns, ModuleId};
+use roc_mono::ir::OptLevel;
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_region::all::{LineColumnRegion, LineInfo};
+use roc_reporting::report::{can_problem, type_problem, CiWrite, Report, RocDocAllocator};
+use roc_solve_problem::TypeError;
+use target_lexicon::Triple;
+
+pub use roc_problem::Severity;
+
+use crate::program::{
+    gen_from_mono_module, standard_load_config, BuildOrdering, CodeGenBackend, CodeGenOptions,
+    ProfileOptions,
+};
+
+/// Where the app's main module comes from
+#[derive(Debug, Clone)]
+pub enum Source {
+    Path(PathBuf),
+    InMemory {
+        /// The name to use for the module in diagnostics
+        filename: PathBuf,
+        src: String,
+        /// Imports (e.g. of the platform) are resolved relative to this directory
+        src_dir: PathBuf,
+    },
+}
+
+/// Compiles a single Roc app. Use the methods to configure it, then call [Compiler::compile].
+#[derive(Debug, Clone)]
+pub struct Compiler {
+    source: Source,
+    target: Triple,
+    opt_level: OptLevel,
+    emit_debug_info: bool,
+    threading: Threading,
+    download_packages: bool,
+}
+
+/// The result of a successful compilation
+#[derive(Debug)]
+pub struct Compiled {
+    /// The app's object file, for the configured target
+    pub object: Vec<u8>,
+    /// Problems which did not stop compilation, e.g. warnings
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// A single problem found in the program
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub title: String,
+    /// The file the problem is in, if any
+    pub filename: Option<PathBuf>,
+    /// Where in that file the problem is, if it's about a specific part of it
+    pub region: Option<LineColumnRegion>,
+    /// The full explanation, rendered without colors
+    pub message: String,
+}
+
+impl Compiler {
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        Self::new(Source::Path(path.into()))
+    }
+
+    pub fn from_source(
+        filename: impl Into<PathBuf>,
+        src: impl Into<String>,
+        src_dir: impl Into<PathBuf>,
+    ) -> Self {
+        Self::new(Source::InMemory {
+            filename: filename.into(),
+            src: src.into(),
+            src_dir: src_dir.into(),
+        })
+    }
+
+    fn new(source: Source) -> Self {
+        Self {
+            source,
+            target: Triple::host(),
+            opt_level: OptLevel::Normal,
+            emit_debug_info: false,
+            threading: Threading::AllAvailable,
+            download_packages: true,
+        }
+    }
+
+    /// Compile for this target instead of the current machine
+    pub fn target(mut self, target: Triple) -> Self {
+        self.target = target;
+        self
+    }
+
+    pub fn opt_level(mut self, opt_level: OptLevel) -> Self {
+        self.opt_level = opt_level;
+        self
+    }
+
+    pub fn emit_debug_info(mut self, emit_debug_info: bool) -> Self {
+        self.emit_debug_info = emit_debug_info;
+        self
+    }
+
+    pub fn threading(mut self, threading: Threading) -> Self {
+        self.threading = threading;
+        self
+    }
+
+    /// Whether packages imported by URL may be downloaded into the user's cache directory.
+    /// When disallowed, only packages which are already cached can be used.
+    pub fn download_packages(mut self, download_packages: bool) -> Self {
+        self.download_packages = download_packages;
+        self
+    }
+
+    /// Compile the app. This only returns an error if there is a problem which stopped
+    /// compilation; a program with only warnings compiles successfully.
+    pub fn compile(self) -> Result<Compiled, Vec<Diagnostic>> {
+        let arena = Bump::new();
+        let cache_dir = cache::roc_cache_dir();
+        let roc_cache_dir = if self.download_packages {
+            RocCacheDir::Persistent(cache_dir.as_path())
+        } else {
+            RocCacheDir::Disallowed
+        };
+
+        let mut load_config =
+            standard_load_config(&self.target, BuildOrdering::BuildIfChecks, self.threading);
+        load_config.render = roc_reporting::report::RenderTarget::Generic;
+        // apps are always built with the LLVM backend, which stores constants as static data
+        load_config.static_data = true;
+
+        let (loaded, path) = match &self.source {
+            Source::Path(path) => (
+                roc_load::load_and_monomorphize(&arena, path.clone(), roc_cache_dir, load_config),
+                path.clone(),
+            ),
+            Source::InMemory {
+                filename,
+                src,
+                src_dir,
+            } => (
+                roc_load::load_and_monomorphize_from_str(
+                    &arena,
+                    filename.clone(),
+                    arena.alloc_str(src),
+                    src_dir.clone(),
+                    roc_cache_dir,
+                    load_config,
+                ),
+                src_dir.join(filename),
+            ),
+        };
+
+        let mut loaded = match loaded {
+            Ok(loaded) => loaded,
+            Err(LoadMonomorphizedError::ErrorModule(mut module)) => {
+                return Err(diagnostics(
+                    &module.sources,
+                    &module.interns,
+                    &mut module.can_problems,
+                    &mut module.type_problems,
+                ));
+            }
+            Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+                return Err(vec![loading_problem_diagnostic(problem)]);
+            }
+        };
+
+        let diagnostics = diagnostics(
+            &loaded.sources,
+            &loaded.interns,
+            &mut loaded.can_problems,
+            &mut loaded.type_problems,
+        );
+
+        let code_gen_options = CodeGenOptions {
+            backend: CodeGenBackend::Llvm(LlvmBackendMode::Binary),
+            opt_level: self.opt_level,
+            emit_debug_info: self.emit_debug_info,
+            profile: ProfileOptions::Off,
+            atomic_refcounts: AtomicRefcounts::Never,
+            arena_allocation: false,
+            schedule_refcounts: false,
+        };
+
+        // The LLVM backend never needs the preprocessed host
+        let (object, _, _) = gen_from_mono_module(
+            &arena,
+            loaded,
+            &path,
+            &self.target,
+            code_gen_options,
+            Path::new(""),
+            None,
+            None,
+        );
+
+        Ok(Compiled {
+            object: object.to_vec(),
+            diagnostics,
+        })
+    }
+}
+
+fn loading_problem_diagnostic(problem: LoadingProblem) -> Diagnostic {
+    let message = match problem {
+        LoadingProblem::FormattedReport(report) => report,
+        other => format!("{:?}", other),
+    };
+
+    Diagnostic {
+        severity: Severity::Fatal,
+        title: "LOADING PROBLEM".to_string(),
+        filename: None,
+        region: None,
+        message,
+    }
+}
+
+fn diagnostics(
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    interns: &Interns,
+    can_problems: &mut MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
+    type_problems: &mut MutMap<ModuleId, Vec<TypeError>>,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for (home, (module_path, src)) in sources.iter() {
+        let src_lines: Vec<&str> = src.split('\n').collect();
+        let lines = LineInfo::new(src);
+        let alloc = RocDocAllocator::new(&src_lines, *home, interns);
+
+        for problem in can_problems.remove(home).unwrap_or_default() {
+            let region = problem.region().map(|region| lines.convert_region(region));
+            let report = can_problem(&alloc, &lines, module_path.clone(), problem);
+
+            diagnostics.push(to_diagnostic(report, region, &alloc));
+        }
+
+        for problem in type_problems.remove(home).unwrap_or_default() {
+            let region = problem.region().map(|region| lines.convert_region(region));
+
+            if let Some(report) = type_problem(&alloc, &lines, module_path.clone(), problem) {
+                diagnostics.push(to_diagnostic(report, region, &alloc));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+fn to_diagnostic<'b>(
+    mut report: Report<'b>,
+    region: Option<LineColumnRegion>,
+    alloc: &'b RocDocAllocator<'b>,
+) -> Diagnostic {
+    let severity = report.severity;
+    let filename = Some(report.filename.clone()).filter(|path| !path.as_os_str().is_empty());
+
+    // Leave out the header; the title and filename are separate fields.
+    let title = std::mem::take(&mut report.title);
+
+    let mut message = String::new();
+    report
+        .pretty(alloc)
+        .1
+        .render_raw(70, &mut CiWrite::new(&mut message))
+        .expect("rendering to a String cannot fail");
+
+    Diagnostic {
+        severity,
+        title,
+        filename,
+        region,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use indoc::indoc;
+    use roc_load::Threading;
+
+    use super::{Compiler, Severity};
+
+    const PLATFORM: &str = indoc!(
+        r#"
+        platform "test"
+            requires {} { main : Str }
+            exposes []
+            packages {}
+            imports []
+            provides [mainForHost]
+
+        mainForHost : Str
+        mainForHost = main
+        "#
+    );
+
+    fn app_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+
+        fs::create_dir(dir.path().join("platform")).unwrap();
+        fs::write(dir.path().join("platform").join("main.roc"), PLATFORM).unwrap();
+
+        dir
+    }
+
+    fn compiler(app_dir: &tempfile::TempDir, main: &str) -> Compiler {
+        let src = format!(
+            indoc!(
+                r#"
+                app "test"
+                    packages {{ pf: "platform/main.roc" }}
+                    imports []
+                    provides [main] to pf
+
+                {}
+                "#
+            ),
+            main
+        );
+
+        Compiler::from_source("main.roc", src, app_dir.path())
+            .threading(Threading::Single)
+            .download_packages(false)
+    }
+
+    #[test]
+    fn compile_tiny_app() {
+        let app_dir = app_dir();
+        let compiled = compiler(&app_dir, "main = \"Hello\"").compile().unwrap();
+
+        assert!(compiled.diagnostics.is_empty());
+
+        let exposed = b"roc__mainForHost_1_exposed_generic";
+        assert!(compiled
+            .object
+            .windows(exposed.len())
+            .any(|window| window == exposed));
+
+        // The object is returned rather than written next to the app, like `roc build` would
+        let written: Vec<_> = fs::read_dir(app_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        assert_eq!(written, ["platform"]);
+    }
+
+    #[test]
+    fn report_type_errors() {
+        let app_dir = app_dir();
+        let diagnostics = compiler(&app_dir, "main : Str\nmain = 42")
+            .compile()
+            .unwrap_err();
+
+        assert_eq!(diagnostics.len(), 1, "{:#?}", diagnostics);
+
+        let diagnostic = &diagnostics[0];
+
+        assert_eq!(diagnostic.severity, Severity::RuntimeError);
+        assert_eq!(diagnostic.title, "TYPE MISMATCH");
+        assert!(diagnostic.filename.as_ref().unwrap().ends_with("main.roc"));
+        // `main = 42` is on the 7th line, and the region is 0-based
+        assert_eq!(diagnostic.region.unwrap().start.line, 6);
+        assert!(diagnostic.message.contains("main = 42"));
+        // rendered without colors
+        assert!(!diagnostic.message.contains('\u{1b}'));
+    }
+}
@@ -0,0 +1,289 @@
+//! Every LLVM build starts from the builtins bitcode, which needs some preparation before the app
+//! can be added to it: the builtins are made internal, and the small ones are marked to always be
+//! or preferably be inlined. The app and the builtins end up in one LLVM module, so this is all
+//! optimized builds need to inline builtins into app code; no link-time optimization is
+//! involved. The prepared builtins are cached in the roc cache dir, keyed by the compiler version,
+//! the target, and a hash of the bitcode itself, so later builds can load them as they are.
+use inkwell::attributes::{Attribute, AttributeLoc};
+use inkwell::context::Context;
+use inkwell::memory_buffer::MemoryBuffer;
+use inkwell::module::{Linkage, Module};
+use inkwell::values::FunctionValue;
+use roc_gen_llvm::llvm::build::{builtins_bitcode, module_from_builtins, parse_builtins};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use target_lexicon::Triple;
+
+const BUILTINS_DIR_NAME: &str = "builtins";
+const BUILTINS_FILENAME: &str = "builtins.bc";
+
+/// Bump this whenever [prepare_builtins] changes, so builtins prepared by an older compiler
+/// built from source are not used.
+const PREPARATION_VERSION: u32 = 1;
+
+/// Builtins with at most this many instructions get an `inlinehint`, which makes LLVM's inliner
+/// much more willing to inline them in optimized builds.
+const SMALL_BUILTIN_INSTRUCTIONS: usize = 40;
+
+/// The builtins module for `target`, ready for the app's code to be added to it. When
+/// `roc_cache_dir` is given, the prepared builtins are loaded from it, or stored in it if they
+/// weren't there yet (or can't be loaded).
+pub fn load_builtins<'ctx>(
+    ctx: &'ctx Context,
+    target: &Triple,
+    module_name: &str,
+    roc_cache_dir: Option<&Path>,
+) -> Module<'ctx> {
+    if let Some(cache_dir) = roc_cache_dir {
+        let cached_path = cached_builtins_path(cache_dir, target);
+
+        let cached = load_cached_builtins(ctx, &cached_path).or_else(|| {
+            // If they can't be stored, they're prepared below as if there were no cache.
+            store_builtins(target, &cached_path).ok()?;
+
+            load_cached_builtins(ctx, &cached_path)
+        });
+
+        if let Some(module) = cached {
+            module.set_name(module_name);
+
+            return module;
+        }
+    }
+
+    let module = module_from_builtins(target, ctx, module_name);
+
+    prepare_builtins(ctx, &module);
+
+    module
+}
+
+/// Where the prepared builtins for this target are cached. The file only exists if they were
+/// prepared before.
+pub fn cached_builtins_path(roc_cache_dir: &Path, target: &Triple) -> PathBuf {
+    cached_builtins_path_help(roc_cache_dir, target, PREPARATION_VERSION)
+}
+
+fn cached_builtins_path_help(
+    roc_cache_dir: &Path,
+    target: &Triple,
+    preparation_version: u32,
+) -> PathBuf {
+    let mut hasher = blake3::Hasher::new();
+
+    hasher.update(target.to_string().as_bytes());
+    hasher.update(include_str!("../../../../version.txt").as_bytes());
+    hasher.update(&preparation_version.to_le_bytes());
+    // A compiler built from source has the same version before and after the builtins change
+    hasher.update(builtins_bitcode(target));
+
+    let hash = hasher.finalize().to_hex().to_string();
+
+    roc_cache_dir
+        .join(BUILTINS_DIR_NAME)
+        .join(hash)
+        .join(BUILTINS_FILENAME)
+}
+
+fn load_cached_builtins<'ctx>(ctx: &'ctx Context, cached_path: &Path) -> Option<Module<'ctx>> {
+    let memory_buffer = MemoryBuffer::create_from_file(cached_path).ok()?;
+
+    parse_builtins(ctx, &memory_buffer).ok()
+}
+
+/// Prepare the builtins in a context of their own, and write them to `cached_path`. They are
+/// written under a temporary name first, so concurrent builds never see partially written
+/// builtins.
+fn store_builtins(target: &Triple, cached_path: &Path) -> io::Result<()> {
+    let dir = cached_path.parent().unwrap();
+
+    fs::create_dir_all(dir)?;
+
+    let ctx = Context::create();
+    let memory_buffer =
+        MemoryBuffer::create_from_memory_range(builtins_bitcode(target), "builtins");
+
+    // The intrinsics are added again whenever the builtins are loaded, so store the module
+    // without them.
+    let module = Module::parse_bitcode_from_buffer(&memory_buffer, &ctx)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+
+    prepare_builtins(&ctx, &module);
+
+    let temp = tempfile::NamedTempFile::new_in(dir)?;
+
+    if !module.write_bitcode_to_path(temp.path()) {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            "LLVM could not write the builtins bitcode",
+        ));
+    }
+
+    temp.persist(cached_path).map_err(|err| err.error)?;
+
+    Ok(())
+}
+
+fn prepare_builtins<'ctx>(ctx: &'ctx Context, module: &Module<'ctx>) {
+    let kind_id = Attribute::get_named_enum_kind_id("alwaysinline");
+    debug_assert!(kind_id > 0);
+    let enum_attr = ctx.create_enum_attribute(kind_id, 1);
+
+    let hint_kind_id = Attribute::get_named_enum_kind_id("inlinehint");
+    debug_assert!(hint_kind_id > 0);
+    let hint_attr = ctx.create_enum_attribute(hint_kind_id, 0);
+
+    for function in module.get_functions() {
+        let name = function.get_name().to_str().unwrap();
+
+        // mark our zig-defined builtins as internal
+        if name.starts_with("roc_builtins") {
+            function.set_linkage(Linkage::Internal);
+        }
+
+        if name.starts_with("roc_builtins.dict")
+            || name.starts_with("roc_builtins.list")
+            || name.starts_with("roc_builtins.dec")
+            || name.starts_with("list.RocList")
+            || name.starts_with("dict.RocDict")
+            || name.contains("incref")
+            || name.contains("decref")
+        {
+            function.add_attribute(AttributeLoc::Function, enum_attr);
+        } else if name.starts_with("roc_builtins")
+            && function.count_basic_blocks() > 0
+            && instruction_count(function) <= SMALL_BUILTIN_INSTRUCTIONS
+        {
+            // e.g. number comparisons and conversions, which are cheaper to inline than to call
+            function.add_attribute(AttributeLoc::Function, hint_attr);
+        }
+    }
+}
+
+fn instruction_count(function: FunctionValue) -> usize {
+    let mut count = 0;
+
+    for block in function.get_basic_blocks() {
+        let mut instruction = block.get_first_instruction();
+
+        while let Some(current) = instruction {
+            count += 1;
+            instruction = current.get_next_instruction();
+        }
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod test {
+    use inkwell::attributes::{Attribute, AttributeLoc};
+    use inkwell::context::Context;
+    use std::fs;
+    use std::path::Path;
+    use target_lexicon::Triple;
+
+    use super::{
+        cached_builtins_path, cached_builtins_path_help, load_builtins, load_cached_builtins,
+        store_builtins, PREPARATION_VERSION,
+    };
+
+    /// Stands in for the cached builtins, so tests can tell whether they were loaded from the
+    /// cache
+    fn write_marker_module(path: &Path) {
+        let ctx = Context::create();
+        let module = ctx.create_module("marker");
+
+        module.add_function("cache_marker", ctx.void_type().fn_type(&[], false), None);
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        assert!(module.write_bitcode_to_path(path));
+    }
+
+    #[test]
+    fn cached_builtins_keep_their_inline_attributes() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let target = Triple::host();
+        let cached_path = cached_builtins_path(cache_dir.path(), &target);
+
+        store_builtins(&target, &cached_path).unwrap();
+
+        let ctx = Context::create();
+        let module = load_cached_builtins(&ctx, &cached_path).unwrap();
+
+        let has_attribute = |function: &str, attribute: &str| {
+            let kind_id = Attribute::get_named_enum_kind_id(attribute);
+
+            module
+                .get_function(function)
+                .unwrap_or_else(|| panic!("the builtins have no {function}"))
+                .get_enum_attribute(AttributeLoc::Function, kind_id)
+                .is_some()
+        };
+
+        // a handful of instructions, so it only gets a hint
+        assert!(has_attribute("roc_builtins.num.is_nan.f64", "inlinehint"));
+        assert!(!has_attribute(
+            "roc_builtins.num.is_nan.f64",
+            "alwaysinline"
+        ));
+
+        assert!(has_attribute("roc_builtins.list.map", "alwaysinline"));
+        assert!(!has_attribute("roc_builtins.list.map", "inlinehint"));
+    }
+
+    #[test]
+    fn cached_builtins_are_reused() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let target = Triple::host();
+        let cached_path = cached_builtins_path(cache_dir.path(), &target);
+
+        let ctx = Context::create();
+        load_builtins(&ctx, &target, "app", Some(cache_dir.path()));
+
+        assert!(cached_path.exists());
+
+        write_marker_module(&cached_path);
+
+        let module = load_builtins(&ctx, &target, "app", Some(cache_dir.path()));
+
+        assert!(module.get_function("cache_marker").is_some());
+        assert_eq!(module.get_name().to_str(), Ok("app"));
+    }
+
+    #[test]
+    fn preparation_version_bump_invalidates() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let target = Triple::host();
+
+        // builtins prepared before the last change to `prepare_builtins`
+        write_marker_module(&cached_builtins_path_help(
+            cache_dir.path(),
+            &target,
+            PREPARATION_VERSION - 1,
+        ));
+
+        let ctx = Context::create();
+        let module = load_builtins(&ctx, &target, "app", Some(cache_dir.path()));
+
+        assert!(module.get_function("cache_marker").is_none());
+        assert!(module.get_function("roc_builtins.list.map").is_some());
+    }
+
+    #[test]
+    fn corrupt_cached_builtins_are_replaced() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let target = Triple::host();
+        let cached_path = cached_builtins_path(cache_dir.path(), &target);
+
+        fs::create_dir_all(cached_path.parent().unwrap()).unwrap();
+        fs::write(&cached_path, b"not bitcode").unwrap();
+
+        let ctx = Context::create();
+        let module = load_builtins(&ctx, &target, "app", Some(cache_dir.path()));
+
+        assert!(module.get_function("roc_builtins.list.map").is_some());
+        assert!(load_cached_builtins(&ctx, &cached_path).is_some());
+    }
+}
@@ -0,0 +1,216 @@
+//! Without the surgical linker, every build rebuilds the host, which often takes longer than
+//! compiling the app. Hosts built for the legacy linker are cached in the roc cache dir, keyed
+//! by a hash of the platform's source files, the target, and the optimization level, so the host
+//! is only rebuilt when one of those changes.
+use crate::link::legacy_host_filename;
+use roc_mono::ir::OptLevel;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use target_lexicon::Triple;
+
+const HOSTS_DIR_NAME: &str = "hosts";
+
+/// Directories which hold build artifacts, rather than the host's source
+const IGNORED_DIRS: &[&str] = &["target", "zig-cache", "zig-out", ".git"];
+
+/// Extensions of the files that building the host (and the app) writes into the platform's
+/// directory. Roc source doesn't affect how the host is built, so it's ignored too.
+const IGNORED_EXTENSIONS: &[&str] = &[
+    "roc", "o", "obj", "a", "lib", "bc", "rh", "rm", "so", "dylib", "dll", "exe", "wasm",
+];
+
+fn is_ignored(path: &Path) -> bool {
+    let file_name = path.file_name().and_then(|name| name.to_str());
+    let extension = path.extension().and_then(|ext| ext.to_str());
+
+    match (file_name, extension) {
+        (Some(name), _) if name.starts_with("dynhost") => true,
+        (Some(name), _) if path.is_dir() => IGNORED_DIRS.contains(&name),
+        (_, Some(extension)) => IGNORED_EXTENSIONS.contains(&extension),
+        _ => false,
+    }
+}
+
+fn hash_dir(hasher: &mut blake3::Hasher, root: &Path, dir: &Path) -> io::Result<()> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    // read_dir's order is platform-dependent, and the hash must not be
+    paths.sort();
+
+    for path in paths {
+        if is_ignored(&path) {
+            continue;
+        }
+
+        if path.is_dir() {
+            hash_dir(hasher, root, &path)?;
+        } else {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+
+            hasher.update(relative.to_string_lossy().as_bytes());
+            hasher.update(&[0]);
+            hasher.update(&fs::read(&path)?);
+            hasher.update(&[0]);
+        }
+    }
+
+    Ok(())
+}
+
+/// A hash of everything that goes into building the host of the platform at `platform_main_roc`
+pub fn platform_hash(
+    platform_main_roc: &Path,
+    target: &Triple,
+    opt_level: OptLevel,
+) -> io::Result<String> {
+    let platform_dir = platform_main_roc.parent().unwrap_or_else(|| Path::new("."));
+
+    let mut hasher = blake3::Hasher::new();
+
+    hasher.update(target.to_string().as_bytes());
+    hasher.update(format!("{:?}", opt_level).as_bytes());
+    hasher.update(include_str!("../../../../version.txt").as_bytes());
+
+    hash_dir(&mut hasher, platform_dir, platform_dir)?;
+
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+/// Where the legacy host for this platform, target, and optimization level is cached. The file
+/// only exists if the host has been built before.
+pub fn cached_host_path(
+    roc_cache_dir: &Path,
+    platform_main_roc: &Path,
+    target: &Triple,
+    opt_level: OptLevel,
+) -> io::Result<PathBuf> {
+    let hash = platform_hash(platform_main_roc, target, opt_level)?;
+    let filename = legacy_host_filename(target).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("There is no legacy host for the target {}", target),
+        )
+    })?;
+
+    Ok(roc_cache_dir.join(HOSTS_DIR_NAME).join(hash).join(filename))
+}
+
+/// Store a freshly built host at `cached_path`. The host is written under a temporary name
+/// first, so concurrent builds never see a partially written host.
+pub fn store_host(host_path: &Path, cached_path: &Path) -> io::Result<()> {
+    let dir = cached_path.parent().unwrap();
+
+    fs::create_dir_all(dir)?;
+
+    let temp = tempfile::NamedTempFile::new_in(dir)?;
+    fs::copy(host_path, temp.path())?;
+    temp.persist(cached_path).map_err(|err| err.error)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::str::FromStr;
+
+    use roc_mono::ir::OptLevel;
+    use target_lexicon::Triple;
+
+    use super::{cached_host_path, store_host};
+
+    struct Platform {
+        dir: tempfile::TempDir,
+    }
+
+    impl Platform {
+        fn new() -> Self {
+            let dir = tempfile::tempdir().unwrap();
+
+            fs::write(dir.path().join("main.roc"), "platform \"test\"").unwrap();
+            fs::write(dir.path().join("host.zig"), "pub fn main() void {}").unwrap();
+
+            Platform { dir }
+        }
+
+        fn main_roc(&self) -> PathBuf {
+            self.dir.path().join("main.roc")
+        }
+
+        fn cached_path(&self, cache_dir: &Path, target: &str) -> PathBuf {
+            let target = Triple::from_str(target).unwrap();
+
+            cached_host_path(cache_dir, &self.main_roc(), &target, OptLevel::Normal).unwrap()
+        }
+    }
+
+    const LINUX: &str = "x86_64-unknown-linux-gnu";
+
+    #[test]
+    fn unchanged_host_is_reused() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let platform = Platform::new();
+        let cached_path = platform.cached_path(cache_dir.path(), LINUX);
+
+        assert!(!cached_path.exists());
+
+        let host = platform.dir.path().join("host.o");
+        fs::write(&host, "host").unwrap();
+        store_host(&host, &cached_path).unwrap();
+
+        // building the app writes into the platform's directory, which must not count as a change
+        fs::write(platform.dir.path().join("app.o"), "app").unwrap();
+        fs::create_dir(platform.dir.path().join("zig-cache")).unwrap();
+        fs::write(platform.dir.path().join("zig-cache").join("h"), "").unwrap();
+        fs::write(platform.main_roc(), "platform \"edited\"").unwrap();
+
+        let reused = platform.cached_path(cache_dir.path(), LINUX);
+
+        assert_eq!(reused, cached_path);
+        assert_eq!(fs::read_to_string(reused).unwrap(), "host");
+    }
+
+    #[test]
+    fn changed_host_source_invalidates() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let platform = Platform::new();
+        let before = platform.cached_path(cache_dir.path(), LINUX);
+
+        fs::write(platform.dir.path().join("host.zig"), "pub fn main() u8 {}").unwrap();
+        let edited = platform.cached_path(cache_dir.path(), LINUX);
+
+        fs::create_dir(platform.dir.path().join("src")).unwrap();
+        fs::write(platform.dir.path().join("src").join("lib.rs"), "").unwrap();
+        let added = platform.cached_path(cache_dir.path(), LINUX);
+
+        assert_ne!(before, edited);
+        assert_ne!(edited, added);
+    }
+
+    #[test]
+    fn changed_target_invalidates() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let platform = Platform::new();
+
+        let linux = platform.cached_path(cache_dir.path(), LINUX);
+        let macos = platform.cached_path(cache_dir.path(), "x86_64-apple-darwin");
+
+        assert_ne!(linux.parent(), macos.parent());
+        assert_ne!(linux.file_name(), macos.file_name());
+
+        let target = Triple::from_str(LINUX).unwrap();
+        let optimized = cached_host_path(
+            cache_dir.path(),
+            &platform.main_roc(),
+            &target,
+            OptLevel::Optimize,
+        )
+        .unwrap();
+
+        assert_ne!(linux, optimized);
+    }
+}
@@ -0,0 +1,281 @@
+//! A platform can declare how its host is built in a `host.recipe` file beside its main.roc.
+//! Without one, the host is built based on which source files exist (`host.zig`, `Cargo.toml`,
+//! `host.rs`, `host.c`, or `host.swift`, in that order), which goes wrong when a platform has
+//! more than one of them, e.g. a zig host with a `Cargo.toml` for its tests.
+//!
+//! A recipe has one `key = value` setting per line. Empty lines and lines starting with `#` are
+//! ignored. The only setting is
+//!
+//! - `builder`: how the host is built. One of `zig` (`host.zig`), `cargo` (the crate in the
+//!   platform's directory, plus `host.c`), `rust` (`host.rs` plus `host.c`), `c` (`host.c`), or
+//!   `swift` (`host.swift`, plus `host.h` if it exists).
+//!
+//! For example:
+//!
+//! ```text
+//! # The Cargo.toml is only used for the host's tests
+//! builder = zig
+//! ```
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub const HOST_RECIPE_FILENAME: &str = "host.recipe";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostBuilder {
+    Zig,
+    Cargo,
+    Rust,
+    C,
+    Swift,
+}
+
+impl HostBuilder {
+    const ALL: [(&'static str, HostBuilder); 5] = [
+        ("zig", HostBuilder::Zig),
+        ("cargo", HostBuilder::Cargo),
+        ("rust", HostBuilder::Rust),
+        ("c", HostBuilder::C),
+        ("swift", HostBuilder::Swift),
+    ];
+
+    /// The file this builder needs in the platform's directory
+    fn required_file(&self) -> &'static str {
+        match self {
+            HostBuilder::Zig => "host.zig",
+            HostBuilder::Cargo => "Cargo.toml",
+            HostBuilder::Rust => "host.rs",
+            HostBuilder::C => "host.c",
+            HostBuilder::Swift => "host.swift",
+        }
+    }
+
+    /// The builder for the host of the platform at `platform_main_roc`, if it has a host to
+    /// build. A `host.recipe` decides, if there is one; otherwise the source files do.
+    pub fn for_platform(platform_main_roc: &Path) -> Result<Option<Self>, RecipeProblem> {
+        let recipe_path = platform_main_roc.with_file_name(HOST_RECIPE_FILENAME);
+
+        match std::fs::read_to_string(&recipe_path) {
+            Ok(text) => {
+                let builder = parse_recipe(&text).map_err(|(line, kind)| RecipeProblem {
+                    path: recipe_path.clone(),
+                    line,
+                    kind,
+                })?;
+
+                let required = platform_main_roc.with_file_name(builder.required_file());
+
+                if !required.exists() {
+                    return Err(RecipeProblem {
+                        path: recipe_path,
+                        line: None,
+                        kind: RecipeProblemKind::MissingSource(required),
+                    });
+                }
+
+                Ok(Some(builder))
+            }
+            Err(_) => Ok(HostBuilder::ALL
+                .iter()
+                .map(|(_, builder)| *builder)
+                .find(|builder| {
+                    platform_main_roc
+                        .with_file_name(builder.required_file())
+                        .exists()
+                })),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RecipeProblem {
+    pub path: PathBuf,
+    /// The 1-based line the problem is on, if it's about a particular line
+    pub line: Option<usize>,
+    pub kind: RecipeProblemKind,
+}
+
+#[derive(Debug)]
+pub enum RecipeProblemKind {
+    NotASetting,
+    UnknownKey(String),
+    UnknownBuilder(String),
+    DuplicateKey(String),
+    MissingBuilder,
+    MissingSource(PathBuf),
+}
+
+impl fmt::Display for RecipeProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: ", self.path.display(), line)?,
+            None => write!(f, "{}: ", self.path.display())?,
+        }
+
+        match &self.kind {
+            RecipeProblemKind::NotASetting => {
+                write!(f, "expected a setting like `builder = zig`")
+            }
+            RecipeProblemKind::UnknownKey(key) => {
+                write!(f, "unknown setting `{}`, the only setting is `builder`", key)
+            }
+            RecipeProblemKind::UnknownBuilder(builder) => {
+                let names: Vec<_> = HostBuilder::ALL.iter().map(|(name, _)| *name).collect();
+
+                write!(
+                    f,
+                    "unknown builder `{}`, expected one of: {}",
+                    builder,
+                    names.join(", ")
+                )
+            }
+            RecipeProblemKind::DuplicateKey(key) => write!(f, "`{}` is set more than once", key),
+            RecipeProblemKind::MissingBuilder => write!(f, "the recipe does not set `builder`"),
+            RecipeProblemKind::MissingSource(path) => write!(
+                f,
+                "the recipe's builder needs {}, which does not exist",
+                path.display()
+            ),
+        }
+    }
+}
+
+fn parse_recipe(text: &str) -> Result<HostBuilder, (Option<usize>, RecipeProblemKind)> {
+    let mut builder = None;
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = Some(index + 1);
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => return Err((line_number, RecipeProblemKind::NotASetting)),
+        };
+
+        if key != "builder" {
+            return Err((line_number, RecipeProblemKind::UnknownKey(key.to_string())));
+        }
+
+        if builder.is_some() {
+            return Err((line_number, RecipeProblemKind::DuplicateKey(key.to_string())));
+        }
+
+        builder = match HostBuilder::ALL.iter().find(|(name, _)| *name == value) {
+            Some((_, found)) => Some(*found),
+            None => {
+                return Err((
+                    line_number,
+                    RecipeProblemKind::UnknownBuilder(value.to_string()),
+                ))
+            }
+        };
+    }
+
+    builder.ok_or((None, RecipeProblemKind::MissingBuilder))
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::{parse_recipe, HostBuilder, RecipeProblemKind, HOST_RECIPE_FILENAME};
+
+    #[test]
+    fn parse_builder() {
+        assert_eq!(parse_recipe("builder = zig").unwrap(), HostBuilder::Zig);
+        assert_eq!(parse_recipe("builder=cargo").unwrap(), HostBuilder::Cargo);
+        assert_eq!(
+            parse_recipe("# comment\n\n  builder =  swift  \n# another\n").unwrap(),
+            HostBuilder::Swift
+        );
+    }
+
+    #[test]
+    fn parse_malformed() {
+        let problem = |text| parse_recipe(text).unwrap_err();
+
+        assert!(matches!(
+            problem("builder = zig\nzig"),
+            (Some(2), RecipeProblemKind::NotASetting)
+        ));
+        assert!(matches!(
+            problem("linker = surgical"),
+            (Some(1), RecipeProblemKind::UnknownKey(key)) if key == "linker"
+        ));
+        assert!(matches!(
+            problem("builder = make"),
+            (Some(1), RecipeProblemKind::UnknownBuilder(builder)) if builder == "make"
+        ));
+        assert!(matches!(
+            problem("builder = zig\n\nbuilder = c"),
+            (Some(3), RecipeProblemKind::DuplicateKey(key)) if key == "builder"
+        ));
+        assert!(matches!(
+            problem("# nothing here\n"),
+            (None, RecipeProblemKind::MissingBuilder)
+        ));
+    }
+
+    #[test]
+    fn recipe_overrides_source_files() {
+        let platform_dir = tempfile::tempdir().unwrap();
+        let main_roc = platform_dir.path().join("main.roc");
+
+        fs::write(platform_dir.path().join("host.zig"), "").unwrap();
+        fs::write(platform_dir.path().join("host.c"), "").unwrap();
+
+        // Without a recipe, host.zig wins
+        assert_eq!(
+            HostBuilder::for_platform(&main_roc).unwrap(),
+            Some(HostBuilder::Zig)
+        );
+
+        fs::write(
+            platform_dir.path().join(HOST_RECIPE_FILENAME),
+            "builder = c",
+        )
+        .unwrap();
+
+        assert_eq!(
+            HostBuilder::for_platform(&main_roc).unwrap(),
+            Some(HostBuilder::C)
+        );
+    }
+
+    #[test]
+    fn recipe_problems_name_the_file() {
+        let platform_dir = tempfile::tempdir().unwrap();
+        let main_roc = platform_dir.path().join("main.roc");
+        let recipe = platform_dir.path().join(HOST_RECIPE_FILENAME);
+
+        fs::write(&recipe, "builder = rust").unwrap();
+
+        let problem = HostBuilder::for_platform(&main_roc).unwrap_err();
+
+        assert!(matches!(
+            &problem.kind,
+            RecipeProblemKind::MissingSource(path) if path.ends_with("host.rs")
+        ));
+        assert_eq!(
+            problem.to_string(),
+            format!(
+                "{}: the recipe's builder needs {}, which does not exist",
+                recipe.display(),
+                platform_dir.path().join("host.rs").display()
+            )
+        );
+
+        fs::write(&recipe, "builder = rust\nbuilder = c").unwrap();
+
+        assert_eq!(
+            HostBuilder::for_platform(&main_roc)
+                .unwrap_err()
+                .to_string(),
+            format!("{}:2: `builder` is set more than once", recipe.display())
+        );
+    }
+}
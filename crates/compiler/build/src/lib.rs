@@ -2,6 +2,10 @@
 #![warn(clippy::dbg_macro)]
 // See github.com/roc-lang/roc/issues/800 for discussion of the large_enum_variant check.
 #![allow(clippy::large_enum_variant)]
+pub mod api;
+pub mod builtins_cache;
+pub mod host_cache;
+pub mod host_recipe;
 pub mod link;
 pub mod program;
 pub mod target;
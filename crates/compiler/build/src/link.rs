@@ -1,7 +1,8 @@
+use crate::host_recipe::HostBuilder;
 use crate::target::{arch_str, target_zig_str};
 use libloading::{Error, Library};
 use roc_command_utils::{cargo, clang, rustup, zig};
-use roc_error_macros::internal_error;
+use roc_error_macros::{internal_error, user_error};
 use roc_mono::ir::OptLevel;
 use std::collections::HashMap;
 use std::fs::DirEntry;
@@ -531,7 +532,6 @@ pub fn rebuild_host(
     let zig_host_src = platform_main_roc.with_file_name("host.zig");
     let rust_host_src = platform_main_roc.with_file_name("host.rs");
     let rust_host_dest = platform_main_roc.with_file_name("rust_host.o");
-    let cargo_host_src = platform_main_roc.with_file_name("Cargo.toml");
     let swift_host_src = platform_main_roc.with_file_name("host.swift");
     let swift_host_header_src = platform_main_roc.with_file_name("host.h");
 
@@ -560,10 +560,15 @@ pub fn rebuild_host(
     let env_home = env::var("HOME").unwrap_or_else(|_| "".to_string());
     let env_cpath = env::var("CPATH").unwrap_or_else(|_| "".to_string());
 
+    let builder = match HostBuilder::for_platform(platform_main_roc) {
+        Ok(builder) => builder,
+        Err(problem) => user_error!("{}", problem),
+    };
+
     let builtins_host_tempfile =
         roc_bitcode::host_tempfile().expect("failed to write host builtins object to tempfile");
 
-    if zig_host_src.exists() {
+    if builder == Some(HostBuilder::Zig) {
         // Compile host.zig
         let zig_cmd = match target.architecture {
             Architecture::Wasm32 => {
@@ -615,7 +620,7 @@ pub fn rebuild_host(
         };
 
         run_build_command(zig_cmd, "host.zig", 0);
-    } else if cargo_host_src.exists() {
+    } else if builder == Some(HostBuilder::Cargo) {
         // Compile and link Cargo.toml, if it exists
         let cargo_dir = platform_main_roc.parent().unwrap();
 
@@ -702,7 +707,7 @@ pub fn rebuild_host(
                 std::fs::remove_file(c_host_dest).unwrap();
             }
         }
-    } else if rust_host_src.exists() {
+    } else if builder == Some(HostBuilder::Rust) {
         // Compile and link host.rs, if it exists
         let mut rustc_cmd = Command::new("rustc");
         rustc_cmd.args([
@@ -771,7 +776,7 @@ pub fn rebuild_host(
         if rust_host_dest.exists() {
             std::fs::remove_file(rust_host_dest).unwrap();
         }
-    } else if c_host_src.exists() {
+    } else if builder == Some(HostBuilder::C) {
         // Compile host.c, if it exists
         let clang_cmd = build_c_host_native(
             target,
@@ -786,7 +791,7 @@ pub fn rebuild_host(
         );
 
         run_build_command(clang_cmd, "host.c", 0);
-    } else if swift_host_src.exists() {
+    } else if builder == Some(HostBuilder::Swift) {
         // Compile host.swift, if it exists
         let swiftc_cmd = build_swift_host_native(
             &env_path,
@@ -1,3 +1,5 @@
+use crate::builtins_cache;
+use crate::host_cache;
 use crate::link::{
     legacy_host_filename, link, preprocess_host_wasm32, rebuild_host, LinkType, LinkingStrategy,
 };
@@ -5,17 +7,20 @@ use bumpalo::Bump;
 use inkwell::memory_buffer::MemoryBuffer;
 use roc_error_macros::internal_error;
 use roc_gen_dev::AssemblyBackendMode;
-use roc_gen_llvm::llvm::build::{module_from_builtins, LlvmBackendMode};
+use roc_gen_llvm::llvm::build::LlvmBackendMode;
 use roc_gen_llvm::llvm::externs::add_default_roc_externs;
+use roc_gen_llvm::llvm::profile::{Profile, ProfileMode};
+use roc_gen_llvm::llvm::refcounting::{AtomicRefcounts, RefcountMode};
+use roc_linker::LinkLog;
 use roc_load::{
     EntryPoint, ExecutionMode, ExpectMetadata, LoadConfig, LoadMonomorphizedError, LoadedModule,
-    LoadingProblem, MonomorphizedModule, Threading,
+    LoadingProblem, MonomorphizedModule, OverflowMode, Threading,
 };
 use roc_mono::ir::{OptLevel, SingleEntryPoint};
 use roc_packaging::cache::RocCacheDir;
 use roc_reporting::{
     cli::{report_problems, Problems},
-    report::{RenderTarget, DEFAULT_PALETTE},
+    report::{ansi_color, RenderTarget, ANSI_STYLE_CODES, DEFAULT_PALETTE},
 };
 use roc_target::TargetInfo;
 use std::ffi::OsStr;
@@ -27,8 +32,9 @@ use std::{
 };
 use target_lexicon::Triple;
 
-#[cfg(feature = "target-wasm32")]
-use roc_collections::all::MutSet;
+use roc_collections::all::{MutMap, MutSet};
+use roc_module::symbol::Symbol;
+use roc_region::all::LineInfo;
 
 pub const DEFAULT_ROC_FILENAME: &str = "main.roc";
 
@@ -80,11 +86,31 @@ pub enum CodeGenBackend {
     Wasm,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct CodeGenOptions {
     pub backend: CodeGenBackend,
     pub opt_level: OptLevel,
     pub emit_debug_info: bool,
+    pub profile: ProfileOptions,
+    /// Only the LLVM backend supports atomic refcounts
+    pub atomic_refcounts: AtomicRefcounts,
+    /// The host allocates everything in an arena which it resets as a whole,
+    /// so the generated code never needs to decrement refcounts.
+    pub arena_allocation: bool,
+    /// Move refcount operations next to the uses of their values. Off by default until the pass
+    /// has had more testing.
+    pub schedule_refcounts: bool,
+}
+
+/// Profile-guided optimization. Only the LLVM backend supports this.
+#[derive(Debug, Clone, Default)]
+pub enum ProfileOptions {
+    #[default]
+    Off,
+    /// Build an executable which writes a profile to this (absolute) path when it exits
+    Generate(PathBuf),
+    /// Optimize using a profile written by an earlier `Generate` build
+    Use(Profile),
 }
 
 type GenFromMono<'a> = (CodeObject, CodeGenTiming, ExpectMetadata<'a>);
@@ -92,17 +118,26 @@ type GenFromMono<'a> = (CodeObject, CodeGenTiming, ExpectMetadata<'a>);
 #[allow(clippy::too_many_arguments)]
 pub fn gen_from_mono_module<'a>(
     arena: &'a bumpalo::Bump,
-    loaded: MonomorphizedModule<'a>,
+    mut loaded: MonomorphizedModule<'a>,
     roc_file_path: &Path,
     target: &target_lexicon::Triple,
     code_gen_options: CodeGenOptions,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
+    builtins_cache_dir: Option<&Path>,
 ) -> GenFromMono<'a> {
     let path = roc_file_path;
     let debug = code_gen_options.emit_debug_info;
     let opt = code_gen_options.opt_level;
 
+    if code_gen_options.arena_allocation {
+        roc_mono::arena_allocation::remove_decrements(arena, &mut loaded.procedures);
+    }
+
+    if code_gen_options.schedule_refcounts {
+        roc_mono::rc_scheduling::schedule_refcounts(arena, &mut loaded.procedures);
+    }
+
     match code_gen_options.backend {
         CodeGenBackend::Wasm => gen_from_mono_module_dev(
             arena,
@@ -120,15 +155,51 @@ pub fn gen_from_mono_module<'a>(
             wasm_dev_stack_bytes,
             backend_mode,
         ),
-        CodeGenBackend::Llvm(backend_mode) => {
-            gen_from_mono_module_llvm(arena, loaded, path, target, opt, backend_mode, debug)
+        CodeGenBackend::Llvm(backend_mode) => gen_from_mono_module_llvm(
+            arena,
+            loaded,
+            path,
+            target,
+            opt,
+            backend_mode,
+            debug,
+            &code_gen_options.profile,
+            code_gen_options.atomic_refcounts,
+            builtins_cache_dir,
+        ),
+    }
+}
+
+/// Where each `dbg` in the app is, e.g. `Main.roc:12:5`, for the host's `roc_dbg`
+fn dbg_locations<'a>(arena: &'a Bump, loaded: &MonomorphizedModule<'a>) -> MutMap<Symbol, &'a str> {
+    let mut locations = MutMap::default();
+
+    for (module_id, expectations) in loaded.expectations.iter() {
+        let line_info = match loaded.sources.get(module_id) {
+            Some((_, src)) => LineInfo::new(src),
+            None => continue,
+        };
+
+        for (symbol, lookup) in expectations.dbgs.iter() {
+            let position = line_info.convert_pos(lookup.region.start());
+            let location = format!(
+                "{}:{}:{}",
+                expectations.path.display(),
+                position.line + 1,
+                position.column + 1
+            );
+
+            locations.insert(*symbol, &*arena.alloc_str(&location));
         }
     }
+
+    locations
 }
 
 // TODO how should imported modules factor into this? What if those use builtins too?
 // TODO this should probably use more helper functions
 // TODO make this polymorphic in the llvm functions so it can be reused for another backend.
+#[allow(clippy::too_many_arguments)]
 fn gen_from_mono_module_llvm<'a>(
     arena: &'a bumpalo::Bump,
     mut loaded: MonomorphizedModule<'a>,
@@ -137,11 +208,12 @@ fn gen_from_mono_module_llvm<'a>(
     opt_level: OptLevel,
     backend_mode: LlvmBackendMode,
     emit_debug_info: bool,
+    profile: &ProfileOptions,
+    atomic_refcounts: AtomicRefcounts,
+    builtins_cache_dir: Option<&Path>,
 ) -> GenFromMono<'a> {
     use crate::target::{self, convert_opt_level};
-    use inkwell::attributes::{Attribute, AttributeLoc};
     use inkwell::context::Context;
-    use inkwell::module::Linkage;
     use inkwell::targets::{FileType, RelocMode};
 
     let code_gen_start = Instant::now();
@@ -149,12 +221,16 @@ fn gen_from_mono_module_llvm<'a>(
     // Generate the binary
     let target_info = roc_target::TargetInfo::from(target);
     let context = Context::create();
-    let module = arena.alloc(module_from_builtins(target, &context, "app"));
+    let module = arena.alloc(builtins_cache::load_builtins(
+        &context,
+        target,
+        "app",
+        builtins_cache_dir,
+    ));
 
     // strip Zig debug stuff
     // module.strip_debug_info();
 
-    // mark our zig-defined builtins as internal
     let app_ll_file = {
         let mut temp = PathBuf::from(roc_file_path);
         temp.set_extension("ll");
@@ -162,34 +238,26 @@ fn gen_from_mono_module_llvm<'a>(
         temp
     };
 
-    let kind_id = Attribute::get_named_enum_kind_id("alwaysinline");
-    debug_assert!(kind_id > 0);
-    let enum_attr = context.create_enum_attribute(kind_id, 1);
-
-    for function in module.get_functions() {
-        let name = function.get_name().to_str().unwrap();
-
-        // mark our zig-defined builtins as internal
-        if name.starts_with("roc_builtins") {
-            function.set_linkage(Linkage::Internal);
-        }
-
-        if name.starts_with("roc_builtins.dict")
-            || name.starts_with("roc_builtins.list")
-            || name.starts_with("roc_builtins.dec")
-            || name.starts_with("list.RocList")
-            || name.starts_with("dict.RocDict")
-            || name.contains("incref")
-            || name.contains("decref")
-        {
-            function.add_attribute(AttributeLoc::Function, enum_attr);
-        }
-    }
-
     let builder = context.create_builder();
     let (dibuilder, compile_unit) = roc_gen_llvm::llvm::build::Env::new_debug_info(module);
     let (mpm, _fpm) = roc_gen_llvm::llvm::build::construct_optimization_passes(module, opt_level);
 
+    let exposed_to_host: MutSet<_> = loaded
+        .exposed_to_host
+        .top_level_values
+        .keys()
+        .copied()
+        .collect();
+
+    let refcounting = RefcountMode::new(
+        atomic_refcounts,
+        &loaded.layout_interner,
+        &loaded.procedures,
+        &exposed_to_host,
+    );
+
+    let dbg_locations = dbg_locations(arena, &loaded);
+
     // Compile and add all the Procs before adding main
     let env = roc_gen_llvm::llvm::build::Env {
         arena,
@@ -202,12 +270,18 @@ fn gen_from_mono_module_llvm<'a>(
         target_info,
         mode: backend_mode,
 
-        exposed_to_host: loaded
-            .exposed_to_host
-            .top_level_values
-            .keys()
-            .copied()
-            .collect(),
+        exposed_to_host,
+        profile: match profile {
+            ProfileOptions::Off => ProfileMode::Off,
+            ProfileOptions::Generate(output_path) => ProfileMode::Generate {
+                output_path: output_path.as_path(),
+            },
+            ProfileOptions::Use(profile) => ProfileMode::Use(profile),
+        },
+        refcounting,
+        dbg_locations,
+        static_constants: loaded.static_constants,
+        func_spec_names: Default::default(),
     };
 
     // does not add any externs for this mode (we have a host) but cleans up some functions around
@@ -675,13 +749,17 @@ pub fn handle_error_module(
     if print_run_anyway_hint {
         // If you're running "main.roc" then you can just do `roc run`
         // to re-run the program.
-        print!(".\n\nYou can run the program anyway with \x1B[32mroc run");
+        let mut command = String::from("roc run");
 
         if filename != DEFAULT_ROC_FILENAME {
-            print!(" {}", &filename.to_string_lossy());
+            command.push(' ');
+            command.push_str(&filename.to_string_lossy());
         }
 
-        println!("\x1B[39m");
+        println!(
+            ".\n\nYou can run the program anyway with {}",
+            ansi_color(ANSI_STYLE_CODES.green, command)
+        );
     }
 
     Ok(problems.exit_code())
@@ -720,6 +798,10 @@ pub fn standard_load_config(
         palette: DEFAULT_PALETTE,
         threading,
         exec_mode,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants: true,
     }
 }
 
@@ -730,15 +812,22 @@ pub fn build_file<'a>(
     app_module_path: PathBuf,
     code_gen_options: CodeGenOptions,
     emit_timings: bool,
+    explain_specializations: bool,
+    explain_closures: bool,
     link_type: LinkType,
     linking_strategy: LinkingStrategy,
+    link_log: LinkLog,
     prebuilt_requested: bool,
+    rebuild_host_requested: bool,
     wasm_dev_stack_bytes: Option<u32>,
     roc_cache_dir: RocCacheDir<'_>,
-    load_config: LoadConfig,
+    mut load_config: LoadConfig,
 ) -> Result<BuiltFile<'a>, BuildFileError<'a>> {
     let compilation_start = Instant::now();
 
+    // Only the LLVM backend stores top-level constants as static data
+    load_config.static_data = matches!(code_gen_options.backend, CodeGenBackend::Llvm(_));
+
     // Step 1: compile the app and generate the .o file
     let loaded =
         roc_load::load_and_monomorphize(arena, app_module_path.clone(), roc_cache_dir, load_config)
@@ -750,10 +839,15 @@ pub fn build_file<'a>(
         app_module_path,
         code_gen_options,
         emit_timings,
+        explain_specializations,
+        explain_closures,
         link_type,
         linking_strategy,
+        link_log,
         prebuilt_requested,
+        rebuild_host_requested,
         wasm_dev_stack_bytes,
+        roc_cache_dir,
         loaded,
         compilation_start,
     )
@@ -766,14 +860,20 @@ fn build_loaded_file<'a>(
     app_module_path: PathBuf,
     code_gen_options: CodeGenOptions,
     emit_timings: bool,
+    explain_specializations: bool,
+    explain_closures: bool,
     link_type: LinkType,
     linking_strategy: LinkingStrategy,
+    link_log: LinkLog,
     prebuilt_requested: bool,
+    rebuild_host_requested: bool,
     wasm_dev_stack_bytes: Option<u32>,
+    roc_cache_dir: RocCacheDir<'_>,
     loaded: roc_load::MonomorphizedModule<'a>,
     compilation_start: Instant,
 ) -> Result<BuiltFile<'a>, BuildFileError<'a>> {
     let operating_system = roc_target::OperatingSystem::from(target.operating_system);
+    let backend = code_gen_options.backend;
 
     let platform_main_roc = match &loaded.entry_point {
         EntryPoint::Executable { platform_path, .. } => platform_path.to_path_buf(),
@@ -804,8 +904,34 @@ fn build_loaded_file<'a>(
         output_exe_path.set_extension(extension);
     }
 
-    // We don't need to spawn a rebuild thread when using a prebuilt host.
-    let rebuild_thread = if matches!(link_type, LinkType::Dylib | LinkType::None) {
+    // Hosts built for the legacy linker are cached, so we only rebuild them when the platform changes
+    let cached_host_path = match roc_cache_dir {
+        RocCacheDir::Persistent(cache_dir)
+            if linking_strategy == LinkingStrategy::Legacy
+                && !matches!(operating_system, roc_target::OperatingSystem::Wasi) =>
+        {
+            host_cache::cached_host_path(
+                cache_dir,
+                &platform_main_roc,
+                target,
+                code_gen_options.opt_level,
+            )
+            .ok()
+        }
+        _ => None,
+    };
+
+    let restored_cached_host = match &cached_host_path {
+        Some(cached) if cached.exists() && !is_platform_prebuilt && !rebuild_host_requested => {
+            std::fs::copy(cached, &preprocessed_host_path).is_ok()
+        }
+        _ => false,
+    };
+
+    // We don't need to spawn a rebuild thread when using a prebuilt or cached host.
+    let rebuild_thread = if restored_cached_host {
+        None
+    } else if matches!(link_type, LinkType::Dylib | LinkType::None) {
         None
     } else if is_platform_prebuilt {
         if !preprocessed_host_path.exists() {
@@ -840,6 +966,7 @@ fn build_loaded_file<'a>(
             output_exe_path.clone(),
             target,
             dll_stub_symbols,
+            cached_host_path,
         );
 
         Some(join_handle)
@@ -876,6 +1003,26 @@ fn build_loaded_file<'a>(
     let problems = report_problems_monomorphized(&mut loaded);
     let loaded = loaded;
 
+    if explain_specializations {
+        let report = roc_mono::debug::format_specializations(
+            &loaded.interns,
+            &loaded.layout_interner,
+            &loaded.procedures,
+        );
+
+        println!("{}\n", report);
+    }
+
+    if explain_closures {
+        let report = roc_mono::debug::format_closures(
+            &loaded.interns,
+            &loaded.layout_interner,
+            &loaded.procedures,
+        );
+
+        println!("{}\n", report);
+    }
+
     enum HostRebuildTiming {
         BeforeApp(u128),
         ConcurrentWithApp(JoinHandle<u128>),
@@ -902,6 +1049,11 @@ fn build_loaded_file<'a>(
         None
     };
 
+    let builtins_cache_dir = match roc_cache_dir {
+        RocCacheDir::Persistent(cache_dir) => Some(cache_dir),
+        _ => None,
+    };
+
     let (roc_app_bytes, code_gen_timing, expect_metadata) = gen_from_mono_module(
         arena,
         loaded,
@@ -910,6 +1062,7 @@ fn build_loaded_file<'a>(
         code_gen_options,
         &preprocessed_host_path,
         wasm_dev_stack_bytes,
+        builtins_cache_dir,
     );
 
     buf.push('\n');
@@ -960,6 +1113,7 @@ fn build_loaded_file<'a>(
                 &platform_main_roc,
                 &roc_app_bytes,
                 &output_exe_path,
+                link_log,
             );
         }
         (LinkingStrategy::Additive, _) | (LinkingStrategy::Legacy, LinkType::None) => {
@@ -995,7 +1149,7 @@ fn build_loaded_file<'a>(
                 inputs.push(preprocessed_host_path.as_path().to_str().unwrap());
             }
 
-            if matches!(code_gen_options.backend, CodeGenBackend::Assembly(_)) {
+            if matches!(backend, CodeGenBackend::Assembly(_)) {
                 inputs.push(builtins_host_tempfile.path().to_str().unwrap());
             }
 
@@ -1075,6 +1229,7 @@ fn spawn_rebuild_thread(
     output_exe_path: PathBuf,
     target: &Triple,
     dll_stub_symbols: Vec<String>,
+    cached_host_path: Option<PathBuf>,
 ) -> std::thread::JoinHandle<u128> {
     let thread_local_target = target.clone();
     std::thread::spawn(move || {
@@ -1110,12 +1265,19 @@ fn spawn_rebuild_thread(
                 std::fs::copy(&preprocessed_host_path, output_exe_path.as_path()).unwrap();
             }
             LinkingStrategy::Legacy => {
-                rebuild_host(
+                let host_dest = rebuild_host(
                     opt_level,
                     &thread_local_target,
                     platform_main_roc.as_path(),
                     None,
                 );
+
+                if let Some(cached_host_path) = cached_host_path {
+                    // A failure here only means the next build rebuilds the host too
+                    if let Err(err) = host_cache::store_host(&host_dest, &cached_host_path) {
+                        eprintln!("Could not cache the rebuilt platform: {}", err);
+                    }
+                }
             }
         }
 
@@ -1169,6 +1331,7 @@ pub fn check_file<'a>(
     arena: &'a Bump,
     roc_file_path: PathBuf,
     emit_timings: bool,
+    lint_single_implementations: bool,
     roc_cache_dir: RocCacheDir<'_>,
     threading: Threading,
 ) -> Result<(Problems, Duration), LoadingProblem<'a>> {
@@ -1187,10 +1350,24 @@ pub fn check_file<'a>(
         palette: DEFAULT_PALETTE,
         threading,
         exec_mode: ExecutionMode::Check,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants: true,
     };
     let mut loaded =
         roc_load::load_and_typecheck(arena, roc_file_path, roc_cache_dir, load_config)?;
 
+    if lint_single_implementations {
+        for (module_id, lints) in std::mem::take(&mut loaded.lints) {
+            loaded
+                .type_problems
+                .entry(module_id)
+                .or_default()
+                .extend(lints);
+        }
+    }
+
     let buf = &mut String::with_capacity(1024);
 
     let mut it = loaded.timings.iter().peekable();
@@ -1249,6 +1426,10 @@ pub fn build_str_test<'a>(
         backend: CodeGenBackend::Llvm(LlvmBackendMode::Binary),
         opt_level: OptLevel::Normal,
         emit_debug_info: false,
+        profile: ProfileOptions::Off,
+        atomic_refcounts: AtomicRefcounts::Never,
+        arena_allocation: false,
+        schedule_refcounts: false,
     };
 
     let emit_timings = false;
@@ -1281,10 +1462,15 @@ pub fn build_str_test<'a>(
         app_module_path.to_path_buf(),
         code_gen_options,
         emit_timings,
+        false,
+        false,
         link_type,
         linking_strategy,
+        LinkLog::default(),
         assume_prebuild,
+        false,
         wasm_dev_stack_bytes,
+        roc_cache_dir,
         loaded,
         compilation_start,
     )
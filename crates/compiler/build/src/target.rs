@@ -45,6 +45,11 @@ pub fn target_triple_str(target: &Triple) -> &'static str {
             operating_system: OperatingSystem::Windows,
             ..
         } => "x86_64-pc-windows-gnu",
+        Triple {
+            architecture: Architecture::S390x,
+            operating_system: OperatingSystem::Linux,
+            ..
+        } => "s390x-unknown-linux-gnu",
         _ => internal_error!("TODO gracefully handle unsupported target: {:?}", target),
     }
 }
@@ -93,6 +98,11 @@ pub fn target_zig_str(target: &Triple) -> &'static str {
             operating_system: OperatingSystem::Darwin,
             ..
         } => "aarch64-apple-darwin",
+        Triple {
+            architecture: Architecture::S390x,
+            operating_system: OperatingSystem::Linux,
+            ..
+        } => "s390x-linux-gnu",
         _ => internal_error!("TODO gracefully handle unsupported target: {:?}", target),
     }
 }
@@ -113,6 +123,9 @@ pub fn init_arch(target: &Triple) {
         Architecture::Wasm32 if cfg!(feature = "target-wasm32") => {
             Target::initialize_webassembly(&InitializationConfig::default());
         }
+        Architecture::S390x if cfg!(feature = "target-s390x") => {
+            Target::initialize_system_z(&InitializationConfig::default());
+        }
         _ => internal_error!(
             "TODO gracefully handle unsupported target architecture: {:?}",
             target.architecture
@@ -133,6 +146,7 @@ pub fn arch_str(target: &Triple) -> &'static str {
         Architecture::Aarch64(_) if cfg!(feature = "target-aarch64") => "aarch64",
         Architecture::Arm(_) if cfg!(feature = "target-arm") => "arm",
         Architecture::Wasm32 if cfg!(feature = "target-webassembly") => "wasm32",
+        Architecture::S390x if cfg!(feature = "target-s390x") => "systemz",
         _ => internal_error!(
             "TODO gracefully handle unsupported target architecture: {:?}",
             target.architecture
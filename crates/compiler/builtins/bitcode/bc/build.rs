@@ -40,6 +40,7 @@ fn main() {
         "ir-windows-x86_64",
         "builtins-windows-x86_64",
     );
+    generate_bc_file(&bitcode_path, "ir-s390x", "builtins-s390x");
 
     get_zig_files(bitcode_path.as_path(), &|path| {
         let path: &Path = path;
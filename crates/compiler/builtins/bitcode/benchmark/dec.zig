@@ -22,6 +22,12 @@ pub fn main() !void {
 
     try stdout.print("7 divisions took ", .{});
     try avg_runs(div7);
+
+    try stdout.print("7 multiply-adds took ", .{});
+    try avg_runs(mulAdd7);
+
+    try stdout.print("7 toStrs took ", .{});
+    try avg_runs(toStr7);
 }
 
 fn avg_runs(func: fn() u64) !void {
@@ -172,3 +178,63 @@ fn div7() u64 {
 
     return timer.read();
 }
+
+fn mulAdd7() u64 {
+    var str1 = RocStr.init("1.2", 3);
+    const dec1 = RocDec.fromStr(str1).?;
+
+    var str2 = RocStr.init("3.4", 3);
+    const dec2 = RocDec.fromStr(str2).?;
+
+    timer.reset();
+
+    var a = dec1.mulAdd(dec2, dec1);
+    a = a.mulAdd(dec1, dec2);
+    a = a.mulAdd(dec2, dec1);
+    a = a.mulAdd(dec1, dec2);
+    a = a.mulAdd(dec2, dec1);
+    a = a.mulAdd(dec1, dec2);
+    a = a.mulAdd(dec2, dec1);
+    a = a.mulAdd(dec1, dec2);
+    a = a.mulAdd(dec2, dec1);
+    a = a.mulAdd(dec1, dec2);
+    a = a.mulAdd(dec2, dec1);
+    a = a.mulAdd(dec1, dec2);
+    a = a.mulAdd(dec2, dec1);
+    a = a.mulAdd(dec1, dec2);
+    a = a.mulAdd(dec2, dec1);
+    a = a.mulAdd(dec1, dec2);
+    a = a.mulAdd(dec2, dec1);
+
+    return timer.read();
+}
+
+fn toStr7() u64 {
+    var str1 = RocStr.init("123.111111111111444444", 22);
+    const dec1 = RocDec.fromStr(str1).?;
+
+    var buf: [41]u8 = undefined;
+    var len: usize = 0;
+
+    timer.reset();
+
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+    len += dec1.toStrBuf(&buf).len;
+
+    return timer.read();
+}
@@ -32,6 +32,7 @@ pub fn build(b: *Builder) void {
     const linux64_target = makeLinux64Target();
     const windows64_target = makeWindows64Target();
     const wasm32_target = makeWasm32Target();
+    const linux_s390x_target = makeLinuxS390xTarget();
 
     // LLVM IR
     generateLlvmIrFile(b, mode, host_target, main_path, "ir", "builtins-host");
@@ -39,6 +40,7 @@ pub fn build(b: *Builder) void {
     generateLlvmIrFile(b, mode, linux64_target, main_path, "ir-x86_64", "builtins-x86_64");
     generateLlvmIrFile(b, mode, windows64_target, main_path, "ir-windows-x86_64", "builtins-windows-x86_64");
     generateLlvmIrFile(b, mode, wasm32_target, main_path, "ir-wasm32", "builtins-wasm32");
+    generateLlvmIrFile(b, mode, linux_s390x_target, main_path, "ir-s390x", "builtins-s390x");
 
     // Generate Object Files
     generateObjectFile(b, mode, host_target, main_path, "object", "builtins-host");
@@ -134,6 +136,17 @@ fn makeWasm32Target() CrossTarget {
     return target;
 }
 
+// big-endian, so it catches code that assumes a byte order
+fn makeLinuxS390xTarget() CrossTarget {
+    var target = CrossTarget.parse(.{}) catch unreachable;
+
+    target.cpu_arch = std.Target.Cpu.Arch.s390x;
+    target.os_tag = std.Target.Os.Tag.linux;
+    target.abi = std.Target.Abi.musl;
+
+    return target;
+}
+
 fn removeInstallSteps(b: *Builder) void {
     for (b.top_level_steps.items) |top_level_step, i| {
         const name = top_level_step.step.name;
@@ -0,0 +1,96 @@
+const std = @import("std");
+const list = @import("list.zig");
+const RocList = list.RocList;
+
+const Sha256 = std.crypto.hash.sha2.Sha256;
+const Blake3 = std.crypto.hash.Blake3;
+
+const expect = std.testing.expect;
+
+fn bytesOf(input: RocList) []const u8 {
+    if (input.elements(u8)) |bytes| {
+        return bytes[0..input.len()];
+    } else {
+        return &[_]u8{};
+    }
+}
+
+/// The 32-byte SHA-256 digest of a List U8
+pub fn sha256(input: RocList) callconv(.C) RocList {
+    var digest: [Sha256.digest_length]u8 = undefined;
+    Sha256.hash(bytesOf(input), &digest, .{});
+
+    return RocList.fromSlice(u8, &digest);
+}
+
+/// The 32-byte BLAKE3 digest of a List U8
+pub fn blake3(input: RocList) callconv(.C) RocList {
+    var digest: [Blake3.digest_length]u8 = undefined;
+    Blake3.hash(bytesOf(input), &digest, .{});
+
+    return RocList.fromSlice(u8, &digest);
+}
+
+/// Whether two List U8 are equal, in a time which depends only on their lengths. Lists of
+/// different lengths are unequal straight away; the length of a digest or a token is not secret.
+pub fn constantTimeEq(a: RocList, b: RocList) callconv(.C) bool {
+    const a_bytes = bytesOf(a);
+    const b_bytes = bytesOf(b);
+
+    if (a_bytes.len != b_bytes.len) {
+        return false;
+    }
+
+    var difference: u8 = 0;
+    for (a_bytes) |byte, i| {
+        difference |= byte ^ b_bytes[i];
+    }
+
+    // keep the optimizer from turning the loop into an early-exit comparison
+    return @ptrCast(*const volatile u8, &difference).* == 0;
+}
+
+fn expectDigest(comptime hash: fn (RocList) callconv(.C) RocList, input: []const u8, comptime expected_hex: []const u8) !void {
+    var expected: [32]u8 = undefined;
+    _ = try std.fmt.hexToBytes(&expected, expected_hex);
+
+    const input_list = RocList.fromSlice(u8, input);
+    defer input_list.decref(@alignOf(u8));
+
+    const digest = hash(input_list);
+    defer digest.decref(@alignOf(u8));
+
+    try expect(std.mem.eql(u8, bytesOf(digest), &expected));
+}
+
+test "sha256: empty input" {
+    try expectDigest(sha256, "", "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+}
+
+test "sha256: abc" {
+    try expectDigest(sha256, "abc", "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+}
+
+test "blake3: empty input" {
+    try expectDigest(blake3, "", "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262");
+}
+
+test "blake3: abc" {
+    try expectDigest(blake3, "abc", "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85");
+}
+
+test "constantTimeEq" {
+    const a = RocList.fromSlice(u8, "secret token");
+    defer a.decref(@alignOf(u8));
+    const b = RocList.fromSlice(u8, "secret token");
+    defer b.decref(@alignOf(u8));
+    const c = RocList.fromSlice(u8, "secret tokem");
+    defer c.decref(@alignOf(u8));
+    const d = RocList.fromSlice(u8, "secret");
+    defer d.decref(@alignOf(u8));
+
+    try expect(constantTimeEq(a, b));
+    try expect(!constantTimeEq(a, c));
+    try expect(!constantTimeEq(a, d));
+    try expect(constantTimeEq(RocList.empty(), RocList.empty()));
+}
@@ -23,6 +23,7 @@ pub const RocDec = extern struct {
     pub const max: RocDec = .{ .num = math.maxInt(i128) };
 
     pub const one_point_zero_i128: i128 = math.pow(i128, 10, RocDec.decimal_places);
+    const one_point_zero_u128: u128 = math.pow(u128, 10, RocDec.decimal_places);
     pub const one_point_zero: RocDec = .{ .num = one_point_zero_i128 };
 
     pub fn fromU64(num: u64) RocDec {
@@ -132,81 +133,70 @@ pub const RocDec = extern struct {
     }
 
     pub fn toStr(self: RocDec) RocStr {
-        // Special case
-        if (self.num == 0) {
-            return RocStr.init("0.0", 3);
-        }
+        var str_bytes: [max_str_length]u8 = undefined;
+        const bytes = self.toStrBuf(&str_bytes);
 
-        const num = self.num;
-        const is_negative = num < 0;
+        return RocStr.init(bytes.ptr, bytes.len);
+    }
 
-        // Format the backing i128 into an array of digit (ascii) characters (u8s)
-        var digit_bytes_storage: [max_digits + 1]u8 = undefined;
-        var num_digits = std.fmt.formatIntBuf(digit_bytes_storage[0..], num, 10, .lower, .{});
-        var digit_bytes: [*]u8 = digit_bytes_storage[0..];
+    /// Formats the number into `buf`, without allocating, and returns the part of `buf` it used.
+    /// The digits are written from the end of `buf` towards its start, in a single pass.
+    pub fn toStrBuf(self: RocDec, buf: *[max_str_length]u8) []const u8 {
+        const is_negative = self.num < 0;
+        const magnitude = math.absCast(self.num);
 
-        // space where we assemble all the characters that make up the final string
-        var str_bytes: [max_str_length]u8 = undefined;
-        var position: usize = 0;
+        var whole = magnitude / one_point_zero_u128;
+        // less than 10^18, so the digits after the decimal point only need 64-bit arithmetic
+        var fraction = @intCast(u64, magnitude % one_point_zero_u128);
 
-        // if negative, the first character is a negating minus
-        if (is_negative) {
-            str_bytes[position] = '-';
-            position += 1;
+        var position: usize = buf.len;
 
-            // but also, we have one fewer digit than we have characters
-            num_digits -= 1;
+        if (fraction == 0) {
+            // a single zero when all decimal digits are zero
+            position -= 1;
+            buf[position] = '0';
+        } else {
+            // drop the trailing zeros, but keep the leading ones, like in 0.00045
+            var digits: usize = decimal_places;
+            while (fraction % 10 == 0) {
+                fraction /= 10;
+                digits -= 1;
+            }
 
-            // and we drop the minus to make later arithmetic correct
-            digit_bytes += 1;
+            while (digits > 0) : (digits -= 1) {
+                position -= 1;
+                buf[position] = '0' + @intCast(u8, fraction % 10);
+                fraction /= 10;
+            }
         }
 
-        // Get the slice for before the decimal point
-        var before_digits_offset: usize = 0;
-        if (num_digits > decimal_places) {
-            // we have more digits than fit after the decimal point,
-            // so we must have digits before the decimal point
-            before_digits_offset = num_digits - decimal_places;
+        position -= 1;
+        buf[position] = '.';
 
-            for (digit_bytes[0..before_digits_offset]) |c| {
-                str_bytes[position] = c;
-                position += 1;
-            }
-        } else {
-            // otherwise there are no actual digits before the decimal point
-            // but we format it with a '0'
-            str_bytes[position] = '0';
-            position += 1;
+        // the whole part can have up to 21 digits, so it only fits in 64 bits once it's smaller
+        while (whole > math.maxInt(u64)) {
+            position -= 1;
+            buf[position] = '0' + @intCast(u8, whole % 10);
+            whole /= 10;
         }
 
-        // we've done everything before the decimal point, so now we can put the decimal point in
-        str_bytes[position] = '.';
-        position += 1;
+        var small_whole = @intCast(u64, whole);
+        while (true) {
+            position -= 1;
+            buf[position] = '0' + @intCast(u8, small_whole % 10);
+            small_whole /= 10;
 
-        const trailing_zeros: u6 = count_trailing_zeros_base10(num);
-        if (trailing_zeros == decimal_places) {
-            // add just a single zero if all decimal digits are zero
-            str_bytes[position] = '0';
-            position += 1;
-        } else {
-            // Figure out if we need to prepend any zeros to the after decimal point
-            // For example, for the number 0.000123 we need to prepend 3 zeros after the decimal point
-            const after_zeros_num = if (num_digits < decimal_places) decimal_places - num_digits else 0;
-
-            var i: usize = 0;
-            while (i < after_zeros_num) : (i += 1) {
-                str_bytes[position] = '0';
-                position += 1;
+            if (small_whole == 0) {
+                break;
             }
+        }
 
-            // otherwise append the decimal digits except the trailing zeros
-            for (digit_bytes[before_digits_offset .. num_digits - trailing_zeros]) |c| {
-                str_bytes[position] = c;
-                position += 1;
-            }
+        if (is_negative) {
+            position -= 1;
+            buf[position] = '-';
         }
 
-        return RocStr.init(&str_bytes, position);
+        return buf[position..];
     }
 
     pub fn eq(self: RocDec, other: RocDec) bool {
@@ -316,13 +306,11 @@ pub const RocDec = extern struct {
             }
         });
 
-        const unsigned_answer: i128 = mul_and_decimalize(self_u128, other_u128);
+        const unsigned_answer = mul_and_decimalize(self_u128, other_u128) orelse {
+            return overflow_bound(is_answer_negative);
+        };
 
-        if (is_answer_negative) {
-            return .{ .value = RocDec{ .num = -unsigned_answer }, .has_overflowed = false };
-        } else {
-            return .{ .value = RocDec{ .num = unsigned_answer }, .has_overflowed = false };
-        }
+        return from_magnitude(unsigned_answer, is_answer_negative);
     }
 
     pub fn mul(self: RocDec, other: RocDec) RocDec {
@@ -341,6 +329,49 @@ pub const RocDec = extern struct {
         return answer.value;
     }
 
+    /// `self * other + addend`, fused: the product is kept at full precision and only truncated
+    /// to 18 decimal places after the addition, so there is one rounding instead of two.
+    pub fn mulAddWithOverflow(self: RocDec, other: RocDec, addend: RocDec) WithOverflow(RocDec) {
+        const is_product_negative = (self.num < 0) != (other.num < 0);
+        const is_addend_negative = addend.num < 0;
+
+        // both are below 2^255, so adding them can't overflow
+        const product = mul_u128(math.absCast(self.num), math.absCast(other.num));
+        const scaled_addend = mul_u128(math.absCast(addend.num), one_point_zero_u128);
+
+        var sum: U256 = undefined;
+        var is_answer_negative: bool = undefined;
+
+        if (is_product_negative == is_addend_negative) {
+            sum = add_u256(product, scaled_addend);
+            is_answer_negative = is_product_negative;
+        } else if (lt_u256(product, scaled_addend)) {
+            sum = sub_u256(scaled_addend, product);
+            is_answer_negative = is_addend_negative;
+        } else {
+            sum = sub_u256(product, scaled_addend);
+            is_answer_negative = is_product_negative;
+        }
+
+        // the quotient wouldn't fit in 128 bits
+        if (sum.hi >= one_point_zero_u128) {
+            return overflow_bound(is_answer_negative);
+        }
+
+        return from_magnitude(div_u256_by_u128(sum, one_point_zero_u128), is_answer_negative);
+    }
+
+    pub fn mulAdd(self: RocDec, other: RocDec, addend: RocDec) RocDec {
+        const answer = RocDec.mulAddWithOverflow(self, other, addend);
+
+        if (answer.has_overflowed) {
+            roc_panic("Decimal multiply-add overflowed!", 0);
+            unreachable;
+        } else {
+            return answer.value;
+        }
+    }
+
     pub fn div(self: RocDec, other: RocDec) RocDec {
         const numerator_i128 = self.num;
         const denominator_i128 = other.num;
@@ -398,12 +429,18 @@ pub const RocDec = extern struct {
         };
         const denominator_u128 = @intCast(u128, denominator_abs_i128);
 
-        const numerator_u256: U256 = mul_u128(numerator_u128, math.pow(u128, 10, decimal_places));
+        const numerator_u256: U256 = mul_u128(numerator_u128, one_point_zero_u128);
+
+        // the quotient wouldn't fit in 128 bits
+        if (numerator_u256.hi >= denominator_u128) {
+            @panic("TODO runtime exception for overflow when dividing!");
+        }
+
         const answer = div_u256_by_u128(numerator_u256, denominator_u128);
 
         var unsigned_answer: i128 = undefined;
-        if (answer.hi == 0 and answer.lo <= math.maxInt(i128)) {
-            unsigned_answer = @intCast(i128, answer.lo);
+        if (answer <= math.maxInt(i128)) {
+            unsigned_answer = @intCast(i128, answer);
         } else {
             @panic("TODO runtime exception for overflow when dividing!");
         }
@@ -412,29 +449,55 @@ pub const RocDec = extern struct {
     }
 };
 
-// A number has `k` trailling zeros if `10^k` divides into it cleanly
-inline fn count_trailing_zeros_base10(input: i128) u6 {
-    if (input == 0) {
-        // this should not happen in practice
-        return 0;
+/// The result of an operation that overflowed, which is the bound on the side it overflowed on
+fn overflow_bound(is_negative: bool) WithOverflow(RocDec) {
+    if (is_negative) {
+        return .{ .value = RocDec.min, .has_overflowed = true };
+    } else {
+        return .{ .value = RocDec.max, .has_overflowed = true };
     }
+}
 
-    var count: u6 = 0;
-    var k: i128 = 1;
+/// The Dec whose backing number has the absolute value `magnitude`, and is negative when
+/// `is_negative` is
+fn from_magnitude(magnitude: u128, is_negative: bool) WithOverflow(RocDec) {
+    if (is_negative) {
+        if (magnitude > (1 << 127)) {
+            return overflow_bound(true);
+        }
 
-    while (true) {
-        if (@mod(input, std.math.pow(i128, 10, k)) == 0) {
-            count += 1;
-            k += 1;
-        } else {
-            break;
+        // wraps for 2^127, which is the magnitude of the smallest i128
+        return .{ .value = RocDec{ .num = -%@bitCast(i128, magnitude) }, .has_overflowed = false };
+    } else {
+        if (magnitude > math.maxInt(i128)) {
+            return overflow_bound(false);
         }
+
+        return .{ .value = RocDec{ .num = @intCast(i128, magnitude) }, .has_overflowed = false };
     }
+}
+
+fn add_u256(a: U256, b: U256) U256 {
+    var lo: u128 = undefined;
+    const carry = @addWithOverflow(u128, a.lo, b.lo, &lo);
+
+    return .{ .hi = a.hi +% b.hi +% @boolToInt(carry), .lo = lo };
+}
+
+fn sub_u256(a: U256, b: U256) U256 {
+    var lo: u128 = undefined;
+    const borrow = @subWithOverflow(u128, a.lo, b.lo, &lo);
 
-    return count;
+    return .{ .hi = a.hi -% b.hi -% @boolToInt(borrow), .lo = lo };
 }
 
-fn mul_and_decimalize(a: u128, b: u128) i128 {
+fn lt_u256(a: U256, b: U256) bool {
+    return a.hi < b.hi or (a.hi == b.hi and a.lo < b.lo);
+}
+
+// Multiply two 128-bit ints and divide the result by 10^DECIMAL_PLACES, or null when the result
+// doesn't fit in 128 bits
+fn mul_and_decimalize(a: u128, b: u128) ?u128 {
     const answer_u256 = mul_u128(a, b);
 
     var lhs_hi = answer_u256.hi;
@@ -541,183 +604,72 @@ fn mul_and_decimalize(a: u128, b: u128) i128 {
     overflowed = overflowed or @addWithOverflow(u128, d, c_carry3, &d);
     overflowed = overflowed or @addWithOverflow(u128, d, c_carry4, &d);
 
-    if (overflowed) {
-        @panic("TODO runtime exception for overflow!");
+    // the bits of d above the lowest 59 would be shifted out of the result
+    if (overflowed or d >> 59 != 0) {
+        return null;
     }
 
     // Final 512bit value is d, c, b, a
     // need to left shift 321 times
     // 315 - 256 is 59. So left shift d, c 59 times.
-    return @intCast(i128, c >> 59 | (d << (128 - 59)));
+    return c >> 59 | (d << (128 - 59));
 }
 
-// Multiply two 128-bit ints and divide the result by 10^DECIMAL_PLACES
-//
-// Adapted from https://github.com/nlordell/ethnum-rs/blob/c9ed57e131bffde7bcc8274f376e5becf62ef9ac/src/intrinsics/native/divmod.rs
-// Copyright (c) 2020 Nicholas Rodrigues Lordello
-// Licensed under the Apache License version 2.0
+// Divide a 256-bit int by a 128-bit int, when the quotient fits in 128 bits (numer.hi < denom).
 //
-// When translating this to Zig, we often have to use math.shr/shl instead of >>/<<
-// This is because casting to the right types for Zig can be kind of tricky.
-// See https://github.com/ziglang/zig/issues/7605
-fn div_u256_by_u128(numer: U256, denom: u128) U256 {
-    const N_UDWORD_BITS: u8 = 128;
-    const N_UTWORD_BITS: u9 = 256;
-
-    var q: U256 = undefined;
-    var r: U256 = undefined;
-    var sr: u8 = undefined;
-
-    // special case
+// This is Knuth's algorithm D, with 64-bit digits, as `divlu` in Hacker's Delight (2nd edition,
+// section 9-4) does it for a double word divided by a word. It takes two 128-bit divisions,
+// instead of a loop over every bit of the numerator.
+fn div_u256_by_u128(numer: U256, denom: u128) u128 {
+    // 0 X
+    // ---
+    // 0 X
     if (numer.hi == 0) {
-        // 0 X
-        // ---
-        // 0 X
-        return .{
-            .hi = 0,
-            .lo = numer.lo / denom,
-        };
+        return numer.lo / denom;
     }
 
-    // numer.hi != 0
-    if (denom == 0) {
-        // K X
-        // ---
-        // 0 0
-        return .{
-            .hi = 0,
-            .lo = numer.hi / denom,
-        };
-    } else {
-        // K X
-        // ---
-        // 0 K
-        // NOTE: Modified from `if (d.low() & (d.low() - 1)) == 0`.
-        if (math.isPowerOfTwo(denom)) {
-            // if d is a power of 2
-            if (denom == 1) {
-                return numer;
-            }
+    const b: u128 = 1 << 64;
 
-            sr = @ctz(u128, denom);
+    // normalize, so the highest bit of the divisor is set
+    const s = @intCast(u7, @clz(u128, denom));
+    const v = denom << s;
+    const vn1 = v >> 64;
+    const vn0 = v & (b - 1);
 
-            return .{
-                .hi = math.shr(u128, numer.hi, sr),
-                .lo = math.shl(u128, numer.hi, N_UDWORD_BITS - sr) | math.shr(u128, numer.lo, sr),
-            };
-        }
+    const un64 = (numer.hi << s) | math.shr(u128, numer.lo, 128 - @as(u8, s));
+    const un10 = numer.lo << s;
+    const un1 = un10 >> 64;
+    const un0 = un10 & (b - 1);
 
-        // K X
-        // ---
-        // 0 K
-        var denom_leading_zeros = @clz(u128, denom);
-        var numer_hi_leading_zeros = @clz(u128, numer.hi);
-        sr = 1 + N_UDWORD_BITS + denom_leading_zeros - numer_hi_leading_zeros;
-        // 2 <= sr <= N_UTWORD_BITS - 1
-        // q.all = n.all << (N_UTWORD_BITS - sr);
-        // r.all = n.all >> sr;
-        // #[allow(clippy::comparison_chain)]
-        if (sr == N_UDWORD_BITS) {
-            q = .{
-                .hi = numer.lo,
-                .lo = 0,
-            };
-            r = .{
-                .hi = 0,
-                .lo = numer.hi,
-            };
-        } else if (sr < N_UDWORD_BITS) {
-            // 2 <= sr <= N_UDWORD_BITS - 1
-            q = .{
-                .hi = math.shl(u128, numer.lo, N_UDWORD_BITS - sr),
-                .lo = 0,
-            };
-            r = .{
-                .hi = math.shr(u128, numer.hi, sr),
-                .lo = math.shl(u128, numer.hi, N_UDWORD_BITS - sr) | math.shr(u128, numer.lo, sr),
-            };
-        } else {
-            // N_UDWORD_BITS + 1 <= sr <= N_UTWORD_BITS - 1
-            q = .{
-                .hi = math.shl(u128, numer.hi, N_UTWORD_BITS - sr) | math.shr(u128, numer.lo, sr - N_UDWORD_BITS),
-                .lo = math.shl(u128, numer.lo, N_UTWORD_BITS - sr),
-            };
-            r = .{
-                .hi = 0,
-                .lo = math.shr(u128, numer.hi, sr - N_UDWORD_BITS),
-            };
-        }
-    }
+    // the high 64 bits of the quotient
+    var q1 = un64 / vn1;
+    var rhat = un64 - q1 * vn1;
 
-    // Not a special case
-    // q and r are initialized with:
-    // q.all = n.all << (N_UTWORD_BITS - sr);
-    // r.all = n.all >> sr;
-    // 1 <= sr <= N_UTWORD_BITS - 1
-    var carry: u128 = 0;
-
-    while (sr > 0) {
-        // r:q = ((r:q)  << 1) | carry
-        r.hi = (r.hi << 1) | (r.lo >> (N_UDWORD_BITS - 1));
-        r.lo = (r.lo << 1) | (q.hi >> (N_UDWORD_BITS - 1));
-        q.hi = (q.hi << 1) | (q.lo >> (N_UDWORD_BITS - 1));
-        q.lo = (q.lo << 1) | carry;
-
-        // carry = 0;
-        // if (r.all >= d.all)
-        // {
-        //     r.all -= d.all;
-        //      carry = 1;
-        // }
-        // NOTE: Modified from `(d - r - 1) >> (N_UTWORD_BITS - 1)` to be an
-        // **arithmetic** shift.
-
-        var lo: u128 = undefined;
-        var lo_overflowed: bool = undefined;
-        var hi: u128 = undefined;
-
-        lo_overflowed = @subWithOverflow(u128, denom, r.lo, &lo);
-        hi = 0 -% @intCast(u128, @bitCast(u1, lo_overflowed)) -% r.hi;
-
-        lo_overflowed = @subWithOverflow(u128, lo, 1, &lo);
-        hi = hi -% @intCast(u128, @bitCast(u1, lo_overflowed));
-
-        // NOTE: this U256 was originally created by:
-        //
-        // ((hi as i128) >> 127).as_u256()
-        //
-        // As an implementation of `as_u256`, we wrap a negative value around to the maximum value of U256.
-
-        var s_u128 = math.shr(u128, hi, 127);
-        var s_hi: u128 = undefined;
-        var s_lo: u128 = undefined;
-        if (s_u128 == 1) {
-            s_hi = math.maxInt(u128);
-            s_lo = math.maxInt(u128);
-        } else {
-            s_hi = 0;
-            s_lo = 0;
+    while (q1 >= b or q1 * vn0 > b * rhat + un1) {
+        q1 -= 1;
+        rhat += vn1;
+
+        if (rhat >= b) {
+            break;
         }
-        var s = .{
-            .hi = s_hi,
-            .lo = s_lo,
-        };
+    }
 
-        carry = s.lo & 1;
+    const un21 = (un64 *% b +% un1) -% q1 *% v;
 
-        // var (lo, carry) = r.lo.overflowing_sub(denom & s.lo);
-        lo_overflowed = @subWithOverflow(u128, r.lo, (denom & s.lo), &lo);
-        hi = r.hi -% @intCast(u128, @bitCast(u1, lo_overflowed));
+    // the low 64 bits of the quotient
+    var q0 = un21 / vn1;
+    rhat = un21 - q0 * vn1;
 
-        r = .{ .hi = hi, .lo = lo };
+    while (q0 >= b or q0 * vn0 > b * rhat + un0) {
+        q0 -= 1;
+        rhat += vn1;
 
-        sr -= 1;
+        if (rhat >= b) {
+            break;
+        }
     }
 
-    var hi = (q.hi << 1) | (q.lo >> (127));
-    var lo = (q.lo << 1) | carry;
-
-    return .{ .hi = hi, .lo = lo };
+    return q1 * b + q0;
 }
 
 const testing = std.testing;
@@ -985,6 +937,29 @@ test "toStr: 0" {
     try expectEqualSlices(u8, res_slice, res_roc_str.asSlice());
 }
 
+test "toStrBuf: -123.45" {
+    var dec: RocDec = .{ .num = -123450000000000000000 };
+    var buf: [RocDec.max_str_length]u8 = undefined;
+
+    try expectEqualSlices(u8, "-123.45", dec.toStrBuf(&buf));
+}
+
+test "toStrBuf: RocDec.max and RocDec.min" {
+    var buf: [RocDec.max_str_length]u8 = undefined;
+
+    try expectEqualSlices(u8, "170141183460469231731.687303715884105727", RocDec.max.toStrBuf(&buf));
+    try expectEqualSlices(u8, "-170141183460469231731.687303715884105728", RocDec.min.toStrBuf(&buf));
+}
+
+test "toStrBuf: leading zeros after the decimal point" {
+    var buf: [RocDec.max_str_length]u8 = undefined;
+
+    try expectEqualSlices(u8, "0.00045", (RocDec{ .num = 450000000000000 }).toStrBuf(&buf));
+    try expectEqualSlices(u8, "-0.00045", (RocDec{ .num = -450000000000000 }).toStrBuf(&buf));
+    try expectEqualSlices(u8, "0.000000000000000001", (RocDec{ .num = 1 }).toStrBuf(&buf));
+    try expectEqualSlices(u8, "-1.000000000000000001", (RocDec{ .num = -1000000000000000001 }).toStrBuf(&buf));
+}
+
 test "add: 0" {
     var dec: RocDec = .{ .num = 0 };
 
@@ -1027,6 +1002,83 @@ test "mul: by 2" {
     try expectEqual(RocDec.fromU64(30), dec.mul(RocDec.fromU64(2)));
 }
 
+test "mul: overflow" {
+    var dec: RocDec = RocDec.max;
+    var res = dec.mulWithOverflow(RocDec.fromU64(2));
+
+    try expect(res.has_overflowed);
+    try expectEqual(RocDec.max, res.value);
+}
+
+test "mul: negative overflow" {
+    var dec: RocDec = RocDec.max;
+    var res = dec.mulWithOverflow(.{ .num = -2 * RocDec.one_point_zero_i128 });
+
+    try expect(res.has_overflowed);
+    try expectEqual(RocDec.min, res.value);
+}
+
+test "mulAdd: 1.5 * 2 + 0.25" {
+    var dec: RocDec = RocDec.fromStr(RocStr.init("1.5", 3)).?;
+    var addend: RocDec = RocDec.fromStr(RocStr.init("0.25", 4)).?;
+    var res: RocDec = RocDec.fromStr(RocStr.init("3.25", 4)).?;
+
+    try expectEqual(res, dec.mulAdd(RocDec.fromU64(2), addend));
+}
+
+test "mulAdd: rounds once" {
+    // 0.000000000000000001 * 0.5 is truncated to 0 on its own, but not when -1 is added first
+    var dec: RocDec = .{ .num = 1 };
+    var half: RocDec = .{ .num = 500000000000000000 };
+    var minus_one: RocDec = .{ .num = -RocDec.one_point_zero_i128 };
+
+    try expectEqual(RocDec{ .num = -999999999999999999 }, dec.mulAdd(half, minus_one));
+    try expectEqual(minus_one, dec.mul(half).add(minus_one));
+}
+
+test "mulAdd: overflow" {
+    var dec: RocDec = RocDec.max;
+    var res = dec.mulAddWithOverflow(RocDec.one_point_zero, RocDec.one_point_zero);
+
+    try expect(res.has_overflowed);
+    try expectEqual(RocDec.max, res.value);
+}
+
+test "mulAdd: negative overflow" {
+    var dec: RocDec = RocDec.min;
+    var minus_one: RocDec = .{ .num = -RocDec.one_point_zero_i128 };
+
+    // the addend pushes the answer past RocDec.min
+    var res = dec.mulAddWithOverflow(RocDec.one_point_zero, minus_one);
+    try expect(res.has_overflowed);
+    try expectEqual(RocDec.min, res.value);
+
+    // the product alone is past RocDec.min
+    res = RocDec.max.mulAddWithOverflow(.{ .num = -2 * RocDec.one_point_zero_i128 }, .{ .num = 0 });
+    try expect(res.has_overflowed);
+    try expectEqual(RocDec.min, res.value);
+}
+
+test "mulAdd: the addend cancels an overflowing product" {
+    var dec: RocDec = RocDec.max;
+    var two: RocDec = RocDec.fromU64(2);
+
+    try expect(dec.mulWithOverflow(two).has_overflowed);
+
+    var res = dec.mulAddWithOverflow(two, .{ .num = -RocDec.max.num });
+    try expect(!res.has_overflowed);
+    try expectEqual(RocDec.max, res.value);
+}
+
+test "mulAdd: lands exactly on RocDec.min" {
+    var dec: RocDec = RocDec.max;
+    var minus_one: RocDec = .{ .num = -RocDec.one_point_zero_i128 };
+
+    var res = dec.mulAddWithOverflow(minus_one, .{ .num = -1 });
+    try expect(!res.has_overflowed);
+    try expectEqual(RocDec.min, res.value);
+}
+
 test "div: 0 / 2" {
     var dec: RocDec = RocDec.fromU64(0);
 
@@ -1090,6 +1142,49 @@ test "div: 500 / 1000" {
     try expectEqual(RocDec.fromStr(roc_str), number1.div(number2));
 }
 
+test "div: 100000000000000000000 / 4" {
+    var number1: RocDec = .{ .num = 100000000000000000000000000000000000000 };
+    var number2: RocDec = RocDec.fromU64(4);
+
+    try expectEqual(RocDec{ .num = 25000000000000000000000000000000000000 }, number1.div(number2));
+}
+
+test "div: 1000 / 30 (divisor above 2^64)" {
+    var number1: RocDec = RocDec.fromU64(1000);
+    var number2: RocDec = RocDec.fromU64(30);
+
+    try expectEqual(RocDec{ .num = 33333333333333333333 }, number1.div(number2));
+}
+
+test "div_u256_by_u128: divisor above 2^64" {
+    try expectEqual(
+        @as(u128, 0xfffffffffffffffe123456789abcdef1),
+        div_u256_by_u128(.{ .hi = 0xffffffffffffffff, .lo = 0x123456789abcdef0123456789abcdef0 }, 0x10000000000000001),
+    );
+
+    // the highest bit of the divisor is already set, so it isn't shifted
+    try expectEqual(
+        @as(u128, 0x1249249249249237ec687d6343eb1a3),
+        div_u256_by_u128(.{ .hi = 0x0123456789abcdef0123456789abcdef, .lo = 0xfedcba9876543210fedcba9876543210 }, 0xfedcba9876543210fedcba9876543210),
+    );
+}
+
+test "div_u256_by_u128: numer.hi just below denom" {
+    const max_u128: u128 = math.maxInt(u128);
+    const denoms = [_]u128{ 0x10000000000000001, 1000000000000000000, max_u128 };
+    const quotients_of_lo_zero = [_]u128{
+        340282366920938463444927863358058659840,
+        340282366920938463123092240510829747992,
+        340282366920938463463374607431768211454,
+    };
+
+    for (denoms) |denom, i| {
+        // ((denom - 1) * 2^128 + 2^128 - 1) / denom is just below 2^128
+        try expectEqual(max_u128, div_u256_by_u128(.{ .hi = denom - 1, .lo = max_u128 }, denom));
+        try expectEqual(quotients_of_lo_zero[i], div_u256_by_u128(.{ .hi = denom - 1, .lo = 0 }, denom));
+    }
+}
+
 // exports
 
 pub fn fromStr(arg: RocStr) callconv(.C) num_.NumParseResult(i128) {
@@ -1132,6 +1227,10 @@ pub fn mulC(arg1: RocDec, arg2: RocDec) callconv(.C) WithOverflow(RocDec) {
     return @call(.{ .modifier = always_inline }, RocDec.mulWithOverflow, .{ arg1, arg2 });
 }
 
+pub fn mulAddC(arg1: RocDec, arg2: RocDec, arg3: RocDec) callconv(.C) WithOverflow(RocDec) {
+    return @call(.{ .modifier = always_inline }, RocDec.mulAddWithOverflow, .{ arg1, arg2, arg3 });
+}
+
 pub fn divC(arg1: RocDec, arg2: RocDec) callconv(.C) i128 {
     return @call(.{ .modifier = always_inline }, RocDec.div, .{ arg1, arg2 }).num;
 }
@@ -1159,3 +1258,7 @@ pub fn mulOrPanicC(arg1: RocDec, arg2: RocDec) callconv(.C) RocDec {
 pub fn mulSaturatedC(arg1: RocDec, arg2: RocDec) callconv(.C) RocDec {
     return @call(.{ .modifier = always_inline }, RocDec.mulSaturated, .{ arg1, arg2 });
 }
+
+pub fn mulAddOrPanicC(arg1: RocDec, arg2: RocDec, arg3: RocDec) callconv(.C) RocDec {
+    return @call(.{ .modifier = always_inline }, RocDec.mulAdd, .{ arg1, arg2, arg3 });
+}
@@ -2,6 +2,8 @@ const std = @import("std");
 const builtin = @import("builtin");
 
 const Atomic = std.atomic.Atomic;
+const RocStr = @import("str.zig").RocStr;
+const stdio = @import("stdio.zig");
 
 const O_RDWR: c_int = 2;
 const O_CREAT: c_int = 64;
@@ -100,3 +102,13 @@ pub fn notifyParentExpect(shared_buffer: [*]u8) callconv(.C) void {
 pub fn notifyParentDbg(shared_buffer: [*]u8) callconv(.C) void {
     notifyParent(shared_buffer, 2);
 }
+
+// The default `roc_dbg`, for hosts which don't route `dbg` output to their own logging. It writes
+// through the `roc_stderr` hook, so hosts which only redirect stderr get `dbg` output too.
+pub fn dbgToStderr(loc: *const RocStr, msg: *const RocStr) callconv(.C) void {
+    stdio.writeStderr("[");
+    stdio.writeStderr(loc.asSlice());
+    stdio.writeStderr("] ");
+    stdio.writeStderr(msg.asSlice());
+    stdio.writeStderr("\n");
+}
@@ -537,6 +537,28 @@ pub fn listAppendUnsafe(
     return output;
 }
 
+/// A list of `count` copies of `element`. The copies are made without touching any refcounts;
+/// the caller increments the element once for every copy.
+pub fn listRepeat(
+    element: Opaque,
+    count: usize,
+    alignment: u32,
+    element_width: usize,
+) callconv(.C) RocList {
+    const output = RocList.allocateExact(alignment, count, element_width);
+
+    if (output.bytes) |target| {
+        if (element) |source| {
+            var i: usize = 0;
+            while (i < count) : (i += 1) {
+                @memcpy(target + i * element_width, source, element_width);
+            }
+        }
+    }
+
+    return output;
+}
+
 fn listAppend(list: RocList, alignment: u32, element: Opaque, element_width: usize, update_mode: UpdateMode) callconv(.C) RocList {
     const with_capacity = listReserve(list, alignment, 1, element_width, update_mode);
     return listAppendUnsafe(with_capacity, element, element_width);
@@ -662,6 +684,88 @@ pub fn listSublist(
     return RocList.empty();
 }
 
+/// A new list holding copies of `length` elements starting at `source`. The caller increments
+/// the copied elements.
+fn copyElements(source: [*]u8, length: usize, alignment: u32, element_width: usize) RocList {
+    const output = RocList.allocateExact(alignment, length, element_width);
+    if (output.bytes) |target| {
+        @memcpy(target, source, length * element_width);
+    }
+
+    return output;
+}
+
+/// The elements of `list`, `chunk_size` at a time; the last chunk has the elements which are
+/// left over. The outer list and every chunk are allocated once, at their final size.
+pub fn listChunksOf(
+    list: RocList,
+    alignment: u32,
+    element_width: usize,
+    chunk_size: usize,
+    inc_n_element: IncN,
+) callconv(.C) RocList {
+    const size = list.len();
+    if (size == 0 or chunk_size == 0) {
+        return RocList.empty();
+    }
+
+    const source_ptr = list.bytes orelse unreachable;
+    const count = size / chunk_size + @boolToInt(size % chunk_size != 0);
+    const output = RocList.allocateExact(@alignOf(RocList), count, @sizeOf(RocList));
+    const chunks = output.elements(RocList) orelse unreachable;
+
+    var i: usize = 0;
+    while (i < count) : (i += 1) {
+        const start = i * chunk_size;
+        const length = std.math.min(chunk_size, size - start);
+        chunks[i] = copyElements(source_ptr + start * element_width, length, alignment, element_width);
+    }
+
+    // every element is in exactly one chunk
+    i = 0;
+    while (i < size) : (i += 1) {
+        inc_n_element(source_ptr + i * element_width, 1);
+    }
+
+    return output;
+}
+
+/// Every run of `window_size` consecutive elements of `list`, in order. There are none when the
+/// list is shorter than `window_size`.
+pub fn listWindows(
+    list: RocList,
+    alignment: u32,
+    element_width: usize,
+    window_size: usize,
+    inc_n_element: IncN,
+) callconv(.C) RocList {
+    const size = list.len();
+    if (window_size == 0 or window_size > size) {
+        return RocList.empty();
+    }
+
+    const source_ptr = list.bytes orelse unreachable;
+    const count = size - window_size + 1;
+    const output = RocList.allocateExact(@alignOf(RocList), count, @sizeOf(RocList));
+    const windows = output.elements(RocList) orelse unreachable;
+
+    var i: usize = 0;
+    while (i < count) : (i += 1) {
+        windows[i] = copyElements(source_ptr + i * element_width, window_size, alignment, element_width);
+    }
+
+    // element `i` is in the windows starting from `i - window_size + 1` to `i`, the ones of them
+    // which exist
+    i = 0;
+    while (i < size) : (i += 1) {
+        const first_window = if (i + 1 > window_size) i + 1 - window_size else 0;
+        const last_window = std.math.min(i, count - 1);
+        inc_n_element(source_ptr + i * element_width, last_window - first_window + 1);
+    }
+
+    return output;
+}
+
 pub fn listDropAt(
     list: RocList,
     alignment: u32,
@@ -732,40 +836,97 @@ pub fn listDropAt(
     }
 }
 
-fn partition(source_ptr: [*]u8, transform: Opaque, wrapper: CompareFn, element_width: usize, low: isize, high: isize) isize {
-    const pivot = source_ptr + (@intCast(usize, high) * element_width);
-    var i = (low - 1); // Index of smaller element and indicates the right position of pivot found so far
-    var j = low;
+/// Runs shorter than this are sorted by insertion before they are merged
+const INSERTION_SORT_RUN = 16;
+
+/// A stable merge sort: elements which compare as EQ keep their order. Runs of
+/// `INSERTION_SORT_RUN` elements are sorted by insertion, then merged bottom-up, back and forth
+/// between the list and `scratch`. `scratch` has room for one more element than the list, which
+/// insertion sort uses to hold the element it is moving.
+fn mergeSort(source_ptr: [*]u8, scratch_ptr: [*]u8, data: Opaque, compare: CompareFn, element_width: usize, length: usize) void {
+    var run_start: usize = 0;
+    while (run_start < length) : (run_start += INSERTION_SORT_RUN) {
+        const run_end = std.math.min(run_start + INSERTION_SORT_RUN, length);
+        insertionSort(source_ptr, scratch_ptr + length * element_width, data, compare, element_width, run_start, run_end);
+    }
+
+    var from = source_ptr;
+    var to = scratch_ptr;
+    var width: usize = INSERTION_SORT_RUN;
+    while (width < length) : (width *= 2) {
+        var left: usize = 0;
+        while (left < length) : (left += 2 * width) {
+            const middle = std.math.min(left + width, length);
+            const right = std.math.min(left + 2 * width, length);
+            merge(from, to, data, compare, element_width, left, middle, right);
+        }
 
-    while (j <= high - 1) : (j += 1) {
-        const current_elem = source_ptr + (@intCast(usize, j) * element_width);
+        const previous = from;
+        from = to;
+        to = previous;
+    }
 
-        const ordering = wrapper(transform, current_elem, pivot);
-        const order = @intToEnum(utils.Ordering, ordering);
+    if (from != source_ptr) {
+        @memcpy(source_ptr, from, length * element_width);
+    }
+}
+
+fn insertionSort(source_ptr: [*]u8, temporary: [*]u8, data: Opaque, compare: CompareFn, element_width: usize, start: usize, end: usize) void {
+    var i = start + 1;
+    while (i < end) : (i += 1) {
+        const current = source_ptr + i * element_width;
+        if (!isGreater(data, compare, current - element_width, current)) {
+            continue;
+        }
 
-        switch (order) {
-            utils.Ordering.LT => {
-                // the current element is smaller than the pivot; swap it
-                i += 1;
-                swapElements(source_ptr, element_width, @intCast(usize, i), @intCast(usize, j));
-            },
-            utils.Ordering.EQ, utils.Ordering.GT => {},
+        // find where the element goes, after every element which isn't greater than it
+        @memcpy(temporary, current, element_width);
+        var j = i - 1;
+        while (j > start and isGreater(data, compare, source_ptr + (j - 1) * element_width, temporary)) {
+            j -= 1;
         }
+
+        const destination = source_ptr + j * element_width;
+        const moved_bytes = (i - j) * element_width;
+        std.mem.copyBackwards(u8, (destination + element_width)[0..moved_bytes], destination[0..moved_bytes]);
+        @memcpy(destination, temporary, element_width);
     }
-    swapElements(source_ptr, element_width, @intCast(usize, i + 1), @intCast(usize, high));
-    return (i + 1);
 }
 
-fn quicksort(source_ptr: [*]u8, transform: Opaque, wrapper: CompareFn, element_width: usize, low: isize, high: isize) void {
-    if (low < high) {
-        // partition index
-        const pi = partition(source_ptr, transform, wrapper, element_width, low, high);
+/// Merges the sorted elements `left..middle` and `middle..right` of `from` into the same indices
+/// of `to`, taking from the left when elements are equal.
+fn merge(from: [*]u8, to: [*]u8, data: Opaque, compare: CompareFn, element_width: usize, left: usize, middle: usize, right: usize) void {
+    var i = left;
+    var j = middle;
+    var k = left;
+
+    // the halves are already in order, which is common when sorting a mostly sorted list
+    if (middle == right or !isGreater(data, compare, from + (middle - 1) * element_width, from + middle * element_width)) {
+        @memcpy(to + left * element_width, from + left * element_width, (right - left) * element_width);
+        return;
+    }
 
-        _ = quicksort(source_ptr, transform, wrapper, element_width, low, pi - 1); // before pi
-        _ = quicksort(source_ptr, transform, wrapper, element_width, pi + 1, high); // after pi
+    while (i < middle and j < right) : (k += 1) {
+        if (isGreater(data, compare, from + i * element_width, from + j * element_width)) {
+            @memcpy(to + k * element_width, from + j * element_width, element_width);
+            j += 1;
+        } else {
+            @memcpy(to + k * element_width, from + i * element_width, element_width);
+            i += 1;
+        }
+    }
+
+    if (i < middle) {
+        @memcpy(to + k * element_width, from + i * element_width, (middle - i) * element_width);
+    } else if (j < right) {
+        @memcpy(to + k * element_width, from + j * element_width, (right - j) * element_width);
     }
 }
 
+inline fn isGreater(data: Opaque, compare: CompareFn, a: [*]u8, b: [*]u8) bool {
+    return @intToEnum(utils.Ordering, compare(data, a, b)) == utils.Ordering.GT;
+}
+
 pub fn listSortWith(
     input: RocList,
     caller: CompareFn,
@@ -781,10 +942,14 @@ pub fn listSortWith(
         inc_n_data(data, list.len());
     }
 
-    if (list.bytes) |source_ptr| {
-        const low = 0;
-        const high: isize = @intCast(isize, list.len()) - 1;
-        quicksort(source_ptr, data, caller, element_width, low, high);
+    if (list.len() > 1) {
+        const source_ptr = list.bytes orelse unreachable;
+
+        // the elements are moved around, never copied, so their refcounts don't change
+        const scratch = RocList.allocateExact(alignment, list.len() + 1, element_width);
+        defer scratch.decref(alignment);
+
+        mergeSort(source_ptr, scratch.bytes orelse unreachable, data, caller, element_width, list.len());
     }
 
     return list;
@@ -986,3 +1151,106 @@ test "listConcat: non-unique with unique overlapping" {
 
     try expect(concatted.eql(wanted));
 }
+
+fn compareHighBytes(_: ?[*]u8, a: ?[*]u8, b: ?[*]u8) callconv(.C) u8 {
+    const x = @ptrCast(*const u16, @alignCast(@alignOf(u16), a)).* >> 8;
+    const y = @ptrCast(*const u16, @alignCast(@alignOf(u16), b)).* >> 8;
+
+    const order = if (x < y) utils.Ordering.LT else if (x > y) utils.Ordering.GT else utils.Ordering.EQ;
+    return @enumToInt(order);
+}
+
+fn incNothing(_: ?[*]u8, _: usize) callconv(.C) void {}
+
+test "listSortWith: keeps the order of equal elements" {
+    // the high byte is the key, and the low byte is the original position
+    var elements: [100]u16 = undefined;
+    for (elements) |*element, i| {
+        element.* = @intCast(u16, ((i * 37) % 7) << 8 | i);
+    }
+
+    const list = RocList.fromSlice(u16, elements[0..]);
+    const sorted = listSortWith(list, compareHighBytes, null, incNothing, false, @alignOf(u16), @sizeOf(u16));
+    defer sorted.decref(@alignOf(u16));
+
+    const sorted_elements = sorted.elements(u16) orelse unreachable;
+    var i: usize = 1;
+    while (i < sorted.len()) : (i += 1) {
+        const previous = sorted_elements[i - 1];
+        const current = sorted_elements[i];
+
+        try expect(previous >> 8 < current >> 8 or (previous >> 8 == current >> 8 and previous < current));
+    }
+}
+
+test "listSortWith: sorts a reversed list" {
+    var elements: [256]u16 = undefined;
+    for (elements) |*element, i| {
+        element.* = @intCast(u16, 255 - i) << 8;
+    }
+
+    const list = RocList.fromSlice(u16, elements[0..]);
+    const sorted = listSortWith(list, compareHighBytes, null, incNothing, false, @alignOf(u16), @sizeOf(u16));
+    defer sorted.decref(@alignOf(u16));
+
+    const sorted_elements = sorted.elements(u16) orelse unreachable;
+    for (sorted_elements[0..sorted.len()]) |element, i| {
+        try expect(element >> 8 == i);
+    }
+}
+
+fn expectListOfU8(list: RocList, expected: []const u8) !void {
+    const elements = list.elements(u8) orelse unreachable;
+    try expect(std.mem.eql(u8, elements[0..list.len()], expected));
+}
+
+test "listChunksOf: the last chunk has the elements left over" {
+    const list = RocList.fromSlice(u8, &[_]u8{ 1, 2, 3, 4, 5 });
+    defer list.decref(@alignOf(u8));
+
+    const chunks = listChunksOf(list, @alignOf(u8), @sizeOf(u8), 2, incNothing);
+    const elements = chunks.elements(RocList) orelse unreachable;
+    defer {
+        for (elements[0..chunks.len()]) |chunk| {
+            chunk.decref(@alignOf(u8));
+        }
+        chunks.decref(@alignOf(RocList));
+    }
+
+    try expect(chunks.len() == 3);
+    try expectListOfU8(elements[0], &[_]u8{ 1, 2 });
+    try expectListOfU8(elements[1], &[_]u8{ 3, 4 });
+    try expectListOfU8(elements[2], &[_]u8{5});
+}
+
+test "listWindows: every run of consecutive elements" {
+    const list = RocList.fromSlice(u8, &[_]u8{ 1, 2, 3, 4 });
+    defer list.decref(@alignOf(u8));
+
+    const windows = listWindows(list, @alignOf(u8), @sizeOf(u8), 3, incNothing);
+    const elements = windows.elements(RocList) orelse unreachable;
+    defer {
+        for (elements[0..windows.len()]) |window| {
+            window.decref(@alignOf(u8));
+        }
+        windows.decref(@alignOf(RocList));
+    }
+
+    try expect(windows.len() == 2);
+    try expectListOfU8(elements[0], &[_]u8{ 1, 2, 3 });
+    try expectListOfU8(elements[1], &[_]u8{ 2, 3, 4 });
+
+    try expect(listWindows(list, @alignOf(u8), @sizeOf(u8), 5, incNothing).isEmpty());
+}
+
+test "listRepeat: copies the element" {
+    var element: u16 = 0x0102;
+    const list = listRepeat(@ptrCast(Opaque, &element), 3, @alignOf(u16), @sizeOf(u16));
+    defer list.decref(@alignOf(u16));
+
+    const elements = list.elements(u16) orelse unreachable;
+    try expect(list.len() == 3);
+    try expect(std.mem.eql(u16, elements[0..3], &[_]u16{ element, element, element }));
+
+    try expect(listRepeat(@ptrCast(Opaque, &element), 0, @alignOf(u16), @sizeOf(u16)).isEmpty());
+}
@@ -3,6 +3,8 @@ const builtin = @import("builtin");
 const math = std.math;
 const utils = @import("utils.zig");
 const expect = @import("expect.zig");
+const profile = @import("profile.zig");
+const stdio = @import("stdio.zig");
 const panic_utils = @import("panic.zig");
 
 const ROC_BUILTINS = "roc_builtins";
@@ -32,6 +34,9 @@ comptime {
     exportDecFn(dec.mulC, "mul_with_overflow");
     exportDecFn(dec.mulOrPanicC, "mul_or_panic");
     exportDecFn(dec.mulSaturatedC, "mul_saturated");
+
+    exportDecFn(dec.mulAddC, "mul_add_with_overflow");
+    exportDecFn(dec.mulAddOrPanicC, "mul_add_or_panic");
 }
 
 // List Module
@@ -43,12 +48,15 @@ comptime {
     exportListFn(list.listMap3, "map3");
     exportListFn(list.listMap4, "map4");
     exportListFn(list.listAppendUnsafe, "append_unsafe");
+    exportListFn(list.listRepeat, "repeat");
     exportListFn(list.listReserve, "reserve");
     exportListFn(list.listPrepend, "prepend");
     exportListFn(list.listWithCapacity, "with_capacity");
     exportListFn(list.listSortWith, "sort_with");
     exportListFn(list.listConcat, "concat");
     exportListFn(list.listSublist, "sublist");
+    exportListFn(list.listChunksOf, "chunks_of");
+    exportListFn(list.listWindows, "windows");
     exportListFn(list.listDropAt, "drop_at");
     exportListFn(list.listReplace, "replace");
     exportListFn(list.listReplaceInPlace, "replace_in_place");
@@ -86,10 +94,12 @@ comptime {
         num.exportAddWithOverflow(T, ROC_BUILTINS ++ "." ++ NUM ++ ".add_with_overflow.");
         num.exportAddOrPanic(T, ROC_BUILTINS ++ "." ++ NUM ++ ".add_or_panic.");
         num.exportAddSaturatedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".add_saturated.");
+        num.exportAddWrappedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".add_wrapped.");
 
         num.exportSubWithOverflow(T, ROC_BUILTINS ++ "." ++ NUM ++ ".sub_with_overflow.");
         num.exportSubOrPanic(T, ROC_BUILTINS ++ "." ++ NUM ++ ".sub_or_panic.");
         num.exportSubSaturatedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".sub_saturated.");
+        num.exportSubWrappedInt(T, ROC_BUILTINS ++ "." ++ NUM ++ ".sub_wrapped.");
 
         num.exportMulWithOverflow(T, WIDEINTS[i], ROC_BUILTINS ++ "." ++ NUM ++ ".mul_with_overflow.");
         num.exportMulOrPanic(T, WIDEINTS[i], ROC_BUILTINS ++ "." ++ NUM ++ ".mul_or_panic.");
@@ -178,11 +188,30 @@ comptime {
     }
 }
 
+// Crypto Module
+const crypto = @import("crypto.zig");
+comptime {
+    exportCryptoFn(crypto.sha256, "sha256");
+    exportCryptoFn(crypto.blake3, "blake3");
+    exportCryptoFn(crypto.constantTimeEq, "constant_time_eq");
+}
+
+// Random Module
+const random = @import("random.zig");
+comptime {
+    exportRandomFn(random.u64s, "u64s");
+    exportRandomFn(random.bytes, "bytes");
+}
+
 // Utils
 comptime {
     exportUtilsFn(utils.test_panic, "test_panic");
     exportUtilsFn(utils.increfRcPtrC, "incref_rc_ptr");
     exportUtilsFn(utils.decrefRcPtrC, "decref_rc_ptr");
+    exportUtilsFn(utils.increfRcPtrAtomicC, "incref_rc_ptr_atomic");
+    exportUtilsFn(utils.decrefRcPtrAtomicC, "decref_rc_ptr_atomic");
+    exportUtilsFn(utils.increfRcPtrNonAtomicC, "incref_rc_ptr_non_atomic");
+    exportUtilsFn(utils.decrefRcPtrNonAtomicC, "decref_rc_ptr_non_atomic");
     exportUtilsFn(utils.increfDataPtrC, "incref_data_ptr");
     exportUtilsFn(utils.decrefDataPtrC, "decref_data_ptr");
     exportUtilsFn(utils.isUnique, "is_unique");
@@ -190,6 +219,7 @@ comptime {
     exportUtilsFn(utils.allocateWithRefcountC, "allocate_with_refcount");
 
     @export(panic_utils.panic, .{ .name = "roc_builtins.utils." ++ "panic", .linkage = .Weak });
+    @export(utils.atomic_refcounts, .{ .name = "roc_builtins.utils." ++ "atomic_refcounts", .linkage = .Strong });
 
     if (builtin.target.cpu.arch != .wasm32) {
         exportUtilsFn(expect.expectFailedStartSharedBuffer, "expect_failed_start_shared_buffer");
@@ -197,10 +227,23 @@ comptime {
         exportUtilsFn(expect.notifyParentExpect, "notify_parent_expect");
         exportUtilsFn(expect.notifyParentDbg, "notify_parent_dbg");
 
+        // hosts can define their own, to route `dbg` output elsewhere
+        @export(expect.dbgToStderr, .{ .name = "roc_dbg", .linkage = .Weak });
+
+        // the standard I/O hooks, for hosts which don't choose where the streams, environment and
+        // clock of Roc code come from
+        @export(stdio.defaultStdout, .{ .name = "roc_stdout", .linkage = .Weak });
+        @export(stdio.defaultStderr, .{ .name = "roc_stderr", .linkage = .Weak });
+        @export(stdio.defaultStdin, .{ .name = "roc_stdin", .linkage = .Weak });
+        @export(stdio.defaultGetenv, .{ .name = "roc_getenv", .linkage = .Weak });
+        @export(stdio.defaultCurrentTime, .{ .name = "roc_current_time", .linkage = .Weak });
+
         // sets the buffer used for expect failures
         @export(expect.setSharedBuffer, .{ .name = "set_shared_buffer", .linkage = .Weak });
 
         exportUtilsFn(expect.readSharedBufferEnv, "read_env_shared_buffer");
+
+        exportUtilsFn(profile.writeProfile, "write_profile");
     }
 
     if (builtin.target.cpu.arch == .aarch64) {
@@ -248,6 +291,12 @@ fn exportListFn(comptime func: anytype, comptime func_name: []const u8) void {
 fn exportDecFn(comptime func: anytype, comptime func_name: []const u8) void {
     exportBuiltinFn(func, "dec." ++ func_name);
 }
+fn exportCryptoFn(comptime func: anytype, comptime func_name: []const u8) void {
+    exportBuiltinFn(func, "crypto." ++ func_name);
+}
+fn exportRandomFn(comptime func: anytype, comptime func_name: []const u8) void {
+    exportBuiltinFn(func, "random." ++ func_name);
+}
 
 fn exportUtilsFn(comptime func: anytype, comptime func_name: []const u8) void {
     exportBuiltinFn(func, "utils." ++ func_name);
@@ -236,13 +236,15 @@ pub fn exportToIntCheckingMaxAndMin(comptime From: type, comptime To: type, comp
     @export(f, .{ .name = name ++ @typeName(From), .linkage = .Strong });
 }
 
+// The bytes are read as little-endian on every target, so a program gets the same number
+// from the same bytes wherever it runs.
 pub fn bytesToU16C(arg: RocList, position: usize) callconv(.C) u16 {
     return @call(.{ .modifier = always_inline }, bytesToU16, .{ arg, position });
 }
 
 fn bytesToU16(arg: RocList, position: usize) u16 {
     const bytes = @ptrCast([*]const u8, arg.bytes);
-    return @bitCast(u16, [_]u8{ bytes[position], bytes[position + 1] });
+    return std.mem.readIntLittle(u16, bytes[position..][0..2]);
 }
 
 pub fn bytesToU32C(arg: RocList, position: usize) callconv(.C) u32 {
@@ -251,7 +253,7 @@ pub fn bytesToU32C(arg: RocList, position: usize) callconv(.C) u32 {
 
 fn bytesToU32(arg: RocList, position: usize) u32 {
     const bytes = @ptrCast([*]const u8, arg.bytes);
-    return @bitCast(u32, [_]u8{ bytes[position], bytes[position + 1], bytes[position + 2], bytes[position + 3] });
+    return std.mem.readIntLittle(u32, bytes[position..][0..4]);
 }
 
 pub fn bytesToU64C(arg: RocList, position: usize) callconv(.C) u64 {
@@ -260,7 +262,7 @@ pub fn bytesToU64C(arg: RocList, position: usize) callconv(.C) u64 {
 
 fn bytesToU64(arg: RocList, position: usize) u64 {
     const bytes = @ptrCast([*]const u8, arg.bytes);
-    return @bitCast(u64, [_]u8{ bytes[position], bytes[position + 1], bytes[position + 2], bytes[position + 3], bytes[position + 4], bytes[position + 5], bytes[position + 6], bytes[position + 7] });
+    return std.mem.readIntLittle(u64, bytes[position..][0..8]);
 }
 
 pub fn bytesToU128C(arg: RocList, position: usize) callconv(.C) u128 {
@@ -269,7 +271,7 @@ pub fn bytesToU128C(arg: RocList, position: usize) callconv(.C) u128 {
 
 fn bytesToU128(arg: RocList, position: usize) u128 {
     const bytes = @ptrCast([*]const u8, arg.bytes);
-    return @bitCast(u128, [_]u8{ bytes[position], bytes[position + 1], bytes[position + 2], bytes[position + 3], bytes[position + 4], bytes[position + 5], bytes[position + 6], bytes[position + 7], bytes[position + 8], bytes[position + 9], bytes[position + 10], bytes[position + 11], bytes[position + 12], bytes[position + 13], bytes[position + 14], bytes[position + 15] });
+    return std.mem.readIntLittle(u128, bytes[position..][0..16]);
 }
 
 fn isMultipleOf(comptime T: type, lhs: T, rhs: T) bool {
@@ -339,6 +341,15 @@ pub fn exportAddSaturatedInt(comptime T: type, comptime name: []const u8) void {
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
 }
 
+pub fn exportAddWrappedInt(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T, other: T) callconv(.C) T {
+            return self +% other;
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
 pub fn exportAddOrPanic(comptime T: type, comptime name: []const u8) void {
     comptime var f = struct {
         fn func(self: T, other: T) callconv(.C) T {
@@ -398,6 +409,15 @@ pub fn exportSubSaturatedInt(comptime T: type, comptime name: []const u8) void {
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
 }
 
+pub fn exportSubWrappedInt(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T, other: T) callconv(.C) T {
+            return self -% other;
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
 pub fn exportSubOrPanic(comptime T: type, comptime name: []const u8) void {
     comptime var f = struct {
         fn func(self: T, other: T) callconv(.C) T {
@@ -496,11 +516,15 @@ pub fn exportMulWithOverflow(comptime T: type, comptime W: type, comptime name:
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
 }
 
+fn mulSaturatedInt(comptime T: type, comptime W: type, self: T, other: T) T {
+    // on overflow, mulWithOverflow returns the bound of T in the direction of the true product
+    return mulWithOverflow(T, W, self, other).value;
+}
+
 pub fn exportMulSaturatedInt(comptime T: type, comptime W: type, comptime name: []const u8) void {
     comptime var f = struct {
         fn func(self: T, other: T) callconv(.C) T {
-            const result = @call(.{ .modifier = always_inline }, mulWithOverflow, .{ T, W, self, other });
-            return result.value;
+            return @call(.{ .modifier = always_inline }, mulSaturatedInt, .{ T, W, self, other });
         }
     }.func;
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
@@ -572,3 +596,56 @@ pub fn exportCountOneBits(comptime T: type, comptime name: []const u8) void {
     }.func;
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
 }
+
+const testing = std.testing;
+const expectEqual = testing.expectEqual;
+
+test "mulSaturatedInt: i8" {
+    try expectEqual(@as(i8, -30), mulSaturatedInt(i8, i16, 10, -3));
+    try expectEqual(@as(i8, 127), mulSaturatedInt(i8, i16, 100, 2));
+    try expectEqual(@as(i8, -128), mulSaturatedInt(i8, i16, -100, 2));
+    try expectEqual(@as(i8, -128), mulSaturatedInt(i8, i16, -64, 2));
+    try expectEqual(@as(i8, 127), mulSaturatedInt(i8, i16, -128, -1));
+}
+
+test "mulSaturatedInt: u8" {
+    try expectEqual(@as(u8, 250), mulSaturatedInt(u8, u16, 125, 2));
+    try expectEqual(@as(u8, 255), mulSaturatedInt(u8, u16, 200, 2));
+    try expectEqual(@as(u8, 255), mulSaturatedInt(u8, u16, 255, 255));
+}
+
+test "mulSaturatedInt: i64 and u64" {
+    const max_i64 = std.math.maxInt(i64);
+    const min_i64 = std.math.minInt(i64);
+    const max_u64 = std.math.maxInt(u64);
+
+    try expectEqual(@as(i64, max_i64), mulSaturatedInt(i64, i128, max_i64, 2));
+    try expectEqual(@as(i64, min_i64), mulSaturatedInt(i64, i128, max_i64, -2));
+    try expectEqual(@as(i64, max_i64), mulSaturatedInt(i64, i128, min_i64, -1));
+    try expectEqual(@as(u64, max_u64), mulSaturatedInt(u64, u128, max_u64, max_u64));
+}
+
+test "mulSaturatedInt: i128" {
+    const max = std.math.maxInt(i128);
+    const min = std.math.minInt(i128);
+
+    try expectEqual(@as(i128, max), mulSaturatedInt(i128, i256, max, 2));
+    try expectEqual(@as(i128, min), mulSaturatedInt(i128, i256, max, -2));
+    try expectEqual(@as(i128, -max), mulSaturatedInt(i128, i256, max, -1));
+    try expectEqual(@as(i128, min), mulSaturatedInt(i128, i256, min, 2));
+    try expectEqual(@as(i128, max), mulSaturatedInt(i128, i256, min, -1));
+    try expectEqual(@as(i128, min), mulSaturatedInt(i128, i256, min, 1));
+    try expectEqual(@as(i128, 0), mulSaturatedInt(i128, i256, min, 0));
+
+    // 2^127 is one past the largest i128, but -2^127 is exactly the smallest
+    try expectEqual(@as(i128, max), mulSaturatedInt(i128, i256, 1 << 64, 1 << 63));
+    try expectEqual(@as(i128, min), mulSaturatedInt(i128, i256, -(1 << 64), 1 << 63));
+}
+
+test "mulSaturatedInt: u128" {
+    const max = std.math.maxInt(u128);
+
+    try expectEqual(@as(u128, 1 << 127), mulSaturatedInt(u128, u256, 1 << 64, 1 << 63));
+    try expectEqual(@as(u128, max), mulSaturatedInt(u128, u256, 1 << 64, 1 << 64));
+    try expectEqual(@as(u128, max), mulSaturatedInt(u128, u256, max, 2));
+}
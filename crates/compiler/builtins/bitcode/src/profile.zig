@@ -0,0 +1,38 @@
+const std = @import("std");
+const builtin = @import("builtin");
+
+// The counters of one proc or switch in a profiling build. Must match the LLVM backend.
+pub const ProfileEntry = extern struct {
+    name: [*:0]const u8,
+    counts: [*]const u64,
+    len: usize,
+};
+
+// Runs when a profiling build exits. Writes one line per entry: the name, then the counts,
+// all separated by tabs.
+pub fn writeProfile(path: [*:0]const u8, entries: [*]const ProfileEntry, len: usize) callconv(.C) void {
+    if (builtin.os.tag == .macos or builtin.os.tag == .linux) {
+        // there is nobody left to report a failure to; the next build just won't have a profile
+        writeProfileHelp(path, entries[0..len]) catch {};
+    }
+}
+
+fn writeProfileHelp(path: [*:0]const u8, entries: []const ProfileEntry) !void {
+    const file = try std.fs.createFileAbsoluteZ(path, .{});
+    defer file.close();
+
+    var buffered = std.io.bufferedWriter(file.writer());
+    const writer = buffered.writer();
+
+    for (entries) |entry| {
+        try writer.print("{s}", .{entry.name});
+
+        for (entry.counts[0..entry.len]) |count| {
+            try writer.print("\t{}", .{count});
+        }
+
+        try writer.writeByte('\n');
+    }
+
+    try buffered.flush();
+}
@@ -0,0 +1,106 @@
+const std = @import("std");
+const list = @import("list.zig");
+const RocList = list.RocList;
+
+const expect = std.testing.expect;
+
+/// The PCG64 (XSL-RR 128/64) generator that Random.roc implements. The bulk functions here must
+/// produce exactly the numbers that calling `Random.u64` over and over would.
+const Pcg64 = struct {
+    state: u128,
+    increment: u128,
+
+    const multiplier: u128 = 0x2360ED051FC65DA44385DF649FCCF645;
+
+    fn next(self: *Pcg64) u64 {
+        self.state = self.state *% multiplier +% self.increment;
+
+        const xored = @truncate(u64, self.state >> 64) ^ @truncate(u64, self.state);
+        const rotation = @intCast(u6, self.state >> 122);
+
+        return std.math.rotr(u64, xored, rotation);
+    }
+};
+
+/// `count` numbers from the generator with the given state and increment
+pub fn u64s(state: u128, increment: u128, count: usize) callconv(.C) RocList {
+    if (count == 0) {
+        return RocList.empty();
+    }
+
+    var generator = Pcg64{ .state = state, .increment = increment };
+    const output = RocList.allocateExact(@alignOf(u64), count, @sizeOf(u64));
+    const numbers = output.elements(u64) orelse unreachable;
+
+    var i: usize = 0;
+    while (i < count) : (i += 1) {
+        numbers[i] = generator.next();
+    }
+
+    return output;
+}
+
+/// `count` bytes from the generator with the given state and increment, eight bytes from each
+/// number, least significant first
+pub fn bytes(state: u128, increment: u128, count: usize) callconv(.C) RocList {
+    if (count == 0) {
+        return RocList.empty();
+    }
+
+    var generator = Pcg64{ .state = state, .increment = increment };
+    const output = RocList.allocateExact(@alignOf(u8), count, @sizeOf(u8));
+    const output_bytes = output.elements(u8) orelse unreachable;
+
+    var i: usize = 0;
+    while (i < count) : (i += 8) {
+        var number = generator.next();
+        var j: usize = i;
+        while (j < count and j < i + 8) : (j += 1) {
+            output_bytes[j] = @truncate(u8, number);
+            number >>= 8;
+        }
+    }
+
+    return output;
+}
+
+test "u64s: matches the reference PCG64 stream" {
+    // pcg64 seeded with pcg_setseq_128_srandom_r(42, 54), as in the PCG reference tests
+    var generator = Pcg64{ .state = 0, .increment = (54 << 1) | 1 };
+    _ = generator.next();
+    generator.state +%= 42;
+    _ = generator.next();
+
+    const numbers = u64s(generator.state, generator.increment, 3);
+    defer numbers.decref(@alignOf(u64));
+
+    const elements = numbers.elements(u64) orelse unreachable;
+    try expect(numbers.len() == 3);
+    try expect(elements[0] == 0x86b1da1d72062b68);
+    try expect(elements[1] == 0x1304aa46c9853d39);
+    try expect(elements[2] == 0xa3670e9e0dd50358);
+}
+
+test "bytes: takes eight bytes from each number" {
+    const state: u128 = 12345;
+    const increment: u128 = 67891;
+
+    const output = bytes(state, increment, 11);
+    defer output.decref(@alignOf(u8));
+
+    var generator = Pcg64{ .state = state, .increment = increment };
+    const first = generator.next();
+    const second = generator.next();
+
+    const output_bytes = output.elements(u8) orelse unreachable;
+    try expect(output.len() == 11);
+    try expect(output_bytes[0] == @truncate(u8, first));
+    try expect(output_bytes[7] == @truncate(u8, first >> 56));
+    try expect(output_bytes[8] == @truncate(u8, second));
+    try expect(output_bytes[10] == @truncate(u8, second >> 16));
+}
+
+test "u64s: empty" {
+    const output = u64s(1, 1, 0);
+    try expect(output.isEmpty());
+}
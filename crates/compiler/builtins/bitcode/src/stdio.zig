@@ -0,0 +1,84 @@
+const std = @import("std");
+
+// The standard I/O hooks of the platform ABI. Hosts can define these to decide where Roc code's
+// standard streams, environment and clock come from; the defaults below, which main.zig exports
+// weakly, use the process's own. The builtins' own tests link against those defaults.
+extern fn roc_stdout(bytes: [*]const u8, len: usize) callconv(.C) void;
+extern fn roc_stderr(bytes: [*]const u8, len: usize) callconv(.C) void;
+extern fn roc_stdin(buffer: [*]u8, capacity: usize) callconv(.C) usize;
+extern fn roc_getenv(name: [*]const u8, name_len: usize, value_len: *usize) callconv(.C) ?[*]const u8;
+extern fn roc_current_time() callconv(.C) i64;
+
+extern fn getenv(name: [*:0]const u8) ?[*:0]const u8;
+
+/// Write to stdout through the host's `roc_stdout`
+pub fn writeStdout(bytes: []const u8) void {
+    roc_stdout(bytes.ptr, bytes.len);
+}
+
+/// Write to stderr through the host's `roc_stderr`
+pub fn writeStderr(bytes: []const u8) void {
+    roc_stderr(bytes.ptr, bytes.len);
+}
+
+/// Read from stdin through the host's `roc_stdin`; returns how many bytes were read, 0 at the end
+pub fn readStdin(buffer: []u8) usize {
+    return roc_stdin(buffer.ptr, buffer.len);
+}
+
+/// The value of an environment variable, through the host's `roc_getenv`
+pub fn getEnv(name: []const u8) ?[]const u8 {
+    var value_len: usize = 0;
+    const value = roc_getenv(name.ptr, name.len, &value_len) orelse return null;
+
+    return value[0..value_len];
+}
+
+/// The current instant in nanoseconds since the Unix epoch, through the host's `roc_current_time`
+pub fn currentTime() i64 {
+    return roc_current_time();
+}
+
+pub fn defaultStdout(bytes: [*]const u8, len: usize) callconv(.C) void {
+    std.io.getStdOut().writeAll(bytes[0..len]) catch {};
+}
+
+pub fn defaultStderr(bytes: [*]const u8, len: usize) callconv(.C) void {
+    std.io.getStdErr().writeAll(bytes[0..len]) catch {};
+}
+
+pub fn defaultStdin(buffer: [*]u8, capacity: usize) callconv(.C) usize {
+    return std.io.getStdIn().read(buffer[0..capacity]) catch 0;
+}
+
+pub fn defaultGetenv(name: [*]const u8, name_len: usize, value_len: *usize) callconv(.C) ?[*]const u8 {
+    // libc wants a NUL-terminated name; no real environment variable has a longer one
+    var name_buffer: [256]u8 = undefined;
+    if (name_len >= name_buffer.len or std.mem.indexOfScalar(u8, name[0..name_len], 0) != null) {
+        return null;
+    }
+
+    std.mem.copy(u8, name_buffer[0..name_len], name[0..name_len]);
+    name_buffer[name_len] = 0;
+
+    const value = getenv(@ptrCast([*:0]const u8, &name_buffer)) orelse return null;
+    value_len.* = std.mem.len(value);
+
+    return value;
+}
+
+pub fn defaultCurrentTime() callconv(.C) i64 {
+    // an i64 of nanoseconds covers the years 1678 to 2262
+    const nanos = std.time.nanoTimestamp();
+
+    return @intCast(i64, std.math.clamp(nanos, std.math.minInt(i64), std.math.maxInt(i64)));
+}
+
+test "getEnv: unset and overlong names" {
+    try std.testing.expect(getEnv("ROC_SURELY_NOT_SET_ANYWHERE") == null);
+    try std.testing.expect(getEnv("A" ** 300) == null);
+}
+
+test "currentTime: the default clock is past 2020-01-01" {
+    try std.testing.expect(currentTime() > 1577836800 * std.time.ns_per_s);
+}
@@ -3,6 +3,7 @@ const RocList = @import("list.zig").RocList;
 const grapheme = @import("helpers/grapheme.zig");
 const UpdateMode = utils.UpdateMode;
 const std = @import("std");
+const builtin = @import("builtin");
 const mem = std.mem;
 const always_inline = std.builtin.CallOptions.Modifier.always_inline;
 const unicode = std.unicode;
@@ -20,8 +21,15 @@ const MASK_ISIZE: isize = std.math.minInt(isize);
 const MASK: usize = @bitCast(usize, MASK_ISIZE);
 const SEAMLESS_SLICE_BIT: usize = MASK;
 
-const SMALL_STR_MAX_LENGTH = SMALL_STRING_SIZE - 1;
-const SMALL_STRING_SIZE = @sizeOf(RocStr);
+// A small string stores its length, together with the small string flag, in the most significant
+// byte of its capacity, so that small strings have a negative capacity. The bytes of the string
+// come before it. That's the last byte on little-endian targets, and on big-endian ones the bytes
+// after it are not used.
+const SMALL_STR_LENGTH_BYTE = switch (builtin.cpu.arch.endian()) {
+    .Little => @sizeOf(RocStr) - 1,
+    .Big => 2 * @sizeOf(usize),
+};
+const SMALL_STR_MAX_LENGTH = SMALL_STR_LENGTH_BYTE;
 
 fn init_blank_small_string(comptime n: usize) [n]u8 {
     var prime_list: [n]u8 = undefined;
@@ -107,7 +115,7 @@ pub const RocStr = extern struct {
     // May have a larger capacity than the length.
     pub fn allocate(length: usize) RocStr {
         const element_width = 1;
-        const result_is_big = length >= SMALL_STRING_SIZE;
+        const result_is_big = length > SMALL_STR_MAX_LENGTH;
 
         if (result_is_big) {
             const capacity = utils.calculateCapacity(0, length, element_width);
@@ -115,7 +123,7 @@ pub const RocStr = extern struct {
         } else {
             var string = RocStr.empty();
 
-            string.asU8ptrMut()[@sizeOf(RocStr) - 1] = @intCast(u8, length) | 0b1000_0000;
+            string.asU8ptrMut()[SMALL_STR_LENGTH_BYTE] = @intCast(u8, length) | 0b1000_0000;
 
             return string;
         }
@@ -124,14 +132,14 @@ pub const RocStr = extern struct {
     // allocate space for a (big or small) RocStr, but put nothing in it yet.
     // Will have the exact same capacity as length if it is not a small string.
     pub fn allocateExact(length: usize) RocStr {
-        const result_is_big = length >= SMALL_STRING_SIZE;
+        const result_is_big = length > SMALL_STR_MAX_LENGTH;
 
         if (result_is_big) {
             return RocStr.allocateBig(length, length);
         } else {
             var string = RocStr.empty();
 
-            string.asU8ptrMut()[@sizeOf(RocStr) - 1] = @intCast(u8, length) | 0b1000_0000;
+            string.asU8ptrMut()[SMALL_STR_LENGTH_BYTE] = @intCast(u8, length) | 0b1000_0000;
 
             return string;
         }
@@ -232,7 +240,7 @@ pub const RocStr = extern struct {
         }
 
         if (self.str_bytes) |source_ptr| {
-            if (old_capacity > new_length) {
+            if (old_capacity >= new_length) {
                 var output = self;
                 output.setLen(new_length);
                 return output;
@@ -291,7 +299,7 @@ pub const RocStr = extern struct {
 
     pub fn len(self: RocStr) usize {
         if (self.isSmallStr()) {
-            return self.asArray()[@sizeOf(RocStr) - 1] ^ 0b1000_0000;
+            return self.asArray()[SMALL_STR_LENGTH_BYTE] ^ 0b1000_0000;
         } else {
             return self.str_len & (~SEAMLESS_SLICE_BIT);
         }
@@ -299,7 +307,7 @@ pub const RocStr = extern struct {
 
     pub fn setLen(self: *RocStr, length: usize) void {
         if (self.isSmallStr()) {
-            self.asU8ptrMut()[@sizeOf(RocStr) - 1] = @intCast(u8, length) | 0b1000_0000;
+            self.asU8ptrMut()[SMALL_STR_LENGTH_BYTE] = @intCast(u8, length) | 0b1000_0000;
         } else {
             self.str_len = length | (SEAMLESS_SLICE_BIT & self.str_len);
         }
@@ -336,7 +344,7 @@ pub const RocStr = extern struct {
     // null-terminated string, which has a much higher performance cost!
     fn isNullTerminated(self: RocStr) bool {
         const length = self.len();
-        const longest_small_str = @sizeOf(RocStr) - 1;
+        const longest_small_str = SMALL_STR_MAX_LENGTH;
 
         // NOTE: We want to compare length here, *NOT* check for isSmallStr!
         // This is because we explicitly want the empty string to be handled in
@@ -1782,6 +1790,28 @@ test "RocStr.concat: small concat small" {
     try expect(roc_str3.eq(result));
 }
 
+test "RocStr.concat: appending to a unique string reallocates a logarithmic number of times" {
+    const piece = RocStr.init("abc", 3);
+    defer piece.decref();
+
+    var result = RocStr.empty();
+    defer result.decref();
+
+    var reallocations: usize = 0;
+    var i: usize = 0;
+    while (i < 1000) : (i += 1) {
+        const old_capacity = result.getCapacity();
+        result = strConcat(result, piece);
+
+        if (result.getCapacity() != old_capacity) {
+            reallocations += 1;
+        }
+    }
+
+    try expectEqual(@as(usize, 3000), result.len());
+    try expect(reallocations < 20);
+}
+
 pub const RocListStr = extern struct {
     list_elements: ?[*]RocStr,
     list_length: usize,
@@ -125,6 +125,9 @@ pub const Inc = fn (?[*]u8) callconv(.C) void;
 pub const IncN = fn (?[*]u8, u64) callconv(.C) void;
 pub const Dec = fn (?[*]u8) callconv(.C) void;
 
+// The refcount of static data (e.g. string literals and constants evaluated at compile time),
+// which is never freed. Incrementing or decrementing it leaves it unchanged, without branching,
+// so static data must live in writable memory.
 const REFCOUNT_MAX_ISIZE: isize = 0;
 pub const REFCOUNT_ONE_ISIZE: isize = std.math.minInt(isize);
 pub const REFCOUNT_ONE: usize = @bitCast(usize, REFCOUNT_ONE_ISIZE);
@@ -150,30 +153,61 @@ const Refcount = enum {
 
 const RC_TYPE = Refcount.normal;
 
+/// Set by the compiler when an app is built with atomic refcounts, so that the
+/// refcount updates done inside of builtins are safe to perform across threads.
+pub var atomic_refcounts: bool = false;
+
+inline fn rcType() Refcount {
+    if (RC_TYPE == Refcount.normal and atomic_refcounts) {
+        return Refcount.atomic;
+    }
+
+    return RC_TYPE;
+}
+
 pub fn increfRcPtrC(ptr_to_refcount: *isize, amount: isize) callconv(.C) void {
-    if (RC_TYPE == Refcount.none) return;
-    // Ensure that the refcount is not whole program lifetime.
-    if (ptr_to_refcount.* != REFCOUNT_MAX_ISIZE) {
-        // Note: we assume that a refcount will never overflow.
-        // As such, we do not need to cap incrementing.
-        switch (RC_TYPE) {
-            Refcount.normal => {
-                const old = @bitCast(usize, ptr_to_refcount.*);
-                ptr_to_refcount.* += amount;
-                const new = @bitCast(usize, ptr_to_refcount.*);
-
-                if (DEBUG_INCDEC and builtin.target.cpu.arch != .wasm32) {
-                    const oldH = old - REFCOUNT_ONE + 1;
-                    const newH = new - REFCOUNT_ONE + 1;
-
-                    std.debug.print("| increment {*}: {} + {} = {}!\n", .{ ptr_to_refcount, oldH, amount, newH });
-                }
-            },
-            Refcount.atomic => {
+    return @call(.{ .modifier = always_inline }, increfRcPtr, .{ ptr_to_refcount, amount, rcType() });
+}
+
+pub fn increfRcPtrAtomicC(ptr_to_refcount: *isize, amount: isize) callconv(.C) void {
+    return @call(.{ .modifier = always_inline }, increfRcPtr, .{ ptr_to_refcount, amount, Refcount.atomic });
+}
+
+pub fn increfRcPtrNonAtomicC(ptr_to_refcount: *isize, amount: isize) callconv(.C) void {
+    return @call(.{ .modifier = always_inline }, increfRcPtr, .{ ptr_to_refcount, amount, RC_TYPE });
+}
+
+/// All ones for a normal refcount, and zero for the refcount of static data.
+inline fn nonStaticMask(refcount: isize) isize {
+    return -@as(isize, @boolToInt(refcount != REFCOUNT_MAX_ISIZE));
+}
+
+inline fn increfRcPtr(ptr_to_refcount: *isize, amount: isize, rc_type: Refcount) void {
+    if (rc_type == Refcount.none) return;
+    // Note: we assume that a refcount will never overflow.
+    // As such, we do not need to cap incrementing.
+    switch (rc_type) {
+        Refcount.normal => {
+            const old = ptr_to_refcount.*;
+            // Static data gets incremented by 0, which keeps it static.
+            ptr_to_refcount.* = old +% (amount & nonStaticMask(old));
+            const new = ptr_to_refcount.*;
+
+            if (DEBUG_INCDEC and builtin.target.cpu.arch != .wasm32) {
+                const oldH = @bitCast(usize, old) -% REFCOUNT_ONE +% 1;
+                const newH = @bitCast(usize, new) -% REFCOUNT_ONE +% 1;
+
+                std.debug.print("| increment {*}: {} + {} = {}!\n", .{ ptr_to_refcount, oldH, amount, newH });
+            }
+        },
+        Refcount.atomic => {
+            // Atomically adding 0 to static data would still make every thread fight over it,
+            // so check for it first.
+            if (ptr_to_refcount.* != REFCOUNT_MAX_ISIZE) {
                 _ = @atomicRmw(isize, ptr_to_refcount, std.builtin.AtomicRmwOp.Add, amount, Monotonic);
-            },
-            Refcount.none => unreachable,
-        }
+            }
+        },
+        Refcount.none => unreachable,
     }
 }
 
@@ -187,7 +221,25 @@ pub fn decrefRcPtrC(
     // this is of course unsafe, but we trust what we get from the llvm side
     var bytes = @ptrCast([*]isize, bytes_or_null);
 
-    return @call(.{ .modifier = always_inline }, decref_ptr_to_refcount, .{ bytes, alignment });
+    return @call(.{ .modifier = always_inline }, decref_ptr_to_refcount, .{ bytes, alignment, rcType() });
+}
+
+pub fn decrefRcPtrAtomicC(
+    bytes_or_null: ?[*]isize,
+    alignment: u32,
+) callconv(.C) void {
+    var bytes = @ptrCast([*]isize, bytes_or_null);
+
+    return @call(.{ .modifier = always_inline }, decref_ptr_to_refcount, .{ bytes, alignment, Refcount.atomic });
+}
+
+pub fn decrefRcPtrNonAtomicC(
+    bytes_or_null: ?[*]isize,
+    alignment: u32,
+) callconv(.C) void {
+    var bytes = @ptrCast([*]isize, bytes_or_null);
+
+    return @call(.{ .modifier = always_inline }, decref_ptr_to_refcount, .{ bytes, alignment, RC_TYPE });
 }
 
 pub fn decrefCheckNullC(
@@ -196,7 +248,7 @@ pub fn decrefCheckNullC(
 ) callconv(.C) void {
     if (bytes_or_null) |bytes| {
         const isizes: [*]isize = @ptrCast([*]isize, @alignCast(@sizeOf(isize), bytes));
-        return @call(.{ .modifier = always_inline }, decref_ptr_to_refcount, .{ isizes - 1, alignment });
+        return @call(.{ .modifier = always_inline }, decref_ptr_to_refcount, .{ isizes - 1, alignment, rcType() });
     }
 }
 
@@ -243,43 +295,47 @@ pub fn decref(
 
     const isizes: [*]isize = @ptrCast([*]isize, @alignCast(@alignOf(isize), bytes));
 
-    decref_ptr_to_refcount(isizes - 1, alignment);
+    decref_ptr_to_refcount(isizes - 1, alignment, rcType());
 }
 
 inline fn decref_ptr_to_refcount(
     refcount_ptr: [*]isize,
     alignment: u32,
+    rc_type: Refcount,
 ) void {
-    if (RC_TYPE == Refcount.none) return;
+    if (rc_type == Refcount.none) return;
     const extra_bytes = std.math.max(alignment, @sizeOf(usize));
-    // Ensure that the refcount is not whole program lifetime.
     const refcount: isize = refcount_ptr[0];
-    if (refcount != REFCOUNT_MAX_ISIZE) {
-        switch (RC_TYPE) {
-            Refcount.normal => {
-                const old = @bitCast(usize, refcount);
-                refcount_ptr[0] = refcount -% 1;
-                const new = @bitCast(usize, refcount -% 1);
-
-                if (DEBUG_INCDEC and builtin.target.cpu.arch != .wasm32) {
-                    const oldH = old - REFCOUNT_ONE + 1;
-                    const newH = new - REFCOUNT_ONE + 1;
-
-                    std.debug.print("| decrement {*}: {} - 1 = {}!\n", .{ refcount_ptr, oldH, newH });
-                }
-
-                if (refcount == REFCOUNT_ONE_ISIZE) {
-                    dealloc(@ptrCast([*]u8, refcount_ptr) - (extra_bytes - @sizeOf(usize)), alignment);
-                }
-            },
-            Refcount.atomic => {
-                var last = @atomicRmw(isize, &refcount_ptr[0], std.builtin.AtomicRmwOp.Sub, 1, Monotonic);
+    switch (rc_type) {
+        Refcount.normal => {
+            // Static data gets decremented by 0, which keeps it static.
+            const new = refcount -% (1 & nonStaticMask(refcount));
+            refcount_ptr[0] = new;
+
+            if (DEBUG_INCDEC and builtin.target.cpu.arch != .wasm32) {
+                const oldH = @bitCast(usize, refcount) -% REFCOUNT_ONE +% 1;
+                const newH = @bitCast(usize, new) -% REFCOUNT_ONE +% 1;
+
+                std.debug.print("| decrement {*}: {} - 1 = {}!\n", .{ refcount_ptr, oldH, newH });
+            }
+
+            // This is never true for static data
+            if (refcount == REFCOUNT_ONE_ISIZE) {
+                dealloc(@ptrCast([*]u8, refcount_ptr) - (extra_bytes - @sizeOf(usize)), alignment);
+            }
+        },
+        Refcount.atomic => {
+            // See increfRcPtr for why static data is checked for first.
+            if (refcount != REFCOUNT_MAX_ISIZE) {
+                var last = @atomicRmw(isize, &refcount_ptr[0], std.builtin.AtomicRmwOp.Sub, 1, .Release);
                 if (last == REFCOUNT_ONE_ISIZE) {
+                    // make sure every other thread is done with the allocation before freeing it
+                    @fence(.Acquire);
                     dealloc(@ptrCast([*]u8, refcount_ptr) - (extra_bytes - @sizeOf(usize)), alignment);
                 }
-            },
-            Refcount.none => unreachable,
-        }
+            }
+        },
+        Refcount.none => unreachable,
     }
 }
 
@@ -425,3 +481,17 @@ test "increfC, static data" {
     increfRcPtrC(ptr_to_refcount, 2);
     try std.testing.expectEqual(mock_rc, REFCOUNT_MAX_ISIZE);
 }
+
+test "decrefC, refcounted data" {
+    var mock_rc: isize = REFCOUNT_ONE_ISIZE + 17;
+    var ptr_to_refcount: *isize = &mock_rc;
+    decrefRcPtrC(@ptrCast([*]isize, ptr_to_refcount), @alignOf(isize));
+    try std.testing.expectEqual(mock_rc, REFCOUNT_ONE_ISIZE + 16);
+}
+
+test "decrefC, static data" {
+    var mock_rc: isize = REFCOUNT_MAX_ISIZE;
+    var ptr_to_refcount: *isize = &mock_rc;
+    decrefRcPtrC(@ptrCast([*]isize, ptr_to_refcount), @alignOf(isize));
+    try std.testing.expectEqual(mock_rc, REFCOUNT_MAX_ISIZE);
+}
@@ -0,0 +1,82 @@
+## Cryptographic hashes, and comparing secrets without leaking them through timing.
+##
+## ```
+## Crypto.sha256Str "abc" |> Crypto.toHex
+## # "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+## ```
+##
+## Unlike the `Hash` ability, which is fast but easy to collide on purpose, these hashes are meant
+## for checksums, content addresses and signatures.
+interface Crypto
+    exposes [
+        sha256,
+        sha256Str,
+        blake3,
+        blake3Str,
+        constantTimeEq,
+        toHex,
+    ]
+    imports [
+        Bool.{ Bool },
+        List,
+        Num.{ U8 },
+        Result.{ Result },
+        Str,
+    ]
+
+## The 32-byte [SHA-256](https://en.wikipedia.org/wiki/SHA-2) digest of some bytes.
+## ```
+## expect List.len (Crypto.sha256 []) == 32
+## ```
+sha256 : List U8 -> List U8
+
+## The 32-byte SHA-256 digest of the UTF-8 bytes of a [Str].
+sha256Str : Str -> List U8
+sha256Str = \string -> sha256 (Str.toUtf8 string)
+
+## The 32-byte [BLAKE3](https://github.com/BLAKE3-team/BLAKE3) digest of some bytes. BLAKE3 is
+## much faster than SHA-256; use SHA-256 when another system expects it.
+blake3 : List U8 -> List U8
+
+## The 32-byte BLAKE3 digest of the UTF-8 bytes of a [Str].
+blake3Str : Str -> List U8
+blake3Str = \string -> blake3 (Str.toUtf8 string)
+
+## Whether two lists of bytes are equal, taking the same time no matter where they differ.
+##
+## Use this instead of `==` to check a password hash, a signature or a token: `==` stops at the
+## first byte that differs, so how long it takes tells an attacker how much of their guess was
+## right. Lists of different lengths are unequal straight away, so only the length is revealed.
+## ```
+## expect Crypto.constantTimeEq (Crypto.sha256Str "a") (Crypto.sha256Str "a")
+## expect !(Crypto.constantTimeEq (Crypto.sha256Str "a") (Crypto.sha256Str "b"))
+## ```
+constantTimeEq : List U8, List U8 -> Bool
+
+## Lowercase hexadecimal, two digits for each byte.
+## ```
+## expect Crypto.toHex [0, 15, 255] == "000fff"
+## ```
+toHex : List U8 -> Str
+toHex = \bytes ->
+    digits = List.walk bytes (List.withCapacity (2 * List.len bytes)) \hex, byte ->
+        hex
+        |> List.append (hexDigit (Num.shiftRightZfBy byte 4))
+        |> List.append (hexDigit (Num.bitwiseAnd byte 15))
+
+    # the digits are ASCII, so they are always valid UTF-8
+    Str.fromUtf8 digits |> Result.withDefault ""
+
+hexDigit : U8 -> U8
+hexDigit = \nibble ->
+    if nibble < 10 then
+        nibble + '0'
+    else
+        nibble - 10 + 'a'
+
+expect toHex (sha256Str "abc") == "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+expect toHex (blake3Str "") == "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+expect constantTimeEq [1, 2, 3] [1, 2, 3]
+expect !(constantTimeEq [1, 2, 3] [1, 2, 4])
+expect !(constantTimeEq [1, 2, 3] [1, 2])
+expect toHex [] == ""
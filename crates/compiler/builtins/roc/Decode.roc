@@ -31,8 +31,8 @@ interface Decode
         mapResult,
     ]
     imports [
+        Bool.{ Bool },
         List,
-        Result.{ Result },
         Num.{
             U8,
             U16,
@@ -49,7 +49,7 @@ interface Decode
             F64,
             Dec,
         },
-        Bool.{ Bool },
+        Result.{ Result },
     ]
 
 ## Error types when decoding a `List U8` of utf-8 bytes using a [Decoder]
@@ -24,11 +24,11 @@ interface Dict
     ]
     imports [
         Bool.{ Bool, Eq },
-        Result.{ Result },
+        Hash.{ Hasher, Hash },
         List,
-        Str,
         Num.{ Nat, U64, U8, I8 },
-        Hash.{ Hasher, Hash },
+        Result.{ Result },
+        Str,
     ]
 
 ## A [dictionary](https://en.wikipedia.org/wiki/Associative_array) that lets you
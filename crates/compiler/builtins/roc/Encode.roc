@@ -29,6 +29,7 @@ interface Encode
         toBytes,
     ]
     imports [
+        Bool.{ Bool },
         Num.{
             U8,
             U16,
@@ -44,7 +45,6 @@ interface Encode
             F64,
             Dec,
         },
-        Bool.{ Bool },
     ]
 
 Encoder fmt := List U8, fmt -> List U8 | fmt has EncoderFormatting
@@ -0,0 +1,34 @@
+interface Enum
+    exposes [Enum, toInt, fromInt, all]
+    imports [
+        List,
+        Num.{ U64 },
+        Result.{ Result },
+    ]
+
+## A tag union whose tags have no payloads, like `[Red, Green, Blue]`, numbered the
+## same way the compiler numbers its tags at runtime.
+##
+## The compiler sorts the tags of a union by name, and gives the first tag the
+## number 0, the next one 1, and so on. The derived implementations of [Enum] use
+## exactly those numbers, so code which serializes a value, or passes it to a host,
+## doesn't need to hard-code them:
+## ```
+## Color := [Red, Green, Blue] has [Enum, Eq]
+##
+## expect Enum.toInt (@Color Blue) == 0
+## expect Enum.fromInt 2 == Ok (@Color Red)
+## expect Enum.fromInt 3 == Err OutOfRange
+## ```
+## [Enum] can only be derived for tag unions. It can't be derived for a union with
+## a tag that has a payload.
+Enum has
+    ## Returns the number of the tag.
+    toInt : a -> U64 | a has Enum
+
+    ## Returns the tag with the given number, or `Err OutOfRange` if there is no
+    ## such tag.
+    fromInt : U64 -> Result a [OutOfRange] | a has Enum
+
+    ## Returns every tag, in order of their numbers.
+    all : {} -> List a | a has Enum
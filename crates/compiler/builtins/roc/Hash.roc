@@ -23,8 +23,8 @@ interface Hash
     ] imports [
         Bool.{ Bool, isEq },
         List,
-        Str,
         Num.{ U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, Nat },
+        Str,
     ]
 
 ## A value that can hashed.
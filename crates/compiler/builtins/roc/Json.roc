@@ -42,20 +42,17 @@ interface Json
         fromUtf8,
     ]
     imports [
-        List,
-        Str,
-        Result.{ Result },
-        Encode,
+        Bool.{ Bool, Eq },
+        Decode.{
+            DecoderFormatting,
+            DecodeResult,
+        },
         Encode.{
             Encoder,
             EncoderFormatting,
             appendWith,
         },
-        Decode,
-        Decode.{
-            DecoderFormatting,
-            DecodeResult,
-        },
+        List,
         Num.{
             U8,
             U16,
@@ -71,8 +68,8 @@ interface Json
             F64,
             Dec,
         },
-        Bool.{ Bool, Eq },
-        Result,
+        Result.{ Result },
+        Str,
     ]
 
 ## An opaque type with the `EncoderFormatting` and
@@ -52,6 +52,11 @@ interface List
         findLastIndex,
         sublist,
         intersperse,
+        chunksOf,
+        windows,
+        zip,
+        zip3,
+        unzip,
         split,
         splitFirst,
         splitLast,
@@ -61,6 +66,7 @@ interface List
         dropIf,
         sortAsc,
         sortDesc,
+        sortBy,
         reserve,
         releaseExcessCapacity,
         walkBackwardsUntil,
@@ -68,8 +74,8 @@ interface List
     ]
     imports [
         Bool.{ Bool, Eq },
-        Result.{ Result },
         Num.{ Nat, Num, Int },
+        Result.{ Result },
     ]
 
 ## Types
@@ -325,15 +331,6 @@ single = \x -> [x]
 
 ## Returns a list with the given length, where every element is the given value.
 repeat : a, Nat -> List a
-repeat = \value, count ->
-    repeatHelp value count (List.withCapacity count)
-
-repeatHelp : a, Nat, List a -> List a
-repeatHelp = \value, count, accum ->
-    if count > 0 then
-        repeatHelp value (count - 1) (List.appendUnsafe accum value)
-    else
-        accum
 
 ## Returns the list with its elements reversed.
 ## ```
@@ -795,7 +792,15 @@ expect
 expect
     List.range { start: At 4, end: At 0 } == [4, 3, 2, 1, 0]
 
-## Sort with a custom comparison function
+## Sort with a custom comparison function.
+##
+## The sort is stable: elements which compare as `EQ` stay in the order they were in, so a list
+## can be sorted by one field and then by another. It makes O(n log n) comparisons, and runs in
+## place when the list is unique.
+## ```
+## List.sortWith [{ n: 2, s: "a" }, { n: 1, s: "b" }, { n: 2, s: "c" }] \a, b -> Num.compare a.n b.n
+## # [{ n: 1, s: "b" }, { n: 2, s: "a" }, { n: 2, s: "c" }]
+## ```
 sortWith : List a, (a, a -> [LT, EQ, GT]) -> List a
 
 ## Sorts a list in ascending order (lowest to highest), using a function which
@@ -812,6 +817,24 @@ sortAsc = \list -> List.sortWith list Num.compare
 sortDesc : List (Num a) -> List (Num a)
 sortDesc = \list -> List.sortWith list (\a, b -> Num.compare b a)
 
+## Sorts a list in ascending order of a number computed from each element. The number is computed
+## once for each element, rather than once for each comparison, so this is faster than
+## [List.sortWith] when computing it takes a while. Like [List.sortWith], the sort is stable.
+## ```
+## List.sortBy ["ccc", "a", "bb"] Str.countGraphemes # ["a", "bb", "ccc"]
+## ```
+sortBy : List a, (a -> Num *) -> List a
+sortBy = \list, toKey ->
+    list
+    |> List.map (\element -> { key: toKey element, element })
+    |> List.sortWith (\a, b -> Num.compare a.key b.key)
+    |> List.map .element
+
+expect
+    sorted = List.sortBy [{ n: 2, s: "a" }, { n: 1, s: "b" }, { n: 2, s: "c" }, { n: 1, s: "d" }] .n
+
+    sorted == [{ n: 1, s: "b" }, { n: 1, s: "d" }, { n: 2, s: "a" }, { n: 2, s: "c" }]
+
 swap : List a, Nat, Nat -> List a
 
 ## Returns the first element in the list, or `ListWasEmpty` if it was empty.
@@ -1020,15 +1043,69 @@ sublistLowlevel : List elem, Nat, Nat -> List elem
 ## ```
 intersperse : List elem, elem -> List elem
 intersperse = \list, sep ->
-    capacity = 2 * List.len list
+    capacity = Num.subSaturated (2 * List.len list) 1
     init = List.withCapacity capacity
-    newList =
-        List.walk list init \acc, elem ->
+
+    List.walk list init \acc, elem ->
+        if List.isEmpty acc then
+            List.appendUnsafe acc elem
+        else
             acc
-            |> List.appendUnsafe elem
             |> List.appendUnsafe sep
+            |> List.appendUnsafe elem
+
+expect List.intersperse [1, 2, 3] 9 == [1, 9, 2, 9, 3]
+expect List.intersperse [1] 9 == [1]
+expect List.intersperse [] 9 == []
+
+## Splits a list into chunks of `size` elements, in order. The last chunk has
+## the elements which are left over, so it can be shorter. Returns `[]` when
+## `size` is 0.
+## ```
+## List.chunksOf [1, 2, 3, 4, 5] 2 # [[1, 2], [3, 4], [5]]
+## ```
+chunksOf : List elem, Nat -> List (List elem)
+
+## Every run of `size` consecutive elements of a list, in order. Returns `[]`
+## when the list is shorter than `size`, or when `size` is 0.
+## ```
+## List.windows [1, 2, 3, 4] 3 # [[1, 2, 3], [2, 3, 4]]
+## ```
+windows : List elem, Nat -> List (List elem)
+
+expect List.chunksOf [1, 2, 3, 4, 5] 2 == [[1, 2], [3, 4], [5]]
+expect List.chunksOf [1, 2, 3, 4] 2 == [[1, 2], [3, 4]]
+expect List.chunksOf [1, 2, 3] 0 == []
+expect List.windows [1, 2, 3, 4] 3 == [[1, 2, 3], [2, 3, 4]]
+expect List.windows [1, 2] 3 == []
+
+## Pairs up the elements of two lists, in order. Stops when either list runs
+## out of elements.
+## ```
+## List.zip ["a", "b", "c"] [1, 2] # [{ first: "a", second: 1 }, { first: "b", second: 2 }]
+## ```
+zip : List a, List b -> List { first : a, second : b }
+zip = \firsts, seconds ->
+    List.map2 firsts seconds \first, second -> { first, second }
+
+## Like [List.zip], for three lists.
+zip3 : List a, List b, List c -> List { first : a, second : b, third : c }
+zip3 = \firsts, seconds, thirds ->
+    List.map3 firsts seconds thirds \first, second, third -> { first, second, third }
+
+## Splits a list of pairs into a list of their first elements and a list of
+## their second elements. This undoes [List.zip].
+## ```
+## List.unzip [{ first: "a", second: 1 }, { first: "b", second: 2 }]
+## # { first: ["a", "b"], second: [1, 2] }
+## ```
+unzip : List { first : a, second : b } -> { first : List a, second : List b }
+unzip = \pairs ->
+    { first: List.map pairs .first, second: List.map pairs .second }
 
-    List.dropLast newList
+expect List.zip [1, 2, 3] [4, 5] == [{ first: 1, second: 4 }, { first: 2, second: 5 }]
+expect List.zip3 [1, 2] [3, 4] [5, 6] == [{ first: 1, second: 3, third: 5 }, { first: 2, second: 4, third: 6 }]
+expect List.unzip (List.zip [1, 2, 3] [4, 5, 6]) == { first: [1, 2, 3], second: [4, 5, 6] }
 
 ## Returns `Bool.true` if the first list starts with the second list.
 ##
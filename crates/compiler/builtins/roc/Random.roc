@@ -0,0 +1,364 @@
+## Pseudorandom numbers, from a [PCG64](https://www.pcg-random.org/) generator.
+##
+## Builtins can't make up randomness, so a [Generator] starts from a seed, which the platform
+## provides, or which is fixed to get the same numbers every time. Every function takes a
+## generator and returns the next one along with what it generated, in a [Step]:
+##
+## ```
+## { value: roll, generator } = Random.u8 (Random.seed 42) 1 6
+## { value: deck } = Random.shuffle generator (List.range { start: At 1, end: At 52 })
+## ```
+##
+## [split] turns a generator into two independent ones, so a part of a program can get its own
+## generator without threading one through everything else.
+##
+## These numbers are fast to generate and good for simulations, games and tests, but they are
+## predictable to anyone who sees enough of them: use the platform's cryptographic random numbers
+## for secrets.
+interface Random
+    exposes [
+        Generator,
+        Step,
+        seed,
+        split,
+        bool,
+        u8,
+        u16,
+        u32,
+        u64,
+        u128,
+        i8,
+        i16,
+        i32,
+        i64,
+        i128,
+        nat,
+        f32,
+        f64,
+        shuffle,
+        choose,
+        u64s,
+        bytes,
+    ]
+    imports [
+        Bool.{ Bool },
+        List,
+        Num.{ U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, Nat, F32, F64 },
+        Result.{ Result },
+    ]
+
+## The state of a generator. `increment` is always odd, and picks one of 2^127 streams of numbers.
+Generator := { state : U128, increment : U128 }
+
+## Something generated, and the generator to use for what comes next.
+Step a : { value : a, generator : Generator }
+
+multiplier : U128
+multiplier = 0x2360ED051FC65DA44385DF649FCCF645
+
+## A generator that starts from a seed. Generators from the same seed generate the same numbers.
+seed : U64 -> Generator
+seed = \value ->
+    # spread the 64 bits of the seed over the 256 bits that start a generator
+    part = \index -> splitMix (Num.addWrap value (Num.mulWrap index 0x9E3779B97F4A7C15))
+
+    fromSeeds (joinU64s (part 1) (part 2)) (joinU64s (part 3) (part 4))
+
+## Like `pcg_setseq_128_srandom_r` in the PCG reference implementation
+fromSeeds : U128, U128 -> Generator
+fromSeeds = \initState, initSequence ->
+    increment = Num.bitwiseOr (Num.shiftLeftBy initSequence 1) 1
+    @Generator started = advance (@Generator { state: 0, increment })
+
+    advance (@Generator { state: Num.addWrap started.state initState, increment })
+
+splitMix : U64 -> U64
+splitMix = \value ->
+    a = Num.mulWrap (Num.bitwiseXor value (Num.shiftRightZfBy value 30)) 0xBF58476D1CE4E5B9
+    b = Num.mulWrap (Num.bitwiseXor a (Num.shiftRightZfBy a 27)) 0x94D049BB133111EB
+
+    Num.bitwiseXor b (Num.shiftRightZfBy b 31)
+
+joinU64s : U64, U64 -> U128
+joinU64s = \high, low ->
+    Num.bitwiseOr (Num.shiftLeftBy (Num.toU128 high) 64) (Num.toU128 low)
+
+advance : Generator -> Generator
+advance = \@Generator { state, increment } ->
+    @Generator { state: Num.addWrap (Num.mulWrap state multiplier) increment, increment }
+
+## The next 64 random bits
+next : Generator -> Step U64
+next = \generator ->
+    advanced = advance generator
+    @Generator { state } = advanced
+    xored = Num.bitwiseXor (Num.toU64 (Num.shiftRightZfBy state 64)) (Num.toU64 state)
+    rotation = Num.toU8 (Num.shiftRightZfBy state 122)
+
+    { value: rotateRight xored rotation, generator: advanced }
+
+rotateRight : U64, U8 -> U64
+rotateRight = \bits, by ->
+    if by == 0 then
+        bits
+    else
+        Num.bitwiseOr (Num.shiftRightZfBy bits by) (Num.shiftLeftBy bits (64 - by))
+
+mapStep : Step a, (a -> b) -> Step b
+mapStep = \{ value, generator }, transform ->
+    { value: transform value, generator }
+
+## Two generators, which generate different numbers from each other and from the generator they
+## were split from.
+split : Generator -> { left : Generator, right : Generator }
+split = \generator ->
+    a = next generator
+    b = next a.generator
+    c = next b.generator
+    d = next c.generator
+
+    { left: d.generator, right: fromSeeds (joinU64s a.value b.value) (joinU64s c.value d.value) }
+
+## [Bool.true] or [Bool.false], each half of the time.
+bool : Generator -> Step Bool
+bool = \generator ->
+    next generator |> mapStep \bits -> Num.shiftRightZfBy bits 63 == 1
+
+## A number between `low` and `high`, both included, each as likely as the others. `low` and
+## `high` can be given in either order.
+##
+## To get any [U8], use `Random.u8 generator 0 Num.maxU8`.
+u8 : Generator, U8, U8 -> Step U8
+u8 = \generator, low, high ->
+    u64 generator (Num.toU64 low) (Num.toU64 high) |> mapStep Num.toU8
+
+## A number between `low` and `high`, both included. See [u8].
+u16 : Generator, U16, U16 -> Step U16
+u16 = \generator, low, high ->
+    u64 generator (Num.toU64 low) (Num.toU64 high) |> mapStep Num.toU16
+
+## A number between `low` and `high`, both included. See [u8].
+u32 : Generator, U32, U32 -> Step U32
+u32 = \generator, low, high ->
+    u64 generator (Num.toU64 low) (Num.toU64 high) |> mapStep Num.toU32
+
+## A number between `low` and `high`, both included. See [u8].
+u64 : Generator, U64, U64 -> Step U64
+u64 = \generator, low, high ->
+    if low > high then
+        u64 generator high low
+    else
+        upTo generator (high - low) |> mapStep \offset -> low + offset
+
+## A number between `low` and `high`, both included. See [u8].
+u128 : Generator, U128, U128 -> Step U128
+u128 = \generator, low, high ->
+    if low > high then
+        u128 generator high low
+    else
+        upTo128 generator (high - low) |> mapStep \offset -> low + offset
+
+## A number between `low` and `high`, both included. See [u8].
+i8 : Generator, I8, I8 -> Step I8
+i8 = \generator, low, high ->
+    i64 generator (Num.toI64 low) (Num.toI64 high) |> mapStep Num.toI8
+
+## A number between `low` and `high`, both included. See [u8].
+i16 : Generator, I16, I16 -> Step I16
+i16 = \generator, low, high ->
+    i64 generator (Num.toI64 low) (Num.toI64 high) |> mapStep Num.toI16
+
+## A number between `low` and `high`, both included. See [u8].
+i32 : Generator, I32, I32 -> Step I32
+i32 = \generator, low, high ->
+    i64 generator (Num.toI64 low) (Num.toI64 high) |> mapStep Num.toI32
+
+## A number between `low` and `high`, both included. See [u8].
+i64 : Generator, I64, I64 -> Step I64
+i64 = \generator, low, high ->
+    if low > high then
+        i64 generator high low
+    else
+        # the distance between them always fits in a U64, even when it doesn't fit in an I64
+        upTo generator (Num.toU64 (Num.subWrap high low))
+        |> mapStep \offset -> Num.addWrap low (Num.toI64 offset)
+
+## A number between `low` and `high`, both included. See [u8].
+i128 : Generator, I128, I128 -> Step I128
+i128 = \generator, low, high ->
+    if low > high then
+        i128 generator high low
+    else
+        upTo128 generator (Num.toU128 (Num.subWrap high low))
+        |> mapStep \offset -> Num.addWrap low (Num.toI128 offset)
+
+## A number between `low` and `high`, both included. See [u8].
+nat : Generator, Nat, Nat -> Step Nat
+nat = \generator, low, high ->
+    u64 generator (Num.toU64 low) (Num.toU64 high) |> mapStep Num.toNat
+
+## A number from 0 to `span`, with Lemire's multiply-and-reject method: the high half of
+## `bits * (span + 1)` is uniform once the few low halves which would bias it are rejected.
+upTo : Generator, U64 -> Step U64
+upTo = \generator, span ->
+    if span == Num.maxU64 then
+        next generator
+    else
+        range = span + 1
+        threshold = Num.subWrap 0 range % range
+
+        upToHelp generator range threshold
+
+upToHelp : Generator, U64, U64 -> Step U64
+upToHelp = \generator, range, threshold ->
+    bits = next generator
+    product = Num.mulWrap (Num.toU128 bits.value) (Num.toU128 range)
+
+    if Num.toU64 product < threshold then
+        upToHelp bits.generator range threshold
+    else
+        { value: Num.toU64 (Num.shiftRightZfBy product 64), generator: bits.generator }
+
+## A number from 0 to `span`. There is no wider type to multiply in, so this masks off the bits
+## above `span` and rejects numbers which are still too big, at most half of them.
+upTo128 : Generator, U128 -> Step U128
+upTo128 = \generator, span ->
+    if span == 0 then
+        { value: 0, generator }
+    else
+        mask = Num.shiftRightZfBy Num.maxU128 (Num.toU8 (Num.countLeadingZeroBits span))
+
+        upTo128Help generator span mask
+
+upTo128Help : Generator, U128, U128 -> Step U128
+upTo128Help = \generator, span, mask ->
+    high = next generator
+    low = next high.generator
+    candidate = Num.bitwiseAnd (joinU64s high.value low.value) mask
+
+    if candidate > span then
+        upTo128Help low.generator span mask
+    else
+        { value: candidate, generator: low.generator }
+
+## A number from 0 up to, but not including, 1.
+f64 : Generator -> Step F64
+f64 = \generator ->
+    # 53 random bits, the precision of an F64, scaled by 2^-53
+    next generator
+    |> mapStep \bits -> Num.toF64 (Num.shiftRightZfBy bits 11) * 1.1102230246251565e-16
+
+## A number from 0 up to, but not including, 1.
+f32 : Generator -> Step F32
+f32 = \generator ->
+    # 24 random bits, the precision of an F32, scaled by 2^-24
+    next generator
+    |> mapStep \bits -> Num.toF32 (Num.shiftRightZfBy bits 40) * 5.9604645e-8
+
+## The elements of a list in a random order, each order as likely as the others. Swaps elements
+## in place when the list is unique.
+shuffle : Generator, List a -> Step (List a)
+shuffle = \generator, list ->
+    shuffleHelp generator list (List.len list)
+
+# Fisher-Yates: swap a random element among the first `remaining` into the last of them
+shuffleHelp : Generator, List a, Nat -> Step (List a)
+shuffleHelp = \generator, list, remaining ->
+    if remaining <= 1 then
+        { value: list, generator }
+    else
+        index = nat generator 0 (remaining - 1)
+
+        shuffleHelp index.generator (List.swap list index.value (remaining - 1)) (remaining - 1)
+
+## A random element of a list, or `Err ListWasEmpty`.
+choose : Generator, List a -> Step (Result a [ListWasEmpty])
+choose = \generator, list ->
+    if List.isEmpty list then
+        { value: Err ListWasEmpty, generator }
+    else
+        index = nat generator 0 (List.len list - 1)
+
+        when List.get list index.value is
+            Ok element -> { value: Ok element, generator: index.generator }
+            Err OutOfBounds -> { value: Err ListWasEmpty, generator: index.generator }
+
+## `count` random [U64]s, generated in one go. This is much faster than calling [u64] `count`
+## times, for simulations which need a lot of numbers.
+u64s : Generator, Nat -> Step (List U64)
+u64s = \generator, count ->
+    { left, right } = split generator
+    @Generator { state, increment } = right
+
+    { value: u64sLowlevel state increment count, generator: left }
+
+## `count` random bytes, generated in one go.
+bytes : Generator, Nat -> Step (List U8)
+bytes = \generator, count ->
+    { left, right } = split generator
+    @Generator { state, increment } = right
+
+    { value: bytesLowlevel state increment count, generator: left }
+
+# `count` numbers from the generator with this state, exactly as `next` would generate them
+u64sLowlevel : U128, U128, Nat -> List U64
+
+# `count` bytes from the generator with this state, eight from each number, least significant first
+bytesLowlevel : U128, U128, Nat -> List U8
+
+# the PCG reference implementation's pcg64 test, seeded with 42 and 54
+expect
+    reference = fromSeeds 42 54
+    a = next reference
+    b = next a.generator
+
+    a.value == 0x86b1da1d72062b68 && b.value == 0x1304aa46c9853d39
+
+expect
+    @Generator { state, increment } = fromSeeds 42 54
+    u64sLowlevel state increment 3 == [0x86b1da1d72062b68, 0x1304aa46c9853d39, 0xa3670e9e0dd50358]
+
+expect
+    generator = seed 7
+
+    (u64 generator 0 Num.maxU64).value == (u64 (seed 7) 0 Num.maxU64).value
+
+expect
+    { left, right } = split (seed 7)
+
+    (u64 left 0 Num.maxU64).value != (u64 right 0 Num.maxU64).value
+
+expect
+    rolls = List.walk (List.repeat {} 1000) { generator: seed 1, inRange: Bool.true } \state, {} ->
+        roll = i8 state.generator (-3) 3
+
+        { generator: roll.generator, inRange: state.inRange && roll.value >= (-3) && roll.value <= 3 }
+
+    rolls.inRange
+
+expect
+    (u128 (seed 3) 10 10).value == 10 && (i128 (seed 3) 5 5).value == 5
+
+expect
+    fraction = (f64 (seed 11)).value
+
+    fraction >= 0 && fraction < 1
+
+expect
+    list = List.range { start: At 0, end: Before 100 }
+    shuffled = (shuffle (seed 5) list).value
+
+    List.len shuffled == 100 && List.sortAsc shuffled == list
+
+expect
+    when (choose (seed 5) [1, 2, 3]).value is
+        Ok element -> element >= 1 && element <= 3
+        Err ListWasEmpty -> Bool.false
+
+expect
+    empty : List U8
+    empty = []
+
+    (choose (seed 5) empty).value == Err ListWasEmpty
+
+expect List.len (bytes (seed 9) 13).value == 13
@@ -0,0 +1,614 @@
+## The Roc Binary Format (RBF) is a compact binary data format for persisting
+## Roc values, and for exchanging them between versions of an app or between
+## platforms.
+##
+## Like [Json], this module implements the `Encode` and `Decode` abilities, so
+## any type with `Encoding` or `Decoding` can be written to or read from RBF.
+## Unlike JSON, every value round-trips exactly, including floats.
+##
+## ```
+## Point : { x : F64, y : F64 }
+##
+## bytes = Rbf.toBytes { x: 1.5, y: -0.1 }
+##
+## point : Result Point _
+## point = Rbf.fromBytes bytes # returns `Ok { x: 1.5, y: -0.1 }`
+## ```
+##
+## The wire format doesn't depend on the memory layout of the values, or on the
+## platform that wrote them:
+##
+## - Integers are written in little-endian two's complement, using all the
+##   bytes of their type: 1 for [U8] and [I8], up to 16 for [U128] and [I128].
+## - [F32] and [F64] are written as their IEEE 754 bits, in little-endian. Every
+##   NaN is written as the same quiet NaN.
+## - [Dec] is written as its decimal digits, like a string.
+## - [Bool] is one byte: 0 for false, 1 for true.
+## - Lengths are unsigned LEB128 varints: 7 bits per byte, low bits first, with
+##   the high bit set on every byte except the last.
+## - A string is its length in bytes, followed by its UTF-8 bytes.
+## - A list is its number of elements, followed by the elements.
+## - A tuple is its number of elements, followed by the elements.
+## - A record is its number of fields, followed by each field's name (as a
+##   string), the length in bytes of the field's value, and the value. Fields
+##   a decoder doesn't know are skipped, so a record can gain fields without
+##   breaking readers of the older version.
+## - A tag is its name (as a string), followed by its number of payloads and
+##   the payloads.
+##
+## [toBytes] starts its output with a header of the bytes `RBF` and the format
+## version, and [fromBytes] checks it. [Encode.toBytes] and [Decode.fromBytes]
+## with [format] read and write values without the header.
+interface Rbf
+    exposes [
+        Rbf,
+        format,
+        formatVersion,
+        toBytes,
+        fromBytes,
+    ]
+    imports [
+        Bool.{ Bool, Eq },
+        Decode.{
+            Decoding,
+            DecoderFormatting,
+            DecodeResult,
+        },
+        Encode.{
+            Encoding,
+            Encoder,
+            EncoderFormatting,
+            appendWith,
+        },
+        List,
+        Num.{
+            U8,
+            U16,
+            U32,
+            U64,
+            U128,
+            I8,
+            I16,
+            I32,
+            I64,
+            I128,
+            F32,
+            F64,
+            Dec,
+            Nat,
+        },
+        Result.{ Result },
+        Str,
+    ]
+
+## An opaque type with the `EncoderFormatting` and
+## `DecoderFormatting` abilities.
+Rbf := {} has [
+         EncoderFormatting {
+             u8: encodeU8,
+             u16: encodeU16,
+             u32: encodeU32,
+             u64: encodeU64,
+             u128: encodeU128,
+             i8: encodeI8,
+             i16: encodeI16,
+             i32: encodeI32,
+             i64: encodeI64,
+             i128: encodeI128,
+             f32: encodeF32,
+             f64: encodeF64,
+             dec: encodeDec,
+             bool: encodeBool,
+             string: encodeString,
+             list: encodeList,
+             record: encodeRecord,
+             tuple: encodeTuple,
+             tag: encodeTag,
+         },
+         DecoderFormatting {
+             u8: decodeU8,
+             u16: decodeU16,
+             u32: decodeU32,
+             u64: decodeU64,
+             u128: decodeU128,
+             i8: decodeI8,
+             i16: decodeI16,
+             i32: decodeI32,
+             i64: decodeI64,
+             i128: decodeI128,
+             f32: decodeF32,
+             f64: decodeF64,
+             dec: decodeDec,
+             bool: decodeBool,
+             string: decodeString,
+             list: decodeList,
+             record: decodeRecord,
+             tuple: decodeTuple,
+         },
+     ]
+
+## Returns the RBF `Encoder` and `Decoder`
+format = @Rbf {}
+
+## The version of the wire format that [toBytes] writes into its header.
+## It only changes if the format changes incompatibly.
+formatVersion : U8
+formatVersion = 1
+
+## Encode a value as RBF, starting with a header of the bytes `RBF` and the
+## [formatVersion].
+toBytes : val -> List U8 | val has Encoding
+toBytes = \val ->
+    Encode.append ['R', 'B', 'F', formatVersion] val format
+
+## Decode a value written by [toBytes]. Fails with `UnsupportedVersion` if the
+## header has a format version this module can't read.
+fromBytes : List U8 -> Result val [Leftover (List U8), TooShort, UnsupportedVersion U8] | val has Decoding
+fromBytes = \bytes ->
+    when bytes is
+        ['R', 'B', 'F', version, ..] ->
+            if version == formatVersion then
+                when Decode.fromBytes (List.drop bytes 4) format is
+                    Ok val -> Ok val
+                    Err (Leftover rest) -> Err (Leftover rest)
+                    Err TooShort -> Err TooShort
+            else
+                Err (UnsupportedVersion version)
+
+        _ -> Err TooShort
+
+# Write the low `count` bytes of `n`, least significant first
+appendLittleEndian : List U8, Int a, Nat -> List U8
+appendLittleEndian = \bytes, n, count ->
+    if count == 0 then
+        bytes
+    else
+        appendLittleEndian (List.append bytes (Num.toU8 n)) (Num.shiftRightZfBy n 8) (count - 1)
+
+# Read `count` bytes, least significant first
+takeLittleEndian : List U8, Nat -> DecodeResult U128
+takeLittleEndian = \bytes, count ->
+    if List.len bytes < count then
+        { result: Err TooShort, rest: bytes }
+    else
+        { before, others } = List.split bytes count
+        n = List.walkBackwards before 0 \acc, byte ->
+            Num.bitwiseOr (Num.shiftLeftBy acc 8) (Num.toU128 byte)
+
+        { result: Ok n, rest: others }
+
+appendLength : List U8, U64 -> List U8
+appendLength = \bytes, n ->
+    if n < 0x80 then
+        List.append bytes (Num.toU8 n)
+    else
+        byte = Num.bitwiseOr (Num.toU8 (Num.bitwiseAnd n 0x7F)) 0x80
+
+        appendLength (List.append bytes byte) (Num.shiftRightZfBy n 7)
+
+takeLength : List U8 -> DecodeResult Nat
+takeLength = \bytes ->
+    takeLengthHelp bytes 0 0 0
+
+takeLengthHelp : List U8, Nat, U8, U64 -> DecodeResult Nat
+takeLengthHelp = \bytes, index, shift, acc ->
+    when List.get bytes index is
+        Ok byte ->
+            n = Num.bitwiseOr acc (Num.shiftLeftBy (Num.toU64 (Num.bitwiseAnd byte 0x7F)) shift)
+
+            if byte < 0x80 then
+                { result: Ok (Num.toNat n), rest: List.drop bytes (index + 1) }
+            else if shift >= 63 then
+                { result: Err TooShort, rest: bytes }
+            else
+                takeLengthHelp bytes (index + 1) (shift + 7) n
+
+        Err OutOfBounds -> { result: Err TooShort, rest: bytes }
+
+appendBytes : List U8, List U8 -> List U8
+appendBytes = \bytes, new ->
+    appendLength bytes (Num.toU64 (List.len new))
+    |> List.concat new
+
+takeBytes : List U8 -> DecodeResult (List U8)
+takeBytes = \bytes ->
+    { result, rest } = takeLength bytes
+
+    when result is
+        Ok len if List.len rest >= len ->
+            { before, others } = List.split rest len
+
+            { result: Ok before, rest: others }
+
+        _ -> { result: Err TooShort, rest: bytes }
+
+takeStr : List U8 -> DecodeResult Str
+takeStr = \bytes ->
+    { result, rest } = takeBytes bytes
+
+    when Result.try result Str.fromUtf8 is
+        Ok str -> { result: Ok str, rest }
+        Err _ -> { result: Err TooShort, rest: bytes }
+
+# Multiply by 2^exponent one doubling (or halving) at a time, so every step is
+# exact as long as the result is representable.
+scaleF64 : F64, I64 -> F64
+scaleF64 = \x, exponent ->
+    if exponent > 0 then
+        scaleF64 (x * 2) (exponent - 1)
+    else if exponent < 0 then
+        scaleF64 (x / 2) (exponent + 1)
+    else
+        x
+
+scaleF32 : F32, I64 -> F32
+scaleF32 = \x, exponent ->
+    if exponent > 0 then
+        scaleF32 (x * 2) (exponent - 1)
+    else if exponent < 0 then
+        scaleF32 (x / 2) (exponent + 1)
+    else
+        x
+
+# Split a positive, finite float into a significand in [1, 2) and an exponent
+normalizeF64 : F64, I64 -> { significand : F64, exponent : I64 }
+normalizeF64 = \x, exponent ->
+    if x >= 2 then
+        normalizeF64 (x / 2) (exponent + 1)
+    else if x < 1 then
+        normalizeF64 (x * 2) (exponent - 1)
+    else
+        { significand: x, exponent }
+
+normalizeF32 : F32, I64 -> { significand : F32, exponent : I64 }
+normalizeF32 = \x, exponent ->
+    if x >= 2 then
+        normalizeF32 (x / 2) (exponent + 1)
+    else if x < 1 then
+        normalizeF32 (x * 2) (exponent - 1)
+    else
+        { significand: x, exponent }
+
+f64ToBits : F64 -> U64
+f64ToBits = \x ->
+    signBit = if x < 0 || (x == 0 && 1 / x < 0) then 0x8000_0000_0000_0000 else 0
+
+    if Num.isNaN x then
+        0x7FF8_0000_0000_0000
+    else if Num.isInfinite x then
+        Num.bitwiseOr signBit 0x7FF0_0000_0000_0000
+    else if x == 0 then
+        signBit
+    else
+        { significand, exponent } = normalizeF64 (Num.abs x) 0
+
+        if exponent < -1022 then
+            # Subnormal: the fraction is the value in units of 2^-1074
+            Num.bitwiseOr signBit (Num.floor (scaleF64 (Num.abs x) 1074))
+        else
+            biased = Num.toU64 (exponent + 1023)
+            fraction = Num.floor (scaleF64 (significand - 1) 52)
+
+            signBit
+            |> Num.bitwiseOr (Num.shiftLeftBy biased 52)
+            |> Num.bitwiseOr fraction
+
+f64FromBits : U64 -> F64
+f64FromBits = \bits ->
+    biased = Num.toI64 (Num.bitwiseAnd (Num.shiftRightZfBy bits 52) 0x7FF)
+    fraction = Num.bitwiseAnd bits 0x000F_FFFF_FFFF_FFFF
+    magnitude =
+        if biased == 0x7FF then
+            if fraction == 0 then 1 / 0 else 0 / 0
+        else if biased == 0 then
+            scaleF64 (Num.toF64 fraction) (-1074)
+        else
+            scaleF64 (1 + scaleF64 (Num.toF64 fraction) (-52)) (biased - 1023)
+
+    if Num.bitwiseAnd bits 0x8000_0000_0000_0000 == 0 then
+        magnitude
+    else
+        Num.neg magnitude
+
+f32ToBits : F32 -> U32
+f32ToBits = \x ->
+    signBit = if x < 0 || (x == 0 && 1 / x < 0) then 0x8000_0000 else 0
+
+    if Num.isNaN x then
+        0x7FC0_0000
+    else if Num.isInfinite x then
+        Num.bitwiseOr signBit 0x7F80_0000
+    else if x == 0 then
+        signBit
+    else
+        { significand, exponent } = normalizeF32 (Num.abs x) 0
+
+        if exponent < -126 then
+            Num.bitwiseOr signBit (Num.floor (scaleF32 (Num.abs x) 149))
+        else
+            biased = Num.toU32 (exponent + 127)
+            fraction = Num.floor (scaleF32 (significand - 1) 23)
+
+            signBit
+            |> Num.bitwiseOr (Num.shiftLeftBy biased 23)
+            |> Num.bitwiseOr fraction
+
+f32FromBits : U32 -> F32
+f32FromBits = \bits ->
+    biased = Num.toI64 (Num.bitwiseAnd (Num.shiftRightZfBy bits 23) 0xFF)
+    fraction = Num.bitwiseAnd bits 0x007F_FFFF
+    magnitude =
+        if biased == 0xFF then
+            if fraction == 0 then 1 / 0 else 0 / 0
+        else if biased == 0 then
+            scaleF32 (Num.toF32 fraction) (-149)
+        else
+            scaleF32 (1 + scaleF32 (Num.toF32 fraction) (-23)) (biased - 127)
+
+    if Num.bitwiseAnd bits 0x8000_0000 == 0 then
+        magnitude
+    else
+        Num.neg magnitude
+
+encodeU8 = \n -> Encode.custom \bytes, @Rbf {} -> List.append bytes n
+
+encodeU16 = \n -> Encode.custom \bytes, @Rbf {} -> appendLittleEndian bytes n 2
+
+encodeU32 = \n -> Encode.custom \bytes, @Rbf {} -> appendLittleEndian bytes n 4
+
+encodeU64 = \n -> Encode.custom \bytes, @Rbf {} -> appendLittleEndian bytes n 8
+
+encodeU128 = \n -> Encode.custom \bytes, @Rbf {} -> appendLittleEndian bytes n 16
+
+encodeI8 = \n -> Encode.custom \bytes, @Rbf {} -> appendLittleEndian bytes n 1
+
+encodeI16 = \n -> Encode.custom \bytes, @Rbf {} -> appendLittleEndian bytes n 2
+
+encodeI32 = \n -> Encode.custom \bytes, @Rbf {} -> appendLittleEndian bytes n 4
+
+encodeI64 = \n -> Encode.custom \bytes, @Rbf {} -> appendLittleEndian bytes n 8
+
+encodeI128 = \n -> Encode.custom \bytes, @Rbf {} -> appendLittleEndian bytes n 16
+
+encodeF32 = \n -> Encode.custom \bytes, @Rbf {} -> appendLittleEndian bytes (f32ToBits n) 4
+
+encodeF64 = \n -> Encode.custom \bytes, @Rbf {} -> appendLittleEndian bytes (f64ToBits n) 8
+
+encodeDec = \n -> Encode.custom \bytes, @Rbf {} -> appendBytes bytes (Str.toUtf8 (Num.toStr n))
+
+encodeBool = \b -> Encode.custom \bytes, @Rbf {} ->
+        if b then List.append bytes 1 else List.append bytes 0
+
+encodeString = \s -> Encode.custom \bytes, @Rbf {} -> appendBytes bytes (Str.toUtf8 s)
+
+encodeList = \lst, encodeElem ->
+    Encode.custom \bytes, @Rbf {} ->
+        head = appendLength bytes (Num.toU64 (List.len lst))
+
+        List.walk lst head \buffer, elem -> appendWith buffer (encodeElem elem) (@Rbf {})
+
+encodeRecord = \fields ->
+    Encode.custom \bytes, @Rbf {} ->
+        writeField = \buffer, { key, value } ->
+            valueBytes = appendWith [] value (@Rbf {})
+
+            buffer
+            |> appendBytes (Str.toUtf8 key)
+            |> appendBytes valueBytes
+
+        head = appendLength bytes (Num.toU64 (List.len fields))
+
+        List.walk fields head writeField
+
+encodeTuple = \elems ->
+    Encode.custom \bytes, @Rbf {} ->
+        head = appendLength bytes (Num.toU64 (List.len elems))
+
+        List.walk elems head \buffer, elemEncoder -> appendWith buffer elemEncoder (@Rbf {})
+
+encodeTag = \name, payload ->
+    Encode.custom \bytes, @Rbf {} ->
+        head =
+            bytes
+            |> appendBytes (Str.toUtf8 name)
+            |> appendLength (Num.toU64 (List.len payload))
+
+        List.walk payload head \buffer, encoder -> appendWith buffer encoder (@Rbf {})
+
+decodeU8 = Decode.custom \bytes, @Rbf {} ->
+    when bytes is
+        [n, ..] -> { result: Ok n, rest: List.dropFirst bytes }
+        _ -> { result: Err TooShort, rest: bytes }
+
+decodeU16 = Decode.custom \bytes, @Rbf {} ->
+    takeLittleEndian bytes 2 |> Decode.mapResult Num.toU16
+
+decodeU32 = Decode.custom \bytes, @Rbf {} ->
+    takeLittleEndian bytes 4 |> Decode.mapResult Num.toU32
+
+decodeU64 = Decode.custom \bytes, @Rbf {} ->
+    takeLittleEndian bytes 8 |> Decode.mapResult Num.toU64
+
+decodeU128 = Decode.custom \bytes, @Rbf {} ->
+    takeLittleEndian bytes 16
+
+decodeI8 = Decode.custom \bytes, @Rbf {} ->
+    takeLittleEndian bytes 1 |> Decode.mapResult Num.toI8
+
+decodeI16 = Decode.custom \bytes, @Rbf {} ->
+    takeLittleEndian bytes 2 |> Decode.mapResult Num.toI16
+
+decodeI32 = Decode.custom \bytes, @Rbf {} ->
+    takeLittleEndian bytes 4 |> Decode.mapResult Num.toI32
+
+decodeI64 = Decode.custom \bytes, @Rbf {} ->
+    takeLittleEndian bytes 8 |> Decode.mapResult Num.toI64
+
+decodeI128 = Decode.custom \bytes, @Rbf {} ->
+    takeLittleEndian bytes 16 |> Decode.mapResult Num.toI128
+
+decodeF32 = Decode.custom \bytes, @Rbf {} ->
+    takeLittleEndian bytes 4 |> Decode.mapResult \n -> f32FromBits (Num.toU32 n)
+
+decodeF64 = Decode.custom \bytes, @Rbf {} ->
+    takeLittleEndian bytes 8 |> Decode.mapResult \n -> f64FromBits (Num.toU64 n)
+
+decodeDec = Decode.custom \bytes, @Rbf {} ->
+    { result, rest } = takeStr bytes
+
+    when Result.try result Str.toDec is
+        Ok n -> { result: Ok n, rest }
+        Err _ -> { result: Err TooShort, rest: bytes }
+
+decodeBool = Decode.custom \bytes, @Rbf {} ->
+    when bytes is
+        [0, ..] -> { result: Ok Bool.false, rest: List.dropFirst bytes }
+        [1, ..] -> { result: Ok Bool.true, rest: List.dropFirst bytes }
+        _ -> { result: Err TooShort, rest: bytes }
+
+decodeString = Decode.custom \bytes, @Rbf {} ->
+    takeStr bytes
+
+decodeList = \decodeElem -> Decode.custom \bytes, @Rbf {} ->
+        # NB: the decoder must be passed explicitly until #2894 is resolved.
+        decodeElems = \decoder, remaining, accum, elemBytes ->
+            if remaining == 0 then
+                { result: Ok accum, rest: elemBytes }
+            else
+                when Decode.decodeWith elemBytes decoder (@Rbf {}) is
+                    { result: Ok val, rest } -> decodeElems decoder (remaining - 1) (List.append accum val) rest
+                    { result: Err e, rest } -> { result: Err e, rest }
+
+        { result: lenResult, rest: afterLen } = takeLength bytes
+
+        when lenResult is
+            Ok len -> decodeElems decodeElem len (List.withCapacity len) afterLen
+            Err e -> { result: Err e, rest: bytes }
+
+tryDecode : DecodeResult a, ({ val : a, rest : List U8 } -> DecodeResult b) -> DecodeResult b
+tryDecode = \{ result, rest }, mapper ->
+    when result is
+        Ok val -> mapper { val, rest }
+        Err e -> { result: Err e, rest }
+
+decodeRecord = \initialState, stepField, finalizer -> Decode.custom \bytes, @Rbf {} ->
+        # NB: the stepper function must be passed explicitly until #2894 is resolved.
+        decodeFields = \stepper, state, remaining, fieldBytes ->
+            if remaining == 0 then
+                { result: Ok state, rest: fieldBytes }
+            else
+                { val: key, rest: afterKey } <- takeStr fieldBytes |> tryDecode
+                { val: valueBytes, rest: afterValue } <- takeBytes afterKey |> tryDecode
+
+                when stepper state key is
+                    Skip ->
+                        decodeFields stepper state (remaining - 1) afterValue
+
+                    Keep decoder ->
+                        when Decode.decodeWith valueBytes decoder (@Rbf {}) is
+                            { result: Ok newState, rest: [] } ->
+                                decodeFields stepper newState (remaining - 1) afterValue
+
+                            _ -> { result: Err TooShort, rest: fieldBytes }
+
+        { val: count, rest: afterCount } <- takeLength bytes |> tryDecode
+        { val: endState, rest: afterRecord } <- decodeFields stepField initialState count afterCount |> tryDecode
+
+        when finalizer endState is
+            Ok val -> { result: Ok val, rest: afterRecord }
+            Err e -> { result: Err e, rest: afterRecord }
+
+decodeTuple = \initialState, stepElem, finalizer -> Decode.custom \bytes, @Rbf {} ->
+        # NB: the stepper function must be passed explicitly until #2894 is resolved.
+        decodeElems = \stepper, state, index, count, elemBytes ->
+            if index == count then
+                { result: Ok state, rest: elemBytes }
+            else
+                when stepper state index is
+                    TooLong -> { result: Err TooShort, rest: elemBytes }
+                    Next decoder ->
+                        { val: newState, rest } <- Decode.decodeWith elemBytes decoder (@Rbf {}) |> tryDecode
+
+                        decodeElems stepper newState (index + 1) count rest
+
+        { val: count, rest: afterCount } <- takeLength bytes |> tryDecode
+        { val: endState, rest: afterTuple } <- decodeElems stepElem initialState 0 count afterCount |> tryDecode
+
+        when finalizer endState is
+            Ok val -> { result: Ok val, rest: afterTuple }
+            Err e -> { result: Err e, rest: afterTuple }
+
+# Test that lengths are written as LEB128 varints
+expect
+    actual = appendLength [] 300
+    expected = [0xAC, 0x02]
+
+    actual == expected
+
+# Test reading back a varint length
+expect
+    actual = takeLength [0xAC, 0x02, 7]
+    expected = { result: Ok 300, rest: [7] }
+
+    actual == expected
+
+# Test integers are written in little-endian two's complement
+expect
+    n : I16
+    n = -2
+    actual = Encode.toBytes n format
+    expected = [0xFE, 0xFF]
+
+    actual == expected
+
+# Test integer round trip
+expect
+    n : I64
+    n = -1234567890123
+    actual = Decode.fromBytes (Encode.toBytes n format) format
+
+    actual == Ok n
+
+# Test floats are written as their IEEE 754 bits
+expect
+    x : F64
+    x = 1.5
+    actual = Encode.toBytes x format
+    expected = [0, 0, 0, 0, 0, 0, 0xF8, 0x3F]
+
+    actual == expected
+
+# Test a float that JSON can't round trip exactly
+expect
+    f64ToBits 0.1 == 0x3FB9_9999_9999_999A
+
+# Test float bits round trip, including subnormals and negative zero
+expect
+    values : List F64
+    values = [0.1, -2.5e-310, 1.7976931348623157e308, -0.0]
+
+    List.all values \x -> f64ToBits (f64FromBits (f64ToBits x)) == f64ToBits x
+
+# Test records skip fields the decoder doesn't know
+expect
+    written : { a : U8, b : Str, c : U16 }
+    written = { a: 1, b: "skipped", c: 3 }
+    bytes = Encode.toBytes written format
+    actual : Result { a : U8, c : U16 } _
+    actual = Decode.fromBytes bytes format
+
+    actual == Ok { a: 1, c: 3 }
+
+# Test the header written by toBytes
+expect
+    actual = toBytes "hi"
+    expected = ['R', 'B', 'F', formatVersion, 2, 'h', 'i']
+
+    actual == expected
+
+# Test fromBytes rejects other format versions
+expect
+    actual : Result Str _
+    actual = fromBytes ['R', 'B', 'F', 99, 0]
+
+    actual == Err (UnsupportedVersion 99)
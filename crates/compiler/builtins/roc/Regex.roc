@@ -0,0 +1,480 @@
+## Regular expressions, for finding and replacing patterns in a [Str].
+##
+## ```
+## when Regex.fromStr "(\\w+)@(\\w+)\\.com" is
+##     Ok regex -> Regex.captures regex "mail roc@example.com" # Ok ["roc@example.com", "roc", "example"]
+##     Err _ -> crash "the pattern is valid"
+## ```
+##
+## A pattern can contain:
+## - any scalar, which matches itself
+## - `.`, which matches any scalar
+## - `[abc]`, `[a-z]` and `[^abc]`, which match any of the scalars in the brackets, or any scalar
+##   that isn't in them
+## - `\d`, `\w` and `\s`, which match a digit, a letter, digit or underscore, and whitespace; `\D`,
+##   `\W` and `\S` match everything else. They can also be used in brackets.
+## - `\n`, `\t` and `\r`, and a backslash followed by anything else, which matches that scalar, like
+##   `\.` or `\(`
+## - `^` and `$`, which match at the start and at the end of the string
+## - `(...)`, which groups part of a pattern, and captures what it matched
+## - `a|b`, which matches either `a` or `b`
+## - `*`, `+` and `?` after something, which match it any number of times, at least once, or at
+##   most once; they match as many times as they can
+##
+## [fromStr] compiles a pattern into a small program when it runs, so compile a pattern once and
+## reuse the [Regex], rather than calling [fromStr] in a loop. Matching backtracks: patterns that
+## repeat something which can match nothing, like `(a*)*`, can run out of stack.
+interface Regex
+    exposes [
+        Regex,
+        Problem,
+        fromStr,
+        isMatch,
+        find,
+        captures,
+        replaceFirst,
+        replaceEach,
+    ]
+    imports [
+        Bool.{ Bool },
+        List,
+        Num.{ Nat, I64, U32 },
+        Result.{ Result },
+        Str,
+    ]
+
+## A compiled pattern.
+##
+## `program` runs from its first instruction, on the scalars of a string, from a position in them.
+## `groups` is the number of capture groups, counting the whole match as group 0.
+Regex := {
+    program : List Inst,
+    groups : Nat,
+}
+
+## Why [fromStr] couldn't compile a pattern. The [Nat] is the index of the scalar in the pattern
+## where the problem is.
+Problem : [
+    UnclosedGroup Nat,
+    UnopenedGroup Nat,
+    UnclosedClass Nat,
+    NothingToRepeat Nat,
+    TrailingBackslash Nat,
+]
+
+## An instruction of a compiled pattern. `Split` and `Jump` are relative to their own index, so a
+## program can be put anywhere inside another one.
+Inst : [
+    Scalar U32,
+    AnyScalar,
+    Class { ranges : List Range, negated : Bool },
+    Start,
+    End,
+    # try the first offset, and the second one when the first doesn't match
+    Split I64 I64,
+    Jump I64,
+    # remember the current position as the start or end of a group
+    Save Nat,
+    Match,
+]
+
+Range : { start : U32, end : U32 }
+
+Parser : { pattern : List U32, index : Nat, groups : Nat }
+
+Parsed : Result { program : List Inst, parser : Parser } Problem
+
+## Compile a pattern.
+## ```
+## Regex.fromStr "a(b" # Err (UnclosedGroup 1)
+## ```
+fromStr : Str -> Result Regex Problem
+fromStr = \pattern ->
+    scalars = Str.toScalars pattern
+    { program, parser } <- parseAlternation { pattern: scalars, index: 0, groups: 1 } |> Result.try
+
+    if parser.index < List.len scalars then
+        # alternatives only stop early at a `)`
+        Err (UnopenedGroup parser.index)
+    else
+        Ok (@Regex { program: List.join [[Save 0], program, [Save 1, Match]], groups: parser.groups })
+
+## Whether the pattern matches anywhere in the string.
+isMatch : Regex, Str -> Bool
+isMatch = \regex, string ->
+    search regex (Str.toScalars string) 0 |> Result.isOk
+
+## The first part of the string the pattern matches.
+find : Regex, Str -> Result Str [NotFound]
+find = \regex, string ->
+    scalars = Str.toScalars string
+    slots <- search regex scalars 0 |> Result.map
+
+    slice scalars (slotAt slots 0) (slotAt slots 1)
+
+## What each group captured in the first match of the pattern, starting with the whole match. A
+## group that didn't take part in the match captured `""`.
+captures : Regex, Str -> Result (List Str) [NotFound]
+captures = \regex, string ->
+    scalars = Str.toScalars string
+    slots <- search regex scalars 0 |> Result.map
+
+    capturesHelp scalars slots 0 []
+
+capturesHelp : List U32, List Nat, Nat, List Str -> List Str
+capturesHelp = \scalars, slots, group, texts ->
+    if 2 * group < List.len slots then
+        text = slice scalars (slotAt slots (2 * group)) (slotAt slots (2 * group + 1))
+
+        capturesHelp scalars slots (group + 1) (List.append texts text)
+    else
+        texts
+
+## Replace the first part of the string the pattern matches.
+replaceFirst : Regex, Str, Str -> Str
+replaceFirst = \regex, string, replacement ->
+    scalars = Str.toScalars string
+
+    when search regex scalars 0 is
+        Ok slots ->
+            start = slotAt slots 0
+            end = slotAt slots 1
+
+            List.join [
+                List.takeFirst scalars start,
+                Str.toScalars replacement,
+                List.sublist scalars { start: end, len: List.len scalars - end },
+            ]
+            |> fromScalars
+
+        Err NotFound -> string
+
+## Replace every part of the string the pattern matches, from left to right.
+replaceEach : Regex, Str, Str -> Str
+replaceEach = \regex, string, replacement ->
+    scalars = Str.toScalars string
+
+    replaceEachHelp regex scalars (Str.toScalars replacement) 0 (List.withCapacity (List.len scalars))
+    |> fromScalars
+
+replaceEachHelp : Regex, List U32, List U32, Nat, List U32 -> List U32
+replaceEachHelp = \regex, scalars, replacement, from, output ->
+    when search regex scalars from is
+        Ok slots ->
+            start = slotAt slots 0
+            end = slotAt slots 1
+
+            replaced =
+                output
+                |> List.concat (List.sublist scalars { start: from, len: start - from })
+                |> List.concat replacement
+
+            if end == start then
+                # keep the scalar after an empty match, so the next search starts after it
+                when List.get scalars end is
+                    Ok scalar -> replaceEachHelp regex scalars replacement (end + 1) (List.append replaced scalar)
+                    Err OutOfBounds -> replaced
+            else
+                replaceEachHelp regex scalars replacement end replaced
+
+        Err NotFound ->
+            List.concat output (List.sublist scalars { start: from, len: List.len scalars - from })
+
+# Parsing
+
+parseAlternation : Parser -> Parsed
+parseAlternation = \parser ->
+    { program: first, parser: afterFirst } <- parseSequence parser [] |> Result.try
+
+    if List.get afterFirst.pattern afterFirst.index == Ok '|' then
+        { program: rest, parser: afterRest } <- parseAlternation { afterFirst & index: afterFirst.index + 1 } |> Result.try
+        firstLen = List.len first |> Num.toI64
+        restLen = List.len rest |> Num.toI64
+
+        Ok {
+            program: List.join [[Split 1 (firstLen + 2)], first, [Jump (restLen + 1)], rest],
+            parser: afterRest,
+        }
+    else
+        Ok { program: first, parser: afterFirst }
+
+parseSequence : Parser, List Inst -> Parsed
+parseSequence = \parser, program ->
+    when List.get parser.pattern parser.index is
+        Ok '|' | Ok ')' | Err OutOfBounds -> Ok { program, parser }
+        Ok _ ->
+            { program: atom, parser: afterAtom } <- parseAtom parser |> Result.try
+            { program: repeated, parser: afterRepetition } = parseRepetition afterAtom atom
+
+            parseSequence afterRepetition (List.concat program repeated)
+
+parseRepetition : Parser, List Inst -> { program : List Inst, parser : Parser }
+parseRepetition = \parser, atom ->
+    len = List.len atom |> Num.toI64
+    next = { parser & index: parser.index + 1 }
+
+    when List.get parser.pattern parser.index is
+        Ok '*' -> { program: List.join [[Split 1 (len + 2)], atom, [Jump (Num.neg (len + 1))]], parser: next }
+        Ok '+' -> { program: List.append atom (Split (Num.neg len) 1), parser: next }
+        Ok '?' -> { program: List.prepend atom (Split 1 (len + 1)), parser: next }
+        _ -> { program: atom, parser }
+
+parseAtom : Parser -> Parsed
+parseAtom = \parser ->
+    next = { parser & index: parser.index + 1 }
+
+    when List.get parser.pattern parser.index is
+        Ok '(' ->
+            group = parser.groups
+            { program, parser: inner } <- parseAlternation { next & groups: group + 1 } |> Result.try
+
+            if List.get inner.pattern inner.index == Ok ')' then
+                Ok {
+                    program: List.join [[Save (2 * group)], program, [Save (2 * group + 1)]],
+                    parser: { inner & index: inner.index + 1 },
+                }
+            else
+                Err (UnclosedGroup parser.index)
+
+        Ok '[' ->
+            if List.get next.pattern next.index == Ok '^' then
+                parseClass { next & index: next.index + 1 } [] Bool.true parser.index
+            else
+                parseClass next [] Bool.false parser.index
+
+        Ok '\\' ->
+            when List.get parser.pattern next.index is
+                Ok escaped ->
+                    afterEscape = { parser & index: parser.index + 2 }
+
+                    when escape escaped is
+                        Ranges ranges negated -> Ok { program: [Class { ranges, negated }], parser: afterEscape }
+                        Literal scalar -> Ok { program: [Scalar scalar], parser: afterEscape }
+
+                Err OutOfBounds -> Err (TrailingBackslash parser.index)
+
+        Ok '.' -> Ok { program: [AnyScalar], parser: next }
+        Ok '^' -> Ok { program: [Start], parser: next }
+        Ok '$' -> Ok { program: [End], parser: next }
+        Ok '*' | Ok '+' | Ok '?' -> Err (NothingToRepeat parser.index)
+        Ok scalar -> Ok { program: [Scalar scalar], parser: next }
+        # parseSequence doesn't parse an atom at the end of the pattern
+        Err OutOfBounds -> Ok { program: [], parser }
+
+## Parse the inside of `[...]`, up to and including the `]`.
+parseClass : Parser, List Range, Bool, Nat -> Parsed
+parseClass = \parser, ranges, negated, openedAt ->
+    next = { parser & index: parser.index + 1 }
+
+    when List.get parser.pattern parser.index is
+        Ok ']' -> Ok { program: [Class { ranges, negated }], parser: next }
+        Ok '\\' ->
+            when List.get parser.pattern next.index is
+                Ok escaped ->
+                    afterEscape = { parser & index: parser.index + 2 }
+
+                    when escape escaped is
+                        Ranges escapedRanges escapedNegated ->
+                            added = if escapedNegated then complement escapedRanges else escapedRanges
+
+                            parseClass afterEscape (List.concat ranges added) negated openedAt
+
+                        Literal scalar -> parseClassRange afterEscape scalar ranges negated openedAt
+
+                Err OutOfBounds -> Err (TrailingBackslash parser.index)
+
+        Ok scalar -> parseClassRange next scalar ranges negated openedAt
+        Err OutOfBounds -> Err (UnclosedClass openedAt)
+
+## Parse the rest of a range like `a-z` in `[...]`, after its first scalar.
+parseClassRange : Parser, U32, List Range, Bool, Nat -> Parsed
+parseClassRange = \parser, start, ranges, negated, openedAt ->
+    isRange = List.get parser.pattern parser.index == Ok '-' && List.get parser.pattern (parser.index + 1) != Ok ']'
+
+    when List.get parser.pattern (parser.index + 1) is
+        Ok end if isRange ->
+            parseClass { parser & index: parser.index + 2 } (List.append ranges { start, end }) negated openedAt
+
+        _ ->
+            parseClass parser (List.append ranges { start, end: start }) negated openedAt
+
+escape : U32 -> [Ranges (List Range) Bool, Literal U32]
+escape = \scalar ->
+    when scalar is
+        'd' -> Ranges digit Bool.false
+        'D' -> Ranges digit Bool.true
+        'w' -> Ranges word Bool.false
+        'W' -> Ranges word Bool.true
+        's' -> Ranges space Bool.false
+        'S' -> Ranges space Bool.true
+        'n' -> Literal '\n'
+        't' -> Literal '\t'
+        'r' -> Literal '\r'
+        _ -> Literal scalar
+
+digit : List Range
+digit = [{ start: '0', end: '9' }]
+
+word : List Range
+word = [
+    { start: '0', end: '9' },
+    { start: 'A', end: 'Z' },
+    { start: '_', end: '_' },
+    { start: 'a', end: 'z' },
+]
+
+space : List Range
+space = [
+    # tab, newline, vertical tab, form feed and carriage return
+    { start: 9, end: 13 },
+    { start: ' ', end: ' ' },
+]
+
+## The scalars that are in none of the ranges, which must be sorted and not overlap.
+complement : List Range -> List Range
+complement = \ranges ->
+    { result, from } =
+        List.walk ranges { result: [], from: 0 } \state, { start, end } ->
+            if start > state.from then
+                { result: List.append state.result { start: state.from, end: start - 1 }, from: end + 1 }
+            else
+                { result: state.result, from: end + 1 }
+
+    if from <= maxScalar then
+        List.append result { start: from, end: maxScalar }
+    else
+        result
+
+maxScalar : U32
+maxScalar = 0x10FFFF
+
+# Matching
+
+## The positions each group was saved at, in the first match at or after `from`.
+search : Regex, List U32, Nat -> Result (List Nat) [NotFound]
+search = \@Regex { program, groups }, scalars, from ->
+    # a position after the end, for groups that aren't saved
+    unsaved = List.len scalars + 1
+
+    searchHelp program scalars from (List.repeat unsaved (2 * groups))
+
+searchHelp : List Inst, List U32, Nat, List Nat -> Result (List Nat) [NotFound]
+searchHelp = \program, scalars, position, slots ->
+    when run program scalars 0 position slots is
+        Ok saved -> Ok saved
+        Err NoMatch ->
+            if position < List.len scalars then
+                searchHelp program scalars (position + 1) slots
+            else
+                Err NotFound
+
+## Run the program from the instruction at `pc`, at `position` in the scalars.
+run : List Inst, List U32, Nat, Nat, List Nat -> Result (List Nat) [NoMatch]
+run = \program, scalars, pc, position, slots ->
+    when List.get program pc is
+        Ok (Scalar expected) ->
+            if List.get scalars position == Ok expected then
+                run program scalars (pc + 1) (position + 1) slots
+            else
+                Err NoMatch
+
+        Ok AnyScalar ->
+            if position < List.len scalars then
+                run program scalars (pc + 1) (position + 1) slots
+            else
+                Err NoMatch
+
+        Ok (Class { ranges, negated }) ->
+            when List.get scalars position is
+                Ok scalar if inRanges ranges scalar != negated ->
+                    run program scalars (pc + 1) (position + 1) slots
+
+                _ -> Err NoMatch
+
+        Ok Start ->
+            if position == 0 then
+                run program scalars (pc + 1) position slots
+            else
+                Err NoMatch
+
+        Ok End ->
+            if position == List.len scalars then
+                run program scalars (pc + 1) position slots
+            else
+                Err NoMatch
+
+        Ok (Split first second) ->
+            when run program scalars (jump pc first) position slots is
+                Ok saved -> Ok saved
+                Err NoMatch -> run program scalars (jump pc second) position slots
+
+        Ok (Jump offset) -> run program scalars (jump pc offset) position slots
+        Ok (Save slot) -> run program scalars (pc + 1) position (List.set slots slot position)
+        Ok Match -> Ok slots
+        Err OutOfBounds -> Err NoMatch
+
+jump : Nat, I64 -> Nat
+jump = \pc, offset ->
+    Num.toI64 pc + offset |> Num.toNat
+
+inRanges : List Range, U32 -> Bool
+inRanges = \ranges, scalar ->
+    List.any ranges \{ start, end } -> scalar >= start && scalar <= end
+
+slotAt : List Nat, Nat -> Nat
+slotAt = \slots, index ->
+    List.get slots index |> Result.withDefault 0
+
+## The scalars from `start` up to `end`, or `""` for a group that wasn't saved.
+slice : List U32, Nat, Nat -> Str
+slice = \scalars, start, end ->
+    if start <= end && end <= List.len scalars then
+        List.sublist scalars { start, len: end - start } |> fromScalars
+    else
+        ""
+
+fromScalars : List U32 -> Str
+fromScalars = \scalars ->
+    List.walk scalars (Str.withCapacity (List.len scalars)) \string, scalar ->
+        # the scalars all came from a Str, so they are valid
+        Str.appendScalar string scalar |> Result.withDefault string
+
+expect
+    when fromStr "(\\w+)@(\\w+)\\.com" is
+        Ok regex -> captures regex "mail roc@example.com" == Ok ["roc@example.com", "roc", "example"]
+        Err _ -> Bool.false
+
+expect
+    when fromStr "a(x)?b|c+" is
+        Ok regex -> captures regex "zzabccc" == Ok ["ab", ""]
+        Err _ -> Bool.false
+
+expect
+    when fromStr "[^0-9 ]+" is
+        Ok regex -> replaceEach regex "ab 12 cd" "_" == "_ 12 _"
+        Err _ -> Bool.false
+
+expect
+    when fromStr "x*" is
+        Ok regex -> replaceEach regex "ab" "-" == "-a-b-"
+        Err _ -> Bool.false
+
+expect
+    when fromStr "^\\d+$" is
+        Ok regex -> isMatch regex "123" && !(isMatch regex "12a")
+        Err _ -> Bool.false
+
+expect
+    when fromStr "a(b" is
+        Ok _ -> Bool.false
+        Err problem -> problem == UnclosedGroup 1
+
+expect
+    when fromStr "ab)" is
+        Ok _ -> Bool.false
+        Err problem -> problem == UnopenedGroup 2
+
+expect
+    when fromStr "*a" is
+        Ok _ -> Bool.false
+        Err problem -> problem == NothingToRepeat 0
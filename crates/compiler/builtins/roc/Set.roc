@@ -16,11 +16,11 @@ interface Set
         difference,
     ]
     imports [
-        List,
         Bool.{ Bool, Eq },
         Dict.{ Dict },
-        Num.{ Nat },
         Hash.{ Hash },
+        List,
+        Num.{ Nat },
     ]
 
 # We should have this line above the next has.
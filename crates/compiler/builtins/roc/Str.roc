@@ -138,9 +138,9 @@ interface Str
     ]
     imports [
         Bool.{ Bool, Eq },
-        Result.{ Result },
         List,
         Num.{ Nat, Num, U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, F32, F64, Dec },
+        Result.{ Result },
     ]
 
 Utf8ByteProblem : [
@@ -167,6 +167,16 @@ isEmpty : Str -> Bool
 ## expect Str.concat "hello" "" == "hello"
 ## expect Str.concat "" "" == ""
 ## ```
+##
+## When nothing else refers to the first string, its bytes are reused and the second string is
+## copied after them. Whenever that needs more room, the capacity grows by a multiple of what it was
+## (see [Str.reserve]), so building a string up by concatenating onto the end of it in a loop takes
+## time proportional to its final length:
+## ```
+## List.walk ["a", "b", "c"] (Str.withCapacity 3) Str.concat
+## ```
+## Concatenating onto the front, like `Str.concat piece acc`, copies all of `acc` every time. In that
+## case, collect the pieces in a `List` and combine them at the end with [Str.joinWith].
 concat : Str, Str -> Str
 
 ## Returns a string of the specified capacity without any content.
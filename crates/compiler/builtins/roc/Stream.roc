@@ -0,0 +1,187 @@
+## A lazy pipeline over the elements of a list.
+##
+## Each step of a [List] pipeline builds a whole new list, so
+## ```
+## list |> List.map f |> List.keepIf g |> List.map h
+## ```
+## allocates two lists that are thrown away right after. The same pipeline on a
+## [Stream] builds no lists until [toList], which runs every step on one element
+## at a time, in a single pass over `list`:
+## ```
+## list
+## |> Stream.fromList
+## |> Stream.map f
+## |> Stream.keepIf g
+## |> Stream.map h
+## |> Stream.toList
+## ```
+## Steps run each time a stream is consumed, so consume a stream once; call
+## [toList] to keep the results around.
+interface Stream
+    exposes [
+        Stream,
+        fromList,
+        toList,
+        map,
+        keepIf,
+        dropIf,
+        keepOks,
+        walk,
+        walkUntil,
+        countIf,
+    ]
+    imports [
+        Bool.{ Bool },
+        List,
+        Num.{ Nat },
+        Result.{ Result },
+        Str,
+    ]
+
+## A pipeline of steps over the elements of a list, which haven't run yet.
+##
+## `get` runs every step on the element at the given index of the list, and
+## says whether the element made it through the steps.
+Stream a := {
+    len : Nat,
+    get : Nat -> [Keep a, Skip],
+}
+
+## Start a pipeline over the elements of a list.
+fromList : List a -> Stream a
+fromList = \list ->
+    get = \index ->
+        when List.get list index is
+            Ok elem -> Keep elem
+            Err OutOfBounds -> Skip
+
+    @Stream { len: List.len list, get }
+
+## Run the pipeline, and put the elements that make it through into a list.
+##
+## The list is allocated once, with room for every element of the original
+## list.
+## ```
+## [1, 2, 3, 4] |> Stream.fromList |> Stream.keepIf Num.isEven |> Stream.toList
+## ```
+toList : Stream a -> List a
+toList = \@Stream { len, get } ->
+    walkHelp get (List.withCapacity len) List.append 0 len
+
+## Add a step that transforms each element.
+## ```
+## [1, 2, 3] |> Stream.fromList |> Stream.map Num.toStr |> Stream.toList
+## ```
+map : Stream a, (a -> b) -> Stream b
+map = \@Stream { len, get }, transform ->
+    mappedGet = \index ->
+        when get index is
+            Keep elem -> Keep (transform elem)
+            Skip -> Skip
+
+    @Stream { len, get: mappedGet }
+
+## Add a step that only keeps the elements for which the function returns
+## `Bool.true`.
+keepIf : Stream a, (a -> Bool) -> Stream a
+keepIf = \@Stream { len, get }, predicate ->
+    keptGet = \index ->
+        when get index is
+            Keep elem if predicate elem -> Keep elem
+            _ -> Skip
+
+    @Stream { len, get: keptGet }
+
+## Add a step that drops the elements for which the function returns
+## `Bool.true`.
+dropIf : Stream a, (a -> Bool) -> Stream a
+dropIf = \stream, predicate ->
+    keepIf stream \elem -> !(predicate elem)
+
+## Add a step that transforms each element, and keeps the `Ok` results.
+keepOks : Stream a, (a -> Result b *) -> Stream b
+keepOks = \@Stream { len, get }, transform ->
+    okGet = \index ->
+        when get index is
+            Keep elem ->
+                when transform elem is
+                    Ok ok -> Keep ok
+                    Err _ -> Skip
+
+            Skip -> Skip
+
+    @Stream { len, get: okGet }
+
+## Run the pipeline, and build up a state from the elements that make it
+## through, like [List.walk].
+walk : Stream a, state, (state, a -> state) -> state
+walk = \@Stream { len, get }, state, step ->
+    walkHelp get state step 0 len
+
+walkHelp : (Nat -> [Keep a, Skip]), state, (state, a -> state), Nat, Nat -> state
+walkHelp = \get, state, step, index, len ->
+    if index < len then
+        when get index is
+            Keep elem -> walkHelp get (step state elem) step (index + 1) len
+            Skip -> walkHelp get state step (index + 1) len
+    else
+        state
+
+## Same as [walk], except you can stop walking early. The steps don't run
+## on the elements after the one that returned `Break`.
+walkUntil : Stream a, state, (state, a -> [Continue state, Break state]) -> state
+walkUntil = \@Stream { len, get }, state, step ->
+    walkUntilHelp get state step 0 len
+
+walkUntilHelp : (Nat -> [Keep a, Skip]), state, (state, a -> [Continue state, Break state]), Nat, Nat -> state
+walkUntilHelp = \get, state, step, index, len ->
+    if index < len then
+        when get index is
+            Keep elem ->
+                when step state elem is
+                    Continue next -> walkUntilHelp get next step (index + 1) len
+                    Break done -> done
+
+            Skip -> walkUntilHelp get state step (index + 1) len
+    else
+        state
+
+## Run the pipeline, and count the elements that make it through and for which
+## the function returns `Bool.true`.
+countIf : Stream a, (a -> Bool) -> Nat
+countIf = \stream, predicate ->
+    walk stream 0 \count, elem -> if predicate elem then count + 1 else count
+
+expect
+    actual =
+        [1, 2, 3, 4, 5, 6]
+        |> fromList
+        |> map (\n -> n * 10)
+        |> keepIf (\n -> n > 20)
+        |> map (\n -> n + 1)
+        |> toList
+
+    actual == [31, 41, 51, 61]
+
+expect
+    actual =
+        ["1", "x", "3"]
+        |> fromList
+        |> keepOks Str.toU8
+        |> dropIf (\n -> n == 3)
+        |> toList
+
+    actual == [1]
+
+expect
+    actual =
+        [1, 2, 3, 4]
+        |> fromList
+        |> walkUntil 0 \sum, n -> if n > 2 then Break sum else Continue (sum + n)
+
+    actual == 3
+
+expect
+    actual = [1, 2, 3, 4] |> fromList |> countIf Num.isEven
+
+    actual == 2
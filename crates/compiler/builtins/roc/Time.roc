@@ -0,0 +1,454 @@
+## Dates, times of day, instants and durations, with calendar math and
+## [ISO 8601](https://en.wikipedia.org/wiki/ISO_8601) formatting and parsing.
+##
+## - A [Duration] is an amount of time, in nanoseconds.
+## - A [Date] is a day in the proleptic Gregorian calendar.
+## - A [Time] is a time of day, from midnight up to the nanosecond before the next midnight.
+## - A [DateTime] is an instant, in UTC.
+##
+## Everything here is pure, so this module can't read the clock. Platforms that give access to the
+## current instant give it as the nanoseconds since the Unix epoch (1970-01-01T00:00:00Z), which
+## [fromUnixNanos] turns into a [DateTime]:
+## ```
+## now <- Utc.now |> Task.await
+## Time.toIso8601 (Time.fromUnixNanos now) # "2023-06-01T12:30:05.25Z"
+## ```
+## Hosts read that number through the `roc_current_time` hook of the platform ABI, so every platform
+## gets the same clock unless its host defines the hook itself (Rust hosts can use
+## `roc_std::unix_nanos_now`). Leap seconds are not counted, like in Unix time.
+interface Time
+    exposes [
+        Duration,
+        Date,
+        Time,
+        DateTime,
+        nanoseconds,
+        milliseconds,
+        seconds,
+        minutes,
+        hours,
+        days,
+        toNanoseconds,
+        addDurations,
+        date,
+        toCalendar,
+        weekday,
+        addDays,
+        daysBetween,
+        isLeapYear,
+        daysInMonth,
+        dateToIso8601,
+        dateFromIso8601,
+        time,
+        toClock,
+        midnight,
+        dateTime,
+        toDate,
+        toTime,
+        fromUnixNanos,
+        toUnixNanos,
+        add,
+        since,
+        toIso8601,
+        fromIso8601,
+    ]
+    imports [
+        Bool.{ Bool, Eq },
+        List,
+        Num.{ I64, I128, U8, U32, U64, Nat },
+        Result.{ Result },
+        Str,
+    ]
+
+## An amount of time, which can be negative.
+Duration := I128 has [Eq]
+
+## A day in the proleptic Gregorian calendar, which extends the current calendar to the days before
+## it was introduced.
+Date := I64 has [Eq]
+
+## A time of day.
+Time := U64 has [Eq]
+
+## An instant, in UTC.
+DateTime := I128 has [Eq]
+
+nanosPerSecond : I128
+nanosPerSecond = 1_000_000_000
+
+nanosPerDay : I128
+nanosPerDay = 86_400 * nanosPerSecond
+
+# Durations
+
+nanoseconds : I128 -> Duration
+nanoseconds = \count -> @Duration count
+
+milliseconds : I128 -> Duration
+milliseconds = \count -> @Duration (count * 1_000_000)
+
+seconds : I128 -> Duration
+seconds = \count -> @Duration (count * nanosPerSecond)
+
+minutes : I128 -> Duration
+minutes = \count -> @Duration (count * 60 * nanosPerSecond)
+
+hours : I128 -> Duration
+hours = \count -> @Duration (count * 3_600 * nanosPerSecond)
+
+## Days of exactly 24 hours.
+days : I128 -> Duration
+days = \count -> @Duration (count * nanosPerDay)
+
+toNanoseconds : Duration -> I128
+toNanoseconds = \@Duration nanos -> nanos
+
+addDurations : Duration, Duration -> Duration
+addDurations = \@Duration a, @Duration b -> @Duration (a + b)
+
+# Dates
+
+## The date with the given year, month (from 1 to 12) and day of the month (from 1).
+## ```
+## Time.date { year: 2024, month: 2, day: 29 } # Ok
+## Time.date { year: 2023, month: 2, day: 29 } # Err InvalidDate
+## ```
+date : { year : I64, month : U8, day : U8 } -> Result Date [InvalidDate]
+date = \{ year, month, day } ->
+    if month >= 1 && month <= 12 && day >= 1 && day <= daysInMonth year month then
+        Ok (@Date (daysFromCivil year (Num.toI64 month) (Num.toI64 day)))
+    else
+        Err InvalidDate
+
+## The year, month (from 1 to 12) and day of the month (from 1) of a date.
+toCalendar : Date -> { year : I64, month : U8, day : U8 }
+toCalendar = \@Date daysSinceEpoch ->
+    civilFromDays daysSinceEpoch
+
+weekday : Date -> [Mon, Tue, Wed, Thu, Fri, Sat, Sun]
+weekday = \@Date daysSinceEpoch ->
+    # 1970-01-01 was a Thursday
+    when floorMod (daysSinceEpoch + 3) 7 is
+        0 -> Mon
+        1 -> Tue
+        2 -> Wed
+        3 -> Thu
+        4 -> Fri
+        5 -> Sat
+        _ -> Sun
+
+addDays : Date, I64 -> Date
+addDays = \@Date daysSinceEpoch, count -> @Date (daysSinceEpoch + count)
+
+## The number of days from the first date to the second, which is negative when the second date is
+## earlier.
+daysBetween : Date, Date -> I64
+daysBetween = \@Date start, @Date end -> end - start
+
+isLeapYear : I64 -> Bool
+isLeapYear = \year ->
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+
+## The number of days in a month (from 1 to 12) of a year, or 0 for a month that doesn't exist.
+daysInMonth : I64, U8 -> U8
+daysInMonth = \year, month ->
+    when month is
+        1 | 3 | 5 | 7 | 8 | 10 | 12 -> 31
+        4 | 6 | 9 | 11 -> 30
+        2 -> if isLeapYear year then 29 else 28
+        _ -> 0
+
+## Format a date like `2023-06-01`.
+dateToIso8601 : Date -> Str
+dateToIso8601 = \d ->
+    { year, month, day } = toCalendar d
+    yearStr = yearToIso8601 year
+    monthStr = padded (Num.toU64 month) 2
+    dayStr = padded (Num.toU64 day) 2
+
+    "\(yearStr)-\(monthStr)-\(dayStr)"
+
+## Parse a date like `2023-06-01`.
+dateFromIso8601 : Str -> Result Date [InvalidIso8601]
+dateFromIso8601 = \string ->
+    bytes = Str.toUtf8 string
+
+    if List.len bytes == 10 then
+        parseDate bytes
+    else
+        Err InvalidIso8601
+
+# Times of day
+
+## The time of day with the given hour (from 0 to 23), minute and second (from 0 to 59), and
+## nanosecond (from 0 to 999,999,999).
+time : { hour : U8, minute : U8, second : U8, nanosecond : U32 } -> Result Time [InvalidTime]
+time = \{ hour, minute, second, nanosecond } ->
+    if hour < 24 && minute < 60 && second < 60 && nanosecond < 1_000_000_000 then
+        secondsOfDay = Num.toU64 hour * 3_600 + Num.toU64 minute * 60 + Num.toU64 second
+
+        Ok (@Time (secondsOfDay * 1_000_000_000 + Num.toU64 nanosecond))
+    else
+        Err InvalidTime
+
+toClock : Time -> { hour : U8, minute : U8, second : U8, nanosecond : U32 }
+toClock = \@Time nanos ->
+    secondsOfDay = nanos // 1_000_000_000
+
+    {
+        hour: Num.toU8 (secondsOfDay // 3_600),
+        minute: Num.toU8 (secondsOfDay % 3_600 // 60),
+        second: Num.toU8 (secondsOfDay % 60),
+        nanosecond: Num.toU32 (nanos % 1_000_000_000),
+    }
+
+midnight : Time
+midnight = @Time 0
+
+# Instants
+
+## The instant at a time of day on a date, in UTC.
+dateTime : Date, Time -> DateTime
+dateTime = \@Date daysSinceEpoch, @Time nanos ->
+    @DateTime (Num.toI128 daysSinceEpoch * nanosPerDay + Num.toI128 nanos)
+
+## The date of an instant, in UTC.
+toDate : DateTime -> Date
+toDate = \@DateTime nanos ->
+    @Date (Num.toI64 (floorDiv nanos nanosPerDay))
+
+## The time of day of an instant, in UTC.
+toTime : DateTime -> Time
+toTime = \@DateTime nanos ->
+    @Time (Num.toU64 (floorMod nanos nanosPerDay))
+
+## The instant a number of nanoseconds after the Unix epoch (1970-01-01T00:00:00Z), or before it
+## when the number is negative. This is how platforms give the current instant.
+fromUnixNanos : I128 -> DateTime
+fromUnixNanos = \nanos -> @DateTime nanos
+
+toUnixNanos : DateTime -> I128
+toUnixNanos = \@DateTime nanos -> nanos
+
+add : DateTime, Duration -> DateTime
+add = \@DateTime nanos, @Duration duration -> @DateTime (nanos + duration)
+
+## The duration from the second instant to the first one.
+## ```
+## Time.since later earlier
+## ```
+since : DateTime, DateTime -> Duration
+since = \@DateTime later, @DateTime earlier -> @Duration (later - earlier)
+
+## Format an instant like `2023-06-01T12:30:05Z`, or `2023-06-01T12:30:05.25Z` when it isn't a
+## whole second.
+toIso8601 : DateTime -> Str
+toIso8601 = \instant ->
+    { hour, minute, second, nanosecond } = toClock (toTime instant)
+    hourStr = padded (Num.toU64 hour) 2
+    minuteStr = padded (Num.toU64 minute) 2
+    secondStr = padded (Num.toU64 second) 2
+    dateStr = dateToIso8601 (toDate instant)
+    fractionStr = fraction nanosecond
+
+    "\(dateStr)T\(hourStr):\(minuteStr):\(secondStr)\(fractionStr)Z"
+
+## Parse an instant like `2023-06-01T12:30:05Z`, with up to nine digits for the fraction of a second,
+## like `2023-06-01T12:30:05.25Z`. It can also be in another timezone, like
+## `2023-06-01T14:30:05+02:00`, which is converted to UTC.
+fromIso8601 : Str -> Result DateTime [InvalidIso8601]
+fromIso8601 = \string ->
+    bytes = Str.toUtf8 string
+    day <- parseDate (List.takeFirst bytes 10) |> Result.try
+    hour <- digitsAt bytes 11 2 |> Result.try
+    minute <- digitsAt bytes 14 2 |> Result.try
+    second <- digitsAt bytes 17 2 |> Result.try
+    { nanosecond, offsetAt } <- parseFraction bytes 19 |> Result.try
+    offset <- parseOffset (List.drop bytes offsetAt) |> Result.try
+
+    separated = List.get bytes 10 == Ok 'T' && List.get bytes 13 == Ok ':' && List.get bytes 16 == Ok ':'
+
+    clock =
+        time {
+            hour: Num.toU8 hour,
+            minute: Num.toU8 minute,
+            second: Num.toU8 second,
+            nanosecond: Num.toU32 nanosecond,
+        }
+
+    when clock is
+        Ok timeOfDay if separated ->
+            Ok (add (dateTime day timeOfDay) (@Duration (Num.neg offset)))
+
+        _ -> Err InvalidIso8601
+
+# Calendar math, from http://howardhinnant.github.io/date_algorithms.html
+
+## The number of days from 1970-01-01 to a date.
+daysFromCivil : I64, I64, I64 -> I64
+daysFromCivil = \year, month, day ->
+    # count years from March, so the leap day is the last day of a year
+    y = if month <= 2 then year - 1 else year
+    era = (if y >= 0 then y else y - 399) // 400
+    yearOfEra = y - era * 400
+    dayOfYear = (153 * (if month > 2 then month - 3 else month + 9) + 2) // 5 + day - 1
+    dayOfEra = yearOfEra * 365 + yearOfEra // 4 - yearOfEra // 100 + dayOfYear
+
+    era * 146_097 + dayOfEra - 719_468
+
+## The date a number of days after 1970-01-01.
+civilFromDays : I64 -> { year : I64, month : U8, day : U8 }
+civilFromDays = \daysSinceEpoch ->
+    z = daysSinceEpoch + 719_468
+    era = (if z >= 0 then z else z - 146_096) // 146_097
+    dayOfEra = z - era * 146_097
+    yearOfEra = (dayOfEra - dayOfEra // 1_460 + dayOfEra // 36_524 - dayOfEra // 146_096) // 365
+    dayOfYear = dayOfEra - (365 * yearOfEra + yearOfEra // 4 - yearOfEra // 100)
+    monthFromMarch = (5 * dayOfYear + 2) // 153
+    day = dayOfYear - (153 * monthFromMarch + 2) // 5 + 1
+    month = if monthFromMarch < 10 then monthFromMarch + 3 else monthFromMarch - 9
+    year = yearOfEra + era * 400
+
+    { year: if month <= 2 then year + 1 else year, month: Num.toU8 month, day: Num.toU8 day }
+
+floorDiv : Int a, Int a -> Int a
+floorDiv = \a, b ->
+    quotient = a // b
+
+    if a % b != 0 && (a < 0) != (b < 0) then quotient - 1 else quotient
+
+floorMod : Int a, Int a -> Int a
+floorMod = \a, b ->
+    a - floorDiv a b * b
+
+# Formatting and parsing
+
+## Four digits for the years 0 to 9999, and a sign before the others, as ISO 8601 does.
+yearToIso8601 : I64 -> Str
+yearToIso8601 = \year ->
+    if year < 0 then
+        Str.concat "-" (padded (Num.toU64 (Num.neg year)) 4)
+    else if year > 9_999 then
+        Str.concat "+" (Num.toStr year)
+    else
+        padded (Num.toU64 year) 4
+
+## The number, with zeros in front of it up to the width.
+padded : U64, Nat -> Str
+padded = \number, width ->
+    digits = Num.toStr number
+    len = Str.countUtf8Bytes digits
+
+    if len < width then
+        Str.concat (Str.repeat "0" (width - len)) digits
+    else
+        digits
+
+## The fraction of a second, without the zeros at its end, or `""` for a whole second.
+fraction : U32 -> Str
+fraction = \nanosecond ->
+    if nanosecond == 0 then
+        ""
+    else
+        digits = Str.toUtf8 (padded (Num.toU64 nanosecond) 9)
+        significant = List.walkBackwardsUntil digits (List.len digits) \len, digit ->
+            if digit == '0' then Continue (len - 1) else Break len
+
+        when Str.fromUtf8 (List.takeFirst digits significant) is
+            Ok trimmed -> ".\(trimmed)"
+            Err _ -> ""
+
+parseDate : List U8 -> Result Date [InvalidIso8601]
+parseDate = \bytes ->
+    year <- digitsAt bytes 0 4 |> Result.try
+    month <- digitsAt bytes 5 2 |> Result.try
+    day <- digitsAt bytes 8 2 |> Result.try
+
+    if List.get bytes 4 == Ok '-' && List.get bytes 7 == Ok '-' then
+        date { year: Num.toI64 year, month: Num.toU8 month, day: Num.toU8 day }
+        |> Result.mapErr \InvalidDate -> InvalidIso8601
+    else
+        Err InvalidIso8601
+
+## The optional fraction of a second at an index, and the index after it.
+parseFraction : List U8, Nat -> Result { nanosecond : U64, offsetAt : Nat } [InvalidIso8601]
+parseFraction = \bytes, index ->
+    if List.get bytes index == Ok '.' then
+        count = countDigits bytes (index + 1) 0
+
+        if count >= 1 && count <= 9 then
+            digits <- digitsAt bytes (index + 1) count |> Result.try
+            scale = Num.powInt 10 (Num.toU64 (9 - count))
+
+            Ok { nanosecond: digits * scale, offsetAt: index + 1 + count }
+        else
+            Err InvalidIso8601
+    else
+        Ok { nanosecond: 0, offsetAt: index }
+
+## The offset from UTC in nanoseconds, of `Z` or of an offset like `+02:00` or `-05:30`.
+parseOffset : List U8 -> Result I128 [InvalidIso8601]
+parseOffset = \bytes ->
+    when List.first bytes is
+        Ok 'Z' if List.len bytes == 1 -> Ok 0
+        Ok sign if (sign == '+' || sign == '-') && List.len bytes == 6 && List.get bytes 3 == Ok ':' ->
+            hour <- digitsAt bytes 1 2 |> Result.try
+            minute <- digitsAt bytes 4 2 |> Result.try
+            offset = Num.toI128 (hour * 3_600 + minute * 60) * nanosPerSecond
+
+            if hour >= 24 || minute >= 60 then
+                Err InvalidIso8601
+            else if sign == '-' then
+                Ok (Num.neg offset)
+            else
+                Ok offset
+
+        _ -> Err InvalidIso8601
+
+countDigits : List U8, Nat, Nat -> Nat
+countDigits = \bytes, index, count ->
+    when List.get bytes index is
+        Ok byte if byte >= '0' && byte <= '9' -> countDigits bytes (index + 1) (count + 1)
+        _ -> count
+
+## The number written with the `count` digits at the index.
+digitsAt : List U8, Nat, Nat -> Result U64 [InvalidIso8601]
+digitsAt = \bytes, index, count ->
+    digits = List.sublist bytes { start: index, len: count }
+
+    if List.len digits == count then
+        List.walkUntil digits (Ok 0) \state, byte ->
+            when state is
+                Ok number if byte >= '0' && byte <= '9' -> Continue (Ok (number * 10 + Num.toU64 (byte - '0')))
+                _ -> Break (Err InvalidIso8601)
+    else
+        Err InvalidIso8601
+
+expect
+    when date { year: 2024, month: 2, day: 29 } is
+        Ok leapDay -> toCalendar (addDays leapDay 1) == { year: 2024, month: 3, day: 1 }
+        Err InvalidDate -> Bool.false
+
+expect date { year: 2023, month: 2, day: 29 } == Err InvalidDate
+
+expect
+    when date { year: 1969, month: 12, day: 31 } is
+        Ok d -> daysBetween d (addDays d 1) == 1 && weekday d == Wed && dateToIso8601 d == "1969-12-31"
+        Err InvalidDate -> Bool.false
+
+expect toIso8601 (fromUnixNanos 0) == "1970-01-01T00:00:00Z"
+
+expect toIso8601 (fromUnixNanos (-1)) == "1969-12-31T23:59:59.999999999Z"
+
+expect
+    when fromIso8601 "2023-06-01T14:30:05.25+02:00" is
+        Ok instant -> toIso8601 instant == "2023-06-01T12:30:05.25Z"
+        Err InvalidIso8601 -> Bool.false
+
+expect fromIso8601 "2023-06-01T24:00:00Z" == Err InvalidIso8601
+
+expect
+    earlier = fromUnixNanos 0
+    later = add earlier (hours 36)
+
+    since later earlier == hours 36 && toDate later == addDays (toDate earlier) 1
@@ -0,0 +1,175 @@
+## [UUIDs](https://www.rfc-editor.org/rfc/rfc9562): 128-bit identifiers, usually written as 32
+## hexadecimal digits in groups of 8, 4, 4, 4 and 12.
+##
+## ```
+## when Uuid.fromStr "f81d4fae-7dec-11d0-a765-00a0c91e6bf6" is
+##     Ok uuid -> Uuid.version uuid # 1
+##     Err InvalidUuid -> crash "the UUID is valid"
+## ```
+##
+## Builtins can't make up random numbers, so a random ([version 4](v4)) UUID is made from 16
+## random bytes which the platform provides, for example from the operating system's random
+## number generator.
+interface Uuid
+    exposes [
+        Uuid,
+        nil,
+        v4,
+        fromBytes,
+        toBytes,
+        fromU128,
+        toU128,
+        fromStr,
+        toStr,
+        version,
+    ]
+    imports [
+        Bool.{ Eq },
+        Crypto,
+        List,
+        Num.{ U8, U128, Nat },
+        Result.{ Result },
+        Str,
+    ]
+
+## A UUID. The most significant bits of the [U128] are the first bytes of the UUID.
+Uuid := U128 has [Eq]
+
+## The UUID whose bits are all zero, `00000000-0000-0000-0000-000000000000`.
+nil : Uuid
+nil = @Uuid 0
+
+## A random (version 4) UUID, from 16 random bytes. Six of their bits are replaced by the version
+## and variant of the UUID, so 122 random bits are left.
+##
+## Fails when there aren't exactly 16 bytes, with the number of bytes there are.
+## ```
+## expect
+##     when Uuid.v4 (List.repeat 0xFF 16) is
+##         Ok uuid -> Uuid.toStr uuid == "ffffffff-ffff-4fff-bfff-ffffffffffff"
+##         Err _ -> Bool.false
+## ```
+v4 : List U8 -> Result Uuid [WrongLength Nat]
+v4 = \random ->
+    when fromBytes random is
+        Ok (@Uuid value) ->
+            # clear the version and the top bits of the variant, then set them
+            cleared = Num.bitwiseXor value (Num.bitwiseAnd value 0xF000_C000_0000_0000_0000)
+
+            Ok (@Uuid (Num.bitwiseOr cleared 0x4000_8000_0000_0000_0000))
+
+        Err problem -> Err problem
+
+## The UUID made of 16 bytes, as they are. Fails when there aren't exactly 16 bytes, with the
+## number of bytes there are.
+fromBytes : List U8 -> Result Uuid [WrongLength Nat]
+fromBytes = \bytes ->
+    if List.len bytes == 16 then
+        value = List.walk bytes 0 \state, byte ->
+            Num.shiftLeftBy state 8 |> Num.bitwiseOr (Num.toU128 byte)
+
+        Ok (@Uuid value)
+    else
+        Err (WrongLength (List.len bytes))
+
+## The 16 bytes of a UUID.
+toBytes : Uuid -> List U8
+toBytes = \@Uuid value ->
+    toBytesHelp value 16 (List.withCapacity 16)
+
+toBytesHelp : U128, U8, List U8 -> List U8
+toBytesHelp = \value, remaining, bytes ->
+    if remaining == 0 then
+        bytes
+    else
+        byte = Num.toU8 (Num.shiftRightZfBy value ((remaining - 1) * 8))
+
+        toBytesHelp value (remaining - 1) (List.append bytes byte)
+
+fromU128 : U128 -> Uuid
+fromU128 = \value -> @Uuid value
+
+toU128 : Uuid -> U128
+toU128 = \@Uuid value -> value
+
+## Parses a UUID written as 32 hexadecimal digits in groups of 8, 4, 4, 4 and 12, separated by
+## `-`. Both lowercase and uppercase digits are accepted.
+## ```
+## expect Uuid.fromStr "F81D4FAE-7DEC-11D0-A765-00A0C91E6BF6" == Uuid.fromStr "f81d4fae-7dec-11d0-a765-00a0c91e6bf6"
+## expect Uuid.fromStr "f81d4fae7dec11d0a76500a0c91e6bf6" == Err InvalidUuid
+## ```
+fromStr : Str -> Result Uuid [InvalidUuid]
+fromStr = \string ->
+    bytes = Str.toUtf8 string
+
+    parsed =
+        List.walkUntil bytes { index: 0, value: 0 } \state, byte ->
+            if state.index == 8 || state.index == 13 || state.index == 18 || state.index == 23 then
+                if byte == '-' then
+                    Continue { index: state.index + 1, value: state.value }
+                else
+                    Break state
+            else
+                when hexValue byte is
+                    Ok digit ->
+                        value = Num.shiftLeftBy state.value 4 |> Num.bitwiseOr (Num.toU128 digit)
+
+                        Continue { index: state.index + 1, value }
+
+                    Err NotHex -> Break state
+
+    if List.len bytes == 36 && parsed.index == 36 then
+        Ok (@Uuid parsed.value)
+    else
+        Err InvalidUuid
+
+hexValue : U8 -> Result U8 [NotHex]
+hexValue = \byte ->
+    if byte >= '0' && byte <= '9' then
+        Ok (byte - '0')
+    else if byte >= 'a' && byte <= 'f' then
+        Ok (byte - 'a' + 10)
+    else if byte >= 'A' && byte <= 'F' then
+        Ok (byte - 'A' + 10)
+    else
+        Err NotHex
+
+## The lowercase, hyphenated form of a UUID, like `f81d4fae-7dec-11d0-a765-00a0c91e6bf6`.
+toStr : Uuid -> Str
+toStr = \uuid ->
+    digits = Str.toUtf8 (Crypto.toHex (toBytes uuid))
+    group = \start, len -> List.sublist digits { start, len }
+
+    [group 0 8, ['-'], group 8 4, ['-'], group 12 4, ['-'], group 16 4, ['-'], group 20 12]
+    |> List.join
+    |> Str.fromUtf8
+    |> Result.withDefault ""
+
+## The version of a UUID, which says how it was made: 4 for random UUIDs, 1 and 7 for UUIDs made
+## from a timestamp, 3 and 5 for UUIDs made from a name.
+version : Uuid -> U8
+version = \@Uuid value ->
+    Num.toU8 (Num.bitwiseAnd (Num.shiftRightZfBy value 76) 0xF)
+
+expect toStr nil == "00000000-0000-0000-0000-000000000000"
+expect
+    when fromStr "f81d4fae-7dec-11d0-a765-00a0c91e6bf6" is
+        Ok uuid -> toStr uuid == "f81d4fae-7dec-11d0-a765-00a0c91e6bf6" && version uuid == 1
+        Err InvalidUuid -> Bool.false
+expect fromStr "f81d4fae-7dec-11d0-a765-00a0c91e6bf" == Err InvalidUuid
+expect fromStr "f81d4fae-7dec-11d0-a765-00a0c91e6bfg" == Err InvalidUuid
+expect fromStr "f81d4fae+7dec-11d0-a765-00a0c91e6bf6" == Err InvalidUuid
+expect
+    when v4 (List.repeat 0 16) is
+        Ok uuid -> toStr uuid == "00000000-0000-4000-8000-000000000000" && version uuid == 4
+        Err _ -> Bool.false
+expect
+    when v4 (List.repeat 0 15) is
+        Ok _ -> Bool.false
+        Err (WrongLength len) -> len == 15
+expect
+    bytes = List.range { start: At 1, end: At 16 }
+
+    when fromBytes bytes is
+        Ok uuid -> toBytes uuid == bytes
+        Err _ -> Bool.false
@@ -115,6 +115,7 @@ impl IntWidth {
                 Architecture::X86_64
                 | Architecture::Aarch64
                 | Architecture::Aarch32
+                | Architecture::S390x
                 | Architecture::Wasm32 => 8,
                 Architecture::X86_32 => 4,
             },
@@ -275,12 +276,14 @@ pub const NUM_ROUND_F64: IntrinsicName = int_intrinsic!("roc_builtins.num.round_
 
 pub const NUM_ADD_OR_PANIC_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.add_or_panic");
 pub const NUM_ADD_SATURATED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.add_saturated");
+pub const NUM_ADD_WRAP_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.add_wrapped");
 pub const NUM_ADD_CHECKED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.add_with_overflow");
 pub const NUM_ADD_CHECKED_FLOAT: IntrinsicName =
     float_intrinsic!("roc_builtins.num.add_with_overflow");
 
 pub const NUM_SUB_OR_PANIC_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.sub_or_panic");
 pub const NUM_SUB_SATURATED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.sub_saturated");
+pub const NUM_SUB_WRAP_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.sub_wrapped");
 pub const NUM_SUB_CHECKED_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.sub_with_overflow");
 pub const NUM_SUB_CHECKED_FLOAT: IntrinsicName =
     float_intrinsic!("roc_builtins.num.sub_with_overflow");
@@ -350,6 +353,8 @@ pub const LIST_MAP2: &str = "roc_builtins.list.map2";
 pub const LIST_MAP3: &str = "roc_builtins.list.map3";
 pub const LIST_MAP4: &str = "roc_builtins.list.map4";
 pub const LIST_SUBLIST: &str = "roc_builtins.list.sublist";
+pub const LIST_CHUNKS_OF: &str = "roc_builtins.list.chunks_of";
+pub const LIST_WINDOWS: &str = "roc_builtins.list.windows";
 pub const LIST_DROP_AT: &str = "roc_builtins.list.drop_at";
 pub const LIST_SWAP: &str = "roc_builtins.list.swap";
 pub const LIST_WITH_CAPACITY: &str = "roc_builtins.list.with_capacity";
@@ -360,6 +365,7 @@ pub const LIST_REPLACE_IN_PLACE: &str = "roc_builtins.list.replace_in_place";
 pub const LIST_IS_UNIQUE: &str = "roc_builtins.list.is_unique";
 pub const LIST_PREPEND: &str = "roc_builtins.list.prepend";
 pub const LIST_APPEND_UNSAFE: &str = "roc_builtins.list.append_unsafe";
+pub const LIST_REPEAT: &str = "roc_builtins.list.repeat";
 pub const LIST_RESERVE: &str = "roc_builtins.list.reserve";
 pub const LIST_CAPACITY: &str = "roc_builtins.list.capacity";
 pub const LIST_REFCOUNT_PTR: &str = "roc_builtins.list.refcount_ptr";
@@ -381,11 +387,25 @@ pub const DEC_SUB_OR_PANIC: &str = "roc_builtins.dec.sub_or_panic";
 pub const DEC_SUB_SATURATED: &str = "roc_builtins.dec.sub_saturated";
 pub const DEC_MUL_OR_PANIC: &str = "roc_builtins.dec.mul_or_panic";
 pub const DEC_MUL_SATURATED: &str = "roc_builtins.dec.mul_saturated";
+pub const DEC_MUL_ADD_WITH_OVERFLOW: &str = "roc_builtins.dec.mul_add_with_overflow";
+pub const DEC_MUL_ADD_OR_PANIC: &str = "roc_builtins.dec.mul_add_or_panic";
+
+pub const CRYPTO_SHA256: &str = "roc_builtins.crypto.sha256";
+pub const CRYPTO_BLAKE3: &str = "roc_builtins.crypto.blake3";
+pub const CRYPTO_CONSTANT_TIME_EQ: &str = "roc_builtins.crypto.constant_time_eq";
+
+pub const RANDOM_U64S: &str = "roc_builtins.random.u64s";
+pub const RANDOM_BYTES: &str = "roc_builtins.random.bytes";
 
 pub const UTILS_TEST_PANIC: &str = "roc_builtins.utils.test_panic";
 pub const UTILS_ALLOCATE_WITH_REFCOUNT: &str = "roc_builtins.utils.allocate_with_refcount";
 pub const UTILS_INCREF_RC_PTR: &str = "roc_builtins.utils.incref_rc_ptr";
 pub const UTILS_DECREF_RC_PTR: &str = "roc_builtins.utils.decref_rc_ptr";
+pub const UTILS_INCREF_RC_PTR_ATOMIC: &str = "roc_builtins.utils.incref_rc_ptr_atomic";
+pub const UTILS_DECREF_RC_PTR_ATOMIC: &str = "roc_builtins.utils.decref_rc_ptr_atomic";
+pub const UTILS_INCREF_RC_PTR_NON_ATOMIC: &str = "roc_builtins.utils.incref_rc_ptr_non_atomic";
+pub const UTILS_DECREF_RC_PTR_NON_ATOMIC: &str = "roc_builtins.utils.decref_rc_ptr_non_atomic";
+pub const UTILS_ATOMIC_REFCOUNTS: &str = "roc_builtins.utils.atomic_refcounts";
 pub const UTILS_INCREF_DATA_PTR: &str = "roc_builtins.utils.incref_data_ptr";
 pub const UTILS_DECREF_DATA_PTR: &str = "roc_builtins.utils.decref_data_ptr";
 pub const UTILS_IS_UNIQUE: &str = "roc_builtins.utils.is_unique";
@@ -398,6 +418,7 @@ pub const UTILS_EXPECT_FAILED_START_SHARED_FILE: &str =
 pub const UTILS_EXPECT_READ_ENV_SHARED_BUFFER: &str = "roc_builtins.utils.read_env_shared_buffer";
 pub const NOTIFY_PARENT_EXPECT: &str = "roc_builtins.utils.notify_parent_expect";
 pub const NOTIFY_PARENT_DBG: &str = "roc_builtins.utils.notify_parent_dbg";
+pub const UTILS_WRITE_PROFILE: &str = "roc_builtins.utils.write_profile";
 
 pub const UTILS_LONGJMP: &str = "longjmp";
 pub const UTILS_SETJMP: &str = "setjmp";
@@ -16,6 +16,14 @@ pub fn module_source(module_id: ModuleId) -> &'static str {
         ModuleId::DECODE => DECODE,
         ModuleId::HASH => HASH,
         ModuleId::JSON => JSON,
+        ModuleId::RBF => RBF,
+        ModuleId::STREAM => STREAM,
+        ModuleId::REGEX => REGEX,
+        ModuleId::TIME => TIME,
+        ModuleId::CRYPTO => CRYPTO,
+        ModuleId::UUID => UUID,
+        ModuleId::RANDOM => RANDOM,
+        ModuleId::ENUM => ENUM,
         _ => internal_error!(
             "ModuleId {:?} is not part of the standard library",
             module_id
@@ -35,3 +43,11 @@ const ENCODE: &str = include_str!("../roc/Encode.roc");
 const DECODE: &str = include_str!("../roc/Decode.roc");
 const HASH: &str = include_str!("../roc/Hash.roc");
 const JSON: &str = include_str!("../roc/Json.roc");
+const RBF: &str = include_str!("../roc/Rbf.roc");
+const STREAM: &str = include_str!("../roc/Stream.roc");
+const REGEX: &str = include_str!("../roc/Regex.roc");
+const TIME: &str = include_str!("../roc/Time.roc");
+const CRYPTO: &str = include_str!("../roc/Crypto.roc");
+const UUID: &str = include_str!("../roc/Uuid.roc");
+const RANDOM: &str = include_str!("../roc/Random.roc");
+const ENUM: &str = include_str!("../roc/Enum.roc");
@@ -116,12 +116,25 @@ static_assertions::assert_eq_size!(SpecializationId, Option<SpecializationId>);
 pub enum SpecializationLambdaSetError {}
 
 /// A key into a particular implementation of an ability member for an opaque type.
+///
+/// Implementations can only be declared where the opaque type is defined, so each key has at
+/// most one implementation in the whole module graph, no matter which packages the modules come
+/// from.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct ImplKey {
     pub opaque: Symbol,
     pub ability_member: Symbol,
 }
 
+/// The default implementation of an ability member, which an opaque type implementing the ability
+/// uses unless it implements the member itself.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DefaultImpl {
+    pub symbol: Symbol,
+    /// The number of arguments the member takes; zero for constants like `zero : a | a has Zero`.
+    pub arity: u32,
+}
+
 /// Fully-resolved implementation of an ability member for an opaque type.
 /// This is only fully known after type solving of the owning module.
 #[derive(Clone, Debug)]
@@ -167,6 +180,14 @@ pub struct IAbilitiesStore<Phase: ResolvePhase> {
     /// Resolved specializations for a symbol. These might be ephemeral (known due to type solving),
     /// or resolved on-the-fly during mono.
     resolved_specializations: MutMap<SpecializationId, Symbol>,
+
+    /// Maps ability members to their default implementations, if they have one.
+    default_implementations: MutMap<Symbol, DefaultImpl>,
+
+    /// Implementations that forward to the default implementation of their member. Unlike
+    /// implementations written by hand, these are generalized rather than specialized to their
+    /// opaque type.
+    defaulted_implementations: VecSet<Symbol>,
 }
 
 impl<Phase: ResolvePhase> Default for IAbilitiesStore<Phase> {
@@ -181,6 +202,8 @@ impl<Phase: ResolvePhase> Default for IAbilitiesStore<Phase> {
                 // Safety: 1 != 0
                 unsafe { NonZeroU32::new_unchecked(1) },
             resolved_specializations: Default::default(),
+            default_implementations: Default::default(),
+            defaulted_implementations: Default::default(),
          }
     }
 }
@@ -264,6 +287,45 @@ impl<Phase: ResolvePhase> IAbilitiesStore<Phase> {
         }
     }
 
+    /// Records the default implementation of an ability member.
+    pub fn register_default_implementation(&mut self, member: Symbol, default_impl: DefaultImpl) {
+        let old_default = self.default_implementations.insert(member, default_impl);
+        debug_assert!(old_default.is_none(), "Replacing existing default");
+    }
+
+    /// Returns the default implementation of an ability member, if it has one.
+    pub fn default_implementation(&self, member: Symbol) -> Option<DefaultImpl> {
+        self.default_implementations.get(&member).copied()
+    }
+
+    /// Returns whether a symbol is the default implementation of an ability member.
+    pub fn is_default_implementation(&self, symbol: Symbol) -> bool {
+        self.default_implementations
+            .values()
+            .any(|default_impl| default_impl.symbol == symbol)
+    }
+
+    /// Records that `implementing_type` implements `member` with `impl_symbol`, which forwards to
+    /// the default implementation of the member.
+    pub fn register_defaulted_implementation(
+        &mut self,
+        implementing_type: Symbol,
+        member: Symbol,
+        impl_symbol: Symbol,
+    ) {
+        let impl_key = ImplKey {
+            opaque: implementing_type,
+            ability_member: member,
+        };
+        self.register_one_declared_impl(impl_key, MemberImpl::Impl(impl_symbol));
+        self.defaulted_implementations.insert(impl_symbol);
+    }
+
+    /// Returns whether a symbol implements an ability member by forwarding to its default.
+    pub fn is_defaulted_implementation(&self, symbol: Symbol) -> bool {
+        self.defaulted_implementations.contains(&symbol)
+    }
+
     /// Returns whether a symbol is declared to specialize an ability member.
     pub fn is_specialization_name(&self, symbol: Symbol) -> bool {
         self.specialization_to_root.contains_key(&symbol)
@@ -324,6 +386,11 @@ impl<Phase: ResolvePhase> IAbilitiesStore<Phase> {
             // Taking closure for a new module, so specialization IDs can be fresh
             next_specialization_id: _,
             resolved_specializations: _,
+
+            // Only needed to solve the module that declared the implementations
+            defaulted_implementations: _,
+
+            default_implementations,
         } = self;
 
         let mut new = PendingAbilitiesStore::default();
@@ -360,6 +427,10 @@ impl<Phase: ResolvePhase> IAbilitiesStore<Phase> {
                 };
 
                 imported_member_data.push((*member, imported_data));
+
+                if let Some(default_impl) = default_implementations.get(member) {
+                    new.register_default_implementation(*member, *default_impl);
+                }
             }
 
             new.register_ability(ability, imported_member_data);
@@ -487,6 +558,137 @@ impl IAbilitiesStore<Resolved> {
     }
 }
 
+impl AbilitiesStore {
+    /// Rewrite every symbol in the store with `f`, e.g. to move a store solved in one compilation
+    /// into another, where modules have different ids. Fails if `f` fails for any symbol.
+    pub fn try_map_symbols(self, f: &impl Fn(Symbol) -> Option<Symbol>) -> Option<Self> {
+        let Self {
+            members_of_ability,
+            specialization_to_root,
+            ability_members,
+            declared_implementations,
+            specializations,
+            next_specialization_id,
+            resolved_specializations,
+            default_implementations,
+            defaulted_implementations,
+        } = self;
+
+        let members_of_ability = members_of_ability
+            .into_iter()
+            .map(|(ability, members)| {
+                let members = members.into_iter().map(f).collect::<Option<_>>()?;
+
+                Some((f(ability)?, members))
+            })
+            .collect::<Option<_>>()?;
+
+        let specialization_to_root = specialization_to_root
+            .into_iter()
+            .map(|(symbol, key)| Some((f(symbol)?, key.try_map_symbols(f)?)))
+            .collect::<Option<_>>()?;
+
+        let ability_members = ability_members
+            .into_iter()
+            .map(|(member, data)| {
+                let data = AbilityMemberData {
+                    parent_ability: f(data.parent_ability)?,
+                    ..data
+                };
+
+                Some((f(member)?, data))
+            })
+            .collect::<Option<_>>()?;
+
+        let declared_implementations = declared_implementations
+            .into_iter()
+            .map(|(key, member_impl)| {
+                let member_impl = match member_impl {
+                    MemberImpl::Impl(symbol) => MemberImpl::Impl(f(symbol)?),
+                    MemberImpl::Error => MemberImpl::Error,
+                };
+
+                Some((key.try_map_symbols(f)?, member_impl))
+            })
+            .collect::<Option<_>>()?;
+
+        let specializations = specializations
+            .into_iter()
+            .map(|(symbol, info)| Some((f(symbol)?, info.try_map_symbols(f)?)))
+            .collect::<Option<_>>()?;
+
+        let resolved_specializations = resolved_specializations
+            .into_iter()
+            .map(|(id, symbol)| Some((id, f(symbol)?)))
+            .collect::<Option<_>>()?;
+
+        let default_implementations = default_implementations
+            .into_iter()
+            .map(|(member, default_impl)| {
+                let default_impl = DefaultImpl {
+                    symbol: f(default_impl.symbol)?,
+                    ..default_impl
+                };
+
+                Some((f(member)?, default_impl))
+            })
+            .collect::<Option<_>>()?;
+
+        let defaulted_implementations = defaulted_implementations
+            .into_iter()
+            .map(f)
+            .collect::<Option<_>>()?;
+
+        Some(Self {
+            members_of_ability,
+            specialization_to_root,
+            ability_members,
+            declared_implementations,
+            specializations,
+            next_specialization_id,
+            resolved_specializations,
+            default_implementations,
+            defaulted_implementations,
+        })
+    }
+}
+
+impl ImplKey {
+    fn try_map_symbols(self, f: &impl Fn(Symbol) -> Option<Symbol>) -> Option<Self> {
+        Some(ImplKey {
+            opaque: f(self.opaque)?,
+            ability_member: f(self.ability_member)?,
+        })
+    }
+}
+
+impl MemberSpecializationInfo<Resolved> {
+    fn try_map_symbols(self, f: &impl Fn(Symbol) -> Option<Symbol>) -> Option<Self> {
+        Some(MemberSpecializationInfo {
+            symbol: f(self.symbol)?,
+            ..self
+        })
+    }
+}
+
+/// Rewrite every symbol in `implementations` with `f`, like [AbilitiesStore::try_map_symbols].
+pub fn try_map_resolved_implementations(
+    implementations: ResolvedImplementations,
+    f: &impl Fn(Symbol) -> Option<Symbol>,
+) -> Option<ResolvedImplementations> {
+    implementations
+        .into_iter()
+        .map(|(key, resolved)| {
+            let resolved = match resolved {
+                ResolvedImpl::Impl(info) => ResolvedImpl::Impl(info.try_map_symbols(f)?),
+                ResolvedImpl::Error => ResolvedImpl::Error,
+            };
+
+            Some((key.try_map_symbols(f)?, resolved))
+        })
+        .collect()
+}
+
 pub use serialize::deserialize_solved_implementations;
 pub use serialize::serialize_solved_implementations;
 
@@ -500,15 +702,10 @@ impl IAbilitiesStore<Pending> {
             ResolvedImpl::Error => MemberImpl::Error,
         };
 
-        let old_declared_impl = self.declared_implementations.insert(impl_key, member_impl);
-        debug_assert!(
-            old_declared_impl.is_none() ||
-                // Can happen between we import declared implementations during canonicalization, but
-                // implementation information only after solving
-                old_declared_impl.unwrap() == member_impl,
-            "Replacing existing declared impl: {:?}",
-            (impl_key, old_declared_impl)
-        );
+        // The key can already be present, because we import declared implementations during
+        // canonicalization, but implementation information only after solving. A different
+        // implementation was already reported as a conflict when the modules were imported.
+        self.declared_implementations.insert(impl_key, member_impl);
     }
 
     fn import_specialization(
@@ -532,7 +729,13 @@ impl IAbilitiesStore<Pending> {
         debug_assert!(old_spec.is_none(), "Replacing existing specialization");
     }
 
-    pub fn union(&mut self, other: Self) {
+    /// Add everything in `other` to this store. Returns the implementations which `other`
+    /// declares differently from this store, which keeps its own implementation for them.
+    ///
+    /// An opaque type can only implement abilities in the module that defines it, so two
+    /// different implementations mean that two copies of that module were loaded, for example
+    /// from two versions of the same package.
+    pub fn union(&mut self, other: Self) -> Vec<ImplKey> {
         let Self {
             members_of_ability: other_members_of_ability,
             ability_members: mut other_ability_members,
@@ -541,6 +744,8 @@ impl IAbilitiesStore<Pending> {
             next_specialization_id,
             resolved_specializations,
             specializations,
+            default_implementations,
+            defaulted_implementations,
         } = other;
 
         for (ability, members) in other_members_of_ability.into_iter() {
@@ -561,9 +766,16 @@ impl IAbilitiesStore<Pending> {
             debug_assert!(old_root.is_none() || old_root.unwrap() == member);
         }
 
+        let mut conflicts = Vec::new();
+
         for (impl_key, impl_) in declared_implementations.into_iter() {
-            let old_impl = self.declared_implementations.insert(impl_key, impl_);
-            debug_assert!(old_impl.is_none() || old_impl.unwrap() == impl_);
+            match self.declared_implementations.get(&impl_key) {
+                Some(old_impl) if *old_impl != impl_ => conflicts.push(impl_key),
+                Some(_) => {}
+                None => {
+                    self.declared_implementations.insert(impl_key, impl_);
+                }
+            }
         }
 
         for (symbol, specialization_info) in specializations.into_iter() {
@@ -576,10 +788,20 @@ impl IAbilitiesStore<Pending> {
             );
         }
 
+        for (member, default_impl) in default_implementations.into_iter() {
+            let old_default = self.default_implementations.insert(member, default_impl);
+            debug_assert!(old_default.is_none() || old_default.unwrap() == default_impl);
+        }
+
+        self.defaulted_implementations
+            .extend(defaulted_implementations.into_iter());
+
         debug_assert_eq!(next_specialization_id.get(), 1);
         debug_assert_eq!(self.next_specialization_id.get(), 1);
         debug_assert!(resolved_specializations.is_empty());
         debug_assert!(self.resolved_specializations.is_empty());
+
+        conflicts
     }
 
     pub fn resolve_for_module<Ctx, VarOfSymbol, ImportVar>(
@@ -601,6 +823,8 @@ impl IAbilitiesStore<Pending> {
             next_specialization_id,
             resolved_specializations,
             specializations,
+            default_implementations,
+            defaulted_implementations,
         } = self;
 
         let ability_members = ability_members
@@ -679,12 +903,14 @@ impl IAbilitiesStore<Pending> {
             next_specialization_id,
             resolved_specializations,
             specializations,
+            default_implementations,
+            defaulted_implementations,
         }
     }
 }
 
 mod serialize {
-    use roc_collections::{MutMap, VecMap};
+    use roc_collections::{MutMap, VecMap, VecSet};
     use roc_module::symbol::Symbol;
     use roc_region::all::Region;
     use roc_serialize::bytes;
@@ -694,8 +920,8 @@ mod serialize {
     };
 
     use super::{
-        AbilitiesStore, AbilityMemberData, ImplKey, MemberSpecializationInfo, Resolved,
-        ResolvedImpl, ResolvedImplementations, ResolvedMemberType, SpecializationId,
+        AbilitiesStore, AbilityMemberData, DefaultImpl, ImplKey, MemberSpecializationInfo,
+        Resolved, ResolvedImpl, ResolvedImplementations, ResolvedMemberType, SpecializationId,
     };
 
     use std::io::{self, Write};
@@ -710,6 +936,8 @@ mod serialize {
         specializations: u64,
         next_specialization_id: u64,
         resolved_specializations: u64,
+        default_implementations: u64,
+        defaulted_implementations: u64,
     }
 
     impl Header {
@@ -722,6 +950,8 @@ mod serialize {
                 specializations,
                 next_specialization_id,
                 resolved_specializations,
+                default_implementations,
+                defaulted_implementations,
             } = store;
 
             Self {
@@ -732,6 +962,8 @@ mod serialize {
                 specializations: specializations.len() as _,
                 next_specialization_id: next_specialization_id.get() as _,
                 resolved_specializations: resolved_specializations.len() as _,
+                default_implementations: default_implementations.len() as _,
+                defaulted_implementations: defaulted_implementations.len() as _,
             }
         }
 
@@ -759,6 +991,8 @@ mod serialize {
             specializations,
             next_specialization_id: _, // written in the header
             resolved_specializations,
+            default_implementations,
+            defaulted_implementations,
         } = store;
 
         let written = serialize_members_of_ability(members_of_ability, writer, written)?;
@@ -769,6 +1003,15 @@ mod serialize {
         let written = serialize_specializations(specializations, writer, written)?;
         let written =
             serialize_resolved_specializations(resolved_specializations, writer, written)?;
+        let written = serialize_default_implementations(default_implementations, writer, written)?;
+        let written = bytes::serialize_slice(
+            &defaulted_implementations
+                .iter()
+                .copied()
+                .collect::<Vec<_>>(),
+            writer,
+            written,
+        )?;
 
         Ok(written)
     }
@@ -798,6 +1041,10 @@ mod serialize {
             header.resolved_specializations as _,
             offset,
         );
+        let (default_implementations, offset) =
+            deserialize_default_implementations(bytes, header.default_implementations as _, offset);
+        let (defaulted_implementations, offset) =
+            bytes::deserialize_vec::<Symbol>(bytes, header.defaulted_implementations as _, offset);
 
         (
             AbilitiesStore {
@@ -808,6 +1055,8 @@ mod serialize {
                 specializations,
                 next_specialization_id: (header.next_specialization_id as u32).try_into().unwrap(),
                 resolved_specializations,
+                default_implementations,
+                defaulted_implementations: VecSet::from(defaulted_implementations),
             },
             offset,
         )
@@ -1125,6 +1374,64 @@ mod serialize {
         )
     }
 
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    struct SerDefaultImpl(Symbol, u32);
+    impl From<&DefaultImpl> for SerDefaultImpl {
+        fn from(default_impl: &DefaultImpl) -> Self {
+            Self(default_impl.symbol, default_impl.arity)
+        }
+    }
+    impl From<&SerDefaultImpl> for DefaultImpl {
+        fn from(default_impl: &SerDefaultImpl) -> Self {
+            Self {
+                symbol: default_impl.0,
+                arity: default_impl.1,
+            }
+        }
+    }
+
+    fn serialize_default_implementations(
+        default_implementations: &MutMap<Symbol, DefaultImpl>,
+        writer: &mut impl Write,
+        written: usize,
+    ) -> io::Result<usize> {
+        bytes::serialize_map(
+            default_implementations,
+            bytes::serialize_slice,
+            |default_impls, writer, written| {
+                bytes::serialize_slice(
+                    &default_impls
+                        .iter()
+                        .map(SerDefaultImpl::from)
+                        .collect::<Vec<_>>(),
+                    writer,
+                    written,
+                )
+            },
+            writer,
+            written,
+        )
+    }
+
+    fn deserialize_default_implementations(
+        bytes: &[u8],
+        length: usize,
+        offset: usize,
+    ) -> (MutMap<Symbol, DefaultImpl>, usize) {
+        bytes::deserialize_map(
+            bytes,
+            bytes::deserialize_vec,
+            |bytes, length, offset| {
+                let (slice, offset) =
+                    bytes::deserialize_slice::<SerDefaultImpl>(bytes, length, offset);
+                (slice.iter().map(DefaultImpl::from).collect(), offset)
+            },
+            length,
+            offset,
+        )
+    }
+
     #[derive(Copy, Clone)]
     #[repr(C)]
     enum SerResolvedImpl {
@@ -1342,6 +1649,19 @@ mod test {
             store.insert_resolved(spec_id1, Symbol::ARG_2);
             store.insert_resolved(spec_id2, Symbol::ARG_3);
 
+            store.register_default_implementation(
+                Symbol::ARG_1,
+                DefaultImpl {
+                    symbol: Symbol::ARG_6,
+                    arity: 2,
+                },
+            );
+            store.register_defaulted_implementation(
+                Symbol::ATTR_ATTR,
+                Symbol::ARG_1,
+                Symbol::ARG_7,
+            );
+
             store
         };
 
@@ -1357,6 +1677,8 @@ mod test {
             specializations,
             next_specialization_id,
             resolved_specializations,
+            default_implementations,
+            defaulted_implementations,
         } = store;
 
         let (de_store, offset) = AbilitiesStore::deserialize(&bytes);
@@ -1370,5 +1692,10 @@ mod test {
         assert_eq!(specializations, de_store.specializations);
         assert_eq!(next_specialization_id, de_store.next_specialization_id);
         assert_eq!(resolved_specializations, de_store.resolved_specializations);
+        assert_eq!(default_implementations, de_store.default_implementations);
+        assert_eq!(
+            defaulted_implementations,
+            de_store.defaulted_implementations
+        );
     }
 }
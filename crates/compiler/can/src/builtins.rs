@@ -137,6 +137,7 @@ map_symbol_to_lowlevel_and_arity! {
     ListReserve; LIST_RESERVE; 2,
     ListIsUnique; LIST_IS_UNIQUE; 1,
     ListAppendUnsafe; LIST_APPEND_UNSAFE; 2,
+    ListRepeat; LIST_REPEAT; 2,
     ListPrepend; LIST_PREPEND; 2,
     ListGetUnsafe; LIST_GET_UNSAFE; 2,
     ListReplaceUnsafe; LIST_REPLACE_UNSAFE; 3,
@@ -147,6 +148,8 @@ map_symbol_to_lowlevel_and_arity! {
     ListMap4; LIST_MAP4; 5,
     ListSortWith; LIST_SORT_WITH; 2,
     ListSublist; LIST_SUBLIST_LOWLEVEL; 3,
+    ListChunksOf; LIST_CHUNKS_OF; 2,
+    ListWindows; LIST_WINDOWS; 2,
     ListDropAt; LIST_DROP_AT; 2,
     ListSwap; LIST_SWAP; 3,
     ListGetCapacity; LIST_CAPACITY; 1,
@@ -154,6 +157,13 @@ map_symbol_to_lowlevel_and_arity! {
 
     ListGetUnsafe; DICT_LIST_GET_UNSAFE; 2,
 
+    CryptoSha256; CRYPTO_SHA256; 1,
+    CryptoBlake3; CRYPTO_BLAKE3; 1,
+    CryptoConstantTimeEq; CRYPTO_CONSTANT_TIME_EQ; 2,
+
+    RandomU64s; RANDOM_U64S_LOWLEVEL; 3,
+    RandomBytes; RANDOM_BYTES_LOWLEVEL; 3,
+
     NumAdd; NUM_ADD; 2,
     NumAddWrap; NUM_ADD_WRAP; 2,
     NumAddChecked; NUM_ADD_CHECKED_LOWLEVEL; 2,
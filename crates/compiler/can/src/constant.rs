@@ -0,0 +1,140 @@
+//! Evaluate simple top-level constants, like `maxRetries = 5` or `timeoutMs = 60 * 1000`,
+//! at compile time. This is only used to show their values (e.g. in docs), so anything
+//! other than literals and a few arithmetic and string operations is left unevaluated.
+use crate::expr::{DeclarationTag, Declarations, Expr, IntValue};
+use roc_collections::VecMap;
+use roc_module::symbol::Symbol;
+use roc_types::num::IntLitWidth;
+use roc_types::types::{AliasCommon, Type};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    Int(i128),
+    Float(f64),
+    Str(Box<str>),
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constant::Int(int) => write!(f, "{int}"),
+            Constant::Float(float) => {
+                let string = float.to_string();
+
+                if string.contains('.') {
+                    f.write_str(&string)
+                } else {
+                    // Make sure e.g. 2.0 doesn't look like an integer
+                    write!(f, "{string}.0")
+                }
+            }
+            Constant::Str(string) => {
+                f.write_str("\"")?;
+
+                for ch in string.chars() {
+                    match ch {
+                        '"' => f.write_str("\\\"")?,
+                        '\\' => f.write_str("\\\\")?,
+                        '$' => f.write_str("\\$")?,
+                        '\n' => f.write_str("\\n")?,
+                        '\r' => f.write_str("\\r")?,
+                        '\t' => f.write_str("\\t")?,
+                        _ => write!(f, "{ch}")?,
+                    }
+                }
+
+                f.write_str("\"")
+            }
+        }
+    }
+}
+
+/// The values of all the top-level constants in these declarations which could be evaluated.
+pub fn fold_constants(declarations: &Declarations) -> VecMap<Symbol, Constant> {
+    let mut constants = VecMap::default();
+
+    // Declarations are sorted so that a constant always comes after the ones it refers to.
+    for index in 0..declarations.len() {
+        if !matches!(declarations.declarations[index], DeclarationTag::Value) {
+            continue;
+        }
+
+        let opt_width = declarations.annotations[index]
+            .as_ref()
+            .and_then(|annotation| annotated_int_width(&annotation.signature));
+
+        let opt_constant = fold_constant(&declarations.expressions[index].value, &constants)
+            .filter(|constant| match (constant, opt_width) {
+                (Constant::Int(int), Some(width)) => {
+                    *int >= width.min_value() && (*int < 0 || *int as u128 <= width.max_value())
+                }
+                _ => true,
+            });
+
+        if let Some(constant) = opt_constant {
+            constants.insert(declarations.symbols[index].value, constant);
+        }
+    }
+
+    constants
+}
+
+/// Evaluate the given expression, if it's simple enough. Lookups can refer to any of the given
+/// (already evaluated) constants.
+pub fn fold_constant(expr: &Expr, constants: &VecMap<Symbol, Constant>) -> Option<Constant> {
+    use Constant::*;
+
+    match expr {
+        Expr::Num(_, _, int_value, _) | Expr::Int(_, _, _, int_value, _) => match int_value {
+            IntValue::I128(bytes) => Some(Int(i128::from_ne_bytes(*bytes))),
+            IntValue::U128(bytes) => i128::try_from(u128::from_ne_bytes(*bytes)).ok().map(Int),
+        },
+        Expr::Float(_, _, _, float, _) => Some(Float(*float)),
+        Expr::Str(string) => Some(Str(string.clone())),
+        Expr::Var(symbol, _) => constants.get(symbol).cloned(),
+        Expr::Call(boxed, args, _) => {
+            let symbol = match &boxed.1.value {
+                Expr::Var(symbol, _) => *symbol,
+                _ => return None,
+            };
+
+            let mut folded_args = Vec::with_capacity(args.len());
+
+            for (_, loc_arg) in args {
+                folded_args.push(fold_constant(&loc_arg.value, constants)?);
+            }
+
+            match (symbol, folded_args.as_slice()) {
+                (Symbol::NUM_NEG, [Int(a)]) => a.checked_neg().map(Int),
+                (Symbol::NUM_NEG, [Float(a)]) => Some(Float(-a)),
+                (Symbol::NUM_ADD, [Int(a), Int(b)]) => a.checked_add(*b).map(Int),
+                (Symbol::NUM_SUB, [Int(a), Int(b)]) => a.checked_sub(*b).map(Int),
+                (Symbol::NUM_MUL, [Int(a), Int(b)]) => a.checked_mul(*b).map(Int),
+                (Symbol::NUM_DIV_TRUNC, [Int(a), Int(b)]) => a.checked_div(*b).map(Int),
+                (Symbol::NUM_REM, [Int(a), Int(b)]) => a.checked_rem(*b).map(Int),
+                (Symbol::STR_CONCAT, [Str(a), Str(b)]) => {
+                    Some(Str(format!("{a}{b}").into_boxed_str()))
+                }
+                // Float arithmetic is left alone, because its result depends on whether
+                // the constant ends up being an F64 or a Dec.
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// If this annotation is a specific integer type like `U8`, which one it is.
+fn annotated_int_width(signature: &Type) -> Option<IntLitWidth> {
+    use IntLitWidth::*;
+
+    let symbol = match signature {
+        Type::Alias { symbol, .. } | Type::DelayedAlias(AliasCommon { symbol, .. }) => *symbol,
+        _ => return None,
+    };
+
+    [U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, Nat]
+        .into_iter()
+        .find(|width| width.symbol() == symbol)
+}
@@ -424,6 +424,9 @@ fn deep_copy_expr_help<C: CopyEnv>(env: &mut C, copied: &mut Vec<Variable>, expr
             foreign_symbol,
             args,
             ret_var,
+            arg_ownership,
+            ret_ownership,
+            pure,
         } => ForeignCall {
             foreign_symbol: foreign_symbol.clone(),
             args: args
@@ -431,6 +434,9 @@ fn deep_copy_expr_help<C: CopyEnv>(env: &mut C, copied: &mut Vec<Variable>, expr
                 .map(|(var, expr)| (sub!(*var), go_help!(expr)))
                 .collect(),
             ret_var: sub!(*ret_var),
+            arg_ownership: arg_ownership.clone(),
+            ret_ownership: *ret_ownership,
+            pure: *pure,
         },
 
         Closure(ClosureData {
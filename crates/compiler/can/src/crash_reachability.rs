@@ -0,0 +1,189 @@
+//! Finds out which top-level defs can crash at runtime, and through which calls.
+//!
+//! Every module gets a [CrashSummary] after it is solved: the defs that can crash by themselves,
+//! and the top-level defs that each def refers to. Putting the summaries of all modules together
+//! gives a graph in which [crash_path] looks for the shortest path from a def to a crash.
+//!
+//! A reference counts as a call, even when the function is only passed around; and a crash in a
+//! nested function counts for the top-level def it is in. So the analysis errs on the side of
+//! reporting defs which never crash in practice. Builtins are assumed not to crash.
+
+use crate::abilities::AbilitiesStore;
+use crate::expr::{DeclarationTag, Declarations, Expr};
+use crate::traverse::{walk_expr, Visitor};
+use roc_collections::{MutMap, VecMap};
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_region::all::Region;
+use roc_types::subs::{Subs, Variable};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrashKind {
+    /// A `crash` expression
+    Crash,
+    /// A `when` whose branches do not cover every possible value
+    NonExhaustiveWhen,
+    /// Code that did not compile, like a reference to an undefined name
+    RuntimeError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrashSite {
+    pub kind: CrashKind,
+    pub region: Region,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct CrashSummary {
+    /// The first crash in each top-level def that can crash by itself
+    pub crash_sites: VecMap<Symbol, CrashSite>,
+    /// The symbols each top-level def refers to, with the region of the first reference
+    pub references: VecMap<Symbol, Vec<(Symbol, Region)>>,
+}
+
+/// One step of a path to a crash: the def, and the region in its module where it calls the next
+/// def, or where it crashes if it's the last step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrashStep {
+    pub symbol: Symbol,
+    pub region: Region,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashPath {
+    pub steps: Vec<CrashStep>,
+    pub kind: CrashKind,
+}
+
+pub fn summarize(
+    decls: &Declarations,
+    subs: &Subs,
+    abilities_store: &AbilitiesStore,
+) -> CrashSummary {
+    let mut summary = CrashSummary::default();
+
+    for (index, tag) in decls.declarations.iter().enumerate() {
+        match tag {
+            DeclarationTag::Value
+            | DeclarationTag::Function(_)
+            | DeclarationTag::Recursive(_)
+            | DeclarationTag::TailRecursive(_) => {}
+            DeclarationTag::Expectation
+            | DeclarationTag::ExpectationFx
+            | DeclarationTag::Destructure(_)
+            | DeclarationTag::MutualRecursion { .. } => continue,
+        }
+
+        let symbol = decls.symbols[index].value;
+        let loc_expr = &decls.expressions[index];
+
+        let mut visitor = SummaryVisitor {
+            subs,
+            abilities_store,
+            crash_site: None,
+            references: Vec::new(),
+        };
+        visitor.visit_expr(&loc_expr.value, loc_expr.region, decls.variables[index]);
+
+        if let Some(crash_site) = visitor.crash_site {
+            summary.crash_sites.insert(symbol, crash_site);
+        }
+
+        summary.references.insert(symbol, visitor.references);
+    }
+
+    summary
+}
+
+/// The shortest path from `symbol` to a crash, if it can crash
+pub fn crash_path(summaries: &MutMap<ModuleId, CrashSummary>, symbol: Symbol) -> Option<CrashPath> {
+    let summary_of = |symbol: Symbol| summaries.get(&symbol.module_id());
+
+    // breadth-first, remembering how each def was reached
+    let mut reached_from: MutMap<Symbol, Option<(Symbol, Region)>> = MutMap::default();
+    let mut queue = std::collections::VecDeque::from([symbol]);
+    reached_from.insert(symbol, None);
+
+    while let Some(current) = queue.pop_front() {
+        let summary = match summary_of(current) {
+            Some(summary) => summary,
+            None => continue,
+        };
+
+        if let Some(crash_site) = summary.crash_sites.get(&current) {
+            let mut steps = vec![CrashStep {
+                symbol: current,
+                region: crash_site.region,
+            }];
+
+            let mut step = current;
+            while let Some(Some((caller, region))) = reached_from.get(&step) {
+                steps.push(CrashStep {
+                    symbol: *caller,
+                    region: *region,
+                });
+                step = *caller;
+            }
+
+            steps.reverse();
+
+            return Some(CrashPath {
+                steps,
+                kind: crash_site.kind,
+            });
+        }
+
+        for (callee, region) in summary.references.get(&current).into_iter().flatten() {
+            let is_top_level = summary_of(*callee)
+                .map_or(false, |summary| summary.references.contains_key(callee));
+
+            if is_top_level && !reached_from.contains_key(callee) {
+                reached_from.insert(*callee, Some((current, *region)));
+                queue.push_back(*callee);
+            }
+        }
+    }
+
+    None
+}
+
+struct SummaryVisitor<'a> {
+    subs: &'a Subs,
+    abilities_store: &'a AbilitiesStore,
+    crash_site: Option<CrashSite>,
+    references: Vec<(Symbol, Region)>,
+}
+
+impl SummaryVisitor<'_> {
+    fn add_crash(&mut self, kind: CrashKind, region: Region) {
+        self.crash_site.get_or_insert(CrashSite { kind, region });
+    }
+
+    fn add_reference(&mut self, symbol: Symbol, region: Region) {
+        if !self.references.iter().any(|(other, _)| *other == symbol) {
+            self.references.push((symbol, region));
+        }
+    }
+}
+
+impl Visitor for SummaryVisitor<'_> {
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        match expr {
+            Expr::Crash { .. } => self.add_crash(CrashKind::Crash, region),
+            Expr::RuntimeError(_) => self.add_crash(CrashKind::RuntimeError, region),
+            Expr::When { exhaustive, .. } if exhaustive.is_non_exhaustive(self.subs) => {
+                self.add_crash(CrashKind::NonExhaustiveWhen, region)
+            }
+            Expr::Var(symbol, _) => self.add_reference(*symbol, region),
+            Expr::AbilityMember(_, Some(specialization_id), _) => {
+                // only the specialization that was resolved gets called
+                if let Some(specialization) = self.abilities_store.get_resolved(*specialization_id)
+                {
+                    self.add_reference(specialization, region);
+                }
+            }
+            _ => {}
+        }
+
+        walk_expr(self, expr, var);
+    }
+}
@@ -0,0 +1,263 @@
+//! Finds the top-level defs, types and imports that nothing uses, across all modules of a package.
+//!
+//! Every module gets a [ReferenceSummary] after it is solved: its top-level defs and types, and
+//! the symbols each of them refers to. [find_unused] starts from the symbols the package exposes
+//! (or the app provides), and follows the references through the summaries of all modules.
+//! Whatever it doesn't reach is unused, and so are the imports that only unused code needs.
+//!
+//! Top-level `expect`s and destructures count as used, and so does everything they refer to.
+//! The implementations of abilities for an opaque type are used when the opaque type is.
+
+use crate::abilities::AbilitiesStore;
+use crate::def::Annotation;
+use crate::expr::{DeclarationTag, Declarations, Expr, OpaqueWrapFunctionData};
+use crate::pattern::Pattern;
+use crate::traverse::{walk_expr, walk_pattern, Visitor};
+use roc_collections::{MutMap, MutSet, VecMap, VecSet};
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_region::all::Region;
+use roc_types::subs::Variable;
+use roc_types::types::{Alias, MemberImpl};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopLevelKind {
+    Value,
+    Type,
+}
+
+#[derive(Debug, Clone)]
+pub struct TopLevel {
+    pub kind: TopLevelKind,
+    pub region: Region,
+    pub references: Vec<Symbol>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ReferenceSummary {
+    pub top_levels: VecMap<Symbol, TopLevel>,
+    /// What the module exposes (or the app provides)
+    pub exposed: Vec<Symbol>,
+    /// Symbols that are used no matter what, like the ones `expect`s refer to
+    pub always_used: Vec<Symbol>,
+    /// The modules this module imports, with the region of the import in its header
+    pub imports: Vec<(ModuleId, Region)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnusedKind {
+    Value(Symbol),
+    Type(Symbol),
+    Import(ModuleId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unused {
+    /// The module the unused def, type or import is in
+    pub module_id: ModuleId,
+    pub region: Region,
+    pub kind: UnusedKind,
+}
+
+pub fn summarize(
+    home: ModuleId,
+    decls: &Declarations,
+    aliases: &MutMap<Symbol, (bool, Alias)>,
+    abilities_store: &AbilitiesStore,
+    imports: &MutMap<ModuleId, Region>,
+    exposed: &VecSet<Symbol>,
+) -> ReferenceSummary {
+    let mut summary = ReferenceSummary {
+        exposed: exposed.iter().copied().collect(),
+        imports: imports
+            .iter()
+            .filter(|(module_id, _)| !module_id.is_builtin())
+            .map(|(module_id, region)| (*module_id, *region))
+            .collect(),
+        ..Default::default()
+    };
+
+    for (index, tag) in decls.declarations.iter().enumerate() {
+        let loc_expr = &decls.expressions[index];
+
+        let mut visitor = ReferenceVisitor {
+            abilities_store,
+            references: MutSet::default(),
+        };
+        visitor.visit_expr(&loc_expr.value, loc_expr.region, decls.variables[index]);
+
+        if let Some(annotation) = &decls.annotations[index] {
+            visitor.visit_annotation(annotation);
+        }
+
+        match tag {
+            DeclarationTag::Value
+            | DeclarationTag::Function(_)
+            | DeclarationTag::Recursive(_)
+            | DeclarationTag::TailRecursive(_) => {
+                let loc_symbol = decls.symbols[index];
+                let top_level = TopLevel {
+                    kind: TopLevelKind::Value,
+                    region: loc_symbol.region,
+                    references: visitor.references.into_iter().collect(),
+                };
+
+                summary.top_levels.insert(loc_symbol.value, top_level);
+            }
+            DeclarationTag::Expectation
+            | DeclarationTag::ExpectationFx
+            | DeclarationTag::Destructure(_) => {
+                summary.always_used.extend(visitor.references);
+            }
+            DeclarationTag::MutualRecursion { .. } => {
+                // only marks the start of the defs that follow it
+            }
+        }
+    }
+
+    for (symbol, (_, alias)) in aliases.iter() {
+        if symbol.module_id() != home {
+            continue;
+        }
+
+        let mut references = alias.typ.symbols();
+
+        // the implementations of abilities for an opaque type are called through the ability
+        references.extend(abilities_store.iter_declared_implementations().filter_map(
+            |(impl_key, member_impl)| match member_impl {
+                MemberImpl::Impl(specialization) if impl_key.opaque == *symbol => {
+                    Some(*specialization)
+                }
+                _ => None,
+            },
+        ));
+
+        let top_level = TopLevel {
+            kind: TopLevelKind::Type,
+            region: alias.region,
+            references,
+        };
+
+        summary.top_levels.insert(*symbol, top_level);
+    }
+
+    summary
+}
+
+/// The top-level defs, types and imports of the summarized modules that can't be reached from
+/// what they expose, starting from the exposed symbols of the `root_modules`.
+pub fn find_unused(
+    summaries: &MutMap<ModuleId, ReferenceSummary>,
+    root_modules: &[ModuleId],
+) -> Vec<Unused> {
+    let mut used: MutSet<Symbol> = MutSet::default();
+    let mut stack: Vec<Symbol> = Vec::new();
+
+    for module_id in root_modules {
+        if let Some(summary) = summaries.get(module_id) {
+            stack.extend(summary.exposed.iter().copied());
+        }
+    }
+
+    for summary in summaries.values() {
+        stack.extend(summary.always_used.iter().copied());
+    }
+
+    while let Some(symbol) = stack.pop() {
+        if !used.insert(symbol) {
+            continue;
+        }
+
+        let top_level = summaries
+            .get(&symbol.module_id())
+            .and_then(|summary| summary.top_levels.get(&symbol));
+
+        if let Some(top_level) = top_level {
+            stack.extend(top_level.references.iter().copied());
+        }
+    }
+
+    let mut unused = Vec::new();
+
+    for (module_id, summary) in summaries.iter() {
+        // the modules this module still needs after the unused code is removed
+        let mut needed_modules: MutSet<ModuleId> = summary
+            .always_used
+            .iter()
+            .map(|symbol| symbol.module_id())
+            .collect();
+
+        for (symbol, top_level) in summary.top_levels.iter() {
+            if used.contains(symbol) {
+                needed_modules.extend(top_level.references.iter().map(|s| s.module_id()));
+            } else {
+                let kind = match top_level.kind {
+                    TopLevelKind::Value => UnusedKind::Value(*symbol),
+                    TopLevelKind::Type => UnusedKind::Type(*symbol),
+                };
+
+                unused.push(Unused {
+                    module_id: *module_id,
+                    region: top_level.region,
+                    kind,
+                });
+            }
+        }
+
+        for (imported, region) in summary.imports.iter() {
+            if !needed_modules.contains(imported) {
+                unused.push(Unused {
+                    module_id: *module_id,
+                    region: *region,
+                    kind: UnusedKind::Import(*imported),
+                });
+            }
+        }
+    }
+
+    unused
+}
+
+struct ReferenceVisitor<'a> {
+    abilities_store: &'a AbilitiesStore,
+    references: MutSet<Symbol>,
+}
+
+impl Visitor for ReferenceVisitor<'_> {
+    fn visit_annotation(&mut self, annotation: &Annotation) {
+        self.references.extend(annotation.signature.symbols());
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        match expr {
+            Expr::Var(symbol, _) => {
+                self.references.insert(*symbol);
+            }
+            Expr::AbilityMember(member, specialization_id, _) => {
+                self.references.insert(*member);
+
+                if let Some(specialization) =
+                    specialization_id.and_then(|id| self.abilities_store.get_resolved(id))
+                {
+                    self.references.insert(specialization);
+                }
+            }
+            Expr::OpaqueRef { name, .. }
+            | Expr::OpaqueWrapFunction(OpaqueWrapFunctionData {
+                opaque_name: name, ..
+            }) => {
+                self.references.insert(*name);
+            }
+            _ => {}
+        }
+
+        walk_expr(self, expr, var);
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern, _region: Region, _opt_var: Option<Variable>) {
+        if let Pattern::UnwrappedOpaque { opaque, .. } = pattern {
+            self.references.insert(*opaque);
+        }
+
+        walk_pattern(self, pattern);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::abilities::AbilityMemberData;
+use crate::abilities::DefaultImpl;
 use crate::abilities::ImplKey;
 use crate::abilities::MemberVariables;
 use crate::abilities::PendingMemberType;
@@ -10,13 +11,13 @@ use crate::annotation::IntroducedVariables;
 use crate::annotation::OwnedNamedOrAble;
 use crate::derive;
 use crate::env::Env;
-use crate::expr::get_lookup_symbols;
 use crate::expr::AnnotatedMark;
 use crate::expr::ClosureData;
 use crate::expr::Declarations;
 use crate::expr::Expr::{self, *};
 use crate::expr::StructAccessorData;
 use crate::expr::{canonicalize_expr, Output, Recursive};
+use crate::expr::{expect_capturing_compared_values, toplevel_expect_capturing_compared_values};
 use crate::pattern::{canonicalize_def_header_pattern, BindingsFromPattern, Pattern};
 use crate::procedure::References;
 use crate::scope::create_alias;
@@ -25,7 +26,8 @@ use roc_collections::ReferenceMatrix;
 use roc_collections::VecMap;
 use roc_collections::VecSet;
 use roc_collections::{ImSet, MutMap, SendMap};
-use roc_error_macros::internal_error;
+use roc_error_macros::{crash_report, internal_error};
+use roc_module::called_via::CalledVia;
 use roc_module::ident::Ident;
 use roc_module::ident::Lowercase;
 use roc_module::symbol::IdentId;
@@ -141,6 +143,9 @@ enum PendingValueDef<'a> {
         &'a Loc<ast::TypeAnnotation<'a>>,
         &'a Loc<ast::Expr<'a>>,
     ),
+    /// An opaque type's implementation of an ability member that forwards to the member's
+    /// default implementation
+    Defaulted(Loc<Pattern>, DefaultImpl),
 }
 
 impl PendingValueDef<'_> {
@@ -149,6 +154,23 @@ impl PendingValueDef<'_> {
             PendingValueDef::AnnotationOnly(_, loc_pattern, _) => loc_pattern,
             PendingValueDef::Body(loc_pattern, _) => loc_pattern,
             PendingValueDef::TypedBody(_, loc_pattern, _, _) => loc_pattern,
+            PendingValueDef::Defaulted(loc_pattern, _) => loc_pattern,
+        }
+    }
+
+    /// The whole def, from the annotation or pattern to the end of the body
+    fn region(&self) -> Region {
+        match self {
+            PendingValueDef::AnnotationOnly(loc_ann_pattern, _, loc_ann) => {
+                Region::span_across(&loc_ann_pattern.region, &loc_ann.region)
+            }
+            PendingValueDef::Body(loc_pattern, loc_expr) => {
+                Region::span_across(&loc_pattern.region, &loc_expr.region)
+            }
+            PendingValueDef::TypedBody(loc_ann_pattern, _, _, loc_expr) => {
+                Region::span_across(&loc_ann_pattern.region, &loc_expr.region)
+            }
+            PendingValueDef::Defaulted(loc_pattern, _) => loc_pattern.region,
         }
     }
 }
@@ -157,6 +179,8 @@ impl PendingValueDef<'_> {
 struct PendingAbilityMember<'a> {
     name: Loc<Symbol>,
     typ: Loc<ast::TypeAnnotation<'a>>,
+    /// The member's default implementation, and its definition
+    default: Option<(DefaultImpl, PendingValueDef<'a>)>,
 }
 
 #[derive(Debug, Clone)]
@@ -806,6 +830,19 @@ fn canonicalize_opaque<'a>(
                     });
                 }
 
+                // Members the opaque doesn't implement itself use their default implementation,
+                // if they have one.
+                let mut not_implemented = not_implemented;
+                not_implemented.retain(|member| {
+                    match defaulted_member_impl(env, scope, name.value, name_str, *member, region) {
+                        Some(defaulted_def) => {
+                            derived_defs.push(defaulted_def);
+                            false
+                        }
+                        None => true,
+                    }
+                });
+
                 if !not_implemented.is_empty() {
                     // We'll generate runtime errors for the members that are needed but
                     // unspecified.
@@ -851,6 +888,21 @@ fn canonicalize_opaque<'a>(
                     .register_declared_implementations(name.value, impls);
 
                 derived_abilities.push(Loc::at(ability_region, ability));
+            } else if !members.is_empty()
+                && members.iter().all(|member| {
+                    scope
+                        .abilities_store
+                        .default_implementation(*member)
+                        .is_some()
+                })
+            {
+                // Every member has a default implementation, so the opaque can implement the
+                // ability without specifying any members.
+                for &member in members.iter() {
+                    derived_defs.extend(defaulted_member_impl(
+                        env, scope, name.value, name_str, member, region,
+                    ));
+                }
             } else {
                 // There was no record specified of functions to use for
                 // members, but also this isn't a builtin ability, so we don't
@@ -902,6 +954,44 @@ fn canonicalize_opaque<'a>(
     })
 }
 
+/// Implements `member` for `opaque` with the member's default implementation, if it has one, by
+/// introducing a def that forwards to the default.
+fn defaulted_member_impl<'a>(
+    env: &Env<'a>,
+    scope: &mut Scope,
+    opaque: Symbol,
+    opaque_name: &str,
+    member: Symbol,
+    region: Region,
+) -> Option<DerivedDef<'a>> {
+    let default_impl = scope.abilities_store.default_implementation(member)?;
+
+    let member_name = if member.module_id() == env.home {
+        scope.locals.ident_ids.get_name(member.ident_id())
+    } else {
+        env.dep_idents
+            .get(&member.module_id())
+            .and_then(|ident_ids| ident_ids.get_name(member.ident_id()))
+    };
+    let impl_name = format!("#{}_{}", opaque_name, member_name.unwrap_or_default());
+
+    // Two abilities the opaque implements may have members with the same name.
+    let impl_symbol = scope
+        .introduce_str(&impl_name, region)
+        .unwrap_or_else(|_| scope.gen_unique_symbol());
+
+    scope
+        .abilities_store
+        .register_defaulted_implementation(opaque, member, impl_symbol);
+
+    let loc_pattern = Loc::at(region, Pattern::Identifier(impl_symbol));
+
+    Some(Loc::at(
+        region,
+        PendingValue::Def(PendingValueDef::Defaulted(loc_pattern, default_impl)),
+    ))
+}
+
 #[inline(always)]
 pub(crate) fn canonicalize_defs<'a>(
     env: &mut Env<'a>,
@@ -946,6 +1036,20 @@ pub(crate) fn canonicalize_defs<'a>(
                     name.value,
                     members.iter().map(|mem| mem.name.value).collect(),
                 );
+
+                // Register default implementations right away, so that opaques canonicalized
+                // alongside the ability can use them.
+                for member in members {
+                    if let Some((default_impl, pending_def)) = &member.default {
+                        scope
+                            .abilities_store
+                            .register_default_implementation(member.name.value, *default_impl);
+                        pending_value_defs.push(Loc::at(
+                            pending_def.loc_pattern().region,
+                            PendingValue::Def(pending_def.clone()),
+                        ));
+                    }
+                }
             }
             pending_type_defs.push(pending_type_def);
         }
@@ -1126,6 +1230,15 @@ fn canonicalize_value_defs<'a>(
             &pending.condition.value,
         );
 
+        // Inline expects get their compared values captured when they are turned into
+        // `Expr::Expect`; toplevel ones are only turned into that later, after constraining.
+        let loc_can_condition = match pattern_type {
+            PatternType::TopLevelDef => {
+                toplevel_expect_capturing_compared_values(scope, var_store, loc_can_condition)
+            }
+            _ => loc_can_condition,
+        };
+
         expects.push(loc_can_condition, pending.preceding_comment);
 
         output.union(can_output);
@@ -1355,6 +1468,7 @@ fn resolve_abilities(
                     region: member_name_region,
                 },
             typ,
+            default: _,
         } in members
         {
             let member_annot = canonicalize_annotation(
@@ -2094,6 +2208,9 @@ fn canonicalize_pending_value_def<'a>(
     // All abilities should be resolved by the time we're canonicalizing value defs.
     let pending_abilities_in_scope = &Default::default();
 
+    let def_region = pending_def.region();
+    crash_report::set_region(def_region.start().offset, def_region.end().offset);
+
     let output = match pending_def {
         AnnotationOnly(_, loc_can_pattern, loc_ann) => {
             // Make types for the body expr, even if we won't end up having a body.
@@ -2241,6 +2358,14 @@ fn canonicalize_pending_value_def<'a>(
                 None,
             )
         }
+        Defaulted(loc_can_pattern, default_impl) => canonicalize_defaulted_impl(
+            env,
+            output,
+            scope,
+            var_store,
+            loc_can_pattern,
+            default_impl,
+        ),
     };
 
     // Disallow ability specializations that aren't on the toplevel (note: we might loosen this
@@ -2258,6 +2383,115 @@ fn canonicalize_pending_value_def<'a>(
     output
 }
 
+/// Builds `\#arg1, #arg2 -> default #arg1 #arg2` for an implementation that forwards to the
+/// default implementation of its member, or just `default` if the member is a constant.
+fn canonicalize_defaulted_impl(
+    env: &mut Env,
+    mut output: Output,
+    scope: &mut Scope,
+    var_store: &mut VarStore,
+    loc_can_pattern: Loc<Pattern>,
+    default_impl: DefaultImpl,
+) -> DefOutput {
+    let region = loc_can_pattern.region;
+    let impl_symbol = match loc_can_pattern.value {
+        Pattern::Identifier(symbol) => symbol,
+        _ => internal_error!("defaulted implementations are always identifiers"),
+    };
+
+    let mut references = References::new();
+    references.insert_value_lookup(default_impl.symbol);
+    if default_impl.symbol.module_id() != env.home {
+        env.qualified_value_lookups.insert(default_impl.symbol);
+    }
+
+    let loc_default = Loc::at(region, Var(default_impl.symbol, var_store.fresh()));
+
+    let (loc_can_expr, def_references) = if default_impl.arity == 0 {
+        (loc_default, DefReferences::Value(references.clone()))
+    } else {
+        references.insert_call(default_impl.symbol);
+
+        let argument_symbols: Vec<_> = (0..default_impl.arity)
+            .map(|_| scope.gen_unique_symbol())
+            .collect();
+
+        let arguments = argument_symbols
+            .iter()
+            .map(|symbol| {
+                (
+                    var_store.fresh(),
+                    AnnotatedMark::known_exhaustive(),
+                    Loc::at(region, Pattern::Identifier(*symbol)),
+                )
+            })
+            .collect();
+
+        let call_arguments = argument_symbols
+            .iter()
+            .map(|symbol| {
+                (
+                    var_store.fresh(),
+                    Loc::at(region, Var(*symbol, var_store.fresh())),
+                )
+            })
+            .collect();
+
+        let call = Call(
+            Box::new((
+                var_store.fresh(),
+                loc_default,
+                var_store.fresh(),
+                var_store.fresh(),
+            )),
+            call_arguments,
+            CalledVia::Space,
+        );
+
+        // The closure only refers to its own arguments and a toplevel value, so it captures
+        // nothing.
+        env.closures.insert(impl_symbol, references.clone());
+        output.non_closures.insert(impl_symbol);
+
+        let closure = Closure(ClosureData {
+            function_type: var_store.fresh(),
+            closure_type: var_store.fresh(),
+            return_type: var_store.fresh(),
+            name: impl_symbol,
+            captured_symbols: Vec::new(),
+            recursive: Recursive::NotRecursive,
+            arguments,
+            loc_body: Box::new(Loc::at(region, call)),
+        });
+
+        (
+            Loc::at(region, closure),
+            DefReferences::Function(references.clone()),
+        )
+    };
+
+    output.references.union_mut(&references);
+
+    let expr_var = var_store.fresh();
+    let mut vars_by_symbol = SendMap::default();
+
+    pattern_to_vars_by_symbol(&mut vars_by_symbol, &loc_can_pattern.value, expr_var);
+
+    let def = single_can_def(
+        loc_can_pattern,
+        loc_can_expr,
+        expr_var,
+        None,
+        vars_by_symbol,
+    );
+
+    DefOutput {
+        output,
+        references: def_references,
+        def,
+    }
+}
+
 // TODO trim down these arguments!
 #[allow(clippy::too_many_arguments)]
 #[allow(clippy::cognitive_complexity)]
@@ -2430,13 +2664,18 @@ pub fn can_defs_with_return<'a>(
     let mut loc_expr: Loc<Expr> = ret_expr;
 
     for declaration in declarations.into_iter().rev() {
-        loc_expr = decl_to_let(declaration, loc_expr);
+        loc_expr = decl_to_let(scope, var_store, declaration, loc_expr);
     }
 
     (loc_expr.value, output)
 }
 
-fn decl_to_let(decl: Declaration, loc_ret: Loc<Expr>) -> Loc<Expr> {
+fn decl_to_let(
+    scope: &mut Scope,
+    var_store: &mut VarStore,
+    decl: Declaration,
+    loc_ret: Loc<Expr>,
+) -> Loc<Expr> {
     match decl {
         Declaration::Declare(def) => {
             let region = Region::span_across(&def.loc_pattern.region, &loc_ret.region);
@@ -2466,13 +2705,14 @@ fn decl_to_let(decl: Declaration, loc_ret: Loc<Expr>) -> Loc<Expr> {
 
             for ((expect_region, condition_region), condition) in it {
                 let region = Region::span_across(&expect_region, &loc_ret.region);
-                let lookups_in_cond = get_lookup_symbols(&condition);
 
-                let expr = Expr::Expect {
-                    loc_condition: Box::new(Loc::at(condition_region, condition)),
-                    loc_continuation: Box::new(loc_ret),
-                    lookups_in_cond,
-                };
+                let expr = expect_capturing_compared_values(
+                    scope,
+                    var_store,
+                    region,
+                    Loc::at(condition_region, condition),
+                    loc_ret,
+                );
 
                 loc_ret = Loc::at(region, expr);
             }
@@ -2660,9 +2900,20 @@ fn to_pending_type_def<'a>(
                     }
                 };
 
+                let default = member.default.and_then(|default| {
+                    to_pending_ability_member_default(
+                        env,
+                        scope,
+                        member_name,
+                        &member.typ,
+                        default.body,
+                    )
+                });
+
                 named_members.push(PendingAbilityMember {
                     name: Loc::at(name_region, member_sym),
                     typ: member.typ,
+                    default,
                 });
 
                 if pattern_type == PatternType::TopLevelDef {
@@ -2678,6 +2929,46 @@ fn to_pending_type_def<'a>(
     }
 }
 
+/// Introduces the default implementation of an ability member as a hidden value annotated with the
+/// member's signature, like `#isNotEq_default : a, a -> Bool | a has MyEq`.
+fn to_pending_ability_member_default<'a>(
+    env: &mut Env<'a>,
+    scope: &mut Scope,
+    member_name: &'a str,
+    typ: &'a Loc<ast::TypeAnnotation<'a>>,
+    body: &'a Loc<ast::Expr<'a>>,
+) -> Option<(DefaultImpl, PendingValueDef<'a>)> {
+    let default_name = format!("#{}_default", member_name);
+    let symbol = scope.introduce_str(&default_name, body.region).ok()?;
+
+    let default_impl = DefaultImpl {
+        symbol,
+        arity: annotation_arity(&typ.value) as u32,
+    };
+    let loc_pattern = &*env
+        .arena
+        .alloc(Loc::at(body.region, ast::Pattern::Identifier(member_name)));
+    let pending_def = PendingValueDef::TypedBody(
+        loc_pattern,
+        Loc::at(body.region, Pattern::Identifier(symbol)),
+        typ,
+        body,
+    );
+
+    Some((default_impl, pending_def))
+}
+
+/// The number of arguments a function annotation takes, or zero if it is not a function.
+fn annotation_arity(annotation: &ast::TypeAnnotation) -> usize {
+    match annotation {
+        ast::TypeAnnotation::Function(arguments, _) => arguments.len(),
+        ast::TypeAnnotation::Where(annotation, _) => annotation_arity(&annotation.value),
+        ast::TypeAnnotation::SpaceBefore(annotation, _)
+        | ast::TypeAnnotation::SpaceAfter(annotation, _) => annotation_arity(annotation),
+        _ => 0,
+    }
+}
+
 enum PendingValue<'a> {
     Def(PendingValueDef<'a>),
     Dbg(PendingExpectOrDbg<'a>),
@@ -2847,6 +3138,47 @@ fn correct_mutual_recursive_type_alias(
     for (cycle, _is_initial) in sccs.groups() {
         debug_assert!(cycle.count_ones() > 0);
 
+        let is_mutually_recursive = cycle.count_ones() > 1;
+
+        // In a mutually recursive group, an alias which is neither a tag union nor just another
+        // alias (e.g. `Items : List Expr`) can't be made recursive itself. That's fine as long as
+        // the recursion goes through one of the tag unions in the group, so we process the tag
+        // unions first, and then instantiate their finished (recursive) versions into the others.
+        let mut tag_unions = bitvec::vec::BitVec::<usize>::repeat(false, capacity);
+        let mut others = bitvec::vec::BitVec::<usize>::repeat(false, capacity);
+
+        if is_mutually_recursive {
+            for index in cycle.iter_ones() {
+                match aliases[index].typ {
+                    Type::TagUnion(..) | Type::RecursiveTagUnion(..) => tag_unions.set(index, true),
+                    Type::DelayedAlias(..) | Type::Alias { .. } => {}
+                    _ => others.set(index, true),
+                }
+            }
+
+            if tag_unions.not_any() {
+                // Nothing in this cycle is a tag union, so the recursion can never bottom out.
+                report_alias_cycle(
+                    env,
+                    &matrix,
+                    &symbols_introduced,
+                    &mut aliases,
+                    cycle,
+                    cycle.first_one().unwrap(),
+                );
+
+                solved_aliases = solved_aliases | cycle;
+
+                continue;
+            }
+        }
+
+        let mut order: Vec<usize> = cycle.iter_ones().collect();
+        order.sort_by_key(|index| !tag_unions[*index]);
+
+        // The tag unions in this group which have already been made recursive
+        let mut finished_tag_unions = bitvec::vec::BitVec::<usize>::repeat(false, capacity);
+
         // We need to instantiate the alias with any symbols in the currrent module it
         // depends on.
         //
@@ -2861,7 +3193,7 @@ fn correct_mutual_recursive_type_alias(
         // alias in the cycle.
         let mut can_still_report_error = true;
 
-        for index in cycle.iter_ones() {
+        for index in order {
             // Don't try to instantiate the alias itself in its own definition.
             to_instantiate.set(index, false);
 
@@ -2917,7 +3249,12 @@ fn correct_mutual_recursive_type_alias(
 
             std::mem::swap(&mut alias_type, &mut alias.typ);
 
+            let is_other = others[index];
             let can_instantiate_symbol = |s| match symbols_introduced.iter().position(|i| *i == s) {
+                Some(s_index) if is_other && finished_tag_unions[s_index] => scratchpad
+                    .iter()
+                    .find(|(i, _)| *i == s_index)
+                    .map(|(_, alias)| alias),
                 Some(s_index) if to_instantiate[s_index] => aliases.get(s_index),
                 _ => None,
             };
@@ -2964,9 +3301,26 @@ fn correct_mutual_recursive_type_alias(
             // Now mark the alias recursive, if it needs to be.
             let rec = symbols_introduced[index];
             let is_self_recursive = cycle.count_ones() == 1 && matrix.get_row_col(index, index);
-            let is_mutually_recursive = cycle.count_ones() > 1;
 
-            if is_self_recursive || is_mutually_recursive {
+            if is_other {
+                // This alias now has the finished tag unions inlined into it. If it still refers
+                // to itself, it does so without going through a tag union, which is degenerate.
+                if alias.typ.contains_delayed_alias(rec) {
+                    let (region, kind) = (alias.region, alias.kind);
+
+                    alias.typ = Type::Error;
+
+                    if can_still_report_error {
+                        let path = alias_cycle_path(&matrix, &others, index);
+                        let rest = cycle_hops(&symbols_introduced, &aliases, path);
+
+                        env.problems
+                            .push(Problem::CyclicAlias(rec, region, rest, kind));
+
+                        can_still_report_error = false;
+                    }
+                }
+            } else if is_self_recursive || is_mutually_recursive {
                 let _made_recursive = make_tag_union_of_alias_recursive(
                     env,
                     rec,
@@ -2975,6 +3329,10 @@ fn correct_mutual_recursive_type_alias(
                     var_store,
                     &mut can_still_report_error,
                 );
+
+                if tag_unions[index] {
+                    finished_tag_unions.set(index, true);
+                }
             }
         }
 
@@ -2993,10 +3351,12 @@ fn correct_mutual_recursive_type_alias(
 
         if all_are_narrow {
             // This cycle is illegal!
-            let mut indices = cycle.iter_ones();
-            let first_index = indices.next().unwrap();
-
-            let rest: Vec<Symbol> = indices.map(|i| symbols_introduced[i]).collect();
+            let first_index = cycle.first_one().unwrap();
+            let rest = cycle_hops(
+                &symbols_introduced,
+                &aliases,
+                alias_cycle_path(&matrix, cycle, first_index),
+            );
 
             let alias_name = symbols_introduced[first_index];
             let alias = aliases.get_mut(first_index).unwrap();
@@ -3020,11 +3380,92 @@ fn correct_mutual_recursive_type_alias(
     unsafe { VecMap::zip(symbols_introduced, aliases) }
 }
 
+/// Report a cycle of aliases in which nothing is a tag union, starting from the alias at
+/// `start`, and turn every alias in the cycle into an error type.
+fn report_alias_cycle(
+    env: &mut Env,
+    matrix: &ReferenceMatrix,
+    symbols_introduced: &[Symbol],
+    aliases: &mut [Alias],
+    cycle: &bitvec::slice::BitSlice<usize>,
+    start: usize,
+) {
+    let rest = cycle_hops(
+        symbols_introduced,
+        aliases,
+        alias_cycle_path(matrix, cycle, start),
+    );
+
+    let alias = &aliases[start];
+    let problem = Problem::CyclicAlias(symbols_introduced[start], alias.region, rest, alias.kind);
+    env.problems.push(problem);
+
+    for index in cycle.iter_ones() {
+        aliases[index].typ = Type::Error;
+    }
+}
+
+/// The shortest chain of references which leads from the alias at `start` back to itself,
+/// only passing through the aliases in `allowed`. The chain doesn't include `start` itself.
+fn alias_cycle_path(
+    matrix: &ReferenceMatrix,
+    allowed: &bitvec::slice::BitSlice<usize>,
+    start: usize,
+) -> Vec<usize> {
+    let mut came_from: Vec<Option<usize>> = vec![None; allowed.len()];
+    let mut queue = std::collections::VecDeque::from([start]);
+
+    while let Some(current) = queue.pop_front() {
+        for next in allowed.iter_ones() {
+            if !matrix.get_row_col(current, next) {
+                continue;
+            }
+
+            if next == start {
+                // Walk back to the start to recover the path
+                let mut path = Vec::new();
+                let mut index = current;
+
+                while index != start {
+                    path.push(index);
+                    index = came_from[index].unwrap();
+                }
+
+                path.reverse();
+
+                return path;
+            }
+
+            if came_from[next].is_none() {
+                came_from[next] = Some(current);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    // This can't happen for a strongly connected component, but there's no need to panic
+    // over an error message.
+    allowed
+        .iter_ones()
+        .filter(|index| *index != start)
+        .collect()
+}
+
+fn cycle_hops(
+    symbols_introduced: &[Symbol],
+    aliases: &[Alias],
+    path: Vec<usize>,
+) -> Vec<(Symbol, Region)> {
+    path.into_iter()
+        .map(|index| (symbols_introduced[index], aliases[index].region))
+        .collect()
+}
+
 fn make_tag_union_of_alias_recursive(
     env: &mut Env,
     alias_name: Symbol,
     alias: &mut Alias,
-    others: Vec<Symbol>,
+    others: Vec<(Symbol, Region)>,
     var_store: &mut VarStore,
     can_report_cyclic_error: &mut bool,
 ) -> Result<(), ()> {
@@ -3108,7 +3549,7 @@ fn make_tag_union_recursive_help<'a, 'b>(
     infer_ext_in_output_variables: impl Iterator<Item = Type>,
     alias_kind: AliasKind,
     region: Region,
-    others: Vec<Symbol>,
+    others: Vec<(Symbol, Region)>,
     typ: &'b mut Type,
     var_store: &mut VarStore,
     can_report_cyclic_error: &mut bool,
@@ -3219,7 +3660,7 @@ fn mark_cyclic_alias(
     symbol: Symbol,
     alias_kind: AliasKind,
     region: Region,
-    others: Vec<Symbol>,
+    others: Vec<(Symbol, Region)>,
     report: bool,
 ) {
     *typ = Type::Error;
@@ -213,6 +213,87 @@ fn is_eq<'a>(env: &mut Env<'a>, at_opaque: &'a str) -> ast::Expr<'a> {
     )
 }
 
+fn enum_to_int<'a>(env: &mut Env<'a>, at_opaque: &'a str) -> ast::Expr<'a> {
+    let alloc_pat = |it| env.arena.alloc(Loc::at(DERIVED_REGION, it));
+    let alloc_expr = |it| env.arena.alloc(Loc::at(DERIVED_REGION, it));
+
+    let payload = "#payload";
+
+    // \@Opaq payload
+    let opaque_ref = alloc_pat(ast::Pattern::OpaqueRef(at_opaque));
+    let opaque_apply_pattern = ast::Pattern::Apply(
+        opaque_ref,
+        &*env
+            .arena
+            .alloc([Loc::at(DERIVED_REGION, ast::Pattern::Identifier(payload))]),
+    );
+
+    // Enum.toInt payload
+    let call_member = alloc_expr(ast::Expr::Apply(
+        alloc_expr(ast::Expr::Var {
+            module_name: "Enum",
+            ident: "toInt",
+        }),
+        &*env.arena.alloc([&*alloc_expr(ast::Expr::Var {
+            module_name: "",
+            ident: payload,
+        })]),
+        CalledVia::Space,
+    ));
+
+    // \@Opaq payload -> Enum.toInt payload
+    ast::Expr::Closure(
+        env.arena
+            .alloc([Loc::at(DERIVED_REGION, opaque_apply_pattern)]),
+        call_member,
+    )
+}
+
+/// Builds `\arg -> Mod.map (Enum.member arg) @Opaq`, wrapping the tags `Enum.member` returns
+/// for the payload of the opaque.
+fn enum_map_member<'a>(
+    env: &mut Env<'a>,
+    at_opaque: &'a str,
+    (module_name, member): (&'a str, &'a str),
+) -> ast::Expr<'a> {
+    let alloc_expr = |it| env.arena.alloc(Loc::at(DERIVED_REGION, it));
+
+    let arg = "#arg";
+
+    // Enum.member arg
+    let call_member = ast::Expr::Apply(
+        alloc_expr(ast::Expr::Var {
+            module_name: "Enum",
+            ident: member,
+        }),
+        &*env.arena.alloc([&*alloc_expr(ast::Expr::Var {
+            module_name: "",
+            ident: arg,
+        })]),
+        CalledVia::Space,
+    );
+
+    // Mod.map (Enum.member arg) @Opaq
+    let call_map = alloc_expr(ast::Expr::Apply(
+        alloc_expr(ast::Expr::Var {
+            module_name,
+            ident: "map",
+        }),
+        env.arena.alloc([
+            &*alloc_expr(call_member),
+            alloc_expr(ast::Expr::OpaqueRef(at_opaque)),
+        ]),
+        CalledVia::Space,
+    ));
+
+    // \arg -> Mod.map (Enum.member arg) @Opaq
+    ast::Expr::Closure(
+        env.arena
+            .alloc([Loc::at(DERIVED_REGION, ast::Pattern::Identifier(arg))]),
+        call_map,
+    )
+}
+
 pub const DERIVED_REGION: Region = Region::zero();
 
 pub(crate) fn synthesize_member_impl<'a>(
@@ -232,6 +313,18 @@ pub(crate) fn synthesize_member_impl<'a>(
         Symbol::DECODE_DECODER => (format!("#{}_decoder", opaque_name), decoder(env, at_opaque)),
         Symbol::HASH_HASH => (format!("#{}_hash", opaque_name), hash(env, at_opaque)),
         Symbol::BOOL_IS_EQ => (format!("#{}_isEq", opaque_name), is_eq(env, at_opaque)),
+        Symbol::ENUM_TO_INT => (
+            format!("#{}_toInt", opaque_name),
+            enum_to_int(env, at_opaque),
+        ),
+        Symbol::ENUM_FROM_INT => (
+            format!("#{}_fromInt", opaque_name),
+            enum_map_member(env, at_opaque, ("Result", "fromInt")),
+        ),
+        Symbol::ENUM_ALL => (
+            format!("#{}_all", opaque_name),
+            enum_map_member(env, at_opaque, ("List", "all")),
+        ),
         other => internal_error!("{:?} is not a derivable ability member!", other),
     };
 
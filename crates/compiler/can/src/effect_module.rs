@@ -1,6 +1,8 @@
 use crate::annotation::IntroducedVariables;
 use crate::def::Def;
-use crate::expr::{AnnotatedMark, ClosureData, Declarations, Expr, Recursive, WhenBranchPattern};
+use crate::expr::{
+    AnnotatedMark, ClosureData, Declarations, Expr, HostOwnership, Recursive, WhenBranchPattern,
+};
 use crate::pattern::Pattern;
 use crate::scope::Scope;
 use roc_collections::{SendMap, VecMap, VecSet};
@@ -9,7 +11,9 @@ use roc_module::ident::TagName;
 use roc_module::symbol::Symbol;
 use roc_region::all::{Loc, Region};
 use roc_types::subs::{ExhaustiveMark, RedundantMark, VarStore, Variable};
-use roc_types::types::{AliasKind, LambdaSet, OptAbleType, OptAbleVar, Type, TypeExtension};
+use roc_types::types::{
+    AliasCommon, AliasKind, AliasVar, LambdaSet, OptAbleType, OptAbleVar, Type, TypeExtension,
+};
 
 #[derive(Debug, Default, Clone, Copy)]
 pub(crate) struct HostedGeneratedFunctions {
@@ -18,6 +22,129 @@ pub(crate) struct HostedGeneratedFunctions {
     pub(crate) always: bool,
     pub(crate) loop_: bool,
     pub(crate) forever: bool,
+    pub(crate) run: bool,
+}
+
+/// The `Owned`, `Borrowed` and `Pure` aliases of a hosted module. All of them are just `a`, but
+/// wrapping an argument or result of a hosted function in `Owned` or `Borrowed` says who owns it
+/// once it has crossed between Roc and the host:
+///
+///  putLine : Owned Str -> Effect {}
+///  getLine : Effect (Borrowed Str)
+///
+/// By default the host only borrows arguments, and hands over ownership of what it returns.
+/// An `Owned` argument becomes the host's to decrement, and Roc increments a `Borrowed` result
+/// because the host keeps its own reference.
+///
+/// Wrapping the result of a host intrinsic in `Pure` promises that the host function gives the
+/// same result for the same arguments every time, and does nothing else:
+///
+///  sqrtApprox : F32 -> Pure F32
+///
+/// so the optimizer can reuse the result of a call instead of making the same call again.
+#[derive(Debug, Clone, Copy)]
+pub struct HostAliases {
+    pub owned: Symbol,
+    pub borrowed: Symbol,
+    pub pure: Symbol,
+}
+
+impl HostAliases {
+    pub(crate) fn introduce(scope: &mut Scope, var_store: &mut VarStore) -> Self {
+        let mut introduce = |name: &str| {
+            let symbol = scope.introduce(name.into(), Region::zero()).unwrap();
+            let a_var = var_store.fresh();
+
+            scope.add_alias(
+                symbol,
+                Region::zero(),
+                vec![Loc::at_zero(AliasVar::unbound("a".into(), a_var))],
+                vec![],
+                Type::Variable(a_var),
+                AliasKind::Structural,
+            );
+
+            symbol
+        };
+
+        Self {
+            owned: introduce("Owned"),
+            borrowed: introduce("Borrowed"),
+            pure: introduce("Pure"),
+        }
+    }
+
+    /// The `a` of `Pure a`, or `None` if the type isn't wrapped in `Pure`
+    fn pure_argument<'t>(&self, typ: &'t Type) -> Option<&'t Type> {
+        alias_argument(typ, self.pure)
+    }
+
+    /// Whether the annotation of a hosted function uses `Pure` anywhere other than around the
+    /// result of a host intrinsic, where it means nothing, or promises something an effect
+    /// can't keep.
+    pub(crate) fn has_misplaced_pure(&self, typ: &Type, effect_symbol: Symbol) -> bool {
+        let is_pure = |typ: &Type| self.pure_argument(typ).is_some();
+
+        match typ.shallow_structural_dealias() {
+            Type::Function(args, _, ret) => {
+                let misplaced_in_result = match self.pure_argument(ret) {
+                    Some(output) => effect_argument(output, effect_symbol).is_some(),
+                    None => effect_argument(ret, effect_symbol).map_or(false, is_pure),
+                };
+
+                args.iter().any(is_pure) || misplaced_in_result
+            }
+            not_a_function => {
+                is_pure(not_a_function)
+                    || effect_argument(not_a_function, effect_symbol).map_or(false, is_pure)
+            }
+        }
+    }
+
+    /// The ownership an argument or result is annotated with, if any
+    fn ownership(&self, typ: &Type) -> Option<HostOwnership> {
+        match alias_symbol(typ) {
+            Some(symbol) if symbol == self.owned => Some(HostOwnership::Owned),
+            Some(symbol) if symbol == self.borrowed => Some(HostOwnership::Borrowed),
+            _ => None,
+        }
+    }
+}
+
+fn alias_symbol(typ: &Type) -> Option<Symbol> {
+    match typ {
+        Type::DelayedAlias(AliasCommon { symbol, .. }) | Type::Alias { symbol, .. } => {
+            Some(*symbol)
+        }
+        _ => None,
+    }
+}
+
+/// What a hosted function's effect produces, e.g. `Str` for `Effect Str`
+fn effect_output(typ: &Type, effect_symbol: Symbol) -> &Type {
+    effect_argument(typ, effect_symbol).unwrap_or(typ)
+}
+
+/// The `a` of `Effect a`, or `None` if the type isn't an effect
+fn effect_argument(typ: &Type, effect_symbol: Symbol) -> Option<&Type> {
+    alias_argument(typ, effect_symbol)
+}
+
+/// The first argument of an alias, or `None` if the type isn't that alias
+fn alias_argument(typ: &Type, alias: Symbol) -> Option<&Type> {
+    match typ {
+        Type::DelayedAlias(AliasCommon {
+            symbol,
+            type_arguments,
+            ..
+        }) if *symbol == alias => type_arguments.first().map(|arg| &arg.value.typ),
+        Type::Alias {
+            symbol,
+            type_arguments,
+            ..
+        } if *symbol == alias => type_arguments.first().map(|arg| &arg.typ),
+        _ => None,
+    }
 }
 
 /// the Effects alias & associated functions
@@ -81,6 +208,12 @@ pub(crate) fn build_effect_builtins(
         declarations.push_def(def);
     }
 
+    // Effect.run : Effect a -> a
+    if generated_functions.run {
+        let def = helper!(build_effect_run);
+        declarations.push_def(def);
+    }
+
     // Useful when working on functions in this module. By default symbols that we named do now
     // show up with their name. We have to register them like below to make the names show up in
     // debug prints
@@ -414,6 +547,105 @@ fn build_effect_map(
     (map_symbol, def)
 }
 
+fn build_effect_run(
+    scope: &mut Scope,
+    effect_symbol: Symbol,
+    var_store: &mut VarStore,
+) -> (Symbol, Def) {
+    // Effect.run = \@Effect thunk -> thunk {}
+    //
+    // This performs the effect right where it is called. It exists so that expects can look at
+    // the result of an effect, with `roc test` answering the effect requests with the
+    // effect interpreters of the tested module.
+
+    let thunk_symbol = new_symbol!(scope, "effect_run_thunk");
+    let run_symbol = new_symbol!(scope, "run");
+
+    // `thunk {}`
+    let force_thunk_var = var_store.fresh();
+    let body = force_thunk(
+        Expr::Var(thunk_symbol, force_thunk_var),
+        force_thunk_var,
+        var_store,
+    );
+
+    // \@Effect thunk
+    let (specialized_def_type, type_arguments, lambda_set_variables) =
+        build_fresh_opaque_variables(var_store);
+    let arguments = vec![(
+        var_store.fresh(),
+        AnnotatedMark::new(var_store),
+        Loc::at_zero(Pattern::UnwrappedOpaque {
+            opaque: effect_symbol,
+            whole_var: var_store.fresh(),
+            argument: Box::new((
+                var_store.fresh(),
+                Loc::at_zero(Pattern::Identifier(thunk_symbol)),
+            )),
+            specialized_def_type,
+            type_arguments,
+            lambda_set_variables,
+        }),
+    )];
+
+    let function_var = var_store.fresh();
+    let run_closure = Expr::Closure(ClosureData {
+        function_type: function_var,
+        closure_type: var_store.fresh(),
+        return_type: var_store.fresh(),
+        name: run_symbol,
+        captured_symbols: Vec::new(),
+        recursive: Recursive::NotRecursive,
+        arguments,
+        loc_body: Box::new(Loc::at_zero(body)),
+    });
+
+    let mut introduced_variables = IntroducedVariables::default();
+
+    let signature = {
+        // Effect.run : Effect a -> a
+        let var_a = var_store.fresh();
+
+        introduced_variables.insert_named("a".into(), Loc::at_zero(var_a));
+
+        let effect_a = build_effect_opaque(
+            effect_symbol,
+            var_a,
+            Type::Variable(var_a),
+            var_store,
+            &mut introduced_variables,
+        );
+
+        let closure_var = var_store.fresh();
+        introduced_variables.insert_wildcard(Loc::at_zero(closure_var));
+        Type::Function(
+            vec![effect_a],
+            Box::new(Type::Variable(closure_var)),
+            Box::new(Type::Variable(var_a)),
+        )
+    };
+
+    let def_annotation = crate::def::Annotation {
+        signature,
+        introduced_variables,
+        aliases: VecMap::default(),
+        region: Region::zero(),
+    };
+
+    let pattern = Pattern::Identifier(run_symbol);
+    let mut pattern_vars = SendMap::default();
+    pattern_vars.insert(run_symbol, function_var);
+    let def = Def {
+        loc_pattern: Loc::at_zero(pattern),
+        loc_expr: Loc::at_zero(run_closure),
+        expr_var: function_var,
+        pattern_vars,
+        annotation: Some(def_annotation),
+    };
+
+    (run_symbol, def)
+}
+
 fn force_thunk(expr: Expr, thunk_var: Variable, var_store: &mut VarStore) -> Expr {
     let boxed = (
         thunk_var,
@@ -1334,11 +1566,20 @@ fn build_effect_loop_inner_body(
     )
 }
 
+/// The body of a function of a hosted module, which calls `roc_fx_{ident}` in the host.
+///
+/// A function returning the platform's effect, like `putLine : Str -> Effect {}`, makes the call
+/// when the effect is run. Any other function, like `sqrtApprox : F32 -> F32`, is a host
+/// intrinsic: it must be pure, so it makes the call right away, and calling it costs no more than
+/// calling the host function itself, without an effect closure to allocate and run. If its result
+/// is wrapped in `Pure`, the optimizer can also reuse the result of an earlier call with the same
+/// arguments.
 pub fn build_host_exposed_def(
     scope: &mut Scope,
     symbol: Symbol,
     ident: &str,
     effect_symbol: Symbol,
+    host_aliases: HostAliases,
     var_store: &mut VarStore,
     annotation: crate::annotation::Annotation,
 ) -> Def {
@@ -1360,7 +1601,7 @@ pub fn build_host_exposed_def(
 
     let def_body = {
         match typ.shallow_structural_dealias() {
-            Type::Function(args, _, _) => {
+            Type::Function(args, _, ret) => {
                 for i in 0..args.len() {
                     let name = format!("closure_arg_{}_{}", ident, i);
 
@@ -1381,44 +1622,69 @@ pub fn build_host_exposed_def(
                     linked_symbol_arguments.push((arg_var, Expr::Var(arg_symbol, arg_var)));
                 }
 
+                let arg_ownership = args
+                    .iter()
+                    .map(|arg| {
+                        host_aliases
+                            .ownership(arg)
+                            .unwrap_or(HostOwnership::Borrowed)
+                    })
+                    .collect();
+                let (pure, ret) = match host_aliases.pure_argument(ret) {
+                    Some(output) => (true, output),
+                    None => (false, &**ret),
+                };
+                let is_intrinsic = effect_argument(ret, effect_symbol).is_none();
+                let ret_ownership = host_aliases
+                    .ownership(effect_output(ret, effect_symbol))
+                    .unwrap_or(HostOwnership::Owned);
+
                 let foreign_symbol_name = format!("roc_fx_{}", ident);
                 let low_level_call = Expr::ForeignCall {
                     foreign_symbol: foreign_symbol_name.into(),
                     args: linked_symbol_arguments,
                     ret_var: var_store.fresh(),
+                    arg_ownership,
+                    ret_ownership,
+                    pure: pure && is_intrinsic,
                 };
 
-                let effect_closure_symbol = {
-                    let name = format!("effect_closure_{}", ident);
-
-                    let ident = name.into();
-                    scope.introduce(ident, Region::zero()).unwrap()
-                };
+                let body = if is_intrinsic {
+                    // `\closure_arg_sqrt_0 -> roc_fx_sqrt closure_arg_sqrt_0`
+                    low_level_call
+                } else {
+                    let effect_closure_symbol = {
+                        let name = format!("effect_closure_{}", ident);
 
-                let effect_closure = Expr::Closure(ClosureData {
-                    function_type: var_store.fresh(),
-                    closure_type: var_store.fresh(),
-                    return_type: var_store.fresh(),
-                    name: effect_closure_symbol,
-                    captured_symbols,
-                    recursive: Recursive::NotRecursive,
-                    arguments: vec![(
-                        var_store.fresh(),
-                        AnnotatedMark::new(var_store),
-                        Loc::at_zero(empty_record_pattern(var_store)),
-                    )],
-                    loc_body: Box::new(Loc::at_zero(low_level_call)),
-                });
+                        let ident = name.into();
+                        scope.introduce(ident, Region::zero()).unwrap()
+                    };
 
-                let (specialized_def_type, type_arguments, lambda_set_variables) =
-                    build_fresh_opaque_variables(var_store);
-                let body = Expr::OpaqueRef {
-                    opaque_var: var_store.fresh(),
-                    name: effect_symbol,
-                    argument: Box::new((var_store.fresh(), Loc::at_zero(effect_closure))),
-                    specialized_def_type,
-                    type_arguments,
-                    lambda_set_variables,
+                    let effect_closure = Expr::Closure(ClosureData {
+                        function_type: var_store.fresh(),
+                        closure_type: var_store.fresh(),
+                        return_type: var_store.fresh(),
+                        name: effect_closure_symbol,
+                        captured_symbols,
+                        recursive: Recursive::NotRecursive,
+                        arguments: vec![(
+                            var_store.fresh(),
+                            AnnotatedMark::new(var_store),
+                            Loc::at_zero(empty_record_pattern(var_store)),
+                        )],
+                        loc_body: Box::new(Loc::at_zero(low_level_call)),
+                    });
+
+                    let (specialized_def_type, type_arguments, lambda_set_variables) =
+                        build_fresh_opaque_variables(var_store);
+                    Expr::OpaqueRef {
+                        opaque_var: var_store.fresh(),
+                        name: effect_symbol,
+                        argument: Box::new((var_store.fresh(), Loc::at_zero(effect_closure))),
+                        specialized_def_type,
+                        type_arguments,
+                        lambda_set_variables,
+                    }
                 };
 
                 Expr::Closure(ClosureData {
@@ -1432,14 +1698,19 @@ pub fn build_host_exposed_def(
                     loc_body: Box::new(Loc::at_zero(body)),
                 })
             }
-            _ => {
-                // not a function
+            not_a_function => {
+                let ret_ownership = host_aliases
+                    .ownership(effect_output(not_a_function, effect_symbol))
+                    .unwrap_or(HostOwnership::Owned);
 
                 let foreign_symbol_name = format!("roc_fx_{}", ident);
                 let low_level_call = Expr::ForeignCall {
                     foreign_symbol: foreign_symbol_name.into(),
                     args: linked_symbol_arguments,
                     ret_var: var_store.fresh(),
+                    arg_ownership: Vec::new(),
+                    ret_ownership,
+                    pure: false,
                 };
 
                 let effect_closure_symbol = {
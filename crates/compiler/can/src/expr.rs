@@ -161,6 +161,13 @@ pub enum Expr {
         foreign_symbol: ForeignSymbol,
         args: Vec<(Variable, Expr)>,
         ret_var: Variable,
+        /// Whether the host takes over each argument, or only borrows it
+        arg_ownership: Vec<HostOwnership>,
+        /// Whether Roc takes over the returned value, or the host keeps owning it
+        ret_ownership: HostOwnership,
+        /// Whether the host function is annotated `Pure`, so a call can reuse the result of an
+        /// earlier one with the same arguments
+        pure: bool,
     },
 
     Closure(ClosureData),
@@ -282,6 +289,14 @@ pub enum Expr {
     RuntimeError(RuntimeError),
 }
 
+/// Who owns a value once it has crossed between Roc and a host, as annotated with `Owned` or
+/// `Borrowed` in the signatures of a hosted module
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HostOwnership {
+    Owned,
+    Borrowed,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct ExpectLookup {
     pub symbol: Symbol,
@@ -1213,10 +1228,6 @@ pub fn canonicalize_expr<'a>(
             let (loc_condition, output1) =
                 canonicalize_expr(env, var_store, scope, condition.region, &condition.value);
 
-            // Get all the lookups that were referenced in the condition,
-            // so we can print their values later.
-            let lookups_in_cond = get_lookup_symbols(&loc_condition.value);
-
             let (loc_continuation, output2) = canonicalize_expr(
                 env,
                 var_store,
@@ -1228,14 +1239,17 @@ pub fn canonicalize_expr<'a>(
             output.union(output1);
             output.union(output2);
 
-            (
-                Expect {
-                    loc_condition: Box::new(loc_condition),
-                    loc_continuation: Box::new(loc_continuation),
-                    lookups_in_cond,
-                },
-                output,
-            )
+            // Capture the lookups (and compared values) in the condition,
+            // so we can print their values later.
+            let expr = expect_capturing_compared_values(
+                scope,
+                var_store,
+                region,
+                loc_condition,
+                loc_continuation,
+            );
+
+            (expr, output)
         }
         ast::Expr::Dbg(condition, continuation) => {
             let mut output = Output::default();
@@ -1351,6 +1365,12 @@ pub fn canonicalize_expr<'a>(
             use roc_problem::can::RuntimeError::*;
             (RuntimeError(MalformedClosure(region)), Output::default())
         }
+        ast::Expr::MalformedExpr(_) => {
+            use roc_problem::can::RuntimeError::*;
+
+            // The syntax error was already reported when the parser recovered from it.
+            (RuntimeError(MalformedExpr(region)), Output::default())
+        }
         ast::Expr::MalformedIdent(name, bad_ident) => {
             use roc_problem::can::RuntimeError::*;
 
@@ -1699,11 +1719,15 @@ fn canonicalize_when_branch<'a>(
 
     // Now that we've collected all the references for this branch, check to see if
     // any of the new idents it defined were unused. If any were, report it.
+    //
+    // With alternatives like `A x | B x`, each pattern binds the same symbol, but an unused one
+    // should only be reported once (at its first occurrence).
     let mut pattern_bound_symbols_body_needs = VecSet::default();
+    let mut reported_unused = VecSet::default();
     for (symbol, region) in BindingsFromPattern::new_many(patterns.iter().map(|pat| &pat.pattern)) {
         if output.references.has_value_lookup(symbol) {
             pattern_bound_symbols_body_needs.insert(symbol);
-        } else {
+        } else if !reported_unused.insert(symbol) {
             env.problem(Problem::UnusedBranchDef(symbol, region));
         }
     }
@@ -2362,7 +2386,9 @@ fn flatten_str_literal<'a>(
     use ast::StrLiteral::*;
 
     match literal {
-        PlainLine(str_slice) => (Expr::Str((*str_slice).into()), Output::default()),
+        PlainLine(str_slice) | Raw(str_slice) => {
+            (Expr::Str((*str_slice).into()), Output::default())
+        }
         Line(segments) => flatten_str_lines(env, var_store, scope, &[segments]),
         Block(lines) => flatten_str_lines(env, var_store, scope, lines),
     }
@@ -3069,6 +3095,134 @@ pub(crate) fn get_lookup_symbols(expr: &Expr) -> Vec<ExpectLookup> {
     lookups
 }
 
+/// When an `expect` compares two values with `==`, we bind both sides to new symbols
+///
+/// ```ignore
+/// expect List.sum xs == total
+/// ```
+///
+/// becomes
+///
+/// ```ignore
+/// 1 = List.sum xs
+/// 2 = total
+///
+/// expect 1 == 2
+/// ```
+///
+/// so that when the expectation fails, both values are captured and the report can show how they
+/// differ. The report recognizes them by their generated names (see [Scope::gen_unique_symbol]).
+///
+/// Returns the defs that the `expect` needs to be wrapped in, and the new condition.
+fn capture_compared_values(
+    scope: &mut Scope,
+    var_store: &mut VarStore,
+    loc_condition: Loc<Expr>,
+) -> (Vec<Def>, Loc<Expr>) {
+    use roc_module::called_via::BinOp;
+
+    let region = loc_condition.region;
+
+    match loc_condition.value {
+        Expr::Call(function, args, CalledVia::BinOp(BinOp::Equals)) if args.len() == 2 => {
+            let mut defs = Vec::with_capacity(args.len());
+            let mut new_args = Vec::with_capacity(args.len());
+
+            for (arg_var, loc_arg) in args {
+                let symbol = scope.gen_unique_symbol();
+                let expr_var = var_store.fresh();
+
+                let mut pattern_vars = SendMap::default();
+                pattern_vars.insert(symbol, expr_var);
+
+                let arg_region = loc_arg.region;
+
+                defs.push(Def {
+                    loc_pattern: Loc::at(arg_region, Pattern::Identifier(symbol)),
+                    loc_expr: loc_arg,
+                    expr_var,
+                    pattern_vars,
+                    annotation: None,
+                });
+
+                let lookup = Expr::Var(symbol, var_store.fresh());
+                new_args.push((arg_var, Loc::at(arg_region, lookup)));
+            }
+
+            let condition = Expr::Call(function, new_args, CalledVia::BinOp(BinOp::Equals));
+
+            (defs, Loc::at(region, condition))
+        }
+        condition => (Vec::new(), Loc::at(region, condition)),
+    }
+}
+
+/// An inline `expect`, with the values it compares captured (see [capture_compared_values])
+pub(crate) fn expect_capturing_compared_values(
+    scope: &mut Scope,
+    var_store: &mut VarStore,
+    region: Region,
+    loc_condition: Loc<Expr>,
+    loc_continuation: Loc<Expr>,
+) -> Expr {
+    let lookups_in_original_cond = get_lookup_symbols(&loc_condition.value);
+
+    let (defs, loc_condition) = capture_compared_values(scope, var_store, loc_condition);
+
+    let mut lookups_in_cond = Vec::with_capacity(defs.len() + lookups_in_original_cond.len());
+
+    for def in defs.iter() {
+        lookups_in_cond.extend(def.pattern_vars.iter().map(|(symbol, _)| ExpectLookup {
+            symbol: *symbol,
+            var: var_store.fresh(),
+            ability_info: None,
+        }));
+    }
+
+    lookups_in_cond.extend(lookups_in_original_cond);
+
+    let expect = Expr::Expect {
+        loc_condition: Box::new(loc_condition),
+        loc_continuation: Box::new(loc_continuation),
+        lookups_in_cond,
+    };
+
+    defs.into_iter().rev().fold(expect, |expr, def| {
+        Expr::LetNonRec(Box::new(def), Box::new(Loc::at(region, expr)))
+    })
+}
+
+/// Like [capture_compared_values], for the body of a toplevel `expect`. Its defs are moved out
+/// of the condition later, by [toplevel_expect_to_inline_expect_pure].
+pub(crate) fn toplevel_expect_capturing_compared_values(
+    scope: &mut Scope,
+    var_store: &mut VarStore,
+    loc_expr: Loc<Expr>,
+) -> Loc<Expr> {
+    let region = loc_expr.region;
+
+    match loc_expr.value {
+        Expr::LetNonRec(def, remainder) => {
+            let remainder = toplevel_expect_capturing_compared_values(scope, var_store, *remainder);
+
+            Loc::at(region, Expr::LetNonRec(def, Box::new(remainder)))
+        }
+        Expr::LetRec(defs, remainder, mark) => {
+            let remainder = toplevel_expect_capturing_compared_values(scope, var_store, *remainder);
+
+            Loc::at(region, Expr::LetRec(defs, Box::new(remainder), mark))
+        }
+        condition => {
+            let (defs, loc_condition) =
+                capture_compared_values(scope, var_store, Loc::at(region, condition));
+
+            defs.into_iter().rev().fold(loc_condition, |loc_expr, def| {
+                Loc::at(region, Expr::LetNonRec(Box::new(def), Box::new(loc_expr)))
+            })
+        }
+    }
+}
+
 /// Here we transform
 ///
 /// ```ignore
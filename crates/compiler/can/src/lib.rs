@@ -9,8 +9,11 @@
 pub mod abilities;
 pub mod annotation;
 pub mod builtins;
+pub mod constant;
 pub mod constraint;
 pub mod copy;
+pub mod crash_reachability;
+pub mod dead_code;
 pub mod def;
 mod derive;
 pub mod effect_module;
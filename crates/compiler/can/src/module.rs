@@ -1,7 +1,7 @@
 use crate::abilities::{AbilitiesStore, ImplKey, PendingAbilitiesStore, ResolvedImpl};
 use crate::annotation::{canonicalize_annotation, AnnotationFor};
 use crate::def::{canonicalize_defs, Def};
-use crate::effect_module::HostedGeneratedFunctions;
+use crate::effect_module::{HostAliases, HostedGeneratedFunctions};
 use crate::env::Env;
 use crate::expr::{
     ClosureData, DbgLookup, Declarations, ExpectLookup, Expr, Output, PendingDerives,
@@ -19,8 +19,9 @@ use roc_parse::header::HeaderType;
 use roc_parse::pattern::PatternType;
 use roc_problem::can::{Problem, RuntimeError};
 use roc_region::all::{Loc, Region};
+use roc_types::num::NumLiteralDefaults;
 use roc_types::subs::{ExposedTypesStorageSubs, Subs, VarStore, Variable};
-use roc_types::types::{AbilitySet, Alias, AliasKind, AliasVar, Type};
+use roc_types::types::{AbilitySet, Alias, AliasCommon, AliasKind, AliasVar, Type};
 
 /// The types of all exposed values/functions of a collection of modules
 #[derive(Clone, Debug, Default)]
@@ -134,6 +135,7 @@ pub struct Module {
     pub abilities_store: PendingAbilitiesStore,
     pub loc_expects: VecMap<Region, Vec<ExpectLookup>>,
     pub loc_dbgs: VecMap<Symbol, DbgLookup>,
+    pub num_literal_defaults: NumLiteralDefaults,
 }
 
 #[derive(Debug, Default)]
@@ -158,6 +160,8 @@ pub struct ModuleOutput {
     pub scope: Scope,
     pub loc_expects: VecMap<Region, Vec<ExpectLookup>>,
     pub loc_dbgs: VecMap<Symbol, DbgLookup>,
+    /// The types unresolved number literals in this module are compiled as
+    pub num_literal_defaults: NumLiteralDefaults,
 }
 
 fn validate_generate_with<'a>(
@@ -173,6 +177,7 @@ fn validate_generate_with<'a>(
             "always" => functions.always = true,
             "loop" => functions.loop_ = true,
             "forever" => functions.forever = true,
+            "run" => functions.run = true,
             other => {
                 // we don't know how to generate this function
                 let ident = Ident::from(other);
@@ -188,6 +193,7 @@ fn validate_generate_with<'a>(
 enum GeneratedInfo {
     Hosted {
         effect_symbol: Symbol,
+        host_aliases: HostAliases,
         generated_functions: HostedGeneratedFunctions,
     },
     Builtin,
@@ -234,8 +240,11 @@ impl GeneratedInfo {
                     );
                 }
 
+                let host_aliases = HostAliases::introduce(scope, var_store);
+
                 GeneratedInfo::Hosted {
                     effect_symbol,
+                    host_aliases,
                     generated_functions,
                 }
             }
@@ -484,6 +493,7 @@ pub fn canonicalize_module_defs<'a>(
     if let GeneratedInfo::Hosted {
         effect_symbol,
         generated_functions,
+        ..
     } = generated_info
     {
         let mut exposed_symbols = VecSet::default();
@@ -530,7 +540,11 @@ pub fn canonicalize_module_defs<'a>(
                                 }
                             }
                         }
-                        GeneratedInfo::Hosted { effect_symbol, .. } => {
+                        GeneratedInfo::Hosted {
+                            effect_symbol,
+                            host_aliases,
+                            ..
+                        } => {
                             let ident_id = symbol.ident_id();
                             let ident = scope
                                 .locals
@@ -541,6 +555,12 @@ pub fn canonicalize_module_defs<'a>(
 
                             let def_annotation = declarations.annotations[index].clone().unwrap();
 
+                            if host_aliases
+                                .has_misplaced_pure(&def_annotation.signature, effect_symbol)
+                            {
+                                env.problem(Problem::MisplacedPure(*symbol, def_annotation.region));
+                            }
+
                             let annotation = crate::annotation::Annotation {
                                 typ: def_annotation.signature,
                                 introduced_variables: def_annotation.introduced_variables,
@@ -553,6 +573,7 @@ pub fn canonicalize_module_defs<'a>(
                                 *symbol,
                                 &ident,
                                 effect_symbol,
+                                host_aliases,
                                 var_store,
                                 annotation,
                             );
@@ -588,7 +609,11 @@ pub fn canonicalize_module_defs<'a>(
                                 }
                             }
                         }
-                        GeneratedInfo::Hosted { effect_symbol, .. } => {
+                        GeneratedInfo::Hosted {
+                            effect_symbol,
+                            host_aliases,
+                            ..
+                        } => {
                             let ident_id = symbol.ident_id();
                             let ident = scope
                                 .locals
@@ -599,6 +624,12 @@ pub fn canonicalize_module_defs<'a>(
 
                             let def_annotation = declarations.annotations[index].clone().unwrap();
 
+                            if host_aliases
+                                .has_misplaced_pure(&def_annotation.signature, effect_symbol)
+                            {
+                                env.problem(Problem::MisplacedPure(*symbol, def_annotation.region));
+                            }
+
                             let annotation = crate::annotation::Annotation {
                                 typ: def_annotation.signature,
                                 introduced_variables: def_annotation.introduced_variables,
@@ -611,6 +642,7 @@ pub fn canonicalize_module_defs<'a>(
                                 *symbol,
                                 &ident,
                                 effect_symbol,
+                                host_aliases,
                                 var_store,
                                 annotation,
                             );
@@ -638,7 +670,12 @@ pub fn canonicalize_module_defs<'a>(
 
     let mut aliases = MutMap::default();
 
-    if let GeneratedInfo::Hosted { effect_symbol, .. } = generated_info {
+    if let GeneratedInfo::Hosted {
+        effect_symbol,
+        host_aliases,
+        ..
+    } = generated_info
+    {
         // Remove this from exposed_symbols,
         // so that at the end of the process,
         // we can see if there were any
@@ -646,10 +683,19 @@ pub fn canonicalize_module_defs<'a>(
         // corresponding defs.
         exposed_but_not_defined.remove(&effect_symbol);
 
-        let hosted_alias = scope.lookup_alias(effect_symbol).unwrap().clone();
-        aliases.insert(effect_symbol, hosted_alias);
+        for symbol in [
+            effect_symbol,
+            host_aliases.owned,
+            host_aliases.borrowed,
+            host_aliases.pure,
+        ] {
+            let hosted_alias = scope.lookup_alias(symbol).unwrap().clone();
+            aliases.insert(symbol, hosted_alias);
+        }
     }
 
+    let mut num_literal_defaults = NumLiteralDefaults::default();
+
     for (symbol, alias) in output.aliases {
         // Remove this from exposed_symbols,
         // so that at the end of the process,
@@ -658,6 +704,8 @@ pub fn canonicalize_module_defs<'a>(
         // corresponding defs.
         exposed_but_not_defined.remove(&symbol);
 
+        record_num_literal_default(&mut env, &scope, &mut num_literal_defaults, symbol, &alias);
+
         aliases.insert(symbol, alias);
     }
 
@@ -810,7 +858,61 @@ pub fn canonicalize_module_defs<'a>(
         loc_expects: collected.expects,
         loc_dbgs: collected.dbgs,
         exposed_symbols,
+        num_literal_defaults,
+    }
+}
+
+/// If `symbol` is the module's `DefaultInt` or `DefaultFrac` alias, records the number type it
+/// names as the default for the respective kind of number literal.
+fn record_num_literal_default(
+    env: &mut Env,
+    scope: &Scope,
+    defaults: &mut NumLiteralDefaults,
+    symbol: Symbol,
+    alias: &Alias,
+) {
+    let expects_int = match scope.locals.ident_ids.get_name(symbol.ident_id()) {
+        Some(NumLiteralDefaults::INT_ALIAS_NAME) => true,
+        Some(NumLiteralDefaults::FRAC_ALIAS_NAME) => false,
+        _ => return,
+    };
+
+    let named = match &alias.typ {
+        Type::DelayedAlias(AliasCommon {
+            symbol,
+            type_arguments,
+            ..
+        }) if type_arguments.is_empty() => Some(*symbol),
+        Type::Alias {
+            symbol,
+            type_arguments,
+            ..
+        } if type_arguments.is_empty() => Some(*symbol),
+        Type::Apply(symbol, arguments, _) if arguments.is_empty() => Some(*symbol),
+        _ => None,
+    };
+
+    match named {
+        Some(named) if expects_int => {
+            if let Some(width) = NumLiteralDefaults::int_from_symbol(named) {
+                defaults.int = width;
+                return;
+            }
+        }
+        Some(named) => {
+            if let Some(width) = NumLiteralDefaults::frac_from_symbol(named) {
+                defaults.frac = width;
+                return;
+            }
+        }
+        None => {}
     }
+
+    env.problem(Problem::InvalidNumLiteralDefault {
+        alias: symbol,
+        region: alias.region,
+        expects_int,
+    });
 }
 
 fn fix_values_captured_in_closure_def(
@@ -1293,4 +1395,33 @@ impl TypeState {
             total_offset,
         )
     }
+
+    /// Rewrite every symbol in this type state with `f`. Symbols contain the id of their module,
+    /// and module ids differ between compilations, so this is how a type state solved in one
+    /// compilation gets used in another. Fails if `f` fails for any symbol.
+    pub fn try_map_symbols(self, f: &impl Fn(Symbol) -> Option<Symbol>) -> Option<Self> {
+        let Self {
+            mut subs,
+            exposed_vars_by_symbol,
+            abilities,
+            solved_implementations,
+        } = self;
+
+        subs.try_map_symbols(f)?;
+
+        let exposed_vars_by_symbol = exposed_vars_by_symbol
+            .into_iter()
+            .map(|(symbol, var)| Some((f(symbol)?, var)))
+            .collect::<Option<_>>()?;
+
+        Some(Self {
+            subs,
+            exposed_vars_by_symbol,
+            abilities: abilities.try_map_symbols(f)?,
+            solved_implementations: crate::abilities::try_map_resolved_implementations(
+                solved_implementations,
+                f,
+            )?,
+        })
+    }
 }
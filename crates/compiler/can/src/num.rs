@@ -159,19 +159,53 @@ pub fn finish_parsing_float(raw: &str) -> Result<(&str, f64, FloatBound), (&str,
     };
 
     // Ignore underscores.
-    match raw_without_suffix.replace('_', "").parse::<f64>() {
-        Ok(float) if float.is_finite() => Ok((raw_without_suffix, float, bound)),
-        Ok(float) => {
+    let without_underscores = raw_without_suffix.replace('_', "");
+    let opt_float = match without_underscores.strip_prefix('-') {
+        Some(hex) if hex.starts_with("0x") => parse_hex_float(&hex[2..]).map(|float| -float),
+        None if without_underscores.starts_with("0x") => parse_hex_float(&without_underscores[2..]),
+        _ => without_underscores.parse::<f64>().ok(),
+    };
+
+    match opt_float {
+        Some(float) if float.is_finite() => Ok((raw_without_suffix, float, bound)),
+        Some(float) => {
             if float.is_sign_positive() {
                 Err((raw, FloatErrorKind::PositiveInfinity))
             } else {
                 Err((raw, FloatErrorKind::NegativeInfinity))
             }
         }
-        Err(_) => Err((raw, FloatErrorKind::Error)),
+        None => Err((raw, FloatErrorKind::Error)),
     }
 }
 
+/// Parse the part of a hex float like `0x1.8p3` after the `0x`. The binary exponent is required,
+/// so that e.g. `0x1.f32` isn't mistaken for a float with a suffix.
+fn parse_hex_float(digits: &str) -> Option<f64> {
+    let (mantissa, exponent) = digits.split_once(|c| c == 'p' || c == 'P')?;
+    let exponent = exponent.parse::<i32>().ok()?;
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+
+    let mut float = 0.0f64;
+
+    for c in whole.chars() {
+        float = float * 16.0 + c.to_digit(16)? as f64;
+    }
+
+    let mut place = 1.0f64;
+
+    for c in fraction.chars() {
+        place /= 16.0;
+        float += c.to_digit(16)? as f64 * place;
+    }
+
+    Some(float * 2f64.powi(exponent))
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum ParsedWidth {
     Int(IntLitWidth),
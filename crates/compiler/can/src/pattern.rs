@@ -1047,7 +1047,7 @@ fn flatten_str_literal(literal: &StrLiteral<'_>) -> Pattern {
     use ast::StrLiteral::*;
 
     match literal {
-        PlainLine(str_slice) => Pattern::StrLiteral((*str_slice).into()),
+        PlainLine(str_slice) | Raw(str_slice) => Pattern::StrLiteral((*str_slice).into()),
         Line(segments) => flatten_str_lines(&[segments]),
         Block(lines) => flatten_str_lines(lines),
     }
@@ -300,6 +300,9 @@ pub fn walk_expr<V: Visitor>(visitor: &mut V, expr: &Expr, var: Variable) {
             foreign_symbol: _,
             args,
             ret_var: _,
+            arg_ownership: _,
+            ret_ownership: _,
+            pure: _,
         } => {
             args.iter()
                 .for_each(|(v, e)| visitor.visit_expr(e, Region::zero(), *v));
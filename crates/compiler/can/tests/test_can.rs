@@ -276,6 +276,62 @@ mod test_can {
         assert_can_int("-0b11", -0b11);
     }
 
+    #[test]
+    fn underscores() {
+        assert_can_num("1_000_000", 1_000_000);
+        assert_can_int("0b1010_1100", 0b1010_1100);
+        assert_can_int("0xFF_FF", 0xFF_FF);
+        assert_can_float("1_000.000_5", 1_000.000_5);
+    }
+
+    #[test]
+    fn hex_float() {
+        assert_can_float("0x1.8p3", 12.0);
+        assert_can_float("0x1p-2", 0.25);
+        assert_can_float("-0xA.8p0", -10.5);
+        assert_can_float("0x1_0.0p+1f64", 32.0);
+    }
+
+    #[test]
+    fn hex_float_without_exponent() {
+        let string = "0x1.8";
+
+        assert_can_runtime_error(
+            string,
+            RuntimeError::InvalidFloat(FloatErrorKind::Error, Region::zero(), string.into()),
+        );
+    }
+
+    // CONSTANTS
+
+    fn fold(input: &str) -> Option<roc_can::constant::Constant> {
+        let arena = Bump::new();
+        let actual_out = can_expr_with(&arena, test_home(), input);
+
+        roc_can::constant::fold_constant(&actual_out.loc_expr.value, &Default::default())
+    }
+
+    #[test]
+    fn fold_int_arithmetic() {
+        use roc_can::constant::Constant;
+
+        assert_eq!(fold("60 * 1000 + 1"), Some(Constant::Int(60_001)));
+        assert_eq!(fold("-(7 // 2)"), Some(Constant::Int(-3)));
+        assert_eq!(fold("1 // 0"), None);
+    }
+
+    #[test]
+    fn fold_strings_and_floats() {
+        use roc_can::constant::Constant;
+
+        assert_eq!(
+            fold(r#"Str.concat "a" "b""#).map(|c| c.to_string()),
+            Some(r#""ab""#.to_string())
+        );
+        assert_eq!(fold("2.0").map(|c| c.to_string()), Some("2.0".to_string()));
+        assert_eq!(fold("1.5 + 1.5"), None);
+    }
+
     // ANNOTATIONS
     #[test]
     fn correct_annotated_body() {
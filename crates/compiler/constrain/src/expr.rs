@@ -1683,6 +1683,9 @@ pub fn constrain_expr(
             args,
             ret_var,
             foreign_symbol,
+            arg_ownership: _,
+            ret_ownership: _,
+            pure: _,
         } => {
             // This is a modified version of what we do for function calls.
 
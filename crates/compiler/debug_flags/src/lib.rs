@@ -124,6 +124,33 @@ flags! {
     /// Writes a pretty-printed mono IR to stderr after function specialization.
     ROC_PRINT_IR_AFTER_SPECIALIZATION
 
+    /// Writes a pretty-printed mono IR to stderr after the effects of a test build have been
+    /// replaced with calls to the effect interpreters of the tested module.
+    ROC_PRINT_IR_AFTER_EFFECT_INTERPRETERS
+
+    /// Writes a pretty-printed mono IR to stderr after constant folding.
+    ROC_PRINT_IR_AFTER_CONSTANT_FOLDING
+
+    /// Writes a pretty-printed mono IR to stderr after functions which call each other in tail
+    /// position have been merged into a single loop.
+    ROC_PRINT_IR_AFTER_MUTUAL_TAIL_CALLS
+
+    /// Writes a pretty-printed mono IR to stderr after repeated calls of `Pure` host functions
+    /// have been replaced by the result of the first call.
+    ROC_PRINT_IR_AFTER_PURE_CALLS
+
+    /// Writes a pretty-printed mono IR to stderr after closures which capture a single small
+    /// value have been changed to be called with that value instead of their lambda set.
+    ROC_PRINT_IR_AFTER_FLATTEN_CLOSURES
+
+    /// Writes a pretty-printed mono IR to stderr after `List.get` calls with indices that are
+    /// known to be in bounds have been replaced.
+    ROC_PRINT_IR_AFTER_BOUNDS_CHECKS
+
+    /// Writes a pretty-printed mono IR to stderr after boxes and lists which don't escape
+    /// have been moved to the stack.
+    ROC_PRINT_IR_AFTER_ESCAPE_ANALYSIS
+
     /// Writes a pretty-printed mono IR to stderr after insertion of reset/reuse
     /// instructions.
     ROC_PRINT_IR_AFTER_RESET_REUSE
@@ -0,0 +1,358 @@
+//! Derivers for the `Enum` ability.
+
+use std::iter::once;
+
+use roc_can::{
+    expr::{AnnotatedMark, ClosureData, Expr, IntValue, Recursive, WhenBranch, WhenBranchPattern},
+    num::{IntBound, IntLitWidth},
+    pattern::Pattern,
+};
+use roc_derive_key::enumeration::FlatEnumKey;
+use roc_module::{ident::TagName, symbol::Symbol};
+use roc_region::all::{Loc, Region};
+use roc_types::subs::{
+    Content, ExhaustiveMark, FlatType, LambdaSet, OptVariable, RedundantMark, SubsSlice, TagExt,
+    UnionLambdas, UnionTags, Variable, VariableSubsSlice,
+};
+
+use crate::{
+    synth_var,
+    util::{Env, ExtensionKind},
+    DerivedBody,
+};
+
+pub(crate) fn derive_to_int(
+    env: &mut Env<'_>,
+    key: FlatEnumKey,
+    def_symbol: Symbol,
+) -> DerivedBody {
+    let (body_type, body) = to_int(env, def_symbol, key.0);
+
+    derived_body(env, Symbol::ENUM_TO_INT, body_type, body)
+}
+
+pub(crate) fn derive_from_int(
+    env: &mut Env<'_>,
+    key: FlatEnumKey,
+    def_symbol: Symbol,
+) -> DerivedBody {
+    let (body_type, body) = from_int(env, def_symbol, key.0);
+
+    derived_body(env, Symbol::ENUM_FROM_INT, body_type, body)
+}
+
+pub(crate) fn derive_all(env: &mut Env<'_>, key: FlatEnumKey, def_symbol: Symbol) -> DerivedBody {
+    let (body_type, body) = all(env, def_symbol, key.0);
+
+    derived_body(env, Symbol::ENUM_ALL, body_type, body)
+}
+
+fn derived_body(env: &mut Env<'_>, member: Symbol, body_type: Variable, body: Expr) -> DerivedBody {
+    let specialization_lambda_sets = env.get_specialization_lambda_sets(body_type, member);
+
+    DerivedBody {
+        body,
+        body_type,
+        specialization_lambda_sets,
+    }
+}
+
+fn to_int(env: &mut Env<'_>, fn_name: Symbol, tags: Vec<TagName>) -> (Variable, Expr) {
+    let union_var = union_var(env, &tags);
+
+    // The tags are sorted by name, which is how the layout numbers them, so
+    //
+    // toInt_[A,B] : [A, B] -> U64
+    // toInt_[A,B] = \tag ->
+    //   when tag is
+    //     A -> 0
+    //     B -> 1
+    let tag_sym = env.new_symbol("tag");
+
+    let body = if tags.is_empty() {
+        // There are no values of `[]` to number.
+        Expr::Crash {
+            msg: Box::new(Loc::at_zero(Expr::Str(
+                "Enum.toInt called on a value of an empty tag union".into(),
+            ))),
+            ret_var: Variable::U64,
+        }
+    } else {
+        let branches = tags
+            .into_iter()
+            .enumerate()
+            .map(|(n, tag_name)| WhenBranch {
+                patterns: vec![WhenBranchPattern {
+                    pattern: Loc::at_zero(Pattern::AppliedTag {
+                        whole_var: union_var,
+                        ext_var: Variable::EMPTY_TAG_UNION,
+                        tag_name,
+                        arguments: vec![],
+                    }),
+                    degenerate: false,
+                }],
+                value: Loc::at_zero(u64_literal(n)),
+                guard: None,
+                redundant: RedundantMark::known_non_redundant(),
+            })
+            .collect();
+
+        Expr::When {
+            loc_cond: Box::new(Loc::at_zero(Expr::Var(tag_sym, union_var))),
+            cond_var: union_var,
+            expr_var: Variable::U64,
+            region: Region::zero(),
+            branches,
+            branches_cond_var: union_var,
+            exhaustive: ExhaustiveMark::known_exhaustive(),
+        }
+    };
+
+    build_derived_closure(
+        env,
+        fn_name,
+        (union_var, Pattern::Identifier(tag_sym)),
+        (Variable::U64, body),
+    )
+}
+
+fn from_int(env: &mut Env<'_>, fn_name: Symbol, tags: Vec<TagName>) -> (Variable, Expr) {
+    let union_var = union_var(env, &tags);
+
+    // [OutOfRange]
+    let out_of_range_var = {
+        let union_tags = UnionTags::insert_into_subs(env.subs, [("OutOfRange".into(), [])]);
+        synth_var(
+            env.subs,
+            Content::Structure(FlatType::TagUnion(
+                union_tags,
+                TagExt::Any(Variable::EMPTY_TAG_UNION),
+            )),
+        )
+    };
+
+    // [Err [OutOfRange], Ok [A, B]], which is what `Result [A, B] [OutOfRange]` stands for
+    let result_var = {
+        let union_tags = UnionTags::insert_into_subs(
+            env.subs,
+            [
+                ("Err".into(), [out_of_range_var]),
+                ("Ok".into(), [union_var]),
+            ],
+        );
+        synth_var(
+            env.subs,
+            Content::Structure(FlatType::TagUnion(
+                union_tags,
+                TagExt::Any(Variable::EMPTY_TAG_UNION),
+            )),
+        )
+    };
+
+    // fromInt_[A,B] : U64 -> Result [A, B] [OutOfRange]
+    // fromInt_[A,B] = \n ->
+    //   when n is
+    //     0 -> Ok A
+    //     1 -> Ok B
+    //     _ -> Err OutOfRange
+    let n_sym = env.new_symbol("n");
+
+    let mut branches: Vec<_> = tags
+        .into_iter()
+        .enumerate()
+        .map(|(n, tag_name)| {
+            let ok_tag = Expr::Tag {
+                tag_union_var: result_var,
+                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                name: "Ok".into(),
+                arguments: vec![(
+                    union_var,
+                    Loc::at_zero(Expr::Tag {
+                        tag_union_var: union_var,
+                        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                        name: tag_name,
+                        arguments: vec![],
+                    }),
+                )],
+            };
+
+            WhenBranch {
+                patterns: vec![WhenBranchPattern {
+                    pattern: Loc::at_zero(u64_pattern(n)),
+                    degenerate: false,
+                }],
+                value: Loc::at_zero(ok_tag),
+                guard: None,
+                redundant: RedundantMark::known_non_redundant(),
+            }
+        })
+        .collect();
+
+    let err_tag = Expr::Tag {
+        tag_union_var: result_var,
+        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+        name: "Err".into(),
+        arguments: vec![(
+            out_of_range_var,
+            Loc::at_zero(Expr::Tag {
+                tag_union_var: out_of_range_var,
+                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                name: "OutOfRange".into(),
+                arguments: vec![],
+            }),
+        )],
+    };
+
+    branches.push(WhenBranch {
+        patterns: vec![WhenBranchPattern {
+            pattern: Loc::at_zero(Pattern::Underscore),
+            degenerate: false,
+        }],
+        value: Loc::at_zero(err_tag),
+        guard: None,
+        redundant: RedundantMark::known_non_redundant(),
+    });
+
+    let body = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(n_sym, Variable::U64))),
+        cond_var: Variable::U64,
+        expr_var: result_var,
+        region: Region::zero(),
+        branches,
+        branches_cond_var: Variable::U64,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    build_derived_closure(
+        env,
+        fn_name,
+        (Variable::U64, Pattern::Identifier(n_sym)),
+        (result_var, body),
+    )
+}
+
+fn all(env: &mut Env<'_>, fn_name: Symbol, tags: Vec<TagName>) -> (Variable, Expr) {
+    let union_var = union_var(env, &tags);
+
+    let list_var = {
+        let elem_var_slice = VariableSubsSlice::insert_into_subs(env.subs, [union_var]);
+        synth_var(
+            env.subs,
+            Content::Structure(FlatType::Apply(Symbol::LIST_LIST, elem_var_slice)),
+        )
+    };
+
+    // all_[A,B] : {} -> List [A, B]
+    // all_[A,B] = \{} -> [A, B]
+    let loc_elems = tags
+        .into_iter()
+        .map(|tag_name| {
+            Loc::at_zero(Expr::Tag {
+                tag_union_var: union_var,
+                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                name: tag_name,
+                arguments: vec![],
+            })
+        })
+        .collect();
+
+    let body = Expr::List {
+        elem_var: union_var,
+        loc_elems,
+    };
+
+    build_derived_closure(
+        env,
+        fn_name,
+        (Variable::EMPTY_RECORD, Pattern::Underscore),
+        (list_var, body),
+    )
+}
+
+/// Build the type `[A, B, ..]` of a union without payloads.
+fn union_var(env: &mut Env<'_>, tags: &[TagName]) -> Variable {
+    let union_tags =
+        UnionTags::insert_into_subs(env.subs, tags.iter().map(|tag| (tag.clone(), [])));
+
+    synth_var(
+        env.subs,
+        Content::Structure(FlatType::TagUnion(
+            union_tags,
+            TagExt::Any(Variable::EMPTY_TAG_UNION),
+        )),
+    )
+}
+
+fn u64_literal(n: usize) -> Expr {
+    Expr::Int(
+        Variable::U64,
+        Variable::UNSIGNED64,
+        n.to_string().into_boxed_str(),
+        IntValue::I128((n as i128).to_ne_bytes()),
+        IntBound::Exact(IntLitWidth::U64),
+    )
+}
+
+fn u64_pattern(n: usize) -> Pattern {
+    Pattern::IntLiteral(
+        Variable::U64,
+        Variable::UNSIGNED64,
+        n.to_string().into_boxed_str(),
+        IntValue::I128((n as i128).to_ne_bytes()),
+        IntBound::Exact(IntLitWidth::U64),
+    )
+}
+
+fn build_derived_closure(
+    env: &mut Env<'_>,
+    fn_name: Symbol,
+    arg: (Variable, Pattern),
+    body: (Variable, Expr),
+) -> (Variable, Expr) {
+    let (arg_var, arg_pattern) = arg;
+    let (body_var, body_expr) = body;
+
+    let (fn_var, fn_clos_var) = {
+        // Create fn_var for ambient capture; we fix it up below.
+        let fn_var = synth_var(env.subs, Content::Error);
+
+        // -[fn_name]->
+        let fn_captures = vec![];
+        let fn_name_labels = UnionLambdas::insert_into_subs(env.subs, once((fn_name, fn_captures)));
+        let fn_clos_var = synth_var(
+            env.subs,
+            Content::LambdaSet(LambdaSet {
+                solved: fn_name_labels,
+                recursion_var: OptVariable::NONE,
+                unspecialized: SubsSlice::default(),
+                ambient_function: fn_var,
+            }),
+        );
+
+        // arg_var -[fn_name]-> body_var
+        let args_slice = SubsSlice::insert_into_subs(env.subs, [arg_var]);
+        env.subs.set_content(
+            fn_var,
+            Content::Structure(FlatType::Func(args_slice, fn_clos_var, body_var)),
+        );
+
+        (fn_var, fn_clos_var)
+    };
+
+    let clos_expr = Expr::Closure(ClosureData {
+        function_type: fn_var,
+        closure_type: fn_clos_var,
+        return_type: body_var,
+        name: fn_name,
+        captured_symbols: vec![],
+        recursive: Recursive::NotRecursive,
+        arguments: vec![(
+            arg_var,
+            AnnotatedMark::known_exhaustive(),
+            Loc::at_zero(arg_pattern),
+        )],
+        loc_body: Box::new(Loc::at_zero(body_expr)),
+    });
+
+    (fn_var, clos_expr)
+}
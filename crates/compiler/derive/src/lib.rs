@@ -18,6 +18,7 @@ use util::Env;
 
 mod decoding;
 mod encoding;
+mod enumeration;
 mod hash;
 
 mod util;
@@ -79,6 +80,13 @@ fn build_derived_body(
             decoding::derive_decoder(&mut env, decoder_key, derived_symbol)
         }
         DeriveKey::Hash(hash_key) => hash::derive_hash(&mut env, hash_key, derived_symbol),
+        DeriveKey::EnumToInt(enum_key) => {
+            enumeration::derive_to_int(&mut env, enum_key, derived_symbol)
+        }
+        DeriveKey::EnumFromInt(enum_key) => {
+            enumeration::derive_from_int(&mut env, enum_key, derived_symbol)
+        }
+        DeriveKey::EnumAll(enum_key) => enumeration::derive_all(&mut env, enum_key, derived_symbol),
     };
 
     let def = Def {
@@ -0,0 +1,73 @@
+use roc_module::ident::TagName;
+use roc_types::subs::{Content, FlatType, GetSubsSlice, Subs, Variable};
+
+use crate::{
+    util::{check_derivable_ext_var, debug_name_tag},
+    DeriveError,
+};
+
+/// The tags of a union without payloads, sorted by name, which is also the order their
+/// discriminants are given in by the layout.
+#[derive(Hash, PartialEq, Eq, Debug, Clone)]
+pub struct FlatEnumKey(pub Vec<TagName>);
+
+impl FlatEnumKey {
+    pub(crate) fn debug_name(&self) -> String {
+        let tags: Vec<_> = self.0.iter().map(|tag| (tag.clone(), 0)).collect();
+        debug_name_tag(&tags)
+    }
+
+    pub(crate) fn from_var(subs: &Subs, var: Variable) -> Result<FlatEnumKey, DeriveError> {
+        use DeriveError::*;
+        match *subs.get_content_without_compacting(var) {
+            Content::Structure(flat_type) => match flat_type {
+                FlatType::TagUnion(tags, ext) => {
+                    let (tags_iter, ext) = tags.unsorted_tags_and_ext(subs, ext);
+
+                    check_derivable_ext_var(subs, ext.var(), |ext| {
+                        matches!(ext, Content::Structure(FlatType::EmptyTagUnion))
+                    })?;
+
+                    let mut tag_names = Vec::with_capacity(tags_iter.tags.len());
+                    for (name, payload_slice) in tags_iter.tags {
+                        if !payload_slice.is_empty() {
+                            return Err(Underivable);
+                        }
+                        tag_names.push(name.clone());
+                    }
+
+                    tag_names.sort();
+
+                    Ok(FlatEnumKey(tag_names))
+                }
+                FlatType::FunctionOrTagUnion(names_index, _, _) => {
+                    let mut tag_names = subs.get_subs_slice(names_index).to_vec();
+                    tag_names.sort();
+
+                    Ok(FlatEnumKey(tag_names))
+                }
+                FlatType::EmptyTagUnion => Ok(FlatEnumKey(vec![])),
+                //
+                FlatType::RecursiveTagUnion(..)
+                | FlatType::Apply(..)
+                | FlatType::Func(..)
+                | FlatType::Record(..)
+                | FlatType::Tuple(..)
+                | FlatType::EmptyRecord
+                | FlatType::EmptyTuple => Err(Underivable),
+            },
+            // NB: like for `Hash`, it's okay to unwrap opaques here, because derivers are only
+            // used by the backend, which treats opaques like structural aliases.
+            Content::Alias(_, _, real_var, _) => Self::from_var(subs, real_var),
+            //
+            Content::Error | Content::RecursionVar { .. } | Content::RangedNumber(_) => {
+                Err(Underivable)
+            }
+            Content::FlexVar(_)
+            | Content::RigidVar(_)
+            | Content::FlexAbleVar(_, _)
+            | Content::RigidAbleVar(_, _) => Err(UnboundVar),
+            Content::LambdaSet(_) => Err(Underivable),
+        }
+    }
+}
@@ -9,17 +9,21 @@
 //!   between e.g. required and optional record fields.
 //! - `Decoding` is like encoding, but has some differences. For one, it *does* need to distinguish
 //!   between required and optional record fields.
+//! - `Enum` only cares about the tag names of a union, since it is only derivable for unions
+//!   without payloads.
 //!
 //! For these reasons the content keying is based on a strategy as well, which are the variants of
 //! [`DeriveKey`].
 
 pub mod decoding;
 pub mod encoding;
+pub mod enumeration;
 pub mod hash;
 mod util;
 
 use decoding::{FlatDecodable, FlatDecodableKey};
 use encoding::{FlatEncodable, FlatEncodableKey};
+use enumeration::FlatEnumKey;
 use hash::{FlatHash, FlatHashKey};
 
 use roc_module::symbol::Symbol;
@@ -40,6 +44,9 @@ pub enum DeriveKey {
     ToEncoder(FlatEncodableKey),
     Decoder(FlatDecodableKey),
     Hash(FlatHashKey),
+    EnumToInt(FlatEnumKey),
+    EnumFromInt(FlatEnumKey),
+    EnumAll(FlatEnumKey),
 }
 
 impl DeriveKey {
@@ -48,6 +55,9 @@ impl DeriveKey {
             DeriveKey::ToEncoder(key) => format!("toEncoder_{}", key.debug_name()),
             DeriveKey::Decoder(key) => format!("decoder_{}", key.debug_name()),
             DeriveKey::Hash(key) => format!("hash_{}", key.debug_name()),
+            DeriveKey::EnumToInt(key) => format!("toInt_{}", key.debug_name()),
+            DeriveKey::EnumFromInt(key) => format!("fromInt_{}", key.debug_name()),
+            DeriveKey::EnumAll(key) => format!("all_{}", key.debug_name()),
         }
     }
 }
@@ -77,6 +87,9 @@ pub enum DeriveBuiltin {
     Decoder,
     Hash,
     IsEq,
+    EnumToInt,
+    EnumFromInt,
+    EnumAll,
 }
 
 impl TryFrom<Symbol> for DeriveBuiltin {
@@ -88,6 +101,9 @@ impl TryFrom<Symbol> for DeriveBuiltin {
             Symbol::DECODE_DECODER => Ok(DeriveBuiltin::Decoder),
             Symbol::HASH_HASH => Ok(DeriveBuiltin::Hash),
             Symbol::BOOL_IS_EQ => Ok(DeriveBuiltin::IsEq),
+            Symbol::ENUM_TO_INT => Ok(DeriveBuiltin::EnumToInt),
+            Symbol::ENUM_FROM_INT => Ok(DeriveBuiltin::EnumFromInt),
+            Symbol::ENUM_ALL => Ok(DeriveBuiltin::EnumAll),
             _ => Err(value),
         }
     }
@@ -121,6 +137,15 @@ impl Derived {
                     Symbol::BOOL_STRUCTURAL_EQ,
                 ))
             }
+            DeriveBuiltin::EnumToInt => Ok(Derived::Key(DeriveKey::EnumToInt(
+                FlatEnumKey::from_var(subs, var)?,
+            ))),
+            DeriveBuiltin::EnumFromInt => Ok(Derived::Key(DeriveKey::EnumFromInt(
+                FlatEnumKey::from_var(subs, var)?,
+            ))),
+            DeriveBuiltin::EnumAll => Ok(Derived::Key(DeriveKey::EnumAll(FlatEnumKey::from_var(
+                subs, var,
+            )?))),
         }
     }
 
@@ -151,6 +176,10 @@ impl Derived {
                     Symbol::BOOL_STRUCTURAL_EQ,
                 ))
             }
+            DeriveBuiltin::EnumToInt | DeriveBuiltin::EnumFromInt | DeriveBuiltin::EnumAll => {
+                // No builtin opaque implements `Enum`.
+                Err(DeriveError::Underivable)
+            }
         }
     }
 }
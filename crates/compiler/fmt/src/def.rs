@@ -411,7 +411,7 @@ pub fn fmt_body<'a>(buf: &mut Buf, pattern: &'a Pattern<'a>, body: &'a Expr<'a>,
                 buf.newline();
                 body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent + INDENT);
             }
-            Expr::When(..) | Expr::Str(StrLiteral::Block(_)) => {
+            Expr::When(..) | Expr::Str(StrLiteral::Block(_) | StrLiteral::Raw(_)) => {
                 buf.ensure_ends_with_newline();
                 body.format_with_options(buf, Parens::NotNeeded, Newlines::Yes, indent + INDENT);
             }
@@ -428,7 +428,7 @@ pub fn fmt_body<'a>(buf: &mut Buf, pattern: &'a Pattern<'a>, body: &'a Expr<'a>,
 
 impl<'a> Formattable for AbilityMember<'a> {
     fn is_multiline(&self) -> bool {
-        self.name.value.is_multiline() || self.typ.is_multiline()
+        self.name.value.is_multiline() || self.typ.is_multiline() || self.default.is_some()
     }
 
     fn format_with_options(
@@ -447,5 +447,11 @@ impl<'a> Formattable for AbilityMember<'a> {
         buf.push(':');
         buf.spaces(1);
         self.typ.value.format(buf, indent + INDENT);
+
+        if let Some(default) = &self.default {
+            fmt_spaces(buf, default.spaces_before.iter(), indent);
+            buf.ensure_ends_with_newline();
+            fmt_body(buf, &Pattern::Identifier(item), &default.body.value, indent);
+        }
     }
 }
@@ -84,6 +84,8 @@ impl<'a> Formattable for Expr<'a> {
 
             ParensAround(subexpr) => subexpr.is_multiline(),
 
+            MalformedExpr(src) => src.contains('\n'),
+
             Closure(loc_patterns, loc_body) => {
                 // check the body first because it's more likely to be multiline
                 loc_body.is_multiline()
@@ -499,6 +501,11 @@ impl<'a> Formattable for Expr<'a> {
                 buf.push_str(str)
             }
             MalformedClosure => {}
+            MalformedExpr(src) => {
+                // Reproduce the source which failed to parse exactly as it was written
+                buf.indent(indent);
+                buf.push_str_allow_spaces(src)
+            }
             PrecedenceConflict { .. } => {}
             MultipleRecordBuilders { .. } => {}
             UnappliedRecordBuilder { .. } => {}
@@ -511,7 +518,7 @@ fn is_str_multiline(literal: &StrLiteral) -> bool {
     use roc_parse::ast::StrLiteral::*;
 
     match literal {
-        PlainLine(string) => {
+        PlainLine(string) | Raw(string) => {
             // When a PlainLine contains '\n' or '"', format as a block string
             string.contains('"') || string.contains('\n')
         }
@@ -694,6 +701,30 @@ pub fn fmt_str_literal(buf: &mut Buf, literal: StrLiteral, indent: u16) {
             buf.indent(indent);
             buf.push_str("\"\"\"");
         }
+        Raw(string) => {
+            // Like a PlainLine, a raw string which contains '\n' or '"' has to be a block string
+            if string.contains('"') || string.contains('\n') {
+                buf.ensure_ends_with_newline();
+                buf.indent(indent);
+                buf.push_str("r\"\"\"");
+                buf.push_newline_literal();
+                for line in string.split('\n') {
+                    // only add indent if the line isn't empty
+                    if !line.is_empty() {
+                        buf.indent(indent);
+                        buf.push_str_allow_spaces(line);
+                    }
+                    buf.push_newline_literal();
+                }
+                buf.indent(indent);
+                buf.push_str("\"\"\"");
+            } else {
+                buf.indent(indent);
+                buf.push_str("r\"");
+                buf.push_str_allow_spaces(string);
+                buf.push('"');
+            }
+        }
     }
 }
 
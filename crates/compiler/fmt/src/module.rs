@@ -5,7 +5,7 @@ use crate::spaces::RemoveSpaces;
 use crate::spaces::{fmt_comments_only, fmt_default_spaces, fmt_spaces, NewlineAt, INDENT};
 use crate::Buf;
 use bumpalo::Bump;
-use roc_parse::ast::{Collection, Header, Module, Spaced, Spaces};
+use roc_parse::ast::{Collection, CommentOrNewline, ExtractSpaces, Header, Module, Spaced, Spaces};
 use roc_parse::header::{
     AppHeader, ExposedName, ExposesKeyword, GeneratesKeyword, HostedHeader, ImportsEntry,
     ImportsKeyword, InterfaceHeader, Keyword, KeywordItem, ModuleName, PackageEntry, PackageHeader,
@@ -334,9 +334,140 @@ fn fmt_imports<'a>(
     loc_entries: Collection<'a, Loc<Spaced<'a, ImportsEntry<'a>>>>,
     indent: u16,
 ) {
+    let arena = Bump::new();
+    let loc_entries = sort_imports(&arena, loc_entries);
+
     fmt_collection(buf, indent, Braces::Square, loc_entries, Newlines::No)
 }
 
+/// Put imports in the order the formatter writes them in: modules of the same package first,
+/// then modules of other packages, each sorted by name, and ingested files last, in the order
+/// they were written. Imports of the same module are merged into one that exposes the names of
+/// both, unless that would lose a comment.
+pub fn sort_imports<'a>(
+    arena: &'a Bump,
+    loc_entries: Collection<'a, Loc<Spaced<'a, ImportsEntry<'a>>>>,
+) -> Collection<'a, Loc<Spaced<'a, ImportsEntry<'a>>>> {
+    let mut sorted = loc_entries.items.to_vec();
+    sorted.sort_by_key(|loc_entry| import_order(&loc_entry.value.extract_spaces().item));
+
+    let mut merged: Vec<Loc<Spaced<'a, ImportsEntry<'a>>>> = Vec::with_capacity(sorted.len());
+
+    for loc_entry in sorted {
+        let previous = merged.last_mut().and_then(|previous| {
+            merge_imports(arena, &previous.value, &loc_entry.value)
+                .map(|merged_entry| (previous, merged_entry))
+        });
+
+        match previous {
+            Some((previous, merged_entry)) => previous.value = merged_entry,
+            None => merged.push(loc_entry),
+        }
+    }
+
+    loc_entries.replace_items(arena.alloc_slice_copy(&merged))
+}
+
+/// Ingested files all compare equal, so sorting keeps them in the order they were written.
+fn import_order<'a>(entry: &ImportsEntry<'a>) -> (u8, &'a str, &'a str) {
+    match entry {
+        ImportsEntry::Module(module_name, _) => (0, "", (*module_name).into()),
+        ImportsEntry::Package(shorthand, module_name, _) => (1, *shorthand, (*module_name).into()),
+        ImportsEntry::IngestedFile(_, _) => (2, "", ""),
+    }
+}
+
+/// The import of both `first` and `second`, if they import the same module and merging them
+/// doesn't drop any comments.
+fn merge_imports<'a>(
+    arena: &'a Bump,
+    first: &Spaced<'a, ImportsEntry<'a>>,
+    second: &Spaced<'a, ImportsEntry<'a>>,
+) -> Option<Spaced<'a, ImportsEntry<'a>>> {
+    let first_entry = first.extract_spaces().item;
+    let second_spaces = second.extract_spaces();
+
+    let is_same_module = matches!(
+        first_entry,
+        ImportsEntry::Module(..) | ImportsEntry::Package(..)
+    ) && import_order(&first_entry) == import_order(&second_spaces.item);
+
+    if !is_same_module || has_comments(second_spaces.before) || has_comments(second_spaces.after) {
+        return None;
+    }
+
+    let merged_entry = match (first_entry, second_spaces.item) {
+        (
+            ImportsEntry::Module(module_name, first_exposes),
+            ImportsEntry::Module(_, second_exposes),
+        ) => ImportsEntry::Module(
+            module_name,
+            merge_exposes(arena, first_exposes, second_exposes)?,
+        ),
+        (
+            ImportsEntry::Package(shorthand, module_name, first_exposes),
+            ImportsEntry::Package(_, _, second_exposes),
+        ) => ImportsEntry::Package(
+            shorthand,
+            module_name,
+            merge_exposes(arena, first_exposes, second_exposes)?,
+        ),
+        _ => return None,
+    };
+
+    Some(replace_spaced_item(arena, first, merged_entry))
+}
+
+fn merge_exposes<'a>(
+    arena: &'a Bump,
+    first: Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>>,
+    second: Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>>,
+) -> Option<Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>>> {
+    if has_comments(second.final_comments()) {
+        return None;
+    }
+
+    let mut exposes = first.items.to_vec();
+
+    for loc_name in second.iter() {
+        let spaces = loc_name.value.extract_spaces();
+        let name: &str = spaces.item.into();
+
+        let is_exposed = exposes.iter().any(|exposed| {
+            let exposed_name: &str = exposed.value.extract_spaces().item.into();
+            exposed_name == name
+        });
+
+        // a name with a comment is kept even if it is exposed already, so the comment isn't lost
+        if !is_exposed || has_comments(spaces.before) || has_comments(spaces.after) {
+            exposes.push(*loc_name);
+        }
+    }
+
+    Some(first.replace_items(arena.alloc_slice_copy(&exposes)))
+}
+
+/// `spaced` with `item` in place of its item, keeping the spaces and comments around it.
+pub fn replace_spaced_item<'a, T: Copy>(
+    arena: &'a Bump,
+    spaced: &Spaced<'a, T>,
+    item: T,
+) -> Spaced<'a, T> {
+    match spaced {
+        Spaced::Item(_) => Spaced::Item(item),
+        Spaced::SpaceBefore(inner, spaces) => {
+            Spaced::SpaceBefore(arena.alloc(replace_spaced_item(arena, inner, item)), spaces)
+        }
+        Spaced::SpaceAfter(inner, spaces) => {
+            Spaced::SpaceAfter(arena.alloc(replace_spaced_item(arena, inner, item)), spaces)
+        }
+    }
+}
+
+fn has_comments(spaces: &[CommentOrNewline]) -> bool {
+    spaces.iter().any(|space| space.is_comment())
+}
+
 fn fmt_provides<'a>(
     buf: &mut Buf,
     loc_exposed_names: Collection<'a, Loc<Spaced<'a, ExposedName<'a>>>>,
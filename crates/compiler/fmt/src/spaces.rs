@@ -3,20 +3,21 @@ use bumpalo::Bump;
 use roc_module::called_via::{BinOp, UnaryOp};
 use roc_parse::{
     ast::{
-        AbilityMember, AssignedField, Collection, CommentOrNewline, Defs, Expr, Has, HasAbilities,
-        HasAbility, HasClause, HasImpls, Header, Module, Pattern, RecordBuilderField, Spaced,
-        Spaces, StrLiteral, StrSegment, Tag, TypeAnnotation, TypeDef, TypeHeader, ValueDef,
-        WhenBranch,
+        AbilityMember, AbilityMemberDefault, AssignedField, Collection, CommentOrNewline, Defs,
+        Expr, Has, HasAbilities, HasAbility, HasClause, HasImpls, Header, Module, Pattern,
+        RecordBuilderField, Spaced, Spaces, StrLiteral, StrSegment, Tag, TypeAnnotation, TypeDef,
+        TypeHeader, ValueDef, WhenBranch,
     },
     header::{
-        AppHeader, ExposedName, HostedHeader, ImportsEntry, InterfaceHeader, KeywordItem,
-        ModuleName, PackageEntry, PackageHeader, PackageName, PlatformHeader, PlatformRequires,
-        ProvidesTo, To, TypedIdent,
+        AppHeader, ExposedName, HostedHeader, ImportsEntry, ImportsKeyword, InterfaceHeader,
+        KeywordItem, ModuleName, PackageEntry, PackageHeader, PackageName, PlatformHeader,
+        PlatformRequires, ProvidesTo, To, TypedIdent,
     },
     ident::{BadIdent, UppercaseIdent},
 };
 use roc_region::all::{Loc, Position, Region};
 
+use crate::module::sort_imports;
 use crate::{Ast, Buf};
 
 /// The number of spaces to indent.
@@ -284,13 +285,16 @@ impl<'a> RemoveSpaces<'a> for Module<'a> {
                 before_name: &[],
                 name: header.name.remove_spaces(arena),
                 exposes: header.exposes.remove_spaces(arena),
-                imports: header.imports.remove_spaces(arena),
+                imports: remove_spaces_from_imports(arena, &header.imports),
             }),
             Header::App(header) => Header::App(AppHeader {
                 before_name: &[],
                 name: header.name.remove_spaces(arena),
                 packages: header.packages.remove_spaces(arena),
-                imports: header.imports.remove_spaces(arena),
+                imports: header
+                    .imports
+                    .as_ref()
+                    .map(|imports| remove_spaces_from_imports(arena, imports)),
                 provides: header.provides.remove_spaces(arena),
             }),
             Header::Package(header) => Header::Package(PackageHeader {
@@ -305,14 +309,14 @@ impl<'a> RemoveSpaces<'a> for Module<'a> {
                 requires: header.requires.remove_spaces(arena),
                 exposes: header.exposes.remove_spaces(arena),
                 packages: header.packages.remove_spaces(arena),
-                imports: header.imports.remove_spaces(arena),
+                imports: remove_spaces_from_imports(arena, &header.imports),
                 provides: header.provides.remove_spaces(arena),
             }),
             Header::Hosted(header) => Header::Hosted(HostedHeader {
                 before_name: &[],
                 name: header.name.remove_spaces(arena),
                 exposes: header.exposes.remove_spaces(arena),
-                imports: header.imports.remove_spaces(arena),
+                imports: remove_spaces_from_imports(arena, &header.imports),
                 generates: header.generates.remove_spaces(arena),
                 generates_with: header.generates_with.remove_spaces(arena),
             }),
@@ -324,6 +328,19 @@ impl<'a> RemoveSpaces<'a> for Module<'a> {
     }
 }
 
+type Imports<'a> =
+    KeywordItem<'a, ImportsKeyword, Collection<'a, Loc<Spaced<'a, ImportsEntry<'a>>>>>;
+
+/// The formatter sorts and merges imports, so they are compared in that order.
+fn remove_spaces_from_imports<'a>(arena: &'a Bump, imports: &Imports<'a>) -> Imports<'a> {
+    let imports = imports.remove_spaces(arena);
+
+    KeywordItem {
+        keyword: imports.keyword,
+        item: sort_imports(arena, imports.item),
+    }
+}
+
 impl<'a> RemoveSpaces<'a> for Region {
     fn remove_spaces(&self, _arena: &'a Bump) -> Self {
         Region::zero()
@@ -580,6 +597,10 @@ impl<'a> RemoveSpaces<'a> for AbilityMember<'a> {
         AbilityMember {
             name: self.name.remove_spaces(arena),
             typ: self.typ.remove_spaces(arena),
+            default: self.default.map(|default| AbilityMemberDefault {
+                spaces_before: &[],
+                body: arena.alloc(default.body.remove_spaces(arena)),
+            }),
         }
     }
 }
@@ -642,6 +663,7 @@ impl<'a> RemoveSpaces<'a> for StrLiteral<'a> {
     fn remove_spaces(&self, arena: &'a Bump) -> Self {
         match *self {
             StrLiteral::PlainLine(t) => StrLiteral::PlainLine(t),
+            StrLiteral::Raw(t) => StrLiteral::Raw(t),
             StrLiteral::Line(t) => StrLiteral::Line(t.remove_spaces(arena)),
             StrLiteral::Block(t) => StrLiteral::Block(t.remove_spaces(arena)),
         }
@@ -746,6 +768,7 @@ impl<'a> RemoveSpaces<'a> for Expr<'a> {
             }
             Expr::MalformedIdent(a, b) => Expr::MalformedIdent(a, remove_spaces_bad_ident(b)),
             Expr::MalformedClosure => Expr::MalformedClosure,
+            Expr::MalformedExpr(a) => Expr::MalformedExpr(a),
             Expr::PrecedenceConflict(a) => Expr::PrecedenceConflict(a),
             Expr::MultipleRecordBuilders(a) => Expr::MultipleRecordBuilders(a),
             Expr::UnappliedRecordBuilder(a) => Expr::UnappliedRecordBuilder(a),
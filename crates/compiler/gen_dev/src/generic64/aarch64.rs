@@ -499,6 +499,25 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         add_reg64_reg64_reg64(buf, dst, src1, src2);
     }
     #[inline(always)]
+    fn add_overflow_reg64_reg64_reg64(
+        buf: &mut Vec<'_, u8>,
+        signed: bool,
+        dst: AArch64GeneralReg,
+        overflow: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
+    ) {
+        adds_reg64_reg64_reg64(buf, dst, src1, src2);
+
+        // unsigned overflow carries out of the top bit
+        let cond = if signed {
+            ConditionCode::VS
+        } else {
+            ConditionCode::CSHS
+        };
+        cset_reg64_cond(buf, overflow, cond);
+    }
+    #[inline(always)]
     fn add_freg32_freg32_freg32(
         buf: &mut Vec<'_, u8>,
         dst: AArch64FloatReg,
@@ -518,18 +537,28 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
 
     #[inline(always)]
-    fn call(_buf: &mut Vec<'_, u8>, _relocs: &mut Vec<'_, Relocation>, _fn_name: String) {
-        todo!("calling functions literal for AArch64");
+    fn call(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, fn_name: String) {
+        bl_imm26(buf, 0);
+        relocs.push(Relocation::LinkedFunction {
+            offset: buf.len() as u64 - 4,
+            name: fn_name,
+        });
     }
 
     #[inline(always)]
     fn function_pointer(
-        _buf: &mut Vec<'_, u8>,
-        _relocs: &mut Vec<'_, Relocation>,
-        _fn_name: String,
-        _dst: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        relocs: &mut Vec<'_, Relocation>,
+        fn_name: String,
+        dst: AArch64GeneralReg,
     ) {
-        todo!("calling functions literal for AArch64");
+        // the address is loaded from the GOT: ADRP finds its page, and LDR the entry in it
+        adrp_reg64_imm21(buf, dst, 0);
+        ldr_reg64_reg64_imm12(buf, dst, dst, 0);
+        relocs.push(Relocation::LinkedFunctionPointer {
+            offset: buf.len() as u64 - 8,
+            name: fn_name,
+        });
     }
 
     #[inline(always)]
@@ -542,6 +571,21 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         mul_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
+    fn imul_overflow_reg64_reg64_reg64(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        overflow: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
+    ) {
+        // The product fits when its high 64 bits are all copies of the sign bit of the low 64 bits.
+        // The high bits are computed first, because dst may be one of the sources.
+        smulh_reg64_reg64_reg64(buf, overflow, src1, src2);
+        mul_reg64_reg64_reg64(buf, dst, src1, src2);
+        cmp_reg64_reg64_asr_imm6(buf, overflow, dst, 63);
+        cset_reg64_cond(buf, overflow, ConditionCode::NE);
+    }
+
     fn umul_reg64_reg64_reg64<'a, ASM, CC>(
         buf: &mut Vec<'a, u8>,
         _storage_manager: &mut StorageManager<'a, '_, AArch64GeneralReg, AArch64FloatReg, ASM, CC>,
@@ -1051,6 +1095,25 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     ) {
         sub_reg64_reg64_reg64(buf, dst, src1, src2);
     }
+    #[inline(always)]
+    fn sub_overflow_reg64_reg64_reg64(
+        buf: &mut Vec<'_, u8>,
+        signed: bool,
+        dst: AArch64GeneralReg,
+        overflow: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
+    ) {
+        subs_reg64_reg64_reg64(buf, dst, src1, src2);
+
+        // the carry flag is set when an unsigned subtraction does *not* borrow
+        let cond = if signed {
+            ConditionCode::VS
+        } else {
+            ConditionCode::CCLO
+        };
+        cset_reg64_cond(buf, overflow, cond);
+    }
 
     #[inline(always)]
     fn eq_reg_reg_reg(
@@ -1127,10 +1190,6 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         fcvt_freg64_freg32(buf, dst, src);
     }
 
-    fn set_if_overflow(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg) {
-        todo!("set if overflow for AArch64");
-    }
-
     #[inline(always)]
     fn ret(buf: &mut Vec<'_, u8>) {
         ret_reg64(buf, AArch64GeneralReg::LR)
@@ -1887,6 +1946,38 @@ impl UnconditionalBranchImmediate {
     }
 }
 
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct PcRelativeAddressing {
+    op: bool, // false=ADR, true=ADRP
+    immlo: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<5>>,
+    immhi: Integer<u32, packed_bits::Bits<19>>,
+    rd: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for PcRelativeAddressing {}
+
+pub struct PcRelativeAddressingParams {
+    op: bool,
+    imm21: u32,
+    rd: AArch64GeneralReg,
+}
+
+impl PcRelativeAddressing {
+    #[inline(always)]
+    fn new(PcRelativeAddressingParams { op, imm21, rd }: PcRelativeAddressingParams) -> Self {
+        debug_assert!(imm21 <= 0b1_1111_1111_1111_1111_1111);
+        Self {
+            op,
+            immlo: ((imm21 & 0b11) as u8).into(),
+            fixed: 0b10000.into(),
+            immhi: (imm21 >> 2).into(),
+            rd: rd.id().into(),
+        }
+    }
+}
+
 // Uses unsigned Offset
 // opc = 0b01 means load
 // opc = 0b00 means store
@@ -2309,6 +2400,43 @@ fn add_reg64_reg64_reg64(
     buf.extend(inst.bytes());
 }
 
+/// `ADDS Xd, Xn, Xm` -> Add Xn and Xm and place the result into Xd. Set condition flags.
+#[inline(always)]
+fn adds_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = ArithmeticShifted::new(ArithmeticShiftedParams {
+        op: false,
+        s: true,
+        shift: ShiftType::LSL,
+        imm6: 0,
+        rm: src2,
+        rn: src1,
+        rd: dst,
+    });
+
+    buf.extend(inst.bytes());
+}
+
+/// `ADRP Xd, imm21` -> Place the address of the 4KB page at PC + imm21 pages into Xd.
+#[inline(always)]
+fn adrp_reg64_imm21(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm21: i32) {
+    debug_assert!((-(1 << 20)..(1 << 20)).contains(&imm21));
+    // Our offset is only 21 bits, so we need to remove the first 11 bits
+    let left_removed = ((imm21 as u32) << 11) >> 11;
+
+    let inst = PcRelativeAddressing::new(PcRelativeAddressingParams {
+        op: true,
+        imm21: left_removed,
+        rd: dst,
+    });
+
+    buf.extend(inst.bytes());
+}
+
 /// `AND Xd, Xn, Xm` -> Bitwise AND Xn and Xm and place the result into Xd.
 #[inline(always)]
 fn and_reg64_reg64_reg64(
@@ -2399,6 +2527,23 @@ fn b_imm26(buf: &mut Vec<'_, u8>, imm26: i32) {
     buf.extend(inst.bytes());
 }
 
+/// `BL imm26` -> Call the function at PC + imm26, with the return address in X30.
+#[inline(always)]
+fn bl_imm26(buf: &mut Vec<'_, u8>, imm26: i32) {
+    // Since instructions are 4 bytes, the branch instructions assume the last 2 bits are 0
+    debug_assert!(imm26 & 0b11 == 0, "branch location must be 4-byte aligned");
+    debug_assert!((-(1 << 27)..(1 << 27)).contains(&imm26));
+    // Our offset is only 26 bits, so we need to remove the first 6 bits
+    let left_removed = (((imm26 >> 2) as u32) << 6) >> 6;
+
+    let inst = UnconditionalBranchImmediate::new(UnconditionalBranchImmediateParams {
+        op: true,
+        imm26: left_removed,
+    });
+
+    buf.extend(inst.bytes());
+}
+
 /// `CMP Xn, imm12` -> Compare Xn and imm12, setting condition flags.
 #[inline(always)]
 fn cmp_reg64_imm12(buf: &mut Vec<'_, u8>, src: AArch64GeneralReg, imm12: u16) {
@@ -2411,6 +2556,28 @@ fn cmp_reg64_reg64(buf: &mut Vec<'_, u8>, src1: AArch64GeneralReg, src2: AArch64
     subs_reg64_reg64_reg64(buf, AArch64GeneralReg::ZRSP, src1, src2);
 }
 
+/// `CMP Xn, Xm, ASR #imm6` -> Compare Xn and Xm shifted right arithmetically by imm6, setting
+/// condition flags.
+#[inline(always)]
+fn cmp_reg64_reg64_asr_imm6(
+    buf: &mut Vec<'_, u8>,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+    imm6: u8,
+) {
+    let inst = ArithmeticShifted::new(ArithmeticShiftedParams {
+        op: true,
+        s: true,
+        shift: ShiftType::ASR,
+        imm6,
+        rm: src2,
+        rn: src1,
+        rd: AArch64GeneralReg::ZRSP,
+    });
+
+    buf.extend(inst.bytes());
+}
+
 /// `CNEG Xd, Xn, cond` -> If cond is true, then Xd = -Xn, else Xd = Xn.
 #[inline(always)]
 fn cneg_reg64_reg64_cond(
@@ -2703,6 +2870,26 @@ fn sub_reg64_reg64_imm12(
     buf.extend(inst.bytes());
 }
 
+/// `SMULH Xd, Xn, Xm` -> Multiply Xn and Xm as signed integers, and place the high 64 bits of the
+/// 128-bit product into Xd.
+#[inline(always)]
+fn smulh_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = DataProcessingThreeSource::new(DataProcessingThreeSourceParams {
+        op31: 0b010,
+        rm: src2,
+        ra: AArch64GeneralReg::ZRSP,
+        rn: src1,
+        rd: dst,
+    });
+
+    buf.extend(inst.bytes());
+}
+
 /// `SUB Xd, Xm, Xn` -> Subtract Xm and Xn and place the result into Xd.
 #[inline(always)]
 fn sub_reg64_reg64_reg64(
@@ -3282,6 +3469,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_adds_reg64_reg64_reg64() {
+        disassembler_test!(
+            adds_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| {
+                if reg1 == AArch64GeneralReg::ZRSP {
+                    // When the first register is ZR, it gets disassembled as cmn,
+                    // which is an alias for adds.
+                    format!(
+                        "cmn {}, {}",
+                        reg2.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
+                } else {
+                    format!(
+                        "adds {}, {}, {}",
+                        reg1.capstone_string(UsesZR),
+                        reg2.capstone_string(UsesZR),
+                        reg3.capstone_string(UsesZR)
+                    )
+                }
+            },
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_adrp_reg64_imm21() {
+        disassembler_test!(
+            adrp_reg64_imm21,
+            |reg1: AArch64GeneralReg, imm| format!(
+                "adrp {}, #0x{:x}",
+                reg1.capstone_string(UsesZR),
+                imm << 12
+            ),
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
     #[test]
     fn test_and_reg64_reg64_reg64() {
         disassembler_test!(
@@ -3333,6 +3562,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bl_imm26() {
+        disassembler_test!(
+            bl_imm26,
+            |imm| format!("bl #0x{:x}", imm as i64),
+            [0x120, -0x120, (1 << 27) - 4, -(1 << 27)]
+        );
+    }
+
     #[test]
     fn test_cmp_reg64_imm12() {
         disassembler_test!(
@@ -3361,6 +3599,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cmp_reg64_reg64_asr_imm6() {
+        disassembler_test!(
+            cmp_reg64_reg64_asr_imm6,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "cmp {}, {}, asr #{}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                imm
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [1, 63]
+        );
+    }
+
     #[test]
     fn test_cneg_reg64_reg64_cond() {
         disassembler_test!(
@@ -3644,6 +3898,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_smulh_reg64_reg64_reg64() {
+        disassembler_test!(
+            smulh_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "smulh {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
     #[test]
     fn test_neg_reg64_reg64() {
         disassembler_test!(
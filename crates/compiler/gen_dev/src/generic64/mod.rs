@@ -143,6 +143,13 @@ pub enum CompareOperation {
     GreaterThanOrEqual,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CheckedOperation {
+    Add,
+    Sub,
+    Mul,
+}
+
 /// Assembler contains calls to the backend assembly generator.
 /// These calls do not necessarily map directly to a single assembly instruction.
 /// They are higher level in cases where an instruction would not be common and shared between multiple architectures.
@@ -178,6 +185,17 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
         src2: GeneralReg,
     );
 
+    /// `dst = src1 + src2`, and `overflow = 1` if that overflowed as a signed (or unsigned) 64-bit
+    /// addition, or 0 if it didn't. The overflow is read from the flags the addition sets.
+    fn add_overflow_reg64_reg64_reg64(
+        buf: &mut Vec<'_, u8>,
+        signed: bool,
+        dst: GeneralReg,
+        overflow: GeneralReg,
+        src1: GeneralReg,
+        src2: GeneralReg,
+    );
+
     fn and_reg64_reg64_reg64(
         buf: &mut Vec<'_, u8>,
         dst: GeneralReg,
@@ -442,6 +460,16 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
         src1: GeneralReg,
         src2: GeneralReg,
     );
+
+    /// `dst = src1 * src2`, and `overflow = 1` if that overflowed as a signed 64-bit
+    /// multiplication, or 0 if it didn't.
+    fn imul_overflow_reg64_reg64_reg64(
+        buf: &mut Vec<'_, u8>,
+        dst: GeneralReg,
+        overflow: GeneralReg,
+        src1: GeneralReg,
+        src2: GeneralReg,
+    );
     fn umul_reg64_reg64_reg64<'a, ASM, CC>(
         buf: &mut Vec<'a, u8>,
         storage_manager: &mut StorageManager<'a, '_, GeneralReg, FloatReg, ASM, CC>,
@@ -500,6 +528,17 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
         src2: GeneralReg,
     );
 
+    /// `dst = src1 - src2`, and `overflow = 1` if that overflowed as a signed (or unsigned) 64-bit
+    /// subtraction, or 0 if it didn't. The overflow is read from the flags the subtraction sets.
+    fn sub_overflow_reg64_reg64_reg64(
+        buf: &mut Vec<'_, u8>,
+        signed: bool,
+        dst: GeneralReg,
+        overflow: GeneralReg,
+        src1: GeneralReg,
+        src2: GeneralReg,
+    );
+
     fn eq_reg_reg_reg(
         buf: &mut Vec<'_, u8>,
         register_width: RegisterWidth,
@@ -562,8 +601,6 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
 
     fn to_float_freg64_freg32(buf: &mut Vec<'_, u8>, dst: FloatReg, src: FloatReg);
 
-    fn set_if_overflow(buf: &mut Vec<'_, u8>, dst: GeneralReg);
-
     fn ret(buf: &mut Vec<'_, u8>);
 }
 
@@ -825,6 +862,12 @@ impl<
                         offset: offset + setup_offset as u64,
                         name,
                     },
+                    Relocation::LinkedFunctionPointer { offset, name } => {
+                        Relocation::LinkedFunctionPointer {
+                            offset: offset + setup_offset as u64,
+                            name,
+                        }
+                    }
                     Relocation::JmpToReturn { .. } => unreachable!(),
                 }),
         );
@@ -1115,51 +1158,14 @@ impl<
         num_layout: &InLayout<'a>,
         return_layout: &InLayout<'a>,
     ) {
-        use Builtin::Int;
-
-        let buf = &mut self.buf;
-
-        let struct_size = self.layout_interner.stack_size(*return_layout);
-
-        let base_offset = self.storage_manager.claim_stack_area(dst, struct_size);
-
-        match self.layout_interner.get(*num_layout).repr {
-            LayoutRepr::Builtin(Int(
-                IntWidth::I64 | IntWidth::I32 | IntWidth::I16 | IntWidth::I8,
-            )) => {
-                let dst_reg = self
-                    .storage_manager
-                    .claim_general_reg(buf, &Symbol::DEV_TMP);
-
-                let overflow_reg = self
-                    .storage_manager
-                    .claim_general_reg(buf, &Symbol::DEV_TMP2);
-
-                let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
-                let src2_reg = self.storage_manager.load_to_general_reg(buf, src2);
-
-                ASM::add_reg64_reg64_reg64(buf, dst_reg, src1_reg, src2_reg);
-                ASM::set_if_overflow(buf, overflow_reg);
-
-                ASM::mov_base32_reg64(buf, base_offset, dst_reg);
-                ASM::mov_base32_reg64(buf, base_offset + 8, overflow_reg);
-
-                self.free_symbol(&Symbol::DEV_TMP);
-                self.free_symbol(&Symbol::DEV_TMP2);
-            }
-            LayoutRepr::Builtin(Int(
-                IntWidth::U64 | IntWidth::U32 | IntWidth::U16 | IntWidth::U8,
-            )) => {
-                todo!("addChecked for unsigned integers")
-            }
-            LayoutRepr::Builtin(Builtin::Float(FloatWidth::F64)) => {
-                todo!("addChecked for f64")
-            }
-            LayoutRepr::Builtin(Builtin::Float(FloatWidth::F32)) => {
-                todo!("addChecked for f32")
-            }
-            x => todo!("NumAdd: layout, {:?}", x),
-        }
+        self.build_num_checked(
+            CheckedOperation::Add,
+            dst,
+            src1,
+            src2,
+            num_layout,
+            return_layout,
+        )
     }
 
     fn build_num_sub_checked(
@@ -1170,18 +1176,30 @@ impl<
         num_layout: &InLayout<'a>,
         return_layout: &InLayout<'a>,
     ) {
-        let function_name = match self.interner().get(*num_layout).repr {
-            LayoutRepr::Builtin(Builtin::Int(width)) => &bitcode::NUM_SUB_CHECKED_INT[width],
-            LayoutRepr::Builtin(Builtin::Float(width)) => &bitcode::NUM_SUB_CHECKED_FLOAT[width],
-            LayoutRepr::Builtin(Builtin::Decimal) => bitcode::DEC_SUB_WITH_OVERFLOW,
-            x => internal_error!("NumSubChecked is not defined for {:?}", x),
-        };
+        self.build_num_checked(
+            CheckedOperation::Sub,
+            dst,
+            src1,
+            src2,
+            num_layout,
+            return_layout,
+        )
+    }
 
-        self.build_fn_call(
+    fn build_num_mul_checked(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        num_layout: &InLayout<'a>,
+        return_layout: &InLayout<'a>,
+    ) {
+        self.build_num_checked(
+            CheckedOperation::Mul,
             dst,
-            function_name.to_string(),
-            &[*src1, *src2],
-            &[*num_layout, *num_layout],
+            src1,
+            src2,
+            num_layout,
             return_layout,
         )
     }
@@ -2141,6 +2159,73 @@ impl<
         self.free_symbol(&Symbol::DEV_TMP3);
     }
 
+    fn build_list_repeat(
+        &mut self,
+        dst: &Symbol,
+        args: &'a [Symbol],
+        arg_layouts: &[InLayout<'a>],
+        ret_layout: &InLayout<'a>,
+    ) {
+        let elem = args[0];
+        let elem_layout = arg_layouts[0];
+        let count = args[1];
+        let count_layout = arg_layouts[1];
+
+        // Have to pass the input element by pointer, so put it on the stack and load it's address.
+        self.storage_manager
+            .ensure_symbol_on_stack(&mut self.buf, &elem);
+        let (elem_offset, _) = self.storage_manager.stack_offset_and_size(&elem);
+
+        let reg = self
+            .storage_manager
+            .claim_general_reg(&mut self.buf, &Symbol::DEV_TMP);
+        ASM::add_reg64_reg64_imm32(&mut self.buf, reg, CC::BASE_PTR_REG, elem_offset);
+
+        // Element alignment argument (u32).
+        self.load_layout_alignment(elem_layout, Symbol::DEV_TMP2);
+
+        // Load element_width argument (usize).
+        self.load_layout_stack_size(elem_layout, Symbol::DEV_TMP3);
+
+        // Setup the return location.
+        let base_offset = self
+            .storage_manager
+            .claim_stack_area(dst, self.layout_interner.stack_size(*ret_layout));
+
+        let lowlevel_args = [
+            // element
+            Symbol::DEV_TMP,
+            count,
+            // alignment
+            Symbol::DEV_TMP2,
+            // element_width
+            Symbol::DEV_TMP3,
+        ];
+        let lowlevel_arg_layouts = [Layout::U64, count_layout, Layout::U32, Layout::U64];
+
+        self.build_fn_call(
+            &Symbol::DEV_TMP4,
+            bitcode::LIST_REPEAT.to_string(),
+            &lowlevel_args,
+            &lowlevel_arg_layouts,
+            ret_layout,
+        );
+        self.free_symbol(&Symbol::DEV_TMP);
+        self.free_symbol(&Symbol::DEV_TMP2);
+        self.free_symbol(&Symbol::DEV_TMP3);
+
+        // Return list value from fn call
+        self.storage_manager.copy_symbol_to_stack_offset(
+            self.layout_interner,
+            &mut self.buf,
+            base_offset,
+            &Symbol::DEV_TMP4,
+            ret_layout,
+        );
+
+        self.free_symbol(&Symbol::DEV_TMP4);
+    }
+
     fn build_list_get_unsafe(
         &mut self,
         dst: &Symbol,
@@ -2445,7 +2530,6 @@ impl<
         element_in_layout: &InLayout<'a>,
         elements: &[ListLiteralElement<'a>],
     ) {
-        let element_layout = self.layout_interner.get(*element_in_layout);
         let element_width = self.layout_interner.stack_size(*element_in_layout) as u64;
 
         // load the total size of the data we want to store (excludes refcount)
@@ -2471,59 +2555,26 @@ impl<
         self.free_symbol(&element_alignment_symbol);
 
         // The pointer already points to the first element
-        let ptr_reg = self
-            .storage_manager
-            .load_to_general_reg(&mut self.buf, &Symbol::DEV_TMP3);
-
-        // Copy everything into output array.
-        let mut element_offset = 0;
-        for elem in elements {
-            // TODO: this could be a lot faster when loading large lists
-            // if we move matching on the element layout to outside this loop.
-            // It also greatly bloats the code here.
-            // Refactor this and switch to one external match.
-            // We also could make loadining indivitual literals much faster
-            let element_symbol = match elem {
-                ListLiteralElement::Symbol(sym) => {
-                    self.load_literal_symbols(&[*sym]);
-                    *sym
-                }
-                ListLiteralElement::Literal(lit) => {
-                    self.load_literal(&Symbol::DEV_TMP, element_in_layout, lit);
-                    Symbol::DEV_TMP
-                }
-            };
-
-            Self::ptr_write(
-                &mut self.buf,
-                &mut self.storage_manager,
-                self.layout_interner,
-                ptr_reg,
-                element_offset,
-                element_width,
-                element_layout,
-                element_symbol,
-            );
+        self.write_list_literal(sym, element_in_layout, elements);
+    }
 
-            element_offset += element_width as i32;
-            if element_symbol == Symbol::DEV_TMP {
-                self.free_symbol(&element_symbol);
-            }
-        }
+    fn create_stack_array(
+        &mut self,
+        sym: &Symbol,
+        element_in_layout: &InLayout<'a>,
+        elements: &[ListLiteralElement<'a>],
+    ) {
+        let element_width = self.layout_interner.stack_size(*element_in_layout);
+        let base_offset = self
+            .storage_manager
+            .claim_anonymous_stack_area(element_width * elements.len() as u32);
 
-        // Setup list on stack.
-        self.storage_manager.with_tmp_general_reg(
-            &mut self.buf,
-            |storage_manager, buf, tmp_reg| {
-                let base_offset = storage_manager.claim_stack_area(sym, 24);
-                ASM::mov_base32_reg64(buf, base_offset, ptr_reg);
+        let ptr_reg = self
+            .storage_manager
+            .claim_general_reg(&mut self.buf, &Symbol::DEV_TMP3);
+        ASM::add_reg64_reg64_imm32(&mut self.buf, ptr_reg, CC::BASE_PTR_REG, base_offset);
 
-                ASM::mov_reg64_imm64(buf, tmp_reg, elements.len() as i64);
-                ASM::mov_base32_reg64(buf, base_offset + 8, tmp_reg);
-                ASM::mov_base32_reg64(buf, base_offset + 16, tmp_reg);
-            },
-        );
-        self.free_symbol(&Symbol::DEV_TMP3);
+        self.write_list_literal(sym, element_in_layout, elements);
     }
 
     fn create_struct(&mut self, sym: &Symbol, layout: &InLayout<'a>, fields: &'a [Symbol]) {
@@ -2640,6 +2691,21 @@ impl<
         self.free_symbol(&Symbol::DEV_TMP3);
     }
 
+    fn stack_box(&mut self, sym: Symbol, value: Symbol, element_layout: InLayout<'a>) {
+        let base_offset = self
+            .storage_manager
+            .claim_anonymous_stack_area(self.layout_interner.stack_size(element_layout));
+
+        let ptr_reg = self
+            .storage_manager
+            .claim_general_reg(&mut self.buf, &Symbol::DEV_TMP3);
+        ASM::add_reg64_reg64_imm32(&mut self.buf, ptr_reg, CC::BASE_PTR_REG, base_offset);
+
+        self.build_ptr_write(sym, Symbol::DEV_TMP3, value, element_layout);
+
+        self.free_symbol(&Symbol::DEV_TMP3);
+    }
+
     fn expr_unbox(&mut self, dst: Symbol, ptr: Symbol, element_layout: InLayout<'a>) {
         let ptr_reg = self
             .storage_manager
@@ -3242,6 +3308,185 @@ impl<
         }
     }
 
+    /// Store the `{ value, overflowed }` record of a checked operation into dst. 64-bit integers
+    /// read the overflow from the flags the instruction sets; other numbers call the builtin.
+    fn build_num_checked(
+        &mut self,
+        op: CheckedOperation,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        num_layout: &InLayout<'a>,
+        return_layout: &InLayout<'a>,
+    ) {
+        let repr = self.layout_interner.get(*num_layout).repr;
+
+        let signed = match repr {
+            LayoutRepr::Builtin(Builtin::Int(IntWidth::I64)) => Some(true),
+            // there is no single instruction for an unsigned multiplication into any register
+            LayoutRepr::Builtin(Builtin::Int(IntWidth::U64)) if op != CheckedOperation::Mul => {
+                Some(false)
+            }
+            _ => None,
+        };
+
+        if let Some(signed) = signed {
+            let struct_size = self.layout_interner.stack_size(*return_layout);
+            let base_offset = self.storage_manager.claim_stack_area(dst, struct_size);
+
+            let buf = &mut self.buf;
+
+            let dst_reg = self
+                .storage_manager
+                .claim_general_reg(buf, &Symbol::DEV_TMP);
+            let overflow_reg = self
+                .storage_manager
+                .claim_general_reg(buf, &Symbol::DEV_TMP2);
+
+            let src1_reg = self.storage_manager.load_to_general_reg(buf, src1);
+            let src2_reg = self.storage_manager.load_to_general_reg(buf, src2);
+
+            match op {
+                CheckedOperation::Add => ASM::add_overflow_reg64_reg64_reg64(
+                    buf,
+                    signed,
+                    dst_reg,
+                    overflow_reg,
+                    src1_reg,
+                    src2_reg,
+                ),
+                CheckedOperation::Sub => ASM::sub_overflow_reg64_reg64_reg64(
+                    buf,
+                    signed,
+                    dst_reg,
+                    overflow_reg,
+                    src1_reg,
+                    src2_reg,
+                ),
+                CheckedOperation::Mul => ASM::imul_overflow_reg64_reg64_reg64(
+                    buf,
+                    dst_reg,
+                    overflow_reg,
+                    src1_reg,
+                    src2_reg,
+                ),
+            }
+
+            ASM::mov_base32_reg64(buf, base_offset, dst_reg);
+            ASM::mov_base32_reg64(buf, base_offset + 8, overflow_reg);
+
+            self.free_symbol(&Symbol::DEV_TMP);
+            self.free_symbol(&Symbol::DEV_TMP2);
+
+            return;
+        }
+
+        let function_name = match (op, repr) {
+            (CheckedOperation::Add, LayoutRepr::Builtin(Builtin::Int(width))) => {
+                &bitcode::NUM_ADD_CHECKED_INT[width]
+            }
+            (CheckedOperation::Sub, LayoutRepr::Builtin(Builtin::Int(width))) => {
+                &bitcode::NUM_SUB_CHECKED_INT[width]
+            }
+            (CheckedOperation::Mul, LayoutRepr::Builtin(Builtin::Int(width))) => {
+                &bitcode::NUM_MUL_CHECKED_INT[width]
+            }
+            (CheckedOperation::Add, LayoutRepr::Builtin(Builtin::Float(width))) => {
+                &bitcode::NUM_ADD_CHECKED_FLOAT[width]
+            }
+            (CheckedOperation::Sub, LayoutRepr::Builtin(Builtin::Float(width))) => {
+                &bitcode::NUM_SUB_CHECKED_FLOAT[width]
+            }
+            (CheckedOperation::Mul, LayoutRepr::Builtin(Builtin::Float(width))) => {
+                &bitcode::NUM_MUL_CHECKED_FLOAT[width]
+            }
+            (CheckedOperation::Add, LayoutRepr::Builtin(Builtin::Decimal)) => {
+                bitcode::DEC_ADD_WITH_OVERFLOW
+            }
+            (CheckedOperation::Sub, LayoutRepr::Builtin(Builtin::Decimal)) => {
+                bitcode::DEC_SUB_WITH_OVERFLOW
+            }
+            (CheckedOperation::Mul, LayoutRepr::Builtin(Builtin::Decimal)) => {
+                bitcode::DEC_MUL_WITH_OVERFLOW
+            }
+            (_, x) => internal_error!("checked arithmetic is not defined for {:?}", x),
+        };
+
+        self.build_fn_call(
+            dst,
+            function_name.to_string(),
+            &[*src1, *src2],
+            &[*num_layout, *num_layout],
+            return_layout,
+        )
+    }
+
+    /// Copy the elements of a list literal to the pointer in `Symbol::DEV_TMP3`,
+    /// and store the list itself in `sym`.
+    fn write_list_literal(
+        &mut self,
+        sym: &Symbol,
+        element_in_layout: &InLayout<'a>,
+        elements: &[ListLiteralElement<'a>],
+    ) {
+        let element_layout = self.layout_interner.get(*element_in_layout);
+        let element_width = self.layout_interner.stack_size(*element_in_layout) as u64;
+
+        let ptr_reg = self
+            .storage_manager
+            .load_to_general_reg(&mut self.buf, &Symbol::DEV_TMP3);
+
+        // Copy everything into output array.
+        let mut element_offset = 0;
+        for elem in elements {
+            // TODO: this could be a lot faster when loading large lists
+            // if we move matching on the element layout to outside this loop.
+            // It also greatly bloats the code here.
+            // Refactor this and switch to one external match.
+            // We also could make loadining indivitual literals much faster
+            let element_symbol = match elem {
+                ListLiteralElement::Symbol(sym) => {
+                    self.load_literal_symbols(&[*sym]);
+                    *sym
+                }
+                ListLiteralElement::Literal(lit) => {
+                    self.load_literal(&Symbol::DEV_TMP, element_in_layout, lit);
+                    Symbol::DEV_TMP
+                }
+            };
+
+            Self::ptr_write(
+                &mut self.buf,
+                &mut self.storage_manager,
+                self.layout_interner,
+                ptr_reg,
+                element_offset,
+                element_width,
+                element_layout,
+                element_symbol,
+            );
+
+            element_offset += element_width as i32;
+            if element_symbol == Symbol::DEV_TMP {
+                self.free_symbol(&element_symbol);
+            }
+        }
+
+        // Setup list on stack.
+        self.storage_manager.with_tmp_general_reg(
+            &mut self.buf,
+            |storage_manager, buf, tmp_reg| {
+                let base_offset = storage_manager.claim_stack_area(sym, 24);
+                ASM::mov_base32_reg64(buf, base_offset, ptr_reg);
+
+                ASM::mov_reg64_imm64(buf, tmp_reg, elements.len() as i64);
+                ASM::mov_base32_reg64(buf, base_offset + 8, tmp_reg);
+                ASM::mov_base32_reg64(buf, base_offset + 16, tmp_reg);
+            },
+        );
+        self.free_symbol(&Symbol::DEV_TMP3);
+    }
+
     fn allocate_with_refcount(
         &mut self,
         dst: Symbol,
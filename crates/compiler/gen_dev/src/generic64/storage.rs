@@ -1336,6 +1336,13 @@ impl<
         base_offset
     }
 
+    /// claim_anonymous_stack_area claims stack space that doesn't belong to any symbol.
+    /// It is never freed, so it stays valid until the end of the current function.
+    /// It returns the base offset of the stack area.
+    pub fn claim_anonymous_stack_area(&mut self, size: u32) -> i32 {
+        self.claim_stack_size(size)
+    }
+
     /// claim_stack_size claims `amount` bytes from the stack alignind to 8.
     /// This may be free space in the stack or result in increasing the stack size.
     /// It returns base pointer relative offset of the new data.
@@ -1145,6 +1145,16 @@ where
     }
 }
 
+/// Set `dst` to 1 if the last addition or subtraction overflowed: signed overflow sets OF, and
+/// unsigned overflow sets CF.
+fn set_if_overflowed(buf: &mut Vec<'_, u8>, signed: bool, dst: X86_64GeneralReg) {
+    if signed {
+        seto_reg64(buf, dst);
+    } else {
+        setb_reg64(buf, dst);
+    }
+}
+
 impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
     // These functions should map to the raw assembly functions below.
     // In some cases, that means you can just directly call one of the direct assembly functions.
@@ -1189,6 +1199,18 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         binop_move_src_to_dst_reg64(buf, add_reg64_reg64, dst, src1, src2)
     }
 
+    fn add_overflow_reg64_reg64_reg64(
+        buf: &mut Vec<'_, u8>,
+        signed: bool,
+        dst: Reg64,
+        overflow: Reg64,
+        src1: Reg64,
+        src2: Reg64,
+    ) {
+        binop_move_src_to_dst_reg64(buf, add_reg64_reg64, dst, src1, src2);
+        set_if_overflowed(buf, signed, overflow);
+    }
+
     #[inline(always)]
     fn add_freg32_freg32_freg32(
         buf: &mut Vec<'_, u8>,
@@ -1238,9 +1260,10 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         fn_name: String,
         dst: X86_64GeneralReg,
     ) {
-        lea_reg64(buf, dst);
+        // the address is loaded from the GOT, since the function may be in another object
+        mov_reg64_rip_offset32(buf, dst, 0);
 
-        relocs.push(Relocation::LinkedFunction {
+        relocs.push(Relocation::LinkedFunctionPointer {
             offset: buf.len() as u64 - 4,
             name: fn_name,
         });
@@ -1257,6 +1280,18 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         imul_reg64_reg64(buf, dst, src2);
     }
 
+    fn imul_overflow_reg64_reg64_reg64(
+        buf: &mut Vec<'_, u8>,
+        dst: X86_64GeneralReg,
+        overflow: X86_64GeneralReg,
+        src1: X86_64GeneralReg,
+        src2: X86_64GeneralReg,
+    ) {
+        mov_reg64_reg64(buf, dst, src1);
+        imul_reg64_reg64(buf, dst, src2);
+        seto_reg64(buf, overflow);
+    }
+
     fn umul_reg64_reg64_reg64<'a, ASM, CC>(
         buf: &mut Vec<'a, u8>,
         storage_manager: &mut StorageManager<'a, '_, X86_64GeneralReg, X86_64FloatReg, ASM, CC>,
@@ -1737,6 +1772,19 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         sub_reg64_reg64(buf, dst, src2);
     }
 
+    fn sub_overflow_reg64_reg64_reg64(
+        buf: &mut Vec<'_, u8>,
+        signed: bool,
+        dst: X86_64GeneralReg,
+        overflow: X86_64GeneralReg,
+        src1: X86_64GeneralReg,
+        src2: X86_64GeneralReg,
+    ) {
+        mov_reg64_reg64(buf, dst, src1);
+        sub_reg64_reg64(buf, dst, src2);
+        set_if_overflowed(buf, signed, overflow);
+    }
+
     #[inline(always)]
     fn eq_reg_reg_reg(
         buf: &mut Vec<'_, u8>,
@@ -1865,10 +1913,6 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         ret(buf);
     }
 
-    fn set_if_overflow(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg) {
-        seto_reg64(buf, dst);
-    }
-
     fn and_reg64_reg64_reg64(buf: &mut Vec<'_, u8>, dst: Reg64, src1: Reg64, src2: Reg64) {
         binop_move_src_to_dst_reg64(buf, and_reg64_reg64, dst, src1, src2)
     }
@@ -2573,23 +2617,15 @@ fn mov_reg64_imm64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, imm: i64) {
     }
 }
 
-/// `LEA r64, m` -> Store effective address for m in register r64.
+/// `MOV r64, m64` -> Load the 64 bits at RIP + offset into r64.
 #[inline(always)]
-fn lea_reg64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg) {
-    let rex = add_opcode_extension(dst, REX_W);
-    let rex = add_reg_extension(dst, rex);
+fn mov_reg64_rip_offset32(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, offset: u32) {
+    let rex = add_reg_extension(dst, REX_W);
     let dst_mod = dst as u8 % 8;
 
     #[allow(clippy::unusual_byte_groupings)]
-    buf.extend([
-        rex,
-        0x8d,
-        0b00_000_101 | (dst_mod << 3),
-        0x00,
-        0x00,
-        0x00,
-        0x00,
-    ])
+    buf.extend([rex, 0x8b, 0b00_000_101 | (dst_mod << 3)]);
+    buf.extend(offset.to_le_bytes());
 }
 
 fn raw_mov_reg_reg(
@@ -3745,11 +3781,12 @@ mod tests {
     }
 
     #[test]
-    fn test_lea_reg64() {
+    fn test_mov_reg64_rip_offset32() {
         disassembler_test!(
-            lea_reg64,
-            |reg| format!("lea {}, [rip]", reg),
-            ALL_GENERAL_REGS
+            mov_reg64_rip_offset32,
+            |reg, imm| format!("mov {}, qword ptr [rip + 0x{:x}]", reg, imm),
+            ALL_GENERAL_REGS,
+            [TEST_I32 as u32]
         );
     }
 
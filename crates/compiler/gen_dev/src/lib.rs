@@ -15,13 +15,14 @@ use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::code_gen_help::{CallerProc, CodeGenHelp};
 use roc_mono::ir::{
     BranchInfo, CallType, CrashTag, Expr, HigherOrderLowLevel, JoinPointId, ListLiteralElement,
-    Literal, Param, Proc, ProcLayout, SelfRecursive, Stmt,
+    Literal, ModifyRc, Param, Proc, ProcLayout, SelfRecursive, Stmt,
 };
 use roc_mono::layout::{
-    Builtin, InLayout, Layout, LayoutIds, LayoutInterner, LayoutRepr, STLayoutInterner,
+    Builtin, InLayout, Layout, LayoutIds, LayoutInterner, LayoutRepr, Niche, STLayoutInterner,
     TagIdIntType, UnionLayout,
 };
 use roc_mono::list_element_layout;
+use roc_mono::mangle::{proc_layout_hash, specialization_name};
 
 mod generic64;
 mod object_builder;
@@ -68,6 +69,12 @@ pub enum Relocation {
         offset: u64,
         name: String,
     },
+    /// Loads the address of a function from the global offset table, so the code still works
+    /// when it ends up in a shared library, or in a binary that is loaded at a random address.
+    LinkedFunctionPointer {
+        offset: u64,
+        name: String,
+    },
     LinkedData {
         offset: u64,
         name: String,
@@ -131,29 +138,16 @@ trait Backend<'a> {
         &mut Vec<'a, CallerProc<'a>>,
     );
 
-    fn function_symbol_to_string<'b, I>(
+    fn function_symbol_to_string<I>(
         &self,
         symbol: Symbol,
         arguments: I,
         _lambda_set: Option<InLayout>,
-        result: InLayout,
+        result: InLayout<'a>,
     ) -> String
     where
-        I: Iterator<Item = InLayout<'b>>,
+        I: Iterator<Item = InLayout<'a>>,
     {
-        use std::hash::{BuildHasher, Hash, Hasher};
-
-        // NOTE: due to randomness, this will not be consistent between runs
-        let mut state = roc_collections::all::BuildHasher::default().build_hasher();
-        for a in arguments {
-            a.hash(&mut state);
-        }
-
-        // lambda set should not matter; it should already be added as an argument
-        // lambda_set.hash(&mut state);
-
-        result.hash(&mut state);
-
         let interns = self.interns();
         let ident_string = symbol.as_str(interns);
         let module_string = interns.module_ids.get_name(symbol.module_id()).unwrap();
@@ -163,7 +157,11 @@ trait Backend<'a> {
         if ident_string.contains("#help") {
             format!("{}_{}_1", module_string, ident_string)
         } else {
-            format!("{}_{}_{}", module_string, ident_string, state.finish())
+            // lambda set should not matter; it should already be added as an argument
+            let layout_hash =
+                proc_layout_hash(self.interner(), interns, arguments, Niche::NONE, result);
+
+            specialization_name(interns, symbol, layout_hash)
         }
     }
 
@@ -541,6 +539,7 @@ trait Backend<'a> {
                     CallType::Foreign {
                         foreign_symbol,
                         ret_layout,
+                        ..
                     } => {
                         let mut arg_layouts: bumpalo::collections::Vec<InLayout<'a>> =
                             bumpalo::vec![in self.env().arena];
@@ -578,6 +577,9 @@ trait Backend<'a> {
                 }
                 self.create_array(sym, elem_layout, elems);
             }
+            Expr::StackArray { elem_layout, elems } => {
+                self.create_stack_array(sym, elem_layout, elems);
+            }
             Expr::Struct(fields) => {
                 self.load_literal_symbols(fields);
                 self.create_struct(sym, layout, fields);
@@ -621,6 +623,15 @@ trait Backend<'a> {
                 self.load_literal_symbols([*value].as_slice());
                 self.expr_box(*sym, *value, element_layout)
             }
+            Expr::StackBox { symbol: value } => {
+                let element_layout = match self.interner().get(*layout).repr {
+                    LayoutRepr::Boxed(boxed) => boxed,
+                    _ => unreachable!("{:?}", self.interner().dbg(*layout)),
+                };
+
+                self.load_literal_symbols([*value].as_slice());
+                self.stack_box(*sym, *value, element_layout)
+            }
             Expr::ExprUnbox { symbol: ptr } => {
                 let element_layout = *layout;
 
@@ -694,6 +705,9 @@ trait Backend<'a> {
             LowLevel::NumSubChecked => {
                 self.build_num_sub_checked(sym, &args[0], &args[1], &arg_layouts[0], ret_layout)
             }
+            LowLevel::NumMulChecked => {
+                self.build_num_mul_checked(sym, &args[0], &args[1], &arg_layouts[0], ret_layout)
+            }
             LowLevel::NumAcos => self.build_fn_call(
                 sym,
                 bitcode::NUM_ACOS[FloatWidth::F64].to_string(),
@@ -786,6 +800,48 @@ trait Backend<'a> {
                 );
                 self.build_num_sub_wrap(sym, &args[0], &args[1], ret_layout)
             }
+            LowLevel::NumAddSaturated => match self.interner().get(*ret_layout).repr {
+                LayoutRepr::Builtin(Builtin::Int(int_width)) => self.build_fn_call(
+                    sym,
+                    bitcode::NUM_ADD_SATURATED_INT[int_width].to_string(),
+                    args,
+                    arg_layouts,
+                    ret_layout,
+                ),
+                LayoutRepr::Builtin(Builtin::Float(_)) => {
+                    // saturated add is just normal add
+                    self.build_num_add(sym, &args[0], &args[1], ret_layout)
+                }
+                LayoutRepr::Builtin(Builtin::Decimal) => self.build_fn_call(
+                    sym,
+                    bitcode::DEC_ADD_SATURATED.to_string(),
+                    args,
+                    arg_layouts,
+                    ret_layout,
+                ),
+                _ => internal_error!("invalid return type"),
+            },
+            LowLevel::NumMulSaturated => match self.interner().get(*ret_layout).repr {
+                LayoutRepr::Builtin(Builtin::Int(int_width)) => self.build_fn_call(
+                    sym,
+                    bitcode::NUM_MUL_SATURATED_INT[int_width].to_string(),
+                    args,
+                    arg_layouts,
+                    ret_layout,
+                ),
+                LayoutRepr::Builtin(Builtin::Float(_)) => {
+                    // saturated mul is just normal mul
+                    self.build_num_mul(sym, &args[0], &args[1], ret_layout)
+                }
+                LayoutRepr::Builtin(Builtin::Decimal) => self.build_fn_call(
+                    sym,
+                    bitcode::DEC_MUL_SATURATED.to_string(),
+                    args,
+                    arg_layouts,
+                    ret_layout,
+                ),
+                _ => internal_error!("invalid return type"),
+            },
             LowLevel::NumSubSaturated => match self.interner().get(*ret_layout).repr {
                 LayoutRepr::Builtin(Builtin::Int(int_width)) => self.build_fn_call(
                     sym,
@@ -801,10 +857,13 @@ trait Backend<'a> {
                     // saturated sub is just normal sub
                     self.build_num_sub(sym, &args[0], &args[1], ret_layout)
                 }
-                LayoutRepr::Builtin(Builtin::Decimal) => {
-                    // self.load_args_and_call_zig(backend, bitcode::DEC_SUB_SATURATED)
-                    todo!()
-                }
+                LayoutRepr::Builtin(Builtin::Decimal) => self.build_fn_call(
+                    sym,
+                    bitcode::DEC_SUB_SATURATED.to_string(),
+                    args,
+                    arg_layouts,
+                    ret_layout,
+                ),
                 _ => internal_error!("invalid return type"),
             },
             LowLevel::NumBitwiseAnd => {
@@ -1101,6 +1160,14 @@ trait Backend<'a> {
                 );
                 self.build_list_append_unsafe(sym, args, arg_layouts, ret_layout)
             }
+            LowLevel::ListRepeat => {
+                debug_assert_eq!(
+                    2,
+                    args.len(),
+                    "ListRepeat: expected to have exactly two arguments"
+                );
+                self.build_list_repeat(sym, args, arg_layouts, ret_layout)
+            }
             LowLevel::ListGetUnsafe => {
                 debug_assert_eq!(
                     2,
@@ -1429,6 +1496,44 @@ trait Backend<'a> {
                 let intrinsic = bitcode::LIST_SUBLIST.to_string();
                 self.build_fn_call(sym, intrinsic, &args, &arg_layouts, &list_layout);
             }
+            LowLevel::ListChunksOf | LowLevel::ListWindows => {
+                //    list: RocList,
+                //    alignment: u32,
+                //    element_width: usize,
+                //    size: usize,
+                //    inc_n_element: IncN,
+
+                let list = args[0];
+                let size = args[1];
+
+                let list_layout = arg_layouts[0];
+                let list_argument = self.list_argument(list_layout);
+                let element_layout = list_argument.element_layout;
+
+                let args = [
+                    list,
+                    list_argument.alignment,
+                    list_argument.element_width,
+                    size,
+                    self.increment_fn_pointer(element_layout),
+                ];
+
+                let layout_usize = Layout::U64;
+
+                let arg_layouts = [
+                    arg_layouts[0],
+                    Layout::U32,
+                    layout_usize,
+                    arg_layouts[1],
+                    layout_usize,
+                ];
+
+                let intrinsic = match lowlevel {
+                    LowLevel::ListChunksOf => bitcode::LIST_CHUNKS_OF,
+                    _ => bitcode::LIST_WINDOWS,
+                };
+                self.build_fn_call(sym, intrinsic.to_string(), &args, &arg_layouts, ret_layout);
+            }
             LowLevel::ListSwap => {
                 let list = args[0];
                 let i = args[1];
@@ -1541,6 +1646,41 @@ trait Backend<'a> {
                     ret_layout,
                 );
             }
+            LowLevel::CryptoSha256 => self.build_fn_call(
+                sym,
+                bitcode::CRYPTO_SHA256.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::CryptoBlake3 => self.build_fn_call(
+                sym,
+                bitcode::CRYPTO_BLAKE3.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::CryptoConstantTimeEq => self.build_fn_call(
+                sym,
+                bitcode::CRYPTO_CONSTANT_TIME_EQ.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::RandomU64s => self.build_fn_call(
+                sym,
+                bitcode::RANDOM_U64S.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::RandomBytes => self.build_fn_call(
+                sym,
+                bitcode::RANDOM_BYTES.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
 
             x => todo!("low level, {:?}", x),
         }
@@ -1688,6 +1828,16 @@ trait Backend<'a> {
         return_layout: &InLayout<'a>,
     );
 
+    /// build_num_mul_checked stores the product of src1 and src2 into dst.
+    fn build_num_mul_checked(
+        &mut self,
+        dst: &Symbol,
+        src1: &Symbol,
+        src2: &Symbol,
+        num_layout: &InLayout<'a>,
+        return_layout: &InLayout<'a>,
+    );
+
     /// build_num_mul stores `src1 * src2` into dst.
     fn build_num_mul(&mut self, dst: &Symbol, src1: &Symbol, src2: &Symbol, layout: &InLayout<'a>);
 
@@ -1874,6 +2024,15 @@ trait Backend<'a> {
         ret_layout: &InLayout<'a>,
     );
 
+    /// build_list_repeat returns a new list holding the given element the given number of times.
+    fn build_list_repeat(
+        &mut self,
+        dst: &Symbol,
+        args: &'a [Symbol],
+        arg_layouts: &[InLayout<'a>],
+        ret_layout: &InLayout<'a>,
+    );
+
     /// build_list_get_unsafe loads the element from the list at the index.
     fn build_list_get_unsafe(
         &mut self,
@@ -1977,6 +2136,15 @@ trait Backend<'a> {
         elems: &'a [ListLiteralElement<'a>],
     );
 
+    /// create_stack_array creates an array whose elements are stored in the stack frame.
+    /// It must never be refcounted.
+    fn create_stack_array(
+        &mut self,
+        sym: &Symbol,
+        elem_layout: &InLayout<'a>,
+        elems: &'a [ListLiteralElement<'a>],
+    );
+
     /// create_struct creates a struct with the elements specified loaded into it as data.
     fn create_struct(&mut self, sym: &Symbol, layout: &InLayout<'a>, fields: &'a [Symbol]);
 
@@ -2017,6 +2185,9 @@ trait Backend<'a> {
     /// store a refcounted value on the heap
     fn expr_box(&mut self, sym: Symbol, value: Symbol, element_layout: InLayout<'a>);
 
+    /// store a value in the stack frame, and point to it like a box (without a refcount)
+    fn stack_box(&mut self, sym: Symbol, value: Symbol, element_layout: InLayout<'a>);
+
     /// return_symbol moves a symbol to the correct return location for the backend and adds a jump to the end of the function.
     fn return_symbol(&mut self, sym: &Symbol, layout: &InLayout<'a>);
 
@@ -2097,7 +2268,7 @@ trait Backend<'a> {
                             self.set_last_seen(*sym, stmt);
                         }
                     }
-                    Expr::ExprBox { symbol } => {
+                    Expr::ExprBox { symbol } | Expr::StackBox { symbol } => {
                         self.set_last_seen(*symbol, stmt);
                     }
                     Expr::ExprUnbox { symbol } => {
@@ -2117,7 +2288,7 @@ trait Backend<'a> {
                     Expr::UnionAtIndex { structure, .. } => {
                         self.set_last_seen(*structure, stmt);
                     }
-                    Expr::Array { elems, .. } => {
+                    Expr::Array { elems, .. } | Expr::StackArray { elems, .. } => {
                         for elem in *elems {
                             if let ListLiteralElement::Symbol(sym) = elem {
                                 self.set_last_seen(*sym, stmt);
@@ -2160,6 +2331,9 @@ trait Backend<'a> {
                 let sym = modify.get_symbol();
 
                 self.set_last_seen(sym, stmt);
+                if let ModifyRc::IncN(_, count) = modify {
+                    self.set_last_seen(*count, stmt);
+                }
                 self.scan_ast(following);
             }
             Stmt::Join {
@@ -596,7 +596,7 @@ fn build_proc<'a, B: Backend<'a>>(
     let (proc_data, relocs, rc_proc_names) = backend.build_proc(proc, layout_ids);
     let proc_offset = output.add_symbol_data(proc_id, section_id, &proc_data, 16);
     for reloc in relocs.iter() {
-        let elfreloc = match reloc {
+        let (offset, symbol, access) = match reloc {
             Relocation::LocalData { offset, data } => {
                 let data_symbol = write::Symbol {
                     name: format!("{}.data{}", fn_name, local_data_index)
@@ -613,30 +613,18 @@ fn build_proc<'a, B: Backend<'a>>(
                 local_data_index += 1;
                 let data_id = output.add_symbol(data_symbol);
                 output.add_symbol_data(data_id, data_section, data, 4);
-                write::Relocation {
-                    offset: offset + proc_offset,
-                    size: 32,
-                    kind: RelocationKind::Relative,
-                    encoding: RelocationEncoding::Generic,
-                    symbol: data_id,
-                    addend: -4,
-                }
+
+                (offset, data_id, SymbolAccess::Relative)
             }
             Relocation::LinkedData { offset, name } => {
                 if let Some(sym_id) = output.symbol_id(name.as_bytes()) {
-                    write::Relocation {
-                        offset: offset + proc_offset,
-                        size: 32,
-                        kind: RelocationKind::GotRelative,
-                        encoding: RelocationEncoding::Generic,
-                        symbol: sym_id,
-                        addend: -4,
-                    }
+                    (offset, sym_id, SymbolAccess::Got)
                 } else {
                     internal_error!("failed to find data symbol for {:?}", name);
                 }
             }
-            Relocation::LinkedFunction { offset, name } => {
+            Relocation::LinkedFunction { offset, name }
+            | Relocation::LinkedFunctionPointer { offset, name } => {
                 // If the symbol is an undefined roc function, we need to add it here.
                 if output.symbol_id(name.as_bytes()).is_none() && name.starts_with("roc_") {
                     let builtin_symbol = Symbol {
@@ -677,21 +665,116 @@ fn build_proc<'a, B: Backend<'a>>(
                     }
                 }
 
+                let access = match reloc {
+                    Relocation::LinkedFunctionPointer { .. } => SymbolAccess::Got,
+                    _ => SymbolAccess::Call,
+                };
+
                 if let Some(sym_id) = output.symbol_id(name.as_bytes()) {
-                    write::Relocation {
-                        offset: offset + proc_offset,
-                        size: 32,
-                        kind: RelocationKind::PltRelative,
-                        encoding: RelocationEncoding::X86Branch,
-                        symbol: sym_id,
-                        addend: -4,
-                    }
+                    (offset, sym_id, access)
                 } else {
                     internal_error!("failed to find fn symbol for {:?}", name);
                 }
             }
             Relocation::JmpToReturn { .. } => unreachable!(),
         };
-        relocations.push((section_id, elfreloc));
+
+        for reloc in position_independent_relocations(output, offset + proc_offset, symbol, access)
+        {
+            relocations.push((section_id, reloc));
+        }
+    }
+}
+
+/// How an instruction refers to a symbol
+#[derive(Debug, Clone, Copy)]
+enum SymbolAccess {
+    /// A call, which goes through the PLT if the function ends up in another object
+    Call,
+    /// A load of data in this object, relative to the instruction
+    Relative,
+    /// A load of the address of the symbol from the GOT
+    Got,
+}
+
+/// The relocations for the instruction at `offset` that refers to `symbol`. They are all relative
+/// to the instruction, or go through the GOT or PLT, so the code is position independent: it can
+/// be linked into a PIE binary, or into a shared library.
+///
+/// On x86_64, `offset` is where the 32-bit displacement in the instruction starts. On AArch64 it
+/// is the instruction itself, or the ADRP of an ADRP + LDR pair.
+fn position_independent_relocations(
+    output: &Object,
+    offset: u64,
+    symbol: SymbolId,
+    access: SymbolAccess,
+) -> std::vec::Vec<write::Relocation> {
+    let relocation = |offset, kind, encoding, addend| write::Relocation {
+        offset,
+        size: 32,
+        kind,
+        encoding,
+        symbol,
+        addend,
+    };
+
+    match (output.architecture(), output.format()) {
+        (Architecture::X86_64, _) => {
+            let (kind, encoding) = match access {
+                SymbolAccess::Call => (RelocationKind::PltRelative, RelocationEncoding::X86Branch),
+                SymbolAccess::Relative => (RelocationKind::Relative, RelocationEncoding::Generic),
+                SymbolAccess::Got => (
+                    RelocationKind::GotRelative,
+                    RelocationEncoding::X86RipRelativeMovq,
+                ),
+            };
+
+            // the displacement is relative to the end of the instruction, 4 bytes after it
+            vec![relocation(offset, kind, encoding, -4)]
+        }
+        (Architecture::Aarch64, format) => {
+            let kinds: &[RelocationKind] = match (access, format) {
+                (SymbolAccess::Call, BinaryFormat::Elf) => {
+                    &[RelocationKind::Elf(object::elf::R_AARCH64_CALL26)]
+                }
+                (SymbolAccess::Call, _) => &[RelocationKind::MachO {
+                    value: object::macho::ARM64_RELOC_BRANCH26,
+                    relative: true,
+                }],
+                (SymbolAccess::Got, BinaryFormat::Elf) => &[
+                    RelocationKind::Elf(object::elf::R_AARCH64_ADR_GOT_PAGE),
+                    RelocationKind::Elf(object::elf::R_AARCH64_LD64_GOT_LO12_NC),
+                ],
+                (SymbolAccess::Got, _) => &[
+                    RelocationKind::MachO {
+                        value: object::macho::ARM64_RELOC_GOT_LOAD_PAGE21,
+                        relative: true,
+                    },
+                    RelocationKind::MachO {
+                        value: object::macho::ARM64_RELOC_GOT_LOAD_PAGEOFF12,
+                        relative: false,
+                    },
+                ],
+                (SymbolAccess::Relative, _) => {
+                    internal_error!("the AArch64 backend does not load local data yet")
+                }
+            };
+
+            kinds
+                .iter()
+                .enumerate()
+                .map(|(index, kind)| {
+                    relocation(
+                        offset + 4 * index as u64,
+                        *kind,
+                        RelocationEncoding::Generic,
+                        0,
+                    )
+                })
+                .collect()
+        }
+        (architecture, _) => {
+            internal_error!("the dev backend does not support {:?}", architecture)
+        }
     }
 }
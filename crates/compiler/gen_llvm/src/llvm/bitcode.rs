@@ -20,6 +20,7 @@ use roc_module::symbol::Symbol;
 use roc_mono::layout::{
     Builtin, InLayout, LambdaSet, LayoutIds, LayoutInterner, LayoutRepr, STLayoutInterner,
 };
+use roc_mono::mangle::{layout_hash, specialization_name};
 
 use super::build::{create_entry_block_alloca, BuilderExt};
 use super::convert::zig_list_type;
@@ -361,9 +362,8 @@ fn build_rc_wrapper<'a, 'ctx>(
     let di_location = env.builder.get_current_debug_location().unwrap();
 
     let symbol = Symbol::GENERIC_RC_REF;
-    let fn_name = layout_ids
-        .get(symbol, &layout)
-        .to_symbol_string(symbol, &env.interns);
+    let layout_hash = layout_hash(layout_interner, &env.interns, layout);
+    let fn_name = specialization_name(&env.interns, symbol, layout_hash);
 
     let fn_name = match rc_operation {
         Mode::IncN => format!("{}_inc_n", fn_name),
@@ -463,9 +463,8 @@ pub fn build_eq_wrapper<'a, 'ctx>(
     let di_location = env.builder.get_current_debug_location().unwrap();
 
     let symbol = Symbol::GENERIC_EQ_REF;
-    let fn_name = layout_ids
-        .get(symbol, &layout)
-        .to_symbol_string(symbol, &env.interns);
+    let layout_hash = layout_hash(layout_interner, &env.interns, layout);
+    let fn_name = specialization_name(&env.interns, symbol, layout_hash);
 
     let function_value = match env.module.get_function(fn_name.as_str()) {
         Some(function_value) => function_value,
@@ -1,11 +1,13 @@
 use crate::llvm::bitcode::call_bitcode_fn;
 use crate::llvm::build_list::{self, allocate_list, empty_polymorphic_list};
 use crate::llvm::convert::{
-    argument_type_from_layout, basic_type_from_builtin, basic_type_from_layout, zig_str_type,
+    argument_type_from_layout, basic_type_from_builtin, basic_type_from_layout, zig_list_type,
+    zig_str_type,
 };
 use crate::llvm::expect::{clone_to_shared_memory, SharedMemoryPointer};
 use crate::llvm::refcounting::{
-    build_reset, decrement_refcount_layout, increment_refcount_layout, PointerToRefcount,
+    build_reset, decrement_refcount_layout, increment_n_refcount_layout, increment_refcount_layout,
+    set_builtins_refcount_mode, PointerToRefcount, RefcountMode,
 };
 use bumpalo::collections::Vec;
 use bumpalo::Bump;
@@ -19,6 +21,7 @@ use inkwell::debug_info::{
 use inkwell::memory_buffer::MemoryBuffer;
 use inkwell::module::{Linkage, Module};
 use inkwell::passes::{PassManager, PassManagerBuilder};
+use inkwell::support::LLVMString;
 use inkwell::types::{
     AnyType, BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType, IntType, StructType,
 };
@@ -38,6 +41,7 @@ use roc_debug_flags::dbg_do;
 #[cfg(debug_assertions)]
 use roc_debug_flags::ROC_PRINT_LLVM_FN_VERIFICATION;
 use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_mono::code_gen_help::REFCOUNT_MAX;
 use roc_mono::ir::{
     BranchInfo, CallType, CrashTag, EntryPoint, GlueLayouts, HostExposedLambdaSet, JoinPointId,
     ListLiteralElement, ModifyRc, OptLevel, ProcLayout, SingleEntryPoint,
@@ -46,8 +50,11 @@ use roc_mono::layout::{
     Builtin, InLayout, LambdaName, LambdaSet, Layout, LayoutIds, LayoutInterner, LayoutRepr, Niche,
     RawFunctionLayout, STLayoutInterner, TagIdIntType, UnionLayout,
 };
+use roc_mono::mangle::{proc_layout_hash, specialization_name};
+use roc_mono::static_data::{StaticConstants, StaticValue};
 use roc_std::RocDec;
-use roc_target::{PtrWidth, TargetInfo};
+use roc_target::{Endianness, PtrWidth, TargetInfo};
+use std::cell::RefCell;
 use std::convert::TryInto;
 use std::path::Path;
 use target_lexicon::{Architecture, OperatingSystem, Triple};
@@ -58,6 +65,8 @@ use super::intrinsics::{
     LLVM_STACK_SAVE,
 };
 use super::lowlevel::run_higher_order_low_level;
+use super::profile::{self, ProfileMode};
+use super::stack_probe;
 
 pub(crate) trait BuilderExt<'ctx> {
     fn new_build_struct_gep(
@@ -253,6 +262,15 @@ pub struct Env<'a, 'ctx, 'env> {
     pub target_info: TargetInfo,
     pub mode: LlvmBackendMode,
     pub exposed_to_host: MutSet<Symbol>,
+    pub profile: ProfileMode<'env>,
+    pub refcounting: RefcountMode<'a>,
+    /// Where each `dbg` is, e.g. `Main.roc:12:5`, for the host's `roc_dbg`
+    pub dbg_locations: MutMap<Symbol, &'a str>,
+    /// The values of top-level constants which were evaluated at compile time
+    pub static_constants: StaticConstants<'a>,
+    /// The name of the function of each specialization, which stays the same from one build to
+    /// the next; see [roc_mono::mangle]
+    pub func_spec_names: RefCell<MutMap<FuncSpec, &'a str>>,
 }
 
 impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
@@ -286,6 +304,10 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
         self.target_info.ptr_width() as u32 * 3
     }
 
+    pub fn small_str_length_byte(&self) -> usize {
+        small_str_length_byte(self.target_info)
+    }
+
     pub fn build_intrinsic_call(
         &self,
         intrinsic_name: &'static str,
@@ -433,6 +455,12 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
             .build_call(function, &[msg.into(), tag_id.into()], "roc_panic");
 
         call.set_call_convention(C_CALL_CONV);
+
+        // crashing is the exceptional case; keep it out of the way of the code around it
+        let kind_id = Attribute::get_named_enum_kind_id("cold");
+        debug_assert!(kind_id > 0);
+        let enum_attr = self.context.create_enum_attribute(kind_id, 0);
+        call.add_attribute(AttributeLoc::Function, enum_attr);
     }
 
     pub fn new_debug_info(module: &Module<'ctx>) -> (DebugInfoBuilder<'ctx>, DICompileUnit<'ctx>) {
@@ -491,14 +519,11 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
     }
 }
 
-pub fn module_from_builtins<'ctx>(
-    target: &target_lexicon::Triple,
-    ctx: &'ctx Context,
-    module_name: &str,
-) -> Module<'ctx> {
+/// The builtins compiled to LLVM bitcode for this target
+pub fn builtins_bitcode(target: &target_lexicon::Triple) -> &'static [u8] {
     // In the build script for the builtins module, we compile the builtins into LLVM bitcode
 
-    let bitcode_bytes: &[u8] = if target == &target_lexicon::Triple::host() {
+    if target == &target_lexicon::Triple::host() {
         include_bytes!("../../../builtins/bitcode/builtins-host.bc")
     } else {
         match target {
@@ -529,22 +554,45 @@ pub fn module_from_builtins<'ctx>(
             } => {
                 include_bytes!("../../../builtins/bitcode/builtins-windows-x86_64.bc")
             }
+            Triple {
+                architecture: Architecture::S390x,
+                operating_system: OperatingSystem::Linux,
+                ..
+            } => {
+                include_bytes!("../../../builtins/bitcode/builtins-s390x.bc")
+            }
             _ => panic!(
                 "The zig builtins are not currently built for this target: {:?}",
                 target
             ),
         }
-    };
+    }
+}
 
-    let memory_buffer = MemoryBuffer::create_from_memory_range(bitcode_bytes, module_name);
+pub fn module_from_builtins<'ctx>(
+    target: &target_lexicon::Triple,
+    ctx: &'ctx Context,
+    module_name: &str,
+) -> Module<'ctx> {
+    let memory_buffer =
+        MemoryBuffer::create_from_memory_range(builtins_bitcode(target), module_name);
+
+    parse_builtins(ctx, &memory_buffer)
+        .unwrap_or_else(|err| panic!("Unable to import builtins bitcode. LLVM error: {:?}", err))
+}
 
-    let module = Module::parse_bitcode_from_buffer(&memory_buffer, ctx)
-        .unwrap_or_else(|err| panic!("Unable to import builtins bitcode. LLVM error: {:?}", err));
+/// Parse bitcode of the builtins, either the one from [builtins_bitcode] or a copy of it
+/// that was saved earlier, and add the LLVM intrinsics the generated code uses.
+pub fn parse_builtins<'ctx>(
+    ctx: &'ctx Context,
+    memory_buffer: &MemoryBuffer,
+) -> Result<Module<'ctx>, LLVMString> {
+    let module = Module::parse_bitcode_from_buffer(memory_buffer, ctx)?;
 
     // Add LLVM intrinsics.
     add_intrinsics(ctx, &module);
 
-    module
+    Ok(module)
 }
 
 pub fn construct_optimization_passes<'a>(
@@ -817,12 +865,42 @@ pub fn build_exp_literal<'a, 'ctx>(
     }
 }
 
-fn build_string_literal<'ctx>(
+/// The index of the byte in which a small string stores its length, with the small string flag.
+/// It is the most significant byte of the capacity, so that small strings have a negative
+/// capacity. The bytes of the string come before it, which makes it the length of the longest
+/// small string too.
+fn small_str_length_byte(target_info: TargetInfo) -> usize {
+    let ptr_width = target_info.ptr_width() as usize;
+
+    match target_info.endianness() {
+        Endianness::Little => 3 * ptr_width - 1,
+        Endianness::Big => 2 * ptr_width,
+    }
+}
+
+/// The pointer, length and capacity words of a small string, read in the target's byte order.
+/// The string must not be longer than `small_str_length_byte`.
+fn small_str_words(target_info: TargetInfo, str_literal: &str) -> [u64; 3] {
+    let ptr_width = target_info.ptr_width() as usize;
+    let endianness = target_info.endianness();
+    let mut bytes = [0u8; 24];
+
+    bytes[..str_literal.len()].copy_from_slice(str_literal.as_bytes());
+    bytes[small_str_length_byte(target_info)] = str_literal.len() as u8 | roc_std::RocStr::MASK;
+
+    [
+        endianness.read_uint(&bytes[0..ptr_width]),
+        endianness.read_uint(&bytes[ptr_width..2 * ptr_width]),
+        endianness.read_uint(&bytes[2 * ptr_width..3 * ptr_width]),
+    ]
+}
+
+pub(crate) fn build_string_literal<'ctx>(
     env: &Env<'_, 'ctx, '_>,
     parent: FunctionValue<'ctx>,
     str_literal: &str,
 ) -> BasicValueEnum<'ctx> {
-    if str_literal.len() < env.small_str_bytes() as usize {
+    if str_literal.len() <= env.small_str_length_byte() {
         match env.small_str_bytes() {
             24 => small_str_ptr_width_8(env, parent, str_literal).into(),
             12 => small_str_ptr_width_4(env, str_literal).into(),
@@ -869,15 +947,7 @@ fn small_str_ptr_width_8<'ctx>(
 ) -> PointerValue<'ctx> {
     debug_assert_eq!(env.target_info.ptr_width() as u8, 8);
 
-    let mut array = [0u8; 24];
-
-    array[..str_literal.len()].copy_from_slice(str_literal.as_bytes());
-
-    array[env.small_str_bytes() as usize - 1] = str_literal.len() as u8 | roc_std::RocStr::MASK;
-
-    let word1 = u64::from_ne_bytes(array[0..8].try_into().unwrap());
-    let word2 = u64::from_ne_bytes(array[8..16].try_into().unwrap());
-    let word3 = u64::from_ne_bytes(array[16..24].try_into().unwrap());
+    let [word1, word2, word3] = small_str_words(env.target_info, str_literal);
 
     let ptr = env.ptr_int().const_int(word1, false);
     let len = env.ptr_int().const_int(word2, false);
@@ -893,19 +963,11 @@ fn small_str_ptr_width_8<'ctx>(
 fn small_str_ptr_width_4<'ctx>(env: &Env<'_, 'ctx, '_>, str_literal: &str) -> StructValue<'ctx> {
     debug_assert_eq!(env.target_info.ptr_width() as u8, 4);
 
-    let mut array = [0u8; 12];
-
-    array[..str_literal.len()].copy_from_slice(str_literal.as_bytes());
-
-    array[env.small_str_bytes() as usize - 1] = str_literal.len() as u8 | roc_std::RocStr::MASK;
+    let [word1, word2, word3] = small_str_words(env.target_info, str_literal);
 
-    let word1 = u32::from_ne_bytes(array[0..4].try_into().unwrap());
-    let word2 = u32::from_ne_bytes(array[4..8].try_into().unwrap());
-    let word3 = u32::from_ne_bytes(array[8..12].try_into().unwrap());
-
-    let ptr = env.ptr_int().const_int(word1 as u64, false);
-    let len = env.ptr_int().const_int(word2 as u64, false);
-    let cap = env.ptr_int().const_int(word3 as u64, false);
+    let ptr = env.ptr_int().const_int(word1, false);
+    let len = env.ptr_int().const_int(word2, false);
+    let cap = env.ptr_int().const_int(word3, false);
 
     let address_space = AddressSpace::default();
     let ptr_type = env.context.i8_type().ptr_type(address_space);
@@ -1002,6 +1064,7 @@ pub fn build_exp_call<'a, 'ctx>(
         CallType::Foreign {
             foreign_symbol,
             ret_layout,
+            ..
         } => build_foreign_symbol(
             env,
             layout_interner,
@@ -1150,6 +1213,16 @@ pub fn build_exp_expr<'a, 'ctx>(
             allocation.into()
         }
 
+        StackBox { symbol } => {
+            let (value, layout) = load_symbol_and_layout(scope, symbol);
+            let basic_type = basic_type_from_layout(env, layout_interner, layout);
+            let allocation = create_entry_block_alloca(env, parent, basic_type, "stack_box");
+
+            store_roc_value(env, layout_interner, layout, allocation, value);
+
+            allocation.into()
+        }
+
         ExprUnbox { symbol } => {
             let value = load_symbol(scope, symbol);
 
@@ -1330,6 +1403,9 @@ pub fn build_exp_expr<'a, 'ctx>(
         Array { elem_layout, elems } => {
             list_literal(env, layout_interner, parent, scope, *elem_layout, elems)
         }
+        StackArray { elem_layout, elems } => {
+            stack_list_literal(env, layout_interner, parent, scope, *elem_layout, elems)
+        }
         RuntimeErrorFunction(_) => todo!(),
 
         UnionAtIndex {
@@ -2305,7 +2381,9 @@ fn list_literal<'a, 'ctx>(
             let typ = element_type.array_type(const_elements.len() as u32);
             let global = env.module.add_global(typ, None, "roc__list_literal");
 
-            global.set_constant(true);
+            // Incrementing and decrementing the refcount of static data writes it back unchanged,
+            // so a list that is used directly must not end up in read-only memory.
+            global.set_constant(!is_all_constant);
             global.set_alignment(alignment);
             global.set_unnamed_addr(true);
             global.set_linkage(inkwell::module::Linkage::Private);
@@ -2361,26 +2439,84 @@ fn list_literal<'a, 'ctx>(
     } else {
         let ptr = allocate_list(env, layout_interner, element_layout, list_length_intval);
 
-        // Copy the elements from the list literal into the array
-        for (index, element) in elems.iter().enumerate() {
-            let val = match element {
-                ListLiteralElement::Literal(literal) => {
-                    build_exp_literal(env, layout_interner, parent, element_layout, literal)
-                }
-                ListLiteralElement::Symbol(symbol) => load_symbol(scope, symbol),
-            };
-            let index_val = ctx.i64_type().const_int(index as u64, false);
-            let elem_ptr = unsafe {
-                builder.new_build_in_bounds_gep(element_type, ptr, &[index_val], "index")
-            };
-
-            store_roc_value(env, layout_interner, element_layout, elem_ptr, val);
-        }
+        store_list_literal_elements(
+            env,
+            layout_interner,
+            parent,
+            scope,
+            element_layout,
+            elems,
+            ptr,
+        );
 
         super::build_list::store_list(env, ptr, list_length_intval).into()
     }
 }
 
+/// A list literal whose elements are stored in the stack frame of the current function.
+/// This list must never be reference counted!
+fn stack_list_literal<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    parent: FunctionValue<'ctx>,
+    scope: &Scope<'a, 'ctx>,
+    element_layout: InLayout<'a>,
+    elems: &[ListLiteralElement],
+) -> BasicValueEnum<'ctx> {
+    let element_type = basic_type_from_layout(env, layout_interner, element_layout);
+    let array_type = element_type.array_type(elems.len() as u32);
+
+    let array = create_entry_block_alloca(env, parent, array_type.into(), "stack_list");
+    let ptr = env.builder.build_pointer_cast(
+        array,
+        element_type.ptr_type(AddressSpace::default()),
+        "stack_list_elements",
+    );
+
+    store_list_literal_elements(
+        env,
+        layout_interner,
+        parent,
+        scope,
+        element_layout,
+        elems,
+        ptr,
+    );
+
+    let list_length_intval = env.ptr_int().const_int(elems.len() as _, false);
+
+    super::build_list::store_list(env, ptr, list_length_intval).into()
+}
+
+/// Copy the elements from a list literal into the given array
+fn store_list_literal_elements<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    parent: FunctionValue<'ctx>,
+    scope: &Scope<'a, 'ctx>,
+    element_layout: InLayout<'a>,
+    elems: &[ListLiteralElement],
+    ptr: PointerValue<'ctx>,
+) {
+    let element_type = basic_type_from_layout(env, layout_interner, element_layout);
+
+    for (index, element) in elems.iter().enumerate() {
+        let val = match element {
+            ListLiteralElement::Literal(literal) => {
+                build_exp_literal(env, layout_interner, parent, element_layout, literal)
+            }
+            ListLiteralElement::Symbol(symbol) => load_symbol(scope, symbol),
+        };
+        let index_val = env.context.i64_type().const_int(index as u64, false);
+        let elem_ptr = unsafe {
+            env.builder
+                .new_build_in_bounds_gep(element_type, ptr, &[index_val], "index")
+        };
+
+        store_roc_value(env, layout_interner, element_layout, elem_ptr, val);
+    }
+}
+
 pub fn load_roc_value<'a, 'ctx>(
     env: &Env<'a, 'ctx, '_>,
     layout_interner: &mut STLayoutInterner<'a>,
@@ -2787,6 +2923,31 @@ pub fn build_exp_stmt<'a, 'ctx>(
                         cont,
                     )
                 }
+                IncN(symbol, count) => {
+                    let (value, layout) = load_symbol_and_layout(scope, symbol);
+                    if layout_interner.contains_refcounted(layout) {
+                        // the count is a Nat, which has the same width as the refcount
+                        let amount = load_symbol(scope, count).into_int_value();
+                        increment_n_refcount_layout(
+                            env,
+                            layout_interner,
+                            layout_ids,
+                            amount,
+                            value,
+                            layout,
+                        );
+                    }
+
+                    build_exp_stmt(
+                        env,
+                        layout_interner,
+                        layout_ids,
+                        func_spec_solutions,
+                        scope,
+                        parent,
+                        cont,
+                    )
+                }
                 Dec(symbol) => {
                     let (value, layout) = load_symbol_and_layout(scope, symbol);
 
@@ -2885,6 +3046,18 @@ pub fn build_exp_stmt<'a, 'ctx>(
                 );
 
                 crate::llvm::expect::notify_parent_dbg(env, &shared_memory);
+            } else if let LlvmBackendMode::Binary = env.mode {
+                let (value, layout) = load_symbol_and_layout(scope, symbol);
+
+                crate::llvm::dbg::call_roc_dbg(
+                    env,
+                    layout_interner,
+                    layout_ids,
+                    parent,
+                    *symbol,
+                    value,
+                    layout,
+                );
             }
 
             build_exp_stmt(
@@ -2920,7 +3093,8 @@ pub fn build_exp_stmt<'a, 'ctx>(
             let then_block = context.append_basic_block(parent, "then_block");
             let throw_block = context.append_basic_block(parent, "throw_block");
 
-            bd.build_conditional_branch(condition, then_block, throw_block);
+            let branch = bd.build_conditional_branch(condition, then_block, throw_block);
+            profile::mark_else_unlikely(env, branch);
 
             if env.mode.runs_expects() {
                 bd.position_at_end(throw_block);
@@ -2992,7 +3166,8 @@ pub fn build_exp_stmt<'a, 'ctx>(
             let then_block = context.append_basic_block(parent, "then_block");
             let throw_block = context.append_basic_block(parent, "throw_block");
 
-            bd.build_conditional_branch(condition, then_block, throw_block);
+            let branch = bd.build_conditional_branch(condition, then_block, throw_block);
+            profile::mark_else_unlikely(env, branch);
 
             if env.mode.runs_expects() {
                 bd.position_at_end(throw_block);
@@ -3423,10 +3598,21 @@ fn build_switch_ir<'a, 'ctx>(
                 let then_block = context.append_basic_block(parent, "then_block");
                 let else_block = context.append_basic_block(parent, "else_block");
 
-                builder.build_conditional_branch(cond, then_block, else_block);
+                let branch = builder.build_conditional_branch(cond, then_block, else_block);
+
+                // the explicit branch comes first in the profile, the default branch second
+                let (then_index, else_index) = if branches[0].0 == 1 { (0, 1) } else { (1, 0) };
+                profile::add_branch_weights(
+                    env,
+                    parent,
+                    *cond_symbol,
+                    branch,
+                    &[then_index, else_index],
+                );
 
                 {
                     builder.position_at_end(then_block);
+                    profile::count_branch(env, parent, *cond_symbol, 2, then_index);
 
                     let branch_val = build_exp_stmt(
                         env,
@@ -3446,6 +3632,7 @@ fn build_switch_ir<'a, 'ctx>(
 
                 {
                     builder.position_at_end(else_block);
+                    profile::count_branch(env, parent, *cond_symbol, 2, else_index);
 
                     let branch_val = build_exp_stmt(
                         env,
@@ -3496,10 +3683,18 @@ fn build_switch_ir<'a, 'ctx>(
             cases.push((int_val, block));
         }
 
-        builder.build_switch(cond, default_block, &cases);
+        let switch = builder.build_switch(cond, default_block, &cases);
+
+        // LLVM puts the default branch first, the profile puts it last
+        let default_index = branches.len();
+        let order = std::iter::once(default_index)
+            .chain(0..default_index)
+            .collect::<std::vec::Vec<_>>();
+        profile::add_branch_weights(env, parent, *cond_symbol, switch, &order);
 
-        for ((_, _, branch_expr), (_, block)) in branches.iter().zip(cases) {
+        for (index, ((_, _, branch_expr), (_, block))) in branches.iter().zip(cases).enumerate() {
             builder.position_at_end(block);
+            profile::count_branch(env, parent, *cond_symbol, default_index + 1, index);
 
             let branch_val = build_exp_stmt(
                 env,
@@ -3519,6 +3714,7 @@ fn build_switch_ir<'a, 'ctx>(
 
         // The block for the conditional's default branch.
         builder.position_at_end(default_block);
+        profile::count_branch(env, parent, *cond_symbol, default_index + 1, default_index);
 
         let default_val = build_exp_stmt(
             env,
@@ -4629,13 +4825,35 @@ pub fn build_proc_headers<'a, 'r, 'ctx>(
 
         let func_solutions = mod_solutions.func_solutions(func_name).unwrap();
 
+        let layout_hash = proc_layout_hash(
+            layout_interner,
+            &env.interns,
+            layout.arguments.iter().copied(),
+            layout.niche,
+            layout.result,
+        );
+        let base_name = specialization_name(&env.interns, symbol, layout_hash);
+
+        let has_many_specializations = func_solutions.specs().count() > 1;
+
         let it = func_solutions.specs();
         let mut function_values = std::vec::Vec::with_capacity(it.size_hint().0);
         for specialization in it {
+            // The versions of a procedure which alias analysis makes for in-place updates are
+            // told apart by their spec. Those aren't stable, but most procedures only have one.
+            let fn_name = if has_many_specializations {
+                let spec_bytes = &specialization.0[..4];
+                let spec: String = spec_bytes.iter().map(|b| format!("{:02x}", b)).collect();
+                format!("{}.spec{}", base_name, spec)
+            } else {
+                base_name.clone()
+            };
+
             let fn_val = build_proc_header(
                 env,
                 layout_interner,
                 *specialization,
+                &fn_name,
                 symbol,
                 &proc,
                 layout_ids,
@@ -4675,6 +4893,9 @@ pub fn build_procedures<'a>(
         debug_output_file,
     );
 
+    profile::write_profile_at_exit(env);
+    set_builtins_refcount_mode(env);
+
     let niche = Niche::NONE;
 
     for (symbol, top_level) in glue_layouts.getters.iter().copied() {
@@ -4853,6 +5074,7 @@ fn build_procedures_help<'a>(
         opt_level,
         entry_point,
         it,
+        &env.static_constants,
     ) {
         Err(e) => panic!("Error in alias analysis: {}", e),
         Ok(solutions) => solutions,
@@ -4938,31 +5160,11 @@ fn build_procedures_help<'a>(
     mod_solutions
 }
 
-fn func_spec_name<'a>(
-    arena: &'a Bump,
-    interns: &Interns,
-    symbol: Symbol,
-    func_spec: FuncSpec,
-) -> bumpalo::collections::String<'a> {
-    use std::fmt::Write;
-
-    let mut buf = bumpalo::collections::String::with_capacity_in(1, arena);
-
-    let ident_string = symbol.as_str(interns);
-    let module_string = interns.module_ids.get_name(symbol.module_id()).unwrap();
-    write!(buf, "{}_{}_", module_string, ident_string).unwrap();
-
-    for byte in func_spec.0.iter() {
-        write!(buf, "{:x?}", byte).unwrap();
-    }
-
-    buf
-}
-
 fn build_proc_header<'a, 'ctx>(
     env: &Env<'a, 'ctx, '_>,
     layout_interner: &mut STLayoutInterner<'a>,
     func_spec: FuncSpec,
+    fn_name: &str,
     symbol: Symbol,
     proc: &roc_mono::ir::Proc<'a>,
     layout_ids: &mut LayoutIds<'a>,
@@ -4970,8 +5172,6 @@ fn build_proc_header<'a, 'ctx>(
     let args = proc.args;
     let arena = env.arena;
 
-    let fn_name = func_spec_name(env.arena, &env.interns, symbol, func_spec);
-
     let ret_type = basic_type_from_layout(env, layout_interner, proc.ret_layout);
     let mut arg_basic_types = Vec::with_capacity_in(args.len(), arena);
 
@@ -4984,17 +5184,16 @@ fn build_proc_header<'a, 'ctx>(
     let roc_return = RocReturn::from_layout(env, layout_interner, proc.ret_layout);
     let fn_spec = FunctionSpec::fastcc(env, roc_return, ret_type, arg_basic_types);
 
-    let fn_val = add_func(
-        env.context,
-        env.module,
-        fn_name.as_str(),
-        fn_spec,
-        Linkage::Internal,
-    );
+    let fn_val = add_func(env.context, env.module, fn_name, fn_spec, Linkage::Internal);
 
-    let subprogram = env.new_subprogram(&fn_name);
+    let fn_name = env.arena.alloc_str(fn_name);
+    env.func_spec_names.borrow_mut().insert(func_spec, fn_name);
+
+    let subprogram = env.new_subprogram(fn_name);
     fn_val.set_subprogram(subprogram);
 
+    profile::set_proc_heat(env, fn_val);
+
     if env.exposed_to_host.contains(&symbol) {
         let arguments = Vec::from_iter_in(proc.args.iter().map(|(layout, _)| *layout), env.arena);
         expose_function_to_host(
@@ -5339,21 +5538,50 @@ fn build_proc<'a, 'ctx>(
 
     debug_info_init!(env, fn_val);
 
+    stack_probe::check_stack(env, fn_val, proc.name.name());
+
+    profile::count_proc_call(env, fn_val);
+
     // Add args to scope
     for (arg_val, (layout, arg_symbol)) in fn_val.get_param_iter().zip(args) {
         arg_val.set_name(arg_symbol.as_str(&env.interns));
         scope.insert(*arg_symbol, (*layout, arg_val));
     }
 
-    let body = build_exp_stmt(
-        env,
-        layout_interner,
-        layout_ids,
-        func_spec_solutions,
-        &mut scope,
-        fn_val,
-        &proc.body,
-    );
+    let proc_layout = ProcLayout {
+        arguments: &[],
+        result: proc.ret_layout,
+        niche: proc.name.niche(),
+    };
+
+    let body = match env.static_constants.get(&(proc.name.name(), proc_layout)) {
+        Some(static_value) if args.is_empty() => {
+            // This constant was evaluated at compile time, so just return the static data.
+            let symbol = proc.name.name();
+            let value =
+                build_static_value(env, layout_interner, fn_val, proc.ret_layout, static_value);
+            scope.insert(symbol, (proc.ret_layout, value));
+
+            build_exp_stmt(
+                env,
+                layout_interner,
+                layout_ids,
+                func_spec_solutions,
+                &mut scope,
+                fn_val,
+                &roc_mono::ir::Stmt::Ret(symbol),
+            )
+        }
+        _ => build_exp_stmt(
+            env,
+            layout_interner,
+            layout_ids,
+            func_spec_solutions,
+            &mut scope,
+            fn_val,
+            &proc.body,
+        ),
+    };
 
     // only add a return if codegen did not already add one
     if let Some(block) = builder.get_insert_block() {
@@ -5381,8 +5609,13 @@ pub(crate) fn function_value_by_func_spec<'a, 'ctx>(
     niche: Niche<'a>,
     result: InLayout<'a>,
 ) -> FunctionValue<'ctx> {
-    let fn_name = func_spec_name(env.arena, &env.interns, symbol, func_spec);
-    let fn_name = fn_name.as_str();
+    // A specialization without a header has no name, which is reported as an unknown function
+    let fn_name = env
+        .func_spec_names
+        .borrow()
+        .get(&func_spec)
+        .copied()
+        .unwrap_or_default();
 
     function_value_by_name_help(env, arguments, niche, result, symbol, fn_name)
 }
@@ -6121,7 +6354,10 @@ fn define_global_str_literal<'ctx>(
             // mimic the `global_string` function; we cannot use it directly because it assumes
             // strings are NULL-terminated, which means we can't store the refcount (which is 8
             // NULL bytes)
-            global.set_constant(true);
+            //
+            // It is not constant though: incrementing and decrementing the refcount of static
+            // data writes it back unchanged, which would fault in read-only memory.
+            global.set_constant(false);
             global.set_alignment(env.target_info.ptr_width() as u32);
             global.set_unnamed_addr(true);
             global.set_linkage(inkwell::module::Linkage::Private);
@@ -6131,6 +6367,156 @@ fn define_global_str_literal<'ctx>(
     }
 }
 
+/// Build the value of a top-level constant which was evaluated at compile time. Its heap data
+/// lives in private globals, with a refcount of `REFCOUNT_MAX`, so it is never freed and never
+/// updated in place.
+fn build_static_value<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    parent: FunctionValue<'ctx>,
+    layout: InLayout<'a>,
+    value: &StaticValue<'a>,
+) -> BasicValueEnum<'ctx> {
+    let constant = const_static_value(env, layout_interner, parent, layout, value);
+
+    use_roc_value(env, layout_interner, layout, constant, "static_value")
+}
+
+fn const_static_value<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    parent: FunctionValue<'ctx>,
+    layout: InLayout<'a>,
+    value: &StaticValue<'a>,
+) -> BasicValueEnum<'ctx> {
+    match value {
+        StaticValue::Literal(roc_mono::ir::Literal::Str(string)) => const_str_value(env, string),
+        StaticValue::Literal(literal) => {
+            build_exp_literal(env, layout_interner, parent, layout, literal)
+        }
+        StaticValue::Struct(fields) => {
+            let field_layouts = match layout_interner.get(layout).repr {
+                LayoutRepr::Struct { field_layouts } => field_layouts,
+                other => unreachable!("static struct with a non-struct layout: {:?}", other),
+            };
+
+            let mut field_values = Vec::with_capacity_in(fields.len(), env.arena);
+
+            for (field, field_layout) in fields.iter().zip(field_layouts.iter()) {
+                let field_value =
+                    const_static_value(env, layout_interner, parent, *field_layout, field);
+
+                field_values.push(field_value);
+            }
+
+            env.context
+                .const_struct(field_values.into_bump_slice(), false)
+                .into()
+        }
+        StaticValue::List(elems) => {
+            let element_layout = match layout_interner.get(layout).repr {
+                LayoutRepr::Builtin(Builtin::List(element_layout)) => element_layout,
+                other => unreachable!("static list with a non-list layout: {:?}", other),
+            };
+
+            if elems.is_empty() {
+                return zig_list_type(env).const_zero().into();
+            }
+
+            let mut elem_values = Vec::with_capacity_in(elems.len(), env.arena);
+
+            for elem in elems.iter() {
+                let elem_value =
+                    const_static_value(env, layout_interner, parent, element_layout, elem);
+
+                elem_values.push(elem_value);
+            }
+
+            // The elements are preceded by the refcount, padded so that the elements are aligned.
+            let ptr_width = env.target_info.ptr_width() as u32;
+            let alignment = layout_interner
+                .alignment_bytes(element_layout)
+                .max(ptr_width);
+            let refcount_max = env.ptr_int().const_int(REFCOUNT_MAX as u64, false);
+            let refcount_words = vec![refcount_max; (alignment / ptr_width) as usize];
+
+            // A struct whose fields all have the same type is laid out just like an array
+            let elements = env
+                .context
+                .const_struct(elem_values.into_bump_slice(), false);
+            let data = env.context.const_struct(
+                &[
+                    env.ptr_int().const_array(&refcount_words).into(),
+                    elements.into(),
+                ],
+                false,
+            );
+
+            // use None for the address space (e.g. Const does not work)
+            let global = env
+                .module
+                .add_global(data.get_type(), None, "roc__static_list");
+
+            // Not constant, because refcount updates write the refcount back unchanged
+            global.set_constant(false);
+            global.set_alignment(alignment);
+            global.set_unnamed_addr(true);
+            global.set_linkage(inkwell::module::Linkage::Private);
+            global.set_initializer(&data);
+
+            let elements_ptr = env
+                .builder
+                .new_build_struct_gep(
+                    data.get_type(),
+                    global.as_pointer_value(),
+                    1,
+                    "static_list_elements",
+                )
+                .unwrap();
+            let elements_ptr = env.builder.build_pointer_cast(
+                elements_ptr,
+                env.context.i8_type().ptr_type(AddressSpace::default()),
+                "to_opaque",
+            );
+
+            let len = env.ptr_int().const_int(elems.len() as u64, false);
+
+            zig_list_type(env)
+                .const_named_struct(&[elements_ptr.into(), len.into(), len.into()])
+                .into()
+        }
+    }
+}
+
+fn const_str_value<'ctx>(env: &Env<'_, 'ctx, '_>, string: &str) -> BasicValueEnum<'ctx> {
+    let str_type = zig_str_type(env);
+
+    if string.len() <= env.small_str_length_byte() {
+        // Small strings store their bytes inline, followed by the length
+        let mut words = small_str_words(env.target_info, string)
+            .into_iter()
+            .map(|word| env.ptr_int().const_int(word, false));
+
+        let ptr = words
+            .next()
+            .unwrap()
+            .const_to_pointer(env.context.i8_type().ptr_type(AddressSpace::default()));
+        let len = words.next().unwrap();
+        let cap = words.next().unwrap();
+
+        str_type
+            .const_named_struct(&[ptr.into(), len.into(), cap.into()])
+            .into()
+    } else {
+        let ptr = define_global_str_literal_ptr(env, string);
+        let len = env.ptr_int().const_int(string.len() as u64, false);
+
+        str_type
+            .const_named_struct(&[ptr.into(), len.into(), len.into()])
+            .into()
+    }
+}
+
 pub(crate) fn throw_internal_exception<'ctx>(
     env: &Env<'_, 'ctx, '_>,
     parent: FunctionValue<'ctx>,
@@ -6203,3 +6589,52 @@ pub fn add_func<'ctx>(
 
     fn_val
 }
+
+#[cfg(test)]
+mod test {
+    use super::{small_str_length_byte, small_str_words};
+    use roc_target::{Architecture, OperatingSystem, TargetInfo};
+
+    fn target_info(architecture: Architecture) -> TargetInfo {
+        TargetInfo {
+            architecture,
+            operating_system: OperatingSystem::Unix,
+        }
+    }
+
+    #[test]
+    fn small_str_on_little_endian_targets() {
+        let x86_64 = target_info(Architecture::X86_64);
+
+        assert_eq!(small_str_length_byte(x86_64), 23);
+        assert_eq!(
+            small_str_words(x86_64, "hello"),
+            [0x0000_006f_6c6c_6568, 0, 0x8500_0000_0000_0000]
+        );
+
+        let wasm32 = target_info(Architecture::Wasm32);
+
+        assert_eq!(small_str_length_byte(wasm32), 11);
+        assert_eq!(small_str_words(wasm32, "hi"), [0x0000_6968, 0, 0x8200_0000]);
+    }
+
+    #[test]
+    fn small_str_on_big_endian_targets() {
+        let s390x = target_info(Architecture::S390x);
+
+        // the length goes in the first byte of the capacity, which is its most significant one
+        assert_eq!(small_str_length_byte(s390x), 16);
+        assert_eq!(
+            small_str_words(s390x, "hello"),
+            [0x6865_6c6c_6f00_0000, 0, 0x8500_0000_0000_0000]
+        );
+        assert_eq!(
+            small_str_words(s390x, "abcdefghijklmnop"),
+            [
+                0x6162_6364_6566_6768,
+                0x696a_6b6c_6d6e_6f70,
+                0x9000_0000_0000_0000
+            ]
+        );
+    }
+}
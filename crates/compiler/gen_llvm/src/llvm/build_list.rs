@@ -1,4 +1,4 @@
-use crate::llvm::bitcode::build_dec_wrapper;
+use crate::llvm::bitcode::{build_dec_wrapper, build_inc_n_wrapper};
 use crate::llvm::build::{
     allocate_with_refcount_help, cast_basic_basic, Env, RocFunctionCall, Scope,
 };
@@ -216,6 +216,28 @@ pub(crate) fn list_append_unsafe<'a, 'ctx>(
     )
 }
 
+/// List.repeat : elem, Nat -> List elem
+pub(crate) fn list_repeat<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    element: BasicValueEnum<'ctx>,
+    count: IntValue<'ctx>,
+    element_layout: InLayout<'a>,
+) -> BasicValueEnum<'ctx> {
+    call_list_bitcode_fn(
+        env,
+        &[],
+        &[
+            pass_element_as_opaque(env, layout_interner, element, element_layout),
+            count.into(),
+            env.alignment_intvalue(layout_interner, element_layout),
+            layout_width(env, layout_interner, element_layout),
+        ],
+        BitcodeReturns::List,
+        bitcode::LIST_REPEAT,
+    )
+}
+
 /// List.prepend : List elem, elem -> List elem
 pub(crate) fn list_prepend<'a, 'ctx>(
     env: &Env<'a, 'ctx, '_>,
@@ -285,6 +307,52 @@ pub(crate) fn list_sublist<'a, 'ctx>(
     )
 }
 
+/// List.chunksOf : List elem, Nat -> List (List elem)
+pub(crate) fn list_chunks_of<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    layout_ids: &mut LayoutIds<'a>,
+    original_wrapper: StructValue<'ctx>,
+    chunk_size: IntValue<'ctx>,
+    element_layout: InLayout<'a>,
+) -> BasicValueEnum<'ctx> {
+    let inc_element_fn = build_inc_n_wrapper(env, layout_interner, layout_ids, element_layout);
+    call_list_bitcode_fn_1(
+        env,
+        original_wrapper,
+        &[
+            env.alignment_intvalue(layout_interner, element_layout),
+            layout_width(env, layout_interner, element_layout),
+            chunk_size.into(),
+            inc_element_fn.as_global_value().as_pointer_value().into(),
+        ],
+        bitcode::LIST_CHUNKS_OF,
+    )
+}
+
+/// List.windows : List elem, Nat -> List (List elem)
+pub(crate) fn list_windows<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    layout_ids: &mut LayoutIds<'a>,
+    original_wrapper: StructValue<'ctx>,
+    window_size: IntValue<'ctx>,
+    element_layout: InLayout<'a>,
+) -> BasicValueEnum<'ctx> {
+    let inc_element_fn = build_inc_n_wrapper(env, layout_interner, layout_ids, element_layout);
+    call_list_bitcode_fn_1(
+        env,
+        original_wrapper,
+        &[
+            env.alignment_intvalue(layout_interner, element_layout),
+            layout_width(env, layout_interner, element_layout),
+            window_size.into(),
+            inc_element_fn.as_global_value().as_pointer_value().into(),
+        ],
+        bitcode::LIST_WINDOWS,
+    )
+}
+
 /// List.dropAt : List elem, Nat -> List elem
 pub(crate) fn list_drop_at<'a, 'ctx>(
     env: &Env<'a, 'ctx, '_>,
@@ -13,6 +13,7 @@ use roc_module::symbol::Symbol;
 use roc_mono::layout::{
     Builtin, InLayout, LayoutIds, LayoutInterner, LayoutRepr, STLayoutInterner, UnionLayout,
 };
+use roc_mono::mangle::{layout_hash, specialization_name};
 
 use super::build::{load_roc_value, use_roc_value, BuilderExt};
 use super::convert::argument_type_from_union_layout;
@@ -430,9 +431,8 @@ fn build_list_eq<'a, 'ctx>(
         } else {
             element_layout
         };
-    let fn_name = layout_ids
-        .get(symbol, &element_layout)
-        .to_symbol_string(symbol, &env.interns);
+    let layout_hash = layout_hash(layout_interner, &env.interns, element_layout);
+    let fn_name = specialization_name(&env.interns, symbol, layout_hash);
 
     let function = match env.module.get_function(fn_name.as_str()) {
         Some(function_value) => function_value,
@@ -640,9 +640,8 @@ fn build_struct_eq<'a, 'ctx>(
     let di_location = env.builder.get_current_debug_location().unwrap();
 
     let symbol = Symbol::GENERIC_EQ;
-    let fn_name = layout_ids
-        .get(symbol, &struct_layout)
-        .to_symbol_string(symbol, &env.interns);
+    let layout_hash = layout_hash(layout_interner, &env.interns, struct_layout);
+    let fn_name = specialization_name(&env.interns, symbol, layout_hash);
 
     let function = match env.module.get_function(fn_name.as_str()) {
         Some(function_value) => function_value,
@@ -825,9 +824,8 @@ fn build_tag_eq<'a, 'ctx>(
     let di_location = env.builder.get_current_debug_location().unwrap();
 
     let symbol = Symbol::GENERIC_EQ;
-    let fn_name = layout_ids
-        .get(symbol, &tag_layout)
-        .to_symbol_string(symbol, &env.interns);
+    let layout_hash = layout_hash(layout_interner, &env.interns, tag_layout);
+    let fn_name = specialization_name(&env.interns, symbol, layout_hash);
 
     let function = match env.module.get_function(fn_name.as_str()) {
         Some(function_value) => function_value,
@@ -1329,9 +1327,8 @@ fn build_box_eq<'a, 'ctx>(
     let di_location = env.builder.get_current_debug_location().unwrap();
 
     let symbol = Symbol::GENERIC_EQ;
-    let fn_name = layout_ids
-        .get(symbol, &box_layout)
-        .to_symbol_string(symbol, &env.interns);
+    let layout_hash = layout_hash(layout_interner, &env.interns, box_layout);
+    let fn_name = specialization_name(&env.interns, symbol, layout_hash);
 
     let function = match env.module.get_function(fn_name.as_str()) {
         Some(function_value) => function_value,
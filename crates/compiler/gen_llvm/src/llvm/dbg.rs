@@ -0,0 +1,347 @@
+//! `dbg` in optimized builds, where there is no `roc` process around to render the value. The
+//! value is rendered at runtime instead, and passed to the host's
+//!
+//! ```text
+//! void roc_dbg(RocStr *loc, RocStr *msg)
+//! ```
+//!
+//! together with the location of the `dbg`, so that platforms can route it to their own logging.
+//! The builtins define a weak `roc_dbg` which writes to stderr, for hosts which don't.
+//!
+//! Layouts don't know the names of record fields and tags, so records are rendered like tuples,
+//! and tags by their id.
+
+use inkwell::module::Linkage;
+use inkwell::types::BasicType;
+use inkwell::values::{BasicValueEnum, FunctionValue, PointerValue};
+use inkwell::{AddressSpace, IntPredicate};
+use roc_builtins::bitcode::{self, IntWidth};
+use roc_module::symbol::Symbol;
+use roc_mono::layout::{
+    Builtin, InLayout, Layout, LayoutIds, LayoutInterner, LayoutRepr, STLayoutInterner,
+};
+use roc_target::PtrWidth;
+
+use super::bitcode::{call_str_bitcode_fn, BitcodeReturns};
+use super::build::{
+    add_func, build_string_literal, create_entry_block_alloca, get_tag_id, load_roc_value,
+    BuilderExt, Env, FunctionSpec, C_CALL_CONV,
+};
+use super::build_list::{incrementing_elem_loop, list_len, load_list_ptr};
+use super::convert::{basic_type_from_layout, zig_str_type};
+use super::lowlevel::dec_to_str;
+use super::refcounting::decrement_refcount_layout;
+
+pub(crate) fn call_roc_dbg<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    layout_ids: &mut LayoutIds<'a>,
+    parent: FunctionValue<'ctx>,
+    symbol: Symbol,
+    value: BasicValueEnum<'ctx>,
+    layout: InLayout<'a>,
+) {
+    let loc = match env.dbg_locations.get(&symbol) {
+        Some(loc) => *loc,
+        None => env.interns.module_name(symbol.module_id()).as_str(),
+    };
+
+    let loc_str = build_string_literal(env, parent, loc);
+
+    let msg = create_entry_block_alloca(env, parent, zig_str_type(env).into(), "dbg_msg");
+    let empty = build_string_literal(env, parent, "");
+    env.builder.build_store(msg, str_to_struct(env, empty));
+
+    push_value(env, layout_interner, layout_ids, parent, msg, value, layout);
+
+    let function = roc_dbg_function(env);
+
+    let call = env.builder.build_call(
+        function,
+        &[str_to_ptr(env, parent, loc_str).into(), msg.into()],
+        "call_roc_dbg",
+    );
+
+    call.set_call_convention(C_CALL_CONV);
+
+    let msg = str_value(env, msg);
+    decrement_refcount_layout(env, layout_interner, layout_ids, msg, Layout::STR);
+}
+
+fn roc_dbg_function<'ctx>(env: &Env<'_, 'ctx, '_>) -> FunctionValue<'ctx> {
+    // The builtins define a default on every target but wasm32
+    if let Some(function) = env.module.get_function("roc_dbg") {
+        return function;
+    }
+
+    let str_ptr_type = zig_str_type(env).ptr_type(AddressSpace::default());
+
+    let function_spec = FunctionSpec::cconv(
+        env,
+        super::build::CCReturn::Void,
+        None,
+        &[str_ptr_type.into(), str_ptr_type.into()],
+    );
+
+    add_func(
+        env.context,
+        env.module,
+        "roc_dbg",
+        function_spec,
+        Linkage::External,
+    )
+}
+
+/// Append the rendered `value` to the string stored in `acc`
+fn push_value<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    layout_ids: &mut LayoutIds<'a>,
+    parent: FunctionValue<'ctx>,
+    acc: PointerValue<'ctx>,
+    value: BasicValueEnum<'ctx>,
+    layout: InLayout<'a>,
+) {
+    match layout_interner.get(layout).repr {
+        LayoutRepr::Builtin(Builtin::Int(int_width)) => {
+            let piece = call_str_bitcode_fn(
+                env,
+                &[],
+                &[value],
+                BitcodeReturns::Str,
+                &bitcode::STR_FROM_INT[int_width],
+            );
+
+            push_owned_str(env, layout_interner, layout_ids, acc, piece);
+        }
+        LayoutRepr::Builtin(Builtin::Float(float_width)) => {
+            let piece = call_str_bitcode_fn(
+                env,
+                &[],
+                &[value],
+                BitcodeReturns::Str,
+                &bitcode::STR_FROM_FLOAT[float_width],
+            );
+
+            push_owned_str(env, layout_interner, layout_ids, acc, piece);
+        }
+        LayoutRepr::Builtin(Builtin::Decimal) => {
+            let piece = dec_to_str(env, value);
+
+            push_owned_str(env, layout_interner, layout_ids, acc, piece);
+        }
+        LayoutRepr::Builtin(Builtin::Bool) => {
+            let true_str = build_string_literal(env, parent, "Bool.true");
+            let false_str = build_string_literal(env, parent, "Bool.false");
+
+            let piece =
+                env.builder
+                    .build_select(value.into_int_value(), true_str, false_str, "bool_str");
+
+            push_str(env, acc, piece);
+        }
+        LayoutRepr::Builtin(Builtin::Str) => {
+            push_literal(env, parent, acc, "\"");
+            push_str(env, acc, value);
+            push_literal(env, parent, acc, "\"");
+        }
+        LayoutRepr::Builtin(Builtin::List(element_layout)) => {
+            let list = value.into_struct_value();
+            let len = list_len(env.builder, list);
+
+            let element_type = basic_type_from_layout(env, layout_interner, element_layout);
+            let ptr = load_list_ptr(
+                env.builder,
+                list,
+                element_type.ptr_type(AddressSpace::default()),
+            );
+
+            push_literal(env, parent, acc, "[");
+
+            incrementing_elem_loop(
+                env,
+                layout_interner,
+                parent,
+                element_layout,
+                ptr,
+                len,
+                "dbg_index",
+                |layout_interner, index, element| {
+                    let is_first = env.builder.build_int_compare(
+                        IntPredicate::EQ,
+                        index,
+                        env.ptr_int().const_zero(),
+                        "is_first",
+                    );
+
+                    let separator_block = env.context.append_basic_block(parent, "dbg_separator");
+                    let element_block = env.context.append_basic_block(parent, "dbg_element");
+
+                    env.builder
+                        .build_conditional_branch(is_first, element_block, separator_block);
+
+                    env.builder.position_at_end(separator_block);
+                    push_literal(env, parent, acc, ", ");
+                    env.builder.build_unconditional_branch(element_block);
+
+                    env.builder.position_at_end(element_block);
+                    push_value(
+                        env,
+                        layout_interner,
+                        layout_ids,
+                        parent,
+                        acc,
+                        element,
+                        element_layout,
+                    );
+                },
+            );
+
+            push_literal(env, parent, acc, "]");
+        }
+        LayoutRepr::Struct { field_layouts: [] } => {
+            push_literal(env, parent, acc, "{}");
+        }
+        LayoutRepr::Struct { field_layouts } => {
+            let struct_type =
+                basic_type_from_layout(env, layout_interner, layout).into_struct_type();
+
+            let ptr = if layout_interner.is_passed_by_reference(layout) {
+                value.into_pointer_value()
+            } else {
+                let ptr = create_entry_block_alloca(env, parent, struct_type.into(), "dbg_struct");
+                env.builder.build_store(ptr, value);
+
+                ptr
+            };
+
+            push_literal(env, parent, acc, "(");
+
+            for (index, field_layout) in field_layouts.iter().enumerate() {
+                if index > 0 {
+                    push_literal(env, parent, acc, ", ");
+                }
+
+                let field_ptr = env
+                    .builder
+                    .new_build_struct_gep(struct_type, ptr, index as u32, "dbg_field_ptr")
+                    .unwrap();
+
+                let field =
+                    load_roc_value(env, layout_interner, *field_layout, field_ptr, "dbg_field");
+
+                push_value(
+                    env,
+                    layout_interner,
+                    layout_ids,
+                    parent,
+                    acc,
+                    field,
+                    *field_layout,
+                );
+            }
+
+            push_literal(env, parent, acc, ")");
+        }
+        LayoutRepr::Union(union_layout) => {
+            let tag_id = get_tag_id(env, layout_interner, parent, &union_layout, value);
+            let tag_id =
+                env.builder
+                    .build_int_z_extend(tag_id, env.context.i64_type(), "tag_id_u64");
+
+            let piece = call_str_bitcode_fn(
+                env,
+                &[],
+                &[tag_id.into()],
+                BitcodeReturns::Str,
+                &bitcode::STR_FROM_INT[IntWidth::U64],
+            );
+
+            push_literal(env, parent, acc, "<tag #");
+            push_owned_str(env, layout_interner, layout_ids, acc, piece);
+            push_literal(env, parent, acc, ">");
+        }
+        LayoutRepr::Boxed(_) | LayoutRepr::RecursivePointer(_) => {
+            push_literal(env, parent, acc, "<box>");
+        }
+        LayoutRepr::LambdaSet(_) => {
+            push_literal(env, parent, acc, "<function>");
+        }
+    }
+}
+
+fn push_literal<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    parent: FunctionValue<'ctx>,
+    acc: PointerValue<'ctx>,
+    literal: &str,
+) {
+    let piece = build_string_literal(env, parent, literal);
+
+    push_str(env, acc, piece);
+}
+
+/// Append a string which was allocated just for rendering, and free it afterwards
+fn push_owned_str<'a, 'ctx>(
+    env: &Env<'a, 'ctx, '_>,
+    layout_interner: &mut STLayoutInterner<'a>,
+    layout_ids: &mut LayoutIds<'a>,
+    acc: PointerValue<'ctx>,
+    piece: BasicValueEnum<'ctx>,
+) {
+    push_str(env, acc, piece);
+
+    decrement_refcount_layout(env, layout_interner, layout_ids, piece, Layout::STR);
+}
+
+/// Append a borrowed string; `Str.concat` consumes its first argument, but not the second
+fn push_str<'ctx>(env: &Env<'_, 'ctx, '_>, acc: PointerValue<'ctx>, piece: BasicValueEnum<'ctx>) {
+    let result = call_str_bitcode_fn(
+        env,
+        &[str_value(env, acc), piece],
+        &[],
+        BitcodeReturns::Str,
+        bitcode::STR_CONCAT,
+    );
+
+    env.builder.build_store(acc, str_to_struct(env, result));
+}
+
+/// Load the string stored at `ptr` the way Roc passes strings around on this target
+fn str_value<'ctx>(env: &Env<'_, 'ctx, '_>, ptr: PointerValue<'ctx>) -> BasicValueEnum<'ctx> {
+    match env.target_info.ptr_width() {
+        PtrWidth::Bytes4 => env
+            .builder
+            .new_build_load(zig_str_type(env), ptr, "load_str"),
+        PtrWidth::Bytes8 => ptr.into(),
+    }
+}
+
+fn str_to_struct<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    value: BasicValueEnum<'ctx>,
+) -> BasicValueEnum<'ctx> {
+    match env.target_info.ptr_width() {
+        PtrWidth::Bytes4 => value,
+        PtrWidth::Bytes8 => {
+            env.builder
+                .new_build_load(zig_str_type(env), value.into_pointer_value(), "load_str")
+        }
+    }
+}
+
+fn str_to_ptr<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    parent: FunctionValue<'ctx>,
+    value: BasicValueEnum<'ctx>,
+) -> PointerValue<'ctx> {
+    match env.target_info.ptr_width() {
+        PtrWidth::Bytes4 => {
+            let ptr = create_entry_block_alloca(env, parent, zig_str_type(env).into(), "str_ptr");
+            env.builder.build_store(ptr, value);
+
+            ptr
+        }
+        PtrWidth::Bytes8 => value.into_pointer_value(),
+    }
+}
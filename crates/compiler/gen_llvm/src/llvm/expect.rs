@@ -15,6 +15,7 @@ use roc_mono::ir::LookupType;
 use roc_mono::layout::{
     Builtin, InLayout, LayoutIds, LayoutInterner, LayoutRepr, STLayoutInterner, UnionLayout,
 };
+use roc_mono::mangle::{layout_hash, specialization_name};
 use roc_region::all::Region;
 
 use super::build::BuilderExt;
@@ -477,8 +478,8 @@ fn build_clone_tag<'a, 'ctx>(
     union_layout: UnionLayout<'a>,
 ) -> IntValue<'ctx> {
     let layout = layout_interner.insert_no_semantic(LayoutRepr::Union(union_layout));
-    let layout_id = layout_ids.get(Symbol::CLONE, &layout);
-    let fn_name = layout_id.to_symbol_string(Symbol::CLONE, &env.interns);
+    let layout_hash = layout_hash(layout_interner, &env.interns, layout);
+    let fn_name = specialization_name(&env.interns, Symbol::CLONE, layout_hash);
 
     let function = match env.module.get_function(fn_name.as_str()) {
         Some(function_value) => function_value,
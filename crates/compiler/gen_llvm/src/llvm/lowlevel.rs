@@ -28,13 +28,14 @@ use crate::llvm::{
     },
     build::{
         complex_bitcast_check_size, create_entry_block_alloca, function_value_by_func_spec,
-        load_roc_value, roc_function_call, tag_pointer_clear_tag_id, BuilderExt, RocReturn,
+        load_roc_value, roc_function_call, store_roc_value, tag_pointer_clear_tag_id, BuilderExt,
+        RocReturn,
     },
     build_list::{
-        list_append_unsafe, list_concat, list_drop_at, list_get_unsafe, list_len, list_map,
-        list_map2, list_map3, list_map4, list_prepend, list_release_excess_capacity,
-        list_replace_unsafe, list_reserve, list_sort_with, list_sublist, list_swap,
-        list_symbol_to_c_abi, list_with_capacity, pass_update_mode,
+        list_append_unsafe, list_chunks_of, list_concat, list_drop_at, list_get_unsafe, list_len,
+        list_map, list_map2, list_map3, list_map4, list_prepend, list_release_excess_capacity,
+        list_repeat, list_replace_unsafe, list_reserve, list_sort_with, list_sublist, list_swap,
+        list_symbol_to_c_abi, list_windows, list_with_capacity, pass_update_mode,
     },
     compare::{generic_eq, generic_neq},
     convert::{
@@ -701,6 +702,21 @@ pub(crate) fn run_low_level<'a, 'ctx>(
 
             list_append_unsafe(env, layout_interner, original_wrapper, elem, elem_layout)
         }
+        ListRepeat => {
+            // List.repeat : elem, Nat -> List elem
+            debug_assert_eq!(args.len(), 2);
+
+            let (elem, elem_layout) = load_symbol_and_layout(scope, &args[0]);
+            let count = load_symbol(scope, &args[1]);
+
+            list_repeat(
+                env,
+                layout_interner,
+                elem,
+                count.into_int_value(),
+                elem_layout,
+            )
+        }
         ListPrepend => {
             // List.prepend : List elem, elem -> List elem
             debug_assert_eq!(args.len(), 2);
@@ -777,6 +793,44 @@ pub(crate) fn run_low_level<'a, 'ctx>(
                 element_layout,
             )
         }
+        ListChunksOf => {
+            // List.chunksOf : List elem, Nat -> List (List elem)
+            debug_assert_eq!(args.len(), 2);
+
+            let (list, list_layout) = load_symbol_and_layout(scope, &args[0]);
+            let original_wrapper = list.into_struct_value();
+
+            let chunk_size = load_symbol(scope, &args[1]);
+
+            let element_layout = list_element_layout!(layout_interner, list_layout);
+            list_chunks_of(
+                env,
+                layout_interner,
+                layout_ids,
+                original_wrapper,
+                chunk_size.into_int_value(),
+                element_layout,
+            )
+        }
+        ListWindows => {
+            // List.windows : List elem, Nat -> List (List elem)
+            debug_assert_eq!(args.len(), 2);
+
+            let (list, list_layout) = load_symbol_and_layout(scope, &args[0]);
+            let original_wrapper = list.into_struct_value();
+
+            let window_size = load_symbol(scope, &args[1]);
+
+            let element_layout = list_element_layout!(layout_interner, list_layout);
+            list_windows(
+                env,
+                layout_interner,
+                layout_ids,
+                original_wrapper,
+                window_size.into_int_value(),
+                element_layout,
+            )
+        }
         ListDropAt => {
             // List.dropAt : List elem, Nat -> List elem
             debug_assert_eq!(args.len(), 2);
@@ -846,6 +900,54 @@ pub(crate) fn run_low_level<'a, 'ctx>(
                 bitcode::LIST_IS_UNIQUE,
             )
         }
+        CryptoSha256 | CryptoBlake3 => {
+            // Crypto.sha256 : List U8 -> List U8
+            // Crypto.blake3 : List U8 -> List U8
+            arguments!(list);
+
+            let fn_name = match op {
+                CryptoSha256 => bitcode::CRYPTO_SHA256,
+                _ => bitcode::CRYPTO_BLAKE3,
+            };
+
+            call_list_bitcode_fn(
+                env,
+                &[list.into_struct_value()],
+                &[],
+                BitcodeReturns::List,
+                fn_name,
+            )
+        }
+        CryptoConstantTimeEq => {
+            // Crypto.constantTimeEq : List U8, List U8 -> Bool
+            arguments!(list1, list2);
+
+            call_list_bitcode_fn(
+                env,
+                &[list1.into_struct_value(), list2.into_struct_value()],
+                &[],
+                BitcodeReturns::Basic,
+                bitcode::CRYPTO_CONSTANT_TIME_EQ,
+            )
+        }
+        RandomU64s | RandomBytes => {
+            // Random.u64sLowlevel : U128, U128, Nat -> List U64
+            // Random.bytesLowlevel : U128, U128, Nat -> List U8
+            arguments!(state, increment, count);
+
+            let fn_name = match op {
+                RandomU64s => bitcode::RANDOM_U64S,
+                _ => bitcode::RANDOM_BYTES,
+            };
+
+            call_list_bitcode_fn(
+                env,
+                &[],
+                &[state, increment, count],
+                BitcodeReturns::List,
+                fn_name,
+            )
+        }
         NumToStr => {
             // Num.toStr : Num a -> Str
             arguments_with_layouts!((num, num_layout));
@@ -1256,11 +1358,25 @@ pub(crate) fn run_low_level<'a, 'ctx>(
             unreachable!("The {:?} operation is turned into mono Expr", op)
         }
 
-        PtrCast | PtrWrite | RefCountIncRcPtr | RefCountDecRcPtr | RefCountIncDataPtr
-        | RefCountDecDataPtr => {
+        PtrCast | RefCountIncRcPtr | RefCountDecRcPtr | RefCountIncDataPtr | RefCountDecDataPtr => {
             unreachable!("Not used in LLVM backend: {:?}", op);
         }
 
+        PtrWrite => {
+            // Overwrite the contents of a (unique) box, and return the box
+            arguments_with_layouts!((ptr, _ptr_layout), (value, value_layout));
+
+            store_roc_value(
+                env,
+                layout_interner,
+                value_layout,
+                ptr.into_pointer_value(),
+                value,
+            );
+
+            ptr
+        }
+
         RefCountIsUnique => {
             arguments_with_layouts!((data_ptr, data_layout));
 
@@ -1618,6 +1734,7 @@ fn build_float_binop<'ctx>(
             struct_value.into()
         }
         NumAddWrap => unreachable!("wrapping addition is not defined on floats"),
+        NumAddSaturated => bd.build_float_add(lhs, rhs, "add_float").into(),
         NumSub => bd.build_float_sub(lhs, rhs, "sub_float").into(),
         NumSubChecked => {
             let context = env.context;
@@ -1647,6 +1764,7 @@ fn build_float_binop<'ctx>(
             struct_value.into()
         }
         NumSubWrap => unreachable!("wrapping subtraction is not defined on floats"),
+        NumSubSaturated => bd.build_float_sub(lhs, rhs, "sub_float").into(),
         NumMul => bd.build_float_mul(lhs, rhs, "mul_float").into(),
         NumMulSaturated => bd.build_float_mul(lhs, rhs, "mul_float").into(),
         NumMulChecked => {
@@ -1758,7 +1876,10 @@ fn dec_alloca<'ctx>(env: &Env<'_, 'ctx, '_>, value: IntValue<'ctx>) -> PointerVa
     alloca
 }
 
-fn dec_to_str<'ctx>(env: &Env<'_, 'ctx, '_>, dec: BasicValueEnum<'ctx>) -> BasicValueEnum<'ctx> {
+pub(crate) fn dec_to_str<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    dec: BasicValueEnum<'ctx>,
+) -> BasicValueEnum<'ctx> {
     use roc_target::OperatingSystem::*;
 
     let dec = dec.into_int_value();
@@ -1917,6 +2038,9 @@ fn build_dec_binop<'a, 'ctx>(
             rhs,
             "decimal multiplication overflowed",
         ),
+        NumAddSaturated => dec_binop_with_unchecked(env, bitcode::DEC_ADD_SATURATED, lhs, rhs),
+        NumSubSaturated => dec_binop_with_unchecked(env, bitcode::DEC_SUB_SATURATED, lhs, rhs),
+        NumMulSaturated => dec_binop_with_unchecked(env, bitcode::DEC_MUL_SATURATED, lhs, rhs),
         NumDivFrac => dec_binop_with_unchecked(env, bitcode::DEC_DIV, lhs, rhs),
         _ => {
             unreachable!("Unrecognized int binary operation: {:?}", op);
@@ -4,8 +4,11 @@ pub mod build_list;
 pub mod build_str;
 pub mod compare;
 pub mod convert;
+mod dbg;
 mod expect;
 pub mod externs;
 mod intrinsics;
 mod lowlevel;
+pub mod profile;
 pub mod refcounting;
+mod stack_probe;
@@ -0,0 +1,363 @@
+//! Profile-guided code generation.
+//!
+//! A program built with [`ProfileMode::Generate`] counts how often each proc is called, and how
+//! often each branch of each switch is taken. When it exits, it writes those counts to a file.
+//!
+//! Building the program again with that file loaded into [`ProfileMode::Use`] groups the hot procs
+//! together, moves the procs which never ran to a cold section, and gives LLVM the branch weights
+//! of every switch it has counts for.
+use inkwell::attributes::{Attribute, AttributeLoc};
+use inkwell::module::Linkage;
+use inkwell::values::{
+    BasicMetadataValueEnum, FunctionValue, GlobalValue, InstructionValue, PointerValue,
+};
+use inkwell::AddressSpace;
+use roc_builtins::bitcode;
+use roc_collections::MutMap;
+use roc_module::symbol::Symbol;
+use std::path::Path;
+
+use crate::llvm::bitcode::call_void_bitcode_fn;
+use crate::llvm::build::Env;
+
+/// Prefix of the globals which hold the counters in a profiling build
+const COUNTER_PREFIX: &str = "roc_profile.";
+
+/// A proc counts as hot when it was called at least 1/HOT_PROC_FRACTION times as often as the
+/// most called proc.
+const HOT_PROC_FRACTION: u64 = 100;
+
+const HOT_SECTION: &str = ".text.hot.roc";
+const COLD_SECTION: &str = ".text.unlikely.roc";
+
+#[derive(Debug, Clone, Copy)]
+pub enum ProfileMode<'a> {
+    Off,
+    /// Count calls and branches, and write the counts to this (absolute) path on exit
+    Generate {
+        output_path: &'a Path,
+    },
+    /// Optimize using the counts of an earlier profiling run
+    Use(&'a Profile),
+}
+
+/// The counts written by a profiling build.
+///
+/// The file has one line per proc or switch: its name, followed by its counts, all separated by
+/// tabs. Procs have a single count, switches have one count per branch, with the default last.
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    counts: MutMap<String, Vec<u64>>,
+    max_proc_count: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Heat {
+    Hot,
+    Cold,
+    Unknown,
+}
+
+impl Profile {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut profile = Profile::default();
+
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.split('\t');
+            let name = parts.next().unwrap_or_default();
+
+            let counts = parts
+                .map(|count| count.trim().parse::<u64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| format!("line {} has a count which is not a number", index + 1))?;
+
+            if counts.is_empty() {
+                return Err(format!("line {} has no counts", index + 1));
+            }
+
+            if !is_switch_name(name) {
+                profile.max_proc_count = profile.max_proc_count.max(counts[0]);
+            }
+
+            profile.counts.insert(name.to_string(), counts);
+        }
+
+        Ok(profile)
+    }
+
+    fn heat(&self, fn_name: &str) -> Heat {
+        match self.counts.get(fn_name) {
+            None => Heat::Unknown,
+            Some(counts) if counts[0] == 0 => Heat::Cold,
+            Some(counts) if counts[0].saturating_mul(HOT_PROC_FRACTION) >= self.max_proc_count => {
+                Heat::Hot
+            }
+            Some(_) => Heat::Unknown,
+        }
+    }
+}
+
+fn switch_name(parent: FunctionValue<'_>, cond_symbol: Symbol) -> String {
+    format!(
+        "{}#switch{}",
+        parent.get_name().to_str().unwrap(),
+        cond_symbol.ident_id().index()
+    )
+}
+
+fn is_switch_name(name: &str) -> bool {
+    name.contains("#switch")
+}
+
+/// Get the counters with this name, creating them on first use.
+///
+/// Two switches on the same symbol within one proc share their counters. That makes their
+/// branch weights a bit less precise, but never wrong.
+fn counters<'ctx>(env: &Env<'_, 'ctx, '_>, name: &str, len: u32) -> GlobalValue<'ctx> {
+    let global_name = format!("{}{}", COUNTER_PREFIX, name);
+
+    match env.module.get_global(&global_name) {
+        Some(global) => global,
+        None => {
+            let typ = env.context.i64_type().array_type(len);
+            let global = env.module.add_global(typ, None, &global_name);
+            global.set_initializer(&typ.const_zero());
+            global.set_linkage(Linkage::Private);
+
+            global
+        }
+    }
+}
+
+/// Increment a counter at the current position of the builder
+fn increment<'ctx>(env: &Env<'_, 'ctx, '_>, counters: GlobalValue<'ctx>, index: u32) {
+    let builder = env.builder;
+    let i64_type = env.context.i64_type();
+
+    let ptr = unsafe {
+        builder.build_in_bounds_gep(
+            counters.as_pointer_value(),
+            &[
+                i64_type.const_zero(),
+                i64_type.const_int(index as u64, false),
+            ],
+            "profile_counter",
+        )
+    };
+
+    let count = builder.build_load(ptr, "count").into_int_value();
+    let count = builder.build_int_add(count, i64_type.const_int(1, false), "count_plus_one");
+    builder.build_store(ptr, count);
+}
+
+/// Count a call of the proc whose entry block the builder is positioned in
+pub(crate) fn count_proc_call<'ctx>(env: &Env<'_, 'ctx, '_>, fn_val: FunctionValue<'ctx>) {
+    if let ProfileMode::Generate { .. } = env.profile {
+        let name = fn_val.get_name().to_str().unwrap();
+        increment(env, counters(env, name, 1), 0);
+    }
+}
+
+/// Count that branch `index` (out of `branch_count`, including the default) of a switch was taken
+pub(crate) fn count_branch<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    parent: FunctionValue<'ctx>,
+    cond_symbol: Symbol,
+    branch_count: usize,
+    index: usize,
+) {
+    if let ProfileMode::Generate { .. } = env.profile {
+        let name = switch_name(parent, cond_symbol);
+        increment(env, counters(env, &name, branch_count as u32), index as u32);
+    }
+}
+
+/// Attach the profiled branch weights to the terminator of a switch.
+///
+/// The `order` gives, for each successor of the terminator, which branch of the switch it is.
+pub(crate) fn add_branch_weights<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    parent: FunctionValue<'ctx>,
+    cond_symbol: Symbol,
+    terminator: InstructionValue<'ctx>,
+    order: &[usize],
+) {
+    if let ProfileMode::Use(profile) = env.profile {
+        let counts = match profile.counts.get(&switch_name(parent, cond_symbol)) {
+            Some(counts) if order.iter().all(|index| *index < counts.len()) => counts,
+            _ => return,
+        };
+
+        // branch weights are 32-bit; scale the counts down if they don't fit
+        let max = order.iter().map(|index| counts[*index]).max().unwrap_or(0);
+        let scale = (max / u32::MAX as u64) + 1;
+
+        let weights = order.iter().map(|index| counts[*index] / scale);
+
+        set_branch_weights(env, terminator, weights);
+    }
+}
+
+/// Mark the else branch of a conditional branch as unlikely. This is used for the failure
+/// branches of expects, so that their code does not get in the way of the happy path.
+pub(crate) fn mark_else_unlikely<'ctx>(env: &Env<'_, 'ctx, '_>, branch: InstructionValue<'ctx>) {
+    set_branch_weights(env, branch, [2000, 1].into_iter());
+}
+
+fn set_branch_weights<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    terminator: InstructionValue<'ctx>,
+    weights: impl Iterator<Item = u64>,
+) {
+    let i32_type = env.context.i32_type();
+
+    let mut values: Vec<BasicMetadataValueEnum> =
+        vec![env.context.metadata_string("branch_weights").into()];
+    values.extend(weights.map(|weight| i32_type.const_int(weight, false).into()));
+
+    let kind_id = env.context.get_kind_id("prof");
+    let metadata = env.context.metadata_node(&values);
+
+    terminator.set_metadata(metadata, kind_id).unwrap();
+}
+
+/// Mark a proc as hot or cold according to the profile, and group it with the other procs of the
+/// same heat.
+pub(crate) fn set_proc_heat<'ctx>(env: &Env<'_, 'ctx, '_>, fn_val: FunctionValue<'ctx>) {
+    if let ProfileMode::Use(profile) = env.profile {
+        let (attribute, section) = match profile.heat(fn_val.get_name().to_str().unwrap()) {
+            Heat::Hot => ("hot", HOT_SECTION),
+            Heat::Cold => ("cold", COLD_SECTION),
+            Heat::Unknown => return,
+        };
+
+        let kind_id = Attribute::get_named_enum_kind_id(attribute);
+        debug_assert!(kind_id > 0);
+        let enum_attr = env.context.create_enum_attribute(kind_id, 0);
+        fn_val.add_attribute(AttributeLoc::Function, enum_attr);
+
+        // Sections names are object format specific; only ELF gets grouped this way.
+        let triple = env.module.get_triple();
+        if triple.as_str().to_string_lossy().contains("linux") {
+            fn_val.as_global_value().set_section(section);
+        }
+    }
+}
+
+/// Add a destructor which writes all counters to the profile file when the program exits
+pub(crate) fn write_profile_at_exit(env: &Env<'_, '_, '_>) {
+    let output_path = match env.profile {
+        ProfileMode::Generate { output_path } => output_path,
+        _ => return,
+    };
+
+    let context = env.context;
+    let module = env.module;
+    let i8_ptr = context.i8_type().ptr_type(AddressSpace::default());
+    let i64_ptr = context.i64_type().ptr_type(AddressSpace::default());
+
+    // must match `ProfileEntry` in profile.zig
+    let entry_type = context.struct_type(
+        &[i8_ptr.into(), i64_ptr.into(), env.ptr_int().into()],
+        false,
+    );
+
+    let mut entries = Vec::new();
+
+    let mut global = module.get_first_global();
+    while let Some(counters) = global {
+        global = counters.get_next_global();
+
+        let global_name = counters.get_name().to_str().unwrap();
+        let name = match global_name.strip_prefix(COUNTER_PREFIX) {
+            Some(name) => name,
+            None => continue,
+        };
+
+        let len = counters
+            .as_pointer_value()
+            .get_type()
+            .get_element_type()
+            .into_array_type()
+            .len();
+
+        let name_ptr = const_c_string(env, name, "roc_profile_name");
+        let counts_ptr = counters.as_pointer_value().const_cast(i64_ptr);
+
+        entries.push(entry_type.const_named_struct(&[
+            name_ptr.into(),
+            counts_ptr.into(),
+            env.ptr_int().const_int(len as u64, false).into(),
+        ]));
+    }
+
+    let table_type = entry_type.array_type(entries.len() as u32);
+    let table = module.add_global(table_type, None, "roc_profile_table");
+    table.set_initializer(&entry_type.const_array(&entries));
+    table.set_linkage(Linkage::Private);
+    table.set_constant(true);
+
+    // void roc_profile_write() { writeProfile(path, table, len) }
+    let write_fn = module.add_function(
+        "roc_profile_write",
+        context.void_type().fn_type(&[], false),
+        Some(Linkage::Internal),
+    );
+
+    let builder = env.builder;
+    let entry = context.append_basic_block(write_fn, "entry");
+    builder.position_at_end(entry);
+
+    call_void_bitcode_fn(
+        env,
+        &[
+            const_c_string(env, &output_path.to_string_lossy(), "roc_profile_path").into(),
+            table
+                .as_pointer_value()
+                .const_cast(entry_type.ptr_type(AddressSpace::default()))
+                .into(),
+            env.ptr_int().const_int(entries.len() as u64, false).into(),
+        ],
+        bitcode::UTILS_WRITE_PROFILE,
+    );
+
+    builder.build_return(None);
+
+    // Register the function as a destructor, so it runs after the host's main returns
+    let dtor_fn_type = context.void_type().fn_type(&[], false);
+    let dtor_type = context.struct_type(
+        &[
+            context.i32_type().into(),
+            dtor_fn_type.ptr_type(AddressSpace::default()).into(),
+            i8_ptr.into(),
+        ],
+        false,
+    );
+
+    let dtor = dtor_type.const_named_struct(&[
+        context.i32_type().const_int(65535, false).into(),
+        write_fn.as_global_value().as_pointer_value().into(),
+        i8_ptr.const_null().into(),
+    ]);
+
+    let dtors = module.add_global(dtor_type.array_type(1), None, "llvm.global_dtors");
+    dtors.set_initializer(&dtor_type.const_array(&[dtor]));
+    dtors.set_linkage(Linkage::Appending);
+}
+
+fn const_c_string<'ctx>(env: &Env<'_, 'ctx, '_>, string: &str, name: &str) -> PointerValue<'ctx> {
+    let bytes = env.context.const_string(string.as_bytes(), true);
+
+    let global = env.module.add_global(bytes.get_type(), None, name);
+    global.set_initializer(&bytes);
+    global.set_constant(true);
+    global.set_linkage(Linkage::Private);
+
+    global
+        .as_pointer_value()
+        .const_cast(env.context.i8_type().ptr_type(AddressSpace::default()))
+}
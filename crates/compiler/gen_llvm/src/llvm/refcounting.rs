@@ -16,15 +16,164 @@ use inkwell::module::Linkage;
 use inkwell::types::{AnyTypeEnum, BasicMetadataTypeEnum, BasicType, BasicTypeEnum};
 use inkwell::values::{BasicValueEnum, FunctionValue, IntValue, PointerValue};
 use inkwell::{AddressSpace, IntPredicate};
+use roc_collections::all::{MutMap, MutSet};
 use roc_module::symbol::Interns;
 use roc_module::symbol::Symbol;
+use roc_mono::ir::{HostExposedLayouts, Proc, ProcLayout};
 use roc_mono::layout::{
     Builtin, InLayout, LayoutIds, LayoutInterner, LayoutRepr, STLayoutInterner, UnionLayout,
 };
+use roc_mono::mangle::{layout_hash, specialization_name};
 
 use super::build::{cast_if_necessary_for_opaque_recursive_pointers, load_roc_value, FunctionSpec};
 use super::convert::{argument_type_from_layout, argument_type_from_union_layout};
 
+/// Whether refcounts are updated using atomic operations, which makes it safe
+/// for the host to share Roc values between threads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AtomicRefcounts {
+    /// Every refcount update is non-atomic; values must stay on one thread
+    #[default]
+    Never,
+    /// Every refcount update is atomic
+    Always,
+    /// Values that can reach the host use atomic refcount updates. Values that
+    /// provably stay inside of Roc code keep using the (faster) non-atomic ones.
+    Inferred,
+}
+
+pub enum RefcountMode<'a> {
+    NonAtomic,
+    Atomic,
+    Inferred {
+        /// The refcounted layouts which are reachable from a host-exposed value
+        shared: std::vec::Vec<InLayout<'a>>,
+    },
+}
+
+impl<'a> RefcountMode<'a> {
+    pub fn new(
+        atomic_refcounts: AtomicRefcounts,
+        layout_interner: &STLayoutInterner<'a>,
+        procedures: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+        exposed_to_host: &MutSet<Symbol>,
+    ) -> Self {
+        match atomic_refcounts {
+            AtomicRefcounts::Never => RefcountMode::NonAtomic,
+            AtomicRefcounts::Always => RefcountMode::Atomic,
+            AtomicRefcounts::Inferred => RefcountMode::Inferred {
+                shared: shared_layouts(layout_interner, procedures, exposed_to_host),
+            },
+        }
+    }
+
+    /// Does the builtins' own refcounting (e.g. for list elements) need to be atomic?
+    fn any_atomic(&self) -> bool {
+        !matches!(self, RefcountMode::NonAtomic)
+    }
+
+    fn is_atomic(&self, layout_interner: &STLayoutInterner<'a>, layout: InLayout<'a>) -> bool {
+        match self {
+            RefcountMode::NonAtomic => false,
+            RefcountMode::Atomic => true,
+            RefcountMode::Inferred { shared } => {
+                let layout = layout_interner.chase_recursive_in(layout);
+
+                shared
+                    .iter()
+                    .any(|shared| layout_interner.equiv(*shared, layout))
+            }
+        }
+    }
+}
+
+/// Collect the refcounted layouts that values passed to or from the host can contain.
+/// Only these can ever be observed by more than one thread.
+fn shared_layouts<'a>(
+    layout_interner: &STLayoutInterner<'a>,
+    procedures: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    exposed_to_host: &MutSet<Symbol>,
+) -> std::vec::Vec<InLayout<'a>> {
+    let mut stack = std::vec::Vec::new();
+
+    for ((symbol, proc_layout), proc) in procedures {
+        if exposed_to_host.contains(symbol) {
+            stack.extend(proc_layout.arguments.iter().copied());
+            stack.push(proc_layout.result);
+        }
+
+        if let HostExposedLayouts::HostExposed { rigids, aliases } = &proc.host_exposed_layouts {
+            stack.extend(rigids.values().copied());
+
+            for lambda_set in aliases.values() {
+                stack.extend(lambda_set.proc_layout.arguments.iter().copied());
+                stack.push(lambda_set.proc_layout.result);
+            }
+        }
+    }
+
+    let mut visited = MutSet::default();
+    let mut shared = std::vec::Vec::new();
+
+    while let Some(layout) = stack.pop() {
+        let layout = layout_interner.chase_recursive_in(layout);
+
+        if !visited.insert(layout) || !layout_interner.contains_refcounted(layout) {
+            continue;
+        }
+
+        if layout_interner.is_refcounted(layout) {
+            shared.push(layout);
+        }
+
+        match layout_interner.get(layout).repr {
+            LayoutRepr::Builtin(Builtin::List(element_layout)) => stack.push(element_layout),
+            LayoutRepr::Builtin(_) => {}
+            LayoutRepr::Struct { field_layouts } => stack.extend(field_layouts.iter().copied()),
+            LayoutRepr::Boxed(inner_layout) => stack.push(inner_layout),
+            LayoutRepr::Union(union_layout) => match union_layout {
+                UnionLayout::NonRecursive(tags) | UnionLayout::Recursive(tags) => {
+                    stack.extend(tags.iter().flat_map(|fields| fields.iter().copied()))
+                }
+                UnionLayout::NullableWrapped { other_tags, .. } => {
+                    stack.extend(other_tags.iter().flat_map(|fields| fields.iter().copied()))
+                }
+                UnionLayout::NonNullableUnwrapped(fields)
+                | UnionLayout::NullableUnwrapped {
+                    other_fields: fields,
+                    ..
+                } => stack.extend(fields.iter().copied()),
+            },
+            LayoutRepr::LambdaSet(lambda_set) => stack.push(lambda_set.runtime_representation()),
+            LayoutRepr::RecursivePointer(_) => {
+                unreachable!("recursive pointers are chased above")
+            }
+        }
+    }
+
+    shared
+}
+
+/// Make the refcount updates inside of the builtins atomic when any Roc value can be shared.
+pub fn set_builtins_refcount_mode(env: &Env<'_, '_, '_>) {
+    if !env.refcounting.any_atomic() {
+        return;
+    }
+
+    if let Some(global) = env
+        .module
+        .get_global(roc_builtins::bitcode::UTILS_ATOMIC_REFCOUNTS)
+    {
+        let flag_type = global
+            .get_initializer()
+            .map(|initializer| initializer.get_type().into_int_type())
+            .unwrap_or_else(|| env.context.bool_type());
+
+        global.set_initializer(&flag_type.const_int(1, false));
+        global.set_constant(true);
+    }
+}
+
 pub struct PointerToRefcount<'ctx> {
     value: PointerValue<'ctx>,
 }
@@ -109,13 +258,16 @@ impl<'ctx> PointerToRefcount<'ctx> {
         layout_interner: &mut STLayoutInterner<'a>,
     ) {
         match mode {
-            CallMode::Inc(inc_amount) => self.increment(inc_amount, env),
+            CallMode::Inc(inc_amount) => {
+                let atomic = env.refcounting.is_atomic(layout_interner, layout);
+                self.increment(inc_amount, env, atomic)
+            }
             CallMode::Dec => self.decrement(env, layout_interner, layout),
         }
     }
 
-    fn increment<'a, 'env>(&self, amount: IntValue<'ctx>, env: &Env<'a, 'ctx, 'env>) {
-        incref_pointer(env, self.value, amount);
+    fn increment<'a, 'env>(&self, amount: IntValue<'ctx>, env: &Env<'a, 'ctx, 'env>, atomic: bool) {
+        incref_pointer(env, self.value, amount, atomic);
     }
 
     pub fn decrement<'a, 'env>(
@@ -128,11 +280,17 @@ impl<'ctx> PointerToRefcount<'ctx> {
             .allocation_alignment_bytes(layout)
             .max(env.target_info.ptr_width() as u32);
 
+        let atomic = env.refcounting.is_atomic(layout_interner, layout);
+
         let context = env.context;
         let block = env.builder.get_insert_block().expect("to be in a function");
         let di_location = env.builder.get_current_debug_location().unwrap();
 
-        let fn_name = &format!("decrement_refcounted_ptr_{}", alignment);
+        let fn_name = &if atomic {
+            format!("decrement_refcounted_ptr_{}_atomic", alignment)
+        } else {
+            format!("decrement_refcounted_ptr_{}", alignment)
+        };
 
         let function = match env.module.get_function(fn_name) {
             Some(function_value) => function_value,
@@ -154,7 +312,7 @@ impl<'ctx> PointerToRefcount<'ctx> {
                 let subprogram = env.new_subprogram(fn_name);
                 function_value.set_subprogram(subprogram);
 
-                Self::build_decrement_function_body(env, function_value, alignment);
+                Self::build_decrement_function_body(env, function_value, alignment, atomic);
 
                 function_value
             }
@@ -176,6 +334,7 @@ impl<'ctx> PointerToRefcount<'ctx> {
         env: &Env<'a, 'ctx, 'env>,
         parent: FunctionValue<'ctx>,
         alignment: u32,
+        atomic: bool,
     ) {
         let builder = env.builder;
         let ctx = env.context;
@@ -189,6 +348,7 @@ impl<'ctx> PointerToRefcount<'ctx> {
             env,
             parent.get_nth_param(0).unwrap().into_pointer_value(),
             alignment,
+            atomic,
         );
 
         builder.build_return(None);
@@ -199,7 +359,14 @@ fn incref_pointer<'ctx>(
     env: &Env<'_, 'ctx, '_>,
     pointer: PointerValue<'ctx>,
     amount: IntValue<'ctx>,
+    atomic: bool,
 ) {
+    let fn_name = if atomic {
+        roc_builtins::bitcode::UTILS_INCREF_RC_PTR_ATOMIC
+    } else {
+        roc_builtins::bitcode::UTILS_INCREF_RC_PTR_NON_ATOMIC
+    };
+
     call_void_bitcode_fn(
         env,
         &[
@@ -212,11 +379,22 @@ fn incref_pointer<'ctx>(
                 .into(),
             amount.into(),
         ],
-        roc_builtins::bitcode::UTILS_INCREF_RC_PTR,
+        fn_name,
     );
 }
 
-fn decref_pointer<'ctx>(env: &Env<'_, 'ctx, '_>, pointer: PointerValue<'ctx>, alignment: u32) {
+fn decref_pointer<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    pointer: PointerValue<'ctx>,
+    alignment: u32,
+    atomic: bool,
+) {
+    let fn_name = if atomic {
+        roc_builtins::bitcode::UTILS_DECREF_RC_PTR_ATOMIC
+    } else {
+        roc_builtins::bitcode::UTILS_DECREF_RC_PTR_NON_ATOMIC
+    };
+
     let alignment = env.context.i32_type().const_int(alignment as _, false);
     call_void_bitcode_fn(
         env,
@@ -230,7 +408,7 @@ fn decref_pointer<'ctx>(env: &Env<'_, 'ctx, '_>, pointer: PointerValue<'ctx>, al
                 .into(),
             alignment.into(),
         ],
-        roc_builtins::bitcode::UTILS_DECREF_RC_PTR,
+        fn_name,
     );
 }
 
@@ -270,7 +448,7 @@ fn modify_refcount_struct<'a, 'ctx>(
     let layout = layout_interner.insert_no_semantic(LayoutRepr::struct_(layouts));
 
     let (_, fn_name) = function_name_from_mode(
-        layout_ids,
+        layout_interner,
         &env.interns,
         "increment_struct",
         "decrement_struct",
@@ -424,13 +602,7 @@ fn modify_refcount_builtin<'a, 'ctx>(
             Some(function)
         }
 
-        Str => Some(modify_refcount_str(
-            env,
-            layout_interner,
-            layout_ids,
-            mode,
-            layout,
-        )),
+        Str => Some(modify_refcount_str(env, layout_interner, mode, layout)),
 
         _ => {
             debug_assert!(!builtin.is_refcounted());
@@ -612,7 +784,7 @@ fn modify_refcount_list<'a, 'ctx>(
     let list_layout =
         layout_interner.insert_no_semantic(LayoutRepr::Builtin(Builtin::List(element_layout)));
     let (_, fn_name) = function_name_from_mode(
-        layout_ids,
+        layout_interner,
         &env.interns,
         "increment_list",
         "decrement_list",
@@ -744,7 +916,6 @@ fn modify_refcount_list_help<'a, 'ctx>(
 fn modify_refcount_str<'a, 'ctx>(
     env: &Env<'a, 'ctx, '_>,
     layout_interner: &mut STLayoutInterner<'a>,
-    layout_ids: &mut LayoutIds<'a>,
     mode: Mode,
     layout: InLayout<'a>,
 ) -> FunctionValue<'ctx> {
@@ -752,7 +923,7 @@ fn modify_refcount_str<'a, 'ctx>(
     let di_location = env.builder.get_current_debug_location().unwrap();
 
     let (_, fn_name) = function_name_from_mode(
-        layout_ids,
+        layout_interner,
         &env.interns,
         "increment_str",
         "decrement_str",
@@ -862,7 +1033,7 @@ fn modify_refcount_boxed<'a, 'ctx>(
     let boxed_layout = layout_interner.insert_no_semantic(LayoutRepr::Boxed(inner_layout));
 
     let (_, fn_name) = function_name_from_mode(
-        layout_ids,
+        layout_interner,
         &env.interns,
         "increment_boxed",
         "decrement_boxed",
@@ -1068,7 +1239,7 @@ fn build_rec_union<'a, 'ctx>(
     let layout = layout_interner.insert_no_semantic(LayoutRepr::Union(union_layout));
 
     let (_, fn_name) = function_name_from_mode(
-        layout_ids,
+        layout_interner,
         &env.interns,
         "increment_rec_union",
         "decrement_rec_union",
@@ -1471,8 +1642,8 @@ pub fn build_reset<'a, 'ctx>(
     let mode = Mode::Dec;
 
     let union_layout_in = layout_interner.insert_no_semantic(LayoutRepr::Union(union_layout));
-    let layout_id = layout_ids.get(Symbol::DEC, &union_layout_in);
-    let fn_name = layout_id.to_symbol_string(Symbol::DEC, &env.interns);
+    let layout_hash = layout_hash(layout_interner, &env.interns, union_layout_in);
+    let fn_name = specialization_name(&env.interns, Symbol::DEC, layout_hash);
     let fn_name = format!("{}_reset", fn_name);
 
     let dec_function = build_rec_union(env, layout_interner, layout_ids, Mode::Dec, union_layout);
@@ -1601,22 +1772,23 @@ fn build_reuse_rec_union_help<'a, 'ctx>(
 }
 
 fn function_name_from_mode<'a>(
-    layout_ids: &mut LayoutIds<'a>,
+    layout_interner: &STLayoutInterner<'a>,
     interns: &Interns,
     if_inc: &'static str,
     if_dec: &'static str,
     layout: InLayout<'a>,
     mode: Mode,
 ) -> (&'static str, String) {
-    // NOTE this is not a typo, we always determine the layout ID
-    // using the DEC symbol. Anything that is incrementing must also be
-    // decremented, so `dec` is used on more layouts. That can cause the
-    // layout ids of the inc and dec versions to be different, which is
-    // rather confusing, so now `inc_x` always corresponds to `dec_x`
-    let layout_id = layout_ids.get(Symbol::DEC, &layout);
+    let layout_hash = layout_hash(layout_interner, interns, layout);
     match mode {
-        Mode::Inc => (if_inc, layout_id.to_symbol_string(Symbol::INC, interns)),
-        Mode::Dec => (if_dec, layout_id.to_symbol_string(Symbol::DEC, interns)),
+        Mode::Inc => (
+            if_inc,
+            specialization_name(interns, Symbol::INC, layout_hash),
+        ),
+        Mode::Dec => (
+            if_dec,
+            specialization_name(interns, Symbol::DEC, layout_hash),
+        ),
     }
 }
 
@@ -1634,7 +1806,7 @@ fn modify_refcount_nonrecursive<'a, 'ctx>(
     let di_location = env.builder.get_current_debug_location().unwrap();
 
     let (_, fn_name) = function_name_from_mode(
-        layout_ids,
+        layout_interner,
         &env.interns,
         "increment_union",
         "decrement_union",
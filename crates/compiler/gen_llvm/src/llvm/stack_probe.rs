@@ -0,0 +1,81 @@
+//! Stack overflow detection.
+//!
+//! Before it does anything else, every proc compares the address of its stack frame with
+//! `roc_stack_limit`: the lowest address Roc code may use on the current thread. When the frame is
+//! below the limit, the proc crashes with "stack overflow in <proc>" through `roc_panic`, like any
+//! other crash, instead of running into the guard page and getting killed by a signal.
+//!
+//! The app defines `roc_stack_limit` as a weak global that is 0, which turns the check off. A host
+//! that wants the friendly error sets it before it calls into Roc, to the lowest address of the
+//! stack of the calling thread plus enough room for `roc_panic` to run in, like 64KB.
+use inkwell::module::Linkage;
+use inkwell::values::{FunctionValue, GlobalValue};
+use inkwell::IntPredicate;
+use roc_module::symbol::Symbol;
+use roc_target::Architecture;
+
+use crate::llvm::build::{throw_internal_exception, BuilderExt, Env};
+use crate::llvm::intrinsics::LLVM_FRAME_ADDRESS;
+
+/// The global the host sets to the lowest address Roc code may use on the current thread
+const STACK_LIMIT: &str = "roc_stack_limit";
+
+/// Crashes with a "stack overflow" message when the frame of `parent` is below the stack limit.
+/// Must be called at the start of the entry block of `parent`.
+pub(crate) fn check_stack<'ctx>(
+    env: &Env<'_, 'ctx, '_>,
+    parent: FunctionValue<'ctx>,
+    proc: Symbol,
+) {
+    // the stack of wasm is in linear memory, without a guard page below it
+    if let Architecture::Wasm32 = env.target_info.architecture {
+        return;
+    }
+
+    let builder = env.builder;
+    let ptr_int = env.ptr_int();
+
+    let limit = builder
+        .new_build_load(ptr_int, stack_limit(env).as_pointer_value(), "stack_limit")
+        .into_int_value();
+
+    let frame_address = env.call_intrinsic(
+        LLVM_FRAME_ADDRESS,
+        &[env.context.i32_type().const_zero().into()],
+    );
+    let frame_address =
+        builder.build_ptr_to_int(frame_address.into_pointer_value(), ptr_int, "frame_address");
+
+    // the stack grows down, and a limit of 0 is never above the frame
+    let overflowed =
+        builder.build_int_compare(IntPredicate::ULT, frame_address, limit, "stack_overflowed");
+
+    let overflow_block = env.context.append_basic_block(parent, "stack_overflow");
+    let continue_block = env.context.append_basic_block(parent, "stack_ok");
+
+    builder.build_conditional_branch(overflowed, overflow_block, continue_block);
+
+    builder.position_at_end(overflow_block);
+
+    let message = format!(
+        "stack overflow in {}.{}",
+        proc.module_string(&env.interns),
+        proc.as_str(&env.interns)
+    );
+    throw_internal_exception(env, parent, &message);
+
+    builder.position_at_end(continue_block);
+}
+
+fn stack_limit<'ctx>(env: &Env<'_, 'ctx, '_>) -> GlobalValue<'ctx> {
+    env.module.get_global(STACK_LIMIT).unwrap_or_else(|| {
+        let ptr_int = env.ptr_int();
+        let global = env.module.add_global(ptr_int, None, STACK_LIMIT);
+        global.set_initializer(&ptr_int.const_zero());
+
+        // weak, so a host can define the global itself instead
+        global.set_linkage(Linkage::WeakAny);
+
+        global
+    })
+}
@@ -12,9 +12,10 @@ use roc_mono::ir::{
     Param, Proc, ProcLayout, Stmt,
 };
 use roc_mono::layout::{
-    Builtin, InLayout, Layout, LayoutIds, LayoutInterner, LayoutRepr, STLayoutInterner,
-    TagIdIntType, UnionLayout,
+    Builtin, InLayout, Layout, LayoutInterner, LayoutRepr, STLayoutInterner, TagIdIntType,
+    UnionLayout,
 };
+use roc_mono::mangle::{proc_layout_hash, specialization_name};
 use roc_std::RocDec;
 
 use roc_wasm_module::linking::{DataSymbol, WasmObjectSymbol};
@@ -58,7 +59,6 @@ pub struct WasmBackend<'a, 'r> {
 
     // Module-level data
     module: WasmModule<'a>,
-    layout_ids: LayoutIds<'a>,
     pub fn_index_offset: u32,
     import_fn_count: u32,
     called_fns: BitVec<usize>,
@@ -82,7 +82,6 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         env: &'r Env<'a>,
         layout_interner: &'r mut STLayoutInterner<'a>,
         interns: &'r mut Interns,
-        layout_ids: LayoutIds<'a>,
         proc_lookup: Vec<'a, ProcLookupData<'a>>,
         host_to_app_map: Vec<'a, (&'a str, u32)>,
         mut module: WasmModule<'a>,
@@ -124,7 +123,6 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
             // Module-level data
             module,
-            layout_ids,
             fn_index_offset,
             import_fn_count: import_fn_count as u32,
             called_fns,
@@ -259,11 +257,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
         let proc_index = self.proc_lookup.len();
         let wasm_fn_index = self.fn_index_offset + proc_index as u32;
 
-        let name = self
-            .layout_ids
-            .get_toplevel(symbol, &layout)
-            .to_symbol_string(symbol, self.interns);
-        let name = String::from_str_in(&name, self.env.arena).into_bump_str();
+        let name = self.proc_name(symbol, &layout);
 
         self.proc_lookup.push(ProcLookupData {
             name: symbol,
@@ -476,10 +470,25 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             .unwrap();
         let wasm_fn_index = self.fn_index_offset + proc_index as u32;
 
-        let name = String::from_str_in(sym.as_str(self.interns), self.env.arena).into_bump_str();
+        let layout = self.proc_lookup[proc_index].layout;
+        let name = self.proc_name(sym, &layout);
         self.module.names.append_function(wasm_fn_index, name);
     }
 
+    /// The name of a specialization, which stays the same from one build to the next
+    fn proc_name(&self, symbol: Symbol, layout: &ProcLayout<'a>) -> &'a str {
+        let layout_hash = proc_layout_hash(
+            self.layout_interner,
+            self.interns,
+            layout.arguments.iter().copied(),
+            layout.niche,
+            layout.result,
+        );
+        let name = specialization_name(self.interns, symbol, layout_hash);
+
+        String::from_str_in(&name, self.env.arena).into_bump_str()
+    }
+
     /// Build a wrapper around a Roc procedure so that it can be called from Zig builtins List.map*
     ///
     /// The generic Zig code passes *pointers* to all of the argument values (e.g. on the heap in a List).
@@ -1058,7 +1067,11 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             } => self.expr_struct_at_index(sym, *index, field_layouts, *structure),
 
             Expr::Array { elems, elem_layout } => {
-                self.expr_array(sym, storage, *elem_layout, elems)
+                self.expr_array(sym, storage, *elem_layout, elems, false)
+            }
+
+            Expr::StackArray { elems, elem_layout } => {
+                self.expr_array(sym, storage, *elem_layout, elems, true)
             }
 
             Expr::EmptyArray => self.expr_empty_array(sym, storage),
@@ -1082,7 +1095,13 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
                 index,
             } => self.expr_union_at_index(*structure, *tag_id, union_layout, *index, sym),
 
-            Expr::ExprBox { symbol: arg_sym } => self.expr_box(sym, *arg_sym, layout, storage),
+            Expr::ExprBox { symbol: arg_sym } => {
+                self.expr_box(sym, *arg_sym, layout, storage, false)
+            }
+
+            Expr::StackBox { symbol: arg_sym } => {
+                self.expr_box(sym, *arg_sym, layout, storage, true)
+            }
 
             Expr::ExprUnbox { symbol: arg_sym } => self.expr_unbox(sym, *arg_sym),
 
@@ -1286,6 +1305,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             CallType::Foreign {
                 foreign_symbol,
                 ret_layout,
+                ..
             } => {
                 let name = foreign_symbol.as_str();
                 let wasm_layout = WasmLayout::new(self.layout_interner, *ret_layout);
@@ -1529,28 +1549,35 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
      * Arrays
      *******************************************************************/
 
+    /// Create a list literal. If `on_stack` is true, its elements are stored in the stack frame
+    /// rather than on the heap, so the list must never be refcounted.
     pub fn expr_array(
         &mut self,
         sym: Symbol,
         storage: &StoredValue,
         elem_layout: InLayout<'a>,
         elems: &'a [ListLiteralElement<'a>],
+        on_stack: bool,
     ) {
         if let StoredValue::StackMemory { location, .. } = storage {
             let size = self.layout_interner.stack_size(elem_layout) * (elems.len() as u32);
+            let alignment = self.layout_interner.alignment_bytes(elem_layout);
 
-            // Allocate heap space and store its address in a local variable
-            let heap_local_id = self.storage.create_anonymous_local(PTR_TYPE);
-            let heap_alignment = self.layout_interner.alignment_bytes(elem_layout);
-            self.allocate_with_refcount(Some(size), heap_alignment, 1);
-            self.code_builder.set_local(heap_local_id);
+            // Allocate space for the elements and store its address in a local variable
+            let elems_local_id = self.storage.create_anonymous_local(PTR_TYPE);
+            if on_stack {
+                self.stack_frame_address(size, alignment);
+            } else {
+                self.allocate_with_refcount(Some(size), alignment, 1);
+            }
+            self.code_builder.set_local(elems_local_id);
 
             let (stack_local_id, stack_offset) =
                 location.local_and_offset(self.storage.stack_frame_pointer);
 
             // elements pointer
             self.code_builder.get_local(stack_local_id);
-            self.code_builder.get_local(heap_local_id);
+            self.code_builder.get_local(elems_local_id);
             self.code_builder.i32_store(Align::Bytes4, stack_offset);
 
             // length of the list
@@ -1591,7 +1618,7 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
 
                 elem_offset += self.storage.copy_value_to_memory(
                     &mut self.code_builder,
-                    heap_local_id,
+                    elems_local_id,
                     elem_offset,
                     elem_sym,
                 );
@@ -1883,12 +1910,15 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
      * Box
      *******************************************************************/
 
+    /// Box a value. If `on_stack` is true, the value is stored in the stack frame rather than
+    /// on the heap, so the box must never be refcounted.
     fn expr_box(
         &mut self,
         ret_sym: Symbol,
         arg_sym: Symbol,
         layout: InLayout<'a>,
         storage: &StoredValue,
+        on_stack: bool,
     ) {
         // create a local variable for the heap pointer
         let ptr_local_id = match self.storage.ensure_value_has_local(
@@ -1906,7 +1936,11 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
             _ => internal_error!("ExprBox should always produce a Boxed layout"),
         };
         let (size, alignment) = self.layout_interner.stack_size_and_alignment(arg_layout);
-        self.allocate_with_refcount(Some(size), alignment, 1);
+        if on_stack {
+            self.stack_frame_address(size, alignment);
+        } else {
+            self.allocate_with_refcount(Some(size), alignment, 1);
+        }
 
         // store the pointer value from the value stack into the local variable
         self.code_builder.set_local(ptr_local_id);
@@ -1944,6 +1978,17 @@ impl<'a, 'r> WasmBackend<'a, 'r> {
      * Refcounting & Heap allocation
      *******************************************************************/
 
+    /// Allocate space in the stack frame, and leave its address on the VM stack
+    fn stack_frame_address(&mut self, size: u32, alignment_bytes: u32) {
+        let (frame_ptr, offset) = self
+            .storage
+            .allocate_anonymous_stack_memory(size, alignment_bytes);
+
+        self.code_builder.get_local(frame_ptr);
+        self.code_builder.i32_const(offset as i32);
+        self.code_builder.i32_add();
+    }
+
     /// Allocate heap space and write an initial refcount
     /// If the data size is known at compile time, pass it in comptime_data_size.
     /// If size is only known at runtime, push *data* size to the VM stack first.
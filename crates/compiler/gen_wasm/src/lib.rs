@@ -130,7 +130,6 @@ pub fn build_app_module<'a, 'r>(
         env,
         layout_interner,
         interns,
-        layout_ids,
         proc_lookup,
         host_to_app_map,
         host_module,
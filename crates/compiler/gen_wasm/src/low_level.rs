@@ -325,6 +325,15 @@ impl<'a> LowLevelCall<'a> {
 
             ListIsUnique => self.load_args_and_call_zig(backend, bitcode::LIST_IS_UNIQUE),
 
+            CryptoSha256 => self.load_args_and_call_zig(backend, bitcode::CRYPTO_SHA256),
+            CryptoBlake3 => self.load_args_and_call_zig(backend, bitcode::CRYPTO_BLAKE3),
+            CryptoConstantTimeEq => {
+                self.load_args_and_call_zig(backend, bitcode::CRYPTO_CONSTANT_TIME_EQ)
+            }
+
+            RandomU64s => self.load_args_and_call_zig(backend, bitcode::RANDOM_U64S),
+            RandomBytes => self.load_args_and_call_zig(backend, bitcode::RANDOM_BYTES),
+
             ListMap | ListMap2 | ListMap3 | ListMap4 | ListSortWith => {
                 internal_error!("HigherOrder lowlevels should not be handled here")
             }
@@ -637,6 +646,44 @@ impl<'a> LowLevelCall<'a> {
 
                 backend.call_host_fn_after_loading_args(bitcode::LIST_APPEND_UNSAFE, 4, false);
             }
+            ListRepeat => {
+                // List.repeat : elem, Nat -> List elem
+
+                let elem: Symbol = self.arguments[0];
+                let count: Symbol = self.arguments[1];
+
+                let elem_layout = unwrap_list_elem_layout(self.ret_layout_raw);
+                let (elem_width, elem_align) = backend
+                    .layout_interner
+                    .stack_size_and_alignment(elem_layout);
+                let (elem_local, elem_offset, _) =
+                    ensure_symbol_is_in_memory(backend, elem, elem_layout, backend.env.arena);
+
+                // Zig arguments              Wasm types
+                //  (return pointer)           i32
+                //  element: Opaque            i32
+                //  count: usize               i32
+                //  alignment: u32             i32
+                //  element_width: usize       i32
+
+                backend
+                    .storage
+                    .load_symbols(&mut backend.code_builder, &[self.ret_symbol]);
+
+                backend.code_builder.get_local(elem_local);
+                if elem_offset > 0 {
+                    backend.code_builder.i32_const(elem_offset as i32);
+                    backend.code_builder.i32_add();
+                }
+
+                backend
+                    .storage
+                    .load_symbols(&mut backend.code_builder, &[count]);
+                backend.code_builder.i32_const(elem_align as i32);
+                backend.code_builder.i32_const(elem_width as i32);
+
+                backend.call_host_fn_after_loading_args(bitcode::LIST_REPEAT, 5, false);
+            }
             ListPrepend => {
                 // List.prepend : List elem, elem -> List elem
 
@@ -729,6 +776,61 @@ impl<'a> LowLevelCall<'a> {
 
                 backend.call_host_fn_after_loading_args(bitcode::LIST_SUBLIST, 8, false);
             }
+            ListChunksOf | ListWindows => {
+                // List.chunksOf : List elem, Nat -> List (List elem)
+                // List.windows : List elem, Nat -> List (List elem)
+
+                let list: Symbol = self.arguments[0];
+                let size: Symbol = self.arguments[1];
+
+                let inner_list_layout = unwrap_list_elem_layout(self.ret_layout_raw);
+                let elem_layout =
+                    unwrap_list_elem_layout(backend.layout_interner.get(inner_list_layout));
+                let (elem_width, elem_align) = backend
+                    .layout_interner
+                    .stack_size_and_alignment(elem_layout);
+
+                // The refcount function receives a pointer to an element in the list
+                // This is the same as a Struct containing the element
+                let in_memory_layout =
+                    backend
+                        .layout_interner
+                        .insert_no_semantic(LayoutRepr::Struct {
+                            field_layouts: backend.env.arena.alloc([elem_layout]),
+                        });
+                let inc_fn = backend.get_refcount_fn_index(in_memory_layout, HelperOp::Inc);
+                let inc_fn_ptr = backend.get_fn_ptr(inc_fn);
+
+                // Zig arguments              Wasm types
+                //  (return pointer)           i32
+                //  list: RocList,             i64, i32
+                //  alignment: u32,            i32
+                //  element_width: usize,      i32
+                //  size: usize,               i32
+                //  inc_n_element: IncN,       i32
+
+                backend.storage.load_symbols_for_call(
+                    backend.env.arena,
+                    &mut backend.code_builder,
+                    &[list],
+                    self.ret_symbol,
+                    &WasmLayout::new(backend.layout_interner, self.ret_layout),
+                    CallConv::Zig,
+                );
+
+                backend.code_builder.i32_const(elem_align as i32);
+                backend.code_builder.i32_const(elem_width as i32);
+                backend
+                    .storage
+                    .load_symbols(&mut backend.code_builder, &[size]);
+                backend.code_builder.i32_const(inc_fn_ptr);
+
+                let zig_fn_name = match self.lowlevel {
+                    ListChunksOf => bitcode::LIST_CHUNKS_OF,
+                    _ => bitcode::LIST_WINDOWS,
+                };
+                backend.call_host_fn_after_loading_args(zig_fn_name, 7, false);
+            }
             ListDropAt => {
                 // List.dropAt : List elem, Nat -> List elem
                 let list: Symbol = self.arguments[0];
@@ -841,8 +943,7 @@ impl<'a> LowLevelCall<'a> {
             NumAddWrap => match self.ret_layout_raw.repr {
                 LayoutRepr::Builtin(Builtin::Int(width)) => match width {
                     IntWidth::I128 | IntWidth::U128 => {
-                        // TODO: don't panic
-                        self.load_args_and_call_zig(backend, &bitcode::NUM_ADD_OR_PANIC_INT[width])
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_ADD_WRAP_INT[width])
                     }
                     IntWidth::I64 | IntWidth::U64 => {
                         self.load_args(backend);
@@ -932,8 +1033,7 @@ impl<'a> LowLevelCall<'a> {
             NumSubWrap => match self.ret_layout_raw.repr {
                 LayoutRepr::Builtin(Builtin::Int(width)) => match width {
                     IntWidth::I128 | IntWidth::U128 => {
-                        // TODO: don't panic
-                        self.load_args_and_call_zig(backend, &bitcode::NUM_SUB_OR_PANIC_INT[width])
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_SUB_WRAP_INT[width])
                     }
                     IntWidth::I64 | IntWidth::U64 => {
                         self.load_args(backend);
@@ -1974,7 +2074,31 @@ impl<'a> LowLevelCall<'a> {
                 backend.storage.load_symbols(code_builder, self.arguments);
             }
 
-            PtrWrite => todo!("{:?}", self.lowlevel),
+            PtrWrite => {
+                // Overwrite the contents of a (unique) box, and return the box
+                let (ptr, value) = match self.arguments {
+                    [ptr, value] => (*ptr, *value),
+                    _ => internal_error!("PtrWrite expects a pointer and a value"),
+                };
+
+                let ptr_local_id = match backend.storage.ensure_value_has_local(
+                    &mut backend.code_builder,
+                    ptr,
+                    backend.storage.get(&ptr).clone(),
+                ) {
+                    StoredValue::Local { local_id, .. } => local_id,
+                    _ => internal_error!("A heap pointer will always be an i32"),
+                };
+
+                backend.storage.copy_value_to_memory(
+                    &mut backend.code_builder,
+                    ptr_local_id,
+                    0,
+                    value,
+                );
+
+                backend.code_builder.get_local(ptr_local_id);
+            }
 
             Hash => todo!("{:?}", self.lowlevel),
 
@@ -26,6 +26,14 @@ const MODULES: &[(ModuleId, &str)] = &[
     (ModuleId::DECODE, "Decode.roc"),
     (ModuleId::HASH, "Hash.roc"),
     (ModuleId::JSON, "Json.roc"),
+    (ModuleId::RBF, "Rbf.roc"),
+    (ModuleId::STREAM, "Stream.roc"),
+    (ModuleId::REGEX, "Regex.roc"),
+    (ModuleId::TIME, "Time.roc"),
+    (ModuleId::CRYPTO, "Crypto.roc"),
+    (ModuleId::UUID, "Uuid.roc"),
+    (ModuleId::RANDOM, "Random.roc"),
+    (ModuleId::ENUM, "Enum.roc"),
 ];
 
 fn main() {
@@ -3,11 +3,16 @@
 use bumpalo::Bump;
 use roc_can::module::{ExposedByModule, TypeState};
 use roc_collections::all::MutMap;
+use roc_load_internal::precompiled::{
+    source_hash, PrecompiledDependency, PrecompiledModule, PrecompiledPackage,
+};
 use roc_module::symbol::ModuleId;
 use roc_packaging::cache::RocCacheDir;
-use roc_reporting::report::{Palette, RenderTarget};
+use roc_reporting::report::{Palette, RenderTarget, DEFAULT_PALETTE};
 use roc_target::TargetInfo;
-use std::path::PathBuf;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 const SKIP_SUBS_CACHE: bool = {
     match option_env!("ROC_SKIP_SUBS_CACHE") {
@@ -19,8 +24,10 @@ const SKIP_SUBS_CACHE: bool = {
 pub use roc_load_internal::docs;
 pub use roc_load_internal::file::{
     EntryPoint, ExecutionMode, ExpectMetadata, Expectations, ExposedToHost, LoadConfig, LoadResult,
-    LoadStart, LoadedModule, LoadingProblem, MonomorphizedModule, Phase, Threading,
+    LoadStart, LoadedModule, LoadingProblem, MonomorphizedModule, OverflowMode, Phase, Threading,
+    TESTED_PACKAGE_SHORTHAND,
 };
+pub use roc_load_internal::precompiled;
 
 #[allow(clippy::too_many_arguments)]
 fn load<'a>(
@@ -65,6 +72,9 @@ pub fn load_single_threaded<'a>(
         render,
         palette,
         exec_mode,
+        false,
+        false,
+        OverflowMode::Trap,
         roc_cache_dir,
     )
 }
@@ -192,6 +202,134 @@ pub fn load_and_typecheck_str<'a>(
     }
 }
 
+fn collect_module_paths(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_hidden = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or(false, |name| name.starts_with('.'));
+
+        if is_hidden {
+            continue;
+        } else if path.is_dir() {
+            collect_module_paths(&path, paths)?;
+        } else if path.extension().map_or(false, |ext| ext == "roc") {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn precompiled_module(loaded: LoadedModule) -> Option<PrecompiledModule> {
+    let LoadedModule {
+        module_id,
+        interns,
+        solved,
+        exposed_to_host,
+        abilities_store,
+        resolved_implementations,
+        sources,
+        ..
+    } = loaded;
+
+    let module_name = |id: ModuleId| Some(interns.module_ids.get_name(id)?.as_str().to_string());
+
+    let dependencies = sources
+        .iter()
+        .filter(|(id, _)| !id.is_builtin())
+        .map(|(id, (_, src))| {
+            Some(PrecompiledDependency {
+                index: id.index() as u32,
+                name: module_name(*id)?,
+                source_hash: source_hash(src),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let type_state = TypeState {
+        subs: solved.into_inner(),
+        exposed_vars_by_symbol: exposed_to_host.into_iter().collect(),
+        abilities: abilities_store,
+        solved_implementations: resolved_implementations,
+    };
+
+    let mut bytes = Vec::new();
+    type_state.serialize(&mut bytes).ok()?;
+
+    Some(PrecompiledModule {
+        name: module_name(module_id)?,
+        dependencies,
+        type_state: bytes,
+    })
+}
+
+/// Typecheck each module of the package whose `main.roc` is at `package_main`, and write the
+/// solved types of every module that checks without problems to the package's precompiled
+/// artifact. Returns the names of the modules that were precompiled.
+///
+/// Modules that don't typecheck on their own, like ones that import other packages, are left
+/// out; apps solve those as usual.
+pub fn precompile_package(
+    package_main: &Path,
+    target_info: TargetInfo,
+    roc_cache_dir: RocCacheDir<'_>,
+) -> io::Result<Vec<String>> {
+    let package_dir = match package_main.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    let mut module_paths = Vec::new();
+    collect_module_paths(package_dir, &mut module_paths)?;
+
+    // Sorted, so precompiling the same package twice writes the same artifact
+    module_paths.sort();
+
+    let mut package = PrecompiledPackage::default();
+
+    for module_path in module_paths {
+        let is_package_main = module_path.parent() == Some(package_dir)
+            && module_path.file_name() == package_main.file_name();
+
+        if is_package_main {
+            continue;
+        }
+
+        let arena = Bump::new();
+        let src = arena.alloc_str(&fs::read_to_string(&module_path)?);
+
+        let loaded = match load_and_typecheck_str(
+            &arena,
+            module_path.clone(),
+            src,
+            package_dir.to_path_buf(),
+            target_info,
+            RenderTarget::Generic,
+            roc_cache_dir,
+            DEFAULT_PALETTE,
+        ) {
+            Ok(loaded) if loaded.total_problems() == 0 => loaded,
+            _ => continue,
+        };
+
+        package.modules.extend(precompiled_module(loaded));
+    }
+
+    let mut writer =
+        io::BufWriter::new(fs::File::create(PrecompiledPackage::path_in(package_dir))?);
+
+    package.write(&mut writer)?;
+    writer.flush()?;
+
+    Ok(package
+        .modules
+        .into_iter()
+        .map(|module| module.name)
+        .collect())
+}
+
 // IFTTT: crates/compiler/load/build.rs
 const BOOL: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Bool.dat")) as &[_];
 const DICT: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/Dict.dat")) as &[_];
@@ -1,7 +1,8 @@
 use crate::docs::DocEntry::DetachedDoc;
 use crate::docs::TypeAnnotation::{Apply, BoundVariable, Function, NoTypeAnn, Record, TagUnion};
+use roc_can::constant::Constant;
 use roc_can::scope::Scope;
-use roc_collections::VecSet;
+use roc_collections::{VecMap, VecSet};
 use roc_module::ident::ModuleName;
 use roc_module::symbol::{IdentIds, ModuleId, ModuleIds, Symbol};
 use roc_parse::ast::AssignedField;
@@ -31,6 +32,12 @@ pub struct DocDef {
     pub type_vars: Vec<String>,
     pub type_annotation: TypeAnnotation,
     pub docs: Option<String>,
+    /// The value of this def, if it's a constant which could be evaluated at compile time
+    pub constant: Option<Constant>,
+    /// The type the solver inferred for this def, if it's a value without a type annotation
+    pub inferred_type: Option<String>,
+    /// The definitions of the aliases in the inferred type, like `Alias Point = { x : I64, y : I64 }`
+    pub alias_hints: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -100,6 +107,7 @@ pub fn generate_module_docs(
     exposed_module_ids: &[ModuleId],
     exposed_symbols: VecSet<Symbol>,
     header_comments: &[CommentOrNewline<'_>],
+    constants: &VecMap<Symbol, Constant>,
 ) -> ModuleDocumentation {
     let entries = generate_entry_docs(
         home,
@@ -108,6 +116,7 @@ pub fn generate_module_docs(
         parsed_defs,
         exposed_module_ids,
         header_comments,
+        constants,
     );
 
     ModuleDocumentation {
@@ -152,6 +161,7 @@ fn generate_entry_docs(
     defs: &roc_parse::ast::Defs<'_>,
     exposed_module_ids: &[ModuleId],
     header_comments: &[CommentOrNewline<'_>],
+    constants: &VecMap<Symbol, Constant>,
 ) -> Vec<DocEntry> {
     use roc_parse::ast::Pattern;
 
@@ -191,6 +201,9 @@ fn generate_entry_docs(
                                 type_annotation: type_to_docs(false, loc_ann.value),
                                 type_vars: Vec::new(),
                                 docs,
+                                constant: None,
+                                inferred_type: None,
+                                alias_hints: Vec::new(),
                             };
                             acc.push(DocEntry::DocDef(doc_def));
                         }
@@ -205,20 +218,41 @@ fn generate_entry_docs(
                     if let Pattern::Identifier(identifier) = ann_pattern.value {
                         // Check if this module exposes the def
                         if let Some(ident_id) = ident_ids.get_id(identifier) {
+                            let symbol = Symbol::new(home, ident_id);
                             let doc_def = DocDef {
                                 name: identifier.to_string(),
                                 type_annotation: type_to_docs(false, ann_type.value),
                                 type_vars: Vec::new(),
-                                symbol: Symbol::new(home, ident_id),
+                                symbol,
                                 docs,
+                                constant: constants.get(&symbol).cloned(),
+                                inferred_type: None,
+                                alias_hints: Vec::new(),
                             };
                             acc.push(DocEntry::DocDef(doc_def));
                         }
                     }
                 }
 
-                ValueDef::Body(_, _) => {
-                    // TODO generate docs for un-annotated bodies
+                ValueDef::Body(loc_pattern, _) => {
+                    if let Pattern::Identifier(identifier) = loc_pattern.value {
+                        // Check if this module exposes the def
+                        if let Some(ident_id) = ident_ids.get_id(identifier) {
+                            let symbol = Symbol::new(home, ident_id);
+                            // The type is filled in once the module is solved
+                            let doc_def = DocDef {
+                                name: identifier.to_string(),
+                                type_annotation: TypeAnnotation::NoTypeAnn,
+                                type_vars: Vec::new(),
+                                symbol,
+                                docs,
+                                constant: constants.get(&symbol).cloned(),
+                                inferred_type: None,
+                                alias_hints: Vec::new(),
+                            };
+                            acc.push(DocEntry::DocDef(doc_def));
+                        }
+                    }
                 }
 
                 ValueDef::Dbg { .. } => {
@@ -263,6 +297,9 @@ fn generate_entry_docs(
                         type_annotation,
                         type_vars,
                         docs,
+                        constant: None,
+                        inferred_type: None,
+                        alias_hints: Vec::new(),
                         symbol: Symbol::new(home, ident_id),
                     };
                     acc.push(DocEntry::DocDef(doc_def));
@@ -286,6 +323,9 @@ fn generate_entry_docs(
                         type_annotation: TypeAnnotation::NoTypeAnn,
                         type_vars,
                         docs,
+                        constant: None,
+                        inferred_type: None,
+                        alias_hints: Vec::new(),
                         symbol: Symbol::new(home, ident_id),
                     };
                     acc.push(DocEntry::DocDef(doc_def));
@@ -327,6 +367,9 @@ fn generate_entry_docs(
                         symbol: Symbol::new(home, ident_id),
                         type_vars,
                         docs,
+                        constant: None,
+                        inferred_type: None,
+                        alias_hints: Vec::new(),
                     };
                     acc.push(DocEntry::DocDef(doc_def));
                 }
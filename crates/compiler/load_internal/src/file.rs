@@ -1,14 +1,17 @@
 #![allow(clippy::too_many_arguments)]
 
 use crate::docs::ModuleDocumentation;
+use crate::precompiled::PrecompiledPackage;
 use bumpalo::{collections::CollectIn, Bump};
 use crossbeam::channel::{bounded, Sender};
 use crossbeam::deque::{Injector, Stealer, Worker};
 use crossbeam::thread;
 use parking_lot::Mutex;
 use roc_builtins::roc::module_source;
-use roc_can::abilities::{AbilitiesStore, PendingAbilitiesStore, ResolvedImpl};
+use roc_can::abilities::{AbilitiesStore, ImplKey, PendingAbilitiesStore, ResolvedImpl};
 use roc_can::constraint::{Constraint as ConstraintSoa, Constraints, TypeOrVar};
+use roc_can::crash_reachability::{self, CrashSummary};
+use roc_can::dead_code::{self, ReferenceSummary};
 use roc_can::expr::{DbgLookup, Declarations, ExpectLookup, PendingDerives};
 use roc_can::module::{
     canonicalize_module_defs, ExposedByModule, ExposedForModule, ExposedModuleTypes, Module,
@@ -19,16 +22,20 @@ use roc_constrain::module::constrain_module;
 use roc_debug_flags::dbg_do;
 #[cfg(debug_assertions)]
 use roc_debug_flags::{
-    ROC_CHECK_MONO_IR, ROC_PRINT_IR_AFTER_DROP_SPECIALIZATION, ROC_PRINT_IR_AFTER_REFCOUNT,
-    ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SPECIALIZATION, ROC_PRINT_LOAD_LOG,
+    ROC_CHECK_MONO_IR, ROC_PRINT_IR_AFTER_BOUNDS_CHECKS, ROC_PRINT_IR_AFTER_CONSTANT_FOLDING,
+    ROC_PRINT_IR_AFTER_DROP_SPECIALIZATION, ROC_PRINT_IR_AFTER_EFFECT_INTERPRETERS,
+    ROC_PRINT_IR_AFTER_ESCAPE_ANALYSIS, ROC_PRINT_IR_AFTER_FLATTEN_CLOSURES,
+    ROC_PRINT_IR_AFTER_MUTUAL_TAIL_CALLS, ROC_PRINT_IR_AFTER_PURE_CALLS,
+    ROC_PRINT_IR_AFTER_REFCOUNT, ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SPECIALIZATION,
+    ROC_PRINT_LOAD_LOG,
 };
 use roc_derive::SharedDerivedModule;
-use roc_error_macros::internal_error;
+use roc_error_macros::{crash_report, internal_error};
 use roc_late_solve::{AbilitiesView, WorldAbilities};
 use roc_module::ident::{Ident, ModuleName, QualifiedModuleName};
 use roc_module::symbol::{
     IdentIds, IdentIdsByModule, Interns, ModuleId, ModuleIds, PQModuleName, PackageModuleIds,
-    PackageQualified, Symbol,
+    PackageQualified, Symbol, HOST_SHORTHAND,
 };
 use roc_mono::ir::{
     CapturedSymbols, ExternalSpecializations, GlueLayouts, LambdaSetId, PartialProc, Proc,
@@ -39,7 +46,11 @@ use roc_mono::layout::{
     GlobalLayoutInterner, LambdaName, Layout, LayoutCache, LayoutProblem, Niche, STLayoutInterner,
 };
 use roc_mono::reset_reuse;
-use roc_mono::{drop_specialization, inc_dec};
+use roc_mono::static_data::{self, StaticConstants};
+use roc_mono::{
+    bounds_checks, constant_folding, drop_specialization, effect_interpreters, escape_analysis,
+    flatten_closures, inc_dec, initialization, overflow, pure_calls, tail_recursion,
+};
 use roc_packaging::cache::RocCacheDir;
 use roc_parse::ast::{
     self, CommentOrNewline, Defs, Expr, ExtractSpaces, Pattern, Spaced, StrLiteral, TypeAnnotation,
@@ -49,7 +60,7 @@ use roc_parse::header::{
     ExposedName, ImportsEntry, PackageEntry, PackageHeader, PlatformHeader, To, TypedIdent,
 };
 use roc_parse::header::{HeaderType, PackageName};
-use roc_parse::module::module_defs;
+use roc_parse::module::{module_defs, module_defs_recovering};
 use roc_parse::parser::{FileError, Parser, SourceError, SyntaxError};
 use roc_problem::Severity;
 use roc_region::all::{LineInfo, Loc, Region};
@@ -57,6 +68,7 @@ use roc_reporting::report::{to_file_problem_report_string, Palette, RenderTarget
 use roc_solve::module::{extract_module_owned_implementations, Solved, SolvedModule};
 use roc_solve_problem::TypeError;
 use roc_target::TargetInfo;
+use roc_types::num::NumLiteralDefaults;
 use roc_types::subs::{CopiedImport, ExposedTypesStorageSubs, Subs, VarStore, Variable};
 use roc_types::types::{Alias, Types};
 use std::collections::hash_map::Entry::{Occupied, Vacant};
@@ -72,11 +84,14 @@ use std::{env, fs};
 #[cfg(not(target_family = "wasm"))]
 use {
     roc_packaging::cache::{self},
+    roc_packaging::config::PackageConfig,
     roc_packaging::https::PackageMetadata,
+    roc_packaging::lock::{LockProblem, VersionedPackage},
 };
 
 pub use crate::work::Phase;
 use crate::work::{DepCycle, Dependencies};
+pub use roc_mono::overflow::OverflowMode;
 
 #[cfg(target_family = "wasm")]
 use crate::wasm_instant::{Duration, Instant};
@@ -105,6 +120,19 @@ pub struct LoadConfig {
     pub palette: Palette,
     pub threading: Threading,
     pub exec_mode: ExecutionMode,
+    /// Keep going when a top-level def (or `when` branch) has a syntax error, reporting the error
+    /// and treating that def as malformed, instead of giving up on the whole module. Editor
+    /// tooling wants this, so a typo doesn't lose the types of everything else in the module.
+    pub recover_from_syntax_errors: bool,
+    /// Evaluate top-level constants at compile time, and leave their refcounting out of the
+    /// generated code. Only set this for backends which store those constants as static data
+    /// (currently just LLVM); the others build them again on every use, and need the refcounts.
+    pub static_data: bool,
+    /// What `+`, `-` and `*` on integers do when the result doesn't fit.
+    pub overflow: OverflowMode,
+    /// Replace operations whose arguments are all constants with their results. The IR
+    /// snapshot tests turn this off, so that their small programs don't all fold away.
+    pub fold_constants: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -116,20 +144,32 @@ pub enum ExecutionMode {
     /// Test is like [`ExecutionMode::ExecutableIfCheck`], but rather than producing a proper
     /// executable, run tests.
     Test,
+    /// Like [`ExecutionMode::Test`], but runs the tests of the package or platform that the root
+    /// app depends on as [`TESTED_PACKAGE_SHORTHAND`], instead of the tests of the app itself.
+    /// `roc test` uses this to test a package or a platform, through an app it generates.
+    TestPackage,
 }
 
+/// The shorthand under which an app generated by `roc test` depends on the package or platform
+/// under test
+pub const TESTED_PACKAGE_SHORTHAND: &str = "tested";
+
 impl ExecutionMode {
     fn goal_phase(&self) -> Phase {
         use ExecutionMode::*;
 
         match self {
             Executable => Phase::MakeSpecializations,
-            Check | ExecutableIfCheck | Test => Phase::SolveTypes,
+            Check | ExecutableIfCheck | Test | TestPackage => Phase::SolveTypes,
         }
     }
 
     fn build_if_checks(&self) -> bool {
-        matches!(self, Self::ExecutableIfCheck | Self::Test)
+        matches!(self, Self::ExecutableIfCheck | Self::Test | Self::TestPackage)
+    }
+
+    fn is_test(&self) -> bool {
+        matches!(self, Self::Test | Self::TestPackage)
     }
 }
 
@@ -152,11 +192,27 @@ struct ModuleCache<'a> {
     /// Various information
     imports: MutMap<ModuleId, MutSet<ModuleId>>,
     top_level_thunks: MutMap<ModuleId, MutSet<Symbol>>,
+    /// The regions of the name and the body of each top-level value; used to report values
+    /// which depend on themselves, when that is only found out after specialization
+    thunk_regions: MutMap<Symbol, (Region, Region)>,
     documentation: VecMap<ModuleId, ModuleDocumentation>,
+    num_literal_defaults: MutMap<ModuleId, NumLiteralDefaults>,
     can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     type_problems: MutMap<ModuleId, Vec<TypeError>>,
+    lints: MutMap<ModuleId, Vec<TypeError>>,
+    crash_summaries: MutMap<ModuleId, CrashSummary>,
+    reference_summaries: MutMap<ModuleId, ReferenceSummary>,
 
     sources: MutMap<ModuleId, (PathBuf, &'a str)>,
+
+    /// Where each module's header imports its dependencies; used to report import cycles
+    import_sites: MutMap<ModuleId, ImportSites<'a>>,
+}
+
+struct ImportSites<'a> {
+    module_path: PathBuf,
+    src: &'a [u8],
+    regions: MutMap<ModuleId, Region>,
 }
 
 impl<'a> ModuleCache<'a> {
@@ -205,6 +261,14 @@ impl Default for ModuleCache<'_> {
             DECODE,
             HASH,
             JSON,
+            RBF,
+            STREAM,
+            REGEX,
+            TIME,
+            CRYPTO,
+            UUID,
+            RANDOM,
+            ENUM,
         }
 
         Self {
@@ -220,10 +284,16 @@ impl Default for ModuleCache<'_> {
             external_specializations_requested: Default::default(),
             imports: Default::default(),
             top_level_thunks: Default::default(),
+            thunk_regions: Default::default(),
             documentation: Default::default(),
+            num_literal_defaults: Default::default(),
             can_problems: Default::default(),
             type_problems: Default::default(),
+            lints: Default::default(),
+            crash_summaries: Default::default(),
+            reference_summaries: Default::default(),
             sources: Default::default(),
+            import_sites: Default::default(),
         }
     }
 }
@@ -282,7 +352,10 @@ fn start_phase<'a>(
                 // parse the file
                 let header = state.module_cache.headers.remove(&module_id).unwrap();
 
-                BuildTask::Parse { header }
+                BuildTask::Parse {
+                    header,
+                    recover_from_syntax_errors: state.recover_from_syntax_errors,
+                }
             }
             Phase::CanonicalizeAndConstrain => {
                 // canonicalize the file
@@ -335,7 +408,12 @@ fn start_phase<'a>(
                 let mut aliases = MutMap::default();
                 let mut abilities_store = PendingAbilitiesStore::default();
 
-                for imported in parsed.imported_modules.keys() {
+                // The import each implementation was first brought in by, to point at when
+                // another import brings in a different one.
+                let mut impl_imports: MutMap<ImplKey, Region> = MutMap::default();
+                let mut impl_conflicts = std::vec::Vec::new();
+
+                for (imported, import_region) in parsed.imported_modules.iter() {
                     match state.module_cache.aliases.get(imported) {
                         None => unreachable!(
                             r"imported module {:?} did not register its aliases, so {:?} cannot use them",
@@ -372,12 +450,40 @@ fn start_phase<'a>(
                             // Add the declared abilities from the modules we import;
                             // we may not know all their types yet since type-solving happens in
                             // parallel, but we'll fill that in during type-checking our module.
-                            abilities_store
-                                .union(import_store.closure_from_imported(exposed_symbols));
+                            let imported_store =
+                                import_store.closure_from_imported(exposed_symbols);
+
+                            for (impl_key, _) in imported_store.iter_declared_implementations() {
+                                impl_imports.entry(impl_key).or_insert(*import_region);
+                            }
+
+                            for impl_key in abilities_store.union(imported_store) {
+                                impl_conflicts.push(roc_problem::can::Problem::ConflictingImpls {
+                                    ability_member: impl_key.ability_member,
+                                    opaque: impl_key.opaque,
+                                    first_import: impl_imports[&impl_key],
+                                    second_import: *import_region,
+                                });
+                            }
                         }
                     }
                 }
 
+                if !impl_conflicts.is_empty() {
+                    state
+                        .module_cache
+                        .can_problems
+                        .entry(module_id)
+                        .or_default()
+                        .extend(impl_conflicts);
+                }
+
+                if let Some(type_state) =
+                    precompiled_type_state(state, module_id, &parsed.module_path)
+                {
+                    state.cached_types.lock().insert(module_id, type_state);
+                }
+
                 let skip_constraint_gen = {
                     // Give this its own scope to make sure that the Guard from the lock() is dropped
                     // immediately after contains_key returns
@@ -462,7 +568,7 @@ fn start_phase<'a>(
                 let derived_module = SharedDerivedModule::clone(&state.derived_module);
 
                 let build_expects =
-                    matches!(state.exec_mode, ExecutionMode::Test) && expectations.is_some();
+                    state.exec_mode.is_test() && expectations.is_some();
 
                 BuildTask::BuildPendingSpecializations {
                     layout_cache,
@@ -629,14 +735,26 @@ pub struct LoadedModule {
     pub solved: Solved<Subs>,
     pub can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub type_problems: MutMap<ModuleId, Vec<TypeError>>,
+    /// Warnings that are only reported when asked for, see [roc_solve::single_implementation]
+    pub lints: MutMap<ModuleId, Vec<TypeError>>,
+    /// What can crash in each module, see [roc_can::crash_reachability]
+    pub crash_summaries: MutMap<ModuleId, CrashSummary>,
+    /// What uses what in each module of the root's package, see [roc_can::dead_code]
+    pub reference_summaries: MutMap<ModuleId, ReferenceSummary>,
     pub declarations_by_id: MutMap<ModuleId, Declarations>,
     pub exposed_to_host: MutMap<Symbol, Variable>,
     pub dep_idents: IdentIdsByModule,
     pub exposed_aliases: MutMap<Symbol, Alias>,
     pub exposed_values: Vec<Symbol>,
+    /// The values and types each module exposes
+    pub exposed_symbols_by_module: MutMap<ModuleId, VecSet<Symbol>>,
+    /// The shorthand of the package each module of a dependency is imported from, like `pf`
+    pub package_shorthands: MutMap<ModuleId, Box<str>>,
     pub exposed_types_storage: ExposedTypesStorageSubs,
     pub resolved_implementations: ResolvedImplementations,
     pub sources: MutMap<ModuleId, (PathBuf, Box<str>)>,
+    /// The modules each module imports
+    pub imports: MutMap<ModuleId, MutSet<ModuleId>>,
     pub timings: MutMap<ModuleId, ModuleTiming>,
     pub docs_by_module: VecMap<ModuleId, ModuleDocumentation>,
     pub abilities_store: AbilitiesStore,
@@ -695,6 +813,8 @@ struct ModuleHeader<'a> {
     symbols_from_requires: Vec<(Loc<Symbol>, Loc<TypeAnnotation<'a>>)>,
     module_timing: ModuleTiming,
     defined_values: Vec<ValueDef<'a>>,
+    /// The modules this one imports from the platform through the `host` shorthand
+    host_imports: Vec<(ModuleName, Region)>,
 }
 
 #[derive(Debug)]
@@ -763,10 +883,14 @@ pub struct MonomorphizedModule<'a> {
     pub can_problems: MutMap<ModuleId, Vec<roc_problem::can::Problem>>,
     pub type_problems: MutMap<ModuleId, Vec<TypeError>>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    /// The top-level constants among the procedures whose values are known at compile time
+    pub static_constants: StaticConstants<'a>,
     pub toplevel_expects: ToplevelExpects,
     pub entry_point: EntryPoint<'a>,
     pub exposed_to_host: ExposedToHost,
     pub sources: MutMap<ModuleId, (PathBuf, Box<str>)>,
+    /// The modules each module imports
+    pub imports: MutMap<ModuleId, MutSet<ModuleId>>,
     pub timings: MutMap<ModuleId, ModuleTiming>,
     pub expectations: VecMap<ModuleId, Expectations>,
     pub uses_prebuilt_platform: bool,
@@ -836,6 +960,9 @@ struct ParsedModule<'a> {
     exposed_ident_ids: IdentIds,
     exposed_imports: MutMap<Ident, (Symbol, Region)>,
     parsed_defs: Defs<'a>,
+    /// Syntax errors that parsing recovered from; these get reported along with the
+    /// canonicalization problems.
+    syntax_problems: Vec<roc_problem::can::Problem>,
     symbols_from_requires: Vec<(Loc<Symbol>, Loc<TypeAnnotation<'a>>)>,
     header_type: HeaderType<'a>,
     header_comments: &'a [CommentOrNewline<'a>],
@@ -883,6 +1010,7 @@ enum Msg<'a> {
         module_timing: ModuleTiming,
         abilities_store: AbilitiesStore,
         toplevel_expects: ToplevelExpects,
+        thunk_regions: VecMap<Symbol, (Region, Region)>,
         expectations: Option<Expectations>,
     },
     MadeSpecializations {
@@ -939,6 +1067,7 @@ enum PlatformPath<'a> {
 struct PlatformData<'a> {
     module_id: ModuleId,
     provides: &'a [(Loc<ExposedName<'a>>, Loc<TypedIdent<'a>>)],
+    exposes: &'a [Loc<roc_parse::header::ModuleName<'a>>],
     is_prebuilt: bool,
 }
 
@@ -986,6 +1115,7 @@ struct State<'a> {
     pub module_cache: ModuleCache<'a>,
     pub dependencies: Dependencies<'a>,
     pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    pub static_constants: StaticConstants<'a>,
     pub toplevel_expects: ToplevelExpects,
     pub exposed_to_host: ExposedToHost,
 
@@ -1016,20 +1146,96 @@ struct State<'a> {
     pub render: RenderTarget,
     pub palette: Palette,
     pub exec_mode: ExecutionMode,
+    recover_from_syntax_errors: bool,
+    static_data: bool,
+    overflow: OverflowMode,
+    fold_constants: bool,
 
     /// All abilities across all modules.
     pub world_abilities: WorldAbilities,
 
     make_specializations_pass: MakeSpecializationsPass,
 
-    // cached types (used for builtin modules, could include packages in the future too)
+    // cached types (used for builtin modules, and package modules that were precompiled)
     cached_types: CachedTypeState,
 
+    /// The precompiled artifacts of the packages loaded so far, by package directory
+    precompiled_packages: MutMap<PathBuf, Option<PrecompiledPackage>>,
+
+    /// `host.` imports which still have to be checked against what the platform exposes
+    pending_host_imports: Vec<HostImport<'a>>,
+
     layout_interner: GlobalLayoutInterner<'a>,
 }
 
+/// Packages can only import the modules the app's platform exposes through `host`,
+/// since the rest of the platform's modules are its own business.
+fn check_host_imports<'a>(state: &mut State<'a>) -> Result<(), LoadingProblem<'a>> {
+    let platform_exposes = match (&state.platform_data, state.opt_platform_shorthand) {
+        (Some(platform_data), _) => platform_data.exposes,
+        // the platform's header hasn't been loaded yet, so these have to wait
+        (None, Some(_)) => return Ok(()),
+        (None, None) => {
+            return match state.pending_host_imports.pop() {
+                Some(host_import) => Err(LoadingProblem::UnexposedHostModule(host_import, None)),
+                None => Ok(()),
+            };
+        }
+    };
+
+    for host_import in state.pending_host_imports.drain(..) {
+        let is_exposed = platform_exposes
+            .iter()
+            .any(|exposed| exposed.value.as_str() == host_import.module_name.as_str());
+
+        if !is_exposed {
+            let exposes = platform_exposes
+                .iter()
+                .map(|exposed| exposed.value.as_str().into())
+                .collect();
+
+            return Err(LoadingProblem::UnexposedHostModule(
+                host_import,
+                Some(exposes),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 type CachedTypeState = Arc<Mutex<MutMap<ModuleId, TypeState>>>;
 
+/// The solved types of a package module from its package's precompiled artifact, if the package
+/// has one and it still matches the source of the module and everything it imports.
+fn precompiled_type_state(
+    state: &mut State,
+    module_id: ModuleId,
+    module_path: &Path,
+) -> Option<TypeState> {
+    let module_ids = state.arc_modules.lock();
+
+    let (shorthand, module_name) = match module_ids.get_name(module_id)? {
+        PQModuleName::Qualified(shorthand, module_name) => (*shorthand, module_name),
+        PQModuleName::Unqualified(_) => return None,
+    };
+
+    // A module named Foo.Bar lives at Foo/Bar.roc, relative to the package's main.roc
+    let depth = module_name.as_str().split('.').count();
+    let package_dir = module_path.ancestors().nth(depth)?;
+
+    let package = state
+        .precompiled_packages
+        .entry(package_dir.to_path_buf())
+        .or_insert_with(|| PrecompiledPackage::read_from_dir(package_dir));
+
+    package.as_ref()?.get(module_name.as_str())?.type_state(
+        shorthand,
+        &module_ids,
+        &state.module_cache.sources,
+    )
+}
+
 impl<'a> State<'a> {
     fn goal_phase(&self) -> Phase {
         self.exec_mode.goal_phase()
@@ -1047,6 +1253,10 @@ impl<'a> State<'a> {
         palette: Palette,
         number_of_workers: usize,
         exec_mode: ExecutionMode,
+        recover_from_syntax_errors: bool,
+        static_data: bool,
+        overflow: OverflowMode,
+        fold_constants: bool,
     ) -> Self {
         let arc_shorthands = Arc::new(Mutex::new(MutMap::default()));
         let cache_dir = roc_packaging::cache::roc_cache_dir();
@@ -1064,6 +1274,7 @@ impl<'a> State<'a> {
             module_cache: ModuleCache::default(),
             dependencies,
             procedures: MutMap::default(),
+            static_constants: StaticConstants::default(),
             toplevel_expects: ToplevelExpects::default(),
             exposed_to_host: ExposedToHost::default(),
             exposed_modules: &[],
@@ -1078,9 +1289,15 @@ impl<'a> State<'a> {
             timings: MutMap::default(),
             layout_caches: std::vec::Vec::with_capacity(number_of_workers),
             cached_types: Arc::new(Mutex::new(cached_types)),
+            precompiled_packages: MutMap::default(),
+            pending_host_imports: Vec::new(),
             render,
             palette,
             exec_mode,
+            recover_from_syntax_errors,
+            static_data,
+            overflow,
+            fold_constants,
             make_specializations_pass: MakeSpecializationsPass::Pass(1),
             world_abilities: Default::default(),
             layout_interner: GlobalLayoutInterner::with_capacity(128, target_info),
@@ -1215,6 +1432,7 @@ enum BuildTask<'a> {
     },
     Parse {
         header: ModuleHeader<'a>,
+        recover_from_syntax_errors: bool,
     },
     CanonicalizeAndConstrain {
         parsed: ParsedModule<'a>,
@@ -1300,9 +1518,33 @@ pub enum LoadingProblem<'a> {
     /// a formatted report
     FormattedReport(String),
 
-    ImportCycle(PathBuf, Vec<ModuleId>),
+    /// The first hop's importer is the module where the cycle was found;
+    /// the last hop's imported module is that same module again.
+    ImportCycle(PathBuf, Vec<ImportCycleHop<'a>>),
     IncorrectModuleName(FileError<'a, IncorrectModuleName<'a>>),
     CouldNotFindCacheDir,
+
+    /// A module was imported through `host` which the platform doesn't expose.
+    /// Along with it, the modules the platform does expose, or `None` if there is no platform.
+    UnexposedHostModule(HostImport<'a>, Option<Vec<ModuleName>>),
+}
+
+/// One module importing another, as part of an import cycle
+#[derive(Debug)]
+pub struct ImportCycleHop<'a> {
+    pub importer: ModuleId,
+    pub imported: ModuleId,
+    /// The importing module's path and source, and where its header imports the other module
+    pub site: Option<(PathBuf, &'a [u8], Region)>,
+}
+
+/// A module imported from the platform through the `host` shorthand, like `host.Sha`
+#[derive(Debug)]
+pub struct HostImport<'a> {
+    pub importer: ModuleId,
+    pub module_name: ModuleName,
+    /// The importing module's path and source, and where its header imports the module
+    pub site: (PathBuf, &'a [u8], Region),
 }
 
 pub enum Phases {
@@ -1357,6 +1599,10 @@ pub fn load_and_typecheck_str<'a>(
         palette,
         threading,
         exec_mode: ExecutionMode::Check,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants: true,
     };
 
     match load(
@@ -1616,6 +1862,10 @@ pub fn load<'a>(
             load_config.render,
             load_config.palette,
             load_config.exec_mode,
+            load_config.recover_from_syntax_errors,
+            load_config.static_data,
+            load_config.overflow,
+            load_config.fold_constants,
             roc_cache_dir,
         ),
         Threads::Many(threads) => load_multi_threaded(
@@ -1628,6 +1878,10 @@ pub fn load<'a>(
             load_config.palette,
             threads,
             load_config.exec_mode,
+            load_config.recover_from_syntax_errors,
+            load_config.static_data,
+            load_config.overflow,
+            load_config.fold_constants,
             roc_cache_dir,
         ),
     }
@@ -1643,6 +1897,10 @@ pub fn load_single_threaded<'a>(
     render: RenderTarget,
     palette: Palette,
     exec_mode: ExecutionMode,
+    recover_from_syntax_errors: bool,
+    static_data: bool,
+    overflow: OverflowMode,
+    fold_constants: bool,
     roc_cache_dir: RocCacheDir<'_>,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
     let LoadStart {
@@ -1674,6 +1932,10 @@ pub fn load_single_threaded<'a>(
         palette,
         number_of_workers,
         exec_mode,
+        recover_from_syntax_errors,
+        static_data,
+        overflow,
+        fold_constants,
     );
 
     // We'll add tasks to this, and then worker threads will take tasks from it.
@@ -1865,19 +2127,32 @@ fn state_thread_step<'a>(
                             );
                             Err(LoadingProblem::FormattedReport(buf))
                         }
-                        Err(LoadingProblem::ImportCycle(filename, cycle)) => {
+                        Err(LoadingProblem::ImportCycle(filename, hops)) => {
                             let module_ids = arc_modules.lock().clone().into_module_ids();
 
                             let root_exposed_ident_ids = IdentIds::exposed_builtins(0);
                             let buf = to_import_cycle_report(
                                 module_ids,
                                 root_exposed_ident_ids,
-                                cycle,
+                                hops,
                                 filename,
                                 render,
                             );
                             return Err(LoadingProblem::FormattedReport(buf));
                         }
+                        Err(LoadingProblem::UnexposedHostModule(host_import, platform_exposes)) => {
+                            let module_ids = arc_modules.lock().clone().into_module_ids();
+
+                            let root_exposed_ident_ids = IdentIds::exposed_builtins(0);
+                            let buf = to_unexposed_host_module_report(
+                                module_ids,
+                                root_exposed_ident_ids,
+                                host_import,
+                                platform_exposes,
+                                render,
+                            );
+                            return Err(LoadingProblem::FormattedReport(buf));
+                        }
                         Err(LoadingProblem::IncorrectModuleName(FileError {
                             problem: SourceError { problem, bytes },
                             filename,
@@ -1920,10 +2195,21 @@ pub fn report_loading_problem(
 
             to_parse_problem_report(problem, module_ids, root_exposed_ident_ids, render, palette)
         }
-        LoadingProblem::ImportCycle(filename, cycle) => {
+        LoadingProblem::ImportCycle(filename, hops) => {
+            let root_exposed_ident_ids = IdentIds::exposed_builtins(0);
+
+            to_import_cycle_report(module_ids, root_exposed_ident_ids, hops, filename, render)
+        }
+        LoadingProblem::UnexposedHostModule(host_import, platform_exposes) => {
             let root_exposed_ident_ids = IdentIds::exposed_builtins(0);
 
-            to_import_cycle_report(module_ids, root_exposed_ident_ids, cycle, filename, render)
+            to_unexposed_host_module_report(
+                module_ids,
+                root_exposed_ident_ids,
+                host_import,
+                platform_exposes,
+                render,
+            )
         }
         LoadingProblem::IncorrectModuleName(FileError {
             problem: SourceError { problem, bytes },
@@ -1958,6 +2244,10 @@ fn load_multi_threaded<'a>(
     palette: Palette,
     available_threads: usize,
     exec_mode: ExecutionMode,
+    recover_from_syntax_errors: bool,
+    static_data: bool,
+    overflow: OverflowMode,
+    fold_constants: bool,
     roc_cache_dir: RocCacheDir<'_>,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
     let LoadStart {
@@ -2004,6 +2294,10 @@ fn load_multi_threaded<'a>(
         palette,
         num_workers,
         exec_mode,
+        recover_from_syntax_errors,
+        static_data,
+        overflow,
+        fold_constants,
     );
 
     // an arena for every worker, stored in an arena-allocated bumpalo vec to make the lifetimes work
@@ -2237,7 +2531,7 @@ fn worker_task<'a>(
                             BuildTask::LoadModule { module_name, .. } => {
                                 format!("BuildTask::LoadModule({:?})", module_name)
                             }
-                            BuildTask::Parse { header } => {
+                            BuildTask::Parse { header, .. } => {
                                 format!("BuildTask::Parse({})", header.module_path.display())
                             }
                             BuildTask::CanonicalizeAndConstrain { parsed, .. } => format!(
@@ -2346,6 +2640,57 @@ macro_rules! debug_check_ir {
     };
 }
 
+/// Show the types the solver inferred for the values in the docs which don't have an annotation
+fn add_inferred_types_to_docs(
+    docs: &mut ModuleDocumentation,
+    decls: &Declarations,
+    subs: &mut Subs,
+    interns: &Interns,
+) {
+    use crate::docs::{DocEntry, TypeAnnotation};
+    use roc_types::pretty_print::{alias_hints, name_and_print_var, DebugPrint};
+
+    for entry in docs.entries.iter_mut() {
+        let doc_def = match entry {
+            DocEntry::DocDef(doc_def)
+                if matches!(doc_def.type_annotation, TypeAnnotation::NoTypeAnn) =>
+            {
+                doc_def
+            }
+            _ => continue,
+        };
+
+        // opaque types have no annotation either, but only values are declarations
+        let index = match decls
+            .symbols
+            .iter()
+            .position(|loc_symbol| loc_symbol.value == doc_def.symbol)
+        {
+            Some(index) => index,
+            None => continue,
+        };
+
+        // naming the type variables changes the subs, which are still needed after this
+        let snapshot = subs.snapshot();
+
+        doc_def.inferred_type = Some(name_and_print_var(
+            decls.variables[index],
+            subs,
+            doc_def.symbol.module_id(),
+            interns,
+            DebugPrint::NOTHING,
+        ));
+        doc_def.alias_hints = alias_hints(
+            decls.variables[index],
+            subs,
+            doc_def.symbol.module_id(),
+            interns,
+        );
+
+        subs.rollback_to(snapshot);
+    }
+}
+
 /// Report modules that are imported, but from which nothing is used
 fn report_unused_imported_modules(
     state: &mut State<'_>,
@@ -2517,6 +2862,7 @@ fn update<'a>(
                     Platform {
                         config_shorthand,
                         provides,
+                        exposes,
                         exposes_ids,
                         ..
                     } => {
@@ -2548,6 +2894,7 @@ fn update<'a>(
                             state.platform_data = Some(PlatformData {
                                 module_id: header.module_id,
                                 provides,
+                                exposes,
                                 is_prebuilt,
                             });
                         }
@@ -2582,6 +2929,22 @@ fn update<'a>(
                 state.module_cache.module_names.insert(*id, name.clone());
             }
 
+            // A `host.` import can only be checked once the platform's header is loaded,
+            // which may be before or after this header.
+            for (module_name, region) in header.host_imports.iter() {
+                state.pending_host_imports.push(HostImport {
+                    importer: header.module_id,
+                    module_name: module_name.clone(),
+                    site: (
+                        header.module_path.clone(),
+                        header.parse_state.original_bytes(),
+                        *region,
+                    ),
+                });
+            }
+
+            check_host_imports(&mut state)?;
+
             // This was a dependency. Write it down and keep processing messages.
             let mut exposed_symbols: VecSet<Symbol> = VecSet::with_capacity(header.exposes.len());
 
@@ -2615,6 +2978,7 @@ fn update<'a>(
                 extend_header_with_builtin(header, ModuleId::ENCODE);
                 extend_header_with_builtin(header, ModuleId::DECODE);
                 extend_header_with_builtin(header, ModuleId::HASH);
+                extend_header_with_builtin(header, ModuleId::ENUM);
             }
 
             state
@@ -2629,6 +2993,15 @@ fn update<'a>(
                         .map(|x| *x.as_inner()),
                 );
 
+            state.module_cache.import_sites.insert(
+                header.module_id,
+                ImportSites {
+                    module_path: header.module_path.clone(),
+                    src: header.parse_state.original_bytes(),
+                    regions: header.imported_modules.clone(),
+                },
+            );
+
             let added_deps_result = state.dependencies.add_module(
                 header.module_id,
                 &header.package_qualified_imported_modules,
@@ -2638,9 +3011,30 @@ fn update<'a>(
             let new_work = match added_deps_result {
                 Ok(work) => work,
                 Err(DepCycle { cycle }) => {
+                    let hops =
+                        cycle
+                            .windows(2)
+                            .map(|pair| {
+                                let (importer, imported) = (pair[0], pair[1]);
+                                let site = state.module_cache.import_sites.get(&importer).and_then(
+                                    |sites| {
+                                        let region = *sites.regions.get(&imported)?;
+
+                                        Some((sites.module_path.clone(), sites.src, region))
+                                    },
+                                );
+
+                                ImportCycleHop {
+                                    importer,
+                                    imported,
+                                    site,
+                                }
+                            })
+                            .collect();
+
                     return Err(LoadingProblem::ImportCycle(
                         header.module_path.clone(),
-                        cycle,
+                        hops,
                     ));
                 }
             };
@@ -2696,10 +3090,13 @@ fn update<'a>(
         }) => {
             let module_id = constrained_module.module.module_id;
             log!("generated constraints for {:?}", module_id);
+            // Conflicting ability implementations were already reported while importing
             state
                 .module_cache
                 .can_problems
-                .insert(module_id, canonicalization_problems);
+                .entry(module_id)
+                .or_default()
+                .extend(canonicalization_problems);
 
             if let Some(docs) = module_docs {
                 state.module_cache.documentation.insert(module_id, docs);
@@ -2717,6 +3114,11 @@ fn update<'a>(
                 .pending_abilities
                 .insert(module_id, constrained_module.module.abilities_store.clone());
 
+            state.module_cache.num_literal_defaults.insert(
+                module_id,
+                constrained_module.module.num_literal_defaults,
+            );
+
             state
                 .module_cache
                 .constrained
@@ -2734,7 +3136,7 @@ fn update<'a>(
             module_id,
             ident_ids,
             solved_module,
-            solved_subs,
+            mut solved_subs,
             decls,
             dep_idents,
             mut module_timing,
@@ -2749,14 +3151,78 @@ fn update<'a>(
                 .module_cache
                 .type_problems
                 .insert(module_id, solved_module.problems);
+            state
+                .module_cache
+                .lints
+                .insert(module_id, solved_module.lints);
 
-            let should_include_expects = (!loc_expects.is_empty() || !loc_dbgs.is_empty()) && {
+            if matches!(state.exec_mode, ExecutionMode::Check) && !module_id.is_builtin() {
+                let crash_summary =
+                    crash_reachability::summarize(&decls, solved_subs.inner(), &abilities_store);
+
+                state
+                    .module_cache
+                    .crash_summaries
+                    .insert(module_id, crash_summary);
+            }
+
+            let in_root_package = matches!(state.exec_mode, ExecutionMode::Check) && {
                 let modules = state.arc_modules.lock();
                 modules
                     .package_eq(module_id, state.root_id)
                     .expect("root or this module is not yet known - that's a bug!")
             };
 
+            if in_root_package {
+                let no_imports = MutMap::default();
+                let imports = match state.module_cache.import_sites.get(&module_id) {
+                    Some(import_sites) => &import_sites.regions,
+                    None => &no_imports,
+                };
+
+                let reference_summary = dead_code::summarize(
+                    module_id,
+                    &decls,
+                    &solved_module.aliases,
+                    &abilities_store,
+                    imports,
+                    &state.exposed_symbols_by_module[&module_id],
+                );
+
+                state
+                    .module_cache
+                    .reference_summaries
+                    .insert(module_id, reference_summary);
+            }
+
+            if let Some(docs) = state.module_cache.documentation.get_mut(&module_id) {
+                let mut all_ident_ids = dep_idents.clone();
+                all_ident_ids.insert(module_id, ident_ids.clone());
+
+                let interns = Interns {
+                    module_ids: state.arc_modules.lock().clone().into_module_ids(),
+                    all_ident_ids,
+                };
+
+                add_inferred_types_to_docs(docs, &decls, solved_subs.inner_mut(), &interns);
+            }
+
+            let should_include_expects = (!loc_expects.is_empty() || !loc_dbgs.is_empty()) && {
+                let modules = state.arc_modules.lock();
+
+                match state.exec_mode {
+                    ExecutionMode::TestPackage => match modules.get_name(module_id) {
+                        Some(PQModuleName::Qualified(shorthand, _)) => {
+                            *shorthand == TESTED_PACKAGE_SHORTHAND
+                        }
+                        _ => false,
+                    },
+                    _ => modules
+                        .package_eq(module_id, state.root_id)
+                        .expect("root or this module is not yet known - that's a bug!"),
+                }
+            };
+
             let opt_expectations = if should_include_expects {
                 let (path, _) = state.module_cache.sources.get(&module_id).unwrap();
 
@@ -2782,7 +3248,7 @@ fn update<'a>(
 
             let add_to_host_exposed = is_host_exposed &&
                 // During testing, we don't need to expose anything to the host.
-                !matches!(state.exec_mode, ExecutionMode::Test);
+                !state.exec_mode.is_test();
 
             if add_to_host_exposed {
                 state.exposed_to_host.top_level_values.extend(
@@ -2870,10 +3336,19 @@ fn update<'a>(
                 );
 
                 if state.goal_phase() > Phase::SolveTypes || state.exec_mode.build_if_checks() {
-                    let layout_cache = state.layout_caches.pop().unwrap_or_else(|| {
+                    let mut layout_cache = state.layout_caches.pop().unwrap_or_else(|| {
                         LayoutCache::new(state.layout_interner.fork(), state.target_info)
                     });
 
+                    // Caches are reused across modules, but each module chooses its own defaults
+                    // for unresolved number literals.
+                    layout_cache.num_defaults = state
+                        .module_cache
+                        .num_literal_defaults
+                        .get(&module_id)
+                        .copied()
+                        .unwrap_or_default();
+
                     let typechecked = TypeCheckedModule {
                         module_id,
                         layout_cache,
@@ -2922,6 +3397,7 @@ fn update<'a>(
             module_timing,
             abilities_store,
             toplevel_expects,
+            thunk_regions,
             expectations,
         } => {
             log!("found specializations for {:?}", module_id);
@@ -2938,6 +3414,8 @@ fn update<'a>(
                 .or_default()
                 .extend(procs_base.module_thunks.iter().copied());
 
+            state.module_cache.thunk_regions.extend(thunk_regions);
+
             let found_specializations_module = FoundSpecializationsModule {
                 ident_ids,
                 layout_cache,
@@ -3100,8 +3578,103 @@ fn update<'a>(
                     debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_SPECIALIZATION);
                     debug_check_ir!(state, arena, layout_interner, ROC_CHECK_MONO_IR);
 
+                    if state.exec_mode.is_test() {
+                        effect_interpreters::interpret_effects(
+                            arena,
+                            state.root_id,
+                            &state.constrained_ident_ids[&state.root_id],
+                            &mut state.procedures,
+                        );
+
+                        debug_print_ir!(
+                            state,
+                            &layout_interner,
+                            ROC_PRINT_IR_AFTER_EFFECT_INTERPRETERS
+                        );
+                    }
+
                     let ident_ids = state.constrained_ident_ids.get_mut(&module_id).unwrap();
 
+                    tail_recursion::merge_mutual_tail_calls(
+                        arena,
+                        &mut layout_interner,
+                        module_id,
+                        ident_ids,
+                        &mut state.procedures,
+                    );
+
+                    debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_MUTUAL_TAIL_CALLS);
+
+                    overflow::lower_overflowing_arithmetic(
+                        arena,
+                        &layout_interner,
+                        state.overflow,
+                        &mut state.procedures,
+                    );
+
+                    let initialization = initialization::order_initialization(
+                        arena,
+                        module_id,
+                        ident_ids,
+                        &mut state.procedures,
+                    );
+
+                    for cycle in initialization.cycles.iter() {
+                        report_initialization_cycle(&mut state.module_cache, cycle);
+                    }
+
+                    if state.fold_constants {
+                        constant_folding::fold_constants(
+                            arena,
+                            &layout_interner,
+                            &initialization,
+                            &mut state.procedures,
+                        );
+                    }
+
+                    debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_CONSTANT_FOLDING);
+
+                    pure_calls::reuse_pure_calls(arena, &mut state.procedures);
+
+                    debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_PURE_CALLS);
+
+                    flatten_closures::flatten_single_captures(
+                        arena,
+                        &layout_interner,
+                        module_id,
+                        ident_ids,
+                        &mut state.procedures,
+                    );
+
+                    debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_FLATTEN_CLOSURES);
+
+                    bounds_checks::remove_bounds_checks(
+                        arena,
+                        &layout_interner,
+                        module_id,
+                        ident_ids,
+                        &mut state.procedures,
+                    );
+
+                    debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_BOUNDS_CHECKS);
+
+                    if state.static_data {
+                        state.static_constants = static_data::evaluate_static_constants(
+                            arena,
+                            &layout_interner,
+                            &initialization,
+                            &state.procedures,
+                        );
+                    }
+
+                    escape_analysis::stack_allocate_non_escaping(
+                        arena,
+                        &layout_interner,
+                        &mut state.procedures,
+                    );
+
+                    debug_print_ir!(state, &layout_interner, ROC_PRINT_IR_AFTER_ESCAPE_ANALYSIS);
+
                     inc_dec::insert_inc_dec_operations(
                         arena,
                         &layout_interner,
@@ -3127,6 +3700,7 @@ fn update<'a>(
                         module_id,
                         ident_ids,
                         state.target_info,
+                        &state.static_constants,
                         &mut state.procedures,
                     );
 
@@ -3286,6 +3860,36 @@ fn log_layout_stats(module_id: ModuleId, layout_cache: &LayoutCache) {
     );
 }
 
+/// Report a top-level value which depends on itself through an ability as a circular definition,
+/// in the module of that value.
+fn report_initialization_cycle(module_cache: &mut ModuleCache<'_>, cycle: &[Symbol]) {
+    use roc_problem::can::{CycleEntry, Problem, RuntimeError};
+
+    let entries = cycle
+        .iter()
+        .map(|symbol| {
+            // only the regions of the value are shown; the other entries may be functions
+            let (symbol_region, expr_region) = module_cache
+                .thunk_regions
+                .get(symbol)
+                .copied()
+                .unwrap_or_default();
+
+            CycleEntry {
+                symbol: *symbol,
+                symbol_region,
+                expr_region,
+            }
+        })
+        .collect();
+
+    module_cache
+        .can_problems
+        .entry(cycle[0].module_id())
+        .or_default()
+        .push(Problem::RuntimeError(RuntimeError::CircularDef(entries)));
+}
+
 fn finish_specialization<'a>(
     arena: &'a Bump,
     state: State<'a>,
@@ -3324,7 +3928,7 @@ fn finish_specialization<'a>(
     let entry_point = {
         let interns: &mut Interns = &mut interns;
         match state.exec_mode {
-            ExecutionMode::Test => Ok(EntryPoint::Test),
+            ExecutionMode::Test | ExecutionMode::TestPackage => Ok(EntryPoint::Test),
             ExecutionMode::Executable | ExecutionMode::ExecutableIfCheck => {
                 use PlatformPath::*;
 
@@ -3390,6 +3994,7 @@ fn finish_specialization<'a>(
     let State {
         toplevel_expects,
         procedures,
+        static_constants,
         module_cache,
         output_path,
         platform_data,
@@ -3400,6 +4005,7 @@ fn finish_specialization<'a>(
         type_problems,
         can_problems,
         sources,
+        imports,
         ..
     } = module_cache;
 
@@ -3480,8 +4086,10 @@ fn finish_specialization<'a>(
         interns,
         layout_interner,
         procedures,
+        static_constants,
         entry_point,
         sources,
+        imports,
         timings: state.timings,
         toplevel_expects,
         glue_layouts: GlueLayouts {
@@ -3551,20 +4159,36 @@ fn finish(
 
     let exposed_values = exposed_vars_by_symbol.iter().map(|x| x.0).collect();
 
+    let package_shorthands = state
+        .module_cache
+        .module_names
+        .iter()
+        .filter_map(|(module_id, module_name)| match module_name {
+            PQModuleName::Qualified(shorthand, _) => Some((*module_id, (*shorthand).into())),
+            PQModuleName::Unqualified(_) => None,
+        })
+        .collect();
+
     LoadedModule {
         module_id: state.root_id,
         interns,
         solved,
         can_problems: state.module_cache.can_problems,
         type_problems: state.module_cache.type_problems,
+        lints: state.module_cache.lints,
+        crash_summaries: state.module_cache.crash_summaries,
+        reference_summaries: state.module_cache.reference_summaries,
         declarations_by_id: state.declarations_by_id,
         dep_idents,
         exposed_aliases: exposed_aliases_by_symbol,
         exposed_values,
+        exposed_symbols_by_module: state.exposed_symbols_by_module,
+        package_shorthands,
         exposed_to_host: exposed_vars_by_symbol.into_iter().collect(),
         exposed_types_storage,
         resolved_implementations,
         sources,
+        imports: state.module_cache.imports,
         timings: state.timings,
         docs_by_module: documentation,
         abilities_store,
@@ -3846,6 +4470,14 @@ fn load_module<'a>(
         "Decode", ModuleId::DECODE
         "Hash", ModuleId::HASH
         "Json", ModuleId::JSON
+        "Rbf", ModuleId::RBF
+        "Stream", ModuleId::STREAM
+        "Regex", ModuleId::REGEX
+        "Time", ModuleId::TIME
+        "Crypto", ModuleId::CRYPTO
+        "Uuid", ModuleId::UUID
+        "Random", ModuleId::RANDOM
+        "Enum", ModuleId::ENUM
     }
 
     let (filename, opt_shorthand) = module_name_to_path(src_dir, &module_name, arc_shorthands);
@@ -4160,6 +4792,11 @@ fn parse_header<'a>(
                 }
             }
 
+            // Packages import modules of the platform as `host.Foo`, which is `pf.Foo` in this app
+            if let To::ExistingPackage(shorthand) = header.provides.to.value {
+                (*module_ids).lock().set_platform_shorthand(shorthand);
+            }
+
             let info = HeaderInfo {
                 filename,
                 is_root_module,
@@ -4304,6 +4941,23 @@ fn load_packages<'a>(
     module_ids: Arc<Mutex<PackageModuleIds<'a>>>,
     ident_ids_by_module: SharedIdentIdsByModule,
 ) {
+    #[cfg(not(target_family = "wasm"))]
+    let config = match PackageConfig::load() {
+        Ok(config) => config,
+        Err(problem) => {
+            load_messages.push(Msg::FailedToLoad(LoadingProblem::FormattedReport(format!(
+                "{problem}\n"
+            ))));
+
+            return;
+        }
+    };
+
+    // In offline mode, we report every package that's missing from the cache at once,
+    // so that they can all be fetched in one go (e.g. on another machine).
+    #[cfg(not(target_family = "wasm"))]
+    let mut missing_while_offline = Vec::new();
+
     // Load all the packages
     for Loc { value: entry, .. } in packages.iter() {
         let PackageEntry {
@@ -4316,7 +4970,29 @@ fn load_packages<'a>(
             ..
         } = entry;
 
-        let src = package_name.to_str();
+        let mut src = package_name.to_str();
+
+        // A package can also be specified by name and version constraint,
+        // e.g. "basic-cli@^0.3.0", in which case we resolve it to a URL via roc.lock
+        // (or, if it isn't locked yet, via the registry index).
+        #[cfg(not(target_family = "wasm"))]
+        if let Some(result) = VersionedPackage::from_src(src) {
+            match result
+                .map_err(LockProblem::InvalidConstraint)
+                .and_then(|versioned| roc_packaging::lock::resolve_url(&versioned, &cwd, &config))
+            {
+                Ok(url) => {
+                    src = arena.alloc_str(&url);
+                }
+                Err(problem) => {
+                    load_messages.push(Msg::FailedToLoad(LoadingProblem::FormattedReport(
+                        format!("I could not resolve the package \"{src}\":\n\n{problem}\n"),
+                    )));
+
+                    continue;
+                }
+            }
+        }
 
         // find the `package` or `platform` module on disk,
         // downloading it into a cache dir first if necessary.
@@ -4329,10 +5005,18 @@ fn load_packages<'a>(
                 // TODO we should do this async; however, with the current
                 // architecture of file.rs (which doesn't use async/await),
                 // this would be very difficult!
-                let (package_dir, opt_root_module) = cache::install_package(roc_cache_dir, src)
-                    .unwrap_or_else(|err| {
-                        todo!("TODO gracefully handle package install error {:?}", err);
-                    });
+                let (package_dir, opt_root_module) =
+                    match cache::install_package(roc_cache_dir, &config, src) {
+                        Ok(installed) => installed,
+                        Err(roc_packaging::https::Problem::NotCachedWhileOffline) => {
+                            missing_while_offline.push(src);
+
+                            continue;
+                        }
+                        Err(err) => {
+                            todo!("TODO gracefully handle package install error {:?}", err);
+                        }
+                    };
 
                 // You can optionally specify the root module using the URL fragment,
                 // e.g. #foo.roc
@@ -4367,6 +5051,23 @@ fn load_packages<'a>(
             }
         }
     }
+
+    #[cfg(not(target_family = "wasm"))]
+    if !missing_while_offline.is_empty() {
+        let mut report = String::from(
+            "I'm in offline mode, so I can't download packages, and these ones aren't in the package cache yet:\n\n",
+        );
+
+        for url in missing_while_offline {
+            report.push_str("    ");
+            report.push_str(url);
+            report.push('\n');
+        }
+
+        report.push_str("\nTo download them, turn off offline mode (it was turned on by the ROC_OFFLINE environment variable or by `offline = true` in your packages.conf file).\n");
+
+        load_messages.push(Msg::FailedToLoad(LoadingProblem::FormattedReport(report)));
+    }
 }
 
 /// Load a module by its filename
@@ -4521,6 +5222,7 @@ fn build_header<'a>(
     }
 
     let mut exposed: Vec<Symbol> = Vec::with_capacity(num_exposes);
+    let mut host_imports = Vec::new();
 
     // Make sure the module_ids has ModuleIds for all our deps,
     // then record those ModuleIds in can_module_ids for later.
@@ -4552,6 +5254,13 @@ fn build_header<'a>(
         for (qualified_module_name, exposed_idents, region) in imported.into_iter() {
             let pq_module_name = qualified_module_name.into_pq_module_name(opt_shorthand);
 
+            // e.g. for `imports [host.Sha]`, remember to check that the platform exposes `Sha`
+            if let PQModuleName::Qualified(HOST_SHORTHAND, name) = &pq_module_name {
+                host_imports.push((name.clone(), region));
+            }
+
+            let pq_module_name = module_ids.resolve_host_shorthand(pq_module_name);
+
             let module_id = module_ids.get_or_insert(&pq_module_name);
 
             imported_modules.insert(module_id, region);
@@ -4764,6 +5473,7 @@ fn build_header<'a>(
             header_comments,
             module_timing,
             defined_values,
+            host_imports,
         },
     ))
 }
@@ -5139,13 +5849,15 @@ fn run_solve_solve(
                 })
         };
 
-        // Expose anything that is explicitly exposed by the header, or is a specialization of an
-        // ability.
+        // Expose anything that is explicitly exposed by the header, is a specialization of an
+        // ability, or is the default implementation of an ability member, which opaques in other
+        // modules may forward to.
         let exposed_vars_by_symbol: Vec<_> = solved_env
             .vars_by_symbol()
             .filter(|(k, _)| {
                 exposed_symbols.contains(k)
                     || is_specialization_symbol(*k)
+                    || abilities_store.is_default_implementation(*k)
                     || k.is_exposed_for_builtin_derivers()
             })
             .collect();
@@ -5195,34 +5907,13 @@ fn run_solve<'a>(
     let loc_dbgs = std::mem::take(&mut module.loc_dbgs);
     let module = module;
 
-    let (solved_subs, solved_implementations, exposed_vars_by_symbol, problems, abilities_store) = {
-        if module_id.is_builtin() {
-            match cached_types.lock().remove(&module_id) {
-                None => run_solve_solve(
-                    exposed_for_module,
-                    types,
-                    constraints,
-                    constraint,
-                    pending_derives,
-                    var_store,
-                    module,
-                    derived_module,
-                ),
-                Some(TypeState {
-                    subs,
-                    exposed_vars_by_symbol,
-                    abilities,
-                    solved_implementations,
-                }) => (
-                    Solved(subs),
-                    solved_implementations,
-                    exposed_vars_by_symbol,
-                    vec![],
-                    abilities,
-                ),
-            }
-        } else {
-            run_solve_solve(
+    // Builtins, and package modules that were precompiled, have their types solved already
+    let cached = cached_types.lock().remove(&module_id);
+    let is_cached = cached.is_some();
+
+    let (solved_subs, solved_implementations, exposed_vars_by_symbol, problems, abilities_store) =
+        match cached {
+            None => run_solve_solve(
                 exposed_for_module,
                 types,
                 constraints,
@@ -5231,11 +5922,33 @@ fn run_solve<'a>(
                 var_store,
                 module,
                 derived_module,
-            )
-        }
-    };
+            ),
+            Some(TypeState {
+                subs,
+                exposed_vars_by_symbol,
+                abilities,
+                solved_implementations,
+            }) => (
+                Solved(subs),
+                solved_implementations,
+                exposed_vars_by_symbol,
+                vec![],
+                abilities,
+            ),
+        };
 
     let mut solved_subs = solved_subs;
+
+    let lints = if is_cached {
+        Vec::new()
+    } else {
+        roc_solve::single_implementation::single_implementations(
+            solved_subs.inner_mut(),
+            &decls,
+            &exposed_vars_by_symbol,
+        )
+    };
+
     let exposed_types = roc_solve::module::exposed_types_storage_subs(
         module_id,
         &mut solved_subs,
@@ -5247,6 +5960,7 @@ fn run_solve<'a>(
     let solved_module = SolvedModule {
         exposed_vars_by_symbol,
         problems,
+        lints,
         aliases,
         solved_implementations,
         exposed_types,
@@ -5420,6 +6134,7 @@ fn canonicalize_and_constrain<'a>(
         imported_modules,
         mut module_timing,
         symbols_from_requires,
+        syntax_problems,
         ..
     } = parsed;
 
@@ -5486,6 +6201,7 @@ fn canonicalize_and_constrain<'a>(
                 exposed_module_ids,
                 module_output.exposed_symbols.clone(),
                 parsed.header_comments,
+                &roc_can::constant::fold_constants(&module_output.declarations),
             );
 
             Some(docs)
@@ -5566,6 +6282,7 @@ fn canonicalize_and_constrain<'a>(
         abilities_store: module_output.scope.abilities_store,
         loc_expects: module_output.loc_expects,
         loc_dbgs: module_output.loc_dbgs,
+        num_literal_defaults: module_output.num_literal_defaults,
     };
 
     let constrained_module = ConstrainedModule {
@@ -5582,24 +6299,36 @@ fn canonicalize_and_constrain<'a>(
         pending_derives: module_output.pending_derives,
     };
 
+    // Syntax errors come first, since they're likely the cause of some of the other problems.
+    let mut canonicalization_problems = syntax_problems;
+    canonicalization_problems.extend(module_output.problems);
+
     CanAndCon {
         constrained_module,
-        canonicalization_problems: module_output.problems,
+        canonicalization_problems,
         module_docs,
     }
 }
 
-fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, LoadingProblem<'a>> {
+fn parse<'a>(
+    arena: &'a Bump,
+    header: ModuleHeader<'a>,
+    recover_from_syntax_errors: bool,
+) -> Result<Msg<'a>, LoadingProblem<'a>> {
     let mut module_timing = header.module_timing;
     let parse_start = Instant::now();
     let source = header.parse_state.original_bytes();
     let parse_state = header.parse_state;
-    let mut parsed_defs = match module_defs().parse(arena, parse_state.clone(), 0) {
-        Ok((_, success, _state)) => success,
-        Err((_, fail)) => {
-            return Err(LoadingProblem::ParsingFailed(
-                fail.into_file_error(header.module_path, &parse_state),
-            ));
+    let (mut parsed_defs, recovered_errors) = if recover_from_syntax_errors {
+        module_defs_recovering(arena, parse_state)
+    } else {
+        match module_defs().parse(arena, parse_state.clone(), 0) {
+            Ok((_, success, _state)) => (success, Vec::new()),
+            Err((_, fail)) => {
+                return Err(LoadingProblem::ParsingFailed(
+                    fail.into_file_error(header.module_path, &parse_state),
+                ));
+            }
         }
     };
     for value in header.defined_values.into_iter() {
@@ -5632,6 +6361,8 @@ fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, Loadi
         ..
     } = header;
 
+    let syntax_problems = recovered_syntax_problems(module_id, &module_path, src, recovered_errors);
+
     let parsed = ParsedModule {
         module_id,
         module_path,
@@ -5642,6 +6373,7 @@ fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, Loadi
         exposed_ident_ids,
         exposed_imports,
         parsed_defs,
+        syntax_problems,
         symbols_from_requires,
         header_type,
         header_comments: header_docs,
@@ -5650,6 +6382,51 @@ fn parse<'a>(arena: &'a Bump, header: ModuleHeader<'a>) -> Result<Msg<'a>, Loadi
     Ok(Msg::Parsed(parsed))
 }
 
+/// Render the syntax errors that parsing recovered from, so they can be reported alongside
+/// the module's canonicalization problems.
+fn recovered_syntax_problems<'a>(
+    module_id: ModuleId,
+    module_path: &Path,
+    src: &'a str,
+    recovered_errors: Vec<(Region, SyntaxError<'a>)>,
+) -> Vec<roc_problem::can::Problem> {
+    use roc_reporting::report::{parse_problem, RocDocAllocator};
+
+    if recovered_errors.is_empty() {
+        return Vec::new();
+    }
+
+    let src_lines = src.lines().collect::<Vec<_>>();
+    let interns = Interns::default();
+    let alloc = RocDocAllocator::new(&src_lines, module_id, &interns);
+    let lines = LineInfo::new(src);
+
+    recovered_errors
+        .into_iter()
+        .map(|(region, problem)| {
+            let file_error = SourceError {
+                problem,
+                bytes: src.as_bytes(),
+            }
+            .into_file_error(module_path.to_path_buf());
+            let mut report =
+                parse_problem(&alloc, &lines, module_path.to_path_buf(), 0, file_error);
+
+            // The title goes in the problem, so it gets rendered as the report's header later.
+            let title = std::mem::take(&mut report.title);
+            let mut message = String::new();
+
+            report.render_ci(&mut message, &alloc);
+
+            roc_problem::can::Problem::SyntaxError {
+                title,
+                region,
+                message,
+            }
+        })
+        .collect()
+}
+
 fn exposed_from_import<'a>(
     entry: &ImportsEntry<'a>,
 ) -> Option<(QualifiedModuleName<'a>, Vec<Loc<Ident>>)> {
@@ -5854,6 +6631,7 @@ fn build_pending_specializations<'a>(
     let find_specializations_start = Instant::now();
 
     let mut module_thunks = bumpalo::collections::Vec::new_in(arena);
+    let mut thunk_regions = VecMap::default();
     let mut toplevel_expects = ToplevelExpects::default();
 
     let mut procs_base = ProcsBase {
@@ -5893,12 +6671,24 @@ fn build_pending_specializations<'a>(
         let symbol = declarations.symbols[index].value;
         let expr_var = declarations.variables[index];
 
-        let is_host_exposed = exposed_to_host.top_level_values.contains_key(&symbol);
+        // When running expects, nothing calls the effect interpreters until the effect_interpreters
+        // pass replaces the effects with them after specialization, so they must be specialized
+        // as if they were exposed to the host.
+        let is_effect_interpreter = build_expects
+            && ident_ids
+                .get_name(symbol.ident_id())
+                .and_then(effect_interpreters::interpreted_effect)
+                .is_some();
+
+        let is_host_exposed =
+            exposed_to_host.top_level_values.contains_key(&symbol) || is_effect_interpreter;
 
         // TODO remove clones (with drain)
         let annotation = declarations.annotations[index].clone();
         let body = declarations.expressions[index].clone();
 
+        crash_report::set_region(body.region.start().offset, body.region.end().offset);
+
         let tag = declarations.declarations[index];
         match tag {
             Value => {
@@ -5957,6 +6747,8 @@ fn build_pending_specializations<'a>(
                     _ => {
                         // mark this symbols as a top-level thunk before any other work on the procs
                         module_thunks.push(symbol);
+                        thunk_regions
+                            .insert(symbol, (declarations.symbols[index].region, body.region));
 
                         let proc = PartialProc {
                             annotation: expr_var,
@@ -6280,6 +7072,7 @@ fn build_pending_specializations<'a>(
         module_timing,
         abilities_store,
         toplevel_expects,
+        thunk_regions,
         expectations,
     }
 }
@@ -6430,6 +7223,27 @@ fn run_task<'a>(
 ) -> Result<(), LoadingProblem<'a>> {
     use BuildTask::*;
 
+    // if the compiler panics during this task, the crash report says what it was working on
+    let (phase, module_id) = match &task {
+        LoadModule { .. } => ("loading a module header", None),
+        Parse { header, .. } => {
+            crash_report::register_module(header.module_id.index() as u32, &header.module_path);
+
+            ("parsing", Some(header.module_id))
+        }
+        CanonicalizeAndConstrain { parsed, .. } => (
+            "canonicalization and constraint generation",
+            Some(parsed.module_id),
+        ),
+        Solve { module, .. } => ("type checking", Some(module.module_id)),
+        BuildPendingSpecializations { module_id, .. } => {
+            ("finding specializations", Some(*module_id))
+        }
+        MakeSpecializations { module_id, .. } => ("making specializations", Some(*module_id)),
+    };
+    let _crash_context =
+        crash_report::enter_phase(phase, module_id.map(|module_id| module_id.index() as u32));
+
     let msg = match task {
         LoadModule {
             module_name,
@@ -6446,7 +7260,10 @@ fn run_task<'a>(
             ident_ids_by_module,
         )
         .map(|HeaderOutput { msg, .. }| msg),
-        Parse { header } => parse(arena, header),
+        Parse {
+            header,
+            recover_from_syntax_errors,
+        } => parse(arena, header, recover_from_syntax_errors),
         CanonicalizeAndConstrain {
             parsed,
             module_ids,
@@ -6570,32 +7387,45 @@ fn run_task<'a>(
 fn to_import_cycle_report(
     module_ids: ModuleIds,
     all_ident_ids: IdentIdsByModule,
-    import_cycle: Vec<ModuleId>,
+    hops: Vec<ImportCycleHop<'_>>,
     filename: PathBuf,
     render: RenderTarget,
 ) -> String {
     use roc_reporting::report::{Report, RocDocAllocator, DEFAULT_PALETTE};
     use ven_pretty::DocAllocator;
 
-    // import_cycle looks like CycleModule, Import1, ..., ImportN, CycleModule
-    // In a self-referential case, it just looks like CycleModule, CycleModule.
-    debug_assert!(import_cycle.len() >= 2);
-    let source_of_cycle = import_cycle.first().unwrap();
-
-    // We won't be printing any lines for this report, so this is okay.
-    // TODO: it would be nice to show how each module imports another in the cycle.
-    let src_lines = &[];
+    // The hops look like CycleModule -> Import1, ..., ImportN -> CycleModule.
+    // In a self-referential case, it's just CycleModule -> CycleModule.
+    debug_assert!(!hops.is_empty());
+    let source_of_cycle = hops.first().unwrap().importer;
 
     let interns = Interns {
         module_ids,
         all_ident_ids,
     };
-    let alloc = RocDocAllocator::new(src_lines, *source_of_cycle, &interns);
 
-    let doc = alloc.stack([
+    // Each hop's import is in a different module, so each needs an allocator with its own lines.
+    let srcs: Vec<&str> = hops
+        .iter()
+        .map(|hop| match &hop.site {
+            Some((_, src, _)) => std::str::from_utf8(src).unwrap_or_default(),
+            None => "",
+        })
+        .collect();
+    let hop_lines: Vec<Vec<&str>> = srcs.iter().map(|src| src.split('\n').collect()).collect();
+    let hop_allocs: Vec<RocDocAllocator> = hops
+        .iter()
+        .zip(hop_lines.iter())
+        .map(|(hop, lines)| RocDocAllocator::new(lines, hop.importer, &interns))
+        .collect();
+
+    let src_lines = &[];
+    let alloc = RocDocAllocator::new(src_lines, source_of_cycle, &interns);
+
+    let mut docs = vec![
         alloc.concat([
             alloc.reflow("I can't compile "),
-            alloc.module(*source_of_cycle),
+            alloc.module(source_of_cycle),
             alloc.reflow(
                 " because it depends on itself through the following chain of module imports:",
             ),
@@ -6603,15 +7433,76 @@ fn to_import_cycle_report(
         roc_reporting::report::cycle(
             &alloc,
             4,
-            alloc.module(*source_of_cycle),
-            import_cycle
-                .into_iter()
-                .skip(1)
-                .map(|module| alloc.module(module))
-                .collect(),
+            alloc.module(source_of_cycle),
+            hops.iter().map(|hop| alloc.module(hop.imported)).collect(),
         ),
-        alloc.reflow("Cyclic dependencies are not allowed in Roc! Can you restructure a module in this import chain so that it doesn't have to depend on itself?")
-    ]);
+    ];
+
+    for ((hop, hop_alloc), src) in hops.iter().zip(hop_allocs.iter()).zip(srcs.iter()) {
+        if let Some((_, _, region)) = &hop.site {
+            let line_info = LineInfo::new(src);
+
+            docs.push(alloc.concat([
+                alloc.module(hop.importer),
+                alloc.reflow(" imports "),
+                alloc.module(hop.imported),
+                alloc.reflow(" here:"),
+            ]));
+            docs.push(hop_alloc.region(line_info.convert_region(*region)));
+        }
+    }
+
+    docs.push(alloc.reflow("Cyclic dependencies are not allowed in Roc! Can you restructure a module in this import chain so that it doesn't have to depend on itself?"));
+
+    // Suggest breaking the import which the fewest things depend on.
+    let (easiest, used) = hops
+        .iter()
+        .zip(srcs.iter())
+        .map(|(hop, src)| {
+            let module_name = interns.module_name(hop.imported).as_str();
+
+            (hop, names_used_from_module(src, module_name))
+        })
+        .min_by_key(|(_, used)| used.len())
+        .unwrap();
+
+    let suggestion = if easiest.importer == easiest.imported {
+        alloc.reflow(
+            "A module can use its own definitions without importing itself, so this import can just be removed.",
+        )
+    } else if used.is_empty() {
+        alloc.concat([
+            alloc.module(easiest.importer),
+            alloc.reflow(" doesn't seem to use anything from "),
+            alloc.module(easiest.imported),
+            alloc.reflow(", so removing that import may be all it takes to break the cycle."),
+        ])
+    } else {
+        let mut names = Vec::with_capacity(2 * used.len());
+
+        for (index, name) in used.into_iter().enumerate() {
+            if index > 0 {
+                names.push(alloc.text(", "));
+            }
+
+            names.push(alloc.ident(Ident::from(name)));
+        }
+
+        alloc.concat([
+            alloc.module(easiest.importer),
+            alloc.reflow(" only uses "),
+            alloc.concat(names),
+            alloc.reflow(" from "),
+            alloc.module(easiest.imported),
+            alloc.reflow(". Moving that into "),
+            alloc.module(easiest.importer),
+            alloc.reflow(", or into a new module which both can import, would break the cycle."),
+        ])
+    };
+
+    docs.push(alloc.tip().append(suggestion));
+
+    let doc = alloc.stack(docs);
 
     let report = Report {
         filename,
@@ -6626,6 +7517,140 @@ fn to_import_cycle_report(
     buf
 }
 
+fn to_unexposed_host_module_report(
+    module_ids: ModuleIds,
+    all_ident_ids: IdentIdsByModule,
+    host_import: HostImport<'_>,
+    platform_exposes: Option<Vec<ModuleName>>,
+    render: RenderTarget,
+) -> String {
+    use roc_reporting::report::{Report, RocDocAllocator, DEFAULT_PALETTE};
+    use ven_pretty::DocAllocator;
+
+    let HostImport {
+        importer,
+        module_name,
+        site: (filename, src, region),
+    } = host_import;
+
+    let interns = Interns {
+        module_ids,
+        all_ident_ids,
+    };
+
+    let src = std::str::from_utf8(src).unwrap_or_default();
+    let src_lines: Vec<&str> = src.split('\n').collect();
+    let line_info = LineInfo::new(src);
+    let alloc = RocDocAllocator::new(&src_lines, importer, &interns);
+
+    let mut docs = vec![
+        alloc.concat([
+            alloc.module(importer),
+            alloc.reflow(" imports "),
+            alloc.module_name(module_name.clone()),
+            alloc.reflow(" from the platform here:"),
+        ]),
+        alloc.region(line_info.convert_region(region)),
+    ];
+
+    let title = match platform_exposes {
+        Some(exposes) => {
+            docs.push(alloc.concat([
+                alloc.reflow("But the platform doesn't expose a "),
+                alloc.module_name(module_name),
+                alloc.reflow(" module. Through "),
+                alloc.keyword("host"),
+                alloc.reflow(", packages can only import the modules a platform exposes."),
+            ]));
+
+            if exposes.is_empty() {
+                docs.push(alloc.reflow("This platform doesn't expose any modules."));
+            } else {
+                docs.push(alloc.reflow("These are the modules it exposes:"));
+                docs.push(
+                    alloc
+                        .vcat(
+                            exposes
+                                .into_iter()
+                                .map(|exposed| alloc.module_name(exposed)),
+                        )
+                        .indent(4),
+                );
+            }
+
+            "UNEXPOSED HOST MODULE"
+        }
+        None => {
+            docs.push(alloc.concat([
+                alloc.reflow("But there is no platform to import it from. "),
+                alloc.keyword("host"),
+                alloc.reflow(" refers to the platform of the app using this package, so this module can only be checked as part of an app."),
+            ]));
+
+            "NO PLATFORM FOR HOST IMPORT"
+        }
+    };
+
+    let doc = alloc.stack(docs);
+
+    let report = Report {
+        filename,
+        doc,
+        title: title.to_string(),
+        severity: Severity::RuntimeError,
+    };
+
+    let mut buf = String::new();
+    let palette = DEFAULT_PALETTE;
+    report.render(render, &mut buf, &alloc, &palette);
+    buf
+}
+
+/// The distinct names a module's source refers to through the given module name,
+/// e.g. `["map", "Parser"]` for source which uses `Parse.map` and `Parse.Parser`,
+/// or `["map"]` for a header which imports `Parse.{ map }`.
+fn names_used_from_module<'s>(src: &'s str, module_name: &str) -> Vec<&'s str> {
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_';
+    let prefix = format!("{module_name}.");
+    let mut used = Vec::new();
+
+    for (start, _) in src.match_indices(&prefix) {
+        let is_start_of_name = src[..start]
+            .chars()
+            .next_back()
+            .map_or(true, |c| !is_ident_char(c) && c != '.');
+
+        if !is_start_of_name {
+            continue;
+        }
+
+        let rest = &src[start + prefix.len()..];
+
+        if let Some(exposed) = rest.strip_prefix('{') {
+            // An exposing import in the header, like `Parse.{ map, Parser }`
+            let exposed = exposed.split('}').next().unwrap_or_default();
+
+            used.extend(
+                exposed
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty()),
+            );
+        } else {
+            let end = rest.find(|c| !is_ident_char(c)).unwrap_or(rest.len());
+
+            if end > 0 {
+                used.push(&rest[..end]);
+            }
+        }
+    }
+
+    used.sort_unstable();
+    used.dedup();
+
+    used
+}
+
 fn to_incorrect_module_name_report<'a>(
     module_ids: ModuleIds,
     all_ident_ids: IdentIdsByModule,
@@ -6,6 +6,7 @@
 use roc_module::symbol::ModuleId;
 pub mod docs;
 pub mod file;
+pub mod precompiled;
 mod work;
 
 #[cfg(target_family = "wasm")]
@@ -24,4 +25,12 @@ pub const BUILTIN_MODULES: &[(ModuleId, &str)] = &[
     (ModuleId::DECODE, "Decode"),
     (ModuleId::HASH, "Hash"),
     (ModuleId::JSON, "Json"),
+    (ModuleId::RBF, "Rbf"),
+    (ModuleId::STREAM, "Stream"),
+    (ModuleId::REGEX, "Regex"),
+    (ModuleId::TIME, "Time"),
+    (ModuleId::CRYPTO, "Crypto"),
+    (ModuleId::UUID, "Uuid"),
+    (ModuleId::RANDOM, "Random"),
+    (ModuleId::ENUM, "Enum"),
 ];
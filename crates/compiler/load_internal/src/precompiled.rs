@@ -0,0 +1,357 @@
+//! Precompiled package artifacts.
+//!
+//! A package can ship the solved types of its modules next to its `main.roc`, so apps that
+//! depend on it skip constraint generation and solving for those modules, the same way the
+//! builtins' types are cached. The modules are still canonicalized from their source, and
+//! specialization still happens in the final app: Roc's mono IR only exists in specialized form,
+//! so there is no generic mono IR to ship.
+//!
+//! Symbols contain module ids, which are assigned in load order and so differ between the
+//! compilation that wrote the artifact and the one reading it. Each module records the name of
+//! every module its types could mention, and the symbols get remapped by name when the
+//! artifact is read. Each of those modules' source is hashed too, and the artifact is only used
+//! if every hash still matches.
+use roc_can::module::TypeState;
+use roc_collections::MutMap;
+use roc_module::symbol::{ModuleId, PQModuleName, PackageModuleIds, Symbol};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+pub use roc_packaging::tarball::PRECOMPILED_PACKAGE_FILENAME;
+
+const MAGIC: &[u8; 8] = b"rocpkg\0\x01";
+
+/// Artifacts are only read by the compiler version that wrote them, because the serialized
+/// types are in the compiler's in-memory layout.
+const ROC_VERSION: &str = include_str!("../../../../version.txt");
+
+pub type SourceHash = [u8; 32];
+
+pub fn source_hash(src: &str) -> SourceHash {
+    *blake3::hash(src.as_bytes()).as_bytes()
+}
+
+/// A module that a precompiled module's types depend on
+#[derive(Debug, Clone)]
+pub struct PrecompiledDependency {
+    /// The index of the module's id in the compilation that wrote the artifact
+    pub index: u32,
+    pub name: String,
+    pub source_hash: SourceHash,
+}
+
+#[derive(Debug, Clone)]
+pub struct PrecompiledModule {
+    pub name: String,
+    /// Every non-builtin module loaded while solving this one, including itself
+    pub dependencies: Vec<PrecompiledDependency>,
+    /// A serialized `TypeState`
+    pub type_state: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PrecompiledPackage {
+    pub modules: Vec<PrecompiledModule>,
+}
+
+impl PrecompiledPackage {
+    pub fn get(&self, module_name: &str) -> Option<&PrecompiledModule> {
+        self.modules
+            .iter()
+            .find(|module| module.name == module_name)
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        write_bytes(writer, ROC_VERSION.as_bytes())?;
+        write_len(writer, self.modules.len())?;
+
+        for module in self.modules.iter() {
+            write_bytes(writer, module.name.as_bytes())?;
+            write_len(writer, module.dependencies.len())?;
+
+            for dependency in module.dependencies.iter() {
+                writer.write_all(&dependency.index.to_le_bytes())?;
+                write_bytes(writer, dependency.name.as_bytes())?;
+                writer.write_all(&dependency.source_hash)?;
+            }
+
+            write_bytes(writer, &module.type_state)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns `None` if the bytes are not an artifact written by this version of the compiler.
+    pub fn read(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader { bytes };
+
+        if reader.take(MAGIC.len())? != MAGIC || reader.bytes_field()? != ROC_VERSION.as_bytes() {
+            return None;
+        }
+
+        let mut modules = Vec::new();
+
+        for _ in 0..reader.read_len()? {
+            let name = reader.string()?;
+            let mut dependencies = Vec::new();
+
+            for _ in 0..reader.read_len()? {
+                let index = u32::from_le_bytes(reader.take(4)?.try_into().ok()?);
+                let name = reader.string()?;
+                let source_hash = reader.take(32)?.try_into().ok()?;
+
+                dependencies.push(PrecompiledDependency {
+                    index,
+                    name,
+                    source_hash,
+                });
+            }
+
+            let type_state = reader.bytes_field()?.to_vec();
+
+            modules.push(PrecompiledModule {
+                name,
+                dependencies,
+                type_state,
+            });
+        }
+
+        if !reader.bytes.is_empty() {
+            return None;
+        }
+
+        Some(Self { modules })
+    }
+
+    pub fn path_in(package_dir: &Path) -> PathBuf {
+        package_dir.join(PRECOMPILED_PACKAGE_FILENAME)
+    }
+
+    /// The artifact of the package rooted at `package_dir`, if it has a usable one
+    pub fn read_from_dir(package_dir: &Path) -> Option<Self> {
+        Self::read(&fs::read(Self::path_in(package_dir)).ok()?)
+    }
+}
+
+impl PrecompiledModule {
+    /// The solved types of this module, with its symbols remapped to the module ids of the
+    /// current compilation. The module's own package is imported under `shorthand`.
+    ///
+    /// Returns `None` if any module the types depend on has changed since the artifact was
+    /// written, has not been loaded, or belongs to another package.
+    pub fn type_state(
+        &self,
+        shorthand: &str,
+        module_ids: &PackageModuleIds<'_>,
+        sources: &MutMap<ModuleId, (PathBuf, &str)>,
+    ) -> Option<TypeState> {
+        let mut remapped: Vec<Option<ModuleId>> = Vec::new();
+
+        for dependency in self.dependencies.iter() {
+            let pq_name = PQModuleName::Qualified(shorthand, dependency.name.as_str().into());
+            let module_id = module_ids.get_id(&pq_name)?;
+            let (_, src) = sources.get(&module_id)?;
+
+            if source_hash(src) != dependency.source_hash {
+                return None;
+            }
+
+            let index = dependency.index as usize;
+
+            if remapped.len() <= index {
+                remapped.resize(index + 1, None);
+            }
+
+            remapped[index] = Some(module_id);
+        }
+
+        let remap = |symbol: Symbol| {
+            let module_id = symbol.module_id();
+
+            let module_id =
+                if module_id == ModuleId::DERIVED_SYNTH || module_id == ModuleId::DERIVED_GEN {
+                    // The derived modules' idents are generated during solving, so they can't be
+                    // matched up between compilations.
+                    return None;
+                } else if module_id.is_builtin() {
+                    // Builtins have the same ids in every compilation
+                    module_id
+                } else {
+                    (*remapped.get(module_id.index())?)?
+                };
+
+            Some(Symbol::new(module_id, symbol.ident_id()))
+        };
+
+        let (type_state, _) = TypeState::deserialize(&self.type_state);
+
+        type_state.try_map_symbols(&remap)
+    }
+}
+
+fn write_len(writer: &mut impl Write, len: usize) -> io::Result<()> {
+    writer.write_all(&(len as u64).to_le_bytes())
+}
+
+fn write_bytes(writer: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+    write_len(writer, bytes.len())?;
+    writer.write_all(bytes)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        Some(taken)
+    }
+
+    fn read_len(&mut self) -> Option<usize> {
+        let bytes = self.take(8)?.try_into().ok()?;
+
+        usize::try_from(u64::from_le_bytes(bytes)).ok()
+    }
+
+    fn bytes_field(&mut self) -> Option<&'a [u8]> {
+        let len = self.read_len()?;
+
+        self.take(len)
+    }
+
+    fn string(&mut self) -> Option<String> {
+        String::from_utf8(self.bytes_field()?.to_vec()).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn package() -> PrecompiledPackage {
+        PrecompiledPackage {
+            modules: vec![PrecompiledModule {
+                name: "Json.Decode".to_string(),
+                dependencies: vec![PrecompiledDependency {
+                    index: 7,
+                    name: "Json.Decode".to_string(),
+                    source_hash: source_hash("interface Json.Decode exposes [] imports []"),
+                }],
+                type_state: vec![1, 2, 3],
+            }],
+        }
+    }
+
+    fn written(package: &PrecompiledPackage) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        package.write(&mut bytes).unwrap();
+
+        bytes
+    }
+
+    #[test]
+    fn artifact_lives_next_to_main() {
+        let package_dir = Path::new("packages").join("json");
+
+        assert_eq!(
+            PrecompiledPackage::path_in(&package_dir),
+            package_dir.join("precompiled.rocpkg")
+        );
+    }
+
+    #[test]
+    fn write_then_read() {
+        let read = PrecompiledPackage::read(&written(&package())).unwrap();
+        let module = read.get("Json.Decode").unwrap();
+
+        assert_eq!(module.dependencies.len(), 1);
+        assert_eq!(module.dependencies[0].index, 7);
+        assert_eq!(module.dependencies[0].name, "Json.Decode");
+        assert_eq!(
+            module.dependencies[0].source_hash,
+            package().modules[0].dependencies[0].source_hash
+        );
+        assert_eq!(module.type_state, vec![1, 2, 3]);
+        assert!(read.get("Json.Encode").is_none());
+    }
+
+    #[test]
+    fn read_from_dir() {
+        let package_dir = tempfile::tempdir().unwrap();
+
+        // A package without an artifact is solved from source as usual
+        assert!(PrecompiledPackage::read_from_dir(package_dir.path()).is_none());
+
+        fs::write(
+            PrecompiledPackage::path_in(package_dir.path()),
+            written(&package()),
+        )
+        .unwrap();
+
+        let read = PrecompiledPackage::read_from_dir(package_dir.path()).unwrap();
+        assert!(read.get("Json.Decode").is_some());
+    }
+
+    #[test]
+    fn reject_foreign_artifacts() {
+        let bytes = written(&package());
+
+        // Not an artifact at all
+        assert!(PrecompiledPackage::read(b"app \"hello\"").is_none());
+
+        // Written by another compiler version
+        let mut other_version = MAGIC.to_vec();
+        write_bytes(&mut other_version, b"0.0.0-other").unwrap();
+        other_version.extend_from_slice(&bytes[other_version.len()..]);
+        assert!(PrecompiledPackage::read(&other_version).is_none());
+
+        // Truncated, or with trailing bytes
+        assert!(PrecompiledPackage::read(&bytes[..bytes.len() - 1]).is_none());
+
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert!(PrecompiledPackage::read(&trailing).is_none());
+    }
+
+    #[test]
+    fn no_type_state_for_changed_or_missing_dependencies() {
+        let module = &package().modules[0];
+        let mut module_ids = PackageModuleIds::default();
+        let mut sources = MutMap::default();
+
+        // The module has not been loaded
+        assert!(module.type_state("json", &module_ids, &sources).is_none());
+
+        let module_id =
+            module_ids.get_or_insert(&PQModuleName::Qualified("json", "Json.Decode".into()));
+
+        // Loaded under another package's shorthand
+        sources.insert(
+            module_id,
+            (
+                PathBuf::from("Json/Decode.roc"),
+                "interface Json.Decode exposes [] imports []",
+            ),
+        );
+        assert!(module.type_state("other", &module_ids, &sources).is_none());
+
+        // The source changed since the artifact was written
+        sources.insert(
+            module_id,
+            (
+                PathBuf::from("Json/Decode.roc"),
+                "interface Json.Decode exposes [decode] imports []",
+            ),
+        );
+        assert!(module.type_state("json", &module_ids, &sources).is_none());
+    }
+}
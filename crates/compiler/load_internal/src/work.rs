@@ -231,18 +231,21 @@ impl<'a> Dependencies<'a> {
         module_id: ModuleId,
         target: ModuleId,
     ) -> Vec<ModuleId> {
-        let mut stack = vec![module_id];
+        // A breadth-first search, so that we report the shortest cycle
+        let mut queue = std::collections::VecDeque::from([module_id]);
         let mut backlinks = VecMap::with_capacity(16);
         let mut found_import = false;
-        while let Some(module) = stack.pop() {
+        while let Some(module) = queue.pop_front() {
             if module == target {
                 found_import = true;
                 break;
             }
             if let Some(dependencies) = self.make_specializations_dependents.0.get(&module) {
                 for import in dependencies.succ.iter() {
-                    backlinks.insert(*import, module);
-                    stack.push(*import);
+                    if *import != module_id && !backlinks.contains_key(import) {
+                        backlinks.insert(*import, module);
+                        queue.push_back(*import);
+                    }
                 }
             }
         }
@@ -17,7 +17,7 @@ mod helpers;
 use crate::helpers::fixtures_dir;
 use bumpalo::Bump;
 use roc_can::module::ExposedByModule;
-use roc_load_internal::file::{ExecutionMode, LoadConfig, Threading};
+use roc_load_internal::file::{ExecutionMode, LoadConfig, OverflowMode, Threading};
 use roc_load_internal::file::{LoadResult, LoadStart, LoadedModule, LoadingProblem};
 use roc_module::ident::ModuleName;
 use roc_module::symbol::{Interns, ModuleId};
@@ -54,6 +54,10 @@ fn load_and_typecheck(
         palette: DEFAULT_PALETTE,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Check,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants: true,
     };
 
     match roc_load_internal::file::load(
@@ -966,6 +970,159 @@ fn import_builtin_in_platform_and_check_app() {
     assert!(result.is_ok(), "should check");
 }
 
+#[test]
+fn hosted_functions_with_ownership_annotations() {
+    let modules = vec![
+        (
+            "platform/main.roc",
+            indoc!(
+                r#"
+                    platform "testplatform"
+                        requires {} { main : Effect.Effect {} }
+                        exposes []
+                        packages {}
+                        imports [pf.Effect]
+                        provides [mainForHost]
+
+                    mainForHost : Effect.Effect {}
+                    mainForHost = main
+                    "#
+            ),
+        ),
+        (
+            "platform/Effect",
+            indoc!(
+                r#"
+                    hosted Effect
+                        exposes [Effect, after, putLine, getLine]
+                        imports []
+                        generates Effect with [after]
+
+                    putLine : Owned Str -> Effect {}
+
+                    getLine : Effect (Borrowed Str)
+                    "#
+            ),
+        ),
+        (
+            "Main",
+            indoc!(
+                r#"
+                    app "test"
+                        packages { pf: "platform/main.roc" }
+                        imports [pf.Effect]
+                        provides [main] to pf
+
+                    main = Effect.after Effect.getLine \line -> Effect.putLine (Str.concat line "!")
+                    "#
+            ),
+        ),
+    ];
+
+    let result = multiple_modules("hosted_functions_with_ownership_annotations", modules);
+    assert!(result.is_ok(), "should check: {:?}", result.err());
+}
+
+fn host_import_modules(platform_exposes: &str) -> Vec<(&str, String)> {
+    vec![
+        (
+            "platform/main.roc",
+            formatdoc!(
+                r#"
+                    platform "testplatform"
+                        requires {{}} {{ main : Str }}
+                        exposes [{}]
+                        packages {{}}
+                        imports []
+                        provides [mainForHost]
+
+                    mainForHost : Str
+                    mainForHost = main
+                    "#,
+                platform_exposes
+            ),
+        ),
+        (
+            "platform/Sha",
+            indoc!(
+                r#"
+                    interface Sha exposes [digest] imports []
+
+                    digest : Str -> Str
+                    digest = \str -> str
+                    "#
+            )
+            .to_string(),
+        ),
+        (
+            "json/main.roc",
+            indoc!(
+                r#"
+                    package "json"
+                        exposes [Hash]
+                        packages {}
+                    "#
+            )
+            .to_string(),
+        ),
+        (
+            "json/Hash",
+            indoc!(
+                r#"
+                    interface Hash exposes [hash] imports [host.Sha]
+
+                    hash : Str -> Str
+                    hash = \str -> Sha.digest str
+                    "#
+            )
+            .to_string(),
+        ),
+        (
+            "Main",
+            indoc!(
+                r#"
+                    app "test"
+                        packages { pf: "platform/main.roc", json: "json/main.roc" }
+                        imports [json.Hash]
+                        provides [main] to pf
+
+                    main = Hash.hash "roc"
+                    "#
+            )
+            .to_string(),
+        ),
+    ]
+}
+
+#[test]
+fn package_imports_exposed_host_module() {
+    let modules = host_import_modules("Sha");
+    let modules = modules
+        .iter()
+        .map(|(name, src)| (*name, src.as_str()))
+        .collect();
+
+    let result = multiple_modules("package_imports_exposed_host_module", modules);
+    assert!(result.is_ok(), "should check");
+}
+
+#[test]
+fn package_imports_unexposed_host_module() {
+    let modules = host_import_modules("");
+    let modules = modules
+        .iter()
+        .map(|(name, src)| (*name, src.as_str()))
+        .collect();
+
+    match multiple_modules("package_imports_unexposed_host_module", modules) {
+        Err(report) => {
+            assert!(report.contains("UNEXPOSED HOST MODULE"), "{}", report);
+            assert!(report.contains("This platform doesn't expose any modules."));
+        }
+        Ok(_) => unreachable!("we expect failure here"),
+    }
+}
+
 #[test]
 fn module_doesnt_match_file_path() {
     let modules = vec![(
@@ -1028,9 +1185,17 @@ fn module_cyclic_import_itself() {
                 │     Age
                 └─────┘
 
+            Age imports Age here:
+
+            1│  interface Age exposes [] imports [Age]
+                                                  ^^^
+
             Cyclic dependencies are not allowed in Roc! Can you restructure a
             module in this import chain so that it doesn't have to depend on
-            itself?"#
+            itself?
+
+            Tip: A module can use its own definitions without importing itself, so
+            this import can just be removed."#
         ),
         "\n{}",
         err
@@ -1076,9 +1241,22 @@ fn module_cyclic_import_transitive() {
                 │     Age
                 └─────┘
 
+            Age imports Person here:
+
+            1│  interface Age exposes [] imports [Person]
+                                                  ^^^^^^
+
+            Person imports Age here:
+
+            1│  interface Person exposes [] imports [Age]
+                                                     ^^^
+
             Cyclic dependencies are not allowed in Roc! Can you restructure a
             module in this import chain so that it doesn't have to depend on
-            itself?"#
+            itself?
+
+            Tip: Age doesn't seem to use anything from Person, so removing that
+            import may be all it takes to break the cycle."#
         ),
         "\n{}",
         err
@@ -1126,3 +1304,492 @@ fn nested_module_has_incorrect_name() {
         err
     );
 }
+
+#[test]
+fn recover_from_syntax_error_in_def() {
+    use LoadResult::*;
+
+    let arena = Bump::new();
+    let src = indoc!(
+        r#"
+        interface Main exposes [x, y] imports []
+
+        x : I64
+        x = 1 +
+
+        y = \n -> n + 1
+        "#
+    );
+    let load_start = LoadStart::from_str(
+        &arena,
+        PathBuf::from("Main.roc"),
+        src,
+        RocCacheDir::Disallowed,
+        PathBuf::from("."),
+    )
+    .unwrap();
+    let load_config = LoadConfig {
+        target_info: TARGET_INFO,
+        render: RenderTarget::Generic,
+        palette: DEFAULT_PALETTE,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Check,
+        recover_from_syntax_errors: true,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants: true,
+    };
+
+    let mut loaded_module = match roc_load_internal::file::load(
+        &arena,
+        load_start,
+        Default::default(),
+        Default::default(),
+        RocCacheDir::Disallowed,
+        load_config,
+    ) {
+        Ok(TypeChecked(module)) => module,
+        Ok(Monomorphized(_)) => unreachable!(""),
+        Err(problem) => panic!("Expected the load to recover, but got {:?}", problem),
+    };
+
+    // The syntax error gets reported, but the rest of the module still gets checked.
+    let home = loaded_module.module_id;
+    let can_problems = loaded_module.can_problems.remove(&home).unwrap_or_default();
+
+    assert!(matches!(
+        can_problems.as_slice(),
+        [Problem::SyntaxError { .. }]
+    ));
+
+    expect_types(
+        loaded_module,
+        hashmap! {
+            "x" => "I64",
+            "y" => "Num a -> Num a",
+        },
+    );
+}
+
+#[test]
+fn docs_for_abilities_and_opaques() {
+    use roc_load_internal::docs::{DocEntry, TypeAnnotation};
+
+    let modules = vec![
+        (
+            "Hashing",
+            indoc!(
+                r#"
+                interface Hashing exposes [MyHash, myHash, Id] imports []
+
+                ## Things which can be hashed
+                MyHash has
+                    ## Hashes a value
+                    myHash : a -> U64 | a has MyHash
+
+                ## A unique identifier
+                Id := U64
+                "#
+            ),
+        ),
+        (
+            "main.roc",
+            indoc!(
+                r#"
+                package "hashing"
+                    exposes [Hashing]
+                    packages {}
+                "#
+            ),
+        ),
+    ];
+
+    let loaded_module = multiple_modules("docs_for_abilities_and_opaques", modules).unwrap();
+    let module_docs = loaded_module
+        .docs_by_module
+        .values()
+        .find(|docs| docs.name == "Hashing")
+        .unwrap();
+    let doc_defs: Vec<_> = module_docs
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            DocEntry::DocDef(doc_def) => Some(doc_def),
+            DocEntry::DetachedDoc(_) => None,
+        })
+        .collect();
+
+    assert_eq!(doc_defs.len(), 2);
+    assert_eq!(doc_defs[0].name, "MyHash");
+    assert_eq!(
+        doc_defs[0].docs.as_deref(),
+        Some("Things which can be hashed\n")
+    );
+
+    match &doc_defs[0].type_annotation {
+        TypeAnnotation::Ability { members } => {
+            assert_eq!(members.len(), 1);
+            assert_eq!(members[0].name, "myHash");
+            assert_eq!(members[0].docs.as_deref(), Some("Hashes a value\n"));
+        }
+        other => panic!("Expected an ability, but got {:?}", other),
+    }
+
+    assert_eq!(doc_defs[1].name, "Id");
+    assert_eq!(doc_defs[1].docs.as_deref(), Some("A unique identifier\n"));
+}
+
+#[test]
+fn docs_for_constants() {
+    use roc_can::constant::Constant;
+    use roc_load_internal::docs::DocEntry;
+
+    let modules = vec![
+        (
+            "Config",
+            indoc!(
+                r#"
+                interface Config exposes [maxRetries, timeoutMs, tooBig, greet] imports []
+
+                maxRetries : U8
+                maxRetries = 5
+
+                timeoutMs : U64
+                timeoutMs = maxRetries * 1000
+
+                tooBig : U8
+                tooBig = 200 + 100
+
+                greet : Str -> Str
+                greet = \name -> Str.concat "Hi, " name
+                "#
+            ),
+        ),
+        (
+            "main.roc",
+            indoc!(
+                r#"
+                package "config"
+                    exposes [Config]
+                    packages {}
+                "#
+            ),
+        ),
+    ];
+
+    let loaded_module = multiple_modules("docs_for_constants", modules).unwrap();
+    let module_docs = loaded_module
+        .docs_by_module
+        .values()
+        .find(|docs| docs.name == "Config")
+        .unwrap();
+    let constants: Vec<_> = module_docs
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            DocEntry::DocDef(doc_def) => Some((doc_def.name.as_str(), doc_def.constant.clone())),
+            DocEntry::DetachedDoc(_) => None,
+        })
+        .collect();
+
+    assert_eq!(
+        constants,
+        vec![
+            ("maxRetries", Some(Constant::Int(5))),
+            ("timeoutMs", Some(Constant::Int(5000))),
+            ("tooBig", None),
+            ("greet", None),
+        ]
+    );
+}
+
+#[test]
+fn docs_for_unannotated_values() {
+    use roc_load_internal::docs::DocEntry;
+
+    let modules = vec![
+        (
+            "Geometry",
+            indoc!(
+                r#"
+                interface Geometry exposes [Point, makePoint, origin, greet, twice] imports []
+
+                Point : { x : I64, y : I64 }
+
+                makePoint : I64, I64 -> Point
+                makePoint = \x, y -> { x, y }
+
+                origin = makePoint 0 0
+
+                greet = \name -> Str.concat "Hi, " name
+
+                twice = \f, x -> f (f x)
+                "#
+            ),
+        ),
+        (
+            "main.roc",
+            indoc!(
+                r#"
+                package "geometry"
+                    exposes [Geometry]
+                    packages {}
+                "#
+            ),
+        ),
+    ];
+
+    let loaded_module = multiple_modules("docs_for_unannotated_values", modules).unwrap();
+    let module_docs = loaded_module
+        .docs_by_module
+        .values()
+        .find(|docs| docs.name == "Geometry")
+        .unwrap();
+    let inferred_types: Vec<_> = module_docs
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            DocEntry::DocDef(doc_def) => {
+                Some((doc_def.name.as_str(), doc_def.inferred_type.as_deref()))
+            }
+            DocEntry::DetachedDoc(_) => None,
+        })
+        .collect();
+
+    assert_eq!(
+        inferred_types,
+        vec![
+            ("Point", None),
+            ("makePoint", None),
+            ("origin", Some("Point")),
+            ("greet", Some("Str -> Str")),
+            ("twice", Some("(a -> a), a -> a")),
+        ]
+    );
+}
+
+#[test]
+fn lint_single_ability_implementations() {
+    use roc_solve_problem::TypeError;
+    use roc_types::types::ErrorType;
+
+    let modules = vec![
+        (
+            "Lib",
+            indoc!(
+                r#"
+                interface Lib exposes [summary, isAny] imports []
+
+                isMember : List a, a -> Bool | a has Eq
+                isMember = \list, x -> List.contains list x
+
+                isFirst : List a, a -> Bool | a has Eq
+                isFirst = \list, x -> List.first list == Ok x
+
+                isAny : List a, a -> Bool | a has Eq
+                isAny = \list, x -> List.contains list x
+
+                summary =
+                    isMember ["a", "b"] "a"
+                    && isMember ["c"] "d"
+                    && isFirst [1u8] 1u8
+                    && isFirst ["e"] "e"
+                    && isAny ["f"] "f"
+                "#
+            ),
+        ),
+        (
+            "main.roc",
+            indoc!(
+                r#"
+                package "lib"
+                    exposes [Lib]
+                    packages {}
+                "#
+            ),
+        ),
+    ];
+
+    let loaded_module = multiple_modules("lint_single_ability_implementations", modules).unwrap();
+    let interns = &loaded_module.interns;
+
+    // `isFirst` is used with two types, and other modules could use `isAny` with any type
+    let lints: Vec<_> = loaded_module
+        .lints
+        .values()
+        .flatten()
+        .map(|lint| match lint {
+            TypeError::SingleAbilityImplementation {
+                symbol,
+                implementations,
+                ..
+            } => {
+                let implementations: Vec<_> = implementations
+                    .iter()
+                    .map(|(ability, typ)| {
+                        let typ = match typ {
+                            ErrorType::Type(symbol, args) if args.is_empty() => {
+                                symbol.as_str(interns)
+                            }
+                            other => panic!("unexpected type {:?}", other),
+                        };
+
+                        (ability.as_str(interns), typ)
+                    })
+                    .collect();
+
+                (symbol.as_str(interns), implementations)
+            }
+            other => panic!("unexpected lint {:?}", other),
+        })
+        .collect();
+
+    assert_eq!(lints, vec![("isMember", vec![("Eq", "Str")])]);
+}
+
+#[test]
+fn crash_paths_across_modules() {
+    use roc_can::crash_reachability::{crash_path, CrashKind};
+
+    let modules = vec![
+        (
+            "Helper",
+            indoc!(
+                r#"
+                interface Helper exposes [digit] imports []
+
+                digit = \str ->
+                    when str is
+                        "0" -> 0
+                        "1" -> 1
+                        _ -> crash "not a digit"
+                "#
+            ),
+        ),
+        (
+            "Lib",
+            indoc!(
+                r#"
+                interface Lib exposes [parse, increment] imports [Helper]
+
+                parse = \str -> Helper.digit str
+
+                increment = \n -> n + 1
+                "#
+            ),
+        ),
+        (
+            "main.roc",
+            indoc!(
+                r#"
+                package "lib"
+                    exposes [Lib]
+                    packages {}
+                "#
+            ),
+        ),
+    ];
+
+    let loaded_module = multiple_modules("crash_paths_across_modules", modules).unwrap();
+    let interns = &loaded_module.interns;
+
+    let symbol_named = |name: &str| {
+        loaded_module
+            .crash_summaries
+            .values()
+            .flat_map(|summary| summary.references.keys())
+            .copied()
+            .find(|symbol| symbol.as_str(interns) == name)
+            .unwrap()
+    };
+
+    let path = crash_path(&loaded_module.crash_summaries, symbol_named("parse")).unwrap();
+    let steps: Vec<_> = path
+        .steps
+        .iter()
+        .map(|step| step.symbol.as_str(interns))
+        .collect();
+
+    assert_eq!(steps, vec!["parse", "digit"]);
+    assert_eq!(path.kind, CrashKind::Crash);
+
+    assert_eq!(
+        crash_path(&loaded_module.crash_summaries, symbol_named("increment")),
+        None
+    );
+}
+
+#[test]
+fn unused_across_modules() {
+    use roc_can::dead_code::{find_unused, UnusedKind};
+
+    let modules = vec![
+        (
+            "Helper",
+            indoc!(
+                r#"
+                interface Helper exposes [double, triple, Count] imports []
+
+                Count : U64
+
+                double = \n -> n * 2
+
+                triple = \n -> n * 3
+                "#
+            ),
+        ),
+        (
+            "Extra",
+            indoc!(
+                r#"
+                interface Extra exposes [extra] imports []
+
+                extra = 42
+                "#
+            ),
+        ),
+        (
+            "Lib",
+            indoc!(
+                r#"
+                interface Lib exposes [quadruple] imports [Helper, Extra]
+
+                quadruple = \n -> Helper.double (Helper.double n)
+
+                leftover = \n -> Extra.extra + n
+
+                expect leftover 0 == 42
+                "#
+            ),
+        ),
+        (
+            "main.roc",
+            indoc!(
+                r#"
+                package "lib"
+                    exposes [Lib]
+                    packages {}
+                "#
+            ),
+        ),
+    ];
+
+    let loaded_module = multiple_modules("unused_across_modules", modules).unwrap();
+    let interns = &loaded_module.interns;
+
+    let root_modules: Vec<_> = loaded_module.docs_by_module.keys().copied().collect();
+
+    let mut unused: Vec<_> = find_unused(&loaded_module.reference_summaries, &root_modules)
+        .into_iter()
+        .map(|unused| match unused.kind {
+            UnusedKind::Value(symbol) | UnusedKind::Type(symbol) => {
+                symbol.as_str(interns).to_string()
+            }
+            UnusedKind::Import(module_id) => format!("import {}", interns.module_name(module_id)),
+        })
+        .collect();
+    unused.sort();
+
+    // `leftover` is only used by an `expect`, which keeps it and the import of `Extra` around
+    assert_eq!(unused, vec!["Count", "triple"]);
+}
@@ -114,6 +114,14 @@ impl ModuleName {
     pub const DECODE: &'static str = "Decode";
     pub const HASH: &'static str = "Hash";
     pub const JSON: &'static str = "Json";
+    pub const RBF: &'static str = "Rbf";
+    pub const STREAM: &'static str = "Stream";
+    pub const REGEX: &'static str = "Regex";
+    pub const TIME: &'static str = "Time";
+    pub const CRYPTO: &'static str = "Crypto";
+    pub const UUID: &'static str = "Uuid";
+    pub const RANDOM: &'static str = "Random";
+    pub const ENUM: &'static str = "Enum";
 
     pub fn as_str(&self) -> &str {
         self.0.as_str()
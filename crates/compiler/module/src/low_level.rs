@@ -38,6 +38,7 @@ pub enum LowLevel {
     ListReserve,
     ListReleaseExcessCapacity,
     ListAppendUnsafe,
+    ListRepeat,
     ListGetUnsafe,
     ListReplaceUnsafe,
     ListConcat,
@@ -48,10 +49,17 @@ pub enum LowLevel {
     ListMap4,
     ListSortWith,
     ListSublist,
+    ListChunksOf,
+    ListWindows,
     ListDropAt,
     ListSwap,
     ListIsUnique,
     ListGetCapacity,
+    CryptoSha256,
+    CryptoBlake3,
+    CryptoConstantTimeEq,
+    RandomU64s,
+    RandomBytes,
     NumAdd,
     NumAddWrap,
     NumAddChecked,
@@ -278,13 +286,21 @@ map_symbol_to_lowlevel! {
     ListReleaseExcessCapacity <= LIST_RELEASE_EXCESS_CAPACITY,
     ListIsUnique <= LIST_IS_UNIQUE,
     ListAppendUnsafe <= LIST_APPEND_UNSAFE,
+    ListRepeat <= LIST_REPEAT,
     ListPrepend <= LIST_PREPEND,
     ListGetUnsafe <= LIST_GET_UNSAFE,
     ListReplaceUnsafe <= LIST_REPLACE_UNSAFE,
     ListConcat <= LIST_CONCAT,
     ListSublist <= LIST_SUBLIST_LOWLEVEL,
+    ListChunksOf <= LIST_CHUNKS_OF,
+    ListWindows <= LIST_WINDOWS,
     ListDropAt <= LIST_DROP_AT,
     ListSwap <= LIST_SWAP,
+    CryptoSha256 <= CRYPTO_SHA256,
+    CryptoBlake3 <= CRYPTO_BLAKE3,
+    CryptoConstantTimeEq <= CRYPTO_CONSTANT_TIME_EQ,
+    RandomU64s <= RANDOM_U64S_LOWLEVEL,
+    RandomBytes <= RANDOM_BYTES_LOWLEVEL,
     NumAdd <= NUM_ADD,
     NumAddWrap <= NUM_ADD_WRAP,
     NumAddChecked <= NUM_ADD_CHECKED_LOWLEVEL,
@@ -53,6 +53,10 @@ pub const DERIVABLE_ABILITIES: &[(Symbol, &[Symbol])] = &[
     (Symbol::DECODE_DECODING, &[Symbol::DECODE_DECODER]),
     (Symbol::HASH_HASH_ABILITY, &[Symbol::HASH_HASH]),
     (Symbol::BOOL_EQ, &[Symbol::BOOL_IS_EQ]),
+    (
+        Symbol::ENUM_ENUM,
+        &[Symbol::ENUM_TO_INT, Symbol::ENUM_FROM_INT, Symbol::ENUM_ALL],
+    ),
 ];
 
 /// In Debug builds only, Symbol has a name() method that lets
@@ -362,6 +366,11 @@ impl ModuleId {
         (self.0.get() - 1) as usize
     }
 
+    /// The position of this module in the `ModuleIds` (or `PackageModuleIds`) it came from
+    pub const fn index(self) -> usize {
+        self.to_zero_indexed()
+    }
+
     #[cfg(any(debug_assertions, feature = "debug-symbols"))]
     pub fn register_debug_idents(self, ident_ids: &IdentIds) {
         let mut all = DEBUG_IDENT_IDS_BY_MODULE_ID.lock().expect("Failed to acquire lock for Debug interning into DEBUG_MODULE_ID_NAMES, presumably because a thread panicked.");
@@ -446,12 +455,34 @@ impl<'a, T> PackageQualified<'a, T> {
     }
 }
 
+/// The shorthand packages use to import a module of the platform, like `host.Sha.{ sha256 }`.
+/// A package doesn't know which platform it will be used with, let alone the shorthand the app
+/// gave that platform, so this resolves to whichever platform the app uses.
+pub const HOST_SHORTHAND: &str = "host";
+
 #[derive(Debug, Clone)]
 pub struct PackageModuleIds<'a> {
     by_id: Vec<PQModuleName<'a>>,
+    /// The shorthand the app gave its platform, which `host.` imports resolve to
+    platform_shorthand: Option<&'a str>,
 }
 
 impl<'a> PackageModuleIds<'a> {
+    pub fn set_platform_shorthand(&mut self, shorthand: &'a str) {
+        self.platform_shorthand = Some(shorthand);
+    }
+
+    /// Turn `host.Foo` into `pf.Foo`, where `pf` is the shorthand the app gave its platform,
+    /// so both refer to the same module. Without a platform, `host.Foo` stays as it is.
+    pub fn resolve_host_shorthand(&self, module_name: PQModuleName<'a>) -> PQModuleName<'a> {
+        match (module_name, self.platform_shorthand) {
+            (PQModuleName::Qualified(HOST_SHORTHAND, name), Some(platform_shorthand)) => {
+                PQModuleName::Qualified(platform_shorthand, name)
+            }
+            (module_name, _) => module_name,
+        }
+    }
+
     pub fn get_or_insert(&mut self, module_name: &PQModuleName<'a>) -> ModuleId {
         if let Some(module_id) = self.get_id(module_name) {
             return module_id;
@@ -944,7 +975,7 @@ macro_rules! define_builtins {
                     insert_both(ModuleId::$module_const, $module_name);
                 )+
 
-                PackageModuleIds { by_id }
+                PackageModuleIds { by_id, platform_shorthand: None }
             }
         }
 
@@ -1422,6 +1453,12 @@ define_builtins! {
         79 LIST_WALK_FROM_UNTIL: "walkFromUntil"
         80 LIST_ITER_HELP: "iterHelp"
         81 LIST_RELEASE_EXCESS_CAPACITY: "releaseExcessCapacity"
+        82 LIST_SORT_BY: "sortBy"
+        83 LIST_CHUNKS_OF: "chunksOf"
+        84 LIST_WINDOWS: "windows"
+        85 LIST_ZIP: "zip"
+        86 LIST_ZIP3: "zip3"
+        87 LIST_UNZIP: "unzip"
     }
     7 RESULT: "Result" => {
         0 RESULT_RESULT: "Result" exposed_type=true // the Result.Result type alias
@@ -1573,6 +1610,36 @@ define_builtins! {
     14 JSON: "Json" => {
         0 JSON_JSON: "Json"
     }
+    15 RBF: "Rbf" => {
+        0 RBF_RBF: "Rbf"
+    }
+    16 STREAM: "Stream" => {
+        0 STREAM_STREAM: "Stream"
+    }
+    17 REGEX: "Regex" => {
+        0 REGEX_REGEX: "Regex"
+    }
+    18 TIME: "Time" => {
+        0 TIME_TIME: "Time"
+    }
+    19 CRYPTO: "Crypto" => {
+        0 CRYPTO_SHA256: "sha256"
+        1 CRYPTO_BLAKE3: "blake3"
+        2 CRYPTO_CONSTANT_TIME_EQ: "constantTimeEq"
+    }
+    20 UUID: "Uuid" => {
+        0 UUID_UUID: "Uuid"
+    }
+    21 RANDOM: "Random" => {
+        0 RANDOM_U64S_LOWLEVEL: "u64sLowlevel"
+        1 RANDOM_BYTES_LOWLEVEL: "bytesLowlevel"
+    }
+    22 ENUM: "Enum" => {
+        0 ENUM_ENUM: "Enum" exposed_type=true
+        1 ENUM_TO_INT: "toInt"
+        2 ENUM_FROM_INT: "fromInt"
+        3 ENUM_ALL: "all"
+    }
 
-    num_modules: 15 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
+    num_modules: 23 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
 }
@@ -0,0 +1,64 @@
+//! Arena allocation mode, for platforms (e.g. servers) which give every request its own bump
+//! arena, and reset that arena once the request is handled. Memory is never freed individually
+//! then, so decrementing refcounts is wasted work: drop specialization degenerates every
+//! decrement to a no-op, and resets no longer recursively decrement the children of a value.
+//!
+//! Increments stay, so that uniqueness checks (and with them in-place updates) remain correct.
+//! A value which was shared once just stays shared until the arena is reset.
+//!
+//! What the host's `roc_alloc`, `roc_realloc` and `roc_dealloc` have to do in this mode is
+//! described in `examples/platform-switching/README.md`.
+//!
+//! This runs after all other passes which insert or move refcounting operations.
+
+use bumpalo::Bump;
+use roc_collections::MutMap;
+use roc_module::symbol::Symbol;
+
+use crate::ir::{Expr, ModifyRc, Proc, ProcLayout};
+use crate::ir_walk::{rewrite_stmt, Let, Rewriter};
+use crate::layout::InLayout;
+
+pub fn remove_decrements<'a>(
+    arena: &'a Bump,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    for proc in procs.values_mut() {
+        let body = arena.alloc(proc.body.clone());
+        let new_body = rewrite_stmt(arena, &mut RemoveDecrements, &mut (), body);
+
+        proc.body = new_body.clone();
+    }
+}
+
+struct RemoveDecrements;
+
+impl<'a> Rewriter<'a> for RemoveDecrements {
+    type Scope = ();
+
+    fn rewrite_let(
+        &mut self,
+        _scope: &mut (),
+        binding: Symbol,
+        expr: &Expr<'a>,
+        layout: InLayout<'a>,
+        lets: &mut Vec<Let<'a>>,
+    ) {
+        let new_expr = match expr {
+            Expr::Reset {
+                symbol,
+                update_mode,
+            } => Expr::ResetRef {
+                symbol: *symbol,
+                update_mode: *update_mode,
+            },
+            _ => expr.clone(),
+        };
+
+        lets.push((binding, new_expr, layout));
+    }
+
+    fn keep_refcounting(&mut self, modify_rc: &ModifyRc) -> bool {
+        !matches!(modify_rc, ModifyRc::Dec(_) | ModifyRc::DecRef(_))
+    }
+}
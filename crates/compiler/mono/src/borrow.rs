@@ -7,6 +7,7 @@ use crate::ir::{
 use crate::layout::{InLayout, Layout, LayoutInterner, STLayoutInterner};
 use bumpalo::collections::Vec;
 use bumpalo::Bump;
+use roc_can::expr::HostOwnership;
 use roc_collections::all::{MutMap, MutSet};
 use roc_collections::ReferenceMatrix;
 use roc_module::low_level::LowLevel;
@@ -36,6 +37,15 @@ impl Ownership {
         }
     }
 }
+
+impl From<HostOwnership> for Ownership {
+    fn from(ownership: HostOwnership) -> Self {
+        match ownership {
+            HostOwnership::Owned => Ownership::Owned,
+            HostOwnership::Borrowed => Ownership::Borrowed,
+        }
+    }
+}
 pub fn infer_borrow<'a>(
     arena: &'a Bump,
     interner: &STLayoutInterner<'a>,
@@ -651,13 +661,12 @@ impl<'a> BorrowInfState<'a> {
                 }
             }
 
-            Foreign { .. } => {
+            Foreign { arg_ownership, .. } => {
                 // very unsure what demand ForeignCall should place upon its arguments
                 self.own_var(z);
 
-                let ps = foreign_borrow_signature(self.arena, arguments.len());
-
-                self.own_args_using_bools(arguments, ps);
+                // arguments the host takes over (`Owned` in the hosted module) have to be owned
+                self.own_args_using_bools(arguments, arg_ownership);
             }
         }
     }
@@ -672,7 +681,7 @@ impl<'a> BorrowInfState<'a> {
         use Expr::*;
 
         match e {
-            Array { elems: xs, .. } => {
+            Array { elems: xs, .. } | StackArray { elems: xs, .. } => {
                 let xs = Vec::from_iter_in(xs.iter().filter_map(|e| e.to_symbol()), self.arena);
                 self.own_var(z);
 
@@ -688,7 +697,7 @@ impl<'a> BorrowInfState<'a> {
                 self.own_args_if_param(xs);
             }
 
-            ExprBox { symbol: x } => {
+            ExprBox { symbol: x } | StackBox { symbol: x } => {
                 self.own_var(z);
 
                 // if the used symbol is an argument to the current function,
@@ -923,13 +932,6 @@ impl<'a> BorrowInfState<'a> {
     }
 }
 
-pub fn foreign_borrow_signature(arena: &Bump, arity: usize) -> &[Ownership] {
-    // NOTE this means that Roc is responsible for cleaning up resources;
-    // the host cannot (currently) take ownership
-    let all = bumpalo::vec![in arena; Ownership::Borrowed; arity];
-    all.into_bump_slice()
-}
-
 pub fn lowlevel_borrow_signature(arena: &Bump, op: LowLevel) -> &[Ownership] {
     use LowLevel::*;
 
@@ -974,8 +976,10 @@ pub fn lowlevel_borrow_signature(arena: &Bump, op: LowLevel) -> &[Ownership] {
         ListSortWith => arena.alloc_slice_copy(&[owned, function, closure_data]),
 
         ListAppendUnsafe => arena.alloc_slice_copy(&[owned, owned]),
+        ListRepeat => arena.alloc_slice_copy(&[borrowed, irrelevant]),
         ListReserve => arena.alloc_slice_copy(&[owned, irrelevant]),
         ListSublist => arena.alloc_slice_copy(&[owned, irrelevant, irrelevant]),
+        ListChunksOf | ListWindows => arena.alloc_slice_copy(&[borrowed, irrelevant]),
         ListDropAt => arena.alloc_slice_copy(&[owned, irrelevant]),
         ListSwap => arena.alloc_slice_copy(&[owned, irrelevant, irrelevant]),
         ListReleaseExcessCapacity => arena.alloc_slice_copy(&[owned]),
@@ -1028,6 +1032,9 @@ pub fn lowlevel_borrow_signature(arena: &Bump, op: LowLevel) -> &[Ownership] {
         Hash => arena.alloc_slice_copy(&[borrowed, irrelevant]),
 
         ListIsUnique => arena.alloc_slice_copy(&[borrowed]),
+        CryptoSha256 | CryptoBlake3 => arena.alloc_slice_copy(&[borrowed]),
+        CryptoConstantTimeEq => arena.alloc_slice_copy(&[borrowed, borrowed]),
+        RandomU64s | RandomBytes => arena.alloc_slice_copy(&[irrelevant, irrelevant, irrelevant]),
 
         BoxExpr | UnboxExpr => {
             unreachable!("These lowlevel operations are turned into mono Expr's")
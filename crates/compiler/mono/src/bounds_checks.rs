@@ -0,0 +1,639 @@
+//! Replace `List.get` calls whose index is known to be in bounds with `List.getUnsafe` (wrapped
+//! in an `Ok`), so they don't check the bounds again. An index is known to be in bounds when
+//!
+//! - the call is in a branch where `index < List.len list` (or an equivalent comparison) holds,
+//!   like the `then` branch of `if index < List.len list then ...`
+//! - the index is a literal which is smaller than the length of the list, which is known when the
+//!   list is a list literal, was matched by a list pattern like `[_, _, _]`, or was compared
+//!   against an index literal before.
+//!
+//! This runs before refcounting operations are inserted, so the `List.getUnsafe` gets the same
+//! refcounting operations as any other.
+
+use bumpalo::Bump;
+use roc_collections::{MutMap, MutSet};
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+
+use crate::ir::{BranchInfo, Call, CallType, Expr, Literal, Proc, ProcLayout, UpdateModeId};
+use crate::ir_walk::{rewrite_stmt, Let, Rewriter, SwitchBranch};
+use crate::layout::{
+    Builtin, InLayout, Layout, LayoutInterner, LayoutRepr, STLayoutInterner, UnionLayout,
+};
+
+pub fn remove_bounds_checks<'a, 'i>(
+    arena: &'a Bump,
+    layout_interner: &'i STLayoutInterner<'a>,
+    home: ModuleId,
+    ident_ids: &'i mut IdentIds,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    let mut env = Env {
+        arena,
+        layout_interner,
+        home,
+        ident_ids,
+    };
+
+    for proc in procs.values_mut() {
+        let body = arena.alloc(proc.body.clone());
+        let new_body = rewrite_stmt(arena, &mut env, &mut Facts::default(), body);
+
+        proc.body = new_body.clone();
+    }
+}
+
+struct Env<'a, 'i> {
+    arena: &'a Bump,
+    layout_interner: &'i STLayoutInterner<'a>,
+    home: ModuleId,
+    ident_ids: &'i mut IdentIds,
+}
+
+impl<'a, 'i> Env<'a, 'i> {
+    fn create_symbol(&mut self, debug_name: &str) -> Symbol {
+        let ident_id = self.ident_ids.add_str(debug_name);
+        Symbol::new(self.home, ident_id)
+    }
+}
+
+/// What is known about the symbols in scope. Symbols are never reassigned, so facts stay true
+/// for the rest of the scope they were learned in.
+#[derive(Clone, Default)]
+struct Facts {
+    // Map containing the current known index value of a symbol.
+    symbol_index: MutMap<Symbol, u64>,
+
+    // Map containing the known minimum length of a list.
+    list_min_length: MutMap<Symbol, u64>,
+
+    // Map from a symbol holding the length of a list, to that list.
+    list_length_of: MutMap<Symbol, Symbol>,
+
+    // Map from a boolean to the (list, index) it compares, and whether the index is in bounds
+    // when the boolean is true or when it is false.
+    bounds_tests: MutMap<Symbol, (bool, Symbol, Symbol)>,
+
+    // The (list, index) pairs for which the index is known to be in bounds.
+    in_bounds: MutSet<(Symbol, Symbol)>,
+}
+
+impl Facts {
+    fn add_in_bounds(&mut self, list: Symbol, index: Symbol) {
+        self.in_bounds.insert((list, index));
+
+        if let Some(index_value) = self.symbol_index.get(&index) {
+            self.add_min_length(list, index_value + 1);
+        }
+    }
+
+    fn add_min_length(&mut self, list: Symbol, length: u64) {
+        let min_length = self.list_min_length.entry(list).or_insert(0);
+        *min_length = (*min_length).max(length);
+    }
+
+    fn is_in_bounds(&self, list: Symbol, index: Symbol) -> bool {
+        if self.in_bounds.contains(&(list, index)) {
+            return true;
+        }
+
+        match (self.symbol_index.get(&index), self.list_min_length.get(&list)) {
+            (Some(index_value), Some(min_length)) => index_value < min_length,
+            _ => false,
+        }
+    }
+
+    /// Learn what a branch of a switch on `cond_symbol` tells us.
+    fn add_branch_info(
+        &mut self,
+        cond_symbol: Symbol,
+        cond_value: Option<bool>,
+        info: &BranchInfo,
+    ) {
+        if let BranchInfo::List { scrutinee, len } = info {
+            self.add_min_length(*scrutinee, *len);
+        }
+
+        if let (Some(value), Some((when, list, index))) =
+            (cond_value, self.bounds_tests.get(&cond_symbol).copied())
+        {
+            if value == when {
+                self.add_in_bounds(list, index);
+            }
+        }
+    }
+}
+
+impl<'a, 'i> Rewriter<'a> for Env<'a, 'i> {
+    type Scope = Facts;
+
+    fn rewrite_let(
+        &mut self,
+        facts: &mut Facts,
+        binding: Symbol,
+        expr: &Expr<'a>,
+        layout: InLayout<'a>,
+        lets: &mut Vec<Let<'a>>,
+    ) {
+        match get_unsafe(self, facts, binding, expr, layout) {
+            Some([get, ok]) => {
+                lets.push(get);
+                lets.push(ok);
+            }
+            None => {
+                learn_from_expr(facts, binding, expr);
+                lets.push((binding, expr.clone(), layout));
+            }
+        }
+    }
+
+    fn enter_branch(
+        &mut self,
+        facts: &mut Facts,
+        cond_symbol: Symbol,
+        cond_layout: InLayout<'a>,
+        branch: SwitchBranch<'_, 'a>,
+    ) {
+        let is_bool = cond_layout == Layout::BOOL;
+
+        let (cond_value, info) = match branch {
+            SwitchBranch::Case(tag_id, info) => (is_bool.then_some(tag_id != 0), info),
+            SwitchBranch::Default(branches, info) => {
+                // A boolean goes to the default branch only if it has the value that none of
+                // the other branches have.
+                let cond_value = match branches.first() {
+                    Some((tag_id, _, _))
+                        if is_bool && branches.iter().all(|(other, _, _)| other == tag_id) =>
+                    {
+                        Some(*tag_id == 0)
+                    }
+                    _ => None,
+                };
+
+                (cond_value, info)
+            }
+        };
+
+        facts.add_branch_info(cond_symbol, cond_value, info);
+    }
+}
+
+fn learn_from_expr(facts: &mut Facts, binding: Symbol, expr: &Expr) {
+    match expr {
+        Expr::Literal(Literal::Int(int)) => {
+            if let Ok(index) = u64::try_from(i128::from_ne_bytes(*int)) {
+                facts.symbol_index.insert(binding, index);
+            }
+        }
+        Expr::Array { elems, .. } | Expr::StackArray { elems, .. } => {
+            facts.add_min_length(binding, elems.len() as u64);
+        }
+        Expr::Call(Call {
+            call_type,
+            arguments,
+        }) => {
+            let op = match call_type.clone().replace_lowlevel_wrapper() {
+                CallType::LowLevel { op, .. } => op,
+                _ => return,
+            };
+
+            // (whether the index is in bounds when the comparison is true, index, length)
+            let comparison = match (op, arguments) {
+                (LowLevel::ListLen, [list]) => {
+                    facts.list_length_of.insert(binding, *list);
+                    return;
+                }
+                (LowLevel::NumLt, [index, len]) => (true, index, len),
+                (LowLevel::NumGt, [len, index]) => (true, index, len),
+                (LowLevel::NumGte, [index, len]) => (false, index, len),
+                (LowLevel::NumLte, [len, index]) => (false, index, len),
+                _ => return,
+            };
+
+            let (when, index, len) = comparison;
+
+            if let Some(list) = facts.list_length_of.get(len).copied() {
+                facts.bounds_tests.insert(binding, (when, list, *index));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Build `element = List.getUnsafe list index` and `binding = Ok element` if the expression
+/// is a `List.get` call whose index is known to be in bounds.
+fn get_unsafe<'a>(
+    env: &mut Env<'a, '_>,
+    facts: &Facts,
+    binding: Symbol,
+    expr: &Expr<'a>,
+    layout: InLayout<'a>,
+) -> Option<[Let<'a>; 2]> {
+    let (list, index, list_layout) = match expr {
+        Expr::Call(Call {
+            call_type:
+                CallType::ByName {
+                    name, arg_layouts, ..
+                },
+            arguments: [list, index],
+        }) if name.name() == Symbol::LIST_GET => (*list, *index, arg_layouts[0]),
+        _ => return None,
+    };
+
+    if !facts.is_in_bounds(list, index) {
+        return None;
+    }
+
+    let element_layout = match env.layout_interner.get(list_layout).repr {
+        LayoutRepr::Builtin(Builtin::List(element_layout)) => element_layout,
+        _ => return None,
+    };
+
+    // `Result a [OutOfBounds]`, where `Err` has tag id 0 and `Ok` has tag id 1
+    let tag_layout = match env.layout_interner.get(layout).repr {
+        LayoutRepr::Union(union_layout @ UnionLayout::NonRecursive(tags))
+            if tags.len() == 2 && *tags[1] == [element_layout] =>
+        {
+            union_layout
+        }
+        _ => return None,
+    };
+
+    let element = env.create_symbol("element");
+
+    let get_expr = Expr::Call(Call {
+        call_type: CallType::LowLevel {
+            op: LowLevel::ListGetUnsafe,
+            update_mode: UpdateModeId::BACKEND_DUMMY,
+        },
+        arguments: env.arena.alloc([list, index]),
+    });
+
+    let ok_expr = Expr::Tag {
+        tag_layout,
+        tag_id: 1,
+        arguments: env.arena.alloc([element]),
+    };
+
+    Some([
+        (element, get_expr, element_layout),
+        (binding, ok_expr, layout),
+    ])
+}
+
+#[cfg(test)]
+mod test {
+    use bumpalo::Bump;
+    use roc_collections::MutMap;
+    use roc_module::low_level::LowLevel;
+    use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+    use roc_target::TargetInfo;
+
+    use crate::ir::{
+        BranchInfo, Call, CallSpecId, CallType, Expr, HostExposedLayouts, Literal, Proc,
+        ProcLayout, SelfRecursive, Stmt, UpdateModeId,
+    };
+    use crate::layout::{
+        Builtin, InLayout, LambdaName, Layout, LayoutInterner, LayoutRepr, Niche, STLayoutInterner,
+        UnionLayout,
+    };
+
+    use super::remove_bounds_checks;
+
+    const TARGET_INFO: TargetInfo = TargetInfo::default_x86_64();
+
+    /// A procedure taking two lists of `I64`s and two indexes, whose body is built by a test
+    struct Program<'a> {
+        arena: &'a Bump,
+        interner: STLayoutInterner<'a>,
+        ident_ids: IdentIds,
+        list_layout: InLayout<'a>,
+        result_layout: InLayout<'a>,
+        list: Symbol,
+        other_list: Symbol,
+        index: Symbol,
+        other_index: Symbol,
+    }
+
+    impl<'a> Program<'a> {
+        fn new(arena: &'a Bump) -> Self {
+            let mut interner = STLayoutInterner::with_capacity(4, TARGET_INFO);
+            let mut ident_ids = IdentIds::default();
+
+            let list_layout =
+                interner.insert_no_semantic(LayoutRepr::Builtin(Builtin::List(Layout::I64)));
+            // `Result I64 [OutOfBounds]`
+            let tags: &[&[InLayout]] = arena.alloc([&[Layout::UNIT][..], &[Layout::I64][..]]);
+            let result_layout =
+                interner.insert_no_semantic(LayoutRepr::Union(UnionLayout::NonRecursive(tags)));
+
+            let [list, other_list, index, other_index] =
+                ["list", "otherList", "index", "otherIndex"]
+                    .map(|name| Symbol::new(ModuleId::ATTR, ident_ids.add_str(name)));
+
+            Program {
+                arena,
+                interner,
+                ident_ids,
+                list_layout,
+                result_layout,
+                list,
+                other_list,
+                index,
+                other_index,
+            }
+        }
+
+        fn symbol(&mut self, name: &str) -> Symbol {
+            Symbol::new(ModuleId::ATTR, self.ident_ids.add_str(name))
+        }
+
+        fn lowlevel(&self, op: LowLevel, arguments: &[Symbol]) -> Expr<'a> {
+            Expr::Call(Call {
+                call_type: CallType::LowLevel {
+                    op,
+                    update_mode: UpdateModeId::BACKEND_DUMMY,
+                },
+                arguments: self.arena.alloc_slice_copy(arguments),
+            })
+        }
+
+        fn list_get(&self, list: Symbol, index: Symbol) -> Expr<'a> {
+            Expr::Call(Call {
+                call_type: CallType::ByName {
+                    name: LambdaName::no_niche(Symbol::LIST_GET),
+                    ret_layout: self.result_layout,
+                    arg_layouts: self.arena.alloc([self.list_layout, Layout::U64]),
+                    specialization_id: CallSpecId::BACKEND_DUMMY,
+                },
+                arguments: self.arena.alloc([list, index]),
+            })
+        }
+
+        /// `List.get list index` for each of the pairs, returning the last one
+        fn gets(&mut self, pairs: &[(Symbol, Symbol)]) -> Stmt<'a> {
+            let lets: Vec<_> = pairs
+                .iter()
+                .map(|(list, index)| (self.symbol("result"), self.list_get(*list, *index)))
+                .collect();
+
+            let returned = lets.last().unwrap().0;
+
+            lets.into_iter()
+                .rev()
+                .fold(Stmt::Ret(returned), |continuation, (symbol, expr)| {
+                    let continuation = self.arena.alloc(continuation);
+
+                    Stmt::Let(symbol, expr, self.result_layout, continuation)
+                })
+        }
+
+        /// `len = List.len list`, then `cond = len <op> index` (or `index <op> len`), then an
+        /// `if cond then ... else ...`
+        fn if_compared(
+            &mut self,
+            op: LowLevel,
+            index_first: bool,
+            then_branch: Stmt<'a>,
+            else_branch: Stmt<'a>,
+        ) -> Stmt<'a> {
+            let len = self.symbol("len");
+            let cond = self.symbol("cond");
+
+            let arguments = if index_first {
+                [self.index, len]
+            } else {
+                [len, self.index]
+            };
+
+            let switch = Stmt::Switch {
+                cond_symbol: cond,
+                cond_layout: Layout::BOOL,
+                branches: self.arena.alloc([(
+                    1,
+                    BranchInfo::Constructor {
+                        scrutinee: cond,
+                        layout: Layout::BOOL,
+                        tag_id: 1,
+                    },
+                    then_branch,
+                )]),
+                default_branch: (
+                    BranchInfo::Constructor {
+                        scrutinee: cond,
+                        layout: Layout::BOOL,
+                        tag_id: 0,
+                    },
+                    self.arena.alloc(else_branch),
+                ),
+                ret_layout: self.result_layout,
+            };
+
+            let compare = Stmt::Let(
+                cond,
+                self.lowlevel(op, &arguments),
+                Layout::BOOL,
+                self.arena.alloc(switch),
+            );
+
+            Stmt::Let(
+                len,
+                self.lowlevel(LowLevel::ListLen, &[self.list]),
+                Layout::U64,
+                self.arena.alloc(compare),
+            )
+        }
+
+        /// Removes the bounds checks of a procedure with this body, and returns its new body
+        fn run(&mut self, body: Stmt<'a>) -> Stmt<'a> {
+            let name = self.symbol("f");
+            let args = self.arena.alloc([
+                (self.list_layout, self.list),
+                (self.list_layout, self.other_list),
+                (Layout::U64, self.index),
+                (Layout::U64, self.other_index),
+            ]);
+            let proc_layout = ProcLayout {
+                arguments: self.arena.alloc(args.map(|(layout, _)| layout)),
+                result: self.result_layout,
+                niche: Niche::NONE,
+            };
+
+            let proc = Proc {
+                name: LambdaName::no_niche(name),
+                args,
+                body,
+                closure_data_layout: None,
+                ret_layout: self.result_layout,
+                is_self_recursive: SelfRecursive::NotSelfRecursive,
+                host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+            };
+
+            let mut procs = MutMap::default();
+            procs.insert((name, proc_layout), proc);
+
+            remove_bounds_checks(
+                self.arena,
+                &self.interner,
+                ModuleId::ATTR,
+                &mut self.ident_ids,
+                &mut procs,
+            );
+
+            procs.remove(&(name, proc_layout)).unwrap().body
+        }
+    }
+
+    /// For every `List.get` in the lets of `stmt`, whether it was replaced by `List.getUnsafe`
+    fn unchecked_gets(stmt: &Stmt) -> Vec<bool> {
+        let mut unchecked = Vec::new();
+        let mut current = stmt;
+
+        while let Stmt::Let(_, expr, _, continuation) = current {
+            match expr {
+                Expr::Call(Call {
+                    call_type: CallType::ByName { name, .. },
+                    ..
+                }) if name.name() == Symbol::LIST_GET => unchecked.push(false),
+                Expr::Call(Call {
+                    call_type:
+                        CallType::LowLevel {
+                            op: LowLevel::ListGetUnsafe,
+                            ..
+                        },
+                    ..
+                }) => unchecked.push(true),
+                _ => {}
+            }
+
+            current = continuation;
+        }
+
+        unchecked
+    }
+
+    /// The `then` and `else` branches of the first `if` in `stmt`
+    fn if_branches<'s, 'a>(stmt: &'s Stmt<'a>) -> (&'s Stmt<'a>, &'s Stmt<'a>) {
+        match stmt {
+            Stmt::Let(_, _, _, continuation) => if_branches(continuation),
+            Stmt::Switch {
+                branches,
+                default_branch,
+                ..
+            } => (&branches[0].2, default_branch.1),
+            _ => panic!("no if in {stmt:?}"),
+        }
+    }
+
+    /// Which branch of `if <comparison> then List.get list index else List.get list index`
+    /// gets its bounds check removed
+    fn unchecked_branches(op: LowLevel, index_first: bool) -> (bool, bool) {
+        let arena = Bump::new();
+        let mut program = Program::new(&arena);
+        let (list, index) = (program.list, program.index);
+
+        let then_branch = program.gets(&[(list, index)]);
+        let else_branch = program.gets(&[(list, index)]);
+        let body = program.if_compared(op, index_first, then_branch, else_branch);
+        let body = program.run(body);
+
+        let (then_branch, else_branch) = if_branches(&body);
+
+        (
+            unchecked_gets(then_branch) == [true],
+            unchecked_gets(else_branch) == [true],
+        )
+    }
+
+    #[test]
+    fn index_compared_to_length() {
+        // index < len, len > index
+        assert_eq!(unchecked_branches(LowLevel::NumLt, true), (true, false));
+        assert_eq!(unchecked_branches(LowLevel::NumGt, false), (true, false));
+
+        // index >= len, len <= index
+        assert_eq!(unchecked_branches(LowLevel::NumGte, true), (false, true));
+        assert_eq!(unchecked_branches(LowLevel::NumLte, false), (false, true));
+    }
+
+    #[test]
+    fn length_compared_to_index() {
+        // The same comparisons with the operands swapped say nothing about the bounds:
+        // len < index, index > len, len >= index, index <= len
+        assert_eq!(unchecked_branches(LowLevel::NumLt, false), (false, false));
+        assert_eq!(unchecked_branches(LowLevel::NumGt, true), (false, false));
+        assert_eq!(unchecked_branches(LowLevel::NumGte, false), (false, false));
+        assert_eq!(unchecked_branches(LowLevel::NumLte, true), (false, false));
+    }
+
+    #[test]
+    fn comparison_only_covers_its_list_and_index() {
+        let arena = Bump::new();
+        let mut program = Program::new(&arena);
+        let (list, other_list, index, other_index) = (
+            program.list,
+            program.other_list,
+            program.index,
+            program.other_index,
+        );
+
+        let then_branch = program.gets(&[
+            (list, index),
+            (list, other_index),
+            (other_list, index),
+            (other_list, other_index),
+        ]);
+        let else_branch = program.gets(&[(list, index)]);
+        let body = program.if_compared(LowLevel::NumLt, true, then_branch, else_branch);
+        let body = program.run(body);
+
+        let (then_branch, _) = if_branches(&body);
+
+        assert_eq!(unchecked_gets(then_branch), [true, false, false, false]);
+    }
+
+    #[test]
+    fn list_pattern_minimum_length() {
+        // `when list is [_, _, ..] -> ...`, with the literal indexes 1 and 2
+        let arena = Bump::new();
+        let mut program = Program::new(&arena);
+        let (list, other_list) = (program.list, program.other_list);
+        let [len, one, two] = ["len", "one", "two"].map(|name| program.symbol(name));
+
+        let branch = program.gets(&[(list, one), (list, two), (other_list, one)]);
+        let fallback = program.gets(&[(list, one)]);
+
+        let switch = Stmt::Switch {
+            cond_symbol: len,
+            cond_layout: Layout::U64,
+            branches: arena.alloc([(
+                2,
+                BranchInfo::List {
+                    scrutinee: list,
+                    len: 2,
+                },
+                branch,
+            )]),
+            default_branch: (BranchInfo::None, arena.alloc(fallback)),
+            ret_layout: program.result_layout,
+        };
+
+        let literal = |value: i128| Expr::Literal(Literal::Int(value.to_ne_bytes()));
+        let body = [
+            (len, program.lowlevel(LowLevel::ListLen, &[list])),
+            (one, literal(1)),
+            (two, literal(2)),
+        ]
+        .into_iter()
+        .rev()
+        .fold(switch, |continuation, (symbol, expr)| {
+            Stmt::Let(symbol, expr, Layout::U64, arena.alloc(continuation))
+        });
+        let body = program.run(body);
+
+        let (branch, fallback) = if_branches(&body);
+
+        assert_eq!(unchecked_gets(branch), [true, false, false]);
+        assert_eq!(unchecked_gets(fallback), [false]);
+    }
+}
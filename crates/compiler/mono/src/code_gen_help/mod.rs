@@ -135,7 +135,7 @@ impl<'a> CodeGenHelp<'a> {
         following: &'a Stmt<'a>,
     ) -> (&'a Stmt<'a>, Vec<'a, (Symbol, ProcLayout<'a>)>) {
         let op = match modify {
-            ModifyRc::Inc(..) => HelperOp::Inc,
+            ModifyRc::Inc(..) | ModifyRc::IncN(..) => HelperOp::Inc,
             ModifyRc::Dec(_) => HelperOp::Dec,
             ModifyRc::DecRef(_) => {
                 let jp_decref = JoinPointId(self.create_symbol(ident_ids, "jp_decref"));
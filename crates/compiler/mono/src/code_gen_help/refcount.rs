@@ -58,6 +58,23 @@ pub fn refcount_stmt<'a>(
             arena.alloc(amount_stmt(arena.alloc(call_stmt)))
         }
 
+        ModifyRc::IncN(structure, amount) => {
+            // Call helper proc, passing the Roc structure and the amount as it is at runtime
+            let call_result_empty = root.create_symbol(ident_ids, "call_result_empty");
+            let call_expr = root
+                .call_specialized_op(
+                    ident_ids,
+                    ctx,
+                    layout_interner,
+                    layout,
+                    arena.alloc([*structure, *amount]),
+                )
+                .unwrap();
+
+            let call_stmt = Stmt::Let(call_result_empty, call_expr, LAYOUT_UNIT, following);
+            arena.alloc(call_stmt)
+        }
+
         ModifyRc::Dec(structure) => {
             // Call helper proc, passing the Roc structure
             let call_result_empty = root.create_symbol(ident_ids, "call_result_empty");
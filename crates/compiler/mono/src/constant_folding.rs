@@ -0,0 +1,242 @@
+//! Replace operations whose arguments are all known at compile time (like `1 + 2`,
+//! `Str.concat "a" "b"`, or `List.len [1, 2, 3]`) with their results, and then remove the
+//! literals which are no longer used.
+//!
+//! Top-level values are folded first, in initialization order. A value which folds down to a
+//! literal, like `timeoutMs = 60 * 1000`, is then used as that literal wherever it is referred to,
+//! instead of calling its thunk.
+//!
+//! This runs before refcounting operations are inserted, so the temporaries it removes
+//! never get any refcounting operations in the first place.
+
+use bumpalo::Bump;
+use roc_builtins::bitcode::IntWidth;
+use roc_collections::MutMap;
+use roc_module::low_level::{LowLevel, LowLevelWrapperType};
+use roc_module::symbol::Symbol;
+
+use crate::initialization::{is_thunk, InitializationOrder};
+use crate::ir::{Call, CallType, Expr, ListLiteralElement, Literal, Proc, ProcLayout, Stmt};
+use crate::ir_walk::{map_lets, used_symbols};
+use crate::layout::{Builtin, InLayout, LayoutInterner, LayoutRepr, STLayoutInterner};
+
+/// The top-level values which were folded to a literal
+type KnownThunks<'a> = MutMap<(Symbol, ProcLayout<'a>), Literal<'a>>;
+
+pub fn fold_constants<'a, 'i>(
+    arena: &'a Bump,
+    layout_interner: &'i STLayoutInterner<'a>,
+    initialization: &InitializationOrder<'a>,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    let mut thunks = KnownThunks::default();
+
+    for key in initialization.order.iter() {
+        if let Some(proc) = procs.get_mut(key) {
+            fold_proc(arena, layout_interner, &thunks, proc);
+
+            if let Some(literal) = returned_literal(&proc.body) {
+                thunks.insert(*key, literal);
+            }
+        }
+    }
+
+    for (key, proc) in procs.iter_mut() {
+        // the thunks were folded above, apart from the ones in cycles, which only crash
+        if !is_thunk(key) {
+            fold_proc(arena, layout_interner, &thunks, proc);
+        }
+    }
+}
+
+fn fold_proc<'a>(
+    arena: &'a Bump,
+    layout_interner: &STLayoutInterner<'a>,
+    thunks: &KnownThunks<'a>,
+    proc: &mut Proc<'a>,
+) {
+    let mut known = MutMap::default();
+
+    let folded = map_lets(
+        arena,
+        arena.alloc(proc.body.clone()),
+        &mut |symbol, expr, layout| {
+            let new_expr = match fold_expr(arena, layout_interner, &known, thunks, expr, layout) {
+                Some(literal) => Expr::Literal(literal),
+                None => expr.clone(),
+            };
+
+            match &new_expr {
+                Expr::Literal(literal) => {
+                    known.insert(symbol, Known::Literal(*literal));
+                }
+                Expr::Array { elems, .. } => {
+                    known.insert(symbol, Known::ListLen(elems.len() as u64));
+                }
+                Expr::EmptyArray => {
+                    known.insert(symbol, Known::ListLen(0));
+                }
+                _ => {}
+            }
+
+            Some(new_expr)
+        },
+    );
+
+    let used = used_symbols(folded);
+
+    let pruned = map_lets(arena, folded, &mut |symbol, expr, _layout| {
+        if is_literal(expr) && !used.contains(&symbol) {
+            None
+        } else {
+            Some(expr.clone())
+        }
+    });
+
+    proc.body = pruned.clone();
+}
+
+/// The literal a folded body returns, if that is all it does.
+fn returned_literal<'a>(body: &Stmt<'a>) -> Option<Literal<'a>> {
+    match body {
+        Stmt::Let(symbol, Expr::Literal(literal), _, Stmt::Ret(returned)) if symbol == returned => {
+            Some(*literal)
+        }
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Known<'a> {
+    Literal(Literal<'a>),
+    /// A list literal, whose length we know even if its elements aren't constants
+    ListLen(u64),
+}
+
+fn fold_expr<'a>(
+    arena: &'a Bump,
+    layout_interner: &STLayoutInterner<'a>,
+    known: &MutMap<Symbol, Known<'a>>,
+    thunks: &KnownThunks<'a>,
+    expr: &Expr<'a>,
+    layout: InLayout<'a>,
+) -> Option<Literal<'a>> {
+    let (op, arguments) = match expr {
+        Expr::Call(Call {
+            call_type:
+                CallType::ByName {
+                    name,
+                    ret_layout,
+                    arg_layouts: [],
+                    ..
+                },
+            arguments: [],
+        }) => {
+            let proc_layout = ProcLayout {
+                arguments: &[],
+                result: *ret_layout,
+                niche: name.niche(),
+            };
+
+            return thunks.get(&(name.name(), proc_layout)).copied();
+        }
+        Expr::Call(Call {
+            call_type: CallType::LowLevel { op, .. },
+            arguments,
+        }) => (*op, *arguments),
+        Expr::Call(Call {
+            call_type: CallType::ByName { name, .. },
+            arguments,
+        }) => match LowLevelWrapperType::from_symbol(name.name()) {
+            LowLevelWrapperType::CanBeReplacedBy(op) => (op, *arguments),
+            LowLevelWrapperType::NotALowLevelWrapper => return None,
+        },
+        _ => return None,
+    };
+
+    let literal = |symbol: &Symbol| match known.get(symbol) {
+        Some(Known::Literal(literal)) => Some(*literal),
+        _ => None,
+    };
+
+    match (op, arguments) {
+        (LowLevel::ListLen, [list]) => match known.get(list) {
+            Some(Known::ListLen(len)) => Some(Literal::Int((*len as i128).to_ne_bytes())),
+            _ => None,
+        },
+        (LowLevel::StrConcat, [a, b]) => match (literal(a)?, literal(b)?) {
+            (Literal::Str(a), Literal::Str(b)) => Some(Literal::Str(
+                bumpalo::format!(in arena, "{}{}", a, b).into_bump_str(),
+            )),
+            _ => None,
+        },
+        _ => {
+            // U128s are stored differently, and are rare enough not to bother with.
+            let width = match layout_interner.get(layout).repr {
+                LayoutRepr::Builtin(Builtin::Int(width)) if width != IntWidth::U128 => width,
+                _ => return None,
+            };
+
+            let mut ints = Vec::with_capacity(arguments.len());
+
+            for argument in arguments.iter() {
+                match literal(argument)? {
+                    Literal::Int(bytes) => ints.push(i128::from_ne_bytes(bytes)),
+                    _ => return None,
+                }
+            }
+
+            // Operations which would overflow are left alone, so they still crash at runtime.
+            let answer = match (op, ints.as_slice()) {
+                (LowLevel::NumAdd, [a, b]) => a.checked_add(*b).filter(|n| fits(width, *n)),
+                (LowLevel::NumSub, [a, b]) => a.checked_sub(*b).filter(|n| fits(width, *n)),
+                (LowLevel::NumMul, [a, b]) => a.checked_mul(*b).filter(|n| fits(width, *n)),
+                (LowLevel::NumNeg, [a]) => a.checked_neg().filter(|n| fits(width, *n)),
+                (LowLevel::NumAddWrap, [a, b]) => Some(wrap(width, a.wrapping_add(*b))),
+                (LowLevel::NumSubWrap, [a, b]) => Some(wrap(width, a.wrapping_sub(*b))),
+                (LowLevel::NumMulWrap, [a, b]) => Some(wrap(width, a.wrapping_mul(*b))),
+                _ => None,
+            }?;
+
+            Some(Literal::Int(answer.to_ne_bytes()))
+        }
+    }
+}
+
+fn fits(width: IntWidth, n: i128) -> bool {
+    let bits = width.stack_size() * 8;
+
+    match (width.is_signed(), bits) {
+        (true, 128) => true,
+        (true, _) => (-(1i128 << (bits - 1))..(1i128 << (bits - 1))).contains(&n),
+        (false, _) => (0..(1i128 << bits)).contains(&n),
+    }
+}
+
+/// Wrap around the given number like an integer of the given width would on overflow.
+fn wrap(width: IntWidth, n: i128) -> i128 {
+    let bits = width.stack_size() * 8;
+
+    if bits == 128 {
+        return n;
+    }
+
+    let truncated = n & ((1i128 << bits) - 1);
+
+    if width.is_signed() && truncated >= 1i128 << (bits - 1) {
+        truncated - (1i128 << bits)
+    } else {
+        truncated
+    }
+}
+
+/// Whether this expression is a constant which can be removed if nothing refers to it.
+fn is_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal(_) | Expr::EmptyArray => true,
+        Expr::Array { elems, .. } => elems
+            .iter()
+            .all(|elem| matches!(elem, ListLiteralElement::Literal(_))),
+        _ => false,
+    }
+}
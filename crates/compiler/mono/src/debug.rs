@@ -1,5 +1,20 @@
+mod call_graph;
 mod checker;
+mod closures;
+mod layout_info;
+mod refcount_interpreter;
 mod report;
+mod specializations;
 
+pub use call_graph::{call_graph, CallGraph, CallGraphFunction};
 pub use checker::{check_procs, Problem, Problems};
+pub use closures::format_closures;
+pub use layout_info::{
+    layout_info, FieldInfo, LayoutInfo, Shape, TagIdStorage, TagInfo, TagUnionInfo,
+};
+pub use refcount_interpreter::{
+    check_drop_specialization, check_rc_scheduling, trace_heap, HeapTrace, InterpretError,
+    RefcountPassMismatch,
+};
 pub use report::format_problems;
+pub use specializations::format_specializations;
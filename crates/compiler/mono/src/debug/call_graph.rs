@@ -0,0 +1,87 @@
+//! The graph of which specialized functions call which, for tools that visualize a program or
+//! want to know what a change to a function can affect.
+
+use roc_collections::MutMap;
+use roc_module::symbol::{Interns, Symbol};
+use ven_pretty::Arena;
+
+use crate::{
+    ir::{Proc, ProcLayout},
+    layout::LayoutInterner,
+};
+
+use super::report::{format_proc_layout, format_symbol};
+use super::specializations::for_each_callee;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallGraphFunction {
+    pub symbol: Symbol,
+    /// The layout this specialization of the function was made for, as it is shown in reports
+    pub layout: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallGraph {
+    pub functions: Vec<CallGraphFunction>,
+    /// Calls from one function to another, as indices into `functions`
+    pub calls: Vec<(usize, usize)>,
+}
+
+/// Builds the call graph of the specialized procedures. A function that is passed to a
+/// higher-order builtin like `List.map` counts as called by the function that passes it.
+pub fn call_graph<'a, I>(
+    interns: &Interns,
+    interner: &I,
+    procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) -> CallGraph
+where
+    I: LayoutInterner<'a>,
+{
+    let f = Arena::new();
+
+    let mut specializations: Vec<_> = procs
+        .keys()
+        .map(|(symbol, proc_layout)| {
+            // on one line, to be usable as a label
+            let layout = format_proc_layout(&f, interner, *proc_layout)
+                .1
+                .pretty(usize::MAX)
+                .to_string();
+
+            ((*symbol, *proc_layout), layout)
+        })
+        .collect();
+
+    // keep the output stable between runs, in which modules can get different ids
+    specializations.sort_by_cached_key(|((symbol, _), layout)| {
+        let name = format_symbol(&f, interns, *symbol).1.pretty(80).to_string();
+
+        (name, layout.clone())
+    });
+
+    let index_of: MutMap<(Symbol, ProcLayout<'a>), usize> = specializations
+        .iter()
+        .enumerate()
+        .map(|(index, (key, _))| (*key, index))
+        .collect();
+
+    let mut calls = Vec::new();
+
+    for (caller, (key, _)) in specializations.iter().enumerate() {
+        for_each_callee(&procs[key].body, &mut |callee| {
+            if let Some(callee) = index_of.get(&callee) {
+                calls.push((caller, *callee));
+            }
+        });
+    }
+
+    calls.sort_unstable();
+    calls.dedup();
+
+    let functions = specializations
+        .into_iter()
+        .map(|((symbol, _), layout)| CallGraphFunction { symbol, layout })
+        .collect();
+
+    CallGraph { functions, calls }
+}
@@ -27,6 +27,7 @@ pub enum UseKind {
     SwitchCond,
     ExpectCond,
     ExpectLookup,
+    IncCount,
 }
 
 pub enum ProblemKind<'a> {
@@ -429,7 +430,7 @@ impl<'a, 'r> Ctx<'a, 'r> {
             } => self.with_sym_layout(structure, |ctx, _def_line, layout| {
                 ctx.check_union_at_index(structure, layout, union_layout, tag_id, index)
             }),
-            Expr::Array { elem_layout, elems } => {
+            Expr::Array { elem_layout, elems } | Expr::StackArray { elem_layout, elems } => {
                 for elem in elems.iter() {
                     match elem {
                         ListLiteralElement::Literal(_) => {}
@@ -447,10 +448,12 @@ impl<'a, 'r> Ctx<'a, 'r> {
                 // TODO don't know what the element layout is
                 None
             }
-            &Expr::ExprBox { symbol } => self.with_sym_layout(symbol, |ctx, _def_line, layout| {
-                let inner = layout;
-                Some(ctx.interner.insert_no_semantic(LayoutRepr::Boxed(inner)))
-            }),
+            &Expr::ExprBox { symbol } | &Expr::StackBox { symbol } => {
+                self.with_sym_layout(symbol, |ctx, _def_line, layout| {
+                    let inner = layout;
+                    Some(ctx.interner.insert_no_semantic(LayoutRepr::Boxed(inner)))
+                })
+            }
             &Expr::ExprUnbox { symbol } => self.with_sym_layout(symbol, |ctx, def_line, layout| {
                 let layout = ctx.resolve(layout);
                 match ctx.interner.get(layout).repr {
@@ -614,6 +617,9 @@ impl<'a, 'r> Ctx<'a, 'r> {
             CallType::Foreign {
                 foreign_symbol: _,
                 ret_layout,
+                arg_ownership: _,
+                ret_ownership: _,
+                pure: _,
             } => Some(*ret_layout),
             CallType::LowLevel {
                 op: _,
@@ -656,6 +662,11 @@ impl<'a, 'r> Ctx<'a, 'r> {
                 // TODO: also check that sym layout needs refcounting
                 self.check_sym_exists(sym);
             }
+            ModifyRc::IncN(sym, count) => {
+                self.check_sym_exists(sym);
+                let count_layout = Layout::usize(self.interner.target_info());
+                self.check_sym_layout(count, count_layout, UseKind::IncCount);
+            }
         }
     }
 }
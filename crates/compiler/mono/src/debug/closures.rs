@@ -0,0 +1,338 @@
+//! A report of how each lambda set is represented at runtime, to find out why calling a closure
+//! allocates.
+
+use std::fmt::Display;
+
+use roc_collections::{MutMap, MutSet};
+use roc_module::symbol::{Interns, Symbol};
+use ven_pretty::{Arena, DocAllocator};
+
+use crate::{
+    ir::{Call, CallType, Expr, Parens, Proc, ProcLayout, Stmt},
+    layout::{
+        Builtin, ClosureCallOptions, InLayout, LambdaSet, LayoutInterner, LayoutRepr, UnionLayout,
+    },
+};
+
+use super::report::{format_header, format_symbol, stack, Doc};
+
+type Procs<'a> = MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>;
+
+/// Lists every lambda set in the program, the boxed ones first, with how its closures are
+/// represented.
+///
+/// A lambda set is boxed when some of its functions capture closures of the same lambda set,
+/// like the functions of a parser combinator library. The report names those functions, since
+/// they are what needs to change to keep the closures off the heap. It also names the functions
+/// which are called with their capture instead of the lambda set, which the
+/// [flatten_closures](crate::flatten_closures) pass does for lambda sets represented as unions.
+pub fn format_closures<'a, I>(interns: &Interns, interner: &I, procs: &Procs<'a>) -> impl Display
+where
+    I: LayoutInterner<'a>,
+{
+    let mut lambda_sets = MutMap::default();
+    let mut seen = MutSet::default();
+    let mut flattened: MutMap<InLayout<'a>, Vec<Symbol>> = MutMap::default();
+
+    for proc in procs.values() {
+        for (layout, _) in proc.args.iter() {
+            collect_lambda_sets(interner, *layout, &mut seen, &mut lambda_sets);
+        }
+
+        collect_lambda_sets(interner, proc.ret_layout, &mut seen, &mut lambda_sets);
+
+        for_each_layout(&proc.body, &mut |layout| {
+            collect_lambda_sets(interner, layout, &mut seen, &mut lambda_sets);
+        });
+
+        // The function of a closure takes its lambda set as its last argument, unless it was
+        // changed to take its capture instead.
+        if let (Some(closure_data_layout), Some((last_layout, _))) =
+            (proc.closure_data_layout, proc.args.last())
+        {
+            if *last_layout != closure_data_layout {
+                flattened
+                    .entry(closure_data_layout)
+                    .or_default()
+                    .push(proc.name.name());
+            }
+        }
+    }
+
+    let f = Arena::new();
+
+    // Render the lambda sets up front, so they can be listed in a stable order
+    let mut rendered: Vec<_> = lambda_sets
+        .into_values()
+        .filter(|lambda_set| !lambda_set.is_empty())
+        .map(|lambda_set| {
+            let flattened = flattened
+                .get(&lambda_set.full_layout)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+
+            let (is_boxed, doc) = format_lambda_set(&f, interns, interner, lambda_set, flattened);
+
+            (!is_boxed, doc.1.pretty(80).to_string())
+        })
+        .collect();
+    rendered.sort();
+    rendered.dedup();
+
+    let lambda_set_docs = rendered
+        .into_iter()
+        .map(|(_, rendered)| f.as_string(rendered));
+
+    let all = stack(
+        &f,
+        std::iter::once(format_header(&f, "CLOSURES")).chain(lambda_set_docs),
+    );
+
+    all.1.pretty(80).to_string()
+}
+
+fn format_lambda_set<'a, 'd, I>(
+    f: &'d Arena<'d>,
+    interns: &'d Interns,
+    interner: &I,
+    lambda_set: LambdaSet<'a>,
+    flattened: &[Symbol],
+) -> (bool, Doc<'d>)
+where
+    I: LayoutInterner<'a>,
+{
+    let members = f.intersperse(
+        lambda_set
+            .set
+            .iter()
+            .map(|(symbol, _)| format_symbol(f, interns, *symbol)),
+        f.reflow(", "),
+    );
+    let name = f.concat([f.text("["), members, f.text("]")]);
+
+    let (is_boxed, representation) = match lambda_set.call_by_name_options(interner) {
+        ClosureCallOptions::Union(union_layout)
+            if !matches!(union_layout, UnionLayout::NonRecursive(_)) =>
+        {
+            (true, format_boxed(f, interns, interner, lambda_set))
+        }
+        options => (false, f.reflow(unboxed_reason(options))),
+    };
+
+    let captures = lambda_set.set.iter().map(|(symbol, captures)| {
+        let captures_doc = if captures.is_empty() {
+            f.reflow("captures nothing")
+        } else {
+            f.reflow("captures ").append(f.intersperse(
+                captures.iter().map(|capture| {
+                    interner.to_doc(*capture, f, &mut Default::default(), Parens::NotNeeded)
+                }),
+                f.reflow(", "),
+            ))
+        };
+
+        let flattened_doc = if flattened.contains(symbol) {
+            f.reflow(", and is called with its capture instead of the lambda set")
+        } else {
+            f.nil()
+        };
+
+        f.concat([
+            format_symbol(f, interns, *symbol),
+            f.space(),
+            captures_doc,
+            flattened_doc,
+        ])
+    });
+
+    let doc = f.concat([name, f.space(), representation]).append(
+        f.hardline()
+            .append(f.intersperse(captures, f.hardline()))
+            .nest(4),
+    );
+
+    (is_boxed, doc)
+}
+
+/// Why a recursive lambda set is boxed: the functions that capture closures of the same set.
+fn format_boxed<'a, 'd, I>(
+    f: &'d Arena<'d>,
+    interns: &'d Interns,
+    interner: &I,
+    lambda_set: LambdaSet<'a>,
+) -> Doc<'d>
+where
+    I: LayoutInterner<'a>,
+{
+    let recursive: Vec<_> = lambda_set
+        .set
+        .iter()
+        .filter(|(_, captures)| {
+            captures
+                .iter()
+                .any(|capture| refers_to(interner, *capture, lambda_set))
+        })
+        .map(|(symbol, _)| format_symbol(f, interns, *symbol))
+        .collect();
+
+    if recursive.is_empty() {
+        f.reflow("is boxed, because it is recursive")
+    } else {
+        f.reflow("is boxed, because these functions capture closures of the same lambda set: ")
+            .append(f.intersperse(recursive, f.reflow(", ")))
+    }
+}
+
+fn unboxed_reason(options: ClosureCallOptions) -> &'static str {
+    match options {
+        ClosureCallOptions::Void => "has no functions, so it is never called",
+        ClosureCallOptions::EnumDispatch(_) => {
+            "is not boxed: none of its functions capture anything, so it is just a tag id"
+        }
+        ClosureCallOptions::Struct { field_layouts: &[] } => {
+            "is not boxed: its only function captures nothing, so it takes no space"
+        }
+        ClosureCallOptions::Struct { .. } => {
+            "is not boxed: the captures of its only function are stored in a struct"
+        }
+        ClosureCallOptions::UnwrappedCapture(_) => {
+            "is not boxed: its only function captures a single value, which is stored as is"
+        }
+        ClosureCallOptions::Union(_) => {
+            "is not boxed: the captures of its functions are stored in a tag union on the stack"
+        }
+    }
+}
+
+/// Adds every lambda set that occurs in `layout` to `lambda_sets`, including the ones in the
+/// captures of other lambda sets.
+fn collect_lambda_sets<'a, I>(
+    interner: &I,
+    layout: InLayout<'a>,
+    seen: &mut MutSet<InLayout<'a>>,
+    lambda_sets: &mut MutMap<InLayout<'a>, LambdaSet<'a>>,
+) where
+    I: LayoutInterner<'a>,
+{
+    if !seen.insert(layout) {
+        return;
+    }
+
+    match interner.get(layout).repr {
+        LayoutRepr::Builtin(Builtin::List(element)) | LayoutRepr::Boxed(element) => {
+            collect_lambda_sets(interner, element, seen, lambda_sets);
+        }
+        LayoutRepr::Builtin(_) | LayoutRepr::RecursivePointer(_) => {}
+        LayoutRepr::Struct { field_layouts } => {
+            for field in field_layouts {
+                collect_lambda_sets(interner, *field, seen, lambda_sets);
+            }
+        }
+        LayoutRepr::Union(union_layout) => {
+            for tag in union_tags(union_layout) {
+                for field in tag {
+                    collect_lambda_sets(interner, *field, seen, lambda_sets);
+                }
+            }
+        }
+        LayoutRepr::LambdaSet(lambda_set) => {
+            lambda_sets.insert(lambda_set.full_layout, lambda_set);
+
+            for (_, captures) in lambda_set.set.iter() {
+                for capture in captures.iter() {
+                    collect_lambda_sets(interner, *capture, seen, lambda_sets);
+                }
+            }
+        }
+    }
+}
+
+/// Whether `layout` contains a recursion pointer to the lambda set, or to its representation,
+/// without going through any other recursion pointers.
+fn refers_to<'a, I>(interner: &I, layout: InLayout<'a>, target: LambdaSet<'a>) -> bool
+where
+    I: LayoutInterner<'a>,
+{
+    match interner.get(layout).repr {
+        LayoutRepr::RecursivePointer(pointee) => {
+            pointee == target.full_layout || pointee == target.representation
+        }
+        LayoutRepr::Builtin(Builtin::List(element)) | LayoutRepr::Boxed(element) => {
+            refers_to(interner, element, target)
+        }
+        LayoutRepr::Builtin(_) => false,
+        LayoutRepr::Struct { field_layouts } => field_layouts
+            .iter()
+            .any(|field| refers_to(interner, *field, target)),
+        LayoutRepr::Union(union_layout) => union_tags(union_layout)
+            .into_iter()
+            .any(|tag| tag.iter().any(|field| refers_to(interner, *field, target))),
+        LayoutRepr::LambdaSet(lambda_set) => refers_to(interner, lambda_set.representation, target),
+    }
+}
+
+/// The field layouts of each tag of the union that has fields.
+fn union_tags<'a>(union_layout: UnionLayout<'a>) -> Vec<&'a [InLayout<'a>]> {
+    match union_layout {
+        UnionLayout::NonRecursive(tags) | UnionLayout::Recursive(tags) => tags.to_vec(),
+        UnionLayout::NullableWrapped { other_tags, .. } => other_tags.to_vec(),
+        UnionLayout::NonNullableUnwrapped(fields)
+        | UnionLayout::NullableUnwrapped {
+            other_fields: fields,
+            ..
+        } => vec![fields],
+    }
+}
+
+/// Calls `f` with the layout of every value that `stmt` binds or passes to a call.
+fn for_each_layout<'a>(stmt: &Stmt<'a>, f: &mut impl FnMut(InLayout<'a>)) {
+    let mut stack = vec![stmt];
+
+    while let Some(stmt) = stack.pop() {
+        match stmt {
+            Stmt::Let(_, expr, layout, rest) => {
+                f(*layout);
+
+                if let Expr::Call(Call {
+                    call_type: CallType::ByName { arg_layouts, .. },
+                    ..
+                }) = expr
+                {
+                    arg_layouts.iter().for_each(|layout| f(*layout));
+                }
+
+                stack.push(rest);
+            }
+            Stmt::Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                stack.push(default_branch.1);
+            }
+            Stmt::Join {
+                parameters,
+                body,
+                remainder,
+                ..
+            } => {
+                parameters.iter().for_each(|param| f(param.layout));
+
+                stack.push(body);
+                stack.push(remainder);
+            }
+            Stmt::Refcounting(_, rest)
+            | Stmt::Expect {
+                remainder: rest, ..
+            }
+            | Stmt::ExpectFx {
+                remainder: rest, ..
+            }
+            | Stmt::Dbg {
+                remainder: rest, ..
+            } => stack.push(rest),
+            Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => {}
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! The size, alignment and memory layout of a type for a target, for platform authors who need
+//! to match it on the host side, and for finding out why a value takes up so much memory.
+
+use roc_module::ident::TagName;
+use roc_types::pretty_print::{chase_ext_tag_union, ChasedExt};
+use roc_types::subs::{Subs, Variable};
+
+use crate::layout::{InLayout, Layout, LayoutInterner, LayoutRepr, UnionLayout};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutInfo {
+    /// The layout, as it is shown in reports
+    pub layout: String,
+    pub size: u32,
+    pub alignment: u32,
+    pub shape: Shape,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Shape {
+    /// A number, string, list or other builtin
+    Builtin,
+    /// A record or tuple, or a tag union with just one tag
+    Struct(Vec<FieldInfo>),
+    /// A tag union without payloads, which is stored as just the tag id
+    Enum(Vec<TagInfo>),
+    TagUnion(TagUnionInfo),
+    /// A pointer to a value on the heap, as in a `Box`
+    Boxed(String),
+}
+
+/// A field of a struct or of a tag's payload. Fields are stored in order of alignment, so their
+/// order in memory is not the order in which they are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldInfo {
+    /// The name of a record field; tuple elements and tag payloads have none
+    pub name: Option<String>,
+    pub offset: u32,
+    pub size: u32,
+    pub layout: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagInfo {
+    pub name: String,
+    pub id: u16,
+    /// Whether the tag is represented as a null pointer
+    pub is_null: bool,
+    pub payload: Vec<FieldInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagUnionInfo {
+    /// Recursive tag unions are pointers to their payload on the heap
+    pub recursive: bool,
+    pub tag_id: TagIdStorage,
+    pub tags: Vec<TagInfo>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagIdStorage {
+    /// There is only one tag that isn't null, so no tag id needs to be stored
+    None,
+    /// The tag id is stored after the payload
+    Data { offset: u32, size: u32 },
+    /// The tag id is stored in the low bits of the pointer to the payload
+    PointerBits { bits: u32 },
+}
+
+/// Describes the `layout` computed for `var`; the type is needed for the names of the tags.
+pub fn layout_info<'a, I>(
+    interner: &I,
+    subs: &Subs,
+    var: Variable,
+    layout: InLayout<'a>,
+) -> LayoutInfo
+where
+    I: LayoutInterner<'a>,
+{
+    let layout = Layout::runtime_representation_in(layout, interner);
+    let target_info = interner.target_info();
+    let (size, alignment) = interner.stack_size_and_alignment(layout);
+
+    let shape = match interner.get(layout).repr {
+        LayoutRepr::Builtin(_) => match tag_names(subs, var) {
+            Some(names) if names.len() > 1 => Shape::Enum(
+                names
+                    .into_iter()
+                    .enumerate()
+                    .map(|(id, name)| TagInfo {
+                        name,
+                        id: id as u16,
+                        is_null: false,
+                        payload: Vec::new(),
+                    })
+                    .collect(),
+            ),
+            _ => Shape::Builtin,
+        },
+        LayoutRepr::Struct { field_layouts } => {
+            let names = interner.get(layout).semantic().record_fields();
+
+            Shape::Struct(fields(interner, field_layouts, names))
+        }
+        LayoutRepr::Boxed(inner) => Shape::Boxed(interner.dbg(inner)),
+        LayoutRepr::Union(union_layout) => {
+            let tag_id = if union_layout.stores_tag_id_as_data(target_info) {
+                TagIdStorage::Data {
+                    offset: union_layout
+                        .tag_id_offset(interner, target_info)
+                        .unwrap_or(0),
+                    size: union_layout.discriminant().stack_size(),
+                }
+            } else if union_layout.stores_tag_id_in_pointer(target_info) {
+                let (bits, _) = UnionLayout::tag_id_pointer_bits_and_mask(target_info);
+
+                TagIdStorage::PointerBits { bits: bits as u32 }
+            } else {
+                TagIdStorage::None
+            };
+
+            Shape::TagUnion(TagUnionInfo {
+                recursive: !matches!(union_layout, UnionLayout::NonRecursive(_)),
+                tag_id,
+                tags: tags(interner, subs, var, union_layout),
+            })
+        }
+        LayoutRepr::LambdaSet(_) | LayoutRepr::RecursivePointer(_) => {
+            // the runtime representation of a lambda set is never another lambda set, and a
+            // recursive pointer only appears inside of a recursive tag union
+            Shape::Builtin
+        }
+    };
+
+    LayoutInfo {
+        layout: interner.dbg(layout),
+        size,
+        alignment,
+        shape,
+    }
+}
+
+fn tags<'a, I>(
+    interner: &I,
+    subs: &Subs,
+    var: Variable,
+    union_layout: UnionLayout<'a>,
+) -> Vec<TagInfo>
+where
+    I: LayoutInterner<'a>,
+{
+    // tags with payloads in the layout, in the order of their ids
+    let payloads: Vec<(u16, bool, &[InLayout<'a>])> = match union_layout {
+        UnionLayout::NonRecursive(tags) | UnionLayout::Recursive(tags) => tags
+            .iter()
+            .enumerate()
+            .map(|(id, fields)| (id as u16, false, *fields))
+            .collect(),
+        UnionLayout::NonNullableUnwrapped(fields) => vec![(0, false, fields)],
+        UnionLayout::NullableWrapped {
+            nullable_id,
+            other_tags,
+        } => {
+            let mut other_tags = other_tags.iter();
+
+            (0..other_tags.len() as u16 + 1)
+                .map(|id| {
+                    if id == nullable_id {
+                        (id, true, &[][..])
+                    } else {
+                        (id, false, *other_tags.next().unwrap())
+                    }
+                })
+                .collect()
+        }
+        UnionLayout::NullableUnwrapped {
+            nullable_id,
+            other_fields,
+        } => {
+            let nullable_id = nullable_id as u16;
+
+            (0..2)
+                .map(|id| {
+                    if id == nullable_id {
+                        (id, true, &[][..])
+                    } else {
+                        (id, false, other_fields)
+                    }
+                })
+                .collect()
+        }
+    };
+
+    // tags whose payload can't be constructed are left out of the layout, and then the ids no
+    // longer line up with the sorted tag names
+    let names = tag_names(subs, var).filter(|names| names.len() == payloads.len());
+
+    payloads
+        .into_iter()
+        .map(|(id, is_null, field_layouts)| TagInfo {
+            name: match &names {
+                Some(names) => names[id as usize].clone(),
+                None => format!("#{}", id),
+            },
+            id,
+            is_null,
+            payload: fields(interner, field_layouts, None),
+        })
+        .collect()
+}
+
+fn fields<'a, I>(
+    interner: &I,
+    field_layouts: &[InLayout<'a>],
+    names: Option<&[&str]>,
+) -> Vec<FieldInfo>
+where
+    I: LayoutInterner<'a>,
+{
+    let mut offset = 0;
+
+    field_layouts
+        .iter()
+        .enumerate()
+        .map(|(index, field_layout)| {
+            let size = interner.stack_size(*field_layout);
+
+            // fields are sorted by alignment, so they need no padding between them
+            let field = FieldInfo {
+                name: names
+                    .and_then(|names| names.get(index))
+                    .map(|s| s.to_string()),
+                offset,
+                size,
+                layout: interner.dbg(*field_layout),
+            };
+
+            offset += size;
+
+            field
+        })
+        .collect()
+}
+
+/// The names of the tags of a tag union type, in the order of their tag ids
+fn tag_names(subs: &Subs, var: Variable) -> Option<Vec<String>> {
+    let mut tags = Vec::new();
+
+    match chase_ext_tag_union(subs, var, &mut tags) {
+        ChasedExt::Empty => {}
+        ChasedExt::NonEmpty { .. } if !tags.is_empty() => {
+            // an open tag union, whose extension the layout leaves out
+        }
+        ChasedExt::NonEmpty { .. } => return None,
+    }
+
+    let mut names: Vec<TagName> = tags.into_iter().map(|(name, _)| name).collect();
+    names.sort_unstable();
+
+    Some(
+        names
+            .iter()
+            .map(|name| name.0.as_str().to_string())
+            .collect(),
+    )
+}
@@ -0,0 +1,943 @@
+//! A small reference interpreter for mono IR, which models the heap cells and reference counts
+//! of a program and records which cells it allocates and frees. It serves as an oracle for the
+//! passes that rewrite refcount operations: a rewrite is only sound when the program performs
+//! the same logical allocations and frees before and after it, without touching freed memory.
+//!
+//! Only the parts of the IR that describe the shape of data are modelled: structs, boxes, tag
+//! unions, refcount operations, reset and reuse, control flow and calls to other procs. Numbers
+//! are opaque, and anything else (lists, strings, most lowlevels, the host) is reported as
+//! unsupported.
+
+use bumpalo::Bump;
+use roc_collections::MutMap;
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+use roc_target::TargetInfo;
+
+use crate::drop_specialization::specialize_drops;
+use crate::ir::{
+    Call, CallType, Expr, JoinPointId, Literal, ModifyRc, Param, Proc, ProcLayout, Stmt,
+};
+use crate::layout::{InLayout, STLayoutInterner, TagIdIntType, UnionLayout};
+use crate::rc_scheduling::schedule_refcounts;
+use crate::static_data::StaticConstants;
+
+/// How many statements a program may run before we assume it does not terminate
+const FUEL: usize = 100_000;
+
+/// The heap operations a program performed. Writing into a cell that is reused counts as a free
+/// of its old value and an allocation of the new one, so reusing memory does not change a trace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeapTrace<'a> {
+    /// How many cells of each layout were allocated
+    pub allocations: MutMap<InLayout<'a>, u64>,
+    /// How many cells of each layout were freed
+    pub frees: MutMap<InLayout<'a>, u64>,
+}
+
+impl<'a> HeapTrace<'a> {
+    /// How many cells of each layout were still alive when the program returned
+    pub fn leaked(&self) -> MutMap<InLayout<'a>, u64> {
+        self.allocations
+            .iter()
+            .filter_map(|(layout, allocated)| {
+                let freed = self.frees.get(layout).copied().unwrap_or(0);
+
+                (*allocated > freed).then_some((*layout, allocated - freed))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterpretError {
+    /// The cell behind this symbol was used, or its refcount changed, after it was freed
+    UseAfterFree(Symbol),
+    UnknownSymbol(Symbol),
+    UnknownJoinPoint(JoinPointId),
+    UnknownProc(Symbol),
+    /// The program crashed, through a `crash` or a runtime error
+    Crash,
+    /// The program ran for longer than we are willing to interpret it
+    OutOfFuel,
+    /// The program uses IR that the interpreter does not model
+    Unsupported(&'static str),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefcountPassMismatch<'a> {
+    /// The program could not be interpreted before the pass
+    Before(InterpretError),
+    /// The program could not be interpreted after the pass, e.g. because it now uses a cell
+    /// after freeing it
+    After(InterpretError),
+    /// The pass changed which cells are allocated or freed
+    Trace {
+        before: HeapTrace<'a>,
+        after: HeapTrace<'a>,
+    },
+}
+
+/// Runs the proc `entry`, which must not take arguments, and records its heap operations.
+pub fn trace_heap<'a>(
+    procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    entry: (Symbol, ProcLayout<'a>),
+) -> Result<HeapTrace<'a>, InterpretError> {
+    let mut interpreter = Interpreter {
+        procs,
+        heap: Vec::new(),
+        trace: HeapTrace::default(),
+        fuel: FUEL,
+    };
+
+    interpreter.call(entry, Vec::new())?;
+
+    Ok(interpreter.trace)
+}
+
+/// Runs `entry` before and after [specialize_drops], and checks that both runs perform the same
+/// logical allocations and frees. Static constants are not modelled, so none are passed to the
+/// pass.
+pub fn check_drop_specialization<'a>(
+    arena: &'a Bump,
+    layout_interner: &mut STLayoutInterner<'a>,
+    home: ModuleId,
+    ident_ids: &mut IdentIds,
+    target_info: TargetInfo,
+    procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    entry: (Symbol, ProcLayout<'a>),
+) -> Result<(), RefcountPassMismatch<'a>> {
+    check_pass(procs, entry, |procs| {
+        specialize_drops(
+            arena,
+            layout_interner,
+            home,
+            ident_ids,
+            target_info,
+            &StaticConstants::default(),
+            procs,
+        )
+    })
+}
+
+/// Like [check_drop_specialization], but runs [schedule_refcounts] after [specialize_drops], the
+/// way `--schedule-refcounts` does.
+pub fn check_rc_scheduling<'a>(
+    arena: &'a Bump,
+    layout_interner: &mut STLayoutInterner<'a>,
+    home: ModuleId,
+    ident_ids: &mut IdentIds,
+    target_info: TargetInfo,
+    procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    entry: (Symbol, ProcLayout<'a>),
+) -> Result<(), RefcountPassMismatch<'a>> {
+    check_pass(procs, entry, |procs| {
+        specialize_drops(
+            arena,
+            layout_interner,
+            home,
+            ident_ids,
+            target_info,
+            &StaticConstants::default(),
+            procs,
+        );
+        schedule_refcounts(arena, procs);
+    })
+}
+
+fn check_pass<'a>(
+    procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    entry: (Symbol, ProcLayout<'a>),
+    pass: impl FnOnce(&mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>),
+) -> Result<(), RefcountPassMismatch<'a>> {
+    let before = trace_heap(procs, entry).map_err(RefcountPassMismatch::Before)?;
+
+    let mut rewritten = procs.clone();
+    pass(&mut rewritten);
+
+    let after = trace_heap(&rewritten, entry).map_err(RefcountPassMismatch::After)?;
+
+    if before == after {
+        Ok(())
+    } else {
+        Err(RefcountPassMismatch::Trace { before, after })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    /// A number or enum; the interpreter never needs to do arithmetic on them
+    Int(i128),
+    Bool(bool),
+    Struct(Vec<Value>),
+    /// A tag of a non-recursive union, which is stored inline
+    Tag(TagIdIntType, Vec<Value>),
+    /// A pointer to a heap cell
+    Cell(usize),
+    /// The nullable tag of a union, or the token of a reset that did not get a cell
+    Null,
+}
+
+struct Cell<'a> {
+    layout: InLayout<'a>,
+    refcount: u64,
+    /// `None` once the cell is freed, or while it waits to be reused after a reset
+    contents: Option<Value>,
+}
+
+type JoinPoints<'a, 'p> = MutMap<JoinPointId, (&'p [Param<'a>], &'p Stmt<'a>)>;
+
+struct Interpreter<'a, 'p> {
+    procs: &'p MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    heap: Vec<Cell<'a>>,
+    trace: HeapTrace<'a>,
+    fuel: usize,
+}
+
+impl<'a, 'p> Interpreter<'a, 'p> {
+    fn call(
+        &mut self,
+        key: (Symbol, ProcLayout<'a>),
+        arguments: Vec<Value>,
+    ) -> Result<Value, InterpretError> {
+        let procs = self.procs;
+        let proc = procs.get(&key).ok_or(InterpretError::UnknownProc(key.0))?;
+
+        let mut env: MutMap<Symbol, Value> = proc
+            .args
+            .iter()
+            .map(|(_, symbol)| *symbol)
+            .zip(arguments)
+            .collect();
+
+        self.stmt(&mut env, &mut JoinPoints::default(), &proc.body)
+    }
+
+    fn stmt(
+        &mut self,
+        env: &mut MutMap<Symbol, Value>,
+        join_points: &mut JoinPoints<'a, 'p>,
+        mut stmt: &'p Stmt<'a>,
+    ) -> Result<Value, InterpretError> {
+        loop {
+            self.fuel = self.fuel.checked_sub(1).ok_or(InterpretError::OutOfFuel)?;
+
+            match stmt {
+                Stmt::Let(binding, expr, layout, continuation) => {
+                    let value = self.expr(env, expr, *layout)?;
+                    env.insert(*binding, value);
+                    stmt = *continuation;
+                }
+                Stmt::Switch {
+                    cond_symbol,
+                    branches,
+                    default_branch,
+                    ..
+                } => {
+                    let label = match lookup(env, *cond_symbol)? {
+                        Value::Int(int) => int as u64,
+                        Value::Bool(value) => value as u64,
+                        _ => return Err(InterpretError::Unsupported("switch on a non-integer")),
+                    };
+
+                    stmt = branches
+                        .iter()
+                        .find(|(branch_label, _, _)| *branch_label == label)
+                        .map(|(_, _, branch)| branch)
+                        .unwrap_or(default_branch.1);
+                }
+                Stmt::Ret(symbol) => return lookup(env, *symbol),
+                Stmt::Refcounting(modify_rc, continuation) => {
+                    self.modify_rc(env, *modify_rc)?;
+                    stmt = *continuation;
+                }
+                Stmt::Expect { remainder, .. }
+                | Stmt::ExpectFx { remainder, .. }
+                | Stmt::Dbg { remainder, .. } => {
+                    stmt = *remainder;
+                }
+                Stmt::Join {
+                    id,
+                    parameters,
+                    body,
+                    remainder,
+                } => {
+                    join_points.insert(*id, (*parameters, *body));
+                    stmt = *remainder;
+                }
+                Stmt::Jump(id, arguments) => {
+                    let (parameters, body) = *join_points
+                        .get(id)
+                        .ok_or(InterpretError::UnknownJoinPoint(*id))?;
+
+                    let values = arguments
+                        .iter()
+                        .map(|argument| lookup(env, *argument))
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    for (parameter, value) in parameters.iter().zip(values) {
+                        env.insert(parameter.symbol, value);
+                    }
+
+                    stmt = body;
+                }
+                Stmt::Crash(..) => return Err(InterpretError::Crash),
+            }
+        }
+    }
+
+    fn expr(
+        &mut self,
+        env: &MutMap<Symbol, Value>,
+        expr: &Expr<'a>,
+        layout: InLayout<'a>,
+    ) -> Result<Value, InterpretError> {
+        let values = |symbols: &[Symbol]| {
+            symbols
+                .iter()
+                .map(|symbol| lookup(env, *symbol))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        match expr {
+            Expr::Literal(literal) => match literal {
+                Literal::Int(bytes) => Ok(Value::Int(i128::from_ne_bytes(*bytes))),
+                Literal::Bool(value) => Ok(Value::Bool(*value)),
+                Literal::Byte(byte) => Ok(Value::Int(*byte as i128)),
+                _ => Err(InterpretError::Unsupported("non-integer literal")),
+            },
+            Expr::Call(call) => self.call_expr(env, call),
+            Expr::Tag {
+                tag_layout,
+                tag_id,
+                arguments,
+            } => {
+                let fields = values(*arguments)?;
+                Ok(self.tag(layout, *tag_layout, *tag_id, fields))
+            }
+            Expr::Struct(fields) => Ok(Value::Struct(values(*fields)?)),
+            Expr::NullPointer => Ok(Value::Null),
+            Expr::StructAtIndex {
+                index, structure, ..
+            } => match lookup(env, *structure)? {
+                Value::Struct(fields) => field(fields, *index),
+                _ => Err(InterpretError::Unsupported("index into a non-struct")),
+            },
+            Expr::GetTagId {
+                structure,
+                union_layout,
+            } => {
+                let tag_id = match self.union_contents(env, *structure)? {
+                    Some((tag_id, _)) => tag_id,
+                    None => nullable_id(*union_layout)
+                        .ok_or(InterpretError::Unsupported("null of a non-nullable union"))?,
+                };
+
+                Ok(Value::Int(tag_id as i128))
+            }
+            Expr::UnionAtIndex {
+                structure, index, ..
+            } => match self.union_contents(env, *structure)? {
+                Some((_, fields)) => field(fields, *index),
+                None => Err(InterpretError::Unsupported("index into a null")),
+            },
+            Expr::ExprBox { symbol } => {
+                let contents = lookup(env, *symbol)?;
+                Ok(self.allocate(layout, contents))
+            }
+            Expr::ExprUnbox { symbol } => match lookup(env, *symbol)? {
+                Value::Cell(cell) => self.contents(cell, *symbol).cloned(),
+                _ => Err(InterpretError::Unsupported("unbox of a non-pointer")),
+            },
+            Expr::Reset { symbol, .. } => self.reset(env, *symbol, true),
+            Expr::ResetRef { symbol, .. } => self.reset(env, *symbol, false),
+            Expr::Reuse {
+                symbol,
+                tag_layout,
+                tag_id,
+                arguments,
+                ..
+            } => {
+                let fields = values(*arguments)?;
+
+                match lookup(env, *symbol)? {
+                    Value::Cell(cell) => {
+                        let heap_cell = &mut self.heap[cell];
+                        if heap_cell.contents.is_some() {
+                            return Err(InterpretError::Unsupported("reuse without a reset"));
+                        }
+
+                        heap_cell.contents = Some(Value::Tag(*tag_id, fields));
+                        *self.trace.allocations.entry(heap_cell.layout).or_default() += 1;
+
+                        Ok(Value::Cell(cell))
+                    }
+                    _ => Ok(self.tag(layout, *tag_layout, *tag_id, fields)),
+                }
+            }
+            Expr::RuntimeErrorFunction(_) => Err(InterpretError::Crash),
+            Expr::Array { .. } | Expr::EmptyArray | Expr::StackArray { .. } => {
+                Err(InterpretError::Unsupported("list"))
+            }
+            Expr::StackBox { .. } => Err(InterpretError::Unsupported("stack box")),
+        }
+    }
+
+    fn call_expr(
+        &mut self,
+        env: &MutMap<Symbol, Value>,
+        call: &Call<'a>,
+    ) -> Result<Value, InterpretError> {
+        let arguments = call
+            .arguments
+            .iter()
+            .map(|argument| lookup(env, *argument))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match call.call_type.clone().replace_lowlevel_wrapper() {
+            CallType::ByName {
+                name,
+                ret_layout,
+                arg_layouts,
+                ..
+            } => {
+                let proc_layout = ProcLayout {
+                    arguments: arg_layouts,
+                    result: ret_layout,
+                    niche: name.niche(),
+                };
+
+                self.call((name.name(), proc_layout), arguments)
+            }
+            CallType::LowLevel { op, .. } => match (op, arguments.as_slice()) {
+                (LowLevel::RefCountIsUnique, [Value::Cell(cell)]) => {
+                    self.contents(*cell, call.arguments[0])?;
+
+                    Ok(Value::Bool(self.heap[*cell].refcount == 1))
+                }
+                (LowLevel::PtrWrite, [Value::Cell(cell), value]) => {
+                    self.contents(*cell, call.arguments[0])?;
+
+                    let heap_cell = &mut self.heap[*cell];
+                    heap_cell.contents = Some(value.clone());
+
+                    *self.trace.frees.entry(heap_cell.layout).or_default() += 1;
+                    *self.trace.allocations.entry(heap_cell.layout).or_default() += 1;
+
+                    Ok(Value::Cell(*cell))
+                }
+                _ => Err(InterpretError::Unsupported("lowlevel")),
+            },
+            CallType::Foreign { .. } => Err(InterpretError::Unsupported("call to the host")),
+            CallType::HigherOrder(_) => Err(InterpretError::Unsupported("higher-order lowlevel")),
+        }
+    }
+
+    fn tag(
+        &mut self,
+        layout: InLayout<'a>,
+        union_layout: UnionLayout<'a>,
+        tag_id: TagIdIntType,
+        fields: Vec<Value>,
+    ) -> Value {
+        if let UnionLayout::NonRecursive(_) = union_layout {
+            Value::Tag(tag_id, fields)
+        } else if nullable_id(union_layout) == Some(tag_id) {
+            Value::Null
+        } else {
+            self.allocate(layout, Value::Tag(tag_id, fields))
+        }
+    }
+
+    /// The tag id and fields of a union value, or `None` for its nullable tag
+    fn union_contents(
+        &self,
+        env: &MutMap<Symbol, Value>,
+        symbol: Symbol,
+    ) -> Result<Option<(TagIdIntType, Vec<Value>)>, InterpretError> {
+        let value = match lookup(env, symbol)? {
+            Value::Cell(cell) => self.contents(cell, symbol)?.clone(),
+            value => value,
+        };
+
+        match value {
+            Value::Tag(tag_id, fields) => Ok(Some((tag_id, fields))),
+            Value::Null => Ok(None),
+            _ => Err(InterpretError::Unsupported(
+                "union operation on a non-union",
+            )),
+        }
+    }
+
+    fn allocate(&mut self, layout: InLayout<'a>, contents: Value) -> Value {
+        self.heap.push(Cell {
+            layout,
+            refcount: 1,
+            contents: Some(contents),
+        });
+
+        *self.trace.allocations.entry(layout).or_default() += 1;
+
+        Value::Cell(self.heap.len() - 1)
+    }
+
+    fn contents(&self, cell: usize, symbol: Symbol) -> Result<&Value, InterpretError> {
+        self.heap[cell]
+            .contents
+            .as_ref()
+            .ok_or(InterpretError::UseAfterFree(symbol))
+    }
+
+    /// A unique cell is freed (after decrementing its children, when `recursive`) and kept as the
+    /// reuse token. A shared cell is decremented and the token is null.
+    fn reset(
+        &mut self,
+        env: &MutMap<Symbol, Value>,
+        symbol: Symbol,
+        recursive: bool,
+    ) -> Result<Value, InterpretError> {
+        let value = lookup(env, symbol)?;
+
+        let cell = match value {
+            Value::Cell(cell) => cell,
+            Value::Null => return Ok(Value::Null),
+            _ => return Err(InterpretError::Unsupported("reset of a non-pointer")),
+        };
+
+        self.contents(cell, symbol)?;
+
+        if self.heap[cell].refcount > 1 {
+            self.heap[cell].refcount -= 1;
+            return Ok(Value::Null);
+        }
+
+        let heap_cell = &mut self.heap[cell];
+        let contents = heap_cell.contents.take();
+        *self.trace.frees.entry(heap_cell.layout).or_default() += 1;
+
+        if let (true, Some(contents)) = (recursive, contents) {
+            self.dec(&contents, symbol, true)?;
+        }
+
+        Ok(Value::Cell(cell))
+    }
+
+    fn modify_rc(
+        &mut self,
+        env: &MutMap<Symbol, Value>,
+        modify_rc: ModifyRc,
+    ) -> Result<(), InterpretError> {
+        match modify_rc {
+            ModifyRc::Inc(symbol, count) => self.inc(&lookup(env, symbol)?, symbol, count),
+            ModifyRc::IncN(symbol, count) => match lookup(env, count)? {
+                Value::Int(count) => self.inc(&lookup(env, symbol)?, symbol, count as u64),
+                _ => Err(InterpretError::Unsupported("non-integer increment amount")),
+            },
+            ModifyRc::Dec(symbol) => self.dec(&lookup(env, symbol)?, symbol, true),
+            ModifyRc::DecRef(symbol) => self.dec(&lookup(env, symbol)?, symbol, false),
+        }
+    }
+
+    fn inc(&mut self, value: &Value, symbol: Symbol, count: u64) -> Result<(), InterpretError> {
+        match value {
+            Value::Struct(fields) | Value::Tag(_, fields) => fields
+                .iter()
+                .try_for_each(|field| self.inc(field, symbol, count)),
+            Value::Cell(cell) => {
+                self.contents(*cell, symbol)?;
+                self.heap[*cell].refcount += count;
+
+                Ok(())
+            }
+            Value::Int(_) | Value::Bool(_) | Value::Null => Ok(()),
+        }
+    }
+
+    /// Decrements the refcount of the cells in a value, and frees the cells that are no longer
+    /// referenced. Only when `recursive` are the children of freed cells decremented as well.
+    fn dec(
+        &mut self,
+        value: &Value,
+        symbol: Symbol,
+        recursive: bool,
+    ) -> Result<(), InterpretError> {
+        match value {
+            Value::Struct(fields) | Value::Tag(_, fields) => fields
+                .iter()
+                .try_for_each(|field| self.dec(field, symbol, recursive)),
+            Value::Cell(cell) => {
+                self.contents(*cell, symbol)?;
+
+                let heap_cell = &mut self.heap[*cell];
+                heap_cell.refcount -= 1;
+
+                if heap_cell.refcount > 0 {
+                    return Ok(());
+                }
+
+                let contents = heap_cell.contents.take();
+                *self.trace.frees.entry(heap_cell.layout).or_default() += 1;
+
+                match contents {
+                    Some(contents) if recursive => self.dec(&contents, symbol, true),
+                    _ => Ok(()),
+                }
+            }
+            Value::Int(_) | Value::Bool(_) | Value::Null => Ok(()),
+        }
+    }
+}
+
+fn lookup(env: &MutMap<Symbol, Value>, symbol: Symbol) -> Result<Value, InterpretError> {
+    env.get(&symbol)
+        .cloned()
+        .ok_or(InterpretError::UnknownSymbol(symbol))
+}
+
+fn field(fields: Vec<Value>, index: u64) -> Result<Value, InterpretError> {
+    fields
+        .into_iter()
+        .nth(index as usize)
+        .ok_or(InterpretError::Unsupported("index out of bounds"))
+}
+
+fn nullable_id(union_layout: UnionLayout) -> Option<TagIdIntType> {
+    match union_layout {
+        UnionLayout::NullableWrapped { nullable_id, .. } => Some(nullable_id),
+        UnionLayout::NullableUnwrapped { nullable_id, .. } => Some(nullable_id as TagIdIntType),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bumpalo::Bump;
+    use proptest::prelude::*;
+    use roc_collections::MutMap;
+    use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+    use roc_target::TargetInfo;
+
+    use crate::ir::{
+        Expr, HostExposedLayouts, Literal, ModifyRc, Proc, ProcLayout, SelfRecursive, Stmt,
+    };
+    use crate::layout::{
+        InLayout, LambdaName, Layout, LayoutInterner, LayoutRepr, Niche, STLayoutInterner,
+    };
+
+    use super::{check_drop_specialization, check_rc_scheduling, trace_heap, InterpretError};
+
+    const TARGET_INFO: TargetInfo = TargetInfo::default_x86_64();
+
+    #[derive(Debug, Clone)]
+    enum Shape {
+        Int,
+        Boxed(Box<Shape>),
+        Pair(Box<Shape>, Box<Shape>),
+    }
+
+    impl Shape {
+        fn is_refcounted(&self) -> bool {
+            match self {
+                Shape::Int => false,
+                Shape::Boxed(_) => true,
+                Shape::Pair(a, b) => a.is_refcounted() || b.is_refcounted(),
+            }
+        }
+    }
+
+    /// What the program does with the value it currently owns
+    #[derive(Debug, Clone, Copy)]
+    enum Step {
+        /// Take another reference to the value, which is dropped at the end of the program
+        Share,
+        /// Make drop specialization forget the increments it has seen so far
+        Barrier,
+        /// Take the contents of a box, or the first or second field of a pair, and drop the rest
+        Index(bool),
+        /// Take the contents of a box and put them in a new box, like a record update does
+        Rebox,
+    }
+
+    enum Line<'a> {
+        Let(Symbol, Expr<'a>, InLayout<'a>),
+        Refcounting(ModifyRc),
+    }
+
+    struct Program<'a, 'i> {
+        arena: &'a Bump,
+        interner: &'i mut STLayoutInterner<'a>,
+        ident_ids: &'i mut IdentIds,
+        lines: Vec<Line<'a>>,
+    }
+
+    impl<'a, 'i> Program<'a, 'i> {
+        fn symbol(&mut self) -> Symbol {
+            let name = format!("v{}", self.lines.len());
+            Symbol::new(ModuleId::ATTR, self.ident_ids.add_str(&name))
+        }
+
+        fn layout(&mut self, shape: &Shape) -> InLayout<'a> {
+            match shape {
+                Shape::Int => Layout::I64,
+                Shape::Boxed(inner) => {
+                    let inner = self.layout(inner);
+                    self.interner.insert_no_semantic(LayoutRepr::Boxed(inner))
+                }
+                Shape::Pair(a, b) => {
+                    let fields = [self.layout(a), self.layout(b)];
+                    let fields = self.arena.alloc(fields);
+                    self.interner
+                        .insert_no_semantic(LayoutRepr::struct_(fields))
+                }
+            }
+        }
+
+        fn field_layouts(&mut self, a: &Shape, b: &Shape) -> &'a [InLayout<'a>] {
+            let fields = [self.layout(a), self.layout(b)];
+            self.arena.alloc(fields)
+        }
+
+        fn bind(&mut self, expr: Expr<'a>, layout: InLayout<'a>) -> Symbol {
+            let symbol = self.symbol();
+            self.lines.push(Line::Let(symbol, expr, layout));
+            symbol
+        }
+
+        fn rc(&mut self, modify_rc: ModifyRc) {
+            self.lines.push(Line::Refcounting(modify_rc));
+        }
+
+        fn build(&mut self, shape: &Shape) -> Symbol {
+            let layout = self.layout(shape);
+
+            match shape {
+                Shape::Int => {
+                    let int = Literal::Int((self.lines.len() as i128).to_ne_bytes());
+                    self.bind(Expr::Literal(int), layout)
+                }
+                Shape::Boxed(inner) => {
+                    let symbol = self.build(inner);
+                    self.bind(Expr::ExprBox { symbol }, layout)
+                }
+                Shape::Pair(a, b) => {
+                    let fields = [self.build(a), self.build(b)];
+                    self.bind(Expr::Struct(self.arena.alloc(fields)), layout)
+                }
+            }
+        }
+
+        /// Builds a value of the shape, walks into it following the steps the way the refcount
+        /// pass would have written it, and drops everything that is still owned at the end.
+        fn walk(&mut self, shape: Shape, steps: &[Step]) {
+            let mut current = self.build(&shape);
+            let mut shape = shape;
+            let mut shared = Vec::new();
+
+            for step in steps {
+                match (step, shape.clone()) {
+                    (_, Shape::Int) => break,
+                    (Step::Share, _) => {
+                        if shape.is_refcounted() {
+                            self.rc(ModifyRc::Inc(current, 1));
+                            shared.push(current);
+                        }
+                    }
+                    (Step::Barrier, _) => {
+                        self.bind(Expr::Struct(&[]), Layout::UNIT);
+                    }
+                    (Step::Index(_) | Step::Rebox, Shape::Boxed(inner)) => {
+                        let inner_layout = self.layout(&inner);
+                        let contents = self.bind(Expr::ExprUnbox { symbol: current }, inner_layout);
+
+                        if inner.is_refcounted() {
+                            self.rc(ModifyRc::Inc(contents, 1));
+                        }
+                        self.rc(ModifyRc::Dec(current));
+
+                        if let Step::Rebox = step {
+                            let box_layout = self.layout(&shape);
+                            current = self.bind(Expr::ExprBox { symbol: contents }, box_layout);
+                        } else {
+                            current = contents;
+                            shape = *inner;
+                        }
+                    }
+                    // A pair can't be reboxed, so take its first field instead
+                    (Step::Index(_) | Step::Rebox, Shape::Pair(a, b)) => {
+                        let field_layouts = self.field_layouts(&a, &b);
+                        let (index, field) = if let Step::Index(true) = step {
+                            (1, *b)
+                        } else {
+                            (0, *a)
+                        };
+
+                        let expr = Expr::StructAtIndex {
+                            index,
+                            field_layouts,
+                            structure: current,
+                        };
+                        let field_symbol = self.bind(expr, field_layouts[index as usize]);
+
+                        if field.is_refcounted() {
+                            self.rc(ModifyRc::Inc(field_symbol, 1));
+                        }
+                        if shape.is_refcounted() {
+                            self.rc(ModifyRc::Dec(current));
+                        }
+
+                        current = field_symbol;
+                        shape = field;
+                    }
+                }
+            }
+
+            if shape.is_refcounted() {
+                self.rc(ModifyRc::Dec(current));
+            }
+
+            for owner in shared.into_iter().rev() {
+                self.rc(ModifyRc::Dec(owner));
+            }
+        }
+
+        fn into_procs(
+            mut self,
+        ) -> (
+            MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+            (Symbol, ProcLayout<'a>),
+        ) {
+            let result = self.bind(
+                Expr::Literal(Literal::Int(0i128.to_ne_bytes())),
+                Layout::I64,
+            );
+            let name = self.symbol();
+
+            let mut body = Stmt::Ret(result);
+            for line in self.lines.into_iter().rev() {
+                let continuation = self.arena.alloc(body);
+                body = match line {
+                    Line::Let(symbol, expr, layout) => {
+                        Stmt::Let(symbol, expr, layout, continuation)
+                    }
+                    Line::Refcounting(modify_rc) => Stmt::Refcounting(modify_rc, continuation),
+                };
+            }
+
+            let proc_layout = ProcLayout {
+                arguments: &[],
+                result: Layout::I64,
+                niche: Niche::NONE,
+            };
+
+            let proc = Proc {
+                name: LambdaName::no_niche(name),
+                args: &[],
+                body,
+                closure_data_layout: None,
+                ret_layout: Layout::I64,
+                is_self_recursive: SelfRecursive::NotSelfRecursive,
+                host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+            };
+
+            let mut procs = MutMap::default();
+            procs.insert((name, proc_layout), proc);
+
+            (procs, (name, proc_layout))
+        }
+    }
+
+    fn shape() -> impl Strategy<Value = Shape> {
+        Just(Shape::Int).prop_recursive(4, 16, 2, |inner| {
+            prop_oneof![
+                inner
+                    .clone()
+                    .prop_map(|shape| Shape::Boxed(Box::new(shape))),
+                (inner.clone(), inner).prop_map(|(a, b)| Shape::Pair(Box::new(a), Box::new(b))),
+            ]
+        })
+    }
+
+    fn step() -> impl Strategy<Value = Step> {
+        prop_oneof![
+            Just(Step::Share),
+            Just(Step::Barrier),
+            any::<bool>().prop_map(Step::Index),
+            Just(Step::Rebox),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn refcount_passes_keep_frees(
+            shape in shape(),
+            steps in proptest::collection::vec(step(), 0..10),
+        ) {
+            let arena = Bump::new();
+            let mut interner = STLayoutInterner::with_capacity(4, TARGET_INFO);
+            let mut ident_ids = IdentIds::default();
+
+            let mut program = Program {
+                arena: &arena,
+                interner: &mut interner,
+                ident_ids: &mut ident_ids,
+                lines: Vec::new(),
+            };
+            program.walk(shape, &steps);
+            let (procs, entry) = program.into_procs();
+
+            // The generated program itself has to be sound, or the comparison means nothing.
+            let trace = trace_heap(&procs, entry).unwrap();
+            prop_assert!(trace.leaked().is_empty());
+
+            let result = check_drop_specialization(
+                &arena,
+                &mut interner,
+                ModuleId::ATTR,
+                &mut ident_ids,
+                TARGET_INFO,
+                &procs,
+                entry,
+            );
+            prop_assert_eq!(result, Ok(()));
+
+            let result = check_rc_scheduling(
+                &arena,
+                &mut interner,
+                ModuleId::ATTR,
+                &mut ident_ids,
+                TARGET_INFO,
+                &procs,
+                entry,
+            );
+            prop_assert_eq!(result, Ok(()));
+        }
+    }
+
+    #[test]
+    fn use_after_free() {
+        let arena = Bump::new();
+        let mut interner = STLayoutInterner::with_capacity(4, TARGET_INFO);
+        let mut ident_ids = IdentIds::default();
+
+        let mut program = Program {
+            arena: &arena,
+            interner: &mut interner,
+            ident_ids: &mut ident_ids,
+            lines: Vec::new(),
+        };
+
+        // Dropping a box frees its contents, so they must be incremented to outlive it.
+        let shape = Shape::Boxed(Box::new(Shape::Boxed(Box::new(Shape::Int))));
+        let outer = program.build(&shape);
+        let inner_layout = program.layout(&Shape::Boxed(Box::new(Shape::Int)));
+        let inner = program.bind(Expr::ExprUnbox { symbol: outer }, inner_layout);
+        program.rc(ModifyRc::Dec(outer));
+        program.rc(ModifyRc::Dec(inner));
+        let (procs, entry) = program.into_procs();
+
+        assert_eq!(
+            trace_heap(&procs, entry),
+            Err(InterpretError::UseAfterFree(inner))
+        );
+    }
+}
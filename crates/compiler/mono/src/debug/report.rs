@@ -30,7 +30,7 @@ where
     all.1.pretty(80).to_string()
 }
 
-type Doc<'d> = DocBuilder<'d, Arena<'d>>;
+pub(super) type Doc<'d> = DocBuilder<'d, Arena<'d>>;
 
 const GUTTER_BAR: &str = "│";
 const HEADER_WIDTH: usize = 80;
@@ -101,7 +101,7 @@ fn format_sourced_doc<'d>(f: &'d Arena<'d>, line: usize, source: &str, doc: Doc<
     stack(f, [pretty_lines, doc])
 }
 
-fn format_header<'d>(f: &'d Arena<'d>, title: &str) -> Doc<'d> {
+pub(super) fn format_header<'d>(f: &'d Arena<'d>, title: &str) -> Doc<'d> {
     let title_width = title.len() + 4;
     text!(f, "── {} {}", title, "─".repeat(HEADER_WIDTH - title_width))
 }
@@ -410,7 +410,7 @@ where
     (title, docs_before, doc)
 }
 
-fn format_symbol<'d>(f: &'d Arena<'d>, interns: &'d Interns, symbol: Symbol) -> Doc<'d> {
+pub(super) fn format_symbol<'d>(f: &'d Arena<'d>, interns: &'d Interns, symbol: Symbol) -> Doc<'d> {
     f.text(symbol.module_string(interns).to_string())
         .append(f.text("."))
         .append(f.text(symbol.as_str(interns)))
@@ -429,6 +429,7 @@ fn format_use_kind(use_kind: UseKind) -> &'static str {
         UseKind::SwitchCond => "switch condition",
         UseKind::ExpectCond => "expect condition",
         UseKind::ExpectLookup => "lookup for an expect",
+        UseKind::IncCount => "increment amount",
     }
 }
 
@@ -449,7 +450,7 @@ where
     ])
 }
 
-fn format_proc_layout<'a, 'd, I>(
+pub(super) fn format_proc_layout<'a, 'd, I>(
     f: &'d Arena<'d>,
     interner: &I,
     proc_layout: ProcLayout<'a>,
@@ -479,6 +480,6 @@ where
     f.concat([fun, f.space(), niche])
 }
 
-fn stack<'d>(f: &'d Arena<'d>, docs: impl IntoIterator<Item = Doc<'d>>) -> Doc<'d> {
+pub(super) fn stack<'d>(f: &'d Arena<'d>, docs: impl IntoIterator<Item = Doc<'d>>) -> Doc<'d> {
     f.intersperse(docs, f.line().append(f.line()))
 }
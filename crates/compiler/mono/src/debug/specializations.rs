@@ -0,0 +1,206 @@
+//! A report of the specializations made for each function, to find out why a program with
+//! many higher-order functions ends up with so much code.
+
+use std::fmt::Display;
+
+use roc_collections::{MutMap, VecSet};
+use roc_module::symbol::{Interns, Symbol};
+use ven_pretty::{Arena, DocAllocator};
+
+use crate::{
+    ir::{CallType, Expr, Proc, ProcLayout, Stmt},
+    layout::LayoutInterner,
+};
+
+use super::report::{format_header, format_proc_layout, format_symbol, stack, Doc};
+
+type Procs<'a> = MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>;
+
+/// Lists every function that was specialized more than once, most specialized first.
+///
+/// Each specialization is shown with its layout, in which closure arguments appear as the
+/// lambda sets the function was specialized for, and with the functions that call it.
+pub fn format_specializations<'a, I>(
+    interns: &Interns,
+    interner: &I,
+    procs: &Procs<'a>,
+) -> impl Display
+where
+    I: LayoutInterner<'a>,
+{
+    let mut callers: MutMap<(Symbol, ProcLayout<'a>), VecSet<Symbol>> = MutMap::default();
+
+    for ((caller, _), proc) in procs.iter() {
+        for_each_callee(&proc.body, &mut |callee| {
+            callers.entry(callee).or_default().insert(*caller);
+        });
+    }
+
+    let mut specializations: MutMap<Symbol, Vec<ProcLayout<'a>>> = MutMap::default();
+
+    for (symbol, proc_layout) in procs.keys() {
+        specializations
+            .entry(*symbol)
+            .or_default()
+            .push(*proc_layout);
+    }
+
+    let name = |symbol: Symbol| {
+        format!(
+            "{}.{}",
+            symbol.module_string(interns),
+            symbol.as_str(interns)
+        )
+    };
+
+    let mut specializations: Vec<_> = specializations
+        .into_iter()
+        .filter(|(_, proc_layouts)| proc_layouts.len() > 1)
+        .collect();
+
+    specializations.sort_by(|(a, a_layouts), (b, b_layouts)| {
+        (b_layouts.len().cmp(&a_layouts.len())).then_with(|| name(*a).cmp(&name(*b)))
+    });
+
+    let f = Arena::new();
+    let function_docs = specializations.into_iter().map(|(symbol, proc_layouts)| {
+        format_function(&f, interns, interner, &callers, symbol, proc_layouts)
+    });
+
+    let all = stack(
+        &f,
+        std::iter::once(format_header(&f, "SPECIALIZATIONS")).chain(function_docs),
+    );
+
+    all.1.pretty(80).to_string()
+}
+
+fn format_function<'a, 'd, I>(
+    f: &'d Arena<'d>,
+    interns: &'d Interns,
+    interner: &I,
+    callers: &MutMap<(Symbol, ProcLayout<'a>), VecSet<Symbol>>,
+    symbol: Symbol,
+    proc_layouts: Vec<ProcLayout<'a>>,
+) -> Doc<'d>
+where
+    I: LayoutInterner<'a>,
+{
+    let title = f.concat([
+        format_symbol(f, interns, symbol),
+        f.reflow(" was specialized "),
+        f.as_string(proc_layouts.len()),
+        f.reflow(" times"),
+    ]);
+
+    // Render the layouts up front, so the specializations can be listed in a stable order
+    let mut rendered: Vec<_> = proc_layouts
+        .into_iter()
+        .map(|proc_layout| {
+            let layout = format_proc_layout(f, interner, proc_layout)
+                .1
+                .pretty(80)
+                .to_string();
+
+            let mut called_by: Vec<_> = callers
+                .get(&(symbol, proc_layout))
+                .into_iter()
+                .flat_map(|callers| callers.iter())
+                .map(|caller| format_symbol(f, interns, *caller).1.pretty(80).to_string())
+                .collect();
+            called_by.sort();
+
+            (layout, called_by)
+        })
+        .collect();
+    rendered.sort();
+
+    let specialization_docs = rendered.into_iter().map(|(layout, called_by)| {
+        let called_by = if called_by.is_empty() {
+            f.reflow("not called by any function (exposed to the host, or passed as a value)")
+        } else {
+            f.reflow("called by ").append(f.intersperse(
+                called_by.into_iter().map(|caller| f.as_string(caller)),
+                f.reflow(", "),
+            ))
+        };
+
+        f.as_string(layout)
+            .append(f.hardline().append(called_by).nest(4))
+    });
+
+    title.append(
+        f.hardline()
+            .append(f.intersperse(specialization_docs, f.hardline()))
+            .nest(4),
+    )
+}
+
+/// Calls `f` with the specialization of every function that `stmt` calls by name, including
+/// the functions passed to higher-order builtins like `List.map`.
+pub(super) fn for_each_callee<'a>(stmt: &Stmt<'a>, f: &mut impl FnMut((Symbol, ProcLayout<'a>))) {
+    match stmt {
+        Stmt::Let(_, expr, _, rest) => {
+            if let Expr::Call(call) = expr {
+                match &call.call_type {
+                    CallType::ByName {
+                        name,
+                        ret_layout,
+                        arg_layouts,
+                        ..
+                    } => f((
+                        name.name(),
+                        ProcLayout {
+                            arguments: arg_layouts,
+                            result: *ret_layout,
+                            niche: name.niche(),
+                        },
+                    )),
+                    CallType::HigherOrder(higher_order) => {
+                        let passed = &higher_order.passed_function;
+
+                        f((
+                            passed.name.name(),
+                            ProcLayout {
+                                arguments: passed.argument_layouts,
+                                result: passed.return_layout,
+                                niche: passed.name.niche(),
+                            },
+                        ))
+                    }
+                    CallType::Foreign { .. } | CallType::LowLevel { .. } => {}
+                }
+            }
+
+            for_each_callee(rest, f);
+        }
+        Stmt::Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            for (_, _, branch) in branches.iter() {
+                for_each_callee(branch, f);
+            }
+
+            for_each_callee(default_branch.1, f);
+        }
+        Stmt::Join {
+            body, remainder, ..
+        } => {
+            for_each_callee(body, f);
+            for_each_callee(remainder, f);
+        }
+        Stmt::Refcounting(_, rest)
+        | Stmt::Expect {
+            remainder: rest, ..
+        }
+        | Stmt::ExpectFx {
+            remainder: rest, ..
+        }
+        | Stmt::Dbg {
+            remainder: rest, ..
+        } => for_each_callee(rest, f),
+        Stmt::Ret(_) | Stmt::Jump(..) | Stmt::Crash(..) => {}
+    }
+}
@@ -17,13 +17,16 @@ use roc_module::low_level::LowLevel;
 use roc_module::symbol::{IdentIds, ModuleId, Symbol};
 use roc_target::TargetInfo;
 
+use crate::borrow::Ownership;
 use crate::ir::{
-    BranchInfo, Call, CallType, Expr, JoinPointId, Literal, ModifyRc, Proc, ProcLayout, Stmt,
-    UpdateModeId,
+    BranchInfo, Call, CallType, Expr, JoinPointId, Literal, ModifyRc, Param, Proc, ProcLayout,
+    Stmt, UpdateModeId,
 };
 use crate::layout::{
-    Builtin, InLayout, Layout, LayoutInterner, LayoutRepr, STLayoutInterner, UnionLayout,
+    Builtin, InLayout, LambdaName, Layout, LayoutInterner, LayoutRepr, STLayoutInterner,
+    UnionLayout,
 };
+use crate::static_data::StaticConstants;
 
 use bumpalo::Bump;
 
@@ -32,6 +35,8 @@ use roc_collections::{MutMap, MutSet};
 /**
 Try to find increments of symbols followed by decrements of the symbol they were indexed out of (their parent).
 Then inline the decrement operation of the parent and removing matching pairs of increments and decrements.
+Refcount operations on static constants (and values indexed out of them) are removed entirely,
+since they would not do anything.
 */
 pub fn specialize_drops<'a, 'i>(
     arena: &'a Bump,
@@ -39,11 +44,19 @@ pub fn specialize_drops<'a, 'i>(
     home: ModuleId,
     ident_ids: &'i mut IdentIds,
     target_info: TargetInfo,
+    static_constants: &StaticConstants<'a>,
     procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
 ) {
+    let static_thunks: &'a MutSet<_> = arena.alloc(static_constants.keys().copied().collect());
+
     for ((_symbol, proc_layout), proc) in procs.iter_mut() {
-        let mut environment =
-            DropSpecializationEnvironment::new(arena, home, proc_layout.result, target_info);
+        let mut environment = DropSpecializationEnvironment::new(
+            arena,
+            home,
+            proc_layout.result,
+            target_info,
+            static_thunks,
+        );
         specialize_drops_proc(arena, layout_interner, ident_ids, &mut environment, proc);
     }
 }
@@ -105,9 +118,27 @@ fn specialize_drops_stmt<'a, 'i>(
                             };
 
                             environment.add_list_child(*structure, *binding, index);
+                            environment.add_static_child(*structure, *binding);
+
+                            alloc_let_with_continuation!(environment)
+                        }
+                        CallType::ByName {
+                            name,
+                            ret_layout,
+                            arg_layouts,
+                            ..
+                        } if environment.is_static_thunk(&name, &ret_layout, arg_layouts) => {
+                            // The backend replaces the body of a static thunk with its static
+                            // value, so calling it does not modify any RC.
+                            environment.static_symbols.insert(*binding);
 
                             alloc_let_with_continuation!(environment)
                         }
+                        foreign_call if foreign_call.is_pure_borrowing_foreign() => {
+                            // A `Pure` host function which borrows its arguments can't modify
+                            // any RC.
+                            alloc_let_with_continuation!(environment)
+                        }
                         _ => {
                             // TODO perhaps allow for some e.g. lowlevel functions to be called if they cannot modify the RC of the symbol.
 
@@ -140,6 +171,7 @@ fn specialize_drops_stmt<'a, 'i>(
                     index, structure, ..
                 } => {
                     environment.add_struct_child(*structure, *binding, *index);
+                    environment.add_static_child(*structure, *binding);
                     // alloc_let_with_continuation!(environment)
 
                     // TODO do we need to remove the indexed value to prevent it from being dropped sooner?
@@ -188,10 +220,12 @@ fn specialize_drops_stmt<'a, 'i>(
 
                 Expr::RuntimeErrorFunction(_)
                 | Expr::ExprBox { .. }
+                | Expr::StackBox { .. }
                 | Expr::NullPointer
                 | Expr::GetTagId { .. }
                 | Expr::EmptyArray
-                | Expr::Array { .. } => {
+                | Expr::Array { .. }
+                | Expr::StackArray { .. } => {
                     // Does nothing relevant to drop specialization. So we can just continue.
                     alloc_let_with_continuation!(environment)
                 }
@@ -272,6 +306,16 @@ fn specialize_drops_stmt<'a, 'i>(
             })
         }
         Stmt::Ret(symbol) => arena.alloc(Stmt::Ret(*symbol)),
+        Stmt::Refcounting(rc, continuation) if environment.is_static(&rc.get_symbol()) => {
+            // Static data has a refcount of `REFCOUNT_MAX`, so this would not do anything.
+            specialize_drops_stmt(
+                arena,
+                layout_interner,
+                ident_ids,
+                environment,
+                continuation,
+            )
+        }
         Stmt::Refcounting(rc, continuation) => match rc {
             ModifyRc::Inc(symbol, count) => {
                 let any = environment.any_incremented(symbol);
@@ -362,13 +406,15 @@ fn specialize_drops_stmt<'a, 'i>(
                             &mut incremented_children,
                             continuation,
                         ),
-                        LayoutRepr::Boxed(_layout) => specialize_boxed(
+                        LayoutRepr::Boxed(inner_layout) => specialize_boxed(
                             arena,
                             layout_interner,
                             ident_ids,
                             environment,
                             &mut incremented_children,
                             symbol,
+                            *in_layout,
+                            inner_layout,
                             continuation,
                         ),
                         LayoutRepr::Builtin(Builtin::List(layout)) => specialize_list(
@@ -404,8 +450,9 @@ fn specialize_drops_stmt<'a, 'i>(
                     new_dec
                 }
             }
-            ModifyRc::DecRef(_) => {
-                // Inlining has no point, since it doesn't decrement it's children
+            ModifyRc::IncN(_, _) | ModifyRc::DecRef(_) => {
+                // An increment by a runtime amount can't be crossed off against a decrement,
+                // and inlining a DecRef has no point, since it doesn't decrement it's children
                 arena.alloc(Stmt::Refcounting(
                     *rc,
                     specialize_drops_stmt(
@@ -812,28 +859,208 @@ fn specialize_boxed<'a, 'i>(
     environment: &mut DropSpecializationEnvironment<'a>,
     incremented_children: &mut MutSet<Child>,
     symbol: &Symbol,
+    box_layout: InLayout<'a>,
+    inner_layout: InLayout<'a>,
     continuation: &'a Stmt<'a>,
-) -> &'a mut Stmt<'a> {
-    let removed = match incremented_children.iter().next() {
-        Some(s) => incremented_children.remove(&s.clone()),
-        None => false,
+) -> &'a Stmt<'a> {
+    let removed = match incremented_children.iter().next().copied() {
+        Some(s) => incremented_children.remove(&s).then_some(s),
+        None => None,
     };
 
     let new_continuation =
         specialize_drops_stmt(arena, layout_interner, ident_ids, environment, continuation);
 
-    if removed {
-        // No need to decrement the containing value since we already decremented the child.
-        arena.alloc(Stmt::Refcounting(
-            ModifyRc::DecRef(*symbol),
+    // If the only thing the decrement would do is free the box, and a new box of the same layout
+    // is created afterwards, we can write into the old box when it is unique instead.
+    if removed.is_some() || !layout_interner.contains_refcounted(inner_layout) {
+        if let Some(reused) = reuse_box_in_place(
+            arena,
+            ident_ids,
+            environment,
+            *symbol,
+            box_layout,
+            removed,
+            new_continuation,
+        ) {
+            return reused;
+        }
+    }
+
+    match removed {
+        Some(child) => branch_uniqueness(
+            arena,
+            ident_ids,
+            layout_interner,
+            environment,
+            *symbol,
+            // If the box is unique, the child is handed over to us, so we only have to free the box.
+            |_, _, continuation| {
+                arena.alloc(Stmt::Refcounting(ModifyRc::DecRef(*symbol), continuation))
+            },
+            // If the box is not unique, it keeps its child, so we take our own reference to it.
+            |_, _, continuation| {
+                arena.alloc(Stmt::Refcounting(
+                    ModifyRc::Inc(child, 1),
+                    arena.alloc(Stmt::Refcounting(ModifyRc::DecRef(*symbol), continuation)),
+                ))
+            },
             new_continuation,
-        ))
-    } else {
+        ),
         // No known children, keep decrementing the symbol.
-        arena.alloc(Stmt::Refcounting(ModifyRc::Dec(*symbol), new_continuation))
+        None => arena.alloc(Stmt::Refcounting(ModifyRc::Dec(*symbol), new_continuation)),
     }
 }
 
+/**
+Turn
+let b' = Box s; rest
+(where b' has the same layout as the box b which is dropped before it) into
+let is_unique = RefCountIsUnique b;
+if is_unique then let b' = PtrWrite b s; rest else dec b; let b' = Box s; rest
+This is what makes record updates of uniquely boxed records happen in place.
+When the increment of the child of b was removed, the shared case increments the child instead
+of decrementing b's contents. That increment has to happen before anything could drop the child,
+so then only lets without calls may come between the drop and the new box.
+*/
+fn reuse_box_in_place<'a, 'i>(
+    arena: &'a Bump,
+    ident_ids: &'i mut IdentIds,
+    environment: &DropSpecializationEnvironment<'a>,
+    symbol: Symbol,
+    box_layout: InLayout<'a>,
+    removed: Option<Child>,
+    continuation: &'a Stmt<'a>,
+) -> Option<&'a Stmt<'a>> {
+    // Only look through straight-line code, to keep the uniqueness check close to the drop.
+    let mut prefix = Vec::new_in(arena);
+    let mut current = continuation;
+
+    let (new_box, value, rest) = loop {
+        match current {
+            Stmt::Let(binding, Expr::ExprBox { symbol: value }, layout, rest)
+                if *layout == box_layout =>
+            {
+                break (*binding, *value, *rest);
+            }
+            Stmt::Let(_, Expr::Call(_), _, _) | Stmt::Refcounting(_, _) if removed.is_some() => {
+                return None;
+            }
+            Stmt::Let(_, _, _, rest) | Stmt::Refcounting(_, rest) => {
+                prefix.push(current);
+                current = rest;
+            }
+            _ => return None,
+        }
+    };
+
+    let branches = |unique_box: Symbol, new_allocation: Symbol, rest: &'a Stmt<'a>| {
+        let unique = arena.alloc(Stmt::Let(
+            unique_box,
+            Expr::Call(Call {
+                call_type: CallType::LowLevel {
+                    op: LowLevel::PtrWrite,
+                    update_mode: UpdateModeId::BACKEND_DUMMY,
+                },
+                arguments: arena.alloc([symbol, value]),
+            }),
+            box_layout,
+            rest,
+        ));
+
+        let allocate = arena.alloc(Stmt::Let(
+            new_allocation,
+            Expr::ExprBox { symbol: value },
+            box_layout,
+            rest,
+        ));
+
+        let not_unique = match removed {
+            Some(child) => arena.alloc(Stmt::Refcounting(
+                ModifyRc::Inc(child, 1),
+                arena.alloc(Stmt::Refcounting(ModifyRc::DecRef(symbol), allocate)),
+            )),
+            None => arena.alloc(Stmt::Refcounting(ModifyRc::Dec(symbol), allocate)),
+        };
+
+        (unique, not_unique)
+    };
+
+    let switch = |unique: &'a Stmt<'a>, not_unique: &'a Stmt<'a>| {
+        move |is_unique| {
+            arena.alloc(Stmt::Switch {
+                cond_symbol: is_unique,
+                cond_layout: Layout::BOOL,
+                branches: &*arena.alloc([(1, BranchInfo::None, unique.clone())]),
+                default_branch: (BranchInfo::None, not_unique),
+                ret_layout: environment.layout,
+            })
+        }
+    };
+
+    let check = match rest {
+        // The rest is a single stmt. So we can insert it inline and skip creating a joinpoint.
+        Stmt::Ret(_) | Stmt::Jump(_, _) => {
+            let (unique, not_unique) = branches(new_box, new_box, rest);
+            unique_symbol(
+                arena,
+                ident_ids,
+                environment,
+                symbol,
+                switch(unique, not_unique),
+            )
+        }
+        // Both branches create the new box, so pass it to a joinpoint containing the rest.
+        _ => {
+            let join_id = JoinPointId(environment.create_symbol(ident_ids, "box_reuse_join"));
+            let unique_box = environment.create_symbol(ident_ids, "unique_box");
+            let new_allocation = environment.create_symbol(ident_ids, "new_box");
+
+            // The branches each bind their own symbol, which is then passed to the joinpoint.
+            let (unique, _) = branches(
+                unique_box,
+                new_allocation,
+                arena.alloc(Stmt::Jump(join_id, arena.alloc([unique_box]))),
+            );
+            let (_, not_unique) = branches(
+                unique_box,
+                new_allocation,
+                arena.alloc(Stmt::Jump(join_id, arena.alloc([new_allocation]))),
+            );
+
+            let check = unique_symbol(
+                arena,
+                ident_ids,
+                environment,
+                symbol,
+                switch(unique, not_unique),
+            );
+
+            arena.alloc(Stmt::Join {
+                id: join_id,
+                parameters: arena.alloc([Param {
+                    symbol: new_box,
+                    ownership: Ownership::Owned,
+                    layout: box_layout,
+                }]),
+                body: rest,
+                remainder: check,
+            })
+        }
+    };
+
+    // Put the straight-line code before the box back in front of the check.
+    let new_stmt = prefix.iter().rev().fold(check, |next, stmt| match stmt {
+        Stmt::Let(binding, expr, layout, _) => {
+            arena.alloc(Stmt::Let(*binding, expr.clone(), *layout, next))
+        }
+        Stmt::Refcounting(modify_rc, _) => arena.alloc(Stmt::Refcounting(*modify_rc, next)),
+        _ => unreachable!(),
+    });
+
+    Some(new_stmt)
+}
+
 fn specialize_list<'a, 'i>(
     arena: &'a Bump,
     layout_interner: &'i mut STLayoutInterner<'a>,
@@ -891,25 +1118,43 @@ fn specialize_list<'a, 'i>(
                         continuation,
                     );
 
-                    let mut newer_continuation = arena.alloc(Stmt::Refcounting(
-                        ModifyRc::DecRef(*symbol),
-                        new_continuation,
-                    ));
+                    // Like for unions, the items only become ours when the list is unique.
+                    let refcount_items = |not_unique: bool, continuation: &'a Stmt<'a>| {
+                        let mut newer_continuation =
+                            arena.alloc(Stmt::Refcounting(ModifyRc::DecRef(*symbol), continuation));
 
-                    // Reversed to ensure that the generated code decrements the items in the correct order.
-                    for i in (0..length).rev() {
-                        let (s, popped) = index_symbols.get(&i).unwrap();
+                        // Reversed to ensure that the generated code decrements the items in the correct order.
+                        for i in (0..length).rev() {
+                            let (s, popped) = index_symbols.get(&i).unwrap();
 
-                        if !*popped {
-                            // Decrement the children that were not incremented before. And thus don't cancel out.
-                            newer_continuation = arena
-                                .alloc(Stmt::Refcounting(ModifyRc::Dec(*s), newer_continuation));
+                            newer_continuation = match (*popped, not_unique) {
+                                // Unique: decrement the children that were not incremented before. And thus don't cancel out.
+                                (false, false) => arena.alloc(Stmt::Refcounting(
+                                    ModifyRc::Dec(*s),
+                                    newer_continuation,
+                                )),
+                                // Not unique: increment the children that were incremented before.
+                                (true, true) => arena.alloc(Stmt::Refcounting(
+                                    ModifyRc::Inc(*s, 1),
+                                    newer_continuation,
+                                )),
+                                _ => newer_continuation,
+                            };
                         }
 
-                        // Do nothing for the children that were incremented before, as the decrement will cancel out.
-                    }
+                        newer_continuation
+                    };
 
-                    newer_continuation
+                    branch_uniqueness(
+                        arena,
+                        ident_ids,
+                        layout_interner,
+                        environment,
+                        *symbol,
+                        |_, _, continuation| refcount_items(false, continuation),
+                        |_, _, continuation| refcount_items(true, continuation),
+                        new_continuation,
+                    )
                 }
                 _ => keep_original_decrement!(),
             }
@@ -1122,10 +1367,22 @@ struct DropSpecializationEnvironment<'a> {
 
     // Map containing the current known length of a list.
     list_length: MutMap<Symbol, u64>,
+
+    // The top-level constants that the backend stores as static data.
+    static_thunks: &'a MutSet<(Symbol, ProcLayout<'a>)>,
+
+    // Symbols whose values are (part of) static data.
+    static_symbols: MutSet<Symbol>,
 }
 
 impl<'a> DropSpecializationEnvironment<'a> {
-    fn new(arena: &'a Bump, home: ModuleId, layout: InLayout<'a>, target_info: TargetInfo) -> Self {
+    fn new(
+        arena: &'a Bump,
+        home: ModuleId,
+        layout: InLayout<'a>,
+        target_info: TargetInfo,
+        static_thunks: &'a MutSet<(Symbol, ProcLayout<'a>)>,
+    ) -> Self {
         Self {
             arena,
             home,
@@ -1140,6 +1397,8 @@ impl<'a> DropSpecializationEnvironment<'a> {
             symbol_tag: MutMap::default(),
             symbol_index: MutMap::default(),
             list_length: MutMap::default(),
+            static_thunks,
+            static_symbols: MutSet::default(),
         }
     }
 
@@ -1158,6 +1417,8 @@ impl<'a> DropSpecializationEnvironment<'a> {
             symbol_tag: self.symbol_tag.clone(),
             symbol_index: self.symbol_index.clone(),
             list_length: self.list_length.clone(),
+            static_thunks: self.static_thunks,
+            static_symbols: self.static_symbols.clone(),
         }
     }
 
@@ -1206,6 +1467,34 @@ impl<'a> DropSpecializationEnvironment<'a> {
         }
     }
 
+    fn is_static_thunk(
+        &self,
+        name: &LambdaName<'a>,
+        ret_layout: &InLayout<'a>,
+        arg_layouts: &[InLayout<'a>],
+    ) -> bool {
+        let proc_layout = ProcLayout {
+            arguments: arg_layouts,
+            result: *ret_layout,
+            niche: name.niche(),
+        };
+
+        arg_layouts.is_empty() && self.static_thunks.contains(&(name.name(), proc_layout))
+    }
+
+    fn is_static(&self, symbol: &Symbol) -> bool {
+        self.static_symbols.contains(symbol)
+    }
+
+    /**
+    Anything indexed out of static data is static data as well.
+     */
+    fn add_static_child(&mut self, parent: Parent, child: Child) {
+        if self.is_static(&parent) {
+            self.static_symbols.insert(child);
+        }
+    }
+
     fn get_children(&self, parent: &Parent) -> Vec<'a, Symbol> {
         let mut res = Vec::new_in(self.arena);
 
@@ -0,0 +1,168 @@
+//! In test builds, answer the effects of the program with the effect interpreters of the tested
+//! module instead of the host. An effect interpreter is a toplevel function named `interpret`
+//! followed by the capitalized name of the effect, like `interpretPutLine : Str -> {}` for the
+//! `putLine` effect of a platform. Effects without arguments are interpreted by a value,
+//! like `interpretGetInt : { value : I64, isError : Bool }` for `getInt : Effect { ... }`.
+//!
+//! `roc test` does not link a host, so the `roc_fx_*` functions that hosted modules call do not
+//! exist. This pass replaces those calls with calls to the interpreter, when the layouts of the
+//! interpreter match the layouts of the effect. Other effects are left alone.
+//!
+//! This runs right after specialization, so the interpreter calls get the same optimizations and
+//! refcounting operations as any other call.
+
+use bumpalo::Bump;
+use roc_collections::MutMap;
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+
+use crate::ir::{Call, CallSpecId, CallType, Expr, Proc, ProcLayout, Stmt};
+use crate::ir_walk::map_lets;
+use crate::layout::{InLayout, LambdaName};
+
+/// The prefix of the names of effect interpreters
+pub const INTERPRETER_PREFIX: &str = "interpret";
+
+/// The name of the effect interpreted by a function with this name, if it is an effect
+/// interpreter, e.g. `putLine` for `interpretPutLine`.
+pub fn interpreted_effect(name: &str) -> Option<String> {
+    let mut rest = name.strip_prefix(INTERPRETER_PREFIX)?.chars();
+    let first = rest.next()?;
+
+    if first.is_ascii_uppercase() {
+        Some(format!("{}{}", first.to_ascii_lowercase(), rest.as_str()))
+    } else {
+        None
+    }
+}
+
+pub fn interpret_effects<'a>(
+    arena: &'a Bump,
+    home: ModuleId,
+    ident_ids: &IdentIds,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    // the specializations of the interpreters, by the foreign symbol of the effect they interpret
+    let mut interpreters: MutMap<String, Vec<(Symbol, ProcLayout<'a>)>> = MutMap::default();
+
+    for (symbol, proc_layout) in procs.keys() {
+        if symbol.module_id() != home {
+            continue;
+        }
+
+        let effect = ident_ids
+            .get_name(symbol.ident_id())
+            .and_then(interpreted_effect);
+
+        if let Some(effect) = effect {
+            interpreters
+                .entry(format!("roc_fx_{}", effect))
+                .or_default()
+                .push((*symbol, *proc_layout));
+        }
+    }
+
+    if interpreters.is_empty() {
+        return;
+    }
+
+    for proc in procs.values_mut() {
+        let layouts = symbol_layouts(proc);
+
+        // call specialization ids must be unique within a proc
+        let mut new_calls = 0;
+
+        let new_body = map_lets(arena, arena.alloc(proc.body.clone()), &mut |_, expr, _| {
+            let (foreign_symbol, ret_layout, arguments) = match expr {
+                Expr::Call(Call {
+                    call_type:
+                        CallType::Foreign {
+                            foreign_symbol,
+                            ret_layout,
+                            ..
+                        },
+                    arguments,
+                }) => (foreign_symbol, *ret_layout, *arguments),
+                _ => return Some(expr.clone()),
+            };
+
+            let matching = interpreters
+                .get(foreign_symbol.as_str())
+                .into_iter()
+                .flatten()
+                .find(|(_, proc_layout)| {
+                    proc_layout.result == ret_layout
+                        && proc_layout.arguments.len() == arguments.len()
+                        && arguments
+                            .iter()
+                            .zip(proc_layout.arguments)
+                            .all(|(argument, layout)| layouts.get(argument) == Some(layout))
+                });
+
+            match matching {
+                Some((interpreter, proc_layout)) => {
+                    let call_type = CallType::ByName {
+                        name: LambdaName::no_niche(*interpreter),
+                        ret_layout,
+                        arg_layouts: proc_layout.arguments,
+                        specialization_id: CallSpecId::after_specialization(new_calls),
+                    };
+
+                    new_calls += 1;
+
+                    Some(Expr::Call(Call {
+                        call_type,
+                        arguments,
+                    }))
+                }
+                None => Some(expr.clone()),
+            }
+        });
+
+        proc.body = new_body.clone();
+    }
+}
+
+/// The layout of every symbol that is defined in the proc
+fn symbol_layouts<'a>(proc: &Proc<'a>) -> MutMap<Symbol, InLayout<'a>> {
+    let mut layouts: MutMap<Symbol, InLayout<'a>> = proc
+        .args
+        .iter()
+        .map(|(layout, symbol)| (*symbol, *layout))
+        .collect();
+
+    let mut stack = vec![&proc.body];
+
+    while let Some(stmt) = stack.pop() {
+        match stmt {
+            Stmt::Let(symbol, _, layout, continuation) => {
+                layouts.insert(*symbol, *layout);
+                stack.push(continuation);
+            }
+            Stmt::Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                stack.push(default_branch.1);
+            }
+            Stmt::Join {
+                parameters,
+                body,
+                remainder,
+                ..
+            } => {
+                layouts.extend(parameters.iter().map(|param| (param.symbol, param.layout)));
+                stack.push(body);
+                stack.push(remainder);
+            }
+            Stmt::Refcounting(_, continuation) => stack.push(continuation),
+            Stmt::Expect { remainder, .. }
+            | Stmt::ExpectFx { remainder, .. }
+            | Stmt::Dbg { remainder, .. } => stack.push(remainder),
+            Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => {}
+        }
+    }
+
+    layouts
+}
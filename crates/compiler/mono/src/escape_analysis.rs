@@ -0,0 +1,96 @@
+//! Find boxes and list literals which never leave the proc that creates them, and store them
+//! in that proc's stack frame instead of on the heap.
+//!
+//! This is deliberately conservative. A box or list only counts as not escaping if all that's
+//! ever done with it is unboxing it, or getting its length or (already bounds-checked) elements.
+//! Those operations copy values out, so the contents must not contain anything reference
+//! counted. That way, stack allocations never need any refcounting operations at all.
+//!
+//! This runs before refcounting operations are inserted, which then skip stack allocations.
+
+use bumpalo::Bump;
+use roc_collections::MutMap;
+use roc_module::low_level::{LowLevel, LowLevelWrapperType};
+use roc_module::symbol::Symbol;
+
+use crate::ir::{Call, CallType, Expr, Proc, ProcLayout};
+use crate::ir_walk::{map_lets, used_symbols_except};
+use crate::layout::{InLayout, LayoutInterner, LayoutRepr, STLayoutInterner};
+
+/// Allocations bigger than this stay on the heap, so that stack frames stay small.
+pub const MAX_STACK_ALLOCATION_BYTES: u32 = 64;
+
+pub fn stack_allocate_non_escaping<'a, 'i>(
+    arena: &'a Bump,
+    layout_interner: &'i STLayoutInterner<'a>,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    for proc in procs.values_mut() {
+        let body = arena.alloc(proc.body.clone());
+
+        // Any symbol in here is used in some way other than the ones allowed for stack allocations.
+        let escaping = used_symbols_except(body, is_non_escaping_use);
+
+        let new_body = map_lets(arena, body, &mut |symbol, expr, layout| {
+            if escaping.contains(&symbol) {
+                return Some(expr.clone());
+            }
+
+            let new_expr = match expr {
+                Expr::ExprBox { symbol: value } => match layout_interner.get(layout).repr {
+                    LayoutRepr::Boxed(inner) if fits_on_stack(layout_interner, inner, 1) => {
+                        Expr::StackBox { symbol: *value }
+                    }
+                    _ => expr.clone(),
+                },
+                Expr::Array { elem_layout, elems }
+                    if fits_on_stack(layout_interner, *elem_layout, elems.len()) =>
+                {
+                    Expr::StackArray {
+                        elem_layout: *elem_layout,
+                        elems,
+                    }
+                }
+                _ => expr.clone(),
+            };
+
+            Some(new_expr)
+        });
+
+        proc.body = new_body.clone();
+    }
+}
+
+/// Whether this many values of the given layout can be stored on the stack
+fn fits_on_stack<'a>(
+    layout_interner: &STLayoutInterner<'a>,
+    layout: InLayout<'a>,
+    count: usize,
+) -> bool {
+    let size = layout_interner.stack_size(layout) as usize * count;
+
+    size > 0
+        && size <= MAX_STACK_ALLOCATION_BYTES as usize
+        && !layout_interner.contains_refcounted(layout)
+}
+
+/// Whether this expression only reads from the boxes or lists it refers to
+fn is_non_escaping_use(expr: &Expr) -> bool {
+    let op = match expr {
+        Expr::ExprUnbox { .. } => return true,
+        Expr::Call(Call {
+            call_type: CallType::LowLevel { op, .. },
+            ..
+        }) => *op,
+        Expr::Call(Call {
+            call_type: CallType::ByName { name, .. },
+            ..
+        }) => match LowLevelWrapperType::from_symbol(name.name()) {
+            LowLevelWrapperType::CanBeReplacedBy(op) => op,
+            LowLevelWrapperType::NotALowLevelWrapper => return false,
+        },
+        _ => return false,
+    };
+
+    matches!(op, LowLevel::ListLen | LowLevel::ListGetUnsafe)
+}
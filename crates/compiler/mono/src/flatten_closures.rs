@@ -0,0 +1,343 @@
+//! Pass the capture of a closure which captures a single small value straight to the closure's
+//! function, instead of the whole lambda set it belongs to.
+//!
+//! When a lambda set has several members, a closure is represented as a tag union of their
+//! captures, which is put on the heap when the lambda set is recursive. Calling the closure
+//! switches on the tag, and calls the member's function with the whole union, which the
+//! function then takes its captures out of:
+//!
+//! ```text
+//! procedure Test.adder (Test.x, #Attr.closure):
+//!     let Test.n = UnionAtIndex (Id 0) (Index 0) #Attr.closure;
+//!     ...
+//!
+//! let Test.r = CallByName Test.adder Test.x Test.f;
+//! ```
+//!
+//! If the function only ever uses the union to take out its one capture, the caller can do that
+//! instead, and pass just the capture:
+//!
+//! ```text
+//! procedure Test.adder (Test.x, Test.n):
+//!     ...
+//!
+//! let Test.c = UnionAtIndex (Id 0) (Index 0) Test.f;
+//! let Test.r = CallByName Test.adder Test.x Test.c;
+//! ```
+//!
+//! That keeps the capture in a register, and lets the caller drop a boxed closure as soon as it
+//! has read the capture out of it.
+//!
+//! Functions passed to higher-order builtins like `List.map`, and functions exposed to the host,
+//! are called by code which expects them to take the lambda set, so they are left alone.
+//!
+//! This runs before refcounting operations are inserted, so the capture gets the refcounting
+//! operations it needs as an argument like any other.
+
+use bumpalo::Bump;
+use roc_collections::all::{BumpMap, BumpMapDefault};
+use roc_collections::{MutMap, MutSet};
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+
+use crate::ir::{
+    substitute_in_exprs_many, Call, CallType, Expr, HostExposedLayouts, Proc, ProcLayout, Stmt,
+};
+use crate::ir_walk::{
+    for_each_let_expr, map_lets, rewrite_stmt, used_symbols_except, Let, Rewriter,
+};
+use crate::layout::{
+    ClosureRepresentation, InLayout, LayoutInterner, LayoutRepr, STLayoutInterner, TagIdIntType,
+    UnionLayout,
+};
+
+/// Captures bigger than this are still passed in their lambda set, which can be cheaper than
+/// copying them into the arguments.
+pub const MAX_FLATTENED_CAPTURE_BYTES: u32 = 16;
+
+/// How calls to a function whose capture is passed directly must be changed
+#[derive(Clone, Copy)]
+struct Flattened<'a> {
+    /// The layout of the function once it takes its capture instead of the lambda set
+    proc_layout: ProcLayout<'a>,
+    capture_layout: InLayout<'a>,
+    tag_id: TagIdIntType,
+    union_layout: UnionLayout<'a>,
+}
+
+pub fn flatten_single_captures<'a, 'i>(
+    arena: &'a Bump,
+    layout_interner: &'i STLayoutInterner<'a>,
+    home: ModuleId,
+    ident_ids: &'i mut IdentIds,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    let not_called_by_name = not_called_by_name(procs);
+
+    let mut flattened = MutMap::default();
+    let mut new_bodies = MutMap::default();
+
+    for ((symbol, proc_layout), proc) in procs.iter() {
+        if not_called_by_name.contains(symbol) {
+            continue;
+        }
+
+        if let Some((flat, new_body)) = flatten_proc(arena, layout_interner, *proc_layout, proc) {
+            flattened.insert((*symbol, *proc_layout), flat);
+            new_bodies.insert((*symbol, *proc_layout), new_body);
+        }
+    }
+
+    if flattened.is_empty() {
+        return;
+    }
+
+    let mut env = Env {
+        arena,
+        home,
+        ident_ids,
+        flattened: &flattened,
+    };
+
+    for (key, mut proc) in std::mem::take(procs) {
+        let key = match (flattened.get(&key), new_bodies.remove(&key)) {
+            (Some(flat), Some((capture, new_body))) => {
+                let mut args = Vec::from_iter(proc.args.iter().copied());
+                *args.last_mut().unwrap() = (flat.capture_layout, capture);
+
+                proc.args = arena.alloc_slice_copy(&args);
+                proc.body = new_body;
+
+                (key.0, flat.proc_layout)
+            }
+            _ => key,
+        };
+
+        proc.body = rewrite_stmt(arena, &mut env, &mut (), arena.alloc(proc.body)).clone();
+
+        procs.insert(key, proc);
+    }
+}
+
+/// The functions which are called other than by name: by higher-order builtins, or by the host.
+fn not_called_by_name<'a>(procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>) -> MutSet<Symbol> {
+    let mut symbols = MutSet::default();
+
+    for proc in procs.values() {
+        if let HostExposedLayouts::HostExposed { aliases, .. } = &proc.host_exposed_layouts {
+            symbols.insert(proc.name.name());
+            symbols.extend(aliases.values().map(|alias| alias.symbol));
+        }
+
+        for_each_let_expr(&proc.body, &mut |expr| {
+            if let Expr::Call(Call {
+                call_type: CallType::HigherOrder(higher_order),
+                ..
+            }) = expr
+            {
+                symbols.insert(higher_order.passed_function.name.name());
+            }
+        });
+    }
+
+    symbols
+}
+
+/// If the function only uses its lambda set to take out a single small capture, how calls to it
+/// must change, and its body without the lets that take out the capture, along with the symbol
+/// the capture should be bound to instead.
+fn flatten_proc<'a>(
+    arena: &'a Bump,
+    layout_interner: &STLayoutInterner<'a>,
+    proc_layout: ProcLayout<'a>,
+    proc: &Proc<'a>,
+) -> Option<(Flattened<'a>, (Symbol, Stmt<'a>))> {
+    if proc.name.niche().captures().len() != 1 {
+        return None;
+    }
+
+    let (closure_layout, closure) = *proc.args.last()?;
+    if proc.closure_data_layout != Some(closure_layout) {
+        return None;
+    }
+
+    let lambda_set = match layout_interner.get(closure_layout).repr {
+        LayoutRepr::LambdaSet(lambda_set) => lambda_set,
+        _ => return None,
+    };
+
+    let (tag_id, union_layout) =
+        match lambda_set.layout_for_member_with_lambda_name(layout_interner, proc.name) {
+            ClosureRepresentation::Union {
+                tag_id,
+                union_layout,
+                ..
+            } => (tag_id, union_layout),
+            _ => return None,
+        };
+
+    let body: &'a Stmt<'a> = arena.alloc(proc.body.clone());
+
+    let is_capture_read = move |expr: &Expr<'a>| match expr {
+        Expr::UnionAtIndex {
+            structure,
+            tag_id: read_tag_id,
+            union_layout: read_union_layout,
+            index: 0,
+        } => *structure == closure && *read_tag_id == tag_id && *read_union_layout == union_layout,
+        _ => false,
+    };
+
+    if used_symbols_except(body, is_capture_read).contains(&closure) {
+        return None;
+    }
+
+    let mut reads = Vec::new();
+    let new_body = map_lets(arena, body, &mut |binding, expr, layout| {
+        if is_capture_read(expr) {
+            reads.push((binding, layout));
+            None
+        } else {
+            Some(expr.clone())
+        }
+    });
+
+    let (capture, capture_layout) = *reads.first()?;
+    if layout_interner.stack_size(capture_layout) > MAX_FLATTENED_CAPTURE_BYTES {
+        return None;
+    }
+
+    // The capture is usually read once, but any other reads become the same symbol.
+    let mut new_body = new_body.clone();
+    if reads.len() > 1 {
+        let mut subs = BumpMap::new_in(arena);
+        subs.extend(reads[1..].iter().map(|(binding, _)| (*binding, capture)));
+        substitute_in_exprs_many(arena, &mut new_body, subs);
+    }
+
+    let mut arguments = Vec::from_iter(proc_layout.arguments.iter().copied());
+    *arguments.last_mut().unwrap() = capture_layout;
+
+    let flat = Flattened {
+        proc_layout: ProcLayout::new(
+            arena,
+            arena.alloc_slice_copy(&arguments),
+            proc_layout.niche,
+            proc_layout.result,
+        ),
+        capture_layout,
+        tag_id,
+        union_layout,
+    };
+
+    Some((flat, (capture, new_body)))
+}
+
+struct Env<'a, 'i> {
+    arena: &'a Bump,
+    home: ModuleId,
+    ident_ids: &'i mut IdentIds,
+    flattened: &'i MutMap<(Symbol, ProcLayout<'a>), Flattened<'a>>,
+}
+
+impl<'a, 'i> Env<'a, 'i> {
+    fn create_symbol(&mut self, debug_name: &str) -> Symbol {
+        let ident_id = self.ident_ids.add_str(debug_name);
+        Symbol::new(self.home, ident_id)
+    }
+}
+
+/// Take the capture out of the lambda set before every call of a flattened function, and pass
+/// it instead of the lambda set.
+impl<'a, 'i> Rewriter<'a> for Env<'a, 'i> {
+    type Scope = ();
+
+    fn rewrite_let(
+        &mut self,
+        _scope: &mut (),
+        binding: Symbol,
+        expr: &Expr<'a>,
+        layout: InLayout<'a>,
+        lets: &mut Vec<Let<'a>>,
+    ) {
+        let (call, flat) = match flattened_call(self, expr) {
+            Some(found) => found,
+            None => {
+                lets.push((binding, expr.clone(), layout));
+                return;
+            }
+        };
+
+        let closure = *call.arguments.last().unwrap();
+        let capture = self.create_symbol("capture");
+
+        let mut arguments = Vec::from_iter(call.arguments.iter().copied());
+        *arguments.last_mut().unwrap() = capture;
+
+        let call_type = match &call.call_type {
+            CallType::ByName {
+                name,
+                ret_layout,
+                specialization_id,
+                ..
+            } => CallType::ByName {
+                name: *name,
+                ret_layout: *ret_layout,
+                arg_layouts: flat.proc_layout.arguments,
+                specialization_id: *specialization_id,
+            },
+            _ => unreachable!(),
+        };
+
+        lets.push((
+            capture,
+            Expr::UnionAtIndex {
+                structure: closure,
+                tag_id: flat.tag_id,
+                union_layout: flat.union_layout,
+                index: 0,
+            },
+            flat.capture_layout,
+        ));
+        lets.push((
+            binding,
+            Expr::Call(Call {
+                call_type,
+                arguments: self.arena.alloc_slice_copy(&arguments),
+            }),
+            layout,
+        ));
+    }
+}
+
+/// The call this expression makes, if it calls a flattened function by name.
+fn flattened_call<'a, 'e>(
+    env: &Env<'a, '_>,
+    expr: &'e Expr<'a>,
+) -> Option<(&'e Call<'a>, Flattened<'a>)> {
+    match expr {
+        Expr::Call(
+            call @ Call {
+                call_type:
+                    CallType::ByName {
+                        name,
+                        ret_layout,
+                        arg_layouts,
+                        ..
+                    },
+                ..
+            },
+        ) => {
+            let key = (
+                name.name(),
+                ProcLayout {
+                    arguments: *arg_layouts,
+                    result: *ret_layout,
+                    niche: name.niche(),
+                },
+            );
+
+            env.flattened.get(&key).map(|flat| (call, *flat))
+        }
+        _ => None,
+    }
+}
@@ -157,11 +157,18 @@ impl<'a, 'i> SymbolRcTypesEnv<'a, 'i> {
             Stmt::Let(
                 binding,
                 // Expressions can be omitted, as they won't create new symbols.
-                _expr,
+                expr,
                 layout,
                 continuation,
             ) => {
-                self.insert_symbol_layout_rc_type(binding, layout);
+                match expr {
+                    // Stack allocations don't escape the procedure, and are never reference counted.
+                    Expr::StackBox { .. } | Expr::StackArray { .. } => {
+                        self.symbols_rc_type
+                            .insert(*binding, VarRcType::NotReferenceCounted);
+                    }
+                    _ => self.insert_symbol_layout_rc_type(binding, layout),
+                }
                 self.insert_symbols_rc_type_stmt(continuation);
             }
             Stmt::Switch {
@@ -894,6 +901,11 @@ fn insert_refcount_operations_binding<'a>(
             new_let!(stmt)
         }
 
+        Expr::StackBox { .. } | Expr::StackArray { .. } => {
+            // Stack allocations are not reference counted, and only ever contain values that aren't either.
+            new_let!(stmt)
+        }
+
         Expr::Tag { arguments, .. } | Expr::Struct(arguments) => {
             let new_let = new_let!(stmt);
 
@@ -962,12 +974,36 @@ fn insert_refcount_operations_binding<'a>(
 
                     inc_owned!(arguments.iter().copied(), new_let)
                 }
-                CallType::Foreign { .. } => {
-                    // Foreign functions should be responsible for their own memory management.
-                    // But previously they were assumed to be called with borrowed parameters, so we do the same now.
-                    let new_stmt = dec_borrowed!(arguments.iter().copied(), stmt);
+                CallType::Foreign {
+                    arg_ownership,
+                    ret_ownership,
+                    ..
+                } => {
+                    // The host borrows its arguments, unless the hosted module says it takes them over with `Owned`.
+                    let arguments_with_ownership =
+                        arguments.iter().copied().zip(arg_ownership.iter().copied());
+                    let owned_arguments = arguments_with_ownership
+                        .clone()
+                        .filter_map(|(symbol, ownership)| ownership.is_owned().then_some(symbol));
+                    let borrowed_arguments =
+                        arguments_with_ownership.filter_map(|(symbol, ownership)| {
+                            ownership.is_borrowed().then_some(symbol)
+                        });
+                    let new_stmt = dec_borrowed!(borrowed_arguments, stmt);
+
+                    // The host keeps owning a `Borrowed` result, so Roc needs a reference of its own.
+                    let newer_stmt = if ret_ownership.is_borrowed()
+                        && matches!(
+                            environment.get_symbol_rc_type(binding),
+                            VarRcType::ReferenceCounted
+                        ) {
+                        insert_inc_stmt(arena, *binding, 1, new_stmt)
+                    } else {
+                        new_stmt
+                    };
 
-                    new_let!(new_stmt)
+                    let new_let = new_let!(newer_stmt);
+                    inc_owned!(owned_arguments, new_let)
                 }
                 // Doesn't include higher order
                 CallType::LowLevel {
@@ -992,6 +1028,24 @@ fn insert_refcount_operations_binding<'a>(
                         };
                         new_let!(newer_stmt)
                     }
+                    // List repeat stores the element as many times as the count, so it needs that many references to it.
+                    // The count is only known at runtime, so this is a single increment by the count instead of a loop of increments.
+                    LowLevel::ListRepeat => {
+                        let (element, count) = match arguments {
+                            [element, count] => (*element, *count),
+                            _ => unreachable!("List repeat should have two arguments"),
+                        };
+                        let new_stmt = dec_borrowed!([element], stmt);
+                        let new_let = new_let!(new_stmt);
+                        if matches!(
+                            environment.get_symbol_rc_type(&element),
+                            VarRcType::ReferenceCounted
+                        ) {
+                            arena.alloc(Stmt::Refcounting(ModifyRc::IncN(element, count), new_let))
+                        } else {
+                            new_let
+                        }
+                    }
                     // Otherwise, perform regular reference counting using the lowlevel borrow signature.
                     _ => {
                         let borrow_signature = lowlevel_borrow_signature(arena, operator);
@@ -0,0 +1,215 @@
+//! The order in which top-level values are initialized.
+//!
+//! A top-level value like `primes = List.keepIf numbers isPrime` is compiled to a thunk: a
+//! procedure without arguments, which computes the value and is called wherever the value is
+//! used. Roc is pure, so when a thunk runs can only be observed through crashes, `dbg` and how
+//! long the program takes. The order is still well-defined: a value is initialized after every
+//! value its initializer depends on, whether it refers to them directly, through the functions it
+//! calls, or from another module. Values that don't depend on each other are ordered by their
+//! symbols, so the order is the same in every build.
+//!
+//! Constant folding and static data evaluate thunks at compile time in this order, so a value is
+//! always evaluated after the values it needs, in a single pass.
+//!
+//! A value which depends on itself can never finish initializing. Canonicalization and type
+//! checking reject most of these, but they can't see dependencies that go through an ability,
+//! like an `Encoding` implementation which refers to the value that uses it. Those are only
+//! known once everything is specialized, so they are found here. The thunks of such a value
+//! crash when they run, and the cycle is returned so it can be reported.
+
+use bumpalo::Bump;
+use roc_collections::{MutMap, ReferenceMatrix};
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+
+use crate::ir::{Call, CallType, CrashTag, Expr, Literal, Proc, ProcLayout, Stmt};
+use crate::layout::Layout;
+
+const CYCLE_MESSAGE: &str = "This value depends on itself, so it can never be initialized";
+
+#[derive(Debug, Default)]
+pub struct InitializationOrder<'a> {
+    /// The thunks of the top-level values, each after the thunks it depends on
+    pub order: Vec<(Symbol, ProcLayout<'a>)>,
+    /// The values which depend on themselves. Each cycle starts with a value, followed by the
+    /// procedures it goes through on the way back to itself.
+    pub cycles: Vec<Vec<Symbol>>,
+}
+
+pub fn order_initialization<'a>(
+    arena: &'a Bump,
+    home: ModuleId,
+    ident_ids: &mut IdentIds,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) -> InitializationOrder<'a> {
+    let mut keys: Vec<_> = procs.keys().copied().collect();
+    keys.sort_by_key(|(symbol, _)| *symbol);
+
+    let index_of: MutMap<_, _> = keys.iter().enumerate().map(|(i, key)| (*key, i)).collect();
+
+    let mut matrix = ReferenceMatrix::new(keys.len());
+
+    for (row, key) in keys.iter().enumerate() {
+        for_each_callee(&procs[key].body, &mut |callee| {
+            if let Some(col) = index_of.get(&callee) {
+                matrix.set_row_col(row, *col, true);
+            }
+        });
+    }
+
+    let mut initialization = InitializationOrder::default();
+    let mut cyclic_thunks = Vec::new();
+
+    // the groups are in topological order, with the procedures a group calls before it
+    for (group, _) in matrix.strongly_connected_components_all().groups() {
+        let members: Vec<usize> = group.iter_ones().collect();
+        let thunks = members
+            .iter()
+            .copied()
+            .filter(|index| is_thunk(&keys[*index]));
+
+        let is_cyclic = match members.as_slice() {
+            [index] => matrix.get_row_col(*index, *index),
+            _ => true,
+        };
+
+        if !is_cyclic {
+            initialization.order.extend(thunks.map(|index| keys[index]));
+            continue;
+        }
+
+        let thunks: Vec<usize> = thunks.collect();
+
+        if let Some(first) = thunks.first() {
+            let path = cycle_path(&matrix, &members, *first);
+            let cycle = path.into_iter().map(|index| keys[index].0).collect();
+
+            initialization.cycles.push(cycle);
+        }
+
+        cyclic_thunks.extend(thunks.into_iter().map(|index| keys[index]));
+    }
+
+    for key in cyclic_thunks {
+        let message = Symbol::new(home, ident_ids.gen_unique());
+        let proc = procs.get_mut(&key).unwrap();
+
+        proc.body = Stmt::Let(
+            message,
+            Expr::Literal(Literal::Str(CYCLE_MESSAGE)),
+            Layout::STR,
+            arena.alloc(Stmt::Crash(message, CrashTag::Roc)),
+        );
+    }
+
+    initialization
+}
+
+/// Whether this procedure is the thunk of a top-level value.
+pub fn is_thunk(key: &(Symbol, ProcLayout)) -> bool {
+    key.1.arguments.is_empty()
+}
+
+/// The shortest path from `start` back to itself, through the members of its group.
+fn cycle_path(matrix: &ReferenceMatrix, members: &[usize], start: usize) -> Vec<usize> {
+    let mut came_from = MutMap::default();
+    let mut queue = std::collections::VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        for next in matrix.references_for(node) {
+            if next == start {
+                let mut path = vec![node];
+                let mut current = node;
+
+                while current != start {
+                    current = came_from[&current];
+                    path.push(current);
+                }
+
+                path.reverse();
+                return path;
+            }
+
+            if members.contains(&next) && !came_from.contains_key(&next) {
+                came_from.insert(next, node);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    vec![start]
+}
+
+/// Calls `f` with the specialization of every procedure that `stmt` calls, or passes to a
+/// higher-order builtin.
+fn for_each_callee<'a>(stmt: &Stmt<'a>, f: &mut impl FnMut((Symbol, ProcLayout<'a>))) {
+    let mut stack = vec![stmt];
+
+    while let Some(stmt) = stack.pop() {
+        match stmt {
+            Stmt::Let(_, expr, _, continuation) => {
+                match expr {
+                    Expr::Call(Call {
+                        call_type:
+                            CallType::ByName {
+                                name,
+                                ret_layout,
+                                arg_layouts,
+                                ..
+                            },
+                        ..
+                    }) => f((
+                        name.name(),
+                        ProcLayout {
+                            arguments: *arg_layouts,
+                            result: *ret_layout,
+                            niche: name.niche(),
+                        },
+                    )),
+                    Expr::Call(Call {
+                        call_type: CallType::HigherOrder(higher_order),
+                        ..
+                    }) => {
+                        let passed_function = &higher_order.passed_function;
+
+                        f((
+                            passed_function.name.name(),
+                            ProcLayout {
+                                arguments: passed_function.argument_layouts,
+                                result: passed_function.return_layout,
+                                niche: passed_function.name.niche(),
+                            },
+                        ))
+                    }
+                    _ => {}
+                }
+
+                stack.push(continuation);
+            }
+            Stmt::Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                stack.push(default_branch.1);
+            }
+            Stmt::Join {
+                body, remainder, ..
+            } => {
+                stack.push(body);
+                stack.push(remainder);
+            }
+            Stmt::Refcounting(_, rest)
+            | Stmt::Expect {
+                remainder: rest, ..
+            }
+            | Stmt::ExpectFx {
+                remainder: rest, ..
+            }
+            | Stmt::Dbg {
+                remainder: rest, ..
+            } => stack.push(rest),
+            Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => {}
+        }
+    }
+}
@@ -17,8 +17,12 @@ use roc_collections::VecMap;
 use roc_debug_flags::dbg_do;
 #[cfg(debug_assertions)]
 use roc_debug_flags::{
-    ROC_PRINT_IR_AFTER_DROP_SPECIALIZATION, ROC_PRINT_IR_AFTER_REFCOUNT,
-    ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SPECIALIZATION, ROC_PRINT_RUNTIME_ERROR_GEN,
+    ROC_PRINT_IR_AFTER_BOUNDS_CHECKS, ROC_PRINT_IR_AFTER_CONSTANT_FOLDING,
+    ROC_PRINT_IR_AFTER_DROP_SPECIALIZATION, ROC_PRINT_IR_AFTER_EFFECT_INTERPRETERS,
+    ROC_PRINT_IR_AFTER_ESCAPE_ANALYSIS, ROC_PRINT_IR_AFTER_FLATTEN_CLOSURES,
+    ROC_PRINT_IR_AFTER_MUTUAL_TAIL_CALLS, ROC_PRINT_IR_AFTER_PURE_CALLS,
+    ROC_PRINT_IR_AFTER_REFCOUNT, ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SPECIALIZATION,
+    ROC_PRINT_RUNTIME_ERROR_GEN,
 };
 use roc_derive::SharedDerivedModule;
 use roc_error_macros::{internal_error, todo_abilities};
@@ -51,6 +55,27 @@ pub fn pretty_print_ir_symbols() -> bool {
     dbg_do!(ROC_PRINT_IR_AFTER_SPECIALIZATION, {
         return true;
     });
+    dbg_do!(ROC_PRINT_IR_AFTER_EFFECT_INTERPRETERS, {
+        return true;
+    });
+    dbg_do!(ROC_PRINT_IR_AFTER_MUTUAL_TAIL_CALLS, {
+        return true;
+    });
+    dbg_do!(ROC_PRINT_IR_AFTER_CONSTANT_FOLDING, {
+        return true;
+    });
+    dbg_do!(ROC_PRINT_IR_AFTER_PURE_CALLS, {
+        return true;
+    });
+    dbg_do!(ROC_PRINT_IR_AFTER_FLATTEN_CLOSURES, {
+        return true;
+    });
+    dbg_do!(ROC_PRINT_IR_AFTER_BOUNDS_CHECKS, {
+        return true;
+    });
+    dbg_do!(ROC_PRINT_IR_AFTER_ESCAPE_ANALYSIS, {
+        return true;
+    });
     dbg_do!(ROC_PRINT_IR_AFTER_RESET_REUSE, {
         return true;
     });
@@ -1606,6 +1631,11 @@ pub enum BranchInfo<'a> {
         scrutinee: Symbol,
         len: u64,
     },
+    /// The string has this many bytes
+    Str {
+        scrutinee: Symbol,
+        len: u64,
+    },
 }
 
 impl<'a> BranchInfo<'a> {
@@ -1623,6 +1653,9 @@ impl<'a> BranchInfo<'a> {
 pub enum ModifyRc {
     /// Increment a reference count
     Inc(Symbol, u64),
+    /// Increment a reference count by an amount which is only known at runtime, e.g. the
+    /// number of times `List.repeat` stores its element. The second symbol is that amount.
+    IncN(Symbol, Symbol),
     /// Decrement a reference count
     Dec(Symbol),
     /// A DecRef is a non-recursive reference count decrement
@@ -1654,6 +1687,12 @@ impl ModifyRc {
                 .append(text!(alloc, "{} ", n))
                 .append(symbol_to_doc(alloc, symbol, pretty))
                 .append(";"),
+            IncN(symbol, count) => alloc
+                .text("inc ")
+                .append(symbol_to_doc(alloc, count, pretty))
+                .append(" ")
+                .append(symbol_to_doc(alloc, symbol, pretty))
+                .append(";"),
             Dec(symbol) => alloc
                 .text("dec ")
                 .append(symbol_to_doc(alloc, symbol, pretty))
@@ -1670,6 +1709,7 @@ impl ModifyRc {
 
         match self {
             Inc(symbol, _) => *symbol,
+            IncN(symbol, _) => *symbol,
             Dec(symbol) => *symbol,
             DecRef(symbol) => *symbol,
         }
@@ -1736,6 +1776,14 @@ impl CallSpecId {
     /// Dummy value for generating refcount helper procs in the backends
     /// This happens *after* specialization so it's safe
     pub const BACKEND_DUMMY: Self = Self { id: 0 };
+
+    /// For calls that are added after specialization. These count down from the largest id,
+    /// so they don't clash with the ids of the calls made during specialization.
+    pub(crate) fn after_specialization(index: u32) -> Self {
+        Self {
+            id: u32::MAX - index,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -1781,6 +1829,12 @@ pub enum CallType<'a> {
     Foreign {
         foreign_symbol: ForeignSymbol,
         ret_layout: InLayout<'a>,
+        /// Whether the host takes over each argument (`Owned`), or only borrows it
+        arg_ownership: &'a [Ownership],
+        /// Whether Roc takes over the returned value (`Owned`), or has to take its own reference
+        ret_ownership: Ownership,
+        /// Whether the host function is annotated `Pure`
+        pure: bool,
     },
     LowLevel {
         op: LowLevel,
@@ -1805,6 +1859,21 @@ impl<'a> CallType<'a> {
             _ => self,
         }
     }
+
+    /**
+    Whether this is a call of a `Pure` host function which only borrows its arguments. Such a call
+    neither changes nor looks at any refcount, so refcounting operations can move across it.
+    */
+    pub fn is_pure_borrowing_foreign(&self) -> bool {
+        match self {
+            CallType::Foreign {
+                arg_ownership,
+                pure,
+                ..
+            } => *pure && arg_ownership.iter().all(|o| *o == Ownership::Borrowed),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -1885,6 +1954,20 @@ pub enum Expr<'a> {
         symbol: Symbol,
     },
 
+    /// Like [Expr::ExprBox], but the value is stored in the current proc's stack frame instead
+    /// of on the heap. Escape analysis only produces this for boxes which never leave the proc,
+    /// so they are not reference counted.
+    StackBox {
+        symbol: Symbol,
+    },
+
+    /// Like [Expr::Array], but the elements are stored in the current proc's stack frame.
+    /// See [Expr::StackBox].
+    StackArray {
+        elem_layout: InLayout<'a>,
+        elems: &'a [ListLiteralElement<'a>],
+    },
+
     Reuse {
         symbol: Symbol,
         update_tag_id: bool,
@@ -2060,6 +2143,17 @@ impl<'a> Expr<'a> {
                     .append(alloc.text("]"))
             }
             EmptyArray => alloc.text("Array []"),
+            StackArray { elems, .. } => {
+                let it = elems.iter().map(|e| match e {
+                    ListLiteralElement::Literal(l) => l.to_doc(alloc),
+                    ListLiteralElement::Symbol(s) => symbol_to_doc(alloc, *s, pretty),
+                });
+
+                alloc
+                    .text("StackArray [")
+                    .append(alloc.intersperse(it, ", "))
+                    .append(alloc.text("]"))
+            }
 
             StructAtIndex {
                 index, structure, ..
@@ -2080,6 +2174,10 @@ impl<'a> Expr<'a> {
                 .text("Unbox ")
                 .append(symbol_to_doc(alloc, *symbol, pretty)),
 
+            StackBox { symbol, .. } => alloc
+                .text("StackBox ")
+                .append(symbol_to_doc(alloc, *symbol, pretty)),
+
             UnionAtIndex {
                 tag_id,
                 structure,
@@ -5394,6 +5492,9 @@ pub fn with_hole<'a>(
             foreign_symbol,
             args,
             ret_var,
+            arg_ownership,
+            ret_ownership,
+            pure,
         } => {
             let mut arg_symbols = Vec::with_capacity_in(args.len(), env.arena);
 
@@ -5419,6 +5520,13 @@ pub fn with_hole<'a>(
                 call_type: CallType::Foreign {
                     foreign_symbol,
                     ret_layout: layout,
+                    arg_ownership: arg_ownership
+                        .iter()
+                        .map(|ownership| Ownership::from(*ownership))
+                        .collect_in::<Vec<_>>(env.arena)
+                        .into_bump_slice(),
+                    ret_ownership: Ownership::from(ret_ownership),
+                    pure,
                 },
                 arguments: arg_symbols,
             };
@@ -7496,6 +7604,10 @@ fn substitute_in_expr<'a>(
             unreachable!("reset/resetref/reuse have not been introduced yet")
         }
 
+        StackBox { .. } | StackArray { .. } => {
+            unreachable!("escape analysis has not run yet")
+        }
+
         Struct(args) => {
             let mut did_change = false;
             let new_args = Vec::from_iter_in(
@@ -121,6 +121,13 @@ enum Test<'a> {
         bound: ListLenBound,
         len: u64,
     },
+    /// The length of a string in bytes; only used to dispatch on string patterns, see [`str_decider`]
+    IsStrLen(u64),
+    /// A byte of a string; only used to dispatch on string patterns, see [`str_decider`]
+    IsStrByte {
+        index: u64,
+        byte: u8,
+    },
 }
 
 impl<'a> Test<'a> {
@@ -140,6 +147,8 @@ impl<'a> Test<'a> {
                 ListLenBound::Exact => true,
                 ListLenBound::AtLeast => false,
             },
+            Test::IsStrLen(_) => true,
+            Test::IsStrByte { .. } => true,
         }
     }
 }
@@ -186,6 +195,14 @@ impl<'a> Hash for Test<'a> {
                 state.write_u8(7);
                 (len, bound).hash(state);
             }
+            IsStrLen(len) => {
+                state.write_u8(8);
+                len.hash(state);
+            }
+            IsStrByte { index, byte } => {
+                state.write_u8(9);
+                (index, byte).hash(state);
+            }
         }
     }
 }
@@ -373,6 +390,8 @@ fn tests_are_complete_help(last_test: &Test, number_of_tests: usize) -> bool {
             len: 0,
         } => true, // [..] test
         Test::IsListLen { .. } => false,
+        Test::IsStrLen(_) => false,
+        Test::IsStrByte { .. } => false,
     }
 }
 
@@ -1801,9 +1820,68 @@ fn test_to_comparison<'a>(
                 ),
             }
         }
+
+        Test::IsStrLen(len) => {
+            let real_len_expr = Expr::Call(Call {
+                call_type: CallType::LowLevel {
+                    op: LowLevel::StrCountUtf8Bytes,
+                    update_mode: env.next_update_mode_id(),
+                },
+                arguments: env.arena.alloc([rhs_symbol]),
+            });
+            let test_len_expr = Expr::Literal(Literal::Int((len as i128).to_ne_bytes()));
+
+            let real_len = env.unique_symbol();
+            let test_len = env.unique_symbol();
+
+            let usize_layout = Layout::usize(env.target_info);
+
+            stores.push((real_len, usize_layout, real_len_expr));
+            stores.push((test_len, usize_layout, test_len_expr));
+
+            (stores, (real_len, Comparator::Eq, test_len), None)
+        }
+
+        Test::IsStrByte { index, byte } => {
+            let (real_byte, new_stores) = build_str_byte_probe(env, rhs_symbol, index);
+            stores.extend(new_stores);
+
+            let test_byte = env.unique_symbol();
+            stores.push((test_byte, Layout::U8, Expr::Literal(Literal::Byte(byte))));
+
+            (stores, (real_byte, Comparator::Eq, test_byte), None)
+        }
     }
 }
 
+/// Load the byte at the given index of a string, which must be in bounds.
+fn build_str_byte_probe<'a>(
+    env: &mut Env<'a, '_>,
+    str_sym: Symbol,
+    index: u64,
+) -> (Symbol, StoresVec<'a>) {
+    let usize_layout = Layout::usize(env.target_info);
+
+    let index_sym = env.unique_symbol();
+    let index_expr = Expr::Literal(Literal::Int((index as i128).to_ne_bytes()));
+
+    let byte_sym = env.unique_symbol();
+    let byte_expr = Expr::Call(Call {
+        call_type: CallType::LowLevel {
+            op: LowLevel::StrGetUnsafe,
+            update_mode: env.next_update_mode_id(),
+        },
+        arguments: env.arena.alloc([str_sym, index_sym]),
+    });
+
+    let stores = bumpalo::vec![in env.arena;
+        (index_sym, usize_layout, index_expr),
+        (byte_sym, Layout::U8, byte_expr),
+    ];
+
+    (byte_sym, stores)
+}
+
 #[derive(Debug, Clone, Copy)]
 enum Comparator {
     Eq,
@@ -1925,6 +2003,12 @@ fn compile_test_help<'a>(
                 (pass_info, BranchInfo::None)
             }
 
+            StrLen { scrutinee, len } => {
+                let pass_info = BranchInfo::Str { scrutinee, len };
+
+                (pass_info, BranchInfo::None)
+            }
+
             None => (BranchInfo::None, BranchInfo::None),
         }
     };
@@ -2002,6 +2086,10 @@ enum ConstructorKnown<'a> {
         scrutinee: Symbol,
         len: u64,
     },
+    StrLen {
+        scrutinee: Symbol,
+        len: u64,
+    },
     None,
 }
 
@@ -2037,6 +2125,10 @@ impl<'a> ConstructorKnown<'a> {
                     scrutinee: cond_symbol,
                     len: *len,
                 },
+                Test::IsStr(string) if path.is_empty() => ConstructorKnown::StrLen {
+                    scrutinee: cond_symbol,
+                    len: string.len() as u64,
+                },
                 _ => ConstructorKnown::None,
             },
             _ => ConstructorKnown::None,
@@ -2243,6 +2335,12 @@ fn decide_to_branching<'a>(
             let mut tag_id_sum: i64 = (0..tests.len() as i64 + 1).sum();
             let mut union_size: i64 = -1;
 
+            // a switch on a string is either on its length, or on one of its bytes
+            let str_byte_index = tests.iter().find_map(|(test, _)| match test {
+                Test::IsStrByte { index, .. } => Some(*index),
+                _ => None,
+            });
+
             for (test, decider) in tests {
                 let branch = decide_to_branching(
                     env,
@@ -2267,6 +2365,8 @@ fn decide_to_branching<'a>(
                             unreachable!("at-least bounds cannot be switched on")
                         }
                     },
+                    Test::IsStrLen(len) => len,
+                    Test::IsStrByte { byte, .. } => byte as u64,
                     Test::IsDecimal(_) => unreachable!("decimals cannot be switched on"),
                     Test::IsStr(_) => unreachable!("strings cannot be switched on"),
                 };
@@ -2293,6 +2393,13 @@ fn decide_to_branching<'a>(
                             len,
                         }
                     }
+                    Test::IsStrLen(len) => {
+                        tag_id_sum = -1;
+                        BranchInfo::Str {
+                            scrutinee: inner_cond_symbol,
+                            len,
+                        }
+                    }
                     _ => {
                         tag_id_sum = -1;
                         BranchInfo::None
@@ -2363,6 +2470,45 @@ fn decide_to_branching<'a>(
                     Layout::usize(env.target_info),
                     env.arena.alloc(switch),
                 )
+            } else if let LayoutRepr::Builtin(Builtin::Str) = inner_cond_layout_raw.repr {
+                let (switch_symbol, switch_layout, stores) = match str_byte_index {
+                    Some(index) => {
+                        let (byte_symbol, stores) =
+                            build_str_byte_probe(env, inner_cond_symbol, index);
+
+                        (byte_symbol, Layout::U8, stores)
+                    }
+                    None => {
+                        let len_symbol = env.unique_symbol();
+                        let len_expr = Expr::Call(Call {
+                            call_type: CallType::LowLevel {
+                                op: LowLevel::StrCountUtf8Bytes,
+                                update_mode: env.next_update_mode_id(),
+                            },
+                            arguments: env.arena.alloc([inner_cond_symbol]),
+                        });
+
+                        let usize_layout = Layout::usize(env.target_info);
+                        let stores =
+                            bumpalo::vec![in env.arena; (len_symbol, usize_layout, len_expr)];
+
+                        (len_symbol, usize_layout, stores)
+                    }
+                };
+
+                let mut switch = Stmt::Switch {
+                    cond_layout: switch_layout,
+                    cond_symbol: switch_symbol,
+                    branches: branches.into_bump_slice(),
+                    default_branch: (default_branch_info, env.arena.alloc(default_branch)),
+                    ret_layout,
+                };
+
+                for (symbol, layout, expr) in stores.into_iter().rev() {
+                    switch = Stmt::Let(symbol, expr, layout, env.arena.alloc(switch));
+                }
+
+                switch
             } else {
                 Stmt::Switch {
                     cond_layout: inner_cond_layout,
@@ -2571,25 +2717,164 @@ fn fanout_decider<'a>(
         .map(|(test, tree)| fanout_decider_help(tree, test))
         .collect();
 
+    let all_strings = necessary_tests
+        .iter()
+        .all(|(t, _)| matches!(t, Test::IsStr(_)));
+
     if necessary_tests.iter().all(|(t, _)| t.can_be_switch()) {
         Decider::FanOut {
             path,
             tests: necessary_tests,
             fallback: Box::new(fallback_decider),
         }
+    } else if let (true, Decider::Leaf(fallback_target)) = (all_strings, &fallback_decider) {
+        str_decider(&path, necessary_tests, *fallback_target)
     } else {
         // in llvm, we cannot switch on strings so must chain
-        let mut decider = fallback_decider;
+        chain_tests(&path, necessary_tests, fallback_decider)
+    }
+}
 
-        for (test, branch_decider) in necessary_tests.into_iter().rev() {
-            decider = Decider::Chain {
-                test_chain: vec![(path.clone(), test)],
-                success: Box::new(branch_decider),
-                failure: Box::new(decider),
-            };
+fn chain_tests<'a>(
+    path: &[PathInstruction],
+    tests: Vec<(Test<'a>, Decider<'a, u64>)>,
+    failure: Decider<'a, u64>,
+) -> Decider<'a, u64> {
+    let mut decider = failure;
+
+    for (test, branch_decider) in tests.into_iter().rev() {
+        decider = Decider::Chain {
+            test_chain: vec![(path.to_vec(), test)],
+            success: Box::new(branch_decider),
+            failure: Box::new(decider),
+        };
+    }
+
+    decider
+}
+
+/// Below this many string patterns, comparing against each of them in turn is just as fast.
+const STR_DISPATCH_THRESHOLD: usize = 3;
+
+/// Strings cannot be switched on, but their lengths and bytes can. So rather than comparing a
+/// string against every pattern in turn, first switch on its length, then on a byte where the
+/// remaining patterns (of the same length) differ, and only then compare it in full.
+///
+/// Every group falls back to the same target, which is duplicated. That is fine for a jump to a
+/// target, so this is only used when the fallback is a leaf.
+fn str_decider<'a>(
+    path: &[PathInstruction],
+    tests: Vec<(Test<'a>, Decider<'a, u64>)>,
+    fallback: u64,
+) -> Decider<'a, u64> {
+    if tests.len() < STR_DISPATCH_THRESHOLD {
+        return chain_tests(path, tests, Decider::Leaf(fallback));
+    }
+
+    let mut by_len: Vec<(u64, Vec<_>)> = Vec::new();
+
+    for (test, decider) in tests {
+        let len = match &test {
+            Test::IsStr(string) => string.len() as u64,
+            _ => internal_error!("not a string test: {:?}", test),
+        };
+
+        match by_len.iter_mut().find(|(l, _)| *l == len) {
+            Some((_, group)) => group.push((test, decider)),
+            None => by_len.push((len, vec![(test, decider)])),
         }
+    }
+
+    if by_len.len() == 1 {
+        // all strings have the same length, so there is no point in switching on it
+        let (_, group) = by_len.pop().unwrap();
+
+        return str_bytes_decider(path, group, fallback);
+    }
 
-        decider
+    let tests = by_len
+        .into_iter()
+        .map(|(len, group)| {
+            let decider = str_bytes_decider(path, group, fallback);
+
+            (Test::IsStrLen(len), decider)
+        })
+        .collect();
+
+    Decider::FanOut {
+        path: path.to_vec(),
+        tests,
+        fallback: Box::new(Decider::Leaf(fallback)),
+    }
+}
+
+/// Dispatch on string patterns of the same length, see [`str_decider`].
+fn str_bytes_decider<'a>(
+    path: &[PathInstruction],
+    tests: Vec<(Test<'a>, Decider<'a, u64>)>,
+    fallback: u64,
+) -> Decider<'a, u64> {
+    let bytes_at = |index: usize| {
+        let mut bytes: Vec<u8> = tests
+            .iter()
+            .map(|(test, _)| match test {
+                Test::IsStr(string) => string.as_bytes()[index],
+                _ => internal_error!("not a string test: {:?}", test),
+            })
+            .collect();
+
+        bytes.sort_unstable();
+        bytes.dedup();
+        bytes
+    };
+
+    let len = match tests.first() {
+        Some((Test::IsStr(string), _)) => string.len(),
+        _ => 0,
+    };
+
+    // the byte which tells the most patterns apart
+    let best_index = (0..len)
+        .map(|index| (index, bytes_at(index).len()))
+        .filter(|(_, distinct)| *distinct > 1)
+        .max_by_key(|(index, distinct)| (*distinct, std::cmp::Reverse(*index)))
+        .map(|(index, _)| index);
+
+    match best_index {
+        Some(index) if tests.len() >= STR_DISPATCH_THRESHOLD => {
+            let mut by_byte: Vec<(u8, Vec<_>)> = Vec::new();
+
+            for (test, decider) in tests {
+                let byte = match &test {
+                    Test::IsStr(string) => string.as_bytes()[index],
+                    _ => internal_error!("not a string test: {:?}", test),
+                };
+
+                match by_byte.iter_mut().find(|(b, _)| *b == byte) {
+                    Some((_, group)) => group.push((test, decider)),
+                    None => by_byte.push((byte, vec![(test, decider)])),
+                }
+            }
+
+            let tests = by_byte
+                .into_iter()
+                .map(|(byte, group)| {
+                    let test = Test::IsStrByte {
+                        index: index as u64,
+                        byte,
+                    };
+
+                    (test, chain_tests(path, group, Decider::Leaf(fallback)))
+                })
+                .collect();
+
+            Decider::FanOut {
+                path: path.to_vec(),
+                tests,
+                fallback: Box::new(Decider::Leaf(fallback)),
+            }
+        }
+        _ => chain_tests(path, tests, Decider::Leaf(fallback)),
     }
 }
 
@@ -99,7 +99,13 @@ pub fn make_num_literal<'a>(
             },
         },
         LayoutRepr::Builtin(Builtin::Decimal) => {
-            let dec = match RocDec::from_str(num_str) {
+            // Literals RocDec can't read directly (like `1e3` or `0x1.8p3`) go through their
+            // float value instead.
+            let opt_dec = RocDec::from_str(&num_str.replace('_', "")).or_else(|| match num_value {
+                IntOrFloatValue::Float(float) => RocDec::from_str(&float.to_string()),
+                IntOrFloatValue::Int(_) => None,
+            });
+            let dec = match opt_dec {
                 Some(d) => d,
                 None => internal_error!(
                     "Invalid decimal for float literal = {}. This should be a type error!",
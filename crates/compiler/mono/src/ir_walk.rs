@@ -0,0 +1,397 @@
+//! Walking and rewriting the statements of a procedure, for the passes which run between
+//! specialization and the insertion of refcounting operations (and after it, in the case of
+//! arena allocation).
+
+use bumpalo::collections::CollectIn;
+use bumpalo::Bump;
+use roc_collections::MutSet;
+use roc_module::symbol::Symbol;
+
+use crate::ir::{BranchInfo, CallType, Expr, ModifyRc, Stmt};
+use crate::layout::InLayout;
+
+/// A let binding: the symbol, the expression bound to it and its layout
+pub(crate) type Let<'a> = (Symbol, Expr<'a>, InLayout<'a>);
+
+/// The branch of a switch which is about to be rewritten
+pub(crate) enum SwitchBranch<'s, 'a> {
+    /// The branch taken when the condition has the given value
+    Case(u64, &'s BranchInfo<'a>),
+    /// The branch taken when the condition has none of the values of the given branches
+    Default(&'s [(u64, BranchInfo<'a>, Stmt<'a>)], &'s BranchInfo<'a>),
+}
+
+/// A rewrite of the lets and refcounting operations of a statement, done by [rewrite_stmt].
+///
+/// What a rewriter knows at a point of the statement is kept in its `Scope`. Every branch of a
+/// switch starts from a copy of the scope before the switch, and so does the body of a join
+/// point: the body only runs after a jump from the remainder, so everything known before the
+/// join point is known in the body too, but the remainder may jump before it learns more.
+pub(crate) trait Rewriter<'a> {
+    type Scope: Clone;
+
+    /// Push the lets which replace `binding = expr` onto `lets`, in order. Pushing none removes
+    /// the binding.
+    fn rewrite_let(
+        &mut self,
+        scope: &mut Self::Scope,
+        binding: Symbol,
+        expr: &Expr<'a>,
+        layout: InLayout<'a>,
+        lets: &mut Vec<Let<'a>>,
+    );
+
+    /// Learn what taking the given branch of a switch on `cond_symbol` tells us.
+    fn enter_branch(
+        &mut self,
+        _scope: &mut Self::Scope,
+        _cond_symbol: Symbol,
+        _cond_layout: InLayout<'a>,
+        _branch: SwitchBranch<'_, 'a>,
+    ) {
+    }
+
+    /// Whether to keep the given refcounting operation.
+    fn keep_refcounting(&mut self, _modify_rc: &ModifyRc) -> bool {
+        true
+    }
+}
+
+/// Rebuild the given statement, with its lets and refcounting operations rewritten by the
+/// given rewriter, in the order they run in.
+pub(crate) fn rewrite_stmt<'a, R: Rewriter<'a>>(
+    arena: &'a Bump,
+    rewriter: &mut R,
+    scope: &mut R::Scope,
+    stmt: &'a Stmt<'a>,
+) -> &'a Stmt<'a> {
+    match stmt {
+        Stmt::Let(..) => {
+            // Handle a whole chain of lets at once, so long chains don't overflow the stack.
+            let mut new_lets = Vec::new();
+            let mut current_stmt = stmt;
+
+            while let Stmt::Let(binding, expr, layout, next_stmt) = current_stmt {
+                rewriter.rewrite_let(scope, *binding, expr, *layout, &mut new_lets);
+
+                current_stmt = next_stmt;
+            }
+
+            let new_continuation = rewrite_stmt(arena, rewriter, scope, current_stmt);
+
+            new_lets.into_iter().rev().fold(
+                new_continuation,
+                |new_continuation, (binding, new_expr, layout)| {
+                    arena.alloc(Stmt::Let(binding, new_expr, layout, new_continuation))
+                },
+            )
+        }
+        Stmt::Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout,
+        } => {
+            let new_branches = branches
+                .iter()
+                .map(|(tag_id, info, branch)| {
+                    let mut branch_scope = scope.clone();
+                    let taken = SwitchBranch::Case(*tag_id, info);
+                    rewriter.enter_branch(&mut branch_scope, *cond_symbol, *cond_layout, taken);
+
+                    let new_branch = rewrite_stmt(arena, rewriter, &mut branch_scope, branch);
+
+                    (*tag_id, info.clone(), new_branch.clone())
+                })
+                .collect_in::<bumpalo::collections::Vec<_>>(arena)
+                .into_bump_slice();
+
+            let new_default_branch = {
+                let (info, branch) = default_branch;
+
+                let mut branch_scope = scope.clone();
+                let taken = SwitchBranch::Default(branches, info);
+                rewriter.enter_branch(&mut branch_scope, *cond_symbol, *cond_layout, taken);
+
+                (
+                    info.clone(),
+                    rewrite_stmt(arena, rewriter, &mut branch_scope, branch),
+                )
+            };
+
+            arena.alloc(Stmt::Switch {
+                cond_symbol: *cond_symbol,
+                cond_layout: *cond_layout,
+                branches: new_branches,
+                default_branch: new_default_branch,
+                ret_layout: *ret_layout,
+            })
+        }
+        Stmt::Refcounting(modify_rc, continuation) => {
+            if rewriter.keep_refcounting(modify_rc) {
+                arena.alloc(Stmt::Refcounting(
+                    *modify_rc,
+                    rewrite_stmt(arena, rewriter, scope, continuation),
+                ))
+            } else {
+                rewrite_stmt(arena, rewriter, scope, continuation)
+            }
+        }
+        Stmt::Expect {
+            condition,
+            region,
+            lookups,
+            variables,
+            remainder,
+        } => arena.alloc(Stmt::Expect {
+            condition: *condition,
+            region: *region,
+            lookups: *lookups,
+            variables: *variables,
+            remainder: rewrite_stmt(arena, rewriter, scope, remainder),
+        }),
+        Stmt::ExpectFx {
+            condition,
+            region,
+            lookups,
+            variables,
+            remainder,
+        } => arena.alloc(Stmt::ExpectFx {
+            condition: *condition,
+            region: *region,
+            lookups: *lookups,
+            variables: *variables,
+            remainder: rewrite_stmt(arena, rewriter, scope, remainder),
+        }),
+        Stmt::Dbg {
+            symbol,
+            variable,
+            remainder,
+        } => arena.alloc(Stmt::Dbg {
+            symbol: *symbol,
+            variable: *variable,
+            remainder: rewrite_stmt(arena, rewriter, scope, remainder),
+        }),
+        Stmt::Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => {
+            let new_body = rewrite_stmt(arena, rewriter, &mut scope.clone(), body);
+            let new_remainder = rewrite_stmt(arena, rewriter, scope, remainder);
+
+            arena.alloc(Stmt::Join {
+                id: *id,
+                parameters: *parameters,
+                body: new_body,
+                remainder: new_remainder,
+            })
+        }
+        Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => stmt,
+    }
+}
+
+/// Rebuild the given statement, passing every let binding (in order) to the given function.
+/// It returns the expression to bind instead, or None to remove the binding entirely.
+pub(crate) fn map_lets<'a>(
+    arena: &'a Bump,
+    stmt: &'a Stmt<'a>,
+    f: &mut impl FnMut(Symbol, &Expr<'a>, InLayout<'a>) -> Option<Expr<'a>>,
+) -> &'a Stmt<'a> {
+    rewrite_stmt(arena, &mut MapLets(f), &mut (), stmt)
+}
+
+struct MapLets<F>(F);
+
+impl<'a, F> Rewriter<'a> for MapLets<F>
+where
+    F: FnMut(Symbol, &Expr<'a>, InLayout<'a>) -> Option<Expr<'a>>,
+{
+    type Scope = ();
+
+    fn rewrite_let(
+        &mut self,
+        _scope: &mut (),
+        binding: Symbol,
+        expr: &Expr<'a>,
+        layout: InLayout<'a>,
+        lets: &mut Vec<Let<'a>>,
+    ) {
+        if let Some(new_expr) = (self.0)(binding, expr, layout) {
+            lets.push((binding, new_expr, layout));
+        }
+    }
+}
+
+/// Calls `f` with every let-bound expression in the given statement.
+pub(crate) fn for_each_let_expr<'a>(stmt: &Stmt<'a>, f: &mut impl FnMut(&Expr<'a>)) {
+    let mut stack = vec![stmt];
+
+    while let Some(stmt) = stack.pop() {
+        match stmt {
+            Stmt::Let(_, expr, _, continuation) => {
+                f(expr);
+                stack.push(continuation);
+            }
+            Stmt::Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                stack.push(default_branch.1);
+            }
+            Stmt::Join {
+                body, remainder, ..
+            } => {
+                stack.push(body);
+                stack.push(remainder);
+            }
+            Stmt::Refcounting(_, rest)
+            | Stmt::Expect {
+                remainder: rest, ..
+            }
+            | Stmt::ExpectFx {
+                remainder: rest, ..
+            }
+            | Stmt::Dbg {
+                remainder: rest, ..
+            } => stack.push(rest),
+            Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => {}
+        }
+    }
+}
+
+/// Every symbol which is referred to anywhere in the given statement.
+pub(crate) fn used_symbols<'a>(stmt: &'a Stmt<'a>) -> MutSet<Symbol> {
+    used_symbols_except(stmt, |_| false)
+}
+
+/// Every symbol which is referred to anywhere in the given statement,
+/// except by let-bound expressions for which `is_ignored` returns true.
+pub(crate) fn used_symbols_except<'a>(
+    stmt: &'a Stmt<'a>,
+    is_ignored: impl Fn(&Expr<'a>) -> bool,
+) -> MutSet<Symbol> {
+    let mut used = MutSet::default();
+    let mut stack = vec![stmt];
+
+    while let Some(stmt) = stack.pop() {
+        match stmt {
+            Stmt::Let(_, expr, _, continuation) => {
+                if !is_ignored(expr) {
+                    insert_expr_symbols(expr, &mut used);
+                }
+
+                stack.push(continuation);
+            }
+            Stmt::Switch {
+                cond_symbol,
+                branches,
+                default_branch,
+                ..
+            } => {
+                used.insert(*cond_symbol);
+
+                for (_, info, branch) in branches.iter() {
+                    insert_branch_info_symbols(info, &mut used);
+                    stack.push(branch);
+                }
+
+                insert_branch_info_symbols(&default_branch.0, &mut used);
+                stack.push(default_branch.1);
+            }
+            Stmt::Ret(symbol) | Stmt::Crash(symbol, _) => {
+                used.insert(*symbol);
+            }
+            Stmt::Refcounting(modify_rc, continuation) => {
+                used.insert(modify_rc.get_symbol());
+                stack.push(continuation);
+            }
+            Stmt::Expect {
+                condition,
+                lookups,
+                remainder,
+                ..
+            }
+            | Stmt::ExpectFx {
+                condition,
+                lookups,
+                remainder,
+                ..
+            } => {
+                used.insert(*condition);
+                used.extend(lookups.iter().copied());
+                stack.push(remainder);
+            }
+            Stmt::Dbg {
+                symbol, remainder, ..
+            } => {
+                used.insert(*symbol);
+                stack.push(remainder);
+            }
+            Stmt::Join {
+                body, remainder, ..
+            } => {
+                stack.push(body);
+                stack.push(remainder);
+            }
+            Stmt::Jump(_, arguments) => {
+                used.extend(arguments.iter().copied());
+            }
+        }
+    }
+
+    used
+}
+
+fn insert_branch_info_symbols(info: &BranchInfo, used: &mut MutSet<Symbol>) {
+    match info {
+        BranchInfo::None => {}
+        BranchInfo::Constructor { scrutinee, .. }
+        | BranchInfo::List { scrutinee, .. }
+        | BranchInfo::Str { scrutinee, .. } => {
+            used.insert(*scrutinee);
+        }
+    }
+}
+
+pub(crate) fn insert_expr_symbols(expr: &Expr, used: &mut MutSet<Symbol>) {
+    match expr {
+        Expr::Literal(_) | Expr::EmptyArray | Expr::NullPointer | Expr::RuntimeErrorFunction(_) => {
+        }
+        Expr::Call(call) => {
+            used.extend(call.arguments.iter().copied());
+
+            if let CallType::HigherOrder(higher_order) = &call.call_type {
+                used.insert(higher_order.passed_function.captured_environment);
+            }
+        }
+        Expr::Tag { arguments, .. } | Expr::Struct(arguments) => {
+            used.extend(arguments.iter().copied());
+        }
+        Expr::StructAtIndex { structure, .. }
+        | Expr::GetTagId { structure, .. }
+        | Expr::UnionAtIndex { structure, .. } => {
+            used.insert(*structure);
+        }
+        Expr::Array { elems, .. } | Expr::StackArray { elems, .. } => {
+            used.extend(elems.iter().filter_map(|elem| elem.to_symbol()));
+        }
+        Expr::ExprBox { symbol }
+        | Expr::ExprUnbox { symbol }
+        | Expr::StackBox { symbol }
+        | Expr::Reset { symbol, .. }
+        | Expr::ResetRef { symbol, .. } => {
+            used.insert(*symbol);
+        }
+        Expr::Reuse {
+            symbol, arguments, ..
+        } => {
+            used.insert(*symbol);
+            used.extend(arguments.iter().copied());
+        }
+    }
+}
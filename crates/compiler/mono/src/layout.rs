@@ -11,7 +11,7 @@ use roc_module::ident::{Lowercase, TagName};
 use roc_module::symbol::{Interns, Symbol};
 use roc_problem::can::RuntimeError;
 use roc_target::{PtrWidth, TargetInfo};
-use roc_types::num::NumericRange;
+use roc_types::num::{NumLiteralDefaults, NumericRange};
 use roc_types::subs::{
     self, Content, FlatType, GetSubsSlice, Label, OptVariable, RecordFields, Subs, TagExt,
     TupleElems, UnsortedUnionLabels, Variable, VariableSubsSlice,
@@ -112,6 +112,9 @@ macro_rules! inc_stat {
 #[derive(Debug)]
 pub struct LayoutCache<'a> {
     pub target_info: TargetInfo,
+    /// The types to give number literals that type checking left unresolved, as chosen by the
+    /// module whose variables this cache lays out.
+    pub num_defaults: NumLiteralDefaults,
     cache: std::vec::Vec<CacheLayer<LayoutResult<'a>>>,
     raw_function_cache: std::vec::Vec<CacheLayer<RawFunctionLayoutResult<'a>>>,
 
@@ -132,6 +135,7 @@ impl<'a> LayoutCache<'a> {
         raw_cache.push(Default::default());
         Self {
             target_info,
+            num_defaults: NumLiteralDefaults::default(),
             cache,
             raw_function_cache: raw_cache,
 
@@ -1299,6 +1303,12 @@ impl<'a> Niche<'a> {
         let NichePriv::Captures(caps) = &self.0;
         interner.dbg_deep_iter(caps)
     }
+
+    /// The layouts of the captures this niche distinguishes.
+    pub(crate) fn captures(&self) -> &'a [InLayout<'a>] {
+        let NichePriv::Captures(captures) = self.0;
+        captures
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -2398,16 +2408,21 @@ impl<'a> Layout<'a> {
                     Symbol::NUM_NUM | Symbol::NUM_INT | Symbol::NUM_INTEGER
                         if is_unresolved_var(env.subs, actual_var) =>
                     {
-                        // default to i64
-                        cacheable(Ok(Layout::default_integer()))
+                        // default to the module's integer type, i64 unless chosen otherwise
+                        cacheable(Ok(Layout::int_literal_width_to_int(
+                            env.cache.num_defaults.int,
+                            env.target_info,
+                        )))
                     }
 
                     Symbol::NUM_FRAC | Symbol::NUM_FLOATINGPOINT
                         if is_unresolved_var(env.subs, actual_var)
                             || is_any_float_range(env.subs, actual_var) =>
                     {
-                        // default to f64
-                        cacheable(Ok(Layout::default_float()))
+                        // default to the module's fraction type, f64 unless chosen otherwise
+                        cacheable(Ok(Layout::frac_literal_width_to_frac(
+                            env.cache.num_defaults.frac,
+                        )))
                     }
 
                     _ => Self::from_var(env, actual_var),
@@ -2426,7 +2441,7 @@ impl<'a> Layout<'a> {
     ) -> Cacheable<LayoutResult<'a>> {
         // We don't pass the range down because `RangedNumber`s are somewhat rare, they only
         // appear due to number literals, so no need to increase parameter list sizes.
-        let num_layout = range.default_compilation_width();
+        let num_layout = range.compilation_width_preferring(env.cache.num_defaults.int);
 
         cacheable(Ok(Layout::int_literal_width_to_int(
             num_layout,
@@ -2852,6 +2867,15 @@ impl<'a> Layout<'a> {
         Layout::F64
     }
 
+    pub fn frac_literal_width_to_frac(width: roc_types::num::FloatWidth) -> InLayout<'a> {
+        use roc_types::num::FloatWidth::*;
+        match width {
+            Dec => Layout::DEC,
+            F32 => Layout::F32,
+            F64 => Layout::F64,
+        }
+    }
+
     pub fn int_literal_width_to_int(
         width: roc_types::num::IntLitWidth,
         target_info: TargetInfo,
@@ -3126,7 +3150,7 @@ fn layout_from_flat_type<'a>(
                     let var = args[0];
                     let content = subs.get_content_without_compacting(var);
 
-                    layout_from_num_content(content, target_info)
+                    layout_from_num_content(content, target_info, env.cache.num_defaults)
                 }
 
                 Symbol::STR_STR => cacheable(Ok(Layout::STR)),
@@ -4308,6 +4332,7 @@ pub fn ext_var_is_empty_tag_union(_: &Subs, _: TagExt) -> bool {
 fn layout_from_num_content<'a>(
     content: &Content,
     target_info: TargetInfo,
+    num_defaults: NumLiteralDefaults,
 ) -> Cacheable<LayoutResult<'a>> {
     use roc_types::subs::Content::*;
     use roc_types::subs::FlatType::*;
@@ -4316,10 +4341,14 @@ fn layout_from_num_content<'a>(
         RecursionVar { .. } => panic!("recursion var in num"),
         FlexVar(_) | RigidVar(_) => {
             // If a Num makes it all the way through type checking with an unbound
-            // type variable, then assume it's a 64-bit integer.
+            // type variable, then assume it's the module's default integer, a 64-bit integer
+            // unless the module chose otherwise.
             //
             // (e.g. for (5 + 5) assume both 5s are 64-bit integers.)
-            Ok(Layout::default_integer())
+            Ok(Layout::int_literal_width_to_int(
+                num_defaults.int,
+                target_info,
+            ))
         }
         FlexAbleVar(_, _) | RigidAbleVar(_, _) => todo_abilities!("Not reachable yet"),
         Structure(Apply(symbol, args)) => match *symbol {
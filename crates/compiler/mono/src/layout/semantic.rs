@@ -31,6 +31,14 @@ impl<'a> SemanticRepr<'a> {
     pub(super) fn tuple(size: usize) -> Self {
         Self(Inner::Tuple(SemaTuple { size }))
     }
+
+    /// The names of the fields of a record, in the order of the fields of its layout
+    pub fn record_fields(&self) -> Option<&'a [&'a str]> {
+        match self.0 {
+            Inner::Record(SemaRecord { fields }) => Some(fields),
+            Inner::None | Inner::Tuple(_) => None,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -9,15 +9,28 @@
 // Not a useful lint for us
 #![allow(clippy::too_many_arguments)]
 
+pub mod arena_allocation;
 pub mod borrow;
+pub mod bounds_checks;
 pub mod code_gen_help;
+pub mod constant_folding;
 pub mod drop_specialization;
+pub mod effect_interpreters;
+pub mod escape_analysis;
+pub mod flatten_closures;
 pub mod inc_dec;
+pub mod initialization;
 pub mod ir;
+mod ir_walk;
 pub mod layout;
 pub mod layout_soa;
 pub mod low_level;
+pub mod mangle;
+pub mod overflow;
+pub mod pure_calls;
+pub mod rc_scheduling;
 pub mod reset_reuse;
+pub mod static_data;
 pub mod tail_recursion;
 
 pub mod debug;
@@ -0,0 +1,376 @@
+//! Stable names for the specializations of procedures in object files.
+//!
+//! A specialization of `def` from `Module` is named `Module_def_0123456789abcdef`, where the last
+//! part is a hash of the layouts the procedure was specialized for. The hash is computed from the
+//! structure of those layouts, rather than from their ids in the layout interner, and with a hash
+//! function that isn't seeded randomly. That makes the name of a specialization the same in every
+//! build of a program, which is what profilers need to match up the functions of two runs.
+//!
+//! Module names may contain underscores, so those are doubled, and the first lone underscore is
+//! where the module name ends.
+//!
+//! [demangle] goes the other way, and is what `roc demangle` uses.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use roc_module::symbol::{Interns, Symbol};
+
+use crate::layout::{
+    Builtin, InLayout, LambdaSet, Layout, LayoutInterner, LayoutRepr, Niche, UnionLayout,
+};
+
+/// The name of the specialization of `symbol` whose layout hashes to `layout_hash`.
+pub fn specialization_name(interns: &Interns, symbol: Symbol, layout_hash: u64) -> String {
+    let ident_string = symbol.as_str(interns);
+    let module_string = module_name(interns, symbol).replace('_', "__");
+    format!("{}_{}_{:016x}", module_string, ident_string, layout_hash)
+}
+
+/// The hash of a layout which [specialization_name] expects.
+pub fn layout_hash<'a, I>(interner: &I, interns: &Interns, layout: InLayout<'a>) -> u64
+where
+    I: LayoutInterner<'a>,
+{
+    let mut hasher = LayoutHasher::new(interner, interns);
+    hasher.layout(layout);
+    hasher.state.finish()
+}
+
+/// The hash of the layout of a procedure which [specialization_name] expects.
+pub fn proc_layout_hash<'a, I, A>(
+    interner: &I,
+    interns: &Interns,
+    arguments: A,
+    niche: Niche<'a>,
+    result: InLayout<'a>,
+) -> u64
+where
+    I: LayoutInterner<'a>,
+    A: IntoIterator<Item = InLayout<'a>>,
+{
+    let mut hasher = LayoutHasher::new(interner, interns);
+
+    let mut argument_count = 0;
+    for argument in arguments {
+        hasher.layout(argument);
+        argument_count += 1;
+    }
+    hasher.state.write_u64(argument_count);
+
+    hasher.layouts(niche.captures());
+
+    hasher.layout(result);
+    hasher.state.finish()
+}
+
+/// The parts of a name made by [specialization_name].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Demangled<'n> {
+    pub module: Cow<'n, str>,
+    pub def: &'n str,
+    pub layout_hash: u64,
+    /// Whatever a backend appended to the name, e.g. to tell apart the in-place versions of a
+    /// procedure which the LLVM backend generates.
+    pub suffix: Option<&'n str>,
+}
+
+impl fmt::Display for Demangled<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{} [{:016x}]",
+            self.module, self.def, self.layout_hash
+        )?;
+
+        if let Some(suffix) = self.suffix {
+            write!(f, " ({})", suffix)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Split a name made by [specialization_name] into its parts, or return `None` if it isn't one.
+///
+/// The module is everything up to the first underscore which isn't doubled.
+pub fn demangle(name: &str) -> Option<Demangled<'_>> {
+    let (module, rest) = split_module(name)?;
+    let (def, hash_and_suffix) = rest.rsplit_once('_')?;
+
+    if module.is_empty() || def.is_empty() || hash_and_suffix.len() < 16 {
+        return None;
+    }
+
+    let (hash, suffix) = hash_and_suffix.split_at(16);
+    let suffix = match suffix.strip_prefix('.') {
+        Some(suffix) if !suffix.is_empty() => Some(suffix),
+        None if suffix.is_empty() => None,
+        None => return None,
+    };
+
+    if !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let module = if module.contains("__") {
+        Cow::Owned(module.replace("__", "_"))
+    } else {
+        Cow::Borrowed(module)
+    };
+
+    Some(Demangled {
+        module,
+        def,
+        layout_hash: u64::from_str_radix(hash, 16).ok()?,
+        suffix,
+    })
+}
+
+/// Split the name at the underscore after the module name, where [specialization_name] has
+/// doubled the underscores of the module name itself.
+fn split_module(name: &str) -> Option<(&str, &str)> {
+    let bytes = name.as_bytes();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'_' {
+            if bytes.get(index + 1) == Some(&b'_') {
+                index += 2;
+                continue;
+            }
+
+            return Some((&name[..index], &name[index + 1..]));
+        }
+
+        index += 1;
+    }
+
+    None
+}
+
+/// 64-bit FNV-1a, which, unlike the hashers of our hash maps, gives the same hash in every run.
+struct StableHasher(u64);
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+struct LayoutHasher<'a, 'r, I> {
+    interner: &'r I,
+    interns: &'r Interns,
+    state: StableHasher,
+    /// The recursive unions we are in, innermost last, so that a recursion pointer can be
+    /// hashed as the number of unions between it and the union it points to.
+    recursive_unions: Vec<InLayout<'a>>,
+}
+
+impl<'a, 'r, I: LayoutInterner<'a>> LayoutHasher<'a, 'r, I> {
+    fn new(interner: &'r I, interns: &'r Interns) -> Self {
+        Self {
+            interner,
+            interns,
+            state: StableHasher(0xcbf2_9ce4_8422_2325),
+            recursive_unions: Vec::new(),
+        }
+    }
+
+    fn layouts(&mut self, layouts: &[InLayout<'a>]) {
+        self.state.write_u64(layouts.len() as u64);
+
+        for layout in layouts {
+            self.layout(*layout);
+        }
+    }
+
+    fn symbol(&mut self, symbol: Symbol) {
+        let module_string = module_name(self.interns, symbol);
+        self.state.write(module_string.as_bytes());
+        self.state.write_u8(0xff);
+        self.state.write(symbol.as_str(self.interns).as_bytes());
+        self.state.write_u8(0xff);
+    }
+
+    fn layout(&mut self, layout: InLayout<'a>) {
+        let Layout { repr, semantic } = self.interner.get(layout);
+
+        semantic.hash(&mut self.state);
+
+        match repr {
+            LayoutRepr::Builtin(builtin) => {
+                self.state.write_u8(0);
+
+                match builtin {
+                    Builtin::Int(width) => {
+                        self.state.write_u8(0);
+                        self.state.write_u8(width as u8);
+                    }
+                    Builtin::Float(width) => {
+                        self.state.write_u8(1);
+                        self.state.write_u8(width as u8);
+                    }
+                    Builtin::Bool => self.state.write_u8(2),
+                    Builtin::Decimal => self.state.write_u8(3),
+                    Builtin::Str => self.state.write_u8(4),
+                    Builtin::List(element) => {
+                        self.state.write_u8(5);
+                        self.layout(element);
+                    }
+                }
+            }
+            LayoutRepr::Struct { field_layouts } => {
+                self.state.write_u8(1);
+                self.layouts(field_layouts);
+            }
+            LayoutRepr::Boxed(inner) => {
+                self.state.write_u8(2);
+                self.layout(inner);
+            }
+            LayoutRepr::Union(union_layout) => {
+                self.state.write_u8(3);
+
+                let is_recursive = !matches!(union_layout, UnionLayout::NonRecursive(_));
+                if is_recursive {
+                    self.recursive_unions.push(layout);
+                }
+
+                self.union(union_layout);
+
+                if is_recursive {
+                    self.recursive_unions.pop();
+                }
+            }
+            LayoutRepr::LambdaSet(lambda_set) => {
+                self.state.write_u8(4);
+                self.lambda_set(lambda_set);
+            }
+            LayoutRepr::RecursivePointer(target) => {
+                // Following the pointer would never end.
+                self.state.write_u8(5);
+
+                let depth = self
+                    .recursive_unions
+                    .iter()
+                    .rev()
+                    .position(|union| *union == target)
+                    .map_or(u64::MAX, |depth| depth as u64);
+                self.state.write_u64(depth);
+            }
+        }
+    }
+
+    fn union(&mut self, union_layout: UnionLayout<'a>) {
+        match union_layout {
+            UnionLayout::NonRecursive(tags) => {
+                self.state.write_u8(0);
+                self.tags(tags);
+            }
+            UnionLayout::Recursive(tags) => {
+                self.state.write_u8(1);
+                self.tags(tags);
+            }
+            UnionLayout::NonNullableUnwrapped(fields) => {
+                self.state.write_u8(2);
+                self.layouts(fields);
+            }
+            UnionLayout::NullableWrapped {
+                nullable_id,
+                other_tags,
+            } => {
+                self.state.write_u8(3);
+                self.state.write_u16(nullable_id);
+                self.tags(other_tags);
+            }
+            UnionLayout::NullableUnwrapped {
+                nullable_id,
+                other_fields,
+            } => {
+                self.state.write_u8(4);
+                self.state.write_u8(nullable_id as u8);
+                self.layouts(other_fields);
+            }
+        }
+    }
+
+    fn tags(&mut self, tags: &[&[InLayout<'a>]]) {
+        self.state.write_u64(tags.len() as u64);
+
+        for fields in tags {
+            self.layouts(fields);
+        }
+    }
+
+    fn lambda_set(&mut self, lambda_set: LambdaSet<'a>) {
+        self.state.write_u64(lambda_set.set.len() as u64);
+
+        for (symbol, captures) in lambda_set.set.iter() {
+            self.symbol(*symbol);
+            self.layouts(captures);
+        }
+
+        self.layout(lambda_set.representation);
+    }
+}
+
+fn module_name(interns: &Interns, symbol: Symbol) -> &str {
+    interns.module_ids.get_name(symbol.module_id()).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::demangle;
+
+    #[test]
+    fn demangle_specialization_name() {
+        let demangled = demangle("List_walk_00c0ffee00c0ffee").unwrap();
+
+        assert_eq!(demangled.module, "List");
+        assert_eq!(demangled.def, "walk");
+        assert_eq!(demangled.layout_hash, 0x00c0_ffee_00c0_ffee);
+        assert_eq!(demangled.suffix, None);
+        assert_eq!(demangled.to_string(), "List.walk [00c0ffee00c0ffee]");
+    }
+
+    #[test]
+    fn demangle_keeps_suffix() {
+        let demangled = demangle("pf.Task_await_00000000000000ff.inplace1").unwrap();
+
+        assert_eq!(demangled.module, "pf.Task");
+        assert_eq!(demangled.def, "await");
+        assert_eq!(demangled.suffix, Some("inplace1"));
+    }
+
+    #[test]
+    fn demangle_module_with_underscores() {
+        let demangled = demangle("pf.Http__Client___walk_00000000000000ff").unwrap();
+
+        assert_eq!(demangled.module, "pf.Http_Client_");
+        assert_eq!(demangled.def, "walk");
+        assert_eq!(
+            demangled.to_string(),
+            "pf.Http_Client_.walk [00000000000000ff]"
+        );
+
+        let demangled = demangle("Http__Client_parse_url_00000000000000ff").unwrap();
+
+        assert_eq!(demangled.module, "Http_Client");
+        assert_eq!(demangled.def, "parse_url");
+    }
+
+    #[test]
+    fn demangle_rejects_other_names() {
+        assert_eq!(demangle("roc__mainForHost_1_exposed"), None);
+        assert_eq!(demangle("List_walk_1"), None);
+        assert_eq!(demangle("memcpy"), None);
+    }
+}
@@ -0,0 +1,121 @@
+//! Choose what `+`, `-` and `*` on integers do when the result doesn't fit. By default they crash
+//! (trap), like `Num.add` is documented to; a build can ask for them to wrap around like
+//! `Num.addWrap`, or to saturate like `Num.addSaturated`, instead. This replaces the operations
+//! with the lowlevel for the requested behavior, so every backend gets it without knowing about
+//! the setting. Operations written out explicitly, like `Num.addChecked` or `Num.mulWrap`, keep
+//! their own behavior.
+//!
+//! Only integers are affected. Floats never overflow (they become infinite), and `Dec` always
+//! crashes on overflow.
+//!
+//! What each backend does for integers, in each mode:
+//!
+//! - **LLVM**: trap crashes through `roc_panic`; wrap uses plain machine arithmetic; saturate uses
+//!   the LLVM saturating intrinsics, or a Zig builtin for multiplication.
+//! - **wasm**: trap crashes through `roc_panic`; wrap uses wasm arithmetic, or Zig builtins for
+//!   128-bit integers; saturate uses Zig builtins.
+//! - **dev** (x86-64 and aarch64): doesn't check for overflow yet, so trap wraps too; wrap uses
+//!   plain machine arithmetic; saturate uses Zig builtins.
+//!
+//! This runs before constant folding, so constants are folded with the requested behavior too.
+
+use bumpalo::Bump;
+use roc_collections::MutMap;
+use roc_module::low_level::{LowLevel, LowLevelWrapperType};
+use roc_module::symbol::Symbol;
+
+use crate::ir::{Call, CallType, Expr, Proc, ProcLayout, UpdateModeId};
+use crate::ir_walk::map_lets;
+use crate::layout::{Builtin, InLayout, LayoutInterner, LayoutRepr, STLayoutInterner};
+
+/// What `+`, `-` and `*` on integers do when the result doesn't fit in the integer type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowMode {
+    /// Crash, which is what the language specifies
+    #[default]
+    Trap,
+    /// Wrap around, like `Num.addWrap`
+    Wrap,
+    /// Clamp to the smallest or largest value of the type, like `Num.addSaturated`
+    Saturate,
+}
+
+impl OverflowMode {
+    /// The lowlevel to use instead of `op`, when it is applied to integers
+    fn lower(self, op: LowLevel) -> Option<LowLevel> {
+        use LowLevel::*;
+
+        match (self, op) {
+            (OverflowMode::Trap, _) => None,
+            (OverflowMode::Wrap, NumAdd) => Some(NumAddWrap),
+            (OverflowMode::Wrap, NumSub) => Some(NumSubWrap),
+            (OverflowMode::Wrap, NumMul) => Some(NumMulWrap),
+            (OverflowMode::Saturate, NumAdd) => Some(NumAddSaturated),
+            (OverflowMode::Saturate, NumSub) => Some(NumSubSaturated),
+            (OverflowMode::Saturate, NumMul) => Some(NumMulSaturated),
+            _ => None,
+        }
+    }
+}
+
+pub fn lower_overflowing_arithmetic<'a, 'i>(
+    arena: &'a Bump,
+    layout_interner: &'i STLayoutInterner<'a>,
+    mode: OverflowMode,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    if mode == OverflowMode::Trap {
+        return;
+    }
+
+    for proc in procs.values_mut() {
+        let new_body = map_lets(
+            arena,
+            arena.alloc(proc.body.clone()),
+            &mut |_symbol, expr, layout| {
+                Some(
+                    lower_expr(layout_interner, mode, expr, layout).unwrap_or_else(|| expr.clone()),
+                )
+            },
+        );
+
+        proc.body = new_body.clone();
+    }
+}
+
+fn lower_expr<'a>(
+    layout_interner: &STLayoutInterner<'a>,
+    mode: OverflowMode,
+    expr: &Expr<'a>,
+    layout: InLayout<'a>,
+) -> Option<Expr<'a>> {
+    let (op, arguments) = match expr {
+        Expr::Call(Call {
+            call_type: CallType::LowLevel { op, .. },
+            arguments,
+        }) => (*op, *arguments),
+        Expr::Call(Call {
+            call_type: CallType::ByName { name, .. },
+            arguments,
+        }) => match LowLevelWrapperType::from_symbol(name.name()) {
+            LowLevelWrapperType::CanBeReplacedBy(op) => (op, *arguments),
+            LowLevelWrapperType::NotALowLevelWrapper => return None,
+        },
+        _ => return None,
+    };
+
+    match layout_interner.get(layout).repr {
+        LayoutRepr::Builtin(Builtin::Int(_)) => {}
+        _ => return None,
+    }
+
+    let op = mode.lower(op)?;
+
+    Some(Expr::Call(Call {
+        call_type: CallType::LowLevel {
+            op,
+            update_mode: UpdateModeId::BACKEND_DUMMY,
+        },
+        arguments,
+    }))
+}
@@ -0,0 +1,430 @@
+//! Reuse the results of calls of `Pure` host functions. A host intrinsic whose result is
+//! wrapped in `Pure` in the hosted module, like `sqrtApprox : F32 -> Pure F32`, gives the same
+//! result for the same arguments, so
+//!
+//! ```text
+//! let x = foreign "roc_fx_sqrtApprox" a;
+//! let y = foreign "roc_fx_sqrtApprox" a;
+//! ```
+//!
+//! can become just the first let, with `y` replaced by `x`. Roc code doesn't call the host
+//! function directly, but the `sqrtApprox` procedure of the hosted module, which only passes its
+//! arguments on; a call of such a procedure counts as a call of the host function. An earlier call
+//! is only reused where it is sure to have happened: after it in the same block, in the branches
+//! of a switch after it, and in join points defined after it.
+//!
+//! This runs before refcounting operations are inserted, so the reused result gets the
+//! increments it needs like any other value that is used more than once.
+
+use bumpalo::Bump;
+use roc_collections::all::{BumpMap, BumpMapDefault};
+use roc_collections::MutMap;
+use roc_module::ident::ForeignSymbol;
+use roc_module::symbol::Symbol;
+
+use crate::ir::{substitute_in_exprs_many, Call, CallType, Expr, Proc, ProcLayout, Stmt};
+use crate::ir_walk::{rewrite_stmt, Let, Rewriter};
+use crate::layout::InLayout;
+
+/// A call of a `Pure` host function: the function, its arguments and its result's layout
+type PureCall<'a> = (ForeignSymbol, Vec<Symbol>, InLayout<'a>);
+
+/// The procedures which only pass their arguments on to a `Pure` host function, and the host
+/// function each one calls
+type PureWrappers<'a> = MutMap<(Symbol, ProcLayout<'a>), ForeignSymbol>;
+
+pub fn reuse_pure_calls<'a>(
+    arena: &'a Bump,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    let wrappers = pure_wrappers(procs);
+
+    for proc in procs.values_mut() {
+        let mut reuse = ReusePureCalls {
+            wrappers: &wrappers,
+            replaced: BumpMap::new_in(arena),
+        };
+
+        let body = arena.alloc(proc.body.clone());
+        let new_body = rewrite_stmt(arena, &mut reuse, &mut MutMap::default(), body);
+
+        if !reuse.replaced.is_empty() {
+            let mut new_body = new_body.clone();
+            substitute_in_exprs_many(arena, &mut new_body, reuse.replaced);
+
+            proc.body = new_body;
+        }
+    }
+}
+
+/// Like the `sqrtApprox` of a hosted module, whose body is just
+///
+/// ```text
+/// let y = foreign "roc_fx_sqrtApprox" x;
+/// ret y;
+/// ```
+fn pure_wrappers<'a>(procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>) -> PureWrappers<'a> {
+    procs
+        .iter()
+        .filter_map(|(key, proc)| match &proc.body {
+            Stmt::Let(
+                result,
+                Expr::Call(Call {
+                    call_type:
+                        CallType::Foreign {
+                            foreign_symbol,
+                            pure: true,
+                            ..
+                        },
+                    arguments,
+                }),
+                _,
+                Stmt::Ret(returned),
+            ) if result == returned
+                && arguments.iter().eq(proc.args.iter().map(|(_, arg)| arg)) =>
+            {
+                Some((*key, foreign_symbol.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Removes the lets which repeat a call that is available in the scope, recording which symbol
+/// each removed binding is replaced by.
+struct ReusePureCalls<'w, 'a> {
+    wrappers: &'w PureWrappers<'a>,
+    replaced: BumpMap<Symbol, Symbol>,
+}
+
+impl<'a> Rewriter<'a> for ReusePureCalls<'_, 'a> {
+    type Scope = MutMap<PureCall<'a>, Symbol>;
+
+    fn rewrite_let(
+        &mut self,
+        available: &mut Self::Scope,
+        binding: Symbol,
+        expr: &Expr<'a>,
+        layout: InLayout<'a>,
+        lets: &mut Vec<Let<'a>>,
+    ) {
+        match pure_call(expr, self.wrappers, &self.replaced) {
+            Some(call) => match available.get(&call) {
+                Some(earlier) => {
+                    self.replaced.insert(binding, *earlier);
+                }
+                None => {
+                    available.insert(call, binding);
+                    lets.push((binding, expr.clone(), layout));
+                }
+            },
+            None => lets.push((binding, expr.clone(), layout)),
+        }
+    }
+}
+
+/// The call this expression makes, if it calls a `Pure` host function, directly or through a
+/// wrapper. Arguments which are replaced by an earlier symbol are given as that symbol, so that
+/// repeated calls are found even if their arguments were repeated calls themselves.
+fn pure_call<'a>(
+    expr: &Expr<'a>,
+    wrappers: &PureWrappers<'a>,
+    replaced: &BumpMap<Symbol, Symbol>,
+) -> Option<PureCall<'a>> {
+    let (call_type, arguments) = match expr {
+        Expr::Call(Call {
+            call_type,
+            arguments,
+        }) => (call_type, arguments),
+        _ => return None,
+    };
+
+    let (foreign_symbol, ret_layout) = match call_type {
+        CallType::Foreign {
+            foreign_symbol,
+            ret_layout,
+            pure: true,
+            ..
+        } => (foreign_symbol.clone(), *ret_layout),
+        CallType::ByName {
+            name,
+            ret_layout,
+            arg_layouts,
+            ..
+        } => {
+            let proc_layout = ProcLayout {
+                arguments: arg_layouts,
+                result: *ret_layout,
+                niche: name.niche(),
+            };
+
+            let foreign_symbol = wrappers.get(&(name.name(), proc_layout))?;
+
+            (foreign_symbol.clone(), *ret_layout)
+        }
+        _ => return None,
+    };
+
+    let arguments = arguments
+        .iter()
+        .map(|argument| *replaced.get(argument).unwrap_or(argument))
+        .collect();
+
+    Some((foreign_symbol, arguments, ret_layout))
+}
+
+#[cfg(test)]
+mod test {
+    use bumpalo::Bump;
+    use roc_collections::MutMap;
+    use roc_module::low_level::LowLevel;
+    use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+    use roc_target::TargetInfo;
+
+    use crate::borrow::Ownership;
+    use crate::ir::{
+        Call, CallSpecId, CallType, CrashTag, Expr, HostExposedLayouts, Literal, Proc, ProcLayout,
+        SelfRecursive, Stmt, UpdateModeId,
+    };
+    use crate::layout::{LambdaName, Layout, Niche, STLayoutInterner};
+
+    use super::reuse_pure_calls;
+
+    const TARGET_INFO: TargetInfo = TargetInfo::default_x86_64();
+
+    /// Every procedure in these tests takes an `F64` and returns one
+    const PROC_LAYOUT: ProcLayout<'static> = ProcLayout {
+        arguments: &[Layout::F64],
+        result: Layout::F64,
+        niche: Niche::NONE,
+    };
+
+    fn symbol(ident_ids: &mut IdentIds, name: &str) -> Symbol {
+        Symbol::new(ModuleId::ATTR, ident_ids.add_str(name))
+    }
+
+    fn foreign<'a>(arena: &'a Bump, name: &str, pure: bool, argument: Symbol) -> Expr<'a> {
+        Expr::Call(Call {
+            call_type: CallType::Foreign {
+                foreign_symbol: name.into(),
+                ret_layout: Layout::F64,
+                arg_ownership: &[Ownership::Borrowed],
+                ret_ownership: Ownership::Owned,
+                pure,
+            },
+            arguments: arena.alloc([argument]),
+        })
+    }
+
+    fn call_by_name<'a>(arena: &'a Bump, name: Symbol, argument: Symbol) -> Expr<'a> {
+        Expr::Call(Call {
+            call_type: CallType::ByName {
+                name: LambdaName::no_niche(name),
+                ret_layout: Layout::F64,
+                arg_layouts: &[Layout::F64],
+                specialization_id: CallSpecId::BACKEND_DUMMY,
+            },
+            arguments: arena.alloc([argument]),
+        })
+    }
+
+    fn add<'a>(arena: &'a Bump, a: Symbol, b: Symbol) -> Expr<'a> {
+        Expr::Call(Call {
+            call_type: CallType::LowLevel {
+                op: LowLevel::NumAdd,
+                update_mode: UpdateModeId::BACKEND_DUMMY,
+            },
+            arguments: arena.alloc([a, b]),
+        })
+    }
+
+    /// The lets, in order, returning the last one
+    fn lets<'a>(arena: &'a Bump, lets: Vec<(Symbol, Expr<'a>)>) -> Stmt<'a> {
+        let returned = lets.last().unwrap().0;
+
+        lets.into_iter()
+            .rev()
+            .fold(Stmt::Ret(returned), |continuation, (symbol, expr)| {
+                Stmt::Let(symbol, expr, Layout::F64, arena.alloc(continuation))
+            })
+    }
+
+    fn proc<'a>(arena: &'a Bump, name: Symbol, argument: Symbol, body: Stmt<'a>) -> Proc<'a> {
+        Proc {
+            name: LambdaName::no_niche(name),
+            args: arena.alloc([(Layout::F64, argument)]),
+            body,
+            closure_data_layout: None,
+            ret_layout: Layout::F64,
+            is_self_recursive: SelfRecursive::NotSelfRecursive,
+            host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+        }
+    }
+
+    /// Reuses the pure calls of `main`, next to the procedures it calls, and compares the IR it
+    /// ends up with to `expected`.
+    fn assert_reuses<'a>(
+        arena: &'a Bump,
+        main: Proc<'a>,
+        callees: Vec<Proc<'a>>,
+        expected: Stmt<'a>,
+    ) {
+        let main_symbol = main.name.name();
+        let mut procs: MutMap<_, _> = std::iter::once(main)
+            .chain(callees)
+            .map(|proc| ((proc.name.name(), PROC_LAYOUT), proc))
+            .collect();
+
+        reuse_pure_calls(arena, &mut procs);
+
+        let interner = STLayoutInterner::with_capacity(4, TARGET_INFO);
+        let actual = &procs[&(main_symbol, PROC_LAYOUT)].body;
+
+        assert_eq!(
+            actual.to_pretty(&interner, 200, false),
+            expected.to_pretty(&interner, 200, false)
+        );
+    }
+
+    #[test]
+    fn repeated_pure_call_is_reused() {
+        let arena = Bump::new();
+        let mut ident_ids = IdentIds::default();
+        let [main, x, a, b, sum] =
+            ["main", "x", "a", "b", "sum"].map(|name| symbol(&mut ident_ids, name));
+
+        let body = lets(
+            &arena,
+            vec![
+                (a, foreign(&arena, "roc_fx_sqrtApprox", true, x)),
+                (b, foreign(&arena, "roc_fx_sqrtApprox", true, x)),
+                (sum, add(&arena, a, b)),
+            ],
+        );
+        let expected = lets(
+            &arena,
+            vec![
+                (a, foreign(&arena, "roc_fx_sqrtApprox", true, x)),
+                (sum, add(&arena, a, a)),
+            ],
+        );
+
+        assert_reuses(&arena, proc(&arena, main, x, body), vec![], expected);
+    }
+
+    #[test]
+    fn repeated_call_of_a_pure_wrapper_is_reused() {
+        // the hosted module's `sqrtApprox`, which Roc code calls instead of the host function
+        let arena = Bump::new();
+        let mut ident_ids = IdentIds::default();
+        let [main, sqrt_approx, y, result, x, a, b, sum] =
+            ["main", "sqrtApprox", "y", "result", "x", "a", "b", "sum"]
+                .map(|name| symbol(&mut ident_ids, name));
+
+        let wrapper_body = lets(
+            &arena,
+            vec![(result, foreign(&arena, "roc_fx_sqrtApprox", true, y))],
+        );
+        let wrapper = proc(&arena, sqrt_approx, y, wrapper_body);
+
+        let body = lets(
+            &arena,
+            vec![
+                (a, call_by_name(&arena, sqrt_approx, x)),
+                (b, call_by_name(&arena, sqrt_approx, x)),
+                (sum, add(&arena, a, b)),
+            ],
+        );
+        let expected = lets(
+            &arena,
+            vec![
+                (a, call_by_name(&arena, sqrt_approx, x)),
+                (sum, add(&arena, a, a)),
+            ],
+        );
+
+        assert_reuses(&arena, proc(&arena, main, x, body), vec![wrapper], expected);
+    }
+
+    #[test]
+    fn effectful_calls_are_not_merged() {
+        // a host function without `Pure` can give a different result every time, whether it is
+        // called directly or through its wrapper
+        let arena = Bump::new();
+        let mut ident_ids = IdentIds::default();
+        let [main, read_sensor, y, result, x, a, b, c, d, sum, total] = [
+            "main",
+            "readSensor",
+            "y",
+            "result",
+            "x",
+            "a",
+            "b",
+            "c",
+            "d",
+            "sum",
+            "total",
+        ]
+        .map(|name| symbol(&mut ident_ids, name));
+
+        let wrapper_body = lets(
+            &arena,
+            vec![(result, foreign(&arena, "roc_fx_readSensor", false, y))],
+        );
+        let wrapper = proc(&arena, read_sensor, y, wrapper_body);
+
+        let body = lets(
+            &arena,
+            vec![
+                (a, foreign(&arena, "roc_fx_readSensor", false, x)),
+                (b, foreign(&arena, "roc_fx_readSensor", false, x)),
+                (c, call_by_name(&arena, read_sensor, x)),
+                (d, call_by_name(&arena, read_sensor, x)),
+                (sum, add(&arena, a, b)),
+                (total, add(&arena, c, d)),
+            ],
+        );
+
+        assert_reuses(
+            &arena,
+            proc(&arena, main, x, body.clone()),
+            vec![wrapper],
+            body,
+        );
+    }
+
+    #[test]
+    fn crashing_calls_are_not_merged() {
+        // calling a Roc function that crashes isn't a call of a host function, so every call of
+        // it has to be made
+        let arena = Bump::new();
+        let mut ident_ids = IdentIds::default();
+        let [main, check, y, message, x, a, b, sum] =
+            ["main", "check", "y", "message", "x", "a", "b", "sum"]
+                .map(|name| symbol(&mut ident_ids, name));
+
+        let check_body = Stmt::Let(
+            message,
+            Expr::Literal(Literal::Str("not a number")),
+            Layout::STR,
+            arena.alloc(Stmt::Crash(message, CrashTag::User)),
+        );
+        let check_proc = proc(&arena, check, y, check_body);
+
+        let body = lets(
+            &arena,
+            vec![
+                (a, call_by_name(&arena, check, x)),
+                (b, call_by_name(&arena, check, x)),
+                (sum, add(&arena, a, b)),
+            ],
+        );
+
+        assert_reuses(
+            &arena,
+            proc(&arena, main, x, body.clone()),
+            vec![check_proc],
+            body,
+        );
+    }
+}
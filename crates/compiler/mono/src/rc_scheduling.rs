@@ -0,0 +1,647 @@
+//! Move refcounting operations as close as possible to the code which needs them.
+//!
+//! A `dec` which sits after some unrelated lets keeps its value (and everything it points to)
+//! alive for longer than necessary, so decrements move up to just after the last use of the
+//! value. An `inc` only has to happen before the value is shared, so increments move down to just
+//! before the first let which uses the value.
+//!
+//! The pass is opt-in (`--schedule-refcounts`), and runs on the finished IR, after drop
+//! specialization. Drop specialization cancels an `inc` of a child against the `dec` of its
+//! parent when nothing in between them can observe the refcounts; running this pass first would
+//! move the pair apart and stop that from happening.
+//!
+//! Operations never move across anything other than lets: switches, join points and jumps are
+//! left alone, and so are other refcounting operations, so their relative order is unchanged.
+
+use bumpalo::collections::CollectIn;
+use bumpalo::Bump;
+use roc_collections::{MutMap, MutSet};
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::Symbol;
+
+use crate::ir::{Call, CallType, Expr, ModifyRc, Proc, ProcLayout, Stmt};
+use crate::ir_walk::insert_expr_symbols;
+use crate::layout::InLayout;
+
+pub fn schedule_refcounts<'a>(
+    arena: &'a Bump,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    for proc in procs.values_mut() {
+        let mut parents = MutMap::default();
+        insert_parents(&proc.body, &mut parents);
+
+        let new_body = schedule_stmt(arena, &parents, arena.alloc(proc.body.clone()));
+
+        proc.body = new_body.clone();
+    }
+}
+
+/// Record which symbols point into which other symbols, so that a parent isn't
+/// decremented before the last use of one of its children.
+fn insert_parents(stmt: &Stmt, parents: &mut MutMap<Symbol, Symbol>) {
+    let mut current_stmt = stmt;
+
+    loop {
+        match current_stmt {
+            Stmt::Let(binding, expr, _, continuation) => {
+                if let Some(parent) = parent_of(expr) {
+                    parents.insert(*binding, parent);
+                }
+
+                current_stmt = continuation;
+            }
+            Stmt::Refcounting(_, continuation) => current_stmt = continuation,
+            Stmt::Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                for (_, _, branch) in branches.iter() {
+                    insert_parents(branch, parents);
+                }
+
+                current_stmt = default_branch.1;
+            }
+            Stmt::Expect { remainder, .. }
+            | Stmt::ExpectFx { remainder, .. }
+            | Stmt::Dbg { remainder, .. } => current_stmt = remainder,
+            Stmt::Join {
+                body, remainder, ..
+            } => {
+                insert_parents(body, parents);
+
+                current_stmt = remainder;
+            }
+            Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => return,
+        }
+    }
+}
+
+fn parent_of(expr: &Expr) -> Option<Symbol> {
+    match expr {
+        Expr::StructAtIndex { structure, .. } | Expr::UnionAtIndex { structure, .. } => {
+            Some(*structure)
+        }
+        Expr::ExprUnbox { symbol } => Some(*symbol),
+        Expr::Call(Call {
+            call_type:
+                CallType::LowLevel {
+                    op: LowLevel::ListGetUnsafe,
+                    ..
+                },
+            arguments,
+        }) => arguments.first().copied(),
+        _ => None,
+    }
+}
+
+/// Whether `symbol` is `ancestor`, or was indexed out of it (possibly through other children).
+fn is_derived_from(parents: &MutMap<Symbol, Symbol>, symbol: Symbol, ancestor: Symbol) -> bool {
+    let mut current = symbol;
+
+    loop {
+        if current == ancestor {
+            return true;
+        }
+
+        match parents.get(&current) {
+            Some(parent) => current = *parent,
+            None => return false,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Scheduled<'a> {
+    Let(Symbol, &'a Expr<'a>, InLayout<'a>),
+    Refcounting(ModifyRc),
+}
+
+fn schedule_stmt<'a>(
+    arena: &'a Bump,
+    parents: &MutMap<Symbol, Symbol>,
+    stmt: &'a Stmt<'a>,
+) -> &'a Stmt<'a> {
+    match stmt {
+        Stmt::Let(..) | Stmt::Refcounting(..) => {
+            // Handle a whole chain at once, both because operations move within it and so long
+            // chains don't overflow the stack.
+            let mut items = Vec::new();
+            let mut current_stmt = stmt;
+
+            loop {
+                match current_stmt {
+                    Stmt::Let(binding, expr, layout, next_stmt) => {
+                        items.push(Scheduled::Let(*binding, expr, *layout));
+                        current_stmt = next_stmt;
+                    }
+                    Stmt::Refcounting(modify_rc, next_stmt) => {
+                        items.push(Scheduled::Refcounting(*modify_rc));
+                        current_stmt = next_stmt;
+                    }
+                    _ => break,
+                }
+            }
+
+            move_decrements_up(parents, &mut items);
+            move_increments_down(&mut items);
+
+            let new_continuation = schedule_stmt(arena, parents, current_stmt);
+
+            items
+                .into_iter()
+                .rev()
+                .fold(new_continuation, |new_continuation, item| match item {
+                    Scheduled::Let(binding, expr, layout) => {
+                        arena.alloc(Stmt::Let(binding, expr.clone(), layout, new_continuation))
+                    }
+                    Scheduled::Refcounting(modify_rc) => {
+                        arena.alloc(Stmt::Refcounting(modify_rc, new_continuation))
+                    }
+                })
+        }
+        Stmt::Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout,
+        } => {
+            let new_branches = branches
+                .iter()
+                .map(|(tag_id, info, branch)| {
+                    (
+                        *tag_id,
+                        info.clone(),
+                        schedule_stmt(arena, parents, branch).clone(),
+                    )
+                })
+                .collect_in::<bumpalo::collections::Vec<_>>(arena)
+                .into_bump_slice();
+
+            let new_default_branch = (
+                default_branch.0.clone(),
+                schedule_stmt(arena, parents, default_branch.1),
+            );
+
+            arena.alloc(Stmt::Switch {
+                cond_symbol: *cond_symbol,
+                cond_layout: *cond_layout,
+                branches: new_branches,
+                default_branch: new_default_branch,
+                ret_layout: *ret_layout,
+            })
+        }
+        Stmt::Expect {
+            condition,
+            region,
+            lookups,
+            variables,
+            remainder,
+        } => arena.alloc(Stmt::Expect {
+            condition: *condition,
+            region: *region,
+            lookups: *lookups,
+            variables: *variables,
+            remainder: schedule_stmt(arena, parents, remainder),
+        }),
+        Stmt::ExpectFx {
+            condition,
+            region,
+            lookups,
+            variables,
+            remainder,
+        } => arena.alloc(Stmt::ExpectFx {
+            condition: *condition,
+            region: *region,
+            lookups: *lookups,
+            variables: *variables,
+            remainder: schedule_stmt(arena, parents, remainder),
+        }),
+        Stmt::Dbg {
+            symbol,
+            variable,
+            remainder,
+        } => arena.alloc(Stmt::Dbg {
+            symbol: *symbol,
+            variable: *variable,
+            remainder: schedule_stmt(arena, parents, remainder),
+        }),
+        Stmt::Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => {
+            let new_body = schedule_stmt(arena, parents, body);
+            let new_remainder = schedule_stmt(arena, parents, remainder);
+
+            arena.alloc(Stmt::Join {
+                id: *id,
+                parameters: *parameters,
+                body: new_body,
+                remainder: new_remainder,
+            })
+        }
+        Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => stmt,
+    }
+}
+
+/// Move every `dec` and `decref` up past the lets before it which don't touch the value or
+/// anything indexed out of it.
+fn move_decrements_up(parents: &MutMap<Symbol, Symbol>, items: &mut Vec<Scheduled>) {
+    for index in 0..items.len() {
+        let symbol = match items[index] {
+            Scheduled::Refcounting(ModifyRc::Dec(symbol) | ModifyRc::DecRef(symbol)) => symbol,
+            _ => continue,
+        };
+
+        let mut target = index;
+
+        while target > 0 {
+            match items[target - 1] {
+                Scheduled::Let(binding, expr, _) => {
+                    if is_derived_from(parents, binding, symbol) {
+                        break;
+                    }
+
+                    let mut used = MutSet::default();
+                    insert_expr_symbols(expr, &mut used);
+
+                    if used
+                        .iter()
+                        .any(|used| is_derived_from(parents, *used, symbol))
+                    {
+                        break;
+                    }
+                }
+                Scheduled::Refcounting(_) => break,
+            }
+
+            target -= 1;
+        }
+
+        let item = items.remove(index);
+        items.insert(target, item);
+    }
+}
+
+/// Move every `inc` down past the lets after it which don't use the value. Calls and resets
+/// could look at the refcount of the value through some alias, so those stop an `inc` too,
+/// except for calls of `Pure` host functions which only borrow their arguments.
+fn move_increments_down(items: &mut Vec<Scheduled>) {
+    for index in (0..items.len()).rev() {
+        let symbol = match items[index] {
+            Scheduled::Refcounting(ModifyRc::Inc(symbol, _)) => symbol,
+            _ => continue,
+        };
+
+        let mut target = index;
+
+        while target + 1 < items.len() {
+            match items[target + 1] {
+                Scheduled::Let(_, expr, _) => {
+                    let may_look_at_refcounts = match expr {
+                        Expr::Call(call) => !call.call_type.is_pure_borrowing_foreign(),
+                        Expr::Reset { .. } | Expr::ResetRef { .. } => true,
+                        _ => false,
+                    };
+
+                    if may_look_at_refcounts {
+                        break;
+                    }
+
+                    let mut used = MutSet::default();
+                    insert_expr_symbols(expr, &mut used);
+
+                    if used.contains(&symbol) {
+                        break;
+                    }
+                }
+                Scheduled::Refcounting(_) => break,
+            }
+
+            target += 1;
+        }
+
+        let item = items.remove(index);
+        items.insert(target, item);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bumpalo::Bump;
+    use roc_collections::MutMap;
+    use roc_module::low_level::LowLevel;
+    use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+    use roc_target::TargetInfo;
+
+    use crate::borrow::Ownership;
+    use crate::ir::{
+        Call, CallSpecId, CallType, Expr, HostExposedLayouts, Literal, ModifyRc, Proc, ProcLayout,
+        SelfRecursive, Stmt, UpdateModeId,
+    };
+    use crate::layout::{LambdaName, Layout, Niche, STLayoutInterner, UnionLayout};
+
+    use super::schedule_refcounts;
+
+    const TARGET_INFO: TargetInfo = TargetInfo::default_x86_64();
+
+    /// Every procedure in these tests takes a `Str` and returns one
+    const PROC_LAYOUT: ProcLayout<'static> = ProcLayout {
+        arguments: &[Layout::STR],
+        result: Layout::STR,
+        niche: Niche::NONE,
+    };
+
+    enum Line<'a> {
+        Let(Symbol, Expr<'a>),
+        Refcounting(ModifyRc),
+    }
+
+    fn symbols<const N: usize>(ident_ids: &mut IdentIds, names: [&str; N]) -> [Symbol; N] {
+        names.map(|name| Symbol::new(ModuleId::ATTR, ident_ids.add_str(name)))
+    }
+
+    fn int<'a>() -> Expr<'a> {
+        Expr::Literal(Literal::Int(0i128.to_ne_bytes()))
+    }
+
+    fn uses<'a>(arena: &'a Bump, symbol: Symbol) -> Expr<'a> {
+        Expr::Struct(arena.alloc([symbol]))
+    }
+
+    fn call<'a>(arena: &'a Bump, call_type: CallType<'a>, arguments: &[Symbol]) -> Expr<'a> {
+        Expr::Call(Call {
+            call_type,
+            arguments: arena.alloc_slice_copy(arguments),
+        })
+    }
+
+    /// The lines, in order, returning `returned`
+    fn body<'a>(arena: &'a Bump, lines: Vec<Line<'a>>, returned: Symbol) -> Stmt<'a> {
+        lines
+            .into_iter()
+            .rev()
+            .fold(Stmt::Ret(returned), |continuation, line| {
+                let continuation = arena.alloc(continuation);
+
+                match line {
+                    Line::Let(symbol, expr) => Stmt::Let(symbol, expr, Layout::STR, continuation),
+                    Line::Refcounting(modify_rc) => Stmt::Refcounting(modify_rc, continuation),
+                }
+            })
+    }
+
+    /// Schedules the refcounts of a procedure with the body `lines`, and compares the IR it ends
+    /// up with to `expected`.
+    fn assert_schedules<'a>(
+        arena: &'a Bump,
+        [name, argument]: [Symbol; 2],
+        lines: Vec<Line<'a>>,
+        expected: Vec<Line<'a>>,
+    ) {
+        let proc = Proc {
+            name: LambdaName::no_niche(name),
+            args: arena.alloc([(Layout::STR, argument)]),
+            body: body(arena, lines, argument),
+            closure_data_layout: None,
+            ret_layout: Layout::STR,
+            is_self_recursive: SelfRecursive::NotSelfRecursive,
+            host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+        };
+
+        let mut procs = MutMap::default();
+        procs.insert((name, PROC_LAYOUT), proc);
+
+        schedule_refcounts(arena, &mut procs);
+
+        let interner = STLayoutInterner::with_capacity(4, TARGET_INFO);
+        let actual = &procs[&(name, PROC_LAYOUT)].body;
+
+        assert_eq!(
+            actual.to_pretty(&interner, 200, false),
+            body(arena, expected, argument).to_pretty(&interner, 200, false)
+        );
+    }
+
+    /// `child` is indexed out of `parent` by `index`, and only used after that. Dropping `parent`
+    /// may free `child` too, so the `dec` has to wait for the last use of `child`.
+    fn assert_dec_waits_for_child<'a>(
+        arena: &'a Bump,
+        ident_ids: &mut IdentIds,
+        index: impl Fn(Symbol) -> Expr<'a>,
+    ) {
+        let [name, parent, child, grandchild, used, other] = symbols(
+            ident_ids,
+            ["f", "parent", "child", "grandchild", "used", "other"],
+        );
+
+        let lines = vec![
+            Line::Let(child, index(parent)),
+            // through another child, to check that children of children count too
+            Line::Let(grandchild, Expr::ExprUnbox { symbol: child }),
+            Line::Let(used, uses(arena, grandchild)),
+            Line::Let(other, int()),
+            Line::Refcounting(ModifyRc::Dec(parent)),
+        ];
+        let expected = vec![
+            Line::Let(child, index(parent)),
+            Line::Let(grandchild, Expr::ExprUnbox { symbol: child }),
+            Line::Let(used, uses(arena, grandchild)),
+            Line::Refcounting(ModifyRc::Dec(parent)),
+            Line::Let(other, int()),
+        ];
+
+        assert_schedules(arena, [name, parent], lines, expected);
+    }
+
+    #[test]
+    fn dec_moves_up_to_last_use() {
+        let arena = Bump::new();
+        let mut ident_ids = IdentIds::default();
+        let [name, s, used, a, b] = symbols(&mut ident_ids, ["f", "s", "used", "a", "b"]);
+
+        let lines = vec![
+            Line::Let(used, uses(&arena, s)),
+            Line::Let(a, int()),
+            Line::Let(b, int()),
+            Line::Refcounting(ModifyRc::Dec(s)),
+        ];
+        let expected = vec![
+            Line::Let(used, uses(&arena, s)),
+            Line::Refcounting(ModifyRc::Dec(s)),
+            Line::Let(a, int()),
+            Line::Let(b, int()),
+        ];
+
+        assert_schedules(&arena, [name, s], lines, expected);
+    }
+
+    #[test]
+    fn dec_waits_for_struct_field() {
+        let arena = Bump::new();
+        let mut ident_ids = IdentIds::default();
+
+        assert_dec_waits_for_child(&arena, &mut ident_ids, |parent| Expr::StructAtIndex {
+            index: 0,
+            field_layouts: &[Layout::STR],
+            structure: parent,
+        });
+    }
+
+    #[test]
+    fn dec_waits_for_union_field() {
+        let arena = Bump::new();
+        let mut ident_ids = IdentIds::default();
+        let union_layout = UnionLayout::NonNullableUnwrapped(&[Layout::STR]);
+
+        assert_dec_waits_for_child(&arena, &mut ident_ids, |parent| Expr::UnionAtIndex {
+            structure: parent,
+            tag_id: 0,
+            union_layout,
+            index: 0,
+        });
+    }
+
+    #[test]
+    fn dec_waits_for_box_contents() {
+        let arena = Bump::new();
+        let mut ident_ids = IdentIds::default();
+
+        assert_dec_waits_for_child(&arena, &mut ident_ids, |parent| Expr::ExprUnbox {
+            symbol: parent,
+        });
+    }
+
+    #[test]
+    fn dec_waits_for_list_element() {
+        let arena = Bump::new();
+        let mut ident_ids = IdentIds::default();
+        let [index] = symbols(&mut ident_ids, ["index"]);
+
+        let list_get = |parent| {
+            let call_type = CallType::LowLevel {
+                op: LowLevel::ListGetUnsafe,
+                update_mode: UpdateModeId::BACKEND_DUMMY,
+            };
+
+            call(&arena, call_type, &[parent, index])
+        };
+
+        assert_dec_waits_for_child(&arena, &mut ident_ids, list_get);
+    }
+
+    #[test]
+    fn inc_moves_down_to_first_use() {
+        let arena = Bump::new();
+        let mut ident_ids = IdentIds::default();
+        let [name, s, a, used] = symbols(&mut ident_ids, ["f", "s", "a", "used"]);
+
+        let lines = vec![
+            Line::Refcounting(ModifyRc::Inc(s, 1)),
+            Line::Let(a, int()),
+            Line::Let(used, uses(&arena, s)),
+        ];
+        let expected = vec![
+            Line::Let(a, int()),
+            Line::Refcounting(ModifyRc::Inc(s, 1)),
+            Line::Let(used, uses(&arena, s)),
+        ];
+
+        assert_schedules(&arena, [name, s], lines, expected);
+    }
+
+    #[test]
+    fn inc_stops_at_call() {
+        // the callee could reach `s` through an alias, and look at its refcount
+        let arena = Bump::new();
+        let mut ident_ids = IdentIds::default();
+        let [name, s, g, x, a, used] = symbols(&mut ident_ids, ["f", "s", "g", "x", "a", "used"]);
+
+        let call_by_name = || {
+            let call_type = CallType::ByName {
+                name: LambdaName::no_niche(g),
+                ret_layout: Layout::STR,
+                arg_layouts: &[Layout::STR],
+                specialization_id: CallSpecId::BACKEND_DUMMY,
+            };
+
+            call(&arena, call_type, &[x])
+        };
+
+        let lines = || {
+            vec![
+                Line::Refcounting(ModifyRc::Inc(s, 1)),
+                Line::Let(a, call_by_name()),
+                Line::Let(used, uses(&arena, s)),
+            ]
+        };
+
+        assert_schedules(&arena, [name, s], lines(), lines());
+    }
+
+    #[test]
+    fn inc_moves_past_pure_borrowing_foreign_call() {
+        let arena = Bump::new();
+        let mut ident_ids = IdentIds::default();
+        let [name, s, x, a, used] = symbols(&mut ident_ids, ["f", "s", "x", "a", "used"]);
+
+        let foreign = || {
+            let call_type = CallType::Foreign {
+                foreign_symbol: "roc_fx_strLen".into(),
+                ret_layout: Layout::STR,
+                arg_ownership: &[Ownership::Borrowed],
+                ret_ownership: Ownership::Owned,
+                pure: true,
+            };
+
+            call(&arena, call_type, &[x])
+        };
+
+        let lines = vec![
+            Line::Refcounting(ModifyRc::Inc(s, 1)),
+            Line::Let(a, foreign()),
+            Line::Let(used, uses(&arena, s)),
+        ];
+        let expected = vec![
+            Line::Let(a, foreign()),
+            Line::Refcounting(ModifyRc::Inc(s, 1)),
+            Line::Let(used, uses(&arena, s)),
+        ];
+
+        assert_schedules(&arena, [name, s], lines, expected);
+    }
+
+    #[test]
+    fn inc_stops_at_reset() {
+        let arena = Bump::new();
+        let mut ident_ids = IdentIds::default();
+        let [name, s, t, token, used] = symbols(&mut ident_ids, ["f", "s", "t", "token", "used"]);
+
+        let resets = [
+            Expr::Reset {
+                symbol: t,
+                update_mode: UpdateModeId::BACKEND_DUMMY,
+            },
+            Expr::ResetRef {
+                symbol: t,
+                update_mode: UpdateModeId::BACKEND_DUMMY,
+            },
+        ];
+
+        for reset in resets {
+            let lines = || {
+                vec![
+                    Line::Refcounting(ModifyRc::Inc(s, 1)),
+                    Line::Let(token, reset.clone()),
+                    Line::Let(used, uses(&arena, s)),
+                ]
+            };
+
+            assert_schedules(&arena, [name, s], lines(), lines());
+        }
+    }
+}
@@ -343,7 +343,7 @@ fn insert_reset_reuse_operations_stmt<'a, 'i>(
         }
         Stmt::Refcounting(rc, continuation) => {
             let reuse_pair = match rc {
-                ModifyRc::Inc(_, _) => {
+                ModifyRc::Inc(_, _) | ModifyRc::IncN(_, _) => {
                     // We don't need to do anything for an inc.
                     None
                 }
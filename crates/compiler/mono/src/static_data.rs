@@ -0,0 +1,153 @@
+//! Evaluate top-level constants like `primes = [2, 3, 5, 7]` or `defaults = { retries: 3 }`
+//! at compile time, so backends can put them in the data section instead of building them
+//! again every time they are used.
+//!
+//! Only a small, pure subset of the language is evaluated: literals, list literals and records
+//! made of those, and references to other such constants. This runs after constant folding, so
+//! things like `60 * 1000` or `Str.concat "a" "b"` have already been turned into literals.
+//!
+//! Backends give the heap-allocated parts of static data a refcount of `REFCOUNT_MAX`. That makes
+//! them immortal: incrementing and decrementing them does nothing, they are never freed, and they
+//! are never considered unique, so they are copied rather than updated in place.
+
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+use roc_collections::MutMap;
+use roc_module::symbol::Symbol;
+
+use crate::initialization::InitializationOrder;
+use crate::ir::{Call, CallType, Expr, ListLiteralElement, Literal, Proc, ProcLayout, Stmt};
+use crate::layout::{Builtin, InLayout, LayoutInterner, LayoutRepr, STLayoutInterner};
+
+/// A value that was computed at compile time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StaticValue<'a> {
+    Literal(Literal<'a>),
+    /// The elements of a list; their layout is the element layout of the list's layout
+    List(&'a [StaticValue<'a>]),
+    /// The fields of a struct, in the order of the struct layout's fields
+    Struct(&'a [StaticValue<'a>]),
+}
+
+impl<'a> StaticValue<'a> {
+    /// Whether storing this value statically saves any work. Scalars and records of scalars are
+    /// just as cheap to build at runtime.
+    fn has_heap_data(&self) -> bool {
+        match self {
+            StaticValue::Literal(literal) => matches!(literal, Literal::Str(_)),
+            StaticValue::List(_) => true,
+            StaticValue::Struct(fields) => fields.iter().any(|field| field.has_heap_data()),
+        }
+    }
+}
+
+/// The top-level constants (zero-argument thunks) whose values are known at compile time.
+pub type StaticConstants<'a> = MutMap<(Symbol, ProcLayout<'a>), StaticValue<'a>>;
+
+pub fn evaluate_static_constants<'a>(
+    arena: &'a Bump,
+    layout_interner: &STLayoutInterner<'a>,
+    initialization: &InitializationOrder<'a>,
+    procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) -> StaticConstants<'a> {
+    let mut constants = StaticConstants::default();
+
+    // Constants can refer to each other; in initialization order, the constants a constant
+    // refers to have already been evaluated when we get to it.
+    for key in initialization.order.iter() {
+        if let Some(proc) = procs.get(key) {
+            if let Some(value) = evaluate_body(arena, layout_interner, &constants, &proc.body) {
+                constants.insert(*key, value);
+            }
+        }
+    }
+
+    // Constants whose values are only scalars were useful while evaluating the others,
+    // but backends are better off building them as usual.
+    constants.retain(|_, value| value.has_heap_data());
+
+    constants
+}
+
+fn evaluate_body<'a>(
+    arena: &'a Bump,
+    layout_interner: &STLayoutInterner<'a>,
+    constants: &StaticConstants<'a>,
+    body: &Stmt<'a>,
+) -> Option<StaticValue<'a>> {
+    let mut values = MutMap::default();
+    let mut stmt = body;
+
+    loop {
+        match stmt {
+            Stmt::Let(symbol, expr, layout, cont) => {
+                let value =
+                    evaluate_expr(arena, layout_interner, constants, &values, expr, *layout)?;
+
+                values.insert(*symbol, value);
+                stmt = cont;
+            }
+            Stmt::Ret(symbol) => return values.remove(symbol),
+            _ => return None,
+        }
+    }
+}
+
+fn evaluate_expr<'a>(
+    arena: &'a Bump,
+    layout_interner: &STLayoutInterner<'a>,
+    constants: &StaticConstants<'a>,
+    values: &MutMap<Symbol, StaticValue<'a>>,
+    expr: &Expr<'a>,
+    layout: InLayout<'a>,
+) -> Option<StaticValue<'a>> {
+    match expr {
+        Expr::Literal(literal) => Some(StaticValue::Literal(*literal)),
+        Expr::Struct(fields) => {
+            let mut field_values = Vec::with_capacity_in(fields.len(), arena);
+
+            for field in fields.iter() {
+                field_values.push(*values.get(field)?);
+            }
+
+            Some(StaticValue::Struct(field_values.into_bump_slice()))
+        }
+        Expr::Array { elems, .. } => {
+            let mut elem_values = Vec::with_capacity_in(elems.len(), arena);
+
+            for elem in elems.iter() {
+                let value = match elem {
+                    ListLiteralElement::Literal(literal) => StaticValue::Literal(*literal),
+                    ListLiteralElement::Symbol(symbol) => *values.get(symbol)?,
+                };
+
+                elem_values.push(value);
+            }
+
+            Some(StaticValue::List(elem_values.into_bump_slice()))
+        }
+        Expr::EmptyArray => match layout_interner.get(layout).repr {
+            LayoutRepr::Builtin(Builtin::List(_)) => Some(StaticValue::List(&[])),
+            _ => None,
+        },
+        Expr::Call(Call {
+            call_type:
+                CallType::ByName {
+                    name,
+                    ret_layout,
+                    arg_layouts,
+                    ..
+                },
+            arguments: [],
+        }) if arg_layouts.is_empty() => {
+            let proc_layout = ProcLayout {
+                arguments: arg_layouts,
+                result: *ret_layout,
+                niche: name.niche(),
+            };
+
+            constants.get(&(name.name(), proc_layout)).copied()
+        }
+        _ => None,
+    }
+}
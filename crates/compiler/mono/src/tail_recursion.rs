@@ -1,11 +1,19 @@
 #![allow(clippy::manual_map)]
 
 use crate::borrow::Ownership;
-use crate::ir::{CallType, Expr, JoinPointId, Param, Stmt};
-use crate::layout::{InLayout, LambdaName};
+use crate::ir::{
+    BranchInfo, Call, CallSpecId, CallType, Expr, HostExposedLayouts, JoinPointId, Param, Proc,
+    ProcLayout, SelfRecursive, Stmt,
+};
+use crate::layout::{
+    InLayout, LambdaName, LayoutInterner, LayoutRepr, Niche, STLayoutInterner, TagIdIntType,
+    UnionLayout,
+};
 use bumpalo::collections::Vec;
 use bumpalo::Bump;
-use roc_module::symbol::Symbol;
+use roc_collections::all::{MutMap, MutSet};
+use roc_collections::ReferenceMatrix;
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
 
 /// Make tail calls into loops (using join points)
 ///
@@ -68,6 +76,359 @@ pub fn make_tail_recursive<'a>(
     Some(join)
 }
 
+/// Make mutual tail calls into a loop, which switches on the function it is in
+///
+/// e.g.
+///
+/// > isEven n = if n == 0 then Bool.true else isOdd (n - 1)
+/// > isOdd n = if n == 0 then Bool.false else isEven (n - 1)
+///
+/// becomes
+///
+/// ```elm
+/// isEvenOrOdd state1 =
+///     let joinpoint j state =
+///             when state is
+///                 IsEven n ->
+///                     if n == 0 then Bool.true else jump j (IsOdd (n - 1))
+///                 IsOdd n ->
+///                     if n == 0 then Bool.false else jump j (IsEven (n - 1))
+///
+///     in
+///         jump j state1
+///
+/// isEven n = isEvenOrOdd (IsEven n)
+/// isOdd n = isEvenOrOdd (IsOdd n)
+/// ```
+///
+/// The state is a tag union on the stack, with a tag for each function holding its arguments.
+/// Functions which call each other in tail position are found as the strongly connected
+/// components of the graph of tail calls. A group is left alone if it contains two
+/// specializations of the same function, which would bind the same symbols, or calls whose
+/// specialization ids clash, which happens when its procs were specialized by different modules.
+pub fn merge_mutual_tail_calls<'a, 'i>(
+    arena: &'a Bump,
+    layout_interner: &'i mut STLayoutInterner<'a>,
+    home: ModuleId,
+    ident_ids: &'i mut IdentIds,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) {
+    let keys: std::vec::Vec<_> = procs.keys().copied().collect();
+    let index_of: MutMap<_, _> = keys.iter().enumerate().map(|(i, key)| (*key, i)).collect();
+
+    let mut matrix = ReferenceMatrix::new(keys.len());
+
+    for (row, key) in keys.iter().enumerate() {
+        for_each_tail_call(&procs[key].body, &mut |callee| {
+            if let Some(col) = index_of.get(&callee) {
+                matrix.set_row_col(row, *col, true);
+            }
+        });
+    }
+
+    let sccs = matrix.strongly_connected_components_all();
+
+    for (group, _) in sccs.groups() {
+        let group: std::vec::Vec<_> = group.iter_ones().map(|index| keys[index]).collect();
+
+        if can_merge(procs, &group) {
+            merge_group(arena, layout_interner, home, ident_ids, procs, &group);
+        }
+    }
+}
+
+fn can_merge<'a>(
+    procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    group: &[(Symbol, ProcLayout<'a>)],
+) -> bool {
+    if group.len() < 2 {
+        // a function that only calls itself is handled by `make_tail_recursive`
+        return false;
+    }
+
+    let mut symbols = MutSet::default();
+    let mut call_spec_ids = MutSet::default();
+
+    for (symbol, proc_layout) in group {
+        if !symbols.insert(*symbol) || proc_layout.result != group[0].1.result {
+            return false;
+        }
+
+        let mut clashes = false;
+        for_each_call_spec_id(&procs[&(*symbol, *proc_layout)].body, &mut |id| {
+            clashes |= !call_spec_ids.insert(id.to_bytes());
+        });
+
+        if clashes {
+            return false;
+        }
+    }
+
+    true
+}
+
+fn merge_group<'a>(
+    arena: &'a Bump,
+    layout_interner: &mut STLayoutInterner<'a>,
+    home: ModuleId,
+    ident_ids: &mut IdentIds,
+    procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    group: &[(Symbol, ProcLayout<'a>)],
+) {
+    let mut create_symbol = |debug_name: &str| Symbol::new(home, ident_ids.add_str(debug_name));
+
+    let merged = LambdaName::no_niche(create_symbol("mutual_tail_calls"));
+    let loop_id = JoinPointId(create_symbol("state_loop"));
+    let first_state = create_symbol("state");
+    let state = create_symbol("state");
+    let tag_id_symbol = create_symbol("state_tag");
+
+    let ret_layout = group[0].1.result;
+
+    let tags = Vec::from_iter_in(
+        group.iter().map(|key| {
+            let args = procs[key].args.iter().map(|(layout, _)| *layout);
+            &*arena.alloc_slice_fill_iter(args)
+        }),
+        arena,
+    );
+    let union_layout = UnionLayout::NonRecursive(tags.into_bump_slice());
+    let state_layout = layout_interner.insert_no_semantic(LayoutRepr::Union(union_layout));
+
+    let tag_of: MutMap<_, _> = group
+        .iter()
+        .enumerate()
+        .map(|(tag_id, key)| (*key, tag_id as TagIdIntType))
+        .collect();
+
+    // a tail call to a function of the group stores its arguments in the state, and loops
+    let mut jump_to_state = |symbol: Symbol, call: &CallType<'a>, arguments: &'a [Symbol]| {
+        let callee = match call {
+            CallType::ByName {
+                name,
+                ret_layout,
+                arg_layouts,
+                ..
+            } => (
+                name.name(),
+                ProcLayout {
+                    arguments: *arg_layouts,
+                    result: *ret_layout,
+                    niche: name.niche(),
+                },
+            ),
+            _ => return None,
+        };
+
+        let expr = Expr::Tag {
+            tag_layout: union_layout,
+            tag_id: *tag_of.get(&callee)?,
+            arguments,
+        };
+        let jump = Stmt::Jump(loop_id, arena.alloc([symbol]));
+
+        Some(Stmt::Let(symbol, expr, state_layout, arena.alloc(jump)))
+    };
+
+    let mut branches = Vec::with_capacity_in(group.len(), arena);
+
+    for (tag_id, key) in group.iter().enumerate() {
+        let tag_id = tag_id as TagIdIntType;
+        let proc = &procs[key];
+
+        let body: &'a Stmt<'a> = arena.alloc(proc.body.clone());
+        let body = replace_tail_calls(arena, body, &mut jump_to_state).unwrap_or(body);
+
+        // take the arguments of this function out of the state
+        let mut branch = body;
+        for (index, (layout, symbol)) in proc.args.iter().enumerate().rev() {
+            let expr = Expr::UnionAtIndex {
+                structure: state,
+                tag_id,
+                union_layout,
+                index: index as u64,
+            };
+
+            branch = arena.alloc(Stmt::Let(*symbol, expr, *layout, branch));
+        }
+
+        branches.push((tag_id as u64, BranchInfo::None, branch.clone()));
+    }
+
+    let (_, _, last_branch) = branches.pop().unwrap();
+
+    let switch = Stmt::Switch {
+        cond_symbol: tag_id_symbol,
+        cond_layout: union_layout.tag_id_layout(),
+        branches: branches.into_bump_slice(),
+        default_branch: (BranchInfo::None, arena.alloc(last_branch)),
+        ret_layout,
+    };
+
+    let dispatch = Stmt::Let(
+        tag_id_symbol,
+        Expr::GetTagId {
+            structure: state,
+            union_layout,
+        },
+        union_layout.tag_id_layout(),
+        arena.alloc(switch),
+    );
+
+    let body = Stmt::Join {
+        id: loop_id,
+        parameters: arena.alloc([Param {
+            symbol: state,
+            layout: state_layout,
+            ownership: Ownership::Borrowed,
+        }]),
+        body: arena.alloc(dispatch),
+        remainder: arena.alloc(Stmt::Jump(loop_id, arena.alloc([first_state]))),
+    };
+
+    let merged_layout = ProcLayout {
+        arguments: arena.alloc([state_layout]),
+        result: ret_layout,
+        niche: Niche::NONE,
+    };
+
+    // every function of the group now starts the loop in its own state
+    for (tag_id, key) in group.iter().enumerate() {
+        let proc = procs.get_mut(key).unwrap();
+
+        let arguments = Vec::from_iter_in(proc.args.iter().map(|(_, symbol)| *symbol), arena);
+        let state = create_symbol("state");
+        let result = create_symbol("result");
+
+        let call = Call {
+            call_type: CallType::ByName {
+                name: merged,
+                ret_layout,
+                arg_layouts: merged_layout.arguments,
+                specialization_id: CallSpecId::after_specialization(0),
+            },
+            arguments: arena.alloc([state]),
+        };
+
+        proc.body = Stmt::Let(
+            state,
+            Expr::Tag {
+                tag_layout: union_layout,
+                tag_id: tag_id as TagIdIntType,
+                arguments: arguments.into_bump_slice(),
+            },
+            state_layout,
+            arena.alloc(Stmt::Let(
+                result,
+                Expr::Call(call),
+                ret_layout,
+                arena.alloc(Stmt::Ret(result)),
+            )),
+        );
+        proc.is_self_recursive = SelfRecursive::NotSelfRecursive;
+    }
+
+    let merged_proc = Proc {
+        name: merged,
+        args: arena.alloc([(state_layout, first_state)]),
+        body,
+        closure_data_layout: None,
+        ret_layout,
+        is_self_recursive: SelfRecursive::SelfRecursive(loop_id),
+        host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+    };
+
+    procs.insert((merged.name(), merged_layout), merged_proc);
+}
+
+/// Calls `f` with the specialization of every function that `stmt` calls in tail position.
+fn for_each_tail_call<'a>(stmt: &Stmt<'a>, f: &mut impl FnMut((Symbol, ProcLayout<'a>))) {
+    for_each_stmt(stmt, &mut |stmt| {
+        if let Stmt::Let(
+            symbol,
+            Expr::Call(Call {
+                call_type:
+                    CallType::ByName {
+                        name,
+                        ret_layout,
+                        arg_layouts,
+                        ..
+                    },
+                ..
+            }),
+            _,
+            Stmt::Ret(rsym),
+        ) = stmt
+        {
+            if symbol == rsym {
+                f((
+                    name.name(),
+                    ProcLayout {
+                        arguments: *arg_layouts,
+                        result: *ret_layout,
+                        niche: name.niche(),
+                    },
+                ));
+            }
+        }
+    });
+}
+
+/// Calls `f` with the specialization id of every call in `stmt`.
+fn for_each_call_spec_id(stmt: &Stmt, f: &mut impl FnMut(CallSpecId)) {
+    for_each_stmt(stmt, &mut |stmt| {
+        if let Stmt::Let(_, Expr::Call(call), _, _) = stmt {
+            match &call.call_type {
+                CallType::ByName {
+                    specialization_id, ..
+                } => f(*specialization_id),
+                CallType::HigherOrder(higher_order) => {
+                    f(higher_order.passed_function.specialization_id)
+                }
+                CallType::Foreign { .. } | CallType::LowLevel { .. } => {}
+            }
+        }
+    });
+}
+
+/// Calls `f` with `stmt` and every statement in it.
+fn for_each_stmt<'s, 'a>(stmt: &'s Stmt<'a>, f: &mut impl FnMut(&'s Stmt<'a>)) {
+    let mut stack = std::vec![stmt];
+
+    while let Some(stmt) = stack.pop() {
+        f(stmt);
+
+        match stmt {
+            Stmt::Let(_, _, _, rest)
+            | Stmt::Refcounting(_, rest)
+            | Stmt::Expect {
+                remainder: rest, ..
+            }
+            | Stmt::ExpectFx {
+                remainder: rest, ..
+            }
+            | Stmt::Dbg {
+                remainder: rest, ..
+            } => stack.push(rest),
+            Stmt::Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                stack.push(default_branch.1);
+            }
+            Stmt::Join {
+                body, remainder, ..
+            } => {
+                stack.push(body);
+                stack.push(remainder);
+            }
+            Stmt::Ret(_) | Stmt::Jump(_, _) | Stmt::Crash(_, _) => {}
+        }
+    }
+}
+
 fn insert_jumps<'a>(
     arena: &'a Bump,
     stmt: &'a Stmt<'a>,
@@ -76,8 +437,6 @@ fn insert_jumps<'a>(
     needle_arguments: &'a [(InLayout<'a>, Symbol, Symbol)],
     needle_result: InLayout<'a>,
 ) -> Option<&'a Stmt<'a>> {
-    use Stmt::*;
-
     // to insert a tail-call, it must not just be a call to the function itself, but it must also
     // have the same layout. In particular when lambda sets get involved, a self-recursive call may
     // have a different type and should not be converted to a jump!
@@ -86,38 +445,46 @@ fn insert_jumps<'a>(
         needle == function_name && it.eq(arguments.iter()) && needle_result == result
     };
 
+    replace_tail_calls(arena, stmt, &mut |_, call, arguments| match call {
+        CallType::ByName {
+            name,
+            ret_layout,
+            arg_layouts,
+            ..
+        } if is_equal_function(*name, arg_layouts, *ret_layout) => {
+            // replace the call and return with a jump
+            Some(Stmt::Jump(goal_id, arguments))
+        }
+        _ => None,
+    })
+}
+
+/// Rebuild `stmt` with every call in tail position, `let x = call; ret x`, replaced by what
+/// `replace` returns for the bound symbol, the call and its arguments. Returns `None` if nothing
+/// was replaced.
+fn replace_tail_calls<'a>(
+    arena: &'a Bump,
+    stmt: &'a Stmt<'a>,
+    replace: &mut impl FnMut(Symbol, &'a CallType<'a>, &'a [Symbol]) -> Option<Stmt<'a>>,
+) -> Option<&'a Stmt<'a>> {
+    use Stmt::*;
+
     match stmt {
         Let(
             symbol,
             Expr::Call(crate::ir::Call {
-                call_type:
-                    CallType::ByName {
-                        name: fsym,
-                        ret_layout,
-                        arg_layouts,
-                        ..
-                    },
+                call_type,
                 arguments,
             }),
             _,
             Stmt::Ret(rsym),
-        ) if symbol == rsym && is_equal_function(*fsym, arg_layouts, *ret_layout) => {
-            // replace the call and return with a jump
-
-            let jump = Stmt::Jump(goal_id, arguments);
-
-            Some(arena.alloc(jump))
-        }
+        ) if symbol == rsym => match replace(*symbol, call_type, arguments) {
+            Some(new_stmt) => Some(arena.alloc(new_stmt)),
+            None => None,
+        },
 
         Let(symbol, expr, layout, cont) => {
-            let opt_cont = insert_jumps(
-                arena,
-                cont,
-                goal_id,
-                needle,
-                needle_arguments,
-                needle_result,
-            );
+            let opt_cont = replace_tail_calls(arena, cont, replace);
 
             if opt_cont.is_some() {
                 let cont = opt_cont.unwrap_or(cont);
@@ -134,22 +501,8 @@ fn insert_jumps<'a>(
             remainder,
             body: continuation,
         } => {
-            let opt_remainder = insert_jumps(
-                arena,
-                remainder,
-                goal_id,
-                needle,
-                needle_arguments,
-                needle_result,
-            );
-            let opt_continuation = insert_jumps(
-                arena,
-                continuation,
-                goal_id,
-                needle,
-                needle_arguments,
-                needle_result,
-            );
+            let opt_remainder = replace_tail_calls(arena, remainder, replace);
+            let opt_continuation = replace_tail_calls(arena, continuation, replace);
 
             if opt_remainder.is_some() || opt_continuation.is_some() {
                 let remainder = opt_remainder.unwrap_or(remainder);
@@ -172,27 +525,13 @@ fn insert_jumps<'a>(
             default_branch,
             ret_layout,
         } => {
-            let opt_default = insert_jumps(
-                arena,
-                default_branch.1,
-                goal_id,
-                needle,
-                needle_arguments,
-                needle_result,
-            );
+            let opt_default = replace_tail_calls(arena, default_branch.1, replace);
 
             let mut did_change = false;
 
             let opt_branches = Vec::from_iter_in(
                 branches.iter().map(|(label, info, branch)| {
-                    match insert_jumps(
-                        arena,
-                        branch,
-                        goal_id,
-                        needle,
-                        needle_arguments,
-                        needle_result,
-                    ) {
+                    match replace_tail_calls(arena, branch, replace) {
                         None => None,
                         Some(branch) => {
                             did_change = true;
@@ -236,32 +575,16 @@ fn insert_jumps<'a>(
                 None
             }
         }
-        Refcounting(modify, cont) => {
-            match insert_jumps(
-                arena,
-                cont,
-                goal_id,
-                needle,
-                needle_arguments,
-                needle_result,
-            ) {
-                Some(cont) => Some(arena.alloc(Refcounting(*modify, cont))),
-                None => None,
-            }
-        }
+        Refcounting(modify, cont) => match replace_tail_calls(arena, cont, replace) {
+            Some(cont) => Some(arena.alloc(Refcounting(*modify, cont))),
+            None => None,
+        },
 
         Dbg {
             symbol,
             variable,
             remainder,
-        } => match insert_jumps(
-            arena,
-            remainder,
-            goal_id,
-            needle,
-            needle_arguments,
-            needle_result,
-        ) {
+        } => match replace_tail_calls(arena, remainder, replace) {
             Some(cont) => Some(arena.alloc(Dbg {
                 symbol: *symbol,
                 variable: *variable,
@@ -276,14 +599,7 @@ fn insert_jumps<'a>(
             lookups,
             variables,
             remainder,
-        } => match insert_jumps(
-            arena,
-            remainder,
-            goal_id,
-            needle,
-            needle_arguments,
-            needle_result,
-        ) {
+        } => match replace_tail_calls(arena, remainder, replace) {
             Some(cont) => Some(arena.alloc(Expect {
                 condition: *condition,
                 region: *region,
@@ -300,14 +616,7 @@ fn insert_jumps<'a>(
             lookups,
             variables,
             remainder,
-        } => match insert_jumps(
-            arena,
-            remainder,
-            goal_id,
-            needle,
-            needle_arguments,
-            needle_result,
-        ) {
+        } => match replace_tail_calls(arena, remainder, replace) {
             Some(cont) => Some(arena.alloc(ExpectFx {
                 condition: *condition,
                 region: *region,
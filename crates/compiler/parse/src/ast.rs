@@ -216,6 +216,9 @@ pub enum StrLiteral<'a> {
     PlainLine(&'a str),
     Line(&'a [StrSegment<'a>]),
     Block(&'a [&'a [StrSegment<'a>]]),
+    /// A raw string like `r"C:\Users"`, which has no escapes or interpolations. For a multi-line
+    /// raw string, this is the contents with the indentation already removed.
+    Raw(&'a str),
 }
 
 /// A parsed expression. This uses lifetimes extensively for two reasons:
@@ -324,6 +327,9 @@ pub enum Expr<'a> {
     // Problems
     MalformedIdent(&'a str, crate::ident::BadIdent),
     MalformedClosure,
+    /// Source which failed to parse, and got skipped over to recover from the syntax error.
+    /// Only produced when parsing with recovery; see [crate::expr::toplevel_defs_recovering].
+    MalformedExpr(&'a str),
     // Both operators were non-associative, e.g. (True == False == False).
     // We should tell the author to disambiguate by grouping them with parens.
     PrecedenceConflict(&'a PrecedenceConflict<'a>),
@@ -371,14 +377,28 @@ pub enum Has<'a> {
 pub struct AbilityMember<'a> {
     pub name: Loc<Spaced<'a, &'a str>>,
     pub typ: Loc<TypeAnnotation<'a>>,
+    pub default: Option<AbilityMemberDefault<'a>>,
 }
 
 impl AbilityMember<'_> {
     pub fn region(&self) -> Region {
-        Region::across_all([self.name.region, self.typ.region].iter())
+        match &self.default {
+            Some(default) => Region::span_across(&self.name.region, &default.body.region),
+            None => Region::across_all([self.name.region, self.typ.region].iter()),
+        }
     }
 }
 
+/// A default implementation of an ability member, defined on the line after its demand; for
+/// example `isNotEq = \a, b -> !(isEq a b)`. Types implementing the ability without
+/// implementing the member use the default.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AbilityMemberDefault<'a> {
+    /// Comments and newlines between the demand and the default implementation
+    pub spaces_before: &'a [CommentOrNewline<'a>],
+    pub body: &'a Loc<Expr<'a>>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TypeDef<'a> {
     /// A type alias. This is like a standalone annotation, except the pattern
@@ -1535,6 +1555,7 @@ impl<'a> Malformed for Expr<'a> {
 
             MalformedIdent(_, _) |
             MalformedClosure |
+            MalformedExpr(_) |
             PrecedenceConflict(_) |
             MultipleRecordBuilders(_) |
             UnappliedRecordBuilder(_) => true,
@@ -1559,7 +1580,7 @@ impl<'a, T: Malformed> Malformed for Collection<'a, T> {
 impl<'a> Malformed for StrLiteral<'a> {
     fn is_malformed(&self) -> bool {
         match self {
-            StrLiteral::PlainLine(_) => false,
+            StrLiteral::PlainLine(_) | StrLiteral::Raw(_) => false,
             StrLiteral::Line(segs) => segs.iter().any(|seg| seg.is_malformed()),
             StrLiteral::Block(lines) => lines
                 .iter()
@@ -1691,6 +1712,9 @@ impl<'a> Malformed for TypeDef<'a> {
 impl<'a> Malformed for AbilityMember<'a> {
     fn is_malformed(&self) -> bool {
         self.typ.is_malformed()
+            || self
+                .default
+                .map_or(false, |default| default.body.is_malformed())
     }
 }
 
@@ -1,6 +1,7 @@
 use crate::ast::{
-    AssignedField, Collection, CommentOrNewline, Defs, Expr, ExtractSpaces, Has, HasAbilities,
-    Pattern, RecordBuilderField, Spaceable, Spaces, TypeAnnotation, TypeDef, TypeHeader, ValueDef,
+    AbilityMember, AssignedField, Collection, CommentOrNewline, Defs, Expr, ExtractSpaces, Has,
+    HasAbilities, Pattern, RecordBuilderField, Spaceable, Spaces, TypeAnnotation, TypeDef,
+    TypeHeader, ValueDef,
 };
 use crate::blankspace::{
     space0_after_e, space0_around_e_no_after_indent_check, space0_around_ee, space0_before_e,
@@ -1246,7 +1247,7 @@ fn finish_parsing_alias_or_opaque<'a>(
 mod ability {
     use super::*;
     use crate::{
-        ast::{AbilityMember, Spaceable, Spaced},
+        ast::{AbilityMember, AbilityMemberDefault, Spaceable, Spaced},
         parser::EAbility,
     };
 
@@ -1269,6 +1270,7 @@ mod ability {
                 AbilityMember {
                     name: name.map_owned(Spaced::Item),
                     typ,
+                    default: None,
                 }
             }
         )
@@ -1357,6 +1359,84 @@ mod ability {
             }
         }
     }
+
+    /// Parses the default implementation of the demand for `member_name`, like
+    /// `isNotEq = \a, b -> !(isEq a b)`, which must be aligned with the demands.
+    pub fn parse_default<'a>(
+        indent: u32,
+        member_name: &'a str,
+    ) -> impl Parser<'a, AbilityMemberDefault<'a>, EAbility<'a>> {
+        move |arena: &'a Bump, state: State<'a>, _min_indent: u32| {
+            let start = state.pos();
+
+            // Anything but `member_name =` at the demand indent is the next demand, or the end
+            // of the ability definition.
+            let (_, spaces_before, state) = space0_e(EAbility::DemandName)
+                .parse(arena, state, 0)
+                .map_err(|(_, fail)| (NoProgress, fail))?;
+
+            if state.column() != indent {
+                return Err((NoProgress, EAbility::DemandName(start)));
+            }
+
+            let state = match lowercase_ident().parse(arena, state, indent) {
+                Ok((_, name, state)) if name == member_name => state,
+                _ => return Err((NoProgress, EAbility::DemandName(start))),
+            };
+
+            let state =
+                match and!(space0_e(EExpr::IndentEquals), operator()).parse(arena, state, indent) {
+                    Ok((_, (_, BinOp::Assignment), state)) => state,
+                    _ => return Err((NoProgress, EAbility::DemandName(start))),
+                };
+
+            let body_start = state.pos();
+            let (_, body, state) = space0_before_e(loc_expr(true), EExpr::IndentEnd)
+                .parse(arena, state, indent + 1)
+                .map_err(|(_, fail)| {
+                    (
+                        MadeProgress,
+                        EAbility::DefaultBody(arena.alloc(fail), body_start),
+                    )
+                })?;
+
+            let default = AbilityMemberDefault {
+                spaces_before,
+                body: arena.alloc(body),
+            };
+
+            Ok((MadeProgress, default, state))
+        }
+    }
+}
+
+/// Pushes `demand` with its default implementation, if one follows it.
+fn parse_ability_member_default<'a>(
+    demand_indent: u32,
+    mut demand: AbilityMember<'a>,
+    demands: &mut Vec<'a, AbilityMember<'a>>,
+    arena: &'a Bump,
+    state: State<'a>,
+) -> Result<State<'a>, (Progress, EExpr<'a>)> {
+    let member_name = demand.name.value.extract_spaces().item;
+    let state = match ability::parse_default(demand_indent, member_name).parse(
+        arena,
+        state.clone(),
+        demand_indent,
+    ) {
+        Ok((_, default, next_state)) => {
+            demand.default = Some(default);
+            next_state
+        }
+        Err((MadeProgress, problem)) => {
+            return Err((MadeProgress, EExpr::Ability(problem, state.pos())));
+        }
+        Err((NoProgress, _)) => state,
+    };
+
+    demands.push(demand);
+
+    Ok(state)
 }
 
 fn finish_parsing_ability_def_help<'a>(
@@ -1378,7 +1458,13 @@ fn finish_parsing_ability_def_help<'a>(
         ability::parse_demand(ability::IndentLevel::PendingMin(min_indent_for_demand))
             .parse(arena, state, min_indent_for_demand)
             .map_err(|(progress, err)| (progress, EExpr::Ability(err, start)))?;
-    demands.push(first_demand);
+    state = parse_ability_member_default(
+        demand_indent_level,
+        first_demand,
+        &mut demands,
+        arena,
+        state,
+    )?;
 
     let demand_indent = ability::IndentLevel::Exact(demand_indent_level);
     let demand_parser = ability::parse_demand(demand_indent);
@@ -1386,8 +1472,13 @@ fn finish_parsing_ability_def_help<'a>(
     loop {
         match demand_parser.parse(arena, state.clone(), min_indent_for_demand) {
             Ok((_, (_indent, demand), next_state)) => {
-                state = next_state;
-                demands.push(demand);
+                state = parse_ability_member_default(
+                    demand_indent_level,
+                    demand,
+                    &mut demands,
+                    arena,
+                    next_state,
+                )?;
             }
             Err((MadeProgress, problem)) => {
                 return Err((MadeProgress, EExpr::Ability(problem, state.pos())));
@@ -1398,7 +1489,7 @@ fn finish_parsing_ability_def_help<'a>(
         }
     }
 
-    let def_region = Region::span_across(&name.region, &demands.last().unwrap().typ.region);
+    let def_region = Region::span_across(&name.region, &demands.last().unwrap().region());
     let type_def = TypeDef::Ability {
         header: TypeHeader { name, vars: args },
         loc_has,
@@ -1924,6 +2015,7 @@ fn expr_to_pattern_help<'a>(arena: &'a Bump, expr: &Expr<'a>) -> Result<Pattern<
         | Expr::Expect(_, _)
         | Expr::Dbg(_, _)
         | Expr::MalformedClosure
+        | Expr::MalformedExpr(_)
         | Expr::PrecedenceConflict { .. }
         | Expr::MultipleRecordBuilders { .. }
         | Expr::UnappliedRecordBuilder { .. }
@@ -2032,6 +2124,214 @@ pub fn toplevel_defs<'a>() -> impl Parser<'a, Defs<'a>, EExpr<'a>> {
     }
 }
 
+/// Like [toplevel_defs], but rather than giving up at the first syntax error, skip over the
+/// top-level def it's in and carry on with the next one. A value def which gets skipped over keeps
+/// its name, with an [Expr::MalformedExpr] for a body, so the rest of the module can still refer
+/// to it. `when` branches whose bodies fail to parse get their bodies replaced the same way.
+///
+/// The syntax errors that were skipped over are available from [State::recovered_errors].
+pub fn toplevel_defs_recovering<'a>(arena: &'a Bump, state: State<'a>) -> (Defs<'a>, State<'a>) {
+    let options = ExprParseOptions {
+        accept_multi_backpassing: true,
+        check_for_arrow: true,
+    };
+    let original_bytes = state.original_bytes();
+    let state = State {
+        recovering: true,
+        ..state
+    };
+    let mut defs = Defs::default();
+
+    // Like toplevel_defs, attach the space before the first def to it, since it may be a doc comment
+    let (initial_space, mut state) = match space0_e(EExpr::IndentEnd).parse(arena, state.clone(), 0)
+    {
+        Ok((_, initial_space, state)) => (initial_space, state),
+        Err(_) => (&[][..], state),
+    };
+
+    loop {
+        let start = state.pos();
+        let mut end = original_bytes.len();
+        let mut opt_error = None;
+
+        // If the defs don't parse all the way to the end, find the start of the top-level def
+        // the error was in, and retry up to there, until everything before the error parses.
+        loop {
+            match defs_until_end(options, defs.clone(), arena, state.truncated(end), start) {
+                Ok((parsed_defs, parsed_state)) => {
+                    defs = parsed_defs;
+                    state = parsed_state;
+
+                    break;
+                }
+                Err((pos, error)) => {
+                    let before = (pos.offset as usize).clamp(start.offset as usize, end - 1);
+
+                    opt_error = Some(error);
+
+                    match recovery_point_at_or_before(original_bytes, start.offset as usize, before)
+                    {
+                        Some(def_start) if def_start > start.offset as usize => end = def_start,
+                        _ => {
+                            end = start.offset as usize;
+
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        let error = match opt_error {
+            Some(error) => error,
+            None => break,
+        };
+
+        // Skip over the def with the error, up to the next top-level def.
+        let next = recovery_point_after(original_bytes, end).unwrap_or(original_bytes.len());
+        let def_start = end + count_leading(&original_bytes[end..next], u8::is_ascii_whitespace);
+        let def_end =
+            next - count_trailing(&original_bytes[def_start..next], u8::is_ascii_whitespace);
+        let region = Region::new(
+            Position::new(def_start as u32),
+            Position::new(def_end as u32),
+        );
+
+        state = state.record_recovered(arena, region, error);
+
+        if let Some((value_def, def_region)) =
+            malformed_value_def(arena, &defs, original_bytes, def_start, def_end)
+        {
+            match value_def {
+                ValueDef::AnnotatedBody { .. } => {
+                    defs.replace_with_value_def(defs.tags.len() - 1, value_def, def_region)
+                }
+                _ => defs.push_value_def(value_def, def_region, &[], &[]),
+            }
+        }
+
+        state = state.at_line_start(original_bytes, next);
+
+        if next == original_bytes.len() {
+            break;
+        }
+    }
+
+    if !defs.is_empty() && defs.space_before[0].is_empty() {
+        defs.space_before[0] = Slice::extend_new(&mut defs.spaces, initial_space.iter().copied());
+    }
+
+    (defs, state)
+}
+
+/// Parse top-level defs until the end of the input, returning where the problem was if they
+/// don't make it there.
+fn defs_until_end<'a>(
+    options: ExprParseOptions,
+    defs: Defs<'a>,
+    arena: &'a Bump,
+    state: State<'a>,
+    start: Position,
+) -> Result<(Defs<'a>, State<'a>), (Position, parser::SyntaxError<'a>)> {
+    let to_error = |fail: EExpr<'a>| (fail.pos(), parser::SyntaxError::Expr(fail, start));
+
+    let (_, defs, state) =
+        parse_defs_end(options, 0, defs, arena, state).map_err(|(_, fail)| to_error(fail))?;
+    let (_, _, state) = space0_e(EExpr::IndentEnd)
+        .parse(arena, state, 0)
+        .map_err(|(_, fail)| to_error(fail))?;
+
+    if state.has_reached_end() {
+        Ok((defs, state))
+    } else {
+        Err((state.pos(), parser::SyntaxError::NotEndOfFile(state.pos())))
+    }
+}
+
+/// If the def with a syntax error in it looks like `name = ...`, a def binding the name to an
+/// [Expr::MalformedExpr]. If the def just before it is an annotation for that name, they get
+/// joined into a [ValueDef::AnnotatedBody], so the name still has a known type.
+fn malformed_value_def<'a>(
+    arena: &'a Bump,
+    defs: &Defs<'a>,
+    original_bytes: &'a [u8],
+    def_start: usize,
+    def_end: usize,
+) -> Option<(ValueDef<'a>, Region)> {
+    let def_bytes = &original_bytes[def_start..def_end];
+    let name_len = count_leading(def_bytes, |&b| b.is_ascii_alphanumeric() || b == b'_');
+    let name = std::str::from_utf8(&def_bytes[..name_len]).ok()?;
+
+    if !name.starts_with(|c: char| c.is_ascii_lowercase()) || keyword::KEYWORDS.contains(&name) {
+        return None;
+    }
+
+    let equals = name_len + count_leading(&def_bytes[name_len..], |&b| b == b' ');
+
+    if def_bytes.get(equals) != Some(&b'=') || def_bytes.get(equals + 1) == Some(&b'=') {
+        return None;
+    }
+
+    let body_start = equals + 1 + count_leading(&def_bytes[equals + 1..], u8::is_ascii_whitespace);
+    let body_src = std::str::from_utf8(&def_bytes[body_start..]).ok()?;
+    let position = |offset: usize| Position::new((def_start + offset) as u32);
+
+    let loc_pattern = Loc::at(
+        Region::new(position(0), position(name_len)),
+        Pattern::Identifier(name),
+    );
+    let loc_body = Loc::at(
+        Region::new(position(body_start), position(def_bytes.len())),
+        Expr::MalformedExpr(body_src),
+    );
+    let region = Region::span_across(&loc_pattern.region, &loc_body.region);
+
+    match defs.last() {
+        Some(Err(ValueDef::Annotation(ann_pattern, ann_type)))
+            if ann_pattern.value.extract_spaces().item == Pattern::Identifier(name) =>
+        {
+            let value_def = ValueDef::AnnotatedBody {
+                ann_pattern: arena.alloc(*ann_pattern),
+                ann_type: arena.alloc(*ann_type),
+                comment: None,
+                body_pattern: arena.alloc(loc_pattern),
+                body_expr: arena.alloc(loc_body),
+            };
+
+            Some((value_def, Region::span_across(&ann_pattern.region, &region)))
+        }
+        _ => Some((
+            ValueDef::Body(arena.alloc(loc_pattern), arena.alloc(loc_body)),
+            region,
+        )),
+    }
+}
+
+/// Whether a top-level def could begin at this offset: the start of a line that isn't
+/// indented, blank, or a comment.
+fn is_recovery_point(bytes: &[u8], offset: usize) -> bool {
+    (offset == 0 || bytes[offset - 1] == b'\n')
+        && matches!(bytes.get(offset), Some(b) if !b.is_ascii_whitespace() && *b != b'#')
+}
+
+fn recovery_point_at_or_before(bytes: &[u8], from: usize, offset: usize) -> Option<usize> {
+    (from..=offset)
+        .rev()
+        .find(|&offset| is_recovery_point(bytes, offset))
+}
+
+fn recovery_point_after(bytes: &[u8], offset: usize) -> Option<usize> {
+    (offset + 1..bytes.len()).find(|&offset| is_recovery_point(bytes, offset))
+}
+
+fn count_leading(bytes: &[u8], pred: impl Fn(&u8) -> bool) -> usize {
+    bytes.iter().take_while(|b| pred(b)).count()
+}
+
+fn count_trailing(bytes: &[u8], pred: impl Fn(&u8) -> bool) -> usize {
+    bytes.iter().rev().take_while(|b| pred(b)).count()
+}
+
 // PARSER HELPERS
 
 fn closure_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EClosure<'a>> {
@@ -2122,8 +2422,8 @@ mod when {
             let original_indent = pattern_indent_level;
 
             // Parse the first "->" and the expression after it.
-            let (_, loc_first_expr, mut state) =
-                branch_result(original_indent + 1).parse(arena, state, original_indent + 1)?;
+            let (loc_first_expr, mut state) =
+                branch_result_recovering(original_indent, arena, state)?;
 
             // Record this as the first branch, then optionally parse additional branches.
             branches.push(arena.alloc(WhenBranch {
@@ -2132,37 +2432,33 @@ mod when {
                 guard: loc_first_guard,
             }));
 
-            let branch_parser = map!(
-                and!(
-                    then(
-                        branch_alternatives(options, Some(pattern_indent_level)),
-                        move |_arena, state, _, ((indent_column, loc_patterns), loc_guard)| {
-                            if pattern_indent_level == indent_column {
-                                Ok((MadeProgress, (loc_patterns, loc_guard), state))
-                            } else {
-                                let indent = pattern_indent_level - indent_column;
-                                Err((MadeProgress, EWhen::PatternAlignment(indent, state.pos())))
-                            }
-                        },
-                    ),
-                    branch_result(original_indent + 1)
-                ),
-                |((patterns, guard), expr)| {
-                    let patterns: Vec<'a, _> = patterns;
-                    WhenBranch {
-                        patterns: patterns.into_bump_slice(),
-                        value: expr,
-                        guard,
+            let branch_patterns_parser = then(
+                branch_alternatives(options, Some(pattern_indent_level)),
+                move |_arena, state, _, ((indent_column, loc_patterns), loc_guard)| {
+                    if pattern_indent_level == indent_column {
+                        Ok((MadeProgress, (loc_patterns, loc_guard), state))
+                    } else {
+                        let indent = pattern_indent_level - indent_column;
+                        Err((MadeProgress, EWhen::PatternAlignment(indent, state.pos())))
                     }
-                }
+                },
             );
 
             while !state.bytes().is_empty() {
-                match branch_parser.parse(arena, state.clone(), min_indent) {
-                    Ok((_, next_output, next_state)) => {
+                match branch_patterns_parser.parse(arena, state.clone(), min_indent) {
+                    Ok((_, (patterns, guard), next_state)) => {
+                        let patterns: Vec<'a, _> = patterns;
+                        let (value, next_state) =
+                            branch_result_recovering(original_indent, arena, next_state)
+                                .map_err(|(_, problem)| (MadeProgress, problem))?;
+
                         state = next_state;
 
-                        branches.push(arena.alloc(next_output));
+                        branches.push(arena.alloc(WhenBranch {
+                            patterns: patterns.into_bump_slice(),
+                            value,
+                            guard,
+                        }));
                     }
                     Err((MadeProgress, problem)) => {
                         return Err((MadeProgress, problem));
@@ -2289,6 +2585,60 @@ mod when {
         }
     }
 
+    /// Like [branch_result], but when recovering from syntax errors, a body which fails to parse
+    /// gets skipped over, up to the next line that is indented no more than the branch's
+    /// patterns, and replaced by an [Expr::MalformedExpr].
+    fn branch_result_recovering<'a>(
+        pattern_indent_level: u32,
+        arena: &'a Bump,
+        state: State<'a>,
+    ) -> Result<(Loc<Expr<'a>>, State<'a>), (Progress, EWhen<'a>)> {
+        let indent = pattern_indent_level + 1;
+
+        match branch_result(indent).parse(arena, state.clone(), indent) {
+            Ok((_, loc_expr, state)) => Ok((loc_expr, state)),
+            Err((MadeProgress, fail)) if state.recovering && state.bytes().starts_with(b"->") => {
+                let bytes = state.original_bytes();
+                let arrow = state.pos();
+                let after_arrow = arrow.offset as usize + 2;
+                let resume = next_line_indented_at_most(bytes, after_arrow, pattern_indent_level)
+                    .unwrap_or(bytes.len());
+                let body_start = after_arrow
+                    + count_leading(&bytes[after_arrow..resume], u8::is_ascii_whitespace);
+                let body_end =
+                    resume - count_trailing(&bytes[body_start..resume], u8::is_ascii_whitespace);
+                let region = Region::new(
+                    Position::new(body_start as u32),
+                    Position::new(body_end as u32),
+                );
+                let body_src = std::str::from_utf8(&bytes[body_start..body_end]).unwrap_or("");
+                let error = parser::SyntaxError::Expr(EExpr::When(fail, arrow), arrow);
+                let state = state
+                    .record_recovered(arena, region, error)
+                    .at_line_start(bytes, resume);
+
+                Ok((Loc::at(region, Expr::MalformedExpr(body_src)), state))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// The start of the first line after the given offset which is indented by at most
+    /// the given amount, skipping over blank lines and comments.
+    fn next_line_indented_at_most(bytes: &[u8], offset: usize, indent: u32) -> Option<usize> {
+        (offset..bytes.len())
+            .filter(|&offset| bytes[offset - 1] == b'\n')
+            .find(|&line_start| {
+                let line = &bytes[line_start..];
+                let line_indent = count_leading(line, |&b| b == b' ');
+
+                match line.get(line_indent) {
+                    None | Some(b'\n' | b'\r' | b'#') => false,
+                    Some(_) => line_indent as u32 <= indent,
+                }
+            })
+    }
+
     /// Parsing the righthandside of a branch in a when conditional.
     fn branch_result<'a>(indent: u32) -> impl Parser<'a, Loc<Expr<'a>>, EWhen<'a>> {
         move |arena, state, _min_indent| {
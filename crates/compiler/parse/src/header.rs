@@ -344,6 +344,10 @@ pub fn package_name<'a>() -> impl Parser<'a, PackageName<'a>, EPackageName<'a>>
             StrLiteral::PlainLine(text) => Ok((progress, PackageName(text), state)),
             StrLiteral::Line(_) => Err((progress, EPackageName::Escapes(text.region.start()))),
             StrLiteral::Block(_) => Err((progress, EPackageName::Multiline(text.region.start()))),
+            StrLiteral::Raw(raw) if raw.contains('\n') => {
+                Err((progress, EPackageName::Multiline(text.region.start())))
+            }
+            StrLiteral::Raw(raw) => Ok((progress, PackageName(raw), state)),
         },
     )
 }
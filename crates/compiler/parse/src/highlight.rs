@@ -115,7 +115,8 @@ fn highlight_inner<'a>(
                         fast_forward_to(&mut state, tokens, start, |c| c == b'\n');
                     }
                 }
-                '"' | '\'' => {
+                // `r"` begins a raw string, rather than an identifier
+                '"' | '\'' | 'r' if b != 'r' || state.bytes().starts_with(b"r\"") => {
                     if let Ok((_, item, new_state)) =
                         parse_str_like_literal().parse(arena, state.clone(), 0)
                     {
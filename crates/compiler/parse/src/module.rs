@@ -16,7 +16,7 @@ use crate::parser::{
 use crate::state::State;
 use crate::string_literal::{self, parse_str_literal};
 use crate::type_annotation;
-use roc_region::all::{Loc, Position};
+use roc_region::all::{Loc, Position, Region};
 
 fn end_of_file<'a>() -> impl Parser<'a, (), SyntaxError<'a>> {
     |_arena, state: State<'a>, _min_indent: u32| {
@@ -36,6 +36,23 @@ pub fn module_defs<'a>() -> impl Parser<'a, Defs<'a>, SyntaxError<'a>> {
     )
 }
 
+/// Like `module_defs`, except that a def which fails to parse doesn't stop the others from
+/// being parsed. Alongside the defs, returns every syntax error that was recovered from.
+pub fn module_defs_recovering<'a>(
+    arena: &'a bumpalo::Bump,
+    state: State<'a>,
+) -> (Defs<'a>, std::vec::Vec<(Region, SyntaxError<'a>)>) {
+    // Most modules parse without errors, so only fall back on recovery when we have to.
+    match module_defs().parse(arena, state.clone(), 0) {
+        Ok((_, defs, _)) => (defs, std::vec::Vec::new()),
+        Err(_) => {
+            let (defs, state) = crate::expr::toplevel_defs_recovering(arena, state);
+
+            (defs, state.recovered_errors())
+        }
+    }
+}
+
 pub fn parse_header<'a>(
     arena: &'a bumpalo::Bump,
     state: State<'a>,
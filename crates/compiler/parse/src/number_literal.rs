@@ -63,11 +63,28 @@ fn chomp_number_base<'a>(
     bytes: &'a [u8],
     state: State<'a>,
 ) -> ParseResult<'a, NumLiteral<'a>, ENumber> {
-    let (_is_float, chomped) = chomp_number(bytes);
+    let (is_float, chomped) = match base {
+        Base::Hex => chomp_hex_number(bytes),
+        _ => chomp_number(bytes),
+    };
+
+    let prefix_len = 2 + is_negative as usize;
+
+    if is_float {
+        // Hex floats like `0x1.8p3` keep their prefix, so canonicalization can tell them apart
+        let string =
+            unsafe { std::str::from_utf8_unchecked(&state.bytes()[..chomped + prefix_len]) };
+
+        return Ok((
+            Progress::MadeProgress,
+            NumLiteral::Float(string),
+            state.advance(chomped + prefix_len),
+        ));
+    }
 
     let string = unsafe { std::str::from_utf8_unchecked(&bytes[..chomped]) };
 
-    let new = state.advance(chomped + 2 + is_negative as usize);
+    let new = state.advance(chomped + prefix_len);
 
     Ok((
         Progress::MadeProgress,
@@ -159,3 +176,36 @@ fn chomp_number(mut bytes: &[u8]) -> (bool, usize) {
     // therefore we parsed all of the bytes in the input
     (is_float, start_bytes_len)
 }
+
+/// Like `chomp_number`, except `e` is always a hex digit, and the exponent
+/// of a hex float like `0x1.8p-3` is introduced by `p` instead.
+fn chomp_hex_number(mut bytes: &[u8]) -> (bool, usize) {
+    let start_bytes_len = bytes.len();
+    let mut is_float = false;
+
+    while let Some(byte) = bytes.first() {
+        match byte {
+            b'.' => {
+                // skip, fix multiple `.`s in canonicalization
+                is_float = true;
+                bytes = &bytes[1..];
+            }
+            b'p' | b'P' => {
+                is_float = true;
+
+                match bytes.get(1) {
+                    Some(b'-' | b'+') => bytes = &bytes[2..],
+                    _ => bytes = &bytes[1..],
+                }
+            }
+            _ if *byte == b'_' || byte.is_ascii_alphanumeric() => {
+                bytes = &bytes[1..];
+            }
+            _ => {
+                return (is_float, start_bytes_len - bytes.len());
+            }
+        }
+    }
+
+    (is_float, start_bytes_len)
+}
@@ -371,6 +371,58 @@ pub enum EExpr<'a> {
     IndentEnd(Position),
 }
 
+impl<'a> EExpr<'a> {
+    /// Where the expression that failed to parse begins
+    pub fn pos(&self) -> Position {
+        use EExpr::*;
+
+        match self {
+            TrailingOperator(pos)
+            | Start(pos)
+            | End(pos)
+            | BadExprEnd(pos)
+            | Space(_, pos)
+            | Dot(pos)
+            | Access(pos)
+            | UnaryNot(pos)
+            | UnaryNegate(pos)
+            | BadOperator(_, pos)
+            | DefMissingFinalExpr(pos)
+            | DefMissingFinalExpr2(_, pos)
+            | Type(_, pos)
+            | Pattern(_, pos)
+            | Ability(_, pos)
+            | IndentDefBody(pos)
+            | IndentEquals(pos)
+            | IndentAnnotation(pos)
+            | Equals(pos)
+            | Colon(pos)
+            | DoubleColon(pos)
+            | Ident(pos)
+            | ElmStyleFunction(_, pos)
+            | MalformedPattern(pos)
+            | QualifiedTag(pos)
+            | BackpassComma(pos)
+            | BackpassArrow(pos)
+            | When(_, pos)
+            | If(_, pos)
+            | Expect(_, pos)
+            | Dbg(_, pos)
+            | Closure(_, pos)
+            | Underscore(pos)
+            | Crash(pos)
+            | InParens(_, pos)
+            | Record(_, pos)
+            | Str(_, pos)
+            | Number(_, pos)
+            | List(_, pos)
+            | IndentStart(pos)
+            | IndentEnd(pos) => *pos,
+            OptionalValueInRecordBuilder(region) | RecordUpdateBuilder(region) => region.start(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ENumber {
     End,
@@ -491,6 +543,7 @@ pub enum EAbility<'a> {
     DemandAlignment(i32, Position),
     DemandName(Position),
     DemandColon(Position),
+    DefaultBody(&'a EExpr<'a>, Position),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
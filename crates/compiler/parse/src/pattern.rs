@@ -77,6 +77,8 @@ fn loc_pattern_help_help<'a>() -> impl Parser<'a, Loc<Pattern<'a>>, EPattern<'a>
     one_of!(
         specialize(EPattern::PInParens, loc_pattern_in_parens_help()),
         loc!(underscore_pattern_help()),
+        // Before identifiers, so the `r` in a raw string like r"\n" isn't parsed as one
+        loc!(string_like_pattern_help()),
         loc_ident_pattern_help(true),
         loc!(specialize(
             EPattern::Record,
@@ -84,7 +86,6 @@ fn loc_pattern_help_help<'a>() -> impl Parser<'a, Loc<Pattern<'a>>, EPattern<'a>
         )),
         loc!(specialize(EPattern::List, list_pattern_help())),
         loc!(number_pattern_help()),
-        loc!(string_like_pattern_help()),
     )
 }
 
@@ -171,13 +172,13 @@ fn loc_parse_tag_pattern_arg<'a>() -> impl Parser<'a, Loc<Pattern<'a>>, EPattern
     one_of!(
         specialize(EPattern::PInParens, loc_pattern_in_parens_help()),
         loc!(underscore_pattern_help()),
+        loc!(string_like_pattern_help()),
         // Make sure `Foo Bar 1` is parsed as `Foo (Bar) 1`, and not `Foo (Bar 1)`
         loc_ident_pattern_help(false),
         loc!(specialize(
             EPattern::Record,
             crate::pattern::record_pattern_help()
         )),
-        loc!(string_like_pattern_help()),
         loc!(number_pattern_help())
     )
 }
@@ -1,7 +1,8 @@
+use bumpalo::Bump;
 use roc_region::all::{Position, Region};
 use std::fmt;
 
-use crate::parser::Progress;
+use crate::parser::{Progress, SyntaxError};
 
 /// A position in a source file.
 // NB: [Copy] is explicitly NOT derived to reduce the chance of bugs due to accidentally re-using
@@ -21,6 +22,21 @@ pub struct State<'a> {
 
     /// Position of the first non-whitespace character on the current line
     pub(crate) line_start_after_whitespace: Position,
+
+    /// Whether to skip over syntax errors where possible, rather than stopping at the first one.
+    /// See [crate::expr::toplevel_defs_recovering].
+    pub(crate) recovering: bool,
+
+    /// The syntax errors skipped over so far, most recent first. These live in the state so that
+    /// when a parser gets backtracked over, the errors it recovered from are forgotten along with it.
+    recovered: Option<&'a RecoveredError<'a>>,
+}
+
+struct RecoveredError<'a> {
+    /// The source that was skipped over to recover from this error
+    region: Region,
+    error: SyntaxError<'a>,
+    previous: Option<&'a RecoveredError<'a>>,
 }
 
 impl<'a> State<'a> {
@@ -33,6 +49,61 @@ impl<'a> State<'a> {
             // Technically not correct.
             // We don't know the position of the first non-whitespace character yet.
             line_start_after_whitespace: Position::zero(),
+
+            recovering: false,
+            recovered: None,
+        }
+    }
+
+    /// Record a syntax error which was recovered from by skipping over the given region.
+    pub(crate) fn record_recovered(
+        mut self,
+        arena: &'a Bump,
+        region: Region,
+        error: SyntaxError<'a>,
+    ) -> State<'a> {
+        self.recovered = Some(arena.alloc(RecoveredError {
+            region,
+            error,
+            previous: self.recovered,
+        }));
+
+        self
+    }
+
+    /// Every syntax error recovered from so far, in source order, along with the region
+    /// which was skipped over to recover from it.
+    pub fn recovered_errors(&self) -> std::vec::Vec<(Region, SyntaxError<'a>)> {
+        let mut errors = std::vec::Vec::new();
+        let mut next = self.recovered;
+
+        while let Some(recovered) = next {
+            errors.push((recovered.region, recovered.error.clone()));
+            next = recovered.previous;
+        }
+
+        errors.reverse();
+
+        errors
+    }
+
+    /// The same state, but with the input cut off at the given offset.
+    pub(crate) fn truncated(&self, end: usize) -> State<'a> {
+        State {
+            original_bytes: &self.original_bytes[..end],
+            ..self.clone()
+        }
+    }
+
+    /// The same state, but reading from the given input (undoing [State::truncated]),
+    /// and moved to the given offset, which must be the start of a line.
+    pub(crate) fn at_line_start(&self, original_bytes: &'a [u8], offset: usize) -> State<'a> {
+        State {
+            original_bytes,
+            offset,
+            line_start: Position::new(offset as u32),
+            line_start_after_whitespace: Position::new(offset as u32),
+            ..self.clone()
         }
     }
 
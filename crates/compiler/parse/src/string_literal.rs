@@ -8,6 +8,7 @@ use crate::parser::{
 use crate::state::State;
 use bumpalo::collections::vec::Vec;
 use bumpalo::Bump;
+use roc_region::all::Position;
 
 /// One or more ASCII hex digits. (Useful when parsing unicode escape codes,
 /// which must consist entirely of ASCII hex digits.)
@@ -93,6 +94,10 @@ pub fn parse_str_like_literal<'a>() -> impl Parser<'a, StrLikeLiteral<'a>, EStri
 
         let start_state;
 
+        if state.bytes().starts_with(b"r\"") {
+            return parse_raw_str_literal(arena, state);
+        }
+
         if state.consume_mut("\"\"\"") {
             start_state = state.clone();
 
@@ -464,3 +469,104 @@ pub fn parse_str_like_literal<'a>() -> impl Parser<'a, StrLikeLiteral<'a>, EStri
         ))
     }
 }
+
+/// A raw string, like `r"C:\Users"`, or a multi-line one starting with `r"""`. Backslashes in
+/// these don't begin escapes or interpolations; everything up to the closing quote is taken as-is,
+/// except that multi-line raw strings get their indentation removed like other block strings.
+fn parse_raw_str_literal<'a>(
+    arena: &'a Bump,
+    mut state: State<'a>,
+) -> Result<(Progress, StrLikeLiteral<'a>, State<'a>), (Progress, EString<'a>)> {
+    let indent = state.column();
+
+    // skip the `r`
+    state.advance_mut(1);
+
+    if state.consume_mut("\"\"\"") {
+        let start_state = state.clone();
+        let bytes = state.bytes();
+        let len = match bytes.windows(3).position(|window| window == b"\"\"\"") {
+            Some(len) => len,
+            None => return Err((MadeProgress, EString::EndlessMultiLine(start_state.pos()))),
+        };
+        let contents = utf8(state.clone(), &bytes[..len])?;
+        let string = dedent_raw_block(arena, contents, indent, state.pos().offset)?;
+
+        Ok((
+            MadeProgress,
+            StrLikeLiteral::Str(StrLiteral::Raw(string)),
+            state.advance(len + 3),
+        ))
+    } else {
+        // skip the opening `"`
+        state.advance_mut(1);
+
+        let start_state = state.clone();
+        let bytes = state.bytes();
+
+        match bytes.iter().position(|&b| b == b'"' || b == b'\n') {
+            Some(len) if bytes[len] == b'"' => {
+                let string = utf8(state.clone(), &bytes[..len])?;
+
+                Ok((
+                    MadeProgress,
+                    StrLikeLiteral::Str(StrLiteral::Raw(string)),
+                    state.advance(len + 1),
+                ))
+            }
+            _ => Err((MadeProgress, EString::EndlessSingleLine(start_state.pos()))),
+        }
+    }
+}
+
+/// Remove the indentation from each line of a multi-line raw string, along with the newline
+/// right after the opening `r"""` and the one before a closing `"""` on its own line.
+fn dedent_raw_block<'a>(
+    arena: &'a Bump,
+    contents: &'a str,
+    indent: u32,
+    start_offset: u32,
+) -> Result<&'a str, (Progress, EString<'a>)> {
+    let (first_line, rest) = match contents.split_once('\n') {
+        Some(pair) => pair,
+        None => return Ok(contents),
+    };
+
+    let mut string = bumpalo::collections::String::new_in(arena);
+    let mut line_offset = first_line.len() + 1;
+    let mut lines = rest.split('\n').peekable();
+
+    // The first line begins right after the opening quotes, so it has no indentation to remove.
+    if !first_line.is_empty() {
+        string.push_str(first_line);
+        string.push('\n');
+    }
+
+    while let Some(line) = lines.next() {
+        let is_last = lines.peek().is_none();
+        let spaces = line.bytes().take_while(|&b| b == b' ').count();
+
+        if is_last && spaces == line.len() {
+            // This is the indentation before the closing quotes, so drop it and its newline.
+            string.pop();
+
+            break;
+        }
+
+        if spaces < indent as usize && spaces < line.len() {
+            let pos = Position::new(start_offset + (line_offset + spaces) as u32);
+
+            return Err((MadeProgress, EString::MultilineInsufficientIndent(pos)));
+        }
+
+        string.push_str(&line[spaces.min(indent as usize)..]);
+
+        if !is_last {
+            string.push('\n');
+        }
+
+        line_offset += line.len() + 1;
+    }
+
+    Ok(string.into_bump_str())
+}
@@ -22,11 +22,11 @@ mod test_parse {
     use roc_parse::ast::StrSegment::*;
     use roc_parse::ast::{self, EscapedChar};
     use roc_parse::ast::{CommentOrNewline, StrLiteral::*};
-    use roc_parse::module::module_defs;
+    use roc_parse::module::{module_defs, module_defs_recovering};
     use roc_parse::parser::{Parser, SyntaxError};
     use roc_parse::state::State;
     use roc_parse::test_helpers::parse_expr_with;
-    use roc_region::all::{Loc, Region};
+    use roc_region::all::{Loc, Position, Region};
     use std::{f64, i64};
 
     fn assert_parses_to<'a>(input: &'a str, expected_expr: Expr<'a>) {
@@ -317,6 +317,60 @@ mod test_parse {
         }
     }
 
+    #[test]
+    fn recover_from_error_in_toplevel_def() {
+        let arena = &Bump::new();
+        let src = indoc!(
+            r#"
+            x : I64
+            x = 1 +
+
+            y = 2
+            "#
+        );
+
+        let (defs, errors) = module_defs_recovering(arena, State::new(src.as_bytes()));
+
+        assert_eq!(defs.len(), 2);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].0,
+            Region::new(Position::new(8), Position::new(15))
+        );
+
+        match &defs.value_defs[0] {
+            ast::ValueDef::AnnotatedBody { body_expr, .. } => {
+                assert_eq!(body_expr.value, MalformedExpr("1 +"));
+            }
+            other => panic!("Expected an annotated body, but got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn recover_from_error_in_when_branch() {
+        let arena = &Bump::new();
+        let src = indoc!(
+            r#"
+            f = \x ->
+                when x is
+                    A -> if x then 1
+                    B -> 2
+
+            y = 3
+            "#
+        );
+
+        let (defs, errors) = module_defs_recovering(arena, State::new(src.as_bytes()));
+
+        assert_eq!(defs.len(), 2);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0].1,
+            SyntaxError::Expr(roc_parse::parser::EExpr::When(_, _), _)
+        ));
+        assert!(format!("{:?}", defs).contains("MalformedExpr(\"if x then 1\")"));
+    }
+
     #[test]
     fn parse_expr_size() {
         assert_eq!(std::mem::size_of::<roc_parse::ast::Expr>(), 40);
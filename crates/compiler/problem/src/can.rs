@@ -40,6 +40,8 @@ pub enum Problem {
     UnusedModuleImport(ModuleId, Region),
     ExposedButNotDefined(Symbol),
     UnknownGeneratesWith(Loc<Ident>),
+    /// A hosted function uses `Pure` somewhere other than around the result of a host intrinsic
+    MisplacedPure(Symbol, Region),
     /// First symbol is the name of the closure with that argument
     /// Bool is whether the closure is anonymous
     /// Second symbol is the name of the argument that is unused
@@ -54,7 +56,7 @@ pub enum Problem {
         shadow: Loc<Ident>,
         kind: ShadowKind,
     },
-    CyclicAlias(Symbol, Region, Vec<Symbol>, AliasKind),
+    CyclicAlias(Symbol, Region, Vec<(Symbol, Region)>, AliasKind),
     BadRecursion(Vec<CycleEntry>),
     PhantomTypeArgument {
         typ: Symbol,
@@ -167,6 +169,14 @@ pub enum Problem {
         original: Region,
         duplicate: Region,
     },
+    /// Two imports bring in different implementations of the same ability member for the same
+    /// opaque type, which means two copies of the module defining the type were loaded
+    ConflictingImpls {
+        ability_member: Symbol,
+        opaque: Symbol,
+        first_import: Region,
+        second_import: Region,
+    },
     NotAnAbility(Region),
     ImplementsNonRequired {
         region: Region,
@@ -207,10 +217,25 @@ pub enum Problem {
     OverAppliedCrash {
         region: Region,
     },
+    /// A `DefaultInt` or `DefaultFrac` alias that doesn't name a builtin integer or fraction type,
+    /// respectively, so it can't choose the module's default for number literals.
+    InvalidNumLiteralDefault {
+        alias: Symbol,
+        region: Region,
+        expects_int: bool,
+    },
     FileProblem {
         filename: PathBuf,
         error: io::ErrorKind,
     },
+    /// A syntax error which the parser recovered from by skipping over the given region.
+    /// Syntax errors borrow from the source, so the report for this one is rendered as
+    /// plain text as soon as the module is parsed.
+    SyntaxError {
+        title: String,
+        region: Region,
+        message: String,
+    },
 }
 
 impl Problem {
@@ -223,6 +248,7 @@ impl Problem {
             Problem::UnusedModuleImport(_, _) => Warning,
             Problem::ExposedButNotDefined(_) => RuntimeError,
             Problem::UnknownGeneratesWith(_) => RuntimeError,
+            Problem::MisplacedPure(_, _) => RuntimeError,
             Problem::UnusedArgument(_, _, _, _) => Warning,
             Problem::UnusedBranchDef(_, _) => Warning,
             Problem::PrecedenceProblem(_) => RuntimeError,
@@ -260,6 +286,7 @@ impl Problem {
             Problem::QualifiedAbilityImpl { .. } => RuntimeError,
             Problem::AbilityImplNotIdent { .. } => RuntimeError,
             Problem::DuplicateImpl { .. } => Warning, // First impl is used at runtime
+            Problem::ConflictingImpls { .. } => RuntimeError,
             Problem::NotAnAbility(_) => Warning,
             Problem::ImplementsNonRequired { .. } => Warning,
             Problem::DoesNotImplementAbility { .. } => RuntimeError,
@@ -275,8 +302,11 @@ impl Problem {
             // injecting a crash message
             Problem::UnappliedCrash { .. } => RuntimeError,
             Problem::OverAppliedCrash { .. } => RuntimeError,
+            // The usual defaults are used instead
+            Problem::InvalidNumLiteralDefault { .. } => Warning,
             Problem::DefsOnlyUsedInRecursion(_, _) => Warning,
             Problem::FileProblem { .. } => Fatal,
+            Problem::SyntaxError { .. } => RuntimeError,
         }
     }
 
@@ -296,6 +326,7 @@ impl Problem {
             | Problem::UnusedImport(_, region)
             | Problem::UnusedModuleImport(_, region)
             | Problem::UnknownGeneratesWith(Loc { region, .. })
+            | Problem::MisplacedPure(_, region)
             | Problem::UnusedArgument(_, _, _, region)
             | Problem::UnusedBranchDef(_, region)
             | Problem::PrecedenceProblem(PrecedenceProblem::BothNonAssociative(region, _, _))
@@ -352,6 +383,7 @@ impl Problem {
             | Problem::RuntimeError(RuntimeError::MalformedIdentifier(_, _, region))
             | Problem::RuntimeError(RuntimeError::MalformedTypeName(_, region))
             | Problem::RuntimeError(RuntimeError::MalformedClosure(region))
+            | Problem::RuntimeError(RuntimeError::MalformedExpr(region))
             | Problem::RuntimeError(RuntimeError::InvalidRecordUpdate { region })
             | Problem::RuntimeError(RuntimeError::InvalidFloat(_, region, _))
             | Problem::RuntimeError(RuntimeError::InvalidInt(_, _, region, _))
@@ -395,6 +427,10 @@ impl Problem {
             | Problem::DuplicateImpl {
                 original: region, ..
             }
+            | Problem::ConflictingImpls {
+                second_import: region,
+                ..
+            }
             | Problem::NotAnAbility(region)
             | Problem::ImplementsNonRequired { region, .. }
             | Problem::DoesNotImplementAbility { region, .. }
@@ -412,6 +448,8 @@ impl Problem {
             | Problem::UnnecessaryOutputWildcard { region }
             | Problem::OverAppliedCrash { region }
             | Problem::UnappliedCrash { region }
+            | Problem::InvalidNumLiteralDefault { region, .. }
+            | Problem::SyntaxError { region, .. }
             | Problem::DefsOnlyUsedInRecursion(_, region) => Some(*region),
             Problem::RuntimeError(RuntimeError::CircularDef(cycle_entries))
             | Problem::BadRecursion(cycle_entries) => {
@@ -560,6 +598,8 @@ pub enum RuntimeError {
     MalformedIdentifier(Box<str>, roc_parse::ident::BadIdent, Region),
     MalformedTypeName(Box<str>, Region),
     MalformedClosure(Region),
+    /// Source which failed to parse; the syntax error is reported separately
+    MalformedExpr(Region),
     InvalidRecordUpdate {
         region: Region,
     },
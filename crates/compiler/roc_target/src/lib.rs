@@ -71,6 +71,10 @@ impl TargetInfo {
         self.architecture.ptr_alignment_bytes()
     }
 
+    pub const fn endianness(&self) -> Endianness {
+        self.architecture.endianness()
+    }
+
     pub const fn default_aarch64() -> Self {
         TargetInfo {
             architecture: Architecture::Aarch64,
@@ -112,12 +116,37 @@ pub enum PtrWidth {
     Bytes8 = 8,
 }
 
+/// The order in which the bytes of a number are stored in memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Endianness {
+    /// The least significant byte comes first
+    Little,
+    /// The most significant byte comes first
+    Big,
+}
+
+impl Endianness {
+    /// Reads `bytes`, as they are stored in memory, as an unsigned integer.
+    /// There must be at most 8 of them.
+    pub fn read_uint(&self, bytes: &[u8]) -> u64 {
+        debug_assert!(bytes.len() <= 8);
+
+        let read = |value: u64, byte: &u8| (value << 8) | *byte as u64;
+
+        match self {
+            Endianness::Little => bytes.iter().rev().fold(0, read),
+            Endianness::Big => bytes.iter().fold(0, read),
+        }
+    }
+}
+
 /// These should be sorted alphabetically!
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter, EnumCount)]
 #[repr(u8)]
 pub enum Architecture {
     Aarch32,
     Aarch64,
+    S390x,
     Wasm32,
     X86_32,
     X86_64,
@@ -128,11 +157,20 @@ impl Architecture {
         use Architecture::*;
 
         match self {
-            X86_64 | Aarch64 => PtrWidth::Bytes8,
+            X86_64 | Aarch64 | S390x => PtrWidth::Bytes8,
             X86_32 | Aarch32 | Wasm32 => PtrWidth::Bytes4,
         }
     }
 
+    pub const fn endianness(&self) -> Endianness {
+        use Architecture::*;
+
+        match self {
+            X86_64 | X86_32 | Aarch64 | Aarch32 | Wasm32 => Endianness::Little,
+            S390x => Endianness::Big,
+        }
+    }
+
     pub const fn ptr_alignment_bytes(&self) -> usize {
         self.ptr_width() as usize
     }
@@ -146,6 +184,7 @@ impl From<target_lexicon::Architecture> for Architecture {
             target_lexicon::Architecture::Aarch64(_) => Architecture::Aarch64,
             target_lexicon::Architecture::Arm(_) => Architecture::Aarch32,
             target_lexicon::Architecture::Wasm32 => Architecture::Wasm32,
+            target_lexicon::Architecture::S390x => Architecture::S390x,
             _ => unreachable!("unsupported architecture"),
         }
     }
@@ -159,6 +198,7 @@ pub const MACOS_X86_64_TARGET_STR: &str = "macos-x86_64";
 pub const WINDOWS_X86_64_TARGET_STR: &str = "windows-x86_64";
 pub const WINDOWS_X86_32_TARGET_STR: &str = "windows-x86_32";
 pub const WIDNOWS_ARM64_TARGET_STR: &str = "windows-arm64";
+pub const LINUX_S390X_TARGET_STR: &str = "linux-s390x";
 
 pub fn get_target_triple_str(target: &target_lexicon::Triple) -> Option<&'static str> {
     match target {
@@ -201,6 +241,11 @@ pub fn get_target_triple_str(target: &target_lexicon::Triple) -> Option<&'static
             architecture: target_lexicon::Architecture::Aarch64(_),
             ..
         } => Some(WIDNOWS_ARM64_TARGET_STR),
+        target_lexicon::Triple {
+            operating_system: target_lexicon::OperatingSystem::Linux,
+            architecture: target_lexicon::Architecture::S390x,
+            ..
+        } => Some(LINUX_S390X_TARGET_STR),
         _ => None,
     }
 }
@@ -9,13 +9,13 @@ use roc_error_macros::internal_error;
 use roc_module::symbol::{ModuleId, Symbol};
 use roc_region::all::{Loc, Region};
 use roc_solve_problem::{
-    NotDerivableContext, NotDerivableDecode, NotDerivableEncode, NotDerivableEq, TypeError,
-    UnderivableReason, Unfulfilled,
+    NotDerivableContext, NotDerivableDecode, NotDerivableEncode, NotDerivableEnum, NotDerivableEq,
+    TypeError, UnderivableReason, Unfulfilled,
 };
 use roc_types::num::NumericRange;
 use roc_types::subs::{
     instantiate_rigids, Content, FlatType, GetSubsSlice, Rank, RecordFields, Subs, SubsSlice,
-    TupleElems, Variable,
+    TupleElems, UnionTags, Variable,
 };
 use roc_types::types::{AliasKind, Category, MemberImpl, PatternCategory, Polarity, Types};
 use roc_unify::unify::{Env, MustImplementConstraints};
@@ -302,6 +302,8 @@ impl ObligationCache {
 
             Symbol::BOOL_EQ => Some(DeriveEq::is_derivable(self, abilities_store, subs, var)),
 
+            Symbol::ENUM_ENUM => Some(DeriveEnum::is_derivable(self, abilities_store, subs, var)),
+
             _ => None,
         };
 
@@ -380,6 +382,7 @@ impl ObligationCache {
             DeriveDecoding::ABILITY => DeriveDecoding::is_derivable_builtin_opaque(opaque),
             DeriveEq::ABILITY => DeriveEq::is_derivable_builtin_opaque(opaque),
             DeriveHash::ABILITY => DeriveHash::is_derivable_builtin_opaque(opaque),
+            DeriveEnum::ABILITY => DeriveEnum::is_derivable_builtin_opaque(opaque),
             _ => false,
         };
 
@@ -577,7 +580,11 @@ trait DerivableVisitor {
     }
 
     #[inline(always)]
-    fn visit_tag_union(var: Variable) -> Result<Descend, NotDerivable> {
+    fn visit_tag_union(
+        _subs: &Subs,
+        var: Variable,
+        _tags: UnionTags,
+    ) -> Result<Descend, NotDerivable> {
         Err(NotDerivable {
             var,
             context: NotDerivableContext::NoContext,
@@ -757,7 +764,7 @@ trait DerivableVisitor {
                         }
                     }
                     TagUnion(tags, ext) => {
-                        let descend = Self::visit_tag_union(var)?;
+                        let descend = Self::visit_tag_union(subs, var, tags)?;
                         if descend.0 {
                             for i in tags.variables() {
                                 push_var_slice!(subs[i]);
@@ -891,7 +898,11 @@ impl DerivableVisitor for DeriveEncoding {
     }
 
     #[inline(always)]
-    fn visit_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+    fn visit_tag_union(
+        _subs: &Subs,
+        _var: Variable,
+        _tags: UnionTags,
+    ) -> Result<Descend, NotDerivable> {
         Ok(Descend(true))
     }
 
@@ -1007,7 +1018,11 @@ impl DerivableVisitor for DeriveDecoding {
     }
 
     #[inline(always)]
-    fn visit_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+    fn visit_tag_union(
+        _subs: &Subs,
+        _var: Variable,
+        _tags: UnionTags,
+    ) -> Result<Descend, NotDerivable> {
         Ok(Descend(true))
     }
 
@@ -1122,7 +1137,11 @@ impl DerivableVisitor for DeriveHash {
     }
 
     #[inline(always)]
-    fn visit_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+    fn visit_tag_union(
+        _subs: &Subs,
+        _var: Variable,
+        _tags: UnionTags,
+    ) -> Result<Descend, NotDerivable> {
         Ok(Descend(true))
     }
 
@@ -1237,7 +1256,11 @@ impl DerivableVisitor for DeriveEq {
     }
 
     #[inline(always)]
-    fn visit_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+    fn visit_tag_union(
+        _subs: &Subs,
+        _var: Variable,
+        _tags: UnionTags,
+    ) -> Result<Descend, NotDerivable> {
         Ok(Descend(true))
     }
 
@@ -1309,6 +1332,48 @@ impl DerivableVisitor for DeriveEq {
     }
 }
 
+struct DeriveEnum;
+impl DerivableVisitor for DeriveEnum {
+    const ABILITY: Symbol = Symbol::ENUM_ENUM;
+    const ABILITY_SLICE: SubsSlice<Symbol> = Subs::AB_ENUM;
+
+    #[inline(always)]
+    fn visit_tag_union(
+        subs: &Subs,
+        var: Variable,
+        tags: UnionTags,
+    ) -> Result<Descend, NotDerivable> {
+        for (tag_name, payloads) in tags.iter_all() {
+            if !subs[payloads].is_empty() {
+                return Err(NotDerivable {
+                    var,
+                    context: NotDerivableContext::Enum(NotDerivableEnum::Payload(
+                        subs[tag_name].clone(),
+                    )),
+                });
+            }
+        }
+
+        // Only the extension is left to check.
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_function_or_tag_union(_var: Variable) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+
+    #[inline(always)]
+    fn visit_empty_tag_union(_var: Variable) -> Result<(), NotDerivable> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn visit_alias(_var: Variable, _symbol: Symbol) -> Result<Descend, NotDerivable> {
+        Ok(Descend(true))
+    }
+}
+
 /// Determines what type implements an ability member of a specialized signature, given the
 /// [MustImplementAbility] constraints of the signature.
 pub fn type_implementing_specialization(
@@ -6,5 +6,6 @@
 
 pub mod ability;
 pub mod module;
+pub mod single_implementation;
 pub mod solve;
 pub mod specialize;
@@ -34,6 +34,8 @@ impl<T> Solved<T> {
 #[derive(Debug)]
 pub struct SolvedModule {
     pub problems: Vec<TypeError>,
+    /// Warnings that are only reported when asked for
+    pub lints: Vec<TypeError>,
 
     /// all aliases and their definitions. this has to include non-exposed aliases
     /// because exposed aliases can depend on non-exposed ones)
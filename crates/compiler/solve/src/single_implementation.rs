@@ -0,0 +1,341 @@
+//! An opt-in lint for functions which are generic over an ability, but are only ever used with
+//! one type. For example, `isMember : List a, a -> Bool | a has Eq` which is only ever called on
+//! a `List Str` could just be `isMember : List Str, Str -> Bool`: a concrete signature is easier to
+//! read, and keeps a later caller from quietly adding more specializations of the function.
+//!
+//! Only the functions which are not exposed by their module are checked. All of their uses are in
+//! the module itself, so this module sees every type they are used with in the whole program.
+
+use roc_can::expr::{DeclarationTag, Declarations, Expr};
+use roc_can::traverse::{walk_expr, Visitor};
+use roc_collections::{MutMap, MutSet, VecMap};
+use roc_module::symbol::Symbol;
+use roc_region::all::Region;
+use roc_solve_problem::TypeError;
+use roc_types::subs::{Content, FlatType, GetSubsSlice, Subs, Variable};
+use roc_types::types::{ErrorType, Polarity};
+
+struct GenericFunction {
+    region: Region,
+    /// The type variables which are bound to an ability, with (one of) their abilities
+    able_vars: Vec<(Variable, Symbol)>,
+}
+
+/// Warn about the functions of this module which are generic over an ability, but whose
+/// ability-bound type variables are always the same concrete type.
+pub fn single_implementations(
+    subs: &mut Subs,
+    decls: &Declarations,
+    exposed_vars_by_symbol: &[(Symbol, Variable)],
+) -> Vec<TypeError> {
+    let mut generic_functions = VecMap::default();
+
+    for (index, tag) in decls.declarations.iter().enumerate() {
+        match tag {
+            DeclarationTag::Function(_)
+            | DeclarationTag::Recursive(_)
+            | DeclarationTag::TailRecursive(_) => {}
+            _ => continue,
+        }
+
+        let loc_symbol = decls.symbols[index];
+
+        // other modules may use exposed functions with other types, and implementations of
+        // ability members have to be exactly as generic as the member
+        if decls.specializes.contains_key(&index)
+            || exposed_vars_by_symbol
+                .iter()
+                .any(|(symbol, _)| *symbol == loc_symbol.value)
+        {
+            continue;
+        }
+
+        let mut able_vars = Vec::new();
+        find_able_vars(
+            subs,
+            decls.variables[index],
+            &mut MutSet::default(),
+            &mut able_vars,
+        );
+
+        if !able_vars.is_empty() {
+            let function = GenericFunction {
+                region: loc_symbol.region,
+                able_vars,
+            };
+
+            generic_functions.insert(loc_symbol.value, (decls.variables[index], function));
+        }
+    }
+
+    if generic_functions.is_empty() {
+        return Vec::new();
+    }
+
+    let mut visitor = LookupVisitor {
+        symbols: generic_functions.keys().copied().collect(),
+        lookups: MutMap::default(),
+    };
+    visitor.visit_decls(decls);
+
+    // turning variables into error types names them, which must not leak into the solved types
+    let snapshot = subs.snapshot();
+    let mut problems = Vec::new();
+
+    for (symbol, (def_var, function)) in generic_functions.iter() {
+        let lookups = match visitor.lookups.get(symbol) {
+            Some(lookups) => lookups,
+            // unused functions get a warning of their own
+            None => continue,
+        };
+
+        let mut implementations = Vec::with_capacity(function.able_vars.len());
+
+        for (able_var, ability) in function.able_vars.iter() {
+            match single_type(subs, *def_var, *able_var, &function.able_vars, lookups) {
+                Some(typ) => implementations.push((*ability, typ)),
+                None => break,
+            }
+        }
+
+        if implementations.len() == function.able_vars.len() {
+            problems.push(TypeError::SingleAbilityImplementation {
+                region: function.region,
+                symbol: *symbol,
+                implementations,
+            });
+        }
+    }
+
+    subs.rollback_to(snapshot);
+
+    problems
+}
+
+/// The one concrete type `able_var` is bound to in every lookup of the function, if there is one
+fn single_type(
+    subs: &mut Subs,
+    def_var: Variable,
+    able_var: Variable,
+    able_vars: &[(Variable, Symbol)],
+    lookups: &[Variable],
+) -> Option<ErrorType> {
+    let mut found: Option<ErrorType> = None;
+
+    for lookup_var in lookups {
+        let mut bindings = MutMap::default();
+        bind_able_vars(
+            subs,
+            def_var,
+            *lookup_var,
+            able_vars,
+            &mut MutSet::default(),
+            &mut bindings,
+        );
+
+        let bound_var = *bindings.get(&able_var)?;
+
+        // a recursive call is made at the type the function is being defined with
+        if subs.equivalent_without_compacting(bound_var, able_var) {
+            continue;
+        }
+
+        let typ = subs.var_to_error_type(bound_var, Polarity::OF_VALUE);
+
+        // a lookup in another generic function can't tell which types it will be used with
+        let mut type_vars = MutSet::default();
+        typ.add_names(&mut type_vars);
+
+        if !type_vars.is_empty() || matches!(typ, ErrorType::Error) {
+            return None;
+        }
+
+        match &found {
+            Some(previous) if *previous != typ => return None,
+            Some(_) => {}
+            None => found = Some(typ),
+        }
+    }
+
+    found
+}
+
+fn find_able_vars(
+    subs: &Subs,
+    var: Variable,
+    visited: &mut MutSet<Variable>,
+    able_vars: &mut Vec<(Variable, Symbol)>,
+) {
+    use Content::*;
+    use FlatType::*;
+
+    let var = subs.get_root_key_without_compacting(var);
+
+    if !visited.insert(var) {
+        return;
+    }
+
+    match subs.get_content_without_compacting(var) {
+        FlexAbleVar(_, abilities) | RigidAbleVar(_, abilities) => {
+            if let Some(ability) = subs.get_subs_slice(*abilities).first() {
+                able_vars.push((var, *ability));
+            }
+        }
+        Alias(_, args, actual, _) => {
+            for index in args.named_type_arguments() {
+                find_able_vars(subs, subs[index], visited, able_vars);
+            }
+            find_able_vars(subs, *actual, visited, able_vars);
+        }
+        Structure(Apply(_, args)) => {
+            for index in args.into_iter() {
+                find_able_vars(subs, subs[index], visited, able_vars);
+            }
+        }
+        Structure(Func(args, _closure, ret)) => {
+            for index in args.into_iter() {
+                find_able_vars(subs, subs[index], visited, able_vars);
+            }
+            find_able_vars(subs, *ret, visited, able_vars);
+        }
+        Structure(Record(fields, ext)) => {
+            for index in fields.iter_variables() {
+                find_able_vars(subs, subs[index], visited, able_vars);
+            }
+            find_able_vars(subs, *ext, visited, able_vars);
+        }
+        Structure(Tuple(elems, ext)) => {
+            for index in elems.iter_variables() {
+                find_able_vars(subs, subs[index], visited, able_vars);
+            }
+            find_able_vars(subs, *ext, visited, able_vars);
+        }
+        Structure(TagUnion(tags, ext)) | Structure(RecursiveTagUnion(_, tags, ext)) => {
+            for (_, payload) in tags.iter_from_subs(subs) {
+                for payload_var in payload {
+                    find_able_vars(subs, *payload_var, visited, able_vars);
+                }
+            }
+            find_able_vars(subs, ext.var(), visited, able_vars);
+        }
+        FlexVar(_)
+        | RigidVar(_)
+        | RecursionVar { .. }
+        | LambdaSet(_)
+        | RangedNumber(_)
+        | Error
+        | Structure(FunctionOrTagUnion(..))
+        | Structure(EmptyRecord)
+        | Structure(EmptyTuple)
+        | Structure(EmptyTagUnion) => {}
+    }
+}
+
+/// Walk the type of a function and the type of a lookup of it side by side, to find out what
+/// the ability-bound type variables of the function are at that lookup.
+fn bind_able_vars(
+    subs: &Subs,
+    def_var: Variable,
+    lookup_var: Variable,
+    able_vars: &[(Variable, Symbol)],
+    visited: &mut MutSet<(Variable, Variable)>,
+    bindings: &mut MutMap<Variable, Variable>,
+) {
+    use Content::*;
+    use FlatType::*;
+
+    let def_var = subs.get_root_key_without_compacting(def_var);
+    let lookup_var = subs.get_root_key_without_compacting(lookup_var);
+
+    if !visited.insert((def_var, lookup_var)) {
+        return;
+    }
+
+    if able_vars.iter().any(|(able_var, _)| *able_var == def_var) {
+        bindings.entry(def_var).or_insert(lookup_var);
+
+        return;
+    }
+
+    let mut bind = |def_var, lookup_var| {
+        bind_able_vars(subs, def_var, lookup_var, able_vars, visited, bindings)
+    };
+
+    match (
+        subs.get_content_without_compacting(def_var),
+        subs.get_content_without_compacting(lookup_var),
+    ) {
+        (Alias(_, _, actual, _), _) => bind(*actual, lookup_var),
+        (_, Alias(_, _, actual, _)) => bind(def_var, *actual),
+        (Structure(Func(args1, _, ret1)), Structure(Func(args2, _, ret2)))
+            if args1.len() == args2.len() =>
+        {
+            for (arg1, arg2) in args1.into_iter().zip(args2.into_iter()) {
+                bind(subs[arg1], subs[arg2]);
+            }
+            bind(*ret1, *ret2);
+        }
+        (Structure(Apply(symbol1, args1)), Structure(Apply(symbol2, args2)))
+            if symbol1 == symbol2 && args1.len() == args2.len() =>
+        {
+            for (arg1, arg2) in args1.into_iter().zip(args2.into_iter()) {
+                bind(subs[arg1], subs[arg2]);
+            }
+        }
+        (Structure(Record(fields1, _)), Structure(Record(fields2, _))) => {
+            for (name1, var1, _) in fields1.iter_all() {
+                let matching = fields2
+                    .iter_all()
+                    .find(|(name2, _, _)| subs[name1] == subs[*name2]);
+
+                if let Some((_, var2, _)) = matching {
+                    bind(subs[var1], subs[var2]);
+                }
+            }
+        }
+        (Structure(Tuple(elems1, _)), Structure(Tuple(elems2, _))) => {
+            for (index1, var1) in elems1.iter_all() {
+                let matching = elems2
+                    .iter_all()
+                    .find(|(index2, _)| subs[index1] == subs[*index2]);
+
+                if let Some((_, var2)) = matching {
+                    bind(subs[var1], subs[var2]);
+                }
+            }
+        }
+        (
+            Structure(TagUnion(tags1, _)) | Structure(RecursiveTagUnion(_, tags1, _)),
+            Structure(TagUnion(tags2, _)) | Structure(RecursiveTagUnion(_, tags2, _)),
+        ) => {
+            for (tag1, payload1) in tags1.iter_from_subs(subs) {
+                let matching = tags2.iter_from_subs(subs).find(|(tag2, _)| tag1 == *tag2);
+
+                if let Some((_, payload2)) = matching {
+                    for (var1, var2) in payload1.iter().zip(payload2) {
+                        bind(*var1, *var2);
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collects the type of every lookup of the given symbols
+struct LookupVisitor {
+    symbols: MutSet<Symbol>,
+    lookups: MutMap<Symbol, Vec<Variable>>,
+}
+
+impl Visitor for LookupVisitor {
+    fn visit_expr(&mut self, expr: &Expr, _region: Region, var: Variable) {
+        if let Expr::Var(symbol, lookup_var) = expr {
+            if self.symbols.contains(symbol) {
+                self.lookups.entry(*symbol).or_default().push(*lookup_var);
+            }
+        }
+
+        walk_expr(self, expr, var);
+    }
+}
@@ -2076,61 +2076,66 @@ fn check_ability_specialization(
                 let specialization_type =
                     type_implementing_specialization(&must_implement_ability, parent_ability);
 
+                let specializes_claimed_opaque = match specialization_type {
+                    // This is a specialization for an opaque - but is it the opaque the
+                    // specialization was claimed to be for?
+                    Some(Obligated::Opaque(opaque)) => opaque == impl_key.opaque,
+                    Some(Obligated::Adhoc(_)) => false,
+                    // An implementation forwarding to the member's default implementation is as
+                    // general as the default; it gets specialized to the opaque where it's used.
+                    None => abilities_store.is_defaulted_implementation(symbol),
+                };
+
                 match specialization_type {
-                    Some(Obligated::Opaque(opaque)) => {
-                        // This is a specialization for an opaque - but is it the opaque the
-                        // specialization was claimed to be for?
-                        if opaque == impl_key.opaque {
-                            // It was! All is good.
+                    _ if specializes_claimed_opaque => {
+                        // All is good.
+                        subs.commit_snapshot(snapshot);
+                        introduce(subs, rank, pools, &vars);
 
-                            subs.commit_snapshot(snapshot);
-                            introduce(subs, rank, pools, &vars);
+                        let specialization_lambda_sets = specialization_lambda_sets
+                            .into_iter()
+                            .map(|((symbol, region), var)| {
+                                debug_assert_eq!(symbol, ability_member);
+                                (region, var)
+                            })
+                            .collect();
 
-                            let specialization_lambda_sets = specialization_lambda_sets
-                                .into_iter()
-                                .map(|((symbol, region), var)| {
-                                    debug_assert_eq!(symbol, ability_member);
-                                    (region, var)
-                                })
-                                .collect();
-
-                            compact_lambdas_and_check_obligations(
-                                arena,
-                                pools,
-                                problems,
-                                subs,
-                                abilities_store,
-                                obligation_cache,
-                                awaiting_specializations,
-                                derived_env,
-                                lambda_sets_to_specialize,
-                            );
+                        compact_lambdas_and_check_obligations(
+                            arena,
+                            pools,
+                            problems,
+                            subs,
+                            abilities_store,
+                            obligation_cache,
+                            awaiting_specializations,
+                            derived_env,
+                            lambda_sets_to_specialize,
+                        );
 
-                            let specialization =
-                                MemberSpecializationInfo::new(symbol, specialization_lambda_sets);
+                        let specialization =
+                            MemberSpecializationInfo::new(symbol, specialization_lambda_sets);
 
-                            Ok(specialization)
-                        } else {
-                            // This def is not specialized for the claimed opaque type, that's an
-                            // error.
+                        Ok(specialization)
+                    }
+                    Some(Obligated::Opaque(opaque)) => {
+                        // This def is not specialized for the claimed opaque type, that's an
+                        // error.
 
-                            // Commit so that the bad signature and its error persists in subs.
-                            subs.commit_snapshot(snapshot);
+                        // Commit so that the bad signature and its error persists in subs.
+                        subs.commit_snapshot(snapshot);
 
-                            let _typ =
-                                subs.var_to_error_type(symbol_loc_var.value, Polarity::OF_VALUE);
+                        let _typ = subs.var_to_error_type(symbol_loc_var.value, Polarity::OF_VALUE);
 
-                            let problem = TypeError::WrongSpecialization {
-                                region: symbol_loc_var.region,
-                                ability_member: impl_key.ability_member,
-                                expected_opaque: impl_key.opaque,
-                                found_opaque: opaque,
-                            };
+                        let problem = TypeError::WrongSpecialization {
+                            region: symbol_loc_var.region,
+                            ability_member: impl_key.ability_member,
+                            expected_opaque: impl_key.opaque,
+                            found_opaque: opaque,
+                        };
 
-                            problems.push(problem);
+                        problems.push(problem);
 
-                            Err(())
-                        }
+                        Err(())
                     }
                     Some(Obligated::Adhoc(var)) => {
                         // This is a specialization of a structural type - never allowed.
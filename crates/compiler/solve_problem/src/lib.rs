@@ -2,7 +2,10 @@
 use std::{path::PathBuf, str::Utf8Error};
 
 use roc_can::expected::{Expected, PExpected};
-use roc_module::{ident::Lowercase, symbol::Symbol};
+use roc_module::{
+    ident::{Lowercase, TagName},
+    symbol::Symbol,
+};
 use roc_problem::{can::CycleEntry, Severity};
 use roc_region::all::Region;
 
@@ -33,6 +36,14 @@ pub enum TypeError {
     },
     IngestedFileBadUtf8(Box<PathBuf>, Utf8Error),
     IngestedFileUnsupportedType(Box<PathBuf>, ErrorType),
+    /// A function which is generic over abilities, but is only ever used with one type for each
+    /// of them. Only reported when asked for.
+    SingleAbilityImplementation {
+        region: Region,
+        symbol: Symbol,
+        /// Each ability with the one type it's used with
+        implementations: Vec<(Symbol, ErrorType)>,
+    },
 }
 
 impl TypeError {
@@ -54,6 +65,35 @@ impl TypeError {
             TypeError::WrongSpecialization { .. } => RuntimeError,
             TypeError::IngestedFileBadUtf8(..) => Fatal,
             TypeError::IngestedFileUnsupportedType(..) => Fatal,
+            TypeError::SingleAbilityImplementation { .. } => Warning,
+        }
+    }
+
+    /// The part of the source code this problem is about, if it's about a specific part
+    pub fn region(&self) -> Option<Region> {
+        match self {
+            TypeError::BadExpr(region, ..)
+            | TypeError::BadPattern(region, ..)
+            | TypeError::CircularType(region, ..)
+            | TypeError::BadExprMissingAbility(region, ..)
+            | TypeError::BadPatternMissingAbility(region, ..)
+            | TypeError::StructuralSpecialization { region, .. }
+            | TypeError::WrongSpecialization { region, .. }
+            | TypeError::SingleAbilityImplementation { region, .. } => Some(*region),
+            TypeError::Exhaustive(roc_exhaustive::Error::Incomplete(region, ..))
+            | TypeError::Exhaustive(roc_exhaustive::Error::Redundant {
+                branch_region: region,
+                ..
+            })
+            | TypeError::Exhaustive(roc_exhaustive::Error::Unmatchable {
+                branch_region: region,
+                ..
+            }) => Some(*region),
+            TypeError::CircularDef(_)
+            | TypeError::UnexposedLookup(_)
+            | TypeError::UnfulfilledAbility(_)
+            | TypeError::IngestedFileBadUtf8(..)
+            | TypeError::IngestedFileUnsupportedType(..) => None,
         }
     }
 }
@@ -96,6 +136,7 @@ pub enum NotDerivableContext {
     Encode(NotDerivableEncode),
     Decode(NotDerivableDecode),
     Eq(NotDerivableEq),
+    Enum(NotDerivableEnum),
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -113,3 +154,9 @@ pub enum NotDerivableDecode {
 pub enum NotDerivableEq {
     FloatingPoint,
 }
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum NotDerivableEnum {
+    /// The tag has a payload.
+    Payload(TagName),
+}
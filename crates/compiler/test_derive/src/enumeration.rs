@@ -0,0 +1,112 @@
+#![cfg(test)]
+// Even with #[allow(non_snake_case)] on individual idents, rust-analyzer issues diagnostics.
+// See https://github.com/rust-lang/rust-analyzer/issues/6541.
+// For the `v!` macro we use uppercase variables when constructing tag unions.
+#![allow(non_snake_case)]
+
+use crate::{
+    test_key_eq, test_key_neq,
+    util::{check_derivable, check_underivable},
+    v,
+};
+use roc_module::symbol::Symbol;
+use roc_types::subs::Variable;
+
+use roc_derive_key::{
+    enumeration::FlatEnumKey,
+    DeriveBuiltin::{EnumAll, EnumFromInt, EnumToInt},
+    DeriveError, DeriveKey,
+};
+
+test_key_eq! {
+    EnumToInt,
+
+    same_tag_union:
+        v!([ A, B ]), v!([ A, B ])
+    same_tag_union_tags_any_order:
+        v!([ A, B, C ]), v!([ C, A, B ])
+    explicit_empty_tag_union_and_implicit_empty_tag_union:
+        v!(EMPTY_TAG_UNION), v!([])
+}
+
+test_key_neq! {
+    EnumToInt,
+
+    different_tag_union_tags:
+        v!([ A ]), v!([ B ])
+    tag_union_empty_vs_nonempty:
+        v!(EMPTY_TAG_UNION), v!([ B ])
+}
+
+#[test]
+fn tags_are_numbered_by_name() {
+    check_derivable(
+        EnumToInt,
+        v!([Red, Green, Blue]),
+        DeriveKey::EnumToInt(FlatEnumKey(vec![
+            "Blue".into(),
+            "Green".into(),
+            "Red".into(),
+        ])),
+    );
+}
+
+#[test]
+fn every_member_has_its_own_key() {
+    check_derivable(
+        EnumFromInt,
+        v!([A, B]),
+        DeriveKey::EnumFromInt(FlatEnumKey(vec!["A".into(), "B".into()])),
+    );
+    check_derivable(
+        EnumAll,
+        v!([A, B]),
+        DeriveKey::EnumAll(FlatEnumKey(vec!["A".into(), "B".into()])),
+    );
+}
+
+#[test]
+fn derivable_tag_ext_flex_var() {
+    check_derivable(
+        EnumToInt,
+        v!([ A ]* ),
+        DeriveKey::EnumToInt(FlatEnumKey(vec!["A".into()])),
+    );
+}
+
+#[test]
+fn derivable_tag_ext_flex_able_var() {
+    check_derivable(
+        EnumToInt,
+        v!([ A ]a has Symbol::ENUM_ENUM),
+        DeriveKey::EnumToInt(FlatEnumKey(vec!["A".into()])),
+    );
+}
+
+#[test]
+fn derivable_tag_with_tag_ext() {
+    check_derivable(
+        EnumToInt,
+        v!([B][A]),
+        DeriveKey::EnumToInt(FlatEnumKey(vec!["A".into(), "B".into()])),
+    );
+}
+
+#[test]
+fn tag_with_payload_derive_error() {
+    check_underivable(EnumToInt, v!([ A, B v!(U8) ]), DeriveError::Underivable);
+}
+
+#[test]
+fn recursive_tag_union_derive_error() {
+    check_underivable(
+        EnumToInt,
+        v!([ Nil, Cons v!(^lst)] as lst),
+        DeriveError::Underivable,
+    );
+}
+
+#[test]
+fn record_derive_error() {
+    check_underivable(EnumToInt, v!({ a: v!(U8), }), DeriveError::Underivable);
+}
@@ -2,6 +2,7 @@
 
 mod decoding;
 mod encoding;
+mod enumeration;
 mod eq;
 mod hash;
 
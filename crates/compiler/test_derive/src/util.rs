@@ -63,6 +63,11 @@ fn module_source_and_path(builtin: DeriveBuiltin) -> (ModuleId, &'static str, Pa
             module_source(ModuleId::BOOL),
             builtins_path.join("Bool.roc"),
         ),
+        DeriveBuiltin::EnumToInt | DeriveBuiltin::EnumFromInt | DeriveBuiltin::EnumAll => (
+            ModuleId::ENUM,
+            module_source(ModuleId::ENUM),
+            builtins_path.join("Enum.roc"),
+        ),
     }
 }
 
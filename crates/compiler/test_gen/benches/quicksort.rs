@@ -88,6 +88,7 @@ fn roc_function<'a>(
         ignore_problems: false,
         add_debug_info: true,
         opt_level: OptLevel::Optimize,
+        overflow: roc_load::OverflowMode::Trap,
     };
 
     let context = inkwell::context::Context::create();
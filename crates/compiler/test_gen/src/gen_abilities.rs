@@ -1402,6 +1402,70 @@ fn decode_tuple_of_tuples() {
     )
 }
 
+#[test]
+#[cfg(all(any(feature = "gen-llvm", feature = "gen-wasm")))]
+fn rbf_encode_then_decode_list_of_strings() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Rbf] provides [main] to "./platform"
+
+            main =
+                when Rbf.toBytes ["a", "b", "c"] |> Rbf.fromBytes is
+                    Ok l -> Str.joinWith l ","
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("a,b,c"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(all(any(feature = "gen-llvm", feature = "gen-wasm")))]
+fn rbf_encode_f64_exact() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Rbf] provides [main] to "./platform"
+
+            main =
+                x : F64
+                x = 0.1
+
+                Encode.toBytes x Rbf.format
+            "#
+        ),
+        RocList::from_slice(&[0x9A, 0x99, 0x99, 0x99, 0x99, 0x99, 0xB9, 0x3F]),
+        RocList<u8>
+    )
+}
+
+#[test]
+#[cfg(all(
+    any(feature = "gen-llvm", feature = "gen-wasm"),
+    not(debug_assertions) // https://github.com/roc-lang/roc/issues/3898
+))]
+fn rbf_decode_record_skips_unknown_fields() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Rbf] provides [main] to "./platform"
+
+            main =
+                written : { first : Str, extra : U64, second : Str }
+                written = { first: "ab", extra: 42, second: "cd" }
+
+                when Rbf.toBytes written |> Rbf.fromBytes is
+                    Ok {first: "ab", second: "cd"} -> "abcd"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("abcd"),
+        RocStr
+    )
+}
+
 #[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
 mod hash {
     #[cfg(feature = "gen-llvm")]
@@ -2091,6 +2155,88 @@ mod eq {
     }
 }
 
+#[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
+mod enumeration {
+    #[cfg(feature = "gen-llvm")]
+    use crate::helpers::llvm::assert_evals_to;
+
+    #[cfg(feature = "gen-wasm")]
+    use crate::helpers::wasm::assert_evals_to;
+
+    use indoc::indoc;
+    use roc_std::{RocList, RocStr};
+
+    #[test]
+    fn to_int_numbers_tags_by_name() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Color : [Red, Green, Blue]
+
+                main =
+                    colors : List Color
+                    colors = [Red, Green, Blue]
+
+                    List.map colors Enum.toInt
+                "#
+            ),
+            RocList::from_slice(&[2u64, 1, 0]),
+            RocList<u64>
+        )
+    }
+
+    #[test]
+    fn all_in_order_of_numbers() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Color : [Red, Green, Blue]
+
+                main =
+                    colors : List Color
+                    colors = Enum.all {}
+
+                    List.map colors \color ->
+                        when color is
+                            Red -> "Red"
+                            Green -> "Green"
+                            Blue -> "Blue"
+                    |> Str.joinWith ","
+                "#
+            ),
+            RocStr::from("Blue,Green,Red"),
+            RocStr
+        )
+    }
+
+    #[test]
+    fn from_int_for_opaque() {
+        assert_evals_to!(
+            indoc!(
+                r#"
+                app "test" provides [main] to "./platform"
+
+                Color := [Red, Green, Blue] has [Enum]
+
+                fromInt : U64 -> Result Color [OutOfRange]
+                fromInt = Enum.fromInt
+
+                main =
+                    when (fromInt 1, fromInt 3) is
+                        (Ok color, Err OutOfRange) -> Enum.toInt color
+                        _ -> 99
+                "#
+            ),
+            1,
+            u64
+        )
+    }
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn issue_4772_weakened_monomorphic_destructure() {
@@ -2123,3 +2269,85 @@ fn issue_4772_weakened_monomorphic_destructure() {
         i64
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn default_member_implementation() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            MEq has
+                isEq : a, a -> Bool | a has MEq
+                isNotEq : a, a -> Bool | a has MEq
+                isNotEq = \a, b -> !(isEq a b)
+
+            Id := U64 has [MEq {isEq: isEqId}]
+
+            isEqId = \@Id m, @Id n -> m == n
+
+            Always := {} has [MEq {isEq: isEqAlways, isNotEq: isNotEqAlways}]
+
+            isEqAlways = \_, _ -> Bool.true
+            isNotEqAlways = \_, _ -> Bool.true
+
+            main = [isNotEq (@Id 1) (@Id 2), isNotEq (@Id 3) (@Id 3), isNotEq (@Always {}) (@Always {})]
+            "#
+        ),
+        RocList::from_slice(&[true, false, true]),
+        RocList<bool>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn ability_with_only_default_members() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            Describe has
+                describe : a -> Str | a has Describe
+                describe = \_ -> "something"
+
+            Thing := {} has [Describe]
+
+            main = describe (@Thing {})
+            "#
+        ),
+        RocStr::from("something"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn constant_member() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            Zero has
+                zero : a | a has Zero
+
+            Money := U64 has [Zero {zero}]
+
+            zero = @Money 0
+
+            Count := U64 has [Zero {zero: zeroCount}]
+
+            zeroCount = @Count 1
+
+            main =
+                @Money m = zero
+                @Count c = zero
+                m + c
+            "#
+        ),
+        1,
+        u64
+    );
+}
@@ -530,6 +530,88 @@ fn list_intersperse() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_chunks_of() {
+    assert_evals_to!(
+        "List.chunksOf [1, 2, 3, 4, 5] 2",
+        RocList::from_slice(&[
+            RocList::from_slice(&[1, 2]),
+            RocList::from_slice(&[3, 4]),
+            RocList::from_slice(&[5]),
+        ]),
+        RocList<RocList<i64>>
+    );
+    assert_evals_to!(
+        "List.chunksOf [1, 2, 3] 0",
+        RocList::<RocList<i64>>::default(),
+        RocList<RocList<i64>>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_windows() {
+    assert_evals_to!(
+        "List.windows [1, 2, 3, 4] 3",
+        RocList::from_slice(&[
+            RocList::from_slice(&[1, 2, 3]),
+            RocList::from_slice(&[2, 3, 4]),
+        ]),
+        RocList<RocList<i64>>
+    );
+    assert_evals_to!(
+        "List.windows [1, 2] 3",
+        RocList::<RocList<i64>>::default(),
+        RocList<RocList<i64>>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_windows_of_refcounted_elements() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            words = ["the quick brown fox", "jumps over", "the lazy dog"]
+
+            List.windows words 2
+            |> List.map (\window -> Str.joinWith window "/")
+            |> List.concat words
+            |> Str.joinWith ","
+            "#
+        ),
+        RocStr::from("the quick brown fox/jumps over,jumps over/the lazy dog,the quick brown fox,jumps over,the lazy dog"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn list_zip_unzip() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            List.zip [1, 2, 3] [4, 5]
+            |> List.map (\pair -> 10 * pair.first + pair.second)
+            "#
+        ),
+        RocList::from_slice(&[14, 25]),
+        RocList<i64>
+    );
+    assert_evals_to!(
+        indoc!(
+            r#"
+            unzipped = List.unzip (List.zip3 [1, 2] [3, 4] [5, 6] |> List.map (\t -> { first: t.first, second: t.third }))
+
+            List.concat unzipped.first unzipped.second
+            "#
+        ),
+        RocList::from_slice(&[1, 2, 5, 6]),
+        RocList<i64>
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_drop_at_shared() {
@@ -2082,6 +2164,34 @@ fn get_int_list_oob() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn get_int_list_checked_index() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            getOr0 : List I64, U64 -> I64
+            getOr0 = \list, i ->
+                if i < List.len list then
+                    when List.get list i is
+                        Ok x -> x
+                        Err _ -> -1
+                else
+                    0
+
+            main =
+                list = [12, 9, 6]
+
+                getOr0 list 2 + getOr0 list 3
+            "#
+        ),
+        6,
+        i64
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn replace_unique_int_list() {
@@ -2929,6 +3039,55 @@ fn list_sort_with() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn list_sort_with_is_stable() {
+    // sort by the tens only: numbers with the same tens keep the order they had
+    assert_evals_to!(
+        indoc!(
+            r#"
+            List.range { start: At 0, end: Before 100 }
+            |> List.map (\n -> (n * 37) % 100)
+            |> List.sortWith (\a, b -> Num.compare (a // 10) (b // 10))
+            |> List.takeFirst 10
+            "#
+        ),
+        RocList::from_slice(&[0, 7, 3, 6, 2, 9, 5, 1, 8, 4]),
+        RocList<i64>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn list_sort_with_sorted_input() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            List.range { start: At 0, end: Before 10000 }
+            |> List.sortWith Num.compare
+            |> List.sum
+            "#
+        ),
+        49995000,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn list_sort_by() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            List.sortBy ["ccc", "a", "bb", "d"] Str.countUtf8Bytes
+            |> Str.joinWith ","
+            "#
+        ),
+        RocStr::from("a,d,bb,ccc"),
+        RocStr
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn list_sort_asc() {
@@ -3809,6 +3968,165 @@ fn list_range_length_overflow() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn stream_map_keep_if_map() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Stream] provides [main] to "./platform"
+
+            main =
+                [1, 2, 3, 4, 5, 6]
+                |> Stream.fromList
+                |> Stream.map (\n -> n * 10)
+                |> Stream.keepIf (\n -> n > 20)
+                |> Stream.map (\n -> n + 1)
+                |> Stream.toList
+            "#
+        ),
+        RocList::from_slice(&[31i64, 41, 51, 61]),
+        RocList<i64>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn stream_walk_until() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Stream] provides [main] to "./platform"
+
+            main =
+                [1, 2, 3, 4]
+                |> Stream.fromList
+                |> Stream.dropIf Num.isEven
+                |> Stream.walkUntil 0 \sum, n -> if n > 2 then Break sum else Continue (sum + n)
+            "#
+        ),
+        1,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn update_top_level_constant_list() {
+    // `primes` is static data, so it must be copied rather than updated in place.
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            primes : List I64
+            primes = [2, 3, 5, 7]
+
+            main =
+                List.concat (List.set primes 0 1) primes
+            "#
+        ),
+        RocList::from_slice(&[1, 3, 5, 7, 2, 3, 5, 7]),
+        RocList<i64>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn top_level_constant_list_used_many_times() {
+    // The refcount operations on `primes` are left out on LLVM, which must not free it.
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            primes : List I64
+            primes = [2, 3, 5, 7]
+
+            sumPrimes : I64, I64 -> I64
+            sumPrimes = \n, acc ->
+                if n == 0 then
+                    acc
+                else
+                    sumPrimes (n - 1) (acc + List.sum primes + Num.toI64 (List.len primes))
+
+            main =
+                sumPrimes 100 0
+            "#
+        ),
+        2100,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn random_shuffle_is_a_permutation() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Random] provides [main] to "./platform"
+
+            main =
+                list = List.range { start: At 0u64, end: Before 50 }
+                { value: shuffled } = Random.shuffle (Random.seed 42) list
+
+                shuffled != list && List.sortAsc shuffled == list
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn random_u64s_match_u64() {
+    // the numbers generated in bulk by a Zig builtin are the ones Random.u64 generates
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Random] provides [main] to "./platform"
+
+            main =
+                { right } = Random.split (Random.seed 42)
+                first = Random.u64 right 0 Num.maxU64
+                second = Random.u64 first.generator 0 Num.maxU64
+
+                { value: numbers } = Random.u64s (Random.seed 42) 2
+
+                numbers == [first.value, second.value]
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn random_ranges_stay_in_bounds() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Random] provides [main] to "./platform"
+
+            main =
+                result = List.walk (List.repeat {} 500) { generator: Random.seed 1, count: 0u64 } \state, {} ->
+                    small = Random.i16 state.generator (-5) 5
+                    big = Random.u128 small.generator 1000 1002
+                    inBounds = small.value >= (-5) && small.value <= 5 && big.value >= 1000 && big.value <= 1002
+
+                    { generator: big.generator, count: if inBounds then state.count + 1 else state.count }
+
+                result.count
+            "#
+        ),
+        500,
+        u64
+    );
+}
+
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 mod pattern_match {
     #[cfg(feature = "gen-llvm")]
@@ -3989,4 +4307,25 @@ mod pattern_match {
             RocList<u8>
         )
     }
+
+    #[test]
+    fn match_on_local_list_literal_in_loop() {
+        // The list never escapes `sumPairs`, so it is stored on the stack. Each iteration must
+        // still see its own elements.
+        assert_evals_to!(
+            r#"
+            sumPairs = \n, acc ->
+                if n == 0 then
+                    acc
+                else
+                    when [n, n * 2] is
+                        [a, b] -> sumPairs (n - 1) (acc + a + b)
+                        _ -> acc
+
+            sumPairs 3u64 0
+            "#,
+            18,
+            u64
+        )
+    }
 }
@@ -0,0 +1,78 @@
+//! Integer `+`, `-` and `*` built with `--overflow=wrap` and `--overflow=saturate`. The default,
+//! crashing on overflow, is covered by the tests in `gen_num`.
+
+#[cfg(feature = "gen-llvm")]
+use crate::helpers::llvm::assert_evals_to_with_overflow;
+
+#[cfg(feature = "gen-dev")]
+use crate::helpers::dev::assert_evals_to_with_overflow;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::assert_evals_to_with_overflow;
+
+#[allow(unused_imports)]
+use indoc::formatdoc;
+#[allow(unused_imports)]
+use roc_load::OverflowMode;
+
+/// Adds one to the largest value, subtracts one from the smallest and doubles the largest. The
+/// operations are in functions, so they aren't folded away at compile time.
+#[allow(dead_code)]
+fn overflowing_program(int_type: &str) -> String {
+    formatdoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        add : {int_type}, {int_type} -> {int_type}
+        add = \a, b -> a + b
+
+        sub : {int_type}, {int_type} -> {int_type}
+        sub = \a, b -> a - b
+
+        mul : {int_type}, {int_type} -> {int_type}
+        mul = \a, b -> a * b
+
+        main = (add Num.max{int_type} 1, sub Num.min{int_type} 1, mul Num.max{int_type} 2)
+        "#,
+        int_type = int_type
+    )
+}
+
+macro_rules! overflow_tests {
+    ($($int_type:literal, $typ:ty, $wrap_test:ident, $saturate_test:ident)*) => {$(
+        #[test]
+        #[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+        fn $wrap_test() {
+            assert_evals_to_with_overflow!(
+                &overflowing_program($int_type),
+                (<$typ>::MIN, <$typ>::MAX, <$typ>::MAX.wrapping_mul(2)),
+                ($typ, $typ, $typ),
+                OverflowMode::Wrap
+            )
+        }
+
+        #[test]
+        #[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+        fn $saturate_test() {
+            assert_evals_to_with_overflow!(
+                &overflowing_program($int_type),
+                (<$typ>::MAX, <$typ>::MIN, <$typ>::MAX),
+                ($typ, $typ, $typ),
+                OverflowMode::Saturate
+            )
+        }
+    )*}
+}
+
+overflow_tests! {
+    "I8", i8, wrap_i8, saturate_i8
+    "U8", u8, wrap_u8, saturate_u8
+    "I16", i16, wrap_i16, saturate_i16
+    "U16", u16, wrap_u16, saturate_u16
+    "I32", i32, wrap_i32, saturate_i32
+    "U32", u32, wrap_u32, saturate_u32
+    "I64", i64, wrap_i64, saturate_i64
+    "U64", u64, wrap_u64, saturate_u64
+    "I128", i128, wrap_i128, saturate_i128
+    "U128", u128, wrap_u128, saturate_u128
+}
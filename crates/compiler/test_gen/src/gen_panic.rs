@@ -83,3 +83,57 @@ fn crash_in_passed_closure() {
         RocList<u8>
     );
 }
+
+#[test]
+#[cfg(all(feature = "gen-llvm", not(feature = "gen-llvm-wasm")))]
+#[should_panic = r#"Roc failed with message: "stack overflow in Test.depth""#]
+fn stack_overflow_in_deep_recursion() {
+    use crate::helpers::llvm::{helper, try_run_lib_function, HelperConfig, OPT_LEVEL};
+    use roc_gen_llvm::llvm::build::LlvmBackendMode;
+
+    let arena = bumpalo::Bump::new();
+    let context = inkwell::context::Context::create();
+
+    let config = HelperConfig {
+        mode: LlvmBackendMode::GenTest,
+        add_debug_info: false,
+        ignore_problems: false,
+        opt_level: OPT_LEVEL,
+        overflow: roc_load::OverflowMode::Trap,
+    };
+
+    // subtraction keeps LLVM from turning the recursion into a loop
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        depth : U64 -> U64
+        depth = \n ->
+            if n == 0 then
+                0
+            else
+                Num.subWrap n (depth (n - 1))
+
+        main = depth 10_000_000
+        "#
+    );
+
+    let (main_fn_name, errors, lib) = helper(&arena, config, src, &context);
+    assert!(errors.is_empty(), "Encountered errors:\n{}", errors);
+
+    // Act as a host that gives Roc 256KB of this thread's stack. Without the probe, the
+    // recursion would run into the guard page long before it finishes.
+    let frame = &arena as *const _ as usize;
+
+    unsafe {
+        let stack_limit: libloading::Symbol<*mut usize> = lib
+            .get(b"roc_stack_limit")
+            .expect("the app does not define roc_stack_limit");
+
+        **stack_limit = frame - 256 * 1024;
+    }
+
+    if let Err((msg, _)) = try_run_lib_function::<u64>(main_fn_name, &lib) {
+        panic!(r#"Roc failed with message: "{}""#, msg);
+    }
+}
@@ -3379,6 +3379,35 @@ fn box_and_unbox_record() {
     )
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn update_boxed_record() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            bump : Box { count : U64, name : Str } -> Box { count : U64, name : Str }
+            bump = \boxed ->
+                record = Box.unbox boxed
+
+                Box.box { record & count: record.count + 1 }
+
+            main =
+                original = Box.box { count: 1, name: "a name which is too long to be a small string" }
+
+                # `original` is still used afterwards, so it must not be updated in place
+                shared = bump original
+                unique = bump (bump shared)
+
+                (Box.unbox original).count * 100 + (Box.unbox unique).count
+            "#
+        ),
+        103,
+        u64
+    )
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn box_and_unbox_tag_union() {
@@ -3556,6 +3585,88 @@ fn mutual_recursion_top_level_defs() {
     )
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn mutual_tail_calls_run_in_constant_stack_space() {
+    // isEven and isOdd are merged into one loop, so this many calls don't overflow the stack
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [ main ] to "./platform"
+
+            isEven = \n ->
+                when n is
+                    0 -> Bool.true
+                    _ -> isOdd (n - 1)
+
+            isOdd = \n ->
+                when n is
+                    0 -> Bool.false
+                    _ -> isEven (n - 1)
+
+            main = isOdd 10_000_001
+            "#
+        ),
+        true,
+        bool
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn mutual_tail_calls_group_member_escapes() {
+    // isEven is passed to List.map as a closure, and still enters the merged loop
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [ main ] to "./platform"
+
+            isEven = \n ->
+                when n is
+                    0 -> Bool.true
+                    _ -> isOdd (n - 1)
+
+            isOdd = \n ->
+                when n is
+                    0 -> Bool.false
+                    _ -> isEven (n - 1)
+
+            main = List.map [1_000_000, 999_999, 0] isEven
+            "#
+        ),
+        RocList::from_slice(&[true, false, true]),
+        RocList<bool>
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn toplevel_values_in_reverse_dependency_order() {
+    // every value is defined after the values that use it, so each one must be initialized
+    // before the values above it
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [ main ] to "./platform"
+
+            main = summary
+
+            summary = Str.concat greeting suffix
+
+            suffix = Str.repeat "!" count
+
+            count = List.len words
+
+            greeting = Str.joinWith words " "
+
+            words = ["hello", "world"]
+            "#
+        ),
+        RocStr::from("hello world!!"),
+        RocStr
+    )
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn polymorphic_lambda_captures_polymorphic_value() {
@@ -3575,6 +3686,93 @@ fn polymorphic_lambda_captures_polymorphic_value() {
     )
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn closure_with_single_small_capture() {
+    // the closure is called with its capture instead of the lambda set
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            apply : ({} -> U64) -> U64
+            apply = \f -> f {}
+
+            main =
+                n : U64
+                n = 42
+
+                closure = \{} -> n
+
+                other = \{} -> 0
+
+                apply (if Bool.true then closure else other)
+            "#
+        ),
+        42,
+        u64
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn closure_with_single_large_capture() {
+    // the capture is too big to be passed on its own, so the closure keeps its lambda set
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            apply : ({} -> U64) -> U64
+            apply = \f -> f {}
+
+            main =
+                point : { x : U64, y : U64, z : U64 }
+                point = { x: 1, y: 20, z: 300 }
+
+                closure = \{} -> point.x + point.y + point.z
+
+                other = \{} -> 0
+
+                apply (if Bool.true then closure else other)
+            "#
+        ),
+        321,
+        u64
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn closure_with_several_captures() {
+    // only closures with a single capture are called with it instead of the lambda set
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            apply : ({} -> U64) -> U64
+            apply = \f -> f {}
+
+            main =
+                a : U64
+                a = 40
+
+                b : U64
+                b = 2
+
+                closure = \{} -> a + b
+
+                other = \{} -> 0
+
+                apply (if Bool.true then closure else other)
+            "#
+        ),
+        42,
+        u64
+    )
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn lambda_capture_niche_u64_vs_u8_capture() {
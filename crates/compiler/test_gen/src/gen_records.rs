@@ -1130,3 +1130,24 @@ fn pass_record_of_u8s() {
         bool
     )
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn top_level_constant_record_with_strings() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            names = ["a string long enough to live on the heap", "short"]
+
+            config = { names, retries: 3u8 }
+
+            main =
+                List.walk config.names (Num.toStr config.retries) Str.concat
+            "#
+        ),
+        RocStr::from("3a string long enough to live on the heapshort"),
+        RocStr
+    );
+}
@@ -1,5 +1,8 @@
 #[cfg(feature = "gen-wasm")]
-use crate::helpers::{wasm::assert_refcounts, RefCount::*};
+use crate::helpers::{
+    wasm::{assert_refcounts, assert_refcounts_with_arena_allocation},
+    RefCount::*,
+};
 
 #[allow(unused_imports)]
 use indoc::indoc;
@@ -103,6 +106,24 @@ fn list_str_inc() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-wasm"))]
+fn list_repeat_str_inc() {
+    assert_refcounts!(
+        indoc!(
+            r#"
+                s = Str.concat "A long enough string " "to be heap-allocated"
+                List.repeat s 3
+            "#
+        ),
+        RocList<RocStr>,
+        &[
+            Live(3), // s
+            Live(1)  // result
+        ]
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-wasm"))]
 fn list_str_dealloc() {
@@ -571,3 +592,39 @@ fn reset_reuse_alignment_8() {
         ]
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-wasm"))]
+fn str_arena_allocation() {
+    // like `str_dealloc`, but the string is only freed when the host resets its arena
+    assert_refcounts_with_arena_allocation!(
+        indoc!(
+            r#"
+                s = Str.concat "A long enough string " "to be heap-allocated"
+
+                Str.isEmpty s
+            "#
+        ),
+        bool,
+        &[Live(1)]
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-wasm"))]
+fn list_int_arena_allocation() {
+    // like `list_int_dealloc`: increments stay, so the list is still known to be shared
+    assert_refcounts_with_arena_allocation!(
+        indoc!(
+            r#"
+                list = [0x111, 0x222, 0x333]
+                List.len [list, list, list]
+            "#
+        ),
+        usize,
+        &[
+            Live(3), // list
+            Live(1)  // result
+        ]
+    );
+}
@@ -2144,3 +2144,222 @@ fn release_excess_capacity_empty() {
         |value: RocStr| (value.capacity(), value)
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn when_on_many_strings() {
+    // Enough patterns that the `when` switches on the length and then on a byte of the string
+    assert_evals_to!(
+        indoc!(
+            r#"
+            route = \path ->
+                when path is
+                    "/" -> 1
+                    "/users" -> 2
+                    "/posts" -> 3
+                    "/users/me" -> 4
+                    "/about" -> 5
+                    "/aboux" -> 6
+                    _ -> 0
+
+            [
+                route "/",
+                route "/users",
+                route "/posts",
+                route "/users/me",
+                route "/about",
+                route "/aboux",
+                route "/abou",
+                route "/usera",
+                route "",
+                route "this one is not a small string",
+            ]
+            "#
+        ),
+        RocList::from_slice(&[1, 2, 3, 4, 5, 6, 0, 0, 0, 0]),
+        RocList<i64>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn when_on_many_strings_with_guard() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            classify = \word, loud ->
+                when word is
+                    "cat" if loud -> "CAT"
+                    "cat" -> "cat"
+                    "dog" -> "dog"
+                    "cow" -> "cow"
+                    "horse" -> "horse"
+                    _ -> "?"
+
+            words = [
+                classify "cat" Bool.true,
+                classify "cat" Bool.false,
+                classify "cow" Bool.true,
+                classify "hen" Bool.true,
+                classify "horse" Bool.false,
+            ]
+
+            Str.joinWith words ","
+            "#
+        ),
+        RocStr::from("CAT,cat,cow,?,horse"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn regex_captures() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Regex] provides [main] to "./platform"
+
+            main =
+                when Regex.fromStr "(\\w+)@(\\w+)\\.com" is
+                    Ok regex ->
+                        when Regex.captures regex "mail roc@example.com" is
+                            Ok groups -> Str.joinWith groups ","
+                            Err NotFound -> "not found"
+
+                    Err _ -> "invalid pattern"
+            "#
+        ),
+        RocStr::from("roc@example.com,roc,example"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn regex_replace_each() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Regex] provides [main] to "./platform"
+
+            main =
+                when Regex.fromStr "[0-9]+" is
+                    Ok regex -> Regex.replaceEach regex "a1b22c333" "_"
+                    Err _ -> "invalid pattern"
+            "#
+        ),
+        RocStr::from("a_b_c_"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn time_iso8601_round_trip() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Time] provides [main] to "./platform"
+
+            main =
+                when Time.fromIso8601 "2024-02-29T23:30:00.5-01:00" is
+                    Ok instant -> Time.toIso8601 instant
+                    Err InvalidIso8601 -> "invalid"
+            "#
+        ),
+        RocStr::from("2024-03-01T00:30:00.5Z"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn crypto_sha256_str() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Crypto] provides [main] to "./platform"
+
+            main = Crypto.sha256Str "abc" |> Crypto.toHex
+            "#
+        ),
+        RocStr::from("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn crypto_blake3_str() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Crypto] provides [main] to "./platform"
+
+            main = Crypto.blake3Str "abc" |> Crypto.toHex
+            "#
+        ),
+        RocStr::from("6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn crypto_constant_time_eq() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Crypto] provides [main] to "./platform"
+
+            main =
+                same = Crypto.constantTimeEq (Crypto.sha256Str "token") (Crypto.sha256Str "token")
+                different = Crypto.constantTimeEq (Crypto.sha256Str "token") (Crypto.sha256Str "tokem")
+                shorter = Crypto.constantTimeEq [1, 2, 3] [1, 2]
+
+                same && !different && !shorter
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn uuid_v4_from_random_bytes() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Uuid] provides [main] to "./platform"
+
+            main =
+                when Uuid.v4 (List.repeat 0xAB 16) is
+                    Ok uuid -> Uuid.toStr uuid
+                    Err (WrongLength _) -> "wrong length"
+            "#
+        ),
+        RocStr::from("abababab-abab-4bab-abab-abababababab"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn uuid_parse_uppercase() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Uuid] provides [main] to "./platform"
+
+            main =
+                when Uuid.fromStr "F81D4FAE-7DEC-11D0-A765-00A0C91E6BF6" is
+                    Ok uuid -> Uuid.toStr uuid
+                    Err InvalidUuid -> "invalid"
+            "#
+        ),
+        RocStr::from("f81d4fae-7dec-11d0-a765-00a0c91e6bf6"),
+        RocStr
+    );
+}
@@ -1,7 +1,7 @@
 use libloading::Library;
 use roc_build::link::{link, LinkType};
 use roc_builtins::bitcode;
-use roc_load::{EntryPoint, ExecutionMode, LoadConfig, Threading};
+use roc_load::{EntryPoint, ExecutionMode, LoadConfig, OverflowMode, Threading};
 use roc_mono::ir::SingleEntryPoint;
 use roc_packaging::cache::RocCacheDir;
 use roc_region::all::LineInfo;
@@ -33,6 +33,7 @@ pub fn helper(
     src: &str,
     _leak: bool,
     lazy_literals: bool,
+    overflow: OverflowMode,
 ) -> (String, Vec<roc_problem::can::Problem>, Library) {
     use std::path::PathBuf;
 
@@ -58,6 +59,10 @@ pub fn helper(
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow,
+        fold_constants: true,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -268,8 +273,13 @@ macro_rules! assert_evals_to {
         use roc_gen_dev::run_jit_function_raw;
 
         let arena = Bump::new();
-        let (main_fn_name, errors, lib) =
-            $crate::helpers::dev::helper(&arena, $src, $leak, $lazy_literals);
+        let (main_fn_name, errors, lib) = $crate::helpers::dev::helper(
+            &arena,
+            $src,
+            $leak,
+            $lazy_literals,
+            roc_load::OverflowMode::Trap,
+        );
 
         let transform = |success| {
             let expected = $expected;
@@ -281,5 +291,26 @@ macro_rules! assert_evals_to {
     };
 }
 
+/// Like `assert_evals_to!`, with `+`, `-` and `*` on integers built to overflow in the given way
+#[allow(unused_macros)]
+macro_rules! assert_evals_to_with_overflow {
+    ($src:expr, $expected:expr, $ty:ty, $overflow:expr) => {{
+        use bumpalo::Bump;
+        use roc_gen_dev::run_jit_function_raw;
+
+        let arena = Bump::new();
+        let (main_fn_name, errors, lib) =
+            $crate::helpers::dev::helper(&arena, $src, true, false, $overflow);
+
+        let transform = |given| {
+            let expected = $expected;
+            assert_eq!(&given, &expected);
+        };
+        run_jit_function_raw!(lib, main_fn_name, $ty, transform, errors)
+    }};
+}
+
 #[allow(unused_imports)]
 pub(crate) use assert_evals_to;
+#[allow(unused_imports)]
+pub(crate) use assert_evals_to_with_overflow;
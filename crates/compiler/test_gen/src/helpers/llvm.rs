@@ -8,7 +8,9 @@ use roc_collections::all::MutSet;
 use roc_command_utils::zig;
 use roc_gen_llvm::llvm::externs::add_default_roc_externs;
 use roc_gen_llvm::{llvm::build::LlvmBackendMode, run_roc::RocCallResult};
-use roc_load::{EntryPoint, ExecutionMode, LoadConfig, LoadMonomorphizedError, Threading};
+use roc_load::{
+    EntryPoint, ExecutionMode, LoadConfig, LoadMonomorphizedError, OverflowMode, Threading,
+};
 use roc_mono::ir::{CrashTag, OptLevel, SingleEntryPoint};
 use roc_packaging::cache::RocCacheDir;
 use roc_region::all::LineInfo;
@@ -74,6 +76,10 @@ fn create_llvm_module<'a>(
         palette: DEFAULT_PALETTE,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        recover_from_syntax_errors: false,
+        static_data: true,
+        overflow: config.overflow,
+        fold_constants: true,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -98,6 +104,7 @@ fn create_llvm_module<'a>(
     use roc_load::MonomorphizedModule;
     let MonomorphizedModule {
         procedures,
+        static_constants,
         interns,
         mut layout_interner,
         ..
@@ -226,6 +233,11 @@ fn create_llvm_module<'a>(
         mode: config.mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        profile: roc_gen_llvm::llvm::profile::ProfileMode::Off,
+        refcounting: roc_gen_llvm::llvm::refcounting::RefcountMode::NonAtomic,
+        dbg_locations: Default::default(),
+        static_constants,
+        func_spec_names: Default::default(),
     };
 
     // strip Zig debug stuff
@@ -315,6 +327,7 @@ pub struct HelperConfig {
     pub ignore_problems: bool,
     pub add_debug_info: bool,
     pub opt_level: OptLevel,
+    pub overflow: OverflowMode,
 }
 
 #[allow(dead_code)]
@@ -509,7 +522,11 @@ fn fake_wasm_main_function(_: u32, _: u32) -> u32 {
 }
 
 #[cfg(feature = "gen-llvm-wasm")]
-pub fn assert_wasm_evals_to_help<T>(src: &str, ignore_problems: bool) -> Result<T, String>
+pub fn assert_wasm_evals_to_help<T>(
+    src: &str,
+    ignore_problems: bool,
+    overflow: OverflowMode,
+) -> Result<T, String>
 where
     T: FromWasm32Memory + Wasm32Result,
 {
@@ -521,6 +538,7 @@ where
         add_debug_info: false,
         ignore_problems,
         opt_level: OPT_LEVEL,
+        overflow,
     };
 
     let wasm_bytes = compile_to_wasm_bytes(&arena, config, src, &context);
@@ -531,7 +549,11 @@ where
 #[allow(unused_macros)]
 macro_rules! assert_wasm_evals_to {
     ($src:expr, $expected:expr, $ty:ty, $transform:expr, $ignore_problems:expr) => {
-        match $crate::helpers::llvm::assert_wasm_evals_to_help::<$ty>($src, $ignore_problems) {
+        match $crate::helpers::llvm::assert_wasm_evals_to_help::<$ty>(
+            $src,
+            $ignore_problems,
+            roc_load::OverflowMode::Trap,
+        ) {
             Err(msg) => panic!("Wasm test failed: {}", msg),
             Ok(actual) => {
                 assert_eq!($transform(actual), $expected, "Wasm test failed")
@@ -575,8 +597,13 @@ pub fn try_run_lib_function<T>(
 
 // only used in tests
 #[allow(unused)]
-pub(crate) fn llvm_evals_to<T, U, F>(src: &str, expected: U, transform: F, ignore_problems: bool)
-where
+pub(crate) fn llvm_evals_to<T, U, F>(
+    src: &str,
+    expected: U,
+    transform: F,
+    ignore_problems: bool,
+    overflow: OverflowMode,
+) where
     U: PartialEq + std::fmt::Debug,
     F: FnOnce(T) -> U,
 {
@@ -591,6 +618,7 @@ where
         add_debug_info: false,
         ignore_problems,
         opt_level: crate::helpers::llvm::OPT_LEVEL,
+        overflow,
     };
 
     let (main_fn_name, errors, lib) = crate::helpers::llvm::helper(&arena, config, src, &context);
@@ -625,6 +653,7 @@ macro_rules! assert_llvm_evals_to {
             $expected,
             $transform,
             $ignore_problems,
+            roc_load::OverflowMode::Trap,
         );
     };
 
@@ -682,6 +711,29 @@ macro_rules! assert_evals_to {
     }};
 }
 
+/// Like `assert_evals_to!`, with `+`, `-` and `*` on integers built to overflow in the given way
+#[allow(unused_macros)]
+macro_rules! assert_evals_to_with_overflow {
+    ($src:expr, $expected:expr, $ty:ty, $overflow:expr) => {{
+        #[cfg(feature = "gen-llvm-wasm")]
+        match $crate::helpers::llvm::assert_wasm_evals_to_help::<$ty>($src, false, $overflow) {
+            Err(msg) => panic!("Wasm test failed: {}", msg),
+            Ok(actual) => {
+                assert_eq!(actual, $expected, "Wasm test failed")
+            }
+        }
+
+        #[cfg(not(feature = "gen-llvm-wasm"))]
+        $crate::helpers::llvm::llvm_evals_to::<$ty, _, _>(
+            $src,
+            $expected,
+            $crate::helpers::llvm::identity,
+            false,
+            $overflow,
+        );
+    }};
+}
+
 #[allow(dead_code)]
 pub fn identity<T>(value: T) -> T {
     value
@@ -690,6 +742,8 @@ pub fn identity<T>(value: T) -> T {
 #[allow(unused_imports)]
 pub(crate) use assert_evals_to;
 #[allow(unused_imports)]
+pub(crate) use assert_evals_to_with_overflow;
+#[allow(unused_imports)]
 pub(crate) use assert_llvm_evals_to;
 #[allow(unused_imports)]
 pub(crate) use assert_wasm_evals_to;
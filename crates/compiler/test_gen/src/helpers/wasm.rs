@@ -4,7 +4,7 @@ use bumpalo::Bump;
 use roc_collections::all::MutSet;
 use roc_gen_wasm::wasm32_result::Wasm32Result;
 use roc_gen_wasm::DEBUG_SETTINGS;
-use roc_load::{ExecutionMode, LoadConfig, Threading};
+use roc_load::{ExecutionMode, LoadConfig, OverflowMode, Threading};
 use roc_packaging::cache::RocCacheDir;
 use roc_reporting::report::DEFAULT_PALETTE_HTML;
 use roc_std::RocStr;
@@ -51,12 +51,20 @@ pub fn compile_to_wasm_bytes<'a, T: Wasm32Result>(
     arena: &'a bumpalo::Bump,
     src: &str,
     test_wrapper_type_info: PhantomData<T>,
+    overflow: OverflowMode,
+    arena_allocation: bool,
 ) -> Vec<u8> {
     let platform_bytes = include_bytes!(host_bytes_path!());
     println!("Loading test host {}", host_bytes_path!());
 
-    let compiled_bytes =
-        compile_roc_to_wasm_bytes(arena, platform_bytes, src, test_wrapper_type_info);
+    let compiled_bytes = compile_roc_to_wasm_bytes(
+        arena,
+        platform_bytes,
+        src,
+        test_wrapper_type_info,
+        overflow,
+        arena_allocation,
+    );
 
     if write_final_wasm() {
         let build_dir_hash = crate::helpers::src_hash(src);
@@ -71,6 +79,8 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
     host_bytes: &[u8],
     src: &str,
     _test_wrapper_type_info: PhantomData<T>,
+    overflow: OverflowMode,
+    arena_allocation: bool,
 ) -> Vec<u8> {
     let filename = PathBuf::from("Test.roc");
     let src_dir = PathBuf::from("fake/test/path");
@@ -92,6 +102,10 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
         palette: DEFAULT_PALETTE_HTML,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow,
+        fold_constants: true,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -107,7 +121,7 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
     use roc_load::MonomorphizedModule;
     let MonomorphizedModule {
         module_id,
-        procedures,
+        mut procedures,
         mut interns,
         exposed_to_host,
         mut layout_interner,
@@ -116,6 +130,11 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
 
     debug_assert_eq!(exposed_to_host.top_level_values.len(), 1);
 
+    if arena_allocation {
+        // like `--arena-allocation`
+        roc_mono::arena_allocation::remove_decrements(arena, &mut procedures);
+    }
+
     let exposed_to_host = exposed_to_host
         .top_level_values
         .keys()
@@ -173,13 +192,18 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
 }
 
 #[allow(dead_code)]
-pub fn assert_evals_to_help<T>(src: &str, phantom: PhantomData<T>) -> Result<T, String>
+pub fn assert_evals_to_help<T>(
+    src: &str,
+    phantom: PhantomData<T>,
+    overflow: OverflowMode,
+) -> Result<T, String>
 where
     T: FromWasm32Memory + Wasm32Result,
 {
     let arena = bumpalo::Bump::new();
 
-    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, phantom);
+    let wasm_bytes =
+        crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, phantom, overflow, false);
 
     run_wasm_test_bytes::<T>(TEST_WRAPPER_NAME, wasm_bytes)
 }
@@ -256,13 +280,20 @@ pub fn assert_wasm_refcounts_help<T>(
     src: &str,
     phantom: PhantomData<T>,
     num_refcounts: usize,
+    arena_allocation: bool,
 ) -> Result<Vec<RefCount>, String>
 where
     T: FromWasm32Memory + Wasm32Result,
 {
     let arena = bumpalo::Bump::new();
 
-    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, phantom);
+    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(
+        &arena,
+        src,
+        phantom,
+        OverflowMode::Trap,
+        arena_allocation,
+    );
 
     let require_relocatable = false;
     let module = WasmModule::preload(&arena, &wasm_bytes, require_relocatable)
@@ -375,7 +406,11 @@ macro_rules! assert_evals_to {
     ($src:expr, $expected:expr, $ty:ty, $transform:expr, $ignore_problems: expr) => {{
         let phantom = std::marker::PhantomData;
         let _ = $ignore_problems; // Always ignore "problems"! One backend (LLVM) is enough to cover them.
-        match $crate::helpers::wasm::assert_evals_to_help::<$ty>($src, phantom) {
+        match $crate::helpers::wasm::assert_evals_to_help::<$ty>(
+            $src,
+            phantom,
+            roc_load::OverflowMode::Trap,
+        ) {
             Err(msg) => panic!("{}", msg),
             Ok(actual) => {
                 assert_eq!($transform(actual), $expected)
@@ -384,6 +419,20 @@ macro_rules! assert_evals_to {
     }};
 }
 
+/// Like `assert_evals_to!`, with `+`, `-` and `*` on integers built to overflow in the given way
+#[allow(unused_macros)]
+macro_rules! assert_evals_to_with_overflow {
+    ($src:expr, $expected:expr, $ty:ty, $overflow:expr) => {{
+        let phantom = std::marker::PhantomData;
+        match $crate::helpers::wasm::assert_evals_to_help::<$ty>($src, phantom, $overflow) {
+            Err(msg) => panic!("{}", msg),
+            Ok(actual) => {
+                assert_eq!(actual, $expected)
+            }
+        }
+    }};
+}
+
 #[allow(dead_code)]
 pub fn identity<T>(value: T) -> T {
     value
@@ -397,8 +446,33 @@ macro_rules! assert_refcounts {
     ($src: expr, $ty: ty, $expected_refcounts: expr) => {{
         let phantom = std::marker::PhantomData;
         let num_refcounts = $expected_refcounts.len();
-        let result =
-            $crate::helpers::wasm::assert_wasm_refcounts_help::<$ty>($src, phantom, num_refcounts);
+        let result = $crate::helpers::wasm::assert_wasm_refcounts_help::<$ty>(
+            $src,
+            phantom,
+            num_refcounts,
+            false,
+        );
+        match result {
+            Err(msg) => panic!("{:?}", msg),
+            Ok(actual_refcounts) => {
+                assert_eq!(&actual_refcounts, $expected_refcounts)
+            }
+        }
+    }};
+}
+
+/// Like `assert_refcounts!`, with the app built in arena allocation mode
+#[allow(unused_macros)]
+macro_rules! assert_refcounts_with_arena_allocation {
+    ($src: expr, $ty: ty, $expected_refcounts: expr) => {{
+        let phantom = std::marker::PhantomData;
+        let num_refcounts = $expected_refcounts.len();
+        let result = $crate::helpers::wasm::assert_wasm_refcounts_help::<$ty>(
+            $src,
+            phantom,
+            num_refcounts,
+            true,
+        );
         match result {
             Err(msg) => panic!("{:?}", msg),
             Ok(actual_refcounts) => {
@@ -410,6 +484,10 @@ macro_rules! assert_refcounts {
 
 #[allow(unused_imports)]
 pub(crate) use assert_evals_to;
+#[allow(unused_imports)]
+pub(crate) use assert_evals_to_with_overflow;
 
 #[allow(unused_imports)]
 pub(crate) use assert_refcounts;
+#[allow(unused_imports)]
+pub(crate) use assert_refcounts_with_arena_allocation;
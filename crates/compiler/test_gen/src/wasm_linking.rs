@@ -13,6 +13,7 @@ use roc_module::symbol::{
     IdentIds, IdentIdsByModule, Interns, ModuleId, ModuleIds, PackageModuleIds, PackageQualified,
     Symbol,
 };
+use roc_mono::borrow::Ownership;
 use roc_mono::ir::{
     Call, CallType, Expr, HostExposedLayouts, Literal, Proc, ProcLayout, SelfRecursive, Stmt,
     UpdateModeId,
@@ -49,6 +50,9 @@ fn build_app_mono<'a>(
         call_type: CallType::Foreign {
             foreign_symbol: ForeignSymbol::from("js_called_directly_from_roc"),
             ret_layout: int_layout,
+            arg_ownership: &[],
+            ret_ownership: Ownership::Owned,
+            pure: false,
         },
         arguments: &[],
     });
@@ -57,6 +61,9 @@ fn build_app_mono<'a>(
         call_type: CallType::Foreign {
             foreign_symbol: ForeignSymbol::from("host_called_directly_from_roc"),
             ret_layout: int_layout,
+            arg_ownership: &[],
+            ret_ownership: Ownership::Owned,
+            pure: false,
         },
         arguments: &[],
     });
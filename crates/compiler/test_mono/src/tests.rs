@@ -16,9 +16,9 @@ const EXPANDED_STACK_SIZE: usize = 8 * 1024 * 1024;
 use bumpalo::Bump;
 use roc_collections::all::MutMap;
 use roc_load::ExecutionMode;
-use roc_load::LoadConfig;
 use roc_load::LoadMonomorphizedError;
 use roc_load::Threading;
+use roc_load::{LoadConfig, OverflowMode};
 use roc_module::symbol::Interns;
 use roc_module::symbol::Symbol;
 use roc_mono::ir::Proc;
@@ -76,7 +76,19 @@ fn promote_expr_to_module(src: &str) -> String {
     buffer
 }
 
-fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: bool, no_check: bool) {
+#[allow(clippy::too_many_arguments)]
+fn compiles_to_ir(
+    test_name: &str,
+    src: &str,
+    mode: &str,
+    allow_type_errors: bool,
+    no_check: bool,
+    fold_constants: bool,
+    arena_allocation: bool,
+    schedule_refcounts: bool,
+    explain_closures: bool,
+    explain_specializations: bool,
+) {
     use roc_packaging::cache::RocCacheDir;
     use std::path::PathBuf;
 
@@ -93,7 +105,7 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: boo
 
     let module_src;
     let temp;
-    if src.starts_with("app") || src.starts_with("interface") {
+    if src.starts_with("app") || src.starts_with("interface") || src.starts_with("hosted") {
         // this is already a module
         module_src = src;
     } else {
@@ -108,6 +120,10 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: boo
         render: roc_reporting::report::RenderTarget::Generic,
         palette: roc_reporting::report::DEFAULT_PALETTE,
         exec_mode,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -132,7 +148,7 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: boo
     use roc_load::MonomorphizedModule;
     let MonomorphizedModule {
         module_id: home,
-        procedures,
+        mut procedures,
         exposed_to_host,
         mut layout_interner,
         interns,
@@ -150,10 +166,40 @@ fn compiles_to_ir(test_name: &str, src: &str, mode: &str, allow_type_errors: boo
 
     let main_fn_symbol = exposed_to_host.top_level_values.keys().copied().next();
 
+    if arena_allocation {
+        // like `--arena-allocation`, which runs the pass before code generation
+        roc_mono::arena_allocation::remove_decrements(arena, &mut procedures);
+    }
+
+    if schedule_refcounts {
+        // like `--schedule-refcounts`, which runs the pass before code generation
+        roc_mono::rc_scheduling::schedule_refcounts(arena, &mut procedures);
+    }
+
     if !no_check {
         check_procedures(arena, &interns, &mut layout_interner, &procedures);
     }
 
+    if explain_closures {
+        // the report `--explain-closures` prints, next to the IR
+        let report =
+            roc_mono::debug::format_closures(&interns, &layout_interner, &procedures).to_string();
+
+        verify_snapshot(&format!("generated/{}.closures.txt", test_name), &report);
+    }
+
+    if explain_specializations {
+        // the report `--explain-specializations` prints
+        let report =
+            roc_mono::debug::format_specializations(&interns, &layout_interner, &procedures)
+                .to_string();
+
+        verify_snapshot(
+            &format!("generated/{}.specializations.txt", test_name),
+            &report,
+        );
+    }
+
     verify_procedures(test_name, layout_interner, procedures, main_fn_symbol);
 }
 
@@ -199,31 +245,34 @@ fn verify_procedures<'a>(
 
     let result = procs_string.join("\n");
 
-    let path = format!("generated/{}.txt", test_name);
+    verify_snapshot(&format!("generated/{}.txt", test_name), &result);
+}
+
+fn verify_snapshot(path: &str, contents: &str) {
     std::fs::create_dir_all("generated").unwrap();
-    std::fs::write(&path, result).unwrap();
+    std::fs::write(path, contents).unwrap();
 
     use std::process::Command;
 
     let is_tracked = Command::new("git")
-        .args(["ls-files", "--error-unmatch", &path])
+        .args(["ls-files", "--error-unmatch", path])
         .output()
         .unwrap();
 
     if !is_tracked.status.success() {
         panic!(
             "The file {:?} is not tracked by git. Try using `git add` on it",
-            &path
+            path
         );
     }
 
     let has_changes = Command::new("git")
-        .args(["diff", "--color=always", &path])
+        .args(["diff", "--color=always", path])
         .output()
         .unwrap();
 
     if !has_changes.status.success() {
-        eprintln!("`git diff {:?}` failed", &path);
+        eprintln!("`git diff {:?}` failed", path);
         unreachable!();
     }
 
@@ -1079,6 +1128,42 @@ fn specialize_closures() {
     )
 }
 
+#[mono_test(explain_closures = "true")]
+fn flatten_single_small_capture() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        apply : ({} -> U64) -> U64
+        apply = \f -> f {}
+
+        main =
+            small : U64
+            small = 1
+
+            big : { x : U64, y : U64, z : U64 }
+            big = { x: 2, y: 3, z: 4 }
+
+            two : U64
+            two = 5
+
+            single = \{} -> small
+            large = \{} -> big.x
+            several = \{} -> small + two
+
+            f =
+                if Bool.true then
+                    single
+                else if Bool.false then
+                    large
+                else
+                    several
+
+            apply f
+        "#
+    )
+}
+
 #[mono_test]
 fn specialize_lowlevel() {
     indoc!(
@@ -1241,6 +1326,23 @@ fn monomorphized_list() {
     )
 }
 
+#[mono_test(explain_specializations = "true")]
+fn specializations_of_local_function() {
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main =
+            l = \{} -> [1, 2, 3]
+
+            f : List U8, List U16 -> Nat
+            f = \_, _ -> 18
+
+            f (l {}) (l {})
+        "#
+    )
+}
+
 #[mono_test]
 fn monomorphized_applied_tag() {
     indoc!(
@@ -1571,6 +1673,25 @@ fn tail_call_elimination() {
     )
 }
 
+#[mono_test]
+fn mutual_tail_calls_merged() {
+    indoc!(
+        r#"
+        isEven = \n ->
+            when n is
+                0 -> Bool.true
+                _ -> isOdd (n - 1)
+
+        isOdd = \n ->
+            when n is
+                0 -> Bool.false
+                _ -> isEven (n - 1)
+
+        isEven 10
+        "#
+    )
+}
+
 #[mono_test]
 fn tail_call_with_same_layout_different_lambda_sets() {
     indoc!(
@@ -2923,3 +3044,149 @@ fn error_on_erroneous_condition() {
         "#
     )
 }
+
+#[mono_test(fold_constants = "true")]
+fn constant_folding_int_add() {
+    r#"
+    x = [1,2]
+    5 + 4 + 3 + List.len x
+    "#
+}
+
+#[mono_test(fold_constants = "true")]
+fn constant_folding_list_len() {
+    r#"
+    x = [1,2,3]
+    y = [1.0]
+
+    List.len x + List.len y
+    "#
+}
+
+#[mono_test(fold_constants = "true")]
+fn constant_folding_two_defs() {
+    r#"
+    x = 3
+    y = 4
+
+    x + y
+    "#
+}
+
+#[mono_test(fold_constants = "true")]
+fn constant_folding_when_on_record() {
+    r#"
+    when { x: 0x2 } is
+        { x } -> x + 3
+    "#
+}
+
+#[mono_test(schedule_refcounts = "true")]
+fn rc_scheduling_dec_after_last_use_of_child() {
+    // `dec Test.50` moves up past `Test.49`, but not past `Test.8`, which is indexed out of it
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        main =
+            isCorrectOrder { left: IsList [IsInteger 10], right: IsList [IsInteger 20] }
+
+        isCorrectOrder = \pair ->
+            when pair is
+                { left: IsInteger left, right: IsInteger right } -> left < right
+                { left: IsList l, right: IsList r } ->
+                    if List.map2 l r (\left, right -> { left, right }) |> List.all isCorrectOrder then
+                        List.len l < List.len r
+                    else
+                        Bool.false
+
+                { left: IsList _, right: IsInteger _ } -> isCorrectOrder { left: pair.left, right: IsList [pair.right] }
+                { left: IsInteger _, right: IsList _ } -> isCorrectOrder { left: IsList [pair.left], right: pair.right }
+        "#
+    )
+}
+
+#[mono_test(schedule_refcounts = "true")]
+fn rc_scheduling_dec_after_last_use() {
+    // `dec #Derived_gen.13` moves up to just after the call which uses it
+    indoc!(
+        r#"
+        app "test"
+            imports [Encode.{ toEncoder }, Json]
+            provides [main] to "./platform"
+
+        main =
+            result = Str.fromUtf8 (Encode.toBytes {a: "foo", b: "bar"} Json.toUtf8)
+            when result is
+                Ok s -> s
+                _ -> "<bad>"
+        "#
+    )
+}
+
+#[mono_test(schedule_refcounts = "true")]
+fn rc_scheduling_inc_before_reset() {
+    // `inc Test.6` stays above the reset of its parent, which looks at the refcount
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        Html state : [
+            Element (List (Html state)),
+        ]
+
+        translateStatic : Html _ -> Html _
+        translateStatic = \node ->
+            when node is
+                Element children ->
+                    newChildren = List.map children translateStatic
+
+                    Element newChildren
+
+        main = when translateStatic (Element []) is
+            _ -> ""
+        "#
+    )
+}
+
+#[mono_test(arena_allocation = "true")]
+fn arena_allocation_removes_decrements() {
+    // the `dec` and `decref` are gone, and the reset no longer decrements the children
+    indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        Html state : [
+            Element (List (Html state)),
+        ]
+
+        translateStatic : Html _ -> Html _
+        translateStatic = \node ->
+            when node is
+                Element children ->
+                    newChildren = List.map children translateStatic
+
+                    Element newChildren
+
+        main = when translateStatic (Element []) is
+            _ -> ""
+        "#
+    )
+}
+
+#[mono_test(mode = "test")]
+fn host_intrinsic_is_a_foreign_call() {
+    // a hosted function which doesn't return an effect calls the host right away
+    indoc!(
+        r#"
+        hosted Test
+            exposes [isReady]
+            imports []
+            generates Effect with []
+
+        isReady : U8 -> Bool
+
+        expect isReady 1
+        "#
+    )
+}
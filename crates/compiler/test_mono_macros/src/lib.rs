@@ -8,6 +8,11 @@ use quote::quote;
 pub fn mono_test(args: TokenStream, item: TokenStream) -> TokenStream {
     let mut no_check = false;
     let mut allow_type_errors = false;
+    let mut fold_constants = false;
+    let mut arena_allocation = false;
+    let mut schedule_refcounts = false;
+    let mut explain_closures = false;
+    let mut explain_specializations = false;
     let mut mode = "exec".to_owned();
     for arg in syn::parse_macro_input!(args as syn::AttributeArgs) {
         use syn::{Lit, Meta, MetaNameValue, NestedMeta};
@@ -26,6 +31,21 @@ pub fn mono_test(args: TokenStream, item: TokenStream) -> TokenStream {
             if path.is_ident("allow_type_errors") {
                 allow_type_errors = true;
             }
+            if path.is_ident("fold_constants") {
+                fold_constants = true;
+            }
+            if path.is_ident("arena_allocation") {
+                arena_allocation = true;
+            }
+            if path.is_ident("schedule_refcounts") {
+                schedule_refcounts = true;
+            }
+            if path.is_ident("explain_closures") {
+                explain_closures = true;
+            }
+            if path.is_ident("explain_specializations") {
+                explain_specializations = true;
+            }
         }
     }
 
@@ -44,7 +64,7 @@ pub fn mono_test(args: TokenStream, item: TokenStream) -> TokenStream {
         #[test]
         #(#attributes)*
         #visibility fn #name(#args) {
-            compiles_to_ir(#name_str, #body, &#mode, #allow_type_errors, #no_check);
+            compiles_to_ir(#name_str, #body, &#mode, #allow_type_errors, #no_check, #fold_constants, #arena_allocation, #schedule_refcounts, #explain_closures, #explain_specializations);
 
         }
     };
@@ -0,0 +1 @@
+0x1_0.8p-3
@@ -7,8 +7,8 @@ hosted Foo
     ]
     imports
     [
-        Blah,
         Baz.{ stuff, things },
+        Blah,
     ]
     generates Bar with
     [
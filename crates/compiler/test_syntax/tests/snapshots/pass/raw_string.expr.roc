@@ -0,0 +1,7 @@
+a = r"C:\Users\(me)"
+b =
+    r"""
+    line \n one
+      "two"
+    """
+a
@@ -1156,6 +1156,69 @@ mod test_fmt {
         ));
     }
 
+    #[test]
+    fn interpolated_block_string() {
+        expr_formats_same(indoc!(
+            r#"
+            """
+            Hello, \(name)!
+            Goodbye
+            """
+            "#
+        ));
+    }
+
+    #[test]
+    fn raw_string() {
+        expr_formats_same(indoc!(
+            r#"
+            r"C:\Users\(name)"
+            "#
+        ));
+    }
+
+    #[test]
+    fn raw_block_string() {
+        expr_formats_same(indoc!(
+            r#"
+            x =
+                r"""
+                C:\Users
+
+                "quoted" \(not interpolated)
+                """
+
+            x
+            "#
+        ));
+    }
+
+    #[test]
+    fn oneline_raw_block_string() {
+        expr_formats_to(
+            indoc!(
+                r#"
+                r"""C:\Users"""
+                "#
+            ),
+            indoc!(
+                r#"
+                r"C:\Users"
+                "#
+            ),
+        );
+    }
+
+    #[test]
+    fn raw_string_in_pattern() {
+        expr_formats_same(indoc!(
+            r#"
+            when foo is
+                r"\n" -> ""
+            "#
+        ));
+    }
+
     #[test]
     fn zero() {
         expr_formats_same(indoc!(
@@ -1192,6 +1255,28 @@ mod test_fmt {
         ));
     }
 
+    #[test]
+    fn non_decimal_ints_with_underscores() {
+        expr_formats_same(indoc!(
+            r#"
+            x = 0b1010_1100
+
+            0xFF_FF
+            "#
+        ));
+    }
+
+    #[test]
+    fn hex_floats() {
+        expr_formats_same(indoc!(
+            r#"
+            x = 0x1.8p3
+
+            -0x1_0.0p-4f64
+            "#
+        ));
+    }
+
     #[test]
     fn multi_arg_closure() {
         expr_formats_same(indoc!(
@@ -4751,7 +4836,7 @@ mod test_fmt {
             r#"
                 interface Foo
                     exposes [Bar, Baz, a, b]
-                    imports [Blah, Thing.{ foo, bar }, Stuff]"#
+                    imports [Blah, Stuff, Thing.{ foo, bar }]"#
         ));
     }
 
@@ -4766,12 +4851,100 @@ mod test_fmt {
                         somethingElse,
                     ]
                     imports [
-                        Blah,
                         Baz.{ stuff, things },
+                        Blah,
                     ]"#
         ));
     }
 
+    #[test]
+    fn imports_are_sorted() {
+        module_formats_to(
+            indoc!(
+                r#"
+                interface Foo
+                    exposes []
+                    imports [Thing.{ foo }, pf.Task, Blah, json.Core, pf.Stdout, "data.txt" as data : Str]"#
+            ),
+            indoc!(
+                r#"
+                interface Foo
+                    exposes []
+                    imports [Blah, Thing.{ foo }, json.Core, pf.Stdout, pf.Task, "data.txt" as data : Str]"#
+            ),
+        );
+    }
+
+    #[test]
+    fn duplicate_imports_are_merged() {
+        module_formats_to(
+            indoc!(
+                r#"
+                interface Foo
+                    exposes []
+                    imports [
+                        Encode.{ Encoder },
+                        Result,
+                        Encode.{ Encoding, Encoder, custom },
+                        Result.{ Result },
+                    ]"#
+            ),
+            indoc!(
+                r#"
+                interface Foo
+                    exposes []
+                    imports [
+                        Encode.{ Encoder, Encoding, custom },
+                        Result.{ Result },
+                    ]"#
+            ),
+        );
+    }
+
+    #[test]
+    fn duplicate_imports_with_comments_are_kept() {
+        module_formats_same(indoc!(
+            r#"
+                interface Foo
+                    exposes []
+                    imports [
+                        Blah,
+                        # needed for the tests
+                        Blah,
+                    ]"#
+        ));
+    }
+
+    #[test]
+    fn comments_move_with_sorted_imports() {
+        module_formats_to(
+            indoc!(
+                r#"
+                interface Foo
+                    exposes []
+                    imports [
+                        # parses things
+                        Parser,
+                        # prints things
+                        Printer,
+                        Blah,
+                    ]"#
+            ),
+            indoc!(
+                r#"
+                interface Foo
+                    exposes []
+                    imports [
+                        Blah,
+                        # parses things
+                        Parser,
+                        # prints things
+                        Printer,
+                    ]"#
+            ),
+        );
+    }
+
     #[test]
     fn single_line_app() {
         module_formats_same(indoc!(
@@ -4875,8 +5048,8 @@ mod test_fmt {
                         somethingElse,
                     ]
                     imports [
-                        Blah,
                         Baz.{ stuff, things },
+                        Blah,
                     ]
                     generates Bar with [
                         map,
@@ -5777,6 +5950,23 @@ mod test_fmt {
         ));
     }
 
+    #[test]
+    fn ability_member_default() {
+        module_formats_same(indoc!(
+            r#"
+            interface Foo exposes [] imports []
+
+            A has
+                ab : a, a -> Bool | a has A
+                ## This is member de
+                de : a, a -> Bool | a has A
+                de = \x, y -> Bool.not (ab x y)
+
+            f = g
+            "#
+        ));
+    }
+
     #[test]
     fn leading_comments_preserved() {
         module_formats_same(indoc!(
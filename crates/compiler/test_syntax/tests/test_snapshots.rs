@@ -318,6 +318,7 @@ mod test_snapshots {
         pass/function_effect_types.header,
         pass/function_with_tuple_ext_type.expr,
         pass/function_with_tuple_type.expr,
+        pass/hex_float.expr,
         pass/highest_float.expr,
         pass/highest_int.expr,
         pass/if_def.expr,
@@ -421,6 +422,7 @@ mod test_snapshots {
         pass/provides_type.header,
         pass/qualified_field.expr,
         pass/qualified_var.expr,
+        pass/raw_string.expr,
         pass/record_access_after_tuple.expr,
         pass/record_destructure_def.expr,
         pass/record_func_type_decl.expr,
@@ -798,6 +800,32 @@ mod test_snapshots {
         });
     }
 
+    #[test]
+    fn block_string_with_interpolation() {
+        // The interpolation's region should point inside the literal, even on a later line
+        let arena = Bump::new();
+        let actual = parse_expr_with(&arena, "\"\"\"\nHi,\n\\(name)!\n\"\"\"");
+        let expr = arena.alloc(Var {
+            module_name: "",
+            ident: "name",
+        });
+        let expected_lines = [&[
+            Plaintext("Hi,\n"),
+            Interpolated(Loc::new(10, 14, expr)),
+            Plaintext("!"),
+        ][..]];
+
+        assert_eq!(Ok(Expr::Str(Block(&expected_lines))), actual);
+    }
+
+    #[test]
+    fn raw_string() {
+        let arena = Bump::new();
+        let actual = parse_expr_with(&arena, r#"r"\(name)\n""#);
+
+        assert_eq!(Ok(Expr::Str(Raw(r"\(name)\n"))), actual);
+    }
+
     #[test]
     fn empty_source_file() {
         assert_parsing_fails("", SyntaxError::Eof(Region::zero()));
@@ -128,14 +128,21 @@ impl NumericRange {
     /// I64 is chosen if the range says that the number will fit,
     /// otherwise the next-largest number layout is chosen.
     pub fn default_compilation_width(&self) -> IntLitWidth {
+        self.compilation_width_preferring(IntLitWidth::I64)
+    }
+
+    /// Like [`Self::default_compilation_width`], but tries `preferred` (a module's default
+    /// integer type) before falling back to the usual widths.
+    pub fn compilation_width_preferring(&self, preferred: IntLitWidth) -> IntLitWidth {
         *match self {
             NumericRange::IntAtLeastSigned(w) | NumericRange::NumAtLeastSigned(w) => {
-                [IntLitWidth::I64, IntLitWidth::I128]
+                [preferred, IntLitWidth::I64, IntLitWidth::I128]
                     .iter()
                     .find(|candidate| candidate.is_superset(w, true))
                     .expect("if number doesn't fit, should have been a type error")
             }
             NumericRange::IntAtLeastEitherSign(w) | NumericRange::NumAtLeastEitherSign(w) => [
+                preferred,
                 IntLitWidth::I64,
                 IntLitWidth::U64,
                 IntLitWidth::I128,
@@ -339,6 +346,49 @@ pub enum FloatWidth {
     F64,
 }
 
+/// The types number literals are compiled as when nothing else determines their type, e.g. the
+/// `5` in `Num.toStr 5`. A module can choose its own defaults by defining the top-level aliases
+/// `DefaultInt` and `DefaultFrac`, for example `DefaultInt : I32`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NumLiteralDefaults {
+    /// Used for unresolved `Num *` and `Int *`; always an integer width.
+    pub int: IntLitWidth,
+    /// Used for unresolved `Frac *`.
+    pub frac: FloatWidth,
+}
+
+impl Default for NumLiteralDefaults {
+    fn default() -> Self {
+        Self {
+            int: IntLitWidth::I64,
+            frac: FloatWidth::F64,
+        }
+    }
+}
+
+impl NumLiteralDefaults {
+    pub const INT_ALIAS_NAME: &'static str = "DefaultInt";
+    pub const FRAC_ALIAS_NAME: &'static str = "DefaultFrac";
+
+    /// The integer width named by `symbol`, if it can be the default for integer literals.
+    pub fn int_from_symbol(symbol: Symbol) -> Option<IntLitWidth> {
+        use IntLitWidth::*;
+        [U8, U16, U32, U64, U128, I8, I16, I32, I64, I128, Nat]
+            .into_iter()
+            .find(|width| width.symbol() == symbol)
+    }
+
+    /// The fraction width named by `symbol`, if it can be the default for fraction literals.
+    pub fn frac_from_symbol(symbol: Symbol) -> Option<FloatWidth> {
+        match symbol {
+            Symbol::NUM_DEC => Some(FloatWidth::Dec),
+            Symbol::NUM_F32 => Some(FloatWidth::F32),
+            Symbol::NUM_F64 => Some(FloatWidth::F64),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum SignDemand {
     /// Can be signed or unsigned.
@@ -5,7 +5,7 @@ use crate::subs::{
     UnsortedUnionLabels, Variable,
 };
 use crate::types::{
-    name_type_var, name_type_var_with_hint, AbilitySet, Polarity, RecordField, Uls,
+    name_type_var, name_type_var_with_hint, AbilitySet, AliasKind, Polarity, RecordField, Uls,
 };
 use roc_collections::all::MutMap;
 use roc_collections::VecSet;
@@ -636,6 +636,133 @@ pub fn name_and_print_var(
     )
 }
 
+/// The definitions of the aliases in the type of `var`, like `Alias Point = { x : I64, y : I64 }`.
+///
+/// Types are printed with their aliases preserved, so these tell what the aliases stand for.
+/// The aliases in a definition are not expanded either, but get a definition of their own.
+/// Builtin aliases like `Num a` are well known, so they are left out.
+pub fn alias_hints(
+    var: Variable,
+    subs: &mut Subs,
+    home: ModuleId,
+    interns: &Interns,
+) -> Vec<String> {
+    let mut aliases = Vec::new();
+    find_aliases(subs, var, &mut VecSet::default(), &mut aliases);
+
+    // Name the type variables of the definition along with the arguments of the alias, so a
+    // variable which appears once in the arguments but twice in the definition still gets a name.
+    let naming = DebugPrint {
+        print_only_under_alias: true,
+        ..DebugPrint::NOTHING
+    };
+
+    aliases
+        .into_iter()
+        .map(|(alias_var, actual)| {
+            let named_result = name_all_type_vars(alias_var, subs, naming);
+            let alias = variable_to_string(
+                alias_var,
+                subs,
+                home,
+                interns,
+                named_result,
+                DebugPrint::NOTHING,
+                Polarity::Pos,
+            );
+
+            let named_result = name_all_type_vars(actual, subs, DebugPrint::NOTHING);
+            let definition = variable_to_string(
+                actual,
+                subs,
+                home,
+                interns,
+                named_result,
+                DebugPrint::NOTHING,
+                Polarity::Pos,
+            );
+
+            format!("Alias {} = {}", alias, definition)
+        })
+        .collect()
+}
+
+/// The structural aliases which aren't builtins in the type of `var`, with their actual type,
+/// in the order they are printed in.
+fn find_aliases(
+    subs: &Subs,
+    var: Variable,
+    visited: &mut VecSet<Variable>,
+    aliases: &mut Vec<(Variable, Variable)>,
+) {
+    use crate::subs::Content::*;
+    use crate::subs::FlatType::*;
+
+    // `insert` returns whether the variable was already there
+    if visited.insert(subs.get_root_key_without_compacting(var)) {
+        return;
+    }
+
+    let mut children = Vec::new();
+
+    match subs.get_content_without_compacting(var) {
+        Alias(symbol, args, actual, kind) => {
+            children.extend(args.named_type_arguments().map(|index| subs[index]));
+
+            let is_listed = aliases.iter().any(|(alias_var, _)| {
+                matches!(
+                    subs.get_content_without_compacting(*alias_var),
+                    Alias(listed, ..) if listed == symbol
+                )
+            });
+
+            if *kind == AliasKind::Structural && !symbol.module_id().is_builtin() && !is_listed {
+                aliases.push((var, *actual));
+
+                // the aliases in the definition are listed after this one
+                children.push(*actual);
+            }
+        }
+        Structure(Apply(_, args)) => {
+            children.extend(args.into_iter().map(|index| subs[index]));
+        }
+        Structure(Func(args, _closure, ret)) => {
+            children.extend(args.into_iter().map(|index| subs[index]));
+            children.push(*ret);
+        }
+        Structure(Record(fields, ext)) => {
+            children.extend(fields.iter_variables().map(|index| subs[index]));
+            children.push(*ext);
+        }
+        Structure(Tuple(elems, ext)) => {
+            children.extend(elems.iter_variables().map(|index| subs[index]));
+            children.push(*ext);
+        }
+        Structure(TagUnion(tags, ext)) | Structure(RecursiveTagUnion(_, tags, ext)) => {
+            for slice_index in tags.variables() {
+                children.extend(subs[slice_index].into_iter().map(|index| subs[index]));
+            }
+            children.push(ext.var());
+        }
+        Structure(FunctionOrTagUnion(_, _, ext)) => children.push(ext.var()),
+        FlexVar(_)
+        | RigidVar(_)
+        | FlexAbleVar(..)
+        | RigidAbleVar(..)
+        | RecursionVar { .. }
+        | LambdaSet(_)
+        | RangedNumber(_)
+        | Error
+        | Structure(EmptyRecord)
+        | Structure(EmptyTuple)
+        | Structure(EmptyTagUnion) => {}
+    }
+
+    for child in children {
+        find_aliases(subs, child, visited, aliases);
+    }
+}
+
 pub fn get_single_arg<'a>(subs: &'a Subs, args: &'a AliasVariables) -> Variable {
     debug_assert_eq!(args.len(), 1);
 
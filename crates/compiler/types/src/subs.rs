@@ -1717,6 +1717,8 @@ impl Subs {
     pub const AB_HASH: SubsSlice<Symbol>     = SubsSlice::new(3, 1);
     #[rustfmt::skip]
     pub const AB_EQ: SubsSlice<Symbol>       = SubsSlice::new(4, 1);
+    #[rustfmt::skip]
+    pub const AB_ENUM: SubsSlice<Symbol>     = SubsSlice::new(5, 1);
 
     pub fn new() -> Self {
         Self::with_capacity(0)
@@ -1741,6 +1743,7 @@ impl Subs {
         symbol_names.push(Symbol::HASH_HASHER);
         symbol_names.push(Symbol::HASH_HASH_ABILITY);
         symbol_names.push(Symbol::BOOL_EQ);
+        symbol_names.push(Symbol::ENUM_ENUM);
 
         let mut subs = Subs {
             utable: UnificationTable::default(),
@@ -2138,6 +2141,37 @@ impl Subs {
         self.utable.is_empty()
     }
 
+    /// Rewrite every symbol in these subs with `f`, e.g. to move subs solved in one compilation
+    /// into another, where modules have different ids. Fails if `f` fails for any symbol.
+    pub fn try_map_symbols(&mut self, f: &impl Fn(Symbol) -> Option<Symbol>) -> Option<()> {
+        // Ability bounds, lambda set labels, and the functions of FunctionOrTagUnion all live here
+        for symbol in self.symbol_names.iter_mut() {
+            *symbol = f(*symbol)?;
+        }
+
+        for Uls(_, member, _) in self.unspecialized_lambda_sets.iter_mut() {
+            *member = f(*member)?;
+        }
+
+        for index in 0..self.len() {
+            let var = unsafe { Variable::from_index(index as u32) };
+
+            let content = match *self.get_content_unchecked(var) {
+                Content::Alias(symbol, args, real_var, kind) => {
+                    Content::Alias(f(symbol)?, args, real_var, kind)
+                }
+                Content::Structure(FlatType::Apply(symbol, args)) => {
+                    Content::Structure(FlatType::Apply(f(symbol)?, args))
+                }
+                _ => continue,
+            };
+
+            self.set_content_unchecked(var, content);
+        }
+
+        Some(())
+    }
+
     pub fn contains(&self, var: Variable) -> bool {
         (var.index() as usize) < self.len()
     }
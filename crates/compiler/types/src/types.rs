@@ -2742,6 +2742,70 @@ impl Type {
         }
     }
 
+    /// Like `contains_symbol`, but only looks for references to the given alias which have not
+    /// been instantiated yet.
+    pub fn contains_delayed_alias(&self, rep_symbol: Symbol) -> bool {
+        use Type::*;
+
+        let in_ext = |ext: &TypeExtension| match ext {
+            TypeExtension::Open(ext, _) => ext.contains_delayed_alias(rep_symbol),
+            TypeExtension::Closed => false,
+        };
+
+        match self {
+            Function(args, closure, ret) => {
+                ret.contains_delayed_alias(rep_symbol)
+                    || closure.contains_delayed_alias(rep_symbol)
+                    || args
+                        .iter()
+                        .any(|arg| arg.contains_delayed_alias(rep_symbol))
+            }
+            FunctionOrTagUnion(_, _, ext) => in_ext(ext),
+            RecursiveTagUnion(_, tags, ext) | TagUnion(tags, ext) => {
+                in_ext(ext)
+                    || tags
+                        .iter()
+                        .flat_map(|v| v.1.iter())
+                        .any(|arg| arg.contains_delayed_alias(rep_symbol))
+            }
+            Record(fields, ext) => {
+                in_ext(ext)
+                    || fields
+                        .values()
+                        .any(|arg| arg.as_inner().contains_delayed_alias(rep_symbol))
+            }
+            Tuple(elems, ext) => {
+                in_ext(ext)
+                    || elems
+                        .iter()
+                        .any(|(_, arg)| arg.contains_delayed_alias(rep_symbol))
+            }
+            DelayedAlias(AliasCommon {
+                symbol,
+                type_arguments,
+                ..
+            }) => {
+                symbol == &rep_symbol
+                    || type_arguments
+                        .iter()
+                        .any(|v| v.value.typ.contains_delayed_alias(rep_symbol))
+            }
+            Alias { actual, .. } | HostExposedAlias { actual, .. } => {
+                actual.contains_delayed_alias(rep_symbol)
+            }
+            Apply(_, args, _) => args
+                .iter()
+                .any(|arg| arg.value.contains_delayed_alias(rep_symbol)),
+            RangedNumber(_)
+            | UnspecializedLambdaSet { .. }
+            | EmptyRec
+            | EmptyTagUnion
+            | ClosureTag { .. }
+            | Error
+            | Variable(_) => false,
+        }
+    }
+
     fn contains_variable_ext(ext: &TypeExtension, rep_variable: Variable) -> bool {
         match ext {
             TypeExtension::Open(ext, _) => ext.contains_variable(rep_variable),
@@ -3775,6 +3839,11 @@ pub enum Mismatch {
 
 pub type DoesNotImplementAbility = Vec<(ErrorType, Symbol)>;
 
+/// The biggest (see [ErrorType::size]) an alias can be for error messages to show its definition in
+/// place of its name. Bigger aliases are always shown by name, and their definition is listed
+/// below the types.
+pub const ALIAS_EXPANSION_BUDGET: usize = 16;
+
 #[derive(PartialEq, Eq, Clone, Hash)]
 pub enum ErrorType {
     Infinite,
@@ -3815,6 +3884,30 @@ impl ErrorType {
         }
     }
 
+    /// Roughly how big this type is when it's printed: the number of types, fields and tags in it.
+    /// Aliases are printed by name, so their definition doesn't count.
+    pub fn size(&self) -> usize {
+        use ErrorType::*;
+
+        let sum = |types: &[ErrorType]| types.iter().map(ErrorType::size).sum::<usize>();
+
+        match self {
+            Infinite | FlexVar(_) | RigidVar(_) | FlexAbleVar(..) | RigidAbleVar(..) | Error => 1,
+            Type(_, args) | Alias(_, args, _, _) | Range(args) => 1 + sum(args),
+            Record(fields, _) => {
+                1 + fields
+                    .iter()
+                    .map(|(_, field)| 1 + field.as_inner().size())
+                    .sum::<usize>()
+            }
+            Tuple(elems, _) => 1 + elems.iter().map(|(_, elem)| elem.size()).sum::<usize>(),
+            TagUnion(tags, _, _) | RecursiveTagUnion(_, tags, _, _) => {
+                1 + tags.iter().map(|(_, args)| 1 + sum(args)).sum::<usize>()
+            }
+            Function(args, _, ret) => 1 + sum(args) + ret.size(),
+        }
+    }
+
     /// Adds all named type variables used in the type to a set.
     pub fn add_names(&self, taken: &mut MutSet<Lowercase>) {
         use ErrorType::*;
@@ -7,7 +7,7 @@ use roc_can::scope::Scope;
 use roc_collections::VecSet;
 use roc_load::docs::{DocEntry, TypeAnnotation};
 use roc_load::docs::{ModuleDocumentation, RecordField};
-use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
+use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, OverflowMode, Threading};
 use roc_module::symbol::{Interns, Symbol};
 use roc_packaging::cache::{self, RocCacheDir};
 use roc_parse::ident::{parse_ident, Accessor, Ident};
@@ -269,9 +269,31 @@ fn render_module_documentation(
 
                     let type_ann = &doc_def.type_annotation;
 
-                    if !matches!(type_ann, TypeAnnotation::NoTypeAnn) {
-                        content.push_str(" : ");
-                        type_annotation_to_html(0, &mut content, type_ann, false);
+                    match type_ann {
+                        TypeAnnotation::NoTypeAnn => {
+                            if let Some(inferred_type) = &doc_def.inferred_type {
+                                content.push_str(" : ");
+                                // Writing to a String can't fail
+                                let _ = pulldown_cmark::escape::escape_html(
+                                    &mut content,
+                                    inferred_type,
+                                );
+                            }
+                        }
+                        TypeAnnotation::Ability { .. } => content.push_str(" has"),
+                        _ => {
+                            content.push_str(" : ");
+                            type_annotation_to_html(0, &mut content, type_ann, false);
+                        }
+                    }
+
+                    if let Some(constant) = &doc_def.constant {
+                        content.push_str(" = ");
+                        // Writing to a String can't fail
+                        let _ = pulldown_cmark::escape::escape_html(
+                            &mut content,
+                            &constant.to_string(),
+                        );
                     }
 
                     push_html(
@@ -281,6 +303,15 @@ fn render_module_documentation(
                         content.as_str(),
                     );
 
+                    for hint in &doc_def.alias_hints {
+                        let mut content = String::from("Hint: ");
+
+                        // Writing to a String can't fail
+                        let _ = pulldown_cmark::escape::escape_html(&mut content, hint);
+
+                        push_html(&mut buf, "p", vec![], content.as_str());
+                    }
+
                     if let Some(docs) = &doc_def.docs {
                         markdown_to_html(
                             &mut buf,
@@ -291,6 +322,54 @@ fn render_module_documentation(
                         );
                     }
 
+                    if let TypeAnnotation::Ability { members } = type_ann {
+                        for member in members {
+                            let mut content = String::new();
+
+                            push_html(&mut content, "strong", vec![], member.name.as_str());
+                            content.push_str(" : ");
+                            type_annotation_to_html(
+                                0,
+                                &mut content,
+                                &member.type_annotation,
+                                false,
+                            );
+
+                            for (index, (var_name, abilities)) in
+                                member.able_variables.iter().enumerate()
+                            {
+                                content.push_str(if index == 0 { " | " } else { ", " });
+                                content.push_str(var_name);
+                                content.push_str(" has ");
+
+                                for (index, ability) in abilities.iter().enumerate() {
+                                    if index > 0 {
+                                        content.push_str(" & ");
+                                    }
+
+                                    type_annotation_to_html(0, &mut content, ability, false);
+                                }
+                            }
+
+                            push_html(
+                                &mut buf,
+                                "h4",
+                                vec![("id", member.name.as_str()), ("class", "entry-name")],
+                                content.as_str(),
+                            );
+
+                            if let Some(docs) = &member.docs {
+                                markdown_to_html(
+                                    &mut buf,
+                                    all_exposed_symbols,
+                                    &module.scope,
+                                    docs,
+                                    root_module,
+                                );
+                            }
+                        }
+                    }
+
                     buf.push_str("</section>");
                 }
             }
@@ -460,6 +539,10 @@ pub fn load_module_for_docs(filename: PathBuf) -> LoadedModule {
         palette: roc_reporting::report::DEFAULT_PALETTE,
         threading: Threading::AllAvailable,
         exec_mode: ExecutionMode::Check,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants: true,
     };
     match roc_load::load_and_typecheck(
         &arena,
@@ -685,7 +768,7 @@ fn type_annotation_to_html(
             }
         }
         TypeAnnotation::Ability { members: _ } => {
-            // TODO(abilities): fill me in
+            // Ability members get rendered separately, each along with its own docs
         }
         TypeAnnotation::ObscuredTagUnion => {
             buf.push_str("[@..]");
@@ -0,0 +1,203 @@
+use crate::editor::grid_node_map::GridNodeMap;
+use crate::ui::text::text_pos::TextPos;
+use bumpalo::Bump;
+use roc_code_markup::slow_pool::{MarkNodeId, SlowPool};
+use roc_code_markup::underline_style::UnderlineStyle;
+use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, OverflowMode, Threading};
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_problem::Severity;
+use roc_region::all::{LineColumnRegion, LineInfo};
+use roc_reporting::report::{
+    can_problem, type_problem, CiWrite, Report, RenderTarget, RocDocAllocator, DEFAULT_PALETTE,
+};
+use roc_target::TargetInfo;
+use std::path::{Path, PathBuf};
+
+// An error or warning reported by the check pipeline (like `roc check`) for the open file.
+#[derive(Debug, Clone)]
+pub struct EdDiagnostic {
+    pub severity: Severity,
+    pub region: LineColumnRegion,
+    pub message: String, // the full report, rendered without colors
+    pub mark_node_ids: Vec<MarkNodeId>, // the MarkupNodes that are underlined for this diagnostic
+}
+
+impl EdDiagnostic {
+    pub fn underline_style(&self) -> UnderlineStyle {
+        match self.severity {
+            Severity::Fatal | Severity::RuntimeError => UnderlineStyle::Error,
+            Severity::Warning => UnderlineStyle::Warning,
+        }
+    }
+}
+
+// Check the file like `roc check` does, this takes a while so it should not run on the UI thread.
+pub fn check_file(file_path: &Path) -> Vec<EdDiagnostic> {
+    let load_config = LoadConfig {
+        target_info: TargetInfo::default_x86_64(), // only type checking, so this is unused
+        render: RenderTarget::Generic,
+        palette: DEFAULT_PALETTE,
+        threading: Threading::AllAvailable,
+        exec_mode: ExecutionMode::Check,
+        // a single typo should not hide the problems in the rest of the file
+        recover_from_syntax_errors: true,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants: true,
+    };
+
+    let arena = Bump::new();
+    let cache_dir = cache::roc_cache_dir();
+
+    let loaded = roc_load::load_and_typecheck(
+        &arena,
+        file_path.to_path_buf(),
+        RocCacheDir::Persistent(cache_dir.as_path()),
+        load_config,
+    );
+
+    match loaded {
+        Ok(mut loaded_module) => module_diagnostics(&mut loaded_module),
+        Err(LoadingProblem::FormattedReport(report)) => vec![EdDiagnostic {
+            severity: Severity::Fatal,
+            region: LineColumnRegion::zero(),
+            message: report,
+            mark_node_ids: Vec::new(),
+        }],
+        Err(problem) => vec![EdDiagnostic {
+            severity: Severity::Fatal,
+            region: LineColumnRegion::zero(),
+            message: format!("{:?}", problem),
+            mark_node_ids: Vec::new(),
+        }],
+    }
+}
+
+// Only the problems in the module itself are returned, the editor does not show its imports.
+fn module_diagnostics(loaded_module: &mut LoadedModule) -> Vec<EdDiagnostic> {
+    let home = loaded_module.module_id;
+    let mut diagnostics = Vec::new();
+
+    let (module_path, src) = match loaded_module.sources.get(&home) {
+        Some((module_path, src)) => (module_path.clone(), src.clone()),
+        None => return diagnostics,
+    };
+
+    let src_lines: Vec<&str> = src.split('\n').collect();
+    let lines = LineInfo::new(&src);
+    let alloc = RocDocAllocator::new(&src_lines, home, &loaded_module.interns);
+
+    let can_problems = loaded_module.can_problems.remove(&home).unwrap_or_default();
+
+    for problem in can_problems {
+        if let Some(region) = problem.region() {
+            let report = can_problem(&alloc, &lines, module_path.clone(), problem);
+
+            diagnostics.push(to_diagnostic(report, lines.convert_region(region), &alloc));
+        }
+    }
+
+    let type_problems = loaded_module.type_problems.remove(&home).unwrap_or_default();
+
+    for problem in type_problems {
+        if let Some(region) = problem.region() {
+            if let Some(report) = type_problem(&alloc, &lines, module_path.clone(), problem) {
+                diagnostics.push(to_diagnostic(report, lines.convert_region(region), &alloc));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+fn to_diagnostic<'b>(
+    report: Report<'b>,
+    region: LineColumnRegion,
+    alloc: &'b RocDocAllocator<'b>,
+) -> EdDiagnostic {
+    let severity = report.severity;
+
+    let mut message = String::new();
+    report
+        .pretty(alloc)
+        .1
+        .render_raw(70, &mut CiWrite::new(&mut message))
+        .expect("rendering to a String cannot fail");
+
+    EdDiagnostic {
+        severity,
+        region,
+        message: message.trim_end().to_string(),
+        mark_node_ids: Vec::new(),
+    }
+}
+
+// Run check_file on a separate thread, `on_done` receives the diagnostics.
+pub fn check_file_in_background<F>(file_path: PathBuf, on_done: F)
+where
+    F: FnOnce(Vec<EdDiagnostic>) + Send + 'static,
+{
+    std::thread::spawn(move || on_done(check_file(&file_path)));
+}
+
+// Underline the MarkupNodes that are (partially) inside the region of every diagnostic.
+// Positions that are not in the grid_node_map are skipped, the file may have changed since it was checked.
+pub fn underline_diagnostics(
+    diagnostics: &mut [EdDiagnostic],
+    grid_node_map: &GridNodeMap,
+    mark_node_pool: &mut SlowPool,
+) {
+    for diagnostic in diagnostics.iter_mut() {
+        let LineColumnRegion { start, end } = diagnostic.region;
+        let mut mark_node_ids = Vec::new();
+
+        for line in start.line..=end.line {
+            let start_col = if line == start.line { start.column } else { 0 };
+            // an empty region still points at the node it starts in
+            let end_col = if line == end.line {
+                end.column.max(start_col + 1)
+            } else {
+                u32::MAX
+            };
+
+            let mut column = start_col;
+
+            while column < end_col {
+                let pos = TextPos {
+                    line: line as usize,
+                    column: column as usize,
+                };
+
+                if !grid_node_map.node_exists_at_pos(pos) {
+                    break;
+                }
+
+                if let Ok(mark_node_id) = grid_node_map.get_id_at_row_col(pos) {
+                    if !mark_node_ids.contains(&mark_node_id) {
+                        mark_node_ids.push(mark_node_id);
+                    }
+                }
+
+                column += 1;
+            }
+        }
+
+        for mark_node_id in mark_node_ids.iter() {
+            if let Some(attributes) = mark_node_pool.get_mut(*mark_node_id).get_attributes_mut() {
+                attributes.add_underline(diagnostic.underline_style());
+            }
+        }
+
+        diagnostic.mark_node_ids = mark_node_ids;
+    }
+}
+
+pub fn remove_underlines(diagnostics: &[EdDiagnostic], mark_node_pool: &mut SlowPool) {
+    for diagnostic in diagnostics {
+        for mark_node_id in diagnostic.mark_node_ids.iter() {
+            if let Some(attributes) = mark_node_pool.get_mut(*mark_node_id).get_attributes_mut() {
+                attributes.delete_underlines();
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use bumpalo::Bump;
+use roc_region::all::LineColumnRegion;
+use std::path::{Path, PathBuf};
+
+// The outcome of one toplevel `expect` of the open file, shown as a marker in the gutter.
+#[derive(Debug, Clone)]
+pub struct EdExpectResult {
+    pub region: LineColumnRegion,
+    pub passed: bool,
+    pub report: String, // the failure with the values of the variables in the condition, empty if it passed
+}
+
+// Why the expects could not be run at all
+#[derive(Debug, Clone)]
+pub enum EdExpectsError {
+    // e.g. a type error, the report is rendered without colors
+    DoesNotCompile(String),
+    Io(String),
+}
+
+impl std::fmt::Display for EdExpectsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EdExpectsError::DoesNotCompile(report) => {
+                write!(
+                    f,
+                    "Could not run the expects, the file does not compile:\n\n{}",
+                    report
+                )
+            }
+            EdExpectsError::Io(msg) => write!(f, "Could not run the expects: {}", msg),
+        }
+    }
+}
+
+// Compile the file in dev mode and run its toplevel expects like `roc test` does.
+// This takes a while so it should not run on the UI thread.
+#[cfg(not(windows))]
+pub fn run_expects(file_path: &Path) -> Result<Vec<EdExpectResult>, EdExpectsError> {
+    use roc_gen_llvm::llvm::build::LlvmBackendMode;
+    use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError, OverflowMode, Threading};
+    use roc_mono::ir::OptLevel;
+    use roc_packaging::cache::{self, RocCacheDir};
+    use roc_region::all::LineInfo;
+    use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+    use roc_target::TargetInfo;
+    use target_lexicon::Triple;
+
+    let triple = Triple::host();
+
+    let load_config = LoadConfig {
+        target_info: TargetInfo::from(&triple),
+        render: RenderTarget::Generic,
+        palette: DEFAULT_PALETTE,
+        threading: Threading::AllAvailable,
+        exec_mode: ExecutionMode::Test,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants: true,
+    };
+
+    let arena = Bump::new();
+    let cache_dir = cache::roc_cache_dir();
+
+    let mut loaded = match roc_load::load_and_monomorphize(
+        &arena,
+        file_path.to_path_buf(),
+        RocCacheDir::Persistent(cache_dir.as_path()),
+        load_config,
+    ) {
+        Ok(loaded) => loaded,
+        Err(LoadMonomorphizedError::LoadingProblem(roc_load::LoadingProblem::FormattedReport(
+            report,
+        ))) => return Err(EdExpectsError::DoesNotCompile(report)),
+        Err(LoadMonomorphizedError::LoadingProblem(problem)) => {
+            return Err(EdExpectsError::Io(format!("{:?}", problem)))
+        }
+        Err(LoadMonomorphizedError::ErrorModule(_)) => {
+            return Err(EdExpectsError::DoesNotCompile(
+                "see the underlined problems in the editor".to_string(),
+            ))
+        }
+    };
+
+    let home = loaded.module_id;
+    let src = match loaded.sources.get(&home) {
+        Some((_, src)) => src.to_string(),
+        None => String::new(),
+    };
+    let lines = LineInfo::new(&src);
+
+    let mut expectations = std::mem::take(&mut loaded.expectations);
+    let interns = loaded.interns.clone();
+
+    let (lib, expects, layout_interner) = roc_repl_expect::run::expect_mono_module_to_dylib(
+        &arena,
+        triple,
+        loaded,
+        OptLevel::Development,
+        LlvmBackendMode::CliTest,
+    )
+    .map_err(|err| EdExpectsError::Io(err.to_string()))?;
+
+    let run_arena = Bump::new();
+    let interns = run_arena.alloc(interns);
+
+    let results = roc_repl_expect::run::run_toplevel_expects_separately(
+        RenderTarget::Generic,
+        &run_arena,
+        interns,
+        &layout_interner.into_global(),
+        &lib,
+        &mut expectations,
+        expects,
+    )
+    .map_err(|err| EdExpectsError::Io(err.to_string()))?;
+
+    // expects of imported modules are not shown, the editor only shows the open file
+    Ok(results
+        .into_iter()
+        .filter(|result| result.module_id == home)
+        .map(|result| EdExpectResult {
+            region: lines.convert_region(result.region),
+            passed: result.passed,
+            report: result.report,
+        })
+        .collect())
+}
+
+#[cfg(windows)]
+pub fn run_expects(_file_path: &Path) -> Result<Vec<EdExpectResult>, EdExpectsError> {
+    Err(EdExpectsError::Io(
+        "running expects does not work on windows right now".to_string(),
+    ))
+}
+
+// Run run_expects on a separate thread, `on_done` receives the results.
+pub fn run_expects_in_background<F>(file_path: PathBuf, on_done: F)
+where
+    F: FnOnce(Result<Vec<EdExpectResult>, EdExpectsError>) + Send + 'static,
+{
+    std::thread::spawn(move || on_done(run_expects(&file_path)));
+}
+
+// The text of the inspection panel: the report of every failed expect, or why they could not run.
+pub fn inspection_panel_text(
+    expect_results: &Result<Vec<EdExpectResult>, EdExpectsError>,
+) -> Option<String> {
+    match expect_results {
+        Ok(results) => {
+            let failed: Vec<&EdExpectResult> =
+                results.iter().filter(|result| !result.passed).collect();
+
+            if failed.is_empty() {
+                None
+            } else {
+                let reports: Vec<String> = failed
+                    .iter()
+                    .map(|result| {
+                        format!("line {}:\n{}", result.region.start.line + 1, result.report)
+                    })
+                    .collect();
+
+                Some(format!(
+                    "{} of {} expects failed\n\n{}",
+                    failed.len(),
+                    results.len(),
+                    reports.join("\n\n")
+                ))
+            }
+        }
+        Err(err) => Some(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+pub mod test_expects {
+    use crate::editor::expects::{inspection_panel_text, EdExpectResult, EdExpectsError};
+    use roc_region::all::{LineColumn, LineColumnRegion};
+
+    fn expect_result(line: u32, passed: bool, report: &str) -> EdExpectResult {
+        EdExpectResult {
+            region: LineColumnRegion::new(
+                LineColumn { line, column: 0 },
+                LineColumn { line, column: 10 },
+            ),
+            passed,
+            report: report.to_string(),
+        }
+    }
+
+    #[test]
+    fn no_panel_when_all_pass() {
+        let results = Ok(vec![expect_result(0, true, ""), expect_result(3, true, "")]);
+
+        assert_eq!(inspection_panel_text(&results), None);
+    }
+
+    #[test]
+    fn panel_lists_failures() {
+        let results = Ok(vec![
+            expect_result(0, true, ""),
+            expect_result(3, false, "a : Num *\na = 2"),
+        ]);
+
+        assert_eq!(
+            inspection_panel_text(&results),
+            Some("1 of 2 expects failed\n\nline 4:\na : Num *\na = 2".to_string())
+        );
+    }
+
+    #[test]
+    fn panel_shows_compile_errors() {
+        let results = Err(EdExpectsError::Io("oops".to_string()));
+
+        assert_eq!(
+            inspection_panel_text(&results),
+            Some("Could not run the expects: oops".to_string())
+        );
+    }
+}
@@ -5,8 +5,11 @@ use crate::editor::mvc::ed_view::RenderedWgpu;
 use crate::editor::resources::strings::{HELLO_WORLD, NOTHING_OPENED};
 use crate::editor::{
     config::Config,
+    diagnostics::{self, EdDiagnostic},
     ed_error::print_err,
+    expects::{self, EdExpectResult, EdExpectsError},
     mvc::{app_model::AppModel, app_update, app_update::InputOutcome, ed_model},
+    plugins::{self, PluginWidget},
     theme::EdTheme,
 };
 use crate::graphics::{
@@ -42,7 +45,7 @@ use winit::{
     dpi::PhysicalSize,
     event,
     event::{Event, ModifiersState},
-    event_loop::ControlFlow,
+    event_loop::{ControlFlow, EventLoopProxy},
     platform::run_return::EventLoopExtRunReturn,
 };
 
@@ -52,6 +55,14 @@ use winit::{
 //
 // See this link to learn wgpu: https://sotrh.github.io/learn-wgpu/
 
+// Events that are sent to the event loop from other threads
+#[derive(Debug)]
+enum EdEvent {
+    Diagnostics(Vec<EdDiagnostic>),
+    ExpectResults(Result<Vec<EdExpectResult>, EdExpectsError>),
+    PluginWidgets(Vec<PluginWidget>),
+}
+
 /// The editor is actually launched from the CLI if you pass it zero arguments,
 /// or if you provide it 1 or more files or directories to open on launch.
 pub fn launch(project_path_opt: Option<&Path>) -> io::Result<()> {
@@ -62,7 +73,7 @@ pub fn launch(project_path_opt: Option<&Path>) -> io::Result<()> {
 
 fn run_event_loop(project_path_opt: Option<&Path>) -> Result<(), Box<dyn Error>> {
     // Open window and create a surface
-    let mut event_loop = winit::event_loop::EventLoop::new();
+    let mut event_loop = winit::event_loop::EventLoop::with_user_event();
 
     let window = winit::window::WindowBuilder::new()
         .with_inner_size(PhysicalSize::new(1900.0, 1000.0))
@@ -180,6 +191,9 @@ fn run_event_loop(project_path_opt: Option<&Path>) -> Result<(), Box<dyn Error>>
 
     let mut app_model = AppModel::init(ed_model_opt);
 
+    let event_loop_proxy = event_loop.create_proxy();
+    request_check_if_needed(&mut app_model, file_path, &event_loop_proxy);
+
     let mut keyboard_modifiers = ModifiersState::empty();
     let ed_theme = EdTheme::default();
 
@@ -261,11 +275,40 @@ fn run_event_loop(project_path_opt: Option<&Path>) -> Result<(), Box<dyn Error>>
                                 print_err(&e)
                             }
 
+                            request_check_if_needed(&mut app_model, file_path, &event_loop_proxy);
+                            request_expects_if_needed(&mut app_model, file_path, &event_loop_proxy);
+
                             window.request_redraw()
                         }
                     }
                 }
             }
+            //Diagnostics of a background check are ready
+            Event::UserEvent(EdEvent::Diagnostics(diagnostics)) => {
+                if let Some(ref mut ed_model) = app_model.ed_model_opt {
+                    ed_model.set_diagnostics(diagnostics);
+
+                    window.request_redraw()
+                }
+            }
+            //Expects that ran in the background are done
+            Event::UserEvent(EdEvent::ExpectResults(expect_results)) => {
+                if let Some(ref mut ed_model) = app_model.ed_model_opt {
+                    ed_model.expect_results_opt = Some(expect_results);
+                    ed_model.dirty = true;
+
+                    window.request_redraw()
+                }
+            }
+            //Widgets of editor plugins are rendered
+            Event::UserEvent(EdEvent::PluginWidgets(plugin_widgets)) => {
+                if let Some(ref mut ed_model) = app_model.ed_model_opt {
+                    ed_model.plugin_widgets = plugin_widgets;
+                    ed_model.dirty = true;
+
+                    window.request_redraw()
+                }
+            }
             //Modifiers Changed
             Event::WindowEvent {
                 event: event::WindowEvent::ModifiersChanged(modifiers),
@@ -443,6 +486,68 @@ async fn create_device(
     }
 }
 
+// Check the file and render the widgets of editor plugins on other threads if the model asks for it,
+// the diagnostics and widgets are sent back to the event loop as an EdEvent.
+fn request_check_if_needed(
+    app_model: &mut AppModel,
+    file_path: &Path,
+    event_loop_proxy: &EventLoopProxy<EdEvent>,
+) {
+    if let Some(ref mut ed_model) = app_model.ed_model_opt {
+        if ed_model.check_requested {
+            ed_model.check_requested = false;
+
+            let proxy = event_loop_proxy.clone();
+
+            diagnostics::check_file_in_background(file_path.to_path_buf(), move |diagnostics| {
+                // the event loop may already be gone if the editor was closed
+                let _ = proxy.send_event(EdEvent::Diagnostics(diagnostics));
+            });
+
+            // the plugins are in the modules that the file uses, see plugins.rs
+            let home = ed_model.loaded_module.module_id;
+            let module_srcs: Vec<String> = ed_model
+                .loaded_module
+                .sources
+                .iter()
+                .filter(|(module_id, _)| **module_id != home)
+                .map(|(_, (_, src))| src.to_string())
+                .collect();
+
+            let proxy = event_loop_proxy.clone();
+
+            plugins::render_widgets_in_background(
+                file_path.to_path_buf(),
+                module_srcs,
+                move |plugin_widgets| {
+                    let _ = proxy.send_event(EdEvent::PluginWidgets(plugin_widgets));
+                },
+            );
+        }
+    }
+}
+
+// Run the expects of the file on another thread if the model asks for it, the results are sent
+// back to the event loop as an EdEvent.
+fn request_expects_if_needed(
+    app_model: &mut AppModel,
+    file_path: &Path,
+    event_loop_proxy: &EventLoopProxy<EdEvent>,
+) {
+    if let Some(ref mut ed_model) = app_model.ed_model_opt {
+        if ed_model.expects_requested {
+            ed_model.expects_requested = false;
+
+            let proxy = event_loop_proxy.clone();
+
+            expects::run_expects_in_background(file_path.to_path_buf(), move |expect_results| {
+                // the event loop may already be gone if the editor was closed
+                let _ = proxy.send_event(EdEvent::ExpectResults(expect_results));
+            });
+        }
+    }
+}
+
 fn draw_rects(
     all_rects: &[Rect],
     encoder: &mut CommandEncoder,
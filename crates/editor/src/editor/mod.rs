@@ -1,10 +1,13 @@
 mod code_lines;
 mod config;
+mod diagnostics;
 pub mod ed_error;
+mod expects;
 mod grid_node_map;
 mod keyboard_input;
 pub mod main;
 mod mvc;
+mod plugins;
 mod render_ast;
 mod render_debug;
 mod resources;
@@ -0,0 +1,182 @@
+use roc_ast::lang::core::ast::ASTNodeId;
+use roc_ast::lang::core::expr::expr2::Expr2;
+use roc_ast::mem_pool::pool_str::PoolStr;
+use roc_module::ident::ModuleName;
+use roc_module::symbol::Symbol;
+
+use crate::editor::ed_error::EdResult;
+use crate::editor::mvc::app_update::InputOutcome;
+use crate::editor::mvc::ed_model::{Completion, CompletionTarget, EdModel};
+
+// Handle the char if it opens the completion popup or if the popup is open.
+// Returns None if the char should be handled as usual.
+pub fn handle_completion_char(
+    received_char: &char,
+    ed_model: &mut EdModel,
+) -> EdResult<Option<InputOutcome>> {
+    let completion = match &mut ed_model.completion_opt {
+        Some(completion) => completion,
+        None => {
+            return if *received_char == '.' {
+                start_completion(ed_model)
+            } else {
+                Ok(None)
+            }
+        }
+    };
+
+    let outcome = match received_char {
+        '\r' | '\t' => accept_completion(ed_model)?,
+        '\u{8}' | '\u{7f}' => {
+            // backspace removes a char from the filter, or closes the popup if there is none
+            if completion.filter.is_empty() {
+                ed_model.completion_opt = None;
+            } else {
+                let mut new_filter = completion.filter.clone();
+                new_filter.pop();
+                completion.set_filter(new_filter);
+            }
+
+            InputOutcome::Accepted
+        }
+        ch if ch.is_ascii_alphanumeric() => {
+            let mut new_filter = completion.filter.clone();
+            new_filter.push(*ch);
+            completion.set_filter(new_filter);
+
+            InputOutcome::Accepted
+        }
+        _ => {
+            // any other char closes the popup and is handled as usual
+            ed_model.completion_opt = None;
+
+            return Ok(None);
+        }
+    };
+
+    Ok(Some(outcome))
+}
+
+// Open the popup if the caret is right behind a record expression or a module name.
+fn start_completion(ed_model: &mut EdModel) -> EdResult<Option<InputOutcome>> {
+    let prev_mark_node_id = match ed_model.get_prev_mark_node_id()? {
+        Some(prev_mark_node_id) => prev_mark_node_id,
+        None => return Ok(None),
+    };
+
+    // the caret should be behind the node, not inside it
+    if ed_model.node_exists_at_caret() && ed_model.get_curr_mark_node_id()? == prev_mark_node_id {
+        return Ok(None);
+    }
+
+    let expr_id = match ed_model.mark_id_ast_id_map.get(prev_mark_node_id)? {
+        ASTNodeId::ADefId(_) => return Ok(None),
+        ASTNodeId::AExprId(expr_id) => expr_id,
+    };
+
+    let (target, items) = match ed_model.module.env.pool.get(expr_id) {
+        Expr2::Var(_) | Expr2::Access { .. } => (
+            CompletionTarget::RecordField { expr_id },
+            ed_model.record_field_items(expr_id),
+        ),
+        Expr2::InvalidLookup(pool_str) => {
+            let module_name = ModuleName::from(pool_str.as_str(ed_model.module.env.pool));
+
+            match ed_model.loaded_module.interns.module_ids.get_id(&module_name) {
+                Some(module_id) => (
+                    CompletionTarget::ModuleValue { expr_id, module_id },
+                    ed_model.module_value_items(module_id),
+                ),
+                None => return Ok(None),
+            }
+        }
+        _ => return Ok(None),
+    };
+
+    if items.is_empty() {
+        return Ok(None);
+    }
+
+    ed_model.completion_opt = Some(Completion {
+        target,
+        items,
+        filter: String::new(),
+        selected_index: 0,
+    });
+
+    Ok(Some(InputOutcome::Accepted))
+}
+
+// Replace the expression in front of the `.` with the one for the selected item,
+// the markup is generated from the new AST like after any other change.
+fn accept_completion(ed_model: &mut EdModel) -> EdResult<InputOutcome> {
+    let completion = match ed_model.completion_opt.take() {
+        Some(completion) => completion,
+        None => return Ok(InputOutcome::Ignored),
+    };
+
+    let item_name = match completion.selected_item() {
+        Some(item) => item.name.clone(),
+        None => return Ok(InputOutcome::Accepted),
+    };
+
+    match completion.target {
+        CompletionTarget::RecordField { expr_id } => {
+            let pool = &mut *ed_model.module.env.pool;
+
+            // the record expression moves to a new node, expr_id becomes the access
+            let record_expr = match pool.get(expr_id) {
+                Expr2::Var(symbol) => Expr2::Var(*symbol),
+                Expr2::Access {
+                    field,
+                    expr,
+                    record_var,
+                    ext_var,
+                    field_var,
+                } => Expr2::Access {
+                    field: *field,
+                    expr: *expr,
+                    record_var: *record_var,
+                    ext_var: *ext_var,
+                    field_var: *field_var,
+                },
+                _ => return Ok(InputOutcome::Ignored),
+            };
+            let record_expr_id = pool.add(record_expr);
+
+            let access = Expr2::Access {
+                field: PoolStr::new(&item_name, pool),
+                expr: record_expr_id,
+                record_var: ed_model.module.env.var_store.fresh(),
+                ext_var: ed_model.module.env.var_store.fresh(),
+                field_var: ed_model.module.env.var_store.fresh(),
+            };
+
+            ed_model.module.env.pool.set(expr_id, access);
+        }
+        CompletionTarget::ModuleValue { expr_id, module_id } => {
+            let ident_id_opt = ed_model
+                .loaded_module
+                .interns
+                .all_ident_ids
+                .get(&module_id)
+                .and_then(|ident_ids| ident_ids.get_id(&item_name));
+
+            match ident_id_opt {
+                Some(ident_id) => {
+                    let symbol = Symbol::new(module_id, ident_id);
+
+                    ed_model.module.env.pool.set(expr_id, Expr2::Var(symbol));
+                }
+                None => return Ok(InputOutcome::Ignored),
+            }
+        }
+    }
+
+    ed_model.post_process_ast_update()?;
+
+    // move the caret behind the `.` and the inserted name
+    ed_model.simple_move_carets_right(1 + item_name.len());
+
+    Ok(InputOutcome::Accepted)
+}
@@ -1,5 +1,9 @@
 use crate::editor::code_lines::CodeLines;
+use crate::editor::diagnostics::{remove_underlines, underline_diagnostics, EdDiagnostic};
+use crate::editor::expects::{EdExpectResult, EdExpectsError};
 use crate::editor::grid_node_map::GridNodeMap;
+use crate::editor::mvc::history::EdHistory;
+use crate::editor::plugins::PluginWidget;
 use crate::editor::{
     ed_error::SrcParseSnafu,
     ed_error::{EdResult, EmptyCodeStringSnafu, MissingParentSnafu, NoNodeAtCaretPositionSnafu},
@@ -12,6 +16,7 @@ use crate::ui::ui_error::UIResult;
 use bumpalo::Bump;
 use nonempty::NonEmpty;
 use roc_ast::lang::core::ast::{ASTNodeId, AST};
+use roc_ast::lang::core::expr::expr2::ExprId;
 use roc_ast::lang::env::Env;
 use roc_ast::mem_pool::pool_str::PoolStr;
 use roc_ast::parse::parse_ast;
@@ -20,7 +25,7 @@ use roc_code_markup::markup::mark_id_ast_id_map::MarkIdAstIdMap;
 use roc_code_markup::markup::nodes;
 use roc_code_markup::slow_pool::{MarkNodeId, SlowPool};
 use roc_load::LoadedModule;
-use roc_module::symbol::Interns;
+use roc_module::symbol::{Interns, ModuleId};
 use std::path::Path;
 
 /// Contains nearly all state related to a single roc file in the editor.
@@ -40,6 +45,13 @@ pub struct EdModel<'a> {
     pub loaded_module: LoadedModule, // contains all roc symbols, exposed values, exposed aliases, solved types... in the file(=module)
     pub show_debug_view: bool,       // see render_debug.rs for the debug view
     pub dirty: bool, // EdModel is dirty if it has changed since the previous render.
+    pub diagnostics: Vec<EdDiagnostic>, // errors and warnings from the last check of the saved file
+    pub check_requested: bool, // the file should be checked (again) in the background, e.g. because it was saved
+    pub completion_opt: Option<Completion>, // popup with the record fields or module values that can follow a typed `.`
+    pub history: EdHistory,                 // for undo and redo
+    pub expects_requested: bool, // the expects of the saved file should be run in the background, see expects.rs
+    pub expect_results_opt: Option<Result<Vec<EdExpectResult>, EdExpectsError>>, // None if the expects were not run yet
+    pub plugin_widgets: Vec<PluginWidget>, // e.g. color swatches, rendered by plugins of packages, see plugins.rs
 }
 
 // a selected AST node, the roc type of this node is shown in the editor on ctrl+shift+"up arrow"
@@ -50,6 +62,95 @@ pub struct SelectedBlock {
     pub type_str: PoolStr,
 }
 
+// a popup that offers the record fields or module values that can follow the `.` the user just typed
+#[derive(Debug, Clone)]
+pub struct Completion {
+    pub target: CompletionTarget,
+    pub items: Vec<CompletionItem>,
+    pub filter: String,        // the part of the name the user typed after the `.`
+    pub selected_index: usize, // index into filtered_items
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum CompletionTarget {
+    // expr_id is the record expression in front of the `.`
+    RecordField {
+        expr_id: ExprId,
+    },
+    // expr_id is the (not yet valid) lookup that holds the module name
+    ModuleValue {
+        expr_id: ExprId,
+        module_id: ModuleId,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct CompletionItem {
+    pub name: String,
+    pub type_str: String, // empty if the type is unknown
+}
+
+impl Completion {
+    pub fn filtered_items(&self) -> Vec<&CompletionItem> {
+        self.items
+            .iter()
+            .filter(|item| item.name.starts_with(&self.filter))
+            .collect()
+    }
+
+    pub fn selected_item(&self) -> Option<&CompletionItem> {
+        self.filtered_items().get(self.selected_index).copied()
+    }
+
+    pub fn select_next(&mut self) {
+        let nr_of_items = self.filtered_items().len();
+
+        if nr_of_items > 0 {
+            self.selected_index = (self.selected_index + 1) % nr_of_items;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        let nr_of_items = self.filtered_items().len();
+
+        if nr_of_items > 0 {
+            self.selected_index = (self.selected_index + nr_of_items - 1) % nr_of_items;
+        }
+    }
+
+    pub fn set_filter(&mut self, filter: String) {
+        self.filter = filter;
+        self.selected_index = 0;
+    }
+
+    // text for the popup, the selected item is marked with `>`
+    pub fn to_popup_string(&self) -> String {
+        let mut popup_str = format!(".{}", self.filter);
+
+        let filtered_items = self.filtered_items();
+
+        if filtered_items.is_empty() {
+            popup_str.push_str("\n  (no matches)");
+        }
+
+        for (index, item) in filtered_items.iter().enumerate() {
+            let marker = if index == self.selected_index {
+                ">"
+            } else {
+                " "
+            };
+
+            popup_str.push_str(&format!("\n{} {}", marker, item.name));
+
+            if !item.type_str.is_empty() {
+                popup_str.push_str(&format!(" : {}", item.type_str));
+            }
+        }
+
+        popup_str
+    }
+}
+
 pub fn init_model<'a>(
     code_str: &'a str, // entire roc file as one str
     file_path: &'a Path,
@@ -117,6 +218,13 @@ pub fn init_model<'a>(
         loaded_module: owned_loaded_module,
         show_debug_view: false,
         dirty: true,
+        diagnostics: Vec::new(),
+        check_requested: true,
+        completion_opt: None,
+        history: EdHistory::default(),
+        expects_requested: false,
+        expect_results_opt: None,
+        plugin_widgets: Vec::new(),
     })
 }
 
@@ -151,6 +259,30 @@ impl<'a> EdModel<'a> {
         Ok(prev_id_opt)
     }
 
+    // replace the diagnostics and their underlines with those of a newer check
+    pub fn set_diagnostics(&mut self, diagnostics: Vec<EdDiagnostic>) {
+        remove_underlines(&self.diagnostics, &mut self.mark_node_pool);
+
+        self.diagnostics = diagnostics;
+
+        underline_diagnostics(
+            &mut self.diagnostics,
+            &self.grid_node_map,
+            &mut self.mark_node_pool,
+        );
+
+        self.dirty = true;
+    }
+
+    // the diagnostic that underlines the MarkupNode at the caret, if any
+    pub fn get_diagnostic_at_caret(&self) -> Option<&EdDiagnostic> {
+        let curr_mark_node_id = self.get_curr_mark_node_id().ok()?;
+
+        self.diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.mark_node_ids.contains(&curr_mark_node_id))
+    }
+
     pub fn node_exists_at_caret(&self) -> bool {
         self.grid_node_map.node_exists_at_pos(self.get_caret())
     }
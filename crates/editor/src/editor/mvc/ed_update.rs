@@ -7,12 +7,15 @@ use crate::editor::ed_error::EdResult;
 use crate::editor::ed_error::{MissingSelectionSnafu, RocCheckFailedSnafu};
 use crate::editor::grid_node_map::GridNodeMap;
 use crate::editor::mvc::app_update::InputOutcome;
+use crate::editor::mvc::completion_update::handle_completion_char;
+use crate::editor::mvc::ed_model::CompletionItem;
 use crate::editor::mvc::ed_model::EdModel;
 use crate::editor::mvc::ed_model::SelectedBlock;
+use crate::editor::mvc::history::{begin_action, end_action, redo, undo, ActionKind};
 use crate::editor::mvc::int_update::start_new_int;
 use crate::editor::mvc::int_update::update_int;
 use crate::editor::mvc::list_update::{add_blank_child, start_new_list};
-use crate::editor::mvc::lookup_update::update_invalid_lookup;
+use crate::editor::mvc::lookup_update::{start_new_lookup, update_invalid_lookup};
 use crate::editor::mvc::record_update::start_new_record;
 use crate::editor::mvc::record_update::update_empty_record;
 use crate::editor::mvc::record_update::update_record_colon;
@@ -55,12 +58,12 @@ use roc_code_markup::slow_pool::SlowPool;
 use roc_collections::all::MutMap;
 use roc_command_utils::cargo;
 use roc_module::ident::Lowercase;
-use roc_module::symbol::Symbol;
+use roc_module::symbol::{ModuleId, Symbol};
 use roc_region::all::Region;
 use roc_solve::module::Solved;
 use roc_types::pretty_print::name_and_print_var;
 use roc_types::pretty_print::DebugPrint;
-use roc_types::subs::{Subs, VarStore, Variable};
+use roc_types::subs::{Content, FlatType, Subs, VarStore, Variable};
 use snafu::OptionExt;
 use threadpool::ThreadPool;
 use winit::event::VirtualKeyCode;
@@ -416,6 +419,7 @@ impl<'a> EdModel<'a> {
                 identifier_id: _,
                 expr_id,
             } => Some(*expr_id),
+            Def2::TypeAnnotation { .. } => None,
             Def2::Blank => None,
             Def2::CommentsBefore {
                 comments: _,
@@ -429,6 +433,109 @@ impl<'a> EdModel<'a> {
     }
 
     fn expr2_to_type(&mut self, expr2_id: ExprId) -> PoolStr {
+        let (var, mut solved) = self.solve_expr2(expr2_id);
+
+        let subs = solved.inner_mut();
+
+        let pretty_var = name_and_print_var(
+            var,
+            subs,
+            self.module.env.home,
+            &self.loaded_module.interns,
+            DebugPrint::NOTHING,
+        );
+
+        PoolStr::new(&pretty_var, self.module.env.pool)
+    }
+
+    // the fields of the record the expression evaluates to, with their types
+    pub fn record_field_items(&mut self, expr2_id: ExprId) -> Vec<CompletionItem> {
+        // the solver does not know the types of other definitions, so a lookup is solved
+        // through the expression of the definition it refers to
+        let expr2_id = self.lookup_def_expr(expr2_id).unwrap_or(expr2_id);
+
+        let (var, mut solved) = self.solve_expr2(expr2_id);
+        let subs = solved.inner_mut();
+
+        let mut record_var = var;
+
+        let fields: Vec<(Lowercase, Variable)> = loop {
+            match subs.get_content_without_compacting(record_var) {
+                Content::Alias(_, _, real_var, _) => record_var = *real_var,
+                Content::Structure(FlatType::Record(fields, ext)) => {
+                    break fields
+                        .sorted_iterator(subs, *ext)
+                        .map(|(label, field)| (label, field.into_inner()))
+                        .collect();
+                }
+                _ => break Vec::new(),
+            }
+        };
+
+        fields
+            .into_iter()
+            .map(|(label, field_var)| CompletionItem {
+                name: label.as_str().to_owned(),
+                type_str: name_and_print_var(
+                    field_var,
+                    subs,
+                    self.module.env.home,
+                    &self.loaded_module.interns,
+                    DebugPrint::NOTHING,
+                ),
+            })
+            .collect()
+    }
+
+    // the values a module exposes, the editor can not solve the types of other modules yet
+    pub fn module_value_items(&self, module_id: ModuleId) -> Vec<CompletionItem> {
+        let mut items: Vec<CompletionItem> = self
+            .loaded_module
+            .interns
+            .all_ident_ids
+            .get(&module_id)
+            .map(|ident_ids| {
+                ident_ids
+                    .ident_strs()
+                    .map(|(_, name)| name)
+                    .filter(|name| {
+                        name.starts_with(|chr: char| chr.is_ascii_lowercase())
+                            && name.chars().all(|chr| chr.is_ascii_alphanumeric())
+                    })
+                    .map(|name| CompletionItem {
+                        name: name.to_owned(),
+                        type_str: String::new(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        items.sort_by(|item_a, item_b| item_a.name.cmp(&item_b.name));
+        items.dedup_by(|item_a, item_b| item_a.name == item_b.name);
+
+        items
+    }
+
+    // if the expression is a lookup of a top level value in this module, the expression of that value
+    fn lookup_def_expr(&self, expr2_id: ExprId) -> Option<ExprId> {
+        let symbol = match self.module.env.pool.get(expr2_id) {
+            Expr2::Var(symbol) if symbol.module_id() == self.module.env.home => *symbol,
+            _ => return None,
+        };
+
+        self.module
+            .ast
+            .def_ids
+            .iter()
+            .find_map(|def_id| match self.module.env.pool.get(*def_id) {
+                Def2::ValueDef { identifier_id, .. } if *identifier_id == symbol.ident_id() => {
+                    self.extract_expr_from_def(*def_id)
+                }
+                _ => None,
+            })
+    }
+
+    fn solve_expr2(&mut self, expr2_id: ExprId) -> (Variable, Solved<Subs>) {
         let var = self.module.env.var_store.fresh();
         let expr = self.module.env.pool.get(expr2_id);
         let arena = Bump::new();
@@ -459,17 +566,7 @@ impl<'a> EdModel<'a> {
             self.module.env.var_store,
         );
 
-        let subs = solved.inner_mut();
-
-        let pretty_var = name_and_print_var(
-            var,
-            subs,
-            self.module.env.home,
-            &self.loaded_module.interns,
-            DebugPrint::NOTHING,
-        );
-
-        PoolStr::new(&pretty_var, self.module.env.pool)
+        (var, solved)
     }
 
     fn run_solve(
@@ -508,6 +605,30 @@ impl<'a> EdModel<'a> {
         virtual_keycode: VirtualKeyCode,
         _sound_thread_pool: &mut ThreadPool,
     ) -> EdResult<()> {
+        if let Some(completion) = &mut self.completion_opt {
+            match virtual_keycode {
+                Up => {
+                    completion.select_prev();
+                    self.dirty = true;
+
+                    return Ok(());
+                }
+                Down => {
+                    completion.select_next();
+                    self.dirty = true;
+
+                    return Ok(());
+                }
+                Escape => {
+                    self.completion_opt = None;
+                    self.dirty = true;
+
+                    return Ok(());
+                }
+                _ => (),
+            }
+        }
+
         match virtual_keycode {
             Left => self.move_caret_left(modifiers)?,
             Up => {
@@ -525,11 +646,26 @@ impl<'a> EdModel<'a> {
                     self.select_all()?
                 }
             }
+            Z => {
+                if modifiers.cmd_or_ctrl() {
+                    if modifiers.shift {
+                        redo(self)?
+                    } else {
+                        undo(self)?
+                    }
+                }
+            }
             S => {
                 if modifiers.cmd_or_ctrl() {
                     self.save_file()?
                 }
             }
+            T => {
+                if modifiers.cmd_or_ctrl() {
+                    self.save_file()?;
+                    self.expects_requested = true;
+                }
+            }
             R => {
                 if modifiers.cmd_or_ctrl() {
                     self.check_file()?;
@@ -606,6 +742,8 @@ impl<'a> EdModel<'a> {
 
         println!("\nsave successful!");
 
+        self.check_requested = true;
+
         Ok(())
     }
 
@@ -647,7 +785,7 @@ impl<'a> EdModel<'a> {
     }
 
     /// update MarkupNode's, grid_node_map, code_lines after the AST has been updated
-    fn post_process_ast_update(&mut self) -> EdResult<()> {
+    pub fn post_process_ast_update(&mut self) -> EdResult<()> {
         //dbg!("{}",self.module.ast.ast_to_string(self.module.env.pool));
 
         let markup_ids_tup = ast_to_mark_nodes(
@@ -906,6 +1044,8 @@ pub fn handle_new_char_def(
                 )?
             }
         }
+        // TODO support editing type annotations
+        Def2::TypeAnnotation { .. } => InputOutcome::Ignored,
         Def2::CommentsBefore { .. } => {
             todo!()
         }
@@ -939,6 +1079,8 @@ pub fn handle_new_char_expr(
     let outcome = if let Expr2::Blank { .. } = expr_ref {
         match ch {
             'a'..='z' => start_new_let_value(ed_model, ch)?,
+            // a module name, e.g. `List` in `List.len`
+            'A'..='Z' => start_new_lookup(ed_model, ch)?,
             '"' => start_new_string(ed_model)?,
             '{' => start_new_record(ed_model)?,
             '0'..='9' => start_new_int(ed_model, ch)?,
@@ -1077,7 +1219,7 @@ pub fn handle_new_char_expr(
                 // insert a Blank first, this results in cleaner code
                 add_blank_child(new_child_index, new_ast_child_index, ed_model)?;
                 ed_model.post_process_ast_update()?;
-                handle_new_char(received_char, ed_model)?
+                apply_new_char(received_char, ed_model)?
             } else {
                 InputOutcome::Ignored
             }
@@ -1160,7 +1302,7 @@ pub fn handle_new_char_diff_mark_nodes_prev_is_expr(
                 // insert a Blank first, this results in cleaner code
                 add_blank_child(new_child_index, new_ast_child_index, ed_model)?;
                 ed_model.post_process_ast_update()?;
-                handle_new_char(received_char, ed_model)?
+                apply_new_char(received_char, ed_model)?
             } else {
                 InputOutcome::Ignored
             }
@@ -1179,9 +1321,27 @@ pub fn handle_new_char_diff_mark_nodes_prev_is_expr(
 }
 
 // updates the ed_model based on the char the user just typed if the result would be syntactically correct.
+// The changes can be undone, see history.rs.
 pub fn handle_new_char(received_char: &char, ed_model: &mut EdModel) -> EdResult<InputOutcome> {
+    let action_start = begin_action(ed_model);
+
+    let outcome_res = apply_new_char(received_char, ed_model);
+
+    end_action(ed_model, action_start, ActionKind::for_char(received_char));
+
+    outcome_res
+}
+
+fn apply_new_char(received_char: &char, ed_model: &mut EdModel) -> EdResult<InputOutcome> {
     //dbg!("{}", ed_model.module.ast.ast_to_string(ed_model.module.env.pool));
 
+    // the completion popup gets the first chance to handle the char
+    if let Some(completion_outcome) = handle_completion_char(received_char, ed_model)? {
+        ed_model.dirty = true;
+
+        return Ok(completion_outcome);
+    }
+
     let input_outcome = match received_char {
             '\u{e000}'..='\u{f8ff}' // http://www.unicode.org/faq/private_use.html
             | '\u{f0000}'..='\u{ffffd}' // ^
@@ -1226,6 +1386,15 @@ pub fn handle_new_char(received_char: &char, ed_model: &mut EdModel) -> EdResult
                                         Expr2::SmallInt{ .. } => {
                                             update_int(ed_model, prev_mark_node_id, ch)?
                                         },
+                                        Expr2::InvalidLookup(old_pool_str) => {
+                                            update_invalid_lookup(
+                                                &ch.to_string(),
+                                                old_pool_str,
+                                                prev_mark_node_id,
+                                                prev_ast_node_id,
+                                                ed_model,
+                                            )?
+                                        },
                                         _ => {
                                             InputOutcome::Ignored
                                         }
@@ -1240,7 +1409,7 @@ pub fn handle_new_char(received_char: &char, ed_model: &mut EdModel) -> EdResult
                                                     ed_model.post_process_ast_update()?;
                                                 }
                                             }
-                                            handle_new_char(received_char, ed_model)?
+                                            apply_new_char(received_char, ed_model)?
                                         }
                                         _ => {
                                             InputOutcome::Ignored
@@ -1282,7 +1451,7 @@ pub mod test_ed_update {
     use crate::ui::text::lines::SelectableLines;
     use crate::ui::ui_error::UIResult;
     use crate::window::keyboard_input::no_mods;
-    use crate::window::keyboard_input::test_modifiers::ctrl_cmd_shift;
+    use crate::window::keyboard_input::test_modifiers::{ctrl_cmd, ctrl_cmd_shift};
     use crate::window::keyboard_input::Modifiers;
     use bumpalo::Bump;
     use roc_code_markup::markup::common_nodes::NEW_LINES_AFTER_DEF;
@@ -2582,6 +2751,127 @@ pub mod test_ed_update {
         Ok(())
     }
 
+    #[test]
+    fn test_completion_record_field() -> Result<(), String> {
+        assert_insert_seq(
+            add_nls(ovec!["ab = { x: 5 }", "", "cd = ab┃"]),
+            add_nls(ovec!["ab = { x: 5 }", "", "cd = ab.x┃"]),
+            ".\r",
+        )?;
+        assert_insert_seq(
+            add_nls(ovec!["ab = { yz: \"a\" }", "", "cd = ab┃"]),
+            add_nls(ovec!["ab = { yz: \"a\" }", "", "cd = ab.yz┃"]),
+            ".y\r",
+        )?;
+        // a filter without matches inserts nothing
+        assert_insert_seq_ignore(add_nls(ovec!["ab = { x: 5 }", "", "cd = ab┃"]), ".q\r")?;
+        // backspace on an empty filter closes the popup
+        assert_insert_seq_ignore(add_nls(ovec!["ab = { x: 5 }", "", "cd = ab┃"]), ".\u{8}")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_completion_module_value() -> Result<(), String> {
+        assert_insert_seq_in_def(add_nls(ovec!["List┃"]), "List")?;
+        assert_insert_seq_in_def(add_nls(ovec!["List.len┃"]), "List.le\r")?;
+        assert_insert_seq_in_def(add_nls(ovec!["Str.isEmpty┃"]), "Str.isEm\t")?;
+        // not a module, so there is nothing to complete
+        assert_insert_seq_in_def(add_nls(ovec!["Lst┃"]), "Lst.")?;
+
+        Ok(())
+    }
+
+    // Like assert_insert_seq, but '↶' in new_char_seq is ctrl+z (undo) and '↷' is ctrl+shift+z (redo).
+    pub fn assert_undo_redo_seq(
+        pre_lines: Vec<String>,
+        expected_post_lines: Vec<String>,
+        new_char_seq: &str,
+    ) -> Result<(), String> {
+        let mut code_str = pre_lines.join("\n").replace('┃', "");
+
+        let mut model_refs = init_model_refs();
+        let code_arena = Bump::new();
+        let module_ids = ModuleIds::default();
+
+        let mut ed_model = ed_model_from_dsl(
+            &mut code_str,
+            pre_lines,
+            &mut model_refs,
+            &module_ids,
+            &code_arena,
+        )?;
+
+        for input_char in new_char_seq.chars() {
+            if input_char == '🡲' {
+                ed_model.simple_move_carets_right(1);
+            } else if input_char == '↶' {
+                ed_res_to_res(ed_model.ed_handle_key_down(
+                    &ctrl_cmd(),
+                    Z,
+                    &mut ThreadPool::new(1),
+                ))?;
+            } else if input_char == '↷' {
+                ed_res_to_res(ed_model.ed_handle_key_down(
+                    &ctrl_cmd_shift(),
+                    Z,
+                    &mut ThreadPool::new(1),
+                ))?;
+            } else {
+                ed_res_to_res(handle_new_char(&input_char, &mut ed_model))?;
+            }
+        }
+
+        let mut post_lines = ui_res_to_res(ed_model_to_dsl(&ed_model))?;
+        strip_header(&mut post_lines); // remove header for clean tests
+
+        assert_eq!(post_lines, expected_post_lines);
+
+        Ok(())
+    }
+
+    // opening code and saving it again should give back the same code
+    pub fn assert_round_trip(lines: Vec<String>) -> Result<(), String> {
+        assert_insert_seq(lines.clone(), add_nls(lines), "")
+    }
+
+    #[test]
+    fn test_round_trip() -> Result<(), String> {
+        assert_round_trip(ovec!["┃val : Str", "val = \"hello\""])?;
+        assert_round_trip(ovec!["┃val : List I64", "val = [ 1, 2 ]"])?;
+        assert_round_trip(ovec!["┃val = \\b ->", "    if b then 1 else 2"])?;
+        assert_round_trip(ovec![
+            "┃val = \\x ->",
+            "    when x is",
+            "        A -> 1",
+            "        B | C -> 2",
+            "        _ -> 3"
+        ])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_undo_redo() -> Result<(), String> {
+        // undo and redo without changes do nothing
+        assert_undo_redo_seq(ovec!["┃"], ovec!["┃"], "↶↷")?;
+
+        // typing a name is undone at once
+        assert_undo_redo_seq(ovec!["┃"], ovec!["┃"], "val↶")?;
+        assert_undo_redo_seq(ovec!["┃"], add_nls(ovec!["val┃ =  "]), "val↶↷")?;
+
+        assert_undo_redo_seq(ovec!["┃"], add_nls(ovec!["val = ┃ "]), "val🡲🡲🡲5↶")?;
+        assert_undo_redo_seq(ovec!["┃"], ovec!["┃"], "val🡲🡲🡲5↶↶")?;
+        assert_undo_redo_seq(ovec!["┃"], add_nls(ovec!["val = 5┃"]), "val🡲🡲🡲5↶↷")?;
+        assert_undo_redo_seq(ovec!["┃"], add_nls(ovec!["val = 5┃"]), "val🡲🡲🡲5↶↶↷↷")?;
+        assert_undo_redo_seq(ovec!["┃"], add_nls(ovec!["val = { a┃ }"]), "val🡲🡲🡲{a:↶")?;
+
+        // a new change after an undo can not be followed by a redo of the undone change
+        assert_undo_redo_seq(ovec!["┃"], add_nls(ovec!["val = 6┃"]), "val🡲🡲🡲5↶6↷")?;
+
+        Ok(())
+    }
+
     // Create ed_model from pre_lines DSL, do handle_new_char for every char in input_seq, do ctrl+shift+up as many times as repeat.
     // check if modified ed_model has expected string representation of code, caret position and active selection.
     pub fn assert_ctrl_shift_up_repeat(
@@ -1,7 +1,10 @@
 use super::ed_model::EdModel;
+use crate::editor::code_lines::CodeLines;
 use crate::editor::config::Config;
 use crate::editor::ed_error::EdResult;
+use crate::editor::expects::{inspection_panel_text, EdExpectResult};
 use crate::editor::mvc::ed_model::SelectedBlock;
+use crate::editor::plugins::{EdWidget, PluginWidget};
 use crate::editor::render_ast::build_code_graphics;
 use crate::editor::render_debug::build_debug_graphics;
 use crate::editor::resources::strings::START_TIP;
@@ -10,7 +13,9 @@ use crate::graphics::primitives::text::{owned_section_from_text, Text};
 use crate::ui::text::caret_w_select::make_caret_rect;
 use crate::ui::text::caret_w_select::make_selection_rect;
 use crate::ui::text::caret_w_select::CaretWSelect;
+use crate::ui::text::lines::SelectableLines;
 use crate::ui::text::selection::Selection;
+use crate::ui::text::text_pos::TextPos;
 use crate::ui::tooltip::ToolTip;
 use crate::ui::ui_error::MissingGlyphDimsSnafu;
 use cgmath::Vector2;
@@ -123,6 +128,53 @@ pub fn model_to_wgpu(
 
     all_rendered.extend(rendered_selection);
 
+    if let Some(completion) = &ed_model.completion_opt {
+        all_rendered.extend(build_caret_popup_graphics(
+            &completion.to_popup_string(),
+            ed_model.get_caret(),
+            txt_coords,
+            config,
+            glyph_dim_rect,
+        ));
+    } else if let Some(diagnostic) = ed_model.get_diagnostic_at_caret() {
+        all_rendered.extend(build_caret_popup_graphics(
+            &diagnostic.message,
+            ed_model.get_caret(),
+            txt_coords,
+            config,
+            glyph_dim_rect,
+        ));
+    }
+
+    all_rendered.extend(build_plugin_widget_graphics(
+        &ed_model.plugin_widgets,
+        &ed_model.code_lines,
+        txt_coords,
+        config,
+        glyph_dim_rect,
+    ));
+
+    if let Some(expect_results) = &ed_model.expect_results_opt {
+        if let Ok(results) = expect_results {
+            all_rendered.add_rects_behind(build_expect_gutter_rects(
+                results,
+                txt_coords,
+                config,
+                glyph_dim_rect,
+            ));
+        }
+
+        if let Some(panel_text) = inspection_panel_text(expect_results) {
+            all_rendered.extend(build_inspection_panel_graphics(
+                &panel_text,
+                size,
+                txt_coords,
+                config,
+                glyph_dim_rect,
+            ));
+        }
+    }
+
     if ed_model.show_debug_view {
         all_rendered.add_text_behind(build_debug_graphics(size, txt_coords, config, ed_model)?);
     }
@@ -197,3 +249,140 @@ pub fn build_selection_graphics(
 
     Ok(all_rendered)
 }
+
+// show a popup, like the report of the diagnostic at the caret, above the caret
+pub fn build_caret_popup_graphics(
+    popup_text: &str,
+    caret_pos: TextPos,
+    txt_coords: Vector2<f32>,
+    config: &Config,
+    glyph_dim_rect: Rect,
+) -> RenderedWgpu {
+    let mut all_rendered = RenderedWgpu::new();
+
+    let tooltip = ToolTip {
+        position_x: txt_coords.x + (caret_pos.column as f32) * glyph_dim_rect.width,
+        position_y: txt_coords.y + ((caret_pos.line as f32) - 1.0) * glyph_dim_rect.height,
+        text: popup_text,
+    };
+
+    let (tip_rect, tip_text) = tooltip.render_tooltip(
+        &glyph_dim_rect,
+        &config.ed_theme.ui_theme,
+        config.code_font_size,
+    );
+
+    all_rendered.add_rect_front(tip_rect);
+    all_rendered.add_text_front(tip_text);
+
+    all_rendered
+}
+
+// a green or red marker left of the first line of every `expect`
+pub fn build_expect_gutter_rects(
+    expect_results: &[EdExpectResult],
+    txt_coords: Vector2<f32>,
+    config: &Config,
+    glyph_dim_rect: Rect,
+) -> Vec<Rect> {
+    let char_width = glyph_dim_rect.width;
+    let char_height = glyph_dim_rect.height;
+
+    expect_results
+        .iter()
+        .map(|result| Rect {
+            top_left_coords: (
+                txt_coords.x - 1.5 * char_width,
+                txt_coords.y + (result.region.start.line as f32) * char_height,
+            )
+                .into(),
+            width: char_width,
+            height: char_height,
+            color: if result.passed {
+                config.ed_theme.expect_passed
+            } else {
+                config.ed_theme.expect_failed
+            },
+        })
+        .collect()
+}
+
+// the reports of the failed expects, on the right side of the window
+pub fn build_inspection_panel_graphics(
+    panel_text: &str,
+    size: &PhysicalSize<u32>,
+    txt_coords: Vector2<f32>,
+    config: &Config,
+    glyph_dim_rect: Rect,
+) -> RenderedWgpu {
+    let mut all_rendered = RenderedWgpu::new();
+
+    let panel_x = (size.width as f32) * 0.6;
+    let padding = glyph_dim_rect.width;
+    let nr_lines = panel_text.lines().count().max(1) as f32;
+
+    all_rendered.add_rect_front(Rect {
+        top_left_coords: (panel_x, txt_coords.y).into(),
+        width: (size.width as f32) - panel_x,
+        height: nr_lines * glyph_dim_rect.height + 2.0 * padding,
+        color: config.ed_theme.ui_theme.tooltip_bg,
+    });
+
+    all_rendered.add_text_front(owned_section_from_text(&Text {
+        position: (panel_x + padding, txt_coords.y + padding).into(),
+        area_bounds: ((size.width as f32) - panel_x - 2.0 * padding, f32::INFINITY).into(),
+        color: config.ed_theme.ui_theme.tooltip_text,
+        text: panel_text,
+        size: config.code_font_size,
+        ..Default::default()
+    }));
+
+    all_rendered
+}
+
+// the widgets of editor plugins, two spaces after the end of their line
+pub fn build_plugin_widget_graphics(
+    plugin_widgets: &[PluginWidget],
+    code_lines: &CodeLines,
+    txt_coords: Vector2<f32>,
+    config: &Config,
+    glyph_dim_rect: Rect,
+) -> RenderedWgpu {
+    let mut all_rendered = RenderedWgpu::new();
+    let char_width = glyph_dim_rect.width;
+    let char_height = glyph_dim_rect.height;
+
+    for plugin_widget in plugin_widgets {
+        // the file may have changed since the widgets were rendered
+        let line_len = match code_lines.line_len(plugin_widget.line) {
+            Ok(line_len) => line_len,
+            Err(_) => continue,
+        };
+
+        let top_left_x = txt_coords.x + ((line_len + 2) as f32) * char_width;
+        let top_left_y = txt_coords.y + (plugin_widget.line as f32) * char_height;
+
+        match &plugin_widget.widget {
+            EdWidget::Swatch { red, green, blue } => all_rendered.add_rect_behind(Rect {
+                top_left_coords: (top_left_x, top_left_y).into(),
+                width: char_height,
+                height: char_height,
+                color: (
+                    *red as f32 / 255.0,
+                    *green as f32 / 255.0,
+                    *blue as f32 / 255.0,
+                    1.0,
+                ),
+            }),
+            EdWidget::Label(text) => all_rendered.add_text_behind(owned_section_from_text(&Text {
+                position: (top_left_x, top_left_y).into(),
+                color: config.ed_theme.subtle_text,
+                text,
+                size: config.code_font_size,
+                ..Default::default()
+            })),
+        }
+    }
+
+    all_rendered
+}
@@ -0,0 +1,179 @@
+use nonempty::NonEmpty;
+use roc_ast::lang::core::def::def2::DefId;
+use roc_ast::mem_pool::pool::PoolChange;
+use roc_code_markup::slow_pool::MarkNodeId;
+use roc_module::symbol::IdentIds;
+
+use crate::editor::ed_error::EdResult;
+use crate::editor::mvc::ed_model::EdModel;
+use crate::ui::text::caret_w_select::CaretWSelect;
+
+type Carets = NonEmpty<(CaretWSelect, Option<MarkNodeId>)>;
+
+// One step that can be undone: the AST nodes that were overwritten, and the state around the AST
+// (top level defs, identifier names, carets) before and after.
+// Markup is not stored, it is generated again from the AST.
+#[derive(Debug, Clone)]
+pub struct EdAction {
+    kind: ActionKind,
+    pool_changes: Vec<PoolChange>,
+    def_ids_before: Vec<DefId>,
+    def_ids_after: Vec<DefId>,
+    ident_ids_before: IdentIds,
+    ident_ids_after: IdentIds,
+    carets_before: Carets,
+    carets_after: Carets,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ActionKind {
+    Typing, // letters and digits, consecutive typing is undone at once
+    Other,
+}
+
+impl ActionKind {
+    pub fn for_char(received_char: &char) -> Self {
+        if received_char.is_ascii_alphanumeric() {
+            ActionKind::Typing
+        } else {
+            ActionKind::Other
+        }
+    }
+}
+
+// the state from before an action, see begin_action
+#[derive(Debug)]
+pub struct ActionStart {
+    def_ids: Vec<DefId>,
+    ident_ids: IdentIds,
+    carets: Carets,
+}
+
+#[derive(Debug, Default)]
+pub struct EdHistory {
+    undo_stack: Vec<EdAction>,
+    redo_stack: Vec<EdAction>,
+    can_merge: bool, // false right after an undo or redo, so typing afterwards starts a new group
+}
+
+impl EdHistory {
+    fn push(&mut self, action: EdAction) {
+        self.redo_stack.clear();
+
+        if self.can_merge {
+            if let Some(prev_action) = self.undo_stack.last_mut() {
+                let is_continuation = prev_action.kind == ActionKind::Typing
+                    && action.kind == ActionKind::Typing
+                    && prev_action.carets_after.first().0.caret_pos
+                        == action.carets_before.first().0.caret_pos;
+
+                if is_continuation {
+                    PoolChange::merge(&mut prev_action.pool_changes, action.pool_changes);
+                    prev_action.def_ids_after = action.def_ids_after;
+                    prev_action.ident_ids_after = action.ident_ids_after;
+                    prev_action.carets_after = action.carets_after;
+
+                    return;
+                }
+            }
+        }
+
+        self.undo_stack.push(action);
+        self.can_merge = true;
+    }
+}
+
+// start recording the changes to the AST
+pub fn begin_action(ed_model: &mut EdModel) -> ActionStart {
+    ed_model.module.env.pool.start_journal();
+
+    ActionStart {
+        def_ids: ed_model.module.ast.def_ids.clone(),
+        ident_ids: ed_model.module.env.ident_ids.clone(),
+        carets: ed_model.caret_w_select_vec.clone(),
+    }
+}
+
+// stop recording, the changes become a step in the history if there were any
+pub fn end_action(ed_model: &mut EdModel, start: ActionStart, kind: ActionKind) {
+    let pool_changes = ed_model.module.env.pool.take_journal();
+
+    // e.g. renaming a top level value only changes the ident_ids
+    let nothing_changed = pool_changes.is_empty()
+        && start.def_ids == ed_model.module.ast.def_ids
+        && start.ident_ids == ed_model.module.env.ident_ids;
+
+    if nothing_changed {
+        return;
+    }
+
+    let action = EdAction {
+        kind,
+        pool_changes,
+        def_ids_before: start.def_ids,
+        def_ids_after: ed_model.module.ast.def_ids.clone(),
+        ident_ids_before: start.ident_ids,
+        ident_ids_after: ed_model.module.env.ident_ids.clone(),
+        carets_before: start.carets,
+        carets_after: ed_model.caret_w_select_vec.clone(),
+    };
+
+    ed_model.history.push(action);
+}
+
+pub fn undo(ed_model: &mut EdModel) -> EdResult<()> {
+    if let Some(action) = ed_model.history.undo_stack.pop() {
+        ed_model.module.env.pool.undo_changes(&action.pool_changes);
+
+        restore(
+            ed_model,
+            action.def_ids_before.clone(),
+            action.ident_ids_before.clone(),
+            &action.carets_before,
+        )?;
+
+        ed_model.history.redo_stack.push(action);
+        ed_model.history.can_merge = false;
+    }
+
+    Ok(())
+}
+
+pub fn redo(ed_model: &mut EdModel) -> EdResult<()> {
+    if let Some(action) = ed_model.history.redo_stack.pop() {
+        ed_model.module.env.pool.redo_changes(&action.pool_changes);
+
+        restore(
+            ed_model,
+            action.def_ids_after.clone(),
+            action.ident_ids_after.clone(),
+            &action.carets_after,
+        )?;
+
+        ed_model.history.undo_stack.push(action);
+        ed_model.history.can_merge = false;
+    }
+
+    Ok(())
+}
+
+fn restore(
+    ed_model: &mut EdModel,
+    def_ids: Vec<DefId>,
+    ident_ids: IdentIds,
+    carets: &Carets,
+) -> EdResult<()> {
+    ed_model.module.ast.def_ids = def_ids;
+    ed_model.module.env.ident_ids = ident_ids;
+
+    // these refer to MarkupNodes, which are all replaced
+    ed_model.selected_block_opt = None;
+    ed_model.completion_opt = None;
+
+    ed_model.post_process_ast_update()?;
+
+    ed_model.caret_w_select_vec = carets.clone().map(|(caret_w_sel, _)| (caret_w_sel, None));
+    ed_model.dirty = true;
+
+    Ok(())
+}
@@ -5,8 +5,38 @@ use roc_code_markup::slow_pool::MarkNodeId;
 use crate::editor::ed_error::EdResult;
 use crate::editor::mvc::app_update::InputOutcome;
 use crate::editor::mvc::ed_model::EdModel;
+use crate::editor::mvc::ed_update::get_node_context;
+use crate::editor::mvc::ed_update::NodeContext;
 use crate::ui::text::lines::SelectableLines;
 
+// a lookup stays invalid until it refers to something, e.g. `List` until it becomes `List.len`
+pub fn start_new_lookup(ed_model: &mut EdModel, new_char: &char) -> EdResult<InputOutcome> {
+    let NodeContext {
+        old_caret_pos: _,
+        curr_mark_node_id: _,
+        curr_mark_node,
+        parent_id_opt: _,
+        ast_node_id,
+    } = get_node_context(ed_model)?;
+
+    if curr_mark_node.is_blank() {
+        let new_pool_str = PoolStr::new(&new_char.to_string(), ed_model.module.env.pool);
+
+        ed_model
+            .module
+            .env
+            .pool
+            .set(ast_node_id.to_expr_id()?, Expr2::InvalidLookup(new_pool_str));
+
+        let char_len = 1;
+        ed_model.simple_move_carets_right(char_len);
+
+        Ok(InputOutcome::Accepted)
+    } else {
+        Ok(InputOutcome::Ignored)
+    }
+}
+
 pub fn update_invalid_lookup(
     input_str: &str,
     old_pool_str: &PoolStr,
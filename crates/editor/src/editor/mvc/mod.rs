@@ -1,9 +1,11 @@
 pub mod app_model;
 pub mod app_update;
 mod break_line;
+mod completion_update;
 pub mod ed_model;
 pub mod ed_update;
 pub mod ed_view;
+mod history;
 mod int_update;
 mod let_update;
 mod list_update;
@@ -0,0 +1,282 @@
+// A package can change how the editor shows values of one of its types, e.g. a color can be shown as a swatch.
+// To do that, a module of the package defines a top-level function named `renderInEditor`:
+//
+//     Color : { red : U8, green : U8, blue : U8 }
+//
+//     renderInEditor : Color -> { kind : Str, text : Str }
+//     renderInEditor = \color -> { kind: "swatch", text: toHex color }
+//
+// It is used for every top-level value of the open file that is annotated with the type of its argument,
+// like `accent : Color`. The returned `kind` is either "swatch", which shows a square with the color
+// `text` (like "#ff8800"), or "label", which shows `text` after the value.
+//
+// The plugin is evaluated with the JIT of the repl, together with all defs of its module and the def of the value.
+// So for now, a plugin module can not use imports and the value can not use other defs of the open file.
+use bumpalo::Bump;
+use roc_mono::ir::OptLevel;
+use roc_parse::ast::{Pattern, TypeAnnotation, ValueDef};
+use roc_parse::module::{module_defs, parse_header};
+use roc_parse::parser::Parser;
+use roc_parse::state::State;
+use roc_region::all::LineInfo;
+use roc_std::RocStr;
+use std::path::PathBuf;
+use target_lexicon::Triple;
+
+pub const PLUGIN_FN_NAME: &str = "renderInEditor";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdPlugin {
+    pub type_name: String, // the plugin renders values of this type
+    pub defs: Vec<String>, // the source of every def of the module of the plugin
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EdWidget {
+    Swatch { red: u8, green: u8, blue: u8 },
+    Label(String),
+}
+
+// A widget that is shown at the end of a line of the open file
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginWidget {
+    pub line: usize,
+    pub widget: EdWidget,
+}
+
+// The Rust version of the `{ kind : Str, text : Str }` that a plugin returns
+#[repr(C)]
+struct RocWidget {
+    kind: RocStr,
+    text: RocStr,
+}
+
+#[derive(Debug)]
+struct TopLevelDef {
+    name: Option<String>,
+    arg_type_name: Option<String>, // the name of the type of the value, or of the first argument if it is a function
+    src: String,
+    line: usize,
+}
+
+// The top-level defs of a module, an empty Vec if it does not parse
+fn top_level_defs(src: &str) -> Vec<TopLevelDef> {
+    let arena = Bump::new();
+
+    let state = match parse_header(&arena, State::new(src.as_bytes())) {
+        Ok((_, state)) => state,
+        Err(_) => return Vec::new(),
+    };
+
+    let defs = match module_defs().parse(&arena, state, 0) {
+        Ok((_, defs, _)) => defs,
+        Err(_) => return Vec::new(),
+    };
+
+    let line_info = LineInfo::new(src);
+
+    defs.defs()
+        .zip(defs.regions.iter())
+        .map(|(def, region)| {
+            let (name, arg_type_name) = match def {
+                Err(ValueDef::Annotation(pattern, ann_type)) => {
+                    (pattern_name(&pattern.value), arg_type_name(&ann_type.value))
+                }
+                Err(ValueDef::AnnotatedBody {
+                    ann_pattern,
+                    ann_type,
+                    ..
+                }) => (pattern_name(&ann_pattern.value), arg_type_name(&ann_type.value)),
+                Err(ValueDef::Body(pattern, _)) => (pattern_name(&pattern.value), None),
+                _ => (None, None),
+            };
+
+            TopLevelDef {
+                name,
+                arg_type_name,
+                src: src[region.start().offset as usize..region.end().offset as usize]
+                    .to_string(),
+                line: line_info.convert_pos(region.start()).line as usize,
+            }
+        })
+        .collect()
+}
+
+fn pattern_name(pattern: &Pattern) -> Option<String> {
+    match pattern {
+        Pattern::Identifier(name) => Some(name.to_string()),
+        Pattern::SpaceBefore(inner, _) | Pattern::SpaceAfter(inner, _) => pattern_name(inner),
+        _ => None,
+    }
+}
+
+fn arg_type_name(ann_type: &TypeAnnotation) -> Option<String> {
+    match ann_type {
+        TypeAnnotation::Apply(_, name, args) if args.is_empty() => Some(name.to_string()),
+        TypeAnnotation::Function(args, _) if args.len() == 1 => arg_type_name(&args[0].value),
+        TypeAnnotation::SpaceBefore(inner, _) | TypeAnnotation::SpaceAfter(inner, _) => {
+            arg_type_name(inner)
+        }
+        _ => None,
+    }
+}
+
+// Find the plugins in the sources of the modules that the open file uses.
+pub fn find_plugins(module_srcs: &[String]) -> Vec<EdPlugin> {
+    let mut plugins = Vec::new();
+
+    for module_src in module_srcs {
+        let defs = top_level_defs(module_src);
+
+        let plugin_def_opt = defs
+            .iter()
+            .find(|def| def.name.as_deref() == Some(PLUGIN_FN_NAME) && def.arg_type_name.is_some());
+
+        if let Some(plugin_def) = plugin_def_opt {
+            plugins.push(EdPlugin {
+                type_name: plugin_def.arg_type_name.clone().unwrap_or_default(),
+                defs: defs.iter().map(|def| def.src.clone()).collect(),
+            });
+        }
+    }
+
+    plugins
+}
+
+// Parse what a plugin returned, None if it is not a widget the editor knows.
+pub fn to_widget(kind: &str, text: &str) -> Option<EdWidget> {
+    match kind {
+        "swatch" => {
+            let hex = text.strip_prefix('#')?;
+
+            if hex.len() != 6 {
+                return None;
+            }
+
+            let channel = |idx: usize| u8::from_str_radix(hex.get(idx..idx + 2)?, 16).ok();
+
+            Some(EdWidget::Swatch {
+                red: channel(0)?,
+                green: channel(2)?,
+                blue: channel(4)?,
+            })
+        }
+        "label" => Some(EdWidget::Label(text.to_string())),
+        _ => None,
+    }
+}
+
+// Evaluate the plugin for the value defined by `value_def`.
+// Values that don't compile with only the defs of the plugin module are skipped.
+fn render_value(plugin: &EdPlugin, value_name: &str, value_def: &str) -> Option<EdWidget> {
+    let defs = plugin
+        .defs
+        .iter()
+        .map(|def| def.as_str())
+        .chain(std::iter::once(value_def));
+    let expr = format!("{} {}", PLUGIN_FN_NAME, value_name);
+
+    let (compiled, _) =
+        roc_repl_cli::compile_expr(defs, &expr, Triple::host(), OptLevel::Development).ok()?;
+
+    // the plugin's annotation guarantees that this is a `{ kind : Str, text : Str }`
+    let roc_widget = unsafe { compiled.call::<RocWidget>() }.ok()?;
+
+    to_widget(roc_widget.kind.as_str(), roc_widget.text.as_str())
+}
+
+// Evaluate the plugins for all values of the file that have the type of a plugin.
+// This compiles every value, so it should not run on the UI thread.
+pub fn render_widgets(file_src: &str, module_srcs: &[String]) -> Vec<PluginWidget> {
+    let plugins = find_plugins(module_srcs);
+
+    if plugins.is_empty() {
+        return Vec::new();
+    }
+
+    let mut widgets = Vec::new();
+
+    for def in top_level_defs(file_src) {
+        let plugin_opt = plugins
+            .iter()
+            .find(|plugin| def.arg_type_name.as_ref() == Some(&plugin.type_name));
+
+        if let (Some(plugin), Some(name)) = (plugin_opt, &def.name) {
+            if let Some(widget) = render_value(plugin, name, &def.src) {
+                widgets.push(PluginWidget {
+                    line: def.line,
+                    widget,
+                });
+            }
+        }
+    }
+
+    widgets
+}
+
+// Run render_widgets on a separate thread for the saved version of the file, `on_done` receives the widgets.
+pub fn render_widgets_in_background<F>(file_path: PathBuf, module_srcs: Vec<String>, on_done: F)
+where
+    F: FnOnce(Vec<PluginWidget>) + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let file_src = std::fs::read_to_string(&file_path).unwrap_or_default();
+
+        on_done(render_widgets(&file_src, &module_srcs))
+    });
+}
+
+#[cfg(test)]
+pub mod test_plugins {
+    use crate::editor::plugins::{find_plugins, to_widget, EdWidget};
+
+    #[test]
+    fn find_color_plugin() {
+        let module_src = r#"interface Color
+    exposes [Color, renderInEditor]
+    imports []
+
+Color : { red : U8, green : U8, blue : U8 }
+
+renderInEditor : Color -> { kind : Str, text : Str }
+renderInEditor = \_ -> { kind: "label", text: "a color" }
+"#;
+
+        let plugins = find_plugins(&[module_src.to_string()]);
+
+        assert_eq!(plugins.len(), 1);
+        assert_eq!(plugins[0].type_name, "Color");
+        assert_eq!(plugins[0].defs.len(), 2);
+    }
+
+    #[test]
+    fn no_plugin_without_render_fn() {
+        let module_src = r#"interface Color
+    exposes [Color]
+    imports []
+
+Color : { red : U8, green : U8, blue : U8 }
+"#;
+
+        assert!(find_plugins(&[module_src.to_string()]).is_empty());
+    }
+
+    #[test]
+    fn parse_widgets() {
+        assert_eq!(
+            to_widget("swatch", "#ff8000"),
+            Some(EdWidget::Swatch {
+                red: 255,
+                green: 128,
+                blue: 0
+            })
+        );
+        assert_eq!(to_widget("swatch", "ff8000"), None);
+        assert_eq!(to_widget("swatch", "#ff80"), None);
+        assert_eq!(
+            to_widget("label", "hi"),
+            Some(EdWidget::Label("hi".to_string()))
+        );
+        assert_eq!(to_widget("button", "hi"), None);
+    }
+}
@@ -5,7 +5,7 @@ use crate::graphics::primitives::text as gr_text;
 use cgmath::Vector2;
 use roc_code_markup::{
     markup::{
-        attribute::Attribute,
+        attribute::{Attribute, UnderlineSpec},
         nodes::{MarkupNode, BLANK_PLACEHOLDER},
     },
     slow_pool::{MarkNodeId, SlowPool},
@@ -145,27 +145,21 @@ fn markup_to_wgpu_helper<'a>(
 
             for attribute in &attributes.all {
                 match attribute {
-                    Attribute::Underline { underline_spec: _ } => {
-                        // TODO use underline_spec
-                        let top_left_coords = (
-                            code_style.txt_coords.x + (txt_row_col.1 as f32) * char_width,
-                            code_style.txt_coords.y
-                                + (txt_row_col.0 as f32) * char_height
-                                + 1.0 * char_height,
-                        );
-
-                        let underline_rect = Rect {
-                            top_left_coords: top_left_coords.into(),
-                            width: char_width * (full_content.len() as f32),
-                            height: 5.0,
-                            color: *code_style
-                                .ed_theme
-                                .underline_color_map
-                                .get(&UnderlineStyle::Error)
-                                .unwrap(),
+                    Attribute::Underline {
+                        underline_spec,
+                        underline_style,
+                    } => {
+                        let (start_col, end_col) = match underline_spec {
+                            UnderlineSpec::Partial { start, end } => (*start, *end),
+                            UnderlineSpec::Full => (0, content.len()),
                         };
 
-                        rects.push(underline_rect);
+                        rects.push(underline_rect(
+                            (txt_row_col.0, txt_row_col.1 + start_col),
+                            end_col.saturating_sub(start_col),
+                            underline_style,
+                            code_style,
+                        )?);
                     }
                     rest => todo!("handle Attribute: {:?}", rest),
                 }
@@ -181,7 +175,7 @@ fn markup_to_wgpu_helper<'a>(
             wgpu_texts.push(glyph_text);
         }
         MarkupNode::Blank {
-            attributes: _,
+            attributes,
             parent_id_opt: _,
             newlines_at_end,
         } => {
@@ -208,6 +202,20 @@ fn markup_to_wgpu_helper<'a>(
             };
             rects.push(blank_rect);
 
+            for attribute in &attributes.all {
+                if let Attribute::Underline {
+                    underline_style, ..
+                } = attribute
+                {
+                    rects.push(underline_rect(
+                        *txt_row_col,
+                        BLANK_PLACEHOLDER.len(),
+                        underline_style,
+                        code_style,
+                    )?);
+                }
+            }
+
             txt_row_col.1 += BLANK_PLACEHOLDER.len();
             wgpu_texts.push(glyph_text);
 
@@ -232,6 +240,29 @@ fn markup_to_wgpu_helper<'a>(
     Ok(())
 }
 
+// underline `width` chars, starting at `(row, col)`, e.g. for a warning or an error
+fn underline_rect(
+    (row, col): (usize, usize),
+    width: usize,
+    underline_style: &UnderlineStyle,
+    code_style: &CodeStyle,
+) -> EdResult<Rect> {
+    let char_width = code_style.glyph_dim_rect.width;
+    let char_height = code_style.glyph_dim_rect.height;
+
+    let top_left_coords = (
+        code_style.txt_coords.x + (col as f32) * char_width,
+        code_style.txt_coords.y + (row as f32) * char_height + 1.0 * char_height,
+    );
+
+    Ok(Rect {
+        top_left_coords: top_left_coords.into(),
+        width: char_width * (width as f32),
+        height: 5.0,
+        color: *map_get(&code_style.ed_theme.underline_color_map, underline_style)?,
+    })
+}
+
 fn newline(font_size: f32) -> glyph_brush::OwnedText {
     glyph_brush::OwnedText::new("\n").with_scale(font_size)
 }
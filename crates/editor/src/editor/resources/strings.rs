@@ -4,12 +4,14 @@ pub const NOTHING_OPENED: &str =
     "Execute `cargo run edit` from the root folder of the repo to try the editor.";
 
 pub const START_TIP: &str = r#"Currently supported: lists, records, string, numbers and value definitions.
+Modules with `when`, `if`, closures, imports and type annotations can be opened and saved, editing those is not supported yet.
 
 Use `Ctrl+Shift+Up` or `Cmd+Shift+Up` to select surrounding expression.
 Use backspace after `Ctrl+Shift+Up` to delete the selected expression.
 
 `Ctrl+S` or `Cmd+S` to save.
 `Ctrl+R` to run.
+`Ctrl+T` or `Cmd+T` to save and run the expects, failures are shown on the right.
 
 Input chars that would create parse errors or change formatting will be ignored.
 For convenience and consistency, there is only one way to format roc.
@@ -16,6 +16,8 @@ pub struct EdTheme {
     pub syntax_high_map: HashMap<HighlightStyle, RgbaTup>,
     pub ui_theme: UITheme,
     pub underline_color_map: HashMap<UnderlineStyle, RgbaTup>,
+    pub expect_passed: RgbaTup, // gutter marker of an `expect` that passed
+    pub expect_failed: RgbaTup,
 }
 
 impl Default for EdTheme {
@@ -26,6 +28,8 @@ impl Default for EdTheme {
             syntax_high_map: default_highlight_map(),
             ui_theme: UITheme::default(),
             underline_color_map: default_underline_color_map(),
+            expect_passed: from_hsb(120, 60, 70),
+            expect_failed: from_hsb(0, 60, 80),
         }
     }
 }
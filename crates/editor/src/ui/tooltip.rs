@@ -65,20 +65,30 @@ impl<'a> ToolTip<'a> {
 
         let y_margin = glyph_dim_rect.height / 4.0;
 
+        // a tooltip with multiple lines grows upwards, so it doesn't cover the line it is about
+        let nr_lines = self.text.lines().count().max(1);
+        let extra_height = glyph_dim_rect.height * ((nr_lines - 1) as f32);
+        let max_line_len = self
+            .text
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+
         let text = self.make_tooltip_text(
             text_x_offset,
             text_y_offset,
-            y_margin,
+            y_margin + extra_height,
             ui_theme,
             code_font_size,
         );
         let text_section = gr_text::owned_section_from_text(&text);
 
         let rect = self.make_tooltip_rect(
-            glyph_dim_rect.width * (text.text.len() as f32) + width_padding,
-            glyph_dim_rect.height,
+            glyph_dim_rect.width * (max_line_len as f32) + width_padding,
+            glyph_dim_rect.height + extra_height,
             height_padding,
-            y_margin,
+            y_margin + extra_height,
             ui_theme,
         );
 
@@ -52,6 +52,26 @@ pub fn from_winit(winit_mods: &winit::event::ModifiersState) -> Modifiers {
 pub mod test_modifiers {
     use crate::window::keyboard_input::Modifiers;
 
+    pub fn ctrl_cmd() -> Modifiers {
+        #[cfg(target_os = "macos")]
+        let mods = Modifiers {
+            shift: false,
+            ctrl: false,
+            alt: false,
+            logo: true,
+        };
+
+        #[cfg(not(target_os = "macos"))]
+        let mods = Modifiers {
+            shift: false,
+            ctrl: true,
+            alt: false,
+            logo: false,
+        };
+
+        mods
+    }
+
     pub fn ctrl_cmd_shift() -> Modifiers {
         #[cfg(target_os = "macos")]
         let mods = Modifiers {
@@ -0,0 +1,254 @@
+//! A panic hook that writes a crash report to a local file when the compiler panics.
+//!
+//! Nothing is sent anywhere: the report is a plain text file, and the user decides whether to attach it
+//! to an issue. To make the report useful, the compiler threads some context through a thread local:
+//! the phase it is in, the module it is working on, and the region inside that module.
+//! Every worker thread handles one module at a time, so a thread local is enough to know what the
+//! thread was doing when it panicked.
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::panic::PanicInfo;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const ISSUE_URL: &str = "https://github.com/roc-lang/roc/issues/new/choose";
+
+/// When this environment variable is set, no report is written, e.g. because the crash is expected
+pub const NO_CRASH_REPORT_ENV_VAR: &str = "ROC_NO_CRASH_REPORT";
+
+/// The number of lines shown before and after the region in the snippet
+const SNIPPET_CONTEXT_LINES: usize = 2;
+
+/// A snippet is cut off after this many lines, a huge def does not help anyone reproduce the crash
+const SNIPPET_MAX_LINES: usize = 20;
+
+/// The path of every module the compiler has seen, by the module's key.
+/// Modules are registered once, when they are parsed, and are looked up only when crashing.
+static MODULE_PATHS: Mutex<Option<HashMap<u32, PathBuf>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Default)]
+struct CrashContext {
+    phase: Option<&'static str>,
+    module_key: Option<u32>,
+    /// byte offsets into the source of the module
+    region: Option<(u32, u32)>,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<CrashContext> = RefCell::new(CrashContext::default());
+}
+
+/// Remember where the source of a module is, so a crash while processing it can show a snippet.
+pub fn register_module(module_key: u32, path: &Path) {
+    if let Ok(mut paths) = MODULE_PATHS.lock() {
+        paths
+            .get_or_insert_with(HashMap::new)
+            .insert(module_key, path.to_path_buf());
+    }
+}
+
+/// Restores the context of the thread to what it was before `enter_phase` when dropped.
+#[must_use]
+pub struct PhaseGuard {
+    previous: CrashContext,
+}
+
+impl Drop for PhaseGuard {
+    fn drop(&mut self) {
+        let previous = std::mem::take(&mut self.previous);
+
+        CONTEXT.with(|context| *context.borrow_mut() = previous);
+    }
+}
+
+/// Record that this thread starts `phase` for the given module, until the returned guard is dropped.
+pub fn enter_phase(phase: &'static str, module_key: Option<u32>) -> PhaseGuard {
+    let new_context = CrashContext {
+        phase: Some(phase),
+        module_key,
+        region: None,
+    };
+
+    let previous = CONTEXT.with(|context| context.replace(new_context));
+
+    PhaseGuard { previous }
+}
+
+/// Record the region (as byte offsets) of the module that this thread is processing right now.
+pub fn set_region(start: u32, end: u32) {
+    CONTEXT.with(|context| context.borrow_mut().region = Some((start, end)));
+}
+
+/// Install the panic hook. The hook that was installed before still runs after the report is written,
+/// so the usual panic message and backtrace are printed too.
+pub fn install(compiler_version: &'static str) {
+    let previous_hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        if std::env::var_os(NO_CRASH_REPORT_ENV_VAR).is_some() {
+            previous_hook(info);
+
+            return;
+        }
+
+        let context = CONTEXT.with(|context| context.borrow().clone());
+        let report = crash_report(compiler_version, &context, info);
+
+        eprintln!("\nThe Roc compiler crashed. This is definitely a compiler bug.");
+
+        match write_report(&report) {
+            Ok(path) => {
+                eprintln!("A crash report was written to {}", path.display());
+                eprintln!(
+                    "Please look it over and attach it to an issue here: {}\n",
+                    ISSUE_URL
+                );
+            }
+            Err(_) => {
+                eprintln!("Please file an issue here: {}\n", ISSUE_URL);
+                eprintln!("{}", report);
+            }
+        }
+
+        previous_hook(info);
+    }));
+}
+
+fn write_report(report: &str) -> std::io::Result<PathBuf> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let path = std::env::temp_dir().join(format!(
+        "roc-crash-{}-{}.txt",
+        timestamp,
+        std::process::id()
+    ));
+
+    std::fs::write(&path, report)?;
+
+    Ok(path)
+}
+
+fn crash_report(compiler_version: &str, context: &CrashContext, info: &PanicInfo) -> String {
+    let module_path = context.module_key.and_then(|key| {
+        let paths = MODULE_PATHS.lock().ok()?;
+
+        paths.as_ref()?.get(&key).cloned()
+    });
+
+    let mut report = String::new();
+
+    // writing to a String can not fail
+    let _ = writeln!(report, "# Roc compiler crash report\n");
+    let _ = writeln!(
+        report,
+        "Please attach this file to an issue at {}",
+        ISSUE_URL
+    );
+    let _ = writeln!(
+        report,
+        "It contains a snippet of your code, remove anything you would rather not share.\n"
+    );
+    let _ = writeln!(report, "compiler version: {}", compiler_version.trim());
+    let _ = writeln!(report, "phase: {}", context.phase.unwrap_or("unknown"));
+
+    match &module_path {
+        Some(path) => {
+            let _ = writeln!(report, "module: {}", path.display());
+        }
+        None => {
+            let _ = writeln!(report, "module: unknown");
+        }
+    }
+
+    let src_opt = module_path.and_then(|path| std::fs::read_to_string(path).ok());
+
+    if let (Some(src), Some((start, end))) = (&src_opt, context.region) {
+        let (line, column) = line_column(src, start as usize);
+
+        let _ = writeln!(report, "region: line {}, column {}", line + 1, column + 1);
+        let _ = writeln!(report, "\n## Snippet\n");
+        let _ = writeln!(report, "{}", snippet(src, start as usize, end as usize));
+    }
+
+    let _ = writeln!(report, "\n## Panic\n");
+
+    let message = if let Some(message) = info.payload().downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = info.payload().downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "(no message)".to_string()
+    };
+
+    let _ = writeln!(report, "{}", message);
+
+    if let Some(location) = info.location() {
+        let _ = writeln!(report, "at {}", location);
+    }
+
+    let _ = writeln!(report, "\n## Backtrace\n");
+    let _ = writeln!(report, "{}", Backtrace::force_capture());
+
+    report
+}
+
+/// The 0-based line and column of a byte offset
+fn line_column(src: &str, offset: usize) -> (usize, usize) {
+    let before = &src.as_bytes()[..offset.min(src.len())];
+    let line = before.iter().filter(|&&byte| byte == b'\n').count();
+    let line_start = before
+        .iter()
+        .rposition(|&byte| byte == b'\n')
+        .map_or(0, |idx| idx + 1);
+
+    (line, offset.min(src.len()) - line_start)
+}
+
+/// The lines of the region with a couple of lines around it, numbered like in an editor.
+/// The lines of the region itself are marked with a `>`.
+fn snippet(src: &str, start: usize, end: usize) -> String {
+    let (start_line, _) = line_column(src, start);
+    let (end_line, _) = line_column(src, end.max(start));
+
+    let first = start_line.saturating_sub(SNIPPET_CONTEXT_LINES);
+    let last = (end_line + SNIPPET_CONTEXT_LINES).min(first + SNIPPET_MAX_LINES - 1);
+
+    let lines: Vec<String> = src
+        .lines()
+        .enumerate()
+        .skip(first)
+        .take(last + 1 - first)
+        .map(|(idx, line)| {
+            let marker = if (start_line..=end_line).contains(&idx) {
+                '>'
+            } else {
+                ' '
+            };
+
+            format!("{} {:>4} | {}", marker, idx + 1, line)
+        })
+        .collect();
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{line_column, snippet};
+
+    #[test]
+    fn snippet_marks_region_lines() {
+        let src = "a\nb\nc\nd = 1\ne\nf\ng\nh\n";
+        let start = src.find('d').unwrap();
+
+        assert_eq!(line_column(src, start), (3, 0));
+        assert_eq!(
+            snippet(src, start, start + 5),
+            "     2 | b\n     3 | c\n>    4 | d = 1\n     5 | e\n     6 | f"
+        );
+    }
+}
@@ -0,0 +1,827 @@
+app "csharp-glue"
+    packages { pf: "../platform/main.roc" }
+    imports [pf.Types.{ Types }, pf.Shape.{ Shape }, pf.File.{ File }, pf.TypeId.{ TypeId }]
+    provides [makeGlue] to pf
+
+## Generates C# bindings for a Roc app built as a shared library (`roc build --lib`), so .NET
+## applications can call it through `DllImport`.
+makeGlue : List Types -> Result (List File) Str
+makeGlue = \typesByArch ->
+    # .NET runs on 64-bit targets, which all lay out Roc values the same way, so one file covers them
+    is64Bit = \types ->
+        when (Types.target types).architecture is
+            X86x64 | Aarch64 -> Bool.true
+            _ -> Bool.false
+
+    when List.findFirst typesByArch is64Bit is
+        Ok types -> Ok [convertTypesToFile types]
+        Err NotFound -> Err "C# glue needs the types of a 64-bit target, like x86_64 or aarch64, and there weren't any."
+
+convertTypesToFile : Types -> File
+convertTypesToFile = \types ->
+    declarations =
+        Types.walkShapes types { buf: fileHeader, declared: Set.empty {} } \state, shape, id ->
+            name = typeName types id
+
+            # identical shapes, like two uses of the same Result, can have different ids
+            if Set.contains state.declared name then
+                state
+            else
+                { buf: generateDeclaration state.buf types shape id, declared: Set.insert state.declared name }
+
+    {
+        name: "Roc.cs",
+        content: declarations.buf |> generateEntryPoints types,
+    }
+
+generateDeclaration : Str, Types, Shape, TypeId -> Str
+generateDeclaration = \buf, types, shape, id ->
+    when shape is
+        Struct { name, fields } ->
+            generateStruct buf types id name (structFields fields)
+
+        TagUnionPayload { name, fields } ->
+            generateStruct buf types id name (payloadFields fields)
+
+        TagUnion (Enumeration { name, tags, size }) ->
+            generateEnumeration buf name tags size
+
+        TagUnion (NonRecursive { name, tags, discriminantSize, discriminantOffset }) ->
+            if List.isEmpty tags then
+                buf
+            else
+                generateTagUnion buf types id name tags discriminantSize discriminantOffset
+
+        TagUnion (SingleTagStruct { name, tagName, payload }) ->
+            generateSingleTagStruct buf types id name tagName payload
+
+        TagUnion (Recursive { name }) | TagUnion (NullableWrapped { name }) | TagUnion (NullableUnwrapped { name }) | TagUnion (NonNullableUnwrapped { name }) ->
+            generateHeapHandle buf name
+
+        RocResult okId errId ->
+            generateResult buf types id okId errId
+
+        RocDict _ _ | RocSet _ ->
+            generateOpaque buf types id
+
+        Function rocFn ->
+            if rocFn.isToplevel then
+                buf
+            else
+                generateOpaque buf types id
+
+        RecursivePointer _
+        | Unit
+        | Unsized
+        | EmptyTagUnion
+        | Num _
+        | Bool
+        | RocStr
+        | RocList _
+        | RocBox _ ->
+            # These are declared in the file header.
+            buf
+
+structFields = \fields ->
+    when fields is
+        HasNoClosure list -> List.map list \field -> { name: field.name, id: field.id }
+        HasClosure list -> List.map list \field -> { name: field.name, id: field.id }
+
+payloadFields = \fields ->
+    # Tag union payloads have numbered fields, so we prefix them with an "f" because C# doesn't
+    # allow field names to be numbers.
+    when fields is
+        HasNoClosure list -> List.map list \field -> { name: "f\(field.name)", id: field.id }
+        HasClosure list -> List.map list \field -> { name: "f\(field.name)", id: field.id }
+
+## The fields of a struct along with their offsets. Roc gives them in the order they're laid out.
+withOffsets : Types, List { name : Str, id : TypeId } -> List { name : Str, id : TypeId, offset : U32 }
+withOffsets = \types, fields ->
+    placed =
+        List.walk fields { end: 0, placed: List.withCapacity (List.len fields) } \state, field ->
+            offset = roundUpToAlignment state.end (Types.alignment types field.id)
+
+            { end: offset + Types.size types field.id, placed: List.append state.placed { name: field.name, id: field.id, offset } }
+
+    placed.placed
+
+generateStruct = \buf, types, id, name, fields ->
+    escapedName = escapeKW name
+    size = Num.toStr (Types.size types id)
+    fieldLines =
+        withOffsets types fields
+        |> List.map \field -> generateField types Public field.name field.id field.offset
+        |> Str.joinWith ""
+
+    """
+    \(buf)
+    [StructLayout(LayoutKind.Explicit, Size = \(size))]
+    public struct \(escapedName)
+    {
+    \(fieldLines)}
+
+    """
+
+generateField = \types, visibility, name, id, offset ->
+    offsetStr = Num.toStr offset
+    type = typeName types id
+    escapedName = escapeKW name
+
+    marshal =
+        when Types.shape types id is
+            # C# marshals bool as 4 bytes unless it's told otherwise
+            Bool -> "[MarshalAs(UnmanagedType.U1)] "
+            _ -> ""
+
+    access =
+        when visibility is
+            Public -> "public"
+            Private -> "private"
+
+    "\(indent)[FieldOffset(\(offsetStr))] \(marshal)\(access) \(type) \(escapedName);\n"
+
+generateEnumeration = \buf, name, tags, size ->
+    escapedName = escapeKW name
+    underlyingType = discriminantType size
+    members =
+        tags
+        |> List.mapWithIndex \tag, index ->
+            escapedTag = escapeKW tag
+            value = Num.toStr index
+
+            "\(indent)\(escapedTag) = \(value),\n"
+        |> Str.joinWith ""
+
+    """
+    \(buf)
+    public enum \(escapedName) : \(underlyingType)
+    {
+    \(members)}
+
+    """
+
+## A non-recursive tag union: its payloads overlap at offset 0, and the discriminant comes after
+## them. C# code reads it with `Match`, or with the `Is` and `As` properties of each tag.
+generateTagUnion = \buf, types, id, name, tags, discriminantSize, discriminantOffset ->
+    escapedName = escapeKW name
+    size = Num.toStr (Types.size types id)
+    offset = Num.toStr discriminantOffset
+    tagType = discriminantType discriminantSize
+
+    tagMembers =
+        tags
+        |> List.mapWithIndex \tag, index ->
+            escapedTag = escapeKW tag.name
+            value = Num.toStr index
+
+            "\(indent)\(indent)\(escapedTag) = \(value),\n"
+        |> Str.joinWith ""
+
+    payloadMembers =
+        tags
+        |> List.map \tag ->
+            when tag.payload is
+                Some payloadId -> generateField types Private "payload\(tag.name)" payloadId 0
+                None -> ""
+        |> Str.joinWith ""
+
+    tagFunctions =
+        tags
+        |> List.map \tag -> generateTagFunctions types escapedName tag.name tag.payload
+        |> Str.joinWith ""
+
+    matchArguments =
+        tags
+        |> List.map \tag ->
+            argName = camelCase tag.name
+
+            when tag.payload is
+                Some payloadId ->
+                    payloadType = typeName types payloadId
+
+                    "Func<\(payloadType), T> \(argName)"
+
+                None -> "Func<T> \(argName)"
+        |> Str.joinWith ", "
+
+    matchArms =
+        tags
+        |> List.map \tag ->
+            payload =
+                when tag.payload is
+                    Some _ -> "payload\(tag.name)"
+                    None -> ""
+
+            escapedTag = escapeKW tag.name
+            argName = camelCase tag.name
+
+            "\(indent)\(indent)Tag.\(escapedTag) => \(argName)(\(payload)),\n"
+        |> Str.joinWith ""
+
+    """
+    \(buf)
+    [StructLayout(LayoutKind.Explicit, Size = \(size))]
+    public struct \(escapedName)
+    {
+        public enum Tag : \(tagType)
+        {
+    \(tagMembers)    }
+
+    \(payloadMembers)    [FieldOffset(\(offset))] private Tag discriminant;
+
+        /// <summary>Which tag this is.</summary>
+        public Tag Which => discriminant;
+    \(tagFunctions)
+        public T Match<T>(\(matchArguments)) => discriminant switch
+        {
+    \(matchArms)        _ => throw new InvalidOperationException("\(escapedName) has an invalid discriminant: " + discriminant),
+        };
+    }
+
+    """
+
+generateTagFunctions = \types, unionName, tagName, payload ->
+    tag = escapeKW tagName
+
+    when payload is
+        Some payloadId ->
+            payloadType = typeName types payloadId
+
+            """
+
+                public static \(unionName) \(tag)(\(payloadType) payload) => new \(unionName) { payload\(tagName) = payload, discriminant = Tag.\(tag) };
+
+                public bool Is\(tagName) => discriminant == Tag.\(tag);
+
+                public \(payloadType) As\(tagName) => discriminant == Tag.\(tag) ? payload\(tagName) : throw new InvalidOperationException("This \(unionName) is a " + discriminant + ", not a \(tagName)");
+
+            """
+
+        None ->
+            """
+
+                public static \(unionName) \(tag)() => new \(unionName) { discriminant = Tag.\(tag) };
+
+                public bool Is\(tagName) => discriminant == Tag.\(tag);
+
+            """
+
+generateSingleTagStruct = \buf, types, id, name, tagName, payload ->
+    fields =
+        when payload is
+            HasNoClosure list -> List.mapWithIndex list \field, index -> { name: Str.concat "f" (Num.toStr index), id: field.id }
+            HasClosure list -> List.map list \field -> { name: field.name, id: field.id }
+
+    generateStruct "\(buf)\n/// <summary>The payload of `\(tagName)`, the only tag of this union.</summary>" types id name fields
+
+## Recursive tag unions live on Roc's heap, so C# code gets a pointer to them, which it can pass
+## back to Roc but not look inside.
+generateHeapHandle = \buf, name ->
+    escapedName = escapeKW name
+
+    """
+    \(buf)
+    /// <summary>A recursive tag union, which lives on Roc's heap. It can be passed back to Roc, but not inspected from C#.</summary>
+    [StructLayout(LayoutKind.Sequential)]
+    public unsafe struct \(escapedName)
+    {
+        private void* pointer;
+    }
+
+    """
+
+## Values whose layout C# code doesn't need to know about, like closures and dictionaries.
+generateOpaque = \buf, types, id ->
+    name = typeName types id
+    # C# doesn't allow a struct size of 0
+    size = Num.toStr (atLeast 1 (Types.size types id))
+
+    """
+    \(buf)
+    /// <summary>A Roc value which can be passed back to Roc, but not inspected from C#.</summary>
+    [StructLayout(LayoutKind.Sequential, Size = \(size))]
+    public struct \(name)
+    {
+    }
+
+    """
+
+generateResult = \buf, types, id, okId, errId ->
+    name = typeName types id
+    okType = typeName types okId
+    errType = typeName types errId
+    size = Num.toStr (Types.size types id)
+
+    # like any other non-recursive tag union, with Err as tag 0 and Ok as tag 1
+    payloadSize = atLeast (Types.size types okId) (Types.size types errId)
+    payloadAlignment = atLeast (Types.alignment types okId) (Types.alignment types errId)
+    offset = Num.toStr (roundUpToAlignment payloadSize payloadAlignment)
+
+    okField = generateField types Private "ok" okId 0
+    errField = generateField types Private "err" errId 0
+
+    """
+    \(buf)
+    [StructLayout(LayoutKind.Explicit, Size = \(size))]
+    public struct \(name)
+    {
+    \(okField)\(errField)    [FieldOffset(\(offset))] private byte discriminant;
+
+        public static \(name) FromOk(\(okType) value) => new \(name) { ok = value, discriminant = 1 };
+
+        public static \(name) FromErr(\(errType) value) => new \(name) { err = value, discriminant = 0 };
+
+        public bool IsOk => discriminant == 1;
+
+        public \(okType) Ok => IsOk ? ok : throw new InvalidOperationException("This result is an Err");
+
+        public \(errType) Err => !IsOk ? err : throw new InvalidOperationException("This result is an Ok");
+
+        public T Match<T>(Func<\(okType), T> onOk, Func<\(errType), T> onErr) => IsOk ? onOk(ok) : onErr(err);
+    }
+
+    """
+
+generateEntryPoints : Str, Types -> Str
+generateEntryPoints = \buf, types ->
+    entryPoints =
+        List.walk (Types.entryPoints types) "" \accum, T name id -> generateEntryPoint accum types name id
+
+    """
+    \(buf)
+    public static unsafe partial class Roc
+    {
+        /// <summary>
+        /// The file name of the Roc library, without its extension. To load it from elsewhere, use
+        /// <see cref="NativeLibrary.SetDllImportResolver"/>.
+        /// </summary>
+        public const string LibraryName = "app";
+    \(entryPoints)}
+
+    """
+
+## Str and List arguments are passed to Roc by pointer, and everything else by value. Roc writes
+## what it returns through a pointer, and takes ownership of the arguments.
+generateEntryPoint : Str, Types, Str, TypeId -> Str
+generateEntryPoint = \buf, types, name, id ->
+    { args, ret } =
+        when Types.shape types id is
+            Function rocFn -> { args: rocFn.args, ret: rocFn.ret }
+            _ -> { args: [], ret: id }
+
+    retType = typeName types ret
+    externName = "roc__\(name)_1_exposed_generic"
+    methodName = pascalCase name
+
+    isPointer = \argId ->
+        when Types.shape types argId is
+            RocStr | RocList _ | Unsized -> Bool.true
+            _ -> Bool.false
+
+    marshal = \argId ->
+        when Types.shape types argId is
+            Bool -> "[MarshalAs(UnmanagedType.U1)] "
+            _ -> ""
+
+    externParams =
+        args
+        |> List.mapWithIndex \argId, index ->
+            attribute = marshal argId
+            type = typeName types argId
+            pointer = if isPointer argId then "*" else ""
+            position = Num.toStr index
+
+            "\(attribute)\(type)\(pointer) arg\(position)"
+        |> List.prepend "\(retType)* ret"
+        |> Str.joinWith ", "
+
+    publicParams =
+        args
+        |> List.mapWithIndex \argId, index ->
+            type = typeName types argId
+            position = Num.toStr index
+
+            "\(type) arg\(position)"
+        |> Str.joinWith ", "
+
+    callArguments =
+        args
+        |> List.mapWithIndex \argId, index ->
+            address = if isPointer argId then "&" else ""
+            position = Num.toStr index
+
+            "\(address)arg\(position)"
+        |> List.prepend "&ret"
+        |> Str.joinWith ", "
+
+    """
+    \(buf)
+        [DllImport(LibraryName)]
+        private static extern void \(externName)(\(externParams));
+
+        public static \(retType) \(methodName)(\(publicParams))
+        {
+            \(retType) ret;
+            \(externName)(\(callArguments));
+
+            return ret;
+        }
+
+    """
+
+typeName = \types, id ->
+    when Types.shape types id is
+        Unit -> "RocUnit"
+        Unsized -> "RocList"
+        EmptyTagUnion -> "RocUnit"
+        RocStr -> "RocStr"
+        Bool -> "bool"
+        Num U8 -> "byte"
+        Num U16 -> "ushort"
+        Num U32 -> "uint"
+        Num U64 -> "ulong"
+        Num U128 -> "RocU128"
+        Num I8 -> "sbyte"
+        Num I16 -> "short"
+        Num I32 -> "int"
+        Num I64 -> "long"
+        Num I128 -> "RocI128"
+        Num F32 -> "float"
+        Num F64 -> "double"
+        Num Dec -> "RocDec"
+        RocList _ -> "RocList"
+        RocBox _ -> "RocBox"
+        RocDict key value ->
+            keyName = typeName types key
+            valueName = typeName types value
+
+            "RocDict_\(keyName)_\(valueName)"
+
+        RocSet elem ->
+            elemName = typeName types elem
+
+            "RocSet_\(elemName)"
+
+        RocResult ok err ->
+            okName = typeName types ok
+            errName = typeName types err
+
+            "RocResult_\(okName)_\(errName)"
+
+        RecursivePointer content -> typeName types content
+        Struct { name } -> escapeKW name
+        TagUnionPayload { name } -> escapeKW name
+        TagUnion (NonRecursive { name }) -> escapeKW name
+        TagUnion (Recursive { name }) -> escapeKW name
+        TagUnion (Enumeration { name }) -> escapeKW name
+        TagUnion (NullableWrapped { name }) -> escapeKW name
+        TagUnion (NullableUnwrapped { name }) -> escapeKW name
+        TagUnion (NonNullableUnwrapped { name }) -> escapeKW name
+        TagUnion (SingleTagStruct { name }) -> escapeKW name
+        Function { functionName } -> escapeKW functionName
+
+discriminantType = \size ->
+    when size is
+        1 -> "byte"
+        2 -> "ushort"
+        _ -> "uint"
+
+roundUpToAlignment = \width, alignment ->
+    if alignment <= 1 || width % alignment == 0 then
+        width
+    else
+        width + alignment - (width % alignment)
+
+pascalCase = \name ->
+    bytes = Str.toUtf8 name
+
+    when List.first bytes is
+        Ok first if first >= 'a' && first <= 'z' ->
+            List.set bytes 0 (first - 32) |> Str.fromUtf8 |> Result.withDefault name
+
+        _ -> name
+
+camelCase = \name ->
+    bytes = Str.toUtf8 name
+
+    lowered =
+        when List.first bytes is
+            Ok first if first >= 'A' && first <= 'Z' ->
+                List.set bytes 0 (first + 32) |> Str.fromUtf8 |> Result.withDefault name
+
+            _ -> name
+
+    escapeKW lowered
+
+atLeast = \minimum, value ->
+    if value < minimum then minimum else value
+
+indent = "    "
+
+reservedKeywords = Set.fromList [
+    "abstract",
+    "as",
+    "base",
+    "bool",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "checked",
+    "class",
+    "const",
+    "continue",
+    "decimal",
+    "default",
+    "delegate",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "event",
+    "explicit",
+    "extern",
+    "false",
+    "finally",
+    "fixed",
+    "float",
+    "for",
+    "foreach",
+    "goto",
+    "if",
+    "implicit",
+    "in",
+    "int",
+    "interface",
+    "internal",
+    "is",
+    "lock",
+    "long",
+    "namespace",
+    "new",
+    "null",
+    "object",
+    "operator",
+    "out",
+    "override",
+    "params",
+    "private",
+    "protected",
+    "public",
+    "readonly",
+    "ref",
+    "return",
+    "sbyte",
+    "sealed",
+    "short",
+    "sizeof",
+    "stackalloc",
+    "static",
+    "string",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "uint",
+    "ulong",
+    "unchecked",
+    "unsafe",
+    "ushort",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+]
+
+escapeKW = \input ->
+    # a verbatim identifier can be a keyword: https://learn.microsoft.com/en-us/dotnet/csharp/language-reference/tokens/verbatim
+    if Set.contains reservedKeywords input then
+        "@\(input)"
+    else
+        input
+
+fileHeader =
+    """
+    // ⚠️ GENERATED CODE ⚠️ - this entire file was generated by the `roc glue` CLI command
+    //
+    // Bindings for calling a Roc app built as a shared library (`roc build --lib`) from .NET 6 or
+    // later. Compile them with <AllowUnsafeBlocks>true</AllowUnsafeBlocks>. The library's host has
+    // to export roc_alloc and roc_dealloc, which these use for the strings and lists they make.
+
+    #nullable enable
+    #pragma warning disable CS0649 // fields which only Roc writes to
+
+    using System;
+    using System.Runtime.InteropServices;
+    using System.Text;
+
+    namespace RocGlue;
+
+    internal static unsafe class RocMemory
+    {
+        private static readonly nint Refcount1 = nint.MinValue;
+
+        [DllImport(Roc.LibraryName)]
+        private static extern void* roc_alloc(nuint size, uint alignment);
+
+        [DllImport(Roc.LibraryName)]
+        private static extern void roc_dealloc(void* ptr, uint alignment);
+
+        /// <summary>Allocates room for some bytes after a refcount of 1, and returns where they start.</summary>
+        internal static byte* Allocate(nuint size, uint alignment)
+        {
+            alignment = Math.Max(alignment, (uint)sizeof(nint));
+            byte* start = (byte*)roc_alloc(size + alignment, alignment) + alignment;
+            ((nint*)start)[-1] = Refcount1;
+
+            return start;
+        }
+
+        /// <summary>Decrements the refcount just before <paramref name="refcounted"/>, and frees its allocation when that was the last reference.</summary>
+        internal static void Decref(byte* refcounted, uint alignment)
+        {
+            alignment = Math.Max(alignment, (uint)sizeof(nint));
+            nint* refcount = (nint*)refcounted - 1;
+
+            if (*refcount == Refcount1)
+            {
+                roc_dealloc(refcounted - alignment, alignment);
+            }
+            else if (*refcount != 0)
+            {
+                // a refcount of 0 means the value is never freed, like a constant in the app
+                *refcount -= 1;
+            }
+        }
+    }
+
+    /// <summary>A value with no information in it, like Roc's <c>{}</c>.</summary>
+    [StructLayout(LayoutKind.Sequential)]
+    public struct RocUnit
+    {
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    public struct RocI128
+    {
+        public ulong Low;
+        public long High;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    public struct RocU128
+    {
+        public ulong Low;
+        public ulong High;
+    }
+
+    /// <summary>A Roc <c>Dec</c>: a 128-bit integer which counts in units of 10^-18.</summary>
+    [StructLayout(LayoutKind.Sequential)]
+    public struct RocDec
+    {
+        public ulong Low;
+        public long High;
+    }
+
+    /// <summary>A Roc <c>Box</c>: a pointer to a value on Roc's heap.</summary>
+    [StructLayout(LayoutKind.Sequential)]
+    public unsafe struct RocBox
+    {
+        public void* Pointer;
+    }
+
+    /// <summary>
+    /// A Roc <c>Str</c>. Strings of fewer than 24 bytes are stored inline, and longer ones on Roc's
+    /// heap, so call <see cref="Drop"/> on the strings Roc returns once they aren't needed anymore.
+    /// </summary>
+    [StructLayout(LayoutKind.Sequential)]
+    public unsafe struct RocStr
+    {
+        private byte* bytes;
+        private nuint length;
+        private nuint capacityOrRefPtr;
+
+        private bool IsSmall => (nint)capacityOrRefPtr < 0;
+
+        private bool IsSeamlessSlice => (nint)length < 0;
+
+        public int Length => IsSmall ? (int)((capacityOrRefPtr >> 56) & 0x7F) : (int)(length & (nuint)nint.MaxValue);
+
+        public static RocStr FromString(string value)
+        {
+            byte[] utf8 = Encoding.UTF8.GetBytes(value);
+            RocStr str = default;
+
+            if (utf8.Length < sizeof(RocStr))
+            {
+                byte* inline = (byte*)&str;
+                Marshal.Copy(utf8, 0, (IntPtr)inline, utf8.Length);
+                inline[sizeof(RocStr) - 1] = (byte)(utf8.Length | 0x80);
+            }
+            else
+            {
+                str.bytes = RocMemory.Allocate((nuint)utf8.Length, 1);
+                Marshal.Copy(utf8, 0, (IntPtr)str.bytes, utf8.Length);
+                str.length = (nuint)utf8.Length;
+                str.capacityOrRefPtr = (nuint)utf8.Length;
+            }
+
+            return str;
+        }
+
+        public override string ToString()
+        {
+            RocStr copy = this;
+            byte* start = IsSmall ? (byte*)&copy : bytes;
+
+            return Encoding.UTF8.GetString(start, Length);
+        }
+
+        /// <summary>Releases this string's reference to its bytes on Roc's heap, if it has any.</summary>
+        public void Drop()
+        {
+            if (IsSmall || bytes == null)
+            {
+                return;
+            }
+
+            byte* refcounted = IsSeamlessSlice ? (byte*)(capacityOrRefPtr << 1) : bytes;
+            RocMemory.Decref(refcounted, 1);
+        }
+    }
+
+    /// <summary>
+    /// A Roc <c>List</c>. Its elements are on Roc's heap, so call <see cref="Drop"/> on the lists Roc
+    /// returns once they aren't needed anymore. That doesn't drop the elements themselves.
+    /// </summary>
+    [StructLayout(LayoutKind.Sequential)]
+    public unsafe struct RocList
+    {
+        private byte* elements;
+        private nuint length;
+        private nuint capacityOrRefPtr;
+
+        private bool IsSeamlessSlice => (nint)capacityOrRefPtr < 0;
+
+        public int Length => (int)length;
+
+        /// <summary>
+        /// A list of some elements. <paramref name="alignment"/> is the alignment of the elements in
+        /// Roc, which is 16 for 128-bit numbers and records which contain them.
+        /// </summary>
+        public static RocList FromArray<T>(T[] items, uint alignment = 8) where T : unmanaged
+        {
+            RocList list = default;
+
+            if (items.Length > 0)
+            {
+                nuint size = (nuint)(items.Length * sizeof(T));
+                list.elements = RocMemory.Allocate(size, alignment);
+                list.length = (nuint)items.Length;
+                list.capacityOrRefPtr = (nuint)items.Length;
+
+                fixed (T* source = items)
+                {
+                    Buffer.MemoryCopy(source, list.elements, size, size);
+                }
+            }
+
+            return list;
+        }
+
+        /// <summary>Copies the elements; elements which are on Roc's heap themselves are shared with the list.</summary>
+        public T[] ToArray<T>() where T : unmanaged
+        {
+            T[] items = new T[Length];
+
+            if (items.Length > 0)
+            {
+                fixed (T* target = items)
+                {
+                    nuint size = (nuint)(items.Length * sizeof(T));
+                    Buffer.MemoryCopy(elements, target, size, size);
+                }
+            }
+
+            return items;
+        }
+
+        /// <summary>Releases this list's reference to its elements, which were laid out with the given alignment.</summary>
+        public void Drop(uint alignment = 8)
+        {
+            if (elements == null)
+            {
+                return;
+            }
+
+            byte* refcounted = IsSeamlessSlice ? (byte*)(capacityOrRefPtr << 1) : elements;
+            RocMemory.Decref(refcounted, alignment);
+        }
+    }
+
+    """
@@ -0,0 +1,1152 @@
+app "python-glue"
+    packages { pf: "../platform/main.roc" }
+    imports [pf.Types.{ Types }, pf.Shape.{ Shape }, pf.File.{ File }, pf.TypeId.{ TypeId }]
+    provides [makeGlue] to pf
+
+## Generates a Python package which calls a Roc app built as a shared library (`roc build --lib`)
+## through cffi, converting between Roc values and Python ones.
+makeGlue : List Types -> Result (List File) Str
+makeGlue = \typesByArch ->
+    # the generated C declarations spell out padding, so they need the layout of one target; all
+    # 64-bit targets lay out Roc values the same way
+    is64Bit = \types ->
+        when (Types.target types).architecture is
+            X86x64 | Aarch64 -> Bool.true
+            _ -> Bool.false
+
+    when List.findFirst typesByArch is64Bit is
+        Ok types ->
+            Ok [
+                { name: "roc_app/__init__.py", content: generateModule types },
+                { name: "pyproject.toml", content: pyproject },
+            ]
+
+        Err NotFound -> Err "Python glue needs the types of a 64-bit target, like x86_64 or aarch64, and there weren't any."
+
+## The C declarations for cffi and the Python classes are generated side by side.
+Declarations : { cdef : Str, py : Str }
+
+generateModule : Types -> Str
+generateModule = \types ->
+    walked =
+        Types.walkShapes types { cdef: "", py: "", declared: Set.empty {} } \state, shape, id ->
+            name = typeName types id
+
+            # identical shapes, like two uses of the same Result, can have different ids
+            if Set.contains state.declared name then
+                state
+            else
+                declarations = generateDeclaration { cdef: state.cdef, py: state.py } types shape id
+
+                { cdef: declarations.cdef, py: declarations.py, declared: Set.insert state.declared name }
+
+    entryPoints =
+        List.walk (Types.entryPoints types) { cdef: "", py: "" } \declarations, T name id ->
+            generateEntryPoint declarations types name id
+
+    """
+    \(moduleHeader)
+    ffi.cdef(
+        '''
+    \(cdefHeader)
+    \(walked.cdef)
+    \(entryPoints.cdef)'''
+    )
+    \(walked.py)\(entryPoints.py)
+    """
+
+generateDeclaration : Declarations, Types, Shape, TypeId -> Declarations
+generateDeclaration = \declarations, types, shape, id ->
+    when shape is
+        Struct { name, fields } ->
+            generateRecord declarations types id name (structFields fields)
+
+        TagUnionPayload { name, fields } ->
+            # tag union classes have the fields of their tags' payloads themselves
+            { declarations & cdef: generateCStruct declarations.cdef types id name (payloadFields fields) }
+
+        TagUnion (Enumeration { name, tags, size }) ->
+            generateEnumeration declarations name tags size
+
+        TagUnion (NonRecursive { name, tags, discriminantSize, discriminantOffset }) ->
+            if List.isEmpty tags then
+                declarations
+            else
+                generateTagUnion declarations types id name tags discriminantSize discriminantOffset
+
+        TagUnion (SingleTagStruct { name, payload }) ->
+            generateRecord declarations types id name (singleTagFields payload)
+
+        TagUnion (Recursive { name }) | TagUnion (NullableWrapped { name }) | TagUnion (NullableUnwrapped { name }) | TagUnion (NonNullableUnwrapped { name }) ->
+            # these live on Roc's heap, so Python code gets a pointer it can only pass back to Roc
+            { declarations & cdef: "\(declarations.cdef)typedef void *\(name);\n" }
+
+        RocResult okId errId ->
+            { declarations & cdef: generateCTagUnion declarations.cdef types id 1 (resultDiscriminantOffset types okId errId) }
+
+        RocDict _ _ | RocSet _ ->
+            { declarations & cdef: generateCOpaque declarations.cdef types id }
+
+        Function rocFn ->
+            if rocFn.isToplevel then
+                declarations
+            else
+                { declarations & cdef: generateCOpaque declarations.cdef types id }
+
+        RecursivePointer _
+        | Unit
+        | Unsized
+        | EmptyTagUnion
+        | Num _
+        | Bool
+        | RocStr
+        | RocList _
+        | RocBox _ ->
+            # These are declared in the module header.
+            declarations
+
+structFields = \fields ->
+    when fields is
+        HasNoClosure list -> List.map list \field -> { name: escapeName field.name, id: field.id }
+        HasClosure list -> List.map list \field -> { name: escapeName field.name, id: field.id }
+
+payloadFields = \fields ->
+    # Tag union payloads have numbered fields, so we prefix them with an "f" because neither C nor
+    # Python allows field names to be numbers.
+    when fields is
+        HasNoClosure list -> List.map list \field -> { name: "f\(field.name)", id: field.id }
+        HasClosure list -> List.map list \field -> { name: "f\(field.name)", id: field.id }
+
+singleTagFields = \payload ->
+    when payload is
+        HasNoClosure list -> List.mapWithIndex list \field, index -> { name: Str.concat "f" (Num.toStr index), id: field.id }
+        HasClosure list -> List.map list \field -> { name: escapeName field.name, id: field.id }
+
+## The fields of a struct along with their offsets. Roc gives them in the order they're laid out.
+withOffsets : Types, List { name : Str, id : TypeId } -> List { name : Str, id : TypeId, offset : U32 }
+withOffsets = \types, fields ->
+    placed =
+        List.walk fields { end: 0, placed: List.withCapacity (List.len fields) } \state, field ->
+            offset = roundUpToAlignment state.end (Types.alignment types field.id)
+
+            { end: offset + Types.size types field.id, placed: List.append state.placed { name: field.name, id: field.id, offset } }
+
+    placed.placed
+
+## cffi lays structs out by C's rules, and some Roc types (like U128) are more aligned than what
+## they're declared as, so every gap is spelled out as padding.
+generateCStruct = \cdef, types, id, name, fields ->
+    size = Types.size types id
+
+    if size == 0 then
+        "\(cdef)typedef RocUnit \(name);\n"
+    else
+        placed =
+            List.walk (withOffsets types fields) { end: 0, lines: "" } \state, field ->
+                fieldSize = Types.size types field.id
+
+                if fieldSize == 0 then
+                    state
+                else
+                    padding = generatePadding state.end field.offset
+                    type = typeName types field.id
+
+                    { end: field.offset + fieldSize, lines: "\(state.lines)\(padding)\(indent)\(type) \(field.name);\n" }
+
+        trailingPadding = generatePadding placed.end size
+
+        """
+        \(cdef)typedef struct {
+        \(placed.lines)\(trailingPadding)} \(name);
+
+        """
+
+## A tag union (or Result) as C sees it: its payloads are only ever read through `_payload`, so
+## they're just bytes.
+generateCTagUnion = \cdef, types, id, discriminantSize, discriminantOffset ->
+    name = typeName types id
+    size = Types.size types id
+    tagType = discriminantType discriminantSize
+
+    payload =
+        if discriminantOffset > 0 then
+            payloadSize = Num.toStr discriminantOffset
+
+            "\(indent)uint8_t payload[\(payloadSize)];\n"
+        else
+            ""
+
+    padding = generatePadding (discriminantOffset + discriminantSize) size
+
+    """
+    \(cdef)typedef struct {
+    \(payload)\(indent)\(tagType) discriminant;
+    \(padding)} \(name);
+
+    """
+
+generateCOpaque = \cdef, types, id ->
+    # C doesn't allow arrays of size 0
+    size = Num.toStr (atLeast 1 (Types.size types id))
+    name = typeName types id
+
+    "\(cdef)typedef struct { uint8_t bytes[\(size)]; } \(name);\n"
+
+generatePadding = \start, end ->
+    if end > start then
+        offset = Num.toStr start
+        length = Num.toStr (end - start)
+
+        "\(indent)uint8_t _padding\(offset)[\(length)];\n"
+    else
+        ""
+
+## Records and single-tag unions become dataclasses with the same fields.
+generateRecord = \declarations, types, id, name, fields ->
+    classFields =
+        fields
+        |> List.map \field ->
+            hint = pyType types field.id
+
+            "\(indent)\(field.name): \(hint)\n"
+        |> Str.joinWith ""
+
+    fromArguments =
+        fields
+        |> List.map \field ->
+            converted = fromRoc types field.id (fieldAccess types field "c.")
+
+            "\(field.name)=\(converted)"
+        |> Str.joinWith ", "
+
+    toEntries =
+        fields
+        |> List.keepIf \field -> Types.size types field.id > 0
+        |> List.map \field -> toRocEntry types field "value."
+        |> Str.joinWith ", "
+
+    dropLines =
+        fields
+        |> List.keepIf \field -> Types.size types field.id > 0
+        |> List.map \field -> dropRoc types field.id (Str.concat "c." field.name)
+        |> generateStatements
+
+    py =
+        """
+        \(declarations.py)
+
+        @dataclass
+        class \(name):
+        \(classFields)
+            @staticmethod
+            def _from_roc(c: Any) -> \(name):
+                return \(name)(\(fromArguments))
+
+            @staticmethod
+            def _to_roc(value: \(name)) -> Any:
+                return {\(toEntries)}
+
+            @staticmethod
+            def _drop_roc(c: Any) -> None:
+        \(dropLines)
+        """
+
+    { cdef: generateCStruct declarations.cdef types id name fields, py }
+
+## Zero-sized fields aren't declared in C, and don't need reading
+fieldAccess = \types, field, prefix ->
+    if Types.size types field.id == 0 then
+        "None"
+    else
+        Str.concat prefix field.name
+
+toRocEntry = \types, field, prefix ->
+    converted = toRoc types field.id (Str.concat prefix field.name)
+
+    "'\(field.name)': \(converted)"
+
+generateEnumeration = \declarations, name, tags, size ->
+    underlyingType = discriminantType size
+    members =
+        tags
+        |> List.mapWithIndex \tag, index ->
+            member = escapeName tag
+            value = Num.toStr index
+
+            "\(indent)\(member) = \(value)\n"
+        |> Str.joinWith ""
+
+    py =
+        """
+        \(declarations.py)
+
+        class \(name)(IntEnum):
+        \(members)
+        """
+
+    { cdef: "\(declarations.cdef)typedef \(underlyingType) \(name);\n", py }
+
+## A non-recursive tag union: each of its tags becomes a dataclass nested in its class, which has
+## the fields of the tag's payload.
+generateTagUnion = \declarations, types, id, name, tags, discriminantSize, discriminantOffset ->
+    tagClasses =
+        tags
+        |> List.map \tag ->
+            fields = tagFields types tag.payload
+            className = escapeName tag.name
+
+            classFields =
+                if List.isEmpty fields then
+                    "\(indent)\(indent)pass\n"
+                else
+                    fields
+                    |> List.map \field ->
+                        hint = pyType types field.id
+
+                        "\(indent)\(indent)\(field.name): \(hint)\n"
+                    |> Str.joinWith ""
+
+            """
+
+                @dataclass
+                class \(className):
+            \(classFields)
+            """
+        |> Str.joinWith ""
+
+    fromArms =
+        tags
+        |> List.mapWithIndex \tag, index ->
+            tagClass = tagClassName name tag.name
+            discriminant = Num.toStr index
+            arguments =
+                tagFields types tag.payload
+                |> List.map \field ->
+                    converted = fromRoc types field.id (payloadAccess types tag.payload field)
+
+                    "\(field.name)=\(converted)"
+                |> Str.joinWith ", "
+
+            """
+                    if discriminant == \(discriminant):
+                        return \(tagClass)(\(arguments))
+
+            """
+        |> Str.joinWith ""
+
+    toArms =
+        tags
+        |> List.mapWithIndex \tag, index ->
+            tagClass = tagClassName name tag.name
+            discriminant = Num.toStr index
+            payload =
+                when tag.payload is
+                    Some payloadId if Types.size types payloadId > 0 ->
+                        payloadType = typeName types payloadId
+                        initializer = payloadInitializer types payloadId
+
+                        "'payload': _payload_bytes('\(payloadType)', \(initializer)), "
+
+                    _ -> ""
+
+            """
+                    if isinstance(value, \(tagClass)):
+                        return {\(payload)'discriminant': \(discriminant)}
+
+            """
+        |> Str.joinWith ""
+
+    dropArms =
+        tags
+        |> List.mapWithIndex \tag, index ->
+            drops =
+                tagFields types tag.payload
+                |> List.keepIf \field -> Types.size types field.id > 0
+                |> List.map \field -> dropRoc types field.id (payloadAccess types tag.payload field)
+                |> List.keepIf \drop -> !(Str.isEmpty drop)
+
+            if List.isEmpty drops then
+                ""
+            else
+                discriminant = Num.toStr index
+                statements =
+                    drops
+                    |> List.map \drop -> "\(indent)\(indent)\(indent)\(drop)\n"
+                    |> Str.joinWith ""
+
+                "\(indent)\(indent)if c.discriminant == \(discriminant):\n\(statements)"
+        |> Str.joinWith ""
+
+    dropBody =
+        if Str.isEmpty dropArms then
+            "\(indent)\(indent)pass\n"
+        else
+            dropArms
+
+    unionHint = pyType types id
+
+    py =
+        """
+        \(declarations.py)
+
+        class \(name):
+            # A Roc tag union, whose values are instances of the dataclasses of its tags below
+        \(tagClasses)
+            @staticmethod
+            def _from_roc(c: Any) -> \(unionHint):
+                discriminant = c.discriminant
+
+        \(fromArms)        raise ValueError(f"\(name) has an invalid discriminant: {discriminant}")
+
+            @staticmethod
+            def _to_roc(value: \(unionHint)) -> Any:
+        \(toArms)        raise TypeError(f"{value!r} is not a \(name)")
+
+            @staticmethod
+            def _drop_roc(c: Any) -> None:
+        \(dropBody)
+        """
+
+    { cdef: generateCTagUnion declarations.cdef types id discriminantSize discriminantOffset, py }
+
+## A tag's payload is either a struct of numbered fields, which the tag's class gets as its own, or
+## some other type, which it gets as a `value` field.
+tagFields = \types, payload ->
+    when payload is
+        None -> []
+        Some payloadId ->
+            when Types.shape types payloadId is
+                TagUnionPayload { fields } -> payloadFields fields
+                _ -> [{ name: "value", id: payloadId }]
+
+payloadAccess = \types, payload, field ->
+    when payload is
+        Some payloadId if Types.size types field.id > 0 ->
+            payloadType = typeName types payloadId
+            access = "_payload(c, '\(payloadType)')"
+
+            when Types.shape types payloadId is
+                TagUnionPayload _ -> "\(access).\(field.name)"
+                _ -> access
+
+        _ -> "None"
+
+payloadInitializer = \types, payloadId ->
+    when Types.shape types payloadId is
+        TagUnionPayload { fields } ->
+            entries =
+                payloadFields fields
+                |> List.keepIf \field -> Types.size types field.id > 0
+                |> List.map \field -> toRocEntry types field "value."
+                |> Str.joinWith ", "
+
+            "{\(entries)}"
+
+        _ -> toRoc types payloadId "value.value"
+
+## Str and List arguments are passed to Roc by pointer, and everything else by value. Roc writes
+## what it returns through a pointer, and takes ownership of the arguments.
+generateEntryPoint : Declarations, Types, Str, TypeId -> Declarations
+generateEntryPoint = \declarations, types, name, id ->
+    { args, ret } =
+        when Types.shape types id is
+            Function rocFn -> { args: rocFn.args, ret: rocFn.ret }
+            _ -> { args: [], ret: id }
+
+    retType = typeName types ret
+    retHint = pyType types ret
+    externName = "roc__\(name)_1_exposed_generic"
+    functionName = snakeCase name
+
+    isPointer = \argId ->
+        when Types.shape types argId is
+            RocStr | RocList _ | Unsized -> Bool.true
+            _ -> Bool.false
+
+    externParams =
+        args
+        |> List.mapWithIndex \argId, index ->
+            type = typeName types argId
+            pointer = if isPointer argId then " *" else " "
+            position = Num.toStr index
+
+            "\(type)\(pointer)arg\(position)"
+        |> List.prepend "\(retType) *ret"
+        |> Str.joinWith ", "
+
+    params =
+        args
+        |> List.mapWithIndex \argId, index ->
+            hint = pyType types argId
+            position = Num.toStr index
+
+            "arg\(position): \(hint)"
+        |> Str.joinWith ", "
+
+    argLines =
+        args
+        |> List.mapWithIndex \argId, index ->
+            arg = Str.concat "arg" (Num.toStr index)
+            type = typeName types argId
+            converted = toRoc types argId arg
+
+            "\(indent)\(arg)_roc = ffi.new('\(type) *', \(converted))\n"
+        |> Str.joinWith ""
+
+    callArguments =
+        args
+        |> List.mapWithIndex \argId, index ->
+            value = if isPointer argId then "" else "[0]"
+            position = Num.toStr index
+
+            "arg\(position)_roc\(value)"
+        |> List.prepend "ret"
+        |> Str.joinWith ", "
+
+    answer = fromRoc types ret "ret[0]"
+    drop =
+        when dropRoc types ret "ret[0]" is
+            "" -> ""
+            statement -> "\(indent)\(statement)\n"
+
+    py =
+        """
+        \(declarations.py)
+
+        def \(functionName)(\(params)) -> \(retHint):
+            ret = ffi.new('\(retType) *')
+        \(argLines)    _library().\(externName)(\(callArguments))
+
+            answer = \(answer)
+        \(drop)    return answer
+
+        """
+
+    { cdef: "\(declarations.cdef)void \(externName)(\(externParams));\n", py }
+
+## A Python expression which converts the Roc value in the cdata `c` to a Python value
+fromRoc : Types, TypeId, Str -> Str
+fromRoc = \types, id, c ->
+    when Types.shape types id is
+        Unit | EmptyTagUnion -> "None"
+        Bool -> c
+        Num U128 -> "_u128_from_roc(\(c))"
+        Num I128 -> "_i128_from_roc(\(c))"
+        Num Dec -> "_dec_from_roc(\(c))"
+        Num _ -> c
+        RocStr -> "_str_from_roc(\(c))"
+        RocList elem ->
+            elemType = typeName types elem
+            convert = fromRoc types elem "c"
+
+            "_list_from_roc(\(c), '\(elemType)', lambda c: \(convert))"
+
+        Unsized -> "_opaque_list_from_roc(\(c))"
+        RocBox _ -> c
+        RocResult okId errId ->
+            ok = fromRoc types okId (resultPayload types okId)
+            err = fromRoc types errId (resultPayload types errId)
+
+            "_result_from_roc(\(c), lambda c: \(ok), lambda c: \(err))"
+
+        RocDict _ _ | RocSet _ | Function _ -> "bytes(list(\(c).bytes))"
+        RecursivePointer _ -> c
+        TagUnion (Recursive _) | TagUnion (NullableWrapped _) | TagUnion (NullableUnwrapped _) | TagUnion (NonNullableUnwrapped _) -> c
+        TagUnion (Enumeration { name }) -> "\(name)(\(c))"
+        Struct _ | TagUnionPayload _ | TagUnion (NonRecursive _) | TagUnion (SingleTagStruct _) ->
+            Str.concat (typeName types id) "._from_roc(\(c))"
+
+## A Python expression which converts the Python value `value` to something cffi can initialize
+## the Roc value with
+toRoc : Types, TypeId, Str -> Str
+toRoc = \types, id, value ->
+    when Types.shape types id is
+        Unit | EmptyTagUnion -> "0"
+        Bool -> value
+        Num U128 -> "_u128_to_roc(\(value))"
+        Num I128 -> "_i128_to_roc(\(value))"
+        Num Dec -> "_dec_to_roc(\(value))"
+        Num _ -> value
+        RocStr -> "_str_to_roc(\(value))"
+        RocList elem ->
+            elemType = typeName types elem
+            alignment = Num.toStr (Types.alignment types elem)
+            convert = toRoc types elem "value"
+
+            "_list_to_roc(\(value), '\(elemType)', \(alignment), lambda value: \(convert))"
+
+        Unsized -> value
+        RocBox _ -> value
+        RocResult okId errId ->
+            okType = resultPayloadType types okId
+            ok = toRoc types okId "value"
+            errType = resultPayloadType types errId
+            err = toRoc types errId "value"
+
+            "_result_to_roc(\(value), \(okType), lambda value: \(ok), \(errType), lambda value: \(err))"
+
+        RocDict _ _ | RocSet _ | Function _ -> "{'bytes': list(\(value))}"
+        RecursivePointer _ -> value
+        TagUnion (Recursive _) | TagUnion (NullableWrapped _) | TagUnion (NullableUnwrapped _) | TagUnion (NonNullableUnwrapped _) -> value
+        TagUnion (Enumeration _) -> "int(\(value))"
+        Struct _ | TagUnionPayload _ | TagUnion (NonRecursive _) | TagUnion (SingleTagStruct _) ->
+            Str.concat (typeName types id) "._to_roc(\(value))"
+
+## A Python statement which releases what the Roc value in the cdata `c` holds on Roc's heap, or ""
+## when it doesn't hold anything there. Values Python code can only pass back to Roc, like boxes,
+## are left alone, since they're passed back as they are.
+dropRoc : Types, TypeId, Str -> Str
+dropRoc = \types, id, c ->
+    when Types.shape types id is
+        RocStr -> "_str_drop(\(c))"
+        RocList elem ->
+            elemType = typeName types elem
+            alignment = Num.toStr (Types.alignment types elem)
+            dropElement =
+                when dropRoc types elem "c" is
+                    "" -> "None"
+                    drop -> "lambda c: \(drop)"
+
+            "_list_drop(\(c), \(alignment), '\(elemType)', \(dropElement))"
+
+        RocResult okId errId ->
+            dropOk = dropRoc types okId (resultPayload types okId)
+            dropErr = dropRoc types errId (resultPayload types errId)
+
+            if Str.isEmpty dropOk && Str.isEmpty dropErr then
+                ""
+            else
+                okLambda = optionalLambda dropOk
+                errLambda = optionalLambda dropErr
+
+                "_result_drop(\(c), \(okLambda), \(errLambda))"
+
+        Struct _ | TagUnion (NonRecursive _) | TagUnion (SingleTagStruct _) ->
+            Str.concat (typeName types id) "._drop_roc(\(c))"
+
+        _ -> ""
+
+optionalLambda = \body ->
+    if Str.isEmpty body then
+        "None"
+    else
+        "lambda c: \(body)"
+
+resultPayload = \types, id ->
+    if Types.size types id == 0 then
+        "None"
+    else
+        payloadType = typeName types id
+
+        "_payload(c, '\(payloadType)')"
+
+resultPayloadType = \types, id ->
+    if Types.size types id == 0 then
+        "None"
+    else
+        payloadType = typeName types id
+
+        "'\(payloadType)'"
+
+## Like any other non-recursive tag union, with Err as tag 0 and Ok as tag 1
+resultDiscriminantOffset = \types, okId, errId ->
+    payloadSize = atLeast (Types.size types okId) (Types.size types errId)
+    payloadAlignment = atLeast (Types.alignment types okId) (Types.alignment types errId)
+
+    roundUpToAlignment payloadSize payloadAlignment
+
+generateStatements = \statements ->
+    nonEmpty = List.keepIf statements \statement -> !(Str.isEmpty statement)
+
+    if List.isEmpty nonEmpty then
+        "\(indent)\(indent)pass\n"
+    else
+        nonEmpty
+        |> List.map \statement -> "\(indent)\(indent)\(statement)\n"
+        |> Str.joinWith ""
+
+## The name of a type in the C declarations, which is also the name of its Python class if it has one
+typeName = \types, id ->
+    when Types.shape types id is
+        Unit -> "RocUnit"
+        Unsized -> "RocList"
+        EmptyTagUnion -> "RocUnit"
+        RocStr -> "RocStr"
+        Bool -> "_Bool"
+        Num U8 -> "uint8_t"
+        Num U16 -> "uint16_t"
+        Num U32 -> "uint32_t"
+        Num U64 -> "uint64_t"
+        Num U128 -> "RocU128"
+        Num I8 -> "int8_t"
+        Num I16 -> "int16_t"
+        Num I32 -> "int32_t"
+        Num I64 -> "int64_t"
+        Num I128 -> "RocI128"
+        Num F32 -> "float"
+        Num F64 -> "double"
+        Num Dec -> "RocDec"
+        RocList _ -> "RocList"
+        RocBox _ -> "RocBox"
+        RocDict key value ->
+            keyName = typeName types key
+            valueName = typeName types value
+
+            "RocDict_\(keyName)_\(valueName)"
+
+        RocSet elem ->
+            elemName = typeName types elem
+
+            "RocSet_\(elemName)"
+
+        RocResult ok err ->
+            okName = typeName types ok
+            errName = typeName types err
+
+            "RocResult_\(okName)_\(errName)"
+
+        RecursivePointer content -> typeName types content
+        Struct { name } -> name
+        TagUnionPayload { name } -> name
+        TagUnion (NonRecursive { name }) -> name
+        TagUnion (Recursive { name }) -> name
+        TagUnion (Enumeration { name }) -> name
+        TagUnion (NullableWrapped { name }) -> name
+        TagUnion (NullableUnwrapped { name }) -> name
+        TagUnion (NonNullableUnwrapped { name }) -> name
+        TagUnion (SingleTagStruct { name }) -> name
+        Function { functionName } -> functionName
+
+## The type annotation for the Python values of a type
+pyType = \types, id ->
+    when Types.shape types id is
+        Unit | EmptyTagUnion -> "None"
+        Unsized -> "dict"
+        RocStr -> "str"
+        Bool -> "bool"
+        Num F32 | Num F64 -> "float"
+        Num Dec -> "Decimal"
+        Num _ -> "int"
+        RocList elem ->
+            elemHint = pyType types elem
+
+            "list[\(elemHint)]"
+
+        RocBox _ -> "Any"
+        RocDict _ _ | RocSet _ | Function _ -> "bytes"
+        RocResult ok err ->
+            okHint = pyType types ok
+            errHint = pyType types err
+
+            "Ok[\(okHint)] | Err[\(errHint)]"
+
+        RecursivePointer _ -> "Any"
+        TagUnion (Recursive _) | TagUnion (NullableWrapped _) | TagUnion (NullableUnwrapped _) | TagUnion (NonNullableUnwrapped _) -> "Any"
+        TagUnion (NonRecursive { name, tags }) ->
+            tags
+            |> List.map \tag -> tagClassName name tag.name
+            |> Str.joinWith " | "
+
+        Struct { name } -> name
+        TagUnionPayload { name } -> name
+        TagUnion (Enumeration { name }) -> name
+        TagUnion (SingleTagStruct { name }) -> name
+
+tagClassName = \unionName, tagName ->
+    className = escapeName tagName
+
+    "\(unionName).\(className)"
+
+discriminantType = \size ->
+    when size is
+        1 -> "uint8_t"
+        2 -> "uint16_t"
+        _ -> "uint32_t"
+
+roundUpToAlignment = \width, alignment ->
+    if alignment <= 1 || width % alignment == 0 then
+        width
+    else
+        width + alignment - (width % alignment)
+
+atLeast = \minimum, value ->
+    if value < minimum then minimum else value
+
+snakeCase = \name ->
+    snake =
+        List.walk (Str.toUtf8 name) [] \bytes, byte ->
+            if byte >= 'A' && byte <= 'Z' && !(List.isEmpty bytes) then
+                bytes |> List.append '_' |> List.append (byte + 32)
+            else if byte >= 'A' && byte <= 'Z' then
+                List.append bytes (byte + 32)
+            else
+                List.append bytes byte
+
+    Str.fromUtf8 snake
+    |> Result.withDefault name
+    |> escapeName
+
+indent = "    "
+
+## Field and tag names end up in both the C declarations and the Python classes, so they can't be
+## keywords in either.
+reservedKeywords = Set.fromList [
+    # C
+    "auto",
+    "char",
+    "const",
+    "default",
+    "do",
+    "double",
+    "enum",
+    "extern",
+    "float",
+    "goto",
+    "inline",
+    "int",
+    "long",
+    "register",
+    "restrict",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "typedef",
+    "union",
+    "unsigned",
+    "void",
+    "volatile",
+    # Python
+    "False",
+    "None",
+    "True",
+    "and",
+    "as",
+    "assert",
+    "async",
+    "await",
+    "class",
+    "def",
+    "del",
+    "elif",
+    "except",
+    "finally",
+    "from",
+    "global",
+    "import",
+    "in",
+    "is",
+    "lambda",
+    "nonlocal",
+    "not",
+    "or",
+    "pass",
+    "raise",
+    "try",
+    "with",
+    "yield",
+    # both
+    "break",
+    "case",
+    "continue",
+    "else",
+    "for",
+    "if",
+    "return",
+    "while",
+]
+
+escapeName = \input ->
+    if Set.contains reservedKeywords input then
+        "\(input)_"
+    else
+        input
+
+pyproject =
+    """
+    # ⚠️ GENERATED CODE ⚠️ - this entire file was generated by the `roc glue` CLI command
+
+    [build-system]
+    requires = ["setuptools>=61"]
+    build-backend = "setuptools.build_meta"
+
+    [project]
+    name = "roc-app"
+    version = "0.0.1"
+    description = "Python bindings for a Roc app"
+    requires-python = ">=3.8"
+    dependencies = ["cffi>=1.15"]
+
+    """
+
+moduleHeader =
+    """
+    # ⚠️ GENERATED CODE ⚠️ - this entire file was generated by the `roc glue` CLI command
+    #
+    # Bindings for calling a Roc app built as a shared library (`roc build --lib`) from Python,
+    # through cffi. Values are converted both ways: Str to str, List to list, records to
+    # dataclasses, and tag unions to a dataclass per tag. The library's host has to export
+    # roc_alloc and roc_dealloc, which these use for the strings and lists they make.
+
+    from __future__ import annotations
+
+    import os
+    import sys
+    from dataclasses import dataclass
+    from decimal import Context, Decimal
+    from enum import IntEnum
+    from typing import Any, Callable, Generic, Optional, TypeVar
+
+    from cffi import FFI
+
+    ffi = FFI()
+    _lib: Any = None
+
+
+    def load(path: Optional[str] = None) -> None:
+        # Loads the Roc library. By default that's the one in $ROC_APP_LIBRARY, or else the one
+        # `roc build --lib` makes from an app.roc in the current directory.
+        global _lib
+
+        if path is None:
+            path = os.environ.get("ROC_APP_LIBRARY")
+
+        if path is None:
+            if sys.platform == "win32":
+                path = "app.dll"
+            elif sys.platform == "darwin":
+                path = "app.dylib"
+            else:
+                path = "app.so"
+
+            path = os.path.join(os.getcwd(), path)
+
+        _lib = ffi.dlopen(path)
+
+
+    def _library() -> Any:
+        if _lib is None:
+            load()
+
+        return _lib
+
+
+    T = TypeVar("T")
+    E = TypeVar("E")
+
+
+    @dataclass
+    class Ok(Generic[T]):
+        value: T
+
+
+    @dataclass
+    class Err(Generic[E]):
+        value: E
+
+
+    _WORD_MASK = 2**64 - 1
+    _REFCOUNT_1 = -(2**63)
+    _SMALL_STR_BYTES = 24
+    _DEC_SCALE = 10**18
+    _DEC_CONTEXT = Context(prec=40)
+
+
+    def _allocate(size: int, alignment: int) -> Any:
+        # Room for some bytes after a refcount of 1; returns where the bytes start
+        alignment = max(alignment, 8)
+        start = ffi.cast("uint8_t *", _library().roc_alloc(size + alignment, alignment)) + alignment
+        ffi.cast("intptr_t *", start)[-1] = _REFCOUNT_1
+
+        return start
+
+
+    def _decref(refcounted: Any, alignment: int, drop_contents: Optional[Callable[[], None]] = None) -> None:
+        # Decrements the refcount just before `refcounted`, and frees its allocation (after dropping
+        # what's in it) when that was the last reference
+        alignment = max(alignment, 8)
+        refcount = ffi.cast("intptr_t *", refcounted) - 1
+
+        if refcount[0] == _REFCOUNT_1:
+            if drop_contents is not None:
+                drop_contents()
+
+            _library().roc_dealloc(ffi.cast("uint8_t *", refcounted) - alignment, alignment)
+        elif refcount[0] != 0:
+            # a refcount of 0 means the value is never freed, like a constant in the app
+            refcount[0] -= 1
+
+
+    def _payload(c: Any, payload_type: str) -> Any:
+        return ffi.cast(payload_type + " *", ffi.addressof(c, "payload"))[0]
+
+
+    def _payload_bytes(payload_type: str, initializer: Any) -> list:
+        payload = ffi.new(payload_type + " *", initializer)
+
+        return list(ffi.buffer(payload)[:])
+
+
+    def _u128_to_roc(value: int) -> Any:
+        return {"low": value & _WORD_MASK, "high": (value >> 64) & _WORD_MASK}
+
+
+    def _u128_from_roc(c: Any) -> int:
+        return (c.high << 64) | c.low
+
+
+    def _i128_to_roc(value: int) -> Any:
+        return _u128_to_roc(value & (2**128 - 1))
+
+
+    def _i128_from_roc(c: Any) -> int:
+        value = _u128_from_roc(c)
+
+        return value - 2**128 if value >> 127 else value
+
+
+    def _dec_to_roc(value: Decimal) -> Any:
+        return _i128_to_roc(int(_DEC_CONTEXT.multiply(Decimal(value), _DEC_SCALE)))
+
+
+    def _dec_from_roc(c: Any) -> Decimal:
+        return _DEC_CONTEXT.divide(Decimal(_i128_from_roc(c)), _DEC_SCALE)
+
+
+    def _str_to_roc(value: str) -> Any:
+        utf8 = value.encode("utf-8")
+
+        if len(utf8) < _SMALL_STR_BYTES:
+            # small strings are stored inline, with their length in the last byte
+            inline = utf8 + bytes(_SMALL_STR_BYTES - 1 - len(utf8)) + bytes([len(utf8) | 0x80])
+            words = [int.from_bytes(inline[i : i + 8], "little") for i in range(0, _SMALL_STR_BYTES, 8)]
+
+            return {"words": words}
+
+        start = _allocate(len(utf8), 1)
+        ffi.memmove(start, utf8, len(utf8))
+
+        return {"words": [int(ffi.cast("uintptr_t", start)), len(utf8), len(utf8)]}
+
+
+    def _str_from_roc(c: Any) -> str:
+        words = list(c.words)
+
+        if words[2] >> 63:
+            inline = b"".join(word.to_bytes(8, "little") for word in words)
+
+            return inline[: (words[2] >> 56) & 0x7F].decode("utf-8")
+
+        # the top bit of the length marks seamless slices
+        length = words[1] & (_WORD_MASK >> 1)
+
+        if length == 0:
+            return ""
+
+        return ffi.unpack(ffi.cast("char *", words[0]), length).decode("utf-8")
+
+
+    def _str_drop(c: Any) -> None:
+        words = list(c.words)
+
+        if words[2] >> 63 or words[0] == 0:
+            return
+
+        refcounted = (words[2] << 1) & _WORD_MASK if words[1] >> 63 else words[0]
+        _decref(ffi.cast("uint8_t *", refcounted), 1)
+
+
+    def _list_to_roc(items: list, element_type: str, alignment: int, convert: Callable[[Any], Any]) -> Any:
+        if not items:
+            return {"elements": 0, "length": 0, "capacity": 0}
+
+        start = _allocate(len(items) * ffi.sizeof(element_type), alignment)
+        elements = ffi.cast(element_type + " *", start)
+
+        for index, item in enumerate(items):
+            element = ffi.new(element_type + " *", convert(item))
+            elements[index] = element[0]
+
+        return {"elements": int(ffi.cast("uintptr_t", start)), "length": len(items), "capacity": len(items)}
+
+
+    def _list_from_roc(c: Any, element_type: str, convert: Callable[[Any], Any]) -> list:
+        elements = ffi.cast(element_type + " *", c.elements)
+
+        return [convert(elements[index]) for index in range(c.length)]
+
+
+    def _list_drop(c: Any, alignment: int, element_type: str, drop_element: Optional[Callable[[Any], None]]) -> None:
+        if c.elements == 0:
+            return
+
+        def drop_elements() -> None:
+            if drop_element is not None:
+                elements = ffi.cast(element_type + " *", c.elements)
+
+                for index in range(c.length):
+                    drop_element(elements[index])
+
+        # the top bit of the capacity marks seamless slices
+        refcounted = (c.capacity << 1) & _WORD_MASK if c.capacity >> 63 else c.elements
+        _decref(ffi.cast("uint8_t *", refcounted), alignment, drop_elements)
+
+
+    def _opaque_list_from_roc(c: Any) -> dict:
+        return {"elements": c.elements, "length": c.length, "capacity": c.capacity}
+
+
+    def _result_to_roc(
+        result: Any,
+        ok_type: Optional[str],
+        ok_to_roc: Callable[[Any], Any],
+        err_type: Optional[str],
+        err_to_roc: Callable[[Any], Any],
+    ) -> Any:
+        if isinstance(result, Ok):
+            payload_type, to_roc, discriminant = ok_type, ok_to_roc, 1
+        elif isinstance(result, Err):
+            payload_type, to_roc, discriminant = err_type, err_to_roc, 0
+        else:
+            raise TypeError(f"{result!r} is neither an Ok nor an Err")
+
+        if payload_type is None:
+            return {"discriminant": discriminant}
+
+        return {"payload": _payload_bytes(payload_type, to_roc(result.value)), "discriminant": discriminant}
+
+
+    def _result_from_roc(c: Any, ok_from_roc: Callable[[Any], Any], err_from_roc: Callable[[Any], Any]) -> Any:
+        if c.discriminant == 1:
+            return Ok(ok_from_roc(c))
+
+        return Err(err_from_roc(c))
+
+
+    def _result_drop(
+        c: Any,
+        drop_ok: Optional[Callable[[Any], None]],
+        drop_err: Optional[Callable[[Any], None]],
+    ) -> None:
+        drop = drop_ok if c.discriminant == 1 else drop_err
+
+        if drop is not None:
+            drop(c)
+
+    """
+
+cdefHeader =
+    """
+    typedef uint8_t RocUnit;
+    typedef struct { uintptr_t words[3]; } RocStr;
+    typedef struct { uintptr_t elements; size_t length; size_t capacity; } RocList;
+    typedef struct { uint64_t low; uint64_t high; } RocU128;
+    typedef RocU128 RocI128;
+    typedef RocU128 RocDec;
+    typedef void *RocBox;
+
+    void *roc_alloc(size_t size, uint32_t alignment);
+    void roc_dealloc(void *ptr, uint32_t alignment);
+    """
@@ -0,0 +1,830 @@
+//! Generate an ABI conformance suite for a platform: a throwaway platform with one entrypoint per
+//! type the real platform exposes, and a C host which builds a value of each of those types using
+//! the layouts glue sees, passes it to Roc, and checks that what comes back is that value after
+//! Roc changed every part of it. If the sizes, alignments, field orders or discriminants glue
+//! reports ever stop matching what the compiler generates, the host reads back garbage and the
+//! suite fails, rather than a platform's users hitting silent memory corruption.
+use crate::load::{load_types, IgnoreErrors};
+use crate::types::{
+    RocNum, RocSingleTagPayload, RocStructFields, RocTagUnion, RocType, TypeId, Types,
+};
+use roc_load::Threading;
+use roc_target::TargetInfo;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use target_lexicon::Triple;
+
+/// The heap-allocated string every `Str` starts out as; small strings are checked by the
+/// `Str`s inside of `List`s and records, which Roc builds from this one
+const SAMPLE_STR: &str = "a string which is too long to be small";
+
+/// Every Roc value has a "mutated" counterpart, which is what the suite's Roc code turns it into
+/// and what the host checks for. This is the sample (and mutated) value of each number type.
+fn sample_number(num: RocNum) -> (&'static str, &'static str, &'static str) {
+    match num {
+        RocNum::I8 => ("int8_t", "42", "43"),
+        RocNum::U8 => ("uint8_t", "42", "43"),
+        RocNum::I16 => ("int16_t", "42", "43"),
+        RocNum::U16 => ("uint16_t", "42", "43"),
+        RocNum::I32 => ("int32_t", "42", "43"),
+        RocNum::U32 => ("uint32_t", "42", "43"),
+        RocNum::I64 => ("int64_t", "42", "43"),
+        RocNum::U64 => ("uint64_t", "42", "43"),
+        RocNum::F32 => ("float", "1.5f", "2.5f"),
+        RocNum::F64 => ("double", "1.5", "2.5"),
+        // 128-bit numbers are written as two little-endian words; Dec stores 1.0 as 10^18
+        RocNum::I128 | RocNum::U128 => ("uint64_t", "42", "43"),
+        RocNum::Dec => ("uint64_t", "5000000000000000000", "6000000000000000000"),
+    }
+}
+
+fn round_up_to_alignment(size: u32, alignment: u32) -> u32 {
+    match alignment {
+        0 => size,
+        _ => (size + alignment - 1) / alignment * alignment,
+    }
+}
+
+struct Suite<'a> {
+    types: &'a Types,
+    /// Every type the suite has to build and check, after the types it contains
+    needed: Vec<TypeId>,
+    indices: HashMap<TypeId, usize>,
+}
+
+impl<'a> Suite<'a> {
+    fn new(types: &'a Types) -> Self {
+        Self {
+            types,
+            needed: Vec::new(),
+            indices: HashMap::new(),
+        }
+    }
+
+    /// Add `id` and everything it contains to the suite, or say why the host can't build it
+    fn require(&mut self, id: TypeId) -> Result<(), String> {
+        if self.indices.contains_key(&id) {
+            return Ok(());
+        }
+
+        let children = match self.types.get_type(id) {
+            RocType::RocStr
+            | RocType::Bool
+            | RocType::Num(_)
+            | RocType::Unit
+            | RocType::EmptyTagUnion
+            | RocType::TagUnion(RocTagUnion::Enumeration { .. }) => Vec::new(),
+            RocType::RocList(elem) | RocType::RocBox(elem) => vec![*elem],
+            RocType::RocResult(ok, err) => vec![*ok, *err],
+            RocType::Struct {
+                fields: RocStructFields::HasNoClosure { fields },
+                ..
+            } => fields.iter().map(|(_, field)| *field).collect(),
+            RocType::TagUnion(RocTagUnion::NonRecursive { tags, .. }) => tags
+                .iter()
+                .flat_map(|(_, payload)| self.payload_fields(*payload))
+                .map(|(_, field, _)| field)
+                .collect(),
+            RocType::TagUnion(RocTagUnion::SingleTagStruct {
+                payload: RocSingleTagPayload::HasNoClosure { payload_fields },
+                ..
+            }) => payload_fields.clone(),
+            RocType::RocDict(_, _) => return Err("a `Dict`".to_string()),
+            RocType::RocSet(_) => return Err("a `Set`".to_string()),
+            RocType::Function(_)
+            | RocType::Struct {
+                fields: RocStructFields::HasClosure { .. },
+                ..
+            }
+            | RocType::TagUnion(RocTagUnion::SingleTagStruct {
+                payload: RocSingleTagPayload::HasClosure { .. },
+                ..
+            }) => return Err("a function".to_string()),
+            RocType::TagUnion(
+                RocTagUnion::Recursive { name, .. }
+                | RocTagUnion::NonNullableUnwrapped { name, .. }
+                | RocTagUnion::NullableWrapped { name, .. }
+                | RocTagUnion::NullableUnwrapped { name, .. },
+            ) => return Err(format!("the recursive tag union `{}`", name)),
+            RocType::RecursivePointer(_) => return Err("a recursive tag union".to_string()),
+            RocType::TagUnionPayload { .. } | RocType::Unsized => {
+                return Err("a value whose size isn't known".to_string())
+            }
+        };
+
+        for child in children {
+            self.require(child)?;
+        }
+
+        self.indices.insert(id, self.needed.len());
+        self.needed.push(id);
+
+        Ok(())
+    }
+
+    fn index(&self, id: TypeId) -> usize {
+        self.indices[&id]
+    }
+
+    /// The fields of a layout, in layout order, along with their offsets
+    fn offsets(
+        &self,
+        fields: impl IntoIterator<Item = (String, TypeId)>,
+    ) -> Vec<(String, TypeId, u32)> {
+        let mut offset = 0;
+
+        fields
+            .into_iter()
+            .map(|(name, id)| {
+                let field_offset = round_up_to_alignment(offset, self.types.align(id));
+
+                offset = field_offset + self.types.size_ignoring_alignment(id);
+
+                (name, id, field_offset)
+            })
+            .collect()
+    }
+
+    /// The fields of a tag's payload in layout order, named by their position in the tag
+    fn payload_fields(&self, payload: Option<TypeId>) -> Vec<(String, TypeId, u32)> {
+        match payload {
+            None => Vec::new(),
+            Some(id) => match self.types.get_type(id) {
+                RocType::TagUnionPayload {
+                    fields: RocStructFields::HasNoClosure { fields },
+                    ..
+                } => self.offsets(fields.iter().cloned()),
+                // a tag with a single payload value has that value's type as its payload
+                _ => vec![("0".to_string(), id, 0)],
+            },
+        }
+    }
+
+    /// The payload fields in the order the tag takes them
+    fn positional(fields: &[(String, TypeId, u32)]) -> Vec<(String, TypeId, u32)> {
+        let mut positional = fields.to_vec();
+
+        positional.sort_by_key(|(name, _, _)| name.parse::<usize>().unwrap_or(usize::MAX));
+
+        positional
+    }
+
+    /// Which tag of a union the host builds: the last one with a payload, since that exercises
+    /// the payload and a discriminant other than 0
+    fn sample_tag(&self, tags: &[(String, Option<TypeId>)]) -> usize {
+        tags.iter()
+            .rposition(|(_, payload)| payload.is_some())
+            .unwrap_or(tags.len() - 1)
+    }
+
+    fn result_discriminant_offset(&self, ok: TypeId, err: TypeId) -> u32 {
+        let size = self
+            .types
+            .size_ignoring_alignment(ok)
+            .max(self.types.size_ignoring_alignment(err));
+        let alignment = self.types.align(ok).max(self.types.align(err));
+
+        round_up_to_alignment(size, alignment)
+    }
+
+    /// The Roc type of `id`, written out structurally so the suite doesn't need any of the
+    /// platform's modules
+    fn roc_type(&self, id: TypeId) -> String {
+        match self.types.get_type(id) {
+            RocType::RocStr => "Str".to_string(),
+            RocType::Bool => "Bool".to_string(),
+            RocType::Num(num) => format!("{:?}", num),
+            RocType::Unit => "{}".to_string(),
+            RocType::EmptyTagUnion => "[]".to_string(),
+            RocType::RocList(elem) => format!("List {}", self.roc_type_arg(*elem)),
+            RocType::RocBox(elem) => format!("Box {}", self.roc_type_arg(*elem)),
+            RocType::RocResult(ok, err) => format!(
+                "Result {} {}",
+                self.roc_type_arg(*ok),
+                self.roc_type_arg(*err)
+            ),
+            RocType::Struct {
+                fields: RocStructFields::HasNoClosure { fields },
+                ..
+            } => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(name, field)| format!("{} : {}", name, self.roc_type(*field)))
+                    .collect();
+
+                match fields.is_empty() {
+                    true => "{}".to_string(),
+                    false => format!("{{ {} }}", fields.join(", ")),
+                }
+            }
+            RocType::TagUnion(RocTagUnion::Enumeration { tags, .. }) => {
+                format!("[{}]", tags.join(", "))
+            }
+            RocType::TagUnion(RocTagUnion::NonRecursive { tags, .. }) => {
+                let tags: Vec<String> = tags
+                    .iter()
+                    .map(|(tag, payload)| {
+                        let fields = Self::positional(&self.payload_fields(*payload));
+
+                        self.roc_tag(tag, fields.iter().map(|(_, field, _)| *field))
+                    })
+                    .collect();
+
+                format!("[{}]", tags.join(", "))
+            }
+            RocType::TagUnion(RocTagUnion::SingleTagStruct {
+                tag_name,
+                payload: RocSingleTagPayload::HasNoClosure { payload_fields },
+                ..
+            }) => format!(
+                "[{}]",
+                self.roc_tag(tag_name, payload_fields.iter().copied())
+            ),
+            other => unreachable!("{:?} should have been rejected by `require`", other),
+        }
+    }
+
+    fn roc_type_arg(&self, id: TypeId) -> String {
+        let roc_type = self.roc_type(id);
+
+        match roc_type.contains(' ') && !roc_type.starts_with(['{', '[']) {
+            true => format!("({})", roc_type),
+            false => roc_type,
+        }
+    }
+
+    fn roc_tag(&self, tag: &str, payload: impl Iterator<Item = TypeId>) -> String {
+        let mut roc_tag = tag.to_string();
+
+        for field in payload {
+            roc_tag.push(' ');
+            roc_tag.push_str(&self.roc_type_arg(field));
+        }
+
+        roc_tag
+    }
+
+    /// A Roc function which changes every part of a value of type `id`
+    fn roc_mutate(&self, out: &mut String, id: TypeId) {
+        let index = self.index(id);
+        let mutate = |field: TypeId| format!("mutate{}", self.index(field));
+
+        let body = match self.types.get_type(id) {
+            RocType::RocStr => "Str.concat value \"!\"".to_string(),
+            RocType::Bool => "!value".to_string(),
+            RocType::Num(RocNum::F32 | RocNum::F64 | RocNum::Dec) => "value + 1".to_string(),
+            RocType::Num(_) => "Num.addWrap value 1".to_string(),
+            RocType::RocList(elem) => format!("List.map value {}", mutate(*elem)),
+            RocType::RocBox(elem) => format!("Box.box ({} (Box.unbox value))", mutate(*elem)),
+            RocType::RocResult(ok, err) => format!(
+                "\n        when value is\n            Ok ok -> Ok ({} ok)\n            Err err -> Err ({} err)",
+                mutate(*ok),
+                mutate(*err)
+            ),
+            RocType::Struct {
+                fields: RocStructFields::HasNoClosure { fields },
+                ..
+            } if !fields.is_empty() => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(name, field)| format!("{}: {} value.{}", name, mutate(*field), name))
+                    .collect();
+
+                format!("{{ {} }}", fields.join(", "))
+            }
+            RocType::TagUnion(RocTagUnion::NonRecursive { tags, .. }) => {
+                let mut branches = String::from("\n        when value is");
+
+                for (tag, payload) in tags {
+                    let fields = Self::positional(&self.payload_fields(*payload));
+                    let mut pattern = tag.clone();
+                    let mut mutated = tag.clone();
+
+                    for (position, (_, field, _)) in fields.iter().enumerate() {
+                        let _ = write!(pattern, " a{}", position);
+                        let _ = write!(mutated, " ({} a{})", mutate(*field), position);
+                    }
+
+                    let _ = write!(branches, "\n            {} -> {}", pattern, mutated);
+                }
+
+                branches
+            }
+            RocType::TagUnion(RocTagUnion::SingleTagStruct {
+                tag_name,
+                payload: RocSingleTagPayload::HasNoClosure { payload_fields },
+                ..
+            }) if !payload_fields.is_empty() => {
+                let mut pattern = tag_name.clone();
+                let mut mutated = tag_name.clone();
+
+                for (position, field) in payload_fields.iter().enumerate() {
+                    let _ = write!(pattern, " a{}", position);
+                    let _ = write!(mutated, " ({} a{})", mutate(*field), position);
+                }
+
+                format!("\n        when value is\n            {} -> {}", pattern, mutated)
+            }
+            // enumerations and zero-sized types have nothing to change
+            _ => "value".to_string(),
+        };
+
+        let separator = if body.starts_with('\n') { "" } else { " " };
+
+        let _ = writeln!(out, "mutate{} = \\value ->{}{}\n", index, separator, body);
+    }
+
+    /// C which writes the sample value of type `id` at `p`
+    fn c_write(&self, out: &mut String, id: TypeId) {
+        let index = self.index(id);
+        let write = |field: TypeId| format!("write_{}", self.index(field));
+        let mut body = String::new();
+
+        match self.types.get_type(id) {
+            RocType::RocStr => {
+                let _ = writeln!(body, "  write_str(p, \"{}\");", SAMPLE_STR);
+            }
+            RocType::Bool => body.push_str("  *p = 1;\n"),
+            RocType::Num(num @ (RocNum::I128 | RocNum::U128 | RocNum::Dec)) => {
+                let (_, sample, _) = sample_number(*num);
+                let _ = writeln!(
+                    body,
+                    "  ((uint64_t *)p)[0] = {};\n  ((uint64_t *)p)[1] = 0;",
+                    sample
+                );
+            }
+            RocType::Num(num) => {
+                let (c_type, sample, _) = sample_number(*num);
+                let _ = writeln!(body, "  *({} *)p = {};", c_type, sample);
+            }
+            RocType::RocList(elem) => {
+                let stride = self.types.size_rounded_to_alignment(*elem);
+                let _ = writeln!(
+                    body,
+                    "  uint8_t *elements = roc_refcounted(2 * {stride}, {align});\n  {write}(elements);\n  {write}(elements + {stride});\n  struct RocList *list = (struct RocList *)p;\n  list->elements = elements;\n  list->len = 2;\n  list->capacity = 2;",
+                    stride = stride,
+                    align = self.types.align(*elem),
+                    write = write(*elem),
+                );
+            }
+            RocType::RocBox(elem) => {
+                let _ = writeln!(
+                    body,
+                    "  uint8_t *boxed = roc_refcounted({}, {});\n  {}(boxed);\n  *(uint8_t **)p = boxed;",
+                    self.types.size_rounded_to_alignment(*elem),
+                    self.types.align(*elem),
+                    write(*elem),
+                );
+            }
+            RocType::RocResult(ok, err) => {
+                let _ = writeln!(
+                    body,
+                    "  {}(p);\n  p[{}] = 1; // Ok",
+                    write(*ok),
+                    self.result_discriminant_offset(*ok, *err)
+                );
+            }
+            RocType::Struct {
+                fields: RocStructFields::HasNoClosure { fields },
+                ..
+            } => {
+                for (_, field, offset) in self.offsets(fields.iter().cloned()) {
+                    let _ = writeln!(body, "  {}(p + {});", write(field), offset);
+                }
+            }
+            RocType::TagUnion(RocTagUnion::Enumeration { tags, size, .. }) => {
+                let _ = writeln!(
+                    body,
+                    "  *({} *)p = {}; // {}",
+                    discriminant_type(*size),
+                    tags.len() - 1,
+                    tags[tags.len() - 1]
+                );
+            }
+            RocType::TagUnion(RocTagUnion::NonRecursive {
+                tags,
+                discriminant_offset,
+                discriminant_size,
+                ..
+            }) => {
+                let tag = self.sample_tag(tags);
+
+                for (_, field, offset) in self.payload_fields(tags[tag].1) {
+                    let _ = writeln!(body, "  {}(p + {});", write(field), offset);
+                }
+
+                let _ = writeln!(
+                    body,
+                    "  *({} *)(p + {}) = {}; // {}",
+                    discriminant_type(*discriminant_size),
+                    discriminant_offset,
+                    tag,
+                    tags[tag].0
+                );
+            }
+            RocType::TagUnion(RocTagUnion::SingleTagStruct {
+                payload: RocSingleTagPayload::HasNoClosure { payload_fields },
+                ..
+            }) => {
+                let fields = payload_fields
+                    .iter()
+                    .enumerate()
+                    .map(|(position, field)| (position.to_string(), *field));
+
+                for (_, field, offset) in self.offsets(fields) {
+                    let _ = writeln!(body, "  {}(p + {});", write(field), offset);
+                }
+            }
+            _ => body.push_str("  (void)p;\n"),
+        }
+
+        let _ = writeln!(
+            out,
+            "static void write_{}(uint8_t *p) {{\n{}}}\n",
+            index, body
+        );
+    }
+
+    /// C which checks that `p` holds the mutated sample value of type `id`
+    fn c_check(&self, out: &mut String, id: TypeId) {
+        let index = self.index(id);
+        let roc_type = self.roc_type(id);
+        let check = |field: TypeId| format!("check_{}", self.index(field));
+        let mut body = String::new();
+
+        match self.types.get_type(id) {
+            RocType::RocStr => {
+                let _ = writeln!(body, "  expect(str_equals(p, \"{}!\"), where);", SAMPLE_STR);
+            }
+            RocType::Bool => body.push_str("  expect(*p == 0, where);\n"),
+            RocType::Num(num @ (RocNum::I128 | RocNum::U128 | RocNum::Dec)) => {
+                let (_, _, mutated) = sample_number(*num);
+                let _ = writeln!(
+                    body,
+                    "  expect(((const uint64_t *)p)[0] == {} && ((const uint64_t *)p)[1] == 0, where);",
+                    mutated
+                );
+            }
+            RocType::Num(num) => {
+                let (c_type, _, mutated) = sample_number(*num);
+                let _ = writeln!(
+                    body,
+                    "  expect(*(const {} *)p == {}, where);",
+                    c_type, mutated
+                );
+            }
+            RocType::RocList(elem) => {
+                let _ = writeln!(
+                    body,
+                    "  const struct RocList *list = (const struct RocList *)p;\n  expect(list->len == 2, \"the length of `{roc_type}`\");\n  if (list->len == 2) {{\n    {check}(list->elements, \"the first element of `{roc_type}`\");\n    {check}((const uint8_t *)list->elements + {stride}, \"the second element of `{roc_type}`\");\n  }}",
+                    roc_type = roc_type,
+                    check = check(*elem),
+                    stride = self.types.size_rounded_to_alignment(*elem),
+                );
+            }
+            RocType::RocBox(elem) => {
+                let _ = writeln!(
+                    body,
+                    "  {}(*(uint8_t *const *)p, \"the contents of `{}`\");",
+                    check(*elem),
+                    roc_type
+                );
+            }
+            RocType::RocResult(ok, err) => {
+                let _ = writeln!(
+                    body,
+                    "  expect(p[{offset}] == 1, \"the tag of `{roc_type}`\");\n  if (p[{offset}] == 1) {{\n    {check}(p, \"the Ok payload of `{roc_type}`\");\n  }}",
+                    offset = self.result_discriminant_offset(*ok, *err),
+                    roc_type = roc_type,
+                    check = check(*ok),
+                );
+            }
+            RocType::Struct {
+                fields: RocStructFields::HasNoClosure { fields },
+                ..
+            } => {
+                for (name, field, offset) in self.offsets(fields.iter().cloned()) {
+                    let _ = writeln!(
+                        body,
+                        "  {}(p + {}, \"field `{}` of `{}`\");",
+                        check(field),
+                        offset,
+                        name,
+                        roc_type
+                    );
+                }
+            }
+            RocType::TagUnion(RocTagUnion::Enumeration { tags, size, .. }) => {
+                let _ = writeln!(
+                    body,
+                    "  expect(*(const {} *)p == {}, where);",
+                    discriminant_type(*size),
+                    tags.len() - 1
+                );
+            }
+            RocType::TagUnion(RocTagUnion::NonRecursive {
+                tags,
+                discriminant_offset,
+                discriminant_size,
+                ..
+            }) => {
+                let tag = self.sample_tag(tags);
+                let discriminant = format!(
+                    "*(const {} *)(p + {})",
+                    discriminant_type(*discriminant_size),
+                    discriminant_offset
+                );
+
+                let _ = writeln!(
+                    body,
+                    "  expect({discriminant} == {tag}, \"the tag of `{roc_type}`\");\n  if ({discriminant} == {tag}) {{",
+                    discriminant = discriminant,
+                    tag = tag,
+                    roc_type = roc_type,
+                );
+
+                for (position, field, offset) in self.payload_fields(tags[tag].1) {
+                    let _ = writeln!(
+                        body,
+                        "    {}(p + {}, \"payload {} of `{}` in `{}`\");",
+                        check(field),
+                        offset,
+                        position,
+                        tags[tag].0,
+                        roc_type
+                    );
+                }
+
+                body.push_str("  }\n");
+            }
+            RocType::TagUnion(RocTagUnion::SingleTagStruct {
+                tag_name,
+                payload: RocSingleTagPayload::HasNoClosure { payload_fields },
+                ..
+            }) => {
+                let fields = payload_fields
+                    .iter()
+                    .enumerate()
+                    .map(|(position, field)| (position.to_string(), *field));
+
+                for (position, field, offset) in self.offsets(fields) {
+                    let _ = writeln!(
+                        body,
+                        "  {}(p + {}, \"payload {} of `{}` in `{}`\");",
+                        check(field),
+                        offset,
+                        position,
+                        tag_name,
+                        roc_type
+                    );
+                }
+            }
+            _ => {}
+        }
+
+        let _ = writeln!(
+            out,
+            "static void check_{}(const uint8_t *p, const char *where) {{\n  (void)p;\n  (void)where;\n{}}}\n",
+            index, body
+        );
+    }
+}
+
+fn discriminant_type(size: u32) -> &'static str {
+    match size {
+        1 => "uint8_t",
+        2 => "uint16_t",
+        4 => "uint32_t",
+        _ => "uint64_t",
+    }
+}
+
+const C_PRELUDE: &str = r#"// Builds a value of each type the platform exposes, passes it through Roc, and checks what
+// comes back. Generated by `roc glue --verify`; any changes will be overwritten.
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+struct RocStr {
+  char *bytes;
+  size_t len;
+  size_t capacity;
+};
+
+struct RocList {
+  void *elements;
+  size_t len;
+  size_t capacity;
+};
+
+"#;
+
+const C_HELPERS: &str = r#"
+static int failures = 0;
+
+static void expect(bool ok, const char *where) {
+  if (!ok) {
+    fprintf(stderr, "  mismatch: %s\n", where);
+    failures++;
+  }
+}
+
+// Allocate `size` bytes which Roc can refcount, with a refcount of 1
+static uint8_t *roc_refcounted(size_t size, size_t alignment) {
+  size_t header = alignment > sizeof(intptr_t) ? alignment : sizeof(intptr_t);
+  uint8_t *data = (uint8_t *)roc_alloc(header + size, (unsigned int)alignment) + header;
+
+  ((intptr_t *)data)[-1] = INTPTR_MIN;
+
+  return data;
+}
+
+static void write_str(uint8_t *p, const char *text) {
+  size_t len = strlen(text);
+  struct RocStr *str = (struct RocStr *)p;
+
+  str->bytes = (char *)roc_refcounted(len, sizeof(size_t));
+  memcpy(str->bytes, text, len);
+  str->len = len;
+  str->capacity = len;
+}
+
+static bool str_equals(const uint8_t *p, const char *text) {
+  const struct RocStr *str = (const struct RocStr *)p;
+  const char *bytes = str->bytes;
+  size_t len = str->len & (SIZE_MAX >> 1);
+
+  // a small string keeps its bytes inline, and its length in its last byte
+  if ((intptr_t)str->capacity < 0) {
+    bytes = (const char *)p;
+    len = p[sizeof(struct RocStr) - 1] & 0x7F;
+  }
+
+  return len == strlen(text) && memcmp(bytes, text, len) == 0;
+}
+
+"#;
+
+/// A type which the platform exposes, and the entrypoints it comes from
+struct Root {
+    id: TypeId,
+    sources: Vec<String>,
+}
+
+fn roots(types: &Types) -> Vec<Root> {
+    let mut roots: Vec<Root> = Vec::new();
+
+    for (name, id) in types.entry_points() {
+        let ids = match types.get_type(*id) {
+            RocType::Function(roc_fn) => roc_fn.args.iter().chain([&roc_fn.ret]).copied().collect(),
+            _ => vec![*id],
+        };
+
+        for id in ids {
+            match roots.iter_mut().find(|root| root.id == id) {
+                Some(root) if !root.sources.contains(name) => root.sources.push(name.clone()),
+                Some(_) => {}
+                None => roots.push(Root {
+                    id,
+                    sources: vec![name.clone()],
+                }),
+            }
+        }
+    }
+
+    roots
+}
+
+fn write_file(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, contents)
+}
+
+/// Generate the conformance suite for the platform whose main module is at `platform_path`, in a
+/// `conformance` directory inside `output_path`. Returns the path of the suite's app, which
+/// `roc run` builds and runs; it exits with a nonzero code if any value came back wrong.
+pub fn generate_conformance_suite(platform_path: &Path, output_path: &Path) -> io::Result<PathBuf> {
+    let types = load_types(
+        platform_path.to_path_buf(),
+        Threading::AllAvailable,
+        IgnoreErrors::NONE,
+    )?;
+
+    // the suite runs on this machine, so it checks the layouts glue generates for this machine
+    let architecture = TargetInfo::from(&Triple::host()).architecture;
+    let types = types
+        .iter()
+        .find(|types| types.target().architecture == architecture)
+        .ok_or_else(|| {
+            io::Error::new(
+                ErrorKind::Unsupported,
+                format!(
+                    "Glue doesn't describe layouts for this machine's architecture ({:?}), so they can't be verified here.",
+                    architecture
+                ),
+            )
+        })?;
+
+    let mut suite = Suite::new(types);
+    let mut tested = Vec::new();
+
+    for root in roots(types) {
+        let sources = root.sources.join("`, `");
+
+        if types.size_ignoring_alignment(root.id) == 0 {
+            continue;
+        }
+
+        match suite.require(root.id) {
+            Ok(()) => tested.push(root),
+            Err(reason) => println!(
+                "Skipped a type from `{}`, because the conformance suite can't build {} yet.",
+                sources, reason
+            ),
+        }
+    }
+
+    let dir = output_path.join("conformance");
+    let entrypoints: Vec<String> = (0..tested.len())
+        .map(|index| format!("roundTrip{}ForHost", index))
+        .collect();
+
+    // Every value goes in and out through a `Box`, so the calling convention is the same for all
+    // of them, and the suite checks only their layouts.
+    let mut platform = format!(
+        "platform \"glue-conformance\"\n    requires {{}} {{ main : {{}} }}\n    exposes []\n    packages {{}}\n    imports []\n    provides [{}]\n\n",
+        entrypoints.join(", ")
+    );
+
+    for (root, entrypoint) in tested.iter().zip(entrypoints.iter()) {
+        let roc_type = suite.roc_type(root.id);
+        let _ = writeln!(
+            platform,
+            "{entrypoint} : Box ({roc_type}) -> Box ({roc_type})\n{entrypoint} = \\boxed -> Box.box (mutate{index} (Box.unbox boxed))\n",
+            entrypoint = entrypoint,
+            roc_type = roc_type,
+            index = suite.index(root.id),
+        );
+    }
+
+    let mut host = String::from(C_PRELUDE);
+
+    for entrypoint in entrypoints.iter() {
+        let _ = writeln!(
+            host,
+            "extern void roc__{}_1_exposed_generic(uint8_t **result, uint8_t *value);",
+            entrypoint
+        );
+    }
+
+    host.push('\n');
+    host.push_str(crate::stub::C_HOST_FUNCTIONS);
+    host.push_str(C_HELPERS);
+
+    for id in suite.needed.iter() {
+        suite.roc_mutate(&mut platform, *id);
+        suite.c_write(&mut host, *id);
+        suite.c_check(&mut host, *id);
+    }
+
+    host.push_str("int main(void) {\n");
+
+    for (root, entrypoint) in tested.iter().zip(entrypoints.iter()) {
+        let index = suite.index(root.id);
+        let _ = writeln!(
+            host,
+            "  {{\n    // from `{sources}`\n    uint8_t *value = roc_refcounted({size}, {align});\n    uint8_t *result;\n\n    write_{index}(value);\n    roc__{entrypoint}_1_exposed_generic(&result, value);\n    check_{index}(result, \"`{roc_type}`\");\n  }}\n",
+            sources = root.sources.join("`, `"),
+            size = types.size_rounded_to_alignment(root.id),
+            align = types.align(root.id),
+            index = index,
+            entrypoint = entrypoint,
+            roc_type = suite.roc_type(root.id),
+        );
+    }
+
+    let _ = writeln!(
+        host,
+        "  if (failures > 0) {{\n    fprintf(stderr, \"%d values came back from Roc differently than glue laid them out.\\n\", failures);\n\n    return 1;\n  }}\n\n  printf(\"All {} exposed types round-tripped through Roc the way glue laid them out.\\n\");\n\n  return 0;\n}}",
+        tested.len()
+    );
+
+    let app_path = dir.join("app.roc");
+
+    write_file(&dir.join("platform.roc"), &platform)?;
+    write_file(&dir.join("host.c"), &host)?;
+    write_file(
+        &app_path,
+        "app \"glue-conformance\"\n    packages { pf: \"platform.roc\" }\n    imports []\n    provides [main] to pf\n\nmain = {}\n",
+    )?;
+
+    Ok(app_path)
+}
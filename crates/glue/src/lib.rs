@@ -1,18 +1,22 @@
 //! Generates code needed for platform hosts to communicate with Roc apps.
 //! This tool is not necessary for writing a platform in another language,
-//! however, it's a great convenience! Currently supports Rust platforms, and
+//! however, it's a great convenience! Currently supports Rust, C# and Python platforms, and
 //! the plan is to support any language via a plugin model.
+pub mod conformance;
 pub mod enums;
 pub mod load;
 pub mod roc_type;
 pub mod rust_glue;
 pub mod structs;
+pub mod stub;
 pub mod types;
 
 #[rustfmt::skip]
 pub mod glue;
 
+pub use conformance::generate_conformance_suite;
 pub use load::generate;
+pub use stub::{generate_stub, StubLanguage};
 
 // required because we use roc_std here
 mod roc_externs {
@@ -6,16 +6,17 @@ use roc_build::{
     link::{LinkType, LinkingStrategy},
     program::{
         build_file, handle_error_module, handle_loading_problem, standard_load_config,
-        BuildFileError, BuildOrdering, BuiltFile, CodeGenBackend, CodeGenOptions,
+        BuildFileError, BuildOrdering, BuiltFile, CodeGenBackend, CodeGenOptions, ProfileOptions,
     },
 };
 use roc_collections::MutMap;
-use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
+use roc_gen_llvm::llvm::refcounting::AtomicRefcounts;
+use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, OverflowMode, Threading};
 use roc_mono::ir::{generate_glue_procs, GlueProc, OptLevel};
 use roc_mono::layout::{GlobalLayoutInterner, LayoutCache, LayoutInterner};
 use roc_packaging::cache::{self, RocCacheDir};
-use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
-use roc_target::{Architecture, TargetInfo};
+use roc_reporting::report::{in_charset, RenderTarget, Report, DEFAULT_PALETTE};
+use roc_target::{Architecture, Endianness, TargetInfo};
 use roc_types::subs::{Subs, Variable};
 use std::fs::File;
 use std::io::{self, ErrorKind, Write};
@@ -30,7 +31,7 @@ pub struct IgnoreErrors {
 }
 
 impl IgnoreErrors {
-    const NONE: Self = IgnoreErrors { can: false };
+    pub(crate) const NONE: Self = IgnoreErrors { can: false };
 }
 
 pub fn generate(
@@ -54,6 +55,10 @@ pub fn generate(
                 backend,
                 opt_level: OptLevel::Development,
                 emit_debug_info: false,
+                profile: ProfileOptions::Off,
+                atomic_refcounts: AtomicRefcounts::Never,
+                arena_allocation: false,
+                schedule_refcounts: false,
             };
 
             let load_config = standard_load_config(
@@ -76,9 +81,13 @@ pub fn generate(
                 spec_path.to_path_buf(),
                 code_gen_options,
                 false,
+                false,
+                false,
                 link_type,
                 linking_strategy,
+                roc_linker::LinkLog::default(),
                 true,
+                false,
                 None,
                 RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
                 load_config,
@@ -112,8 +121,9 @@ pub fn generate(
                     if problems.warnings > 0 {
                         problems.print_to_stdout(total_time);
                         println!(
-                            ".\n\nRunning program…\n\n\x1B[36m{}\x1B[39m",
-                            "─".repeat(80)
+                            ".\n\n{}\n\n{}",
+                            in_charset("Running program…"),
+                            Report::horizontal_rule(&DEFAULT_PALETTE)
                         );
                     }
 
@@ -354,6 +364,10 @@ pub fn load_types(
             palette: DEFAULT_PALETTE,
             threading,
             exec_mode: ExecutionMode::Check,
+            recover_from_syntax_errors: false,
+            static_data: false,
+            overflow: OverflowMode::Trap,
+            fold_constants: true,
         },
     )
     .unwrap_or_else(|problem| match problem {
@@ -388,7 +402,10 @@ pub fn load_types(
     });
 
     let operating_system = target_info.operating_system;
-    let architectures = Architecture::iter();
+    // platform glue has no way to describe a big-endian target yet
+    let architectures: Vec<Architecture> = Architecture::iter()
+        .filter(|architecture| architecture.endianness() == Endianness::Little)
+        .collect();
     let mut arch_types = Vec::with_capacity(architectures.len());
 
     let layout_interner = GlobalLayoutInterner::with_capacity(128, target_info);
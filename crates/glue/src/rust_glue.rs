@@ -296,6 +296,47 @@ fn add_type(target_info: TargetInfo, id: TypeId, types: &Types, impls: &mut Impl
         }
         RocType::Function(roc_fn) => add_function(target_info, roc_fn, types, impls),
     }
+
+    add_thread_safety_impls(target_info, id, types, impls);
+}
+
+/// Roc values which contain refcounted data are only safe to share between threads
+/// when the app was built with `--atomic-refcounts`. The host opts into that by
+/// enabling its `atomic-refcounts` feature, which makes these types Send and Sync.
+fn add_thread_safety_impls(target_info: TargetInfo, id: TypeId, types: &Types, impls: &mut Impls) {
+    let typ = types.get_type(id);
+
+    let name = match typ {
+        RocType::Struct { name, .. } => name,
+        RocType::TagUnion(RocTagUnion::NonRecursive { tags, .. })
+        | RocType::TagUnion(RocTagUnion::Recursive { tags, .. })
+            if tags.is_empty() =>
+        {
+            return;
+        }
+        RocType::TagUnion(RocTagUnion::NonRecursive { name, .. })
+        | RocType::TagUnion(RocTagUnion::Recursive { name, .. })
+        | RocType::TagUnion(RocTagUnion::NullableWrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::NullableUnwrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::NonNullableUnwrapped { name, .. })
+        | RocType::TagUnion(RocTagUnion::SingleTagStruct { name, .. }) => name,
+        _ => return,
+    };
+
+    if has_refcounted(typ, types) {
+        let name = escape_kw(name.to_string());
+
+        for trait_name in ["Send", "Sync"] {
+            add_decl(
+                impls,
+                None,
+                target_info,
+                format!(
+                    "#[cfg(feature = \"atomic-refcounts\")]\nunsafe impl {trait_name} for {name} {{}}"
+                ),
+            );
+        }
+    }
 }
 
 fn add_single_tag_struct(
@@ -2574,6 +2615,7 @@ fn arch_to_str(architecture: Architecture) -> &'static str {
         Architecture::Aarch64 => "aarch64",
         Architecture::Aarch32 => "arm",
         Architecture::Wasm32 => "wasm32",
+        Architecture::S390x => "s390x",
     }
 }
 
@@ -2586,7 +2628,7 @@ fn write_indents(indentations: usize, buf: &mut String) {
 fn max_pointer_tagged_variants(architecture: Architecture) -> usize {
     match architecture {
         // On a 64-bit system, pointers have 3 bits that are unused, so return 2^3 = 8
-        Architecture::X86_64 | Architecture::Aarch64 => 8,
+        Architecture::X86_64 | Architecture::Aarch64 | Architecture::S390x => 8,
         // On a 32-bit system, pointers have 2 bits that are unused, so return 2^4 = 4
         Architecture::X86_32 | Architecture::Aarch32 | Architecture::Wasm32 => 4,
     }
@@ -2596,7 +2638,7 @@ fn max_pointer_tagged_variants(architecture: Architecture) -> usize {
 fn tagged_pointer_bitmask(architecture: Architecture) -> u8 {
     match architecture {
         // On a 64-bit system, pointers have 3 bits that are unused
-        Architecture::X86_64 | Architecture::Aarch64 => 0b0000_0111,
+        Architecture::X86_64 | Architecture::Aarch64 | Architecture::S390x => 0b0000_0111,
         // On a 32-bit system, pointers have 2 bits that are unused
         Architecture::X86_32 | Architecture::Aarch32 | Architecture::Wasm32 => 0b0000_0011,
     }
@@ -2846,6 +2888,103 @@ fn cannot_derive_copy(roc_type: &RocType, types: &Types) -> bool {
     }
 }
 
+/// Useful when determining whether a Rust type can be shared between threads.
+fn has_refcounted(start: &RocType, types: &Types) -> bool {
+    let mut seen: Vec<TypeId> = vec![];
+    let mut stack = vec![start];
+
+    macro_rules! push {
+        ($id:expr) => {{
+            if !seen.contains($id) {
+                seen.push(*$id);
+                stack.push(types.get_type(*$id));
+            }
+        }};
+    }
+
+    while let Some(typ) = stack.pop() {
+        match typ {
+            RocType::RocStr
+            | RocType::RocList(_)
+            | RocType::RocSet(_)
+            | RocType::RocDict(_, _)
+            | RocType::RocBox(_)
+            | RocType::Unsized
+            | RocType::TagUnion(RocTagUnion::Recursive { .. })
+            | RocType::TagUnion(RocTagUnion::NullableWrapped { .. })
+            | RocType::TagUnion(RocTagUnion::NullableUnwrapped { .. })
+            | RocType::TagUnion(RocTagUnion::NonNullableUnwrapped { .. })
+            | RocType::RecursivePointer(_) => return true,
+
+            RocType::Bool
+            | RocType::Unit
+            | RocType::Num(_)
+            | RocType::EmptyTagUnion
+            | RocType::Function(_)
+            | RocType::TagUnion(RocTagUnion::Enumeration { .. }) => { /* terminal */ }
+
+            RocType::TagUnion(RocTagUnion::NonRecursive { tags, .. }) => {
+                for (_, opt_payload_id) in tags.iter() {
+                    if let Some(payload_id) = opt_payload_id {
+                        push!(payload_id);
+                    }
+                }
+            }
+
+            RocType::TagUnion(RocTagUnion::SingleTagStruct {
+                payload: RocSingleTagPayload::HasNoClosure { payload_fields },
+                ..
+            }) => {
+                for payload_id in payload_fields {
+                    push!(payload_id);
+                }
+            }
+
+            RocType::TagUnion(RocTagUnion::SingleTagStruct {
+                payload: RocSingleTagPayload::HasClosure { payload_getters },
+                ..
+            }) => {
+                for (payload_id, _) in payload_getters {
+                    push!(payload_id);
+                }
+            }
+
+            RocType::TagUnionPayload {
+                fields: RocStructFields::HasNoClosure { fields },
+                ..
+            }
+            | RocType::Struct {
+                fields: RocStructFields::HasNoClosure { fields },
+                ..
+            } => {
+                for (_, type_id) in fields {
+                    push!(type_id);
+                }
+            }
+
+            RocType::TagUnionPayload {
+                fields: RocStructFields::HasClosure { fields },
+                ..
+            }
+            | RocType::Struct {
+                fields: RocStructFields::HasClosure { fields },
+                ..
+            } => {
+                for (_, type_id, _) in fields {
+                    push!(type_id);
+                }
+            }
+
+            RocType::RocResult(id1, id2) => {
+                push!(id1);
+                push!(id2);
+            }
+        }
+    }
+
+    false
+}
+
 /// Useful when determining whether to derive Eq, Ord, and Hash in a Rust type.
 fn has_float(roc_type: &RocType, types: &Types) -> bool {
     has_float_help(roc_type, types, &[])
@@ -0,0 +1,1076 @@
+//! Generate a host skeleton for a platform: the functions every host has to define, a stub for
+//! each effect in the platform's `hosted` module, and a `main` which calls the platform's
+//! entrypoints. This gives new platform authors something that builds, which they can then fill in.
+use bumpalo::Bump;
+use roc_parse::ast::{Header, Pattern, Spaced, TypeAnnotation, ValueDef};
+use roc_parse::header::ExposedName;
+use roc_parse::state::State;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StubLanguage {
+    Rust,
+    C,
+}
+
+impl std::str::FromStr for StubLanguage {
+    type Err = String;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "rust" => Ok(StubLanguage::Rust),
+            "c" => Ok(StubLanguage::C),
+            other => Err(format!(
+                "There is no host stub for the language `{}`",
+                other
+            )),
+        }
+    }
+}
+
+/// The host-side type of a Roc value, as far as we can tell from its annotation alone
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HostType {
+    Unit,
+    Str,
+    Bool,
+    Number(&'static str, &'static str),
+    List(Box<HostType>),
+    /// Anything whose layout depends on more than its annotation, like records and tag unions.
+    /// These are passed by pointer, and the generated glue types should be used for them.
+    Opaque(String),
+}
+
+impl HostType {
+    fn from_annotation(annotation: &TypeAnnotation<'_>) -> Self {
+        use TypeAnnotation::*;
+
+        let number = |rust, c| HostType::Number(rust, c);
+
+        match annotation {
+            SpaceBefore(inner, _) | SpaceAfter(inner, _) => HostType::from_annotation(inner),
+            As(inner, _, _) => HostType::from_annotation(&inner.value),
+            Record { fields, ext: None } if fields.is_empty() => HostType::Unit,
+            Apply(_, name, args) => match (*name, *args) {
+                ("Str", []) => HostType::Str,
+                ("Bool", []) => HostType::Bool,
+                ("U8", []) => number("u8", "uint8_t"),
+                ("I8", []) => number("i8", "int8_t"),
+                ("U16", []) => number("u16", "uint16_t"),
+                ("I16", []) => number("i16", "int16_t"),
+                ("U32", []) => number("u32", "uint32_t"),
+                ("I32", []) => number("i32", "int32_t"),
+                ("U64", []) => number("u64", "uint64_t"),
+                ("I64", []) => number("i64", "int64_t"),
+                ("Nat", []) => number("usize", "size_t"),
+                ("F32", []) => number("f32", "float"),
+                ("F64", []) => number("f64", "double"),
+                ("List", [elem]) => {
+                    HostType::List(Box::new(HostType::from_annotation(&elem.value)))
+                }
+                _ => HostType::Opaque(format_annotation(annotation)),
+            },
+            _ => HostType::Opaque(format_annotation(annotation)),
+        }
+    }
+
+    fn is_passed_by_value(&self) -> bool {
+        matches!(self, HostType::Bool | HostType::Number(..))
+    }
+
+    fn rust(&self) -> String {
+        match self {
+            HostType::Unit => "()".to_string(),
+            HostType::Str => "RocStr".to_string(),
+            HostType::Bool => "bool".to_string(),
+            HostType::Number(rust, _) => rust.to_string(),
+            HostType::List(elem) => match elem.as_ref() {
+                HostType::Opaque(_) => "RocList<*mut c_void>".to_string(),
+                elem => format!("RocList<{}>", elem.rust()),
+            },
+            HostType::Opaque(_) => "*mut c_void".to_string(),
+        }
+    }
+
+    fn c(&self) -> String {
+        match self {
+            HostType::Unit => "void".to_string(),
+            HostType::Str => "struct RocStr".to_string(),
+            HostType::Bool => "bool".to_string(),
+            HostType::Number(_, c) => c.to_string(),
+            HostType::List(_) => "struct RocList".to_string(),
+            HostType::Opaque(_) => "void *".to_string(),
+        }
+    }
+
+    fn rust_arg(&self) -> String {
+        match self {
+            HostType::Opaque(_) => self.rust(),
+            _ if self.is_passed_by_value() => self.rust(),
+            _ => format!("&{}", self.rust()),
+        }
+    }
+
+    fn c_arg(&self) -> String {
+        match self {
+            HostType::Opaque(_) => self.c(),
+            _ if self.is_passed_by_value() => self.c(),
+            _ => format!("{} *", self.c()),
+        }
+    }
+
+    fn todo(&self) -> Option<String> {
+        match self {
+            HostType::Opaque(annotation) => Some(format!(
+                "`{}` is passed by pointer; use its generated glue type here",
+                annotation
+            )),
+            HostType::List(elem) => elem.todo(),
+            _ => None,
+        }
+    }
+}
+
+fn format_annotation(annotation: &TypeAnnotation<'_>) -> String {
+    use roc_fmt::annotation::Formattable;
+
+    let arena = Bump::new();
+    let mut buf = roc_fmt::Buf::new_in(&arena);
+    annotation.format(&mut buf, 0);
+
+    buf.into_bump_str().to_string()
+}
+
+/// The file written next to a generated host, with the signature of every effect the host was
+/// generated for. Packages can call these effects through the modules the platform exposes, so
+/// `roc check --host` compares them to the platform's current effects to catch hosts which
+/// have fallen behind.
+pub const EFFECT_SIGNATURES_FILE: &str = "roc-effects.txt";
+
+/// An effect from the platform's `hosted` module, which the host implements as `roc_fx_{name}`
+#[derive(Debug)]
+struct Effect {
+    name: String,
+    annotation: String,
+    args: Vec<HostType>,
+    arg_ownership: Vec<Ownership>,
+    ret: HostType,
+    ret_ownership: Ownership,
+    /// Whether the function returns a plain value instead of an effect, which makes it a host
+    /// intrinsic that Roc code calls directly and assumes to be pure
+    intrinsic: bool,
+    /// Whether the intrinsic's result is wrapped in `Pure`
+    pure: bool,
+}
+
+const INTRINSIC_NOTE: &str =
+    "A host intrinsic: Roc calls it like a pure function, so it must not have side effects";
+
+const PURE_NOTE: &str = "It is `Pure`: Roc reuses the result of an earlier call with the same \
+arguments instead of calling it again, so it must always return the same result for them";
+
+impl Effect {
+    /// What the host has to do differently because of `Owned` and `Borrowed` in the annotation
+    fn ownership_notes(&self, arg_prefix: &str) -> Vec<String> {
+        let mut notes = Vec::new();
+
+        for (index, (arg, ownership)) in self.args.iter().zip(&self.arg_ownership).enumerate() {
+            if *ownership == Ownership::Owned && !arg.is_passed_by_value() {
+                notes.push(format!(
+                    "`{}{}` is `Owned`: the host has to decrement its refcount once it's done with it",
+                    arg_prefix, index
+                ));
+            }
+        }
+
+        if self.ret_ownership == Ownership::Borrowed && !self.ret.is_passed_by_value() {
+            notes.push(
+                "The result is `Borrowed`: Roc takes a reference of its own, so the host keeps owning it"
+                    .to_string(),
+            );
+        }
+
+        notes
+    }
+}
+
+/// Who owns a value once it has crossed between Roc and the host. Effects borrow their arguments
+/// and hand over what they return, unless the hosted module says otherwise with `Owned` or
+/// `Borrowed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ownership {
+    Owned,
+    Borrowed,
+}
+
+/// Strip an `Owned` or `Borrowed` from around an annotation, e.g. `Str` for `Owned Str`
+fn ownership<'a, 'b>(
+    annotation: &'b TypeAnnotation<'a>,
+) -> (Option<Ownership>, &'b TypeAnnotation<'a>) {
+    match annotation {
+        TypeAnnotation::SpaceBefore(inner, _) | TypeAnnotation::SpaceAfter(inner, _) => {
+            ownership(inner)
+        }
+        TypeAnnotation::Apply(_, "Owned", [inner]) => (Some(Ownership::Owned), &inner.value),
+        TypeAnnotation::Apply(_, "Borrowed", [inner]) => (Some(Ownership::Borrowed), &inner.value),
+        other => (None, other),
+    }
+}
+
+/// What a host intrinsic annotated with `Pure` returns, e.g. `F32` for `Pure F32`
+fn pure_output<'a, 'b>(annotation: &'b TypeAnnotation<'a>) -> Option<&'b TypeAnnotation<'a>> {
+    match annotation {
+        TypeAnnotation::SpaceBefore(inner, _) | TypeAnnotation::SpaceAfter(inner, _) => {
+            pure_output(inner)
+        }
+        TypeAnnotation::Apply(_, "Pure", [inner]) => Some(&inner.value),
+        _ => None,
+    }
+}
+
+/// How the host has to call a value the platform provides
+#[derive(Debug)]
+enum EntrypointKind {
+    /// A plain value; the host calls `_exposed_generic` with space for the result
+    Value,
+    /// An effect; the host gets its closure from `_exposed_generic`, then runs it with `_caller`
+    Effect,
+    /// A function; the host has to pass it arguments, which depend on their glue types
+    Function,
+}
+
+#[derive(Debug)]
+struct Entrypoint {
+    name: String,
+    annotation: Option<String>,
+    kind: EntrypointKind,
+}
+
+/// Strip an `Effect` (or `Task`) from around an annotation, e.g. `Str` for `Effect Str`
+fn effect_output<'a, 'b>(annotation: &'b TypeAnnotation<'a>) -> Option<&'b TypeAnnotation<'a>> {
+    match annotation {
+        TypeAnnotation::SpaceBefore(inner, _) | TypeAnnotation::SpaceAfter(inner, _) => {
+            effect_output(inner)
+        }
+        TypeAnnotation::As(inner, _, _) => effect_output(&inner.value),
+        TypeAnnotation::Apply(_, name, [output, ..]) if matches!(*name, "Effect" | "Task") => {
+            Some(&output.value)
+        }
+        _ => None,
+    }
+}
+
+fn is_function(annotation: &TypeAnnotation<'_>) -> bool {
+    match annotation {
+        TypeAnnotation::SpaceBefore(inner, _) | TypeAnnotation::SpaceAfter(inner, _) => {
+            is_function(inner)
+        }
+        TypeAnnotation::As(inner, _, _) => is_function(&inner.value),
+        TypeAnnotation::Function(..) => true,
+        _ => false,
+    }
+}
+
+fn exposed_name<'a>(spaced: &Spaced<'a, ExposedName<'a>>) -> &'a str {
+    match spaced {
+        Spaced::Item(name) => (*name).into(),
+        Spaced::SpaceBefore(inner, _) | Spaced::SpaceAfter(inner, _) => exposed_name(inner),
+    }
+}
+
+/// The annotations of a module's toplevel values, in the order they appear
+fn annotations<'a>(arena: &'a Bump, state: State<'a>) -> Vec<(&'a str, &'a TypeAnnotation<'a>)> {
+    let (defs, _) = roc_parse::module::module_defs_recovering(arena, state);
+
+    let mut annotations = Vec::new();
+
+    for value_def in defs.value_defs.iter() {
+        let (pattern, annotation) = match value_def {
+            ValueDef::Annotation(pattern, annotation) => (pattern, annotation),
+            ValueDef::AnnotatedBody {
+                ann_pattern,
+                ann_type,
+                ..
+            } => (*ann_pattern, *ann_type),
+            _ => continue,
+        };
+
+        if let Pattern::Identifier(name) = pattern.value {
+            annotations.push((name, arena.alloc(annotation.value)));
+        }
+    }
+
+    annotations
+}
+
+fn parse_module<'a>(
+    arena: &'a Bump,
+    path: &Path,
+) -> io::Result<(roc_parse::ast::Module<'a>, State<'a>)> {
+    let src = fs::read(path)?;
+
+    roc_parse::module::parse_header(arena, State::new(arena.alloc_slice_copy(&src))).map_err(|_| {
+        io::Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "The header of {} could not be parsed. Run `roc check` on it to see why.",
+                path.display()
+            ),
+        )
+    })
+}
+
+/// The effects of every `hosted` module next to the platform's main module
+fn hosted_effects(platform_dir: &Path) -> io::Result<Vec<Effect>> {
+    let mut paths: Vec<_> = fs::read_dir(platform_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().map_or(false, |ext| ext == "roc"))
+        .collect();
+
+    paths.sort();
+
+    let mut effects = Vec::new();
+
+    for path in paths {
+        let arena = Bump::new();
+
+        // Modules which don't parse aren't hosted ones we can use, so leave them to `roc check`
+        let (module, state) = match parse_module(&arena, &path) {
+            Ok(parsed) => parsed,
+            Err(_) => continue,
+        };
+
+        let header = match module.header {
+            Header::Hosted(header) => header,
+            _ => continue,
+        };
+
+        let generated: Vec<&str> = header
+            .generates_with
+            .item
+            .items
+            .iter()
+            .map(|name| exposed_name(&name.value))
+            .collect();
+
+        for (name, annotation) in annotations(&arena, state) {
+            // e.g. `after` and `map` in `generates Effect with [after, map]` are implemented in Roc
+            if generated.contains(&name) {
+                continue;
+            }
+
+            let (args, ret) = match annotation {
+                TypeAnnotation::Function(args, ret) => (*args, &ret.value),
+                other => (&[][..], other),
+            };
+
+            let (args, arg_ownership) = args
+                .iter()
+                .map(|arg| {
+                    let (arg_ownership, arg) = ownership(&arg.value);
+
+                    (
+                        HostType::from_annotation(arg),
+                        arg_ownership.unwrap_or(Ownership::Borrowed),
+                    )
+                })
+                .unzip();
+            let (pure, ret) = match pure_output(ret) {
+                Some(output) => (true, output),
+                None => (false, ret),
+            };
+            let intrinsic = is_function(annotation) && effect_output(ret).is_none();
+            let (ret_ownership, ret) = ownership(effect_output(ret).unwrap_or(ret));
+
+            effects.push(Effect {
+                name: name.to_string(),
+                annotation: format_annotation(annotation),
+                args,
+                arg_ownership,
+                ret: HostType::from_annotation(ret),
+                ret_ownership: ret_ownership.unwrap_or(Ownership::Owned),
+                intrinsic,
+                pure: pure && intrinsic,
+            });
+        }
+    }
+
+    Ok(effects)
+}
+
+fn entrypoints(arena: &Bump, platform_path: &Path) -> io::Result<Vec<Entrypoint>> {
+    let (module, state) = parse_module(arena, platform_path)?;
+
+    let header = match module.header {
+        Header::Platform(header) => header,
+        _ => {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{} is not a platform module, so there is no host to generate for it.",
+                    platform_path.display()
+                ),
+            ))
+        }
+    };
+
+    let annotations = annotations(arena, state);
+
+    let entrypoints = header
+        .provides
+        .item
+        .items
+        .iter()
+        .map(|name| {
+            let name = exposed_name(&name.value);
+            let annotation = annotations
+                .iter()
+                .find(|(annotated, _)| *annotated == name)
+                .map(|(_, annotation)| *annotation);
+
+            let kind = match annotation {
+                Some(annotation) if effect_output(annotation).is_some() => EntrypointKind::Effect,
+                Some(annotation) if is_function(annotation) => EntrypointKind::Function,
+                _ => EntrypointKind::Value,
+            };
+
+            Entrypoint {
+                name: name.to_string(),
+                annotation: annotation.map(format_annotation),
+                kind,
+            }
+        })
+        .collect();
+
+    Ok(entrypoints)
+}
+
+/// The name and annotation of every effect in the `hosted` modules next to the platform's
+/// main module, with the annotation on a single line
+pub fn effect_signatures(platform_path: &Path) -> io::Result<Vec<(String, String)>> {
+    let effects = hosted_effects(platform_path.parent().unwrap_or_else(|| Path::new(".")))?;
+
+    Ok(signatures(&effects))
+}
+
+/// The names of the host intrinsics among [effect_signatures]: the hosted functions which don't
+/// return an effect
+pub fn host_intrinsics(platform_path: &Path) -> io::Result<Vec<String>> {
+    let effects = hosted_effects(platform_path.parent().unwrap_or_else(|| Path::new(".")))?;
+
+    Ok(effects
+        .into_iter()
+        .filter(|effect| effect.intrinsic)
+        .map(|effect| effect.name)
+        .collect())
+}
+
+fn signatures(effects: &[Effect]) -> Vec<(String, String)> {
+    effects
+        .iter()
+        .map(|effect| {
+            let annotation = effect
+                .annotation
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            (effect.name.clone(), annotation)
+        })
+        .collect()
+}
+
+/// Read the signatures from the contents of an `EFFECT_SIGNATURES_FILE`, one `name : annotation`
+/// per line
+pub fn parse_effect_signatures(contents: &str) -> Vec<(String, String)> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (name, annotation) = line.split_once(" : ")?;
+
+            Some((name.trim().to_string(), annotation.trim().to_string()))
+        })
+        .collect()
+}
+
+fn effect_signatures_file(signatures: &[(String, String)]) -> String {
+    let mut out = String::new();
+
+    for (name, annotation) in signatures {
+        let _ = writeln!(out, "{} : {}", name, annotation);
+    }
+
+    out
+}
+
+const RUST_HOST_FUNCTIONS: &str = r#"#[no_mangle]
+pub unsafe extern "C" fn roc_alloc(size: usize, _alignment: u32) -> *mut c_void {
+    libc::malloc(size)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_realloc(
+    c_ptr: *mut c_void,
+    new_size: usize,
+    _old_size: usize,
+    _alignment: u32,
+) -> *mut c_void {
+    libc::realloc(c_ptr, new_size)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_dealloc(c_ptr: *mut c_void, _alignment: u32) {
+    libc::free(c_ptr)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_panic(msg: &RocStr, tag_id: u32) {
+    match tag_id {
+        0 => {
+            eprintln!("Roc crashed with:\n\n\t{}\n", msg.as_str());
+            std::process::exit(1);
+        }
+        _ => todo!("handle panics with tag id {}", tag_id),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_memcpy(dst: *mut c_void, src: *mut c_void, n: usize) -> *mut c_void {
+    libc::memcpy(dst, src, n)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn roc_memset(dst: *mut c_void, c: i32, n: usize) -> *mut c_void {
+    libc::memset(dst, c, n)
+}
+"#;
+
+pub(crate) const C_HOST_FUNCTIONS: &str = r#"void *roc_alloc(size_t size, unsigned int alignment) { return malloc(size); }
+
+void *roc_realloc(void *ptr, size_t new_size, size_t old_size, unsigned int alignment) {
+  return realloc(ptr, new_size);
+}
+
+void roc_dealloc(void *ptr, unsigned int alignment) { free(ptr); }
+
+void roc_panic(struct RocStr *msg, unsigned int tag_id) {
+  fprintf(stderr, "Roc crashed (tag id %u)\n", tag_id);
+  exit(1);
+}
+
+void *roc_memcpy(void *dest, const void *src, size_t n) { return memcpy(dest, src, n); }
+
+void *roc_memset(void *dest, int c, size_t n) { return memset(dest, c, n); }
+"#;
+
+fn rust_host(entrypoints: &[Entrypoint], effects: &[Effect]) -> String {
+    let mut out = String::new();
+
+    out.push_str(
+        "#![allow(non_snake_case)]\n\nuse core::ffi::c_void;\nuse roc_std::{RocList, RocStr};\n\n",
+    );
+
+    out.push_str("extern \"C\" {\n");
+    for entrypoint in entrypoints {
+        let name = &entrypoint.name;
+
+        match entrypoint.kind {
+            EntrypointKind::Value | EntrypointKind::Effect => {
+                let _ = writeln!(
+                    out,
+                    "    fn roc__{name}_1_exposed_generic(output: *mut u8);"
+                );
+                let _ = writeln!(out, "    fn roc__{name}_1_exposed_size() -> i64;");
+            }
+            EntrypointKind::Function => {
+                let _ = writeln!(
+                    out,
+                    "    // TODO: declare `roc__{name}_1_exposed_generic` with the glue types of its arguments"
+                );
+            }
+        }
+
+        if let EntrypointKind::Effect = entrypoint.kind {
+            let _ = writeln!(
+                out,
+                "    fn roc__{name}_0_caller(flags: *const u8, closure_data: *const u8, output: *mut u8);"
+            );
+            let _ = writeln!(out, "    fn roc__{name}_0_result_size() -> i64;");
+        }
+    }
+    out.push_str("}\n\n");
+
+    out.push_str(RUST_HOST_FUNCTIONS);
+
+    for effect in effects {
+        let args: Vec<String> = effect
+            .args
+            .iter()
+            .enumerate()
+            .filter(|(_, arg)| **arg != HostType::Unit)
+            .map(|(index, arg)| format!("_arg{}: {}", index, arg.rust_arg()))
+            .collect();
+
+        let ret = match effect.ret {
+            HostType::Unit => String::new(),
+            // Roc only takes a reference of its own, so it mustn't be dropped when it's returned
+            HostType::Str | HostType::List(_) if effect.ret_ownership == Ownership::Borrowed => {
+                format!(" -> core::mem::ManuallyDrop<{}>", effect.ret.rust())
+            }
+            ref ret => format!(" -> {}", ret.rust()),
+        };
+
+        // `Owned` arguments are the host's now, so take them over and let Rust drop them
+        let mut taken_over = String::new();
+        for (index, (arg, ownership)) in effect.args.iter().zip(&effect.arg_ownership).enumerate() {
+            if *ownership == Ownership::Owned && matches!(arg, HostType::Str | HostType::List(_)) {
+                let _ = writeln!(
+                    taken_over,
+                    "    let _arg{index} = unsafe {{ core::ptr::read(_arg{index}) }};"
+                );
+            }
+        }
+
+        let _ = writeln!(out, "\n/// {} : {}", effect.name, effect.annotation);
+        if effect.intrinsic {
+            let _ = writeln!(out, "// {}", INTRINSIC_NOTE);
+        }
+        if effect.pure {
+            let _ = writeln!(out, "// {}", PURE_NOTE);
+        }
+        for note in effect.ownership_notes("_arg") {
+            let _ = writeln!(out, "// {}", note);
+        }
+        for todo in effect
+            .args
+            .iter()
+            .chain([&effect.ret])
+            .filter_map(HostType::todo)
+        {
+            let _ = writeln!(out, "// TODO: {}", todo);
+        }
+        let _ = writeln!(
+            out,
+            "#[no_mangle]\npub extern \"C\" fn roc_fx_{}({}){} {{\n{}    todo!(\"implement the `{}` effect\")\n}}",
+            effect.name,
+            args.join(", "),
+            ret,
+            taken_over,
+            effect.name,
+        );
+    }
+
+    out.push_str("\n#[no_mangle]\npub extern \"C\" fn rust_main() -> i32 {\n");
+    for entrypoint in entrypoints {
+        let name = &entrypoint.name;
+
+        if let Some(annotation) = &entrypoint.annotation {
+            let _ = writeln!(out, "    // {name} : {annotation}");
+        }
+
+        match entrypoint.kind {
+            EntrypointKind::Value => {
+                let _ = writeln!(
+                    out,
+                    "    let mut {name} = vec![0u8; unsafe {{ roc__{name}_1_exposed_size() }} as usize];\n    unsafe {{ roc__{name}_1_exposed_generic({name}.as_mut_ptr()) }};\n    // TODO: read the result out of `{name}`, using its glue type\n"
+                );
+            }
+            EntrypointKind::Effect => {
+                let _ = writeln!(
+                    out,
+                    "    let mut closure = vec![0u8; unsafe {{ roc__{name}_1_exposed_size() }} as usize];\n    unsafe {{ roc__{name}_1_exposed_generic(closure.as_mut_ptr()) }};\n\n    let mut {name} = vec![0u8; unsafe {{ roc__{name}_0_result_size() }} as usize];\n    unsafe {{ roc__{name}_0_caller(std::ptr::null(), closure.as_ptr(), {name}.as_mut_ptr()) }};\n    // TODO: read the result out of `{name}`, using its glue type\n"
+                );
+            }
+            EntrypointKind::Function => {
+                let _ = writeln!(out, "    // TODO: call `{name}` with its arguments\n");
+            }
+        }
+    }
+    out.push_str("    // Exit code\n    0\n}\n");
+
+    out
+}
+
+fn c_host(entrypoints: &[Entrypoint], effects: &[Effect]) -> String {
+    let mut out = String::new();
+
+    out.push_str(
+        "#include <stdbool.h>\n#include <stdint.h>\n#include <stdio.h>\n#include <stdlib.h>\n#include <string.h>\n\n",
+    );
+    out.push_str("struct RocStr {\n  char *bytes;\n  size_t len;\n  size_t capacity;\n};\n\n");
+    out.push_str("struct RocList {\n  void *elements;\n  size_t len;\n  size_t capacity;\n};\n\n");
+
+    for entrypoint in entrypoints {
+        let name = &entrypoint.name;
+
+        match entrypoint.kind {
+            EntrypointKind::Value | EntrypointKind::Effect => {
+                let _ = writeln!(
+                    out,
+                    "extern void roc__{name}_1_exposed_generic(void *output);\nextern int64_t roc__{name}_1_exposed_size(void);"
+                );
+            }
+            EntrypointKind::Function => {
+                let _ = writeln!(
+                    out,
+                    "// TODO: declare `roc__{name}_1_exposed_generic` with the glue types of its arguments"
+                );
+            }
+        }
+
+        if let EntrypointKind::Effect = entrypoint.kind {
+            let _ = writeln!(
+                out,
+                "extern void roc__{name}_0_caller(const void *flags, const void *closure_data, void *output);\nextern int64_t roc__{name}_0_result_size(void);"
+            );
+        }
+    }
+    out.push('\n');
+
+    out.push_str(C_HOST_FUNCTIONS);
+
+    for effect in effects {
+        let args: Vec<String> = effect
+            .args
+            .iter()
+            .enumerate()
+            .filter(|(_, arg)| **arg != HostType::Unit)
+            .map(|(index, arg)| {
+                let arg = arg.c_arg();
+                let separator = if arg.ends_with('*') { "" } else { " " };
+
+                format!("{}{}arg{}", arg, separator, index)
+            })
+            .collect();
+
+        let args = if args.is_empty() {
+            "void".to_string()
+        } else {
+            args.join(", ")
+        };
+
+        let _ = writeln!(out, "\n// {} : {}", effect.name, effect.annotation);
+        if effect.intrinsic {
+            let _ = writeln!(out, "// {}", INTRINSIC_NOTE);
+        }
+        if effect.pure {
+            let _ = writeln!(out, "// {}", PURE_NOTE);
+        }
+        for note in effect.ownership_notes("arg") {
+            let _ = writeln!(out, "// {}", note);
+        }
+        for todo in effect
+            .args
+            .iter()
+            .chain([&effect.ret])
+            .filter_map(HostType::todo)
+        {
+            let _ = writeln!(out, "// TODO: {}", todo);
+        }
+        let _ = writeln!(
+            out,
+            "{} roc_fx_{}({}) {{\n  fprintf(stderr, \"TODO: implement the `{}` effect\\n\");\n  abort();\n}}",
+            effect.ret.c(),
+            effect.name,
+            args,
+            effect.name,
+        );
+    }
+
+    out.push_str("\nint main(void) {\n");
+    for entrypoint in entrypoints {
+        let name = &entrypoint.name;
+
+        if let Some(annotation) = &entrypoint.annotation {
+            let _ = writeln!(out, "  // {name} : {annotation}");
+        }
+
+        match entrypoint.kind {
+            EntrypointKind::Value => {
+                let _ = writeln!(
+                    out,
+                    "  void *{name} = malloc(roc__{name}_1_exposed_size());\n  roc__{name}_1_exposed_generic({name});\n  // TODO: read the result out of `{name}`, using its glue type\n  free({name});\n"
+                );
+            }
+            EntrypointKind::Effect => {
+                let _ = writeln!(
+                    out,
+                    "  void *{name}_closure = malloc(roc__{name}_1_exposed_size());\n  roc__{name}_1_exposed_generic({name}_closure);\n\n  void *{name} = malloc(roc__{name}_0_result_size());\n  roc__{name}_0_caller(NULL, {name}_closure, {name});\n  // TODO: read the result out of `{name}`, using its glue type\n  free({name});\n  free({name}_closure);\n"
+                );
+            }
+            EntrypointKind::Function => {
+                let _ = writeln!(out, "  // TODO: call `{name}` with its arguments\n");
+            }
+        }
+    }
+    out.push_str("  return 0;\n}\n");
+
+    out
+}
+
+const RUST_CARGO_TOML: &str = r#"[package]
+name = "host"
+edition = "2021"
+links = "app"
+version = "0.0.1"
+
+[lib]
+name = "host"
+path = "src/lib.rs"
+crate-type = ["staticlib", "rlib"]
+
+[[bin]]
+name = "host"
+path = "src/main.rs"
+
+[dependencies]
+libc = "0.2"
+roc_std = { git = "https://github.com/roc-lang/roc" }
+
+[workspace]
+"#;
+
+const RUST_BUILD_RS: &str = r#"fn main() {
+    #[cfg(not(windows))]
+    println!("cargo:rustc-link-lib=dylib=app");
+
+    #[cfg(windows)]
+    println!("cargo:rustc-link-lib=dylib=libapp");
+
+    println!("cargo:rustc-link-search=.");
+}
+"#;
+
+const RUST_MAIN_RS: &str = r#"fn main() {
+    std::process::exit(host::rust_main() as _);
+}
+"#;
+
+/// Write `contents` to `path`, unless there's something there already; a stub is only a
+/// starting point, so it should never overwrite a host that has been filled in.
+fn write_new(path: &Path, contents: &str) -> io::Result<()> {
+    if path.exists() {
+        println!("Skipped {}, because it already exists.", path.display());
+
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, contents)
+}
+
+/// Generate a host skeleton in `language` for the platform whose main module is at
+/// `platform_path`, into `output_path`. Every effect in the platform's `hosted` module gets a
+/// stub which crashes, and `main` calls each of the platform's entrypoints.
+pub fn generate_stub(
+    platform_path: &Path,
+    output_path: &Path,
+    language: StubLanguage,
+) -> io::Result<()> {
+    let arena = Bump::new();
+    let entrypoints = entrypoints(&arena, platform_path)?;
+    let effects = hosted_effects(platform_path.parent().unwrap_or_else(|| Path::new(".")))?;
+    let signatures = signatures(&effects);
+
+    let host_path = match language {
+        StubLanguage::Rust => {
+            let host_dir = output_path.join("host");
+
+            write_new(&host_dir.join("Cargo.toml"), RUST_CARGO_TOML)?;
+            write_new(&host_dir.join("build.rs"), RUST_BUILD_RS)?;
+            write_new(&host_dir.join("src").join("main.rs"), RUST_MAIN_RS)?;
+            write_new(
+                &host_dir.join("src").join("lib.rs"),
+                &rust_host(&entrypoints, &effects),
+            )?;
+            write_new(
+                &host_dir.join(EFFECT_SIGNATURES_FILE),
+                &effect_signatures_file(&signatures),
+            )?;
+
+            host_dir
+        }
+        StubLanguage::C => {
+            let host_path = output_path.join("host.c");
+
+            write_new(&host_path, &c_host(&entrypoints, &effects))?;
+            write_new(
+                &output_path.join(EFFECT_SIGNATURES_FILE),
+                &effect_signatures_file(&signatures),
+            )?;
+
+            host_path
+        }
+    };
+
+    println!(
+        "🎉 Generated a host with {} effect {} in:\n\n\t{}",
+        effects.len(),
+        if effects.len() == 1 { "stub" } else { "stubs" },
+        host_path.display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{generate_stub, parse_effect_signatures, StubLanguage, EFFECT_SIGNATURES_FILE};
+    use std::fs;
+    use std::path::Path;
+
+    const MAIN_ROC: &str = r#"platform "stub-test"
+    requires {} { main : Effect.Effect {} }
+    exposes []
+    packages {}
+    imports [pf.Effect]
+    provides [mainForHost]
+
+mainForHost : Effect.Effect {} as Fx
+mainForHost = main
+"#;
+
+    const EFFECT_ROC: &str = r#"hosted Effect
+    exposes [Effect, after, map, always, putLine, getLine, sqrt]
+    imports []
+    generates Effect with [after, map, always]
+
+putLine : Owned Str -> Effect {}
+
+getLine : Effect Str
+
+sqrt : F64 -> Pure F64
+"#;
+
+    fn write_platform(dir: &Path) -> std::path::PathBuf {
+        let main_path = dir.join("main.roc");
+
+        fs::write(&main_path, MAIN_ROC).unwrap();
+        fs::write(dir.join("Effect.roc"), EFFECT_ROC).unwrap();
+
+        main_path
+    }
+
+    fn assert_contains(source: &str, expected: &str) {
+        assert!(
+            source.contains(expected),
+            "expected the generated host to contain\n\n{}\n\nbut it was\n\n{}",
+            expected,
+            source
+        );
+    }
+
+    #[test]
+    fn c_stub_for_a_small_platform() {
+        let dir = tempfile::tempdir().unwrap();
+        let platform_path = write_platform(dir.path());
+        let output_path = dir.path().join("out");
+
+        generate_stub(&platform_path, &output_path, StubLanguage::C).unwrap();
+
+        let host = fs::read_to_string(output_path.join("host.c")).unwrap();
+
+        assert_contains(
+            &host,
+            "void *roc_alloc(size_t size, unsigned int alignment)",
+        );
+        assert_contains(
+            &host,
+            "extern void roc__mainForHost_0_caller(const void *flags, const void *closure_data, void *output);",
+        );
+        assert_contains(&host, "void roc_fx_putLine(struct RocStr *arg0) {");
+        assert_contains(&host, "// `arg0` is `Owned`");
+        assert_contains(&host, "struct RocStr roc_fx_getLine(void) {");
+        assert_contains(&host, "double roc_fx_sqrt(double arg0) {");
+        assert_contains(
+            &host,
+            "roc__mainForHost_0_caller(NULL, mainForHost_closure, mainForHost);",
+        );
+
+        // `after`, `map` and `always` are generated in Roc, so the host doesn't implement them
+        assert!(!host.contains("roc_fx_after"));
+        assert!(!host.contains("roc_fx_map"));
+        assert!(!host.contains("roc_fx_always"));
+
+        // every opened brace is closed again
+        assert_eq!(host.matches('{').count(), host.matches('}').count());
+    }
+
+    #[test]
+    fn rust_stub_for_a_small_platform() {
+        let dir = tempfile::tempdir().unwrap();
+        let platform_path = write_platform(dir.path());
+        let output_path = dir.path().join("out");
+
+        generate_stub(&platform_path, &output_path, StubLanguage::Rust).unwrap();
+
+        let host_dir = output_path.join("host");
+        let lib = fs::read_to_string(host_dir.join("src").join("lib.rs")).unwrap();
+
+        assert!(host_dir.join("Cargo.toml").exists());
+        assert!(host_dir.join("build.rs").exists());
+        assert!(host_dir.join("src").join("main.rs").exists());
+
+        assert_contains(
+            &lib,
+            "fn roc__mainForHost_1_exposed_generic(output: *mut u8);",
+        );
+        assert_contains(&lib, "pub extern \"C\" fn roc_fx_putLine(_arg0: &RocStr) {");
+        assert_contains(&lib, "    let _arg0 = unsafe { core::ptr::read(_arg0) };");
+        assert_contains(&lib, "pub extern \"C\" fn roc_fx_getLine() -> RocStr {");
+        assert_contains(&lib, "pub extern \"C\" fn roc_fx_sqrt(_arg0: f64) -> f64 {");
+        assert_contains(&lib, "pub extern \"C\" fn rust_main() -> i32 {");
+
+        assert_eq!(lib.matches('{').count(), lib.matches('}').count());
+
+        let signatures = fs::read_to_string(host_dir.join(EFFECT_SIGNATURES_FILE)).unwrap();
+
+        assert_eq!(
+            parse_effect_signatures(&signatures),
+            vec![
+                ("putLine".to_string(), "Owned Str -> Effect {}".to_string()),
+                ("getLine".to_string(), "Effect Str".to_string()),
+                ("sqrt".to_string(), "F64 -> Pure F64".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn stub_does_not_overwrite_a_host() {
+        let dir = tempfile::tempdir().unwrap();
+        let platform_path = write_platform(dir.path());
+        let output_path = dir.path().join("out");
+        let host_path = output_path.join("host.c");
+
+        fs::create_dir_all(&output_path).unwrap();
+        fs::write(&host_path, "// filled in by hand\n").unwrap();
+
+        generate_stub(&platform_path, &output_path, StubLanguage::C).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&host_path).unwrap(),
+            "// filled in by hand\n"
+        );
+    }
+
+    #[test]
+    fn stub_needs_a_platform_module() {
+        let dir = tempfile::tempdir().unwrap();
+        write_platform(dir.path());
+
+        let error = generate_stub(
+            &dir.path().join("Effect.roc"),
+            &dir.path().join("out"),
+            StubLanguage::C,
+        )
+        .unwrap_err();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
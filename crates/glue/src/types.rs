@@ -923,6 +923,9 @@ impl From<Architecture> for roc_type::Architecture {
             Architecture::Wasm32 => roc_type::Architecture::Wasm32,
             Architecture::X86_32 => roc_type::Architecture::X86x32,
             Architecture::X86_64 => roc_type::Architecture::X86x64,
+            Architecture::S390x => {
+                unreachable!("glue is only generated for little-endian targets")
+            }
         }
     }
 }
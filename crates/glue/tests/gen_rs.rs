@@ -84,6 +84,26 @@ mod test_gen_rs {
                         pub z: roc_std::RocList<u8>,
                     }
 
+                    #[cfg(any(
+                        target_arch = "arm",
+                        target_arch = "aarch64",
+                        target_arch = "wasm32",
+                        target_arch = "x86",
+                        target_arch = "x86_64"
+                    ))]
+                    #[cfg(feature = "atomic-refcounts")]
+                    unsafe impl Send for Outer {}
+
+                    #[cfg(any(
+                        target_arch = "arm",
+                        target_arch = "aarch64",
+                        target_arch = "wasm32",
+                        target_arch = "x86",
+                        target_arch = "x86_64"
+                    ))]
+                    #[cfg(feature = "atomic-refcounts")]
+                    unsafe impl Sync for Outer {}
+
                     #[cfg(any(
                         target_arch = "arm",
                         target_arch = "aarch64",
@@ -171,6 +191,26 @@ mod test_gen_rs {
                         pub z: roc_std::RocList<u8>,
                     }
 
+                    #[cfg(any(
+                        target_arch = "arm",
+                        target_arch = "aarch64",
+                        target_arch = "wasm32",
+                        target_arch = "x86",
+                        target_arch = "x86_64"
+                    ))]
+                    #[cfg(feature = "atomic-refcounts")]
+                    unsafe impl Send for R1 {}
+
+                    #[cfg(any(
+                        target_arch = "arm",
+                        target_arch = "aarch64",
+                        target_arch = "wasm32",
+                        target_arch = "x86",
+                        target_arch = "x86_64"
+                    ))]
+                    #[cfg(feature = "atomic-refcounts")]
+                    unsafe impl Sync for R1 {}
+
                     #[cfg(any(
                         target_arch = "arm",
                         target_arch = "aarch64",
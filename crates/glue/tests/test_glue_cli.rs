@@ -138,6 +138,141 @@ mod glue_cli_run {
         "#),
     }
 
+    #[test]
+    fn csharp_glue_for_basic_record() {
+        let out_dir = generate_glue_with_spec("CSharpGlue.roc", &fixtures_dir("basic-record"));
+        let generated = fs::read_to_string(out_dir.path().join("Roc.cs")).unwrap();
+
+        assert!(generated.contains(indoc!(
+            r#"
+            [StructLayout(LayoutKind.Explicit, Size = 32)]
+            public struct MyRcd
+            {
+                [FieldOffset(0)] public RocU128 b;
+                [FieldOffset(16)] public ulong a;
+            }
+            "#
+        )));
+        assert!(generated.contains(indoc!(
+            r#"
+                [DllImport(LibraryName)]
+                private static extern void roc__mainForHost_1_exposed_generic(MyRcd* ret);
+
+                public static MyRcd MainForHost()
+            "#
+        )));
+    }
+
+    #[test]
+    fn python_glue_for_basic_record() {
+        let out_dir = generate_glue_with_spec("PythonGlue.roc", &fixtures_dir("basic-record"));
+        let generated =
+            fs::read_to_string(out_dir.path().join("roc_app").join("__init__.py")).unwrap();
+
+        assert!(out_dir.path().join("pyproject.toml").exists());
+        assert!(generated.contains(indoc!(
+            r#"
+            typedef struct {
+                RocU128 b;
+                uint64_t a;
+                uint8_t _padding24[8];
+            } MyRcd;
+            "#
+        )));
+        assert!(generated.contains("void roc__mainForHost_1_exposed_generic(MyRcd *ret);"));
+        assert!(generated.contains(indoc!(
+            r#"
+            @dataclass
+            class MyRcd:
+                b: int
+                a: int
+            "#
+        )));
+        assert!(generated.contains(indoc!(
+            r#"
+            def main_for_host() -> MyRcd:
+                ret = ffi.new('MyRcd *')
+                _library().roc__mainForHost_1_exposed_generic(ret)
+            "#
+        )));
+    }
+
+    #[test]
+    fn conformance_suite_for_union_with_padding() {
+        let glue_out = run_conformance_suite(&fixtures_dir("union-with-padding"));
+
+        assert!(glue_out.status.success(), "bad status {:?}", glue_out);
+        assert!(glue_out
+            .stdout
+            .contains("All 1 exposed types round-tripped through Roc the way glue laid them out."));
+    }
+
+    #[test]
+    fn conformance_suite_for_rocresult() {
+        let glue_out = run_conformance_suite(&fixtures_dir("rocresult"));
+
+        assert!(glue_out.status.success(), "bad status {:?}", glue_out);
+        assert!(glue_out
+            .stdout
+            .contains("All 2 exposed types round-tripped through Roc the way glue laid them out."));
+    }
+
+    /// Runs `roc glue --verify` on a fixture, which generates its conformance suite and runs it
+    fn run_conformance_suite(platform_dir: &Path) -> Out {
+        let out_dir = tempfile::tempdir().unwrap();
+        let glue_spec = platform_dir
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("src")
+            .join("RustGlue.roc");
+
+        run_glue([
+            "glue".to_string(),
+            "--verify".to_string(),
+            glue_spec.to_str().unwrap().to_string(),
+            out_dir.path().to_str().unwrap().to_string(),
+            platform_dir
+                .join("platform.roc")
+                .to_str()
+                .unwrap()
+                .to_string(),
+        ])
+    }
+
+    /// Runs one of the glue specs in src/ which aren't used for the fixtures, and returns the
+    /// directory it generated its files in
+    fn generate_glue_with_spec(spec_file_name: &str, platform_dir: &Path) -> tempfile::TempDir {
+        let out_dir = tempfile::tempdir().unwrap();
+        let glue_spec = platform_dir
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("src")
+            .join(spec_file_name);
+
+        let glue_out = run_glue([
+            "glue".to_string(),
+            glue_spec.to_str().unwrap().to_string(),
+            out_dir.path().to_str().unwrap().to_string(),
+            platform_dir
+                .join("platform.roc")
+                .to_str()
+                .unwrap()
+                .to_string(),
+        ]);
+
+        assert!(glue_out.status.success(), "bad status {:?}", glue_out);
+
+        out_dir
+    }
+
     fn check_for_tests(all_fixtures: &mut roc_collections::VecSet<String>) {
         use roc_collections::VecSet;
 
@@ -20,7 +20,7 @@ use std::{
 
 use crate::{
     align_by_constraint, align_to_offset_by_constraint, load_struct_inplace,
-    load_struct_inplace_mut, load_structs_inplace_mut, open_mmap, open_mmap_mut,
+    load_struct_inplace_mut, load_structs_inplace_mut, open_mmap, open_mmap_mut, LinkLog,
 };
 
 const MIN_SECTION_ALIGNMENT: usize = 0x40;
@@ -1156,6 +1156,7 @@ pub(crate) fn surgery_elf(
     executable_path: &Path,
     verbose: bool,
     time: bool,
+    log: LinkLog,
 ) {
     let app_obj = match object::File::parse(roc_app_bytes) {
         Ok(obj) => obj,
@@ -1192,7 +1193,7 @@ pub(crate) fn surgery_elf(
     let out_gen_start = Instant::now();
     let mut offset = 0;
 
-    surgery_elf_help(verbose, &md, &mut exec_mmap, &mut offset, app_obj);
+    surgery_elf_help(verbose, log, &md, &mut exec_mmap, &mut offset, app_obj);
 
     let out_gen_duration = out_gen_start.elapsed();
     let flushing_data_start = Instant::now();
@@ -1238,8 +1239,16 @@ pub(crate) fn surgery_elf(
     }
 }
 
+fn symbol_name<'a>(app_obj: &object::File<'a>, index: SymbolIndex) -> &'a str {
+    app_obj
+        .symbol_by_index(index)
+        .and_then(|sym| sym.name())
+        .unwrap_or("<unnamed>")
+}
+
 fn surgery_elf_help(
     verbose: bool,
+    log: LinkLog,
     md: &Metadata,
     exec_mmap: &mut MmapMut,
     offset_ref: &mut usize, // TODO return this instead of taking a mutable reference to it
@@ -1343,6 +1352,15 @@ fn surgery_elf_help(
                 virt_offset
             )
         }
+        log.event(
+            "place-section",
+            &[
+                ("name", &sec.name().unwrap_or_default()),
+                ("offset", &format!("{:#x}", offset)),
+                ("vaddr", &format!("{:#x}", virt_offset)),
+                ("size", &sec.size()),
+            ],
+        );
         section_offset_map.insert(sec.index(), (offset, virt_offset));
         for sym in symbols.iter() {
             if sym.section() == SymbolSection::Section(sec.index()) {
@@ -1457,6 +1475,25 @@ fn surgery_elf_help(
                             );
                             println!("\t\tFinal relocation target offset: {target:+x}");
                         }
+                        log.event(
+                            "relocation",
+                            &[
+                                ("section", &sec.name().unwrap_or_default()),
+                                ("vaddr", &format!("{:#x}", virt_base)),
+                                ("kind", &format!("{:?}", rel.1.kind())),
+                                ("bits", &rel.1.size()),
+                                ("symbol", &symbol_name(&app_obj, index)),
+                                (
+                                    "defined_in",
+                                    &if symbol_vaddr_map.contains_key(&index) {
+                                        "app"
+                                    } else {
+                                        "host"
+                                    },
+                                ),
+                                ("value", &format!("{:#x}", target)),
+                            ],
+                        );
                         match rel.1.size() {
                             32 => {
                                 let data = (target as i32).to_le_bytes();
@@ -1476,7 +1513,15 @@ fn surgery_elf_help(
 
                         match app_obj.symbol_by_index(index) {
                             Ok(sym) if ALWAYS_LINKED.contains(&sym.name().unwrap_or_default()) => {
-                                continue
+                                log.event(
+                                    "skip-relocation",
+                                    &[
+                                        ("section", &sec.name().unwrap_or_default()),
+                                        ("symbol", &sym.name().unwrap_or_default()),
+                                        ("reason", &"always-linked"),
+                                    ],
+                                );
+                                continue;
                             }
                             _ => {
                                 internal_error!(
@@ -1526,6 +1571,35 @@ fn surgery_elf_help(
     let new_text_section_vaddr = new_rodata_section_vaddr as u64 + new_rodata_section_size;
     let new_text_section_size = new_sh_offset as u64 - new_text_section_offset as u64;
 
+    log.event(
+        "move-section-headers",
+        &[
+            ("from", &format!("{:#x}", sh_offset)),
+            ("to", &format!("{:#x}", new_sh_offset)),
+            ("count", &(sh_num as usize + new_section_count)),
+        ],
+    );
+    log.event(
+        "add-segment",
+        &[
+            ("kind", &"rodata"),
+            ("offset", &format!("{:#x}", new_rodata_section_offset)),
+            ("vaddr", &format!("{:#x}", new_rodata_section_vaddr)),
+            ("file_size", &new_rodata_section_size),
+            ("memory_size", &new_rodata_section_virtual_size),
+        ],
+    );
+    log.event(
+        "add-segment",
+        &[
+            ("kind", &"text"),
+            ("offset", &format!("{:#x}", new_text_section_offset)),
+            ("vaddr", &format!("{:#x}", new_text_section_vaddr)),
+            ("file_size", &new_text_section_size),
+            ("memory_size", &new_text_section_size),
+        ],
+    );
+
     // set the new rodata section header
     section_headers[section_headers.len() - 2] = elf::SectionHeader64 {
         sh_name: endian::U32::new(LE, 0),
@@ -1620,7 +1694,18 @@ fn surgery_elf_help(
             );
         }
 
-        for s in md.surgeries.get(func_name).unwrap_or(&vec![]) {
+        let surgeries = md.surgeries.get(func_name).map_or(&[][..], Vec::as_slice);
+
+        log.event(
+            "replace-placeholder",
+            &[
+                ("symbol", func_name),
+                ("vaddr", &format!("{:#x}", func_virt_offset)),
+                ("call_sites", &surgeries.len()),
+            ],
+        );
+
+        for s in surgeries {
             if verbose {
                 println!("\tPerforming surgery: {:+x?}", s);
             }
@@ -1628,6 +1713,21 @@ fn surgery_elf_help(
                 VirtualOffset::Relative(vs) => (vs + md.added_byte_count) as i64,
                 VirtualOffset::Absolute => 0,
             };
+            log.event(
+                "patch-call",
+                &[
+                    ("symbol", func_name),
+                    (
+                        "offset",
+                        &format!("{:#x}", s.file_offset + md.added_byte_count),
+                    ),
+                    ("bytes", &s.size),
+                    (
+                        "jump",
+                        &format!("{:#x}", func_virt_offset as i64 - surgery_virt_offset),
+                    ),
+                ],
+            );
             match s.size {
                 4 => {
                     let target = (func_virt_offset as i64 - surgery_virt_offset) as i32;
@@ -1665,6 +1765,14 @@ fn surgery_elf_help(
                 println!("\tPLT: {:+x}, {:+x}", plt_off, plt_vaddr);
                 println!("\tTarget Jump: {:+x}", target);
             }
+            log.event(
+                "patch-plt",
+                &[
+                    ("symbol", func_name),
+                    ("vaddr", &format!("{:#x}", plt_vaddr)),
+                    ("jump", &format!("{:#x}", target)),
+                ],
+            );
             let data = target.to_le_bytes();
             exec_mmap[plt_off] = 0xE9;
             exec_mmap[plt_off + 1..plt_off + jmp_inst_len].copy_from_slice(&data);
@@ -1674,6 +1782,10 @@ fn surgery_elf_help(
         }
 
         if let Some(i) = md.dynamic_symbol_indices.get(func_name) {
+            log.event(
+                "update-symbol",
+                &[("table", &"dynsym"), ("symbol", func_name), ("index", i)],
+            );
             let sym = load_struct_inplace_mut::<elf::Sym64<LE>>(
                 exec_mmap,
                 dynsym_offset as usize + *i as usize * mem::size_of::<elf::Sym64<LE>>(),
@@ -1691,6 +1803,10 @@ fn surgery_elf_help(
 
         // Also update symbols in the regular symbol table as well.
         if let Some(i) = md.static_symbol_indices.get(func_name) {
+            log.event(
+                "update-symbol",
+                &[("table", &"symtab"), ("symbol", func_name), ("index", i)],
+            );
             let sym = load_struct_inplace_mut::<elf::Sym64<LE>>(
                 exec_mmap,
                 symtab_offset as usize + *i as usize * mem::size_of::<elf::Sym64<LE>>(),
@@ -1889,6 +2005,7 @@ mod tests {
             &dir.join("final"),
             false,
             false,
+            LinkLog::default(),
         );
     }
 
@@ -6,7 +6,7 @@
 use memmap2::{Mmap, MmapMut};
 use object::Object;
 use roc_error_macros::internal_error;
-use roc_load::{EntryPoint, ExecutionMode, ExposedToHost, LoadConfig, Threading};
+use roc_load::{EntryPoint, ExecutionMode, ExposedToHost, LoadConfig, OverflowMode, Threading};
 use roc_module::symbol::Interns;
 use roc_packaging::cache::RocCacheDir;
 use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
@@ -73,14 +73,55 @@ fn metadata_file_name(target: &Triple) -> String {
     format!("metadata_{}.rm", target_triple_str.unwrap_or("unknown"))
 }
 
+/// Structured output for `--verbose-link`. Each decision the surgical linker makes is printed to
+/// stderr as one `link: <event> key=value ...` line, so link failures in a user's report can be
+/// traced back to the symbol, relocation, or section that caused them.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LinkLog {
+    enabled: bool,
+}
+
+impl LinkLog {
+    pub fn new(enabled: bool) -> Self {
+        LinkLog { enabled }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) fn event(&self, event: &str, fields: &[(&str, &dyn std::fmt::Display)]) {
+        if self.enabled {
+            use std::fmt::Write;
+
+            let mut line = format!("link: {}", event);
+
+            for (key, value) in fields {
+                let _ = write!(line, " {}={}", key, value);
+            }
+
+            eprintln!("{}", line);
+        }
+    }
+}
+
 pub fn link_preprocessed_host(
     target: &Triple,
     platform_path: &Path,
     roc_app_bytes: &[u8],
     binary_path: &Path,
+    log: LinkLog,
 ) {
     let metadata = platform_path.with_file_name(metadata_file_name(target));
-    surgery(roc_app_bytes, &metadata, binary_path, false, false, target)
+    surgery(
+        roc_app_bytes,
+        &metadata,
+        binary_path,
+        false,
+        false,
+        log,
+        target,
+    )
 }
 
 // Exposed function to load a platform file and generate a stub lib for it.
@@ -105,6 +146,10 @@ pub fn generate_stub_lib(
             palette: DEFAULT_PALETTE,
             threading: Threading::AllAvailable,
             exec_mode: ExecutionMode::Executable,
+            recover_from_syntax_errors: false,
+            static_data: false,
+            overflow: OverflowMode::Trap,
+            fold_constants: true,
         },
     )
     .unwrap_or_else(|problem| todo!("{:?}", problem));
@@ -249,6 +294,80 @@ impl ExposedSymbols {
     }
 }
 
+/// The `roc_` symbols of a host: the ones it defines (e.g. `roc_alloc`), and the ones it expects
+/// the Roc side to define (e.g. `roc__mainForHost_1_exposed_generic`)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HostSymbols {
+    pub defined: Vec<String>,
+    pub undefined: Vec<String>,
+}
+
+impl HostSymbols {
+    fn add_object<'a>(&mut self, object: &object::File<'a, &'a [u8]>) {
+        for sym in object.symbols() {
+            let name = match sym.name() {
+                // remove the leading underscore of mach-o, and potentially trailing "@version"
+                Ok(name) => name.trim_start_matches('_').split('@').next().unwrap(),
+                Err(_) => continue,
+            };
+
+            if !name.starts_with("roc_") {
+                continue;
+            }
+
+            if sym.is_definition() {
+                self.defined.push(name.to_string());
+            } else if sym.is_undefined() {
+                self.undefined.push(name.to_string());
+            }
+        }
+    }
+}
+
+/// Reads the `roc_` symbols of a host object file, or of all the objects in a static library
+pub fn host_symbols(host_path: &Path) -> std::io::Result<HostSymbols> {
+    use object::read::archive::ArchiveFile;
+    use std::io::{Error, ErrorKind};
+
+    let data = std::fs::read(host_path)?;
+    let invalid = |err: object::Error| Error::new(ErrorKind::InvalidData, err.to_string());
+
+    let mut symbols = HostSymbols::default();
+
+    match ArchiveFile::parse(&*data) {
+        Ok(archive) => {
+            for member in archive.members() {
+                let member_data = member.and_then(|member| member.data(&*data));
+                let member_data = member_data.map_err(invalid)?;
+
+                // archives can contain other things than objects, like a symbol table
+                if let Ok(object) = object::File::parse(member_data) {
+                    symbols.add_object(&object);
+                }
+            }
+        }
+        Err(_) => {
+            let object = object::File::parse(&*data).map_err(invalid)?;
+
+            symbols.add_object(&object);
+        }
+    }
+
+    symbols.defined.sort_unstable();
+    symbols.defined.dedup();
+
+    // in a static library, one object can define what another one uses
+    let defined = &symbols.defined;
+    symbols
+        .undefined
+        .retain(|name| defined.binary_search(name).is_err());
+
+    symbols.undefined.sort_unstable();
+    symbols.undefined.dedup();
+
+    Ok(symbols)
+}
+
 fn generate_dynamic_lib(target: &Triple, stub_dll_symbols: &[String], stub_lib_path: &Path) {
     if !stub_lib_is_up_to_date(target, stub_lib_path, stub_dll_symbols) {
         let bytes = crate::generate_dylib::generate(target, stub_dll_symbols)
@@ -489,11 +608,19 @@ fn surgery(
     executable_path: &Path,
     verbose: bool,
     time: bool,
+    log: LinkLog,
     target: &Triple,
 ) {
     match target.binary_format {
         target_lexicon::BinaryFormat::Elf => {
-            crate::elf::surgery_elf(roc_app_bytes, metadata_path, executable_path, verbose, time);
+            crate::elf::surgery_elf(
+                roc_app_bytes,
+                metadata_path,
+                executable_path,
+                verbose,
+                time,
+                log,
+            );
         }
 
         target_lexicon::BinaryFormat::Macho => {
@@ -507,7 +634,7 @@ fn surgery(
         }
 
         target_lexicon::BinaryFormat::Coff => {
-            crate::pe::surgery_pe(executable_path, metadata_path, roc_app_bytes);
+            crate::pe::surgery_pe(executable_path, metadata_path, roc_app_bytes, log);
         }
 
         target_lexicon::BinaryFormat::Wasm => {
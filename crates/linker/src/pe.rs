@@ -20,7 +20,7 @@ use roc_error_macros::internal_error;
 
 use crate::{
     generate_dylib::APP_DLL, load_struct_inplace, load_struct_inplace_mut,
-    load_structs_inplace_mut, open_mmap, open_mmap_mut,
+    load_structs_inplace_mut, open_mmap, open_mmap_mut, LinkLog,
 };
 
 /// The metadata stores information about/from the host .exe because
@@ -285,7 +285,12 @@ fn relocate_to(
     }
 }
 
-pub(crate) fn surgery_pe(executable_path: &Path, metadata_path: &Path, roc_app_bytes: &[u8]) {
+pub(crate) fn surgery_pe(
+    executable_path: &Path,
+    metadata_path: &Path,
+    roc_app_bytes: &[u8],
+    log: LinkLog,
+) {
     let md = PeMetadata::read_from_file(metadata_path);
 
     let app_obj_sections = AppSections::from_data(roc_app_bytes);
@@ -348,6 +353,17 @@ pub(crate) fn surgery_pe(executable_path: &Path, metadata_path: &Path, roc_app_b
         let virtual_size = u32::max(1, length as u32);
         let size_of_raw_data = next_multiple_of(length, file_alignment) as u32;
 
+        log.event(
+            "add-section",
+            &[
+                ("kind", &format!("{:?}", kind)),
+                ("offset", &format!("{:#x}", section_file_offset)),
+                ("vaddr", &format!("{:#x}", section_virtual_address)),
+                ("file_size", &size_of_raw_data),
+                ("memory_size", &virtual_size),
+            ],
+        );
+
         match kind {
             SectionKind::Text => {
                 code_bytes_added += size_of_raw_data;
@@ -397,6 +413,30 @@ pub(crate) fn surgery_pe(executable_path: &Path, metadata_path: &Path, roc_app_b
                     address,
                 } = app_relocation;
 
+                let defined_in = if md.exports.contains_key(name) {
+                    "host"
+                } else if inter_app_relocations.contains_key(name) {
+                    "app"
+                } else if name == "___chkstk_ms" {
+                    "stack-probe"
+                } else {
+                    "import"
+                };
+
+                log.event(
+                    "relocation",
+                    &[
+                        (
+                            "offset",
+                            &format!("{:#x}", offset + *offset_in_section as usize),
+                        ),
+                        ("kind", &format!("{:?}", relocation.kind())),
+                        ("bits", &relocation.size()),
+                        ("symbol", name),
+                        ("defined_in", &defined_in),
+                    ],
+                );
+
                 if let Some(destination) = md.exports.get(name) {
                     match relocation.kind() {
                         object::RelocationKind::Relative => {
@@ -482,6 +522,7 @@ pub(crate) fn surgery_pe(executable_path: &Path, metadata_path: &Path, roc_app_b
         &symbols,
         &md.imports,
         md.thunks_start_offset_in_file,
+        log,
     );
 }
 
@@ -981,6 +1022,7 @@ fn redirect_dummy_dll_functions(
     function_definition_vas: &[(String, u64)],
     imports: &[String],
     thunks_start_offset: usize,
+    log: LinkLog,
 ) {
     // it could be that a symbol exposed by the app is not used by the host. We must skip unused symbols
     // this is an O(n^2) loop, hopefully that does not become a problem. If it does we can sort
@@ -1000,6 +1042,15 @@ fn redirect_dummy_dll_functions(
                 // update the address to a function VA
                 address_bytes.copy_from_slice(&roc_app_target_va.to_le_bytes());
 
+                log.event(
+                    "replace-placeholder",
+                    &[
+                        ("symbol", roc_app_target_name),
+                        ("thunk", &i),
+                        ("vaddr", &format!("{:#x}", roc_app_target_va)),
+                    ],
+                );
+
                 continue 'outer;
             }
         }
@@ -1723,7 +1774,12 @@ mod test {
 
         std::fs::copy(&preprocessed_host_filename, dir.join("app.exe")).unwrap();
 
-        surgery_pe(&dir.join("app.exe"), &dir.join("metadata"), &roc_app);
+        surgery_pe(
+            &dir.join("app.exe"),
+            &dir.join("metadata"),
+            &roc_app,
+            LinkLog::default(),
+        );
     }
 
     #[allow(dead_code)]
@@ -1,5 +1,6 @@
 #[cfg(not(target_family = "wasm"))]
 use {
+    crate::config::PackageConfig,
     crate::https::{self, PackageMetadata, Problem},
     roc_error_macros::internal_error,
     std::fs,
@@ -60,14 +61,18 @@ fn nixos_error_if_dynamic(url: &str, dest_dir: &Path) {
 /// have an entry for the given URL. If we do, return its info. If we don't already have it, then:
 ///
 /// - Download and decompress the compressed tarball from the given URL
+///   (or from its mirror, if the config specifies one)
 /// - Verify its bytes against the hash in the URL
 /// - Extract the tarball's contents into the appropriate cache directory
 ///
 /// Returns the path to the installed package (which will be in the cache dir somewhere), as well
 /// as the requested root module filename (optionally specified via the URL fragment).
+///
+/// In offline mode, returns Problem::NotCachedWhileOffline instead of downloading anything.
 #[cfg(not(target_family = "wasm"))]
 pub fn install_package<'a>(
     roc_cache_dir: RocCacheDir<'_>,
+    config: &PackageConfig,
     url: &'a str,
 ) -> Result<(PathBuf, Option<&'a str>), Problem> {
     let PackageMetadata {
@@ -93,16 +98,25 @@ pub fn install_package<'a>(
                 }
 
                 Ok((dest_dir, root_module_filename))
+            } else if config.offline {
+                Err(Problem::NotCachedWhileOffline)
             } else {
                 // Download into a tempdir; only move it to dest_dir if hash verification passes.
+                // The hash comes from the original URL, so a mirror can't substitute different contents.
+                let download_url = config.mirrored_url(url);
+
                 println!(
-                    "Downloading \u{001b}[36m{url}\u{001b}[0m\n    into {}\n",
+                    "Downloading \u{001b}[36m{download_url}\u{001b}[0m\n    into {}\n",
                     cache_dir.display()
                 );
                 let tempdir = tempfile::tempdir().map_err(Problem::IoErr)?;
                 let tempdir_path = tempdir.path();
-                let downloaded_hash =
-                    https::download_and_hash(url, tempdir_path, MAX_DOWNLOAD_BYTES)?;
+                let downloaded_hash = https::download_and_hash(
+                    &download_url,
+                    tempdir_path,
+                    MAX_DOWNLOAD_BYTES,
+                    config.token_for(&download_url),
+                )?;
 
                 // Download the tarball into memory and verify it.
                 // The tarball name is the hash of its contents.
@@ -162,11 +176,11 @@ pub fn install_package<'a>(
 
 #[cfg(windows)]
 // e.g. the "Roc" in %APPDATA%\\Roc
-const ROC_CACHE_DIR_NAME: &str = "Roc";
+pub(crate) const ROC_CACHE_DIR_NAME: &str = "Roc";
 
 #[cfg(not(windows))]
 // e.g. the "roc" in ~/.cache/roc
-const ROC_CACHE_DIR_NAME: &str = "roc";
+pub(crate) const ROC_CACHE_DIR_NAME: &str = "roc";
 
 /// This looks up environment variables, so it should ideally be called once and then cached!
 ///
@@ -0,0 +1,365 @@
+//! Settings for where packages get downloaded from.
+//!
+//! These are read from a config file (by default `~/.config/roc/packages.conf`, or whatever
+//! the ROC_PACKAGE_CONFIG environment variable points to), and then overridden by environment
+//! variables. The config file has one setting per line:
+//!
+//! ```text
+//! # Where to look up packages specified as "name@constraint"
+//! registry = https://packages.example.com/index.txt
+//!
+//! # Download anything under the first URL prefix from the second one instead.
+//! # The content hash in the URL still gets verified, so a mirror can't tamper with packages.
+//! mirror https://github.com/ = https://mirror.example.com/github/
+//!
+//! # Send this bearer token when downloading from this host
+//! token packages.example.com = abc123
+//!
+//! # Never touch the network; only use packages which are already in the cache
+//! offline = true
+//!
+//! # Where `roc publish --upload` should upload package bundles to
+//! publish = https://packages.example.com/upload/
+//! ```
+//!
+//! The corresponding environment variables are ROC_REGISTRY_INDEX, ROC_PACKAGE_MIRRORS
+//! (a space-separated list of `from=to` pairs), ROC_PACKAGE_TOKEN (sent to every host,
+//! including the registry), ROC_OFFLINE (set to `1` or `true`), and ROC_PUBLISH_URL.
+use crate::registry::DEFAULT_REGISTRY_INDEX_URL;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE_NAME: &str = "packages.conf";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mirror {
+    /// A URL prefix, e.g. `https://github.com/`
+    pub from: String,
+    /// What to replace the prefix with, e.g. `https://mirror.example.com/github/`
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostToken {
+    pub host: String,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageConfig {
+    pub registry_index: String,
+    pub mirrors: Vec<Mirror>,
+    pub tokens: Vec<HostToken>,
+    /// A token to send to every host which doesn't have a more specific one
+    pub default_token: Option<String>,
+    pub offline: bool,
+    /// The URL prefix that `roc publish --upload` uploads bundles to
+    pub publish_url: Option<String>,
+}
+
+impl Default for PackageConfig {
+    fn default() -> Self {
+        Self {
+            registry_index: DEFAULT_REGISTRY_INDEX_URL.to_string(),
+            mirrors: Vec::new(),
+            tokens: Vec::new(),
+            default_token: None,
+            offline: false,
+            publish_url: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigProblem {
+    IoErr(PathBuf, io::Error),
+    MalformedLine {
+        path: PathBuf,
+        line_number: usize,
+        line: String,
+    },
+    MalformedEnvVar {
+        name: &'static str,
+        value: String,
+    },
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigProblem::IoErr(path, err) => {
+                write!(f, "I could not read {}: {err}", path.display())
+            }
+            ConfigProblem::MalformedLine {
+                path,
+                line_number,
+                line,
+            } => write!(
+                f,
+                "Line {line_number} of {} is not a setting I recognize:\n\n    {line}\n\nSettings look like `registry = <url>`, `mirror <url> = <url>`, `token <host> = <token>`, `publish = <url>`, or `offline = true`.",
+                path.display()
+            ),
+            ConfigProblem::MalformedEnvVar { name, value } => {
+                write!(f, "The {name} environment variable has a value I don't understand: {value:?}")
+            }
+        }
+    }
+}
+
+impl PackageConfig {
+    /// Read the config file (if there is one), and then apply environment variable overrides.
+    pub fn load() -> Result<Self, ConfigProblem> {
+        let mut config = match config_file_path() {
+            Some(path) => match fs::read_to_string(&path) {
+                Ok(src) => Self::parse(&src, &path)?,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Self::default(),
+                Err(err) => return Err(ConfigProblem::IoErr(path, err)),
+            },
+            None => Self::default(),
+        };
+
+        config.apply_env_vars(|name| env::var(name).ok())?;
+
+        Ok(config)
+    }
+
+    pub fn parse(src: &str, path: &Path) -> Result<Self, ConfigProblem> {
+        let mut config = Self::default();
+
+        for (index, line) in src.lines().enumerate() {
+            let trimmed = line.trim();
+
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let malformed = || ConfigProblem::MalformedLine {
+                path: path.to_path_buf(),
+                line_number: index + 1,
+                line: line.to_string(),
+            };
+
+            let (key, value) = trimmed.split_once('=').ok_or_else(malformed)?;
+            let value = value.trim();
+            let mut key_parts = key.split_whitespace();
+
+            match (key_parts.next(), key_parts.next(), key_parts.next()) {
+                (Some("registry"), None, _) => config.registry_index = value.to_string(),
+                (Some("publish"), None, _) => config.publish_url = Some(value.to_string()),
+                (Some("offline"), None, _) => {
+                    config.offline = parse_bool(value).ok_or_else(malformed)?;
+                }
+                (Some("mirror"), Some(from), None) => config.mirrors.push(Mirror {
+                    from: from.to_string(),
+                    to: value.to_string(),
+                }),
+                (Some("token"), Some(host), None) => config.tokens.push(HostToken {
+                    host: host.to_string(),
+                    token: value.to_string(),
+                }),
+                _ => return Err(malformed()),
+            }
+        }
+
+        Ok(config)
+    }
+
+    fn apply_env_vars(
+        &mut self,
+        get_var: impl Fn(&'static str) -> Option<String>,
+    ) -> Result<(), ConfigProblem> {
+        if let Some(url) = get_var("ROC_REGISTRY_INDEX") {
+            self.registry_index = url;
+        }
+
+        if let Some(mirrors) = get_var("ROC_PACKAGE_MIRRORS") {
+            // Mirrors from the environment take precedence over the ones in the config file.
+            let mut from_env = Vec::new();
+
+            for pair in mirrors.split_whitespace() {
+                match pair.split_once('=') {
+                    Some((from, to)) if !from.is_empty() && !to.is_empty() => {
+                        from_env.push(Mirror {
+                            from: from.to_string(),
+                            to: to.to_string(),
+                        });
+                    }
+                    _ => {
+                        return Err(ConfigProblem::MalformedEnvVar {
+                            name: "ROC_PACKAGE_MIRRORS",
+                            value: mirrors.clone(),
+                        });
+                    }
+                }
+            }
+
+            from_env.append(&mut self.mirrors);
+            self.mirrors = from_env;
+        }
+
+        if let Some(url) = get_var("ROC_PUBLISH_URL") {
+            self.publish_url = Some(url);
+        }
+
+        if let Some(token) = get_var("ROC_PACKAGE_TOKEN") {
+            self.default_token = Some(token);
+        }
+
+        if let Some(offline) = get_var("ROC_OFFLINE") {
+            self.offline = match parse_bool(&offline) {
+                Some(offline) => offline,
+                None => {
+                    return Err(ConfigProblem::MalformedEnvVar {
+                        name: "ROC_OFFLINE",
+                        value: offline,
+                    });
+                }
+            };
+        }
+
+        Ok(())
+    }
+
+    /// The URL to actually download from, after applying the first matching mirror (if any).
+    pub fn mirrored_url(&self, url: &str) -> String {
+        for Mirror { from, to } in self.mirrors.iter() {
+            if let Some(rest) = url.strip_prefix(from.as_str()) {
+                return format!("{to}{rest}");
+            }
+        }
+
+        url.to_string()
+    }
+
+    /// The bearer token to send when downloading the given URL, if any.
+    pub fn token_for(&self, url: &str) -> Option<&str> {
+        let host = url
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(url)
+            .split(|c| c == '/' || c == '#' || c == '?')
+            .next()
+            .unwrap_or_default();
+
+        self.tokens
+            .iter()
+            .find(|entry| entry.host == host)
+            .map(|entry| entry.token.as_str())
+            .or(self.default_token.as_deref())
+    }
+}
+
+fn parse_bool(string: &str) -> Option<bool> {
+    match string {
+        "1" | "true" | "yes" => Some(true),
+        "0" | "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// ROC_PACKAGE_CONFIG if it's set; otherwise the config dir per the XDG spec
+/// (or %APPDATA% on Windows), in the same way we find the cache dir.
+fn config_file_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("ROC_PACKAGE_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+
+    let config_dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(xdg_config_home) => PathBuf::from(xdg_config_home),
+        None => {
+            #[cfg(windows)]
+            {
+                PathBuf::from(env::var_os("APPDATA")?)
+            }
+
+            #[cfg(not(windows))]
+            {
+                Path::new(&env::var_os("HOME")?).join(".config")
+            }
+        }
+    };
+
+    Some(
+        config_dir
+            .join(crate::cache::ROC_CACHE_DIR_NAME)
+            .join(CONFIG_FILE_NAME),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config_file() {
+        let config = PackageConfig::parse(
+            "# comment\n\
+             registry = https://packages.example.com/index.txt\n\
+             mirror https://github.com/ = https://mirror.example.com/github/\n\
+             token packages.example.com = abc123\n\
+             offline = true\n",
+            Path::new("packages.conf"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.registry_index,
+            "https://packages.example.com/index.txt"
+        );
+        assert!(config.offline);
+        assert_eq!(
+            config.mirrored_url("https://github.com/roc-lang/basic-cli/hash.tar.br"),
+            "https://mirror.example.com/github/roc-lang/basic-cli/hash.tar.br"
+        );
+        assert_eq!(
+            config.mirrored_url("https://example.com/hash.tar.br"),
+            "https://example.com/hash.tar.br"
+        );
+        assert_eq!(
+            config.token_for("https://packages.example.com/foo/hash.tar.br"),
+            Some("abc123")
+        );
+        assert_eq!(config.token_for("https://example.com/hash.tar.br"), None);
+    }
+
+    #[test]
+    fn malformed_config_line() {
+        let result = PackageConfig::parse("mirror = https://example.com", Path::new("x.conf"));
+
+        assert!(matches!(
+            result,
+            Err(ConfigProblem::MalformedLine { line_number: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn env_vars_override_config_file() {
+        let mut config = PackageConfig::parse(
+            "mirror https://github.com/ = https://a.example.com/\n",
+            Path::new("packages.conf"),
+        )
+        .unwrap();
+
+        config
+            .apply_env_vars(|name| match name {
+                "ROC_PACKAGE_MIRRORS" => Some("https://github.com/=https://b.example.com/".into()),
+                "ROC_PACKAGE_TOKEN" => Some("secret".into()),
+                "ROC_OFFLINE" => Some("1".into()),
+                _ => None,
+            })
+            .unwrap();
+
+        assert!(config.offline);
+        assert_eq!(
+            config.mirrored_url("https://github.com/x.tar"),
+            "https://b.example.com/x.tar"
+        );
+        assert_eq!(
+            config.token_for("https://anything.example.com/x.tar"),
+            Some("secret")
+        );
+    }
+}
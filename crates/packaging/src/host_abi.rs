@@ -0,0 +1,168 @@
+//! The optional standard I/O hooks of the platform ABI, and the metadata file which records which
+//! of them each prebuilt host in a platform bundle defines.
+//!
+//! Besides the functions every host has to define (like `roc_alloc`), a host can define these to
+//! decide where the standard streams, the environment and the clock of Roc code come from. The builtins
+//! define weak fallbacks which use the process's own streams and environment, so hosts which
+//! don't define them still link, and tools like `roc test`, `dbg` and the REPL can always call
+//! them.
+//!
+//! A platform bundle records which hooks its hosts define in `host-abi.txt`, with one line per
+//! prebuilt host:
+//!
+//! ```text
+//! # the standard I/O hooks each prebuilt host defines
+//! linux-x64.o: roc_stdout roc_stderr roc_stdin
+//! macos-arm64.o: roc_stdout roc_stderr
+//! ```
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The file in a platform bundle which records the hooks of its prebuilt hosts
+pub const HOST_ABI_FILENAME: &str = "host-abi.txt";
+
+/// The optional standard I/O hooks, along with their C signatures
+pub const STDIO_HOOKS: &[(&str, &str)] = &[
+    (
+        "roc_stdout",
+        "void roc_stdout(const uint8_t *bytes, size_t len)",
+    ),
+    (
+        "roc_stderr",
+        "void roc_stderr(const uint8_t *bytes, size_t len)",
+    ),
+    (
+        // returns how many bytes it read, which is 0 at the end of the input
+        "roc_stdin",
+        "size_t roc_stdin(uint8_t *buffer, size_t capacity)",
+    ),
+    (
+        // returns NULL when the variable isn't set; the host keeps owning the value
+        "roc_getenv",
+        "const uint8_t *roc_getenv(const uint8_t *name, size_t name_len, size_t *value_len)",
+    ),
+    (
+        // nanoseconds since the Unix epoch, which `Time.fromUnixNanos` turns into a `DateTime`;
+        // Rust hosts can narrow `roc_std::unix_nanos_now` to get it
+        "roc_current_time",
+        "int64_t roc_current_time(void)",
+    ),
+];
+
+/// The standard I/O hooks which a prebuilt host (given by its file name) defines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostHooks {
+    pub host: String,
+    pub hooks: Vec<String>,
+}
+
+impl HostHooks {
+    /// Keeps only the symbols which are standard I/O hooks, in the order of [STDIO_HOOKS]
+    pub fn from_defined(host: &str, defined: &[String]) -> Self {
+        let hooks = STDIO_HOOKS
+            .iter()
+            .filter(|(name, _)| defined.iter().any(|symbol| symbol == name))
+            .map(|(name, _)| name.to_string())
+            .collect();
+
+        Self {
+            host: host.to_string(),
+            hooks,
+        }
+    }
+
+    pub fn defines(&self, hook: &str) -> bool {
+        self.hooks.iter().any(|defined| defined == hook)
+    }
+}
+
+pub fn write_host_abi(hosts: &[HostHooks]) -> String {
+    let mut contents = String::from("# the standard I/O hooks each prebuilt host defines\n");
+
+    for host in hosts {
+        contents.push_str(&host.host);
+        contents.push(':');
+
+        for hook in host.hooks.iter() {
+            contents.push(' ');
+            contents.push_str(hook);
+        }
+
+        contents.push('\n');
+    }
+
+    contents
+}
+
+/// Lines which aren't `host: hooks` are skipped, so newer versions of the file can add other
+/// kinds of lines.
+pub fn parse_host_abi(contents: &str) -> Vec<HostHooks> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(':'))
+        .map(|(host, hooks)| HostHooks {
+            host: host.trim().to_string(),
+            hooks: hooks.split_whitespace().map(str::to_string).collect(),
+        })
+        .collect()
+}
+
+/// The hooks of the prebuilt hosts of the platform in `platform_dir`, if its bundle recorded them
+pub fn read_host_abi(platform_dir: &Path) -> io::Result<Option<Vec<HostHooks>>> {
+    match fs::read_to_string(platform_dir.join(HOST_ABI_FILENAME)) {
+        Ok(contents) => Ok(Some(parse_host_abi(&contents))),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_stdio_hooks() {
+        let defined = ["roc_alloc", "roc_stderr", "roc_fx_putLine", "roc_stdout"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+
+        let host = HostHooks::from_defined("linux-x64.o", &defined);
+
+        assert_eq!(host.hooks, vec!["roc_stdout", "roc_stderr"]);
+        assert!(host.defines("roc_stderr"));
+        assert!(!host.defines("roc_getenv"));
+    }
+
+    #[test]
+    fn host_abi_round_trip() {
+        let hosts = vec![
+            HostHooks {
+                host: "linux-x64.o".to_string(),
+                hooks: vec!["roc_stdout".to_string(), "roc_stdin".to_string()],
+            },
+            HostHooks {
+                host: "windows-x64.obj".to_string(),
+                hooks: Vec::new(),
+            },
+        ];
+
+        assert_eq!(parse_host_abi(&write_host_abi(&hosts)), hosts);
+    }
+
+    #[test]
+    fn skips_lines_it_does_not_recognize() {
+        let hosts = parse_host_abi("# comment\nversion 2\n\nmacos-arm64.o: roc_getenv\n");
+
+        assert_eq!(
+            hosts,
+            vec![HostHooks {
+                host: "macos-arm64.o".to_string(),
+                hooks: vec!["roc_getenv".to_string()],
+            }]
+        );
+    }
+}
@@ -116,20 +116,34 @@ pub enum Problem {
     InvalidUrl(UrlProblem),
     /// The Content-Length header of the response exceeded max_download_bytes
     DownloadTooBig(u64),
+    /// The package wasn't in the cache, and we're in offline mode so we can't download it
+    NotCachedWhileOffline,
+}
+
+/// GET the given URL, sending the bearer token (if any) for authentication
+fn get(url: &str, bearer_token: Option<&str>) -> Result<reqwest::blocking::Response, Problem> {
+    // TODO apparently it really improves performance to construct a Client once and then reuse it,
+    // instead of making a new Client for every request.
+    // Per https://github.com/seanmonstar/reqwest/issues/1454#issuecomment-1026076701
+    let mut request = reqwest::blocking::Client::new().get(url);
+
+    if let Some(token) = bearer_token {
+        request = request.bearer_auth(token);
+    }
+
+    request
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .map_err(Problem::HttpErr)
 }
 
 pub fn download_and_hash(
     url: &str,
     dest_dir: &Path,
     max_download_bytes: u64,
+    bearer_token: Option<&str>,
 ) -> Result<String, Problem> {
-    // TODO apparently it really improves performance to construct a Client once and then reuse it,
-    // instead of making a new Client for every request.
-    // Per https://github.com/seanmonstar/reqwest/issues/1454#issuecomment-1026076701
-    let resp = reqwest::blocking::Client::new()
-        .get(url)
-        .send()
-        .map_err(Problem::HttpErr)?;
+    let resp = get(url, bearer_token)?;
 
     // Some servers don't return Content-Length - e.g. Netlify seems to only sometimes return it.
     // If they do, and if it says the file is going to be too big, don't bother downloading it!
@@ -156,6 +170,42 @@ pub fn download_and_hash(
     decompress_into(dest_dir, encoding, resp.take(max_download_bytes))
 }
 
+/// Download a small text file (such as a registry index) into memory.
+pub fn download_text(url: &str, bearer_token: Option<&str>) -> Result<String, Problem> {
+    const MAX_TEXT_BYTES: u64 = 16 * 1_000_000; // MB
+
+    let resp = get(url, bearer_token)?;
+
+    if let Some(content_len) = resp.content_length() {
+        if content_len > MAX_TEXT_BYTES {
+            return Err(Problem::DownloadTooBig(content_len));
+        }
+    }
+
+    let mut text = String::new();
+
+    resp.take(MAX_TEXT_BYTES)
+        .read_to_string(&mut text)
+        .map_err(Problem::IoErr)?;
+
+    Ok(text)
+}
+
+/// Upload the given bytes to the given URL with a HTTP PUT, e.g. to publish a package bundle.
+pub fn upload(url: &str, bytes: Vec<u8>, bearer_token: Option<&str>) -> Result<(), Problem> {
+    let mut request = reqwest::blocking::Client::new().put(url).body(bytes);
+
+    if let Some(token) = bearer_token {
+        request = request.bearer_auth(token);
+    }
+
+    request
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .map(|_| ())
+        .map_err(Problem::HttpErr)
+}
+
 /// The content encodings we support
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Encoding {
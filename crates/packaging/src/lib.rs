@@ -1,4 +1,9 @@
 pub mod cache;
+pub mod config;
+pub mod host_abi;
 #[cfg(not(target_family = "wasm"))]
 pub mod https;
+pub mod lock;
+pub mod registry;
 pub mod tarball;
+pub mod version;
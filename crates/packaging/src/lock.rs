@@ -0,0 +1,393 @@
+//! Packages can be specified in a header either by URL, or as a package name plus a version
+//! constraint, e.g. `pf: "basic-cli@^0.3.0"`. The latter get resolved against a registry index
+//! the first time they're seen, and the result is recorded in a `roc.lock` file next to the
+//! module whose header requested them. From then on, builds use the locked version (as long as
+//! it still satisfies the constraint), so they're reproducible regardless of what has since
+//! been published to the registry.
+//!
+//! The lockfile has one line per package, sorted by name:
+//!
+//! ```text
+//! basic-cli 0.3.2 jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE https://example.com/basic-cli/jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE.tar.br
+//! ```
+use crate::registry::RegistryIndex;
+use crate::version::{Version, VersionProblem, VersionReq};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+#[cfg(not(target_family = "wasm"))]
+use std::sync::Mutex;
+
+pub const LOCKFILE_NAME: &str = "roc.lock";
+
+const LOCKFILE_HEADER: &str =
+    "# This file is generated by roc. Commit it to version control, but don't edit it by hand!\n";
+
+/// Several modules' headers can be loaded in parallel, so make sure
+/// they don't clobber each other's changes to the same lockfile.
+#[cfg(not(target_family = "wasm"))]
+static LOCKFILE_MUTEX: Mutex<()> = Mutex::new(());
+
+/// A package specified by name and version constraint rather than by URL,
+/// e.g. `basic-cli@^0.3.0`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionedPackage<'a> {
+    pub name: &'a str,
+    pub req: VersionReq,
+}
+
+impl<'a> VersionedPackage<'a> {
+    /// Returns None if the given package source isn't of the form `name@constraint`
+    /// (e.g. because it's a URL or a path on disk).
+    pub fn from_src(src: &'a str) -> Option<Result<Self, VersionProblem>> {
+        if src.starts_with("https://") || src.ends_with(".roc") {
+            return None;
+        }
+
+        let (name, req) = src.split_once('@')?;
+
+        if name.is_empty() || name.contains(|c| c == '/' || c == '\\') {
+            return None;
+        }
+
+        Some(VersionReq::parse(req).map(|req| VersionedPackage { name, req }))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: Version,
+    /// The BLAKE3 content hash of the package's tarball, which is also
+    /// the last path segment of its URL (minus the extension).
+    pub content_hash: String,
+    pub url: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Lockfile {
+    /// Always sorted by name, so that the file on disk is stable and diffs nicely.
+    packages: Vec<LockedPackage>,
+}
+
+#[derive(Debug)]
+pub enum LockProblem {
+    IoErr(io::Error),
+    MalformedLockfile {
+        line_number: usize,
+        line: String,
+    },
+    InvalidConstraint(VersionProblem),
+    Index(crate::registry::IndexProblem),
+    /// Nothing in the registry index satisfied the constraint
+    NoMatchingVersion {
+        name: String,
+        req: VersionReq,
+        available: Vec<Version>,
+    },
+    /// Resolving the package would need the registry index, but we're in offline mode
+    NotLockedWhileOffline {
+        name: String,
+        req: VersionReq,
+    },
+    /// The content hash recorded in the lockfile doesn't match the URL it was recorded with
+    HashMismatch {
+        name: String,
+    },
+}
+
+impl fmt::Display for LockProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LockProblem::IoErr(err) => write!(f, "I could not read or write {LOCKFILE_NAME}: {err}"),
+            LockProblem::MalformedLockfile { line_number, line } => write!(
+                f,
+                "Line {line_number} of {LOCKFILE_NAME} is malformed:\n\n    {line}\n\nYou can delete {LOCKFILE_NAME} and it will be regenerated the next time you build."
+            ),
+            LockProblem::InvalidConstraint(problem) => write!(f, "{problem}"),
+            LockProblem::Index(problem) => write!(f, "{problem}"),
+            LockProblem::NoMatchingVersion { name, req, available } => {
+                write!(f, "No published version of the `{name}` package matches `{req}`.")?;
+
+                if available.is_empty() {
+                    write!(f, "\n\nThe package registry doesn't have any package named `{name}`.")
+                } else {
+                    let available = available
+                        .iter()
+                        .map(|version| version.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    write!(f, "\n\nThese versions are available: {available}")
+                }
+            }
+            LockProblem::NotLockedWhileOffline { name, req } => write!(
+                f,
+                "{LOCKFILE_NAME} has no version of `{name}` matching `{req}`, and I can't look one up in the package registry because I'm in offline mode."
+            ),
+            LockProblem::HashMismatch { name } => write!(
+                f,
+                "The content hash recorded for `{name}` in {LOCKFILE_NAME} does not match its URL. Was the file edited by hand? You can delete {LOCKFILE_NAME} and it will be regenerated the next time you build."
+            ),
+        }
+    }
+}
+
+impl Lockfile {
+    pub fn parse(src: &str) -> Result<Self, LockProblem> {
+        let mut lockfile = Lockfile::default();
+
+        for (index, line) in src.lines().enumerate() {
+            let trimmed = line.trim();
+
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let malformed = || LockProblem::MalformedLockfile {
+                line_number: index + 1,
+                line: line.to_string(),
+            };
+
+            let mut columns = trimmed.split_whitespace();
+
+            match (
+                columns.next(),
+                columns.next(),
+                columns.next(),
+                columns.next(),
+                columns.next(),
+            ) {
+                (Some(name), Some(version), Some(content_hash), Some(url), None) => {
+                    lockfile.insert(LockedPackage {
+                        name: name.to_string(),
+                        version: Version::parse(version).map_err(|_| malformed())?,
+                        content_hash: content_hash.to_string(),
+                        url: url.to_string(),
+                    });
+                }
+                _ => return Err(malformed()),
+            }
+        }
+
+        Ok(lockfile)
+    }
+
+    /// Read the lockfile in the given directory, or return an empty one if there isn't one yet.
+    pub fn read(dir: &Path) -> Result<Self, LockProblem> {
+        match fs::read_to_string(dir.join(LOCKFILE_NAME)) {
+            Ok(src) => Self::parse(&src),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(LockProblem::IoErr(err)),
+        }
+    }
+
+    pub fn write(&self, dir: &Path) -> Result<(), LockProblem> {
+        fs::write(dir.join(LOCKFILE_NAME), self.to_string()).map_err(LockProblem::IoErr)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LockedPackage> {
+        self.packages
+            .binary_search_by(|package| package.name.as_str().cmp(name))
+            .ok()
+            .map(|index| &self.packages[index])
+    }
+
+    /// Insert the given package, replacing any existing entry with the same name.
+    pub fn insert(&mut self, package: LockedPackage) {
+        match self
+            .packages
+            .binary_search_by(|existing| existing.name.cmp(&package.name))
+        {
+            Ok(index) => self.packages[index] = package,
+            Err(index) => self.packages.insert(index, package),
+        }
+    }
+}
+
+impl fmt::Display for Lockfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{LOCKFILE_HEADER}")?;
+
+        for package in self.packages.iter() {
+            writeln!(
+                f,
+                "{} {} {} {}",
+                package.name, package.version, package.content_hash, package.url
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolve a package specified by name and version constraint to the URL of its tarball.
+///
+/// If the lockfile in `lock_dir` already has a version of this package which satisfies the
+/// constraint, use that. Otherwise, fetch the registry index, pick the highest matching version,
+/// and record it in the lockfile.
+#[cfg(not(target_family = "wasm"))]
+pub fn resolve_url(
+    package: &VersionedPackage,
+    lock_dir: &Path,
+    config: &crate::config::PackageConfig,
+) -> Result<String, LockProblem> {
+    // If the lock is poisoned, another thread panicked while holding it, and the build
+    // is going to fail anyway; there's no lockfile state we need to protect from that.
+    let _guard = LOCKFILE_MUTEX
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let mut lockfile = Lockfile::read(lock_dir)?;
+
+    if let Some(locked) = lockfile.get(package.name) {
+        if package.req.matches(locked.version) {
+            verify_locked_hash(locked)?;
+
+            return Ok(locked.url.clone());
+        }
+    }
+
+    if config.offline {
+        return Err(LockProblem::NotLockedWhileOffline {
+            name: package.name.to_string(),
+            req: package.req.clone(),
+        });
+    }
+
+    let index = RegistryIndex::fetch(config).map_err(LockProblem::Index)?;
+    let locked = lock_from_index(&index, package)?;
+    let url = locked.url.clone();
+
+    lockfile.insert(locked);
+    lockfile.write(lock_dir)?;
+
+    Ok(url)
+}
+
+/// Pick the best version from the index, without touching the lockfile on disk
+pub fn lock_from_index(
+    index: &RegistryIndex,
+    package: &VersionedPackage,
+) -> Result<LockedPackage, LockProblem> {
+    match index.resolve(package.name, &package.req) {
+        Some(entry) => {
+            let content_hash =
+                content_hash_of(&entry.url).ok_or_else(|| LockProblem::HashMismatch {
+                    name: package.name.to_string(),
+                })?;
+
+            Ok(LockedPackage {
+                name: entry.name.clone(),
+                version: entry.version,
+                content_hash: content_hash.to_string(),
+                url: entry.url.clone(),
+            })
+        }
+        None => Err(LockProblem::NoMatchingVersion {
+            name: package.name.to_string(),
+            req: package.req.clone(),
+            available: index.versions_of(package.name),
+        }),
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn verify_locked_hash(locked: &LockedPackage) -> Result<(), LockProblem> {
+    if content_hash_of(&locked.url) == Some(locked.content_hash.as_str()) {
+        Ok(())
+    } else {
+        Err(LockProblem::HashMismatch {
+            name: locked.name.clone(),
+        })
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn content_hash_of(url: &str) -> Option<&str> {
+    crate::https::PackageMetadata::try_from(url)
+        .ok()
+        .map(|metadata| metadata.content_hash)
+}
+
+#[cfg(target_family = "wasm")]
+fn content_hash_of(_url: &str) -> Option<&str> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE";
+
+    fn locked(name: &str, version: Version) -> LockedPackage {
+        LockedPackage {
+            name: name.to_string(),
+            version,
+            content_hash: HASH.to_string(),
+            url: format!("https://example.com/{name}/{HASH}.tar.br"),
+        }
+    }
+
+    #[test]
+    fn versioned_package_from_src() {
+        let package = VersionedPackage::from_src("basic-cli@^0.3")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(package.name, "basic-cli");
+        assert_eq!(package.req, VersionReq::parse("^0.3").unwrap());
+
+        assert!(VersionedPackage::from_src("https://example.com/foo@1.tar").is_none());
+        assert!(VersionedPackage::from_src("../platform/main.roc").is_none());
+        assert!(VersionedPackage::from_src("../platform").is_none());
+        assert!(matches!(
+            VersionedPackage::from_src("basic-cli@latest"),
+            Some(Err(_))
+        ));
+    }
+
+    #[test]
+    fn lockfile_roundtrip() {
+        let mut lockfile = Lockfile::default();
+
+        lockfile.insert(locked("zzz", Version::new(1, 0, 0)));
+        lockfile.insert(locked("basic-cli", Version::new(0, 3, 1)));
+        lockfile.insert(locked("basic-cli", Version::new(0, 3, 2)));
+
+        let src = lockfile.to_string();
+
+        assert!(src.starts_with(LOCKFILE_HEADER));
+        assert_eq!(Lockfile::parse(&src).unwrap(), lockfile);
+        assert_eq!(
+            lockfile.get("basic-cli").map(|package| package.version),
+            Some(Version::new(0, 3, 2))
+        );
+    }
+
+    #[test]
+    fn lock_picks_highest_match() {
+        let index = RegistryIndex::parse(&format!(
+            "basic-cli 0.3.1 https://example.com/x/{HASH}.tar.br\n\
+             basic-cli 0.3.5 https://example.com/y/{HASH}.tar.br\n\
+             basic-cli 1.0.0 https://example.com/z/{HASH}.tar.br\n"
+        ))
+        .unwrap();
+        let package = VersionedPackage::from_src("basic-cli@~0.3")
+            .unwrap()
+            .unwrap();
+        let locked = lock_from_index(&index, &package).unwrap();
+
+        assert_eq!(locked.version, Version::new(0, 3, 5));
+        assert_eq!(locked.content_hash, HASH);
+
+        let missing = VersionedPackage::from_src("basic-cli@^2").unwrap().unwrap();
+
+        assert!(matches!(
+            lock_from_index(&index, &missing),
+            Err(LockProblem::NoMatchingVersion { available, .. }) if available.len() == 3
+        ));
+    }
+}
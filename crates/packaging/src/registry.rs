@@ -0,0 +1,157 @@
+//! A registry index maps package names and versions to the URLs of their tarballs.
+//!
+//! The index is a plain text file with one published version per line:
+//!
+//! ```text
+//! # name      version   url
+//! basic-cli   0.3.2     https://example.com/basic-cli/jDRlAFAA3738vu3-vMpLUoyxtA86Z7CaZneoOKrihbE.tar.br
+//! ```
+//!
+//! Blank lines and lines beginning with `#` are ignored. The URL must follow the same
+//! content-hash naming scheme as any other package URL, so a package resolved through
+//! the index gets verified exactly like one whose URL was written out by hand.
+use crate::version::{Version, VersionReq};
+use std::fmt;
+
+/// Used when neither the package config file nor the ROC_REGISTRY_INDEX
+/// environment variable specifies a registry.
+pub const DEFAULT_REGISTRY_INDEX_URL: &str = "https://www.roc-lang.org/packages/index.txt";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+    pub name: String,
+    pub version: Version,
+    pub url: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RegistryIndex {
+    entries: Vec<IndexEntry>,
+}
+
+#[derive(Debug)]
+pub enum IndexProblem {
+    /// A line in the index didn't have exactly three columns, or its version was invalid
+    MalformedLine { line_number: usize, line: String },
+    #[cfg(not(target_family = "wasm"))]
+    Download(crate::https::Problem),
+}
+
+impl fmt::Display for IndexProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IndexProblem::MalformedLine { line_number, line } => write!(
+                f,
+                "Line {line_number} of the package registry index is malformed:\n\n    {line}\n\nEach line should have a package name, a version, and a URL, separated by spaces."
+            ),
+            #[cfg(not(target_family = "wasm"))]
+            IndexProblem::Download(problem) => {
+                write!(f, "I could not download the package registry index: {problem:?}")
+            }
+        }
+    }
+}
+
+impl RegistryIndex {
+    pub fn parse(src: &str) -> Result<Self, IndexProblem> {
+        let mut entries = Vec::new();
+
+        for (index, line) in src.lines().enumerate() {
+            let trimmed = line.trim();
+
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let malformed = || IndexProblem::MalformedLine {
+                line_number: index + 1,
+                line: line.to_string(),
+            };
+
+            let mut columns = trimmed.split_whitespace();
+
+            match (
+                columns.next(),
+                columns.next(),
+                columns.next(),
+                columns.next(),
+            ) {
+                (Some(name), Some(version), Some(url), None) => {
+                    entries.push(IndexEntry {
+                        name: name.to_string(),
+                        version: Version::parse(version).map_err(|_| malformed())?,
+                        url: url.to_string(),
+                    });
+                }
+                _ => return Err(malformed()),
+            }
+        }
+
+        Ok(RegistryIndex { entries })
+    }
+
+    /// Download the index from the configured registry (or its mirror)
+    #[cfg(not(target_family = "wasm"))]
+    pub fn fetch(config: &crate::config::PackageConfig) -> Result<Self, IndexProblem> {
+        let url = config.mirrored_url(&config.registry_index);
+        let src = crate::https::download_text(&url, config.token_for(&url))
+            .map_err(IndexProblem::Download)?;
+
+        Self::parse(&src)
+    }
+
+    /// The highest published version of the given package which satisfies the constraint
+    pub fn resolve(&self, name: &str, req: &VersionReq) -> Option<&IndexEntry> {
+        req.best_match(
+            self.entries.iter().filter(|entry| entry.name == name),
+            |entry: &IndexEntry| entry.version,
+        )
+    }
+
+    /// All the published versions of the given package, from lowest to highest
+    pub fn versions_of(&self, name: &str) -> Vec<Version> {
+        let mut versions: Vec<Version> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.name == name)
+            .map(|entry| entry.version)
+            .collect();
+
+        versions.sort();
+
+        versions
+    }
+}
+
+#[test]
+fn resolve_highest_matching() {
+    let index = RegistryIndex::parse(
+        "# comment\n\
+         basic-cli 0.2.0 https://example.com/a.tar.br\n\
+         \n\
+         basic-cli 0.3.1 https://example.com/b.tar.br\n\
+         basic-cli 0.3.4 https://example.com/c.tar.br\n\
+         other 0.3.9 https://example.com/d.tar.br\n\
+         basic-cli 0.4.0 https://example.com/e.tar.br\n",
+    )
+    .unwrap();
+    let req = VersionReq::parse("^0.3").unwrap();
+
+    assert_eq!(
+        index
+            .resolve("basic-cli", &req)
+            .map(|entry| entry.url.as_str()),
+        Some("https://example.com/c.tar.br")
+    );
+    assert_eq!(index.resolve("missing", &req), None);
+}
+
+#[test]
+fn malformed_index_line() {
+    let result = RegistryIndex::parse("basic-cli 0.2 https://example.com/a.tar.br");
+
+    assert!(matches!(
+        result,
+        Err(IndexProblem::MalformedLine { line_number: 1, .. })
+    ));
+}
@@ -1,3 +1,4 @@
+use crate::host_abi::HOST_ABI_FILENAME;
 use brotli::enc::BrotliEncoderParams;
 use bumpalo::Bump;
 use flate2::write::GzEncoder;
@@ -110,6 +111,10 @@ pub fn build(path_to_main: &Path, compression: Compression) -> io::Result<String
     Ok(filename)
 }
 
+/// The precompiled artifact that `roc build --precompile-package` writes next to a package's
+/// `main.roc`. Bundles include it, so apps don't have to solve the package's types themselves.
+pub const PRECOMPILED_PACKAGE_FILENAME: &str = "precompiled.rocpkg";
+
 /// Write an uncompressed tar archive to the given writer.
 fn write_archive<W: Write>(path: &Path, writer: W) -> io::Result<()> {
     let root_dir = if let Some(parent) = path.parent() {
@@ -141,6 +146,12 @@ fn write_archive<W: Write>(path: &Path, writer: W) -> io::Result<()> {
             // TODO report error
         }
         Header::Package(_) => {
+            let precompiled = root_dir.join(PRECOMPILED_PACKAGE_FILENAME);
+
+            if precompiled.is_file() {
+                builder.append_path_with_name(&precompiled, PRECOMPILED_PACKAGE_FILENAME)?;
+            }
+
             add_dot_roc_files(root_dir, &mut builder)?;
         }
         Header::Platform(PlatformHeader { imports: _, .. }) => {
@@ -174,6 +185,13 @@ fn write_archive<W: Write>(path: &Path, writer: W) -> io::Result<()> {
                 }
             }
 
+            // Which standard I/O hooks those hosts define
+            let host_abi = root_dir.join(HOST_ABI_FILENAME);
+
+            if host_abi.is_file() {
+                builder.append_path_with_name(&host_abi, HOST_ABI_FILENAME)?;
+            }
+
             add_dot_roc_files(root_dir, &mut builder)?;
         }
     };
@@ -0,0 +1,368 @@
+//! Semantic versions and version constraints, as used in the `packages` section of a header,
+//! e.g. `pf: "basic-cli@^0.3.0"`
+//!
+//! The constraint syntax follows Cargo's, since that's what most people will already know:
+//!
+//! - `^1.2.3` (or just `1.2.3`) allows anything from 1.2.3 up to (but not including) 2.0.0
+//! - `~1.2.3` allows anything from 1.2.3 up to (but not including) 1.3.0
+//! - `=1.2.3`, `>1.2.3`, `>=1.2.3`, `<1.2.3`, `<=1.2.3` do what they look like
+//! - `*` allows any version
+//!
+//! Multiple constraints can be combined with commas, e.g. `>=1.2, <1.5`
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    pub fn parse(string: &str) -> Result<Self, VersionProblem> {
+        let mut parts = string.trim().split('.');
+
+        let mut next_part = || match parts.next() {
+            Some(part) => parse_number(part, string),
+            None => Err(VersionProblem::InvalidVersion(string.to_string())),
+        };
+
+        let version = Version::new(next_part()?, next_part()?, next_part()?);
+
+        if parts.next().is_some() {
+            return Err(VersionProblem::InvalidVersion(string.to_string()));
+        }
+
+        Ok(version)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionProblem {
+    InvalidVersion(String),
+    InvalidConstraint(String),
+}
+
+impl fmt::Display for VersionProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VersionProblem::InvalidVersion(string) => {
+                write!(f, "`{string}` is not a valid version. Versions look like `1.2.3`.")
+            }
+            VersionProblem::InvalidConstraint(string) => write!(
+                f,
+                "`{string}` is not a valid version constraint. Constraints look like `^1.2.3`, `~1.2`, or `>=1.0, <2.0`."
+            ),
+        }
+    }
+}
+
+fn parse_number(part: &str, whole: &str) -> Result<u64, VersionProblem> {
+    // Don't allow things like "+1" or "01", which `str::parse` would otherwise accept.
+    let is_canonical = !part.is_empty()
+        && part.bytes().all(|byte| byte.is_ascii_digit())
+        && (part == "0" || !part.starts_with('0'));
+
+    match part.parse() {
+        Ok(number) if is_canonical => Ok(number),
+        _ => Err(VersionProblem::InvalidVersion(whole.to_string())),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Caret,
+    Tilde,
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+}
+
+/// A single comparator like `^1.2` or `>=0.3.1`. The minor and patch components are optional.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Comparator {
+    op: Op,
+    major: u64,
+    minor: Option<u64>,
+    patch: Option<u64>,
+}
+
+impl Comparator {
+    fn parse(string: &str, whole: &str) -> Result<Self, VersionProblem> {
+        let string = string.trim();
+        let invalid = || VersionProblem::InvalidConstraint(whole.to_string());
+
+        // Order matters here; ">=" has to be checked before ">"
+        let (op, rest) = [
+            (">=", Op::GreaterEq),
+            ("<=", Op::LessEq),
+            (">", Op::Greater),
+            ("<", Op::Less),
+            ("=", Op::Exact),
+            ("^", Op::Caret),
+            ("~", Op::Tilde),
+        ]
+        .iter()
+        .find_map(|(prefix, op)| string.strip_prefix(prefix).map(|rest| (*op, rest)))
+        .unwrap_or((Op::Caret, string));
+
+        let mut parts = rest.trim().split('.');
+        let major = match parts.next() {
+            Some(part) => parse_number(part, whole).map_err(|_| invalid())?,
+            None => return Err(invalid()),
+        };
+        let minor = match parts.next() {
+            Some(part) => Some(parse_number(part, whole).map_err(|_| invalid())?),
+            None => None,
+        };
+        let patch = match parts.next() {
+            Some(part) => Some(parse_number(part, whole).map_err(|_| invalid())?),
+            None => None,
+        };
+
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+
+        Ok(Comparator {
+            op,
+            major,
+            minor,
+            patch,
+        })
+    }
+
+    /// The lowest version this comparator could be referring to, e.g. 1.2.0 for `^1.2`
+    fn lowest(&self) -> Version {
+        Version::new(self.major, self.minor.unwrap_or(0), self.patch.unwrap_or(0))
+    }
+
+    fn matches(&self, version: Version) -> bool {
+        let lowest = self.lowest();
+
+        match self.op {
+            Op::Exact => {
+                version.major == self.major
+                    && self.minor.map_or(true, |minor| version.minor == minor)
+                    && self.patch.map_or(true, |patch| version.patch == patch)
+            }
+            Op::Greater => match (self.minor, self.patch) {
+                (None, _) => version.major > self.major,
+                (Some(minor), None) => (version.major, version.minor) > (self.major, minor),
+                (Some(_), Some(_)) => version > lowest,
+            },
+            Op::GreaterEq => version >= lowest,
+            Op::Less => version < lowest,
+            Op::LessEq => match (self.minor, self.patch) {
+                (None, _) => version.major <= self.major,
+                (Some(minor), None) => (version.major, version.minor) <= (self.major, minor),
+                (Some(_), Some(_)) => version <= lowest,
+            },
+            Op::Tilde => {
+                version >= lowest
+                    && version.major == self.major
+                    && self.minor.map_or(true, |minor| version.minor == minor)
+            }
+            Op::Caret => {
+                if version < lowest || version.major != self.major {
+                    return false;
+                }
+
+                // For 0.x versions, the leftmost nonzero component is treated as the "major" one.
+                match (self.major, self.minor, self.patch) {
+                    (0, Some(0), Some(patch)) => version.minor == 0 && version.patch == patch,
+                    (0, Some(minor), _) => version.minor == minor,
+                    _ => true,
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let op = match self.op {
+            Op::Caret => "^",
+            Op::Tilde => "~",
+            Op::Exact => "=",
+            Op::Greater => ">",
+            Op::GreaterEq => ">=",
+            Op::Less => "<",
+            Op::LessEq => "<=",
+        };
+
+        write!(f, "{op}{}", self.major)?;
+
+        if let Some(minor) = self.minor {
+            write!(f, ".{minor}")?;
+        }
+
+        if let Some(patch) = self.patch {
+            write!(f, ".{patch}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A version constraint, e.g. `^1.2` or `>=1.0, <1.5`. A version must satisfy
+/// every comparator in the constraint in order to match it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionReq {
+    /// An empty list means "any version" (which is what `*` parses to)
+    comparators: Vec<Comparator>,
+}
+
+impl VersionReq {
+    pub const ANY: Self = VersionReq {
+        comparators: Vec::new(),
+    };
+
+    pub fn parse(string: &str) -> Result<Self, VersionProblem> {
+        if string.trim() == "*" {
+            return Ok(Self::ANY);
+        }
+
+        let comparators = string
+            .split(',')
+            .map(|part| Comparator::parse(part, string))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(VersionReq { comparators })
+    }
+
+    pub fn matches(&self, version: Version) -> bool {
+        self.comparators
+            .iter()
+            .all(|comparator| comparator.matches(version))
+    }
+
+    /// Returns the highest of the given versions which matches this constraint.
+    pub fn best_match<'v, I, T>(
+        &self,
+        candidates: I,
+        get_version: impl Fn(&T) -> Version,
+    ) -> Option<&'v T>
+    where
+        I: IntoIterator<Item = &'v T>,
+        T: 'v,
+    {
+        candidates
+            .into_iter()
+            .filter(|candidate| self.matches(get_version(candidate)))
+            .max_by_key(|candidate| get_version(candidate))
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.comparators.is_empty() {
+            return write!(f, "*");
+        }
+
+        for (index, comparator) in self.comparators.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{comparator}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(req: &str, version: &str) -> bool {
+        VersionReq::parse(req)
+            .unwrap()
+            .matches(Version::parse(version).unwrap())
+    }
+
+    #[test]
+    fn parse_version() {
+        assert_eq!(Version::parse("1.22.333"), Ok(Version::new(1, 22, 333)));
+        assert!(Version::parse("1.2").is_err());
+        assert!(Version::parse("1.2.3.4").is_err());
+        assert!(Version::parse("01.2.3").is_err());
+        assert!(Version::parse("+1.2.3").is_err());
+    }
+
+    #[test]
+    fn caret() {
+        assert!(matches("^1.2.3", "1.2.3"));
+        assert!(matches("^1.2.3", "1.9.0"));
+        assert!(!matches("^1.2.3", "2.0.0"));
+        assert!(!matches("^1.2.3", "1.2.2"));
+        assert!(matches("1.2", "1.4.0"));
+        assert!(matches("^0.3", "0.3.7"));
+        assert!(!matches("^0.3", "0.4.0"));
+        assert!(matches("^0.0.3", "0.0.3"));
+        assert!(!matches("^0.0.3", "0.0.4"));
+        assert!(matches("^0", "0.9.9"));
+    }
+
+    #[test]
+    fn tilde() {
+        assert!(matches("~1.2.3", "1.2.9"));
+        assert!(!matches("~1.2.3", "1.3.0"));
+        assert!(matches("~1", "1.9.0"));
+        assert!(!matches("~1", "2.0.0"));
+    }
+
+    #[test]
+    fn comparisons() {
+        assert!(matches("=1.2.3", "1.2.3"));
+        assert!(!matches("=1.2.3", "1.2.4"));
+        assert!(matches("=1.2", "1.2.4"));
+        assert!(matches(">1.2", "1.3.0"));
+        assert!(!matches(">1.2", "1.2.9"));
+        assert!(matches("<=1.2", "1.2.9"));
+        assert!(matches(">=1.0, <1.5", "1.4.9"));
+        assert!(!matches(">=1.0, <1.5", "1.5.0"));
+        assert!(matches("*", "123.4.5"));
+    }
+
+    #[test]
+    fn best_match() {
+        let versions = [
+            Version::new(0, 2, 0),
+            Version::new(0, 3, 1),
+            Version::new(0, 3, 4),
+            Version::new(0, 4, 0),
+        ];
+        let req = VersionReq::parse("^0.3").unwrap();
+
+        assert_eq!(
+            req.best_match(versions.iter(), |version: &Version| *version),
+            Some(&Version::new(0, 3, 4))
+        );
+    }
+
+    #[test]
+    fn display_roundtrip() {
+        let req = VersionReq::parse(">=1.0, <1.5.2").unwrap();
+
+        assert_eq!(req.to_string(), ">=1.0, <1.5.2");
+        assert_eq!(VersionReq::parse(&req.to_string()), Ok(req));
+    }
+}
@@ -2,13 +2,13 @@ use bumpalo::Bump;
 use inkwell::context::Context;
 use libloading::Library;
 use roc_build::link::llvm_module_to_dylib;
-use roc_collections::all::MutSet;
+use roc_collections::all::{MutMap, MutSet};
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
 use roc_gen_llvm::llvm::externs::add_default_roc_externs;
-use roc_gen_llvm::{run_jit_function, run_jit_function_dynamic_type};
+use roc_gen_llvm::{run_jit_function, run_jit_function_dynamic_type, try_run_jit_function};
 use roc_load::{EntryPoint, MonomorphizedModule};
-use roc_mono::ir::OptLevel;
-use roc_mono::layout::STLayoutInterner;
+use roc_mono::ir::{CrashTag, OptLevel};
+use roc_mono::layout::{LayoutInterner, STLayoutInterner};
 use roc_parse::ast::Expr;
 use roc_repl_eval::eval::jit_to_ast;
 use roc_repl_eval::gen::{compile_to_mono, format_answer, Problems, ReplOutput};
@@ -16,8 +16,10 @@ use roc_repl_eval::{ReplApp, ReplAppMemory};
 use roc_reporting::report::DEFAULT_PALETTE;
 use roc_std::RocStr;
 use roc_target::TargetInfo;
-use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+use roc_types::pretty_print::{alias_hints, name_and_print_var, DebugPrint};
 use roc_types::subs::Subs;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use target_lexicon::Triple;
 
 pub fn gen_and_eval_llvm<'a, I: Iterator<Item = &'a str>>(
@@ -100,6 +102,172 @@ pub fn gen_and_eval_llvm<'a, I: Iterator<Item = &'a str>>(
     )
 }
 
+/// The evaluations of a repl session. Compiling and linking an expression is by far the slowest
+/// part of evaluating it, so an expression that is evaluated again with the same defs
+/// (e.g. looking at `val1` again) reuses the earlier result instead of compiling it again.
+#[derive(Debug, Default)]
+pub struct EvalCache {
+    evaluations: MutMap<u64, (Option<ReplOutput>, Problems)>,
+}
+
+impl EvalCache {
+    /// Like [gen_and_eval_llvm], but only compiles the expression if it was not evaluated
+    /// before in this session with exactly the same defs.
+    pub fn gen_and_eval_llvm<'a, I: Iterator<Item = &'a str> + Clone>(
+        &mut self,
+        defs: I,
+        src: &str,
+        target: Triple,
+        opt_level: OptLevel,
+    ) -> (Option<ReplOutput>, Problems) {
+        let key = {
+            let mut hasher = DefaultHasher::new();
+
+            for def in defs.clone() {
+                def.hash(&mut hasher);
+            }
+
+            src.hash(&mut hasher);
+            target.to_string().hash(&mut hasher);
+            format!("{:?}", opt_level).hash(&mut hasher);
+
+            hasher.finish()
+        };
+
+        if let Some(evaluation) = self.evaluations.get(&key) {
+            return evaluation.clone();
+        }
+
+        let evaluation = gen_and_eval_llvm(defs, src, target, opt_level);
+
+        self.evaluations.insert(key, evaluation.clone());
+
+        evaluation
+    }
+}
+
+/// A Roc expression compiled to native code, which Rust code can call directly.
+pub struct CompiledExpr {
+    lib: Library,
+    main_fn_name: String,
+    /// The expression's type, e.g. `List Str`
+    pub expr_type: String,
+    /// The size in bytes of the expression's value
+    pub value_size: usize,
+}
+
+impl CompiledExpr {
+    /// Evaluate the expression, returning the message it crashed with if it crashed.
+    ///
+    /// # Safety
+    ///
+    /// `T` must have the same memory layout as the expression's value,
+    /// e.g. `roc_std::RocStr` for a `Str` or `roc_std::RocList<u8>` for a `List U8`.
+    pub unsafe fn call<T>(&self) -> Result<T, String> {
+        assert_eq!(
+            std::mem::size_of::<T>(),
+            self.value_size,
+            "the Rust type does not have the size of a `{}`",
+            self.expr_type
+        );
+
+        let result: Result<T, (String, CrashTag)> =
+            try_run_jit_function!(self.lib, self.main_fn_name, T, |v| v);
+
+        result.map_err(|(message, _)| message)
+    }
+}
+
+/// Compile an expression (which may refer to the given defs) to native code,
+/// without evaluating it.
+pub fn compile_expr<'a, I: Iterator<Item = &'a str>>(
+    defs: I,
+    src: &str,
+    target: Triple,
+    opt_level: OptLevel,
+) -> Result<(CompiledExpr, Problems), Problems> {
+    let arena = Bump::new();
+    let target_info = TargetInfo::from(&target);
+
+    let (mut loaded, problems) =
+        match compile_to_mono(&arena, defs, src, target_info, DEFAULT_PALETTE) {
+            (Some(mono), problems) if problems.errors.is_empty() => (mono, problems),
+            (_, problems) => return Err(problems),
+        };
+
+    let (main_fn_symbol, main_fn_var) = match loaded.exposed_to_host.top_level_values.iter().next()
+    {
+        Some((symbol, var)) => (*symbol, *var),
+        None => return Err(problems),
+    };
+
+    let expr_type = name_and_print_var(
+        main_fn_var,
+        &mut loaded.subs,
+        loaded.module_id,
+        &loaded.interns,
+        DebugPrint::NOTHING,
+    );
+
+    let main_fn_layout = match loaded.procedures.keys().find(|(s, _)| *s == main_fn_symbol) {
+        Some((_, layout)) => *layout,
+        None => return Err(problems),
+    };
+
+    let value_size = loaded.layout_interner.stack_size(main_fn_layout.result) as usize;
+
+    let (lib, main_fn_name, _, _) =
+        mono_module_to_dylib(&arena, target, loaded, opt_level).expect("we produce a valid Dylib");
+
+    let compiled = CompiledExpr {
+        lib,
+        main_fn_name: main_fn_name.to_string(),
+        expr_type,
+        value_size,
+    };
+
+    Ok((compiled, problems))
+}
+
+/// The type of an expression (which may refer to the given defs), without generating code for it,
+/// along with the definitions of the aliases in that type.
+pub fn type_of_expr<'a, I: Iterator<Item = &'a str>>(
+    defs: I,
+    src: &str,
+    target: Triple,
+) -> (Option<(String, Vec<String>)>, Problems) {
+    let arena = Bump::new();
+    let target_info = TargetInfo::from(&target);
+
+    let (mut loaded, problems) =
+        match compile_to_mono(&arena, defs, src, target_info, DEFAULT_PALETTE) {
+            (Some(mono), problems) if problems.errors.is_empty() => (mono, problems),
+            (_, problems) => return (None, problems),
+        };
+
+    let main_fn_var = match loaded.exposed_to_host.top_level_values.values().next() {
+        Some(var) => *var,
+        None => return (None, problems),
+    };
+
+    let expr_type = name_and_print_var(
+        main_fn_var,
+        &mut loaded.subs,
+        loaded.module_id,
+        &loaded.interns,
+        DebugPrint::NOTHING,
+    );
+
+    let hints = alias_hints(
+        main_fn_var,
+        &mut loaded.subs,
+        loaded.module_id,
+        &loaded.interns,
+    );
+
+    (Some((expr_type, hints)), problems)
+}
+
 struct CliApp {
     lib: Library,
 }
@@ -190,6 +358,7 @@ fn mono_module_to_dylib<'a>(
 
     let MonomorphizedModule {
         procedures,
+        static_constants,
         entry_point,
         interns,
         subs,
@@ -222,6 +391,11 @@ fn mono_module_to_dylib<'a>(
         mode: LlvmBackendMode::GenTest, // so roc_panic is generated
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        profile: roc_gen_llvm::llvm::profile::ProfileMode::Off,
+        refcounting: roc_gen_llvm::llvm::refcounting::RefcountMode::NonAtomic,
+        dbg_locations: Default::default(),
+        static_constants,
+        func_spec_names: Default::default(),
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
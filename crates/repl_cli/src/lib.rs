@@ -9,6 +9,8 @@ use repl_state::ReplState;
 
 use crate::repl_state::PROMPT;
 
+pub use cli_gen::{compile_expr, CompiledExpr, EvalCache};
+
 pub const WELCOME_MESSAGE: &str = concatcp!(
     "\n  The rockin’ ",
     BLUE,
@@ -25,6 +27,23 @@ pub const WELCOME_MESSAGE: &str = concatcp!(
 // TODO add link to repl tutorial(does not yet exist).
 pub const SHORT_INSTRUCTIONS: &str = "Enter an expression, or :help, or :q to quit.\n\n";
 
+/// Evaluate a single expression (e.g. for `roc run --expr`), and print its value and type.
+/// Returns the exit code, which is 1 if the expression has compile errors.
+pub fn eval_expr(src: &str) -> i32 {
+    let mut repl_state = ReplState::new();
+
+    match repl_state.step(src, None) {
+        Ok(output) => {
+            if !output.is_empty() {
+                println!("{}", output);
+            }
+
+            repl_state.last_eval_failed() as i32
+        }
+        Err(exit_code) => exit_code,
+    }
+}
+
 pub fn main() -> i32 {
     use rustyline::error::ReadlineError;
     use rustyline::Editor;
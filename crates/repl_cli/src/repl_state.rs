@@ -1,4 +1,4 @@
-use crate::cli_gen::gen_and_eval_llvm;
+use crate::cli_gen::{type_of_expr, EvalCache};
 use crate::colors::{BLUE, END_COL, GREEN, PINK};
 use bumpalo::Bump;
 use const_format::concatcp;
@@ -59,6 +59,10 @@ pub const TIPS: &str = concatcp!(
     BLUE,
     "  - ",
     END_COL,
+    ":type <expr> to see the type of an expression without evaluating it\n\n",
+    BLUE,
+    "  - ",
+    END_COL,
     ":help"
 );
 
@@ -74,6 +78,9 @@ pub struct ReplState {
     past_defs: Vec<PastDef>,
     past_def_idents: MutSet<String>,
     last_auto_ident: u64,
+    eval_cache: EvalCache,
+    /// Whether the last input that was evaluated had compile errors
+    last_eval_failed: bool,
 }
 
 impl Default for ReplState {
@@ -89,9 +96,15 @@ impl ReplState {
             past_defs: Default::default(),
             past_def_idents: Default::default(),
             last_auto_ident: 0,
+            eval_cache: Default::default(),
+            last_eval_failed: false,
         }
     }
 
+    pub fn last_eval_failed(&self) -> bool {
+        self.last_eval_failed
+    }
+
     pub fn step(&mut self, line: &str, dimensions: Option<(usize, usize)>) -> Result<String, i32> {
         let arena = Bump::new();
 
@@ -106,11 +119,42 @@ impl ReplState {
                 // TODO add link to repl tutorial(does not yet exist).
                 Ok(TIPS.to_string())
             }
+            ParseOutcome::Type(expr) => Ok(self.type_of(expr, dimensions)),
             ParseOutcome::Exit => Err(0),
         }
     }
 
+    /// The type of an expression, followed by the definitions of the aliases in it
+    fn type_of(&self, src: &str, dimensions: Option<(usize, usize)>) -> String {
+        let defs = defs_used_by(&self.past_defs, &self.past_def_idents, src);
+
+        let (opt_type, problems) = type_of_expr(defs.into_iter(), src, Triple::host());
+
+        let (opt_output, hints) = match opt_type {
+            Some((expr_type, hints)) => {
+                let output = ReplOutput {
+                    expr: src.trim().to_string(),
+                    expr_type,
+                };
+
+                (Some(output), hints)
+            }
+            None => (None, Vec::new()),
+        };
+
+        let mut buf = format_output(opt_output, problems, None, dimensions);
+
+        for hint in hints {
+            buf.push_str("\n\nHint: ");
+            buf.push_str(&hint);
+        }
+
+        buf
+    }
+
     pub fn eval_and_format(&mut self, src: &str, dimensions: Option<(usize, usize)>) -> String {
+        self.last_eval_failed = false;
+
         let arena = Bump::new();
         let pending_past_def;
         let mut opt_var_name;
@@ -220,7 +264,10 @@ impl ReplState {
                 // can be evaluated as expressions.
                 return String::new();
             }
-            ParseOutcome::Empty | ParseOutcome::Help | ParseOutcome::Exit => unreachable!(),
+            ParseOutcome::Empty
+            | ParseOutcome::Help
+            | ParseOutcome::Type(_)
+            | ParseOutcome::Exit => unreachable!(),
         };
 
         // Record e.g. "val1" as a past def, unless our input was exactly the name of
@@ -231,16 +278,20 @@ impl ReplState {
                 Some(existing_ident) => {
                     opt_var_name = Some(existing_ident);
 
-                    gen_and_eval_llvm(
-                        self.past_defs.iter().map(|def| def.src.as_str()),
+                    let defs = defs_used_by(&self.past_defs, &self.past_def_idents, src);
+
+                    self.eval_cache.gen_and_eval_llvm(
+                        defs.into_iter(),
                         src,
                         Triple::host(),
                         OptLevel::Normal,
                     )
                 }
                 None => {
-                    let (output, problems) = gen_and_eval_llvm(
-                        self.past_defs.iter().map(|def| def.src.as_str()),
+                    let defs = defs_used_by(&self.past_defs, &self.past_def_idents, src);
+
+                    let (output, problems) = self.eval_cache.gen_and_eval_llvm(
+                        defs.into_iter(),
                         src,
                         Triple::host(),
                         OptLevel::Normal,
@@ -266,6 +317,8 @@ impl ReplState {
             self.add_past_def(ident, src);
         }
 
+        self.last_eval_failed = !problems.errors.is_empty();
+
         format_output(output, problems, opt_var_name, dimensions)
     }
 
@@ -283,6 +336,42 @@ impl ReplState {
     }
 }
 
+/// The sources of the past defs that `src` refers to, directly or through other past defs.
+/// Only these are compiled along with `src`, so evaluating an expression does not get slower
+/// with every def that was entered before, and its result can be reused by the [EvalCache]
+/// as long as none of the defs it uses change.
+fn defs_used_by<'a>(
+    past_defs: &'a [PastDef],
+    past_def_idents: &MutSet<String>,
+    src: &'a str,
+) -> Vec<&'a str> {
+    let mut used = vec![false; past_defs.len()];
+    let mut pending_srcs = vec![src];
+
+    while let Some(pending_src) = pending_srcs.pop() {
+        for word in pending_src.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+            if !past_def_idents.contains(word) {
+                continue;
+            }
+
+            for (index, past_def) in past_defs.iter().enumerate() {
+                if !used[index] && past_def.ident == word {
+                    used[index] = true;
+                    pending_srcs.push(&past_def.src);
+                }
+            }
+        }
+    }
+
+    // keep the defs in the order they were entered in
+    past_defs
+        .iter()
+        .zip(used)
+        .filter(|(_, is_used)| *is_used)
+        .map(|(past_def, _)| past_def.src.as_str())
+        .collect()
+}
+
 #[derive(Debug, PartialEq)]
 enum ParseOutcome<'a> {
     ValueDef(ValueDef<'a>),
@@ -292,10 +381,16 @@ enum ParseOutcome<'a> {
     SyntaxErr,
     Empty,
     Help,
+    /// `:type <expr>`
+    Type(&'a str),
     Exit,
 }
 
 fn parse_src<'a>(arena: &'a Bump, line: &'a str) -> ParseOutcome<'a> {
+    if let Some(expr) = line.trim_start().strip_prefix(":type ") {
+        return ParseOutcome::Type(expr);
+    }
+
     match line.trim().to_lowercase().as_str() {
         "" => ParseOutcome::Empty,
         ":help" => ParseOutcome::Help,
@@ -493,6 +588,7 @@ pub fn is_incomplete(input: &str) -> bool {
         }
         ParseOutcome::Empty
         | ParseOutcome::Help
+        | ParseOutcome::Type(_)
         | ParseOutcome::Exit
         | ParseOutcome::ValueDef(_)
         | ParseOutcome::TypeDef(_)
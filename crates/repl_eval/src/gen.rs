@@ -1,5 +1,5 @@
 use bumpalo::Bump;
-use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError, Threading};
+use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError, OverflowMode, Threading};
 use roc_packaging::cache::{self, RocCacheDir};
 use roc_problem::Severity;
 use roc_reporting::report::Palette;
@@ -13,7 +13,7 @@ use roc_region::all::LineInfo;
 use roc_reporting::report::{can_problem, type_problem, RocDocAllocator};
 use roc_target::TargetInfo;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ReplOutput {
     pub expr: String,
     pub expr_type: String,
@@ -32,7 +32,7 @@ pub fn format_answer<'a>(arena: &'a Bump, answer: Expr<'_>) -> &'a str {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct Problems {
     pub errors: Vec<String>,
     pub warnings: Vec<String>,
@@ -66,6 +66,10 @@ pub fn compile_to_mono<'a, 'i, I: Iterator<Item = &'i str>>(
             palette,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Executable,
+            recover_from_syntax_errors: false,
+            static_data: false,
+            overflow: OverflowMode::Trap,
+            fold_constants: true,
         },
     );
 
@@ -16,6 +16,8 @@ use {
 mod app;
 #[cfg(not(windows))]
 pub mod run;
+#[cfg(not(windows))]
+pub mod shrink;
 
 #[cfg(not(windows))]
 use app::{ExpectMemory, ExpectReplApp};
@@ -94,7 +96,7 @@ mod test {
     use indoc::indoc;
     use pretty_assertions::assert_eq;
     use roc_gen_llvm::{llvm::build::LlvmBackendMode, run_roc::RocCallResult, run_roc_dylib};
-    use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError, Threading};
+    use roc_load::{ExecutionMode, LoadConfig, LoadMonomorphizedError, OverflowMode, Threading};
     use roc_packaging::cache::RocCacheDir;
     use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
     use target_lexicon::Triple;
@@ -103,7 +105,8 @@ mod test {
 
     use super::*;
 
-    fn run_expect_test(source: &str, expected: &str) {
+    /// Returns the output with its colors, for tests which check how it is highlighted
+    fn run_expect_test(source: &str, expected: &str) -> String {
         let arena = bumpalo::Bump::new();
         let arena = &arena;
 
@@ -125,6 +128,10 @@ mod test {
             palette: DEFAULT_PALETTE,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Test,
+            recover_from_syntax_errors: false,
+            static_data: false,
+            overflow: OverflowMode::Trap,
+            fold_constants: true,
         };
         let loaded = match roc_load::load_and_monomorphize_from_str(
             arena,
@@ -180,6 +187,7 @@ mod test {
             &mut expectations,
             expects,
             &mut memory,
+            &mut Vec::new(),
         )
         .unwrap();
 
@@ -187,7 +195,7 @@ mod test {
         //
         //     Before: "42 \u{1b}[35m:\u{1b}[0m Num *"
         //     After:  "42 : Num *"
-        let bytes = strip_ansi_escapes::strip(writer).unwrap();
+        let bytes = strip_ansi_escapes::strip(&writer).unwrap();
         let actual = String::from_utf8(bytes).unwrap();
 
         if !actual.is_empty() {
@@ -206,6 +214,8 @@ mod test {
         } else {
             assert_eq!(expected, actual);
         }
+
+        String::from_utf8(writer).unwrap()
     }
 
     #[test]
@@ -240,6 +250,11 @@ mod test {
 
                 5│  expect 1 == 2
                     ^^^^^^^^^^^^^
+
+                The two sides were not equal:
+
+                    left:  1
+                    right: 2
                 "#
             ),
         );
@@ -271,6 +286,11 @@ mod test {
                 8│>
                 9│>      a == b
 
+                The two sides were not equal:
+
+                    left:  1
+                    right: 2
+
                 When it failed, these variables had these values:
 
                 a : Num *
@@ -309,6 +329,11 @@ mod test {
                 8│>
                 9│>      a == b
 
+                The two sides were not equal:
+
+                    left:  ["foo"]
+                    right: ["a string so long that it cannot be short"]
+
                 When it failed, these variables had these values:
 
                 a : List Str
@@ -347,6 +372,11 @@ mod test {
                 8│>
                 9│>      a == b
 
+                The two sides were not equal:
+
+                    left:  [["foo"], []]
+                    right: [["a string so long that it cannot be short", "bar"]]
+
                 When it failed, these variables had these values:
 
                 a : List (List Str)
@@ -387,6 +417,11 @@ mod test {
                  9│>
                 10│>      List.get items 0 == expected
 
+                The two sides were not equal:
+
+                    left:  Ok 0
+                    right: Ok 42
+
                 When it failed, these variables had these values:
 
                 items : List (Int Signed64)
@@ -431,6 +466,11 @@ mod test {
                 11│>
                 12│>      a == b
 
+                The two sides were not equal:
+
+                    left:  Ok "foo"
+                    right: Err "bar"
+
                 When it failed, these variables had these values:
 
                 a : Result Str Str
@@ -469,6 +509,11 @@ mod test {
                 8│>
                 9│>      vec1 == vec2
 
+                The two sides were not equal:
+
+                    left:  { x: 1, y: 2 }
+                    right: { x: 4, y: 8 }
+
                 When it failed, these variables had these values:
 
                 vec1 : {
@@ -511,6 +556,11 @@ mod test {
                 7│>
                 8│>      strings == []
 
+                The two sides were not equal:
+
+                    left:  ["Astra mortemque praestare gradatim", "Profundum et fundamentum"]
+                    right: []
+
                 When it failed, these variables had these values:
 
                 strings : List Str
@@ -546,6 +596,11 @@ mod test {
                 8│>
                 9│>      a == b
 
+                The two sides were not equal:
+
+                    left:  "Astra mortemque praestare gradatim"
+                    right: "Profundum et fundamentum"
+
                 When it failed, these variables had these values:
 
                 a : Str
@@ -626,6 +681,11 @@ mod test {
                 8│>
                 9│>      a == b
 
+                The two sides were not equal:
+
+                    left:  Box.box "Astra mortemque praestare gradatim"
+                    right: Box.box "Profundum et fundamentum"
+
                 When it failed, these variables had these values:
 
                 a : Box Str
@@ -664,6 +724,11 @@ mod test {
                 8│>
                 9│>      a == b
 
+                The two sides were not equal:
+
+                    left:  Ok "Astra mortemque praestare gradatim"
+                    right: Err "Profundum et fundamentum"
+
                 When it failed, these variables had these values:
 
                 a : [
@@ -722,6 +787,11 @@ mod test {
                 17│>
                 18│>      a == b
 
+                The two sides were not equal:
+
+                    left:  Nil
+                    right: Cons "Profundum et fundamentum" (Cons "Astra mortemque praestare gradatim" Nil)
+
                 When it failed, these variables had these values:
 
                 a : ConsList Str
@@ -770,6 +840,11 @@ mod test {
                 15│>
                 16│>      a == b
 
+                The two sides were not equal:
+
+                    left:  Leaf "Astra mortemque praestare gradatim"
+                    right: Node Empty Empty
+
                 When it failed, these variables had these values:
 
                 a : Tree Str
@@ -816,6 +891,11 @@ mod test {
                 13│>
                 14│>      a == b
 
+                The two sides were not equal:
+
+                    left:  Leaf "Astra mortemque praestare gradatim"
+                    right: Node (Leaf "a") (Leaf "b")
+
                 When it failed, these variables had these values:
 
                 a : Tree Str
@@ -862,6 +942,11 @@ mod test {
                 13│>
                 14│>      a == b
 
+                The two sides were not equal:
+
+                    left:  Tree "Astra mortemque praestare gradatim" []
+                    right: Tree "foo" [Tree "bar" []]
+
                 When it failed, these variables had these values:
 
                 a : RoseTree Str
@@ -949,6 +1034,11 @@ mod test {
                 10│>
                 11│>      forcer case == 5u8
 
+                The two sides were not equal:
+
+                    left:  2
+                    right: 5
+
                 When it failed, these variables had these values:
 
                 case : Str
@@ -977,6 +1067,11 @@ mod test {
                 3│>  expect
                 4│>      totalCount = \{} -> 1u8
                 5│>      totalCount {} == 96u8
+
+                The two sides were not equal:
+
+                    left:  1
+                    right: 96
                 "#
             ),
         );
@@ -1026,6 +1121,11 @@ mod test {
                 16│>      }
                 17│>      actual == expected
 
+                The two sides were not equal:
+
+                    left:  { body: [], headers: [], x: [] }
+                    right: { body: [42, 43, 44], headers: [15, 16, 17], x: [115, 116, 117] }
+
                 When it failed, these variables had these values:
 
                 actual : {
@@ -1093,6 +1193,11 @@ mod test {
                 20│>      }
                 21│>      actual == expected
 
+                The two sides were not equal:
+
+                    left:  { fieldA: Get, fieldB: "/things?id=2" }
+                    right: { fieldA: Get, fieldB: "/things?id=1" }
+
                 When it failed, these variables had these values:
 
                 actual : Request
@@ -1133,6 +1238,11 @@ mod test {
                  9│>
                 10│>      actual == expected
 
+                The two sides were not equal:
+
+                    left:  Leftover [49, 93]
+                    right: TooShort
+
                 When it failed, these variables had these values:
 
                 expected : [
@@ -1172,6 +1282,11 @@ mod test {
                  9│>
                 10│>      actual == expected
 
+                The two sides were not equal:
+
+                    left:  Err 1
+                    right: Ok "foobar"
+
                 When it failed, these variables had these values:
 
                 expected : Result Str U64
@@ -1201,6 +1316,11 @@ mod test {
                 4│>      t = ("One", "Two")
                 5│>      t.1 == "One"
 
+                The two sides were not equal:
+
+                    left:  "Two"
+                    right: "One"
+
                 When it failed, these variables had these values:
 
                 t : (
@@ -1239,6 +1359,11 @@ mod test {
                  9│>      expected = 't'
                 10│>      actual == expected
 
+                The two sides were not equal:
+
+                    left:  4
+                    right: 116
+
                 When it failed, these variables had these values:
 
                 actual : U8
@@ -1250,4 +1375,70 @@ mod test {
             ),
         );
     }
+
+    #[test]
+    fn structural_diff() {
+        let output = run_expect_test(
+            indoc!(
+                r#"
+                interface Test exposes [] imports []
+
+                Shelf : { label : Str, items : List [Single U8, Nested [Pair U8 U8]] }
+
+                expect
+                    actual : Shelf
+                    actual = { label: "tools", items: [Single 1, Nested (Pair 2 3)] }
+
+                    expected : Shelf
+                    expected = { label: "tools", items: [Single 1, Nested (Pair 2 4)] }
+
+                    actual == expected
+                "#
+            ),
+            indoc!(
+                r#"
+                This expectation failed:
+
+                 5│>  expect
+                 6│>      actual : Shelf
+                 7│>      actual = { label: "tools", items: [Single 1, Nested (Pair 2 3)] }
+                 8│>
+                 9│>      expected : Shelf
+                10│>      expected = { label: "tools", items: [Single 1, Nested (Pair 2 4)] }
+                11│>
+                12│>      actual == expected
+
+                The two sides were not equal:
+
+                    left:  { items: [Single 1, Nested (Pair 2 3)], label: "tools" }
+                    right: { items: [Single 1, Nested (Pair 2 4)], label: "tools" }
+
+                When it failed, these variables had these values:
+
+                actual : Shelf
+                actual = { items: [Single 1, Nested (Pair 2 3)], label: "tools" }
+
+                expected : Shelf
+                expected = { items: [Single 1, Nested (Pair 2 4)], label: "tools" }
+                "#
+            ),
+        );
+
+        // only the values which differ are highlighted, not the structure around them
+        let highlighted = |value: &str| {
+            format!(
+                "{}{}{}",
+                DEFAULT_PALETTE.error, value, DEFAULT_PALETTE.reset
+            )
+        };
+
+        assert!(output.contains(&format!(
+            "left:  {{ items: [Single 1, Nested (Pair 2 {})], label: \"tools\" }}",
+            highlighted("3")
+        )));
+        assert!(output.contains(&format!(
+            "right: {{ items: [Single 1, Nested (Pair 2 {})], label: \"tools\" }}",
+            highlighted("4")
+        )));
+    }
 }
@@ -24,6 +24,7 @@ use roc_mono::{
     ir::OptLevel,
     layout::{GlobalLayoutInterner, STLayoutInterner},
 };
+use roc_parse::ast::Expr;
 use roc_region::all::Region;
 use roc_reporting::{error::expect::Renderer, report::RenderTarget};
 use roc_target::TargetInfo;
@@ -149,9 +150,12 @@ pub fn run_inline_expects<'a, W: std::io::Write>(
         expectations,
         expects,
         &mut memory,
+        &mut Vec::new(),
     )
 }
 
+/// Runs all toplevel expects, rendering the ones which fail. The failures of pure expects are
+/// also added to `failures`, e.g. for [crate::shrink].
 #[allow(clippy::too_many_arguments)]
 pub fn run_toplevel_expects<'a, W: std::io::Write>(
     writer: &mut W,
@@ -162,6 +166,7 @@ pub fn run_toplevel_expects<'a, W: std::io::Write>(
     lib: &libloading::Library,
     expectations: &mut VecMap<ModuleId, Expectations>,
     expects: ExpectFunctions<'_>,
+    failures: &mut Vec<ExpectFailure<'a>>,
 ) -> std::io::Result<(usize, usize)> {
     let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
     let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
@@ -176,9 +181,91 @@ pub fn run_toplevel_expects<'a, W: std::io::Write>(
         expectations,
         expects,
         &mut memory,
+        failures,
     )
 }
 
+/// The outcome of one toplevel `expect`, e.g. to show it next to that `expect` in the editor
+#[derive(Debug, Clone)]
+pub struct ToplevelExpectResult {
+    pub module_id: ModuleId,
+    pub region: Region,
+    pub passed: bool,
+    /// The rendered failure or panic, with the values of the variables in the condition.
+    /// Empty if the `expect` passed.
+    pub report: String,
+}
+
+impl ToplevelExpectResult {
+    fn new(expect: ToplevelExpect<'_>, passed: bool, report: Vec<u8>) -> Self {
+        Self {
+            module_id: expect.symbol.module_id(),
+            region: expect.region,
+            passed,
+            report: String::from_utf8_lossy(&report).trim().to_string(),
+        }
+    }
+}
+
+/// Runs all toplevel expects like [run_toplevel_expects], but returns the result and the
+/// rendered report of every `expect` separately instead of writing them one after the other.
+#[allow(clippy::too_many_arguments)]
+pub fn run_toplevel_expects_separately<'a>(
+    render_target: RenderTarget,
+    arena: &'a Bump,
+    interns: &'a Interns,
+    layout_interner: &GlobalLayoutInterner<'a>,
+    lib: &libloading::Library,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    expects: ExpectFunctions<'_>,
+) -> std::io::Result<Vec<ToplevelExpectResult>> {
+    let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
+    let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
+
+    let mut results = Vec::with_capacity(expects.fx.len() + expects.pure.len());
+
+    for expect in expects.fx {
+        let mut report = Vec::new();
+
+        let passed = run_expect_fx(
+            &mut report,
+            render_target,
+            arena,
+            interns,
+            layout_interner,
+            lib,
+            expectations,
+            &mut memory,
+            expect,
+        )?;
+
+        results.push(ToplevelExpectResult::new(expect, passed, report));
+    }
+
+    memory.set_shared_buffer(lib);
+
+    for expect in expects.pure {
+        let mut report = Vec::new();
+
+        let passed = run_expect_pure(
+            &mut report,
+            render_target,
+            arena,
+            interns,
+            layout_interner,
+            lib,
+            expectations,
+            &mut memory,
+            expect,
+            &mut Vec::new(),
+        )?;
+
+        results.push(ToplevelExpectResult::new(expect, passed, report));
+    }
+
+    Ok(results)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     writer: &mut W,
@@ -190,6 +277,7 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
     expectations: &mut VecMap<ModuleId, Expectations>,
     expects: ExpectFunctions<'_>,
     memory: &mut ExpectMemory,
+    failures: &mut Vec<ExpectFailure<'a>>,
 ) -> std::io::Result<(usize, usize)> {
     let mut failed = 0;
     let mut passed = 0;
@@ -226,6 +314,7 @@ pub(crate) fn run_expects_with_memory<'a, W: std::io::Write>(
             expectations,
             memory,
             expect,
+            failures,
         )?;
 
         match result {
@@ -248,6 +337,7 @@ fn run_expect_pure<'a, W: std::io::Write>(
     expectations: &mut VecMap<ModuleId, Expectations>,
     shared_memory: &mut ExpectMemory,
     expect: ToplevelExpect<'_>,
+    failures: &mut Vec<ExpectFailure<'a>>,
 ) -> std::io::Result<bool> {
     use roc_gen_llvm::try_run_jit_function;
 
@@ -273,7 +363,7 @@ fn run_expect_pure<'a, W: std::io::Write>(
             let mut offset = ExpectSequence::START_OFFSET;
 
             for _ in 0..sequence.count_failures() {
-                offset += render_expect_failure(
+                let (length, failure) = render_expect_failure(
                     writer,
                     &renderer,
                     arena,
@@ -284,6 +374,9 @@ fn run_expect_pure<'a, W: std::io::Write>(
                     shared_memory_ptr,
                     offset,
                 )?;
+
+                offset += length;
+                failures.push(failure);
             }
         }
 
@@ -422,7 +515,7 @@ pub fn render_expects_in_memory<'a>(
         &source,
     );
 
-    render_expect_failure(
+    let (offset, _) = render_expect_failure(
         writer,
         &renderer,
         arena,
@@ -432,7 +525,9 @@ pub fn render_expects_in_memory<'a>(
         layout_interner,
         shared_ptr,
         ExpectSequence::START_OFFSET,
-    )
+    )?;
+
+    Ok(offset)
 }
 
 pub fn render_dbgs_in_memory<'a>(
@@ -552,7 +647,7 @@ fn render_expect_failure<'a>(
     layout_interner: &GlobalLayoutInterner<'a>,
     start: *const u8,
     offset: usize,
-) -> std::io::Result<usize> {
+) -> std::io::Result<(usize, ExpectFailure<'a>)> {
     // we always run programs as the host
     let target_info = (&target_lexicon::Triple::host()).into();
 
@@ -592,7 +687,31 @@ fn render_expect_failure<'a>(
         failure_region,
     )?;
 
-    Ok(offset)
+    let failure = ExpectFailure {
+        module_id,
+        expect_region,
+        failure_region,
+        lookups: symbols.into_iter().zip(expressions).collect(),
+    };
+
+    Ok((offset, failure))
+}
+
+/// Whether running this toplevel expect makes an expectation fail, without rendering anything
+pub(crate) fn expect_fails(
+    lib: &libloading::Library,
+    memory: &mut ExpectMemory,
+    expect: ToplevelExpect<'_>,
+) -> bool {
+    use roc_gen_llvm::try_run_jit_function;
+
+    memory.set_shared_buffer(lib);
+
+    let sequence = ExpectSequence::new(memory.ptr);
+
+    let result: Result<(), (String, _)> = try_run_jit_function!(lib, expect.name, (), |v: ()| v);
+
+    result.is_ok() && sequence.count_failures() > 0
 }
 
 struct ExpectSequence {
@@ -685,6 +804,18 @@ impl ExpectFrame {
     }
 }
 
+/// An expectation which failed while running a toplevel `expect`
+#[derive(Debug)]
+pub struct ExpectFailure<'a> {
+    pub module_id: ModuleId,
+    /// The toplevel `expect` which was running, if known
+    pub expect_region: Option<Region>,
+    /// The condition which evaluated to false
+    pub failure_region: Region,
+    /// The values of the variables in the condition when it failed
+    pub lookups: Vec<(Symbol, Expr<'a>)>,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct ToplevelExpect<'a> {
     pub name: &'a str,
@@ -717,6 +848,7 @@ pub fn expect_mono_module_to_dylib<'a>(
     let MonomorphizedModule {
         toplevel_expects,
         procedures,
+        static_constants,
         interns,
         mut layout_interner,
         ..
@@ -747,6 +879,11 @@ pub fn expect_mono_module_to_dylib<'a>(
         mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        profile: roc_gen_llvm::llvm::profile::ProfileMode::Off,
+        refcounting: roc_gen_llvm::llvm::refcounting::RefcountMode::NonAtomic,
+        dbg_locations: Default::default(),
+        static_constants,
+        func_spec_names: Default::default(),
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -0,0 +1,454 @@
+//! Finds smaller inputs for which a failed inline `expect` still fails, to make it easier to see
+//! what the failure is really about.
+//!
+//! The condition of the `expect` is copied into a new toplevel `expect` at the end of its module,
+//! with the variables it looked up defined as the values they had when it failed. Those values
+//! are then simplified one step at a time (emptier lists, shorter strings, numbers closer to zero),
+//! keeping every step for which the new `expect` still fails.
+
+use bumpalo::Bump;
+use roc_collections::VecMap;
+use roc_gen_llvm::llvm::build::LlvmBackendMode;
+use roc_load::{ExecutionMode, Expectations, LoadConfig, OverflowMode, Threading};
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_mono::ir::OptLevel;
+use roc_packaging::cache::{self, RocCacheDir};
+use roc_parse::ast::{AssignedField, Collection, Expr, StrLiteral};
+use roc_problem::Severity;
+use roc_region::all::{LineInfo, Loc};
+use roc_reporting::report::{RenderTarget, DEFAULT_PALETTE};
+use roc_target::TargetInfo;
+use target_lexicon::Triple;
+
+use crate::run::{expect_fails, expect_mono_module_to_dylib, ExpectFailure, ExpectMemory};
+
+/// Every attempt compiles the module again, so give up on finding smaller inputs at some point
+const MAX_ATTEMPTS: usize = 100;
+
+/// Whether the failure happened in an `expect` inside a function, and there are inputs to shrink
+pub fn can_shrink(failure: &ExpectFailure<'_>, interns: &Interns) -> bool {
+    let in_function = match failure.expect_region {
+        Some(expect_region) => !expect_region.contains(&failure.failure_region),
+        None => true,
+    };
+
+    in_function && inputs(failure, interns).next().is_some()
+}
+
+/// Where the failed `expect` is, as `path:line`
+pub fn failure_location(
+    expectations: &VecMap<ModuleId, Expectations>,
+    failure: &ExpectFailure<'_>,
+) -> String {
+    let data = expectations.get(&failure.module_id).unwrap();
+
+    let line = match std::fs::read_to_string(&data.path) {
+        Ok(source) => {
+            let line_info = LineInfo::new(&source);
+
+            line_info.convert_pos(failure.failure_region.start()).line + 1
+        }
+        Err(_) => 0,
+    };
+
+    format!("{}:{}", data.path.display(), line)
+}
+
+/// Returns the smallest inputs found, as `(name, value)` pairs, or `None` if the failure could
+/// not be reproduced outside of its function (e.g. because the condition uses a function it was
+/// passed, which can't be written down as a value).
+pub fn shrink_failure<'a>(
+    arena: &'a Bump,
+    interns: &Interns,
+    expectations: &VecMap<ModuleId, Expectations>,
+    failure: &ExpectFailure<'a>,
+) -> Option<Vec<(&'a str, String)>> {
+    let data = expectations.get(&failure.module_id)?;
+    let source = std::fs::read_to_string(&data.path).ok()?;
+
+    let region = failure.failure_region;
+    let condition = source.get(region.start().offset as usize..region.end().offset as usize)?;
+
+    let names: Vec<&'a str> = inputs(failure, interns)
+        .map(|(symbol, _)| &*arena.alloc_str(symbol.as_str(interns)))
+        .collect();
+
+    let mut values: Vec<Expr<'a>> = inputs(failure, interns).map(|(_, expr)| *expr).collect();
+
+    let shm_name = format!("/roc_expect_buffer_{}", std::process::id());
+    let mut memory = ExpectMemory::create_or_reuse_mmap(&shm_name);
+
+    let mut still_fails = |values: &[Expr<'a>]| {
+        let program = with_toplevel_expect(&source, &names, values, condition);
+
+        program_fails(data, &mut memory, source.len(), program)
+    };
+
+    if !still_fails(&values) {
+        return None;
+    }
+
+    let mut attempts = 0;
+
+    'shrink: while attempts < MAX_ATTEMPTS {
+        for index in 0..values.len() {
+            for candidate in smaller_values(arena, &values[index]) {
+                if attempts == MAX_ATTEMPTS {
+                    break 'shrink;
+                }
+
+                attempts += 1;
+
+                let mut next = values.clone();
+                next[index] = candidate;
+
+                if still_fails(&next) {
+                    values = next;
+
+                    continue 'shrink;
+                }
+            }
+        }
+
+        // No value can be made any smaller
+        break;
+    }
+
+    let shrunk = names
+        .into_iter()
+        .zip(values.iter())
+        .map(|(name, value)| (name, format_value(value, 0)))
+        .collect();
+
+    Some(shrunk)
+}
+
+/// The values of the variables the condition looked up. The sides of an `==` are captured with
+/// generated names, and aren't inputs; they follow from the others.
+fn inputs<'f, 'a>(
+    failure: &'f ExpectFailure<'a>,
+    interns: &'f Interns,
+) -> impl Iterator<Item = &'f (Symbol, Expr<'a>)> {
+    failure.lookups.iter().filter(|(symbol, _)| {
+        !symbol
+            .as_str(interns)
+            .starts_with(|c: char| c.is_ascii_digit())
+    })
+}
+
+fn format_value(expr: &Expr<'_>, indent: u16) -> String {
+    use roc_fmt::annotation::Formattable;
+
+    let arena = Bump::new();
+    let mut buf = roc_fmt::Buf::new_in(&arena);
+    expr.format(&mut buf, indent);
+
+    buf.into_bump_str().to_string()
+}
+
+/// The module's source, with an `expect` of the condition on the given values added at the end
+fn with_toplevel_expect(
+    source: &str,
+    names: &[&str],
+    values: &[Expr<'_>],
+    condition: &str,
+) -> String {
+    let mut program = String::with_capacity(source.len() + condition.len() + 64);
+
+    program.push_str(source);
+    program.push_str("\n\nexpect\n");
+
+    for (name, value) in names.iter().zip(values) {
+        program.push_str("    ");
+        program.push_str(name);
+        program.push_str(" = ");
+        program.push_str(&format_value(value, 4));
+        program.push('\n');
+    }
+
+    program.push_str("\n    ");
+    program.push_str(condition);
+    program.push('\n');
+
+    program
+}
+
+/// Compiles the program and runs the `expect` which starts after `original_length`
+fn program_fails(
+    data: &Expectations,
+    memory: &mut ExpectMemory,
+    original_length: usize,
+    program: String,
+) -> bool {
+    let arena = Bump::new();
+    let triple = Triple::host();
+
+    let load_config = LoadConfig {
+        target_info: TargetInfo::from(&triple),
+        render: RenderTarget::Generic,
+        palette: DEFAULT_PALETTE,
+        threading: Threading::Single,
+        exec_mode: ExecutionMode::Test,
+        recover_from_syntax_errors: false,
+        static_data: false,
+        overflow: OverflowMode::Trap,
+        fold_constants: true,
+    };
+
+    let src_dir = match data.path.parent() {
+        Some(parent) => parent.to_path_buf(),
+        None => return false,
+    };
+
+    let loaded = roc_load::load_and_monomorphize_from_str(
+        &arena,
+        data.path.clone(),
+        arena.alloc_str(&program),
+        src_dir,
+        RocCacheDir::Persistent(cache::roc_cache_dir().as_path()),
+        load_config,
+    );
+
+    let loaded = match loaded {
+        Ok(loaded) => loaded,
+        Err(_) => return false,
+    };
+
+    // The values might not type-check where the condition uses them
+    let has_errors = loaded
+        .can_problems
+        .values()
+        .flatten()
+        .any(|problem| problem.severity() != Severity::Warning)
+        || loaded
+            .type_problems
+            .values()
+            .flatten()
+            .any(|problem| problem.severity() != Severity::Warning);
+
+    if has_errors {
+        return false;
+    }
+
+    let (lib, expects, _) = match expect_mono_module_to_dylib(
+        &arena,
+        triple,
+        loaded,
+        OptLevel::Normal,
+        LlvmBackendMode::CliTest,
+    ) {
+        Ok(compiled) => compiled,
+        Err(_) => return false,
+    };
+
+    let added = expects
+        .pure
+        .iter()
+        .find(|expect| expect.region.start().offset as usize >= original_length);
+
+    match added {
+        Some(expect) => expect_fails(&lib, memory, *expect),
+        None => false,
+    }
+}
+
+/// Values which are a single step simpler than the given one, simplest first
+fn smaller_values<'a>(arena: &'a Bump, expr: &Expr<'a>) -> Vec<Expr<'a>> {
+    match expr {
+        Expr::Num(string) => match string.parse::<i128>() {
+            Ok(n) => smaller_ints(n)
+                .into_iter()
+                .map(|n| Expr::Num(arena.alloc_str(&n.to_string())))
+                .collect(),
+            Err(_) => Vec::new(),
+        },
+        Expr::Float(string) => match string.parse::<f64>() {
+            Ok(x) => smaller_floats(x)
+                .into_iter()
+                .map(|x| Expr::Float(arena.alloc_str(&format!("{:?}", x))))
+                .collect(),
+            Err(_) => Vec::new(),
+        },
+        Expr::Str(StrLiteral::PlainLine(string)) => {
+            let chars: Vec<char> = string.chars().collect();
+
+            let mut candidates: Vec<String> = Vec::new();
+
+            if !chars.is_empty() {
+                candidates.push(String::new());
+                candidates.push(chars[..chars.len() / 2].iter().collect());
+                candidates.push(chars[..chars.len() - 1].iter().collect());
+            }
+
+            candidates.dedup();
+
+            candidates
+                .into_iter()
+                .filter(|candidate| candidate != *string)
+                .map(|candidate| Expr::Str(StrLiteral::PlainLine(arena.alloc_str(&candidate))))
+                .collect()
+        }
+        Expr::List(items) => {
+            let items = items.items;
+            let mut candidates = Vec::new();
+
+            if !items.is_empty() {
+                candidates.push(Expr::List(Collection::empty()));
+            }
+
+            if items.len() > 2 {
+                let (front, back) = items.split_at(items.len() / 2);
+
+                candidates.push(Expr::List(Collection::with_items(front)));
+                candidates.push(Expr::List(Collection::with_items(back)));
+            }
+
+            if items.len() > 1 {
+                for index in 0..items.len() {
+                    let mut fewer =
+                        bumpalo::collections::Vec::from_iter_in(items.iter().copied(), arena);
+                    fewer.remove(index);
+
+                    candidates.push(Expr::List(Collection::with_items(fewer.into_bump_slice())));
+                }
+            }
+
+            candidates.extend(
+                smaller_items(arena, items)
+                    .into_iter()
+                    .map(|items| Expr::List(Collection::with_items(items))),
+            );
+
+            candidates
+        }
+        Expr::Tuple(items) => smaller_items(arena, items.items)
+            .into_iter()
+            .map(|items| Expr::Tuple(Collection::with_items(items)))
+            .collect(),
+        Expr::Apply(function, args, called_via) => smaller_items(arena, args)
+            .into_iter()
+            .map(|args| Expr::Apply(*function, args, *called_via))
+            .collect(),
+        Expr::Record(fields) => {
+            let fields = fields.items;
+            let mut candidates = Vec::new();
+
+            for (index, field) in fields.iter().enumerate() {
+                if let AssignedField::RequiredValue(label, spaces, value) = field.value {
+                    for smaller in smaller_values(arena, &value.value) {
+                        let mut new_fields =
+                            bumpalo::collections::Vec::from_iter_in(fields.iter().copied(), arena);
+
+                        let value = arena.alloc(Loc::at(value.region, smaller));
+                        new_fields[index] = Loc::at(
+                            field.region,
+                            AssignedField::RequiredValue(label, spaces, value),
+                        );
+
+                        candidates.push(Expr::Record(Collection::with_items(
+                            new_fields.into_bump_slice(),
+                        )));
+                    }
+                }
+            }
+
+            candidates
+        }
+        Expr::ParensAround(inner) => smaller_values(arena, inner)
+            .into_iter()
+            .map(|smaller| Expr::ParensAround(arena.alloc(smaller)))
+            .collect(),
+        Expr::SpaceBefore(inner, spaces) => smaller_values(arena, inner)
+            .into_iter()
+            .map(|smaller| Expr::SpaceBefore(arena.alloc(smaller), *spaces))
+            .collect(),
+        Expr::SpaceAfter(inner, spaces) => smaller_values(arena, inner)
+            .into_iter()
+            .map(|smaller| Expr::SpaceAfter(arena.alloc(smaller), *spaces))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The items, with one of them made smaller
+fn smaller_items<'a>(
+    arena: &'a Bump,
+    items: &'a [&'a Loc<Expr<'a>>],
+) -> Vec<&'a [&'a Loc<Expr<'a>>]> {
+    let mut candidates = Vec::new();
+
+    for (index, item) in items.iter().enumerate() {
+        for smaller in smaller_values(arena, &item.value) {
+            let mut new_items =
+                bumpalo::collections::Vec::from_iter_in(items.iter().copied(), arena);
+            new_items[index] = arena.alloc(Loc::at(item.region, smaller));
+
+            candidates.push(new_items.into_bump_slice());
+        }
+    }
+
+    candidates
+}
+
+fn smaller_ints(n: i128) -> Vec<i128> {
+    let mut candidates = vec![0, n / 2, n - n.signum()];
+
+    candidates.dedup();
+    candidates.retain(|candidate| *candidate != n);
+
+    candidates
+}
+
+fn smaller_floats(x: f64) -> Vec<f64> {
+    let mut candidates = vec![0.0, x.trunc(), x / 2.0];
+
+    candidates.dedup();
+    candidates.retain(|candidate| candidate.is_finite() && *candidate != x);
+
+    candidates
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ints_shrink_towards_zero() {
+        assert_eq!(smaller_ints(0), Vec::<i128>::new());
+        assert_eq!(smaller_ints(1), vec![0]);
+        assert_eq!(smaller_ints(10), vec![0, 5, 9]);
+        assert_eq!(smaller_ints(-3), vec![0, -1, -2]);
+    }
+
+    #[test]
+    fn lists_shrink_to_fewer_and_smaller_items() {
+        let arena = Bump::new();
+
+        let item = |n| &*arena.alloc(Loc::at_zero(Expr::Num(n)));
+        let items = arena.alloc_slice_copy(&[item("1"), item("2"), item("3")]);
+        let list = Expr::List(Collection::with_items(items));
+
+        let candidates: Vec<String> = smaller_values(&arena, &list)
+            .iter()
+            .map(|candidate| format_value(candidate, 0))
+            .collect();
+
+        assert_eq!(
+            candidates,
+            [
+                "[]",
+                "[1]",
+                "[2, 3]",
+                "[2, 3]",
+                "[1, 3]",
+                "[1, 2]",
+                "[0, 2, 3]",
+                "[1, 0, 3]",
+                "[1, 1, 3]",
+                "[1, 2, 0]",
+                "[1, 2, 1]",
+                "[1, 2, 2]",
+            ]
+        );
+    }
+}
@@ -30,6 +30,18 @@ fn persisted_defs() {
     complete("val1 + x + y", &mut state, Ok(("15 : Num *", "val2")));
 }
 
+#[test]
+fn reevaluated_defs() {
+    let mut state = ReplState::new();
+
+    complete("x = 5", &mut state, Ok(("5 : Num *", "x")));
+    complete("s = \"unused\"", &mut state, Ok(("\"unused\" : Str", "s")));
+    complete("x + 1", &mut state, Ok(("6 : Num *", "val1")));
+    complete("val1", &mut state, Ok(("6 : Num *", "val1")));
+    complete("val1", &mut state, Ok(("6 : Num *", "val1")));
+    complete("val1 * x", &mut state, Ok(("30 : Num *", "val2")));
+}
+
 #[test]
 fn annotated_body() {
     let mut input = "t : [A, B, C]".to_string();
@@ -101,6 +113,35 @@ fn standalone_annotation() {
     assert_eq!(state.step(&input, None), Ok(String::new()));
 }
 
+#[test]
+fn type_of_expr() {
+    let mut state = ReplState::new();
+
+    assert_eq!(
+        state.step("Color : [Red, Green, Blue]", None),
+        Ok(String::new())
+    );
+
+    let mut input = "c : Color".to_string();
+
+    incomplete(&mut input);
+
+    input.push_str("c = Red");
+
+    assert!(state.step(&input, None).is_ok());
+
+    error(
+        ":type c",
+        &mut state,
+        "c : Color\n\nHint: Alias Color = [Blue, Green, Red]".to_string(),
+    );
+    error(
+        ":type Str.concat",
+        &mut state,
+        "Str.concat : Str, Str -> Str".to_string(),
+    );
+}
+
 /// validate and step the given input, then check the Result vs the output
 /// with ANSI escape codes stripped.
 fn complete(input: &str, state: &mut ReplState, expected_step_result: Result<(&str, &str), i32>) {
@@ -671,6 +671,11 @@ fn type_problem() {
                 But add needs its 2nd argument to be:
 
                     Num *
+
+                Tip: A value of type Num * can become any number type, depending on
+                how it is used, but never a type that isn't a number. If nothing
+                decides, number literals become I64, or the module's DefaultInt alias
+                if it defines one.
                 "#
         ),
     );
@@ -0,0 +1,101 @@
+//! The English text of every message, which every other catalog falls back to.
+use super::Message::{self, *};
+
+pub(super) fn template(message: Message) -> &'static str {
+    match message {
+        SyntaxProblem => "SYNTAX PROBLEM",
+        NamingProblem => "NAMING PROBLEM",
+        UnrecognizedName => "UNRECOGNIZED NAME",
+        UnusedDef => "UNUSED DEFINITION",
+        UnusedImport => "UNUSED IMPORT",
+        UnusedAliasParam => "UNUSED TYPE ALIAS PARAMETER",
+        UnboundTypeVariable => "UNBOUND TYPE VARIABLE",
+        UnusedArg => "UNUSED ARGUMENT",
+        MissingDefinition => "MISSING DEFINITION",
+        UnknownGeneratesWith => "UNKNOWN GENERATES FUNCTION",
+        MisplacedPure => "MISPLACED PURE",
+        DuplicateFieldName => "DUPLICATE FIELD NAME",
+        DuplicateTagName => "DUPLICATE TAG NAME",
+        InvalidUnicode => "INVALID UNICODE",
+        CircularDef => "CIRCULAR DEFINITION",
+        DuplicateName => "DUPLICATE NAME",
+        ValueNotExposed => "NOT EXPOSED",
+        ModuleNotImported => "MODULE NOT IMPORTED",
+        NestedDatatype => "NESTED DATATYPE",
+        ConflictingNumberSuffix => "CONFLICTING NUMBER SUFFIX",
+        NumberOverflowsSuffix => "NUMBER OVERFLOWS SUFFIX",
+        NumberUnderflowsSuffix => "NUMBER UNDERFLOWS SUFFIX",
+        OpaqueNotDefined => "OPAQUE TYPE NOT DEFINED",
+        OpaqueDeclaredOutsideScope => "OPAQUE TYPE DECLARED OUTSIDE SCOPE",
+        OpaqueNotApplied => "OPAQUE TYPE NOT APPLIED",
+        OpaqueOverApplied => "OPAQUE TYPE APPLIED TO TOO MANY ARGS",
+        InvalidExtensionType => "INVALID_EXTENSION_TYPE",
+        AbilityHasTypeVariables => "ABILITY HAS TYPE VARIABLES",
+        HasClauseIsNotAnAbility => "HAS CLAUSE IS NOT AN ABILITY",
+        IllegalHasClause => "ILLEGAL HAS CLAUSE",
+        AbilityMemberMissingHasClause => "ABILITY MEMBER MISSING HAS CLAUSE",
+        AbilityMemberBindsMultipleVariables => "ABILITY MEMBER BINDS MULTIPLE VARIABLES",
+        AbilityNotOnToplevel => "ABILITY NOT ON TOP-LEVEL",
+        SpecializationNotOnToplevel => "SPECIALIZATION NOT ON TOP-LEVEL",
+        AbilityUsedAsType => "ABILITY USED AS TYPE",
+        IllegalDerive => "ILLEGAL DERIVE",
+        ImplementationNotFound => "IMPLEMENTATION NOT FOUND",
+        NotAnAbilityMember => "NOT AN ABILITY MEMBER",
+        NotAnAbility => "NOT AN ABILITY",
+        OptionalAbilityImplementation => "OPTIONAL ABILITY IMPLEMENTATION",
+        QualifiedAbilityImplementation => "QUALIFIED ABILITY IMPLEMENTATION",
+        AbilityImplementationNotIdentifier => "ABILITY IMPLEMENTATION NOT IDENTIFIER",
+        DuplicateImplementation => "DUPLICATE IMPLEMENTATION",
+        ConflictingImplementations => "CONFLICTING IMPLEMENTATIONS",
+        UnnecessaryImplementations => "UNNECESSARY IMPLEMENTATIONS",
+        IncompleteAbilityImplementation => "INCOMPLETE ABILITY IMPLEMENTATION",
+        InvalidNumberDefault => "INVALID NUMBER DEFAULT",
+
+        UnusedDefNotUsed => "{0} is not used anywhere in your code.",
+        UnusedDefHint => "If you didn't intend on using {0} then remove it so future readers of your code don't wonder why it is there.",
+        UnusedImportNotUsed => "{0} is not used in this module.",
+        UnusedModuleImportNotUsed => "Nothing from {0} is used in this module.",
+        UnusedImportHint => "Since {0} isn't used, you don't need to import it.",
+        DefOnlyUsedInRecursion => "This definition is only used in recursion with itself:",
+        DefOnlyUsedInRecursionHint => "If you don't intend to use or export this definition, it should be removed!",
+
+        PhantomTypeExample => PHANTOM_TYPE_EXAMPLE,
+        UnboundTypeVariableExample => UNBOUND_TYPE_VARIABLE_EXAMPLE,
+        CyclicAliasExample => CYCLIC_ALIAS_EXAMPLE,
+    }
+}
+
+// Paragraphs are separated by an empty line, and lines indented by four spaces are code.
+
+const PHANTOM_TYPE_EXAMPLE: &str = "\
+For example, this type parameter is only there to keep distances in meters and in feet apart:
+
+    Distance unit := F64
+
+A value of a tag union with just one tag takes up no memory, so the parameter can be used in a \
+field without making the type any bigger:
+
+    Meters : [Meters]
+    Feet : [Feet]
+
+    Distance unit := { length : F64, unit : unit }";
+
+const UNBOUND_TYPE_VARIABLE_EXAMPLE: &str = "\
+For example, `a` is not bound in this definition:
+
+    Pair : [Pair a a]
+
+It is bound when it is a parameter of the type:
+
+    Pair a : [Pair a a]
+
+Then every use of the type says what `a` is, like `Pair Str`.";
+
+const CYCLIC_ALIAS_EXAMPLE: &str = "\
+For example, a record can't contain itself, because it would take up infinite memory:
+
+    Person : { name : Str, friend : Person }
+
+Inside a tag union with another tag, the recursion can end, and Roc stores it behind a pointer:
+
+    Person : { name : Str, friend : [Friend Person, NoFriend] }";
@@ -0,0 +1,42 @@
+//! Spanish text, as an example of a catalog. Messages that aren't in here yet are shown in English.
+use super::Message::{self, *};
+
+pub(super) fn template(message: Message) -> Option<&'static str> {
+    let text = match message {
+        SyntaxProblem => "PROBLEMA DE SINTAXIS",
+        NamingProblem => "PROBLEMA DE NOMBRES",
+        UnrecognizedName => "NOMBRE NO RECONOCIDO",
+        UnusedDef => "DEFINICIÓN SIN USAR",
+        UnusedImport => "IMPORTACIÓN SIN USAR",
+        UnusedAliasParam => "PARÁMETRO DE ALIAS DE TIPO SIN USAR",
+        UnboundTypeVariable => "VARIABLE DE TIPO SIN LIGAR",
+        UnusedArg => "ARGUMENTO SIN USAR",
+        MissingDefinition => "FALTA UNA DEFINICIÓN",
+        UnknownGeneratesWith => "FUNCIÓN GENERADORA DESCONOCIDA",
+        DuplicateFieldName => "NOMBRE DE CAMPO DUPLICADO",
+        DuplicateTagName => "NOMBRE DE ETIQUETA DUPLICADO",
+        InvalidUnicode => "UNICODE NO VÁLIDO",
+        CircularDef => "DEFINICIÓN CIRCULAR",
+        DuplicateName => "NOMBRE DUPLICADO",
+        ValueNotExposed => "NO EXPUESTO",
+        ModuleNotImported => "MÓDULO NO IMPORTADO",
+        NestedDatatype => "TIPO DE DATOS ANIDADO",
+        ConflictingNumberSuffix => "SUFIJOS NUMÉRICOS EN CONFLICTO",
+        NumberOverflowsSuffix => "EL NÚMERO DESBORDA SU SUFIJO",
+        NumberUnderflowsSuffix => "EL NÚMERO QUEDA POR DEBAJO DE SU SUFIJO",
+        OpaqueNotDefined => "TIPO OPACO NO DEFINIDO",
+        OpaqueDeclaredOutsideScope => "TIPO OPACO DECLARADO FUERA DE ALCANCE",
+        OpaqueNotApplied => "TIPO OPACO NO APLICADO",
+        OpaqueOverApplied => "TIPO OPACO APLICADO A DEMASIADOS ARGUMENTOS",
+        UnusedDefNotUsed => "{0} no se usa en ninguna parte de tu código.",
+        UnusedDefHint => "Si no pensabas usar {0}, elimínalo para que quienes lean tu código en el futuro no se pregunten por qué está ahí.",
+        UnusedImportNotUsed => "{0} no se usa en este módulo.",
+        UnusedModuleImportNotUsed => "Nada de {0} se usa en este módulo.",
+        UnusedImportHint => "Como {0} no se usa, no hace falta importarlo.",
+        DefOnlyUsedInRecursion => "Esta definición solo se usa en una recursión consigo misma:",
+        DefOnlyUsedInRecursionHint => "Si no piensas usar ni exportar esta definición, ¡deberías eliminarla!",
+        _ => return None,
+    };
+
+    Some(text)
+}
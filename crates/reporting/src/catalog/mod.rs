@@ -0,0 +1,197 @@
+//! The text of diagnostics, kept apart from the code that builds reports so it can be translated.
+//!
+//! Reports refer to their text by [`Message`]. The text of a message is looked up in the catalog
+//! of the language set with [`set_language`], and falls back to English when that catalog doesn't
+//! have the message yet. Text can have argument slots, `{0}`, `{1}` and so on, which are filled in
+//! with documents by [`RocDocAllocator::message`](crate::report::RocDocAllocator::message), so a
+//! translation can put the arguments wherever its grammar needs them.
+//!
+//! Adding a language takes a module like [`es`] with a `template` function, and a [`Language`]
+//! variant that uses it. The CLI picks the language from the `ROC_LANG` environment variable.
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+mod en;
+mod es;
+
+macro_rules! messages {
+    ($($(#[$attr:meta])* $name:ident,)*) => {
+        /// Every piece of text a report can be built from
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum Message {
+            $($(#[$attr])* $name,)*
+        }
+
+        impl Message {
+            pub const ALL: &'static [Message] = &[$(Message::$name,)*];
+        }
+    };
+}
+
+messages! {
+    SyntaxProblem,
+    NamingProblem,
+    UnrecognizedName,
+    UnusedDef,
+    UnusedImport,
+    UnusedAliasParam,
+    UnboundTypeVariable,
+    UnusedArg,
+    MissingDefinition,
+    UnknownGeneratesWith,
+    MisplacedPure,
+    DuplicateFieldName,
+    DuplicateTagName,
+    InvalidUnicode,
+    CircularDef,
+    DuplicateName,
+    ValueNotExposed,
+    ModuleNotImported,
+    NestedDatatype,
+    ConflictingNumberSuffix,
+    NumberOverflowsSuffix,
+    NumberUnderflowsSuffix,
+    OpaqueNotDefined,
+    OpaqueDeclaredOutsideScope,
+    OpaqueNotApplied,
+    OpaqueOverApplied,
+    InvalidExtensionType,
+    AbilityHasTypeVariables,
+    HasClauseIsNotAnAbility,
+    IllegalHasClause,
+    AbilityMemberMissingHasClause,
+    AbilityMemberBindsMultipleVariables,
+    AbilityNotOnToplevel,
+    SpecializationNotOnToplevel,
+    AbilityUsedAsType,
+    IllegalDerive,
+    ImplementationNotFound,
+    NotAnAbilityMember,
+    NotAnAbility,
+    OptionalAbilityImplementation,
+    QualifiedAbilityImplementation,
+    AbilityImplementationNotIdentifier,
+    DuplicateImplementation,
+    ConflictingImplementations,
+    UnnecessaryImplementations,
+    IncompleteAbilityImplementation,
+    InvalidNumberDefault,
+
+    /// `{0}`: the unused def
+    UnusedDefNotUsed,
+    /// `{0}`: the unused def
+    UnusedDefHint,
+    /// `{0}`: the unused import
+    UnusedImportNotUsed,
+    /// `{0}`: the module nothing is used from
+    UnusedModuleImportNotUsed,
+    /// `{0}`: the unused import or module
+    UnusedImportHint,
+    DefOnlyUsedInRecursion,
+    DefOnlyUsedInRecursionHint,
+
+    // worked examples, which expand a tip when reports are verbose
+    PhantomTypeExample,
+    UnboundTypeVariableExample,
+    CyclicAliasExample,
+}
+
+impl Message {
+    /// The text of this message in the language that is set
+    pub fn text(self) -> &'static str {
+        self.template(language())
+    }
+
+    /// The text of this message in `language`, or in English when it has no translation yet
+    pub fn template(self, language: Language) -> &'static str {
+        let translation = match language {
+            Language::English => None,
+            Language::Spanish => es::template(self),
+        };
+
+        translation.unwrap_or_else(|| en::template(self))
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.text())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: &'static [Language] = &[Language::English, Language::Spanish];
+
+    /// The language of a locale like `es`, `es_MX` or `es_ES.UTF-8`, as used in `ROC_LANG` and
+    /// `LANG`. `C` and `POSIX` are English.
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let code = locale
+            .split(|c| matches!(c, '_' | '-' | '.' | '@'))
+            .next()
+            .unwrap_or_default();
+
+        match code.to_ascii_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Language::English),
+            "es" => Some(Language::Spanish),
+            _ => None,
+        }
+    }
+}
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+/// Sets the language of every report this process renders from then on
+pub fn set_language(language: Language) {
+    let index = Language::ALL.iter().position(|l| *l == language).unwrap();
+
+    LANGUAGE.store(index as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    Language::ALL[LANGUAGE.load(Ordering::Relaxed) as usize]
+}
+
+/// A piece of a message's text: either text to show as it is, or the argument in a slot
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Piece<'a> {
+    Text(&'a str),
+    Slot(usize),
+}
+
+/// Splits text into its plain text and its `{n}` argument slots. A `{` that doesn't start a slot
+/// is plain text.
+pub fn pieces(template: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut rest = template;
+    let mut searched = 0;
+
+    while let Some(open) = rest[searched..].find('{').map(|index| searched + index) {
+        let after = &rest[open + 1..];
+        let digits = after.bytes().take_while(u8::is_ascii_digit).count();
+
+        if digits > 0 && after[digits..].starts_with('}') {
+            if open > 0 {
+                pieces.push(Piece::Text(&rest[..open]));
+            }
+
+            pieces.push(Piece::Slot(after[..digits].parse().unwrap()));
+
+            rest = &after[digits + 1..];
+            searched = 0;
+        } else {
+            searched = open + 1;
+        }
+    }
+
+    if !rest.is_empty() {
+        pieces.push(Piece::Text(rest));
+    }
+
+    pieces
+}
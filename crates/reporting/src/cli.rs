@@ -23,25 +23,21 @@ impl Problems {
     }
 
     pub fn print_to_stdout(&self, total_time: std::time::Duration) {
-        const GREEN: usize = 32;
-        const YELLOW: usize = 33;
+        use crate::report::{ansi_color, ANSI_STYLE_CODES};
+
+        let color = |count: usize| match count {
+            0 => ANSI_STYLE_CODES.green,
+            _ => ANSI_STYLE_CODES.yellow,
+        };
 
         print!(
-            "\x1B[{}m{}\x1B[39m {} and \x1B[{}m{}\x1B[39m {} found in {} ms",
-            match self.errors {
-                0 => GREEN,
-                _ => YELLOW,
-            },
-            self.errors,
+            "{} {} and {} {} found in {} ms",
+            ansi_color(color(self.errors), self.errors),
             match self.errors {
                 1 => "error",
                 _ => "errors",
             },
-            match self.warnings {
-                0 => GREEN,
-                _ => YELLOW,
-            },
-            self.warnings,
+            ansi_color(color(self.warnings), self.warnings),
             match self.warnings {
                 1 => "warning",
                 _ => "warnings",
@@ -143,14 +139,13 @@ pub fn report_problems(
         }
     }
 
-    // If we printed any problems, print a horizontal rule at the end,
-    // and then clear any ANSI escape codes (e.g. colors) we've used.
+    // If we printed any problems, print a horizontal rule at the end.
     //
     // The horizontal rule is nice when running the program right after
     // compiling it, as it lets you clearly see where the compiler
     // errors/warnings end and the program output begins.
     if problems_reported > 0 {
-        println!("{}\u{001B}[0m\n", Report::horizontal_rule(&palette));
+        println!("{}\n", Report::horizontal_rule(&palette));
     }
 
     Problems {
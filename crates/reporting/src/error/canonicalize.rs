@@ -11,54 +11,59 @@ use roc_region::all::{LineColumn, LineColumnRegion, LineInfo, Loc, Region};
 use roc_types::types::AliasKind;
 use std::path::PathBuf;
 
+use crate::catalog::Message;
 use crate::error::r#type::suggest;
 use crate::report::{to_file_problem_report, Annotation, Report, RocDocAllocator, RocDocBuilder};
 use ven_pretty::{text, DocAllocator};
 
-const SYNTAX_PROBLEM: &str = "SYNTAX PROBLEM";
-const NAMING_PROBLEM: &str = "NAMING PROBLEM";
-const UNRECOGNIZED_NAME: &str = "UNRECOGNIZED NAME";
-const UNUSED_DEF: &str = "UNUSED DEFINITION";
-const UNUSED_IMPORT: &str = "UNUSED IMPORT";
-const UNUSED_ALIAS_PARAM: &str = "UNUSED TYPE ALIAS PARAMETER";
-const UNBOUND_TYPE_VARIABLE: &str = "UNBOUND TYPE VARIABLE";
-const UNUSED_ARG: &str = "UNUSED ARGUMENT";
-const MISSING_DEFINITION: &str = "MISSING DEFINITION";
-const UNKNOWN_GENERATES_WITH: &str = "UNKNOWN GENERATES FUNCTION";
-const DUPLICATE_FIELD_NAME: &str = "DUPLICATE FIELD NAME";
-const DUPLICATE_TAG_NAME: &str = "DUPLICATE TAG NAME";
-const INVALID_UNICODE: &str = "INVALID UNICODE";
-pub const CIRCULAR_DEF: &str = "CIRCULAR DEFINITION";
-const DUPLICATE_NAME: &str = "DUPLICATE NAME";
-const VALUE_NOT_EXPOSED: &str = "NOT EXPOSED";
-const MODULE_NOT_IMPORTED: &str = "MODULE NOT IMPORTED";
-const NESTED_DATATYPE: &str = "NESTED DATATYPE";
-const CONFLICTING_NUMBER_SUFFIX: &str = "CONFLICTING NUMBER SUFFIX";
-const NUMBER_OVERFLOWS_SUFFIX: &str = "NUMBER OVERFLOWS SUFFIX";
-const NUMBER_UNDERFLOWS_SUFFIX: &str = "NUMBER UNDERFLOWS SUFFIX";
-const OPAQUE_NOT_DEFINED: &str = "OPAQUE TYPE NOT DEFINED";
-const OPAQUE_DECLARED_OUTSIDE_SCOPE: &str = "OPAQUE TYPE DECLARED OUTSIDE SCOPE";
-const OPAQUE_NOT_APPLIED: &str = "OPAQUE TYPE NOT APPLIED";
-const OPAQUE_OVER_APPLIED: &str = "OPAQUE TYPE APPLIED TO TOO MANY ARGS";
-const INVALID_EXTENSION_TYPE: &str = "INVALID_EXTENSION_TYPE";
-const ABILITY_HAS_TYPE_VARIABLES: &str = "ABILITY HAS TYPE VARIABLES";
-const HAS_CLAUSE_IS_NOT_AN_ABILITY: &str = "HAS CLAUSE IS NOT AN ABILITY";
-const ILLEGAL_HAS_CLAUSE: &str = "ILLEGAL HAS CLAUSE";
-const ABILITY_MEMBER_MISSING_HAS_CLAUSE: &str = "ABILITY MEMBER MISSING HAS CLAUSE";
-const ABILITY_MEMBER_BINDS_MULTIPLE_VARIABLES: &str = "ABILITY MEMBER BINDS MULTIPLE VARIABLES";
-const ABILITY_NOT_ON_TOPLEVEL: &str = "ABILITY NOT ON TOP-LEVEL";
-const SPECIALIZATION_NOT_ON_TOPLEVEL: &str = "SPECIALIZATION NOT ON TOP-LEVEL";
-const ABILITY_USED_AS_TYPE: &str = "ABILITY USED AS TYPE";
-const ILLEGAL_DERIVE: &str = "ILLEGAL DERIVE";
-const IMPLEMENTATION_NOT_FOUND: &str = "IMPLEMENTATION NOT FOUND";
-const NOT_AN_ABILITY_MEMBER: &str = "NOT AN ABILITY MEMBER";
-const NOT_AN_ABILITY: &str = "NOT AN ABILITY";
-const OPTIONAL_ABILITY_IMPLEMENTATION: &str = "OPTIONAL ABILITY IMPLEMENTATION";
-const QUALIFIED_ABILITY_IMPLEMENTATION: &str = "QUALIFIED ABILITY IMPLEMENTATION";
-const ABILITY_IMPLEMENTATION_NOT_IDENTIFIER: &str = "ABILITY IMPLEMENTATION NOT IDENTIFIER";
-const DUPLICATE_IMPLEMENTATION: &str = "DUPLICATE IMPLEMENTATION";
-const UNNECESSARY_IMPLEMENTATIONS: &str = "UNNECESSARY IMPLEMENTATIONS";
-const INCOMPLETE_ABILITY_IMPLEMENTATION: &str = "INCOMPLETE ABILITY IMPLEMENTATION";
+const SYNTAX_PROBLEM: Message = Message::SyntaxProblem;
+const NAMING_PROBLEM: Message = Message::NamingProblem;
+const UNRECOGNIZED_NAME: Message = Message::UnrecognizedName;
+const UNUSED_DEF: Message = Message::UnusedDef;
+const UNUSED_IMPORT: Message = Message::UnusedImport;
+const UNUSED_ALIAS_PARAM: Message = Message::UnusedAliasParam;
+const UNBOUND_TYPE_VARIABLE: Message = Message::UnboundTypeVariable;
+const UNUSED_ARG: Message = Message::UnusedArg;
+const MISSING_DEFINITION: Message = Message::MissingDefinition;
+const UNKNOWN_GENERATES_WITH: Message = Message::UnknownGeneratesWith;
+const MISPLACED_PURE: Message = Message::MisplacedPure;
+const DUPLICATE_FIELD_NAME: Message = Message::DuplicateFieldName;
+const DUPLICATE_TAG_NAME: Message = Message::DuplicateTagName;
+const INVALID_UNICODE: Message = Message::InvalidUnicode;
+pub const CIRCULAR_DEF: Message = Message::CircularDef;
+const DUPLICATE_NAME: Message = Message::DuplicateName;
+const VALUE_NOT_EXPOSED: Message = Message::ValueNotExposed;
+const MODULE_NOT_IMPORTED: Message = Message::ModuleNotImported;
+const NESTED_DATATYPE: Message = Message::NestedDatatype;
+const CONFLICTING_NUMBER_SUFFIX: Message = Message::ConflictingNumberSuffix;
+const NUMBER_OVERFLOWS_SUFFIX: Message = Message::NumberOverflowsSuffix;
+const NUMBER_UNDERFLOWS_SUFFIX: Message = Message::NumberUnderflowsSuffix;
+const OPAQUE_NOT_DEFINED: Message = Message::OpaqueNotDefined;
+const OPAQUE_DECLARED_OUTSIDE_SCOPE: Message = Message::OpaqueDeclaredOutsideScope;
+const OPAQUE_NOT_APPLIED: Message = Message::OpaqueNotApplied;
+const OPAQUE_OVER_APPLIED: Message = Message::OpaqueOverApplied;
+const INVALID_EXTENSION_TYPE: Message = Message::InvalidExtensionType;
+const ABILITY_HAS_TYPE_VARIABLES: Message = Message::AbilityHasTypeVariables;
+const HAS_CLAUSE_IS_NOT_AN_ABILITY: Message = Message::HasClauseIsNotAnAbility;
+const ILLEGAL_HAS_CLAUSE: Message = Message::IllegalHasClause;
+const ABILITY_MEMBER_MISSING_HAS_CLAUSE: Message = Message::AbilityMemberMissingHasClause;
+const ABILITY_MEMBER_BINDS_MULTIPLE_VARIABLES: Message =
+    Message::AbilityMemberBindsMultipleVariables;
+const ABILITY_NOT_ON_TOPLEVEL: Message = Message::AbilityNotOnToplevel;
+const SPECIALIZATION_NOT_ON_TOPLEVEL: Message = Message::SpecializationNotOnToplevel;
+const ABILITY_USED_AS_TYPE: Message = Message::AbilityUsedAsType;
+const ILLEGAL_DERIVE: Message = Message::IllegalDerive;
+const IMPLEMENTATION_NOT_FOUND: Message = Message::ImplementationNotFound;
+const NOT_AN_ABILITY_MEMBER: Message = Message::NotAnAbilityMember;
+const NOT_AN_ABILITY: Message = Message::NotAnAbility;
+const OPTIONAL_ABILITY_IMPLEMENTATION: Message = Message::OptionalAbilityImplementation;
+const QUALIFIED_ABILITY_IMPLEMENTATION: Message = Message::QualifiedAbilityImplementation;
+const ABILITY_IMPLEMENTATION_NOT_IDENTIFIER: Message = Message::AbilityImplementationNotIdentifier;
+const DUPLICATE_IMPLEMENTATION: Message = Message::DuplicateImplementation;
+const CONFLICTING_IMPLEMENTATIONS: Message = Message::ConflictingImplementations;
+const UNNECESSARY_IMPLEMENTATIONS: Message = Message::UnnecessaryImplementations;
+const INCOMPLETE_ABILITY_IMPLEMENTATION: Message = Message::IncompleteAbilityImplementation;
+const INVALID_NUMBER_DEFAULT: Message = Message::InvalidNumberDefault;
 
 pub fn can_problem<'b>(
     alloc: &'b RocDocAllocator<'b>,
@@ -72,62 +77,46 @@ pub fn can_problem<'b>(
 
     match problem {
         Problem::UnusedDef(symbol, region) => {
-            let line =
-                r#" then remove it so future readers of your code don't wonder why it is there."#;
-
             doc = alloc.stack([
-                alloc
-                    .symbol_unqualified(symbol)
-                    .append(alloc.reflow(" is not used anywhere in your code.")),
+                alloc.message(
+                    Message::UnusedDefNotUsed,
+                    [alloc.symbol_unqualified(symbol)],
+                ),
                 alloc.region(lines.convert_region(region)),
-                alloc
-                    .reflow("If you didn't intend on using ")
-                    .append(alloc.symbol_unqualified(symbol))
-                    .append(alloc.reflow(line)),
+                alloc.message(Message::UnusedDefHint, [alloc.symbol_unqualified(symbol)]),
             ]);
 
             title = UNUSED_DEF.to_string();
         }
         Problem::UnusedImport(symbol, region) => {
             doc = alloc.stack([
-                alloc.concat([
-                    alloc.symbol_qualified(symbol),
-                    alloc.reflow(" is not used in this module."),
-                ]),
+                alloc.message(
+                    Message::UnusedImportNotUsed,
+                    [alloc.symbol_qualified(symbol)],
+                ),
                 alloc.region(lines.convert_region(region)),
-                alloc.concat([
-                    alloc.reflow("Since "),
-                    alloc.symbol_qualified(symbol),
-                    alloc.reflow(" isn't used, you don't need to import it."),
-                ]),
+                alloc.message(Message::UnusedImportHint, [alloc.symbol_qualified(symbol)]),
             ]);
 
             title = UNUSED_IMPORT.to_string();
         }
         Problem::UnusedModuleImport(module_id, region) => {
             doc = alloc.stack([
-                alloc.concat([
-                    alloc.reflow("Nothing from "),
-                    alloc.module(module_id),
-                    alloc.reflow(" is used in this module."),
-                ]),
+                alloc.message(
+                    Message::UnusedModuleImportNotUsed,
+                    [alloc.module(module_id)],
+                ),
                 alloc.region(lines.convert_region(region)),
-                alloc.concat([
-                    alloc.reflow("Since "),
-                    alloc.module(module_id),
-                    alloc.reflow(" isn't used, you don't need to import it."),
-                ]),
+                alloc.message(Message::UnusedImportHint, [alloc.module(module_id)]),
             ]);
 
             title = UNUSED_IMPORT.to_string();
         }
         Problem::DefsOnlyUsedInRecursion(1, region) => {
             doc = alloc.stack([
-                alloc.reflow("This definition is only used in recursion with itself:"),
+                alloc.message(Message::DefOnlyUsedInRecursion, []),
                 alloc.region(lines.convert_region(region)),
-                alloc.reflow(
-                    "If you don't intend to use or export this definition, it should be removed!",
-                ),
+                alloc.message(Message::DefOnlyUsedInRecursionHint, []),
             ]);
 
             title = "DEFINITION ONLY USED IN RECURSION".to_string();
@@ -176,6 +165,26 @@ pub fn can_problem<'b>(
 
             title = UNKNOWN_GENERATES_WITH.to_string();
         }
+        Problem::MisplacedPure(symbol, region) => {
+            doc = alloc.stack([
+                alloc
+                    .reflow("The annotation of the hosted function ")
+                    .append(alloc.symbol_unqualified(symbol))
+                    .append(alloc.reflow(" uses "))
+                    .append(alloc.type_str("Pure"))
+                    .append(alloc.reflow(" somewhere it doesn't belong:")),
+                alloc.region(lines.convert_region(region)),
+                alloc.concat([
+                    alloc.reflow("Only the result of a hosted function which doesn't return an effect can be "),
+                    alloc.type_str("Pure"),
+                    alloc.reflow(", like "),
+                    alloc.type_str("sqrtApprox : F32 -> Pure F32"),
+                    alloc.reflow(". Running an effect is never pure."),
+                ]),
+            ]);
+
+            title = MISPLACED_PURE.to_string();
+        }
         Problem::UnusedArgument(closure_symbol, is_anonymous, argument_symbol, region) => {
             let line = "\". Adding an underscore at the start of a variable name is a way of saying that the variable is not used.";
 
@@ -326,10 +335,13 @@ pub fn can_problem<'b>(
                 alloc.region(lines.convert_region(variable_region)),
                 alloc.reflow("Roc does not allow unused type parameters!"),
                 // TODO add link to this guide section
-                alloc.tip().append(alloc.reflow(
-                    "If you want an unused type parameter (a so-called \"phantom type\"), \
+                alloc.tip_with_example(
+                    alloc.tip().append(alloc.reflow(
+                        "If you want an unused type parameter (a so-called \"phantom type\"), \
                 read the guide section on phantom values.",
-                )),
+                    )),
+                    Message::PhantomTypeExample,
+                ),
             ]);
 
             title = UNUSED_ALIAS_PARAM.to_string();
@@ -358,14 +370,17 @@ pub fn can_problem<'b>(
                 stack.push(alloc.reflow("Here is one occurrence:"));
             }
             stack.push(alloc.region(lines.convert_region(one_occurrence)));
-            stack.push(alloc.tip().append(alloc.concat([
-                alloc.reflow("Type variables must be bound before the "),
-                alloc.keyword(match kind {
-                    AliasKind::Structural => ":",
-                    AliasKind::Opaque => ":=",
-                }),
-                alloc.reflow(". Perhaps you intended to add a type parameter to this type?"),
-            ])));
+            stack.push(alloc.tip_with_example(
+                alloc.tip().append(alloc.concat([
+                    alloc.reflow("Type variables must be bound before the "),
+                    alloc.keyword(match kind {
+                        AliasKind::Structural => ":",
+                        AliasKind::Opaque => ":=",
+                    }),
+                    alloc.reflow(". Perhaps you intended to add a type parameter to this type?"),
+                ])),
+                Message::UnboundTypeVariableExample,
+            ));
             doc = alloc.stack(stack);
 
             title = UNBOUND_TYPE_VARIABLE.to_string();
@@ -900,6 +915,31 @@ pub fn can_problem<'b>(
             ]);
             title = DUPLICATE_IMPLEMENTATION.to_string();
         }
+        Problem::ConflictingImpls {
+            ability_member,
+            opaque,
+            first_import,
+            second_import,
+        } => {
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("This import brings in an implementation of "),
+                    alloc.symbol_qualified(ability_member),
+                    alloc.reflow(" for "),
+                    alloc.symbol_unqualified(opaque),
+                    alloc.reflow(":"),
+                ]),
+                alloc.region(lines.convert_region(first_import)),
+                alloc.reflow("But this import brings in a different one:"),
+                alloc.region(lines.convert_region(second_import)),
+                alloc.concat([
+                    alloc.reflow("An ability can only be implemented for an opaque type in the module that defines the type, so this happens when two copies of that module are loaded, like from two different versions of the same package. Make sure these imports get "),
+                    alloc.symbol_unqualified(opaque),
+                    alloc.reflow(" from the same version of its package."),
+                ]),
+            ]);
+            title = CONFLICTING_IMPLEMENTATIONS.to_string();
+        }
         Problem::ImplementsNonRequired {
             region,
             ability,
@@ -1093,11 +1133,51 @@ pub fn can_problem<'b>(
             ]);
             title = "OVERAPPLIED CRASH".to_string();
         }
+        Problem::InvalidNumLiteralDefault {
+            alias,
+            region,
+            expects_int,
+        } => {
+            let (literals, choices) = if expects_int {
+                ("integer literals", "an integer type like I32, U64, or Nat")
+            } else {
+                ("fraction literals", "one of Dec, F32, or F64")
+            };
+
+            doc = alloc.stack([
+                alloc.concat([
+                    alloc.reflow("The "),
+                    alloc.symbol_unqualified(alias),
+                    alloc.reflow(" alias chooses the type of unannotated "),
+                    alloc.reflow(literals),
+                    alloc.reflow(" in this module, but it isn't a builtin number type I can use:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+                alloc.concat([
+                    alloc.reflow("It must be exactly "),
+                    alloc.reflow(choices),
+                    alloc.reflow(". Until then, I'll use the usual defaults: "),
+                    alloc.type_str("I64"),
+                    alloc.reflow(" for integers and "),
+                    alloc.type_str("F64"),
+                    alloc.reflow(" for fractions."),
+                ]),
+            ]);
+            title = INVALID_NUMBER_DEFAULT.to_string();
+        }
         Problem::FileProblem { filename, error } => {
             let report = to_file_problem_report(alloc, &filename, error);
             doc = report.doc;
             title = report.title;
         }
+        Problem::SyntaxError {
+            title: syntax_title,
+            message,
+            ..
+        } => {
+            doc = alloc.vcat(message.lines().map(|line| alloc.string(line.to_string())));
+            title = syntax_title;
+        }
     };
 
     Report {
@@ -1549,7 +1629,7 @@ fn report_shadowing<'b>(
         ])
     };
 
-    (DUPLICATE_NAME, doc)
+    (DUPLICATE_NAME.text(), doc)
 }
 
 fn pretty_runtime_error<'b>(
@@ -1582,11 +1662,11 @@ fn pretty_runtime_error<'b>(
 
         RuntimeError::LookupNotInScope(loc_name, options) => {
             doc = not_found(alloc, lines, loc_name.region, &loc_name.value, options);
-            title = UNRECOGNIZED_NAME;
+            title = UNRECOGNIZED_NAME.text();
         }
         RuntimeError::CircularDef(entries) => {
             doc = to_circular_def_doc(alloc, lines, &entries);
-            title = CIRCULAR_DEF;
+            title = CIRCULAR_DEF.text();
         }
         RuntimeError::MalformedPattern(problem, region) => {
             use roc_parse::ast::Base;
@@ -1600,7 +1680,7 @@ fn pretty_runtime_error<'b>(
                 MalformedBase(Base::Octal) => " octal integer ",
                 MalformedBase(Base::Decimal) => " integer ",
                 BadIdent(bad_ident) => {
-                    title = NAMING_PROBLEM;
+                    title = NAMING_PROBLEM.text();
                     doc = to_bad_ident_pattern_report(alloc, lines, bad_ident, region);
 
                     return (doc, title);
@@ -1637,7 +1717,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = SYNTAX_PROBLEM.text();
         }
         RuntimeError::UnsupportedPattern(_) => {
             todo!("unsupported patterns are currently not parsed!")
@@ -1678,7 +1758,7 @@ fn pretty_runtime_error<'b>(
                 did_you_mean,
             ]);
 
-            title = VALUE_NOT_EXPOSED;
+            title = VALUE_NOT_EXPOSED.text();
         }
 
         RuntimeError::ModuleNotImported {
@@ -1696,7 +1776,7 @@ fn pretty_runtime_error<'b>(
                 module_exists,
             );
 
-            title = MODULE_NOT_IMPORTED;
+            title = MODULE_NOT_IMPORTED.text();
         }
         RuntimeError::InvalidPrecedence(_, _) => {
             // do nothing, reported with PrecedenceProblem
@@ -1705,7 +1785,7 @@ fn pretty_runtime_error<'b>(
         RuntimeError::MalformedIdentifier(_box_str, bad_ident, surroundings) => {
             doc = to_bad_ident_expr_report(alloc, lines, bad_ident, surroundings);
 
-            title = SYNTAX_PROBLEM;
+            title = SYNTAX_PROBLEM.text();
         }
         RuntimeError::MalformedTypeName(_box_str, surroundings) => {
             doc = alloc.stack([
@@ -1721,11 +1801,15 @@ fn pretty_runtime_error<'b>(
                 ]),
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = SYNTAX_PROBLEM.text();
         }
         RuntimeError::MalformedClosure(_) => {
             todo!("");
         }
+        RuntimeError::MalformedExpr(_) => {
+            // do nothing, reported with Problem::SyntaxError
+            unreachable!();
+        }
         RuntimeError::InvalidFloat(sign @ FloatErrorKind::PositiveInfinity, region, _raw_str)
         | RuntimeError::InvalidFloat(sign @ FloatErrorKind::NegativeInfinity, region, _raw_str) => {
             let tip = alloc
@@ -1755,7 +1839,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = SYNTAX_PROBLEM.text();
         }
         RuntimeError::InvalidFloat(FloatErrorKind::Error, region, _raw_str) => {
             let tip = alloc
@@ -1773,7 +1857,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = SYNTAX_PROBLEM.text();
         }
         RuntimeError::InvalidFloat(FloatErrorKind::IntSuffix, region, _raw_str) => {
             doc = alloc.stack([
@@ -1783,7 +1867,7 @@ fn pretty_runtime_error<'b>(
                 alloc.region(lines.convert_region(region)),
             ]);
 
-            title = CONFLICTING_NUMBER_SUFFIX;
+            title = CONFLICTING_NUMBER_SUFFIX.text();
         }
         RuntimeError::InvalidInt(error @ IntErrorKind::InvalidDigit, base, region, _raw_str)
         | RuntimeError::InvalidInt(error @ IntErrorKind::Empty, base, region, _raw_str) => {
@@ -1844,7 +1928,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = SYNTAX_PROBLEM.text();
         }
         RuntimeError::InvalidInt(error_kind @ IntErrorKind::Underflow, _base, region, _raw_str)
         | RuntimeError::InvalidInt(error_kind @ IntErrorKind::Overflow, _base, region, _raw_str) => {
@@ -1887,7 +1971,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = SYNTAX_PROBLEM.text();
         }
         RuntimeError::InvalidInt(IntErrorKind::FloatSuffix, _base, region, _raw_str) => {
             doc = alloc.stack([
@@ -1897,7 +1981,7 @@ fn pretty_runtime_error<'b>(
                 alloc.region(lines.convert_region(region)),
             ]);
 
-            title = CONFLICTING_NUMBER_SUFFIX;
+            title = CONFLICTING_NUMBER_SUFFIX.text();
         }
         RuntimeError::InvalidInt(
             IntErrorKind::OverflowsSuffix {
@@ -1921,7 +2005,7 @@ fn pretty_runtime_error<'b>(
                 ])),
             ]);
 
-            title = NUMBER_OVERFLOWS_SUFFIX;
+            title = NUMBER_OVERFLOWS_SUFFIX.text();
         }
         RuntimeError::InvalidInt(
             IntErrorKind::UnderflowsSuffix {
@@ -1945,7 +2029,7 @@ fn pretty_runtime_error<'b>(
                 ])),
             ]);
 
-            title = NUMBER_UNDERFLOWS_SUFFIX;
+            title = NUMBER_UNDERFLOWS_SUFFIX.text();
         }
         RuntimeError::InvalidOptionalValue {
             field_name,
@@ -1960,7 +2044,7 @@ fn pretty_runtime_error<'b>(
                 record_region,
             );
 
-            title = SYNTAX_PROBLEM;
+            title = SYNTAX_PROBLEM.text();
         }
         RuntimeError::InvalidRecordUpdate { region } => {
             doc = alloc.stack([
@@ -1972,7 +2056,7 @@ fn pretty_runtime_error<'b>(
                 alloc.reflow("Only variables can be updated with record update syntax."),
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = SYNTAX_PROBLEM.text();
         }
         RuntimeError::InvalidHexadecimal(region) => {
             todo!(
@@ -2005,7 +2089,7 @@ fn pretty_runtime_error<'b>(
                 .append(alloc.module(symbol.module_id()))
                 .append(alloc.reflow(", but it was not defined anywhere in that module."))]);
 
-            title = MISSING_DEFINITION;
+            title = MISSING_DEFINITION.text();
         }
         RuntimeError::EmptySingleQuote(region) => {
             let tip = alloc
@@ -2018,7 +2102,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = SYNTAX_PROBLEM.text();
         }
         RuntimeError::MultipleCharsInSingleQuote(region) => {
             let tip = alloc
@@ -2034,7 +2118,7 @@ fn pretty_runtime_error<'b>(
                 tip,
             ]);
 
-            title = SYNTAX_PROBLEM;
+            title = SYNTAX_PROBLEM.text();
         }
         RuntimeError::OpaqueNotDefined {
             usage:
@@ -2084,7 +2168,7 @@ fn pretty_runtime_error<'b>(
 
             doc = alloc.stack(stack);
 
-            title = OPAQUE_NOT_DEFINED;
+            title = OPAQUE_NOT_DEFINED.text();
         }
         RuntimeError::OpaqueOutsideScope {
             opaque,
@@ -2105,7 +2189,7 @@ fn pretty_runtime_error<'b>(
                 ),
             ]);
 
-            title = OPAQUE_DECLARED_OUTSIDE_SCOPE;
+            title = OPAQUE_DECLARED_OUTSIDE_SCOPE.text();
         }
         RuntimeError::OpaqueNotApplied(loc_ident) => {
             doc = alloc.stack([
@@ -2114,7 +2198,7 @@ fn pretty_runtime_error<'b>(
                 alloc.note("Opaque types always wrap exactly one argument!"),
             ]);
 
-            title = OPAQUE_NOT_APPLIED;
+            title = OPAQUE_NOT_APPLIED.text();
         }
         RuntimeError::OpaqueAppliedToMultipleArgs(region) => {
             doc = alloc.stack([
@@ -2123,7 +2207,7 @@ fn pretty_runtime_error<'b>(
                 alloc.note("Opaque types always wrap exactly one argument!"),
             ]);
 
-            title = OPAQUE_OVER_APPLIED;
+            title = OPAQUE_OVER_APPLIED.text();
         }
         RuntimeError::DegenerateBranch(region) => {
             doc = alloc.stack([
@@ -4,13 +4,15 @@ use bumpalo::Bump;
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_parse::ast::Expr;
 use roc_problem::Severity;
-use roc_region::all::{LineColumnRegion, LineInfo, Region};
+use roc_region::all::{LineColumnRegion, LineInfo, Loc, Region};
 use roc_types::{
     subs::{Subs, Variable},
     types::{ErrorType, Polarity},
 };
 
-use crate::report::{RenderTarget, RocDocAllocator, RocDocBuilder};
+use crate::report::{
+    ansi_color, Annotation, RenderTarget, RocDocAllocator, RocDocBuilder, ANSI_STYLE_CODES,
+};
 
 pub struct Renderer<'a> {
     arena: &'a Bump,
@@ -72,6 +74,224 @@ impl<'a> Renderer<'a> {
         ])
     }
 
+    fn format_expr(&self, expr: &Expr<'_>) -> &'a str {
+        use roc_fmt::annotation::Formattable;
+
+        let mut buf = roc_fmt::Buf::new_in(self.arena);
+        strip_spaces(expr).format(&mut buf, 0);
+
+        buf.into_bump_str()
+    }
+
+    /// The sides of an `==` in an expect are captured in symbols with generated names, which
+    /// users can't write themselves (see `capture_compared_values` in roc_can)
+    fn is_compared_value(&self, symbol: Symbol) -> bool {
+        symbol
+            .as_str(self.alloc.interns)
+            .starts_with(|c: char| c.is_ascii_digit())
+    }
+
+    /// Render both values, highlighting the parts where they differ
+    fn render_diff(
+        &'a self,
+        left: &Expr<'_>,
+        right: &Expr<'_>,
+    ) -> (RocDocBuilder<'a>, RocDocBuilder<'a>) {
+        use roc_parse::ast::AssignedField;
+        use ven_pretty::DocAllocator;
+
+        let alloc = &self.alloc;
+
+        match (strip_spaces(left), strip_spaces(right)) {
+            (Expr::List(left_items), Expr::List(right_items)) => {
+                let (left_items, right_items) =
+                    self.render_diff_items(left_items.items, right_items.items);
+
+                (
+                    self.render_sequence("[", left_items, "]"),
+                    self.render_sequence("[", right_items, "]"),
+                )
+            }
+            (Expr::Tuple(left_items), Expr::Tuple(right_items)) => {
+                let (left_items, right_items) =
+                    self.render_diff_items(left_items.items, right_items.items);
+
+                (
+                    self.render_sequence("(", left_items, ")"),
+                    self.render_sequence("(", right_items, ")"),
+                )
+            }
+            (Expr::Record(left_fields), Expr::Record(right_fields)) => {
+                fn required_values<'b>(
+                    fields: &'b [Loc<AssignedField<'b, Expr<'b>>>],
+                ) -> Option<Vec<(&'b str, &'b Expr<'b>)>> {
+                    fields
+                        .iter()
+                        .map(|field| {
+                            let mut field = &field.value;
+
+                            loop {
+                                match field {
+                                    AssignedField::SpaceBefore(inner, _)
+                                    | AssignedField::SpaceAfter(inner, _) => field = inner,
+                                    AssignedField::RequiredValue(label, _, value) => {
+                                        return Some((label.value, &value.value))
+                                    }
+                                    _ => return None,
+                                }
+                            }
+                        })
+                        .collect()
+                }
+
+                let left_fields = required_values(left_fields.items);
+                let right_fields = required_values(right_fields.items);
+
+                match (left_fields, right_fields) {
+                    (Some(left_fields), Some(right_fields)) => (
+                        self.render_diff_fields(&left_fields, &right_fields),
+                        self.render_diff_fields(&right_fields, &left_fields),
+                    ),
+                    _ => self.render_diff_leaves(left, right),
+                }
+            }
+            (
+                Expr::Apply(
+                    Loc {
+                        value: Expr::Tag(left_tag),
+                        ..
+                    },
+                    left_args,
+                    _,
+                ),
+                Expr::Apply(
+                    Loc {
+                        value: Expr::Tag(right_tag),
+                        ..
+                    },
+                    right_args,
+                    _,
+                ),
+            ) if left_tag == right_tag && left_args.len() == right_args.len() => {
+                let (left_docs, right_docs) = self.render_diff_items(left_args, right_args);
+
+                let apply = |args: &[&Loc<Expr<'_>>], docs: Vec<RocDocBuilder<'a>>| {
+                    let args = args.iter().zip(docs).map(|(arg, doc)| {
+                        // tags applied to other values need parentheses, like `Ok (Ok 1)`
+                        let doc = match strip_spaces(&arg.value) {
+                            Expr::Apply(..) => doc.parens(),
+                            _ => doc,
+                        };
+
+                        alloc.text(" ").append(doc)
+                    });
+
+                    alloc.text(left_tag.to_string()).append(alloc.concat(args))
+                };
+
+                (apply(left_args, left_docs), apply(right_args, right_docs))
+            }
+            _ => self.render_diff_leaves(left, right),
+        }
+    }
+
+    /// Render the fields of `this` record, highlighting the ones which differ in `other`
+    fn render_diff_fields(
+        &'a self,
+        this: &[(&str, &Expr<'_>)],
+        other: &[(&str, &Expr<'_>)],
+    ) -> RocDocBuilder<'a> {
+        let fields = this
+            .iter()
+            .map(|(label, value)| {
+                let other_value = other
+                    .iter()
+                    .find(|(other_label, _)| other_label == label)
+                    .map(|(_, other_value)| other_value);
+
+                let value = match other_value {
+                    Some(other_value) => self.render_diff(value, other_value).0,
+                    None => self.render_changed(value),
+                };
+
+                self.alloc
+                    .text(label.to_string())
+                    .append(": ")
+                    .append(value)
+            })
+            .collect();
+
+        self.render_sequence("{ ", fields, " }")
+    }
+
+    fn render_diff_items(
+        &'a self,
+        left: &[&Loc<Expr<'_>>],
+        right: &[&Loc<Expr<'_>>],
+    ) -> (Vec<RocDocBuilder<'a>>, Vec<RocDocBuilder<'a>>) {
+        let mut left_docs = Vec::with_capacity(left.len());
+        let mut right_docs = Vec::with_capacity(right.len());
+
+        for index in 0..left.len().max(right.len()) {
+            match (left.get(index), right.get(index)) {
+                (Some(left_item), Some(right_item)) => {
+                    let (left_doc, right_doc) =
+                        self.render_diff(&left_item.value, &right_item.value);
+
+                    left_docs.push(left_doc);
+                    right_docs.push(right_doc);
+                }
+                (Some(left_item), None) => left_docs.push(self.render_changed(&left_item.value)),
+                (None, Some(right_item)) => right_docs.push(self.render_changed(&right_item.value)),
+                (None, None) => unreachable!(),
+            }
+        }
+
+        (left_docs, right_docs)
+    }
+
+    fn render_diff_leaves(
+        &'a self,
+        left: &Expr<'_>,
+        right: &Expr<'_>,
+    ) -> (RocDocBuilder<'a>, RocDocBuilder<'a>) {
+        let left_str = self.format_expr(left);
+        let right_str = self.format_expr(right);
+
+        if left_str == right_str {
+            (self.alloc.text(left_str), self.alloc.text(right_str))
+        } else {
+            (
+                self.alloc.text(left_str).annotate(Annotation::Error),
+                self.alloc.text(right_str).annotate(Annotation::Error),
+            )
+        }
+    }
+
+    fn render_changed(&'a self, expr: &Expr<'_>) -> RocDocBuilder<'a> {
+        self.alloc
+            .text(self.format_expr(expr))
+            .annotate(Annotation::Error)
+    }
+
+    fn render_sequence(
+        &'a self,
+        open: &'static str,
+        items: Vec<RocDocBuilder<'a>>,
+        close: &'static str,
+    ) -> RocDocBuilder<'a> {
+        use ven_pretty::DocAllocator;
+
+        if items.is_empty() {
+            return self.alloc.text(open.trim()).append(close.trim());
+        }
+
+        self.alloc
+            .text(open)
+            .append(self.alloc.intersperse(items, self.alloc.text(", ")))
+            .append(close)
+    }
+
     fn render_lookups(
         &'a self,
         subs: &mut Subs,
@@ -83,32 +303,48 @@ impl<'a> Renderer<'a> {
     ) -> RocDocBuilder<'a> {
         use ven_pretty::DocAllocator;
 
-        let it =
-            symbols
-                .iter()
-                .zip(variables)
-                .zip(expressions)
-                .map(|((symbol, variable), expr)| {
-                    let error_type = subs.var_to_error_type(*variable, Polarity::OF_VALUE);
-                    self.render_lookup(*symbol, expr, error_type)
-                });
-
-        if it.len() > 0 {
-            self.alloc.stack([
-                self.alloc.text("This expectation failed:"),
-                self.alloc.region(line_col_region),
+        let mut compared = Vec::new();
+        let mut lookups = Vec::new();
+
+        for ((symbol, variable), expr) in symbols.iter().zip(variables).zip(expressions) {
+            if self.is_compared_value(*symbol) {
+                compared.push(expr);
+            } else {
+                let error_type = subs.var_to_error_type(*variable, Polarity::OF_VALUE);
+                lookups.push(self.render_lookup(*symbol, expr, error_type));
+            }
+        }
+
+        let mut docs = vec![
+            self.alloc.text("This expectation failed:"),
+            self.alloc.region(line_col_region),
+        ];
+
+        if let [left, right] = compared.as_slice() {
+            let (left, right) = self.render_diff(left, right);
+
+            docs.push(self.alloc.text("The two sides were not equal:"));
+            docs.push(
+                self.alloc
+                    .vcat([
+                        self.alloc.text("left:  ").append(left),
+                        self.alloc.text("right: ").append(right),
+                    ])
+                    .indent(4),
+            );
+        }
+
+        if !lookups.is_empty() {
+            docs.push(
                 self.alloc
                     .text("When it failed, these variables had these values:"),
-                self.alloc.stack(it),
-                self.alloc.text(""), // Blank line at the end
-            ])
-        } else {
-            self.alloc.stack([
-                self.alloc.text("This expectation failed:"),
-                self.alloc.region(line_col_region),
-                self.alloc.text(""), // Blank line at the end
-            ])
+            );
+            docs.push(self.alloc.stack(lookups));
         }
+
+        docs.push(self.alloc.text("")); // Blank line at the end
+
+        self.alloc.stack(docs)
     }
 
     fn to_line_col_region(
@@ -182,13 +418,13 @@ impl<'a> Renderer<'a> {
         W: std::io::Write,
     {
         let line_col_region = self.to_line_col_region(expect_region, dbg_expr_region);
-        write!(
-            writer,
-            "\u{001b}[36m[{} {}:{}] \u{001b}[0m",
+        let location = format!(
+            "[{} {}:{}]",
             self.filename.display(),
             line_col_region.start.line + 1,
             line_col_region.start.column + 1
-        )?;
+        );
+        write!(writer, "{} ", ansi_color(ANSI_STYLE_CODES.cyan, location))?;
 
         let expr = expressions[0];
 
@@ -241,3 +477,14 @@ impl<'a> Renderer<'a> {
         write!(writer, "{}", buf)
     }
 }
+
+fn strip_spaces<'e>(mut expr: &'e Expr<'e>) -> &'e Expr<'e> {
+    loop {
+        match expr {
+            Expr::SpaceBefore(inner, _)
+            | Expr::SpaceAfter(inner, _)
+            | Expr::ParensAround(inner) => expr = inner,
+            _ => return expr,
+        }
+    }
+}
@@ -3915,6 +3915,9 @@ fn to_ability_def_report<'a>(
                 alloc.reflow(" annotating the signature of this value next."),
             ]),
         ),
+        EAbility::DefaultBody(expr, pos) => {
+            to_expr_report(alloc, lines, filename, Context::InDef(start), expr, *pos)
+        }
     }
 }
 
@@ -1,7 +1,8 @@
 #![allow(clippy::too_many_arguments)]
 
+use crate::catalog::Message;
 use crate::error::canonicalize::{to_circular_def_doc, CIRCULAR_DEF};
-use crate::report::{Annotation, Report, RocDocAllocator, RocDocBuilder};
+use crate::report::{in_charset, Annotation, Report, RocDocAllocator, RocDocBuilder};
 use itertools::EitherOrBoth;
 use itertools::Itertools;
 use roc_can::expected::{Expected, PExpected};
@@ -11,18 +12,19 @@ use roc_error_macros::internal_error;
 use roc_exhaustive::{CtorName, ListArity};
 use roc_module::called_via::{BinOp, CalledVia};
 use roc_module::ident::{IdentStr, Lowercase, TagName};
-use roc_module::symbol::Symbol;
+use roc_module::symbol::{ModuleId, Symbol};
 use roc_problem::Severity;
 use roc_region::all::{LineInfo, Region};
 use roc_solve_problem::{
-    NotDerivableContext, NotDerivableDecode, NotDerivableEncode, NotDerivableEq, TypeError,
-    UnderivableReason, Unfulfilled,
+    NotDerivableContext, NotDerivableDecode, NotDerivableEncode, NotDerivableEnum, NotDerivableEq,
+    TypeError, UnderivableReason, Unfulfilled,
 };
 use roc_std::RocDec;
+use roc_types::num::NumLiteralDefaults;
 use roc_types::pretty_print::{Parens, WILDCARD};
 use roc_types::types::{
     AbilitySet, AliasKind, Category, ErrorType, IndexOrField, PatternCategory, Polarity, Reason,
-    RecordField, TypeExt,
+    RecordField, TypeExt, ALIAS_EXPANSION_BUDGET,
 };
 use std::path::PathBuf;
 use ven_pretty::{text, DocAllocator};
@@ -248,6 +250,43 @@ pub fn type_problem<'b>(
                 severity,
             })
         }
+        SingleAbilityImplementation {
+            region,
+            symbol,
+            implementations,
+        } => {
+            let mut stack = vec![
+                alloc.concat([
+                    alloc.symbol_unqualified(symbol),
+                    alloc.reflow(" is generic over abilities, but it's only ever used with one type for each of them:"),
+                ]),
+                alloc.region(lines.convert_region(region)),
+            ];
+
+            stack.extend(implementations.into_iter().map(|(ability, typ)| {
+                alloc.concat([
+                    alloc.reflow("It only ever uses the "),
+                    alloc.symbol_unqualified(ability),
+                    alloc.reflow(" implementation of "),
+                    alloc.inline_type_block(error_type_to_doc(alloc, typ)),
+                    alloc.reflow("."),
+                ])
+            }));
+
+            stack.push(alloc.concat([
+                alloc.tip(),
+                alloc.reflow("Annotating "),
+                alloc.symbol_unqualified(symbol),
+                alloc.reflow(" with these types instead makes its signature easier to read, and keeps new uses from adding more specializations of it."),
+            ]));
+
+            Some(Report {
+                title: "SINGLE ABILITY IMPLEMENTATION".to_string(),
+                filename,
+                doc: alloc.stack(stack),
+                severity,
+            })
+        }
     }
 }
 
@@ -460,6 +499,17 @@ fn underivable_hint<'b>(
                 ])))
             }
         },
+        NotDerivableContext::Enum(reason) => match reason {
+            NotDerivableEnum::Payload(tag_name) => {
+                Some(alloc.note("").append(alloc.concat([
+                    alloc.reflow("I can only derive "),
+                    alloc.symbol_unqualified(Symbol::ENUM_ENUM),
+                    alloc.reflow(" for tag unions whose tags have no payloads, but "),
+                    alloc.tag_name(tag_name),
+                    alloc.reflow(" has one."),
+                ])))
+            }
+        },
     }
 }
 
@@ -468,7 +518,7 @@ pub fn cyclic_alias<'b>(
     lines: &LineInfo,
     symbol: Symbol,
     region: roc_region::all::Region,
-    others: Vec<Symbol>,
+    others: Vec<(Symbol, roc_region::all::Region)>,
     alias_kind: AliasKind,
 ) -> (RocDocBuilder<'b>, String) {
     let when_is_recursion_legal =
@@ -488,7 +538,7 @@ pub fn cyclic_alias<'b>(
             when_is_recursion_legal,
         ])
     } else {
-        alloc.stack([
+        let mut stack = vec![
             alloc
                 .reflow("The ")
                 .append(alloc.symbol_unqualified(symbol))
@@ -509,12 +559,31 @@ pub fn cyclic_alias<'b>(
                 4,
                 alloc.symbol_unqualified(symbol),
                 others
-                    .into_iter()
-                    .map(|other| alloc.symbol_unqualified(other))
+                    .iter()
+                    .map(|(other, _)| alloc.symbol_unqualified(*other))
                     .collect::<Vec<_>>(),
             ),
-            when_is_recursion_legal,
-        ])
+        ];
+
+        for (other, other_region) in others {
+            stack.push(
+                alloc
+                    .reflow("The ")
+                    .append(alloc.symbol_unqualified(other))
+                    .append(alloc.reflow(" definition is here:")),
+            );
+            stack.push(alloc.region(lines.convert_region(other_region)));
+        }
+
+        stack.push(when_is_recursion_legal);
+        stack.push(alloc.tip_with_example(
+            alloc.tip().append(alloc.reflow(
+                "To break the cycle, put one of the references in this chain inside a tag, in a tagged union which also has a tag that does not refer back.",
+            )),
+            Message::CyclicAliasExample,
+        ));
+
+        alloc.stack(stack)
     };
 
     (doc, "CYCLIC ALIAS".to_string())
@@ -838,7 +907,7 @@ fn to_expr_report<'b>(
                     region,
                     Some(expr_region),
                     problem,
-                    alloc.text("Right now it’s"),
+                    alloc.text(in_charset("Right now it’s")),
                     alloc.concat([
                         alloc.reflow("But I need every "),
                         alloc.keyword("expect"),
@@ -878,7 +947,7 @@ fn to_expr_report<'b>(
                     region,
                     Some(expr_region),
                     problem,
-                    alloc.text("Right now it’s"),
+                    alloc.text(in_charset("Right now it’s")),
                     alloc.concat([
                         alloc.reflow("But I need every "),
                         alloc.keyword("if"),
@@ -917,7 +986,7 @@ fn to_expr_report<'b>(
                     region,
                     Some(expr_region),
                     problem,
-                    alloc.text("Right now it’s"),
+                    alloc.text(in_charset("Right now it’s")),
                     alloc.concat([
                         alloc.reflow("But I need every "),
                         alloc.keyword("if"),
@@ -1645,6 +1714,7 @@ fn type_comparison<'b>(
     instead_of: RocDocBuilder<'b>,
     context_hints: Option<RocDocBuilder<'b>>,
 ) -> RocDocBuilder<'b> {
+    let alias_hints = alias_hints(alloc, &actual, &expected);
     let comparison = to_comparison(alloc, actual, expected);
 
     let mut lines = vec![
@@ -1663,6 +1733,7 @@ fn type_comparison<'b>(
         comparison.problems,
         expectation_context,
     ));
+    lines.extend(alias_hints);
 
     alloc.stack(lines)
 }
@@ -1675,6 +1746,7 @@ fn lone_type<'b>(
     i_am_seeing: RocDocBuilder<'b>,
     further_details: RocDocBuilder<'b>,
 ) -> RocDocBuilder<'b> {
+    let alias_hints = alias_hints(alloc, &actual, &expected);
     let comparison = to_comparison(alloc, actual, expected);
 
     let mut lines = vec![i_am_seeing, comparison.actual, further_details];
@@ -1684,10 +1756,80 @@ fn lone_type<'b>(
         comparison.problems,
         expectation_context,
     ));
+    lines.extend(alias_hints);
 
     alloc.stack(lines)
 }
 
+/// Aliases that are bigger than [ALIAS_EXPANSION_BUDGET] are shown by name, so list what they
+/// stand for, like `Hint: Alias Config =` followed by its definition. Builtin aliases are well known and left out.
+fn alias_hints<'b>(
+    alloc: &'b RocDocAllocator<'b>,
+    actual: &ErrorType,
+    expected: &ErrorType,
+) -> Vec<RocDocBuilder<'b>> {
+    let mut aliases = Vec::new();
+
+    find_big_aliases(actual, &mut aliases);
+    find_big_aliases(expected, &mut aliases);
+
+    aliases
+        .into_iter()
+        .map(|(alias, definition)| {
+            alloc.stack([
+                alloc.concat([
+                    alloc.hint("Alias "),
+                    to_doc(alloc, Parens::Unnecessary, alias).0,
+                    alloc.text(" ="),
+                ]),
+                alloc.type_block(to_doc(alloc, Parens::Unnecessary, definition).0),
+            ])
+        })
+        .collect()
+}
+
+/// The aliases in the type that are too big to expand, along with their definition
+fn find_big_aliases(tipe: &ErrorType, aliases: &mut Vec<(ErrorType, ErrorType)>) {
+    use ErrorType::*;
+
+    match tipe {
+        Alias(symbol, args, actual, kind) => {
+            args.iter().for_each(|arg| find_big_aliases(arg, aliases));
+
+            let is_listed = aliases
+                .iter()
+                .any(|(alias, _)| matches!(alias, Alias(listed, ..) if listed == symbol));
+
+            if *kind == AliasKind::Structural
+                && !symbol.module_id().is_builtin()
+                && actual.size() > ALIAS_EXPANSION_BUDGET
+                && !is_listed
+            {
+                aliases.push((tipe.clone(), (**actual).clone()));
+
+                // the aliases in the definition are listed after this one
+                find_big_aliases(actual, aliases);
+            }
+        }
+        Type(_, args) | Range(args) => args.iter().for_each(|arg| find_big_aliases(arg, aliases)),
+        Function(args, _, ret) => {
+            args.iter().for_each(|arg| find_big_aliases(arg, aliases));
+            find_big_aliases(ret, aliases);
+        }
+        Record(fields, _) => fields
+            .iter()
+            .for_each(|(_, field)| find_big_aliases(field.as_inner(), aliases)),
+        Tuple(elems, _) => elems
+            .iter()
+            .for_each(|(_, elem)| find_big_aliases(elem, aliases)),
+        TagUnion(tags, _, _) | RecursiveTagUnion(_, tags, _, _) => tags
+            .iter()
+            .flat_map(|(_, args)| args)
+            .for_each(|arg| find_big_aliases(arg, aliases)),
+        Infinite | FlexVar(_) | RigidVar(_) | FlexAbleVar(..) | RigidAbleVar(..) | Error => {}
+    }
+}
+
 /// Formats an item in a Roc program to a tuple (summary, has_type_colon), where
 /// concatenation of the tuple items introduces the item and leads up to its type.
 fn format_category<'b>(
@@ -2115,6 +2257,7 @@ fn pattern_type_comparison<'b>(
     instead_of: RocDocBuilder<'b>,
     reason_hints: Vec<RocDocBuilder<'b>>,
 ) -> RocDocBuilder<'b> {
+    let alias_hints = alias_hints(alloc, &actual, &expected);
     let comparison = to_comparison(alloc, actual, expected);
 
     let mut lines = vec![
@@ -2130,6 +2273,7 @@ fn pattern_type_comparison<'b>(
         ExpectationContext::Arbitrary,
     ));
     lines.extend(reason_hints);
+    lines.extend(alias_hints);
 
     alloc.stack(lines)
 }
@@ -2214,6 +2358,8 @@ pub enum Problem {
     OptionalRequiredMismatch(Lowercase),
     OpaqueComparedToNonOpaque,
     BoolVsBoolTag(TagName),
+    /// A `Num *`, `Int *`, or `Frac *` compared to a type that isn't a number
+    PolymorphicNumber(Symbol),
 }
 
 fn problems_to_tip<'b>(
@@ -2450,7 +2596,7 @@ fn to_doc_help<'b>(
                 .collect(),
             to_doc_help(ctx, gen_usages, alloc, Parens::InFn, *ret),
         ),
-        Infinite => alloc.text("∞"),
+        Infinite => alloc.text(in_charset("∞")),
         Error => alloc.text("?"),
 
         FlexVar(lowercase) if is_generated_name(&lowercase) => {
@@ -2975,15 +3121,16 @@ fn to_diff<'b>(
         }
 
         (Alias(symbol, _, actual, AliasKind::Structural), other)
-            if !symbol.module_id().is_builtin() =>
+            if !symbol.module_id().is_builtin() && actual.size() <= ALIAS_EXPANSION_BUDGET =>
         {
-            // when diffing a structural alias with a non-alias, de-alias
+            // when diffing a small structural alias with a non-alias, de-alias.
+            // Bigger aliases keep their name, their definition is listed in a hint.
             to_diff(alloc, parens, *actual, other)
         }
         (other, Alias(symbol, _, actual, AliasKind::Structural))
-            if !symbol.module_id().is_builtin() =>
+            if !symbol.module_id().is_builtin() && actual.size() <= ALIAS_EXPANSION_BUDGET =>
         {
-            // when diffing a structural alias with a non-alias, de-alias
+            // when diffing a small structural alias with a non-alias, de-alias
             to_diff(alloc, parens, other, *actual)
         }
 
@@ -3046,10 +3193,43 @@ fn to_diff<'b>(
                 _ => false,
             };
 
+            let polymorphic_number = |t: &ErrorType| match t {
+                ErrorType::Alias(
+                    symbol @ (Symbol::NUM_NUM | Symbol::NUM_INT | Symbol::NUM_FRAC),
+                    args,
+                    _,
+                    _,
+                ) => match args.get(0) {
+                    Some(
+                        ErrorType::FlexVar(_)
+                        | ErrorType::RigidVar(_)
+                        | ErrorType::FlexAbleVar(..)
+                        | ErrorType::RigidAbleVar(..)
+                        | ErrorType::Range(_),
+                    ) => Some(*symbol),
+                    _ => None,
+                },
+                _ => None,
+            };
+            let is_non_number = |t: &ErrorType| match t {
+                ErrorType::Type(symbol, _) | ErrorType::Alias(symbol, _, _, _) => {
+                    symbol.module_id() != ModuleId::NUM
+                }
+                ErrorType::Record(..)
+                | ErrorType::Tuple(..)
+                | ErrorType::TagUnion(..)
+                | ErrorType::RecursiveTagUnion(..)
+                | ErrorType::Function(..) => true,
+                _ => false,
+            };
+
             let problems = match pair {
                 (a, b) if (is_int(&a) && is_float(&b)) || (is_float(&a) && is_int(&b)) => {
                     vec![Problem::IntFloat]
                 }
+                (a, b) | (b, a) if polymorphic_number(&a).is_some() && is_non_number(&b) => {
+                    vec![Problem::PolymorphicNumber(polymorphic_number(&a).unwrap())]
+                }
                 _ => vec![],
             };
 
@@ -4212,7 +4392,7 @@ mod report_text {
             if is_truncated {
                 alloc.vcat(
                     fields
-                        .chain(std::iter::once(alloc.text("…").indent(4)))
+                        .chain(std::iter::once(alloc.text(in_charset("…")).indent(4)))
                         .chain(closing),
                 )
             } else {
@@ -4764,6 +4944,46 @@ fn type_problem_to_pretty<'b>(
             alloc.reflow("."),
         ])),
 
+        (PolymorphicNumber(number), _) => {
+            let (typ, any_type, literals, default, default_alias) = match number {
+                Symbol::NUM_INT => (
+                    "Int *",
+                    " can become any integer type",
+                    "integer literals become ",
+                    "I64",
+                    NumLiteralDefaults::INT_ALIAS_NAME,
+                ),
+                Symbol::NUM_FRAC => (
+                    "Frac *",
+                    " can become any fraction type",
+                    "fraction literals become ",
+                    "F64",
+                    NumLiteralDefaults::FRAC_ALIAS_NAME,
+                ),
+                _ => (
+                    "Num *",
+                    " can become any number type",
+                    "number literals become ",
+                    "I64",
+                    NumLiteralDefaults::INT_ALIAS_NAME,
+                ),
+            };
+
+            alloc.tip().append(alloc.concat([
+                alloc.reflow("A value of type "),
+                alloc.type_str(typ),
+                alloc.reflow(any_type),
+                alloc.reflow(", depending on how it is used, but never a type that isn't"),
+                alloc.reflow(" a number."),
+                alloc.reflow(" If nothing decides, "),
+                alloc.reflow(literals),
+                alloc.type_str(default),
+                alloc.reflow(", or the module's "),
+                alloc.type_str(default_alias),
+                alloc.reflow(" alias if it defines one."),
+            ]))
+        }
+
         (BoolVsBoolTag(tag), _) => alloc.tip().append(alloc.concat([
             alloc.reflow("Did you mean to use "),
             alloc.symbol_qualified(if tag.0.as_str() == "True" {
@@ -4821,7 +5041,7 @@ fn report_record_field_typo<'b>(
         if suggestions.is_empty() {
             let r_doc = match opt_sym {
                 Some(symbol) => alloc.symbol_unqualified(symbol).append(" is"),
-                None => alloc.text("it’s"),
+                None => alloc.text(in_charset("it’s")),
             };
             alloc.concat([
                 alloc.reflow("In fact, "),
@@ -1,9 +1,14 @@
+use crate::catalog::{pieces, Message, Piece};
+use roc_error_macros::internal_error;
 use roc_module::ident::Ident;
 use roc_module::ident::{Lowercase, ModuleName, TagName, Uppercase};
 use roc_module::symbol::{Interns, ModuleId, ModuleIds, PQModuleName, PackageQualified, Symbol};
 use roc_problem::Severity;
 use roc_region::all::LineColumnRegion;
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use std::{fmt, io};
 use ven_pretty::{text, BoxAllocator, DocAllocator, DocBuilder, Render, RenderAnnotated};
 
@@ -37,22 +42,22 @@ pub fn cycle<'b>(
 ) -> RocDocBuilder<'b> {
     let mut lines = Vec::with_capacity(4 + (2 * names.len() - 1));
 
-    lines.push(alloc.text(CYCLE_TOP));
+    lines.push(alloc.text(in_charset(CYCLE_TOP)));
 
-    lines.push(alloc.text(CYCLE_LN).append(name));
-    lines.push(alloc.text(CYCLE_MID));
+    lines.push(alloc.text(in_charset(CYCLE_LN)).append(name));
+    lines.push(alloc.text(in_charset(CYCLE_MID)));
 
     let mut it = names.into_iter().peekable();
 
     while let Some(other_name) = it.next() {
-        lines.push(alloc.text(CYCLE_LN).append(other_name));
+        lines.push(alloc.text(in_charset(CYCLE_LN)).append(other_name));
 
         if it.peek().is_some() {
-            lines.push(alloc.text(CYCLE_MID));
+            lines.push(alloc.text(in_charset(CYCLE_MID)));
         }
     }
 
-    lines.push(alloc.text(CYCLE_END));
+    lines.push(alloc.text(in_charset(CYCLE_END)));
 
     alloc
         .vcat(lines)
@@ -65,7 +70,7 @@ const HEADER_WIDTH: usize = 80;
 pub fn pretty_header(title: &str) -> String {
     let title_width = title.len() + 4;
     let header = format!("── {} {}", title, "─".repeat(HEADER_WIDTH - title_width));
-    header
+    in_charset(&header).into_owned()
 }
 
 pub fn pretty_header_with_path(title: &str, path: &Path) -> String {
@@ -97,7 +102,7 @@ pub fn pretty_header_with_path(title: &str, path: &Path) -> String {
         path
     );
 
-    header
+    in_charset(&header).into_owned()
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -106,6 +111,177 @@ pub enum RenderTarget {
     Generic,
 }
 
+/// Which characters reports draw rules, boxes and punctuation with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Charset {
+    /// Box drawing characters, arrows and typographic punctuation
+    Unicode,
+    /// Plain ASCII, for dumb terminals and log aggregators
+    Ascii,
+}
+
+impl FromStr for Charset {
+    type Err = String;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "unicode" => Ok(Charset::Unicode),
+            "ascii" => Ok(Charset::Ascii),
+            _ => Err(format!(
+                "unknown charset {}, expected unicode or ascii",
+                string
+            )),
+        }
+    }
+}
+
+/// Whether to color the output, as asked for with `--color`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorChoice {
+    Always,
+    Never,
+    /// Color the output when it goes to a terminal, unless `NO_COLOR` is set or the terminal is dumb
+    Auto,
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            "auto" => Ok(ColorChoice::Auto),
+            _ => Err(format!(
+                "unknown color choice {}, expected always, never or auto",
+                string
+            )),
+        }
+    }
+}
+
+/// How everything this process reports is rendered. Reports are rendered in color and with
+/// Unicode until the CLI sets this, once, before it reports anything.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenderStyle {
+    pub color: bool,
+    pub charset: Charset,
+}
+
+impl RenderStyle {
+    /// Works out the style from the `--color` and `--charset` flags, which win over the
+    /// environment: `NO_COLOR` (see https://no-color.org), `ROC_CHARSET`, and `TERM=dumb`, which
+    /// turns off both colors and Unicode.
+    pub fn resolve(
+        color: ColorChoice,
+        charset: Option<Charset>,
+        env_var: impl Fn(&str) -> Option<String>,
+        is_terminal: bool,
+    ) -> Self {
+        let dumb_terminal = env_var("TERM").as_deref() == Some("dumb");
+
+        let color = match color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                let no_color = env_var("NO_COLOR").map_or(false, |value| !value.is_empty());
+
+                is_terminal && !dumb_terminal && !no_color
+            }
+        };
+
+        let charset = charset
+            .or_else(|| env_var("ROC_CHARSET")?.parse().ok())
+            .unwrap_or(if dumb_terminal {
+                Charset::Ascii
+            } else {
+                Charset::Unicode
+            });
+
+        RenderStyle { color, charset }
+    }
+}
+
+const NO_COLOR: u8 = 0b01;
+const ASCII: u8 = 0b10;
+
+static RENDER_STYLE: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_render_style(style: RenderStyle) {
+    let mut bits = 0;
+
+    if !style.color {
+        bits |= NO_COLOR;
+    }
+
+    if style.charset == Charset::Ascii {
+        bits |= ASCII;
+    }
+
+    RENDER_STYLE.store(bits, Ordering::Relaxed);
+}
+
+pub fn render_style() -> RenderStyle {
+    let bits = RENDER_STYLE.load(Ordering::Relaxed);
+
+    RenderStyle {
+        color: bits & NO_COLOR == 0,
+        charset: if bits & ASCII == 0 {
+            Charset::Unicode
+        } else {
+            Charset::Ascii
+        },
+    }
+}
+
+static VERBOSE_ERRORS: AtomicBool = AtomicBool::new(false);
+
+/// Whether tips in reports are expanded with worked examples, as asked for with `--verbose-errors`
+pub fn set_verbose_errors(verbose: bool) {
+    VERBOSE_ERRORS.store(verbose, Ordering::Relaxed);
+}
+
+pub fn verbose_errors() -> bool {
+    VERBOSE_ERRORS.load(Ordering::Relaxed)
+}
+
+/// `text` with its box drawing characters, arrows and typographic punctuation replaced by plain
+/// ASCII, when reports are rendered in ASCII. Only for text the compiler wrote itself, so never
+/// for source code.
+pub fn in_charset(text: &str) -> Cow<'_, str> {
+    if render_style().charset == Charset::Unicode || text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+
+    let mut ascii = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '─' | '—' | '–' => ascii.push('-'),
+            '│' => ascii.push('|'),
+            '┌' | '┐' | '└' | '┘' => ascii.push('+'),
+            '↓' => ascii.push('v'),
+            '→' => ascii.push_str("->"),
+            '…' => ascii.push_str("..."),
+            '’' | '‘' => ascii.push('\''),
+            '“' | '”' => ascii.push('"'),
+            '∞' => ascii.push_str("<infinite>"),
+            c => ascii.push(c),
+        }
+    }
+
+    Cow::Owned(ascii)
+}
+
+/// `text` in an ANSI color, like `ANSI_STYLE_CODES.red`, unless colors are turned off
+pub fn ansi_color(color: &str, text: impl fmt::Display) -> String {
+    if render_style().color {
+        format!("{}{}{}", color, text, ANSI_STYLE_CODES.color_reset)
+    } else {
+        text.to_string()
+    }
+}
+
 /// A textual report.
 pub struct Report<'b> {
     pub title: String,
@@ -129,7 +305,7 @@ impl<'b> Report<'b> {
     }
 
     /// Render to CI console output, where no colors are available.
-    pub fn render_ci(self, buf: &'b mut String, alloc: &'b RocDocAllocator<'b>) {
+    pub fn render_ci(self, buf: &mut String, alloc: &'b RocDocAllocator<'b>) {
         let err_msg = "<buffer is not a utf-8 encoded string>";
 
         self.pretty(alloc)
@@ -140,12 +316,17 @@ impl<'b> Report<'b> {
 
     /// Render to a color terminal using ANSI escape sequences,
     /// or to the web REPL, using HTML tags.
+    /// Falls back to `render_ci` when colors are turned off.
     pub fn render_color_terminal(
         self,
         buf: &mut String,
         alloc: &'b RocDocAllocator<'b>,
         palette: &'b Palette,
     ) {
+        if !render_style().color {
+            return self.render_ci(buf, alloc);
+        }
+
         let err_msg = "<buffer is not a utf-8 encoded string>";
 
         self.pretty(alloc)
@@ -168,8 +349,15 @@ impl<'b> Report<'b> {
         }
     }
 
+    /// The rule between the reports and whatever comes after them
     pub fn horizontal_rule(palette: &'b Palette) -> String {
-        format!("{}{}", palette.header, "─".repeat(80))
+        let rule = in_charset(&"─".repeat(80)).into_owned();
+
+        if render_style().color {
+            format!("{}{}{}", palette.header, rule, palette.reset)
+        } else {
+            rule
+        }
     }
 }
 
@@ -317,6 +505,19 @@ where
     ) -> <Self::Doc as ven_pretty::DocPtr<'a, A>>::WidthFn {
         self.upstream.alloc_width_fn(f)
     }
+
+    /// Reflowed text is always written by the compiler, so it follows the charset
+    fn reflow(&'a self, text: &'a str) -> DocBuilder<'a, Self, A>
+    where
+        Self: Sized,
+        Self::Doc: Clone,
+        A: Clone,
+    {
+        self.intersperse(
+            text.split(char::is_whitespace).map(in_charset),
+            self.line().group(),
+        )
+    }
 }
 
 impl<'a> RocDocAllocator<'a> {
@@ -357,12 +558,27 @@ impl<'a> RocDocAllocator<'a> {
         self.text(x)
     }
 
+    /// The text of `message` in the language that is set, reflowed, with `args` in its slots
+    pub fn message<const N: usize>(
+        &'a self,
+        message: Message,
+        args: [DocBuilder<'a, Self, Annotation>; N],
+    ) -> DocBuilder<'a, Self, Annotation> {
+        self.concat(pieces(message.text()).into_iter().map(|piece| match piece {
+            Piece::Text(text) => self.reflow(text),
+            Piece::Slot(slot) => match args.get(slot) {
+                Some(arg) => arg.clone(),
+                None => internal_error!("{:?} has no argument for slot {{{}}}", message, slot),
+            },
+        }))
+    }
+
     pub fn keyword(&'a self, string: &'a str) -> DocBuilder<'a, Self, Annotation> {
         self.text(string).annotate(Annotation::Keyword)
     }
 
     pub fn ellipsis(&'a self) -> DocBuilder<'a, Self, Annotation> {
-        self.text("…").annotate(Annotation::Ellipsis)
+        self.text(in_charset("…")).annotate(Annotation::Ellipsis)
     }
 
     pub fn parser_suggestion(&'a self, string: &'a str) -> DocBuilder<'a, Self, Annotation> {
@@ -507,6 +723,36 @@ impl<'a> RocDocAllocator<'a> {
             .append(self.softline())
     }
 
+    /// A tip, and below it the worked example from the catalog when errors are verbose. The short
+    /// tip is enough for most people, and beginners can ask for more.
+    pub fn tip_with_example(
+        &'a self,
+        tip: DocBuilder<'a, Self, Annotation>,
+        example: Message,
+    ) -> DocBuilder<'a, Self, Annotation> {
+        if verbose_errors() {
+            self.stack([tip, self.example(example)])
+        } else {
+            tip
+        }
+    }
+
+    /// A worked example. Its paragraphs are reflowed, except for code, which is indented.
+    pub fn example(&'a self, example: Message) -> DocBuilder<'a, Self, Annotation> {
+        self.stack(example.text().split("\n\n").map(|paragraph| {
+            if paragraph.starts_with("    ") {
+                self.vcat(
+                    paragraph
+                        .lines()
+                        .map(|line| self.text(line.strip_prefix("    ").unwrap_or(line))),
+                )
+                .indent(4)
+            } else {
+                self.vcat(paragraph.lines().map(|line| self.reflow(line)))
+            }
+        }))
+    }
+
     pub fn note(&'a self, line: &'a str) -> DocBuilder<'a, Self, Annotation> {
         self.text("Note")
             .annotate(Annotation::Tip)
@@ -560,19 +806,28 @@ impl<'a> RocDocAllocator<'a> {
             let source_line = if highlight {
                 self.text(" ".repeat(max_line_number_length - this_line_number_length))
                     .append(self.text(line_number).annotate(Annotation::LineNumber))
-                    .append(self.text(GUTTER_BAR).annotate(Annotation::GutterBar))
+                    .append(
+                        self.text(in_charset(GUTTER_BAR))
+                            .annotate(Annotation::GutterBar),
+                    )
                     .append(self.text(">").annotate(error_annotation))
                     .append(rest_of_line)
             } else if error_highlight_line {
                 self.text(" ".repeat(max_line_number_length - this_line_number_length))
                     .append(self.text(line_number).annotate(Annotation::LineNumber))
-                    .append(self.text(GUTTER_BAR).annotate(Annotation::GutterBar))
+                    .append(
+                        self.text(in_charset(GUTTER_BAR))
+                            .annotate(Annotation::GutterBar),
+                    )
                     .append(rest_of_line)
             } else {
                 let up_to_gutter = self
                     .text(" ".repeat(max_line_number_length - this_line_number_length))
                     .append(self.text(line_number).annotate(Annotation::LineNumber))
-                    .append(self.text(GUTTER_BAR).annotate(Annotation::GutterBar));
+                    .append(
+                        self.text(in_charset(GUTTER_BAR))
+                            .annotate(Annotation::GutterBar),
+                    );
 
                 if is_line_empty {
                     // Don't put an trailing space after the gutter
@@ -685,19 +940,28 @@ impl<'a> RocDocAllocator<'a> {
             {
                 self.text(" ".repeat(max_line_number_length - this_line_number_length))
                     .append(self.text(line_number).annotate(Annotation::LineNumber))
-                    .append(self.text(GUTTER_BAR).annotate(Annotation::GutterBar))
+                    .append(
+                        self.text(in_charset(GUTTER_BAR))
+                            .annotate(Annotation::GutterBar),
+                    )
                     .append(self.text(">").annotate(Annotation::Error))
                     .append(rest_of_line)
             } else if error_highlight_line {
                 self.text(" ".repeat(max_line_number_length - this_line_number_length))
                     .append(self.text(line_number).annotate(Annotation::LineNumber))
-                    .append(self.text(GUTTER_BAR).annotate(Annotation::GutterBar))
+                    .append(
+                        self.text(in_charset(GUTTER_BAR))
+                            .annotate(Annotation::GutterBar),
+                    )
                     .append(rest_of_line)
             } else {
                 let up_to_gutter = self
                     .text(" ".repeat(max_line_number_length - this_line_number_length))
                     .append(self.text(line_number).annotate(Annotation::LineNumber))
-                    .append(self.text(GUTTER_BAR).annotate(Annotation::GutterBar));
+                    .append(
+                        self.text(in_charset(GUTTER_BAR))
+                            .annotate(Annotation::GutterBar),
+                    );
 
                 if is_line_empty {
                     // Don't put an trailing space after the gutter
@@ -13,14 +13,16 @@ mod test_reporting {
     use indoc::indoc;
     use roc_can::abilities::AbilitiesStore;
     use roc_can::expr::PendingDerives;
-    use roc_load::{self, ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
-    use roc_module::symbol::{Interns, ModuleId};
+    use roc_load::{
+        self, ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, OverflowMode, Threading,
+    };
+    use roc_module::symbol::{Interns, ModuleId, Symbol};
     use roc_packaging::cache::RocCacheDir;
     use roc_parse::module::parse_header;
     use roc_parse::state::State;
     use roc_parse::test_helpers::parse_expr_with;
     use roc_problem::Severity;
-    use roc_region::all::LineInfo;
+    use roc_region::all::{LineInfo, Position, Region};
     use roc_reporting::report::{
         can_problem, parse_problem, type_problem, RenderTarget, Report, ANSI_STYLE_CODES,
         DEFAULT_PALETTE,
@@ -127,6 +129,10 @@ mod test_reporting {
                 palette: DEFAULT_PALETTE,
                 threading: Threading::Single,
                 exec_mode: ExecutionMode::Check,
+                recover_from_syntax_errors: false,
+                static_data: false,
+                overflow: OverflowMode::Trap,
+                fold_constants: true,
             };
             let result = roc_load::load_and_typecheck(
                 arena,
@@ -868,6 +874,11 @@ mod test_reporting {
 
     But I need every `if` guard condition to evaluate to a Bool—either
     `Bool.true` or `Bool.false`.
+
+    Tip: A value of type Num * can become any number type, depending on
+    how it is used, but never a type that isn't a number. If nothing
+    decides, number literals become I64, or the module's DefaultInt alias
+    if it defines one.
     "###
     );
 
@@ -895,6 +906,11 @@ mod test_reporting {
         Num *
 
     All branches in an `if` must have the same type!
+
+    Tip: A value of type Num * can become any number type, depending on
+    how it is used, but never a type that isn't a number. If nothing
+    decides, number literals become I64, or the module's DefaultInt alias
+    if it defines one.
     "###
     );
 
@@ -922,6 +938,11 @@ mod test_reporting {
         Num *
 
     All branches in an `if` must have the same type!
+
+    Tip: A value of type Num * can become any number type, depending on
+    how it is used, but never a type that isn't a number. If nothing
+    decides, number literals become I64, or the module's DefaultInt alias
+    if it defines one.
     "###
     );
 
@@ -1034,6 +1055,11 @@ mod test_reporting {
         Num *
 
     Every element in a list must have the same type!
+
+    Tip: A value of type Num * can become any number type, depending on
+    how it is used, but never a type that isn't a number. If nothing
+    decides, number literals become I64, or the module's DefaultInt alias
+    if it defines one.
     "###
     );
 
@@ -1702,6 +1728,11 @@ mod test_reporting {
         {}a
 
     The branches must be cases of the `when` condition's type!
+
+    Tip: A value of type Num * can become any number type, depending on
+    how it is used, but never a type that isn't a number. If nothing
+    decides, number literals become I64, or the module's DefaultInt alias
+    if it defines one.
     "###
     );
 
@@ -1729,6 +1760,11 @@ mod test_reporting {
     But all the previous branches match:
 
         Num *
+
+    Tip: A value of type Num * can become any number type, depending on
+    how it is used, but never a type that isn't a number. If nothing
+    decides, number literals become I64, or the module's DefaultInt alias
+    if it defines one.
     "###
     );
 
@@ -1757,6 +1793,11 @@ mod test_reporting {
         { foo : [True] }
 
     The branches must be cases of the `when` condition's type!
+
+    Tip: A value of type Num * can become any number type, depending on
+    how it is used, but never a type that isn't a number. If nothing
+    decides, number literals become I64, or the module's DefaultInt alias
+    if it defines one.
     "###
     );
 
@@ -1868,6 +1909,11 @@ mod test_reporting {
     But all the previous branches match:
 
         {}a
+
+    Tip: A value of type Num * can become any number type, depending on
+    how it is used, but never a type that isn't a number. If nothing
+    decides, number literals become I64, or the module's DefaultInt alias
+    if it defines one.
     "###
     );
 
@@ -1896,6 +1942,11 @@ mod test_reporting {
     But you are trying to use it as:
 
         [Foo *]
+
+    Tip: A value of type Num * can become any number type, depending on
+    how it is used, but never a type that isn't a number. If nothing
+    decides, number literals become I64, or the module's DefaultInt alias
+    if it defines one.
     "###
     );
 
@@ -2457,6 +2508,11 @@ mod test_reporting {
     But `add` needs its 2nd argument to be:
 
         Int *
+
+    Tip: A value of type Int * can become any integer type, depending on
+    how it is used, but never a type that isn't a number. If nothing
+    decides, integer literals become I64, or the module's DefaultInt alias
+    if it defines one.
     "###
     );
 
@@ -2510,6 +2566,11 @@ mod test_reporting {
     But `add` needs its 2nd argument to be:
 
         Num *
+
+    Tip: A value of type Num * can become any number type, depending on
+    how it is used, but never a type that isn't a number. If nothing
+    decides, number literals become I64, or the module's DefaultInt alias
+    if it defines one.
     "###
     );
 
@@ -2947,13 +3008,31 @@ mod test_reporting {
         @r###"
     ── CYCLIC ALIAS ────────────────────────────────────────── /code/proj/Main.roc ─
 
-    The `Foo` alias is self-recursive in an invalid way:
+    The `Foo` alias is recursive in an invalid way:
 
     4│      Foo : { x : Bar }
             ^^^
 
+    The `Foo` alias depends on itself through the following chain of
+    definitions:
+
+        ┌─────┐
+        │     Foo
+        │     ↓
+        │     Bar
+        └─────┘
+
+    The `Bar` definition is here:
+
+    5│      Bar : { y : Foo }
+            ^^^
+
     Recursion in aliases is only allowed if recursion happens behind a
     tagged union, at least one variant of which is not recursive.
+
+    Tip: To break the cycle, put one of the references in this chain
+    inside a tag, in a tagged union which also has a tag that does not
+    refer back.
     "###
     );
 
@@ -3853,6 +3932,11 @@ mod test_reporting {
     But `add` needs its 2nd argument to be:
 
         Num a
+
+    Tip: A value of type Num * can become any number type, depending on
+    how it is used, but never a type that isn't a number. If nothing
+    decides, number literals become I64, or the module's DefaultInt alias
+    if it defines one.
     "###
     );
 
@@ -4911,6 +4995,11 @@ Tab characters are not allowed."###,
     But the type annotation on `myDict` says it should be:
 
         Dict I64 Str
+
+    Tip: A value of type Num * can become any number type, depending on
+    how it is used, but never a type that isn't a number. If nothing
+    decides, number literals become I64, or the module's DefaultInt alias
+    if it defines one.
     "###
     );
 
@@ -5559,6 +5648,11 @@ Tab characters are not allowed."###,
         Num *
 
     All branches in an `if` must have the same type!
+
+    Tip: A value of type Num * can become any number type, depending on
+    how it is used, but never a type that isn't a number. If nothing
+    decides, number literals become I64, or the module's DefaultInt alias
+    if it defines one.
     "###
         );
 
@@ -7637,6 +7731,55 @@ In roc, functions are always written as a lambda, like{}
         │     Bar
         └─────┘
 
+    The `Bar` definition is here:
+
+    5│      Bar a : [Stuff (Foo a)]
+            ^^^
+
+    Recursion in aliases is only allowed if recursion happens behind a
+    tagged union, at least one variant of which is not recursive.
+
+    Tip: To break the cycle, put one of the references in this chain
+    inside a tag, in a tagged union which also has a tag that does not
+    refer back.
+    "###
+    );
+
+    test_report!(
+        mutually_recursive_alias_through_tag_union,
+        indoc!(
+            r#"
+            Expr : [Add Expr Expr, Block Stmts, Num I64]
+            Stmts : List Expr
+
+            v : Expr
+            v = Block [Num 1, Add (Num 2) (Num 3)]
+            v
+            "#
+        ),
+        @""
+    );
+
+    test_report!(
+        mutually_recursive_alias_not_through_tag_union,
+        indoc!(
+            r#"
+            Expr : [Add Expr Expr, Num I64, Wrap Stmts]
+            Stmts : { first : Expr, rest : Stmts }
+
+            v : Expr
+            v = Num 1
+            v
+            "#
+        ),
+        @r###"
+    ── CYCLIC ALIAS ────────────────────────────────────────── /code/proj/Main.roc ─
+
+    The `Stmts` alias is self-recursive in an invalid way:
+
+    5│      Stmts : { first : Expr, rest : Stmts }
+            ^^^^^
+
     Recursion in aliases is only allowed if recursion happens behind a
     tagged union, at least one variant of which is not recursive.
     "###
@@ -9699,7 +9842,7 @@ In roc, functions are always written as a lambda, like{}
 
     Only builtin abilities can be derived.
 
-    Note: The builtin abilities are `Encoding`, `Decoding`, `Hash`, `Eq`
+    Note: The builtin abilities are `Encoding`, `Decoding`, `Hash`, `Eq`, `Enum`
     "###
     );
 
@@ -10871,6 +11014,27 @@ I recommend using camelCase. It's the standard style in Roc code!
     "###
     );
 
+    test_report!(
+        unused_def_in_branch_alternatives_reported_once,
+        indoc!(
+            r#"
+            when A "" is
+                A foo | B foo -> ""
+            "#
+        ),
+    @r###"
+    ── UNUSED DEFINITION ───────────────────────────────────── /code/proj/Main.roc ─
+
+    `foo` is not used in this `when` branch.
+
+    5│          A foo | B foo -> ""
+                  ^^^
+
+    If you don't need to use `foo`, prefix it with an underscore, like
+    "_foo", or replace it with just an "_".
+    "###
+    );
+
     test_report!(
         infer_decoded_record_error_with_function_field,
         indoc!(
@@ -11132,6 +11296,158 @@ I recommend using camelCase. It's the standard style in Roc code!
     "###
     );
 
+    #[test]
+    fn conflicting_implementations() {
+        // Imports can only bring in different implementations of an ability member for the same
+        // opaque type when two copies of the module defining the type are loaded, which a single
+        // test module can't set up, so render the problem for two of its imports directly.
+        let src = indoc!(
+            r#"
+            app "test" imports [Dict, Set] provides [main] to "./platform"
+
+            main = ""
+            "#
+        );
+
+        let arena = Bump::new();
+        let (module_src, _, _, home, interns) =
+            match infer_expr_help_new("conflicting_implementations", &arena, src) {
+                Ok(loaded) => loaded,
+                Err(_) => panic!("failed to load"),
+            };
+
+        let problem = roc_problem::can::Problem::ConflictingImpls {
+            ability_member: Symbol::BOOL_IS_EQ,
+            opaque: Symbol::DICT_DICT,
+            first_import: Region::new(Position::new(20), Position::new(24)),
+            second_import: Region::new(Position::new(26), Position::new(29)),
+        };
+
+        let lines = LineInfo::new(&module_src);
+        let src_lines: Vec<&str> = module_src.split('\n').collect();
+        let alloc = RocDocAllocator::new(&src_lines, home, &interns);
+        let filename = filename_from_string(r"/code/proj/Main.roc");
+
+        let mut buf = String::new();
+        can_problem(&alloc, &lines, filename, problem)
+            .pretty(&alloc)
+            .1
+            .render_raw(70, &mut roc_reporting::report::CiWrite::new(&mut buf))
+            .unwrap();
+
+        insta::assert_snapshot!(buf, @r###"
+        ── CONFLICTING IMPLEMENTATIONS ─────────────────────────── /code/proj/Main.roc ─
+
+        This import brings in an implementation of `Bool.isEq` for `Dict`:
+
+        1│  app "test" imports [Dict, Set] provides [main] to "./platform"
+                                ^^^^
+
+        But this import brings in a different one:
+
+        1│  app "test" imports [Dict, Set] provides [main] to "./platform"
+                                      ^^^
+
+        An ability can only be implemented for an opaque type in the module
+        that defines the type, so this happens when two copies of that module
+        are loaded, like from two different versions of the same package. Make
+        sure these imports get `Dict` from the same version of its package.
+        "###);
+    }
+
+    #[test]
+    fn cycle_found_after_specialization() {
+        // `answer` only reaches `thingDefault` through the `Default` ability, so the cycle isn't
+        // visible until `default {}` is specialized. Checking alone doesn't specialize, so this
+        // module has to be monomorphized.
+        use std::io::Write;
+
+        let src = indoc!(
+            r#"
+            app "test" imports [] provides [main] to "./platform"
+
+            Default has default : {} -> a | a has Default
+
+            Thing := U64 has [Default { default: thingDefault }]
+
+            thingDefault = \{} -> answer
+
+            answer : Thing
+            answer = default {}
+
+            main =
+                @Thing n = answer
+
+                n
+            "#
+        );
+
+        let arena = Bump::new();
+        let dir = roc_test_utils::TmpDir::new("tmp/cycle_found_after_specialization");
+        let file_path = dir.path().join("Test.roc");
+        let mut file = std::fs::File::create(&file_path).unwrap();
+        writeln!(file, "{}", src).unwrap();
+
+        let load_config = LoadConfig {
+            target_info: roc_target::TargetInfo::default_x86_64(),
+            render: RenderTarget::Generic,
+            palette: DEFAULT_PALETTE,
+            threading: Threading::Single,
+            exec_mode: ExecutionMode::Executable,
+            recover_from_syntax_errors: false,
+            static_data: false,
+            overflow: OverflowMode::Trap,
+            fold_constants: true,
+        };
+
+        let mut loaded = match roc_load::load_and_monomorphize(
+            &arena,
+            file_path,
+            RocCacheDir::Disallowed,
+            load_config,
+        ) {
+            Ok(loaded) => loaded,
+            Err(_) => panic!("failed to load"),
+        };
+
+        let problems = loaded
+            .can_problems
+            .remove(&loaded.module_id)
+            .unwrap_or_default();
+
+        let lines = LineInfo::new(src);
+        let src_lines: Vec<&str> = src.split('\n').collect();
+        let alloc = RocDocAllocator::new(&src_lines, loaded.module_id, &loaded.interns);
+        let filename = filename_from_string(r"/code/proj/Main.roc");
+
+        let mut buf = String::new();
+        for problem in problems {
+            can_problem(&alloc, &lines, filename.clone(), problem)
+                .pretty(&alloc)
+                .1
+                .render_raw(70, &mut roc_reporting::report::CiWrite::new(&mut buf))
+                .unwrap();
+        }
+
+        insta::assert_snapshot!(buf, @r###"
+        ── CIRCULAR DEFINITION ─────────────────────────────────── /code/proj/Main.roc ─
+
+        The `answer` definition is causing a very tricky infinite loop:
+
+        10│  answer = default {}
+             ^^^^^^
+
+        The `answer` value depends on itself through the following chain of
+        definitions:
+
+            ┌─────┐
+            │     answer
+            │     ↓
+            │     thingDefault
+            └─────┘
+        "###);
+    }
+
     test_report!(
         #[ignore = "https://github.com/roc-lang/roc/issues/4096"]
         unnecessary_builtin_module_import,
@@ -13721,4 +14037,208 @@ I recommend using camelCase. It's the standard style in Roc code!
     Tip: It looks like it takes too many arguments. I'm seeing 1 extra.
     "###
     );
+
+    test_report!(
+        invalid_num_literal_default,
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            DefaultInt : Str
+
+            main = 1
+            "#
+        ),
+        @r###"
+    ── INVALID NUMBER DEFAULT ──────────────────────────────── /code/proj/Main.roc ─
+
+    The `DefaultInt` alias chooses the type of unannotated integer literals
+    in this module, but it isn't a builtin number type I can use:
+
+    3│  DefaultInt : Str
+        ^^^^^^^^^^
+
+    It must be exactly an integer type like I32, U64, or Nat. Until then,
+    I'll use the usual defaults: I64 for integers and F64 for fractions.
+    "###
+    );
+
+    test_report!(
+        big_alias_listed_in_hint,
+        indoc!(
+            r#"
+            Shape : [Circle F64, Rect F64 F64, Triangle F64 F64 F64, Point F64 F64, Polygon (List F64) (List F64)]
+
+            f : Shape
+            f = Hexagon
+
+            f
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    Something is off with the body of the `f` definition:
+
+    6│      f : Shape
+    7│      f = Hexagon
+                ^^^^^^^
+
+    This `Hexagon` tag has the type:
+
+        [Hexagon]
+
+    But the type annotation on `f` says it should be:
+
+        Shape
+
+    Hint: Alias `Shape` =
+
+        [
+            Circle F64,
+            Point F64 F64,
+            Polygon (List F64) (List F64),
+            Rect F64 F64,
+            Triangle F64 F64 F64,
+        ]
+    "###
+    );
+
+    fn resolve_style(
+        color: roc_reporting::report::ColorChoice,
+        env: &[(&str, &str)],
+        is_terminal: bool,
+    ) -> roc_reporting::report::RenderStyle {
+        roc_reporting::report::RenderStyle::resolve(
+            color,
+            None,
+            |name| {
+                env.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            },
+            is_terminal,
+        )
+    }
+
+    #[test]
+    fn render_style_color() {
+        use roc_reporting::report::ColorChoice;
+
+        assert!(resolve_style(ColorChoice::Auto, &[], true).color);
+        assert!(!resolve_style(ColorChoice::Auto, &[], false).color);
+        assert!(!resolve_style(ColorChoice::Auto, &[("NO_COLOR", "1")], true).color);
+        assert!(resolve_style(ColorChoice::Auto, &[("NO_COLOR", "")], true).color);
+        assert!(resolve_style(ColorChoice::Always, &[("NO_COLOR", "1")], false).color);
+        assert!(!resolve_style(ColorChoice::Never, &[], true).color);
+    }
+
+    #[test]
+    fn render_style_charset() {
+        use roc_reporting::report::{Charset, ColorChoice};
+
+        let style = resolve_style(ColorChoice::Auto, &[("TERM", "dumb")], true);
+        assert!(!style.color);
+        assert_eq!(style.charset, Charset::Ascii);
+
+        let style = resolve_style(ColorChoice::Auto, &[("ROC_CHARSET", "ascii")], true);
+        assert_eq!(style.charset, Charset::Ascii);
+
+        let style = resolve_style(ColorChoice::Auto, &[], true);
+        assert_eq!(style.charset, Charset::Unicode);
+    }
+
+    #[test]
+    fn message_pieces() {
+        use roc_reporting::catalog::{pieces, Piece};
+
+        assert_eq!(
+            pieces("Since {0} isn't used, {1}."),
+            vec![
+                Piece::Text("Since "),
+                Piece::Slot(0),
+                Piece::Text(" isn't used, "),
+                Piece::Slot(1),
+                Piece::Text("."),
+            ]
+        );
+        assert_eq!(pieces("{0}"), vec![Piece::Slot(0)]);
+        assert_eq!(pieces("a { b {x} }"), vec![Piece::Text("a { b {x} }")]);
+    }
+
+    #[test]
+    fn catalogs_have_the_slots_of_english() {
+        use roc_reporting::catalog::{pieces, Language, Message, Piece};
+
+        let slots = |template| {
+            let mut slots: Vec<usize> = pieces(template)
+                .into_iter()
+                .filter_map(|piece| match piece {
+                    Piece::Slot(slot) => Some(slot),
+                    Piece::Text(_) => None,
+                })
+                .collect();
+            slots.sort_unstable();
+            slots.dedup();
+            slots
+        };
+
+        for message in Message::ALL {
+            let english = slots(message.template(Language::English));
+
+            for language in Language::ALL {
+                assert_eq!(
+                    slots(message.template(*language)),
+                    english,
+                    "{:?} in {:?}",
+                    message,
+                    language
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn language_from_locale() {
+        use roc_reporting::catalog::Language;
+
+        assert_eq!(Language::from_locale("es"), Some(Language::Spanish));
+        assert_eq!(
+            Language::from_locale("es_MX.UTF-8"),
+            Some(Language::Spanish)
+        );
+        assert_eq!(Language::from_locale("C"), Some(Language::English));
+        assert_eq!(Language::from_locale("xx"), None);
+    }
+
+    #[test]
+    fn worked_example() {
+        use roc_reporting::catalog::Message;
+
+        let arena = Bump::new();
+        let (_type_problems, _can_problems, home, interns) =
+            infer_expr_help(&arena, "x = 1\n\nx").expect("parse error");
+
+        let mut buf = String::new();
+        let alloc = RocDocAllocator::new(&[], home, &interns);
+
+        to_simple_report(alloc.example(Message::UnboundTypeVariableExample))
+            .render_ci(&mut buf, &alloc);
+
+        assert_eq!(
+            buf,
+            indoc!(
+                r#"
+                For example, `a` is not bound in this definition:
+
+                    Pair : [Pair a a]
+
+                It is bound when it is a parameter of the type:
+
+                    Pair a : [Pair a a]
+
+                Then every use of the type says what `a` is, like `Pair Str`."#
+            )
+        );
+    }
 }
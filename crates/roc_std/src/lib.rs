@@ -511,3 +511,19 @@ impl Hash for U128 {
         u128::from(*self).hash(state);
     }
 }
+
+/// The current instant, in nanoseconds since the Unix epoch (1970-01-01T00:00:00Z), or negative
+/// when the clock is set before it. This is how platforms give Roc the current time, which the
+/// `Time.fromUnixNanos` builtin turns into a `DateTime`; hosts which define the
+/// `roc_current_time` hook can return it narrowed to an `i64`.
+#[cfg(feature = "std")]
+pub fn unix_nanos_now() -> I128 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let nanos = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(after) => after.as_nanos() as i128,
+        Err(before) => -(before.duration().as_nanos() as i128),
+    };
+
+    I128::from(nanos)
+}
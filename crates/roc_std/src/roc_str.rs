@@ -545,9 +545,8 @@ impl RocStr {
                 // We need 1 extra elem for the terminator. It must be an elem,
                 // not a byte, because we'll be providing a pointer to elems.
                 let needed_bytes = (len + 1) * size_of::<E>();
-                let available_bytes = size_of::<SmallString>();
 
-                if needed_bytes < available_bytes {
+                if needed_bytes <= SmallString::CAPACITY {
                     terminate(small_str.bytes.as_ptr() as *mut E, self.as_str())
                 } else {
                     fallback(self.as_str())
@@ -712,6 +711,9 @@ enum RocStrInnerRef<'a> {
     SmallString(&'a SmallString),
 }
 
+/// The length of a small string, with the small string flag, is stored in the most significant
+/// byte of the capacity of a heap-allocated string, after the bytes of the string.
+#[cfg(target_endian = "little")]
 #[derive(Debug, Clone, Copy)]
 #[repr(C)]
 struct SmallString {
@@ -719,13 +721,31 @@ struct SmallString {
     len: u8,
 }
 
+/// The length of a small string, with the small string flag, is stored in the most significant
+/// byte of the capacity of a heap-allocated string, after the bytes of the string. On big-endian
+/// targets that's the first byte of the capacity, and the bytes after it are not used.
+#[cfg(target_endian = "big")]
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+struct SmallString {
+    bytes: [u8; Self::CAPACITY],
+    len: u8,
+    unused: [u8; size_of::<usize>() - 1],
+}
+
 impl SmallString {
+    #[cfg(target_endian = "little")]
     const CAPACITY: usize = size_of::<RocList<u8>>() - 1;
 
+    #[cfg(target_endian = "big")]
+    const CAPACITY: usize = 2 * size_of::<usize>();
+
     const fn empty() -> Self {
         Self {
             bytes: [0; Self::CAPACITY],
             len: RocStr::MASK,
+            #[cfg(target_endian = "big")]
+            unused: [0; size_of::<usize>() - 1],
         }
     }
 
@@ -745,6 +765,8 @@ impl SmallString {
         Some(Self {
             bytes,
             len: len_as_u8 | RocStr::MASK,
+            #[cfg(target_endian = "big")]
+            unused: [0; size_of::<usize>() - 1],
         })
     }
 